@@ -0,0 +1,181 @@
+//! Benchmarks of the hot parts of the scene graph: pool iteration, hierarchical data update,
+//! physics synchronization and animation sampling. Run them with `cargo bench --bench graph`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fyrox::{
+    core::{
+        algebra::{Vector2, Vector3},
+        curve::{Curve, CurveKey, CurveKeyKind},
+        pool::Handle,
+    },
+    scene::{
+        animation::prelude::*,
+        base::BaseBuilder,
+        collider::{ColliderBuilder, ColliderShape},
+        graph::{Graph, GraphUpdateSwitches},
+        node::Node,
+        pivot::PivotBuilder,
+        rigidbody::RigidBodyBuilder,
+        transform::TransformBuilder,
+    },
+};
+
+const FRAME_SIZE: Vector2<f32> = Vector2::new(1920.0, 1080.0);
+const DT: f32 = 1.0 / 60.0;
+
+fn make_pivot(graph: &mut Graph, x: f32, children: &[Handle<Node>]) -> Handle<Node> {
+    PivotBuilder::new(
+        BaseBuilder::new()
+            .with_children(children)
+            .with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(x, 0.0, 0.0))
+                    .build(),
+            ),
+    )
+    .build(graph)
+}
+
+// Creates a graph with `width` chains of `depth` nodes each.
+fn make_hierarchy(width: usize, depth: usize) -> Graph {
+    let mut graph = Graph::new();
+    for _ in 0..width {
+        let mut child = Handle::NONE;
+        for level in 0..depth {
+            let children = if child.is_some() { vec![child] } else { vec![] };
+            child = make_pivot(&mut graph, level as f32, &children);
+        }
+    }
+    graph
+}
+
+fn make_physics_scene(count: usize) -> Graph {
+    let mut graph = Graph::new();
+    for i in 0..count {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[collider])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(
+                            (i % 32) as f32 * 2.0,
+                            (i / 32) as f32 * 2.0,
+                            0.0,
+                        ))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+    }
+    graph
+}
+
+fn make_animated_scene(count: usize) -> Graph {
+    let mut graph = Graph::new();
+    let mut animation = Animation::default();
+    for i in 0..count {
+        let node = make_pivot(&mut graph, i as f32, &[]);
+
+        let mut frames_container = TrackDataContainer::new(TrackValueKind::Vector3);
+        for curve in frames_container.curves_mut() {
+            *curve = Curve::from(vec![
+                CurveKey::new(0.0, 0.0, CurveKeyKind::Linear),
+                CurveKey::new(0.5, 1.0, CurveKeyKind::Linear),
+                CurveKey::new(1.0, 0.0, CurveKeyKind::Linear),
+            ]);
+        }
+        let mut track = Track::new(frames_container, ValueBinding::Position);
+        track.set_target(node);
+        animation.add_track(track);
+    }
+    animation.set_time_slice(0.0..1.0);
+    animation.set_loop(true);
+    animation.set_enabled(true);
+
+    let mut animations = AnimationContainer::new();
+    animations.add(animation);
+    AnimationPlayerBuilder::new(BaseBuilder::new())
+        .with_animations(animations)
+        .build(&mut graph);
+    graph
+}
+
+fn pool_iteration(c: &mut Criterion) {
+    let graph = make_hierarchy(100, 100);
+    c.bench_function("pool iteration (10k nodes)", |b| {
+        b.iter(|| {
+            black_box(
+                graph
+                    .pair_iter()
+                    .filter(|(_, node)| node.is_globally_enabled())
+                    .count(),
+            )
+        })
+    });
+}
+
+fn hierarchical_data(c: &mut Criterion) {
+    let mut graph = make_hierarchy(100, 100);
+    c.bench_function("hierarchical data update (10k nodes)", |b| {
+        b.iter(|| graph.update_hierarchical_data())
+    });
+}
+
+fn graph_update(c: &mut Criterion) {
+    let mut graph = make_hierarchy(100, 100);
+    c.bench_function("graph update (10k nodes)", |b| {
+        b.iter(|| graph.update(FRAME_SIZE, DT, Default::default()))
+    });
+}
+
+fn physics_sync(c: &mut Criterion) {
+    let mut graph = make_physics_scene(1000);
+    let bodies = graph
+        .pair_iter()
+        .filter(|(_, node)| node.children().len() == 1)
+        .map(|(handle, _)| handle)
+        .collect::<Vec<_>>();
+    // Simulation is disabled, so only synchronization with native physics objects is measured.
+    let switches = GraphUpdateSwitches {
+        physics: false,
+        ..Default::default()
+    };
+    c.bench_function("physics sync (1k moved bodies)", |b| {
+        b.iter(|| {
+            for &body in bodies.iter() {
+                graph[body]
+                    .local_transform_mut()
+                    .offset(Vector3::new(0.0, 0.001, 0.0));
+            }
+            graph.update(FRAME_SIZE, DT, switches.clone());
+        })
+    });
+}
+
+fn physics_step(c: &mut Criterion) {
+    let mut graph = make_physics_scene(1000);
+    c.bench_function("physics step (1k bodies)", |b| {
+        b.iter(|| graph.update(FRAME_SIZE, DT, Default::default()))
+    });
+}
+
+fn animation_sampling(c: &mut Criterion) {
+    let mut graph = make_animated_scene(1000);
+    c.bench_function("animation sampling (1k tracks)", |b| {
+        b.iter(|| graph.update(FRAME_SIZE, DT, Default::default()))
+    });
+}
+
+criterion_group!(
+    benches,
+    pool_iteration,
+    hierarchical_data,
+    graph_update,
+    physics_sync,
+    physics_step,
+    animation_sampling
+);
+criterion_main!(benches);
@@ -32,6 +32,14 @@ define_push_element_to_collection_command!(AddBlendSpacePointCommand<Handle<Pose
     }
 });
 
+define_push_element_to_collection_command!(AddBlendSpace1DPointCommand<Handle<PoseNode>, BlendSpace1DPoint>(self, context) {
+    let machine = fetch_machine(context, self.node_handle);
+    match &mut machine.layers_mut()[self.layer_index].nodes_mut()[self.handle] {
+        PoseNode::BlendSpace1D(definition) => &mut definition.points,
+        _ => unreachable!(),
+    }
+});
+
 define_set_collection_element_command!(
     SetBlendAnimationByIndexInputPoseSourceCommand<Handle<PoseNode>, Handle<PoseNode>>(self, context) {
         let machine = fetch_machine(context, self.node_handle);
@@ -59,6 +67,15 @@ define_set_collection_element_command!(
     }
 );
 
+define_set_collection_element_command!(
+    SetBlendSpace1DPoseSourceCommand<Handle<PoseNode>, Handle<PoseNode>>(self, context) {
+        let machine = fetch_machine(context, self.node_handle);
+        if let PoseNode::BlendSpace1D(ref mut definition) = machine.layers_mut()[self.layer_index].nodes_mut()[self.handle] {
+            std::mem::swap(&mut definition.points[self.index].pose_source, &mut self.value);
+        }
+    }
+);
+
 define_set_collection_element_command!(
     SetBlendSpacePointPositionCommand<Handle<PoseNode>, Vector2<f32>>(self, context) {
         let machine = fetch_machine(context, self.node_handle);
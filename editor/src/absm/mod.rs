@@ -1,7 +1,10 @@
 use crate::{
     absm::{
         blendspace::BlendSpaceEditor,
-        command::blend::{AddBlendSpacePointCommand, AddInputCommand, AddPoseSourceCommand},
+        command::blend::{
+            AddBlendSpace1DPointCommand, AddBlendSpacePointCommand, AddInputCommand,
+            AddPoseSourceCommand,
+        },
         node::{AbsmNode, AbsmNodeMessage},
         parameter::ParameterPanel,
         selection::AbsmSelection,
@@ -586,6 +589,14 @@ impl AbsmEditor {
                                             BlendSpacePoint::default(),
                                         ));
                                     }
+                                    PoseNode::BlendSpace1D(_) => {
+                                        sender.do_scene_command(AddBlendSpace1DPointCommand::new(
+                                            selection.absm_node_handle,
+                                            node.model_handle,
+                                            layer_index,
+                                            BlendSpace1DPoint::default(),
+                                        ));
+                                    }
                                 }
                             }
                         }
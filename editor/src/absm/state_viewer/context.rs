@@ -3,7 +3,8 @@ use crate::{
         command::{
             blend::{
                 SetBlendAnimationByIndexInputPoseSourceCommand,
-                SetBlendAnimationsPoseSourceCommand, SetBlendSpacePoseSourceCommand,
+                SetBlendAnimationsPoseSourceCommand, SetBlendSpace1DPoseSourceCommand,
+                SetBlendSpacePoseSourceCommand,
             },
             AddPoseNodeCommand, DeletePoseNodeCommand, SetStateRootPoseCommand,
         },
@@ -36,6 +37,7 @@ pub struct CanvasContextMenu {
     create_blend_animations: Handle<UiNode>,
     create_blend_by_index: Handle<UiNode>,
     create_blend_space: Handle<UiNode>,
+    create_blend_space_1d: Handle<UiNode>,
     pub menu: RcUiNodeHandle,
     pub canvas: Handle<UiNode>,
     pub node_context_menu: Option<RcUiNodeHandle>,
@@ -47,6 +49,7 @@ impl CanvasContextMenu {
         let create_blend_animations;
         let create_blend_by_index;
         let create_blend_space;
+        let create_blend_space_1d;
         let menu = PopupBuilder::new(
             WidgetBuilder::new()
                 .with_enabled(false) // Disabled by default.
@@ -70,6 +73,10 @@ impl CanvasContextMenu {
                     .with_child({
                         create_blend_space = create_menu_item("Blend Space", vec![], ctx);
                         create_blend_space
+                    })
+                    .with_child({
+                        create_blend_space_1d = create_menu_item("Blend Space 1D", vec![], ctx);
+                        create_blend_space_1d
                     }),
             )
             .build(ctx),
@@ -82,6 +89,7 @@ impl CanvasContextMenu {
             create_blend_animations,
             create_blend_by_index,
             create_blend_space,
+            create_blend_space_1d,
             menu,
             canvas: Default::default(),
             node_context_menu: Default::default(),
@@ -157,6 +165,23 @@ impl CanvasContextMenu {
                 ]);
 
                 Some(PoseNode::BlendSpace(blend_space))
+            } else if message.destination() == self.create_blend_space_1d {
+                let mut blend_space = BlendSpace1D::default();
+
+                blend_space.position = position;
+                blend_space.parent_state = current_state;
+                blend_space.points = vec![
+                    BlendSpace1DPoint {
+                        position: 0.0,
+                        pose_source: Default::default(),
+                    },
+                    BlendSpace1DPoint {
+                        position: 1.0,
+                        pose_source: Default::default(),
+                    },
+                ];
+
+                Some(PoseNode::BlendSpace1D(blend_space))
             } else {
                 None
             };
@@ -355,6 +380,15 @@ impl ConnectionContextMenu {
                             value: Default::default(),
                         })
                     }
+                    PoseNode::BlendSpace1D(_) => {
+                        sender.do_scene_command(SetBlendSpace1DPoseSourceCommand {
+                            node_handle: absm_node_handle,
+                            layer_index,
+                            handle: model_handle,
+                            index,
+                            value: Default::default(),
+                        })
+                    }
                 }
             }
         } else if let Some(PopupMessage::Placement(Placement::Cursor(target))) = message.data() {
@@ -5,7 +5,8 @@ use crate::{
         command::{
             blend::{
                 SetBlendAnimationByIndexInputPoseSourceCommand,
-                SetBlendAnimationsPoseSourceCommand, SetBlendSpacePoseSourceCommand,
+                SetBlendAnimationsPoseSourceCommand, SetBlendSpace1DPoseSourceCommand,
+                SetBlendSpacePoseSourceCommand,
             },
             MovePoseNodeCommand,
         },
@@ -129,6 +130,9 @@ fn make_pose_node_name(
         PoseNode::BlendSpace(blend_space) => {
             format!("Blend Space: {:?} animations", blend_space.points().len())
         }
+        PoseNode::BlendSpace1D(blend_space) => {
+            format!("Blend Space 1D: {:?} animations", blend_space.points.len())
+        }
     }
 }
 
@@ -338,6 +342,15 @@ impl StateViewer {
                                         value: source_node,
                                     });
                                 }
+                                PoseNode::BlendSpace1D(_) => {
+                                    sender.do_scene_command(SetBlendSpace1DPoseSourceCommand {
+                                        node_handle: absm_node_handle,
+                                        layer_index,
+                                        handle: dest_node,
+                                        index: dest_socket_ref.index,
+                                        value: source_node,
+                                    });
+                                }
                             }
                         }
                         _ => (),
@@ -468,6 +481,9 @@ impl StateViewer {
                                     PoseNode::BlendSpace(blend_space) => {
                                         (blend_space.points().len(), "Blend Space", true, true)
                                     }
+                                    PoseNode::BlendSpace1D(blend_space) => {
+                                        (blend_space.points.len(), "Blend Space 1D", true, false)
+                                    }
                                 };
 
                             let node_view = AbsmNodeBuilder::new(
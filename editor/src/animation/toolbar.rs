@@ -90,6 +90,7 @@ struct RootMotionDropdownArea {
     ignore_y: Handle<UiNode>,
     ignore_z: Handle<UiNode>,
     ignore_rotation: Handle<UiNode>,
+    keep_in_pose: Handle<UiNode>,
     node_selector: Handle<UiNode>,
 }
 
@@ -126,10 +127,11 @@ impl RootMotionDropdownArea {
         let ignore_y = check_box(3, ctx);
         let ignore_z = check_box(4, ctx);
         let ignore_rotation = check_box(5, ctx);
+        let keep_in_pose = check_box(6, ctx);
         let popup = PopupBuilder::new(
             WidgetBuilder::new()
                 .with_width(220.0)
-                .with_height(135.0)
+                .with_height(157.0)
                 .with_visibility(false),
         )
         .stays_open(false)
@@ -158,7 +160,9 @@ impl RootMotionDropdownArea {
                     .with_child(text("Ignore Z", 4, ctx))
                     .with_child(ignore_z)
                     .with_child(text("Ignore Rotation", 5, ctx))
-                    .with_child(ignore_rotation),
+                    .with_child(ignore_rotation)
+                    .with_child(text("Keep In Pose", 6, ctx))
+                    .with_child(keep_in_pose),
             )
             .add_column(Column::strict(90.0))
             .add_column(Column::stretch())
@@ -168,6 +172,7 @@ impl RootMotionDropdownArea {
             .add_row(Row::strict(22.0))
             .add_row(Row::strict(22.0))
             .add_row(Row::strict(22.0))
+            .add_row(Row::strict(22.0))
             .add_row(Row::stretch())
             .build(ctx),
         )
@@ -181,6 +186,7 @@ impl RootMotionDropdownArea {
             ignore_y,
             ignore_z,
             ignore_rotation,
+            keep_in_pose,
             node_selector: Default::default(),
         }
     }
@@ -238,6 +244,13 @@ impl RootMotionDropdownArea {
                                 ..*settings
                             }));
                         }
+                    } else if message.destination() == self.keep_in_pose {
+                        if let Some(settings) = animation.root_motion_settings_ref() {
+                            send_command(Some(RootMotionSettings {
+                                keep_in_pose: *value,
+                                ..*settings
+                            }));
+                        }
                     }
                 }
             } else if let Some(ButtonMessage::Click) = message.data() {
@@ -335,6 +348,7 @@ impl RootMotionDropdownArea {
                 self.ignore_y,
                 self.ignore_z,
                 self.ignore_rotation,
+                self.keep_in_pose,
             ] {
                 send_sync_message(
                     ui,
@@ -363,6 +377,7 @@ impl RootMotionDropdownArea {
                 sync_checked(ui, self.ignore_y, settings.ignore_y_movement);
                 sync_checked(ui, self.ignore_z, settings.ignore_z_movement);
                 sync_checked(ui, self.ignore_rotation, settings.ignore_rotations);
+                sync_checked(ui, self.keep_in_pose, settings.keep_in_pose);
             }
         }
     }
@@ -28,6 +28,7 @@ use fyrox::{
         algebra::{UnitQuaternion, Vector2, Vector3, Vector4},
         color::Color,
         log::Log,
+        math::Rect,
         parking_lot::Mutex,
         pool::Handle,
         reflect::{prelude::*, Reflect, ResolvePath},
@@ -227,6 +228,10 @@ fn type_id_to_supported_type(property_type: TypeId) -> Option<(TrackValueKind, V
         Some((TrackValueKind::UnitQuaternion, ValueType::UnitQuaternionF32))
     } else if property_type == TypeId::of::<UnitQuaternion<f64>>() {
         Some((TrackValueKind::UnitQuaternion, ValueType::UnitQuaternionF64))
+    } else if property_type == TypeId::of::<Color>() {
+        Some((TrackValueKind::Vector4, ValueType::Color))
+    } else if property_type == TypeId::of::<Rect<f32>>() {
+        Some((TrackValueKind::Vector4, ValueType::RectF32))
     } else {
         None
     }
@@ -1126,7 +1131,9 @@ impl TrackList {
             Vector4<u32>, Vector4<i32>,
             Vector4<i16>, Vector4<u16>, Vector4<i8>, Vector4<u8>,
 
-            UnitQuaternion<f32>
+            UnitQuaternion<f32>,
+
+            Color, Rect<f32>
         })))
         .with_property_descriptors(descriptors)
         .build(&mut ui.build_ctx());
@@ -4,7 +4,7 @@ use crate::{
 use fyrox::core::reflect::Reflect;
 use fyrox::{
     asset::{manager::ResourceManager, options::BaseImportOptions},
-    core::{append_extension, futures::executor::block_on, log::Log, pool::Handle},
+    core::{futures::executor::block_on, log::Log, pool::Handle},
     engine::Engine,
     gui::{
         button::{ButtonBuilder, ButtonMessage},
@@ -162,17 +162,11 @@ impl AssetInspector {
                             });
                         }
                     } else if message.destination() == self.apply {
-                        context
-                            .import_options
-                            .save(&append_extension(&context.resource_path, "options"));
-
-                        if let Ok(resource) = block_on(
+                        block_on(
                             engine
                                 .resource_manager
-                                .request_untyped(&context.resource_path),
-                        ) {
-                            engine.resource_manager.state().reload_resource(resource);
-                        }
+                                .reimport(&context.resource_path, &*context.import_options),
+                        );
                     }
                 } else if let Some(InspectorMessage::PropertyChanged(property_changed)) =
                     message.data()
@@ -211,20 +205,5 @@ fn load_import_options_or_default(
     resource_path: &Path,
     resource_manager: &ResourceManager,
 ) -> Option<Box<dyn BaseImportOptions>> {
-    if let Some(extension) = resource_path.extension() {
-        let rm_state = resource_manager.state();
-        for loader in rm_state.loaders.iter() {
-            if loader.supports_extension(&extension.to_string_lossy()) {
-                return if let Some(import_options) = block_on(loader.try_load_import_settings(
-                    resource_path.to_owned(),
-                    rm_state.resource_io.clone(),
-                )) {
-                    Some(import_options)
-                } else {
-                    loader.default_import_options()
-                };
-            }
-        }
-    }
-    None
+    block_on(resource_manager.load_import_options(resource_path))
 }
@@ -939,19 +939,19 @@ impl AssetBrowser {
                 return false;
             };
 
-            // The engine cannot write FBX resources, so we must filter out these and warn the user
-            // that resource references cannot be automatically fixed.
+            // The engine cannot write FBX resources and text prefabs, so we must filter out these
+            // and warn the user that resource references cannot be automatically fixed.
             if let Some(model) = res.try_cast::<Model>() {
                 let kind = model.kind();
                 if let Some(ext) = kind.path().and_then(|path| {
                     path.extension()
                         .map(|ext| ext.to_string_lossy().to_lowercase())
                 }) {
-                    if ext == "fbx" {
+                    if ext == "fbx" || ext == "prefab" {
                         Log::warn(format!(
                             "Resource {} cannot be scanned for \
-                        references, because FBX cannot be exported.",
-                            kind
+                        references, because {} cannot be exported.",
+                            kind, ext
                         ));
                         return false;
                     }
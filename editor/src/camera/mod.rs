@@ -17,6 +17,7 @@ use fyrox::{
     scene::{
         base::BaseBuilder,
         camera::{Camera, CameraBuilder, Exposure, FitParameters, Projection},
+        folder::Folder,
         graph::Graph,
         mesh::{
             buffer::{VertexAttributeUsage, VertexReadTrait},
@@ -584,6 +585,14 @@ impl CameraController {
 
                 let node = &graph[handle];
 
+                // Contents of locked folders cannot be picked.
+                if node
+                    .cast::<Folder>()
+                    .is_some_and(|folder| folder.is_locked())
+                {
+                    continue;
+                }
+
                 self.stack.extend_from_slice(node.children());
 
                 if !node.global_visibility() || !filter(handle, node) {
@@ -281,6 +281,10 @@ impl SceneRenderPass for HighlightRenderPass {
                                 wvp_matrix: &(view_projection * instance.world_transform),
                                 bone_matrices: &instance.bone_matrices,
                                 use_skeletal_animation: batch.is_skinned,
+                                use_dual_quaternion_skinning: instance.use_dual_quaternion_skinning,
+                                instance_matrices: &[],
+                                instance_bone_count: 0,
+                                property_overrides: instance.property_overrides(),
                                 camera_position: &ctx.camera.global_position(),
                                 camera_up_vector: &camera_up,
                                 camera_side_vector: &camera_side,
@@ -297,8 +301,10 @@ impl SceneRenderPass for HighlightRenderPass {
                                 matrix_storage: ctx.matrix_storage,
                                 persistent_identifier: instance.persistent_identifier,
                                 light_data: None,
+                                velocity_data: None,
                                 ambient_light: Default::default(),
                                 scene_depth: Some(&ctx.depth_texture),
+                                scene_color: None,
                             });
                         },
                     )?;
@@ -30,51 +30,63 @@ use fyrox::{
     },
     gui::UserInterface,
     material::{
-        shader::{Shader, ShaderResource},
-        MaterialResource,
+        shader::{SamplerFallback, Shader, ShaderResource},
+        MaterialResource, PropertyValue as MaterialPropertyValue,
     },
     renderer::framework::state::PolygonFillMode,
     resource::{
         curve::{CurveResource, CurveResourceState},
-        model::{MaterialSearchOptions, Model, ModelResource},
+        light_probe::{LightProbeData, LightProbeDataResource},
+        model::{MaterialSearchOptions, Model, ModelResource, ModelUpAxis},
         texture::{
             CompressionOptions, MipFilter, TextureMagnificationFilter, TextureMinificationFilter,
             TextureResource, TextureWrapMode,
         },
+        texture_atlas::{AtlasRegionRef, AtlasRegionRotation, TextureAtlas, TextureAtlasResource},
     },
     scene::{
         animation::{absm::prelude::*, prelude::*},
-        base::{Base, LevelOfDetail, LodGroup, Mobility, Property, PropertyValue},
+        base::{Base, LevelOfDetail, LodGroup, LodMetric, Mobility, Property, PropertyValue},
         camera::{
             ColorGradingLut, Exposure, OrthographicProjection, PerspectiveProjection, Projection,
-            SkyBox,
+            RenderTargetFormat, SkyBox,
         },
         collider::{
             BallShape, BitMask, CapsuleShape, ColliderShape, ConeShape, ConvexPolyhedronShape,
             CuboidShape, CylinderShape, GeometrySource, HeightfieldShape, InteractionGroups,
             SegmentShape, TriangleShape, TrimeshShape,
         },
-        dim2,
+        csg::{BrushShape, CsgOperation},
+        dim2::{
+            self,
+            tilemap::{
+                tileset::{TileSet, TileSetResource},
+                TileMapLayer,
+            },
+        },
         graph::physics::CoefficientCombineRule,
+        ik::IkSolver,
         joint::*,
         light::{
             directional::{CsmOptions, FrustumSplitOptions},
             BaseLight,
         },
         mesh::{
-            surface::{BlendShape, Surface, SurfaceSharedData},
-            RenderPath,
+            surface::{BlendShape, PropertyOverride, Surface, SurfaceSharedData},
+            RenderPath, SkinningMode,
         },
         node::Node,
         particle_system::{
+            collision::{ParticleCollision, ParticleCollisionResponse},
             emitter::{
                 base::BaseEmitter, cuboid::CuboidEmitter, cylinder::CylinderEmitter,
                 sphere::SphereEmitter, Emitter,
             },
-            ParticleSystemRng,
+            ParticleSimulationMode, ParticleSystemRng,
         },
         ragdoll::Limb,
         rigidbody::RigidBodyType,
+        sorting::SortingOrder,
         sound::{
             self,
             filter::{
@@ -82,14 +94,21 @@ use fyrox::{
                 HighShelfFilterEffect, LowPassFilterEffect, LowShelfFilterEffect,
             },
             reverb::Reverb,
-            Attenuate, AudioBus, Biquad, DistanceModel, Effect, SoundBuffer, SoundBufferResource,
-            Status,
+            Attenuate, AudioBus, Biquad, DistanceModel, Ducking, Effect, Occlusion, SoundBuffer,
+            SoundBufferResource, Status,
         },
+        sprite::{Flipbook, FlipbookMode, NineSlice, SliceMargins},
         terrain::{Chunk, Layer},
+        trail::{TrailAlignment, TrailTextureMode},
         transform::Transform,
+        water::{GerstnerWave, WaterWaveMode},
     },
 };
-use std::{path::Path, sync::Arc};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 pub mod animation;
 pub mod font;
@@ -154,15 +173,24 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
 
     container.register_inheritable_vec_collection::<Emitter>();
 
+    container.register_inheritable_vec_collection::<GerstnerWave>();
+    container.register_inheritable_inspectable::<GerstnerWave>();
+
     container.register_inheritable_vec_collection::<LevelOfDetail>();
     container.register_inheritable_inspectable::<LevelOfDetail>();
 
+    container.register_inheritable_enum::<LodMetric, _>();
+    container.register_inheritable_enum::<IkSolver, _>();
+
     container.register_inheritable_vec_collection::<ErasedHandle>();
     container.register_inheritable_inspectable::<ErasedHandle>();
 
     container.register_inheritable_vec_collection::<Property>();
     container.register_inheritable_inspectable::<Property>();
 
+    container.register_inheritable_vec_collection::<PropertyOverride>();
+    container.register_inheritable_inspectable::<PropertyOverride>();
+
     container.register_inheritable_vec_collection::<GeometrySource>();
     container.register_inheritable_inspectable::<GeometrySource>();
 
@@ -222,6 +250,46 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.insert(InheritablePropertyEditorDefinition::<Option<CurveResource>>::new());
     container.register_inheritable_vec_collection::<Option<CurveResource>>();
 
+    container.insert(ResourceFieldPropertyEditorDefinition::<TileSet>::new(
+        Arc::new(Mutex::new(
+            |resource_manager: &ResourceManager, path: &Path| {
+                resource_manager.try_request::<TileSet>(path).map(block_on)
+            },
+        )),
+        sender.clone(),
+    ));
+    container.insert(InheritablePropertyEditorDefinition::<Option<TileSetResource>>::new());
+
+    container.insert(ResourceFieldPropertyEditorDefinition::<TextureAtlas>::new(
+        Arc::new(Mutex::new(
+            |resource_manager: &ResourceManager, path: &Path| {
+                resource_manager
+                    .try_request::<TextureAtlas>(path)
+                    .map(block_on)
+            },
+        )),
+        sender.clone(),
+    ));
+    container.insert(InheritablePropertyEditorDefinition::<
+        Option<TextureAtlasResource>,
+    >::new());
+
+    container.insert(
+        ResourceFieldPropertyEditorDefinition::<LightProbeData>::new(
+            Arc::new(Mutex::new(
+                |resource_manager: &ResourceManager, path: &Path| {
+                    resource_manager
+                        .try_request::<LightProbeData>(path)
+                        .map(block_on)
+                },
+            )),
+            sender.clone(),
+        ),
+    );
+    container.insert(InheritablePropertyEditorDefinition::<
+        Option<LightProbeDataResource>,
+    >::new());
+
     container.insert(ResourceFieldPropertyEditorDefinition::<UserInterface>::new(
         Arc::new(Mutex::new(
             |resource_manager: &ResourceManager, path: &Path| {
@@ -281,6 +349,10 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
 
     container.register_inheritable_inspectable::<Biquad>();
     container.register_inheritable_inspectable::<AudioBus>();
+    container.register_inheritable_inspectable::<Ducking>();
+    container.register_inheritable_option::<Ducking>();
+    container.register_inheritable_inspectable::<Occlusion>();
+    container.register_inheritable_option::<Occlusion>();
     container.register_inheritable_inspectable::<BaseEmitter>();
     container.register_inheritable_inspectable::<SphereEmitter>();
     container.register_inheritable_inspectable::<CylinderEmitter>();
@@ -301,6 +373,14 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_option::<SkyBox>();
 
     container.register_inheritable_inspectable::<SkyBox>();
+    container.register_inheritable_inspectable::<Flipbook>();
+    container.register_inheritable_inspectable::<NineSlice>();
+    container.register_inheritable_inspectable::<AtlasRegionRef>();
+    container.register_inheritable_inspectable::<SliceMargins>();
+    container.register_inheritable_inspectable::<SortingOrder>();
+    container.register_inheritable_inspectable::<ParticleCollision>();
+    container.register_inheritable_inspectable::<TileMapLayer>();
+    container.register_inheritable_vec_collection::<TileMapLayer>();
 
     container.register_inheritable_enum::<dim2::collider::ColliderShape, _>();
     container.register_inheritable_enum::<CoefficientCombineRule, _>();
@@ -309,16 +389,29 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_enum::<TextureMagnificationFilter, _>();
     container.register_inheritable_enum::<TextureMinificationFilter, _>();
     container.register_inheritable_enum::<Projection, _>();
+    container.register_inheritable_enum::<RenderTargetFormat, _>();
     container.register_inheritable_enum::<ColliderShape, _>();
     container.register_inheritable_enum::<PropertyValue, _>();
+    container.register_inheritable_enum::<MaterialPropertyValue, _>();
+    container.register_inheritable_enum::<SamplerFallback, _>();
     container.register_inheritable_enum::<Mobility, _>();
     container.register_inheritable_enum::<RigidBodyType, _>();
+    container.register_inheritable_enum::<BrushShape, _>();
+    container.register_inheritable_enum::<CsgOperation, _>();
     container.register_inheritable_enum::<Exposure, _>();
     container.register_inheritable_enum::<FrustumSplitOptions, _>();
     container.register_inheritable_enum::<MaterialSearchOptions, _>();
     container.register_inheritable_enum::<DistanceModel, _>();
     container.register_inheritable_enum::<sound::Renderer, _>();
     container.register_inheritable_enum::<RenderPath, _>();
+    container.register_inheritable_enum::<SkinningMode, _>();
+    container.register_inheritable_enum::<FlipbookMode, _>();
+    container.register_inheritable_enum::<AtlasRegionRotation, _>();
+    container.register_inheritable_enum::<ParticleSimulationMode, _>();
+    container.register_inheritable_enum::<ParticleCollisionResponse, _>();
+    container.register_inheritable_enum::<TrailAlignment, _>();
+    container.register_inheritable_enum::<TrailTextureMode, _>();
+    container.register_inheritable_enum::<WaterWaveMode, _>();
 
     container.insert(ScriptPropertyEditorDefinition {});
     container.insert(BitFieldPropertyEditorDefinition::<BitMask>::new());
@@ -351,6 +444,8 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.insert(VecCollectionPropertyEditorDefinition::<IndexedBlendInput>::new());
     container.insert(InspectablePropertyEditorDefinition::<BlendSpacePoint>::new());
     container.insert(VecCollectionPropertyEditorDefinition::<BlendSpacePoint>::new());
+    container.insert(InspectablePropertyEditorDefinition::<BlendSpace1DPoint>::new());
+    container.insert(VecCollectionPropertyEditorDefinition::<BlendSpace1DPoint>::new());
     container.insert(InspectablePropertyEditorDefinition::<BlendPose>::new());
     container.insert(VecCollectionPropertyEditorDefinition::<BlendPose>::new());
     container.insert(EnumPropertyEditorDefinition::<PoseWeight>::new());
@@ -360,6 +455,7 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.insert(InspectablePropertyEditorDefinition::<BlendAnimationsByIndex>::new());
     container.insert(InspectablePropertyEditorDefinition::<BlendAnimations>::new());
     container.insert(InspectablePropertyEditorDefinition::<BlendSpace>::new());
+    container.insert(InspectablePropertyEditorDefinition::<BlendSpace1D>::new());
     container.insert(InspectablePropertyEditorDefinition::<PlayAnimation>::new());
 
     container.insert(InspectablePropertyEditorDefinition::<Handle<PoseNode>>::new());
@@ -371,6 +467,11 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.insert(AnimationContainerPropertyEditorDefinition);
     container.insert(InheritablePropertyEditorDefinition::<AnimationContainer>::new());
 
+    container.insert(InspectablePropertyEditorDefinition::<LayerMask>::new());
+    container.register_inheritable_enum::<LayerBlendMode, _>();
+    container.register_inheritable_vec_collection::<AnimationLayer>();
+    container.register_inheritable_inspectable::<AnimationLayer>();
+
     container.insert(MachinePropertyEditorDefinition);
     container.insert(InheritablePropertyEditorDefinition::<Machine>::new());
 
@@ -384,11 +485,14 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.insert(EnumPropertyEditorDefinition::<PolygonFillMode>::new());
 
     container.insert(EnumPropertyEditorDefinition::<MipFilter>::new());
+    container.insert(EnumPropertyEditorDefinition::<ModelUpAxis>::new());
+    container.insert(EnumPropertyEditorDefinition::<Range<f32>>::new_optional());
 
     container.register_inheritable_inspectable::<Limb>();
     container.insert(VecCollectionPropertyEditorDefinition::<Limb>::new());
 
     container.insert(PathPropertyEditorDefinition);
+    container.insert(InheritablePropertyEditorDefinition::<PathBuf>::new());
 
     container
 }
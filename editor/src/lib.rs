@@ -606,6 +606,9 @@ impl Editor {
             &engine.user_interface,
             graphics_context.window.scale_factor() as f32,
         );
+        engine
+            .user_interface
+            .set_accessibility_settings(settings.general.accessibility_settings());
 
         let overlay_pass = OverlayRenderPass::new(graphics_context.renderer.pipeline_state());
         graphics_context
@@ -992,10 +995,15 @@ impl Editor {
             }
         }
 
+        let is_2d = entry
+            .controller
+            .downcast_ref::<GameScene>()
+            .is_some_and(|game_scene| self.engine.scenes[game_scene.scene].is_2d());
+
         self.scenes.add_and_select(entry);
 
         self.scene_viewer
-            .reset_camera_projection(&self.engine.user_interface);
+            .reset_camera_projection(&self.engine.user_interface, is_2d);
         self.engine
             .graphics_context
             .as_initialized_mut()
@@ -1952,9 +1960,9 @@ impl Editor {
         self.poll_ui_messages();
     }
 
-    fn create_new_scene(&mut self) {
+    fn create_new_scene(&mut self, scene: Scene) {
         let entry = EditorSceneEntry::new_game_scene(
-            Scene::new(),
+            scene,
             None,
             &mut self.engine,
             &self.settings,
@@ -2245,7 +2253,11 @@ impl Editor {
                         needs_sync |= self.close_scene(scene);
                     }
                     Message::NewScene => {
-                        self.create_new_scene();
+                        self.create_new_scene(Scene::new());
+                        needs_sync = true;
+                    }
+                    Message::New2dScene => {
+                        self.create_new_scene(Scene::new_2d());
                         needs_sync = true;
                     }
                     Message::NewUiScene => {
@@ -3,6 +3,7 @@ use crate::{
     Engine, MSG_SYNC_FLAG,
 };
 use fyrox::{
+    asset::untyped::ResourceKind,
     core::{log::Log, pool::Handle, reflect::prelude::*, scope_profile},
     gui::{
         button::{ButtonBuilder, ButtonMessage},
@@ -18,8 +19,11 @@ use fyrox::{
         window::{WindowBuilder, WindowMessage, WindowTitle},
         BuildContext, HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
     },
+    resource::light_probe::LightProbeDataResource,
+    scene::{light_probe::LightProbeVolume, node::Node},
     utils::lightmap::{
-        CancellationToken, Lightmap, LightmapGenerationError, LightmapInputData, ProgressIndicator,
+        self, CancellationToken, LightProbeBakeSettings, LightProbeVolumeDefinition, Lightmap,
+        LightmapGenerationError, LightmapInputData, LightmapSettings, ProgressIndicator,
     },
 };
 use std::{
@@ -54,18 +58,54 @@ struct LightmapperSettings {
     the lightmapper automatically generates names for the files."
     )]
     path: PathBuf,
+    #[reflect(
+        description = "Amount of paths traced from every texel of the light maps to calculate indirect (bounced) \
+    lighting. Zero disables indirect lighting. Generation time grows linearly with this value.",
+        min_value = 0.0,
+        max_value = 4096.0
+    )]
+    indirect_samples: u32,
+    #[reflect(
+        description = "Maximum amount of bounces of every path, traced to calculate indirect lighting.",
+        min_value = 1.0,
+        max_value = 16.0
+    )]
+    indirect_bounces: u32,
+    #[reflect(
+        description = "Amount of rays traced from every light probe. The more the value, the less noisy baked \
+    light probes will be, baking time grows linearly with this value.",
+        min_value = 1.0,
+        max_value = 4096.0
+    )]
+    probe_samples: u32,
+    #[reflect(
+        description = "Fraction of light that is reflected by surfaces of the scene when baking light probes.",
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.01
+    )]
+    probe_bounce_albedo: f32,
 }
 
 impl Default for LightmapperSettings {
     fn default() -> Self {
+        let lightmap_settings = LightmapSettings::default();
+        let probe_settings = LightProbeBakeSettings::default();
         Self {
-            texels_per_unit: 64,
-            spacing: 0.005,
+            texels_per_unit: lightmap_settings.texels_per_unit,
+            spacing: lightmap_settings.uv_spacing,
             path: Default::default(),
+            indirect_samples: lightmap_settings.indirect_samples,
+            indirect_bounces: lightmap_settings.indirect_bounces,
+            probe_samples: probe_settings.samples,
+            probe_bounce_albedo: probe_settings.bounce_albedo,
         }
     }
 }
 
+type LightProbeBakeResult =
+    Result<Vec<(Handle<Node>, LightProbeDataResource)>, LightmapGenerationError>;
+
 struct ProgressWindow {
     window: Handle<UiNode>,
     progress_bar: Handle<UiNode>,
@@ -187,10 +227,13 @@ pub struct LightPanel {
     pub window: Handle<UiNode>,
     inspector: Handle<UiNode>,
     generate: Handle<UiNode>,
+    bake_probes: Handle<UiNode>,
     settings: LightmapperSettings,
     progress_window: Option<ProgressWindow>,
     sender: Sender<Result<Lightmap, LightmapGenerationError>>,
     receiver: Receiver<Result<Lightmap, LightmapGenerationError>>,
+    probes_sender: Sender<LightProbeBakeResult>,
+    probes_receiver: Receiver<LightProbeBakeResult>,
 }
 
 impl LightPanel {
@@ -199,6 +242,7 @@ impl LightPanel {
         let container = Arc::new(make_property_editors_container(sender));
 
         let generate;
+        let bake_probes;
         let inspector;
         let ctx = &mut engine.user_interface.build_ctx();
         let window = WindowBuilder::new(
@@ -247,25 +291,41 @@ impl LightPanel {
                         .with_text("Generate Lightmap")
                         .build(ctx);
                         generate
+                    })
+                    .with_child({
+                        bake_probes = ButtonBuilder::new(
+                            WidgetBuilder::new()
+                                .on_row(2)
+                                .on_column(0)
+                                .with_margin(Thickness::uniform(1.0)),
+                        )
+                        .with_text("Bake Light Probes")
+                        .build(ctx);
+                        bake_probes
                     }),
             )
             .add_column(Column::stretch())
             .add_row(Row::stretch())
             .add_row(Row::strict(25.0))
+            .add_row(Row::strict(25.0))
             .build(ctx),
         )
         .build(ctx);
 
         let (sender, receiver) = std::sync::mpsc::channel();
+        let (probes_sender, probes_receiver) = std::sync::mpsc::channel();
 
         Self {
             window,
             inspector,
             generate,
+            bake_probes,
             settings,
             progress_window: None,
             sender,
             receiver,
+            probes_sender,
+            probes_receiver,
         }
     }
 
@@ -299,25 +359,30 @@ impl LightPanel {
                     progress_indicator.clone(),
                 ) {
                     let sender = self.sender.clone();
-                    let texels_per_unit = self.settings.texels_per_unit;
-                    let spacing = self.settings.spacing;
+                    let settings = LightmapSettings {
+                        texels_per_unit: self.settings.texels_per_unit,
+                        uv_spacing: self.settings.spacing,
+                        indirect_samples: self.settings.indirect_samples,
+                        indirect_bounces: self.settings.indirect_bounces,
+                        ..Default::default()
+                    };
                     let path = self.settings.path.clone();
                     let resource_manager = engine.resource_manager.clone();
 
                     if let Err(e) = std::thread::Builder::new()
                         .name("LightmapGenerationThread".to_string())
                         .spawn(move || {
-                            match Lightmap::new(
+                            match Lightmap::bake(
                                 input_data,
-                                texels_per_unit,
-                                spacing,
+                                &settings,
                                 cancellation_token,
                                 progress_indicator,
                             ) {
                                 Ok(lightmap) => {
-                                    if lightmap.save_textures(path, resource_manager).is_err() {
+                                    if let Err(err) = lightmap.save_textures(path, resource_manager)
+                                    {
                                         sender
-                                            .send(Err(LightmapGenerationError::Cancelled))
+                                            .send(Err(LightmapGenerationError::SaveError(err)))
                                             .unwrap();
                                     } else {
                                         sender.send(Ok(lightmap)).unwrap();
@@ -337,6 +402,10 @@ impl LightPanel {
                 }
             }
 
+            if message.destination() == self.bake_probes {
+                self.bake_light_probes(game_scene, engine);
+            }
+
             if let Some(progress_window) = self.progress_window.as_ref() {
                 if message.destination() == progress_window.cancel {
                     progress_window.cancellation_token.cancel();
@@ -357,6 +426,87 @@ impl LightPanel {
         }
     }
 
+    fn bake_light_probes(&mut self, game_scene: &GameScene, engine: &mut Engine) {
+        let scene = &engine.scenes[game_scene.scene];
+
+        let volumes = LightProbeVolumeDefinition::from_graph(&scene.graph);
+        if volumes.is_empty() {
+            Log::warn("There are no light probe volumes in the scene, nothing to bake.");
+            return;
+        }
+
+        let progress_indicator = ProgressIndicator::new();
+        let cancellation_token = CancellationToken::new();
+
+        let progress_window = ProgressWindow::new(
+            &mut engine.user_interface.build_ctx(),
+            progress_indicator.clone(),
+            cancellation_token.clone(),
+        );
+        progress_window.open(&engine.user_interface);
+        self.progress_window = Some(progress_window);
+
+        if let Ok(input_data) = LightmapInputData::from_scene(
+            scene,
+            |handle, _| handle != game_scene.editor_objects_root,
+            cancellation_token.clone(),
+            progress_indicator.clone(),
+        ) {
+            let sender = self.probes_sender.clone();
+            let settings = LightProbeBakeSettings {
+                samples: self.settings.probe_samples,
+                bounce_albedo: self.settings.probe_bounce_albedo,
+                ..Default::default()
+            };
+            let path = self.settings.path.clone();
+            let resource_manager = engine.resource_manager.clone();
+
+            if let Err(e) = std::thread::Builder::new()
+                .name("LightProbeBakingThread".to_string())
+                .spawn(move || {
+                    let result = lightmap::bake_light_probes(
+                        input_data,
+                        volumes,
+                        &settings,
+                        cancellation_token,
+                        progress_indicator,
+                    )
+                    .map(|baked| {
+                        if !path.exists() {
+                            Log::verify(std::fs::create_dir_all(&path));
+                        }
+                        baked
+                            .into_iter()
+                            .map(|(handle, data)| {
+                                let resource =
+                                    LightProbeDataResource::new_ok(ResourceKind::Embedded, data);
+                                let file_path = path.join(format!("{}.probes", handle.index()));
+                                if let Err(err) = resource_manager.register(
+                                    resource.clone().into_untyped(),
+                                    &file_path,
+                                    |data, path| data.save(path).is_ok(),
+                                ) {
+                                    Log::err(format!(
+                                        "Failed to save light probes to {}. Reason: {:?}",
+                                        file_path.display(),
+                                        err
+                                    ));
+                                }
+                                (handle, resource)
+                            })
+                            .collect::<Vec<_>>()
+                    });
+                    sender.send(result).unwrap();
+                })
+            {
+                Log::err(format!(
+                    "Failed to create a new light probe baking thread. Reason: {}",
+                    e
+                ))
+            }
+        }
+    }
+
     pub fn update(&mut self, game_scene: &GameScene, engine: &mut Engine) {
         if let Some(progress_window) = self.progress_window.as_ref() {
             progress_window.show_progress(&engine.user_interface);
@@ -379,6 +529,30 @@ impl LightPanel {
                 progress_window.close(&engine.user_interface);
             }
         }
+
+        if let Ok(result) = self.probes_receiver.try_recv() {
+            let scene = &mut engine.scenes[game_scene.scene];
+            match result {
+                Ok(baked) => {
+                    for (handle, data) in baked {
+                        if let Some(volume) = scene
+                            .graph
+                            .try_get_mut(handle)
+                            .and_then(|node| node.cast_mut::<LightProbeVolume>())
+                        {
+                            volume.set_data(Some(data));
+                        }
+                    }
+                }
+                Err(err) => {
+                    Log::err(format!("Failed to bake light probes. Reason: {}", err));
+                }
+            }
+
+            if let Some(progress_window) = self.progress_window.take() {
+                progress_window.close(&engine.user_interface);
+            }
+        }
     }
 
     pub fn is_in_preview_mode(&self) -> bool {
@@ -6,6 +6,7 @@ use fyrox::{
         animation::{absm::prelude::*, prelude::*},
         base::BaseBuilder,
         node::Node,
+        timeline::TimelinePlayerBuilder,
     },
 };
 
@@ -13,12 +14,14 @@ pub struct AnimationMenu {
     pub menu: Handle<UiNode>,
     create_animation_player: Handle<UiNode>,
     create_absm: Handle<UiNode>,
+    create_timeline_player: Handle<UiNode>,
 }
 
 impl AnimationMenu {
     pub fn new(ctx: &mut BuildContext) -> Self {
         let create_animation_player;
         let create_absm;
+        let create_timeline_player;
 
         let menu = create_menu_item(
             "Animation",
@@ -31,6 +34,10 @@ impl AnimationMenu {
                     create_absm = create_menu_item("Animation Blending State Machine", vec![], ctx);
                     create_absm
                 },
+                {
+                    create_timeline_player = create_menu_item("Timeline Player", vec![], ctx);
+                    create_timeline_player
+                },
             ],
             ctx,
         );
@@ -39,6 +46,7 @@ impl AnimationMenu {
             menu,
             create_animation_player,
             create_absm,
+            create_timeline_player,
         }
     }
 
@@ -63,6 +71,11 @@ impl AnimationMenu {
                 .with_machine(machine)
                 .build_node();
                 Some(node)
+            } else if message.destination() == self.create_timeline_player {
+                let node =
+                    TimelinePlayerBuilder::new(BaseBuilder::new().with_name("Timeline Player"))
+                        .build_node();
+                Some(node)
             } else {
                 None
             }
@@ -5,7 +5,7 @@ use crate::{
         physics::PhysicsMenu, physics2d::Physics2dMenu, ui::UiMenu,
     },
     message::MessageSender,
-    scene::{commands::graph::AddNodeCommand, controller::SceneController, Selection},
+    scene::{commands::graph::AddNodeCommand, controller::SceneController, GameScene, Selection},
     ui_scene::UiScene,
     Mode,
 };
@@ -16,6 +16,7 @@ use fyrox::{
         math::TriangleDefinition,
         pool::Handle,
     },
+    engine::Engine,
     gui::{
         menu::MenuItemMessage, message::MessageDirection, message::UiMessage,
         widget::WidgetMessage, BuildContext, UiNode, UserInterface,
@@ -25,11 +26,15 @@ use fyrox::{
     scene::{
         base::BaseBuilder,
         camera::CameraBuilder,
+        csg::{BrushBuilder, BrushShape, CsgModelBuilder},
         decal::DecalBuilder,
+        folder::FolderBuilder,
+        ik::IkConstraintBuilder,
         light::{
             directional::DirectionalLightBuilder, point::PointLightBuilder, spot::SpotLightBuilder,
             BaseLightBuilder,
         },
+        light_probe::LightProbeVolumeBuilder,
         mesh::{
             surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
             MeshBuilder,
@@ -41,9 +46,12 @@ use fyrox::{
             ParticleSystemBuilder,
         },
         pivot::PivotBuilder,
-        sound::{listener::ListenerBuilder, SoundBuilder},
+        sound::{listener::ListenerBuilder, reverb_zone::ReverbZoneBuilder, SoundBuilder},
         sprite::SpriteBuilder,
+        streaming::StreamingVolumeBuilder,
         terrain::{Layer, TerrainBuilder},
+        trail::TrailBuilder,
+        water::WaterBuilder,
     },
     utils::navmesh::Navmesh,
 };
@@ -68,10 +76,11 @@ impl CreateEntityRootMenu {
         sender: &MessageSender,
         controller: &mut dyn SceneController,
         selection: &Selection,
+        engine: &Engine,
     ) {
         if let Some(node) = self
             .sub_menus
-            .handle_ui_message(message, sender, controller, selection)
+            .handle_ui_message(message, sender, controller, selection, engine)
         {
             sender.do_scene_command(AddNodeCommand::new(node, Handle::NONE, true));
         }
@@ -92,6 +101,9 @@ impl CreateEntityRootMenu {
 
 pub struct CreateEntityMenu {
     create_pivot: Handle<UiNode>,
+    create_folder: Handle<UiNode>,
+    create_ik_constraint: Handle<UiNode>,
+    create_streaming_volume: Handle<UiNode>,
     create_cube: Handle<UiNode>,
     create_cone: Handle<UiNode>,
     create_sphere: Handle<UiNode>,
@@ -101,12 +113,20 @@ pub struct CreateEntityMenu {
     create_point_light: Handle<UiNode>,
     create_spot_light: Handle<UiNode>,
     create_directional_light: Handle<UiNode>,
+    create_light_probe_volume: Handle<UiNode>,
+    create_csg_model: Handle<UiNode>,
+    create_box_brush: Handle<UiNode>,
+    create_cylinder_brush: Handle<UiNode>,
+    create_wedge_brush: Handle<UiNode>,
     create_navmesh: Handle<UiNode>,
     create_terrain: Handle<UiNode>,
     create_camera: Handle<UiNode>,
     create_sprite: Handle<UiNode>,
     create_particle_system: Handle<UiNode>,
+    create_trail: Handle<UiNode>,
+    create_water: Handle<UiNode>,
     create_listener: Handle<UiNode>,
+    create_reverb_zone: Handle<UiNode>,
     create_sound_source: Handle<UiNode>,
     physics_menu: PhysicsMenu,
     physics2d_menu: Physics2dMenu,
@@ -117,6 +137,7 @@ pub struct CreateEntityMenu {
     mesh_menu: Handle<UiNode>,
     sound_menu: Handle<UiNode>,
     light_menu: Handle<UiNode>,
+    csg_menu: Handle<UiNode>,
 }
 
 fn placeholder_material() -> MaterialResource {
@@ -135,15 +156,26 @@ impl CreateEntityMenu {
         let create_point_light;
         let create_spot_light;
         let create_directional_light;
+        let create_light_probe_volume;
+        let create_csg_model;
+        let create_box_brush;
+        let create_cylinder_brush;
+        let create_wedge_brush;
         let create_camera;
         let create_sprite;
         let create_decal;
         let create_navmesh;
         let create_particle_system;
+        let create_trail;
+        let create_water;
         let create_terrain;
         let create_pivot;
+        let create_folder;
+        let create_ik_constraint;
+        let create_streaming_volume;
         let create_sound_source;
         let create_listener;
+        let create_reverb_zone;
         let physics_menu = PhysicsMenu::new(ctx);
         let physics2d_menu = Physics2dMenu::new(ctx);
         let dim2_menu = Dim2Menu::new(ctx);
@@ -151,6 +183,7 @@ impl CreateEntityMenu {
         let mesh_menu;
         let sound_menu;
         let light_menu;
+        let csg_menu;
 
         let ui_menu = UiMenu::new(UiMenu::default_entries(), "UI", ctx);
 
@@ -160,6 +193,18 @@ impl CreateEntityMenu {
                 create_pivot = create_menu_item("Pivot", vec![], ctx);
                 create_pivot
             },
+            {
+                create_folder = create_menu_item("Folder", vec![], ctx);
+                create_folder
+            },
+            {
+                create_ik_constraint = create_menu_item("IK Constraint", vec![], ctx);
+                create_ik_constraint
+            },
+            {
+                create_streaming_volume = create_menu_item("Streaming Volume", vec![], ctx);
+                create_streaming_volume
+            },
             {
                 mesh_menu = create_menu_item(
                     "Mesh",
@@ -201,6 +246,10 @@ impl CreateEntityMenu {
                             create_listener = create_menu_item("Listener", vec![], ctx);
                             create_listener
                         },
+                        {
+                            create_reverb_zone = create_menu_item("Reverb Zone", vec![], ctx);
+                            create_reverb_zone
+                        },
                     ],
                     ctx,
                 );
@@ -223,11 +272,41 @@ impl CreateEntityMenu {
                             create_point_light = create_menu_item("Point Light", vec![], ctx);
                             create_point_light
                         },
+                        {
+                            create_light_probe_volume =
+                                create_menu_item("Light Probe Volume", vec![], ctx);
+                            create_light_probe_volume
+                        },
                     ],
                     ctx,
                 );
                 light_menu
             },
+            {
+                csg_menu = create_menu_item(
+                    "CSG",
+                    vec![
+                        {
+                            create_csg_model = create_menu_item("CSG Model", vec![], ctx);
+                            create_csg_model
+                        },
+                        {
+                            create_box_brush = create_menu_item("Box Brush", vec![], ctx);
+                            create_box_brush
+                        },
+                        {
+                            create_cylinder_brush = create_menu_item("Cylinder Brush", vec![], ctx);
+                            create_cylinder_brush
+                        },
+                        {
+                            create_wedge_brush = create_menu_item("Wedge Brush", vec![], ctx);
+                            create_wedge_brush
+                        },
+                    ],
+                    ctx,
+                );
+                csg_menu
+            },
             physics_menu.menu,
             physics2d_menu.menu,
             dim2_menu.menu,
@@ -244,6 +323,14 @@ impl CreateEntityMenu {
                 create_particle_system = create_menu_item("Particle System", vec![], ctx);
                 create_particle_system
             },
+            {
+                create_trail = create_menu_item("Trail", vec![], ctx);
+                create_trail
+            },
+            {
+                create_water = create_menu_item("Water", vec![], ctx);
+                create_water
+            },
             {
                 create_terrain = create_menu_item("Terrain", vec![], ctx);
                 create_terrain
@@ -268,13 +355,24 @@ impl CreateEntityMenu {
                 create_point_light,
                 create_spot_light,
                 create_directional_light,
+                create_light_probe_volume,
+                create_csg_model,
+                create_box_brush,
+                create_cylinder_brush,
+                create_wedge_brush,
                 create_camera,
                 create_sprite,
                 create_particle_system,
+                create_trail,
+                create_water,
                 create_pivot,
+                create_folder,
+                create_ik_constraint,
+                create_streaming_volume,
                 create_terrain,
                 create_sound_source,
                 create_listener,
+                create_reverb_zone,
                 create_navmesh,
                 create_decal,
                 physics_menu,
@@ -285,6 +383,7 @@ impl CreateEntityMenu {
                 mesh_menu,
                 light_menu,
                 sound_menu,
+                csg_menu,
             },
             items,
         )
@@ -302,10 +401,16 @@ impl CreateEntityMenu {
         for widget in [
             self.mesh_menu,
             self.light_menu,
+            self.csg_menu,
             self.create_camera,
             self.create_sprite,
             self.create_particle_system,
+            self.create_trail,
+            self.create_water,
             self.create_pivot,
+            self.create_folder,
+            self.create_ik_constraint,
+            self.create_streaming_volume,
             self.create_terrain,
             self.sound_menu,
             self.create_navmesh,
@@ -329,6 +434,7 @@ impl CreateEntityMenu {
         sender: &MessageSender,
         controller: &mut dyn SceneController,
         selection: &Selection,
+        engine: &Engine,
     ) -> Option<Node> {
         if let Some(ui_scene) = controller.downcast_mut::<UiScene>() {
             self.ui_menu
@@ -364,6 +470,22 @@ impl CreateEntityMenu {
                         )
                     } else if message.destination() == self.create_pivot {
                         Some(PivotBuilder::new(BaseBuilder::new().with_name("Pivot")).build_node())
+                    } else if message.destination() == self.create_folder {
+                        Some(
+                            FolderBuilder::new(BaseBuilder::new().with_name("Folder")).build_node(),
+                        )
+                    } else if message.destination() == self.create_streaming_volume {
+                        Some(
+                            StreamingVolumeBuilder::new(
+                                BaseBuilder::new().with_name("StreamingVolume"),
+                            )
+                            .build_node(),
+                        )
+                    } else if message.destination() == self.create_ik_constraint {
+                        Some(
+                            IkConstraintBuilder::new(BaseBuilder::new().with_name("IK Constraint"))
+                                .build_node(),
+                        )
                     } else if message.destination() == self.create_point_light {
                         Some(
                             PointLightBuilder::new(BaseLightBuilder::new(
@@ -426,8 +548,17 @@ impl CreateEntityMenu {
                                 .build_node(),
                         )
                     } else if message.destination() == self.create_camera {
+                        // Cameras of 2D scenes should use pixel-perfect orthographic projection.
+                        let projection = controller
+                            .downcast_ref::<GameScene>()
+                            .map(|game_scene| {
+                                engine.scenes[game_scene.scene].default_camera_projection()
+                            })
+                            .unwrap_or_default();
                         Some(
-                            CameraBuilder::new(BaseBuilder::new().with_name("Camera")).build_node(),
+                            CameraBuilder::new(BaseBuilder::new().with_name("Camera"))
+                                .with_projection(projection)
+                                .build_node(),
                         )
                     } else if message.destination() == self.create_navmesh {
                         let navmesh = Navmesh::new(
@@ -448,6 +579,10 @@ impl CreateEntityMenu {
                         Some(
                             SpriteBuilder::new(BaseBuilder::new().with_name("Sprite")).build_node(),
                         )
+                    } else if message.destination() == self.create_trail {
+                        Some(TrailBuilder::new(BaseBuilder::new().with_name("Trail")).build_node())
+                    } else if message.destination() == self.create_water {
+                        Some(WaterBuilder::new(BaseBuilder::new().with_name("Water")).build_node())
                     } else if message.destination() == self.create_sound_source {
                         Some(SoundBuilder::new(BaseBuilder::new().with_name("Sound")).build_node())
                     } else if message.destination() == self.create_particle_system {
@@ -475,11 +610,42 @@ impl CreateEntityMenu {
                         )
                     } else if message.destination() == self.create_decal {
                         Some(DecalBuilder::new(BaseBuilder::new().with_name("Decal")).build_node())
+                    } else if message.destination() == self.create_light_probe_volume {
+                        Some(
+                            LightProbeVolumeBuilder::new(
+                                BaseBuilder::new().with_name("LightProbeVolume"),
+                            )
+                            .build_node(),
+                        )
+                    } else if message.destination() == self.create_csg_model {
+                        Some(
+                            CsgModelBuilder::new(BaseBuilder::new().with_name("CsgModel"))
+                                .build_node(),
+                        )
+                    } else if let Some(shape) = [
+                        (self.create_box_brush, BrushShape::Box),
+                        (self.create_cylinder_brush, BrushShape::Cylinder),
+                        (self.create_wedge_brush, BrushShape::Wedge),
+                    ]
+                    .into_iter()
+                    .find_map(|(item, shape)| (message.destination() == item).then_some(shape))
+                    {
+                        Some(
+                            BrushBuilder::new(BaseBuilder::new().with_name("Brush"))
+                                .with_shape(shape)
+                                .with_material(placeholder_material())
+                                .build_node(),
+                        )
                     } else if message.destination() == self.create_listener {
                         Some(
                             ListenerBuilder::new(BaseBuilder::new().with_name("Listener"))
                                 .build_node(),
                         )
+                    } else if message.destination() == self.create_reverb_zone {
+                        Some(
+                            ReverbZoneBuilder::new(BaseBuilder::new().with_name("ReverbZone"))
+                                .build_node(),
+                        )
                     } else {
                         None
                     }
@@ -23,6 +23,7 @@ use std::path::PathBuf;
 pub struct FileMenu {
     pub menu: Handle<UiNode>,
     new_scene: Handle<UiNode>,
+    new_2d_scene: Handle<UiNode>,
     new_ui_scene: Handle<UiNode>,
     pub save: Handle<UiNode>,
     pub save_as: Handle<UiNode>,
@@ -54,6 +55,7 @@ fn make_recent_files_items(
 impl FileMenu {
     pub fn new(engine: &mut Engine, settings: &Settings) -> Self {
         let new_scene;
+        let new_2d_scene;
         let new_ui_scene;
         let save;
         let save_as;
@@ -85,6 +87,10 @@ impl FileMenu {
                     new_scene = create_menu_item_shortcut("New Scene", "Ctrl+N", vec![], ctx);
                     new_scene
                 },
+                {
+                    new_2d_scene = create_menu_item("New 2D Scene", vec![], ctx);
+                    new_2d_scene
+                },
                 {
                     new_ui_scene = create_menu_item("New UI Scene", vec![], ctx);
                     new_ui_scene
@@ -144,6 +150,7 @@ impl FileMenu {
             load_file_selector,
             menu,
             new_scene,
+            new_2d_scene,
             new_ui_scene,
             save,
             save_as,
@@ -265,6 +272,8 @@ impl FileMenu {
                 sender.send(Message::Exit { force: false });
             } else if message.destination() == self.new_scene {
                 sender.send(Message::NewScene);
+            } else if message.destination() == self.new_2d_scene {
+                sender.send(Message::New2dScene);
             } else if message.destination() == self.new_ui_scene {
                 sender.send(Message::NewUiScene);
             } else if message.destination() == self.configure {
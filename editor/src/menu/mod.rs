@@ -184,6 +184,7 @@ impl Menu {
                 &self.message_sender,
                 &mut *entry.controller,
                 &entry.selection,
+                ctx.engine,
             );
         }
 
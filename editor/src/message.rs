@@ -37,6 +37,7 @@ pub enum Message {
         working_directory: PathBuf,
     },
     NewScene,
+    New2dScene,
     NewUiScene,
     Exit {
         force: bool,
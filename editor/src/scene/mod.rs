@@ -228,19 +228,32 @@ impl GameScene {
 
         scene.drawing_context.clear_lines();
 
+        let is_2d = scene.is_2d();
+
         if let Selection::Graph(selection) = editor_selection {
             for &node in selection.nodes() {
                 let node = &scene.graph[node];
-                scene.drawing_context.draw_oob(
-                    &node.local_bounding_box(),
-                    node.global_transform(),
-                    Color::GREEN,
-                );
+                if is_2d {
+                    scene.drawing_context.draw_oob_2d(
+                        &node.local_bounding_box(),
+                        node.global_transform(),
+                        Color::GREEN,
+                    );
+                } else {
+                    scene.drawing_context.draw_oob(
+                        &node.local_bounding_box(),
+                        node.global_transform(),
+                        Color::GREEN,
+                    );
+                }
             }
         }
 
         if debug_settings.show_physics {
-            scene.graph.physics.draw(&mut scene.drawing_context);
+            // 3D physics is not simulated in 2D scenes, so there's no need to draw it.
+            if !is_2d {
+                scene.graph.physics.draw(&mut scene.drawing_context);
+            }
             scene.graph.physics2d.draw(&mut scene.drawing_context);
         }
 
@@ -251,6 +264,7 @@ impl GameScene {
             editor_selection: &Selection,
             game_scene: &GameScene,
             settings: &Settings,
+            is_2d: bool,
         ) {
             // Ignore editor nodes.
             if node == game_scene.editor_objects_root {
@@ -260,11 +274,19 @@ impl GameScene {
             let node = &graph[node];
 
             if settings.debugging.show_bounds {
-                ctx.draw_oob(
-                    &AxisAlignedBoundingBox::unit(),
-                    node.global_transform(),
-                    Color::opaque(255, 127, 39),
-                );
+                if is_2d {
+                    ctx.draw_oob_2d(
+                        &AxisAlignedBoundingBox::unit(),
+                        node.global_transform(),
+                        Color::opaque(255, 127, 39),
+                    );
+                } else {
+                    ctx.draw_oob(
+                        &AxisAlignedBoundingBox::unit(),
+                        node.global_transform(),
+                        Color::opaque(255, 127, 39),
+                    );
+                }
             }
 
             if node.cast::<Mesh>().is_some() {
@@ -330,7 +352,15 @@ impl GameScene {
             }
 
             for &child in node.children() {
-                draw_recursively(child, graph, ctx, editor_selection, game_scene, settings)
+                draw_recursively(
+                    child,
+                    graph,
+                    ctx,
+                    editor_selection,
+                    game_scene,
+                    settings,
+                    is_2d,
+                )
             }
         }
 
@@ -342,6 +372,7 @@ impl GameScene {
             editor_selection,
             self,
             settings,
+            is_2d,
         );
     }
 
@@ -26,7 +26,7 @@ use fyrox::{
             physics::{IntegrationParameters, PhysicsWorld},
             Graph, NodePool,
         },
-        SceneRenderingOptions,
+        SceneMode, SceneRenderingOptions,
     },
     utils::lightmap::Lightmap,
 };
@@ -65,6 +65,7 @@ impl SceneSettingsWindow {
         container.register_inheritable_inspectable::<PhysicsWorld>();
         container.register_inheritable_inspectable::<dim2::physics::PhysicsWorld>();
         container.register_inheritable_inspectable::<SceneRenderingOptions>();
+        container.register_inheritable_enum::<SceneMode, _>();
         container.insert(EnumPropertyEditorDefinition::<Color>::new_optional());
 
         Self {
@@ -741,12 +741,12 @@ impl SceneViewer {
         ));
     }
 
-    pub fn reset_camera_projection(&self, ui: &UserInterface) {
-        // Default camera projection is Perspective.
+    pub fn reset_camera_projection(&self, ui: &UserInterface, orthographic: bool) {
+        // Default camera projection is Perspective, 2D scenes use Orthographic projection.
         ui.send_message(DropdownListMessage::selection(
             self.camera_projection,
             MessageDirection::ToWidget,
-            Some(0),
+            Some(orthographic as usize),
         ));
     }
 
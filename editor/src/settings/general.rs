@@ -1,4 +1,4 @@
-use fyrox::core::reflect::prelude::*;
+use fyrox::{core::reflect::prelude::*, gui::accessibility::AccessibilitySettings};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, PartialEq, Clone, Debug, Reflect)]
@@ -14,6 +14,18 @@ pub struct GeneralSettings {
     )]
     #[serde(default = "default_suspension_state")]
     pub suspend_unfocused_editor: bool,
+
+    #[reflect(
+        description = "Increases contrast of every widget of the editor, so the widgets become easier to distinguish."
+    )]
+    #[serde(default)]
+    pub high_contrast: bool,
+
+    #[reflect(
+        description = "Disables non-essential animations of the editor widgets (for example, blinking of text caret)."
+    )]
+    #[serde(default)]
+    pub reduced_motion: bool,
 }
 
 fn default_suspension_state() -> bool {
@@ -25,6 +37,17 @@ impl Default for GeneralSettings {
         Self {
             show_node_removal_dialog: true,
             suspend_unfocused_editor: default_suspension_state(),
+            high_contrast: false,
+            reduced_motion: false,
+        }
+    }
+}
+
+impl GeneralSettings {
+    pub fn accessibility_settings(&self) -> AccessibilitySettings {
+        AccessibilitySettings {
+            high_contrast: self.high_contrast,
+            reduced_motion: self.reduced_motion,
         }
     }
 }
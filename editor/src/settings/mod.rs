@@ -30,7 +30,10 @@ use fyrox::{
         window::{WindowBuilder, WindowMessage, WindowTitle},
         HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
     },
-    renderer::{CsmSettings, QualitySettings, ShadowMapPrecision},
+    renderer::{
+        AmbientOcclusionMethod, AmbientOcclusionQuality, CsmSettings, QualitySettings,
+        ShadowMapPrecision,
+    },
 };
 use ron::ser::PrettyConfig;
 use serde::{Deserialize, Serialize};
@@ -184,6 +187,8 @@ impl SettingsData {
         container.insert(InspectablePropertyEditorDefinition::<GraphicsSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<SelectionSettings>::new());
         container.insert(EnumPropertyEditorDefinition::<ShadowMapPrecision>::new());
+        container.insert(EnumPropertyEditorDefinition::<AmbientOcclusionMethod>::new());
+        container.insert(EnumPropertyEditorDefinition::<AmbientOcclusionQuality>::new());
         container.insert(InspectablePropertyEditorDefinition::<DebuggingSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<CsmSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<QualitySettings>::new());
@@ -350,5 +355,12 @@ impl SettingsWindow {
                 Log::info("New graphics quality settings were successfully set!");
             }
         }
+
+        let accessibility_settings = settings.general.accessibility_settings();
+        if *engine.user_interface.accessibility_settings() != accessibility_settings {
+            engine
+                .user_interface
+                .set_accessibility_settings(accessibility_settings);
+        }
     }
 }
@@ -45,6 +45,7 @@ impl SceneLoader {
                 z_near: -0.1,
                 z_far: 16.0,
                 vertical_size: 2.0,
+                pixels_per_unit: None,
             }))
             .build(&mut scene.graph);
 
@@ -0,0 +1,76 @@
+//! Headless lightmap baking. Loads a scene, bakes a lightmap for every static mesh of it (including
+//! indirect lighting) and saves the scene back with the lightmap applied. Lightmap textures are saved
+//! next to the scene.
+//!
+//! Usage: `cargo run --release --example bake_lightmap -- <path/to/scene.rgs> [texels per unit] [indirect samples]`
+
+use fyrox::{
+    asset::{io::FsResourceIo, manager::ResourceManager},
+    core::{futures::executor::block_on, task::TaskPool, visitor::Visitor},
+    engine::{Engine, EngineInitParams, SerializationContext},
+    scene::SceneLoader,
+    utils::lightmap::{CancellationToken, Lightmap, LightmapSettings, ProgressIndicator},
+};
+use std::{path::PathBuf, sync::Arc};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let Some(scene_path) = args.next().map(PathBuf::from) else {
+        eprintln!("Usage: bake_lightmap <path/to/scene.rgs> [texels per unit] [indirect samples]");
+        return;
+    };
+    let defaults = LightmapSettings::default();
+    let settings = LightmapSettings {
+        texels_per_unit: args
+            .next()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.texels_per_unit),
+        indirect_samples: args.next().and_then(|v| v.parse().ok()).unwrap_or(16),
+        ..defaults
+    };
+
+    // The engine is used only to register resource loaders, graphics context is not needed.
+    let task_pool = Arc::new(TaskPool::new());
+    let engine = Engine::new(EngineInitParams {
+        graphics_context_params: Default::default(),
+        serialization_context: Arc::new(SerializationContext::new()),
+        resource_manager: ResourceManager::new(task_pool.clone()),
+        task_pool,
+    })
+    .unwrap();
+
+    let (loader, _) = block_on(SceneLoader::from_file(
+        &scene_path,
+        &FsResourceIo,
+        engine.serialization_context.clone(),
+        engine.resource_manager.clone(),
+    ))
+    .expect("Unable to load the scene!");
+    let mut scene = block_on(loader.finish(&engine.resource_manager));
+    scene.graph.update_hierarchical_data();
+
+    let textures_path = scene_path.with_extension("lightmap");
+    let progress_indicator = ProgressIndicator::with_callback(|stage, percent| {
+        println!("{stage}: {percent}%");
+    });
+
+    Lightmap::bake_scene(
+        &mut scene,
+        &settings,
+        &textures_path,
+        engine.resource_manager.clone(),
+        CancellationToken::new(),
+        progress_indicator,
+    )
+    .expect("Unable to bake the lightmap!");
+
+    let mut visitor = Visitor::new();
+    scene.save("Scene", &mut visitor).unwrap();
+    visitor.save_binary(&scene_path).unwrap();
+
+    println!(
+        "Lightmap textures are saved to {}, the scene is saved to {}.",
+        textures_path.display(),
+        scene_path.display()
+    );
+}
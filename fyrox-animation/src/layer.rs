@@ -0,0 +1,256 @@
+//! Animation layers allows you to play multiple animations on top of each other, for example upper body aiming over
+//! lower body locomotion. See [`AnimationLayer`] docs for more info.
+
+use crate::{
+    core::{
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        visitor::prelude::*,
+    },
+    machine::LayerMask,
+    Animation, AnimationContainer, AnimationPose, EntityId,
+};
+use fyrox_core::{NameProvider, TypeUuidProvider};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Defines how a pose of a layer is combined with the pose produced by the layers below it.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum LayerBlendMode {
+    /// The pose of the layer is interpolated with the pose below using the weight of the layer, which means that the
+    /// layer with weight 1.0 fully overrides the animated properties.
+    #[default]
+    Override,
+    /// A difference between the pose of the layer and a reference pose is added on top of the pose below (scaled by
+    /// the weight of the layer). Additive layers are useful to add small motions (breathing, recoil, leaning, etc.) on
+    /// top of any other animation.
+    Additive,
+}
+
+uuid_provider!(LayerBlendMode = "9b0e6c4d-2a7f-4e18-8f35-c1d7a6e2b904");
+
+/// Animation layer plays a single animation on top of the pose produced by the layers below it (or by other animations).
+/// A layer has a weight, a mask that prevents the layer from animating certain nodes (for example, a layer for upper
+/// body should exclude every bone of lower body) and a blend mode (see [`LayerBlendMode`] docs for more info).
+///
+/// Additive layers require a reference pose, the difference between the current pose of the layer animation and the
+/// reference pose is added to the pose below. Reference pose is the first frame of the reference animation, usually it
+/// is the layer animation itself. If the reference animation is not set, the layer animation is considered as a
+/// difference itself.
+#[derive(Debug, Visit, Reflect, Clone, PartialEq)]
+pub struct AnimationLayer<T: EntityId> {
+    /// Name of the layer.
+    pub name: String,
+    /// An animation, that is played on the layer.
+    pub animation: Handle<Animation<T>>,
+    /// Weight of the layer, it defines how much the layer affects the pose below it.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub weight: f32,
+    /// A mask, that prevents the layer from animating certain nodes.
+    pub mask: LayerMask<T>,
+    /// Defines how a pose of the layer is combined with the pose below it.
+    pub blend_mode: LayerBlendMode,
+    /// An animation, which first frame is used as a reference pose for additive blending.
+    pub additive_reference: Handle<Animation<T>>,
+}
+
+impl<T: EntityId> Default for AnimationLayer<T> {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            animation: Default::default(),
+            weight: 1.0,
+            mask: Default::default(),
+            blend_mode: Default::default(),
+            additive_reference: Default::default(),
+        }
+    }
+}
+
+impl<T: EntityId> TypeUuidProvider for AnimationLayer<T> {
+    fn type_uuid() -> Uuid {
+        uuid!("5f2d8a61-7c3e-4b09-a4d2-e81b9f6c3a75")
+    }
+}
+
+impl<T: EntityId> NameProvider for AnimationLayer<T> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T: EntityId> AnimationLayer<T> {
+    /// Creates a new layer, that plays the given animation with full weight.
+    pub fn new<S: AsRef<str>>(name: S, animation: Handle<Animation<T>>) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            animation,
+            ..Default::default()
+        }
+    }
+
+    /// Sets desired weight of the layer.
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Sets desired mask of the layer.
+    pub fn with_mask(mut self, mask: LayerMask<T>) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// Sets desired blend mode of the layer.
+    pub fn with_blend_mode(mut self, blend_mode: LayerBlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    /// Sets desired reference animation for additive blending.
+    pub fn with_additive_reference(mut self, additive_reference: Handle<Animation<T>>) -> Self {
+        self.additive_reference = additive_reference;
+        self
+    }
+
+    /// Combines the current pose of the layer animation with the given pose. Disabled (or missing) animations do not
+    /// affect the pose.
+    pub fn blend_into(&self, animations: &AnimationContainer<T>, pose: &mut AnimationPose<T>) {
+        let Some(animation) = animations
+            .try_get(self.animation)
+            .filter(|animation| animation.is_enabled())
+        else {
+            return;
+        };
+
+        let mut layer_pose = animation.pose().clone();
+        layer_pose
+            .poses_mut()
+            .retain(|handle, _| self.mask.should_animate(*handle));
+
+        blend_layer_pose(
+            pose,
+            &layer_pose,
+            self.blend_mode,
+            self.additive_reference,
+            animations,
+            self.weight,
+        );
+    }
+}
+
+/// Combines the pose of a layer with the given pose using the given blend mode. Additive reference pose is sampled from
+/// the first frame of the given reference animation (if any).
+pub fn blend_layer_pose<T: EntityId>(
+    pose: &mut AnimationPose<T>,
+    layer_pose: &AnimationPose<T>,
+    blend_mode: LayerBlendMode,
+    additive_reference: Handle<Animation<T>>,
+    animations: &AnimationContainer<T>,
+    weight: f32,
+) {
+    match blend_mode {
+        LayerBlendMode::Override => pose.blend_with(layer_pose, weight),
+        LayerBlendMode::Additive => {
+            let reference = animations
+                .try_get(additive_reference)
+                .map(|animation| animation.sample_pose(animation.time_slice().start));
+            pose.blend_additive(layer_pose, reference.as_ref(), weight);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        container::{TrackDataContainer, TrackValueKind},
+        core::{
+            algebra::Vector3,
+            curve::{Curve, CurveKey, CurveKeyKind},
+            pool::ErasedHandle,
+        },
+        layer::{AnimationLayer, LayerBlendMode},
+        machine::LayerMask,
+        track::Track,
+        value::{TrackValue, ValueBinding},
+        Animation, AnimationContainer, AnimationPose,
+    };
+
+    fn position_animation(node: ErasedHandle, start: f32, end: f32) -> Animation<ErasedHandle> {
+        let mut container = TrackDataContainer::new(TrackValueKind::Vector3);
+        container.curves_mut()[0] = Curve::from(vec![
+            CurveKey::new(0.0, start, CurveKeyKind::Linear),
+            CurveKey::new(1.0, end, CurveKeyKind::Linear),
+        ]);
+        let mut animation = Animation::default();
+        animation.add_track(Track::new(container, ValueBinding::Position).with_target(node));
+        animation.set_time_slice(0.0..1.0);
+        animation
+    }
+
+    fn position_of(pose: &AnimationPose<ErasedHandle>, node: ErasedHandle) -> Vector3<f32> {
+        match pose.poses()[&node].values.values[0].value {
+            TrackValue::Vector3(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_layers() {
+        let a = ErasedHandle::new(1, 1);
+        let b = ErasedHandle::new(2, 1);
+
+        let mut animations = AnimationContainer::new();
+        let base = animations.add(position_animation(a, 1.0, 1.0));
+        animations[base].add_track(
+            Track::new(
+                {
+                    let mut container = TrackDataContainer::new(TrackValueKind::Vector3);
+                    container.curves_mut()[0] =
+                        Curve::from(vec![CurveKey::new(0.0, 5.0, CurveKeyKind::Constant)]);
+                    container
+                },
+                ValueBinding::Position,
+            )
+            .with_target(b),
+        );
+        let additive = animations.add(position_animation(a, 0.0, 2.0));
+        for animation in animations.iter_mut() {
+            // Pose is calculated at the beginning of a tick.
+            animation.set_time_position(0.5).tick(0.0);
+        }
+
+        let mut pose = animations[base].pose().clone();
+        let layer = AnimationLayer::new("Additive", additive)
+            .with_blend_mode(LayerBlendMode::Additive)
+            .with_additive_reference(additive)
+            .with_weight(0.5)
+            .with_mask(LayerMask::from(vec![b]));
+        layer.blend_into(&animations, &mut pose);
+
+        // 1.0 + (1.0 - 0.0) * 0.5
+        assert_eq!(position_of(&pose, a).x, 1.5);
+        assert_eq!(position_of(&pose, b).x, 5.0);
+
+        let override_layer = AnimationLayer::new("Override", additive)
+            .with_weight(0.5)
+            .with_mask(LayerMask::from(vec![b]));
+        override_layer.blend_into(&animations, &mut pose);
+        // lerp(1.5, 1.0, 0.5)
+        assert_eq!(position_of(&pose, a).x, 1.25);
+        assert_eq!(position_of(&pose, b).x, 5.0);
+    }
+}
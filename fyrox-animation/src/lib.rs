@@ -5,7 +5,7 @@
 
 use crate::{
     core::{
-        algebra::{UnitQuaternion, Vector3},
+        algebra::{Matrix4, UnitQuaternion, Vector3},
         math::wrapf,
         pool::{Handle, Pool, Ticket},
         reflect::prelude::*,
@@ -31,6 +31,7 @@ pub use signal::{AnimationEvent, AnimationSignal};
 use value::{TrackValue, ValueBinding};
 
 pub mod container;
+pub mod layer;
 pub mod machine;
 pub mod pose;
 pub mod signal;
@@ -190,6 +191,10 @@ pub struct Animation<T: EntityId> {
     #[reflect(hidden)]
     #[visit(skip)]
     events: VecDeque<AnimationEvent>,
+    // Non-serialized
+    #[reflect(hidden)]
+    #[visit(skip)]
+    tick_events: Vec<AnimationEvent>,
 }
 
 impl<T: EntityId> TypeUuidProvider for Animation<T> {
@@ -223,6 +228,11 @@ pub struct RootMotionSettings<T: EntityId> {
     pub ignore_z_movement: bool,
     /// Keeps rotational part of the motion.
     pub ignore_rotations: bool,
+    /// Keeps the extracted motion in the pose of the root node. By default, the motion is stripped
+    /// from the pose, so the root node stays in place and the motion must be applied by some other
+    /// entity (a character controller, a rigid body, etc.).
+    #[visit(optional)]
+    pub keep_in_pose: bool,
 }
 
 /// Motion of a root node of an hierarchy of nodes. It contains relative rotation and translation in local
@@ -245,6 +255,23 @@ impl RootMotion {
         self.delta_position = self.delta_position.lerp(&other.delta_position, weight);
         self.delta_rotation = self.delta_rotation.nlerp(&other.delta_rotation, weight);
     }
+
+    /// Transforms relative offset of the motion using the given transform (usually it is the global
+    /// transform of the animated model). The result can be used to move the model in world space.
+    pub fn transformed_delta_position(&self, transform: &Matrix4<f32>) -> Vector3<f32> {
+        transform.transform_vector(&self.delta_position)
+    }
+
+    /// Calculates velocity of the motion in the space defined by the given transform (usually it is
+    /// the global transform of the animated model). `dt` must be the same time step that was used
+    /// to update the animation. Returns zero vector if `dt` is zero.
+    pub fn velocity(&self, transform: &Matrix4<f32>, dt: f32) -> Vector3<f32> {
+        if dt > 0.0 {
+            self.transformed_delta_position(transform).scale(1.0 / dt)
+        } else {
+            Vector3::default()
+        }
+    }
 }
 
 impl<T: EntityId> NameProvider for Animation<T> {
@@ -266,6 +293,7 @@ impl<T: EntityId> Clone for Animation<T> {
             signals: self.signals.clone(),
             root_motion_settings: self.root_motion_settings.clone(),
             events: Default::default(),
+            tick_events: Default::default(),
             time_slice: self.time_slice.clone(),
             root_motion: self.root_motion.clone(),
         }
@@ -376,19 +404,25 @@ impl<T: EntityId> Animation<T> {
         let current_time_position = self.time_position();
         let new_time_position = current_time_position + dt * self.speed();
 
+        self.tick_events.clear();
+
         for signal in self.signals.iter_mut().filter(|s| s.enabled) {
             if self.speed >= 0.0
                 && (current_time_position < signal.time && new_time_position >= signal.time)
                 || self.speed < 0.0
                     && (current_time_position > signal.time && new_time_position <= signal.time)
             {
+                let event = AnimationEvent {
+                    signal_id: signal.id,
+                    name: signal.name.clone(),
+                };
+
                 // TODO: Make this configurable.
                 if self.events.len() < 32 {
-                    self.events.push_back(AnimationEvent {
-                        signal_id: signal.id,
-                        name: signal.name.clone(),
-                    });
+                    self.events.push_back(event.clone());
                 }
+
+                self.tick_events.push(event);
             }
         }
 
@@ -493,27 +527,29 @@ impl<T: EntityId> Animation<T> {
                                         delta.z
                                     };
 
-                                // Reset position so the root won't move.
-                                let start_position =
-                                    fetch_position_at_time(&self.tracks, self.time_slice.start);
-
-                                bound_value.value = TrackValue::Vector3(Vector3::new(
-                                    if root_motion_settings.ignore_x_movement {
-                                        pose_position.x
-                                    } else {
-                                        start_position.x
-                                    },
-                                    if root_motion_settings.ignore_y_movement {
-                                        pose_position.y
-                                    } else {
-                                        start_position.y
-                                    },
-                                    if root_motion_settings.ignore_z_movement {
-                                        pose_position.z
-                                    } else {
-                                        start_position.z
-                                    },
-                                ));
+                                if !root_motion_settings.keep_in_pose {
+                                    // Reset position so the root won't move.
+                                    let start_position =
+                                        fetch_position_at_time(&self.tracks, self.time_slice.start);
+
+                                    bound_value.value = TrackValue::Vector3(Vector3::new(
+                                        if root_motion_settings.ignore_x_movement {
+                                            pose_position.x
+                                        } else {
+                                            start_position.x
+                                        },
+                                        if root_motion_settings.ignore_y_movement {
+                                            pose_position.y
+                                        } else {
+                                            start_position.y
+                                        },
+                                        if root_motion_settings.ignore_z_movement {
+                                            pose_position.z
+                                        } else {
+                                            start_position.z
+                                        },
+                                    ));
+                                }
                             }
                         }
                         ValueBinding::Rotation => {
@@ -536,10 +572,14 @@ impl<T: EntityId> Animation<T> {
                                         root_motion.prev_rotation = pose_rotation;
                                     }
 
-                                    // Reset rotation so the root won't rotate.
-                                    bound_value.value = TrackValue::UnitQuaternion(
-                                        fetch_rotation_at_time(&self.tracks, self.time_slice.start),
-                                    );
+                                    if !root_motion_settings.keep_in_pose {
+                                        // Reset rotation so the root won't rotate.
+                                        bound_value.value =
+                                            TrackValue::UnitQuaternion(fetch_rotation_at_time(
+                                                &self.tracks,
+                                                self.time_slice.start,
+                                            ));
+                                    }
                                 }
                             }
                         }
@@ -588,6 +628,13 @@ impl<T: EntityId> Animation<T> {
         &mut self.events
     }
 
+    /// Returns events, that were emitted during the last [`Self::tick`]. Unlike the events queue, it
+    /// does not need to be consumed and it is not limited in size - it is cleared on every tick. It
+    /// is useful to synchronize sounds, effects and physics impulses with the animation.
+    pub fn tick_events(&self) -> &[AnimationEvent] {
+        &self.tick_events
+    }
+
     /// Takes the events queue and returns it to the caller, leaving the internal queue empty.
     pub fn take_events(&mut self) -> VecDeque<AnimationEvent> {
         std::mem::take(&mut self.events)
@@ -753,6 +800,20 @@ impl<T: EntityId> Animation<T> {
     pub fn pose(&self) -> &AnimationPose<T> {
         &self.pose
     }
+
+    /// Calculates a pose of the animation at the given time position, without modifying the state of the animation.
+    /// It could be used to get a reference pose for additive blending, for example.
+    pub fn sample_pose(&self, time: f32) -> AnimationPose<T> {
+        let mut pose = AnimationPose::default();
+        for track in self.tracks.iter() {
+            if track.is_enabled() {
+                if let Some(bound_value) = track.fetch(time) {
+                    pose.add_to_node_pose(track.target(), bound_value);
+                }
+            }
+        }
+        pose
+    }
 }
 
 impl<T: EntityId> Default for Animation<T> {
@@ -768,6 +829,7 @@ impl<T: EntityId> Default for Animation<T> {
             signals: Default::default(),
             root_motion_settings: None,
             events: Default::default(),
+            tick_events: Default::default(),
             time_slice: Default::default(),
             root_motion: None,
         }
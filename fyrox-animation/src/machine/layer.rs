@@ -8,6 +8,7 @@ use crate::{
         reflect::prelude::*,
         visitor::prelude::*,
     },
+    layer::LayerBlendMode,
     machine::{
         event::FixedEventQueue, node::AnimationEventCollectionStrategy, AnimationPoseSource, Event,
         LayerMask, ParameterContainer, PoseNode, State, Transition,
@@ -69,6 +70,12 @@ pub struct MachineLayer<T: EntityId> {
 
     mask: LayerMask<T>,
 
+    #[visit(optional)]
+    blend_mode: LayerBlendMode,
+
+    #[visit(optional)]
+    additive_reference: Handle<Animation<T>>,
+
     #[reflect(hidden)]
     nodes: Pool<PoseNode<T>>,
 
@@ -161,6 +168,8 @@ impl<T: EntityId> MachineLayer<T> {
             events: FixedEventQueue::new(2048),
             debug: false,
             mask: Default::default(),
+            blend_mode: Default::default(),
+            additive_reference: Default::default(),
         }
     }
 
@@ -527,6 +536,36 @@ impl<T: EntityId> MachineLayer<T> {
         &self.mask
     }
 
+    /// Sets new blend mode of the layer. It defines how the pose of the layer is combined with the pose of the
+    /// layers below it. See docs of [`LayerBlendMode`] for more info.
+    #[inline]
+    pub fn set_blend_mode(&mut self, blend_mode: LayerBlendMode) -> LayerBlendMode {
+        std::mem::replace(&mut self.blend_mode, blend_mode)
+    }
+
+    /// Returns current blend mode of the layer.
+    #[inline]
+    pub fn blend_mode(&self) -> LayerBlendMode {
+        self.blend_mode
+    }
+
+    /// Sets new reference animation for additive blending. The first frame of the animation is used as a reference
+    /// pose, the difference between the pose of the layer and the reference pose is added to the pose of the layers
+    /// below. If the reference animation is not set, the pose of the layer is considered as a difference itself.
+    #[inline]
+    pub fn set_additive_reference(
+        &mut self,
+        additive_reference: Handle<Animation<T>>,
+    ) -> Handle<Animation<T>> {
+        std::mem::replace(&mut self.additive_reference, additive_reference)
+    }
+
+    /// Returns current reference animation for additive blending.
+    #[inline]
+    pub fn additive_reference(&self) -> Handle<Animation<T>> {
+        self.additive_reference
+    }
+
     /// Returns final pose of the layer.
     #[inline]
     pub fn pose(&self) -> &AnimationPose<T> {
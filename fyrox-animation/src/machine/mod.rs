@@ -10,6 +10,7 @@ use crate::{
         reflect::prelude::*,
         visitor::{Visit, VisitResult, Visitor},
     },
+    layer::blend_layer_pose,
     AnimationContainer, AnimationPose, EntityId,
 };
 
@@ -51,6 +52,8 @@ pub mod transition;
 /// `Parameter` - is a named variable of a fixed type (see `Parameters` section for more info).
 /// `Layer` - is a separate state graph, there could be any number of layers - each with its own mask.
 /// `Mask` - a set of handles to nodes which will be excluded from animation on a layer.
+/// `Blend Mode` - defines how a pose of a layer is combined with the poses of the layers below it, a layer could either
+/// override the pose or add its difference from a reference pose (additive blending).
 /// `Pose` - a final result of blending multiple animation into one.
 ///
 /// Summarizing everything of this, we can describe animation blending state machine as a state graph, where each state has its
@@ -76,6 +79,10 @@ pub mod transition;
 /// ability to have running character that could aim or melee attack, or crouching and aiming, and so on with any combination.
 /// Both layers use the same set of parameters, so a change in a parameter will affect all layers that use it.
 ///
+/// Layers are combined in order, every layer could either override the pose of the layers below it or add the difference
+/// between its pose and a reference pose (additive blending). Additive layers are useful to add small motions (breathing,
+/// recoil, leaning, etc.) on top of any other animation. See [`crate::layer::LayerBlendMode`] docs for more info.
+///
 /// # Examples
 ///
 /// Let have a quick look at simple state machine graph with a single layer:
@@ -300,9 +307,18 @@ impl<T: EntityId> Machine<T> {
 
         for layer in self.layers.iter_mut() {
             let weight = layer.weight();
+            let blend_mode = layer.blend_mode();
+            let additive_reference = layer.additive_reference();
             let pose = layer.evaluate_pose(animations, &self.parameters, dt);
 
-            self.final_pose.blend_with(pose, weight);
+            blend_layer_pose(
+                &mut self.final_pose,
+                pose,
+                blend_mode,
+                additive_reference,
+                animations,
+                weight,
+            );
         }
 
         &self.final_pose
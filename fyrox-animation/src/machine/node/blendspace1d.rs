@@ -0,0 +1,301 @@
+//! One-dimensional blend space node. See [`BlendSpace1D`] docs for more info.
+
+use crate::{
+    core::{
+        arrayvec::ArrayVec,
+        pool::{Handle, Pool},
+        reflect::prelude::*,
+        visitor::prelude::*,
+    },
+    machine::{
+        node::AnimationEventCollectionStrategy, node::BasePoseNode, AnimationPoseSource, Parameter,
+        ParameterContainer, PoseNode,
+    },
+    Animation, AnimationContainer, AnimationEvent, AnimationPose, EntityId,
+};
+use fyrox_core::uuid::{uuid, Uuid};
+use fyrox_core::TypeUuidProvider;
+use std::cmp::Ordering;
+use std::{
+    cell::{Ref, RefCell},
+    ops::{Deref, DerefMut},
+};
+
+/// A point on the axis of a one-dimensional blend space.
+#[derive(Debug, Visit, Clone, Reflect, PartialEq, Default)]
+pub struct BlendSpace1DPoint<T: EntityId> {
+    /// Position of the point on the axis.
+    pub position: f32,
+
+    /// A source of animation pose.
+    #[reflect(hidden)]
+    pub pose_source: Handle<PoseNode<T>>,
+}
+
+impl<T: EntityId> TypeUuidProvider for BlendSpace1DPoint<T> {
+    fn type_uuid() -> Uuid {
+        uuid!("6d1f7f3e-2b8c-4d5e-9a1f-8c0e4b6a2d71")
+    }
+}
+
+/// One-dimensional blend space blends two closest poses on an axis using a value of a Weight
+/// parameter. It is a convenient way to blend locomotion animations by a single value, for example
+/// idle, walk and run animations by the speed of a character. Points do not need to be sorted, the
+/// node blends the two neighbouring points around the sampling value. Values outside of the range
+/// of the points are clamped to the edge points.
+#[derive(Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct BlendSpace1D<T: EntityId> {
+    /// Base node.
+    pub base: BasePoseNode<T>,
+
+    /// A set of points on the axis of the blend space.
+    pub points: Vec<BlendSpace1DPoint<T>>,
+
+    /// Name of the axis, it is editor-specific data.
+    pub axis_name: String,
+
+    /// A name of a Weight parameter, that will be used to sample the blend space.
+    pub sampling_parameter: String,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pose: RefCell<AnimationPose<T>>,
+}
+
+impl<T: EntityId> Default for BlendSpace1D<T> {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            points: Default::default(),
+            axis_name: "X".to_string(),
+            sampling_parameter: Default::default(),
+            pose: Default::default(),
+        }
+    }
+}
+
+impl<T: EntityId> Deref for BlendSpace1D<T> {
+    type Target = BasePoseNode<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl<T: EntityId> DerefMut for BlendSpace1D<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl<T: EntityId> BlendSpace1D<T> {
+    /// Creates new blend space with the given sampling parameter and points.
+    pub fn new(sampling_parameter: String, points: Vec<BlendSpace1DPoint<T>>) -> Self {
+        Self {
+            points,
+            sampling_parameter,
+            ..Default::default()
+        }
+    }
+
+    /// Returns a set of handles to children pose nodes.
+    pub fn children(&self) -> Vec<Handle<PoseNode<T>>> {
+        self.points.iter().map(|p| p.pose_source).collect()
+    }
+
+    /// Calculates indices and weights of the points, that should be blended for the given sampling
+    /// value. Returns two points if the value is inside the range of the points, a single edge point
+    /// with full weight if the value is outside of the range, and nothing if the blend space has no
+    /// points.
+    pub fn fetch_weights(&self, value: f32) -> ArrayVec<(usize, f32), 2> {
+        let mut left = None::<usize>;
+        let mut right = None::<usize>;
+        let mut first = None::<usize>;
+        let mut last = None::<usize>;
+
+        for (i, point) in self.points.iter().enumerate() {
+            let position = point.position;
+
+            if first.map_or(true, |first| position < self.points[first].position) {
+                first = Some(i);
+            }
+            if last.map_or(true, |last| position > self.points[last].position) {
+                last = Some(i);
+            }
+            if position <= value && left.map_or(true, |left| position > self.points[left].position)
+            {
+                left = Some(i);
+            }
+            if position > value
+                && right.map_or(true, |right| position < self.points[right].position)
+            {
+                right = Some(i);
+            }
+        }
+
+        let mut weights = ArrayVec::new();
+        match (left, right) {
+            (Some(left), Some(right)) => {
+                let a = self.points[left].position;
+                let b = self.points[right].position;
+                let t = (value - a) / (b - a);
+                weights.push((left, 1.0 - t));
+                weights.push((right, t));
+            }
+            (None, Some(_)) => weights.extend(first.map(|first| (first, 1.0))),
+            (Some(_), None) => weights.extend(last.map(|last| (last, 1.0))),
+            (None, None) => (),
+        }
+        weights
+    }
+
+    fn sampling_value(&self, params: &ParameterContainer) -> Option<f32> {
+        if let Some(Parameter::Weight(value)) = params.get(&self.sampling_parameter) {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: EntityId> AnimationPoseSource<T> for BlendSpace1D<T> {
+    fn eval_pose(
+        &self,
+        nodes: &Pool<PoseNode<T>>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer<T>,
+        dt: f32,
+    ) -> Ref<'_, AnimationPose<T>> {
+        let mut pose = self.pose.borrow_mut();
+
+        pose.reset();
+
+        if let Some(value) = self.sampling_value(params) {
+            for (index, weight) in self.fetch_weights(value) {
+                if let Some(pose_source) = nodes.try_borrow(self.points[index].pose_source) {
+                    pose.blend_with(
+                        &pose_source.eval_pose(nodes, params, animations, dt),
+                        weight,
+                    );
+                }
+            }
+        }
+
+        drop(pose);
+
+        self.pose.borrow()
+    }
+
+    fn pose(&self) -> Ref<'_, AnimationPose<T>> {
+        self.pose.borrow()
+    }
+
+    fn collect_animation_events(
+        &self,
+        nodes: &Pool<PoseNode<T>>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer<T>,
+        strategy: AnimationEventCollectionStrategy,
+    ) -> Vec<(Handle<Animation<T>>, AnimationEvent)> {
+        let Some(value) = self.sampling_value(params) else {
+            return Default::default();
+        };
+
+        let weighted_sources = self
+            .fetch_weights(value)
+            .iter()
+            .filter_map(|(index, weight)| {
+                nodes
+                    .try_borrow(self.points[*index].pose_source)
+                    .map(|source| (source, *weight))
+            })
+            .collect::<Vec<_>>();
+
+        let compare = |(_, w1): &&(&PoseNode<T>, f32), (_, w2): &&(&PoseNode<T>, f32)| {
+            w1.partial_cmp(w2).unwrap_or(Ordering::Equal)
+        };
+
+        match strategy {
+            AnimationEventCollectionStrategy::All => {
+                let mut events = Vec::new();
+                for (source, _) in weighted_sources.iter() {
+                    events.extend(
+                        source.collect_animation_events(nodes, params, animations, strategy),
+                    );
+                }
+                events
+            }
+            AnimationEventCollectionStrategy::MaxWeight => weighted_sources
+                .iter()
+                .max_by(compare)
+                .map(|(source, _)| {
+                    source.collect_animation_events(nodes, params, animations, strategy)
+                })
+                .unwrap_or_default(),
+            AnimationEventCollectionStrategy::MinWeight => weighted_sources
+                .iter()
+                .min_by(compare)
+                .map(|(source, _)| {
+                    source.collect_animation_events(nodes, params, animations, strategy)
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::machine::node::blendspace1d::{BlendSpace1D, BlendSpace1DPoint};
+    use fyrox_core::pool::ErasedHandle;
+
+    fn point(position: f32) -> BlendSpace1DPoint<ErasedHandle> {
+        BlendSpace1DPoint {
+            position,
+            pose_source: Default::default(),
+        }
+    }
+
+    // Unsorted points.
+    fn blend_space() -> BlendSpace1D<ErasedHandle> {
+        BlendSpace1D::new(
+            "Speed".to_string(),
+            vec![point(4.0), point(0.0), point(1.0)],
+        )
+    }
+
+    #[test]
+    fn test_blend_space_1d_without_points() {
+        assert!(BlendSpace1D::<ErasedHandle>::default()
+            .fetch_weights(0.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_blend_space_1d_inside_range() {
+        let blend_space = blend_space();
+        assert_eq!(
+            blend_space.fetch_weights(0.0).as_slice(),
+            &[(1, 1.0), (2, 0.0)]
+        );
+        assert_eq!(
+            blend_space.fetch_weights(0.5).as_slice(),
+            &[(1, 0.5), (2, 0.5)]
+        );
+        assert_eq!(
+            blend_space.fetch_weights(1.75).as_slice(),
+            &[(2, 0.75), (0, 0.25)]
+        );
+    }
+
+    #[test]
+    fn test_blend_space_1d_below_range() {
+        assert_eq!(blend_space().fetch_weights(-1.0).as_slice(), &[(1, 1.0)]);
+    }
+
+    #[test]
+    fn test_blend_space_1d_above_range() {
+        let blend_space = blend_space();
+        assert_eq!(blend_space.fetch_weights(4.0).as_slice(), &[(0, 1.0)]);
+        assert_eq!(blend_space.fetch_weights(5.0).as_slice(), &[(0, 1.0)]);
+    }
+}
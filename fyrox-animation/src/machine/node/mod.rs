@@ -9,7 +9,12 @@ use crate::{
         visitor::prelude::*,
     },
     machine::{
-        node::{blend::BlendAnimations, blendspace::BlendSpace, play::PlayAnimation},
+        node::{
+            blend::BlendAnimations,
+            blendspace::BlendSpace,
+            blendspace1d::{BlendSpace1D, BlendSpace1DPoint},
+            play::PlayAnimation,
+        },
         BlendAnimationsByIndex, BlendPose, IndexedBlendInput, ParameterContainer, State,
     },
     Animation, AnimationContainer, AnimationEvent, AnimationPose, EntityId,
@@ -21,6 +26,7 @@ use std::{
 
 pub mod blend;
 pub mod blendspace;
+pub mod blendspace1d;
 pub mod play;
 
 /// A set of common data fields that is used in every node.
@@ -48,6 +54,9 @@ pub enum PoseNode<T: EntityId> {
 
     /// See doc for [`BlendSpace`]
     BlendSpace(BlendSpace<T>),
+
+    /// See doc for [`BlendSpace1D`]
+    BlendSpace1D(BlendSpace1D<T>),
 }
 
 impl<T: EntityId> Default for PoseNode<T> {
@@ -76,6 +85,15 @@ impl<T: EntityId> PoseNode<T> {
         Self::BlendAnimationsByIndex(BlendAnimationsByIndex::new(index_parameter, inputs))
     }
 
+    /// Creates new node that blends two closest poses on an axis using the value of the given
+    /// Weight parameter.
+    pub fn make_blend_space_1d(
+        sampling_parameter: String,
+        points: Vec<BlendSpace1DPoint<T>>,
+    ) -> Self {
+        Self::BlendSpace1D(BlendSpace1D::new(sampling_parameter, points))
+    }
+
     /// Returns a set of handles to children pose nodes.
     pub fn children(&self) -> Vec<Handle<PoseNode<T>>> {
         match self {
@@ -86,6 +104,7 @@ impl<T: EntityId> PoseNode<T> {
             Self::BlendAnimations(blend_animations) => blend_animations.children(),
             Self::BlendAnimationsByIndex(blend_by_index) => blend_by_index.children(),
             Self::BlendSpace(blend_space) => blend_space.children(),
+            Self::BlendSpace1D(blend_space) => blend_space.children(),
         }
     }
 }
@@ -97,6 +116,7 @@ macro_rules! static_dispatch {
             PoseNode::BlendAnimations(v) => v.$func($($args),*),
             PoseNode::BlendAnimationsByIndex(v) => v.$func($($args),*),
             PoseNode::BlendSpace(v) => v.$func($($args),*),
+            PoseNode::BlendSpace1D(v) => v.$func($($args),*),
         }
     };
 }
@@ -21,6 +21,18 @@ impl<T: EntityId> NodePose<T> {
     pub fn blend_with(&mut self, other: &NodePose<T>, weight: f32) {
         self.values.blend_with(&other.values, weight)
     }
+
+    /// Adds a difference between the additive pose and the reference pose to the current pose. See
+    /// [`super::value::TrackValue::blend_additive`] docs for more info.
+    pub fn blend_additive(
+        &mut self,
+        additive: &NodePose<T>,
+        reference: Option<&NodePose<T>>,
+        weight: f32,
+    ) {
+        self.values
+            .blend_additive(&additive.values, reference.map(|r| &r.values), weight)
+    }
 }
 
 /// Animations pose is a set of node poses. See [`NodePose`] docs for more info.
@@ -67,6 +79,24 @@ impl<T: EntityId> AnimationPose<T> {
             .blend_with(&other.root_motion.clone().unwrap_or_default(), weight);
     }
 
+    /// Adds a difference between the additive animation pose and the reference animation pose to the current animation
+    /// pose using a weight coefficient. If there's no reference pose, the additive pose is considered as a difference
+    /// itself. Unlike [`Self::blend_with`], node poses that are missing in the current pose are ignored (since there's
+    /// nothing to add the difference to) and root motion is left unchanged.
+    pub fn blend_additive(
+        &mut self,
+        additive: &AnimationPose<T>,
+        reference: Option<&AnimationPose<T>>,
+        weight: f32,
+    ) {
+        for (handle, additive_pose) in additive.poses.iter() {
+            if let Some(current_pose) = self.poses.get_mut(handle) {
+                let reference_pose = reference.and_then(|reference| reference.poses.get(handle));
+                current_pose.blend_additive(additive_pose, reference_pose, weight);
+            }
+        }
+    }
+
     fn add_node_pose(&mut self, local_pose: NodePose<T>) {
         self.poses.insert(local_pose.node, local_pose);
     }
@@ -3,7 +3,7 @@
 use crate::{
     container::{TrackDataContainer, TrackValueKind},
     core::{reflect::prelude::*, uuid::Uuid, visitor::prelude::*},
-    value::{BoundValue, ValueBinding},
+    value::{BoundValue, ValueBinding, ValueType},
     EntityId,
 };
 use std::fmt::Debug;
@@ -85,6 +85,20 @@ impl<T: EntityId> Track<T> {
         }
     }
 
+    /// Creates a new track that is responsible in animating an arbitrary property of a scene node. The property is
+    /// defined by its path (for example `intensity` or `uv_rect`) and set using reflection, see [`ValueBinding::Property`]
+    /// docs for more info.
+    pub fn new_property<S: AsRef<str>>(name: S, value_type: ValueType) -> Self {
+        Self {
+            frames: TrackDataContainer::new(value_type.value_kind()),
+            binding: ValueBinding::Property {
+                name: name.as_ref().to_owned(),
+                value_type,
+            },
+            ..Default::default()
+        }
+    }
+
     /// Sets target of the track.
     pub fn with_target(mut self, target: T) -> Self {
         self.target = target;
@@ -1,12 +1,16 @@
 //! A module that contains everything related to numeric values of animation tracks. See [`TrackValue`] docs
 //! for more info.
 
-use crate::core::{
-    algebra::{UnitQuaternion, Vector2, Vector3, Vector4},
-    math::lerpf,
-    num_traits::AsPrimitive,
-    reflect::prelude::*,
-    visitor::prelude::*,
+use crate::{
+    container::TrackValueKind,
+    core::{
+        algebra::{UnitQuaternion, Vector2, Vector3, Vector4},
+        color::Color,
+        math::{lerpf, Rect},
+        num_traits::AsPrimitive,
+        reflect::prelude::*,
+        visitor::prelude::*,
+    },
 };
 use std::fmt::{Debug, Display, Formatter};
 
@@ -109,6 +113,11 @@ pub enum ValueType {
     UnitQuaternionF32,
     /// `UnitQuaternion<f64>`
     UnitQuaternionF64,
+
+    /// `Color`, animated as a 4-dimensional vector of normalized (`[0; 1]` range) RGBA components.
+    Color,
+    /// `Rect<f32>`, animated as a 4-dimensional vector, where `X` and `Y` is position and `Z` and `W` is size.
+    RectF32,
 }
 
 impl Default for ValueType {
@@ -117,6 +126,61 @@ impl Default for ValueType {
     }
 }
 
+impl ValueType {
+    /// Returns a kind of track value, that is used to animate a property of the value type.
+    pub fn value_kind(self) -> TrackValueKind {
+        match self {
+            Self::Bool
+            | Self::F32
+            | Self::F64
+            | Self::U64
+            | Self::I64
+            | Self::U32
+            | Self::I32
+            | Self::U16
+            | Self::I16
+            | Self::U8
+            | Self::I8 => TrackValueKind::Real,
+            Self::Vector2Bool
+            | Self::Vector2F32
+            | Self::Vector2F64
+            | Self::Vector2U64
+            | Self::Vector2I64
+            | Self::Vector2U32
+            | Self::Vector2I32
+            | Self::Vector2U16
+            | Self::Vector2I16
+            | Self::Vector2U8
+            | Self::Vector2I8 => TrackValueKind::Vector2,
+            Self::Vector3Bool
+            | Self::Vector3F32
+            | Self::Vector3F64
+            | Self::Vector3U64
+            | Self::Vector3I64
+            | Self::Vector3U32
+            | Self::Vector3I32
+            | Self::Vector3U16
+            | Self::Vector3I16
+            | Self::Vector3U8
+            | Self::Vector3I8 => TrackValueKind::Vector3,
+            Self::Vector4Bool
+            | Self::Vector4F32
+            | Self::Vector4F64
+            | Self::Vector4U64
+            | Self::Vector4I64
+            | Self::Vector4U32
+            | Self::Vector4I32
+            | Self::Vector4U16
+            | Self::Vector4I16
+            | Self::Vector4U8
+            | Self::Vector4I8
+            | Self::Color
+            | Self::RectF32 => TrackValueKind::Vector4,
+            Self::UnitQuaternionF32 | Self::UnitQuaternionF64 => TrackValueKind::UnitQuaternion,
+        }
+    }
+}
+
 /// A real value that can be produced by an animation track. Animations always operate on real numbers (`f32`) for any kind
 /// of machine numeric types (including `bool`). This is needed to be able to blend values; final blending result is then
 /// converted to an actual machine type of a target property.
@@ -152,6 +216,52 @@ impl TrackValue {
         }
     }
 
+    /// Adds a difference between the additive value and the reference value to the current value, scaled by the given
+    /// weight. If there's no reference value, the additive value is considered as a difference itself (zero for numbers
+    /// and vectors, identity for rotations). Rotations are combined by multiplication. Blending is possible only if the
+    /// types are the same.
+    pub fn blend_additive(&mut self, additive: &Self, reference: Option<&Self>, weight: f32) {
+        match (self, additive, reference) {
+            (Self::Real(a), Self::Real(b), reference) => {
+                let r = match reference {
+                    Some(Self::Real(r)) => *r,
+                    _ => 0.0,
+                };
+                *a += (*b - r) * weight;
+            }
+            (Self::Vector2(a), Self::Vector2(b), reference) => {
+                let r = match reference {
+                    Some(Self::Vector2(r)) => *r,
+                    _ => Vector2::default(),
+                };
+                *a += (*b - r).scale(weight);
+            }
+            (Self::Vector3(a), Self::Vector3(b), reference) => {
+                let r = match reference {
+                    Some(Self::Vector3(r)) => *r,
+                    _ => Vector3::default(),
+                };
+                *a += (*b - r).scale(weight);
+            }
+            (Self::Vector4(a), Self::Vector4(b), reference) => {
+                let r = match reference {
+                    Some(Self::Vector4(r)) => *r,
+                    _ => Vector4::default(),
+                };
+                *a += (*b - r).scale(weight);
+            }
+            (Self::UnitQuaternion(a), Self::UnitQuaternion(b), reference) => {
+                let r = match reference {
+                    Some(Self::UnitQuaternion(r)) => *r,
+                    _ => UnitQuaternion::identity(),
+                };
+                let delta = r.inverse() * *b;
+                *a *= UnitQuaternion::identity().nlerp(&delta, weight);
+            }
+            _ => (),
+        }
+    }
+
     /// Tries to perform a numeric type casting of the current value to some other and returns a boxed value, that can
     /// be used to set the value using reflection.
     pub fn numeric_type_cast(&self, value_type: ValueType) -> Option<Box<dyn Reflect>> {
@@ -245,6 +355,8 @@ impl TrackValue {
                 ValueType::Vector4I16 => Some(Box::new(convert_vec4::<i16>(vec4))),
                 ValueType::Vector4U8 => Some(Box::new(convert_vec4::<u8>(vec4))),
                 ValueType::Vector4I8 => Some(Box::new(convert_vec4::<i8>(vec4))),
+                ValueType::Color => Some(Box::new(Color::from(*vec4))),
+                ValueType::RectF32 => Some(Box::new(Rect::new(vec4.x, vec4.y, vec4.z, vec4.w))),
                 _ => None,
             },
             TrackValue::UnitQuaternion(quat) => match value_type {
@@ -272,7 +384,7 @@ pub enum ValueBinding {
     Property {
         /// A path to a property (`foo.bar.baz[1].foobar@EnumVariant.stuff`)
         name: String,
-        /// Actual property type (only numeric properties are supported).
+        /// Actual property type (numeric properties, vectors, quaternions, colors and rectangles are supported).
         value_type: ValueType,
     },
 }
@@ -304,6 +416,14 @@ impl BoundValue {
         assert_eq!(self.binding, other.binding);
         self.value.blend_with(&other.value, weight);
     }
+
+    /// Adds a difference between the additive value and the reference value to the current value. See
+    /// [`TrackValue::blend_additive`] for more info.
+    pub fn blend_additive(&mut self, additive: &Self, reference: Option<&Self>, weight: f32) {
+        assert_eq!(self.binding, additive.binding);
+        self.value
+            .blend_additive(&additive.value, reference.map(|r| &r.value), weight);
+    }
 }
 
 /// A collection of values that are bounds to some properties.
@@ -323,4 +443,46 @@ impl BoundValueCollection {
             }
         }
     }
+
+    /// Adds a difference between each value of the additive collection and a respective (by binding) value of the
+    /// reference collection to a respective value of the current collection. See [`TrackValue::blend_additive`] docs
+    /// for more info.
+    pub fn blend_additive(&mut self, additive: &Self, reference: Option<&Self>, weight: f32) {
+        for value in self.values.iter_mut() {
+            if let Some(additive_value) =
+                additive.values.iter().find(|v| v.binding == value.binding)
+            {
+                let reference_value = reference.and_then(|reference| {
+                    reference.values.iter().find(|v| v.binding == value.binding)
+                });
+                value.blend_additive(additive_value, reference_value, weight);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        container::TrackValueKind,
+        core::{algebra::Vector4, color::Color, math::Rect},
+        value::{TrackValue, ValueType},
+    };
+
+    #[test]
+    fn test_color_and_rect_cast() {
+        assert_eq!(ValueType::Color.value_kind(), TrackValueKind::Vector4);
+        assert_eq!(ValueType::RectF32.value_kind(), TrackValueKind::Vector4);
+
+        let value = TrackValue::Vector4(Vector4::new(1.0, 0.0, 0.0, 1.0));
+
+        let color = value.numeric_type_cast(ValueType::Color).unwrap();
+        assert_eq!(color.downcast::<Color>().ok().map(|c| *c), Some(Color::RED));
+
+        let rect = value.numeric_type_cast(ValueType::RectF32).unwrap();
+        assert_eq!(
+            rect.downcast::<Rect<f32>>().ok().map(|r| *r),
+            Some(Rect::new(1.0, 0.0, 0.0, 1.0))
+        );
+    }
 }
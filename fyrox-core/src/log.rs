@@ -5,6 +5,7 @@ use crate::parking_lot::Mutex;
 use std::fmt::{Debug, Display};
 
 use crate::instant::Instant;
+use std::collections::VecDeque;
 #[cfg(not(target_arch = "wasm32"))]
 use std::io::{self, Write};
 use std::sync::mpsc::Sender;
@@ -23,6 +24,7 @@ extern "C" {
 }
 
 /// A message that could be sent by the logger to all listeners.
+#[derive(Clone)]
 pub struct LogMessage {
     /// Kind of the message: information, warning or error.
     pub kind: MessageKind,
@@ -39,6 +41,8 @@ lazy_static! {
         file: std::fs::File::create("fyrox.log").unwrap(),
         verbosity: MessageKind::Information,
         listeners: Default::default(),
+        history: Default::default(),
+        history_capacity: 0,
         time_origin: Instant::now()
     });
 }
@@ -71,6 +75,8 @@ pub struct Log {
     file: std::fs::File,
     verbosity: MessageKind,
     listeners: Vec<Sender<LogMessage>>,
+    history: VecDeque<LogMessage>,
+    history_capacity: usize,
     time_origin: Instant,
 }
 
@@ -89,6 +95,17 @@ impl Log {
                 });
             }
 
+            if self.history_capacity > 0 {
+                if self.history.len() >= self.history_capacity {
+                    self.history.pop_front();
+                }
+                self.history.push_back(LogMessage {
+                    kind,
+                    content: msg.clone(),
+                    time: Instant::now() - self.time_origin,
+                });
+            }
+
             msg.insert_str(0, kind.as_str());
 
             #[cfg(target_arch = "wasm32")]
@@ -168,6 +185,30 @@ impl Log {
         LOG.lock().listeners.push(listener)
     }
 
+    /// Sets the maximum amount of recent messages, that will be kept in the log history. Zero
+    /// capacity (default) disables the history. Oldest messages are discarded when the history is
+    /// full.
+    pub fn set_history_capacity(capacity: usize) {
+        let mut log = LOG.lock();
+        log.history_capacity = capacity;
+        while log.history.len() > capacity {
+            log.history.pop_front();
+        }
+    }
+
+    /// Returns a copy of recent messages from the log history, oldest first. See
+    /// [`Self::set_history_capacity`] for more info.
+    pub fn history() -> Vec<LogMessage> {
+        LOG.lock().history.iter().cloned().collect()
+    }
+
+    /// Same as [`Self::history`], but returns `None` if the log is currently locked. This method
+    /// never blocks and it is suitable to be used in panic hooks.
+    pub fn try_history() -> Option<Vec<LogMessage>> {
+        LOG.try_lock()
+            .map(|log| log.history.iter().cloned().collect())
+    }
+
     /// Allows you to verify that the result of operation is Ok, or print the error in the log.
     ///
     /// # Use cases
@@ -6,6 +6,15 @@ use std::{
     future::Future,
     sync::mpsc::{self, Receiver, Sender},
 };
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    cell::Cell,
+    cmp::Ordering,
+    collections::BinaryHeap,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
 use uuid::Uuid;
 
 pub struct TaskResult {
@@ -13,9 +22,140 @@ pub struct TaskResult {
     pub payload: Box<dyn Any + Send>,
 }
 
+/// Priority of a task, that is spawned using [`TaskPool::spawn_task_with_priority`]. Priorities make sense
+/// only if there's a limit of concurrent tasks (see [`TaskPool::set_max_concurrent_tasks`]), in this case
+/// queued tasks with higher priority will be started first.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TaskPriority {
+    /// Background tasks, for example - streaming of assets for distant areas of a level.
+    Low,
+    /// Default priority.
+    #[default]
+    Normal,
+    /// Tasks, that must be done as soon as possible.
+    High,
+}
+
+/// An identifier of a task, that was spawned using [`TaskPool::spawn_task_with_priority`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TaskId(u64);
+
+#[cfg(not(target_arch = "wasm32"))]
+type BoxedTask = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+#[cfg(not(target_arch = "wasm32"))]
+struct QueuedTask {
+    priority: TaskPriority,
+    id: TaskId,
+    future: BoxedTask,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PartialEq for QueuedTask {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.id == other.id
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Eq for QueuedTask {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PartialOrd for QueuedTask {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Ord for QueuedTask {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Higher priority first, then first-in-first-out.
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.id.0.cmp(&self.id.0))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct TaskScheduler {
+    queue: BinaryHeap<QueuedTask>,
+    running: usize,
+    max_concurrent_tasks: Option<usize>,
+    next_id: u64,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TaskScheduler {
+    fn has_free_slot(&self) -> bool {
+        !matches!(self.max_concurrent_tasks, Some(limit) if self.running >= limit)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+thread_local! {
+    static INSIDE_SCHEDULED_TASK: Cell<bool> = const { Cell::new(false) };
+}
+
+// Marks the thread as running a scheduled task while the inner future is polled. Tasks spawned from
+// inside a scheduled task (for example, dependencies of a resource) bypass the limit of concurrent
+// tasks, otherwise a task that waits for its dependencies would never finish if the limit is reached.
+#[cfg(not(target_arch = "wasm32"))]
+struct ScheduledTask(BoxedTask);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Future for ScheduledTask {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let previous = INSIDE_SCHEDULED_TASK.with(|inside| inside.replace(true));
+        let result = self.0.as_mut().poll(cx);
+        INSIDE_SCHEDULED_TASK.with(|inside| inside.set(previous));
+        result
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_scheduled(
+    thread_pool: &ThreadPool,
+    scheduler: Arc<Mutex<TaskScheduler>>,
+    task: BoxedTask,
+) {
+    let pool = thread_pool.clone();
+    thread_pool.spawn_ok(async move {
+        ScheduledTask(task).await;
+
+        // Reuse the slot for the next queued task (if any). The limit could be decreased while the
+        // task was running, in this case the slot must be released.
+        let next = {
+            let mut scheduler = scheduler.lock();
+            let can_reuse_slot = !matches!(
+                scheduler.max_concurrent_tasks,
+                Some(limit) if scheduler.running > limit
+            );
+            let next = if can_reuse_slot {
+                scheduler.queue.pop().map(|next| next.future)
+            } else {
+                None
+            };
+            if next.is_none() {
+                scheduler.running -= 1;
+            }
+            next
+        };
+
+        if let Some(next) = next {
+            spawn_scheduled(&pool, scheduler, next);
+        }
+    });
+}
+
 pub struct TaskPool {
     #[cfg(not(target_arch = "wasm32"))]
     thread_pool: ThreadPool,
+    #[cfg(not(target_arch = "wasm32"))]
+    scheduler: Arc<Mutex<TaskScheduler>>,
     sender: Sender<TaskResult>,
     receiver: Mutex<Receiver<TaskResult>>,
 }
@@ -33,6 +173,8 @@ impl TaskPool {
         Self {
             #[cfg(not(target_arch = "wasm32"))]
             thread_pool: ThreadPool::new().unwrap(),
+            #[cfg(not(target_arch = "wasm32"))]
+            scheduler: Default::default(),
             sender,
             receiver: Mutex::new(receiver),
         }
@@ -56,6 +198,141 @@ impl TaskPool {
         self.thread_pool.spawn_ok(future);
     }
 
+    /// Spawns a task with the given priority. Unlike [`Self::spawn_task`], such tasks are limited by
+    /// the maximum amount of concurrent tasks (see [`Self::set_max_concurrent_tasks`]), the tasks
+    /// above the limit are queued and started in the order of their priorities. Tasks spawned from
+    /// inside another prioritized task are never queued.
+    #[inline]
+    #[cfg(target_arch = "wasm32")]
+    pub fn spawn_task_with_priority<F>(&self, future: F, _priority: TaskPriority) -> TaskId
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        self.spawn_task(future);
+        TaskId::default()
+    }
+
+    /// Spawns a task with the given priority. Unlike [`Self::spawn_task`], such tasks are limited by
+    /// the maximum amount of concurrent tasks (see [`Self::set_max_concurrent_tasks`]), the tasks
+    /// above the limit are queued and started in the order of their priorities. Tasks spawned from
+    /// inside another prioritized task are never queued.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn_task_with_priority<F>(&self, future: F, priority: TaskPriority) -> TaskId
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let future = Box::pin(future);
+
+        let mut scheduler = self.scheduler.lock();
+        let id = TaskId(scheduler.next_id);
+        scheduler.next_id += 1;
+        if scheduler.has_free_slot() || Self::is_inside_prioritized_task() {
+            scheduler.running += 1;
+            drop(scheduler);
+            spawn_scheduled(&self.thread_pool, self.scheduler.clone(), future);
+        } else {
+            scheduler.queue.push(QueuedTask {
+                priority,
+                id,
+                future,
+            });
+        }
+        id
+    }
+
+    /// Starts a queued task immediately, bypassing the limit of concurrent tasks. It should be used when
+    /// a prioritized task is waiting for a queued task, otherwise the waiting task could never finish.
+    /// Returns `true` if the task was queued and now started, `false` - otherwise.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn promote(&self, id: TaskId) -> bool {
+        let mut scheduler = self.scheduler.lock();
+        let mut tasks = std::mem::take(&mut scheduler.queue).into_vec();
+        let task = tasks
+            .iter()
+            .position(|task| task.id == id)
+            .map(|position| tasks.swap_remove(position));
+        scheduler.queue = tasks.into();
+
+        if let Some(task) = task {
+            scheduler.running += 1;
+            drop(scheduler);
+            spawn_scheduled(&self.thread_pool, self.scheduler.clone(), task.future);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Starts a queued task immediately. Does nothing on WebAssembly.
+    #[cfg(target_arch = "wasm32")]
+    pub fn promote(&self, _id: TaskId) -> bool {
+        false
+    }
+
+    /// Returns `true` if the current thread is running a task spawned by [`Self::spawn_task_with_priority`].
+    pub fn is_inside_prioritized_task() -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            INSIDE_SCHEDULED_TASK.with(|inside| inside.get())
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            false
+        }
+    }
+
+    /// Sets the maximum amount of concurrent tasks spawned by [`Self::spawn_task_with_priority`].
+    /// `None` means that there's no limit (default).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_max_concurrent_tasks(&self, max_concurrent_tasks: Option<usize>) {
+        let mut scheduler = self.scheduler.lock();
+        scheduler.max_concurrent_tasks = max_concurrent_tasks.map(|limit| limit.max(1));
+
+        // Start queued tasks, if the limit was increased.
+        let mut tasks = Vec::new();
+        while scheduler.has_free_slot() {
+            let Some(task) = scheduler.queue.pop() else {
+                break;
+            };
+            scheduler.running += 1;
+            tasks.push(task.future);
+        }
+        drop(scheduler);
+
+        for task in tasks {
+            spawn_scheduled(&self.thread_pool, self.scheduler.clone(), task);
+        }
+    }
+
+    /// Sets the maximum amount of concurrent tasks spawned by [`Self::spawn_task_with_priority`].
+    /// Does nothing on WebAssembly.
+    #[cfg(target_arch = "wasm32")]
+    pub fn set_max_concurrent_tasks(&self, _max_concurrent_tasks: Option<usize>) {}
+
+    /// Returns the maximum amount of concurrent tasks spawned by [`Self::spawn_task_with_priority`].
+    pub fn max_concurrent_tasks(&self) -> Option<usize> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.scheduler.lock().max_concurrent_tasks
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            None
+        }
+    }
+
+    /// Returns the amount of prioritized tasks, that are waiting for a free slot.
+    pub fn queued_task_count(&self) -> usize {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.scheduler.lock().queue.len()
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            0
+        }
+    }
+
     #[inline]
     pub fn spawn_with_result<F, T>(&self, future: F) -> Uuid
     where
@@ -81,3 +358,62 @@ impl TaskPool {
         self.receiver.lock().try_recv().ok()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{TaskPool, TaskPriority};
+    use crate::futures::channel::oneshot;
+    use std::{sync::mpsc::channel, time::Duration};
+
+    #[test]
+    fn test_task_priorities() {
+        let pool = TaskPool::new();
+        pool.set_max_concurrent_tasks(Some(1));
+
+        // The first task occupies the only slot until it is released.
+        let (release_sender, release_receiver) = oneshot::channel::<()>();
+        let (order_sender, order_receiver) = channel();
+
+        pool.spawn_task_with_priority(
+            async move {
+                release_receiver.await.unwrap();
+            },
+            TaskPriority::Normal,
+        );
+        for priority in [TaskPriority::Low, TaskPriority::Normal, TaskPriority::High] {
+            let order_sender = order_sender.clone();
+            pool.spawn_task_with_priority(
+                async move {
+                    order_sender.send(priority).unwrap();
+                },
+                priority,
+            );
+        }
+        assert_eq!(pool.queued_task_count(), 3);
+
+        let (promoted_sender, promoted_receiver) = channel();
+        let promoted = pool.spawn_task_with_priority(
+            async move {
+                promoted_sender.send(()).unwrap();
+            },
+            TaskPriority::Low,
+        );
+        assert!(pool.promote(promoted));
+        assert!(!pool.promote(promoted));
+        promoted_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(pool.queued_task_count(), 3);
+
+        release_sender.send(()).unwrap();
+
+        let order = (0..3)
+            .map(|_| order_receiver.recv_timeout(Duration::from_secs(5)).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            [TaskPriority::High, TaskPriority::Normal, TaskPriority::Low]
+        );
+        assert_eq!(pool.queued_task_count(), 0);
+    }
+}
@@ -59,6 +59,15 @@ impl<T: TypeUuidProvider> TypeUuidProvider for Option<T> {
     }
 }
 
+impl<T: TypeUuidProvider> TypeUuidProvider for std::ops::Range<T> {
+    fn type_uuid() -> Uuid {
+        combine_uuids(
+            uuid::uuid!("2d5c7f3a-8e41-4b96-b0d7-5a1e9c3f6b28"),
+            T::type_uuid(),
+        )
+    }
+}
+
 #[inline]
 pub fn combine_uuids(a: Uuid, b: Uuid) -> Uuid {
     let mut combined_bytes = a.into_bytes();
@@ -39,6 +39,19 @@ pub enum InheritError {
         /// Type of right property.
         right_type: &'static str,
     },
+    /// There is no property at the given path.
+    InvalidPath {
+        /// Path of the property.
+        path: String,
+    },
+    /// A property at the given path is not an inheritable variable.
+    NotInheritable {
+        /// Path of the property.
+        path: String,
+    },
+    /// There is no parent object to inherit a property from. For example, an object is not an
+    /// instance of a prefab or the prefab is not loaded.
+    NoParent,
 }
 
 /// A wrapper for a variable that hold additional flag that tells that initial value was changed in runtime.
@@ -171,6 +184,14 @@ impl<T> InheritableVariable<T> {
         self.flags.get().contains(VariableFlags::NEED_SYNC)
     }
 
+    /// Raises the [`VariableFlags::NEED_SYNC`] flag without marking the variable modified. It forces
+    /// the respective data model to be synced with the current value.
+    pub fn mark_need_sync(&self) {
+        let mut flags = self.flags.get();
+        flags.insert(VariableFlags::NEED_SYNC);
+        self.flags.set(flags);
+    }
+
     /// Returns a reference to the wrapped value.
     pub fn get_value_ref(&self) -> &T {
         &self.value
@@ -630,13 +651,112 @@ pub fn mark_inheritable_properties_modified(object: &mut dyn Reflect, ignored_ty
     );
 }
 
+/// Compares every modified inheritable variable of the child with the respective variable of the parent
+/// and returns paths of the variables that have different values. Variables that does not exist in the
+/// parent (for example, items of collections with different sizes) are considered different as well.
+/// Non-modified variables are ignored, because they always take their values from the parent.
+///
+/// Returned paths could be used with [`Reflect::resolve_path`](crate::reflect::ResolvePath::resolve_path)
+/// to fetch actual values or with [`revert_inheritable_property`] to discard the changes.
+pub fn collect_overridden_properties(
+    child: &dyn Reflect,
+    parent: &dyn Reflect,
+    ignored_types: &[TypeId],
+) -> Vec<String> {
+    let mut overrides = Vec::new();
+
+    child.enumerate_fields_recursively(
+        &mut |path, _, child_value| {
+            child_value.as_inheritable_variable(&mut |child_variable| {
+                let Some(child_variable) = child_variable else {
+                    return;
+                };
+
+                if !child_variable.is_modified() {
+                    return;
+                }
+
+                let mut differs = true;
+                parent.resolve_path(path, &mut |result| {
+                    if let Ok(parent_value) = result {
+                        parent_value.as_inheritable_variable(&mut |parent_variable| {
+                            if let Some(parent_variable) = parent_variable {
+                                differs = !child_variable.value_equals(parent_variable);
+                            }
+                        })
+                    }
+                });
+
+                if differs {
+                    overrides.push(path.to_string());
+                }
+            })
+        },
+        ignored_types,
+    );
+
+    overrides
+}
+
+/// Discards changes of an inheritable variable at the given path by taking the value of the respective
+/// variable of the parent. The variable will be marked as non-modified (as well as all inheritable variables
+/// inside it), so it will continue to take values from the parent on next inheritance pass.
+pub fn revert_inheritable_property(
+    child: &mut dyn Reflect,
+    parent: &dyn Reflect,
+    path: &str,
+    ignored_types: &[TypeId],
+) -> Result<(), InheritError> {
+    let mut result = Err(InheritError::InvalidPath {
+        path: path.to_string(),
+    });
+
+    parent.resolve_path(path, &mut |parent_result| {
+        let Ok(parent_value) = parent_result else {
+            return;
+        };
+
+        child.resolve_path_mut(path, &mut |child_result| {
+            let Ok(child_value) = child_result else {
+                return;
+            };
+
+            result = Err(InheritError::NotInheritable {
+                path: path.to_string(),
+            });
+
+            child_value.as_inheritable_variable_mut(&mut |child_variable| {
+                let Some(child_variable) = child_variable else {
+                    return;
+                };
+
+                parent_value.as_inheritable_variable(&mut |parent_variable| {
+                    let Some(parent_variable) = parent_variable else {
+                        return;
+                    };
+
+                    child_variable.reset_modified_flag();
+                    result = child_variable
+                        .try_inherit(parent_variable, ignored_types)
+                        .map(|_| ());
+                })
+            })
+        })
+    });
+
+    result
+}
+
 #[cfg(test)]
 mod test {
     use std::{cell::Cell, ops::DerefMut};
 
     use crate::{
         reflect::{prelude::*, ReflectInheritableVariable},
-        variable::{try_inherit_properties, InheritableVariable, VariableFlags},
+        variable::{
+            collect_overridden_properties, revert_inheritable_property, try_inherit_properties,
+            InheritableVariable, VariableFlags,
+        },
         visitor::{Visit, Visitor},
     };
 
@@ -692,6 +812,38 @@ mod test {
         assert!(va.value_equals(&vb))
     }
 
+    #[test]
+    fn test_overrides_collection_and_revert() {
+        let parent = Bar {
+            foo: Foo {
+                value: InheritableVariable::new_non_modified(1.23),
+            },
+            other_value: InheritableVariable::new_non_modified("Foobar".to_string()),
+        };
+
+        let mut child = parent.clone();
+
+        // Modified, but equal values must not be reported.
+        child.foo.value.mark_modified();
+        assert!(collect_overridden_properties(&child, &parent, &[]).is_empty());
+
+        child
+            .other_value
+            .set_value_and_mark_modified("Baz".to_string());
+        assert_eq!(
+            collect_overridden_properties(&child, &parent, &[]),
+            vec!["other_value".to_string()]
+        );
+
+        revert_inheritable_property(&mut child, &parent, "other_value", &[]).unwrap();
+        assert_eq!(*child.other_value, "Foobar");
+        assert!(!child.other_value.is_modified());
+        assert!(collect_overridden_properties(&child, &parent, &[]).is_empty());
+
+        assert!(revert_inheritable_property(&mut child, &parent, "foo", &[]).is_err());
+        assert!(revert_inheritable_property(&mut child, &parent, "baz", &[]).is_err());
+    }
+
     #[derive(Reflect, Debug)]
     enum SomeEnum {
         Bar(InheritableVariable<f32>),
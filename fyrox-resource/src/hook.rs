@@ -0,0 +1,230 @@
+//! Import hooks allow you to enforce asset conventions in code. Pre-import hooks are able to modify
+//! import options of a resource before it is loaded (for example - to force texture compression for
+//! every texture in a folder) and post-import hooks are able to modify the loaded resource data (for
+//! example - to generate colliders for models, that match some naming convention).
+
+use crate::{
+    core::parking_lot::Mutex,
+    options::{BaseImportOptions, ImportOptions},
+    ResourceData, TypedResourceData,
+};
+use std::{path::Path, sync::Arc};
+
+/// A filter that decides whether an import hook should be applied to a resource at the given path.
+pub type PathFilter = Box<dyn Fn(&Path) -> bool + Send>;
+
+/// A function, that is used by typed import hooks to modify a value of the given type.
+pub type HookFn<T> = Box<dyn Fn(&Path, &mut T) + Send>;
+
+/// Import hook is able to modify import options before a resource is loaded and the resource data
+/// after it is loaded. See [`PreImportHook`] and [`PostImportHook`] for typed hooks, that covers the
+/// most common use cases.
+pub trait ImportHook: Send + 'static {
+    /// Returns `true` if the hook should be applied to a resource at the given path.
+    fn matches(&self, #[allow(unused_variables)] path: &Path) -> bool {
+        true
+    }
+
+    /// Called before a resource at the given path is loaded. Import options are either loaded from
+    /// a respective options file or the default import options of a resource loader. Keep in mind,
+    /// that only resources with import options support this hook.
+    fn before_import(
+        &self,
+        #[allow(unused_variables)] path: &Path,
+        #[allow(unused_variables)] options: &mut dyn BaseImportOptions,
+    ) {
+    }
+
+    /// Called after a resource at the given path was successfully loaded (or reloaded), but before
+    /// it is available to the users of the resource.
+    fn after_import(
+        &self,
+        #[allow(unused_variables)] path: &Path,
+        #[allow(unused_variables)] data: &mut dyn ResourceData,
+    ) {
+    }
+}
+
+/// A typed pre-import hook, that modifies import options of a particular type.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_resource::{hook::PreImportHook, options::ImportOptions};
+/// # use std::path::Path;
+/// fn force_options<T: ImportOptions>(modifier: fn(&mut T)) -> PreImportHook<T> {
+///     PreImportHook::new(move |_path: &Path, options: &mut T| modifier(options))
+///         .with_filter(|path| path.starts_with("data/textures/ui"))
+/// }
+/// ```
+pub struct PreImportHook<T> {
+    filter: Option<PathFilter>,
+    func: HookFn<T>,
+}
+
+impl<T: ImportOptions> PreImportHook<T> {
+    /// Creates a new hook with the given function, that will be applied to import options of the
+    /// resources with the `T` type of import options.
+    pub fn new<F>(func: F) -> Self
+    where
+        F: Fn(&Path, &mut T) + Send + 'static,
+    {
+        Self {
+            filter: None,
+            func: Box::new(func),
+        }
+    }
+
+    /// Sets a filter for the hook, the hook will be applied only to resources whose paths pass the
+    /// filter.
+    pub fn with_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Path) -> bool + Send + 'static,
+    {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+}
+
+impl<T: ImportOptions> ImportHook for PreImportHook<T> {
+    fn matches(&self, path: &Path) -> bool {
+        self.filter.as_ref().map_or(true, |filter| filter(path))
+    }
+
+    fn before_import(&self, path: &Path, options: &mut dyn BaseImportOptions) {
+        if let Some(options) = BaseImportOptions::as_any_mut(options).downcast_mut::<T>() {
+            (self.func)(path, options)
+        }
+    }
+}
+
+/// A typed post-import hook, that modifies the resource data of a particular type.
+pub struct PostImportHook<T> {
+    filter: Option<PathFilter>,
+    func: HookFn<T>,
+}
+
+impl<T: TypedResourceData> PostImportHook<T> {
+    /// Creates a new hook with the given function, that will be applied to every loaded resource of
+    /// the `T` type.
+    pub fn new<F>(func: F) -> Self
+    where
+        F: Fn(&Path, &mut T) + Send + 'static,
+    {
+        Self {
+            filter: None,
+            func: Box::new(func),
+        }
+    }
+
+    /// Sets a filter for the hook, the hook will be applied only to resources whose paths pass the
+    /// filter.
+    pub fn with_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Path) -> bool + Send + 'static,
+    {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+}
+
+impl<T: TypedResourceData> ImportHook for PostImportHook<T> {
+    fn matches(&self, path: &Path) -> bool {
+        self.filter.as_ref().map_or(true, |filter| filter(path))
+    }
+
+    fn after_import(&self, path: &Path, data: &mut dyn ResourceData) {
+        if let Some(data) = ResourceData::as_any_mut(data).downcast_mut::<T>() {
+            (self.func)(path, data)
+        }
+    }
+}
+
+/// A shared container for import hooks. It could be cloned and the clones will share the same set of
+/// hooks, so the hooks could be added at any time (even after a resource loader was created).
+#[derive(Clone, Default)]
+pub struct ImportHookContainer {
+    hooks: Arc<Mutex<Vec<Box<dyn ImportHook>>>>,
+}
+
+impl ImportHookContainer {
+    /// Creates new empty container.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a new hook to the container. Hooks are applied in the order of addition.
+    pub fn add<H: ImportHook>(&self, hook: H) {
+        self.hooks.lock().push(Box::new(hook));
+    }
+
+    /// Removes all hooks from the container.
+    pub fn clear(&self) {
+        self.hooks.lock().clear();
+    }
+
+    /// Returns total amount of hooks in the container.
+    pub fn len(&self) -> usize {
+        self.hooks.lock().len()
+    }
+
+    /// Returns `true` if the container has no hooks.
+    pub fn is_empty(&self) -> bool {
+        self.hooks.lock().is_empty()
+    }
+
+    /// Applies every matching hook to the given import options. This method is intended to be used by
+    /// resource loaders, that support import options.
+    pub fn before_import(&self, path: &Path, options: &mut dyn BaseImportOptions) {
+        for hook in self.hooks.lock().iter() {
+            if hook.matches(path) {
+                hook.before_import(path, options);
+            }
+        }
+    }
+
+    /// Applies every matching hook to the given resource data. Resource manager calls this method
+    /// automatically for every loaded resource.
+    pub fn after_import(&self, path: &Path, data: &mut dyn ResourceData) {
+        for hook in self.hooks.lock().iter() {
+            if hook.matches(path) {
+                hook.after_import(path, data);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::reflect::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use std::path::PathBuf;
+
+    #[derive(Clone, Default, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+    struct Options {
+        compressed: bool,
+    }
+
+    impl ImportOptions for Options {}
+
+    #[test]
+    fn test_pre_import_hook() {
+        let hooks = ImportHookContainer::new();
+        hooks.add(
+            PreImportHook::new(|_path: &Path, options: &mut Options| options.compressed = true)
+                .with_filter(|path| path.starts_with("data/textures")),
+        );
+        assert_eq!(hooks.len(), 1);
+
+        let mut options = Options::default();
+        hooks.before_import(&PathBuf::from("data/models/foo.png"), &mut options);
+        assert!(!options.compressed);
+
+        hooks.before_import(&PathBuf::from("data/textures/foo.png"), &mut options);
+        assert!(options.compressed);
+
+        hooks.clear();
+        assert!(hooks.is_empty());
+    }
+}
@@ -126,11 +126,26 @@ impl ResourceIo for FsResourceIo {
         dest: &'a Path,
     ) -> ResourceIoFuture<'a, Result<(), FileLoadError>> {
         Box::pin(async move {
-            std::fs::rename(source, dest)?;
-            Ok(())
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                std::fs::rename(source, dest)?;
+                Ok(())
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            {
+                Err(FileLoadError::Custom(format!(
+                    "Unable to move {} to {}: moving files is not supported on WebAssembly!",
+                    source.display(),
+                    dest.display()
+                )))
+            }
         })
     }
 
+    /// wasm should fallback to the default impl that returns the path as is, because there is
+    /// no file system to resolve the path against.
+    #[cfg(not(target_arch = "wasm32"))]
     fn canonicalize_path<'a>(
         &'a self,
         path: &'a Path,
@@ -140,9 +155,6 @@ impl ResourceIo for FsResourceIo {
 
     /// wasm should fallback to the default no-op impl as im not sure if they
     /// can directly read a directory
-    ///
-    /// Note: Android directory reading should be possible just I have not created
-    /// an implementation for this yet
     #[cfg(all(not(target_os = "android"), not(target_arch = "wasm32")))]
     fn read_directory<'a>(
         &'a self,
@@ -155,6 +167,31 @@ impl ResourceIo for FsResourceIo {
         })
     }
 
+    /// Android assets are read using the asset manager of the app. Keep in mind, that the asset
+    /// manager lists only files of the directory, sub-directories are not listed.
+    #[cfg(target_os = "android")]
+    fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> ResourceIoFuture<'a, Result<PathIter, FileLoadError>> {
+        Box::pin(async move {
+            let app = fyrox_core::io::ANDROID_APP
+                .get()
+                .ok_or_else(|| FileLoadError::Custom("ANDROID_APP is not set".to_string()))?;
+            let dir_path = std::ffi::CString::new(path.to_string_lossy().as_bytes())
+                .map_err(|e| FileLoadError::Custom(e.to_string()))?;
+            let dir = app.asset_manager().open_dir(&dir_path).ok_or_else(|| {
+                FileLoadError::Custom(format!("Directory {} not found!", path.display()))
+            })?;
+            // Asset directory is not thread-safe, so collect its content first.
+            let paths = dir
+                .map(|name| path.join(name.to_string_lossy().as_ref()))
+                .collect::<Vec<_>>();
+            let iter: PathIter = Box::new(paths.into_iter());
+            Ok(iter)
+        })
+    }
+
     /// Android and wasm should fallback to the default no-op impl as they cant be
     /// walked with WalkDir
     #[cfg(all(not(target_os = "android"), not(target_arch = "wasm32")))]
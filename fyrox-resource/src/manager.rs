@@ -11,15 +11,16 @@ use crate::{
         log::Log,
         make_relative_path, notify,
         parking_lot::{Mutex, MutexGuard},
-        task::TaskPool,
+        task::{TaskId, TaskPool, TaskPriority},
         watcher::FileSystemWatcher,
         TypeUuidProvider,
     },
     entry::{TimedEntry, DEFAULT_RESOURCE_LIFETIME},
     event::{ResourceEvent, ResourceEventBroadcaster},
+    hook::ImportHookContainer,
     io::{FsResourceIo, ResourceIo},
     loader::{ResourceLoader, ResourceLoadersContainer},
-    options::OPTIONS_EXTENSION,
+    options::{BaseImportOptions, OPTIONS_EXTENSION},
     state::{LoadError, ResourceState},
     Resource, ResourceData, TypedResourceData, UntypedResource,
 };
@@ -65,10 +66,15 @@ pub struct ResourceManagerState {
     pub built_in_resources: FxHashMap<PathBuf, UntypedResource>,
     /// The resource acccess interface
     pub resource_io: Arc<dyn ResourceIo>,
+    /// A set of import hooks, that are used to modify import options and loaded resource data. Use
+    /// this field to register your own hooks and enforce asset conventions.
+    pub import_hooks: ImportHookContainer,
 
     resources: Vec<TimedEntry<UntypedResource>>,
     task_pool: Arc<TaskPool>,
     watcher: Option<FileSystemWatcher>,
+    // Loading tasks, that could be waiting for a free slot (if the limit of concurrent loads is set).
+    queued_loads: FxHashMap<PathBuf, TaskId>,
 }
 
 /// See module docs.
@@ -189,6 +195,32 @@ impl ResourceManager {
         }
     }
 
+    /// Same as [`Self::request`], but allows you to specify a priority of loading. Priorities make sense only
+    /// if there's a limit of concurrent loads (see [`ResourceManagerState::set_max_concurrent_loads`]), in this
+    /// case queued resources with higher priority will be loaded first. It is useful for streaming, for example
+    /// resources of distant areas of a level could be requested with [`TaskPriority::Low`] priority, so they
+    /// won't delay loading of the resources that are needed right now.
+    ///
+    /// ## Panic
+    ///
+    /// This method will panic, if type UUID of `T` does not match the actual type UUID of the resource.
+    pub fn request_with_priority<T>(
+        &self,
+        path: impl AsRef<Path>,
+        priority: TaskPriority,
+    ) -> Resource<T>
+    where
+        T: TypedResourceData,
+    {
+        let untyped = self.state().request_with_priority(path, priority);
+        let actual_type_uuid = untyped.type_uuid();
+        assert_eq!(actual_type_uuid, <T as TypeUuidProvider>::type_uuid());
+        Resource {
+            untyped,
+            phantom: PhantomData::<T>,
+        }
+    }
+
     /// Same as [`Self::request`], but returns untyped resource.
     pub fn request_untyped<P>(&self, path: P) -> UntypedResource
     where
@@ -197,6 +229,30 @@ impl ResourceManager {
         self.state().request(path)
     }
 
+    /// Returns `true` if there's a resource loader, that supports the extension of the file at the
+    /// given path.
+    pub fn can_load<P>(&self, path: P) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        self.state().find_loader(path.as_ref()).is_some()
+    }
+
+    /// Same as [`Self::request_untyped`], but returns [`None`] if there's no resource loader for the
+    /// file at the given path. It is useful to load files from external sources, for example files
+    /// dropped on the main window by a user, which could be of any type.
+    pub fn try_request_untyped<P>(&self, path: P) -> Option<UntypedResource>
+    where
+        P: AsRef<Path>,
+    {
+        let mut state = self.state();
+        if state.find_loader(path.as_ref()).is_some() {
+            Some(state.request(path))
+        } else {
+            None
+        }
+    }
+
     /// Saves given resources in the specified path and registers it in resource manager, so
     /// it will be accessible through it later.
     pub fn register<P, F>(
@@ -333,6 +389,66 @@ impl ResourceManager {
         Ok(())
     }
 
+    /// Loads import options of a resource at the given path. If there's no options file for the resource,
+    /// default import options of a respective resource loader are returned. Returns `None` if there's no
+    /// loader for the resource or the loader does not support import options.
+    pub async fn load_import_options<P>(&self, path: P) -> Option<Box<dyn BaseImportOptions>>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        // Separate scope to not hold the lock while loading the options.
+        let (options_future, default_options) = {
+            let state = self.state();
+            let loader = state.find_loader(path)?;
+            (
+                loader.try_load_import_settings(path.to_owned(), state.resource_io.clone()),
+                loader.default_import_options(),
+            )
+        };
+
+        options_future.await.or(default_options)
+    }
+
+    /// Saves the given import options in the options file of a resource at the given path and reimports
+    /// the resource if the options have changed. Reimport reloads the resource, which invalidates all the
+    /// data derived from it (GPU textures, model instances in scenes, etc.). Returns `true` if the resource
+    /// is being reimported, `false` - if the options are the same, or they could not be saved, or the
+    /// resource is not loaded (in this case the options will be used on the next load).
+    pub async fn reimport<P>(&self, path: P, import_options: &dyn BaseImportOptions) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let options_path = append_extension(path, OPTIONS_EXTENSION);
+
+        let io = self.resource_io();
+        let previous_options = io.load_file(&options_path).await.ok();
+        if !import_options.save(&options_path) {
+            Log::err(format!(
+                "Unable to save import options of {} resource to {}!",
+                path.display(),
+                options_path.display()
+            ));
+            return false;
+        }
+
+        if previous_options.is_some() && previous_options == io.load_file(&options_path).await.ok()
+        {
+            return false;
+        }
+
+        let reimported = self.state().try_reload_resource_from_path(path);
+        if reimported {
+            Log::info(format!(
+                "Import options of {} resource were changed, reimporting...",
+                path.display()
+            ));
+        }
+        reimported
+    }
+
     /// Reloads all loaded resources. Normally it should never be called, because it is **very** heavy
     /// method! This method is asynchronous, it uses all available CPU power to reload resources as
     /// fast as possible.
@@ -350,7 +466,9 @@ impl ResourceManagerState {
             loaders: Default::default(),
             event_broadcaster: Default::default(),
             constructors_container: Default::default(),
+            import_hooks: Default::default(),
             watcher: None,
+            queued_loads: Default::default(),
             built_in_resources: Default::default(),
             // Use the file system resource io by default
             resource_io: Arc::new(FsResourceIo),
@@ -363,6 +481,21 @@ impl ResourceManagerState {
         self.resource_io = resource_io;
     }
 
+    /// Sets the maximum amount of resources, that could be loaded at the same time. `None` means that
+    /// there's no limit (default). The resources above the limit are queued and loaded in the order of
+    /// their priorities (see [`ResourceManager::request_with_priority`]). Dependencies of a resource
+    /// that is being loaded are never queued. Keep in mind, that the limit is shared across every
+    /// resource manager, that uses the same task pool.
+    pub fn set_max_concurrent_loads(&mut self, max_concurrent_loads: Option<usize>) {
+        self.task_pool
+            .set_max_concurrent_tasks(max_concurrent_loads);
+    }
+
+    /// Returns the maximum amount of resources, that could be loaded at the same time.
+    pub fn max_concurrent_loads(&self) -> Option<usize> {
+        self.task_pool.max_concurrent_tasks()
+    }
+
     /// Sets resource watcher which will track any modifications in file system and forcing
     /// the manager to reload changed resources. By default there is no watcher, since it
     /// may be an undesired effect to reload resources at runtime. This is very useful thing
@@ -397,6 +530,10 @@ impl ResourceManagerState {
     /// Normally, this is called from `Engine::update()`.
     /// You should only call this manually if you don't use that method.
     pub fn update(&mut self, dt: f32) {
+        if self.task_pool.queued_task_count() == 0 {
+            self.queued_loads.clear();
+        }
+
         self.resources.retain_mut(|resource| {
             // One usage means that the resource has single owner, and that owner
             // is this container. Such resources have limited life time, if the time
@@ -429,22 +566,37 @@ impl ResourceManagerState {
         });
 
         if let Some(watcher) = self.watcher.as_ref() {
-            if let Some(evt) = watcher.try_get_event() {
-                if let notify::EventKind::Modify(_) = evt.kind {
+            // Collect every change since the last update, a single save of a file could produce multiple
+            // events (some editors write to a temporary file and then rename it).
+            let mut changed_paths = FxHashSet::default();
+            while let Some(evt) = watcher.try_get_event() {
+                if let notify::EventKind::Modify(_) | notify::EventKind::Create(_) = evt.kind {
                     for path in evt.paths {
                         if let Ok(relative_path) = make_relative_path(path) {
-                            if self.try_reload_resource_from_path(&relative_path) {
-                                Log::info(format!(
-                                        "File {} was changed, trying to reload a respective resource...",
-                                        relative_path.display()
-                                    ));
-
-                                break;
-                            }
+                            changed_paths.insert(relative_path);
                         }
                     }
                 }
             }
+
+            for path in changed_paths {
+                // Changes in import options must cause reimport of a respective resource.
+                let resource_path = if path
+                    .extension()
+                    .is_some_and(|extension| extension == OPTIONS_EXTENSION)
+                {
+                    path.with_extension("")
+                } else {
+                    path
+                };
+
+                if self.try_reload_resource_from_path(&resource_path) {
+                    Log::info(format!(
+                        "File {} was changed, trying to reload a respective resource...",
+                        resource_path.display()
+                    ));
+                }
+            }
         }
     }
 
@@ -525,6 +677,15 @@ impl ResourceManagerState {
 
     /// Tries to load a resources at a given path.
     pub fn request<P>(&mut self, path: P) -> UntypedResource
+    where
+        P: AsRef<Path>,
+    {
+        self.request_with_priority(path, TaskPriority::Normal)
+    }
+
+    /// Tries to load a resources at a given path with the given priority. See
+    /// [`ResourceManager::request_with_priority`] docs for more info.
+    pub fn request_with_priority<P>(&mut self, path: P, priority: TaskPriority) -> UntypedResource
     where
         P: AsRef<Path>,
     {
@@ -533,14 +694,31 @@ impl ResourceManagerState {
         }
 
         match self.find(path.as_ref()) {
-            Some(existing) => existing.clone(),
+            Some(existing) => {
+                let existing = existing.clone();
+                // A resource that is being loaded could wait for its dependencies, so they must not
+                // wait in the queue.
+                if TaskPool::is_inside_prioritized_task() {
+                    if let Some(task_id) = self.queued_loads.remove(path.as_ref()) {
+                        self.task_pool.promote(task_id);
+                    }
+                }
+                existing
+            }
             None => {
                 let path = path.as_ref().to_owned();
                 let kind = ResourceKind::External(path.clone());
 
                 if let Some(loader) = self.find_loader(path.as_ref()) {
                     let resource = UntypedResource::new_pending(kind, loader.data_type_uuid());
-                    self.spawn_loading_task(path, resource.clone(), loader, false);
+                    let task_id = self.spawn_loading_task(
+                        path.clone(),
+                        resource.clone(),
+                        loader,
+                        false,
+                        priority,
+                    );
+                    self.remember_queued_load(path, task_id);
                     self.push(resource.clone());
                     resource
                 } else {
@@ -552,6 +730,12 @@ impl ResourceManagerState {
         }
     }
 
+    fn remember_queued_load(&mut self, path: PathBuf, task_id: TaskId) {
+        if self.task_pool.max_concurrent_tasks().is_some() {
+            self.queued_loads.insert(path, task_id);
+        }
+    }
+
     fn find_loader(&self, path: &Path) -> Option<&dyn ResourceLoader> {
         path.extension().and_then(|extension| {
             self.loaders
@@ -566,40 +750,47 @@ impl ResourceManagerState {
         resource: UntypedResource,
         loader: &dyn ResourceLoader,
         reload: bool,
-    ) {
+        priority: TaskPriority,
+    ) -> TaskId {
         let event_broadcaster = self.event_broadcaster.clone();
+        let import_hooks = self.import_hooks.clone();
         let loader_future = loader.load(path.clone(), self.resource_io.clone());
-        self.task_pool.spawn_task(async move {
-            match loader_future.await {
-                Ok(data) => {
-                    let data = data.0;
+        self.task_pool.spawn_task_with_priority(
+            async move {
+                match loader_future.await {
+                    Ok(data) => {
+                        let mut data = data.0;
 
-                    Log::info(format!(
-                        "Resource {} was loaded successfully!",
-                        path.display()
-                    ));
+                        import_hooks.after_import(&path, &mut *data);
 
-                    // Separate scope to keep mutex locking time at minimum.
-                    {
-                        let mut mutex_guard = resource.0.lock();
-                        assert_eq!(mutex_guard.type_uuid, data.type_uuid());
-                        assert!(mutex_guard.kind.is_external());
-                        mutex_guard.state.commit(ResourceState::Ok(data));
-                    }
+                        Log::info(format!(
+                            "Resource {} was loaded successfully!",
+                            path.display()
+                        ));
 
-                    event_broadcaster.broadcast_loaded_or_reloaded(resource, reload);
-                }
-                Err(error) => {
-                    Log::info(format!(
-                        "Resource {} failed to load. Reason: {:?}",
-                        path.display(),
-                        error
-                    ));
+                        // Separate scope to keep mutex locking time at minimum.
+                        {
+                            let mut mutex_guard = resource.0.lock();
+                            assert_eq!(mutex_guard.type_uuid, data.type_uuid());
+                            assert!(mutex_guard.kind.is_external());
+                            mutex_guard.state.commit(ResourceState::Ok(data));
+                        }
 
-                    resource.commit_error(error);
+                        event_broadcaster.broadcast_loaded_or_reloaded(resource, reload);
+                    }
+                    Err(error) => {
+                        Log::info(format!(
+                            "Resource {} failed to load. Reason: {:?}",
+                            path.display(),
+                            error
+                        ));
+
+                        resource.commit_error(error);
+                    }
                 }
-            }
-        });
+            },
+            priority,
+        )
     }
 
     /// Reloads a single resource.
@@ -612,7 +803,14 @@ impl ResourceManagerState {
                     header.state.switch_to_pending_state();
                     drop(header);
 
-                    self.spawn_loading_task(path, resource, loader, true);
+                    let task_id = self.spawn_loading_task(
+                        path.clone(),
+                        resource,
+                        loader,
+                        true,
+                        TaskPriority::Normal,
+                    );
+                    self.remember_queued_load(path, task_id);
                 } else {
                     let msg = format!(
                         "There's no resource loader for {} resource!",
@@ -682,11 +880,18 @@ mod test {
     use std::error::Error;
     use std::{fs::File, time::Duration};
 
-    use crate::loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader};
+    use crate::{
+        loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+        options::ImportOptions,
+    };
+    use serde::{Deserialize, Serialize};
 
     use super::*;
 
-    use fyrox_core::uuid::{uuid, Uuid};
+    use fyrox_core::{
+        futures::executor::block_on,
+        uuid::{uuid, Uuid},
+    };
     use fyrox_core::{
         reflect::{FieldInfo, Reflect},
         visitor::{Visit, VisitResult, Visitor},
@@ -914,6 +1119,44 @@ mod test {
         assert!(resource.is_loading());
     }
 
+    #[derive(Clone, Default, Debug, Reflect, Serialize, Deserialize)]
+    struct StubOptions {
+        value: u32,
+    }
+
+    impl ImportOptions for StubOptions {}
+
+    #[test]
+    fn resource_manager_reimport() {
+        let manager = ResourceManager::new(Arc::new(Default::default()));
+        manager.state().loaders.set(Stub {});
+
+        let dir = std::env::temp_dir().join(format!("fyrox_resource_reimport_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("test.txt");
+        let resource = UntypedResource::new_load_error(
+            path.clone().into(),
+            Default::default(),
+            Uuid::default(),
+        );
+        manager.state().push(resource);
+
+        assert!(block_on(manager.reimport(&path, &StubOptions { value: 1 })));
+        // Same options must not cause reimport.
+        assert!(!block_on(
+            manager.reimport(&path, &StubOptions { value: 1 })
+        ));
+        // Unknown resources cannot be reimported, but the options must be saved anyway.
+        let unknown = dir.join("unknown.txt");
+        assert!(!block_on(
+            manager.reimport(&unknown, &StubOptions { value: 2 })
+        ));
+        assert!(append_extension(&unknown, OPTIONS_EXTENSION).exists());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn resource_manager_state_get_wait_context() {
         let mut state = new_resource_manager();
@@ -948,6 +1191,26 @@ mod test {
         assert!(res.is_ok());
     }
 
+    #[test]
+    fn resource_manager_request_with_priority() {
+        let manager = ResourceManager::new(Arc::new(Default::default()));
+        manager.state().loaders.set(Stub {});
+        manager.state().set_max_concurrent_loads(Some(1));
+        assert_eq!(manager.state().max_concurrent_loads(), Some(1));
+
+        let resources = [TaskPriority::Low, TaskPriority::Normal, TaskPriority::High]
+            .into_iter()
+            .enumerate()
+            .map(|(i, priority)| {
+                manager.request_with_priority::<Stub>(format!("{i}.txt"), priority)
+            })
+            .collect::<Vec<_>>();
+
+        for resource in resources {
+            assert!(block_on(resource).is_ok());
+        }
+    }
+
     #[test]
     fn resource_manager_request() {
         let manager = ResourceManager::new(Arc::new(Default::default()));
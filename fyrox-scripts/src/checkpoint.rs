@@ -0,0 +1,256 @@
+//! Checkpoint and respawn scripts are optional gameplay scaffolding, that allows you to create
+//! checkpoints that records a snapshot of flagged scene state and death volumes that respawns a
+//! target (usually a player) at the last activated checkpoint. See [`Checkpoint`] and [`DeathVolume`]
+//! docs for more info.
+
+use fyrox::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        impl_component_provider,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid_provider,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    scene::{collider::Collider, graph::Graph, node::Node, rigidbody::RigidBody},
+    script::{ScriptContext, ScriptTrait},
+};
+
+/// Saved state of a single scene node.
+#[derive(Visit, Reflect, Default, Debug, Clone, PartialEq)]
+pub struct NodeSnapshot {
+    /// A handle of the node.
+    pub node: Handle<Node>,
+    /// Local position of the node.
+    pub position: Vector3<f32>,
+    /// Local rotation of the node.
+    pub rotation: UnitQuaternion<f32>,
+    /// Local scale of the node.
+    pub scale: Vector3<f32>,
+    /// Whether the node was enabled or not.
+    pub enabled: bool,
+}
+
+impl NodeSnapshot {
+    fn capture(handle: Handle<Node>, node: &Node) -> Self {
+        let transform = node.local_transform();
+        Self {
+            node: handle,
+            position: **transform.position(),
+            rotation: **transform.rotation(),
+            scale: **transform.scale(),
+            enabled: node.is_enabled(),
+        }
+    }
+
+    fn restore(&self, graph: &mut Graph) {
+        if let Some(node) = graph.try_get_mut(self.node) {
+            node.local_transform_mut()
+                .set_position(self.position)
+                .set_rotation(self.rotation)
+                .set_scale(self.scale);
+            node.set_enabled(self.enabled);
+        }
+    }
+}
+
+/// Returns `true` if the given target node (or any of its descendants) intersects with a sensor
+/// collider with the given handle.
+pub fn is_target_inside_volume(graph: &Graph, volume: Handle<Node>, target: Handle<Node>) -> bool {
+    let Some(collider) = graph.try_get_of_type::<Collider>(volume) else {
+        return false;
+    };
+
+    collider
+        .intersects(&graph.physics)
+        .filter(|pair| pair.has_any_active_contact)
+        .any(|pair| {
+            let mut other = if pair.collider1 == volume {
+                pair.collider2
+            } else {
+                pair.collider1
+            };
+            while other.is_some() {
+                if other == target {
+                    return true;
+                }
+                other = graph.try_get(other).map_or(Handle::NONE, |n| n.parent());
+            }
+            false
+        })
+}
+
+/// Checkpoint is a script, that should be assigned to a sensor collider (trigger volume). When the
+/// target (usually a player) enters the volume, the checkpoint becomes active and records a snapshot
+/// of every scene node that has a tag equal to [`Checkpoint::snapshot_tag`]. The snapshot is
+/// restored when the target is respawned using [`respawn`] (for example by a [`DeathVolume`]).
+///
+/// The state of checkpoints is serialized together with the scene, so it is preserved in saved
+/// games.
+#[derive(Visit, Reflect, Debug, Clone)]
+pub struct Checkpoint {
+    #[reflect(description = "A node (usually a player) that activates the checkpoint.")]
+    #[visit(optional)]
+    pub target: InheritableVariable<Handle<Node>>,
+
+    #[reflect(
+        description = "A node, at which position the target will be respawned. If not set, the \
+        position of the checkpoint is used."
+    )]
+    #[visit(optional)]
+    pub respawn_point: InheritableVariable<Handle<Node>>,
+
+    #[reflect(
+        description = "Every scene node with this tag will be saved when the checkpoint is activated \
+        and restored on respawn."
+    )]
+    #[visit(optional)]
+    pub snapshot_tag: InheritableVariable<String>,
+
+    #[reflect(hidden)]
+    #[visit(optional)]
+    pub activation_order: u32,
+
+    #[reflect(hidden)]
+    #[visit(optional)]
+    pub snapshot: Vec<NodeSnapshot>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub target_inside: bool,
+}
+
+impl Default for Checkpoint {
+    fn default() -> Self {
+        Self {
+            target: Default::default(),
+            respawn_point: Default::default(),
+            snapshot_tag: "Checkpoint".to_string().into(),
+            activation_order: 0,
+            snapshot: Default::default(),
+            target_inside: false,
+        }
+    }
+}
+
+impl_component_provider!(Checkpoint);
+uuid_provider!(Checkpoint = "f3b1c2d4-5e6f-4a7b-8c9d-0e1f2a3b4c5d");
+
+impl Checkpoint {
+    /// Returns `true` if the checkpoint was activated at least once.
+    pub fn is_activated(&self) -> bool {
+        self.activation_order != 0
+    }
+
+    /// Activates the checkpoint and records a snapshot of flagged scene nodes. `this` is a handle of
+    /// the node to which the checkpoint is assigned to.
+    pub fn activate(&mut self, this: Handle<Node>, graph: &Graph) {
+        let last_order = graph
+            .linear_iter()
+            .filter_map(|node| node.try_get_script::<Checkpoint>())
+            .map(|checkpoint| checkpoint.activation_order)
+            .max()
+            .unwrap_or_default();
+        // Do not change the order if the checkpoint is already the last one.
+        if self.activation_order == 0 || self.activation_order < last_order {
+            self.activation_order = last_order + 1;
+        }
+
+        self.snapshot = graph
+            .pair_iter()
+            .filter(|(handle, node)| *handle != this && node.tag() == self.snapshot_tag.as_str())
+            .map(|(handle, node)| NodeSnapshot::capture(handle, node))
+            .collect();
+    }
+
+    fn respawn_position(&self, this: Handle<Node>, graph: &Graph) -> Vector3<f32> {
+        graph
+            .try_get(*self.respawn_point)
+            .or_else(|| graph.try_get(this))
+            .map(|node| node.global_position())
+            .unwrap_or_default()
+    }
+}
+
+impl ScriptTrait for Checkpoint {
+    fn on_update(&mut self, context: &mut ScriptContext) {
+        let inside = is_target_inside_volume(&context.scene.graph, context.handle, *self.target);
+        if inside && !self.target_inside {
+            self.activate(context.handle, &context.scene.graph);
+        }
+        self.target_inside = inside;
+    }
+}
+
+/// Respawns the given target at the last activated checkpoint and restores the snapshot of the
+/// checkpoint. The target is expected to be a root-level node (for example a rigid body of a
+/// player), its velocities are reset if it is a rigid body. Returns `false` if there is no
+/// activated checkpoint in the graph.
+pub fn respawn(graph: &mut Graph, target: Handle<Node>) -> bool {
+    let Some((checkpoint_handle, checkpoint)) = graph
+        .pair_iter()
+        .filter_map(|(handle, node)| Some((handle, node.try_get_script::<Checkpoint>()?)))
+        .filter(|(_, checkpoint)| checkpoint.is_activated())
+        .max_by_key(|(_, checkpoint)| checkpoint.activation_order)
+    else {
+        return false;
+    };
+
+    let position = checkpoint.respawn_position(checkpoint_handle, graph);
+    let snapshot = checkpoint.snapshot.clone();
+
+    for node_snapshot in snapshot.iter() {
+        node_snapshot.restore(graph);
+    }
+
+    if let Some(target) = graph.try_get_mut(target) {
+        target.local_transform_mut().set_position(position);
+        if let Some(rigid_body) = target.cast_mut::<RigidBody>() {
+            rigid_body.set_lin_vel(Vector3::zeros());
+            rigid_body.set_ang_vel(Vector3::zeros());
+        }
+    }
+
+    true
+}
+
+/// Death volume is a script, that should be assigned to a sensor collider (trigger volume). When the
+/// target (usually a player) enters the volume, it is respawned at the last activated [`Checkpoint`].
+/// If there is no activated checkpoint, the target is moved to the fallback respawn point (if any).
+#[derive(Visit, Reflect, Default, Debug, Clone)]
+pub struct DeathVolume {
+    #[reflect(description = "A node (usually a player) that will be respawned.")]
+    #[visit(optional)]
+    pub target: InheritableVariable<Handle<Node>>,
+
+    #[reflect(
+        description = "A node, at which position the target will be respawned if there is no \
+        activated checkpoint."
+    )]
+    #[visit(optional)]
+    pub fallback_respawn_point: InheritableVariable<Handle<Node>>,
+}
+
+impl_component_provider!(DeathVolume);
+uuid_provider!(DeathVolume = "0a6c7e3f-2b1d-4e8a-9f5c-7d4b3a2e1c60");
+
+impl ScriptTrait for DeathVolume {
+    fn on_update(&mut self, context: &mut ScriptContext) {
+        let graph = &mut context.scene.graph;
+        if !is_target_inside_volume(graph, context.handle, *self.target) {
+            return;
+        }
+
+        if !respawn(graph, *self.target) {
+            if let Some(position) = graph
+                .try_get(*self.fallback_respawn_point)
+                .map(|node| node.global_position())
+            {
+                if let Some(target) = graph.try_get_mut(*self.target) {
+                    target.local_transform_mut().set_position(position);
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,13 @@
 //! A set of useful scripts that can be used to in your game.
 
-use crate::camera::FlyingCameraController;
+use crate::{
+    camera::FlyingCameraController,
+    checkpoint::{Checkpoint, DeathVolume},
+};
 use fyrox::script::constructor::ScriptConstructorContainer;
 
 pub mod camera;
+pub mod checkpoint;
 
 /// Registers every script from the crate in the given constructor container. Use it, if you want to register all
 /// available scripts at once. Typical usage could be like this:
@@ -33,4 +37,6 @@ pub mod camera;
 /// ```
 pub fn register(container: &ScriptConstructorContainer) {
     container.add::<FlyingCameraController>("Fyrox Flying Camera Controller");
+    container.add::<Checkpoint>("Fyrox Checkpoint");
+    container.add::<DeathVolume>("Fyrox Death Volume");
 }
@@ -3,6 +3,7 @@
 use crate::buffer::{DataSource, SoundBuffer};
 use fyrox_core::{reflect::prelude::*, uuid::Uuid, TypeUuidProvider};
 use fyrox_resource::{
+    hook::ImportHookContainer,
     io::ResourceIo,
     loader::{BoxedImportOptionsLoaderFuture, BoxedLoaderFuture, LoaderPayload, ResourceLoader},
     options::{
@@ -26,6 +27,9 @@ impl ImportOptions for SoundBufferImportOptions {}
 pub struct SoundBufferLoader {
     /// Default import options for sound buffer resources.
     pub default_import_options: SoundBufferImportOptions,
+    /// A set of import hooks, that will be applied to the import options of every sound buffer
+    /// before loading.
+    pub import_hooks: ImportHookContainer,
 }
 
 impl ResourceLoader for SoundBufferLoader {
@@ -39,14 +43,17 @@ impl ResourceLoader for SoundBufferLoader {
 
     fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
         let default_import_options = self.default_import_options.clone();
+        let import_hooks = self.import_hooks.clone();
 
         Box::pin(async move {
             let io = io.as_ref();
 
-            let import_options = try_get_import_settings(&path, io)
+            let mut import_options = try_get_import_settings(&path, io)
                 .await
                 .unwrap_or(default_import_options);
 
+            import_hooks.before_import(&path, &mut import_options);
+
             let source = DataSource::from_file(&path, io)
                 .await
                 .map_err(LoadError::new)?;
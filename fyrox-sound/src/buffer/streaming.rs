@@ -22,6 +22,12 @@
 //! }
 //! ```
 //!
+//! # Loop points
+//!
+//! Music tracks often have an intro that must be played only once, and a part that must be repeated
+//! while the track is playing. Use [`StreamingBuffer::set_loop_points`] to define such part, it will be
+//! used by looping sources instead of the whole sound.
+//!
 //! # Notes
 //!
 //! Streaming buffer cannot be shared across multiple source. On attempt to create a source with a streaming
@@ -38,6 +44,29 @@ use std::{
     time::Duration,
 };
 
+/// A part of a sound, that is repeated by looping sources. Positions are defined in samples per
+/// channel, which allows to make seamless loops.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+pub struct LoopPoints {
+    /// Position (in samples per channel) to which the playback jumps, when it reaches the end of the
+    /// loop.
+    pub start: usize,
+    /// Position (in samples per channel) at which the playback jumps back to the start of the loop.
+    /// `None` means the end of the sound.
+    pub end: Option<usize>,
+}
+
+impl LoopPoints {
+    /// Creates new loop points from the given time positions.
+    pub fn from_time(start: Duration, end: Option<Duration>, sample_rate: usize) -> Self {
+        let to_samples = |time: Duration| (time.as_secs_f64() * sample_rate as f64) as usize;
+        Self {
+            start: to_samples(start),
+            end: end.map(to_samples),
+        }
+    }
+}
+
 /// Streaming buffer for long sounds. Does not support random access.
 #[derive(Debug, Default, Visit, Reflect)]
 pub struct StreamingBuffer {
@@ -50,6 +79,18 @@ pub struct StreamingBuffer {
     #[visit(skip)]
     #[reflect(hidden)]
     streaming_source: StreamingSource,
+    #[visit(skip)]
+    #[reflect(setter = "set_loop_points")]
+    loop_points: Option<LoopPoints>,
+    // Position (in samples per channel) of the next sample, that will be read from the source.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    position: usize,
+    // Pairs of `(index in block, position in sound)` of contiguous parts of the current block.
+    // There could be more than one part, if the block contains the end of the loop.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    block_parts: Vec<(usize, usize)>,
 }
 
 #[derive(Debug)]
@@ -120,33 +161,20 @@ impl StreamingSource {
         }
     }
 
+    // Appends at most `count` samples per channel to the buffer and returns the amount of samples
+    // per channel that were actually read.
     #[inline]
-    fn read_next_samples_block_into(&mut self, buffer: &mut Vec<f32>) -> usize {
-        buffer.clear();
-        let count = StreamingBuffer::STREAM_SAMPLE_COUNT * self.channel_count();
+    fn read_into(&mut self, buffer: &mut Vec<f32>, count: usize) -> usize {
+        let channel_count = self.channel_count();
+        let begin = buffer.len();
+        let count = count * channel_count;
         match self {
-            StreamingSource::Decoder(decoder) => {
-                for _ in 0..count {
-                    if let Some(sample) = decoder.next() {
-                        buffer.push(sample)
-                    } else {
-                        break;
-                    }
-                }
-            }
-            StreamingSource::Raw(raw_streaming) => {
-                for _ in 0..count {
-                    if let Some(sample) = raw_streaming.next() {
-                        buffer.push(sample)
-                    } else {
-                        break;
-                    }
-                }
-            }
+            StreamingSource::Decoder(decoder) => buffer.extend(decoder.take(count)),
+            StreamingSource::Raw(raw_streaming) => buffer.extend(raw_streaming.take(count)),
             StreamingSource::Null => (),
         }
 
-        buffer.len()
+        (buffer.len() - begin) / channel_count.max(1)
     }
 }
 
@@ -163,38 +191,111 @@ impl StreamingBuffer {
     /// This function will return Err if data source is `Raw`. It makes no sense to stream raw data which
     /// is already loaded into memory. Use Generic source instead!
     pub fn new(source: DataSource) -> Result<Self, DataSource> {
-        let mut streaming_source = StreamingSource::new(source)?;
+        let streaming_source = StreamingSource::new(source)?;
 
-        let mut samples = Vec::new();
-        let channel_count = streaming_source.channel_count();
-        streaming_source.read_next_samples_block_into(&mut samples);
-        debug_assert_eq!(samples.len() % channel_count, 0);
-
-        Ok(Self {
+        let mut buffer = Self {
             generic: GenericBuffer {
-                samples,
+                samples: Default::default(),
                 sample_rate: streaming_source.sample_rate(),
                 channel_count: streaming_source.channel_count(),
                 channel_duration_in_samples: streaming_source.channel_duration_in_samples(),
             },
             use_count: 0,
             streaming_source,
-        })
+            loop_points: None,
+            position: 0,
+            block_parts: Default::default(),
+        };
+
+        buffer.read_next_block(false);
+
+        Ok(buffer)
     }
 
-    #[inline]
-    pub(crate) fn read_next_block(&mut self) {
-        self.streaming_source
-            .read_next_samples_block_into(&mut self.generic.samples);
+    /// Sets new loop points of the buffer. Looping sources will repeat the part of the sound between
+    /// the loop points instead of the whole sound, the part before the start of the loop will be
+    /// played only once. The new loop points will be used starting from the next block of the buffer.
+    /// `None` means that the whole sound will be repeated.
+    pub fn set_loop_points(&mut self, loop_points: Option<LoopPoints>) -> Option<LoopPoints> {
+        std::mem::replace(&mut self.loop_points, loop_points)
+    }
+
+    /// Returns current loop points of the buffer.
+    pub fn loop_points(&self) -> Option<LoopPoints> {
+        self.loop_points
+    }
+
+    /// Fills the buffer with the next block of samples. If `looping` is set and the buffer has loop
+    /// points, the block will be filled with the samples from the start of the loop when the end of
+    /// the loop is reached, so the block is always full.
+    pub(crate) fn read_next_block(&mut self, looping: bool) {
+        let loop_points = if looping { self.loop_points } else { None };
+
+        self.generic.samples.clear();
+        self.block_parts.clear();
+        self.block_parts.push((0, self.position));
+
+        let mut count = 0;
+        let mut rewound = false;
+        while count < Self::STREAM_SAMPLE_COUNT {
+            let mut max_count = Self::STREAM_SAMPLE_COUNT - count;
+            if let Some(end) = loop_points.and_then(|loop_points| loop_points.end) {
+                max_count = max_count.min(end.saturating_sub(self.position));
+            }
+
+            let read = self
+                .streaming_source
+                .read_into(&mut self.generic.samples, max_count);
+            count += read;
+            self.position += read;
+
+            let Some(loop_points) = loop_points else {
+                break;
+            };
+            if count == Self::STREAM_SAMPLE_COUNT || (rewound && read == 0) {
+                // Prevent infinite loop on empty loop regions.
+                break;
+            }
+
+            // End of the loop (or the end of the sound) is reached, continue from the start of the
+            // loop. Half-sample offset prevents the seek from landing on the previous sample because
+            // of rounding errors.
+            self.time_seek(Duration::from_secs_f64(
+                (loop_points.start as f64 + 0.5) / self.generic.sample_rate.max(1) as f64,
+            ));
+            self.position = loop_points.start;
+            self.block_parts.push((count, self.position));
+            rewound = true;
+        }
+
+        debug_assert_eq!(
+            self.generic.samples.len() % self.generic.channel_count.max(1),
+            0
+        );
+    }
+
+    /// Returns the position (in samples per channel) in the sound, that corresponds to the given
+    /// position in the current block.
+    pub(crate) fn sound_position(&self, block_position: f64) -> f64 {
+        let block_position = block_position.max(0.0);
+        self.block_parts
+            .iter()
+            .rev()
+            .find(|(index, _)| *index as f64 <= block_position)
+            .map_or(block_position, |(index, position)| {
+                *position as f64 + block_position - *index as f64
+            })
     }
 
     #[inline]
     pub(crate) fn rewind(&mut self) -> Result<(), SoundError> {
+        self.position = 0;
         self.streaming_source.rewind()
     }
 
     #[inline]
     pub(crate) fn time_seek(&mut self, location: Duration) {
+        self.position = (location.as_secs_f64() * self.generic.sample_rate as f64) as usize;
         self.streaming_source.time_seek(location);
     }
 }
@@ -215,3 +316,96 @@ impl DerefMut for StreamingBuffer {
         &mut self.generic
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::buffer::{
+        streaming::{LoopPoints, StreamingBuffer},
+        DataSource, RawStreamingDataSource,
+    };
+    use std::time::Duration;
+
+    // Mono ramp, each sample is equal to its index.
+    #[derive(Debug)]
+    struct Ramp {
+        position: usize,
+        len: usize,
+    }
+
+    impl Iterator for Ramp {
+        type Item = f32;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            (self.position < self.len).then(|| {
+                self.position += 1;
+                (self.position - 1) as f32
+            })
+        }
+    }
+
+    impl RawStreamingDataSource for Ramp {
+        fn sample_rate(&self) -> usize {
+            10
+        }
+
+        fn channel_count(&self) -> usize {
+            1
+        }
+
+        fn rewind(&mut self) -> Result<(), crate::error::SoundError> {
+            self.position = 0;
+            Ok(())
+        }
+
+        fn time_seek(&mut self, duration: Duration) {
+            self.position = (duration.as_secs_f64() * 10.0) as usize;
+        }
+
+        fn channel_duration_in_samples(&self) -> usize {
+            self.len
+        }
+    }
+
+    #[test]
+    fn test_loop_points() {
+        let mut buffer = StreamingBuffer::new(DataSource::RawStreaming(Box::new(Ramp {
+            position: 0,
+            len: 10,
+        })))
+        .unwrap();
+        assert_eq!(
+            buffer.samples(),
+            &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]
+        );
+
+        buffer.set_loop_points(Some(LoopPoints {
+            start: 2,
+            end: Some(5),
+        }));
+
+        // Loop points are ignored by non-looping sources.
+        buffer.rewind().unwrap();
+        buffer.read_next_block(false);
+        assert_eq!(buffer.samples().len(), 10);
+
+        buffer.rewind().unwrap();
+        buffer.read_next_block(true);
+        assert_eq!(buffer.samples().len(), StreamingBuffer::STREAM_SAMPLE_COUNT);
+        assert_eq!(
+            &buffer.samples()[..11],
+            &[0.0, 1.0, 2.0, 3.0, 4.0, 2.0, 3.0, 4.0, 2.0, 3.0, 4.0]
+        );
+        assert_eq!(buffer.sound_position(4.0), 4.0);
+        assert_eq!(buffer.sound_position(5.5), 2.5);
+        assert_eq!(buffer.sound_position(10.0), 4.0);
+
+        // Empty loop must not hang.
+        buffer.set_loop_points(Some(LoopPoints {
+            start: 20,
+            end: None,
+        }));
+        buffer.rewind().unwrap();
+        buffer.read_next_block(true);
+        assert_eq!(buffer.samples().len(), 10);
+    }
+}
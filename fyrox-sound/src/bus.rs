@@ -1,10 +1,14 @@
 //! Everything related to audio buses and audio bus graphs. See docs of [`AudioBus`] and [`AudioBusGraph`]
 //! for more info and examples
 
-use crate::effects::{Effect, EffectRenderTrait};
+use crate::{
+    context::SAMPLE_RATE,
+    effects::{Effect, EffectRenderTrait},
+};
 use fyrox_core::{
     pool::{Handle, Pool, Ticket},
     reflect::prelude::*,
+    uuid_provider,
     visitor::prelude::*,
 };
 use std::fmt::{Debug, Formatter};
@@ -76,6 +80,71 @@ impl PingPongBuffer {
     }
 }
 
+/// Ducking settings of an audio bus. Ducking automatically lowers the gain of an audio bus while
+/// some other audio bus (trigger bus) plays something loud enough. It is typically used to make
+/// dialogues clearly audible by lowering the volume of music and sound effects while characters
+/// speak.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox_sound::bus::{AudioBus, Ducking};
+///
+/// let mut music = AudioBus::new("Music".to_string());
+/// music.set_ducking(Some(Ducking {
+///     trigger_bus: "Voice".to_string(),
+///     gain: 0.25,
+///     ..Default::default()
+/// }));
+/// ```
+#[derive(Debug, Reflect, Visit, Clone, PartialEq)]
+pub struct Ducking {
+    /// Name of the audio bus, whose signal triggers ducking. Only the signal of sound sources that
+    /// are bound directly to the trigger bus is taken into account.
+    pub trigger_bus: String,
+    /// Peak amplitude of the signal of the trigger bus, above which the ducking is activated.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub threshold: f32,
+    /// Gain multiplier of the ducked bus when the ducking is fully active.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub gain: f32,
+    /// Time (in seconds) in which the gain (mostly) reaches the ducked value.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub attack_time: f32,
+    /// Time (in seconds) in which the gain (mostly) restores after the trigger bus went silent.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub release_time: f32,
+}
+
+uuid_provider!(Ducking = "157c278e-cb50-4c3b-bdaa-468f86786668");
+
+impl Default for Ducking {
+    fn default() -> Self {
+        Self {
+            trigger_bus: Default::default(),
+            threshold: 0.01,
+            gain: 0.3,
+            attack_time: 0.05,
+            release_time: 0.5,
+        }
+    }
+}
+
+impl Ducking {
+    fn update(&self, current_gain: f32, trigger_level: f32, dt: f32) -> f32 {
+        let (target, time) = if trigger_level > self.threshold {
+            (self.gain, self.attack_time)
+        } else {
+            (1.0, self.release_time)
+        };
+        if time > 0.0 {
+            current_gain + (target - current_gain) * (1.0 - (-dt / time).exp())
+        } else {
+            target
+        }
+    }
+}
+
 /// Audio bus is a top-level audio processing unit. It takes data from multiple audio sources and passes their
 /// samples through a chain of effects. Output signal is then can be either sent to an audio playback device or
 /// to some other audio bus and be processed again, but with different sound effects (this can be done via
@@ -86,6 +155,17 @@ pub struct AudioBus {
     effects: Vec<Effect>,
     gain: f32,
 
+    #[visit(optional)]
+    ducking: Option<Ducking>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    ducking_gain: f32,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    level: f32,
+
     #[reflect(hidden)]
     child_buses: Vec<Handle<AudioBus>>,
 
@@ -104,6 +184,9 @@ impl Default for AudioBus {
             child_buses: Default::default(),
             effects: Default::default(),
             gain: 1.0,
+            ducking: None,
+            ducking_gain: 1.0,
+            level: 0.0,
             ping_pong_buffer: Default::default(),
             parent_bus: Default::default(),
         }
@@ -152,6 +235,32 @@ impl AudioBus {
         self.gain
     }
 
+    /// Sets new ducking settings of the audio bus. `None` disables ducking. See [`Ducking`] docs
+    /// for more info.
+    pub fn set_ducking(&mut self, ducking: Option<Ducking>) {
+        self.ducking = ducking;
+        if self.ducking.is_none() {
+            self.ducking_gain = 1.0;
+        }
+    }
+
+    /// Returns current ducking settings of the audio bus.
+    pub fn ducking(&self) -> Option<&Ducking> {
+        self.ducking.as_ref()
+    }
+
+    /// Returns current gain multiplier, that is applied by ducking. It is `1.0` when the bus is
+    /// not ducked.
+    pub fn ducking_gain(&self) -> f32 {
+        self.ducking_gain
+    }
+
+    /// Returns peak amplitude of the signal of the sound sources, that are bound directly to the
+    /// audio bus, after the effects were applied. It is measured over the last rendered buffer.
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
     pub(crate) fn input_buffer(&mut self) -> &mut [(f32, f32)] {
         self.ping_pong_buffer.input_mut()
     }
@@ -171,6 +280,14 @@ impl AudioBus {
             effect.render(input, output);
             self.ping_pong_buffer.swap();
         }
+
+        self.level = self
+            .ping_pong_buffer
+            .input_ref()
+            .iter()
+            .fold(0.0, |level, (left, right)| {
+                level.max(left.abs()).max(right.abs())
+            });
     }
 
     /// Adds new effect to the effects chain.
@@ -460,41 +577,57 @@ impl AudioBusGraph {
     }
 
     pub(crate) fn end_render(&mut self, output_device_buffer: &mut [(f32, f32)]) {
-        let mut leafs = Vec::new();
-        for (handle, bus) in self.buses.pair_iter_mut() {
+        for bus in self.buses.iter_mut() {
             bus.apply_effects();
+        }
 
-            if bus.child_buses.is_empty() {
-                leafs.push(handle);
+        let dt = output_device_buffer.len() as f32 / SAMPLE_RATE as f32;
+        let levels = self
+            .buses
+            .iter()
+            .map(|bus| (bus.name.clone(), bus.level))
+            .collect::<Vec<_>>();
+        for bus in self.buses.iter_mut() {
+            if let Some(ducking) = bus.ducking.as_ref() {
+                let trigger_level = levels
+                    .iter()
+                    .find_map(|(name, level)| (*name == ducking.trigger_bus).then_some(*level))
+                    .unwrap_or_default();
+                bus.ducking_gain = ducking.update(bus.ducking_gain, trigger_level, dt);
             }
         }
 
-        for mut leaf in leafs {
-            while leaf.is_some() {
-                let mut ctx = self.buses.begin_multi_borrow::<2>();
-
-                let leaf_ref = ctx.try_get(leaf).expect("Malformed bus graph!");
-
-                let input_buffer = leaf_ref.ping_pong_buffer.input_ref();
-                let leaf_gain = leaf_ref.gain;
-                let output_buffer = if leaf_ref.parent_bus.is_none() {
-                    // Special case for the root bus - it writes directly to the output device buffer.
-                    &mut *output_device_buffer
-                } else {
-                    ctx.try_get(leaf_ref.parent_bus)
-                        .expect("Malformed bus graph!")
-                        .ping_pong_buffer
-                        .input_mut()
-                };
-
-                for ((input_left, input_right), (output_left, output_right)) in
-                    input_buffer.iter().zip(output_buffer)
-                {
-                    *output_left += *input_left * leaf_gain;
-                    *output_right += *input_right * leaf_gain;
-                }
-
-                leaf = leaf_ref.parent_bus;
+        // Every bus must be mixed into its parent exactly once and only after all its children were
+        // mixed into it, reversed depth-first order guarantees that.
+        let mut order = Vec::with_capacity(self.buses.alive_count() as usize);
+        let mut stack = vec![self.root];
+        while let Some(handle) = stack.pop() {
+            order.push(handle);
+            stack.extend_from_slice(&self.buses[handle].child_buses);
+        }
+
+        for handle in order.into_iter().rev() {
+            let mut ctx = self.buses.begin_multi_borrow::<2>();
+
+            let bus_ref = ctx.try_get(handle).expect("Malformed bus graph!");
+
+            let input_buffer = bus_ref.ping_pong_buffer.input_ref();
+            let gain = bus_ref.gain * bus_ref.ducking_gain;
+            let output_buffer = if bus_ref.parent_bus.is_none() {
+                // Special case for the root bus - it writes directly to the output device buffer.
+                &mut *output_device_buffer
+            } else {
+                ctx.try_get(bus_ref.parent_bus)
+                    .expect("Malformed bus graph!")
+                    .ping_pong_buffer
+                    .input_mut()
+            };
+
+            for ((input_left, input_right), (output_left, output_right)) in
+                input_buffer.iter().zip(output_buffer)
+            {
+                *output_left += *input_left * gain;
+                *output_right += *input_right * gain;
             }
         }
     }
@@ -503,7 +636,7 @@ impl AudioBusGraph {
 #[cfg(test)]
 mod test {
     use crate::{
-        bus::{AudioBus, AudioBusGraph},
+        bus::{AudioBus, AudioBusGraph, Ducking},
         effects::{Attenuate, Effect},
     };
 
@@ -586,4 +719,39 @@ mod test {
 
         assert_eq!(output_buffer[0], (0.75, 0.75));
     }
+
+    #[test]
+    fn test_bus_ducking() {
+        let mut output_buffer = [(0.0f32, 0.0f32)];
+
+        let mut graph = AudioBusGraph::new();
+
+        let mut music = AudioBus::new("Music".to_string());
+        music.set_ducking(Some(Ducking {
+            trigger_bus: "Voice".to_string(),
+            gain: 0.5,
+            attack_time: 0.0,
+            release_time: 0.0,
+            ..Default::default()
+        }));
+        let music = graph.add_bus(music, graph.root);
+        let voice = graph.add_bus(AudioBus::new("Voice".to_string()), graph.root);
+
+        graph.begin_render(output_buffer.len());
+        graph.buses[music].input_buffer()[0] = (1.0, 1.0);
+        graph.buses[voice].input_buffer()[0] = (1.0, 1.0);
+        graph.end_render(&mut output_buffer);
+
+        assert_eq!(graph.buses[music].ducking_gain(), 0.5);
+        assert_eq!(output_buffer[0], (1.5, 1.5));
+
+        // Voice went silent, the ducking must be released.
+        let mut output_buffer = [(0.0f32, 0.0f32)];
+        graph.begin_render(output_buffer.len());
+        graph.buses[music].input_buffer()[0] = (1.0, 1.0);
+        graph.end_render(&mut output_buffer);
+
+        assert_eq!(graph.buses[music].ducking_gain(), 1.0);
+        assert_eq!(output_buffer[0], (1.0, 1.0));
+    }
 }
@@ -33,6 +33,9 @@ use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 /// TODO: Make this configurable, for now its set to most commonly used sample rate of 44100 Hz.
 pub const SAMPLE_RATE: u32 = 44100;
 
+/// Speed of sound in the air (in meters per second), that is used to calculate the Doppler effect.
+pub const SPEED_OF_SOUND: f32 = 343.3;
+
 /// Distance model defines how volume of sound will decay when distance to listener changes.
 #[derive(
     Copy, Clone, Debug, Eq, PartialEq, Reflect, Visit, AsRefStr, EnumString, EnumVariantNames,
@@ -275,6 +278,7 @@ impl State {
             {
                 if let Some(bus_input_buffer) = self.bus_graph.try_get_bus_input_buffer(&source.bus)
                 {
+                    source.doppler_shift = source.calculate_doppler_shift(&self.listener);
                     source.render(output_device_buffer.len());
 
                     match self.renderer {
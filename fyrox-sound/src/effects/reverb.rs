@@ -139,6 +139,8 @@ pub struct Reverb {
     decay_time: f32,
     #[reflect(setter = "set_fc", min_value = 0.0, max_value = 1.0)]
     fc: f32,
+    #[reflect(setter = "set_level", min_value = 0.0, max_value = 1.0)]
+    level: f32,
     #[reflect(hidden)]
     left: ChannelReverb,
     #[reflect(hidden)]
@@ -153,6 +155,8 @@ impl Visit for Reverb {
         self.wet.visit("Wet", &mut region)?;
         self.decay_time.visit("DecayTime", &mut region)?;
         self.fc.visit("Fc", &mut region)?;
+        // Backward compatibility.
+        let _ = self.level.visit("Level", &mut region);
 
         if region.is_reading() {
             self.left = ChannelReverb::new(0, self.fc, Reverb::FEEDBACK, self.decay_time);
@@ -194,6 +198,7 @@ impl Reverb {
             wet: 1.0,
             decay_time: 2.0,
             fc,
+            level: 1.0,
             left: ChannelReverb::new(0, fc, Reverb::FEEDBACK, decay_time),
             right: ChannelReverb::new(23, fc, Reverb::FEEDBACK, decay_time),
         }
@@ -224,6 +229,17 @@ impl Reverb {
         self.wet
     }
 
+    /// Sets the level of the reverberated signal in `[0; 1]` range. It does not affect dry part of
+    /// the signal, so `0.0` effectively disables the reverberation. Default value is 1.0.
+    pub fn set_level(&mut self, level: f32) {
+        self.level = level.clamp(0.0, 1.0);
+    }
+
+    /// Returns the level of the reverberated signal.
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
     /// Sets actual sample rate of effect. It was designed to 44100 Hz sampling rate.
     /// TODO: This shouldn't be in public API.
     pub fn set_sample_rate(&mut self, sample_rate: usize) {
@@ -279,8 +295,10 @@ impl EffectRenderTrait for Reverb {
             let processed_left = self.left.feed(input);
             let processed_right = self.right.feed(input);
 
-            *out_left = processed_left * wet + processed_right * dry + self.dry * left;
-            *out_right = processed_right * wet + processed_left * dry + self.dry * right;
+            *out_left =
+                (processed_left * wet + processed_right * dry) * self.level + self.dry * left;
+            *out_right =
+                (processed_right * wet + processed_left * dry) * self.level + self.dry * right;
         }
     }
 }
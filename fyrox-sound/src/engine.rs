@@ -46,7 +46,8 @@ impl SoundEngine {
         })))
     }
 
-    /// Tries to initialize default audio output device.
+    /// Tries to initialize default audio output device. On Android the device uses AAudio, it must be
+    /// destroyed when the app is suspended and re-initialized when the app is resumed.
     pub fn initialize_audio_output_device(&self) -> Result<(), Box<dyn Error>> {
         let state = self.clone();
 
@@ -31,7 +31,8 @@
 use crate::{
     buffer::{streaming::StreamingBuffer, SoundBuffer, SoundBufferResource},
     bus::AudioBusGraph,
-    context::DistanceModel,
+    context::{DistanceModel, SAMPLE_RATE, SPEED_OF_SOUND},
+    dsp::filters::OnePole,
     error::SoundError,
     listener::Listener,
 };
@@ -61,6 +62,54 @@ pub enum Status {
 
 uuid_provider!(Status = "1980bded-86cd-4eff-a5db-bab729bdb3ad");
 
+/// Occlusion settings of a sound source. Occlusion makes a sound source quieter and muffled when
+/// there is an obstacle between the source and the listener. The sound engine itself knows nothing
+/// about obstacles, so the amount of occlusion must be provided by the user (or by the engine that
+/// uses the sound engine) using [`SoundSource::set_occlusion_factor`].
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox_sound::source::{Occlusion, SoundSource};
+///
+/// fn set_blocked(source: &mut SoundSource, blocked: bool) {
+///     if source.occlusion().is_none() {
+///         source.set_occlusion(Some(Occlusion {
+///             gain: 0.3,
+///             cutoff_frequency: 600.0,
+///             ..Default::default()
+///         }));
+///     }
+///     source.set_occlusion_factor(if blocked { 1.0 } else { 0.0 });
+/// }
+/// ```
+#[derive(Debug, Reflect, Visit, Clone, PartialEq)]
+pub struct Occlusion {
+    /// Gain multiplier of the sound source when it is fully occluded.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub gain: f32,
+    /// Cutoff frequency (in Hz) of the lowpass filter, that is applied to the sound when the source is
+    /// fully occluded.
+    #[reflect(min_value = 0.0, step = 10.0)]
+    pub cutoff_frequency: f32,
+    /// Time (in seconds) in which the occlusion (mostly) reaches its new value. It prevents clicks when
+    /// an obstacle suddenly appears between the source and the listener.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub transition_time: f32,
+}
+
+uuid_provider!(Occlusion = "ff1876d9-0f19-4ac4-ae97-3f6c8d705c3c");
+
+impl Default for Occlusion {
+    fn default() -> Self {
+        Self {
+            gain: 0.5,
+            cutoff_frequency: 1000.0,
+            transition_time: 0.1,
+        }
+    }
+}
+
 /// See module info.
 #[derive(Debug, Clone, Reflect, Visit)]
 pub struct SoundSource {
@@ -138,6 +187,33 @@ pub struct SoundSource {
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) prev_distance_gain: Option<f32>,
+    #[visit(optional)]
+    velocity: Vector3<f32>,
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.05)]
+    doppler_factor: f32,
+    // Pitch multiplier caused by the Doppler effect, it is calculated on each render.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) doppler_shift: f64,
+    // Playback speed multiplier that comes from the time dilation of the owner (hit-stop,
+    // bullet-time, etc.). It is not a part of the sound settings, so it is not serialized.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    time_scale: f64,
+    #[visit(optional)]
+    occlusion: Option<Occlusion>,
+    // Desired amount of occlusion, set by the user.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occlusion_factor: f32,
+    // Actual amount of occlusion, that smoothly follows the desired one.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    current_occlusion: f32,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occlusion_filters: [OnePole; 2],
 }
 
 impl Default for SoundSource {
@@ -168,6 +244,14 @@ impl Default for SoundSource {
             prev_right_samples: Default::default(),
             prev_sampling_vector: Vector3::new(0.0, 0.0, 1.0),
             prev_distance_gain: None,
+            velocity: Default::default(),
+            doppler_factor: 1.0,
+            doppler_shift: 1.0,
+            time_scale: 1.0,
+            occlusion: None,
+            occlusion_factor: 0.0,
+            current_occlusion: 0.0,
+            occlusion_filters: Default::default(),
         }
     }
 }
@@ -340,6 +424,7 @@ impl SoundSource {
         if let Some(buffer) = self.buffer.as_ref() {
             if let Some(SoundBuffer::Streaming(streaming)) = buffer.state().data() {
                 streaming.rewind()?;
+                streaming.read_next_block(self.looping);
             }
         }
 
@@ -394,6 +479,77 @@ impl SoundSource {
         self.max_distance
     }
 
+    /// Sets velocity of the source in world space (in meters per second). It is used to calculate the
+    /// Doppler effect, the position of the source is **not** changed by the velocity.
+    pub fn set_velocity(&mut self, velocity: Vector3<f32>) -> &mut Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// Returns velocity of the source.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
+    /// Sets the strength of the Doppler effect for the source. `0.0` disables the effect, `1.0` gives
+    /// physically correct pitch shift, larger values exaggerate it. Default is `1.0`.
+    pub fn set_doppler_factor(&mut self, doppler_factor: f32) -> &mut Self {
+        self.doppler_factor = doppler_factor.max(0.0);
+        self
+    }
+
+    /// Returns the strength of the Doppler effect for the source.
+    pub fn doppler_factor(&self) -> f32 {
+        self.doppler_factor
+    }
+
+    /// Sets time scale of the source. It works similar to the pitch, but it is meant to be controlled
+    /// by the time dilation of the game world (slow motion, hit-stop, etc.) and not by the sound
+    /// settings. Zero value pauses the playback and makes the source silent. Default is `1.0`.
+    pub fn set_time_scale(&mut self, time_scale: f64) -> &mut Self {
+        self.time_scale = time_scale.max(0.0);
+        self
+    }
+
+    /// Returns time scale of the source.
+    pub fn time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Sets new occlusion settings of the source. `None` disables occlusion. See [`Occlusion`] docs
+    /// for more info.
+    pub fn set_occlusion(&mut self, occlusion: Option<Occlusion>) -> &mut Self {
+        if let Some(occlusion) = occlusion.as_ref() {
+            let fc = occlusion.cutoff_frequency / SAMPLE_RATE as f32;
+            for filter in self.occlusion_filters.iter_mut() {
+                filter.set_fc(fc);
+            }
+        } else {
+            self.current_occlusion = 0.0;
+        }
+        self.occlusion = occlusion;
+        self
+    }
+
+    /// Returns current occlusion settings of the source.
+    pub fn occlusion(&self) -> Option<&Occlusion> {
+        self.occlusion.as_ref()
+    }
+
+    /// Sets desired amount of occlusion in `[0; 1]` range, where `0.0` means that nothing blocks the
+    /// sound and `1.0` means that the sound is fully blocked. Has no effect if occlusion is disabled
+    /// (see [`Self::set_occlusion`]). The actual amount of occlusion follows the desired one smoothly,
+    /// see [`Occlusion::transition_time`].
+    pub fn set_occlusion_factor(&mut self, occlusion_factor: f32) -> &mut Self {
+        self.occlusion_factor = occlusion_factor.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns desired amount of occlusion.
+    pub fn occlusion_factor(&self) -> f32 {
+        self.occlusion_factor
+    }
+
     /// Sets new name of the target audio bus. The name must be valid, otherwise the sound won't play!
     /// Default is [`AudioBusGraph::PRIMARY_BUS`].
     pub fn set_bus<S: AsRef<str>>(&mut self, bus: S) {
@@ -429,6 +585,35 @@ impl SoundSource {
         }
     }
 
+    // Doppler shift formula was taken from OpenAL Specification as well, relative speeds are
+    // clamped to prevent extreme pitch values when the source moves at nearly sound speed.
+    pub(crate) fn calculate_doppler_shift(&self, listener: &Listener) -> f64 {
+        if self.doppler_factor <= 0.0 || self.spatial_blend <= 0.0 {
+            return 1.0;
+        }
+
+        let Some(source_to_listener) =
+            (listener.position() - self.position).try_normalize(f32::EPSILON)
+        else {
+            return 1.0;
+        };
+
+        let max_speed = 0.5 * SPEED_OF_SOUND / self.doppler_factor;
+        let listener_speed = listener
+            .velocity()
+            .dot(&source_to_listener)
+            .clamp(-max_speed, max_speed);
+        let source_speed = self
+            .velocity
+            .dot(&source_to_listener)
+            .clamp(-max_speed, max_speed);
+
+        let shift = (SPEED_OF_SOUND - self.doppler_factor * listener_speed)
+            / (SPEED_OF_SOUND - self.doppler_factor * source_speed);
+
+        1.0 + (shift as f64 - 1.0) * self.spatial_blend as f64
+    }
+
     pub(crate) fn calculate_panning(&self, listener: &Listener) -> f32 {
         (listener.position() - self.position)
             .try_normalize(f32::EPSILON)
@@ -469,15 +654,15 @@ impl SoundSource {
                 }
                 // Set absolute position first.
                 self.playback_pos = (time.as_secs_f64() * buffer.sample_rate as f64)
-                    .clamp(0.0, buffer.duration().as_secs_f64());
+                    .clamp(0.0, buffer.channel_duration_in_samples as f64);
                 // Then adjust buffer read position.
                 self.buf_read_pos = match *buffer {
                     SoundBuffer::Streaming(ref mut streaming) => {
-                        // Make sure to load correct data into buffer from decoder.
-                        streaming.read_next_block();
-                        // Streaming sources has different buffer read position because
-                        // buffer contains only small portion of data.
-                        self.playback_pos % (StreamingBuffer::STREAM_SAMPLE_COUNT as f64)
+                        // Make sure to load correct data into buffer from decoder. The block
+                        // starts exactly at the new position, so the read position is at its
+                        // beginning.
+                        streaming.read_next_block(self.looping);
+                        0.0
                     }
                     SoundBuffer::Generic(_) => self.playback_pos,
                 };
@@ -499,13 +684,68 @@ impl SoundSource {
         if let Some(buffer) = self.buffer.clone() {
             let mut state = buffer.state();
             if let Some(buffer) = state.data() {
-                if self.status == Status::Playing && !buffer.is_empty() {
+                if self.status == Status::Playing && self.time_scale > 0.0 && !buffer.is_empty() {
+                    self.sync_with_buffer(buffer);
                     self.render_playing(buffer, amount);
+                    if let SoundBuffer::Streaming(streaming) = buffer {
+                        // The block could contain the end of the loop, so the position must be
+                        // fetched from the buffer.
+                        self.playback_pos = streaming.sound_position(self.buf_read_pos);
+                    }
                 }
             }
         }
         // Fill the remaining part of frame_samples.
         self.frame_samples.resize(amount, (0.0, 0.0));
+
+        self.apply_occlusion();
+    }
+
+    // The buffer could be reloaded (for example, when it was changed on disk) and its length and sample
+    // rate could differ from the previous ones.
+    fn sync_with_buffer(&mut self, buffer: &SoundBuffer) {
+        let device_sample_rate = f64::from(crate::context::SAMPLE_RATE);
+        self.resampling_multiplier = buffer.sample_rate() as f64 / device_sample_rate;
+
+        let buffer_len = buffer.samples().len() / buffer.channel_count();
+        if self.buf_read_pos > buffer_len as f64 {
+            self.buf_read_pos = 0.0;
+            self.playback_pos = 0.0;
+        }
+    }
+
+    fn apply_occlusion(&mut self) {
+        let Some(occlusion) = self.occlusion.as_ref() else {
+            return;
+        };
+
+        let prev_occlusion = self.current_occlusion;
+        let dt = self.frame_samples.len() as f32 / SAMPLE_RATE as f32;
+        let time = occlusion.transition_time;
+        self.current_occlusion = if time > 0.0 {
+            prev_occlusion + (self.occlusion_factor - prev_occlusion) * (1.0 - (-dt / time).exp())
+        } else {
+            self.occlusion_factor
+        };
+
+        if prev_occlusion == 0.0 && self.current_occlusion == 0.0 {
+            return;
+        }
+
+        // Interpolate the amount of occlusion across the frame to prevent clicks and crossfade
+        // between the dry and the filtered signal.
+        let step =
+            (self.current_occlusion - prev_occlusion) / self.frame_samples.len().max(1) as f32;
+        let mut k = prev_occlusion;
+        let [left_filter, right_filter] = &mut self.occlusion_filters;
+        for (left, right) in self.frame_samples.iter_mut() {
+            k += step;
+            let gain = 1.0 + (occlusion.gain - 1.0) * k;
+            let filtered_left = left_filter.feed(*left);
+            let filtered_right = right_filter.feed(*right);
+            *left = (*left + (filtered_left - *left) * k) * gain;
+            *right = (*right + (filtered_right - *right) * k) * gain;
+        }
     }
 
     fn render_playing(&mut self, buffer: &mut SoundBuffer, amount: usize) {
@@ -527,7 +767,7 @@ impl SoundSource {
                     end_reached = false;
                 }
                 self.prev_buffer_sample = get_last_sample(streaming);
-                streaming.read_next_block();
+                streaming.read_next_block(self.looping);
             }
             if end_reached {
                 self.buf_read_pos = 0.0;
@@ -545,7 +785,7 @@ impl SoundSource {
     // Renders until the end of the block or until amount samples is written and returns
     // the number of written samples.
     fn render_until_block_end(&mut self, buffer: &mut SoundBuffer, mut amount: usize) -> usize {
-        let step = self.pitch * self.resampling_multiplier;
+        let step = self.pitch * self.resampling_multiplier * self.doppler_shift * self.time_scale;
         if step == 1.0 {
             if self.buf_read_pos < 0.0 {
                 // This can theoretically happen if we change pitch on the fly.
@@ -716,6 +956,9 @@ pub struct SoundSourceBuilder {
     rolloff_factor: f32,
     spatial_blend: f32,
     bus: String,
+    velocity: Vector3<f32>,
+    doppler_factor: f32,
+    occlusion: Option<Occlusion>,
 }
 
 impl Default for SoundSourceBuilder {
@@ -743,6 +986,9 @@ impl SoundSourceBuilder {
             rolloff_factor: 1.0,
             spatial_blend: 1.0,
             bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+            velocity: Vector3::new(0.0, 0.0, 0.0),
+            doppler_factor: 1.0,
+            occlusion: None,
         }
     }
 
@@ -836,6 +1082,24 @@ impl SoundSourceBuilder {
         self
     }
 
+    /// See [`SoundSource::set_velocity`]
+    pub fn with_velocity(mut self, velocity: Vector3<f32>) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// See [`SoundSource::set_doppler_factor`]
+    pub fn with_doppler_factor(mut self, doppler_factor: f32) -> Self {
+        self.doppler_factor = doppler_factor.max(0.0);
+        self
+    }
+
+    /// Sets desired occlusion settings of the sound source. See [`Occlusion`] docs for more info.
+    pub fn with_occlusion(mut self, occlusion: Option<Occlusion>) -> Self {
+        self.occlusion = occlusion;
+        self
+    }
+
     /// Sets desired output bus for the sound source.
     pub fn with_bus<S: AsRef<str>>(mut self, bus: S) -> Self {
         self.bus = bus.as_ref().to_string();
@@ -862,12 +1126,87 @@ impl SoundSourceBuilder {
             prev_left_samples: Default::default(),
             prev_right_samples: Default::default(),
             bus: self.bus,
+            velocity: self.velocity,
+            doppler_factor: self.doppler_factor,
+            occlusion: None,
+            occlusion_filters: Default::default(),
             ..Default::default()
         };
 
         source.set_buffer(self.buffer)?;
         source.set_playback_time(self.playback_time);
+        source.set_occlusion(self.occlusion);
 
         Ok(source)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        listener::Listener,
+        source::{Occlusion, SoundSourceBuilder},
+    };
+    use fyrox_core::algebra::Vector3;
+
+    #[test]
+    fn test_doppler_shift() {
+        let listener = Listener::new();
+        let mut source = SoundSourceBuilder::new()
+            .with_position(Vector3::new(0.0, 0.0, 100.0))
+            .with_velocity(Vector3::new(0.0, 0.0, -30.0))
+            .build()
+            .unwrap();
+
+        // Approaching source sounds higher.
+        assert!(source.calculate_doppler_shift(&listener) > 1.0);
+
+        source.set_velocity(Vector3::new(0.0, 0.0, 30.0));
+        assert!(source.calculate_doppler_shift(&listener) < 1.0);
+
+        // Perpendicular motion does not change the pitch.
+        source.set_velocity(Vector3::new(30.0, 0.0, 0.0));
+        assert_eq!(source.calculate_doppler_shift(&listener), 1.0);
+
+        source.set_velocity(Vector3::new(0.0, 0.0, -30.0));
+        source.set_doppler_factor(0.0);
+        assert_eq!(source.calculate_doppler_shift(&listener), 1.0);
+
+        // Extreme speeds are clamped.
+        source.set_doppler_factor(1.0);
+        source.set_velocity(Vector3::new(0.0, 0.0, -10000.0));
+        assert!(source.calculate_doppler_shift(&listener) <= 3.0);
+    }
+
+    #[test]
+    fn test_occlusion() {
+        let mut source = SoundSourceBuilder::new()
+            .with_occlusion(Some(Occlusion {
+                gain: 0.5,
+                transition_time: 0.0,
+                ..Default::default()
+            }))
+            .build()
+            .unwrap();
+
+        // Not occluded - the signal must not be changed.
+        source.frame_samples = vec![(1.0, 1.0); 4];
+        source.apply_occlusion();
+        assert_eq!(source.frame_samples, vec![(1.0, 1.0); 4]);
+
+        // Fully occluded - the signal must be filtered and attenuated. The first frame is a
+        // transition from the previous amount of occlusion.
+        source.set_occlusion_factor(1.0);
+        source.frame_samples = vec![(1.0, 1.0); 4];
+        source.apply_occlusion();
+        source.frame_samples = vec![(1.0, 1.0); 4];
+        source.apply_occlusion();
+        assert!(source.frame_samples.iter().all(|(l, r)| *l < 0.5 && l == r));
+
+        // Disabled occlusion does not affect the signal, even if the factor is set.
+        source.set_occlusion(None);
+        source.frame_samples = vec![(1.0, 1.0); 4];
+        source.apply_occlusion();
+        assert_eq!(source.frame_samples, vec![(1.0, 1.0); 4]);
+    }
+}
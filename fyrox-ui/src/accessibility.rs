@@ -0,0 +1,481 @@
+//! Accessibility support for the user interface. It allows to expose roles, labels and states of widgets to
+//! assistive technologies (screen readers, etc.) and to adjust the look of the user interface for people with
+//! visual or vestibular disorders. See [`AccessibilityTree`] and [`AccessibilitySettings`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    brush::Brush,
+    core::{color::Color, math::Rect, pool::Handle, reflect::prelude::*, visitor::prelude::*},
+    draw::DrawingContext,
+    text::Text,
+    UiNode, UserInterface,
+};
+
+/// Role of a widget, that tells assistive technologies how the widget should be presented to a user.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AccessibilityRole {
+    /// A widget, that simply holds other widgets. Such widgets are not exposed to assistive technologies,
+    /// unless they have a label.
+    #[default]
+    GenericContainer,
+    /// A window.
+    Window,
+    /// A button, that could be clicked.
+    Button,
+    /// A check box with checked, unchecked or undefined state.
+    CheckBox,
+    /// A static text.
+    Label,
+    /// A field for text input.
+    TextInput,
+    /// An image.
+    Image,
+    /// A list of items.
+    List,
+    /// An item of a list.
+    ListItem,
+    /// A menu.
+    Menu,
+    /// An item of a menu.
+    MenuItem,
+    /// A scroll bar.
+    ScrollBar,
+    /// A progress bar.
+    ProgressIndicator,
+    /// A tree.
+    Tree,
+    /// An item of a tree.
+    TreeItem,
+    /// A drop-down list.
+    ComboBox,
+}
+
+impl AccessibilityRole {
+    /// Returns `true` if a widget with the role could be interacted with (clicked, toggled, edited,
+    /// etc.).
+    pub fn is_interactive(self) -> bool {
+        matches!(
+            self,
+            Self::Button
+                | Self::CheckBox
+                | Self::TextInput
+                | Self::ListItem
+                | Self::MenuItem
+                | Self::ScrollBar
+                | Self::TreeItem
+                | Self::ComboBox
+        )
+    }
+}
+
+/// State of a check box (or any other widget, that could be toggled).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CheckedState {
+    /// Unchecked state.
+    False,
+    /// Checked state.
+    True,
+    /// Undefined state.
+    Mixed,
+}
+
+impl From<Option<bool>> for CheckedState {
+    fn from(value: Option<bool>) -> Self {
+        match value {
+            Some(true) => Self::True,
+            Some(false) => Self::False,
+            None => Self::Mixed,
+        }
+    }
+}
+
+/// Numeric value of a widget (progress bar, scroll bar, etc.) with its bounds.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct NumericValue {
+    /// Current value.
+    pub value: f64,
+    /// Minimal value.
+    pub min: f64,
+    /// Maximal value.
+    pub max: f64,
+}
+
+/// Accessibility information of a single widget. It is filled by [`crate::Control::accessibility`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessibilityNode {
+    /// Role of the widget.
+    pub role: AccessibilityRole,
+    /// A human-readable label of the widget. For example, a text of a button.
+    pub label: Option<String>,
+    /// A text value of the widget. For example, a text of a text box.
+    pub value: Option<String>,
+    /// A numeric value of the widget.
+    pub numeric_value: Option<NumericValue>,
+    /// Checked state of the widget, if the widget could be toggled.
+    pub checked: Option<CheckedState>,
+    /// Whether the widget is expanded or not, if the widget could be expanded.
+    pub expanded: Option<bool>,
+    /// Whether the widget is selected or not, if the widget could be selected.
+    pub selected: Option<bool>,
+    /// Whether the widget is read-only or not.
+    pub read_only: bool,
+    /// Whether the widget is disabled or not.
+    pub disabled: bool,
+    /// If `true`, then descendants of the widget are not exposed to assistive technologies. It is
+    /// used by widgets, that already describe their content. For example, a button uses the text of
+    /// its content as its label.
+    pub presentational_children: bool,
+    /// Screen-space bounds of the widget.
+    pub bounds: Rect<f32>,
+    /// Handles of the widgets, that are exposed to assistive technologies as children of the widget.
+    pub children: Vec<Handle<UiNode>>,
+}
+
+impl AccessibilityNode {
+    fn is_exposed(&self) -> bool {
+        self.role != AccessibilityRole::GenericContainer || self.label.is_some()
+    }
+}
+
+/// An action, that could be requested by an assistive technology.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AccessibilityAction {
+    /// Moves keyboard focus to a widget.
+    Focus,
+    /// Performs default action of a widget, for example clicks a button. It is emulated by a click of
+    /// the left mouse button at the center of the widget.
+    Activate,
+}
+
+/// A snapshot of accessibility information of the user interface. It could be created using
+/// [`UserInterface::accessibility_tree`] and then passed to an accessibility backend. If `accesskit`
+/// feature is enabled, the tree could be converted to AccessKit tree update using
+/// [`AccessibilityTree::to_accesskit`].
+///
+/// ## Examples
+///
+/// ```rust
+/// # use fyrox_ui::{accessibility::AccessibilityRole, UserInterface};
+/// fn print_buttons(ui: &UserInterface) {
+///     let tree = ui.accessibility_tree();
+///     for (handle, node) in tree.nodes.iter() {
+///         if node.role == AccessibilityRole::Button {
+///             println!("{:?}: {:?}", handle, node.label);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessibilityTree {
+    /// Handle of the root widget of the tree.
+    pub root: Handle<UiNode>,
+    /// Handle of the widget, that has keyboard focus.
+    pub focus: Handle<UiNode>,
+    /// Exposed widgets with their accessibility information. Parents always go before their children.
+    pub nodes: Vec<(Handle<UiNode>, AccessibilityNode)>,
+}
+
+/// Accessibility settings of the user interface. Use [`UserInterface::set_accessibility_settings`] to change them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Visit, Reflect)]
+pub struct AccessibilitySettings {
+    /// If `true`, then the contrast of every brush of the user interface is increased, so the widgets
+    /// become easier to distinguish.
+    pub high_contrast: bool,
+    /// If `true`, then the widgets avoid any non-essential animations (for example, a caret of a text box
+    /// stops blinking). Custom widgets should check this flag in [`crate::Control::on_accessibility_settings_changed`].
+    pub reduced_motion: bool,
+}
+
+const HIGH_CONTRAST_FACTOR: f32 = 2.0;
+
+fn high_contrast_color(color: Color) -> Color {
+    let adjust = |c: u8| -> u8 {
+        let c = (c as f32 / 255.0 - 0.5) * HIGH_CONTRAST_FACTOR + 0.5;
+        (c.clamp(0.0, 1.0) * 255.0) as u8
+    };
+    Color::from_rgba(adjust(color.r), adjust(color.g), adjust(color.b), color.a)
+}
+
+fn high_contrast_brush(brush: &mut Brush) {
+    match brush {
+        Brush::Solid(color) => *color = high_contrast_color(*color),
+        Brush::LinearGradient { stops, .. } | Brush::RadialGradient { stops, .. } => {
+            for stop in stops {
+                stop.color = high_contrast_color(stop.color);
+            }
+        }
+    }
+}
+
+pub(crate) fn apply_high_contrast(drawing_context: &mut DrawingContext) {
+    for command in drawing_context.get_commands_mut() {
+        high_contrast_brush(&mut command.brush);
+    }
+}
+
+/// Searches for the first [`Text`] widget in the hierarchy of the given widget (including the widget itself)
+/// and returns its text. It could be used to fetch a label for widgets with arbitrary content, such as
+/// buttons.
+pub fn find_text(ui: &UserInterface, root: Handle<UiNode>) -> Option<String> {
+    let mut stack = vec![root];
+    while let Some(handle) = stack.pop() {
+        let Some(node) = ui.try_get_node(handle) else {
+            continue;
+        };
+        if let Some(text) = node.cast::<Text>() {
+            let text = text.text();
+            if !text.is_empty() {
+                return Some(text);
+            }
+        }
+        stack.extend(node.children().iter().rev());
+    }
+    None
+}
+
+pub(crate) fn build_tree(ui: &UserInterface) -> AccessibilityTree {
+    let mut tree = AccessibilityTree {
+        root: ui.root(),
+        focus: ui.root(),
+        nodes: Default::default(),
+    };
+
+    // Widgets, that are not exposed, are skipped and their children are attached to the nearest
+    // exposed ancestor.
+    let mut stack = vec![(ui.root(), None::<usize>)];
+    while let Some((handle, parent_index)) = stack.pop() {
+        let Some(widget) = ui.try_get_node(handle) else {
+            continue;
+        };
+
+        if !widget.is_globally_visible() {
+            continue;
+        }
+
+        let mut node = AccessibilityNode {
+            disabled: !widget.enabled(),
+            bounds: widget.screen_bounds(),
+            ..Default::default()
+        };
+        widget.accessibility(ui, &mut node);
+        // Explicitly specified label always has priority over the label provided by the widget.
+        if let Some(label) = widget.accessibility_label.as_ref() {
+            node.label = Some(label.clone());
+        }
+
+        let index = if handle == ui.root() || node.is_exposed() {
+            if let Some(parent_index) = parent_index {
+                tree.nodes[parent_index].1.children.push(handle);
+            }
+            tree.nodes.push((handle, node));
+            Some(tree.nodes.len() - 1)
+        } else {
+            parent_index
+        };
+
+        let presentational_children = index.is_some_and(|index| {
+            tree.nodes[index].0 == handle && tree.nodes[index].1.presentational_children
+        });
+        if !presentational_children {
+            // Reverse order is needed to keep the order of children.
+            for &child in widget.children().iter().rev() {
+                stack.push((child, index));
+            }
+        }
+    }
+
+    let focus = ui.keyboard_focus_node;
+    if tree.nodes.iter().any(|(handle, _)| *handle == focus) {
+        tree.focus = focus;
+    }
+
+    tree
+}
+
+#[cfg(feature = "accesskit")]
+mod accesskit_impl {
+    use super::*;
+    use accesskit::{
+        Action, ActionRequest, Checked, DefaultActionVerb, NodeBuilder, NodeClassSet, NodeId, Role,
+        Tree, TreeUpdate,
+    };
+
+    /// Packs the handle in a node id: index in the low 32 bits, generation in the high 32 bits.
+    pub(super) fn node_id(handle: Handle<UiNode>) -> NodeId {
+        NodeId(((handle.generation() as u64) << 32) | handle.index() as u64)
+    }
+
+    /// Unpacks the handle from a node id produced by [`node_id`].
+    pub(super) fn handle_from_node_id(id: NodeId) -> Handle<UiNode> {
+        Handle::new(id.0 as u32, (id.0 >> 32) as u32)
+    }
+
+    fn role(role: AccessibilityRole) -> Role {
+        match role {
+            AccessibilityRole::GenericContainer => Role::GenericContainer,
+            AccessibilityRole::Window => Role::Window,
+            AccessibilityRole::Button => Role::Button,
+            AccessibilityRole::CheckBox => Role::CheckBox,
+            AccessibilityRole::Label => Role::StaticText,
+            AccessibilityRole::TextInput => Role::TextInput,
+            AccessibilityRole::Image => Role::Image,
+            AccessibilityRole::List => Role::List,
+            AccessibilityRole::ListItem => Role::ListItem,
+            AccessibilityRole::Menu => Role::Menu,
+            AccessibilityRole::MenuItem => Role::MenuItem,
+            AccessibilityRole::ScrollBar => Role::ScrollBar,
+            AccessibilityRole::ProgressIndicator => Role::ProgressIndicator,
+            AccessibilityRole::Tree => Role::Tree,
+            AccessibilityRole::TreeItem => Role::TreeItem,
+            AccessibilityRole::ComboBox => Role::ComboBox,
+        }
+    }
+
+    impl AccessibilityTree {
+        /// Converts the tree to AccessKit tree update, that could be passed to a platform adapter.
+        pub fn to_accesskit(&self, classes: &mut NodeClassSet) -> TreeUpdate {
+            let nodes = self
+                .nodes
+                .iter()
+                .map(|(handle, node)| {
+                    let mut builder = NodeBuilder::new(role(node.role));
+                    if let Some(label) = node.label.as_ref() {
+                        builder.set_name(label.as_str());
+                    }
+                    if let Some(value) = node.value.as_ref() {
+                        builder.set_value(value.as_str());
+                    }
+                    if let Some(numeric_value) = node.numeric_value {
+                        builder.set_numeric_value(numeric_value.value);
+                        builder.set_min_numeric_value(numeric_value.min);
+                        builder.set_max_numeric_value(numeric_value.max);
+                    }
+                    if let Some(checked) = node.checked {
+                        builder.set_checked(match checked {
+                            CheckedState::False => Checked::False,
+                            CheckedState::True => Checked::True,
+                            CheckedState::Mixed => Checked::Mixed,
+                        });
+                    }
+                    if let Some(expanded) = node.expanded {
+                        builder.set_expanded(expanded);
+                    }
+                    if let Some(selected) = node.selected {
+                        builder.set_selected(selected);
+                    }
+                    if node.read_only {
+                        builder.set_read_only();
+                    }
+                    if node.disabled {
+                        builder.set_disabled();
+                    } else {
+                        builder.add_action(Action::Focus);
+                        builder.set_default_action_verb(DefaultActionVerb::Click);
+                    }
+                    builder.set_bounds(accesskit::Rect {
+                        x0: node.bounds.x() as f64,
+                        y0: node.bounds.y() as f64,
+                        x1: (node.bounds.x() + node.bounds.w()) as f64,
+                        y1: (node.bounds.y() + node.bounds.h()) as f64,
+                    });
+                    builder.set_children(
+                        node.children
+                            .iter()
+                            .map(|child| node_id(*child))
+                            .collect::<Vec<_>>(),
+                    );
+                    (node_id(*handle), builder.build(classes))
+                })
+                .collect();
+
+            TreeUpdate {
+                nodes,
+                tree: Some(Tree::new(node_id(self.root))),
+                focus: node_id(self.focus),
+            }
+        }
+    }
+
+    impl AccessibilityAction {
+        /// Converts AccessKit action request to a pair of target widget handle and an action. Returns
+        /// `None` if the action is not supported.
+        pub fn from_accesskit(request: &ActionRequest) -> Option<(Handle<UiNode>, Self)> {
+            let handle = handle_from_node_id(request.target);
+            match request.action {
+                Action::Focus => Some((handle, Self::Focus)),
+                Action::Default => Some((handle, Self::Activate)),
+                _ => None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        accessibility::{AccessibilityRole, CheckedState},
+        button::ButtonBuilder,
+        check_box::CheckBoxBuilder,
+        core::algebra::Vector2,
+        stack_panel::StackPanelBuilder,
+        widget::WidgetBuilder,
+        UserInterface,
+    };
+
+    #[test]
+    fn test_accessibility_tree() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let ctx = &mut ui.build_ctx();
+        let button = ButtonBuilder::new(WidgetBuilder::new())
+            .with_text("Click Me")
+            .build(ctx);
+        let check_box =
+            CheckBoxBuilder::new(WidgetBuilder::new().with_accessibility_label("Enable Sound"))
+                .checked(Some(true))
+                .build(ctx);
+        let panel = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_child(button)
+                .with_child(check_box),
+        )
+        .build(ctx);
+
+        let tree = ui.accessibility_tree();
+
+        // Stack panel is not exposed, so its children are attached to the root.
+        assert!(tree.nodes.iter().all(|(handle, _)| *handle != panel));
+        let (root, root_node) = &tree.nodes[0];
+        assert_eq!(*root, ui.root());
+        assert_eq!(root_node.children, vec![button, check_box]);
+
+        let button_node = &tree.nodes.iter().find(|(h, _)| *h == button).unwrap().1;
+        assert_eq!(button_node.role, AccessibilityRole::Button);
+        assert_eq!(button_node.label.as_deref(), Some("Click Me"));
+        assert!(button_node.children.is_empty());
+
+        let check_box_node = &tree.nodes.iter().find(|(h, _)| *h == check_box).unwrap().1;
+        assert_eq!(check_box_node.role, AccessibilityRole::CheckBox);
+        assert_eq!(check_box_node.label.as_deref(), Some("Enable Sound"));
+        assert_eq!(check_box_node.checked, Some(CheckedState::True));
+    }
+
+    #[cfg(feature = "accesskit")]
+    #[test]
+    fn test_node_id_round_trip() {
+        use crate::{
+            accessibility::accesskit_impl::{handle_from_node_id, node_id},
+            core::pool::Handle,
+        };
+
+        for handle in [
+            Handle::NONE,
+            Handle::new(0, 1),
+            Handle::new(123, 456),
+            Handle::new(u32::MAX, u32::MAX),
+        ] {
+            assert_eq!(handle_from_node_id(node_id(handle)), handle);
+        }
+        assert_eq!(node_id(Handle::new(1, 2)).0, (2 << 32) | 1);
+    }
+}
@@ -3,6 +3,7 @@
 #![warn(missing_docs)]
 
 use crate::{
+    accessibility::{self, AccessibilityNode, AccessibilityRole},
     border::BorderBuilder,
     core::{
         algebra::Vector2, pool::Handle, reflect::prelude::*, type_traits::prelude::*,
@@ -203,6 +204,12 @@ impl Control for Button {
             }
         }
     }
+
+    fn accessibility(&self, ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::Button;
+        node.label = accessibility::find_text(ui, self.content);
+        node.presentational_children = true;
+    }
 }
 
 /// Possible button content. In general, button widget can contain any type of widget inside. This enum contains
@@ -5,6 +5,7 @@
 #![warn(missing_docs)]
 
 use crate::{
+    accessibility::{self, AccessibilityNode, AccessibilityRole},
     border::BorderBuilder,
     brush::Brush,
     core::{
@@ -241,6 +242,13 @@ impl Control for CheckBox {
             }
         }
     }
+
+    fn accessibility(&self, ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::CheckBox;
+        node.label = accessibility::find_text(ui, self.handle);
+        node.checked = Some(self.checked.into());
+        node.presentational_children = true;
+    }
 }
 
 /// Check box builder creates [`CheckBox`] instances and adds them to the user interface.
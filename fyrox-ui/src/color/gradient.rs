@@ -6,6 +6,7 @@ use crate::{
         color::Color,
         color_gradient::{ColorGradient, GradientPoint},
         math::Rect,
+        parking_lot::Mutex,
         pool::Handle,
         reflect::prelude::*,
         type_traits::prelude::*,
@@ -24,6 +25,7 @@ use crate::{
 use std::{
     cell::Cell,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -176,27 +178,11 @@ impl Control for ColorGradientEditor {
                     value.clone(),
                 ));
 
-                for &point in ui.node(self.points_canvas).children() {
-                    ui.send_message(WidgetMessage::remove(point, MessageDirection::ToWidget));
-                }
-
-                let points = create_color_points(
-                    value,
-                    self.point_context_menu.clone(),
-                    &mut ui.build_ctx(),
-                );
-
-                for point in points {
-                    ui.send_message(WidgetMessage::link(
-                        point,
-                        MessageDirection::ToWidget,
-                        self.points_canvas,
-                    ));
-                }
+                self.sync_points(value, ui);
             }
         }
 
-        if message.direction() == MessageDirection::FromWidget {
+        if message.direction() == MessageDirection::FromWidget && message.flags != SYNC_FLAG {
             if let Some(ColorPointMessage::Location(_)) = message.data() {
                 let gradient = self.fetch_gradient(Handle::NONE, ui);
 
@@ -296,6 +282,61 @@ impl Control for ColorGradientEditor {
 }
 
 impl ColorGradientEditor {
+    // Updates only the points, that differ from the given gradient, instead of re-creating every
+    // point, so the editor could be synced cheaply after every change made in it.
+    fn sync_points(&self, gradient: &ColorGradient, ui: &mut UserInterface) {
+        let mut points = ui
+            .node(self.points_canvas)
+            .children()
+            .iter()
+            .filter_map(|c| {
+                ui.node(*c)
+                    .query_component::<ColorPoint>()
+                    .map(|pt| (*c, pt.location, pt.color()))
+            })
+            .collect::<Vec<_>>();
+        // Gradient points are sorted by their locations, sort the widgets the same way to match them.
+        points.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        for (index, gradient_point) in gradient.points().iter().enumerate() {
+            let Some(&(point, location, color)) = points.get(index) else {
+                let point = create_color_point(
+                    gradient_point,
+                    self.point_context_menu.clone(),
+                    &mut ui.build_ctx(),
+                );
+                ui.send_message(WidgetMessage::link(
+                    point,
+                    MessageDirection::ToWidget,
+                    self.points_canvas,
+                ));
+                continue;
+            };
+
+            if location != gradient_point.location() {
+                let mut msg = ColorPointMessage::location(
+                    point,
+                    MessageDirection::ToWidget,
+                    gradient_point.location(),
+                );
+                msg.flags = SYNC_FLAG;
+                ui.send_message(msg);
+            }
+
+            if color != gradient_point.color() {
+                ui.send_message(WidgetMessage::foreground(
+                    point,
+                    MessageDirection::ToWidget,
+                    Brush::Solid(gradient_point.color()),
+                ));
+            }
+        }
+
+        for &(point, _, _) in points.iter().skip(gradient.points().len()) {
+            ui.send_message(WidgetMessage::remove(point, MessageDirection::ToWidget));
+        }
+    }
+
     fn fetch_gradient(&self, exclude: Handle<UiNode>, ui: &UserInterface) -> ColorGradient {
         let mut gradient = ColorGradient::new();
 
@@ -313,11 +354,106 @@ impl ColorGradientEditor {
     }
 }
 
+/// Binds a [`ColorGradientEditor`] widget to a shared color gradient, so the gradient could be edited
+/// at runtime (for example in modding tools or in-game editors). Every change made in the editor is
+/// written back to the shared gradient, which could then be applied to particle systems, trails, etc.
+///
+/// # Examples
+///
+/// ```rust
+/// # use fyrox_ui::{
+/// #     color::gradient::{ColorGradientEditorBinding, ColorGradientEditorBuilder},
+/// #     core::{color_gradient::ColorGradient, parking_lot::Mutex},
+/// #     message::UiMessage,
+/// #     widget::WidgetBuilder,
+/// #     UserInterface,
+/// # };
+/// # use std::sync::Arc;
+/// fn create_binding(
+///     ui: &mut UserInterface,
+///     gradient: Arc<Mutex<ColorGradient>>,
+/// ) -> ColorGradientEditorBinding {
+///     let editor = ColorGradientEditorBuilder::new(WidgetBuilder::new().with_width(300.0))
+///         .build(&mut ui.build_ctx());
+///     let binding = ColorGradientEditorBinding::new(editor, gradient);
+///     binding.sync_to_editor(ui);
+///     binding
+/// }
+///
+/// fn on_ui_message(binding: &ColorGradientEditorBinding, ui: &UserInterface, message: &UiMessage) {
+///     binding.handle_ui_message(ui, message);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ColorGradientEditorBinding {
+    editor: Handle<UiNode>,
+    gradient: Arc<Mutex<ColorGradient>>,
+}
+
+impl ColorGradientEditorBinding {
+    /// Creates new binding between the given color gradient editor widget and the gradient.
+    pub fn new(editor: Handle<UiNode>, gradient: Arc<Mutex<ColorGradient>>) -> Self {
+        Self { editor, gradient }
+    }
+
+    /// Returns a handle of the bound color gradient editor widget.
+    pub fn editor(&self) -> Handle<UiNode> {
+        self.editor
+    }
+
+    /// Returns the bound gradient.
+    pub fn gradient(&self) -> &Arc<Mutex<ColorGradient>> {
+        &self.gradient
+    }
+
+    /// Sends current gradient to the editor. Call this method when the gradient was modified from
+    /// code.
+    pub fn sync_to_editor(&self, ui: &UserInterface) {
+        ui.send_message(ColorGradientEditorMessage::value(
+            self.editor,
+            MessageDirection::ToWidget,
+            self.gradient.lock().clone(),
+        ));
+    }
+
+    /// Writes the changes made in the editor back to the gradient and syncs the editor with it. Only
+    /// the changed points of the editor are updated. Returns `true` if the message was processed and
+    /// the gradient was changed.
+    pub fn handle_ui_message(&self, ui: &UserInterface, message: &UiMessage) -> bool {
+        if let Some(ColorGradientEditorMessage::Value(gradient)) = message.data() {
+            if message.destination() == self.editor
+                && message.direction() == MessageDirection::FromWidget
+            {
+                *self.gradient.lock() = gradient.clone();
+                self.sync_to_editor(ui);
+                return true;
+            }
+        }
+        false
+    }
+}
+
 pub struct ColorGradientEditorBuilder {
     widget_builder: WidgetBuilder,
     color_gradient: ColorGradient,
 }
 
+fn create_color_point(
+    point: &GradientPoint,
+    point_context_menu: RcUiNodeHandle,
+    ctx: &mut BuildContext,
+) -> Handle<UiNode> {
+    ColorPointBuilder::new(
+        WidgetBuilder::new()
+            .with_context_menu(point_context_menu)
+            .with_cursor(Some(CursorIcon::EwResize))
+            .with_width(6.0)
+            .with_foreground(Brush::Solid(point.color())),
+    )
+    .with_location(point.location())
+    .build(ctx)
+}
+
 fn create_color_points(
     color_gradient: &ColorGradient,
     point_context_menu: RcUiNodeHandle,
@@ -326,17 +462,7 @@ fn create_color_points(
     color_gradient
         .points()
         .iter()
-        .map(|pt| {
-            ColorPointBuilder::new(
-                WidgetBuilder::new()
-                    .with_context_menu(point_context_menu.clone())
-                    .with_cursor(Some(CursorIcon::EwResize))
-                    .with_width(6.0)
-                    .with_foreground(Brush::Solid(pt.color())),
-            )
-            .with_location(pt.location())
-            .build(ctx)
-        })
+        .map(|pt| create_color_point(pt, point_context_menu.clone(), ctx))
         .collect::<Vec<_>>()
 }
 
@@ -628,3 +754,115 @@ impl ColorPointsCanvasBuilder {
         }))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        brush::Brush,
+        color::gradient::{
+            ColorGradientEditor, ColorGradientEditorBinding, ColorGradientEditorBuilder,
+            ColorGradientEditorMessage, ColorPoint,
+        },
+        core::{
+            algebra::Vector2,
+            color::Color,
+            color_gradient::{ColorGradient, GradientPoint},
+            parking_lot::Mutex,
+            pool::Handle,
+        },
+        message::MessageDirection,
+        widget::WidgetBuilder,
+        UserInterface,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn test_color_gradient_editor_binding() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let editor =
+            ColorGradientEditorBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let binding =
+            ColorGradientEditorBinding::new(editor, Arc::new(Mutex::new(ColorGradient::new())));
+
+        let mut gradient = ColorGradient::new();
+        gradient.add_point(GradientPoint::new(0.5, Color::RED));
+
+        // Messages for other widgets or directions must be ignored.
+        assert!(!binding.handle_ui_message(
+            &ui,
+            &ColorGradientEditorMessage::value(
+                editor,
+                MessageDirection::ToWidget,
+                gradient.clone()
+            )
+        ));
+        assert!(binding.gradient().lock().points().is_empty());
+
+        assert!(binding.handle_ui_message(
+            &ui,
+            &ColorGradientEditorMessage::value(
+                editor,
+                MessageDirection::FromWidget,
+                gradient.clone()
+            )
+        ));
+        assert_eq!(binding.gradient().lock().points(), gradient.points());
+    }
+
+    #[test]
+    fn test_sync_updates_only_changed_points() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let mut gradient = ColorGradient::new();
+        gradient.add_point(GradientPoint::new(0.0, Color::RED));
+        gradient.add_point(GradientPoint::new(1.0, Color::GREEN));
+        let editor = ColorGradientEditorBuilder::new(WidgetBuilder::new())
+            .with_color_gradient(gradient.clone())
+            .build(&mut ui.build_ctx());
+        let points = |ui: &UserInterface| -> Vec<(Handle<_>, f32, Brush)> {
+            let canvas = ui
+                .node(editor)
+                .query_component::<ColorGradientEditor>()
+                .unwrap()
+                .points_canvas;
+            ui.node(canvas)
+                .children()
+                .iter()
+                .map(|c| {
+                    let point = ui.node(*c).query_component::<ColorPoint>().unwrap();
+                    (*c, point.location, point.foreground())
+                })
+                .collect()
+        };
+        let initial = points(&ui);
+
+        let sync = |ui: &mut UserInterface, gradient: &ColorGradient| {
+            ui.send_message(ColorGradientEditorMessage::value(
+                editor,
+                MessageDirection::ToWidget,
+                gradient.clone(),
+            ));
+            while ui.poll_message().is_some() {}
+        };
+
+        let mut changed = ColorGradient::new();
+        changed.add_point(GradientPoint::new(0.0, Color::RED));
+        changed.add_point(GradientPoint::new(0.5, Color::BLUE));
+        sync(&mut ui, &changed);
+        let synced = points(&ui);
+        assert_eq!(synced[0], initial[0]);
+        assert_eq!(synced[1], (initial[1].0, 0.5, Brush::Solid(Color::BLUE)));
+
+        // Only the new point is created.
+        changed.add_point(GradientPoint::new(1.0, Color::WHITE));
+        sync(&mut ui, &changed);
+        let synced = points(&ui);
+        assert_eq!(synced.len(), 3);
+        assert_eq!(synced[0].0, initial[0].0);
+        assert_eq!(synced[1].0, initial[1].0);
+        assert_eq!(synced[2].1, 1.0);
+
+        sync(&mut ui, &gradient);
+        let synced = points(&ui);
+        assert_eq!(synced, initial);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    accessibility::{AccessibilityNode, AccessibilitySettings},
     core::{
         algebra::Vector2, pool::Handle, reflect::Reflect, scope_profile, uuid::Uuid, visitor::Visit,
     },
@@ -316,4 +317,24 @@ pub trait Control:
         #[allow(unused_variables)] event: &OsEvent,
     ) {
     }
+
+    /// Fills accessibility information of the widget, that will be exposed to assistive technologies.
+    /// Default implementation does nothing, which means that the widget is treated as a generic container
+    /// and it won't be exposed unless it has [`Widget::accessibility_label`]. See
+    /// [`crate::accessibility::AccessibilityTree`] docs for more info.
+    fn accessibility(
+        &self,
+        #[allow(unused_variables)] ui: &UserInterface,
+        #[allow(unused_variables)] node: &mut AccessibilityNode,
+    ) {
+    }
+
+    /// This method is called when the widget is added to the user interface and every time when accessibility
+    /// settings of the user interface are changed. It could be used to disable animations of the widget when
+    /// [`AccessibilitySettings::reduced_motion`] is set.
+    fn on_accessibility_settings_changed(
+        &mut self,
+        #[allow(unused_variables)] settings: &AccessibilitySettings,
+    ) {
+    }
 }
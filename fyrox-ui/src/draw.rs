@@ -1,4 +1,3 @@
-use crate::font::FontHeight;
 use crate::{
     brush::Brush,
     core::{
@@ -6,7 +5,7 @@ use crate::{
         color::Color,
         math::{self, Rect, TriangleDefinition},
     },
-    font::FontResource,
+    font::{Font, FontResource},
     formatted_text::FormattedText,
     Thickness,
 };
@@ -37,7 +36,6 @@ pub enum CommandTexture {
     Texture(UntypedResource),
     Font {
         font: FontResource,
-        height: FontHeight,
         page_index: usize,
     },
 }
@@ -491,6 +489,43 @@ impl TransformStack {
     }
 }
 
+/// Font pages used by the commands of a drawing context. The pages are pinned until the context is
+/// cleared, so glyphs in the pages won't be moved or evicted before the commands are rendered.
+#[derive(Debug, Default)]
+struct PinnedFontPages(Vec<(FontResource, usize)>);
+
+impl PinnedFontPages {
+    fn pin(&mut self, font: &FontResource, font_data: &mut Font, page_index: usize) {
+        font_data.pin_page(page_index);
+        self.0.push((font.clone(), page_index));
+    }
+
+    fn unpin_all(&mut self) {
+        for (font, page_index) in self.0.drain(..) {
+            if let Some(font_data) = font.state().data() {
+                font_data.unpin_page(page_index);
+            }
+        }
+    }
+}
+
+impl Clone for PinnedFontPages {
+    fn clone(&self) -> Self {
+        for (font, page_index) in self.0.iter() {
+            if let Some(font_data) = font.state().data() {
+                font_data.pin_page(*page_index);
+            }
+        }
+        Self(self.0.clone())
+    }
+}
+
+impl Drop for PinnedFontPages {
+    fn drop(&mut self) {
+        self.unpin_all();
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DrawingContext {
     vertex_buffer: Vec<Vertex>,
@@ -499,6 +534,7 @@ pub struct DrawingContext {
     pub transform_stack: TransformStack,
     opacity_stack: Vec<f32>,
     triangles_to_commit: usize,
+    pinned_font_pages: PinnedFontPages,
 }
 
 fn get_line_thickness_vector(a: Vector2<f32>, b: Vector2<f32>, thickness: f32) -> Vector2<f32> {
@@ -548,6 +584,7 @@ impl DrawingContext {
             triangles_to_commit: 0,
             opacity_stack: vec![1.0],
             transform_stack: Default::default(),
+            pinned_font_pages: Default::default(),
         }
     }
 
@@ -559,6 +596,7 @@ impl DrawingContext {
         self.opacity_stack.clear();
         self.opacity_stack.push(1.0);
         self.triangles_to_commit = 0;
+        self.pinned_font_pages.unpin_all();
     }
 
     #[inline]
@@ -576,6 +614,11 @@ impl DrawingContext {
         &self.command_buffer
     }
 
+    #[inline]
+    pub(crate) fn get_commands_mut(&mut self) -> &mut [Command] {
+        &mut self.command_buffer
+    }
+
     pub fn push_opacity(&mut self, opacity: f32) {
         self.opacity_stack.push(opacity);
     }
@@ -670,29 +713,39 @@ impl DrawingContext {
             brush: Brush,
             font: &FontResource,
         ) {
-            let Some(mut current_page_index) = formatted_text
-                .get_glyphs()
-                .first()
-                .map(|g| g.atlas_page_index)
-            else {
+            let mut font_state = font.state();
+            let Some(font_data) = font_state.data() else {
                 return;
             };
 
+            let mut current_page_index = None;
             for element in formatted_text.get_glyphs() {
+                // Glyphs could be moved across the font pages (or even evicted from them) after the
+                // text was formatted, so fetch their actual location.
+                let (page_index, tex_coords) = font_data
+                    .glyph(element.unicode, formatted_text.font_size())
+                    .map_or((element.atlas_page_index, element.tex_coords), |glyph| {
+                        (glyph.page_index, glyph.tex_coords)
+                    });
+
                 // If we've switched to another atlas page, commit the text and start a new batch.
-                if current_page_index != element.atlas_page_index {
-                    ctx.commit(
-                        clip_bounds,
-                        brush.clone(),
-                        CommandTexture::Font {
-                            font: font.clone(),
-                            page_index: current_page_index,
-                            height: formatted_text.font_size().into(),
-                        },
-                        None,
-                    );
-                    current_page_index = element.atlas_page_index;
+                if current_page_index != Some(page_index) {
+                    if let Some(current_page_index) = current_page_index {
+                        ctx.commit(
+                            clip_bounds,
+                            brush.clone(),
+                            CommandTexture::Font {
+                                font: font.clone(),
+                                page_index: current_page_index,
+                            },
+                            None,
+                        );
+                    }
+
+                    // Keep the glyphs of the page in place until the commands are rendered.
+                    ctx.pinned_font_pages.pin(font, font_data, page_index);
                 }
+                current_page_index = Some(page_index);
 
                 let bounds = element.bounds;
 
@@ -704,20 +757,21 @@ impl DrawingContext {
                 )
                 .inflate(dilation, dilation);
 
-                ctx.push_rect_filled(&final_bounds, Some(&element.tex_coords));
+                ctx.push_rect_filled(&final_bounds, Some(&tex_coords));
             }
 
             // Commit the rest.
-            ctx.commit(
-                clip_bounds,
-                brush,
-                CommandTexture::Font {
-                    font: font.clone(),
-                    page_index: current_page_index,
-                    height: formatted_text.font_size().into(),
-                },
-                None,
-            );
+            if let Some(current_page_index) = current_page_index {
+                ctx.commit(
+                    clip_bounds,
+                    brush,
+                    CommandTexture::Font {
+                        font: font.clone(),
+                        page_index: current_page_index,
+                    },
+                    None,
+                );
+            }
         }
 
         // Draw shadow, if any.
@@ -0,0 +1,433 @@
+//! Drawing area is a widget with immediate-mode drawing API, it allows you to draw lines, arcs, polygons, textures
+//! and text without creating a widget for each element. See [`DrawingArea`] docs for more info and usage examples.
+
+#![warn(missing_docs)]
+
+use crate::{
+    brush::Brush,
+    core::{
+        algebra::Vector2, color::Color, math::Rect, pool::Handle, reflect::prelude::*,
+        type_traits::prelude::*, visitor::prelude::*,
+    },
+    draw::{CommandTexture, Draw, DrawingContext},
+    font::FontResource,
+    formatted_text::{FormattedText, FormattedTextBuilder},
+    message::UiMessage,
+    widget::{Widget, WidgetBuilder},
+    BuildContext, Control, UiNode, UserInterface,
+};
+use fyrox_core::uuid_provider;
+use fyrox_resource::untyped::UntypedResource;
+use std::ops::{Deref, DerefMut, Range};
+
+/// A single drawing command of [`DrawingArea`]. All positions are in local coordinates of the drawing area.
+#[derive(Clone, Debug)]
+pub enum DrawingCommand {
+    /// A line of fixed thickness between two points.
+    Line {
+        /// Beginning of the line.
+        begin: Vector2<f32>,
+        /// End of the line.
+        end: Vector2<f32>,
+        /// Thickness of the line.
+        thickness: f32,
+        /// Brush of the line.
+        brush: Brush,
+    },
+    /// A sequence of connected lines of fixed thickness.
+    Polyline {
+        /// Points of the polyline.
+        points: Vec<Vector2<f32>>,
+        /// Thickness of the lines.
+        thickness: f32,
+        /// Connect the last point with the first one.
+        closed: bool,
+        /// Brush of the lines.
+        brush: Brush,
+    },
+    /// An arc of a circle.
+    Arc {
+        /// Center of the arc.
+        center: Vector2<f32>,
+        /// Radius of the arc.
+        radius: f32,
+        /// Range of angles (in radians) of the arc.
+        angles: Range<f32>,
+        /// Amount of segments that is used to approximate the arc.
+        segments: usize,
+        /// Thickness of the arc.
+        thickness: f32,
+        /// Brush of the arc.
+        brush: Brush,
+    },
+    /// Solid circle.
+    Circle {
+        /// Center of the circle.
+        center: Vector2<f32>,
+        /// Radius of the circle.
+        radius: f32,
+        /// Amount of segments that is used to approximate the circle.
+        segments: usize,
+        /// Brush of the circle.
+        brush: Brush,
+    },
+    /// Solid convex polygon.
+    Polygon {
+        /// Points of the polygon.
+        points: Vec<Vector2<f32>>,
+        /// Brush of the polygon.
+        brush: Brush,
+    },
+    /// A rectangle filled with a texture.
+    Texture {
+        /// Bounds of the rectangle.
+        rect: Rect<f32>,
+        /// Texture of the rectangle.
+        texture: UntypedResource,
+        /// Texture coordinates of the corners of the rectangle (left-top, right-top, right-bottom, left-bottom).
+        tex_coords: [Vector2<f32>; 4],
+        /// Brush that is used to tint the texture.
+        brush: Brush,
+    },
+    /// Formatted text.
+    Text {
+        /// Position of the left-top corner of the text.
+        position: Vector2<f32>,
+        /// Formatted text.
+        text: FormattedText,
+    },
+}
+
+impl DrawingCommand {
+    fn brush(&self) -> Option<&Brush> {
+        match self {
+            DrawingCommand::Line { brush, .. }
+            | DrawingCommand::Polyline { brush, .. }
+            | DrawingCommand::Arc { brush, .. }
+            | DrawingCommand::Circle { brush, .. }
+            | DrawingCommand::Polygon { brush, .. } => Some(brush),
+            DrawingCommand::Texture { .. } | DrawingCommand::Text { .. } => None,
+        }
+    }
+}
+
+/// Drawing area is a widget with immediate-mode drawing API. It stores a list of drawing commands that
+/// are drawn in the order they were added. Typical usage is to clear the drawing area and fill it with
+/// new commands each frame. It is useful for graphs, charts, radar charts, custom HUD elements and so on,
+/// where creating a widget for each element is too heavy.
+///
+/// Consecutive geometric commands with the same brush are merged into a single drawing command of the
+/// user interface renderer.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use fyrox_ui::{
+/// #     brush::Brush,
+/// #     core::{algebra::Vector2, color::Color, pool::Handle},
+/// #     drawing_area::{DrawingArea, DrawingAreaBuilder},
+/// #     widget::WidgetBuilder,
+/// #     BuildContext, UiNode, UserInterface,
+/// # };
+/// #
+/// fn create_drawing_area(ctx: &mut BuildContext) -> Handle<UiNode> {
+///     DrawingAreaBuilder::new(
+///         WidgetBuilder::new()
+///             .with_width(200.0)
+///             .with_height(100.0),
+///     )
+///     .build(ctx)
+/// }
+///
+/// // Call this every frame to update the graph.
+/// fn draw_graph(ui: &mut UserInterface, drawing_area: Handle<UiNode>, samples: &[f32]) {
+///     if let Some(drawing_area) = ui.node_mut(drawing_area).cast_mut::<DrawingArea>() {
+///         drawing_area.clear();
+///
+///         let points = samples
+///             .iter()
+///             .enumerate()
+///             .map(|(i, sample)| Vector2::new(i as f32 * 2.0, 100.0 - sample))
+///             .collect();
+///
+///         drawing_area.polyline(points, 1.0, false, Brush::Solid(Color::GREEN));
+///     }
+/// }
+/// ```
+#[derive(Default, Clone, Visit, Reflect, Debug, ComponentProvider)]
+pub struct DrawingArea {
+    /// Base widget of the drawing area.
+    pub widget: Widget,
+    /// Current set of drawing commands.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub commands: Vec<DrawingCommand>,
+}
+
+crate::define_widget_deref!(DrawingArea);
+
+uuid_provider!(DrawingArea = "a3f8c2a4-5f0c-4a1e-9c3c-2b7e4d1f6a90");
+
+impl DrawingArea {
+    /// Removes every drawing command.
+    pub fn clear(&mut self) -> &mut Self {
+        self.commands.clear();
+        self
+    }
+
+    /// Adds a new drawing command.
+    pub fn push(&mut self, command: DrawingCommand) -> &mut Self {
+        self.commands.push(command);
+        self
+    }
+
+    /// Draws a line of fixed thickness between two points.
+    pub fn line(
+        &mut self,
+        begin: Vector2<f32>,
+        end: Vector2<f32>,
+        thickness: f32,
+        brush: Brush,
+    ) -> &mut Self {
+        self.push(DrawingCommand::Line {
+            begin,
+            end,
+            thickness,
+            brush,
+        })
+    }
+
+    /// Draws a sequence of connected lines.
+    pub fn polyline(
+        &mut self,
+        points: Vec<Vector2<f32>>,
+        thickness: f32,
+        closed: bool,
+        brush: Brush,
+    ) -> &mut Self {
+        self.push(DrawingCommand::Polyline {
+            points,
+            thickness,
+            closed,
+            brush,
+        })
+    }
+
+    /// Draws an arc of a circle. Angles are in radians.
+    pub fn arc(
+        &mut self,
+        center: Vector2<f32>,
+        radius: f32,
+        angles: Range<f32>,
+        thickness: f32,
+        brush: Brush,
+    ) -> &mut Self {
+        self.push(DrawingCommand::Arc {
+            center,
+            radius,
+            angles,
+            segments: 32,
+            thickness,
+            brush,
+        })
+    }
+
+    /// Draws a solid circle.
+    pub fn circle(&mut self, center: Vector2<f32>, radius: f32, brush: Brush) -> &mut Self {
+        self.push(DrawingCommand::Circle {
+            center,
+            radius,
+            segments: 32,
+            brush,
+        })
+    }
+
+    /// Draws a solid convex polygon.
+    pub fn polygon(&mut self, points: Vec<Vector2<f32>>, brush: Brush) -> &mut Self {
+        self.push(DrawingCommand::Polygon { points, brush })
+    }
+
+    /// Draws a rectangle filled with the entire texture.
+    pub fn texture(&mut self, rect: Rect<f32>, texture: UntypedResource) -> &mut Self {
+        self.push(DrawingCommand::Texture {
+            rect,
+            texture,
+            tex_coords: [
+                Vector2::new(0.0, 0.0),
+                Vector2::new(1.0, 0.0),
+                Vector2::new(1.0, 1.0),
+                Vector2::new(0.0, 1.0),
+            ],
+            brush: Brush::Solid(Color::WHITE),
+        })
+    }
+
+    /// Draws a single line of text using the given font.
+    pub fn text(
+        &mut self,
+        position: Vector2<f32>,
+        text: &str,
+        font: FontResource,
+        font_size: f32,
+        brush: Brush,
+    ) -> &mut Self {
+        let mut text = FormattedTextBuilder::new(font)
+            .with_text(text.to_owned())
+            .with_font_size(font_size)
+            .with_brush(brush)
+            .with_constraint(Vector2::new(f32::INFINITY, f32::INFINITY))
+            .build();
+        text.build();
+        self.push(DrawingCommand::Text { position, text })
+    }
+}
+
+impl Control for DrawingArea {
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let origin = self.widget.bounding_rect().position;
+        let clip_bounds = self.clip_bounds();
+
+        // Brush of the geometry, that is not yet committed.
+        let mut pending_brush: Option<&Brush> = None;
+
+        for command in self.commands.iter() {
+            let brush = command.brush();
+            if let Some(pending) = pending_brush {
+                if brush != Some(pending) {
+                    drawing_context.commit(
+                        clip_bounds,
+                        pending.clone(),
+                        CommandTexture::None,
+                        None,
+                    );
+                }
+            }
+            pending_brush = brush;
+
+            match command {
+                DrawingCommand::Line {
+                    begin,
+                    end,
+                    thickness,
+                    ..
+                } => {
+                    drawing_context.push_line(origin + begin, origin + end, *thickness);
+                }
+                DrawingCommand::Polyline {
+                    points,
+                    thickness,
+                    closed,
+                    ..
+                } => {
+                    for pair in points.windows(2) {
+                        drawing_context.push_line(origin + pair[0], origin + pair[1], *thickness);
+                    }
+                    if *closed && points.len() > 2 {
+                        drawing_context.push_line(
+                            origin + points[points.len() - 1],
+                            origin + points[0],
+                            *thickness,
+                        );
+                    }
+                }
+                DrawingCommand::Arc {
+                    center,
+                    radius,
+                    angles,
+                    segments,
+                    thickness,
+                    ..
+                } => {
+                    drawing_context.push_arc(
+                        origin + center,
+                        *radius,
+                        angles.clone(),
+                        *segments,
+                        *thickness,
+                    );
+                }
+                DrawingCommand::Circle {
+                    center,
+                    radius,
+                    segments,
+                    ..
+                } => {
+                    drawing_context.push_circle(origin + center, *radius, *segments, Color::WHITE);
+                }
+                DrawingCommand::Polygon { points, .. } => {
+                    // Triangle fan, works only for convex polygons.
+                    for i in 1..points.len().saturating_sub(1) {
+                        drawing_context.push_triangle_filled([
+                            origin + points[0],
+                            origin + points[i],
+                            origin + points[i + 1],
+                        ]);
+                    }
+                }
+                DrawingCommand::Texture {
+                    rect,
+                    texture,
+                    tex_coords,
+                    brush,
+                } => {
+                    drawing_context.push_rect_filled(
+                        &Rect::new(origin.x + rect.x(), origin.y + rect.y(), rect.w(), rect.h()),
+                        Some(tex_coords),
+                    );
+                    drawing_context.commit(
+                        clip_bounds,
+                        brush.clone(),
+                        CommandTexture::Texture(texture.clone()),
+                        None,
+                    );
+                }
+                DrawingCommand::Text { position, text } => {
+                    drawing_context.draw_text(clip_bounds, origin + position, text);
+                }
+            }
+        }
+
+        if let Some(pending) = pending_brush {
+            drawing_context.commit(clip_bounds, pending.clone(), CommandTexture::None, None);
+        }
+    }
+
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+    }
+}
+
+/// Drawing area builder creates [`DrawingArea`] instances and adds them to the user interface.
+pub struct DrawingAreaBuilder {
+    widget_builder: WidgetBuilder,
+    commands: Vec<DrawingCommand>,
+}
+
+impl DrawingAreaBuilder {
+    /// Creates new drawing area builder.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            commands: Default::default(),
+        }
+    }
+
+    /// Sets the initial set of drawing commands.
+    pub fn with_commands(mut self, commands: Vec<DrawingCommand>) -> Self {
+        self.commands = commands;
+        self
+    }
+
+    /// Builds the drawing area widget.
+    pub fn build_node(self) -> UiNode {
+        UiNode::new(DrawingArea {
+            widget: self.widget_builder.build(),
+            commands: self.commands,
+        })
+    }
+
+    /// Finishes drawing area building and adds it to the user interface.
+    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        ctx.add_node(self.build_node())
+    }
+}
@@ -2,6 +2,7 @@
 //! list to select its current item. It is build using composition with standard list view.
 
 use crate::{
+    accessibility::{self, AccessibilityNode, AccessibilityRole},
     border::BorderBuilder,
     core::{algebra::Vector2, pool::Handle},
     core::{reflect::prelude::*, type_traits::prelude::*, visitor::prelude::*},
@@ -189,6 +190,12 @@ impl Control for DropdownList {
             }
         }
     }
+
+    fn accessibility(&self, ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::ComboBox;
+        node.value = accessibility::find_text(ui, self.current);
+        node.presentational_children = true;
+    }
 }
 
 impl DropdownList {
@@ -1,8 +1,8 @@
 #![allow(clippy::unnecessary_to_owned)] // false-positive
 
 use crate::core::{
-    algebra::Vector2, rectpack::RectPacker, reflect::prelude::*, uuid::Uuid, uuid_provider,
-    visitor::prelude::*, TypeUuidProvider,
+    algebra::Vector2, math::Rect, rectpack::RectPacker, reflect::prelude::*, uuid::Uuid,
+    uuid_provider, visitor::prelude::*, TypeUuidProvider,
 };
 use fxhash::FxHashMap;
 use fyrox_resource::untyped::UntypedResource;
@@ -20,6 +20,15 @@ use std::{
 
 pub mod loader;
 
+/// Size (in pixels) of empty space around each glyph in a page.
+const GLYPH_BORDER: usize = 2;
+
+/// Initial size of a page, pages grow dynamically up to the page size of the font.
+const INITIAL_PAGE_SIZE: usize = 256;
+
+/// Default maximum amount of pages of a font.
+pub const DEFAULT_MAX_PAGES: usize = 16;
+
 #[derive(Debug)]
 pub struct FontGlyph {
     pub top: f32,
@@ -29,14 +38,24 @@ pub struct FontGlyph {
     pub bitmap_width: usize,
     pub bitmap_height: usize,
     pub page_index: usize,
+    /// Location of the glyph in the page (in pixels).
+    pub placement: Rect<usize>,
 }
 
-/// Page is a storage for rasterized glyphs.
+/// Page is a storage for rasterized glyphs. Pages are shared across all sizes of a font, every page
+/// starts small and grows when there's no more space for new glyphs.
 pub struct Page {
     pub pixels: Vec<u8>,
     pub texture: Option<UntypedResource>,
     pub rect_packer: RectPacker<usize>,
     pub modified: bool,
+    /// Current width and height of the page.
+    pub size: usize,
+    /// A value of font's access counter when a glyph from the page was used last time.
+    pub last_access: u64,
+    /// Amount of pending draw commands, that use the page. Pinned pages are never grown or cleared,
+    /// because it would invalidate texture coordinates of the glyphs in the commands.
+    pub pin_count: usize,
 }
 
 impl Debug for Page {
@@ -45,138 +64,60 @@ impl Debug for Page {
             .field("Pixels", &self.pixels)
             .field("Texture", &self.texture)
             .field("Modified", &self.modified)
+            .field("Size", &self.size)
+            .field("PinCount", &self.pin_count)
             .finish()
     }
 }
 
-/// Atlas is a storage for glyphs of a particular size, each atlas could have any number of pages to
-/// store the rasterized glyphs.
-#[derive(Default, Debug)]
-pub struct Atlas {
-    pub glyphs: Vec<FontGlyph>,
-    pub char_map: FxHashMap<char, usize>,
-    pub pages: Vec<Page>,
-}
-
-impl Atlas {
-    fn glyph(
-        &mut self,
-        font: &fontdue::Font,
-        unicode: char,
-        height: FontHeight,
-        page_size: usize,
-    ) -> Option<&FontGlyph> {
-        let border = 2;
+impl Page {
+    fn new(size: usize) -> Self {
+        Self {
+            pixels: vec![0; size * size],
+            texture: None,
+            rect_packer: RectPacker::new(size, size),
+            modified: true,
+            size,
+            last_access: 0,
+            pin_count: 0,
+        }
+    }
 
-        match self.char_map.get(&unicode) {
-            Some(glyph_index) => {
-                return self.glyphs.get(*glyph_index);
-            }
-            None => {
-                // Char might be missing, because it wasn't requested earlier. Try to find
-                // it in the inner font and render/pack it.
-
-                if let Some(char_index) = font.chars().get(&unicode) {
-                    let (metrics, glyph_raster) =
-                        font.rasterize_indexed(char_index.get(), height.0);
-
-                    // Find a page, that is capable to fit the new character or create a new
-                    // page and put the character there.
-                    let mut placement_info =
-                        self.pages
-                            .iter_mut()
-                            .enumerate()
-                            .find_map(|(page_index, page)| {
-                                page.rect_packer
-                                    .find_free(metrics.width + border, metrics.height + border)
-                                    .map(|bounds| (page_index, bounds))
-                            });
-
-                    // No space for the character in any of the existing pages, create a new page.
-                    if placement_info.is_none() {
-                        let mut page = Page {
-                            pixels: vec![0; page_size * page_size],
-                            texture: None,
-                            rect_packer: RectPacker::new(page_size, page_size),
-                            modified: true,
-                        };
-
-                        let page_index = self.pages.len();
-
-                        match page
-                            .rect_packer
-                            .find_free(metrics.width + border, metrics.height + border)
-                        {
-                            Some(bounds) => {
-                                placement_info = Some((page_index, bounds));
-
-                                self.pages.push(page);
-                            }
-                            None => {
-                                // No free space in the given page size (requested glyph is too big).
-                                return None;
-                            }
-                        }
-                    }
+    fn find_free(&mut self, width: usize, height: usize) -> Option<Rect<usize>> {
+        self.rect_packer
+            .find_free(width + GLYPH_BORDER, height + GLYPH_BORDER)
+            .map(|bounds| {
+                Rect::new(
+                    bounds.x() + GLYPH_BORDER / 2,
+                    bounds.y() + GLYPH_BORDER / 2,
+                    width,
+                    height,
+                )
+            })
+    }
+}
 
-                    let (page_index, placement_rect) = placement_info?;
-                    let page = &mut self.pages[page_index];
-                    let glyph_index = self.glyphs.len();
-
-                    // Raise a flag to notify users that the content of the page has changed, and
-                    // it should be re-uploaded to GPU (if needed).
-                    page.modified = true;
-
-                    let mut glyph = FontGlyph {
-                        left: metrics.xmin as f32,
-                        top: metrics.ymin as f32,
-                        advance: metrics.advance_width,
-                        tex_coords: Default::default(),
-                        bitmap_width: metrics.width,
-                        bitmap_height: metrics.height,
-                        page_index,
-                    };
-
-                    let k = 1.0 / page_size as f32;
-
-                    let bw = placement_rect.w().saturating_sub(border);
-                    let bh = placement_rect.h().saturating_sub(border);
-                    let bx = placement_rect.x() + border / 2;
-                    let by = placement_rect.y() + border / 2;
-
-                    let tw = bw as f32 * k;
-                    let th = bh as f32 * k;
-                    let tx = bx as f32 * k;
-                    let ty = by as f32 * k;
-
-                    glyph.tex_coords[0] = Vector2::new(tx, ty);
-                    glyph.tex_coords[1] = Vector2::new(tx + tw, ty);
-                    glyph.tex_coords[2] = Vector2::new(tx + tw, ty + th);
-                    glyph.tex_coords[3] = Vector2::new(tx, ty + th);
-
-                    let row_end = by + bh;
-                    let col_end = bx + bw;
-
-                    // Copy glyph pixels to the atlas pixels
-                    for (src_row, row) in (by..row_end).enumerate() {
-                        for (src_col, col) in (bx..col_end).enumerate() {
-                            page.pixels[row * page_size + col] =
-                                glyph_raster[src_row * bw + src_col];
-                        }
-                    }
+fn tex_coords(placement: Rect<usize>, page_size: usize) -> [Vector2<f32>; 4] {
+    let k = 1.0 / page_size as f32;
 
-                    self.glyphs.push(glyph);
+    let tw = placement.w() as f32 * k;
+    let th = placement.h() as f32 * k;
+    let tx = placement.x() as f32 * k;
+    let ty = placement.y() as f32 * k;
 
-                    // Map the new glyph to its unicode position.
-                    self.char_map.insert(unicode, glyph_index);
+    [
+        Vector2::new(tx, ty),
+        Vector2::new(tx + tw, ty),
+        Vector2::new(tx + tw, ty + th),
+        Vector2::new(tx, ty + th),
+    ]
+}
 
-                    self.glyphs.get(glyph_index)
-                } else {
-                    None
-                }
-            }
-        }
-    }
+/// Atlas is a storage for glyphs of a particular size. Rasterized glyphs are stored in the pages of
+/// the font, which are shared across all atlases of the font.
+#[derive(Default, Debug)]
+pub struct Atlas {
+    pub glyphs: FxHashMap<char, FontGlyph>,
 }
 
 #[derive(Default, Debug, Reflect, Visit)]
@@ -188,6 +129,15 @@ pub struct Font {
     pub atlases: FxHashMap<FontHeight, Atlas>,
     #[visit(skip)]
     pub page_size: usize,
+    #[visit(skip)]
+    pub pages: Vec<Page>,
+    /// Maximum amount of pages. When every page is full, the least recently used page is cleared
+    /// and its glyphs are rasterized again on demand. The limit could be exceeded, if every page is
+    /// pinned (see [`Font::pin_page`]).
+    #[visit(skip)]
+    pub max_pages: usize,
+    #[visit(skip)]
+    access_counter: u64,
 }
 
 uuid_provider!(Font = "692fec79-103a-483c-bb0b-9fc3a349cb48");
@@ -258,6 +208,9 @@ impl Font {
             inner: Some(fontdue_font),
             atlases: Default::default(),
             page_size,
+            pages: Default::default(),
+            max_pages: DEFAULT_MAX_PAGES,
+            access_counter: 0,
         })
     }
 
@@ -274,29 +227,204 @@ impl Font {
     }
 
     /// Tries to get a glyph at the given unicode position of the given height. If there's no rendered
-    /// glyph, this method tries to render the glyph and put into a suitable atlas (see [`Atlas`] docs
+    /// glyph, this method tries to render the glyph and put into a suitable page (see [`Page`] docs
     /// for more info). If the given unicode position has no representation in the font, [`None`] will
     /// be returned. If the requested size of the glyph is too big to fit into the page size of the
-    /// font, [`None`] will be returned. Keep in mind, that this method is free to create as many atlases
-    /// with any number of pages in them. Each atlas corresponds to a particular glyph size, each glyph
-    /// in the atlas could be rendered at any page in the atlas.
+    /// font, [`None`] will be returned.
+    ///
+    /// Keep in mind, that location of a glyph in the pages could change over time: pages are growing
+    /// when they're full (which moves glyphs in them) and the least recently used pages are cleared
+    /// when the font has reached its maximum amount of pages. Do not cache texture coordinates and page
+    /// indices of glyphs, request the glyphs when they're needed. Use [`Self::pin_page`] to keep glyphs
+    /// of a page in place while they're used (for example, until draw commands are rendered).
     #[inline]
     pub fn glyph(&mut self, unicode: char, height: f32) -> Option<&FontGlyph> {
-        self.atlases
-            .entry(FontHeight(height))
-            .or_insert_with(|| Atlas {
-                glyphs: Default::default(),
-                char_map: Default::default(),
-                pages: Default::default(),
-            })
-            .glyph(
-                self.inner
-                    .as_ref()
-                    .expect("Font reader must be initialized!"),
-                unicode,
-                FontHeight(height),
-                self.page_size,
-            )
+        self.access_counter += 1;
+        let access_counter = self.access_counter;
+        let height = FontHeight(height);
+
+        let page_index = self
+            .atlases
+            .get(&height)
+            .and_then(|atlas| atlas.glyphs.get(&unicode))
+            .map(|glyph| glyph.page_index);
+        if let Some(page_index) = page_index {
+            self.pages[page_index].last_access = access_counter;
+            return self
+                .atlases
+                .get(&height)
+                .and_then(|atlas| atlas.glyphs.get(&unicode));
+        }
+
+        // Glyph might be missing, because it wasn't requested earlier. Try to find it in the inner
+        // font and render/pack it.
+        let font = self
+            .inner
+            .as_ref()
+            .expect("Font reader must be initialized!");
+        let char_index = font.chars().get(&unicode)?;
+        let (metrics, glyph_raster) = font.rasterize_indexed(char_index.get(), height.0);
+
+        let (page_index, placement) = self.allocate(metrics.width, metrics.height)?;
+        let page = &mut self.pages[page_index];
+
+        // Raise a flag to notify users that the content of the page has changed, and it should be
+        // re-uploaded to GPU (if needed).
+        page.modified = true;
+        page.last_access = access_counter;
+
+        // Copy glyph pixels to the page pixels.
+        for (src_row, row) in (placement.y()..placement.y() + placement.h()).enumerate() {
+            for (src_col, col) in (placement.x()..placement.x() + placement.w()).enumerate() {
+                page.pixels[row * page.size + col] =
+                    glyph_raster[src_row * metrics.width + src_col];
+            }
+        }
+
+        let glyph = FontGlyph {
+            left: metrics.xmin as f32,
+            top: metrics.ymin as f32,
+            advance: metrics.advance_width,
+            tex_coords: tex_coords(placement, page.size),
+            bitmap_width: metrics.width,
+            bitmap_height: metrics.height,
+            page_index,
+            placement,
+        };
+
+        let glyphs = &mut self.atlases.entry(height).or_default().glyphs;
+        glyphs.insert(unicode, glyph);
+        glyphs.get(&unicode)
+    }
+
+    /// Finds a place for a glyph of the given size. At first, it tries to find free space in existing
+    /// pages, then grows the pages, then creates a new page and finally clears the least recently used
+    /// page.
+    fn allocate(&mut self, width: usize, height: usize) -> Option<(usize, Rect<usize>)> {
+        if width + GLYPH_BORDER > self.page_size || height + GLYPH_BORDER > self.page_size {
+            // Requested glyph is too big.
+            return None;
+        }
+
+        for (page_index, page) in self.pages.iter_mut().enumerate() {
+            if let Some(placement) = page.find_free(width, height) {
+                return Some((page_index, placement));
+            }
+        }
+
+        for page_index in 0..self.pages.len() {
+            while self.pages[page_index].pin_count == 0
+                && self.pages[page_index].size < self.page_size
+            {
+                self.grow_page(page_index);
+                if let Some(placement) = self.pages[page_index].find_free(width, height) {
+                    return Some((page_index, placement));
+                }
+            }
+        }
+
+        let lru_page_index = self
+            .pages
+            .iter()
+            .enumerate()
+            .filter(|(_, page)| page.pin_count == 0)
+            .min_by_key(|(_, page)| page.last_access)
+            .map(|(page_index, _)| page_index);
+
+        // Every page is pinned, exceed the limit instead of breaking the pinned pages. Extra pages
+        // will be reused when they're unpinned.
+        if self.pages.len() < self.max_pages.max(1) || lru_page_index.is_none() {
+            let mut page = Page::new(INITIAL_PAGE_SIZE.min(self.page_size));
+            loop {
+                if let Some(placement) = page.find_free(width, height) {
+                    self.pages.push(page);
+                    return Some((self.pages.len() - 1, placement));
+                }
+                if page.size >= self.page_size {
+                    return None;
+                }
+                page = Page::new((page.size * 2).min(self.page_size));
+            }
+        }
+
+        let page_index = lru_page_index?;
+        self.clear_page(page_index);
+        self.pages[page_index]
+            .find_free(width, height)
+            .map(|placement| (page_index, placement))
+    }
+
+    /// Doubles the size of the page and re-packs its glyphs.
+    fn grow_page(&mut self, page_index: usize) {
+        let old_page = &self.pages[page_index];
+        let mut new_page = Page::new((old_page.size * 2).min(self.page_size));
+        new_page.last_access = old_page.last_access;
+
+        for atlas in self.atlases.values_mut() {
+            let mut glyphs = atlas
+                .glyphs
+                .iter_mut()
+                .filter(|(_, glyph)| glyph.page_index == page_index)
+                .collect::<Vec<_>>();
+
+            // Taller glyphs first for tighter packing.
+            glyphs.sort_by_key(|(_, glyph)| std::cmp::Reverse(glyph.placement.h()));
+
+            let mut lost = Vec::new();
+            for (unicode, glyph) in glyphs {
+                let old = glyph.placement;
+                match new_page.find_free(old.w(), old.h()) {
+                    Some(placement) => {
+                        for row in 0..old.h() {
+                            let src = (old.y() + row) * old_page.size + old.x();
+                            let dest = (placement.y() + row) * new_page.size + placement.x();
+                            new_page.pixels[dest..dest + old.w()]
+                                .copy_from_slice(&old_page.pixels[src..src + old.w()]);
+                        }
+                        glyph.placement = placement;
+                        glyph.tex_coords = tex_coords(placement, new_page.size);
+                    }
+                    // Should never happen, since the new page is bigger. Anyway, the glyph will be
+                    // rasterized again when requested.
+                    None => lost.push(*unicode),
+                }
+            }
+
+            for unicode in lost {
+                atlas.glyphs.remove(&unicode);
+            }
+        }
+
+        self.pages[page_index] = new_page;
+    }
+
+    /// Pins the page, so its glyphs will stay in place until the page is unpinned. Every call must be
+    /// paired with [`Self::unpin_page`].
+    pub fn pin_page(&mut self, page_index: usize) {
+        if let Some(page) = self.pages.get_mut(page_index) {
+            page.pin_count += 1;
+        }
+    }
+
+    /// Unpins the page, that was previously pinned by [`Self::pin_page`].
+    pub fn unpin_page(&mut self, page_index: usize) {
+        if let Some(page) = self.pages.get_mut(page_index) {
+            page.pin_count = page.pin_count.saturating_sub(1);
+        }
+    }
+
+    /// Removes every glyph from the page.
+    fn clear_page(&mut self, page_index: usize) {
+        for atlas in self.atlases.values_mut() {
+            atlas
+                .glyphs
+                .retain(|_, glyph| glyph.page_index != page_index);
+        }
+
+        let page = &mut self.pages[page_index];
+        page.rect_packer.clear();
+        page.pixels.fill(0);
+        page.modified = true;
     }
 
     #[inline]
@@ -356,3 +484,67 @@ impl FontBuilder {
         Font::from_memory(data, self.page_size)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::font::Font;
+
+    #[test]
+    fn test_page_growth_and_eviction() {
+        let mut font =
+            Font::from_memory(include_bytes!("./built_in_font.ttf").to_vec(), 512).unwrap();
+        font.max_pages = 1;
+
+        let first = font.glyph('A', 32.0).unwrap().placement;
+        assert_eq!(font.pages.len(), 1);
+        assert_eq!(font.pages[0].size, 256);
+
+        // Fill the page with glyphs of different sizes, it must grow up to the page size of the font.
+        for height in [32.0, 48.0, 64.0] {
+            for unicode in ('A'..='Z').chain('a'..='z') {
+                font.glyph(unicode, height);
+            }
+        }
+        assert_eq!(font.pages.len(), 1);
+        assert_eq!(font.pages[0].size, 512);
+
+        // Glyphs must still be available, but at new location.
+        let glyph = font.glyph('A', 32.0).unwrap();
+        assert_eq!(glyph.placement.w(), first.w());
+        assert_eq!(glyph.placement.h(), first.h());
+
+        // Overflow the only page - old glyphs must be evicted instead of failing.
+        for unicode in ('A'..='Z').chain('a'..='z') {
+            assert!(font.glyph(unicode, 128.0).is_some());
+        }
+        assert_eq!(font.pages.len(), 1);
+    }
+
+    #[test]
+    fn test_pinned_pages_are_not_modified() {
+        let mut font =
+            Font::from_memory(include_bytes!("./built_in_font.ttf").to_vec(), 256).unwrap();
+        font.max_pages = 1;
+
+        let glyph = font.glyph('A', 32.0).unwrap();
+        let (page_index, tex_coords) = (glyph.page_index, glyph.tex_coords);
+        font.pin_page(page_index);
+
+        // The only page is pinned, so new pages must be created instead of clearing it.
+        for unicode in ('A'..='Z').chain('a'..='z') {
+            assert!(font.glyph(unicode, 128.0).is_some());
+        }
+        assert!(font.pages.len() > 1);
+        let glyph = font.glyph('A', 32.0).unwrap();
+        assert_eq!(glyph.page_index, page_index);
+        assert_eq!(glyph.tex_coords, tex_coords);
+
+        // Unpinned page could be reused again.
+        font.unpin_page(page_index);
+        let page_count = font.pages.len();
+        for unicode in ('A'..='Z').chain('a'..='z') {
+            assert!(font.glyph(unicode, 100.0).is_some());
+        }
+        assert_eq!(font.pages.len(), page_count);
+    }
+}
@@ -13,6 +13,9 @@ pub struct TextGlyph {
     pub bounds: Rect<f32>,
     pub tex_coords: [Vector2<f32>; 4],
     pub atlas_page_index: usize,
+    /// A character that is represented by the glyph. It is used to fetch actual location of the glyph
+    /// in the font pages, since it could change after the text was formatted.
+    pub unicode: char,
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -458,6 +461,7 @@ impl FormattedText {
                             bounds: rect,
                             tex_coords: glyph.tex_coords,
                             atlas_page_index: glyph.page_index,
+                            unicode: character,
                         };
                         self.glyphs.push(text_glyph);
 
@@ -475,6 +479,7 @@ impl FormattedText {
                             bounds: rect,
                             tex_coords: [Vector2::default(); 4],
                             atlas_page_index: 0,
+                            unicode: character,
                         });
                         cursor.x += rect.w();
                     }
@@ -4,6 +4,7 @@
 #![warn(missing_docs)]
 
 use crate::{
+    accessibility::{AccessibilityNode, AccessibilityRole},
     brush::Brush,
     color::draw_checker_board,
     core::{algebra::Vector2, color::Color, math::Rect, pool::Handle},
@@ -246,6 +247,10 @@ impl Control for Image {
             }
         }
     }
+
+    fn accessibility(&self, _ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::Image;
+    }
 }
 
 /// Image builder is used to create [`Image`] widget instances and register them in the user interface.
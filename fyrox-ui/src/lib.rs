@@ -105,9 +105,13 @@
 //! * [`crate::text::Text`]: The Text widget is used to display a string to the user.
 //! * [`crate::image::Image`]: The Image widget is used to display a pixel image to the user.
 //! * [`crate::vector_image::VectorImage`]: The Vector Image is used to render vector instructions as a graphical element.
+//! * [`crate::drawing_area::DrawingArea`]: The Drawing Area provides immediate-mode drawing API (lines, arcs, polygons, textures,
+//!   text) for graphs, charts and custom HUD elements.
 //! * [`crate::rect::RectEditor`]: The Rect allows you to specify numeric values for X, Y, Width, and Height of a rectangle.
 //! * [`crate::progress_bar::ProgressBar`]: The Progress Bar shows a bar whose fill state can be adjusted to indicate visually how full
 //! something is, for example how close to 100% is a loading process.
+//! * [`crate::plot::Plot`]: The Plot shows how some values (frame time, draw calls, etc.) change over time as lines or bars, it
+//!   is useful for in-game performance HUDs.
 //! * [`crate::decorator::Decorator`]: The Decorator is used to style any widget. It has support for different styles depending on various
 //! events like mouse hover or click.
 //! * [`crate::border::Border`]: The Border widget is used in conjunction with the Decorator widget to provide configurable boarders to
@@ -133,6 +137,8 @@
 //! * [`crate::file_browser::FileBrowser`]: The File Browser is a tree view of the file system allowing the user to select a file or folder.
 //! * [`crate::curve::CurveEditor`]: The CurveEditor allows editing parametric curves - adding points, and setting up transitions (constant,
 //! linear, cubic) between them.
+//! * [`crate::color::gradient::ColorGradientEditor`]: The Color Gradient Editor allows editing color gradients - adding,
+//!   moving and removing color points. Use [`crate::color::gradient::ColorGradientEditorBinding`] to bind it to a gradient.
 //! * [`crate::inspector::Inspector`]: The Inspector automatically creates and handles the input of UI elements based on a populated Inspector
 //! Context given to it allowing the user to adjust values of a variety of models without manually creating UI's for each type.
 //!
@@ -183,6 +189,7 @@ pub use copypasta;
 pub use fyrox_core as core;
 use message::TouchPhase;
 
+pub mod accessibility;
 mod alignment;
 pub mod bit;
 pub mod border;
@@ -197,6 +204,7 @@ pub mod curve;
 pub mod decorator;
 pub mod dock;
 pub mod draw;
+pub mod drawing_area;
 pub mod dropdown_list;
 pub mod expander;
 pub mod file_browser;
@@ -215,6 +223,7 @@ pub mod nine_patch;
 mod node;
 pub mod numeric;
 pub mod path;
+pub mod plot;
 pub mod popup;
 pub mod progress_bar;
 pub mod range;
@@ -234,11 +243,13 @@ pub mod utils;
 pub mod uuid;
 pub mod vec;
 pub mod vector_image;
+pub mod virtual_cursor;
 pub mod widget;
 pub mod window;
 pub mod wrap_panel;
 
 use crate::{
+    accessibility::{AccessibilityAction, AccessibilitySettings, AccessibilityTree},
     brush::Brush,
     canvas::Canvas,
     constructor::WidgetConstructorContainer,
@@ -676,6 +687,9 @@ pub struct UserInterface {
     #[reflect(hidden)]
     double_click_entries: FxHashMap<MouseButton, DoubleClickEntry>,
     pub double_click_time_slice: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    accessibility_settings: AccessibilitySettings,
 }
 
 impl Clone for UserInterface {
@@ -718,6 +732,7 @@ impl Clone for UserInterface {
             default_font: self.default_font.clone(),
             double_click_entries: self.double_click_entries.clone(),
             double_click_time_slice: self.double_click_time_slice,
+            accessibility_settings: self.accessibility_settings,
         }
     }
 }
@@ -862,6 +877,7 @@ impl UserInterface {
             default_font: BUILT_IN_FONT.clone(),
             double_click_entries: Default::default(),
             double_click_time_slice: 0.5, // 500 ms is standard in most operating systems.
+            accessibility_settings: Default::default(),
         };
         ui.root_canvas = ui.add_node(UiNode::new(Canvas {
             widget: WidgetBuilder::new().build(),
@@ -979,6 +995,56 @@ impl UserInterface {
         self.screen_size = screen_size;
     }
 
+    /// Returns current accessibility settings of the user interface.
+    pub fn accessibility_settings(&self) -> &AccessibilitySettings {
+        &self.accessibility_settings
+    }
+
+    /// Sets new accessibility settings of the user interface (high-contrast mode, reduced motion, etc.)
+    /// and notifies every widget about the change. See [`AccessibilitySettings`] docs for more info.
+    pub fn set_accessibility_settings(&mut self, settings: AccessibilitySettings) {
+        self.accessibility_settings = settings;
+        for node in self.nodes.iter_mut() {
+            node.on_accessibility_settings_changed(&settings);
+        }
+    }
+
+    /// Collects accessibility information (roles, labels, states) of every visible widget, that
+    /// should be exposed to assistive technologies. See [`AccessibilityTree`] docs for more info.
+    pub fn accessibility_tree(&self) -> AccessibilityTree {
+        accessibility::build_tree(self)
+    }
+
+    /// Performs an action, that was requested by an assistive technology.
+    pub fn perform_accessibility_action(
+        &mut self,
+        handle: Handle<UiNode>,
+        action: AccessibilityAction,
+    ) {
+        let Some(node) = self.try_get_node(handle) else {
+            return;
+        };
+
+        match action {
+            AccessibilityAction::Focus => self.request_focus(handle),
+            AccessibilityAction::Activate => {
+                let position = node.screen_bounds().center();
+                self.send_message(WidgetMessage::mouse_down(
+                    handle,
+                    MessageDirection::FromWidget,
+                    position,
+                    MouseButton::Left,
+                ));
+                self.send_message(WidgetMessage::mouse_up(
+                    handle,
+                    MessageDirection::FromWidget,
+                    position,
+                    MouseButton::Left,
+                ));
+            }
+        }
+    }
+
     fn handle_layout_events(&mut self) {
         fn invalidate_recursive_up(
             nodes: &Pool<UiNode, WidgetContainer>,
@@ -1106,6 +1172,10 @@ impl UserInterface {
             }
         }
 
+        if self.accessibility_settings.high_contrast {
+            accessibility::apply_high_contrast(&mut self.drawing_context);
+        }
+
         // Debug info rendered on top of other.
         if self.visual_debug {
             if self.picked_node.is_some() {
@@ -2550,6 +2620,7 @@ impl UserInterface {
             self.preview_set.insert(node_handle);
         }
         node.handle = node_handle;
+        node.on_accessibility_settings_changed(&self.accessibility_settings);
         node_handle
     }
 
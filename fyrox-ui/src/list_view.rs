@@ -4,6 +4,7 @@
 #![warn(missing_docs)]
 
 use crate::{
+    accessibility::{AccessibilityNode, AccessibilityRole},
     border::BorderBuilder,
     brush::Brush,
     core::{
@@ -339,6 +340,10 @@ impl Control for ListViewItem {
             }
         }
     }
+
+    fn accessibility(&self, _ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::ListItem;
+    }
 }
 
 uuid_provider!(ListView = "5832a643-5bf9-4d84-8358-b4c45bb440e8");
@@ -432,6 +437,10 @@ impl Control for ListView {
             }
         }
     }
+
+    fn accessibility(&self, _ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::List;
+    }
 }
 
 /// List view builder is used to create [`ListView`] widget instances and add them to a user interface.
@@ -4,6 +4,7 @@
 #![warn(missing_docs)]
 
 use crate::{
+    accessibility::{self, AccessibilityNode, AccessibilityRole},
     border::BorderBuilder,
     brush::Brush,
     core::{
@@ -246,6 +247,10 @@ impl Control for Menu {
             }
         }
     }
+
+    fn accessibility(&self, _ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::Menu;
+    }
 }
 
 /// A set of possible placements of a popup with items of a menu item.
@@ -567,6 +572,12 @@ impl Control for MenuItem {
             }
         }
     }
+
+    fn accessibility(&self, ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::MenuItem;
+        node.label = accessibility::find_text(ui, self.handle);
+        node.presentational_children = true;
+    }
 }
 
 /// Menu builder creates [`Menu`] widgets and adds them to the user interface.
@@ -12,6 +12,7 @@ use crate::{
     curve::CurveEditor,
     decorator::Decorator,
     dock::{DockingManager, Tile},
+    drawing_area::DrawingArea,
     dropdown_list::DropdownList,
     expander::Expander,
     file_browser::{FileBrowser, FileSelector, FileSelectorField},
@@ -25,6 +26,7 @@ use crate::{
     nine_patch::NinePatch,
     numeric::NumericUpDown,
     path::PathEditor,
+    plot::Plot,
     progress_bar::ProgressBar,
     range::RangeEditor,
     rect::RectEditor,
@@ -81,6 +83,7 @@ impl WidgetConstructorContainer {
         container.add::<Canvas>();
         container.add::<CheckBox>();
         container.add::<Decorator>();
+        container.add::<DrawingArea>();
         container.add::<DropdownList>();
         container.add::<Expander>();
         container.add::<Grid>();
@@ -128,6 +131,7 @@ impl WidgetConstructorContainer {
         container.add::<RectEditor<f64>>();
 
         container.add::<PathEditor>();
+        container.add::<Plot>();
         container.add::<ProgressBar>();
         container.add::<ScrollBar>();
         container.add::<ScrollPanel>();
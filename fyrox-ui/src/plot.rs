@@ -0,0 +1,396 @@
+//! Plot widget is used to show how some values change over time, for example it could be used to show
+//! frame time or renderer statistics in in-game performance HUDs. See [`Plot`] docs for more info and
+//! usage examples.
+
+#![warn(missing_docs)]
+
+use crate::{
+    brush::Brush,
+    core::{
+        algebra::Vector2, color::Color, math::Rect, pool::Handle, reflect::prelude::*,
+        type_traits::prelude::*, visitor::prelude::*,
+    },
+    define_constructor,
+    draw::{CommandTexture, Draw, DrawingContext},
+    font::FontResource,
+    formatted_text::FormattedTextBuilder,
+    message::{MessageDirection, UiMessage},
+    widget::{Widget, WidgetBuilder},
+    BuildContext, Control, UiNode, UserInterface,
+};
+use fyrox_core::uuid_provider;
+use std::{
+    collections::VecDeque,
+    ops::{Deref, DerefMut, Range},
+};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Defines how values of a series are shown.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum PlotKind {
+    /// Values are connected with lines.
+    #[default]
+    Line,
+    /// Each value is shown as a vertical bar.
+    Bar,
+}
+
+uuid_provider!(PlotKind = "c3d1a5f2-7b66-4c9e-8f0d-5e2a9b4c7d13");
+
+/// A named sequence of values of a plot.
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct PlotSeries {
+    /// Name of the series.
+    pub name: String,
+    /// Brush that is used to draw the series.
+    pub brush: Brush,
+    /// Kind of the series.
+    pub kind: PlotKind,
+    /// Values of the series, the oldest value goes first.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub values: VecDeque<f32>,
+}
+
+uuid_provider!(PlotSeries = "0a6f2d4e-91b8-4c57-b3e2-7d8c5f1a9e64");
+
+impl PlotSeries {
+    /// Creates a new empty series.
+    pub fn new(name: impl Into<String>, brush: Brush, kind: PlotKind) -> Self {
+        Self {
+            name: name.into(),
+            brush,
+            kind,
+            values: Default::default(),
+        }
+    }
+}
+
+/// A set of messages, that can be used to modify [`Plot`] widget state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlotMessage {
+    /// Adds a new value to the series with the given index. The oldest value will be removed if the
+    /// amount of values exceeds the capacity of the plot.
+    Push {
+        /// Index of the series.
+        series: usize,
+        /// The new value.
+        value: f32,
+    },
+    /// Removes every value from every series.
+    Clear,
+    /// Sets a fixed range of values or enables automatic scaling (if [`None`]).
+    Range(Option<Range<f32>>),
+}
+
+impl PlotMessage {
+    define_constructor!(
+        /// Creates [`PlotMessage::Push`] message.
+        PlotMessage:Push => fn push(series: usize, value: f32), layout: false
+    );
+    define_constructor!(
+        /// Creates [`PlotMessage::Clear`] message.
+        PlotMessage:Clear => fn clear(), layout: false
+    );
+    define_constructor!(
+        /// Creates [`PlotMessage::Range`] message.
+        PlotMessage:Range => fn range(Option<Range<f32>>), layout: false
+    );
+}
+
+/// Plot widget shows how some values change over time. It supports multiple series of values, each of
+/// which could be shown as lines or as bars. The plot keeps only a fixed amount of the latest values
+/// (rolling window) and automatically scales vertical axis to fit every value (unless a fixed range is
+/// specified). Maximum and minimum values of the vertical axis are shown at the left side of the plot.
+///
+/// ## Examples
+///
+/// The following example creates a plot of frame time and sends a new value to it each frame:
+///
+/// ```rust
+/// # use fyrox_ui::{
+/// #     brush::Brush,
+/// #     core::{color::Color, pool::Handle},
+/// #     message::MessageDirection,
+/// #     plot::{PlotBuilder, PlotKind, PlotMessage, PlotSeries},
+/// #     widget::WidgetBuilder,
+/// #     BuildContext, UiNode, UserInterface,
+/// # };
+/// #
+/// fn create_frame_time_plot(ctx: &mut BuildContext) -> Handle<UiNode> {
+///     PlotBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(100.0))
+///         .with_series(vec![PlotSeries::new(
+///             "Frame Time",
+///             Brush::Solid(Color::GREEN),
+///             PlotKind::Line,
+///         )])
+///         .with_capacity(120)
+///         .build(ctx)
+/// }
+///
+/// fn update_plot(ui: &UserInterface, plot: Handle<UiNode>, frame_time_ms: f32) {
+///     ui.send_message(PlotMessage::push(
+///         plot,
+///         MessageDirection::ToWidget,
+///         0,
+///         frame_time_ms,
+///     ));
+/// }
+/// ```
+#[derive(Default, Clone, Debug, Visit, Reflect, ComponentProvider)]
+pub struct Plot {
+    /// Base widget of the plot.
+    pub widget: Widget,
+    /// A set of series of the plot.
+    pub series: Vec<PlotSeries>,
+    /// Maximum amount of values in each series.
+    pub capacity: usize,
+    /// Fixed range of values. If [`None`], then the range will be calculated automatically.
+    pub range: Option<Range<f32>>,
+    /// Font that is used to show the range of values.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub font: FontResource,
+}
+
+crate::define_widget_deref!(Plot);
+
+uuid_provider!(Plot = "5b9e8c07-3d2a-4f61-a8c4-1e7f0b6d2a58");
+
+impl Plot {
+    /// Adds a new value to the series with the given index, removes the oldest value if the capacity
+    /// is exceeded.
+    pub fn push(&mut self, series: usize, value: f32) {
+        if let Some(series) = self.series.get_mut(series) {
+            series.values.push_back(value);
+            while series.values.len() > self.capacity {
+                series.values.pop_front();
+            }
+        }
+    }
+
+    /// Returns current range of values of the plot. It is either fixed range or a range that fits
+    /// every value of every series.
+    pub fn value_range(&self) -> Range<f32> {
+        if let Some(range) = self.range.clone() {
+            return range;
+        }
+
+        let mut min = f32::MAX;
+        let mut max = -f32::MAX;
+        for value in self.series.iter().flat_map(|s| s.values.iter()) {
+            min = min.min(*value);
+            max = max.max(*value);
+        }
+
+        if min > max {
+            0.0..1.0
+        } else {
+            // Bars must start from zero and the same is expected for performance counters.
+            min = min.min(0.0);
+            if max - min <= f32::EPSILON {
+                max = min + 1.0;
+            }
+            min..max
+        }
+    }
+}
+
+impl Control for Plot {
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.bounding_rect();
+        let clip_bounds = self.clip_bounds();
+
+        drawing_context.push_rect_filled(&bounds, None);
+        drawing_context.commit(
+            clip_bounds,
+            self.widget.background(),
+            CommandTexture::None,
+            None,
+        );
+
+        let range = self.value_range();
+        let range_size = range.end - range.start;
+        let step = bounds.w() / self.capacity.max(1) as f32;
+        let to_y = |value: f32| {
+            let k = ((value - range.start) / range_size).clamp(0.0, 1.0);
+            bounds.y() + bounds.h() * (1.0 - k)
+        };
+
+        for series in self.series.iter() {
+            // The latest value is always at the right side.
+            let x_offset = bounds.x() + bounds.w() - series.values.len() as f32 * step;
+
+            match series.kind {
+                PlotKind::Line => {
+                    let mut prev: Option<Vector2<f32>> = None;
+                    for (i, value) in series.values.iter().enumerate() {
+                        let point = Vector2::new(x_offset + (i as f32 + 0.5) * step, to_y(*value));
+                        if let Some(prev) = prev {
+                            drawing_context.push_line(prev, point, 1.0);
+                        }
+                        prev = Some(point);
+                    }
+                }
+                PlotKind::Bar => {
+                    let zero = to_y(0.0f32.clamp(range.start, range.end));
+                    for (i, value) in series.values.iter().enumerate() {
+                        let y = to_y(*value);
+                        drawing_context.push_rect_filled(
+                            &Rect::new(
+                                x_offset + i as f32 * step,
+                                y.min(zero),
+                                (step - 1.0).max(1.0),
+                                (y - zero).abs(),
+                            ),
+                            None,
+                        );
+                    }
+                }
+            }
+
+            drawing_context.commit(
+                clip_bounds,
+                series.brush.clone(),
+                CommandTexture::None,
+                None,
+            );
+        }
+
+        for (value, y) in [
+            (range.end, bounds.y()),
+            (range.start, bounds.y() + bounds.h() - 14.0),
+        ] {
+            let mut text = FormattedTextBuilder::new(self.font.clone())
+                .with_text(format!("{:.2}", value))
+                .with_brush(self.widget.foreground())
+                .with_constraint(Vector2::new(f32::INFINITY, f32::INFINITY))
+                .build();
+            text.build();
+            drawing_context.draw_text(clip_bounds, Vector2::new(bounds.x() + 2.0, y), &text);
+        }
+    }
+
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+
+        if message.destination() == self.handle && message.direction() == MessageDirection::ToWidget
+        {
+            if let Some(msg) = message.data::<PlotMessage>() {
+                match msg {
+                    &PlotMessage::Push { series, value } => self.push(series, value),
+                    PlotMessage::Clear => {
+                        for series in self.series.iter_mut() {
+                            series.values.clear();
+                        }
+                    }
+                    PlotMessage::Range(range) => self.range = range.clone(),
+                }
+            }
+        }
+    }
+}
+
+/// Plot builder creates [`Plot`] widget instances and adds them to the user interface.
+pub struct PlotBuilder {
+    widget_builder: WidgetBuilder,
+    series: Vec<PlotSeries>,
+    capacity: usize,
+    range: Option<Range<f32>>,
+    font: Option<FontResource>,
+}
+
+impl PlotBuilder {
+    /// Creates new plot builder.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            series: Default::default(),
+            capacity: 100,
+            range: None,
+            font: None,
+        }
+    }
+
+    /// Sets the desired set of series.
+    pub fn with_series(mut self, series: Vec<PlotSeries>) -> Self {
+        self.series = series;
+        self
+    }
+
+    /// Sets the desired maximum amount of values in each series.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets a fixed range of values, by default the range is calculated automatically.
+    pub fn with_range(mut self, range: Range<f32>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Sets the desired font of the labels.
+    pub fn with_font(mut self, font: FontResource) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Finishes plot building and adds it to the user interface.
+    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let plot = Plot {
+            widget: self
+                .widget_builder
+                .with_background(Brush::Solid(Color::from_rgba(0, 0, 0, 120)))
+                .build(),
+            series: self.series,
+            capacity: self.capacity,
+            range: self.range,
+            font: self.font.unwrap_or_else(|| ctx.default_font()),
+        };
+        ctx.add_node(UiNode::new(plot))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        brush::Brush,
+        plot::{Plot, PlotKind, PlotSeries},
+    };
+
+    #[test]
+    fn test_rolling_window_and_auto_scale() {
+        let mut plot = Plot {
+            series: vec![PlotSeries::new("A", Brush::default(), PlotKind::Line)],
+            capacity: 3,
+            ..Default::default()
+        };
+
+        assert_eq!(plot.value_range(), 0.0..1.0);
+
+        for value in [5.0, 1.0, 2.0, 3.0] {
+            plot.push(0, value);
+        }
+        assert_eq!(plot.series[0].values, [1.0, 2.0, 3.0]);
+        assert_eq!(plot.value_range(), 0.0..3.0);
+
+        plot.push(0, -1.0);
+        assert_eq!(plot.value_range(), -1.0..3.0);
+
+        plot.range = Some(0.0..10.0);
+        assert_eq!(plot.value_range(), 0.0..10.0);
+    }
+}
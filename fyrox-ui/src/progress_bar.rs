@@ -4,6 +4,7 @@
 #![warn(missing_docs)]
 
 use crate::{
+    accessibility::{AccessibilityNode, AccessibilityRole, NumericValue},
     border::BorderBuilder,
     brush::Brush,
     canvas::CanvasBuilder,
@@ -123,6 +124,16 @@ impl Control for ProgressBar {
             }
         }
     }
+
+    fn accessibility(&self, _ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::ProgressIndicator;
+        node.numeric_value = Some(NumericValue {
+            value: self.progress as f64,
+            min: 0.0,
+            max: 1.0,
+        });
+        node.presentational_children = true;
+    }
 }
 
 impl ProgressBar {
@@ -4,6 +4,7 @@
 #![warn(missing_docs)]
 
 use crate::{
+    accessibility::{AccessibilityNode, AccessibilityRole, NumericValue},
     border::BorderBuilder,
     brush::Brush,
     button::{ButtonBuilder, ButtonMessage},
@@ -395,6 +396,16 @@ impl Control for ScrollBar {
             }
         }
     }
+
+    fn accessibility(&self, _ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::ScrollBar;
+        node.numeric_value = Some(NumericValue {
+            value: self.value as f64,
+            min: self.min as f64,
+            max: self.max as f64,
+        });
+        node.presentational_children = true;
+    }
 }
 
 /// Scroll bar widget is used to create [`ScrollBar`] widget instances and add them to the user interface.
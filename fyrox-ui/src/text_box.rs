@@ -4,6 +4,7 @@
 #![warn(missing_docs)]
 
 use crate::{
+    accessibility::{AccessibilityNode, AccessibilityRole, AccessibilitySettings},
     brush::Brush,
     core::{
         algebra::{Point2, Vector2},
@@ -307,6 +308,7 @@ pub type FilterCallback = dyn FnMut(char) -> bool + Send;
 ///
 /// - `Ctrl+A` - select all
 /// - `Ctrl+C` - copy selected text
+/// - `Ctrl+X` - cut selected text
 /// - `Ctrl+V` - paste text from clipboard
 /// - `Ctrl+Home` - move caret to the beginning of the text
 /// - `Ctrl+End` - move caret to the beginning of the text
@@ -405,6 +407,11 @@ pub struct TextBox {
     pub view_position: Vector2<f32>,
     /// A list of custom characters that will be treated as whitespace.
     pub skip_chars: Vec<char>,
+    /// `true` if the caret should not blink, it stays visible while the text box has focus instead. It is
+    /// synchronized with [`AccessibilitySettings::reduced_motion`].
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub reduced_motion: bool,
 }
 
 impl Debug for TextBox {
@@ -913,6 +920,30 @@ impl TextBox {
         self.formatted_text.borrow().text()
     }
 
+    /// Returns currently selected text, or [`None`] if there's no selection.
+    pub fn selected_text(&self) -> Option<String> {
+        let selection_range = self.selection_range?;
+        let begin = self.position_to_char_index_unclamped(selection_range.begin)?;
+        let end = self.position_to_char_index_unclamped(selection_range.end)?;
+        let (begin, end) = if begin < end {
+            (begin, end)
+        } else {
+            (end, begin)
+        };
+        Some(self.text().chars().skip(begin).take(end - begin).collect())
+    }
+
+    /// Puts selected text to the clipboard. Returns `true` if there was a selection and the clipboard
+    /// is available.
+    fn copy_selection(&self, ui: &UserInterface) -> bool {
+        if let Some(text) = self.selected_text() {
+            if let Some(mut clipboard) = ui.clipboard_mut() {
+                return clipboard.set_contents(text).is_ok();
+            }
+        }
+        false
+    }
+
     /// Returns current word wrapping mode of text box.
     pub fn wrap_mode(&self) -> WrapMode {
         self.formatted_text.borrow().wrap_mode()
@@ -1095,6 +1126,11 @@ impl Control for TextBox {
 
     fn update(&mut self, dt: f32, _sender: &Sender<UiMessage>, _screen_size: Vector2<f32>) {
         if self.has_focus {
+            if self.reduced_motion {
+                self.caret_visible = true;
+                return;
+            }
+
             self.blink_timer += dt;
             if self.blink_timer >= self.blink_interval {
                 self.blink_timer = 0.0;
@@ -1307,37 +1343,27 @@ impl Control for TextBox {
                                 }
                             }
                             KeyCode::KeyC if ui.keyboard_modifiers().control => {
-                                if let Some(mut clipboard) = ui.clipboard_mut() {
-                                    if let Some(selection_range) = self.selection_range.as_ref() {
-                                        if let (Some(begin), Some(end)) = (
-                                            self.position_to_char_index_unclamped(
-                                                selection_range.begin,
-                                            ),
-                                            self.position_to_char_index_unclamped(
-                                                selection_range.end,
-                                            ),
-                                        ) {
-                                            let _ = clipboard.set_contents(String::from(
-                                                &self.text()[if begin < end {
-                                                    begin..end
-                                                } else {
-                                                    end..begin
-                                                }],
-                                            ));
-                                        }
-                                    }
+                                self.copy_selection(ui);
+                            }
+                            KeyCode::KeyX
+                                if ui.keyboard_modifiers().control
+                                    && self.editable
+                                    && self.copy_selection(ui) =>
+                            {
+                                if let Some(selection_range) = self.selection_range.take() {
+                                    self.remove_range(ui, selection_range);
                                 }
                             }
-                            KeyCode::KeyV if ui.keyboard_modifiers().control => {
-                                if let Some(mut clipboard) = ui.clipboard_mut() {
-                                    if let Ok(content) = clipboard.get_contents() {
-                                        if let Some(selection_range) = self.selection_range {
-                                            self.remove_range(ui, selection_range);
-                                            self.selection_range = None;
-                                        }
-
-                                        self.insert_str(&content, ui);
+                            KeyCode::KeyV if ui.keyboard_modifiers().control && self.editable => {
+                                let content = ui
+                                    .clipboard_mut()
+                                    .and_then(|mut clipboard| clipboard.get_contents().ok());
+                                if let Some(content) = content {
+                                    if let Some(selection_range) = self.selection_range.take() {
+                                        self.remove_range(ui, selection_range);
                                     }
+
+                                    self.insert_str(&content, ui);
                                 }
                             }
                             _ => (),
@@ -1561,6 +1587,16 @@ impl Control for TextBox {
             }
         }
     }
+
+    fn accessibility(&self, _ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::TextInput;
+        node.value = Some(self.text());
+        node.read_only = !self.editable;
+    }
+
+    fn on_accessibility_settings_changed(&mut self, settings: &AccessibilitySettings) {
+        self.reduced_motion = settings.reduced_motion;
+    }
 }
 
 /// Text box builder creates new [`TextBox`] instances and adds them to the user interface.
@@ -1767,6 +1803,7 @@ impl TextBoxBuilder {
             editable: self.editable,
             view_position: Default::default(),
             skip_chars: self.skip_chars,
+            reduced_motion: false,
         };
 
         ctx.add_node(UiNode::new(text_box))
@@ -4,6 +4,7 @@
 #![warn(missing_docs)]
 
 use crate::{
+    accessibility::{AccessibilityNode, AccessibilityRole},
     border::BorderBuilder,
     brush::Brush,
     check_box::{CheckBoxBuilder, CheckBoxMessage},
@@ -489,6 +490,14 @@ impl Control for Tree {
             }
         }
     }
+
+    fn accessibility(&self, _ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::TreeItem;
+        node.selected = Some(self.is_selected);
+        if !self.items.is_empty() {
+            node.expanded = Some(self.is_expanded);
+        }
+    }
 }
 
 impl Tree {
@@ -806,6 +815,10 @@ impl Control for TreeRoot {
             }
         }
     }
+
+    fn accessibility(&self, _ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::Tree;
+    }
 }
 
 impl TreeRoot {
@@ -0,0 +1,352 @@
+//! Virtual cursor allows to control user interfaces, that were built for mouse, using a gamepad stick.
+//! See [`VirtualCursor`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    accessibility::AccessibilityNode,
+    core::{algebra::Vector2, pool::Handle, reflect::prelude::*, visitor::prelude::*},
+    message::{ButtonState, MessageDirection, MouseButton, OsEvent},
+    widget::WidgetMessage,
+    UiNode, UserInterface,
+};
+
+/// Settings of a [`VirtualCursor`].
+#[derive(Copy, Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct VirtualCursorSettings {
+    /// Maximum speed of the cursor (in pixels per second), that is reached when the stick is fully
+    /// tilted.
+    #[reflect(min_value = 0.0, step = 10.0)]
+    pub max_speed: f32,
+    /// Acceleration of the cursor (in pixels per second squared). Lower values allow more precise
+    /// movements, higher values make the cursor more responsive.
+    #[reflect(min_value = 0.0, step = 10.0)]
+    pub acceleration: f32,
+    /// Stick deflections below this value are ignored. It compensates the drift of worn sticks.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01)]
+    pub dead_zone: f32,
+    /// Exponent of the response curve of the stick. `1.0` means linear response, larger values give
+    /// more precision for small deflections of the stick.
+    #[reflect(min_value = 0.1, step = 0.1)]
+    pub response_curve: f32,
+    /// Speed multiplier of the cursor, when it is over an interactive widget (button, check box, etc.).
+    /// It makes widgets "sticky", so it is easier to stop the cursor over them.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub friction: f32,
+    /// Maximum distance (in pixels) from the cursor to an interactive widget, at which the cursor is
+    /// pulled to the center of the widget when the stick is released. Zero disables snapping.
+    #[reflect(min_value = 0.0, step = 1.0)]
+    pub snap_radius: f32,
+    /// Speed of the snapping. The larger the value, the faster the cursor reaches the center of a
+    /// widget.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub snap_speed: f32,
+}
+
+impl Default for VirtualCursorSettings {
+    fn default() -> Self {
+        Self {
+            max_speed: 1200.0,
+            acceleration: 4000.0,
+            dead_zone: 0.15,
+            response_curve: 2.0,
+            friction: 0.5,
+            snap_radius: 48.0,
+            snap_speed: 15.0,
+        }
+    }
+}
+
+/// Virtual cursor is a synthetic pointer, that is moved by a gamepad stick (or any other analog
+/// input) and acts exactly like a mouse cursor. It is useful for user interfaces, that were not built
+/// around focus navigation (for example, inventories with lots of items or various editors).
+///
+/// The virtual cursor does not depend on any specific input library: the state of the stick and the
+/// "click" button must be passed to [`VirtualCursor::update`] and [`VirtualCursor::set_button_state`]
+/// respectively. The cursor then sends [`OsEvent::CursorMoved`] and [`OsEvent::MouseInput`] events to
+/// the user interface, so every widget works with it without any changes.
+///
+/// ## Assistance
+///
+/// Precise aiming with a stick is hard, so the cursor has a few assistance features:
+///
+/// - Acceleration and non-linear response of the stick, see [`VirtualCursorSettings::acceleration`]
+///   and [`VirtualCursorSettings::response_curve`].
+/// - Friction - the cursor slows down over interactive widgets, see [`VirtualCursorSettings::friction`].
+/// - Snapping - when the stick is released, the cursor is pulled to the center of the closest
+///   interactive widget, see [`VirtualCursorSettings::snap_radius`].
+///
+/// Interactive widgets are detected using their accessibility roles (see [`crate::Control::accessibility`]),
+/// so custom widgets should provide proper roles to be snapped to.
+///
+/// ## Visual
+///
+/// The virtual cursor has no visual representation by default. Use [`VirtualCursor::set_cursor_widget`]
+/// to specify a widget (an image for example), that will be moved with the cursor. The widget must be
+/// a child of a canvas (the root widget of the user interface is a canvas) and it must not be hit-test
+/// visible, otherwise it will block the widgets under it.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox_ui::{
+///     core::algebra::Vector2,
+///     message::ButtonState,
+///     virtual_cursor::VirtualCursor,
+///     UserInterface,
+/// };
+///
+/// // Call this once per frame, `stick` is a state of the left stick of a gamepad in [-1; 1] range
+/// // and `a_pressed` is a state of the "A" button of the gamepad.
+/// fn update_cursor(
+///     cursor: &mut VirtualCursor,
+///     ui: &mut UserInterface,
+///     stick: Vector2<f32>,
+///     a_pressed: bool,
+///     dt: f32,
+/// ) {
+///     cursor.update(ui, stick, dt);
+///     cursor.set_button_state(
+///         ui,
+///         if a_pressed {
+///             ButtonState::Pressed
+///         } else {
+///             ButtonState::Released
+///         },
+///     );
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct VirtualCursor {
+    /// Settings of the cursor.
+    pub settings: VirtualCursorSettings,
+    position: Vector2<f32>,
+    speed: f32,
+    snap_target: Handle<UiNode>,
+    cursor_widget: Handle<UiNode>,
+    button_state: ButtonState,
+}
+
+impl Default for VirtualCursor {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl VirtualCursor {
+    /// Creates new virtual cursor with the given settings.
+    pub fn new(settings: VirtualCursorSettings) -> Self {
+        Self {
+            settings,
+            position: Default::default(),
+            speed: 0.0,
+            snap_target: Default::default(),
+            cursor_widget: Default::default(),
+            button_state: ButtonState::Released,
+        }
+    }
+
+    /// Returns current position of the cursor in screen coordinates.
+    pub fn position(&self) -> Vector2<f32> {
+        self.position
+    }
+
+    /// Instantly moves the cursor to the given position in screen coordinates.
+    pub fn set_position(&mut self, ui: &mut UserInterface, position: Vector2<f32>) {
+        self.speed = 0.0;
+        self.snap_target = Handle::NONE;
+        self.move_to(ui, position);
+    }
+
+    /// Returns a handle of the widget, to which the cursor is being snapped. See [`VirtualCursorSettings::snap_radius`].
+    pub fn snap_target(&self) -> Handle<UiNode> {
+        self.snap_target
+    }
+
+    /// Sets a widget, that will be moved with the cursor. See [`VirtualCursor`] docs for more info.
+    pub fn set_cursor_widget(&mut self, ui: &UserInterface, widget: Handle<UiNode>) {
+        self.cursor_widget = widget;
+        self.sync_cursor_widget(ui);
+    }
+
+    /// Returns a widget, that is moved with the cursor.
+    pub fn cursor_widget(&self) -> Handle<UiNode> {
+        self.cursor_widget
+    }
+
+    /// Sets the state of the "click" button of the cursor. It is emulated by the left mouse button.
+    /// Does nothing if the state is not changed, so it could be called every frame.
+    pub fn set_button_state(&mut self, ui: &mut UserInterface, state: ButtonState) {
+        if self.button_state != state {
+            self.button_state = state;
+            ui.process_os_event(&OsEvent::MouseInput {
+                button: MouseButton::Left,
+                state,
+            });
+        }
+    }
+
+    /// Returns current state of the "click" button of the cursor.
+    pub fn button_state(&self) -> ButtonState {
+        self.button_state
+    }
+
+    /// Moves the cursor using the given state of the stick. Both axes of the stick must be in `[-1; 1]`
+    /// range, the Y axis points up (as most of gamepad libraries report it). Must be called once per
+    /// frame, even if the stick is not touched, otherwise snapping won't work.
+    pub fn update(&mut self, ui: &mut UserInterface, stick: Vector2<f32>, dt: f32) {
+        let settings = &self.settings;
+        let magnitude = stick.norm();
+
+        let mut position = self.position;
+        if magnitude > settings.dead_zone {
+            self.snap_target = Handle::NONE;
+
+            let input = ((magnitude.min(1.0) - settings.dead_zone)
+                / (1.0 - settings.dead_zone).max(f32::EPSILON))
+            .powf(settings.response_curve);
+            let mut target_speed = settings.max_speed * input;
+            if interactive_widget_at(ui, ui.hit_test(position)).is_some() {
+                target_speed *= settings.friction;
+            }
+            self.speed = if target_speed > self.speed {
+                (self.speed + settings.acceleration * dt).min(target_speed)
+            } else {
+                target_speed
+            };
+
+            // Y axis of the screen points down.
+            let direction = Vector2::new(stick.x, -stick.y) / magnitude;
+            position += direction * self.speed * dt;
+        } else {
+            self.speed = 0.0;
+
+            if self.snap_target.is_none() && settings.snap_radius > 0.0 {
+                self.snap_target = find_snap_target(ui, position, settings.snap_radius);
+            }
+
+            if let Some(target) = ui.try_get_node(self.snap_target) {
+                let center = target.screen_bounds().center();
+                let k = 1.0 - (-settings.snap_speed * dt).exp();
+                position += (center - position) * k;
+            } else {
+                self.snap_target = Handle::NONE;
+            }
+        }
+
+        let screen_size = ui.screen_size();
+        position.x = position.x.clamp(0.0, screen_size.x);
+        position.y = position.y.clamp(0.0, screen_size.y);
+
+        if position != self.position {
+            self.move_to(ui, position);
+        }
+    }
+
+    fn move_to(&mut self, ui: &mut UserInterface, position: Vector2<f32>) {
+        self.position = position;
+        ui.process_os_event(&OsEvent::CursorMoved { position });
+        self.sync_cursor_widget(ui);
+    }
+
+    fn sync_cursor_widget(&self, ui: &UserInterface) {
+        if self.cursor_widget.is_some() {
+            ui.send_message(WidgetMessage::desired_position(
+                self.cursor_widget,
+                MessageDirection::ToWidget,
+                self.position,
+            ));
+        }
+    }
+}
+
+fn accessibility_node(ui: &UserInterface, handle: Handle<UiNode>) -> Option<AccessibilityNode> {
+    let widget = ui.try_get_node(handle)?;
+    let mut node = AccessibilityNode {
+        disabled: !widget.enabled(),
+        bounds: widget.screen_bounds(),
+        ..Default::default()
+    };
+    widget.accessibility(ui, &mut node);
+    Some(node)
+}
+
+// Returns the closest interactive widget up in the hierarchy starting from the given one.
+fn interactive_widget_at(ui: &UserInterface, mut handle: Handle<UiNode>) -> Handle<UiNode> {
+    while let Some(node) = accessibility_node(ui, handle) {
+        if node.role.is_interactive() && !node.disabled {
+            return handle;
+        }
+        handle = ui.node(handle).parent();
+    }
+    Handle::NONE
+}
+
+fn find_snap_target(ui: &UserInterface, position: Vector2<f32>, radius: f32) -> Handle<UiNode> {
+    ui.accessibility_tree()
+        .nodes
+        .into_iter()
+        .filter(|(_, node)| node.role.is_interactive() && !node.disabled)
+        .filter_map(|(handle, node)| {
+            let bounds = node.bounds;
+            // Distance to the bounds, it is zero if the cursor is inside the widget.
+            let dx = (bounds.x() - position.x).max(position.x - bounds.x() - bounds.w());
+            let dy = (bounds.y() - position.y).max(position.y - bounds.y() - bounds.h());
+            let distance = Vector2::new(dx.max(0.0), dy.max(0.0)).norm();
+            (distance <= radius).then(|| (handle, (bounds.center() - position).norm()))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(Handle::NONE, |(handle, _)| handle)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        button::ButtonBuilder, core::algebra::Vector2, virtual_cursor::VirtualCursor,
+        widget::WidgetBuilder, UserInterface,
+    };
+
+    #[test]
+    fn test_virtual_cursor() {
+        let screen_size = Vector2::new(400.0, 400.0);
+        let mut ui = UserInterface::new(screen_size);
+        let ctx = &mut ui.build_ctx();
+        let button = ButtonBuilder::new(
+            WidgetBuilder::new()
+                .with_desired_position(Vector2::new(100.0, 100.0))
+                .with_width(50.0)
+                .with_height(20.0),
+        )
+        .with_text("Click Me")
+        .build(ctx);
+        ui.update(screen_size, 0.0);
+
+        let mut cursor = VirtualCursor::default();
+        cursor.set_position(&mut ui, Vector2::new(10.0, 10.0));
+
+        // Moving to the right and down.
+        cursor.update(&mut ui, Vector2::new(1.0, -1.0), 0.1);
+        let position = cursor.position();
+        assert!(position.x > 10.0 && position.y > 10.0);
+        assert_eq!(ui.cursor_position(), position);
+
+        // Stick drift is ignored and the cursor is too far from the button to be snapped.
+        cursor.update(&mut ui, Vector2::new(0.05, 0.0), 0.1);
+        assert_eq!(cursor.position(), position);
+        assert!(cursor.snap_target().is_none());
+
+        // Released stick near the button pulls the cursor to its center.
+        cursor.set_position(&mut ui, Vector2::new(90.0, 95.0));
+        for _ in 0..30 {
+            cursor.update(&mut ui, Vector2::default(), 0.1);
+        }
+        assert_eq!(cursor.snap_target(), button);
+        assert!((cursor.position() - Vector2::new(125.0, 110.0)).norm() < 1.0);
+
+        // The cursor never leaves the screen.
+        for _ in 0..10 {
+            cursor.update(&mut ui, Vector2::new(-1.0, 1.0), 1.0);
+        }
+        assert_eq!(cursor.position(), Vector2::new(0.0, 0.0));
+    }
+}
@@ -784,6 +784,10 @@ pub struct Widget {
     pub layout_events_sender: Option<Sender<LayoutEvent>>,
     /// Unique identifier of the widget.
     pub id: Uuid,
+    /// An optional label of the widget, that is exposed to assistive technologies. It has priority
+    /// over the label provided by the widget itself (for example, a text of a button).
+    #[visit(optional)]
+    pub accessibility_label: Option<String>,
     //
     // Layout. Interior mutability is a must here because layout performed in a series of recursive calls.
     //
@@ -1720,6 +1724,8 @@ pub struct WidgetBuilder {
     pub clip_to_bounds: bool,
     /// Unique id of the widget.
     pub id: Uuid,
+    /// An optional label of the widget, that is exposed to assistive technologies.
+    pub accessibility_label: Option<String>,
 }
 
 impl Default for WidgetBuilder {
@@ -1765,6 +1771,7 @@ impl WidgetBuilder {
             render_transform: Matrix3::identity(),
             clip_to_bounds: true,
             id: Uuid::new_v4(),
+            accessibility_label: None,
         }
     }
 
@@ -1794,6 +1801,13 @@ impl WidgetBuilder {
         self
     }
 
+    /// Sets the label of the widget, that is exposed to assistive technologies (screen readers, etc.).
+    /// It is useful for widgets, that do not have any text, for example for buttons with images.
+    pub fn with_accessibility_label<S: AsRef<str>>(mut self, label: S) -> Self {
+        self.accessibility_label = Some(label.as_ref().to_owned());
+        self
+    }
+
     /// Enables or disables clipping of widget's bound to its parent's bounds.
     pub fn with_clip_to_bounds(mut self, clip_to_bounds: bool) -> Self {
         self.clip_to_bounds = clip_to_bounds;
@@ -2050,6 +2064,7 @@ impl WidgetBuilder {
             visual_transform: Matrix3::identity(),
             clip_to_bounds: self.clip_to_bounds,
             id: self.id,
+            accessibility_label: self.accessibility_label,
         }
     }
 }
@@ -2,6 +2,7 @@
 //! for more info and usage examples.
 
 use crate::{
+    accessibility::{self, AccessibilityNode, AccessibilityRole},
     border::BorderBuilder,
     brush::Brush,
     button::{ButtonBuilder, ButtonMessage},
@@ -844,6 +845,11 @@ impl Control for Window {
             }
         }
     }
+
+    fn accessibility(&self, ui: &UserInterface, node: &mut AccessibilityNode) {
+        node.role = AccessibilityRole::Window;
+        node.label = accessibility::find_text(ui, self.title);
+    }
 }
 
 impl Window {
@@ -0,0 +1,279 @@
+//! Blackboard is a global container for shared game state. See [`Blackboard`] docs for more info.
+
+use crate::core::{
+    algebra::{Vector2, Vector3, Vector4},
+    visitor::prelude::*,
+};
+use fxhash::FxHashMap;
+use std::sync::mpsc::Sender;
+
+/// A value stored in a blackboard.
+#[derive(Debug, Visit, Clone, PartialEq)]
+pub enum BlackboardValue {
+    /// Boolean value.
+    Bool(bool),
+    /// Signed 32-bit integer.
+    I32(i32),
+    /// Signed 64-bit integer.
+    I64(i64),
+    /// Real number.
+    F32(f32),
+    /// Real number with double precision.
+    F64(f64),
+    /// Arbitrary string.
+    String(String),
+    /// Two-dimensional vector.
+    Vector2(Vector2<f32>),
+    /// Three-dimensional vector.
+    Vector3(Vector3<f32>),
+    /// Four-dimensional vector.
+    Vector4(Vector4<f32>),
+}
+
+impl Default for BlackboardValue {
+    fn default() -> Self {
+        Self::Bool(false)
+    }
+}
+
+/// A type that can be stored in a blackboard.
+pub trait BlackboardValueType: Sized {
+    /// Wraps the value into [`BlackboardValue`].
+    fn into_blackboard_value(self) -> BlackboardValue;
+
+    /// Tries to extract a value of the type from the given [`BlackboardValue`]. Returns [`None`] if
+    /// the blackboard value has different type.
+    fn from_blackboard_value(value: &BlackboardValue) -> Option<Self>;
+}
+
+macro_rules! impl_blackboard_value_type {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl BlackboardValueType for $ty {
+                fn into_blackboard_value(self) -> BlackboardValue {
+                    BlackboardValue::$variant(self)
+                }
+
+                fn from_blackboard_value(value: &BlackboardValue) -> Option<Self> {
+                    if let BlackboardValue::$variant(value) = value {
+                        Some(value.clone())
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_blackboard_value_type!(
+    bool => Bool,
+    i32 => I32,
+    i64 => I64,
+    f32 => F32,
+    f64 => F64,
+    String => String,
+    Vector2<f32> => Vector2,
+    Vector3<f32> => Vector3,
+    Vector4<f32> => Vector4
+);
+
+/// An event that happened in a blackboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlackboardEvent {
+    /// A key of the changed entry.
+    pub key: String,
+    /// A value of the entry before the change. [`None`] if the entry was just added.
+    pub old_value: Option<BlackboardValue>,
+    /// A value of the entry after the change. [`None`] if the entry was removed.
+    pub new_value: Option<BlackboardValue>,
+}
+
+/// Blackboard is a typed key-value storage for shared game state (current level, score, quest
+/// flags, etc.). An instance of it is stored in the engine and it is accessible from plugins
+/// and scripts via their contexts (`context.blackboard`).
+///
+/// ## Change notifications
+///
+/// Every change of the blackboard is sent to every subscriber, see [`Blackboard::subscribe`].
+///
+/// ## Saving
+///
+/// Blackboard implements [`Visit`] trait, so it could be saved together with the rest of the
+/// game state. Subscribers are not serialized and no events are sent when the blackboard is
+/// loaded.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox::engine::blackboard::Blackboard;
+/// # use std::sync::mpsc::channel;
+/// let mut blackboard = Blackboard::default();
+///
+/// let (tx, rx) = channel();
+/// blackboard.subscribe(tx);
+///
+/// blackboard.set("Score", 100i32);
+/// assert_eq!(blackboard.get::<i32>("Score"), Some(100));
+/// assert_eq!(rx.try_recv().unwrap().key, "Score");
+///
+/// // Type mismatch.
+/// assert_eq!(blackboard.get::<f32>("Score"), None);
+/// ```
+#[derive(Default, Debug, Visit)]
+pub struct Blackboard {
+    values: FxHashMap<String, BlackboardValue>,
+    #[visit(skip)]
+    senders: Vec<Sender<BlackboardEvent>>,
+}
+
+impl Blackboard {
+    /// Adds new subscriber, that will receive every change of the blackboard.
+    pub fn subscribe(&mut self, sender: Sender<BlackboardEvent>) {
+        self.senders.push(sender);
+    }
+
+    fn broadcast(&mut self, event: BlackboardEvent) {
+        self.senders
+            .retain_mut(|sender| sender.send(event.clone()).is_ok());
+    }
+
+    /// Sets a new value for the given key. Subscribers are notified only if the new value differs
+    /// from the old one.
+    pub fn set<T>(&mut self, key: impl Into<String>, value: T)
+    where
+        T: BlackboardValueType,
+    {
+        self.set_value(key, value.into_blackboard_value())
+    }
+
+    /// Sets a new untyped value for the given key. Subscribers are notified only if the new value
+    /// differs from the old one.
+    pub fn set_value(&mut self, key: impl Into<String>, value: BlackboardValue) {
+        let key = key.into();
+        let old_value = self.values.insert(key.clone(), value.clone());
+        if old_value.as_ref() != Some(&value) {
+            self.broadcast(BlackboardEvent {
+                key,
+                old_value,
+                new_value: Some(value),
+            });
+        }
+    }
+
+    /// Returns a value of the given key. Returns [`None`] if there's no such key or the value has
+    /// different type.
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: BlackboardValueType,
+    {
+        self.values.get(key).and_then(T::from_blackboard_value)
+    }
+
+    /// Returns a value of the given key or the given default value if there's no such key or the
+    /// value has different type.
+    pub fn get_or<T>(&self, key: &str, default: T) -> T
+    where
+        T: BlackboardValueType,
+    {
+        self.get(key).unwrap_or(default)
+    }
+
+    /// Returns an untyped value of the given key.
+    pub fn value(&self, key: &str) -> Option<&BlackboardValue> {
+        self.values.get(key)
+    }
+
+    /// Returns `true` if the blackboard has the given key.
+    pub fn contains(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// Removes the given key from the blackboard and returns its value.
+    pub fn remove(&mut self, key: &str) -> Option<BlackboardValue> {
+        let old_value = self.values.remove(key);
+        if old_value.is_some() {
+            self.broadcast(BlackboardEvent {
+                key: key.to_string(),
+                old_value: old_value.clone(),
+                new_value: None,
+            });
+        }
+        old_value
+    }
+
+    /// Removes every entry of the blackboard. Subscribers are notified about every removed entry.
+    pub fn clear(&mut self) {
+        for (key, old_value) in std::mem::take(&mut self.values) {
+            self.broadcast(BlackboardEvent {
+                key,
+                old_value: Some(old_value),
+                new_value: None,
+            });
+        }
+    }
+
+    /// Returns an iterator over every entry of the blackboard.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BlackboardValue)> {
+        self.values.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Returns total amount of entries in the blackboard.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the blackboard has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, visitor::prelude::*},
+        engine::blackboard::{Blackboard, BlackboardValue},
+    };
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_blackboard_events() {
+        let mut blackboard = Blackboard::default();
+        let (tx, rx) = channel();
+        blackboard.subscribe(tx);
+
+        blackboard.set("Position", Vector3::new(1.0, 2.0, 3.0));
+        blackboard.set("Position", Vector3::new(1.0, 2.0, 3.0));
+        blackboard.set("Position", Vector3::new(4.0, 5.0, 6.0));
+        blackboard.remove("Position");
+
+        let events = rx.try_iter().collect::<Vec<_>>();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].old_value, None);
+        assert_eq!(
+            events[1].old_value,
+            Some(BlackboardValue::Vector3(Vector3::new(1.0, 2.0, 3.0)))
+        );
+        assert_eq!(events[2].new_value, None);
+        assert!(blackboard.is_empty());
+    }
+
+    #[test]
+    fn test_blackboard_serialization() {
+        let mut blackboard = Blackboard::default();
+        blackboard.set("Level", "Forest".to_string());
+        blackboard.set("Coins", 42i64);
+
+        let mut visitor = Visitor::new();
+        blackboard.visit("Blackboard", &mut visitor).unwrap();
+
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut loaded = Blackboard::default();
+        loaded.visit("Blackboard", &mut visitor).unwrap();
+
+        assert_eq!(loaded.get::<String>("Level"), Some("Forest".to_string()));
+        assert_eq!(loaded.get_or("Coins", 0i64), 42);
+    }
+}
@@ -0,0 +1,376 @@
+//! Crash reporting allows you to get actionable bug reports from playtesters. See [`CrashReporter`]
+//! docs for more info.
+
+use crate::{
+    core::{
+        instant::SystemTime,
+        log::{Log, LogMessage, MessageKind},
+        parking_lot::Mutex,
+        pool::Handle,
+        visitor::Visitor,
+    },
+    scene::{Scene, SceneContainer},
+};
+use fxhash::FxHasher;
+use lazy_static::lazy_static;
+use std::{
+    any::Any,
+    cell::Cell,
+    fmt::Write,
+    hash::{Hash, Hasher},
+    panic::{self, AssertUnwindSafe, Location, UnwindSafe},
+    path::PathBuf,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+};
+
+/// Crash reporter settings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrashReportSettings {
+    /// A directory, where crash reports (and scene snapshots) will be written to. Default is
+    /// `crash_reports`.
+    pub directory: PathBuf,
+    /// Maximum amount of recent log messages, that will be included in a crash report. Default is
+    /// 256.
+    pub log_capacity: usize,
+    /// Whether to save a snapshot of every scene when the engine panics. Snapshots are saved in the
+    /// native scene format and could be opened in the editor. Keep in mind, that a scene could be
+    /// in inconsistent state at the moment of panic, so snapshot saving is a best-effort operation.
+    /// Default is `false`.
+    pub save_scene_snapshots: bool,
+}
+
+impl Default for CrashReportSettings {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("crash_reports"),
+            log_capacity: 256,
+            save_scene_snapshots: false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct CrashContext {
+    settings: CrashReportSettings,
+    scenes: Vec<String>,
+    last_report: Option<PathBuf>,
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+// Frame counters are updated every frame, so they're stored separately to not lock the context.
+static FRAME: AtomicU64 = AtomicU64::new(0);
+static ELAPSED_TIME: AtomicU32 = AtomicU32::new(0);
+// Hash of the scene descriptions, the descriptions are rebuilt only when it changes.
+static SCENES_HASH: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    // Depth of nested [`CrashReporter::catch_unwind`] calls on the current thread.
+    static CATCH_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+lazy_static! {
+    static ref CONTEXT: Mutex<CrashContext> = Default::default();
+}
+
+/// Crash reporter installs a panic hook, that writes a crash report to disk when a panic occurs.
+/// A crash report contains a panic message, its location, a backtrace, current frame number, a
+/// list of scenes, recent log messages and optionally snapshots of every scene. The engine updates
+/// the crash context automatically in [`crate::engine::Engine::update`].
+///
+/// Panic hook cannot know whether a panic will be caught or not, so every panic is reported by
+/// default. Use [`CrashReporter::catch_unwind`] instead of [`std::panic::catch_unwind`] for panics
+/// that are expected to be caught, such panics won't produce crash reports.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use fyrox::engine::crash::{CrashReportSettings, CrashReporter};
+/// fn main() {
+///     CrashReporter::install(CrashReportSettings {
+///         save_scene_snapshots: true,
+///         ..Default::default()
+///     });
+///
+///     // Create an executor and run your game as usual.
+/// }
+/// ```
+pub struct CrashReporter;
+
+impl CrashReporter {
+    /// Installs a panic hook, that writes crash reports using the given settings. Previously set
+    /// panic hook is still called after a crash report is written (so the standard panic message is
+    /// still printed). Repeated installation only changes the settings.
+    pub fn install(settings: CrashReportSettings) {
+        Log::set_history_capacity(settings.log_capacity);
+
+        CONTEXT.lock().settings = settings;
+
+        if INSTALLED.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !is_caught() {
+                Self::write_report(info.payload(), info.location());
+            }
+            previous(info);
+        }));
+    }
+
+    /// Returns `true` if the crash reporter is installed, `false` - otherwise.
+    pub fn is_installed() -> bool {
+        INSTALLED.load(Ordering::SeqCst)
+    }
+
+    /// Returns a path of the last written crash report (if any).
+    pub fn last_report() -> Option<PathBuf> {
+        CONTEXT.lock().last_report.clone()
+    }
+
+    /// Same as [`std::panic::catch_unwind`], but panics caught by this method do not produce crash
+    /// reports.
+    pub fn catch_unwind<F, R>(func: F) -> std::thread::Result<R>
+    where
+        F: FnOnce() -> R + UnwindSafe,
+    {
+        CATCH_DEPTH.with(|depth| depth.set(depth.get() + 1));
+        let result = panic::catch_unwind(func);
+        CATCH_DEPTH.with(|depth| depth.set(depth.get() - 1));
+        result
+    }
+
+    /// Advances frame counter and remembers the current set of scenes. The engine calls this method
+    /// automatically, you need to call it manually only if you're not using [`crate::engine::Engine::update`].
+    pub fn update_context(scenes: &SceneContainer, elapsed_time: f32) {
+        if !Self::is_installed() {
+            return;
+        }
+
+        FRAME.fetch_add(1, Ordering::Relaxed);
+        ELAPSED_TIME.store(elapsed_time.to_bits(), Ordering::Relaxed);
+
+        let mut hasher = FxHasher::default();
+        for (handle, scene) in scenes.pair_iter() {
+            handle.hash(&mut hasher);
+            scene.graph.get_root().hash(&mut hasher);
+            scene.graph.node_count().hash(&mut hasher);
+            (*scene.enabled).hash(&mut hasher);
+        }
+        let hash = hasher.finish();
+        if SCENES_HASH.swap(hash, Ordering::Relaxed) != hash {
+            let mut context = CONTEXT.lock();
+            context.scenes.clear();
+            for (handle, scene) in scenes.pair_iter() {
+                context.scenes.push(describe_scene(handle, scene));
+            }
+        }
+    }
+
+    /// Returns `true` if the crash reporter is installed and it should save scene snapshots on panic.
+    pub fn wants_scene_snapshots() -> bool {
+        Self::is_installed() && CONTEXT.lock().settings.save_scene_snapshots
+    }
+
+    /// Saves snapshots of every scene next to the last crash report. The engine calls this method
+    /// automatically when [`crate::engine::Engine::update`] panics.
+    pub fn save_scene_snapshots(scenes: &mut SceneContainer) {
+        let Some(report_path) = Self::last_report() else {
+            return;
+        };
+
+        for (index, (_, scene)) in scenes.pair_iter_mut().enumerate() {
+            let mut path = report_path.clone();
+            path.set_file_name(format!(
+                "{}_scene_{}.rgs",
+                report_path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                index
+            ));
+
+            // The scene could be in inconsistent state, so the saving itself could panic.
+            let result = Self::catch_unwind(AssertUnwindSafe(|| {
+                let mut visitor = Visitor::new();
+                scene
+                    .save("Scene", &mut visitor)
+                    .and_then(|_| visitor.save_binary(&path))
+            }));
+
+            match result {
+                Ok(Ok(_)) => Log::info(format!("Scene snapshot saved to {}", path.display())),
+                Ok(Err(e)) => Log::err(format!(
+                    "Unable to save scene snapshot to {}. Reason: {:?}",
+                    path.display(),
+                    e
+                )),
+                Err(_) => Log::err(format!(
+                    "Unable to save scene snapshot to {}, because saving has panicked.",
+                    path.display()
+                )),
+            }
+        }
+    }
+
+    fn write_report(payload: &(dyn Any + Send), location: Option<&Location<'_>>) {
+        // The panic could happen while the context is locked (on the same thread), do not block.
+        let Some(mut context) = CONTEXT.try_lock() else {
+            return;
+        };
+
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+
+        let thread = std::thread::current();
+        let report = format_report(
+            &panic_message(payload),
+            location.map(|l| l.to_string()).as_deref(),
+            thread.name().unwrap_or("<unnamed>"),
+            FRAME.load(Ordering::Relaxed),
+            f32::from_bits(ELAPSED_TIME.load(Ordering::Relaxed)),
+            &context.scenes,
+            &Log::try_history().unwrap_or_default(),
+            &std::backtrace::Backtrace::force_capture().to_string(),
+        );
+
+        let _ = std::fs::create_dir_all(&context.settings.directory);
+        let path = context
+            .settings
+            .directory
+            .join(format!("crash_{}.txt", timestamp));
+        if std::fs::write(&path, report).is_ok() {
+            context.last_report = Some(path);
+        }
+    }
+}
+
+// Returns `true` if a panic on the current thread will be caught by [`CrashReporter::catch_unwind`].
+fn is_caught() -> bool {
+    CATCH_DEPTH.with(|depth| depth.get()) > 0
+}
+
+fn describe_scene(handle: Handle<Scene>, scene: &Scene) -> String {
+    let root = &scene.graph[scene.graph.get_root()];
+    let name = root
+        .root_resource()
+        .and_then(|resource| resource.kind().path_owned())
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| root.name().to_string());
+    format!(
+        "{} {} (enabled: {}, nodes: {})",
+        handle,
+        name,
+        *scene.enabled,
+        scene.graph.node_count()
+    )
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<non-string panic payload>".to_string()
+    }
+}
+
+fn kind_str(kind: MessageKind) -> &'static str {
+    match kind {
+        MessageKind::Information => "INFO",
+        MessageKind::Warning => "WARNING",
+        MessageKind::Error => "ERROR",
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn format_report(
+    message: &str,
+    location: Option<&str>,
+    thread: &str,
+    frame: u64,
+    elapsed_time: f32,
+    scenes: &[String],
+    log: &[LogMessage],
+    backtrace: &str,
+) -> String {
+    let mut report = String::new();
+
+    let _ = writeln!(report, "Crash Report");
+    let _ = writeln!(report, "Panic: {}", message);
+    let _ = writeln!(report, "Location: {}", location.unwrap_or("<unknown>"));
+    let _ = writeln!(report, "Thread: {}", thread);
+    let _ = writeln!(report, "Frame: {}", frame);
+    let _ = writeln!(report, "Elapsed Time: {:.3}s", elapsed_time);
+
+    let _ = writeln!(report, "\nScenes:");
+    for scene in scenes {
+        let _ = writeln!(report, "  {}", scene);
+    }
+
+    let _ = writeln!(report, "\nRecent Log Messages:");
+    for message in log {
+        let _ = write!(
+            report,
+            "  [{:.3}s] [{}]: {}",
+            message.time.as_secs_f32(),
+            kind_str(message.kind),
+            message.content
+        );
+        if !message.content.ends_with('\n') {
+            report.push('\n');
+        }
+    }
+
+    let _ = writeln!(report, "\nBacktrace:\n{}", backtrace);
+
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_report, is_caught, CrashReporter};
+    use crate::core::log::{LogMessage, MessageKind};
+    use std::time::Duration;
+
+    #[test]
+    fn test_caught_panic_is_not_reported() {
+        // The panic hook is process-wide, so only the decision of the hook is tested here.
+        assert!(!is_caught());
+        assert_eq!(CrashReporter::catch_unwind(is_caught).ok(), Some(true));
+        assert_eq!(
+            CrashReporter::catch_unwind(|| CrashReporter::catch_unwind(is_caught).ok()).ok(),
+            Some(Some(true))
+        );
+        assert!(CrashReporter::catch_unwind(|| -> () { panic!("expected panic") }).is_err());
+        assert!(!is_caught());
+    }
+
+    #[test]
+    fn test_format_report() {
+        let report = format_report(
+            "boom",
+            Some("src/main.rs:1:1"),
+            "main",
+            42,
+            1.5,
+            &["Level".to_string()],
+            &[LogMessage {
+                kind: MessageKind::Warning,
+                content: "Something is wrong\n".to_string(),
+                time: Duration::from_secs(1),
+            }],
+            "<backtrace>",
+        );
+
+        assert!(report.contains("Panic: boom"));
+        assert!(report.contains("Location: src/main.rs:1:1"));
+        assert!(report.contains("Frame: 42"));
+        assert!(report.contains("  Level\n"));
+        assert!(report.contains("[WARNING]: Something is wrong\n"));
+        assert!(report.contains("<backtrace>"));
+    }
+}
@@ -132,6 +132,14 @@ impl Executor {
     }
 
     /// Runs the executor - starts your game.
+    ///
+    /// ## Mobile platforms
+    ///
+    /// When the app is suspended (for example, when it goes to background on Android), the executor
+    /// destroys the graphics context (the OS reclaims the GPU resources of the app), releases the audio
+    /// output device and stops updating the game. Everything is re-created when the app is resumed,
+    /// the time that was spent in background is not "caught up" by the update loop. Touch input is
+    /// routed to the user interface, plugins and scripts as any other OS event.
     pub fn run(self) {
         let mut engine = self.engine;
         let event_loop = self.event_loop;
@@ -152,6 +160,9 @@ impl Executor {
         let mut previous = Instant::now();
         let fixed_time_step = 1.0 / self.desired_update_rate;
         let mut lag = 0.0;
+        // Mobile platforms (Android) suspend the app when it goes to background, the game must not
+        // be updated while the app is suspended.
+        let mut suspended = false;
 
         run_executor(event_loop, move |event, window_target| {
             window_target.set_control_flow(ControlFlow::Wait);
@@ -178,6 +189,13 @@ impl Executor {
                         .initialize_graphics_context(window_target)
                         .expect("Unable to initialize graphics context!");
 
+                    if suspended {
+                        // Do not try to "catch up" with the time that was spent in background.
+                        suspended = false;
+                        previous = Instant::now();
+                        lag = 0.0;
+                    }
+
                     engine.handle_graphics_context_created_by_plugins(
                         fixed_time_step,
                         window_target,
@@ -189,13 +207,15 @@ impl Executor {
                         .destroy_graphics_context()
                         .expect("Unable to destroy graphics context!");
 
+                    suspended = true;
+
                     engine.handle_graphics_context_destroyed_by_plugins(
                         fixed_time_step,
                         window_target,
                         &mut lag,
                     );
                 }
-                Event::AboutToWait => {
+                Event::AboutToWait if !suspended => {
                     let elapsed = previous.elapsed();
                     previous = Instant::now();
                     lag += elapsed.as_secs_f32();
@@ -227,7 +247,34 @@ impl Executor {
                                 &mut lag,
                             );
 
-                            engine.render().unwrap();
+                            let result = engine.render();
+
+                            if engine.is_graphics_context_lost() {
+                                Log::warn("Graphics context was lost, re-creating it...");
+
+                                engine.handle_graphics_context_destroyed_by_plugins(
+                                    fixed_time_step,
+                                    window_target,
+                                    &mut lag,
+                                );
+
+                                match engine.recover_graphics_context() {
+                                    Ok(_) => engine.handle_graphics_context_created_by_plugins(
+                                        fixed_time_step,
+                                        window_target,
+                                        &mut lag,
+                                    ),
+                                    Err(err) => {
+                                        Log::err(format!(
+                                            "Unable to recover graphics context: {:?}",
+                                            err
+                                        ));
+                                        window_target.exit();
+                                    }
+                                }
+                            } else {
+                                result.unwrap();
+                            }
                         }
                         _ => (),
                     }
@@ -0,0 +1,156 @@
+//! Native (OS-provided) file dialogs. See [`FileDialog`] docs for more info and usage examples.
+//!
+//! The module is available only on desktop platforms when the `file_dialogs` feature is enabled,
+//! because native dialogs require extra system libraries (for example, GTK3 on Linux). On other
+//! platforms (or without the feature) a path could be entered using a text box instead.
+
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+};
+
+/// A filter of files, that could be selected in a file dialog.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileFilter {
+    /// Human-readable name of the filter, for example `Scenes`.
+    pub name: String,
+    /// A set of extensions (without leading dot), for example `["rgs"]`.
+    pub extensions: Vec<String>,
+}
+
+impl FileFilter {
+    /// Creates new file filter.
+    pub fn new<S: AsRef<str>>(name: &str, extensions: &[S]) -> Self {
+        Self {
+            name: name.to_owned(),
+            extensions: extensions.iter().map(|e| e.as_ref().to_owned()).collect(),
+        }
+    }
+}
+
+/// File dialog allows you to ask a user to select a file (or a set of files) to open, or a path to
+/// save a file to, using native file dialog of the OS. It could be used in in-game level editors,
+/// user content tools, etc. All the methods are asynchronous (the dialogs does not block the game
+/// loop), so they're meant to be used together with the task pool of the engine.
+///
+/// ## Examples
+///
+/// ```rust ,no_run
+/// # use fyrox::{
+/// #     engine::file_dialog::{FileDialog, FileFilter},
+/// #     plugin::{Plugin, PluginContext},
+/// # };
+/// # use std::path::PathBuf;
+/// #
+/// struct MyGame {
+///     level_path: Option<PathBuf>,
+/// }
+///
+/// impl MyGame {
+///     fn open_level(&self, context: &mut PluginContext) {
+///         context.task_pool.spawn_plugin_task(
+///             FileDialog::new()
+///                 .with_title("Open Level")
+///                 .with_filter(FileFilter::new("Scenes", &["rgs"]))
+///                 .open_file(),
+///             |path, game: &mut MyGame, _context| {
+///                 // The path is `None` if the dialog was cancelled.
+///                 if path.is_some() {
+///                     game.level_path = path;
+///                 }
+///             },
+///         );
+///     }
+/// }
+///
+/// impl Plugin for MyGame {}
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FileDialog {
+    title: Option<String>,
+    directory: Option<PathBuf>,
+    file_name: Option<String>,
+    filters: Vec<FileFilter>,
+}
+
+impl FileDialog {
+    /// Creates new file dialog with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the desired title of the dialog window.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_owned());
+        self
+    }
+
+    /// Sets the desired initial directory of the dialog.
+    pub fn with_directory<P: AsRef<Path>>(mut self, directory: P) -> Self {
+        self.directory = Some(directory.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the desired default file name, it is used only by [`Self::save_file`].
+    pub fn with_file_name(mut self, file_name: &str) -> Self {
+        self.file_name = Some(file_name.to_owned());
+        self
+    }
+
+    /// Adds a new filter of files.
+    pub fn with_filter(mut self, filter: FileFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    fn make_dialog(&self) -> rfd::AsyncFileDialog {
+        let mut dialog = rfd::AsyncFileDialog::new();
+        if let Some(title) = self.title.as_ref() {
+            dialog = dialog.set_title(title);
+        }
+        if let Some(directory) = self.directory.as_ref() {
+            dialog = dialog.set_directory(directory);
+        }
+        if let Some(file_name) = self.file_name.as_ref() {
+            dialog = dialog.set_file_name(file_name);
+        }
+        for filter in self.filters.iter() {
+            dialog = dialog.add_filter(&filter.name, filter.extensions.as_slice());
+        }
+        dialog
+    }
+
+    /// Shows the dialog to select a single file to open. The future resolves to [`None`] if the
+    /// dialog was cancelled.
+    pub fn open_file(self) -> impl Future<Output = Option<PathBuf>> + Send + 'static {
+        let future = self.make_dialog().pick_file();
+        async move { future.await.map(|handle| handle.path().to_owned()) }
+    }
+
+    /// Shows the dialog to select a set of files to open. The future resolves to [`None`] if the
+    /// dialog was cancelled.
+    pub fn open_files(self) -> impl Future<Output = Option<Vec<PathBuf>>> + Send + 'static {
+        let future = self.make_dialog().pick_files();
+        async move {
+            future.await.map(|handles| {
+                handles
+                    .iter()
+                    .map(|handle| handle.path().to_owned())
+                    .collect()
+            })
+        }
+    }
+
+    /// Shows the dialog to select a folder. The future resolves to [`None`] if the dialog was cancelled.
+    pub fn pick_folder(self) -> impl Future<Output = Option<PathBuf>> + Send + 'static {
+        let future = self.make_dialog().pick_folder();
+        async move { future.await.map(|handle| handle.path().to_owned()) }
+    }
+
+    /// Shows the dialog to select a path to save a file to. The future resolves to [`None`] if the
+    /// dialog was cancelled.
+    pub fn save_file(self) -> impl Future<Output = Option<PathBuf>> + Send + 'static {
+        let future = self.make_dialog().save_file();
+        async move { future.await.map(|handle| handle.path().to_owned()) }
+    }
+}
@@ -3,8 +3,16 @@
 
 #![warn(missing_docs)]
 
+pub mod blackboard;
+pub mod crash;
 pub mod error;
 pub mod executor;
+#[cfg(all(
+    feature = "file_dialogs",
+    not(any(target_arch = "wasm32", target_os = "android"))
+))]
+pub mod file_dialog;
+pub mod streaming;
 pub mod task;
 
 use crate::{
@@ -13,11 +21,11 @@ use crate::{
         manager::{ResourceManager, ResourceWaitContext},
     },
     core::{
-        algebra::Vector2, futures::executor::block_on, instant, log::Log, pool::Handle,
-        reflect::Reflect, variable::try_inherit_properties, visitor::VisitError,
+        algebra::Vector2, instant, log::Log, pool::Handle, reflect::Reflect,
+        variable::try_inherit_properties, visitor::VisitError,
     },
-    engine::error::EngineError,
-    event::Event,
+    engine::{crash::CrashReporter, error::EngineError},
+    event::{Event, WindowEvent},
     gui::UserInterface,
     material,
     material::{
@@ -29,22 +37,34 @@ use crate::{
     renderer::{framework::error::FrameworkError, framework::state::GlKind, Renderer},
     resource::{
         curve::{loader::CurveLoader, CurveResourceState},
+        input_glyph::{loader::InputGlyphAtlasLoader, InputGlyphAtlas},
+        light_probe::{loader::LightProbeDataLoader, LightProbeData},
         model::{loader::ModelLoader, Model, ModelResource, NodeMapping},
+        sound_event::{loader::SoundBankLoader, SoundBank},
+        surface::{loader::SurfaceEffectsLoader, SurfaceEffects},
         texture::{loader::TextureLoader, Texture, TextureKind},
+        texture_atlas::{loader::TextureAtlasLoader, TextureAtlas},
+        timeline::{loader::TimelineLoader, Timeline},
     },
     scene::{
         base::NodeScriptMessage,
-        camera::SkyBoxKind,
+        camera::{Camera, SkyBoxKind},
+        dim2::tilemap::{
+            loader::{TileMapDataLoader, TileSetLoader},
+            tileset::TileSet,
+            TileMapData,
+        },
         graph::{GraphUpdateSwitches, NodePool},
         node::{constructor::NodeConstructorContainer, Node},
         sound::SoundEngine,
-        Scene, SceneContainer, SceneLoader,
+        Scene, SceneContainer, SceneLoadProgress, SceneLoader,
     },
     script::{
         constructor::ScriptConstructorContainer, RoutingStrategy, Script, ScriptContext,
         ScriptDeinitContext, ScriptMessage, ScriptMessageContext, ScriptMessageKind,
         ScriptMessageSender,
     },
+    utils::state_hash::StateHasher,
     window::{Window, WindowBuilder},
 };
 use fxhash::{FxHashMap, FxHashSet};
@@ -56,9 +76,10 @@ use fyrox_sound::{
 #[cfg(not(target_arch = "wasm32"))]
 use glutin::{
     config::ConfigTemplateBuilder,
+    config::GetGlConfig,
     context::{
         ContextApi, ContextAttributesBuilder, GlProfile, NotCurrentGlContext,
-        PossiblyCurrentContext, Version,
+        PossiblyCurrentContext, Robustness, Version,
     },
     display::{GetGlDisplay, GlDisplay},
     surface::{GlSurface, Surface, SwapInterval, WindowSurface},
@@ -71,7 +92,7 @@ use raw_window_handle::HasRawWindowHandle;
 #[cfg(not(target_arch = "wasm32"))]
 use std::{ffi::CString, num::NonZeroU32};
 
-use crate::engine::task::TaskPoolHandler;
+use crate::engine::{blackboard::Blackboard, streaming::LevelStreamer, task::TaskPoolHandler};
 use crate::resource::texture;
 use crate::script::PluginsRefMut;
 use fyrox_core::task::TaskPool;
@@ -84,6 +105,7 @@ use std::{
     collections::{HashSet, VecDeque},
     fmt::{Display, Formatter},
     ops::Deref,
+    panic::AssertUnwindSafe,
     path::{Path, PathBuf},
     sync::{
         mpsc::{channel, Receiver, Sender},
@@ -209,6 +231,8 @@ impl GraphicsContext {
 
 struct SceneLoadingOptions {
     derived: bool,
+    // The scene is a chunk requested by the level streamer.
+    streaming: bool,
 }
 
 /// A helper that is used to load scenes asynchronously.
@@ -299,6 +323,7 @@ struct LoadingScene {
     reported: bool,
     path: PathBuf,
     options: SceneLoadingOptions,
+    progress: SceneLoadProgress,
 }
 
 struct SceneLoadingResult {
@@ -327,6 +352,19 @@ impl AsyncSceneLoader {
         if self.loading_scenes.contains_key(&path) {
             Log::warn(format!("A scene {} is already loading!", path.display()))
         } else {
+            // Start loading in a separate off-thread task.
+            let sender = self.sender.clone();
+
+            // Aquire the resource IO from the resource manager
+            let io = self.resource_manager.resource_io();
+
+            let (progress, loading) = SceneLoader::load_async(
+                path.clone(),
+                io,
+                self.serialization_context.clone(),
+                self.resource_manager.clone(),
+            );
+
             // Register a new request.
             self.loading_scenes.insert(
                 path.clone(),
@@ -334,45 +372,18 @@ impl AsyncSceneLoader {
                     reported: false,
                     path: path.clone(),
                     options: opts,
+                    progress,
                 },
             );
 
-            // Start loading in a separate off-thread task.
-            let sender = self.sender.clone();
-            let serialization_context = self.serialization_context.clone();
-            let resource_manager = self.resource_manager.clone();
-
-            // Aquire the resource IO from the resource manager
-            let io = resource_manager.resource_io();
-
             let future = async move {
-                match SceneLoader::from_file(
-                    path.clone(),
-                    io.as_ref(),
-                    serialization_context,
-                    resource_manager.clone(),
-                )
-                .await
-                {
-                    Ok((loader, data)) => {
-                        let scene = loader.finish(&resource_manager).await;
-                        Log::verify(sender.send(SceneLoadingResult {
-                            path,
-                            result: Ok((scene, data)),
-                        }));
-                    }
-                    Err(e) => {
-                        Log::verify(sender.send(SceneLoadingResult {
-                            path,
-                            result: Err(e),
-                        }));
-                    }
-                }
+                let result = loading.await;
+                Log::verify(sender.send(SceneLoadingResult { path, result }));
             };
 
             #[cfg(not(target_arch = "wasm32"))]
             {
-                std::thread::spawn(move || block_on(future));
+                std::thread::spawn(move || crate::core::futures::executor::block_on(future));
             }
 
             #[cfg(target_arch = "wasm32")]
@@ -394,12 +405,43 @@ impl AsyncSceneLoader {
     /// Raw scene, on other hand, loads the scene as-is without any additional markings for the
     /// scene nodes. It could be useful to load saved games.
     pub fn request<P: AsRef<Path>>(&mut self, path: P) {
-        self.request_with_options(path, SceneLoadingOptions { derived: true });
+        self.request_with_options(
+            path,
+            SceneLoadingOptions {
+                derived: true,
+                streaming: false,
+            },
+        );
     }
 
     /// Requests a scene for loading in raw mode. See [`Self::request`] docs for more info.
     pub fn request_raw<P: AsRef<Path>>(&mut self, path: P) {
-        self.request_with_options(path, SceneLoadingOptions { derived: false });
+        self.request_with_options(
+            path,
+            SceneLoadingOptions {
+                derived: false,
+                streaming: false,
+            },
+        );
+    }
+
+    fn request_streaming<P: AsRef<Path>>(&mut self, path: P) {
+        self.request_with_options(
+            path,
+            SceneLoadingOptions {
+                derived: false,
+                streaming: true,
+            },
+        );
+    }
+
+    /// Returns loading progress of a scene at the given path, or [`None`] if there's no such scene
+    /// being loaded. It could be used to render loading screens, see [`SceneLoadProgress`] docs for
+    /// more info.
+    pub fn progress<P: AsRef<Path>>(&self, path: P) -> Option<&SceneLoadProgress> {
+        self.loading_scenes
+            .get(path.as_ref())
+            .map(|loading_scene| &loading_scene.progress)
     }
 }
 
@@ -426,6 +468,13 @@ pub struct Engine {
     /// Task pool for asynchronous task management.
     pub task_pool: TaskPoolHandler,
 
+    /// Global container for shared game state. See [`Blackboard`] docs for more info.
+    pub blackboard: Blackboard,
+
+    /// Level streamer, that loads and unloads chunks of levels using streaming volumes. See
+    /// [`LevelStreamer`] docs for more info.
+    pub level_streamer: LevelStreamer,
+
     performance_statistics: PerformanceStatistics,
 
     model_events_receiver: Receiver<ResourceEvent>,
@@ -503,6 +552,7 @@ impl ScriptMessageDispatcher {
         user_interface: &mut UserInterface,
         graphics_context: &mut GraphicsContext,
         task_pool: &mut TaskPoolHandler,
+        blackboard: &mut Blackboard,
     ) {
         while let Ok(message) = self.message_receiver.try_recv() {
             let receivers = self.type_groups.get(&message.payload.deref().type_id());
@@ -530,6 +580,7 @@ impl ScriptMessageDispatcher {
                                 resource_manager,
                                 message_sender,
                                 task_pool,
+                                blackboard,
                                 graphics_context,
                                 user_interface,
                             };
@@ -555,6 +606,7 @@ impl ScriptMessageDispatcher {
                                     resource_manager,
                                     message_sender,
                                     task_pool,
+                                    blackboard,
                                     graphics_context,
                                     user_interface,
                                 };
@@ -580,6 +632,7 @@ impl ScriptMessageDispatcher {
                                     resource_manager,
                                     message_sender,
                                     task_pool,
+                                    blackboard,
                                     graphics_context,
                                     user_interface,
                                 };
@@ -604,6 +657,7 @@ impl ScriptMessageDispatcher {
                                 resource_manager,
                                 message_sender,
                                 task_pool,
+                                blackboard,
                                 graphics_context,
                                 user_interface,
                             };
@@ -666,6 +720,7 @@ impl ScriptProcessor {
         plugins: &mut [Box<dyn Plugin>],
         resource_manager: &ResourceManager,
         task_pool: &mut TaskPoolHandler,
+        blackboard: &mut Blackboard,
         graphics_context: &mut GraphicsContext,
         user_interface: &mut UserInterface,
         dt: f32,
@@ -730,6 +785,7 @@ impl ScriptProcessor {
                     message_sender: &scripted_scene.message_sender,
                     message_dispatcher: &mut scripted_scene.message_dispatcher,
                     task_pool,
+                    blackboard,
                     graphics_context,
                     user_interface,
                 };
@@ -738,6 +794,9 @@ impl ScriptProcessor {
                     // Process events first. `on_init` of a script can also create some other instances
                     // and these will be correctly initialized on current frame.
                     while let Ok(event) = context.scene.graph.script_message_receiver.try_recv() {
+                        // Scripts were assigned or replaced, so the index of scripts is outdated.
+                        context.scene.graph.invalidate_script_index();
+
                         match event {
                             NodeScriptMessage::InitializeScript { handle } => {
                                 context.handle = handle;
@@ -814,6 +873,7 @@ impl ScriptProcessor {
                         user_interface,
                         graphics_context,
                         task_pool,
+                        blackboard,
                     );
                 }
 
@@ -837,6 +897,7 @@ impl ScriptProcessor {
                 user_interface,
                 graphics_context,
                 task_pool,
+                blackboard,
             };
             while let Some((handle, mut script)) = destruction_queue.pop_front() {
                 context.node_handle = handle;
@@ -862,6 +923,7 @@ impl ScriptProcessor {
                     node_handle: Default::default(),
                     message_sender: &scripted_scene.message_sender,
                     task_pool,
+                    blackboard,
                     graphics_context,
                     user_interface,
                 };
@@ -933,8 +995,10 @@ impl ResourceGraphVertex {
             self.resource.kind()
         ));
 
-        // Wait until resource is fully loaded, then resolve.
-        if block_on(self.resource.clone()).is_ok() {
+        // Do not block here waiting for a resource that is still loading - this would freeze
+        // the main thread (and deadlock on WebAssembly where there is only one thread). Such
+        // resource will be resolved when it finishes loading anyway.
+        if self.resource.is_ok() {
             self.resource
                 .data_ref()
                 .get_scene_mut()
@@ -1047,6 +1111,7 @@ pub(crate) fn process_scripts<T>(
     message_sender: &ScriptMessageSender,
     message_dispatcher: &mut ScriptMessageDispatcher,
     task_pool: &mut TaskPoolHandler,
+    blackboard: &mut Blackboard,
     graphics_context: &mut GraphicsContext,
     user_interface: &mut UserInterface,
     dt: f32,
@@ -1066,6 +1131,7 @@ pub(crate) fn process_scripts<T>(
         message_sender,
         message_dispatcher,
         task_pool,
+        blackboard,
         graphics_context,
         user_interface,
     };
@@ -1081,14 +1147,15 @@ pub(crate) fn initialize_resource_manager_loaders(
     resource_manager: &ResourceManager,
     serialization_context: Arc<SerializationContext>,
 ) {
+    let mut state = resource_manager.state();
+
     let model_loader = ModelLoader {
         resource_manager: resource_manager.clone(),
         serialization_context,
         default_import_options: Default::default(),
+        import_hooks: state.import_hooks.clone(),
     };
 
-    let mut state = resource_manager.state();
-
     for shader in ShaderResource::standard_shaders() {
         state
             .built_in_resources
@@ -1119,6 +1186,7 @@ pub(crate) fn initialize_resource_manager_loaders(
         material::STANDARD_TERRAIN.clone(),
         material::STANDARD_TWOSIDES.clone(),
         material::STANDARD_PARTICLE_SYSTEM.clone(),
+        material::STANDARD_WATER.clone(),
     ] {
         state.built_in_resources.insert(
             material.kind().path_owned().unwrap(),
@@ -1130,22 +1198,39 @@ pub(crate) fn initialize_resource_manager_loaders(
     state.constructors_container.add::<Shader>();
     state.constructors_container.add::<Model>();
     state.constructors_container.add::<CurveResourceState>();
+    state.constructors_container.add::<TileSet>();
+    state.constructors_container.add::<TileMapData>();
     state.constructors_container.add::<SoundBuffer>();
     state.constructors_container.add::<HrirSphereResourceData>();
     state.constructors_container.add::<Material>();
     state.constructors_container.add::<Font>();
     state.constructors_container.add::<UserInterface>();
-
+    state.constructors_container.add::<SurfaceEffects>();
+    state.constructors_container.add::<Timeline>();
+    state.constructors_container.add::<InputGlyphAtlas>();
+    state.constructors_container.add::<LightProbeData>();
+    state.constructors_container.add::<TextureAtlas>();
+    state.constructors_container.add::<SoundBank>();
+
+    let import_hooks = state.import_hooks.clone();
     let loaders = &mut state.loaders;
     loaders.set(model_loader);
     loaders.set(TextureLoader {
         default_import_options: Default::default(),
+        import_hooks: import_hooks.clone(),
     });
     loaders.set(SoundBufferLoader {
         default_import_options: Default::default(),
+        import_hooks,
     });
     loaders.set(ShaderLoader);
     loaders.set(CurveLoader);
+    loaders.set(TileSetLoader {
+        resource_manager: resource_manager.clone(),
+    });
+    loaders.set(TileMapDataLoader {
+        resource_manager: resource_manager.clone(),
+    });
     loaders.set(HrirSphereLoader);
     loaders.set(MaterialLoader {
         resource_manager: resource_manager.clone(),
@@ -1154,6 +1239,182 @@ pub(crate) fn initialize_resource_manager_loaders(
     loaders.set(UserInterfaceLoader {
         resource_manager: resource_manager.clone(),
     });
+    loaders.set(SurfaceEffectsLoader {
+        resource_manager: resource_manager.clone(),
+    });
+    loaders.set(TimelineLoader);
+    loaders.set(InputGlyphAtlasLoader {
+        resource_manager: resource_manager.clone(),
+    });
+    loaders.set(LightProbeDataLoader);
+    loaders.set(TextureAtlasLoader {
+        resource_manager: resource_manager.clone(),
+    });
+    loaders.set(SoundBankLoader {
+        resource_manager: resource_manager.clone(),
+    });
+}
+
+/// Creates a GL context for the given surface and makes it current. Robust contexts with "lose context
+/// on reset" notification strategy are preferred, because they allow to reliably detect context loss.
+/// Drivers, that do not support robustness, fail to create such contexts, so non-robust contexts are
+/// used as a fallback.
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::type_complexity)]
+unsafe fn create_gl_context(
+    gl_config: &glutin::config::Config,
+    raw_window_handle: raw_window_handle::RawWindowHandle,
+    gl_surface: &Surface<WindowSurface>,
+    vsync: bool,
+) -> Result<
+    (
+        PossiblyCurrentContext,
+        glow::Context,
+        GlKind,
+        Option<crate::renderer::framework::state::GraphicsResetStatusFn>,
+    ),
+    EngineError,
+> {
+    use crate::renderer::framework::state::GraphicsResetStatusFn;
+    use glow::HasContext;
+
+    let gl_display = gl_config.display();
+
+    #[cfg(debug_assertions)]
+    let debug = true;
+
+    #[cfg(not(debug_assertions))]
+    let debug = true;
+
+    let gl3_3_core = ContextApi::OpenGl(Some(Version::new(3, 3)));
+    let gles3 = ContextApi::Gles(Some(Version::new(3, 0)));
+    let candidates = [
+        (
+            gl3_3_core,
+            GlKind::OpenGL,
+            Robustness::RobustLoseContextOnReset,
+        ),
+        (gl3_3_core, GlKind::OpenGL, Robustness::NotRobust),
+        (
+            gles3,
+            GlKind::OpenGLES,
+            Robustness::RobustLoseContextOnReset,
+        ),
+        (gles3, GlKind::OpenGLES, Robustness::NotRobust),
+    ];
+
+    let mut last_error = None;
+    for (context_api, gl_kind, robustness) in candidates {
+        let context_attributes = ContextAttributesBuilder::new()
+            .with_debug(debug)
+            .with_profile(GlProfile::Core)
+            .with_context_api(context_api)
+            .with_robustness(robustness)
+            .build(Some(raw_window_handle));
+
+        let non_current_gl_context = match gl_display.create_context(gl_config, &context_attributes)
+        {
+            Ok(context) => context,
+            Err(err) => {
+                last_error = Some(err);
+                continue;
+            }
+        };
+
+        let gl_context = non_current_gl_context.make_current(gl_surface)?;
+
+        if vsync {
+            Log::verify(
+                gl_surface.set_swap_interval(
+                    &gl_context,
+                    SwapInterval::Wait(NonZeroU32::new(1).unwrap()),
+                ),
+            );
+        }
+
+        let glow_context = glow::Context::from_loader_function(|s| {
+            gl_display.get_proc_address(&CString::new(s).unwrap())
+        });
+
+        let reset_status_fn = if robustness == Robustness::RobustLoseContextOnReset {
+            let version = glow_context.version();
+            graphics_reset_status_fn_names(
+                version.major,
+                version.minor,
+                version.is_embedded,
+                glow_context.supported_extensions(),
+            )
+            .iter()
+            .find_map(|name| {
+                let ptr = gl_display.get_proc_address(&CString::new(*name).unwrap());
+                if ptr.is_null() {
+                    None
+                } else {
+                    Some(std::mem::transmute::<
+                        *const std::ffi::c_void,
+                        GraphicsResetStatusFn,
+                    >(ptr))
+                }
+            })
+        } else {
+            None
+        };
+
+        if reset_status_fn.is_none() {
+            Log::warn("Robust GL context is not supported, context loss detection is limited.");
+        }
+
+        return Ok((gl_context, glow_context, gl_kind, reset_status_fn));
+    }
+
+    Err(last_error
+        .map(EngineError::from)
+        .unwrap_or_else(|| EngineError::Custom("Unable to create GL context!".to_string())))
+}
+
+/// Returns names of the functions, that could be used to query reset status of a robust GL context of
+/// the given version with the given set of extensions.
+#[cfg(not(target_arch = "wasm32"))]
+fn graphics_reset_status_fn_names(
+    major: u32,
+    minor: u32,
+    is_embedded: bool,
+    extensions: &std::collections::HashSet<String>,
+) -> &'static [&'static str] {
+    let is_core = if is_embedded {
+        (major, minor) >= (3, 2)
+    } else {
+        (major, minor) >= (4, 5)
+    };
+    if is_core || extensions.contains("GL_KHR_robustness") {
+        &["glGetGraphicsResetStatus", "glGetGraphicsResetStatusKHR"]
+    } else if extensions.contains("GL_ARB_robustness") {
+        &["glGetGraphicsResetStatusARB"]
+    } else if extensions.contains("GL_EXT_robustness") {
+        &["glGetGraphicsResetStatusEXT"]
+    } else {
+        &[]
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn create_webgl2_context(
+    canvas: &crate::core::web_sys::HtmlCanvasElement,
+) -> Result<glow::Context, EngineError> {
+    use crate::core::wasm_bindgen::JsCast;
+
+    let webgl2_context = canvas
+        .get_context("webgl2")
+        .ok()
+        .flatten()
+        .and_then(|context| {
+            context
+                .dyn_into::<crate::core::web_sys::WebGl2RenderingContext>()
+                .ok()
+        })
+        .ok_or_else(|| EngineError::Custom("Unable to create WebGL2 context!".to_string()))?;
+
+    Ok(glow::Context::from_webgl2_context(webgl2_context))
 }
 
 impl Engine {
@@ -1230,6 +1491,8 @@ impl Engine {
             plugin_constructors: Default::default(),
             elapsed_time: 0.0,
             task_pool: TaskPoolHandler::new(task_pool),
+            blackboard: Default::default(),
+            level_streamer: Default::default(),
         })
     }
 
@@ -1281,7 +1544,7 @@ impl Engine {
                 .with_active(params.window_attributes.active);
 
             #[cfg(not(target_arch = "wasm32"))]
-            let (window, gl_context, gl_surface, glow_context, gl_kind) = {
+            let (window, gl_context, gl_surface, glow_context, gl_kind, reset_status_fn) = {
                 let template = ConfigTemplateBuilder::new()
                     .prefer_hardware_accelerated(Some(true))
                     .with_stencil_size(8)
@@ -1295,28 +1558,6 @@ impl Engine {
 
                 let window = opt_window.unwrap();
 
-                let raw_window_handle = window.raw_window_handle();
-
-                let gl_display = gl_config.display();
-
-                #[cfg(debug_assertions)]
-                let debug = true;
-
-                #[cfg(not(debug_assertions))]
-                let debug = true;
-
-                let gl3_3_core_context_attributes = ContextAttributesBuilder::new()
-                    .with_debug(debug)
-                    .with_profile(GlProfile::Core)
-                    .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
-                    .build(Some(raw_window_handle));
-
-                let gles3_context_attributes = ContextAttributesBuilder::new()
-                    .with_debug(debug)
-                    .with_profile(GlProfile::Core)
-                    .with_context_api(ContextApi::Gles(Some(Version::new(3, 0))))
-                    .build(Some(raw_window_handle));
-
                 unsafe {
                     let attrs = window.build_surface_attributes(Default::default());
 
@@ -1324,34 +1565,20 @@ impl Engine {
                         .display()
                         .create_window_surface(&gl_config, &attrs)?;
 
-                    let (non_current_gl_context, gl_kind) = if let Ok(gl3_3_core_context) =
-                        gl_display.create_context(&gl_config, &gl3_3_core_context_attributes)
-                    {
-                        (gl3_3_core_context, GlKind::OpenGL)
-                    } else {
-                        (
-                            gl_display.create_context(&gl_config, &gles3_context_attributes)?,
-                            GlKind::OpenGLES,
-                        )
-                    };
-
-                    let gl_context = non_current_gl_context.make_current(&gl_surface)?;
-
-                    if params.vsync {
-                        Log::verify(gl_surface.set_swap_interval(
-                            &gl_context,
-                            SwapInterval::Wait(NonZeroU32::new(1).unwrap()),
-                        ));
-                    }
+                    let (gl_context, glow_context, gl_kind, reset_status_fn) = create_gl_context(
+                        &gl_config,
+                        window.raw_window_handle(),
+                        &gl_surface,
+                        params.vsync,
+                    )?;
 
                     (
                         window,
                         gl_context,
                         gl_surface,
-                        glow::Context::from_loader_function(|s| {
-                            gl_display.get_proc_address(&CString::new(s).unwrap())
-                        }),
+                        glow_context,
                         gl_kind,
+                        reset_status_fn,
                     )
                 }
             };
@@ -1359,7 +1586,6 @@ impl Engine {
             #[cfg(target_arch = "wasm32")]
             let (window, glow_context, gl_kind) = {
                 use crate::{
-                    core::wasm_bindgen::JsCast,
                     dpi::{LogicalSize, PhysicalSize},
                     platform::web::WindowExtWebSys,
                 };
@@ -1401,17 +1627,7 @@ impl Engine {
                 body.append_child(&canvas)
                     .expect("Append canvas to HTML body");
 
-                let webgl2_context = canvas
-                    .get_context("webgl2")
-                    .unwrap()
-                    .unwrap()
-                    .dyn_into::<crate::core::web_sys::WebGl2RenderingContext>()
-                    .unwrap();
-                (
-                    window,
-                    glow::Context::from_webgl2_context(webgl2_context),
-                    GlKind::OpenGLES,
-                )
+                (window, create_webgl2_context(&canvas)?, GlKind::OpenGLES)
             };
 
             self.user_interface.set_screen_size(Vector2::new(
@@ -1443,6 +1659,13 @@ impl Engine {
                 params: params.clone(),
             });
 
+            #[cfg(not(target_arch = "wasm32"))]
+            if let GraphicsContext::Initialized(ref mut ctx) = self.graphics_context {
+                ctx.renderer
+                    .pipeline_state()
+                    .set_graphics_reset_status_fn(reset_status_fn);
+            }
+
             self.sound_engine.initialize_audio_output_device()?;
 
             Ok(())
@@ -1499,6 +1722,113 @@ impl Engine {
         }
     }
 
+    /// Checks whether the graphics context was lost. It could happen on mobile devices when the OS
+    /// reclaims GPU resources of the app or on any platform after a driver reset. Use
+    /// [`Self::recover_graphics_context`] to re-create the context.
+    pub fn is_graphics_context_lost(&self) -> bool {
+        if let GraphicsContext::Initialized(ref ctx) = self.graphics_context {
+            ctx.renderer.is_context_lost()
+        } else {
+            false
+        }
+    }
+
+    /// Re-creates the graphics context after its loss. Only the GL context and the renderer are
+    /// re-created, the window and the audio output device are kept intact. All GPU resources (textures,
+    /// vertex and index buffers, shaders) are re-created by the new renderer on demand from the CPU-side
+    /// data of the respective resources, quality settings of the renderer are preserved. Keep in mind,
+    /// that the contents of render targets are lost and must be re-rendered.
+    ///
+    /// If the recovery fails, the graphics context is destroyed and an error is returned. In this case
+    /// [`Self::initialize_graphics_context`] could be used to create a new graphics context.
+    pub fn recover_graphics_context(&mut self) -> Result<(), EngineError> {
+        let GraphicsContext::Initialized(ctx) = std::mem::replace(
+            &mut self.graphics_context,
+            GraphicsContext::Uninitialized(Default::default()),
+        ) else {
+            return Err(EngineError::Custom(
+                "Graphics context is not initialized!".to_string(),
+            ));
+        };
+
+        let InitializedGraphicsContext {
+            window,
+            renderer,
+            params,
+            #[cfg(not(target_arch = "wasm32"))]
+            gl_context,
+            #[cfg(not(target_arch = "wasm32"))]
+            gl_surface,
+        } = ctx;
+
+        let quality_settings = renderer.get_quality_settings();
+
+        // GPU resources of the old renderer must be released while the old context is still current,
+        // otherwise the new context could lose its own objects with the same names.
+        drop(renderer);
+
+        self.graphics_context = GraphicsContext::Uninitialized(params.clone());
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let (gl_context, gl_surface, glow_context, gl_kind, reset_status_fn) = {
+            let gl_config = gl_context.config();
+            drop(gl_context);
+
+            let (gl_context, glow_context, gl_kind, reset_status_fn) = unsafe {
+                create_gl_context(
+                    &gl_config,
+                    window.raw_window_handle(),
+                    &gl_surface,
+                    params.vsync,
+                )?
+            };
+
+            (
+                gl_context,
+                gl_surface,
+                glow_context,
+                gl_kind,
+                reset_status_fn,
+            )
+        };
+
+        #[cfg(target_arch = "wasm32")]
+        let (glow_context, gl_kind) = {
+            use crate::platform::web::WindowExtWebSys;
+
+            let canvas = window.canvas().ok_or_else(|| {
+                EngineError::Custom("The window does not have a canvas!".to_string())
+            })?;
+
+            (create_webgl2_context(&canvas)?, GlKind::OpenGLES)
+        };
+
+        let mut renderer = Renderer::new(
+            glow_context,
+            (window.inner_size().width, window.inner_size().height),
+            &self.resource_manager,
+            gl_kind,
+        )?;
+        renderer.set_quality_settings(&quality_settings)?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        renderer
+            .pipeline_state()
+            .set_graphics_reset_status_fn(reset_status_fn);
+
+        self.graphics_context = GraphicsContext::Initialized(InitializedGraphicsContext {
+            window,
+            renderer,
+            params,
+            #[cfg(not(target_arch = "wasm32"))]
+            gl_context,
+            #[cfg(not(target_arch = "wasm32"))]
+            gl_surface,
+        });
+
+        Ok(())
+    }
+
     /// Adjust size of the frame to be rendered. Must be called after the window size changes.
     /// Will update the renderer and GL context frame size.
     pub fn set_frame_size(&mut self, new_size: (u32, u32)) -> Result<(), FrameworkError> {
@@ -1543,16 +1873,31 @@ impl Engine {
         lag: &mut f32,
         switches: FxHashMap<Handle<Scene>, GraphUpdateSwitches>,
     ) {
-        self.handle_async_scene_loading(dt, lag, window_target);
-        self.pre_update(dt, window_target, lag, switches);
-        self.post_update(dt);
+        CrashReporter::update_context(&self.scenes, self.elapsed_time);
+
+        if CrashReporter::wants_scene_snapshots() {
+            let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                self.handle_async_scene_loading(dt, lag, Some(window_target));
+                self.pre_update(dt, window_target, lag, switches);
+                self.post_update(dt);
+            }));
+
+            if let Err(payload) = result {
+                CrashReporter::save_scene_snapshots(&mut self.scenes);
+                std::panic::resume_unwind(payload);
+            }
+        } else {
+            self.handle_async_scene_loading(dt, lag, Some(window_target));
+            self.pre_update(dt, window_target, lag, switches);
+            self.post_update(dt);
+        }
     }
 
     fn handle_async_scene_loading(
         &mut self,
         dt: f32,
         lag: &mut f32,
-        window_target: &EventLoopWindowTarget<()>,
+        window_target: Option<&EventLoopWindowTarget<()>>,
     ) {
         let len = self.async_scene_loader.loading_scenes.len();
         let mut n = 0;
@@ -1562,7 +1907,7 @@ impl Engine {
                     request.reported = true;
 
                     // Notify plugins about a scene, that started loading.
-                    if self.plugins_enabled {
+                    if self.plugins_enabled && !request.options.streaming {
                         let path = request.path.clone();
                         let mut context = PluginContext {
                             scenes: &mut self.scenes,
@@ -1576,8 +1921,9 @@ impl Engine {
                             elapsed_time: self.elapsed_time,
                             script_processor: &self.script_processor,
                             async_scene_loader: &mut self.async_scene_loader,
-                            window_target: Some(window_target),
+                            window_target,
                             task_pool: &mut self.task_pool,
+                            blackboard: &mut self.blackboard,
                         };
 
                         for plugin in self.plugins.iter_mut() {
@@ -1608,8 +1954,9 @@ impl Engine {
                     elapsed_time: self.elapsed_time,
                     script_processor: &self.script_processor,
                     async_scene_loader: &mut self.async_scene_loader,
-                    window_target: Some(window_target),
+                    window_target,
                     task_pool: &mut self.task_pool,
+                    blackboard: &mut self.blackboard,
                 };
 
                 match loading_result.result {
@@ -1674,10 +2021,20 @@ impl Engine {
                             }
                         }
 
+                        if scene.rendering_options.static_batching {
+                            scene.graph.build_static_batches();
+                        }
+
                         let scene_handle = context.scenes.add(scene);
 
-                        // Notify plugins about newly loaded scene.
-                        if self.plugins_enabled {
+                        if request.options.streaming {
+                            self.level_streamer.on_chunk_loaded(
+                                context.scenes,
+                                &request.path,
+                                scene_handle,
+                            );
+                        } else if self.plugins_enabled {
+                            // Notify plugins about newly loaded scene.
                             for plugin in self.plugins.iter_mut() {
                                 Log::info(format!(
                                     "Scene {} was loaded successfully!",
@@ -1694,8 +2051,16 @@ impl Engine {
                         }
                     }
                     Err(error) => {
-                        // Notify plugins about a scene, that is failed to load.
-                        if self.plugins_enabled {
+                        if request.options.streaming {
+                            Log::err(format!(
+                                "Unable to stream chunk {}. Reason: {:?}",
+                                loading_result.path.display(),
+                                error
+                            ));
+
+                            self.level_streamer.on_chunk_loading_failed(&request.path);
+                        } else if self.plugins_enabled {
+                            // Notify plugins about a scene, that is failed to load.
                             Log::err(format!(
                                 "Unable to load scene {}. Reason: {:?}",
                                 loading_result.path.display(),
@@ -1710,6 +2075,9 @@ impl Engine {
                 }
             }
         }
+
+        self.level_streamer
+            .update(&mut self.scenes, &mut self.async_scene_loader);
     }
 
     /// Performs pre update for the engine.
@@ -1739,6 +2107,8 @@ impl Engine {
 
             self.resource_manager.state().update(dt);
             ctx.renderer.update_caches(dt);
+            let projection_jitter = ctx.renderer.projection_jitter();
+            let taa_cameras = ctx.renderer.taa_cameras().clone();
             self.handle_model_events();
 
             for (handle, scene) in self.scenes.pair_iter_mut().filter(|(_, s)| *s.enabled) {
@@ -1755,6 +2125,14 @@ impl Engine {
                             }
                         });
 
+                // Only the cameras, that are rendered with temporal anti-aliasing, need jitter. When
+                // TAA is disabled, the jitter is zero and it resets the offsets of such cameras.
+                for &(_, camera) in taa_cameras.iter().filter(|(scene, _)| *scene == handle) {
+                    if let Some(camera) = scene.graph.try_get_mut_of_type::<Camera>(camera) {
+                        camera.set_projection_jitter(projection_jitter);
+                    }
+                }
+
                 scene.update(
                     frame_size,
                     dt,
@@ -1762,7 +2140,7 @@ impl Engine {
                 );
             }
 
-            self.update_plugins(dt, window_target, lag);
+            self.update_plugins(dt, Some(window_target), lag);
             self.handle_scripts(dt);
         }
     }
@@ -1783,6 +2161,74 @@ impl Engine {
         }
     }
 
+    /// Performs single update tick with given time delta without any window and graphics context. It
+    /// updates resources, scenes (including physics, animations, sound, etc.), plugins, scripts and user
+    /// interface in the same order as [`Self::update`] does. Rendering is not performed, so this method
+    /// is suitable for dedicated servers, automated testing and determinism checks (see
+    /// [`Self::state_hash`]).
+    ///
+    /// Scenes with a render target use its size as the frame size, every other scene uses current screen
+    /// size of the user interface.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use fyrox::engine::Engine;
+    /// fn run(engine: &mut Engine) -> u64 {
+    ///     for _ in 0..100 {
+    ///         engine.step_headless(1.0 / 60.0);
+    ///     }
+    ///     // Compare the hash with the hash of a reference run.
+    ///     engine.state_hash()
+    /// }
+    /// ```
+    pub fn step_headless(&mut self, dt: f32) {
+        let mut lag = 0.0;
+
+        self.handle_async_scene_loading(dt, &mut lag, None);
+
+        self.resource_manager.state().update(dt);
+        self.handle_model_events();
+
+        let screen_size = self.user_interface.screen_size();
+        for scene in self.scenes.iter_mut().filter(|s| *s.enabled) {
+            let frame_size = scene
+                .rendering_options
+                .render_target
+                .as_ref()
+                .and_then(|rt| {
+                    if let TextureKind::Rectangle { width, height } = rt.data_ref().kind() {
+                        Some(Vector2::new(width as f32, height as f32))
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or(screen_size);
+
+            scene.update(frame_size, dt, Default::default());
+        }
+
+        self.update_plugins(dt, None, &mut lag);
+        self.handle_scripts(dt);
+
+        let time = instant::Instant::now();
+        self.user_interface.update(screen_size, dt);
+        self.performance_statistics.ui_time = instant::Instant::now() - time;
+        self.elapsed_time += dt;
+    }
+
+    /// Calculates a platform-independent hash of the state of every scene. See
+    /// [`crate::scene::graph::Graph::state_hash`] docs for more info.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        for (handle, scene) in self.scenes.pair_iter() {
+            hasher.write_u32(handle.index());
+            hasher.write_u32(handle.generation());
+            scene.graph.write_state_hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
     /// Returns true if the scene is registered for script processing.
     pub fn has_scripted_scene(&self, scene: Handle<Scene>) -> bool {
         self.script_processor.has_scripted_scene(scene)
@@ -1824,6 +2270,7 @@ impl Engine {
                                 message_sender: &scripted_scene.message_sender,
                                 message_dispatcher: &mut scripted_scene.message_dispatcher,
                                 task_pool: &mut self.task_pool,
+                                blackboard: &mut self.blackboard,
                                 graphics_context: &mut self.graphics_context,
                                 user_interface: &mut self.user_interface,
                             },
@@ -1842,6 +2289,7 @@ impl Engine {
             &mut self.plugins,
             &self.resource_manager,
             &mut self.task_pool,
+            &mut self.blackboard,
             &mut self.graphics_context,
             &mut self.user_interface,
             dt,
@@ -1854,7 +2302,7 @@ impl Engine {
     fn update_plugins(
         &mut self,
         dt: f32,
-        window_target: &EventLoopWindowTarget<()>,
+        window_target: Option<&EventLoopWindowTarget<()>>,
         lag: &mut f32,
     ) {
         let time = instant::Instant::now();
@@ -1877,8 +2325,9 @@ impl Engine {
                         elapsed_time: self.elapsed_time,
                         script_processor: &self.script_processor,
                         async_scene_loader: &mut self.async_scene_loader,
-                        window_target: Some(window_target),
+                        window_target,
                         task_pool: &mut self.task_pool,
+                        blackboard: &mut self.blackboard,
                     },
                 )
             }
@@ -1896,8 +2345,9 @@ impl Engine {
                 elapsed_time: self.elapsed_time,
                 script_processor: &self.script_processor,
                 async_scene_loader: &mut self.async_scene_loader,
-                window_target: Some(window_target),
+                window_target,
                 task_pool: &mut self.task_pool,
+                blackboard: &mut self.blackboard,
             };
 
             for plugin in self.plugins.iter_mut() {
@@ -1917,8 +2367,9 @@ impl Engine {
                     elapsed_time: self.elapsed_time,
                     script_processor: &self.script_processor,
                     async_scene_loader: &mut self.async_scene_loader,
-                    window_target: Some(window_target),
+                    window_target,
                     task_pool: &mut self.task_pool,
+                    blackboard: &mut self.blackboard,
                 };
 
                 for plugin in self.plugins.iter_mut() {
@@ -1939,24 +2390,43 @@ impl Engine {
     ) {
         if self.plugins_enabled {
             for plugin in self.plugins.iter_mut() {
-                plugin.on_os_event(
-                    event,
-                    PluginContext {
-                        scenes: &mut self.scenes,
-                        resource_manager: &self.resource_manager,
-                        graphics_context: &mut self.graphics_context,
-                        dt,
-                        lag,
-                        user_interface: &mut self.user_interface,
-                        serialization_context: &self.serialization_context,
-                        performance_statistics: &self.performance_statistics,
-                        elapsed_time: self.elapsed_time,
-                        script_processor: &self.script_processor,
-                        async_scene_loader: &mut self.async_scene_loader,
-                        window_target: Some(window_target),
-                        task_pool: &mut self.task_pool,
-                    },
-                );
+                let mut context = PluginContext {
+                    scenes: &mut self.scenes,
+                    resource_manager: &self.resource_manager,
+                    graphics_context: &mut self.graphics_context,
+                    dt,
+                    lag,
+                    user_interface: &mut self.user_interface,
+                    serialization_context: &self.serialization_context,
+                    performance_statistics: &self.performance_statistics,
+                    elapsed_time: self.elapsed_time,
+                    script_processor: &self.script_processor,
+                    async_scene_loader: &mut self.async_scene_loader,
+                    window_target: Some(window_target),
+                    task_pool: &mut self.task_pool,
+                    blackboard: &mut self.blackboard,
+                };
+
+                if let Event::WindowEvent {
+                    event: window_event,
+                    ..
+                } = event
+                {
+                    match window_event {
+                        WindowEvent::HoveredFile(path) => {
+                            plugin.on_file_hovered(path, &mut context)
+                        }
+                        WindowEvent::HoveredFileCancelled => {
+                            plugin.on_file_hover_cancelled(&mut context)
+                        }
+                        WindowEvent::DroppedFile(path) => {
+                            plugin.on_file_dropped(path, &mut context)
+                        }
+                        _ => (),
+                    }
+                }
+
+                plugin.on_os_event(event, context);
             }
         }
     }
@@ -1983,6 +2453,7 @@ impl Engine {
                     async_scene_loader: &mut self.async_scene_loader,
                     window_target: Some(window_target),
                     task_pool: &mut self.task_pool,
+                    blackboard: &mut self.blackboard,
                 });
             }
         }
@@ -2010,6 +2481,7 @@ impl Engine {
                     async_scene_loader: &mut self.async_scene_loader,
                     window_target: Some(window_target),
                     task_pool: &mut self.task_pool,
+                    blackboard: &mut self.blackboard,
                 });
             }
         }
@@ -2037,6 +2509,7 @@ impl Engine {
                     async_scene_loader: &mut self.async_scene_loader,
                     window_target: Some(window_target),
                     task_pool: &mut self.task_pool,
+                    blackboard: &mut self.blackboard,
                 });
             }
         }
@@ -2071,6 +2544,7 @@ impl Engine {
                     &scripted_scene.message_sender,
                     &mut scripted_scene.message_dispatcher,
                     &mut self.task_pool,
+                    &mut self.blackboard,
                     &mut self.graphics_context,
                     &mut self.user_interface,
                     dt,
@@ -2109,6 +2583,9 @@ impl Engine {
                     // however this seems to be very rare case so it should be ok.
                     for scene in self.scenes.iter_mut() {
                         scene.resolve(&self.resource_manager);
+
+                        // Physical shapes could be built from the geometry of the reloaded model.
+                        scene.graph.invalidate_geometry_derived_shapes();
                     }
                 }
             }
@@ -2173,6 +2650,7 @@ impl Engine {
                             async_scene_loader: &mut self.async_scene_loader,
                             window_target,
                             task_pool: &mut self.task_pool,
+                            blackboard: &mut self.blackboard,
                         },
                     ));
                 }
@@ -2195,6 +2673,7 @@ impl Engine {
                         async_scene_loader: &mut self.async_scene_loader,
                         window_target,
                         task_pool: &mut self.task_pool,
+                        blackboard: &mut self.blackboard,
                     });
                 }
             }
@@ -2243,7 +2722,10 @@ mod test {
             impl_component_provider, pool::Handle, reflect::prelude::*, task::TaskPool,
             uuid_provider, visitor::prelude::*,
         },
-        engine::{task::TaskPoolHandler, GraphicsContext, ScriptProcessor},
+        engine::{
+            blackboard::Blackboard, task::TaskPoolHandler, Engine, EngineInitParams,
+            GraphicsContext, ScriptProcessor, SerializationContext,
+        },
         scene::{base::BaseBuilder, node::Node, pivot::PivotBuilder, Scene, SceneContainer},
         script::{
             Script, ScriptContext, ScriptDeinitContext, ScriptMessageContext, ScriptMessagePayload,
@@ -2375,6 +2857,7 @@ mod test {
         let handle_on_start = Handle::new(3, 1);
         let handle_on_update1 = Handle::new(4, 1);
         let mut task_pool = TaskPoolHandler::new(Arc::new(TaskPool::new()));
+        let mut blackboard = Blackboard::default();
         let mut gc = GraphicsContext::Uninitialized(Default::default());
         let mut user_interface = UserInterface::default();
 
@@ -2384,6 +2867,7 @@ mod test {
                 &mut Vec::new(),
                 &resource_manager,
                 &mut task_pool,
+                &mut blackboard,
                 &mut gc,
                 &mut user_interface,
                 0.0,
@@ -2537,6 +3021,7 @@ mod test {
 
         let mut script_processor = ScriptProcessor::default();
         let mut task_pool = TaskPoolHandler::new(Arc::new(TaskPool::new()));
+        let mut blackboard = Blackboard::default();
         let mut gc = GraphicsContext::Uninitialized(Default::default());
         let mut user_interface = UserInterface::default();
 
@@ -2548,6 +3033,7 @@ mod test {
                 &mut Vec::new(),
                 &resource_manager,
                 &mut task_pool,
+                &mut blackboard,
                 &mut gc,
                 &mut user_interface,
                 0.0,
@@ -2567,4 +3053,53 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_recover_uninitialized_graphics_context() {
+        let task_pool = Arc::new(TaskPool::new());
+        let mut engine = Engine::new(EngineInitParams {
+            graphics_context_params: Default::default(),
+            resource_manager: ResourceManager::new(task_pool.clone()),
+            serialization_context: Arc::new(SerializationContext::new()),
+            task_pool,
+        })
+        .unwrap();
+
+        assert!(!engine.is_graphics_context_lost());
+        assert!(engine.recover_graphics_context().is_err());
+        assert!(matches!(
+            engine.graphics_context,
+            GraphicsContext::Uninitialized(_)
+        ));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_graphics_reset_status_fn_names() {
+        use crate::engine::graphics_reset_status_fn_names;
+
+        let no_extensions = Default::default();
+        assert_eq!(
+            graphics_reset_status_fn_names(4, 5, false, &no_extensions),
+            &["glGetGraphicsResetStatus", "glGetGraphicsResetStatusKHR"]
+        );
+        assert_eq!(
+            graphics_reset_status_fn_names(3, 2, true, &no_extensions),
+            &["glGetGraphicsResetStatus", "glGetGraphicsResetStatusKHR"]
+        );
+        assert!(graphics_reset_status_fn_names(3, 3, false, &no_extensions).is_empty());
+        assert!(graphics_reset_status_fn_names(3, 0, true, &no_extensions).is_empty());
+
+        let arb = ["GL_ARB_robustness".to_string()].into_iter().collect();
+        assert_eq!(
+            graphics_reset_status_fn_names(3, 3, false, &arb),
+            &["glGetGraphicsResetStatusARB"]
+        );
+
+        let ext = ["GL_EXT_robustness".to_string()].into_iter().collect();
+        assert_eq!(
+            graphics_reset_status_fn_names(3, 0, true, &ext),
+            &["glGetGraphicsResetStatusEXT"]
+        );
+    }
 }
@@ -0,0 +1,483 @@
+//! Level streaming loads and unloads chunks of levels automatically, using streaming volumes. See
+//! [`LevelStreamer`] docs for more info.
+
+use crate::{
+    core::{algebra::Vector3, log::Log, pool::Handle},
+    engine::AsyncSceneLoader,
+    scene::{
+        camera::Camera,
+        graph::{event::GraphEvent, Graph},
+        node::Node,
+        streaming::StreamingVolume,
+        Scene, SceneContainer,
+    },
+};
+use fxhash::{FxHashMap, FxHashSet};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+};
+
+/// State of a chunk of a streaming volume.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChunkState {
+    /// The chunk is being loaded.
+    Loading(PathBuf),
+    /// The chunk is being loaded, but it is not needed anymore and will be unloaded right after
+    /// it is loaded.
+    Cancelled(PathBuf),
+    /// The chunk is loaded and merged into the scene, the handle points to the root node of the
+    /// chunk.
+    Loaded(Handle<Node>),
+    /// The chunk has failed to load. It won't be requested again until the tracked node leaves
+    /// the volume.
+    Failed,
+}
+
+type VolumeKey = (Handle<Scene>, Handle<Node>);
+
+// Streaming volumes and cameras of a scene, the sets are kept in sync using graph events, so the
+// streamer does not need to iterate over every node of the scene every frame.
+struct SceneNodes {
+    receiver: Receiver<GraphEvent>,
+    volumes: FxHashSet<Handle<Node>>,
+    cameras: FxHashSet<Handle<Node>>,
+}
+
+impl SceneNodes {
+    fn new(graph: &mut Graph) -> Self {
+        let (sender, receiver) = channel();
+        graph.event_broadcaster.subscribe(sender);
+        let mut nodes = Self {
+            receiver,
+            volumes: Default::default(),
+            cameras: Default::default(),
+        };
+        for (handle, node) in graph.pair_iter() {
+            nodes.add(handle, node);
+        }
+        nodes
+    }
+
+    fn add(&mut self, handle: Handle<Node>, node: &Node) {
+        if node.cast::<StreamingVolume>().is_some() {
+            self.volumes.insert(handle);
+        } else if node.cast::<Camera>().is_some() {
+            self.cameras.insert(handle);
+        }
+    }
+
+    fn sync(&mut self, graph: &Graph) {
+        while let Ok(event) = self.receiver.try_recv() {
+            match event {
+                GraphEvent::Added(handle) => {
+                    if let Some(node) = graph.try_get(handle) {
+                        self.add(handle, node);
+                    }
+                }
+                GraphEvent::Removed(handle) => {
+                    self.volumes.remove(&handle);
+                    self.cameras.remove(&handle);
+                }
+            }
+        }
+    }
+
+    // Returns the position of the first enabled camera of the scene.
+    fn camera_position(&self, graph: &Graph) -> Option<Vector3<f32>> {
+        self.cameras
+            .iter()
+            .filter_map(|handle| graph.try_get(*handle).map(|camera| (*handle, camera)))
+            .filter(|(_, camera)| camera.is_globally_enabled())
+            .min_by_key(|(handle, _)| handle.index())
+            .map(|(_, camera)| camera.global_position())
+    }
+}
+
+/// Level streamer loads chunks of levels when tracked nodes enter streaming volumes and unloads them
+/// when the nodes leave the volumes. See [`StreamingVolume`] docs for more info about volumes.
+///
+/// Chunks are loaded using [`AsyncSceneLoader`] in raw mode and merged into scenes using
+/// [`SceneContainer::load_additive`], so the scenes, that were requested by the streamer, are not
+/// reported to plugins. Level streaming is performed only when the engine is updated using
+/// [`crate::engine::Engine::update`] or [`crate::engine::Engine::step_headless`], so it is not
+/// active in the editor.
+///
+/// # Important notes
+///
+/// Loaded chunks are regular nodes of the scene, which means that they will be saved together with
+/// the scene. Make sure to unload them (using [`Self::unload_all`]) before saving the game, if you
+/// don't want this.
+pub struct LevelStreamer {
+    enabled: bool,
+    max_concurrent_loads: usize,
+    chunks: FxHashMap<VolumeKey, ChunkState>,
+    loading: FxHashMap<PathBuf, VolumeKey>,
+    scene_nodes: FxHashMap<Handle<Scene>, SceneNodes>,
+}
+
+impl Default for LevelStreamer {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_concurrent_loads: 2,
+            chunks: Default::default(),
+            loading: Default::default(),
+            scene_nodes: Default::default(),
+        }
+    }
+}
+
+impl LevelStreamer {
+    /// Enables or disables the streamer. Disabled streamer does not load or unload any chunks, but
+    /// finishes the loading of requested chunks.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Returns `true` if the streamer is enabled, `false` - otherwise.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets the maximum amount of chunks that could be loaded simultaneously. Default is 2.
+    pub fn set_max_concurrent_loads(&mut self, count: usize) {
+        self.max_concurrent_loads = count.max(1);
+    }
+
+    /// Returns the maximum amount of chunks that could be loaded simultaneously.
+    pub fn max_concurrent_loads(&self) -> usize {
+        self.max_concurrent_loads
+    }
+
+    /// Returns the state of a chunk of the given streaming volume, `None` means that the chunk is
+    /// not loaded.
+    pub fn chunk_state(&self, scene: Handle<Scene>, volume: Handle<Node>) -> Option<&ChunkState> {
+        self.chunks.get(&(scene, volume))
+    }
+
+    /// Returns a handle of the root node of the loaded chunk of the given streaming volume.
+    pub fn loaded_chunk(&self, scene: Handle<Scene>, volume: Handle<Node>) -> Option<Handle<Node>> {
+        match self.chunk_state(scene, volume) {
+            Some(ChunkState::Loaded(chunk)) => Some(*chunk),
+            _ => None,
+        }
+    }
+
+    /// Unloads every loaded chunk of the given scene. Chunks, that are being loaded, will be
+    /// unloaded right after they're loaded.
+    pub fn unload_all(&mut self, scenes: &mut SceneContainer, scene: Handle<Scene>) {
+        self.chunks.retain(|(chunk_scene, _), state| {
+            if *chunk_scene != scene {
+                return true;
+            }
+            match state {
+                ChunkState::Loading(path) | ChunkState::Cancelled(path) => {
+                    *state = ChunkState::Cancelled(path.clone());
+                    true
+                }
+                ChunkState::Loaded(chunk) => {
+                    if scenes.is_valid_handle(scene) {
+                        scenes.unload_additive(scene, *chunk);
+                    }
+                    false
+                }
+                ChunkState::Failed => false,
+            }
+        });
+    }
+
+    fn unload(&mut self, scenes: &mut SceneContainer, key: VolumeKey) {
+        match self.chunks.get(&key).cloned() {
+            Some(ChunkState::Loading(path)) => {
+                self.chunks.insert(key, ChunkState::Cancelled(path));
+            }
+            Some(ChunkState::Loaded(chunk)) => {
+                if scenes.is_valid_handle(key.0) {
+                    scenes.unload_additive(key.0, chunk);
+                }
+                self.chunks.remove(&key);
+            }
+            Some(ChunkState::Failed) => {
+                self.chunks.remove(&key);
+            }
+            Some(ChunkState::Cancelled(_)) | None => (),
+        }
+    }
+
+    pub(crate) fn update(&mut self, scenes: &mut SceneContainer, loader: &mut AsyncSceneLoader) {
+        // Unload chunks of removed volumes (or scenes).
+        let orphans = self
+            .chunks
+            .keys()
+            .filter(|(scene, volume)| {
+                scenes
+                    .try_get(*scene)
+                    .and_then(|scene| scene.graph.try_get_of_type::<StreamingVolume>(*volume))
+                    .is_none()
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in orphans {
+            self.unload(scenes, key);
+        }
+
+        if !self.enabled {
+            // Dropped receivers unsubscribe from graph events, the caches are rebuilt when the
+            // streamer is enabled again.
+            self.scene_nodes.clear();
+            return;
+        }
+
+        self.scene_nodes
+            .retain(|scene, _| scenes.is_valid_handle(*scene));
+        for (scene_handle, scene) in scenes.pair_iter_mut() {
+            self.scene_nodes
+                .entry(scene_handle)
+                .or_insert_with(|| SceneNodes::new(&mut scene.graph))
+                .sync(&scene.graph);
+        }
+
+        let mut candidates = Vec::new();
+        let mut far_away = Vec::new();
+        for (scene_handle, scene) in scenes.pair_iter().filter(|(_, s)| *s.enabled) {
+            let Some(nodes) = self.scene_nodes.get(&scene_handle) else {
+                continue;
+            };
+            // The camera is searched only once per scene and only if some volume needs it.
+            let mut camera_position = None;
+            for &volume_handle in nodes.volumes.iter() {
+                let Some(volume) = scene
+                    .graph
+                    .try_get_of_type::<StreamingVolume>(volume_handle)
+                else {
+                    continue;
+                };
+                if !volume.is_globally_enabled() || volume.chunk() == Path::new("") {
+                    continue;
+                }
+                let position = match scene.graph.try_get(volume.tracked_node()) {
+                    Some(node) => Some(node.global_position()),
+                    None => {
+                        *camera_position.get_or_insert_with(|| nodes.camera_position(&scene.graph))
+                    }
+                };
+                let Some(position) = position else {
+                    continue;
+                };
+
+                let key = (scene_handle, volume_handle);
+                let distance = volume.distance(position);
+                match self.chunks.get_mut(&key) {
+                    None => {
+                        if distance <= 0.0 {
+                            candidates.push((volume.priority(), key, volume.chunk().to_path_buf()))
+                        }
+                    }
+                    Some(state) => {
+                        if distance > volume.hysteresis() {
+                            far_away.push(key);
+                        } else if let ChunkState::Cancelled(path) = state {
+                            if distance <= 0.0 {
+                                // The chunk is needed again, keep loading it.
+                                *state = ChunkState::Loading(path.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for key in far_away {
+            self.unload(scenes, key);
+        }
+
+        // Volumes are stored in a hash set, so volumes with the same priority are sorted by their
+        // handles to make the loading order deterministic.
+        candidates.sort_by_key(|(priority, (_, volume), _)| {
+            (std::cmp::Reverse(*priority), volume.index())
+        });
+        for (_, key, path) in candidates {
+            if self.loading.len() >= self.max_concurrent_loads {
+                break;
+            }
+            if self.loading.contains_key(&path) || loader.loading_scenes.contains_key(&path) {
+                // The same chunk is requested by some other volume (or by a user), try again later.
+                continue;
+            }
+            loader.request_streaming(&path);
+            self.loading.insert(path.clone(), key);
+            self.chunks.insert(key, ChunkState::Loading(path));
+        }
+    }
+
+    pub(crate) fn on_chunk_loaded(
+        &mut self,
+        scenes: &mut SceneContainer,
+        path: &Path,
+        chunk_scene: Handle<Scene>,
+    ) {
+        let Some(key) = self.loading.remove(path) else {
+            return;
+        };
+
+        let chunk = match self.chunks.get(&key) {
+            Some(ChunkState::Loading(_)) => scenes.load_additive(key.0, chunk_scene),
+            _ => None,
+        };
+
+        if let Some(chunk) = chunk {
+            self.chunks.insert(key, ChunkState::Loaded(chunk));
+            Log::info(format!("Chunk {} was streamed in.", path.display()));
+        } else {
+            if scenes.is_valid_handle(chunk_scene) {
+                scenes.remove(chunk_scene);
+            }
+            self.chunks.remove(&key);
+        }
+    }
+
+    pub(crate) fn on_chunk_loading_failed(&mut self, path: &Path) {
+        if let Some(key) = self.loading.remove(path) {
+            if let Some(state) = self.chunks.get_mut(&key) {
+                *state = ChunkState::Failed;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::manager::ResourceManager,
+        core::{algebra::Vector3, pool::Handle},
+        engine::{
+            streaming::{ChunkState, LevelStreamer},
+            AsyncSceneLoader, SerializationContext,
+        },
+        scene::{
+            base::BaseBuilder, node::Node, pivot::PivotBuilder, sound::SoundEngine,
+            streaming::StreamingVolumeBuilder, transform::TransformBuilder, Scene, SceneContainer,
+        },
+    };
+    use std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
+    struct Context {
+        streamer: LevelStreamer,
+        scenes: SceneContainer,
+        loader: AsyncSceneLoader,
+        scene: Handle<Scene>,
+        player: Handle<Node>,
+        volume: Handle<Node>,
+    }
+
+    impl Context {
+        fn new() -> Self {
+            let mut scenes = SceneContainer::new(SoundEngine::without_device());
+            let mut scene = Scene::new();
+            let player = PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+            let scene = scenes.add(scene);
+            Self {
+                streamer: Default::default(),
+                scenes,
+                loader: AsyncSceneLoader::new(
+                    ResourceManager::new(Arc::new(Default::default())),
+                    Arc::new(SerializationContext::new()),
+                ),
+                scene,
+                player,
+                volume: Handle::NONE,
+            }
+        }
+
+        fn move_player(&mut self, x: f32) -> Option<ChunkState> {
+            let graph = &mut self.scenes[self.scene].graph;
+            graph[self.player]
+                .local_transform_mut()
+                .set_position(Vector3::new(x, 0.0, 0.0));
+            graph.update_hierarchical_data();
+            self.streamer.update(&mut self.scenes, &mut self.loader);
+            self.streamer.chunk_state(self.scene, self.volume).cloned()
+        }
+
+        fn finish_loading(&mut self) -> Handle<Scene> {
+            let mut chunk = Scene::new();
+            PivotBuilder::new(BaseBuilder::new().with_name("Chunk")).build(&mut chunk.graph);
+            let chunk = self.scenes.add(chunk);
+            // Imitate the loader, which forgets the scene before reporting it.
+            self.loader.loading_scenes.remove(Path::new("chunk.rgs"));
+            self.streamer
+                .on_chunk_loaded(&mut self.scenes, Path::new("chunk.rgs"), chunk);
+            chunk
+        }
+    }
+
+    #[test]
+    fn test_streaming_state_machine() {
+        let mut ctx = Context::new();
+        assert_eq!(ctx.move_player(0.0), None);
+
+        // Volumes added after the first update are picked up from graph events.
+        ctx.volume = StreamingVolumeBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_scale(Vector3::repeat(10.0))
+                    .build(),
+            ),
+        )
+        .with_chunk("chunk.rgs")
+        .with_tracked_node(ctx.player)
+        .with_hysteresis(5.0)
+        .build(&mut ctx.scenes[ctx.scene].graph);
+        let loading = Some(ChunkState::Loading(PathBuf::from("chunk.rgs")));
+        let cancelled = Some(ChunkState::Cancelled(PathBuf::from("chunk.rgs")));
+        assert_eq!(ctx.move_player(0.0), loading);
+
+        // Leaving the volume cancels the loading, coming back resumes it.
+        assert_eq!(ctx.move_player(100.0), cancelled);
+        assert_eq!(ctx.move_player(0.0), loading);
+
+        // Cancelled chunks are discarded right after they're loaded.
+        assert_eq!(ctx.move_player(100.0), cancelled);
+        let chunk_scene = ctx.finish_loading();
+        assert!(!ctx.scenes.is_valid_handle(chunk_scene));
+        assert_eq!(
+            ctx.streamer.chunk_state(ctx.scene, ctx.volume).cloned(),
+            None
+        );
+
+        assert_eq!(ctx.move_player(0.0), loading);
+        ctx.finish_loading();
+        let Some(ChunkState::Loaded(chunk)) = ctx.move_player(0.0) else {
+            panic!("The chunk must be loaded.")
+        };
+        assert!(ctx.scenes[ctx.scene].graph.is_valid_handle(chunk));
+
+        // The chunk is kept while the player is within the hysteresis distance.
+        assert_eq!(ctx.move_player(8.0), Some(ChunkState::Loaded(chunk)));
+        assert_eq!(ctx.move_player(20.0), None);
+        assert!(!ctx.scenes[ctx.scene].graph.is_valid_handle(chunk));
+    }
+
+    #[test]
+    fn test_chunk_of_removed_volume_is_unloaded() {
+        let mut ctx = Context::new();
+        ctx.volume = StreamingVolumeBuilder::new(BaseBuilder::new())
+            .with_chunk("chunk.rgs")
+            .with_tracked_node(ctx.player)
+            .build(&mut ctx.scenes[ctx.scene].graph);
+        ctx.move_player(0.0);
+        ctx.finish_loading();
+        let Some(ChunkState::Loaded(chunk)) = ctx.move_player(0.0) else {
+            panic!("The chunk must be loaded.")
+        };
+
+        ctx.scenes[ctx.scene].graph.remove_node(ctx.volume);
+        assert_eq!(ctx.move_player(0.0), None);
+        assert!(!ctx.scenes[ctx.scene].graph.is_valid_handle(chunk));
+        assert!(ctx.streamer.scene_nodes[&ctx.scene].volumes.is_empty());
+    }
+}
@@ -28,10 +28,15 @@ use std::error::Error;
 use std::{
     any::Any,
     fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
     ops::Deref,
     path::Path,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
 pub mod loader;
 pub mod shader;
@@ -42,7 +47,7 @@ pub mod shader;
 ///
 /// There is a limited set of possible types that can be passed to a shader, most of them are
 /// just simple data types.
-#[derive(Debug, Visit, Clone, Reflect)]
+#[derive(Debug, Visit, Clone, Reflect, PartialEq, AsRefStr, EnumString, EnumVariantNames)]
 pub enum PropertyValue {
     /// Real number.
     Float(f32),
@@ -293,12 +298,58 @@ impl PropertyValue {
     }
 }
 
+impl PropertyValue {
+    /// Writes the value to the given hasher. Real numbers are hashed by their bit representation,
+    /// textures are hashed by their resource identity.
+    pub fn hash_value<H: Hasher>(&self, hasher: &mut H) {
+        fn write_floats<'a, H: Hasher>(hasher: &mut H, values: impl Iterator<Item = &'a f32>) {
+            for value in values {
+                hasher.write_u32(value.to_bits());
+            }
+        }
+
+        std::mem::discriminant(self).hash(hasher);
+        match self {
+            PropertyValue::Float(v) => hasher.write_u32(v.to_bits()),
+            PropertyValue::FloatArray(v) => write_floats(hasher, v.iter()),
+            PropertyValue::Int(v) => hasher.write_i32(*v),
+            PropertyValue::IntArray(v) => v.iter().for_each(|v| hasher.write_i32(*v)),
+            PropertyValue::UInt(v) => hasher.write_u32(*v),
+            PropertyValue::UIntArray(v) => v.iter().for_each(|v| hasher.write_u32(*v)),
+            PropertyValue::Vector2(v) => write_floats(hasher, v.iter()),
+            PropertyValue::Vector2Array(v) => v.iter().for_each(|v| write_floats(hasher, v.iter())),
+            PropertyValue::Vector3(v) => write_floats(hasher, v.iter()),
+            PropertyValue::Vector3Array(v) => v.iter().for_each(|v| write_floats(hasher, v.iter())),
+            PropertyValue::Vector4(v) => write_floats(hasher, v.iter()),
+            PropertyValue::Vector4Array(v) => v.iter().for_each(|v| write_floats(hasher, v.iter())),
+            PropertyValue::Matrix2(v) => write_floats(hasher, v.iter()),
+            PropertyValue::Matrix2Array(v) => v.iter().for_each(|v| write_floats(hasher, v.iter())),
+            PropertyValue::Matrix3(v) => write_floats(hasher, v.iter()),
+            PropertyValue::Matrix3Array(v) => v.iter().for_each(|v| write_floats(hasher, v.iter())),
+            PropertyValue::Matrix4(v) => write_floats(hasher, v.iter()),
+            PropertyValue::Matrix4Array(v) => v.iter().for_each(|v| write_floats(hasher, v.iter())),
+            PropertyValue::Bool(v) => hasher.write_u8(*v as u8),
+            PropertyValue::Color(v) => hasher.write(&[v.r, v.g, v.b, v.a]),
+            PropertyValue::Sampler { value, fallback } => {
+                hasher.write_usize(value.as_ref().map_or(0, |texture| texture.key()));
+                hasher.write_u8(*fallback as u8);
+            }
+        }
+    }
+}
+
 impl Default for PropertyValue {
     fn default() -> Self {
         Self::Float(0.0)
     }
 }
 
+impl TypeUuidProvider for PropertyValue {
+    fn type_uuid() -> Uuid {
+        uuid!("3900564b-72b6-48dc-96f0-185d16e4eafe")
+    }
+}
+
 /// Material defines a set of values for a shader. Materials usually contains textures (diffuse,
 /// normal, height, emission, etc. maps), numerical values (floats, integers), vectors, booleans,
 /// matrices and arrays of each type, except textures. Each parameter can be changed in runtime
@@ -399,6 +450,29 @@ impl Default for PropertyValue {
 pub struct Material {
     shader: ShaderResource,
     properties: FxHashMap<ImmutableString, PropertyValue>,
+    #[reflect(hidden)]
+    batching_key: BatchingKeyCache,
+}
+
+// Cached result of `Material::batching_key`, reset on every modification of the material. Zero
+// means that the key is not calculated yet.
+#[derive(Debug, Default)]
+struct BatchingKeyCache(AtomicU64);
+
+impl Clone for BatchingKeyCache {
+    fn clone(&self) -> Self {
+        Self(AtomicU64::new(self.0.load(Ordering::Relaxed)))
+    }
+}
+
+impl BatchingKeyCache {
+    fn get(&self) -> Option<u64> {
+        Some(self.0.load(Ordering::Relaxed)).filter(|key| *key != 0)
+    }
+
+    fn set(&self, key: Option<u64>) {
+        self.0.store(key.unwrap_or_default(), Ordering::Relaxed)
+    }
 }
 
 impl Visit for Material {
@@ -417,6 +491,7 @@ impl Visit for Material {
         shader.visit("Shader", &mut region)?;
         self.shader = shader;
         self.properties.visit("Properties", &mut region)?;
+        self.batching_key.set(None);
 
         Ok(())
     }
@@ -570,6 +645,15 @@ lazy_static! {
     );
 }
 
+lazy_static! {
+    /// Standard water material. Keep in mind that this material is global, any modification
+    /// of it will reflect on every other usage of it.
+    pub static ref STANDARD_WATER: MaterialResource = MaterialResource::new_ok(
+        "__StandardWaterMaterial".into(),
+        Material::from_shader(ShaderResource::standard_water(), None),
+    );
+}
+
 impl Material {
     /// Creates a new instance of material with the standard shader. For the full list
     /// of properties of the standard material see [shader module docs](self::shader).
@@ -628,6 +712,11 @@ impl Material {
         Self::from_shader(ShaderResource::standard_terrain(), None)
     }
 
+    /// Creates new instance of standard water material.
+    pub fn standard_water() -> Self {
+        Self::from_shader(ShaderResource::standard_water(), None)
+    }
+
     /// Creates a new material instance with given shader. Each property will have default values
     /// defined in the shader.
     ///
@@ -678,6 +767,7 @@ impl Material {
         Self {
             shader,
             properties: property_values,
+            batching_key: Default::default(),
         }
     }
 
@@ -694,6 +784,7 @@ impl Material {
         let mut material = Material {
             shader: Default::default(),
             properties: Default::default(),
+            batching_key: Default::default(),
         };
         let mut visitor = Visitor::load_from_memory(&content)?;
         visitor.blackboard.register(Arc::new(resource_manager));
@@ -747,6 +838,7 @@ impl Material {
         new_value: PropertyValue,
     ) -> Result<(), MaterialError> {
         if let Some(value) = self.properties.get_mut(name) {
+            self.batching_key.set(None);
             match (value, new_value) {
                 (
                     PropertyValue::Sampler {
@@ -855,6 +947,7 @@ impl Material {
     /// properties. This method has limited usage, that is mostly related to shader hot reloading. Returns `true`
     /// if the syncing was successful, `false` - if the shader resource is not loaded.
     pub fn sync_to_shader(&mut self, resource_manager: &ResourceManager) -> bool {
+        self.batching_key.set(None);
         let shader_kind = self.shader.kind().clone();
         if let Some(shader) = self.shader.state().data() {
             if shader.definition.properties.len() > self.properties.len() {
@@ -913,6 +1006,40 @@ impl Material {
     pub fn properties(&self) -> &FxHashMap<ImmutableString, PropertyValue> {
         &self.properties
     }
+
+    /// Calculates a hash of the material content (shader and property values). Two materials with
+    /// the same shader and the same property values (including textures) will have the same key, so
+    /// the renderer can put geometry that uses such materials in the same batch even if the materials
+    /// are different resources. Different materials could have the same key too (hash collision), use
+    /// [`Self::has_same_content`] to check whether the materials are really the same. The key is cached
+    /// until the material is modified. The key is not stable across multiple runs of your application!
+    pub fn batching_key(&self) -> u64 {
+        if let Some(key) = self.batching_key.get() {
+            return key;
+        }
+
+        // Properties are stored in a hash map, so the combination must not depend on iteration order.
+        let properties_hash = self.properties.iter().fold(0u64, |acc, (name, value)| {
+            let mut hasher = fxhash::FxHasher::default();
+            hasher.write(name.as_bytes());
+            value.hash_value(&mut hasher);
+            acc.wrapping_add(hasher.finish())
+        });
+
+        let mut hasher = fxhash::FxHasher::default();
+        hasher.write_usize(self.shader.key());
+        hasher.write_u64(properties_hash);
+        // Zero is reserved for the "not calculated" state.
+        let key = hasher.finish().max(1);
+        self.batching_key.set(Some(key));
+        key
+    }
+
+    /// Returns `true` if both materials have the same shader and the same property values (including
+    /// textures), `false` - otherwise.
+    pub fn has_same_content(&self, other: &Self) -> bool {
+        self.shader == other.shader && self.properties == other.properties
+    }
 }
 
 /// Shared material is a material instance that can be used across multiple objects. It is useful
@@ -994,3 +1121,29 @@ where
     }
     None
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{color::Color, sstorage::ImmutableString},
+        material::{Material, PropertyValue},
+    };
+
+    #[test]
+    fn test_material_batching_key() {
+        let mut a = Material::standard();
+        let b = Material::standard();
+        assert_eq!(a.batching_key(), b.batching_key());
+        assert!(a.has_same_content(&b));
+
+        // The cached key must be reset on modification.
+        a.set_property(
+            &ImmutableString::new("diffuseColor"),
+            PropertyValue::Color(Color::RED),
+        )
+        .unwrap();
+        assert_ne!(a.batching_key(), b.batching_key());
+        assert!(!a.has_same_content(&b));
+        assert!(!a.has_same_content(&Material::standard_2d()));
+    }
+}
@@ -133,18 +133,20 @@
 //! There are number of predefined render passes:
 //!
 //! - GBuffer - A pass that fills a set of render target sized textures with various data
-//! about each rendered object. These textures then are used for physically-based lighting.
-//! Use this pass when you want the standard lighting to work with your objects.
+//!   about each rendered object. These textures then are used for physically-based lighting.
+//!   Use this pass when you want the standard lighting to work with your objects. The pass should also
+//!   write per-pixel velocity (`layout(location = 5) out vec2 outVelocity`, see `S_Velocity`), it is
+//!   used by temporal anti-aliasing to reproject previous frames.
 //!
 //! - Forward - A pass that draws an object directly in render target. This pass is very
-//! limiting, it does not support lighting, shadows, etc. It should be only used to render
-//! translucent objects.
+//!   limiting, it does not support lighting, shadows, etc. It should be only used to render
+//!   translucent objects.
 //!
 //! - SpotShadow - A pass that emits depth values for an object, later this depth map will be
-//! used to render shadows.
+//!   used to render shadows.
 //!
 //! - PointShadow - A pass that emits distance from a fragment to a point light, later this depth
-//! map will be used to render shadows.
+//!   map will be used to render shadows.
 //!
 //! ## Built-in properties
 //!
@@ -157,12 +159,19 @@
 //! | fyrox_worldViewProjection  | `mat4`       | Local-to-clip-space transform.                                                                                    |
 //! | fyrox_boneMatrices         | `sampler2D`  | Array of bone matrices packed into a texture. Use `S_FetchMatrix` built-in method to fetch a matrix by its index. |
 //! | fyrox_useSkeletalAnimation | `bool`       | Whether skinned meshes is rendering or not.                                                                       |
+//! | fyrox_useDualQuaternionSkinning | `bool`  | Whether bone matrices should be blended as dual quaternions. Use `S_DualQuaternionBlend` built-in method to blend. |
 //! | fyrox_cameraPosition       | `vec3`       | Position of the camera.                                                                                           |
 //! | fyrox_usePOM               | `bool`       | Whether to use parallax mapping or not.                                                                           |
 //! | fyrox_lightPosition        | `vec3`       | Light position.                                                                                                   |
 //! | fyrox_blendShapesStorage   | `sampler3D`  | 3D texture of layered blend shape storage. Use `S_FetchBlendShapeOffsets` built-in method to fetch info.          |
 //! | fyrox_blendShapesWeights   | `float[128]` | Weights of all available blend shapes.                                                                            |
 //! | fyrox_blendShapesCount     | `int`        | Total amount of blend shapes.                                                                                     |
+//! | fyrox_useInstancing        | `bool`       | Whether instanced rendering is used or not. Only G-Buffer pass is rendered with instancing.                       |
+//! | fyrox_instanceMatrices     | `sampler2D`  | World matrices of instances. Use `S_FetchMatrix(fyrox_instanceMatrices, gl_InstanceID)` to fetch a matrix.        |
+//! | fyrox_instanceBoneCount    | `int`        | Amount of bones per instance. Bone matrices of instanced skinned surfaces are packed in `fyrox_boneMatrices` sequentially, offset bone indices by `gl_InstanceID * fyrox_instanceBoneCount`. |
+//! | fyrox_previousWorldViewProjection | `mat4` | Local-to-clip-space transform of the previous frame (without camera jitter). Available only in G-Buffer pass. |
+//! | fyrox_previousViewProjectionMatrix | `mat4` | World-to-clip-space transform of the previous frame (without camera jitter). Available only in G-Buffer pass. |
+//! | fyrox_projectionJitter     | `vec2`       | Sub-pixel offset of the current projection in normalized device coordinates. Use `S_Velocity` built-in method to calculate per-pixel velocity. |
 //!
 //! To use any of the properties, just define a uniform with an appropriate name:
 //!
@@ -235,7 +244,7 @@ use crate::{
         io::FileLoadError,
         reflect::prelude::*,
         sparse::AtomicIndex,
-        uuid::Uuid,
+        uuid::{uuid, Uuid},
         visitor::prelude::*,
         TypeUuidProvider,
     },
@@ -253,6 +262,7 @@ use std::{
     io::{Cursor, Write},
     path::{Path, PathBuf},
 };
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
 pub mod loader;
 
@@ -293,24 +303,32 @@ pub const STANDARD_SPRITE_SHADER_NAME: &str = "StandardSprite";
 /// A source code of the standard terrain shader.
 pub const STANDARD_TERRAIN_SHADER_SRC: &str = include_str!("standard/terrain.shader");
 
+/// A name of the standard water shader.
+pub const STANDARD_WATER_SHADER_NAME: &str = "StandardWater";
+
+/// A source code of the standard water shader.
+pub const STANDARD_WATER_SHADER_SRC: &str = include_str!("standard/water.shader");
+
 /// A list of names of standard shaders.
-pub const STANDARD_SHADER_NAMES: [&str; 6] = [
+pub const STANDARD_SHADER_NAMES: [&str; 7] = [
     STANDARD_SHADER_NAME,
     STANDARD_2D_SHADER_NAME,
     STANDARD_PARTICLE_SYSTEM_SHADER_NAME,
     STANDARD_SPRITE_SHADER_NAME,
     STANDARD_TWOSIDES_SHADER_NAME,
     STANDARD_TERRAIN_SHADER_NAME,
+    STANDARD_WATER_SHADER_NAME,
 ];
 
 /// A list of source code of standard shaders.
-pub const STANDARD_SHADER_SOURCES: [&str; 6] = [
+pub const STANDARD_SHADER_SOURCES: [&str; 7] = [
     STANDARD_SHADER_SRC,
     STANDARD_2D_SHADER_SRC,
     STANDARD_PARTICLE_SYSTEM_SHADER_SRC,
     STANDARD_SPRITE_SHADER_SRC,
     STANDARD_TWOSIDES_SHADER_SRC,
     STANDARD_TERRAIN_SHADER_SRC,
+    STANDARD_WATER_SHADER_SRC,
 ];
 
 /// Internal state of the shader.
@@ -351,7 +369,20 @@ impl TypeUuidProvider for Shader {
 ///
 /// Fallback value is also helpful to catch missing textures, you'll definitely know the texture is
 /// missing by very specific value in the fallback texture.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Visit, Eq, Reflect)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    PartialEq,
+    Clone,
+    Copy,
+    Visit,
+    Eq,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
 pub enum SamplerFallback {
     /// A 1x1px white texture.
     White,
@@ -367,6 +398,12 @@ impl Default for SamplerFallback {
     }
 }
 
+impl TypeUuidProvider for SamplerFallback {
+    fn type_uuid() -> Uuid {
+        uuid!("5581b854-e508-4763-b64b-fe76b9512948")
+    }
+}
+
 /// Shader property with default value.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Reflect, Visit)]
 pub enum PropertyKind {
@@ -616,6 +653,9 @@ pub trait ShaderResourceExtension: Sized {
     /// Returns an instance of standard two-sides terrain shader.
     fn standard_twosides() -> Self;
 
+    /// Returns an instance of standard water shader.
+    fn standard_water() -> Self;
+
     /// Returns a list of standard shader.
     fn standard_shaders() -> Vec<ShaderResource>;
 }
@@ -649,6 +689,10 @@ impl ShaderResourceExtension for ShaderResource {
         STANDARD_TWOSIDES.clone()
     }
 
+    fn standard_water() -> Self {
+        STANDARD_WATER.clone()
+    }
+
     fn standard_shaders() -> Vec<ShaderResource> {
         vec![
             Self::standard(),
@@ -657,6 +701,7 @@ impl ShaderResourceExtension for ShaderResource {
             Self::standard_sprite(),
             Self::standard_terrain(),
             Self::standard_twosides(),
+            Self::standard_water(),
         ]
     }
 }
@@ -703,6 +748,13 @@ lazy_static! {
     );
 }
 
+lazy_static! {
+    static ref STANDARD_WATER: ShaderResource = ShaderResource::new_ok(
+        STANDARD_WATER_SHADER_NAME.into(),
+        Shader::from_str(STANDARD_WATER_SHADER_SRC).unwrap(),
+    );
+}
+
 #[cfg(test)]
 mod test {
     use crate::material::shader::{
@@ -59,6 +59,15 @@
             name: "parallaxScale",
             kind: Float(0.08),
         ),
+        (
+            name: "vertexLightingStrength",
+            kind: Float(0.0),
+        ),
+        (
+            // L1 spherical harmonics of baked indirect lighting, provided by light probe volumes.
+            name: "lightProbeSH",
+            kind: Vector3Array([(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (0.0, 0.0, 0.0)]),
+        ),
     ],
 
     passes: [
@@ -92,13 +101,21 @@
                 layout(location = 4) in vec4 boneWeights;
                 layout(location = 5) in vec4 boneIndices;
                 layout(location = 6) in vec2 vertexSecondTexCoord;
+                layout(location = 7) in vec4 vertexColor;
 
                 // Define uniforms with reserved names. Fyrox will automatically provide
                 // required data to these uniforms.
                 uniform mat4 fyrox_worldMatrix;
+                uniform mat4 fyrox_viewProjectionMatrix;
                 uniform mat4 fyrox_worldViewProjection;
+                uniform mat4 fyrox_previousWorldViewProjection;
+                uniform mat4 fyrox_previousViewProjectionMatrix;
+                uniform bool fyrox_useInstancing;
+                uniform sampler2D fyrox_instanceMatrices;
                 uniform bool fyrox_useSkeletalAnimation;
+                uniform bool fyrox_useDualQuaternionSkinning;
                 uniform sampler2D fyrox_boneMatrices;
+                uniform int fyrox_instanceBoneCount;
 
                 out vec3 position;
                 out vec3 normal;
@@ -106,6 +123,9 @@
                 out vec3 tangent;
                 out vec3 binormal;
                 out vec2 secondTexCoord;
+                out vec3 vertexLight;
+                out vec4 clipPosition;
+                out vec4 previousClipPosition;
 
                 void main()
                 {
@@ -117,16 +137,28 @@
                     {
                         vec4 vertex = vec4(vertexPosition, 1.0);
 
-                        int i0 = int(boneIndices.x);
-                        int i1 = int(boneIndices.y);
-                        int i2 = int(boneIndices.z);
-                        int i3 = int(boneIndices.w);
+                        // Bone matrices of all instances are packed in a single palette.
+                        int boneOffset = fyrox_useInstancing ? gl_InstanceID * fyrox_instanceBoneCount : 0;
+
+                        int i0 = boneOffset + int(boneIndices.x);
+                        int i1 = boneOffset + int(boneIndices.y);
+                        int i2 = boneOffset + int(boneIndices.z);
+                        int i3 = boneOffset + int(boneIndices.w);
 
                         mat4 m0 = S_FetchMatrix(fyrox_boneMatrices, i0);
                         mat4 m1 = S_FetchMatrix(fyrox_boneMatrices, i1);
                         mat4 m2 = S_FetchMatrix(fyrox_boneMatrices, i2);
                         mat4 m3 = S_FetchMatrix(fyrox_boneMatrices, i3);
 
+                        if (fyrox_useDualQuaternionSkinning)
+                        {
+                            mat4 m = S_DualQuaternionBlend(m0, m1, m2, m3, boneWeights);
+                            m0 = m;
+                            m1 = m;
+                            m2 = m;
+                            m3 = m;
+                        }
+
                         localPosition += m0 * vertex * boneWeights.x;
                         localPosition += m1 * vertex * boneWeights.y;
                         localPosition += m2 * vertex * boneWeights.z;
@@ -149,15 +181,29 @@
                         localTangent = vertexTangent.xyz;
                     }
 
-                    mat3 nm = mat3(fyrox_worldMatrix);
+                    mat4 worldMatrix = fyrox_worldMatrix;
+                    mat4 worldViewProjection = fyrox_worldViewProjection;
+                    if (fyrox_useInstancing) {
+                        worldMatrix = S_FetchMatrix(fyrox_instanceMatrices, gl_InstanceID);
+                        worldViewProjection = fyrox_viewProjectionMatrix * worldMatrix;
+                    }
+
+                    mat3 nm = mat3(worldMatrix);
                     normal = normalize(nm * localNormal);
                     tangent = normalize(nm * localTangent);
                     binormal = normalize(vertexTangent.w * cross(normal, tangent));
                     texCoord = vertexTexCoord;
-                    position = vec3(fyrox_worldMatrix * localPosition);
+                    position = vec3(worldMatrix * localPosition);
                     secondTexCoord = vertexSecondTexCoord;
+                    vertexLight = vertexColor.rgb;
 
-                    gl_Position = fyrox_worldViewProjection * localPosition;
+                    gl_Position = worldViewProjection * localPosition;
+
+                    clipPosition = gl_Position;
+                    mat4 previousWorldViewProjection = fyrox_useInstancing
+                        ? fyrox_previousViewProjectionMatrix * worldMatrix
+                        : fyrox_previousWorldViewProjection;
+                    previousClipPosition = previousWorldViewProjection * localPosition;
                 }
                 "#,
             fragment_shader:
@@ -167,6 +213,7 @@
                 layout(location = 2) out vec4 outAmbient;
                 layout(location = 3) out vec4 outMaterial;
                 layout(location = 4) out uint outDecalMask;
+                layout(location = 5) out vec2 outVelocity;
 
                 // Properties.
                 uniform sampler2D diffuseTexture;
@@ -183,11 +230,14 @@
                 uniform vec4 diffuseColor;
                 uniform float parallaxCenter;
                 uniform float parallaxScale;
+                uniform float vertexLightingStrength;
+                uniform vec3 lightProbeSH[4];
 
                 // Define uniforms with reserved names. Fyrox will automatically provide
                 // required data to these uniforms.
                 uniform vec3 fyrox_cameraPosition;
                 uniform bool fyrox_usePOM;
+                uniform vec2 fyrox_projectionJitter;
 
                 in vec3 position;
                 in vec3 normal;
@@ -195,6 +245,9 @@
                 in vec3 tangent;
                 in vec3 binormal;
                 in vec2 secondTexCoord;
+                in vec3 vertexLight;
+                in vec4 clipPosition;
+                in vec4 previousClipPosition;
 
                 void main()
                 {
@@ -224,17 +277,21 @@
                     outColor.a = 1.0;
 
                     vec4 n = normalize(texture(normalTexture, tc) * 2.0 - 1.0);
-                    outNormal = vec4(normalize(tangentSpace * n.xyz) * 0.5 + 0.5, 1.0);
+                    vec3 worldNormal = normalize(tangentSpace * n.xyz);
+                    outNormal = vec4(worldNormal * 0.5 + 0.5, 1.0);
 
                     outMaterial.x = texture(metallicTexture, tc).r;
                     outMaterial.y = texture(roughnessTexture, tc).r;
                     outMaterial.z = texture(aoTexture, tc).r;
                     outMaterial.a = 1.0;
 
-                    outAmbient.xyz = emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb;
+                    outAmbient.xyz = emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb + vertexLightingStrength * vertexLight;
+                    vec3 lightProbeLight = lightProbeSH[0] + lightProbeSH[1] * worldNormal.x + lightProbeSH[2] * worldNormal.y + lightProbeSH[3] * worldNormal.z;
+                    outAmbient.xyz += max(lightProbeLight, vec3(0.0));
                     outAmbient.a = 1.0;
 
                     outDecalMask = layerIndex;
+                    outVelocity = S_Velocity(clipPosition, previousClipPosition, fyrox_projectionJitter);
                 }
                 "#,
         ),
@@ -279,6 +336,7 @@
 
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
+                uniform bool fyrox_useDualQuaternionSkinning;
                 uniform sampler2D fyrox_boneMatrices;
 
                 out vec3 position;
@@ -301,6 +359,15 @@
                         mat4 m2 = S_FetchMatrix(fyrox_boneMatrices, i2);
                         mat4 m3 = S_FetchMatrix(fyrox_boneMatrices, i3);
 
+                        if (fyrox_useDualQuaternionSkinning)
+                        {
+                            mat4 m = S_DualQuaternionBlend(m0, m1, m2, m3, boneWeights);
+                            m0 = m;
+                            m1 = m;
+                            m2 = m;
+                            m3 = m;
+                        }
+
                         localPosition += m0 * vertex * boneWeights.x;
                         localPosition += m1 * vertex * boneWeights.y;
                         localPosition += m2 * vertex * boneWeights.z;
@@ -362,6 +429,7 @@
 
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
+                uniform bool fyrox_useDualQuaternionSkinning;
                 uniform sampler2D fyrox_boneMatrices;
 
                 out vec2 texCoord;
@@ -379,6 +447,15 @@
                         mat4 m2 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.z));
                         mat4 m3 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.w));
 
+                        if (fyrox_useDualQuaternionSkinning)
+                        {
+                            mat4 m = S_DualQuaternionBlend(m0, m1, m2, m3, boneWeights);
+                            m0 = m;
+                            m1 = m;
+                            m2 = m;
+                            m3 = m;
+                        }
+
                         localPosition += m0 * vertex * boneWeights.x;
                         localPosition += m1 * vertex * boneWeights.y;
                         localPosition += m2 * vertex * boneWeights.z;
@@ -438,6 +515,7 @@
 
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
+                uniform bool fyrox_useDualQuaternionSkinning;
                 uniform sampler2D fyrox_boneMatrices;
 
                 out vec2 texCoord;
@@ -455,6 +533,15 @@
                         mat4 m2 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.z));
                         mat4 m3 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.w));
 
+                        if (fyrox_useDualQuaternionSkinning)
+                        {
+                            mat4 m = S_DualQuaternionBlend(m0, m1, m2, m3, boneWeights);
+                            m0 = m;
+                            m1 = m;
+                            m2 = m;
+                            m3 = m;
+                        }
+
                         localPosition += m0 * vertex * boneWeights.x;
                         localPosition += m1 * vertex * boneWeights.y;
                         localPosition += m2 * vertex * boneWeights.z;
@@ -515,6 +602,7 @@
                 uniform mat4 fyrox_worldMatrix;
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
+                uniform bool fyrox_useDualQuaternionSkinning;
                 uniform sampler2D fyrox_boneMatrices;
 
                 out vec2 texCoord;
@@ -533,6 +621,15 @@
                         mat4 m2 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.z));
                         mat4 m3 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.w));
 
+                        if (fyrox_useDualQuaternionSkinning)
+                        {
+                            mat4 m = S_DualQuaternionBlend(m0, m1, m2, m3, boneWeights);
+                            m0 = m;
+                            m1 = m;
+                            m2 = m;
+                            m3 = m;
+                        }
+
                         localPosition += m0 * vertex * boneWeights.x;
                         localPosition += m1 * vertex * boneWeights.y;
                         localPosition += m2 * vertex * boneWeights.z;
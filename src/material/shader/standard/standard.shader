@@ -59,6 +59,15 @@
             name: "parallaxScale",
             kind: Float(0.08),
         ),
+        (
+            name: "vertexLightingStrength",
+            kind: Float(0.0),
+        ),
+        (
+            // L1 spherical harmonics of baked indirect lighting, provided by light probe volumes.
+            name: "lightProbeSH",
+            kind: Vector3Array([(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (0.0, 0.0, 0.0)]),
+        ),
     ],
 
     passes: [
@@ -92,13 +101,21 @@
                 layout(location = 4) in vec4 boneWeights;
                 layout(location = 5) in vec4 boneIndices;
                 layout(location = 6) in vec2 vertexSecondTexCoord;
+                layout(location = 7) in vec4 vertexColor;
 
                 // Define uniforms with reserved names. Fyrox will automatically provide
                 // required data to these uniforms.
                 uniform mat4 fyrox_worldMatrix;
+                uniform mat4 fyrox_viewProjectionMatrix;
                 uniform mat4 fyrox_worldViewProjection;
+                uniform mat4 fyrox_previousWorldViewProjection;
+                uniform mat4 fyrox_previousViewProjectionMatrix;
+                uniform bool fyrox_useInstancing;
+                uniform sampler2D fyrox_instanceMatrices;
                 uniform bool fyrox_useSkeletalAnimation;
+                uniform bool fyrox_useDualQuaternionSkinning;
                 uniform sampler2D fyrox_boneMatrices;
+                uniform int fyrox_instanceBoneCount;
                 uniform sampler3D fyrox_blendShapesStorage;
                 uniform float fyrox_blendShapesWeights[128];
                 uniform int fyrox_blendShapesCount;
@@ -109,6 +126,9 @@
                 out vec3 tangent;
                 out vec3 binormal;
                 out vec2 secondTexCoord;
+                out vec3 vertexLight;
+                out vec4 clipPosition;
+                out vec4 previousClipPosition;
 
                 void main()
                 {
@@ -130,16 +150,28 @@
 
                     if (fyrox_useSkeletalAnimation)
                     {
-                        int i0 = int(boneIndices.x);
-                        int i1 = int(boneIndices.y);
-                        int i2 = int(boneIndices.z);
-                        int i3 = int(boneIndices.w);
+                        // Bone matrices of all instances are packed in a single palette.
+                        int boneOffset = fyrox_useInstancing ? gl_InstanceID * fyrox_instanceBoneCount : 0;
+
+                        int i0 = boneOffset + int(boneIndices.x);
+                        int i1 = boneOffset + int(boneIndices.y);
+                        int i2 = boneOffset + int(boneIndices.z);
+                        int i3 = boneOffset + int(boneIndices.w);
 
                         mat4 m0 = S_FetchMatrix(fyrox_boneMatrices, i0);
                         mat4 m1 = S_FetchMatrix(fyrox_boneMatrices, i1);
                         mat4 m2 = S_FetchMatrix(fyrox_boneMatrices, i2);
                         mat4 m3 = S_FetchMatrix(fyrox_boneMatrices, i3);
 
+                        if (fyrox_useDualQuaternionSkinning)
+                        {
+                            mat4 m = S_DualQuaternionBlend(m0, m1, m2, m3, boneWeights);
+                            m0 = m;
+                            m1 = m;
+                            m2 = m;
+                            m3 = m;
+                        }
+
                         localPosition += m0 * inputPosition * boneWeights.x;
                         localPosition += m1 * inputPosition * boneWeights.y;
                         localPosition += m2 * inputPosition * boneWeights.z;
@@ -162,15 +194,29 @@
                         localTangent = inputTangent;
                     }
 
-                    mat3 nm = mat3(fyrox_worldMatrix);
+                    mat4 worldMatrix = fyrox_worldMatrix;
+                    mat4 worldViewProjection = fyrox_worldViewProjection;
+                    if (fyrox_useInstancing) {
+                        worldMatrix = S_FetchMatrix(fyrox_instanceMatrices, gl_InstanceID);
+                        worldViewProjection = fyrox_viewProjectionMatrix * worldMatrix;
+                    }
+
+                    mat3 nm = mat3(worldMatrix);
                     normal = normalize(nm * localNormal);
                     tangent = normalize(nm * localTangent);
                     binormal = normalize(vertexTangent.w * cross(normal, tangent));
                     texCoord = vertexTexCoord;
-                    position = vec3(fyrox_worldMatrix * localPosition);
+                    position = vec3(worldMatrix * localPosition);
                     secondTexCoord = vertexSecondTexCoord;
+                    vertexLight = vertexColor.rgb;
 
-                    gl_Position = fyrox_worldViewProjection * localPosition;
+                    gl_Position = worldViewProjection * localPosition;
+
+                    clipPosition = gl_Position;
+                    mat4 previousWorldViewProjection = fyrox_useInstancing
+                        ? fyrox_previousViewProjectionMatrix * worldMatrix
+                        : fyrox_previousWorldViewProjection;
+                    previousClipPosition = previousWorldViewProjection * localPosition;
                 }
                 "#,
             fragment_shader:
@@ -180,6 +226,7 @@
                 layout(location = 2) out vec4 outAmbient;
                 layout(location = 3) out vec4 outMaterial;
                 layout(location = 4) out uint outDecalMask;
+                layout(location = 5) out vec2 outVelocity;
 
                 // Properties.
                 uniform sampler2D diffuseTexture;
@@ -196,11 +243,14 @@
                 uniform vec4 diffuseColor;
                 uniform float parallaxCenter;
                 uniform float parallaxScale;
+                uniform float vertexLightingStrength;
+                uniform vec3 lightProbeSH[4];
 
                 // Define uniforms with reserved names. Fyrox will automatically provide
                 // required data to these uniforms.
                 uniform vec3 fyrox_cameraPosition;
                 uniform bool fyrox_usePOM;
+                uniform vec2 fyrox_projectionJitter;
 
                 in vec3 position;
                 in vec3 normal;
@@ -208,6 +258,9 @@
                 in vec3 tangent;
                 in vec3 binormal;
                 in vec2 secondTexCoord;
+                in vec3 vertexLight;
+                in vec4 clipPosition;
+                in vec4 previousClipPosition;
 
                 void main()
                 {
@@ -237,17 +290,21 @@
                     outColor.a = 1.0;
 
                     vec4 n = normalize(texture(normalTexture, tc) * 2.0 - 1.0);
-                    outNormal = vec4(normalize(tangentSpace * n.xyz) * 0.5 + 0.5, 1.0);
+                    vec3 worldNormal = normalize(tangentSpace * n.xyz);
+                    outNormal = vec4(worldNormal * 0.5 + 0.5, 1.0);
 
                     outMaterial.x = texture(metallicTexture, tc).r;
                     outMaterial.y = texture(roughnessTexture, tc).r;
                     outMaterial.z = texture(aoTexture, tc).r;
                     outMaterial.a = 1.0;
 
-                    outAmbient.xyz = emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb;
+                    outAmbient.xyz = emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb + vertexLightingStrength * vertexLight;
+                    vec3 lightProbeLight = lightProbeSH[0] + lightProbeSH[1] * worldNormal.x + lightProbeSH[2] * worldNormal.y + lightProbeSH[3] * worldNormal.z;
+                    outAmbient.xyz += max(lightProbeLight, vec3(0.0));
                     outAmbient.a = 1.0;
 
                     outDecalMask = layerIndex;
+                    outVelocity = S_Velocity(clipPosition, previousClipPosition, fyrox_projectionJitter);
                 }
                 "#,
         ),
@@ -292,6 +349,7 @@
 
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
+                uniform bool fyrox_useDualQuaternionSkinning;
                 uniform sampler2D fyrox_boneMatrices;
                 uniform sampler3D fyrox_blendShapesStorage;
                 uniform float fyrox_blendShapesWeights[128];
@@ -324,6 +382,15 @@
                         mat4 m2 = S_FetchMatrix(fyrox_boneMatrices, i2);
                         mat4 m3 = S_FetchMatrix(fyrox_boneMatrices, i3);
 
+                        if (fyrox_useDualQuaternionSkinning)
+                        {
+                            mat4 m = S_DualQuaternionBlend(m0, m1, m2, m3, boneWeights);
+                            m0 = m;
+                            m1 = m;
+                            m2 = m;
+                            m3 = m;
+                        }
+
                         localPosition += m0 * inputPosition * boneWeights.x;
                         localPosition += m1 * inputPosition * boneWeights.y;
                         localPosition += m2 * inputPosition * boneWeights.z;
@@ -385,6 +452,7 @@
 
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
+                uniform bool fyrox_useDualQuaternionSkinning;
                 uniform sampler2D fyrox_boneMatrices;
                 uniform sampler3D fyrox_blendShapesStorage;
                 uniform float fyrox_blendShapesWeights[128];
@@ -413,6 +481,15 @@
                         mat4 m2 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.z));
                         mat4 m3 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.w));
 
+                        if (fyrox_useDualQuaternionSkinning)
+                        {
+                            mat4 m = S_DualQuaternionBlend(m0, m1, m2, m3, boneWeights);
+                            m0 = m;
+                            m1 = m;
+                            m2 = m;
+                            m3 = m;
+                        }
+
                         localPosition += m0 * inputPosition * boneWeights.x;
                         localPosition += m1 * inputPosition * boneWeights.y;
                         localPosition += m2 * inputPosition * boneWeights.z;
@@ -472,6 +549,7 @@
 
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
+                uniform bool fyrox_useDualQuaternionSkinning;
                 uniform sampler2D fyrox_boneMatrices;
                 uniform sampler3D fyrox_blendShapesStorage;
                 uniform float fyrox_blendShapesWeights[128];
@@ -500,6 +578,15 @@
                         mat4 m2 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.z));
                         mat4 m3 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.w));
 
+                        if (fyrox_useDualQuaternionSkinning)
+                        {
+                            mat4 m = S_DualQuaternionBlend(m0, m1, m2, m3, boneWeights);
+                            m0 = m;
+                            m1 = m;
+                            m2 = m;
+                            m3 = m;
+                        }
+
                         localPosition += m0 * inputPosition * boneWeights.x;
                         localPosition += m1 * inputPosition * boneWeights.y;
                         localPosition += m2 * inputPosition * boneWeights.z;
@@ -560,6 +647,7 @@
                 uniform mat4 fyrox_worldMatrix;
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
+                uniform bool fyrox_useDualQuaternionSkinning;
                 uniform sampler2D fyrox_boneMatrices;
                 uniform sampler3D fyrox_blendShapesStorage;
                 uniform float fyrox_blendShapesWeights[128];
@@ -589,6 +677,15 @@
                         mat4 m2 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.z));
                         mat4 m3 = S_FetchMatrix(fyrox_boneMatrices, int(boneIndices.w));
 
+                        if (fyrox_useDualQuaternionSkinning)
+                        {
+                            mat4 m = S_DualQuaternionBlend(m0, m1, m2, m3, boneWeights);
+                            m0 = m;
+                            m1 = m;
+                            m2 = m;
+                            m3 = m;
+                        }
+
                         localPosition += m0 * inputPosition * boneWeights.x;
                         localPosition += m1 * inputPosition * boneWeights.y;
                         localPosition += m2 * inputPosition * boneWeights.z;
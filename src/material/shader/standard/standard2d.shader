@@ -72,6 +72,9 @@
                 uniform vec3 fyrox_lightsPosition[16];
                 uniform vec3 fyrox_lightsDirection[16];
                 uniform vec2 fyrox_lightsParameters[16]; // x = hotspot angle, y - full cone angle delta
+                uniform int fyrox_lightsShadowEdgesOffset[16];
+                uniform int fyrox_lightsShadowEdgesCount[16]; // 0 - light does not cast shadows
+                uniform vec4 fyrox_occluderEdges[64]; // xy - begin, zw - end
                 uniform vec4 fyrox_ambientLightColor;
 
                 out vec4 FragColor;
@@ -80,6 +83,32 @@
                 in vec4 color;
                 in vec3 fragmentPosition;
 
+                bool SegmentsIntersect(vec2 a, vec2 b, vec2 c, vec2 d)
+                {
+                    vec2 r = b - a;
+                    vec2 s = d - c;
+                    float denominator = r.x * s.y - r.y * s.x;
+                    if (abs(denominator) < 0.000001) {
+                        return false;
+                    }
+                    vec2 ac = c - a;
+                    float t = (ac.x * s.y - ac.y * s.x) / denominator;
+                    float u = (ac.x * r.y - ac.y * r.x) / denominator;
+                    return t > 0.0 && t < 1.0 && u >= 0.0 && u <= 1.0;
+                }
+
+                // Only the edges that could block the light are checked, they're selected on CPU side.
+                bool IsInShadow(vec2 fragment, vec2 light, int offset, int count)
+                {
+                    for (int i = offset; i < offset + count; ++i) {
+                        vec4 edge = fyrox_occluderEdges[i];
+                        if (SegmentsIntersect(fragment, light, edge.xy, edge.zw)) {
+                            return true;
+                        }
+                    }
+                    return false;
+                }
+
                 void main()
                 {
                     vec3 lighting = fyrox_ambientLightColor.xyz;
@@ -99,7 +128,22 @@
                         float distanceAttenuation = S_LightDistanceAttenuation(distance, radius);
                         float spotAngleCos = dot(toFragmentNormalized, direction);
                         float directionalAttenuation = smoothstep(halfConeAngleCos, halfHotspotAngleCos, spotAngleCos);
-                        lighting += lightColor * (distanceAttenuation * directionalAttenuation);
+                        float attenuation = distanceAttenuation * directionalAttenuation;
+
+                        // Shadow test is the most expensive part, so it is done only for lit fragments.
+                        int shadowEdgesCount = fyrox_lightsShadowEdgesCount[i];
+                        if (attenuation > 0.0 && shadowEdgesCount > 0) {
+                            // Directional lights have infinite radius, so the light "source" is moved
+                            // far away against the direction of the light.
+                            vec2 lightSource = isinf(radius)
+                                ? fragmentPosition.xy - direction.xy * 10000.0
+                                : lightPosition.xy;
+                            if (IsInShadow(fragmentPosition.xy, lightSource, fyrox_lightsShadowEdgesOffset[i], shadowEdgesCount)) {
+                                continue;
+                            }
+                        }
+
+                        lighting += lightColor * attenuation;
                     }
 
                     FragColor = vec4(lighting, 1.0) * color * S_SRGBToLinear(texture(diffuseTexture, texCoord));
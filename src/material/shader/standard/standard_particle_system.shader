@@ -9,6 +9,10 @@
         (
             name: "softBoundarySharpnessFactor",
             kind: Float(100.0),
+        ),
+        (
+            name: "gpuSimulation",
+            kind: Bool(false),
         )
     ],
 
@@ -52,12 +56,28 @@
                layout(location = 2) in float particleSize;
                layout(location = 3) in float particleRotation;
                layout(location = 4) in vec4 vertexColor;
+               // Initial state of a particle, that is simulated on GPU.
+               layout(location = 5) in vec3 particleVelocity;
+               // x - size modifier, y - rotation speed, z - lifetime, w - spawn time.
+               layout(location = 6) in vec4 particleParameters;
 
                uniform mat4 fyrox_viewProjectionMatrix;
                uniform mat4 fyrox_worldMatrix;
                uniform vec3 fyrox_cameraUpVector;
                uniform vec3 fyrox_cameraSideVector;
 
+               uniform bool gpuSimulation;
+               uniform float gpuTime;
+               uniform float gpuTimeStep;
+               uniform vec3 gpuAcceleration;
+               uniform vec4 gpuColorOverLifetime[16];
+               uniform bool gpuCollision;
+               uniform float gpuCollisionThickness;
+
+               uniform sampler2D fyrox_sceneDepth;
+               uniform float fyrox_zNear;
+               uniform float fyrox_zFar;
+
                out vec2 texCoord;
                out vec4 color;
 
@@ -69,13 +89,74 @@
                    return m * v;
                }
 
+               vec4 colorOverLifetime(float k)
+               {
+                   float x = clamp(k, 0.0, 1.0) * 15.0;
+                   int i = int(floor(x));
+                   return mix(gpuColorOverLifetime[i], gpuColorOverLifetime[min(i + 1, 15)], fract(x));
+               }
+
+               // Approximate collision detection - checks if a point went behind visible geometry.
+               bool isBehindSceneGeometry(vec4 worldPosition)
+               {
+                   vec4 clipPosition = fyrox_viewProjectionMatrix * worldPosition;
+                   if (clipPosition.w <= 0.0)
+                   {
+                       return false;
+                   }
+                   vec2 screenPosition = (clipPosition.xy / clipPosition.w) * 0.5 + 0.5;
+                   if (any(lessThan(screenPosition, vec2(0.0))) || any(greaterThan(screenPosition, vec2(1.0))))
+                   {
+                       return false;
+                   }
+                   float depth = textureLod(fyrox_sceneDepth, screenPosition, 0.0).r;
+                   float sceneDepth = (fyrox_zFar * fyrox_zNear) / (fyrox_zFar - depth * (fyrox_zFar - fyrox_zNear));
+                   return clipPosition.w > sceneDepth + gpuCollisionThickness;
+               }
+
                void main()
                {
                    color = vertexColor;
                    texCoord = vertexTexCoord;
-                   vec2 vertexOffset = rotateVec2(vertexTexCoord * 2.0 - 1.0, particleRotation);
-                   vec4 worldPosition = fyrox_worldMatrix * vec4(vertexPosition, 1.0);
-                   vec3 offset = (vertexOffset.x * fyrox_cameraSideVector + vertexOffset.y * fyrox_cameraUpVector) * particleSize;
+
+                   vec3 position = vertexPosition;
+                   float size = particleSize;
+                   float rotation = particleRotation;
+
+                   if (gpuSimulation)
+                   {
+                       float age = gpuTime - particleParameters.w;
+                       float lifetime = particleParameters.z;
+                       if (age <= 0.0 || age >= lifetime)
+                       {
+                           // Dead particle, move it out of the view volume.
+                           color = vec4(0.0);
+                           gl_Position = vec4(2.0, 2.0, 2.0, 1.0);
+                           return;
+                       }
+
+                       // Velocity is defined per simulation step, acceleration is applied every step
+                       // (the same as in CPU simulation).
+                       float timeStep = max(gpuTimeStep, 0.0001);
+                       float steps = age / timeStep;
+                       position += particleVelocity * steps
+                           + gpuAcceleration * (timeStep * timeStep * steps * (steps + 1.0) * 0.5);
+                       size = max(size + particleParameters.x * age, 0.0);
+                       rotation += particleParameters.y * age;
+                       color = colorOverLifetime(age / lifetime);
+                   }
+
+                   vec2 vertexOffset = rotateVec2(vertexTexCoord * 2.0 - 1.0, rotation);
+                   vec4 worldPosition = fyrox_worldMatrix * vec4(position, 1.0);
+
+                   if (gpuSimulation && gpuCollision && isBehindSceneGeometry(worldPosition))
+                   {
+                       color = vec4(0.0);
+                       gl_Position = vec4(2.0, 2.0, 2.0, 1.0);
+                       return;
+                   }
+
+                   vec3 offset = (vertexOffset.x * fyrox_cameraSideVector + vertexOffset.y * fyrox_cameraUpVector) * size;
                    gl_Position = fyrox_viewProjectionMatrix * (worldPosition + vec4(offset.x, offset.y, offset.z, 0.0));
                }
                "#,
@@ -47,6 +47,7 @@
                 layout(location = 1) in vec2 vertexTexCoord;
                 layout(location = 2) in vec2 vertexParams;
                 layout(location = 3) in vec4 vertexColor;
+                layout(location = 4) in vec2 vertexCorner;
 
                 uniform mat4 fyrox_viewProjectionMatrix;
                 uniform mat4 fyrox_worldMatrix;
@@ -71,7 +72,7 @@
 
                     texCoord = vertexTexCoord;
                     color = vertexColor;
-                    vec2 vertexOffset = rotateVec2(vertexTexCoord * 2.0 - 1.0, rotation);
+                    vec2 vertexOffset = rotateVec2(vertexCorner, rotation);
                     vec4 worldPosition = fyrox_worldMatrix * vec4(vertexPosition, 1.0);
                     vec3 offset = (vertexOffset.x * fyrox_cameraSideVector + vertexOffset.y * fyrox_cameraUpVector) * size;
                     gl_Position = fyrox_viewProjectionMatrix * (worldPosition + vec4(offset.x, offset.y, offset.z, 0.0));
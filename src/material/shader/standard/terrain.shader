@@ -71,6 +71,14 @@
             name: "parallaxScale",
             kind: Float(0.08),
         ),
+        (
+            name: "triplanar",
+            kind: Bool(false),
+        ),
+        (
+            name: "triplanarSharpness",
+            kind: Float(4.0),
+        ),
     ],
 
     passes: [
@@ -122,6 +130,7 @@
                 // required data to these uniforms.
                 uniform mat4 fyrox_worldMatrix;
                 uniform mat4 fyrox_worldViewProjection;
+                uniform mat4 fyrox_previousWorldViewProjection;
 
                 out vec3 position;
                 out vec3 normal;
@@ -129,6 +138,8 @@
                 out vec3 tangent;
                 out vec3 binormal;
                 out vec2 secondTexCoord;
+                out vec4 clipPosition;
+                out vec4 previousClipPosition;
 
                 void main()
                 {
@@ -146,6 +157,8 @@
                     position = vec3(fyrox_worldMatrix * finalVertexPosition);
                     secondTexCoord = vertexSecondTexCoord;
                     gl_Position = fyrox_worldViewProjection * finalVertexPosition;
+                    clipPosition = gl_Position;
+                    previousClipPosition = fyrox_previousWorldViewProjection * finalVertexPosition;
                 }
                 "#,
             fragment_shader:
@@ -155,6 +168,7 @@
                 layout(location = 2) out vec4 outAmbient;
                 layout(location = 3) out vec4 outMaterial;
                 layout(location = 4) out uint outDecalMask;
+                layout(location = 5) out vec2 outVelocity;
 
                 // Properties.
                 uniform sampler2D diffuseTexture;
@@ -172,11 +186,14 @@
                 uniform vec4 diffuseColor;
                 uniform float parallaxCenter;
                 uniform float parallaxScale;
+                uniform bool triplanar;
+                uniform float triplanarSharpness;
 
                 // Define uniforms with reserved names. Fyrox will automatically provide
                 // required data to these uniforms.
                 uniform vec3 fyrox_cameraPosition;
                 uniform bool fyrox_usePOM;
+                uniform vec2 fyrox_projectionJitter;
 
                 in vec3 position;
                 in vec3 normal;
@@ -184,40 +201,92 @@
                 in vec3 tangent;
                 in vec3 binormal;
                 in vec2 secondTexCoord;
+                in vec4 clipPosition;
+                in vec4 previousClipPosition;
+
+                vec3 TriplanarWeights(vec3 n)
+                {
+                    vec3 w = pow(abs(n), vec3(triplanarSharpness));
+                    return w / max(w.x + w.y + w.z, 0.0001);
+                }
+
+                vec4 TriplanarSample(sampler2D s, vec3 p, vec3 w)
+                {
+                    return texture(s, p.zy * texCoordScale) * w.x
+                         + texture(s, p.xz * texCoordScale) * w.y
+                         + texture(s, p.xy * texCoordScale) * w.z;
+                }
+
+                // Whiteout blending of normal maps projected along each axis.
+                vec3 TriplanarNormal(vec3 p, vec3 n, vec3 w)
+                {
+                    vec3 tx = texture(normalTexture, p.zy * texCoordScale).xyz * 2.0 - 1.0;
+                    vec3 ty = texture(normalTexture, p.xz * texCoordScale).xyz * 2.0 - 1.0;
+                    vec3 tz = texture(normalTexture, p.xy * texCoordScale).xyz * 2.0 - 1.0;
+
+                    tx = vec3(tx.xy + n.zy, abs(tx.z) * n.x);
+                    ty = vec3(ty.xy + n.xz, abs(ty.z) * n.y);
+                    tz = vec3(tz.xy + n.xy, abs(tz.z) * n.z);
+
+                    return normalize(tx.zyx * w.x + ty.xzy * w.y + tz.xyz * w.z);
+                }
 
                 void main()
                 {
                     mat3 tangentSpace = mat3(tangent, binormal, normal);
                     vec3 toFragment = normalize(position - fyrox_cameraPosition);
 
-                    vec2 tc;
-                    if (fyrox_usePOM) {
-                        vec3 toFragmentTangentSpace = normalize(transpose(tangentSpace) * toFragment);
-                        tc = S_ComputeParallaxTextureCoordinates(
-                            heightTexture,
-                            toFragmentTangentSpace,
-                            texCoord * texCoordScale,
-                            parallaxCenter,
-                            parallaxScale
-                        );
-                    } else {
-                        tc = texCoord * texCoordScale;
-                    }
+                    if (triplanar) {
+                        // Height is applied in the vertex shader, so the actual normal of the surface
+                        // is calculated from the world-space position.
+                        vec3 surfaceNormal = normalize(cross(dFdx(position), dFdy(position)));
+                        if (dot(surfaceNormal, normal) < 0.0) {
+                            surfaceNormal = -surfaceNormal;
+                        }
+                        vec3 w = TriplanarWeights(surfaceNormal);
 
-                    outColor = diffuseColor * texture(diffuseTexture, tc);
+                        outColor = diffuseColor * TriplanarSample(diffuseTexture, position, w);
 
-                    vec4 n = normalize(texture(normalTexture, tc) * 2.0 - 1.0);
-                    outNormal = vec4(normalize(tangentSpace * n.xyz) * 0.5 + 0.5, 1.0);
+                        outNormal = vec4(TriplanarNormal(position, surfaceNormal, w) * 0.5 + 0.5, 1.0);
 
-                    outMaterial.x = texture(metallicTexture, tc).r;
-                    outMaterial.y = texture(roughnessTexture, tc).r;
-                    outMaterial.z = texture(aoTexture, tc).r;
-                    outMaterial.a = 1.0;
+                        outMaterial.x = TriplanarSample(metallicTexture, position, w).r;
+                        outMaterial.y = TriplanarSample(roughnessTexture, position, w).r;
+                        outMaterial.z = TriplanarSample(aoTexture, position, w).r;
+                        outMaterial.a = 1.0;
 
-                    outAmbient.xyz = emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb;
-                    outAmbient.a = 1.0;
+                        outAmbient.xyz = emissionStrength * TriplanarSample(emissionTexture, position, w).rgb + texture(lightmapTexture, secondTexCoord).rgb;
+                        outAmbient.a = 1.0;
+                    } else {
+                        vec2 tc;
+                        if (fyrox_usePOM) {
+                            vec3 toFragmentTangentSpace = normalize(transpose(tangentSpace) * toFragment);
+                            tc = S_ComputeParallaxTextureCoordinates(
+                                heightTexture,
+                                toFragmentTangentSpace,
+                                texCoord * texCoordScale,
+                                parallaxCenter,
+                                parallaxScale
+                            );
+                        } else {
+                            tc = texCoord * texCoordScale;
+                        }
+
+                        outColor = diffuseColor * texture(diffuseTexture, tc);
+
+                        vec4 n = normalize(texture(normalTexture, tc) * 2.0 - 1.0);
+                        outNormal = vec4(normalize(tangentSpace * n.xyz) * 0.5 + 0.5, 1.0);
+
+                        outMaterial.x = texture(metallicTexture, tc).r;
+                        outMaterial.y = texture(roughnessTexture, tc).r;
+                        outMaterial.z = texture(aoTexture, tc).r;
+                        outMaterial.a = 1.0;
+
+                        outAmbient.xyz = emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb;
+                        outAmbient.a = 1.0;
+                    }
 
                     outDecalMask = layerIndex;
+                    outVelocity = S_Velocity(clipPosition, previousClipPosition, fyrox_projectionJitter);
 
                     float mask = texture(maskTexture, texCoord).r;
 
@@ -0,0 +1,257 @@
+(
+    name: "StandardWaterShader",
+
+    // Each property's name must match respective uniform name. Wave parameters, time and reflection
+    // texture are provided by the water node itself (see `Water` node docs).
+    properties: [
+        (
+            name: "normalTexture",
+            kind: Sampler(default: None, fallback: Normal),
+        ),
+        (
+            name: "normalTiling",
+            kind: Float(0.1),
+        ),
+        (
+            // xy - scroll speed of the first normal map layer, zw - of the second.
+            name: "normalScrollSpeed",
+            kind: Vector4((0.02, 0.01, -0.015, 0.02)),
+        ),
+        (
+            name: "normalStrength",
+            kind: Float(1.0),
+        ),
+        (
+            name: "shallowColor",
+            kind: Color(r: 180, g: 235, b: 230, a: 255),
+        ),
+        (
+            name: "deepColor",
+            kind: Color(r: 8, g: 45, b: 60, a: 255),
+        ),
+        (
+            name: "skyColor",
+            kind: Color(r: 120, g: 160, b: 200, a: 255),
+        ),
+        (
+            name: "depthFadeDistance",
+            kind: Float(4.0),
+        ),
+        (
+            name: "edgeFadeDistance",
+            kind: Float(0.1),
+        ),
+        (
+            name: "refractionStrength",
+            kind: Float(0.03),
+        ),
+        (
+            name: "reflectionStrength",
+            kind: Float(1.0),
+        ),
+        (
+            name: "fresnelPower",
+            kind: Float(5.0),
+        ),
+        (
+            name: "specularPower",
+            kind: Float(256.0),
+        ),
+    ],
+
+    passes: [
+        (
+            name: "Forward",
+            draw_parameters: DrawParameters(
+                cull_face: None,
+                color_write: ColorMask(
+                    red: true,
+                    green: true,
+                    blue: true,
+                    alpha: true,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: true,
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+            ),
+            vertex_shader:
+               r#"
+               layout(location = 0) in vec3 vertexPosition;
+
+               uniform mat4 fyrox_worldMatrix;
+               uniform mat4 fyrox_worldViewProjection;
+
+               uniform float waterTime;
+               uniform int waterWaveCount;
+               // xy - normalized direction, z - steepness, w - wavelength.
+               uniform vec4 waterWaves[4];
+
+               out vec3 worldPosition;
+               out vec3 worldNormal;
+               out vec3 worldTangent;
+               out vec3 worldBinormal;
+               out vec4 clipPosition;
+
+               const float GRAVITY = 9.81;
+               const float PI = 3.14159265;
+
+               void main()
+               {
+                   // Sum of Gerstner waves, must match the implementation of `Water::height_at`.
+                   vec3 position = vertexPosition;
+                   vec3 tangent = vec3(1.0, 0.0, 0.0);
+                   vec3 binormal = vec3(0.0, 0.0, 1.0);
+                   for (int i = 0; i < min(waterWaveCount, 4); ++i)
+                   {
+                       vec4 wave = waterWaves[i];
+                       vec2 d = wave.xy;
+                       float k = 2.0 * PI / max(wave.w, 0.0001);
+                       float c = sqrt(GRAVITY / k);
+                       float f = k * (dot(d, vertexPosition.xz) - c * waterTime);
+                       float a = wave.z / k;
+                       float s = wave.z * sin(f);
+                       float cf = wave.z * cos(f);
+
+                       position += vec3(d.x * a * cos(f), a * sin(f), d.y * a * cos(f));
+                       tangent += vec3(-d.x * d.x * s, d.x * cf, -d.x * d.y * s);
+                       binormal += vec3(-d.x * d.y * s, d.y * cf, -d.y * d.y * s);
+                   }
+                   vec3 normal = normalize(cross(binormal, tangent));
+
+                   mat3 nm = mat3(fyrox_worldMatrix);
+                   worldPosition = (fyrox_worldMatrix * vec4(position, 1.0)).xyz;
+                   worldNormal = normalize(nm * normal);
+                   worldTangent = normalize(nm * tangent);
+                   worldBinormal = normalize(nm * binormal);
+                   clipPosition = fyrox_worldViewProjection * vec4(position, 1.0);
+                   gl_Position = clipPosition;
+               }
+               "#,
+
+           fragment_shader:
+               r#"
+               uniform sampler2D normalTexture;
+               uniform float normalTiling;
+               uniform vec4 normalScrollSpeed;
+               uniform float normalStrength;
+               uniform vec4 shallowColor;
+               uniform vec4 deepColor;
+               uniform vec4 skyColor;
+               uniform float depthFadeDistance;
+               uniform float edgeFadeDistance;
+               uniform float refractionStrength;
+               uniform float reflectionStrength;
+               uniform float fresnelPower;
+               uniform float specularPower;
+
+               uniform float waterTime;
+               uniform bool waterUseReflection;
+               uniform sampler2D waterReflectionTexture;
+
+               uniform sampler2D fyrox_sceneDepth;
+               uniform sampler2D fyrox_sceneColor;
+               uniform float fyrox_zNear;
+               uniform float fyrox_zFar;
+               uniform vec3 fyrox_cameraPosition;
+               uniform int fyrox_lightCount;
+               uniform vec4 fyrox_lightsColorRadius[16]; // xyz - color, w = radius
+               uniform vec3 fyrox_lightsPosition[16];
+               uniform vec3 fyrox_lightsDirection[16];
+
+               in vec3 worldPosition;
+               in vec3 worldNormal;
+               in vec3 worldTangent;
+               in vec3 worldBinormal;
+               in vec4 clipPosition;
+
+               out vec4 FragColor;
+
+               float toProjSpace(float z)
+               {
+                   return (fyrox_zFar * fyrox_zNear) / (fyrox_zFar - z * (fyrox_zFar - fyrox_zNear));
+               }
+
+               void main()
+               {
+                   // Two layers of the same normal map scrolled in different directions.
+                   vec2 uv = worldPosition.xz * normalTiling;
+                   vec3 n1 = texture(normalTexture, uv + normalScrollSpeed.xy * waterTime).xyz * 2.0 - 1.0;
+                   vec3 n2 = texture(normalTexture, uv * 0.7 + normalScrollSpeed.zw * waterTime).xyz * 2.0 - 1.0;
+                   vec3 detail = vec3((n1.xy + n2.xy) * normalStrength, n1.z * n2.z);
+                   vec3 N = normalize(worldTangent * detail.x + worldBinormal * detail.y + worldNormal * detail.z);
+
+                   vec3 V = normalize(fyrox_cameraPosition - worldPosition);
+                   if (dot(V, worldNormal) < 0.0)
+                   {
+                       // Looking from under the water.
+                       N = -N;
+                   }
+
+                   // Refraction with depth fade.
+                   vec2 pixelSize = 1.0 / vec2(textureSize(fyrox_sceneDepth, 0));
+                   vec2 screenUV = gl_FragCoord.xy * pixelSize;
+                   vec2 distortion = N.xz * refractionStrength;
+                   float fragmentDepth = toProjSpace(gl_FragCoord.z);
+                   vec2 refractionUV = screenUV + distortion;
+                   float sceneDepth = toProjSpace(texture(fyrox_sceneDepth, refractionUV).r);
+                   if (sceneDepth < fragmentDepth)
+                   {
+                       // Distorted coordinates point to an object in front of the water, discard
+                       // the distortion.
+                       refractionUV = screenUV;
+                       sceneDepth = toProjSpace(texture(fyrox_sceneDepth, refractionUV).r);
+                   }
+                   float waterDepth = max(sceneDepth - fragmentDepth, 0.0);
+                   vec3 sceneColor = texture(fyrox_sceneColor, refractionUV).rgb;
+                   float depthFactor = clamp(waterDepth / max(depthFadeDistance, 0.0001), 0.0, 1.0);
+                   vec3 refraction = mix(sceneColor * shallowColor.rgb, deepColor.rgb, depthFactor);
+
+                   // Planar reflection. The reflection camera is mirrored, so its frame is flipped
+                   // horizontally.
+                   vec3 reflection = skyColor.rgb;
+                   if (waterUseReflection)
+                   {
+                       vec2 ndc = (clipPosition.xy / clipPosition.w) * 0.5 + 0.5;
+                       reflection = texture(waterReflectionTexture, vec2(1.0 - ndc.x, ndc.y) + distortion).rgb;
+                   }
+                   reflection *= reflectionStrength;
+
+                   float fresnel = 0.02 + 0.98 * pow(1.0 - clamp(dot(N, V), 0.0, 1.0), fresnelPower);
+                   vec3 color = mix(refraction, reflection, fresnel);
+
+                   for (int i = 0; i < fyrox_lightCount; ++i)
+                   {
+                       vec3 lightColor = fyrox_lightsColorRadius[i].xyz;
+                       float radius = fyrox_lightsColorRadius[i].w;
+                       vec3 L;
+                       float attenuation = 1.0;
+                       if (isinf(radius))
+                       {
+                           L = -fyrox_lightsDirection[i];
+                       }
+                       else
+                       {
+                           vec3 toLight = fyrox_lightsPosition[i] - worldPosition;
+                           float distance = length(toLight);
+                           L = toLight / distance;
+                           attenuation = S_LightDistanceAttenuation(distance, radius);
+                       }
+                       vec3 H = normalize(L + V);
+                       color += lightColor * pow(max(dot(N, H), 0.0), specularPower) * attenuation;
+                   }
+
+                   // Smooth transition at the shore line.
+                   float edgeFactor = clamp(waterDepth / max(edgeFadeDistance, 0.0001), 0.0, 1.0);
+                   FragColor = vec4(mix(texture(fyrox_sceneColor, screenUV).rgb, color, edgeFactor), 1.0);
+               }
+               "#,
+        )
+    ],
+)
@@ -7,8 +7,8 @@ use crate::{
     asset::manager::ResourceManager,
     core::pool::Handle,
     engine::{
-        AsyncSceneLoader, GraphicsContext, PerformanceStatistics, ScriptProcessor,
-        SerializationContext,
+        blackboard::Blackboard, AsyncSceneLoader, GraphicsContext, PerformanceStatistics,
+        ScriptProcessor, SerializationContext,
     },
     event::Event,
     gui::{message::UiMessage, UserInterface},
@@ -18,6 +18,9 @@ use fyrox_core::visitor::VisitError;
 use std::{any::Any, path::Path, sync::Arc};
 use winit::event_loop::EventLoopWindowTarget;
 
+#[cfg(feature = "wasm_plugins")]
+pub mod wasm;
+
 /// Plugin constructor is a first step of 2-stage plugin initialization. It is responsible for plugin script
 /// registration and for creating actual plugin instance.
 ///
@@ -106,6 +109,9 @@ pub struct PluginContext<'a, 'b> {
 
     /// Task pool for asynchronous task management.
     pub task_pool: &'a mut TaskPoolHandler,
+
+    /// Global container for shared game state. See [`Blackboard`] docs for more info.
+    pub blackboard: &'a mut Blackboard,
 }
 
 /// Base plugin automatically implements type casting for plugins.
@@ -207,6 +213,54 @@ pub trait Plugin: BasePlugin {
     ) {
     }
 
+    /// The method is called when a user drags a file from the OS file manager over the main window.
+    /// It could be used to highlight an area, where the file could be dropped. The method is called
+    /// once per each file, if a user drags multiple files at once.
+    fn on_file_hovered(
+        &mut self,
+        #[allow(unused_variables)] path: &Path,
+        #[allow(unused_variables)] context: &mut PluginContext,
+    ) {
+    }
+
+    /// The method is called when a user moves hovered files out of the main window or cancels the
+    /// dragging.
+    fn on_file_hover_cancelled(&mut self, #[allow(unused_variables)] context: &mut PluginContext) {}
+
+    /// The method is called when a user drops a file from the OS file manager on the main window.
+    /// The method is called once per each file, if a user drops multiple files at once. The path
+    /// is absolute and in most cases it points to a file outside of the project folder. Use
+    /// [`ResourceManager::try_request_untyped`] to load the file as a resource, it returns [`None`]
+    /// if the engine does not know how to load the file.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use fyrox::{
+    /// #     plugin::{Plugin, PluginContext},
+    /// #     resource::texture::Texture,
+    /// # };
+    /// # use std::path::Path;
+    /// #
+    /// struct MyGame;
+    ///
+    /// impl Plugin for MyGame {
+    ///     fn on_file_dropped(&mut self, path: &Path, context: &mut PluginContext) {
+    ///         if let Some(resource) = context.resource_manager.try_request_untyped(path) {
+    ///             if let Some(texture) = resource.try_cast::<Texture>() {
+    ///                 // Do something with the texture, for example apply it to a custom avatar.
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn on_file_dropped(
+        &mut self,
+        #[allow(unused_variables)] path: &Path,
+        #[allow(unused_variables)] context: &mut PluginContext,
+    ) {
+    }
+
     /// The method is called when a graphics context was successfully created. It could be useful
     /// to catch the moment when it was just created and do something in response.
     fn on_graphics_context_initialized(
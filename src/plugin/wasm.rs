@@ -0,0 +1,654 @@
+//! WASM plugins allows you to load game or mod logic from WebAssembly modules and run it in a sandbox.
+//! See [`WasmPlugin`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        log::Log,
+        pool::Handle,
+    },
+    plugin::{Plugin, PluginConstructor, PluginContext},
+    resource::model::{Model, ModelResource, ModelResourceExtension},
+    scene::Scene,
+};
+use fxhash::FxHashMap;
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter},
+    path::{Component, Path, PathBuf},
+};
+use wasmi::{
+    core::{Trap, F32},
+    Caller, Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc,
+};
+
+/// Name of the module, from which a WASM plugin must import host functions.
+pub const HOST_MODULE: &str = "fyrox";
+
+/// Default amount of fuel (roughly - amount of executed WASM instructions), that a plugin can
+/// spend in a single call.
+pub const DEFAULT_FUEL_PER_CALL: u64 = 10_000_000;
+
+/// Default maximum size (in bytes) of the linear memory of a plugin.
+pub const DEFAULT_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Maximum length (in bytes) of a string, that could be passed from a plugin to the host.
+pub const MAX_STRING_LENGTH: usize = 64 * 1024;
+
+/// Maximum amount of messages in the queue of messages sent by a plugin. A plugin that sends more
+/// messages (without the game fetching them) is disabled.
+pub const MAX_QUEUED_MESSAGES: usize = 1024;
+
+/// Maximum amount of prefabs, that a plugin can request to spawn in a single frame. A plugin that
+/// requests more is disabled.
+pub const MAX_SPAWN_REQUESTS_PER_FRAME: usize = 256;
+
+/// Maximum amount of prefabs, that are waiting to be loaded to be spawned. Spawn requests over
+/// this limit are dropped.
+pub const MAX_PENDING_SPAWNS: usize = 1024;
+
+/// An error, that may occur during WASM plugin loading or execution.
+#[derive(Debug)]
+pub enum WasmPluginError {
+    /// An i/o error has occurred.
+    Io(std::io::Error),
+    /// The module is invalid, it imports something that is not a part of the API or it has
+    /// trapped during execution.
+    Wasm(String),
+}
+
+impl Display for WasmPluginError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WasmPluginError::Io(v) => write!(f, "An i/o error has occurred: {v}"),
+            WasmPluginError::Wasm(v) => write!(f, "WASM error: {v}"),
+        }
+    }
+}
+
+impl std::error::Error for WasmPluginError {}
+
+impl From<std::io::Error> for WasmPluginError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+fn wasm_error(error: impl Display) -> WasmPluginError {
+    WasmPluginError::Wasm(error.to_string())
+}
+
+struct SpawnRequest {
+    path: String,
+    position: Vector3<f32>,
+}
+
+/// A state, that is shared between the host and a WASM module.
+struct HostState {
+    name: String,
+    limits: StoreLimits,
+    spawn_requests: Vec<SpawnRequest>,
+    outbox: VecDeque<String>,
+    inbox: VecDeque<String>,
+    blackboard: FxHashMap<String, String>,
+}
+
+/// Reads a string from the memory of a module. Returns `None` if the string is too long, is out of
+/// bounds of the memory or it is not a valid UTF-8 string.
+fn read_string(caller: &Caller<'_, HostState>, ptr: i32, len: i32) -> Option<String> {
+    let memory = caller.get_export("memory")?.into_memory()?;
+    let ptr = usize::try_from(ptr).ok()?;
+    let len = usize::try_from(len).ok()?;
+    if len > MAX_STRING_LENGTH {
+        return None;
+    }
+    let bytes = memory.data(caller).get(ptr..ptr.checked_add(len)?)?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+/// Resolves a path of an asset requested by a module. The path must be relative to the asset root
+/// of the plugin and it must not leave it.
+fn resolve_asset_path(asset_root: &Path, path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    if path.as_os_str().is_empty()
+        || !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return None;
+    }
+    Some(asset_root.join(path))
+}
+
+/// Writes the given bytes into the memory of a module. Returns the length of the data, or `-1`
+/// if the memory is not accessible. Nothing is written if the buffer is too small, in this case
+/// a module should allocate a buffer of the returned size and try again.
+fn write_bytes(caller: &mut Caller<'_, HostState>, ptr: i32, capacity: i32, bytes: &[u8]) -> i32 {
+    let len = bytes.len() as i32;
+    if len > capacity {
+        return len;
+    }
+    let Some(memory) = caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+    else {
+        return -1;
+    };
+    let Ok(ptr) = usize::try_from(ptr) else {
+        return -1;
+    };
+    match memory.write(caller, ptr, bytes) {
+        Ok(_) => len,
+        Err(_) => -1,
+    }
+}
+
+fn create_linker(engine: &Engine) -> Result<Linker<HostState>, WasmPluginError> {
+    let mut linker = Linker::<HostState>::new(engine);
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "log",
+            |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                if let Some(message) = read_string(&caller, ptr, len) {
+                    Log::info(format!("[{}]: {}", caller.data().name, message));
+                }
+            },
+        )
+        .map_err(wasm_error)?;
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "spawn_prefab",
+            |mut caller: Caller<'_, HostState>,
+             ptr: i32,
+             len: i32,
+             x: F32,
+             y: F32,
+             z: F32|
+             -> Result<(), Trap> {
+                if caller.data().spawn_requests.len() >= MAX_SPAWN_REQUESTS_PER_FRAME {
+                    return Err(Trap::new("too many spawn requests in a single frame"));
+                }
+                if let Some(path) = read_string(&caller, ptr, len) {
+                    caller.data_mut().spawn_requests.push(SpawnRequest {
+                        path,
+                        position: Vector3::new(x.to_float(), y.to_float(), z.to_float()),
+                    });
+                }
+                Ok(())
+            },
+        )
+        .map_err(wasm_error)?;
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "send_message",
+            |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> Result<(), Trap> {
+                if caller.data().outbox.len() >= MAX_QUEUED_MESSAGES {
+                    return Err(Trap::new("the queue of messages is full"));
+                }
+                if let Some(message) = read_string(&caller, ptr, len) {
+                    caller.data_mut().outbox.push_back(message);
+                }
+                Ok(())
+            },
+        )
+        .map_err(wasm_error)?;
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "receive_message",
+            |mut caller: Caller<'_, HostState>, ptr: i32, capacity: i32| -> i32 {
+                let Some(message) = caller.data().inbox.front().cloned() else {
+                    return -1;
+                };
+                let result = write_bytes(&mut caller, ptr, capacity, message.as_bytes());
+                if result >= 0 && result <= capacity {
+                    caller.data_mut().inbox.pop_front();
+                }
+                result
+            },
+        )
+        .map_err(wasm_error)?;
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "blackboard_get",
+            |mut caller: Caller<'_, HostState>,
+             key_ptr: i32,
+             key_len: i32,
+             ptr: i32,
+             capacity: i32|
+             -> i32 {
+                let Some(value) = read_string(&caller, key_ptr, key_len)
+                    .and_then(|key| caller.data().blackboard.get(&key).cloned())
+                else {
+                    return -1;
+                };
+                write_bytes(&mut caller, ptr, capacity, value.as_bytes())
+            },
+        )
+        .map_err(wasm_error)?;
+
+    Ok(linker)
+}
+
+/// WASM plugin runs game or mod logic, compiled to a WebAssembly module, in a sandbox. Unlike native
+/// plugins, a module has no access to the engine internals, file system or network - it can only
+/// call a small set of host functions, which makes it safe to run user-generated content. A module
+/// that imports anything else fails to load. Every call into the module is limited by an amount of
+/// fuel (see [`WasmPlugin::set_fuel_per_call`]), so a mod with an infinite loop cannot hang the game.
+/// The memory of a module is limited too (see [`DEFAULT_MEMORY_LIMIT`]), as well as the amount of
+/// queued messages and spawn requests. A module, that exceeds a limit, is disabled.
+///
+/// Prefabs could be spawned only from the asset root of the plugin (see [`WasmPlugin::set_asset_root`]),
+/// absolute paths and paths that leave the root are rejected.
+///
+/// ## API
+///
+/// Host functions are imported from the `fyrox` module. Strings are passed as pointer and length
+/// of UTF-8 data in the exported `memory` of the module.
+///
+/// - `log(ptr: i32, len: i32)` - writes a message to the log.
+/// - `spawn_prefab(ptr: i32, len: i32, x: f32, y: f32, z: f32)` - instantiates a prefab with the
+///   given path (relative to the asset root) at the given position in the target scene (see
+///   [`WasmPlugin::set_target_scene`]).
+/// - `send_message(ptr: i32, len: i32)` - sends a message to the game, it could be fetched using
+///   [`WasmPlugin::pop_message`].
+/// - `receive_message(ptr: i32, capacity: i32) -> i32` - fetches the next message sent by the game
+///   using [`WasmPlugin::send_message`].
+/// - `blackboard_get(key_ptr: i32, key_len: i32, ptr: i32, capacity: i32) -> i32` - reads a value
+///   from the blackboard, that is filled by the game using [`WasmPlugin::set_blackboard_value`].
+///
+/// Strings longer than [`MAX_STRING_LENGTH`] are ignored. Reading functions return `-1` if there is
+/// no data, otherwise they return the length of the data. If the length is larger than the capacity
+/// of the buffer, nothing is written (and a message is not removed from the queue), so a module
+/// should provide a larger buffer and try again.
+///
+/// A module may export `init()` function, that is called once after the module was loaded, and
+/// `update(dt: f32)` function, that is called every frame.
+pub struct WasmPlugin {
+    store: Store<HostState>,
+    update: Option<TypedFunc<F32, ()>>,
+    pending_spawns: Vec<(SpawnRequest, ModelResource)>,
+    scene: Handle<Scene>,
+    asset_root: Option<PathBuf>,
+    fuel_per_call: u64,
+    fuel_added: u64,
+    failed: bool,
+}
+
+impl WasmPlugin {
+    /// Loads a WASM plugin from the given binary. The name is used to identify the plugin in the log.
+    /// The exported `init` function (if any) is called right away.
+    pub fn from_bytes(name: &str, bytes: &[u8]) -> Result<Self, WasmPluginError> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, bytes).map_err(wasm_error)?;
+        let linker = create_linker(&engine)?;
+        let mut store = Store::new(
+            &engine,
+            HostState {
+                name: name.to_string(),
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(DEFAULT_MEMORY_LIMIT)
+                    .memories(1)
+                    .tables(1)
+                    .instances(1)
+                    .build(),
+                spawn_requests: Default::default(),
+                outbox: Default::default(),
+                inbox: Default::default(),
+                blackboard: Default::default(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(wasm_error)?;
+
+        let init = instance.get_typed_func::<(), ()>(&store, "init").ok();
+        let update = instance.get_typed_func::<F32, ()>(&store, "update").ok();
+
+        let mut plugin = Self {
+            store,
+            update,
+            pending_spawns: Default::default(),
+            scene: Default::default(),
+            asset_root: None,
+            fuel_per_call: DEFAULT_FUEL_PER_CALL,
+            fuel_added: 0,
+            failed: false,
+        };
+
+        if let Some(init) = init {
+            plugin.refuel()?;
+            init.call(&mut plugin.store, ()).map_err(wasm_error)?;
+        }
+
+        Ok(plugin)
+    }
+
+    /// Sets the amount of fuel (roughly - amount of executed WASM instructions), that the plugin can
+    /// spend in a single call. If a call runs out of fuel, it is interrupted and the plugin is
+    /// disabled.
+    pub fn set_fuel_per_call(&mut self, fuel: u64) {
+        self.fuel_per_call = fuel;
+    }
+
+    /// Returns the amount of fuel, that the plugin can spend in a single call.
+    pub fn fuel_per_call(&self) -> u64 {
+        self.fuel_per_call
+    }
+
+    /// Sets a scene, in which the prefabs will be spawned. If the handle is invalid, the first scene
+    /// of the engine is used.
+    pub fn set_target_scene(&mut self, scene: Handle<Scene>) {
+        self.scene = scene;
+    }
+
+    /// Sets a directory, from which the plugin can spawn prefabs. Paths passed to `spawn_prefab` are
+    /// relative to this directory. If the root is not set, the plugin cannot spawn anything.
+    pub fn set_asset_root(&mut self, asset_root: impl Into<PathBuf>) {
+        self.asset_root = Some(asset_root.into());
+    }
+
+    /// Returns current asset root of the plugin.
+    pub fn asset_root(&self) -> Option<&Path> {
+        self.asset_root.as_deref()
+    }
+
+    /// Sets a value in the blackboard, the plugin has read-only access to the blackboard.
+    pub fn set_blackboard_value(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.store
+            .data_mut()
+            .blackboard
+            .insert(key.into(), value.into());
+    }
+
+    /// Removes a value from the blackboard.
+    pub fn remove_blackboard_value(&mut self, key: &str) -> Option<String> {
+        self.store.data_mut().blackboard.remove(key)
+    }
+
+    /// Sends a message to the plugin.
+    pub fn send_message(&mut self, message: impl Into<String>) {
+        self.store.data_mut().inbox.push_back(message.into());
+    }
+
+    /// Fetches the next message sent by the plugin (if any).
+    pub fn pop_message(&mut self) -> Option<String> {
+        self.store.data_mut().outbox.pop_front()
+    }
+
+    /// Returns `true` if the plugin was disabled because of an error (a trap or out of fuel).
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+
+    fn refuel(&mut self) -> Result<(), WasmPluginError> {
+        // Top up the fuel to the limit, so unspent fuel is not accumulated between calls.
+        let consumed = self.store.fuel_consumed().unwrap_or_default();
+        let remaining = self.fuel_added.saturating_sub(consumed);
+        let delta = self.fuel_per_call.saturating_sub(remaining);
+        self.store.add_fuel(delta).map_err(wasm_error)?;
+        self.fuel_added += delta;
+        Ok(())
+    }
+
+    fn call_update(&mut self, dt: f32) -> Result<(), WasmPluginError> {
+        if let Some(update) = self.update {
+            self.refuel()?;
+            update
+                .call(&mut self.store, F32::from_float(dt))
+                .map_err(wasm_error)?;
+        }
+        Ok(())
+    }
+
+    fn queue_spawn(&mut self, request: SpawnRequest, resource: ModelResource) {
+        if self.pending_spawns.len() >= MAX_PENDING_SPAWNS {
+            Log::err(format!(
+                "[{}]: unable to spawn prefab {}, too many prefabs are waiting to be loaded.",
+                self.store.data().name,
+                request.path
+            ));
+            return;
+        }
+        self.pending_spawns.push((request, resource));
+    }
+
+    fn process_spawn_requests(&mut self, context: &mut PluginContext) {
+        let requests = std::mem::take(&mut self.store.data_mut().spawn_requests);
+        for request in requests {
+            let Some(path) = self
+                .asset_root
+                .as_deref()
+                .and_then(|root| resolve_asset_path(root, &request.path))
+            else {
+                Log::err(format!(
+                    "[{}]: unable to spawn prefab {}, the path is outside of the asset root.",
+                    self.store.data().name,
+                    request.path
+                ));
+                continue;
+            };
+            let resource = context.resource_manager.request::<Model>(path);
+            self.queue_spawn(request, resource);
+        }
+
+        let scene_handle = if context.scenes.is_valid_handle(self.scene) {
+            self.scene
+        } else {
+            context
+                .scenes
+                .pair_iter()
+                .next()
+                .map_or(Handle::NONE, |(handle, _)| handle)
+        };
+        let name = &self.store.data().name;
+
+        self.pending_spawns.retain(|(request, resource)| {
+            if resource.is_loading() {
+                return true;
+            }
+
+            if resource.is_ok() {
+                if let Some(scene) = context.scenes.try_get_mut(scene_handle) {
+                    resource.instantiate_at(scene, request.position, UnitQuaternion::identity());
+                }
+            } else {
+                Log::err(format!(
+                    "[{}]: unable to spawn prefab {}, it failed to load.",
+                    name, request.path
+                ));
+            }
+
+            false
+        });
+    }
+}
+
+impl Plugin for WasmPlugin {
+    fn update(&mut self, context: &mut PluginContext) {
+        if self.failed {
+            return;
+        }
+
+        if let Err(err) = self.call_update(context.dt) {
+            Log::err(format!(
+                "[{}]: plugin was disabled because of an error: {}",
+                self.store.data().name,
+                err
+            ));
+            self.failed = true;
+        }
+
+        self.process_spawn_requests(context);
+    }
+}
+
+/// Plugin constructor for [`WasmPlugin`]. It should be registered in the engine using
+/// [`crate::engine::Engine::add_plugin_constructor`].
+pub struct WasmPluginConstructor {
+    name: String,
+    bytes: Vec<u8>,
+    asset_root: Option<PathBuf>,
+}
+
+impl WasmPluginConstructor {
+    /// Creates new constructor from the given WASM binary.
+    pub fn new(name: impl Into<String>, bytes: Vec<u8>) -> Self {
+        Self {
+            name: name.into(),
+            bytes,
+            asset_root: None,
+        }
+    }
+
+    /// Sets a directory, from which the plugin can spawn prefabs. See [`WasmPlugin::set_asset_root`]
+    /// for more info.
+    pub fn with_asset_root(mut self, asset_root: impl Into<PathBuf>) -> Self {
+        self.asset_root = Some(asset_root.into());
+        self
+    }
+
+    /// Creates new constructor from the given WASM file. The file name is used as the plugin name.
+    pub fn from_file(path: &Path) -> Result<Self, WasmPluginError> {
+        let bytes = std::fs::read(path)?;
+        Ok(Self::new(path.to_string_lossy(), bytes))
+    }
+}
+
+impl PluginConstructor for WasmPluginConstructor {
+    fn create_instance(
+        &self,
+        _scene_path: Option<&str>,
+        _context: PluginContext,
+    ) -> Box<dyn Plugin> {
+        match WasmPlugin::from_bytes(&self.name, &self.bytes) {
+            Ok(mut plugin) => {
+                if let Some(asset_root) = self.asset_root.as_ref() {
+                    plugin.set_asset_root(asset_root);
+                }
+                Box::new(plugin)
+            }
+            Err(err) => {
+                Log::err(format!(
+                    "Unable to load WASM plugin {}. Reason: {}",
+                    self.name, err
+                ));
+                Box::new(FailedWasmPlugin)
+            }
+        }
+    }
+}
+
+/// A stub, that is used instead of a WASM plugin that failed to load.
+struct FailedWasmPlugin;
+
+impl Plugin for FailedWasmPlugin {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::algebra::Vector3,
+        plugin::wasm::{
+            resolve_asset_path, SpawnRequest, WasmPlugin, MAX_PENDING_SPAWNS, MAX_QUEUED_MESSAGES,
+        },
+        resource::model::ModelResource,
+    };
+    use std::path::Path;
+
+    fn load(source: &str) -> WasmPlugin {
+        WasmPlugin::from_bytes("Test", &wat::parse_str(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_messages() {
+        let mut plugin = load(
+            r#"(module
+                (import "fyrox" "log" (func $log (param i32 i32)))
+                (import "fyrox" "send_message" (func $send (param i32 i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "hello")
+                (func (export "update") (param f32)
+                    ;; Too long and out-of-bounds strings are ignored.
+                    (call $log (i32.const 0) (i32.const 0x7fffffff))
+                    (call $send (i32.const 65530) (i32.const 100))
+                    (call $send (i32.const 0) (i32.const 5))))"#,
+        );
+
+        plugin.call_update(0.1).unwrap();
+        assert_eq!(plugin.pop_message().as_deref(), Some("hello"));
+        assert_eq!(plugin.pop_message(), None);
+    }
+
+    #[test]
+    fn test_limits() {
+        let mut flood = load(
+            r#"(module
+                (import "fyrox" "send_message" (func $send (param i32 i32)))
+                (memory (export "memory") 1)
+                (func (export "update") (param f32)
+                    (loop $loop
+                        (call $send (i32.const 0) (i32.const 1))
+                        (br $loop))))"#,
+        );
+        assert!(flood.call_update(0.1).is_err());
+        assert_eq!(flood.store.data().outbox.len(), MAX_QUEUED_MESSAGES);
+
+        let mut infinite_loop = load(
+            r#"(module
+                (func (export "update") (param f32)
+                    (loop $loop (br $loop))))"#,
+        );
+        infinite_loop.set_fuel_per_call(1000);
+        assert!(infinite_loop.call_update(0.1).is_err());
+
+        // 2048 pages (128 Mb) exceed the memory limit.
+        let huge_memory = wat::parse_str(r#"(module (memory (export "memory") 2048))"#).unwrap();
+        assert!(WasmPlugin::from_bytes("Test", &huge_memory).is_err());
+    }
+
+    #[test]
+    fn test_pending_spawns_are_capped() {
+        let mut plugin = load(r#"(module)"#);
+        for _ in 0..MAX_PENDING_SPAWNS + 10 {
+            plugin.queue_spawn(
+                SpawnRequest {
+                    path: "barrel.rgs".to_string(),
+                    position: Vector3::default(),
+                },
+                ModelResource::new_pending(ResourceKind::Embedded),
+            );
+        }
+        assert_eq!(plugin.pending_spawns.len(), MAX_PENDING_SPAWNS);
+    }
+
+    #[test]
+    fn test_resolve_asset_path() {
+        let root = Path::new("data/mods/foo");
+        assert_eq!(
+            resolve_asset_path(root, "models/barrel.fbx"),
+            Some(root.join("models/barrel.fbx"))
+        );
+        assert_eq!(
+            resolve_asset_path(root, "./barrel.fbx"),
+            Some(root.join("./barrel.fbx"))
+        );
+        assert_eq!(resolve_asset_path(root, ""), None);
+        assert_eq!(resolve_asset_path(root, "../bar/barrel.fbx"), None);
+        assert_eq!(resolve_asset_path(root, "models/../../barrel.fbx"), None);
+        assert_eq!(resolve_asset_path(root, "/etc/passwd"), None);
+    }
+}
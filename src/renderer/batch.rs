@@ -2,14 +2,15 @@
 
 use crate::{
     core::{
-        algebra::{Matrix4, Vector3},
+        algebra::{Matrix4, Vector2, Vector3},
         math::{frustum::Frustum, TriangleDefinition},
         pool::Handle,
         sstorage::ImmutableString,
     },
-    material::MaterialResource,
-    renderer::{cache::TimeToLive, framework::geometry_buffer::ElementRange},
+    material::{MaterialResource, PropertyValue},
+    renderer::{self, cache::TimeToLive, framework::geometry_buffer::ElementRange},
     scene::{
+        collider::BitMask,
         graph::Graph,
         mesh::{
             buffer::{TriangleBuffer, VertexBuffer, VertexTrait},
@@ -26,6 +27,7 @@ use std::{
     collections::hash_map::DefaultHasher,
     fmt::{Debug, Formatter},
     hash::{Hash, Hasher},
+    sync::Arc,
 };
 
 /// Observer info contains all the data, that describes an observer. It could be a real camera, light source's
@@ -41,6 +43,13 @@ pub struct ObserverInfo {
     pub view_matrix: Matrix4<f32>,
     /// Projection matrix of the observer.
     pub projection_matrix: Matrix4<f32>,
+    /// A set of render layers visible to the observer. Nodes, that do not share any layer with this
+    /// mask will be skipped. See [`crate::scene::base::Base::render_layers`] for more info.
+    pub render_mask: BitMask,
+    /// A camera, that selects levels of detail of LOD groups (see [`crate::scene::base::LodGroup`]). It should be set only for
+    /// cameras, other observers (shadow casters, etc.) should use [`Handle::NONE`] - they use the levels
+    /// that were selected by the last rendered camera, so shadows always match visible geometry.
+    pub lod_camera: Handle<Node>,
 }
 
 /// Render context is used to collect render data from the scene nodes. It provides all required information about
@@ -89,6 +98,9 @@ impl PersistentIdentifier {
     }
 }
 
+/// A shared set of material property values, that override the values of a material.
+pub type PropertyOverrides = Arc<[(ImmutableString, PropertyValue)]>;
+
 /// A set of data of a surface for rendering.  
 pub struct SurfaceInstanceData {
     /// A world matrix.
@@ -107,6 +119,36 @@ pub struct SurfaceInstanceData {
     pub persistent_identifier: PersistentIdentifier,
     /// A handle of a node that emitted this surface data. Could be none, if there's no info about scene node.
     pub node_handle: Handle<Node>,
+    /// Defines whether the instance could be merged with other instances of the same batch in a single
+    /// instanced draw call. The renderer also checks that the instance has no blend shapes, no depth
+    /// offset and uses the full element range. Bone matrices of skinned instances are packed in a
+    /// single palette, which is indexed per instance in the shader (see `fyrox_instanceBoneCount`).
+    pub allow_instancing: bool,
+    /// Defines whether the bone matrices should be blended as dual quaternions instead of linear
+    /// blending. See [`crate::scene::mesh::SkinningMode`] for more info.
+    pub use_dual_quaternion_skinning: bool,
+    /// A set of material property values that override the values of the batch material for this
+    /// instance only. [`None`] means that there are no overrides. The set is shared, so nodes could
+    /// cache it between frames instead of building it every frame.
+    pub property_overrides: Option<PropertyOverrides>,
+}
+
+impl SurfaceInstanceData {
+    /// Returns `true` if the instance could be merged with other instances in a single instanced
+    /// draw call.
+    pub fn is_instanceable(&self) -> bool {
+        self.allow_instancing
+            && self.blend_shapes_weights.is_empty()
+            && self.depth_offset == 0.0
+            && self.element_range == ElementRange::Full
+            && self.property_overrides.is_none()
+    }
+
+    /// Returns a set of material property values that override the values of the batch material for
+    /// this instance.
+    pub fn property_overrides(&self) -> &[(ImmutableString, PropertyValue)] {
+        self.property_overrides.as_deref().unwrap_or_default()
+    }
 }
 
 /// A set of surface instances that share the same vertex/index data and a material.
@@ -129,6 +171,13 @@ pub struct RenderDataBatch {
     sort_index: u64,
 }
 
+impl RenderDataBatch {
+    /// Returns sort index of the batch. Batches are rendered in the order of their sort indices.
+    pub fn sort_index(&self) -> u64 {
+        self.sort_index
+    }
+}
+
 impl Debug for RenderDataBatch {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -140,6 +189,61 @@ impl Debug for RenderDataBatch {
     }
 }
 
+fn triangles_batch_key<T: 'static>(
+    material: &MaterialResource,
+    render_path: RenderPath,
+    decal_layer_index: u8,
+    sort_index: u64,
+    is_skinned: bool,
+) -> u64 {
+    let material_key = match material.state().data() {
+        Some(material) => material.batching_key(),
+        None => material.key() as u64,
+    };
+
+    let mut hasher = FxHasher::default();
+    hasher.write_u64(material_key);
+    TypeId::of::<T>().hash(&mut hasher);
+    hasher.write_u8(if is_skinned { 1 } else { 0 });
+    hasher.write_u8(decal_layer_index);
+    hasher.write_u32(render_path as u32);
+    hasher.write_u64(sort_index);
+    hasher.finish()
+}
+
+fn is_same_material(a: &MaterialResource, b: &MaterialResource) -> bool {
+    if a == b {
+        return true;
+    }
+
+    let mut a = a.state();
+    let mut b = b.state();
+    match (a.data(), b.data()) {
+        (Some(a), Some(b)) => a.has_same_content(b),
+        _ => false,
+    }
+}
+
+/// A light source, that is used by the forward renderer. Scene nodes could write light sources to
+/// [`RenderDataBatchStorage::light_sources`] in [`crate::scene::node::NodeTrait::collect_render_data`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightSource {
+    /// World-space position of the light source.
+    pub position: Vector3<f32>,
+    /// World-space direction of the light source.
+    pub direction: Vector3<f32>,
+    /// Color of the light source in linear space.
+    pub color: Vector3<f32>,
+    /// Radius of the light source, [`f32::INFINITY`] for directional light sources.
+    pub radius: f32,
+    /// Cosine of the half of the full cone angle.
+    pub half_cone_angle_cos: f32,
+    /// Cosine of the half of the hotspot cone angle.
+    pub half_hotspot_angle_cos: f32,
+    /// Defines whether the light source is blocked by 2D shadow occluders or not.
+    pub cast_shadows: bool,
+}
+
 /// Batch storage handles batch generation for a scene before rendering. It is used to optimize
 /// rendering by reducing amount of state changes of OpenGL context.
 #[derive(Default)]
@@ -147,6 +251,10 @@ pub struct RenderDataBatchStorage {
     batch_map: FxHashMap<u64, usize>,
     /// A sorted list of batches.
     pub batches: Vec<RenderDataBatch>,
+    /// A list of visible light sources.
+    pub light_sources: Vec<LightSource>,
+    /// Edges of 2D shadow occluders in world coordinates.
+    pub shadow_occluder_edges: Vec<(Vector2<f32>, Vector2<f32>)>,
 }
 
 impl RenderDataBatchStorage {
@@ -163,22 +271,30 @@ impl RenderDataBatchStorage {
         let mut storage = Self {
             batch_map: FxHashMap::with_capacity_and_hasher(capacity, FxBuildHasher::default()),
             batches: Vec::with_capacity(capacity),
+            light_sources: Default::default(),
+            shadow_occluder_edges: Default::default(),
         };
 
         let mut lod_filter = vec![true; graph.capacity() as usize];
         for node in graph.linear_iter() {
             if let Some(lod_group) = node.lod_group() {
-                for level in lod_group.levels.iter() {
+                let active_level =
+                    lod_group.select_level(graph, node.global_position(), &observer_info);
+                // Objects of the active level must stay visible even if they're shared with
+                // other levels.
+                for (index, level) in lod_group.levels.iter().enumerate() {
+                    if Some(index) != active_level {
+                        for &object in level.objects.iter() {
+                            if graph.is_valid_handle(object) {
+                                lod_filter[object.index() as usize] = false;
+                            }
+                        }
+                    }
+                }
+                if let Some(level) = active_level.and_then(|index| lod_group.levels.get(index)) {
                     for &object in level.objects.iter() {
-                        if let Some(object_ref) = graph.try_get(object) {
-                            let distance = observer_info
-                                .observer_position
-                                .metric_distance(&object_ref.global_position());
-                            let z_range = observer_info.z_far - observer_info.z_near;
-                            let normalized_distance = (distance - observer_info.z_near) / z_range;
-                            let visible = normalized_distance >= level.begin()
-                                && normalized_distance <= level.end();
-                            lod_filter[object.index() as usize] = visible;
+                        if graph.is_valid_handle(object) {
+                            lod_filter[object.index() as usize] = true;
                         }
                     }
                 }
@@ -202,12 +318,51 @@ impl RenderDataBatchStorage {
             render_pass_name: &render_pass_name,
         };
 
+        let static_batches = graph.static_batches();
+
         for (handle, node) in graph.pair_iter() {
-            if lod_filter[handle.index() as usize] {
+            if lod_filter[handle.index() as usize]
+                && (node.render_layers() & observer_info.render_mask).0 != 0
+                && !static_batches.is_batched(handle)
+            {
                 node.collect_render_data(&mut ctx);
             }
         }
 
+        let is_shadow_pass = renderer::is_shadow_pass(&render_pass_name);
+        for (index, batch) in static_batches.batches().iter().enumerate() {
+            if (batch.render_layers & observer_info.render_mask).0 == 0
+                || (is_shadow_pass && !batch.cast_shadows)
+                || !frustum.is_intersects_aabb(&batch.bounds)
+            {
+                continue;
+            }
+
+            storage.push(
+                &batch.data,
+                &batch.material,
+                batch.render_path,
+                batch.decal_layer_index,
+                batch.material.key() as u64,
+                SurfaceInstanceData {
+                    world_transform: Matrix4::identity(),
+                    bone_matrices: Default::default(),
+                    depth_offset: 0.0,
+                    blend_shapes_weights: Default::default(),
+                    element_range: ElementRange::Full,
+                    persistent_identifier: PersistentIdentifier::new_combined(
+                        &batch.data,
+                        Handle::NONE,
+                        index,
+                    ),
+                    node_handle: Handle::NONE,
+                    allow_instancing: false,
+                    use_dual_quaternion_skinning: false,
+                    property_overrides: Default::default(),
+                },
+            );
+        }
+
         storage.sort();
 
         storage
@@ -216,15 +371,18 @@ impl RenderDataBatchStorage {
     /// Adds a new mesh to the batch storage using the given set of vertices and triangles. This
     /// method automatically creates a render batch according to a hash of the following parameters:
     ///
-    /// - Material
+    /// - Material content (see [`crate::material::Material::batching_key`])
     /// - Vertex Type
     /// - Render Path
     /// - Skinning
     /// - Decal Layer Index
+    /// - Sort Index
     ///
     /// If one of these parameters is different, then a new batch will be created and used to store
     /// the given vertices and indices. If an appropriate batch exists, the the method will store
-    /// the given vertices and the triangles in it.
+    /// the given vertices and the triangles in it. Since materials are compared by their content,
+    /// sprites that use different material resources but the same texture atlas (and the same
+    /// shader parameters) will be merged in a single draw call.
     ///
     /// ## When to use
     ///
@@ -248,16 +406,35 @@ impl RenderDataBatchStorage {
     ) where
         T: VertexTrait,
     {
-        let mut hasher = FxHasher::default();
-        hasher.write_u64(material.key() as u64);
-        TypeId::of::<T>().hash(&mut hasher);
-        hasher.write_u8(if is_skinned { 1 } else { 0 });
-        hasher.write_u8(decal_layer_index);
-        hasher.write_u32(render_path as u32);
-        let key = hasher.finish();
+        let mut key = triangles_batch_key::<T>(
+            material,
+            render_path,
+            decal_layer_index,
+            sort_index,
+            is_skinned,
+        );
 
-        let batch = if let Some(&batch_index) = self.batch_map.get(&key) {
-            self.batches.get_mut(batch_index).unwrap()
+        // Materials are compared by their content hash, so different materials could have the same
+        // key. Check the content of the materials and probe next keys in case of collision.
+        let mut existing_batch = None;
+        while let Some(&batch_index) = self.batch_map.get(&key) {
+            let batch = &self.batches[batch_index];
+            if batch.is_skinned == is_skinned
+                && batch.decal_layer_index == decal_layer_index
+                && batch.render_path == render_path
+                && batch.sort_index == sort_index
+                && batch.data.lock().vertex_buffer.vertex_size() as usize
+                    == std::mem::size_of::<T>()
+                && is_same_material(&batch.material, material)
+            {
+                existing_batch = Some(batch_index);
+                break;
+            }
+            key = key.wrapping_add(1);
+        }
+
+        let batch = if let Some(batch_index) = existing_batch {
+            &mut self.batches[batch_index]
         } else {
             let default_capacity = 4096;
 
@@ -287,6 +464,9 @@ impl RenderDataBatchStorage {
                         element_range: Default::default(),
                         persistent_identifier,
                         node_handle,
+                        allow_instancing: false,
+                        use_dual_quaternion_skinning: false,
+                        property_overrides: Default::default(),
                     },
                 ],
                 material: material.clone(),
@@ -315,8 +495,8 @@ impl RenderDataBatchStorage {
     }
 
     /// Adds a new surface instance to the storage. The method will automatically put the instance in the appropriate
-    /// batch. Batch selection is done using the material, surface data, render path, decal layer index, skinning flag.
-    /// If only one of these parameters is different, then the surface instance will be put in a separate batch.
+    /// batch. Batch selection is done using the material, surface data, render path, decal layer index, skinning flag
+    /// and sort index. If only one of these parameters is different, then the surface instance will be put in a separate batch.
     pub fn push(
         &mut self,
         data: &SurfaceSharedData,
@@ -334,6 +514,7 @@ impl RenderDataBatchStorage {
         hasher.write_u8(if is_skinned { 1 } else { 0 });
         hasher.write_u8(decal_layer_index);
         hasher.write_u32(render_path as u32);
+        hasher.write_u64(sort_index);
         let key = hasher.finish();
 
         let batch = if let Some(&batch_index) = self.batch_map.get(&key) {
@@ -356,8 +537,63 @@ impl RenderDataBatchStorage {
         batch.instances.push(instance_data)
     }
 
-    /// Sorts the batches by their respective sort index.
+    /// Sorts the batches by their respective sort index. The sorting is stable, batches with the same
+    /// sort index preserve the order in which they were added.
     pub fn sort(&mut self) {
-        self.batches.sort_unstable_by_key(|b| b.sort_index);
+        self.batches.sort_by_key(|b| b.sort_index);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::{color::Color, math::TriangleDefinition, sstorage::ImmutableString},
+        material::{Material, MaterialResource, PropertyValue},
+        renderer::batch::{triangles_batch_key, RenderDataBatchStorage},
+        scene::mesh::{vertex::StaticVertex, RenderPath},
+    };
+
+    fn push(storage: &mut RenderDataBatchStorage, material: &MaterialResource) {
+        storage.push_triangles(
+            [StaticVertex::default(); 3].into_iter(),
+            [TriangleDefinition([0, 1, 2])].into_iter(),
+            material,
+            RenderPath::Forward,
+            0,
+            0,
+            false,
+            Default::default(),
+        );
+    }
+
+    #[test]
+    fn test_push_triangles_batching() {
+        let a = MaterialResource::new_ok(ResourceKind::Embedded, Material::standard());
+        let b = MaterialResource::new_ok(ResourceKind::Embedded, Material::standard());
+        let mut red = Material::standard();
+        red.set_property(
+            &ImmutableString::new("diffuseColor"),
+            PropertyValue::Color(Color::RED),
+        )
+        .unwrap();
+        let red = MaterialResource::new_ok(ResourceKind::Embedded, red);
+
+        let mut storage = RenderDataBatchStorage::default();
+        push(&mut storage, &a);
+        // Different resources with the same content must be merged.
+        push(&mut storage, &b);
+        assert_eq!(storage.batches.len(), 1);
+
+        // Simulate a hash collision, the materials must still be put in separate batches.
+        let red_key = triangles_batch_key::<StaticVertex>(&red, RenderPath::Forward, 0, 0, false);
+        storage.batch_map.insert(red_key, 0);
+        push(&mut storage, &red);
+        assert_eq!(storage.batches.len(), 2);
+        assert_eq!(
+            storage.batches[0].data.lock().vertex_buffer.vertex_count(),
+            6
+        );
+        assert_eq!(storage.batches[1].material, red);
     }
 }
@@ -26,9 +26,17 @@ pub struct GeometryCache {
 fn create_geometry_buffer(
     data: &SurfaceData,
     state: &PipelineState,
+    time_to_live: TimeToLive,
 ) -> Result<SurfaceRenderData, FrameworkError> {
-    let geometry_buffer =
-        GeometryBuffer::from_surface_data(data, GeometryBufferKind::StaticDraw, state)?;
+    // Temporary data (such as batches of 2D sprites) is re-created every frame, so it is better to
+    // hint the driver about it.
+    let kind = if *time_to_live <= 0.0 {
+        GeometryBufferKind::DynamicDraw
+    } else {
+        GeometryBufferKind::StaticDraw
+    };
+
+    let geometry_buffer = GeometryBuffer::from_surface_data(data, kind, state)?;
 
     Ok(SurfaceRenderData {
         buffer: geometry_buffer,
@@ -50,7 +58,7 @@ impl GeometryCache {
         match self
             .buffer
             .get_entry_mut_or_insert_with(&data.cache_index, time_to_live, || {
-                create_geometry_buffer(&data, state)
+                create_geometry_buffer(&data, state, time_to_live)
             }) {
             Ok(entry) => {
                 // We also must check if buffer's layout changed, and if so - recreate the entire
@@ -58,10 +66,26 @@ impl GeometryCache {
                 if entry.layout_hash == data.vertex_buffer.layout_hash() {
                     if data.vertex_buffer.modifications_count() != entry.vertex_modifications_count
                     {
-                        // Vertices has changed, upload the new content.
-                        entry
-                            .buffer
-                            .set_buffer_data(state, 0, data.vertex_buffer.raw_data());
+                        // Vertices has changed, upload the new content. Upload only the modified
+                        // region if the size of the buffer is the same.
+                        let raw_data = data.vertex_buffer.raw_data();
+                        match data
+                            .vertex_buffer
+                            .dirty_range_since(entry.vertex_modifications_count)
+                        {
+                            Some(range)
+                                if entry.buffer.buffer_size(0) == raw_data.len()
+                                    && range.len() < raw_data.len() =>
+                            {
+                                entry.buffer.set_buffer_sub_data(
+                                    state,
+                                    0,
+                                    range.start,
+                                    &raw_data[range],
+                                );
+                            }
+                            _ => entry.buffer.set_buffer_data(state, 0, raw_data),
+                        }
 
                         entry.vertex_modifications_count = data.vertex_buffer.modifications_count();
                     }
@@ -0,0 +1,224 @@
+//! Frame capture allows to fetch the contents of rendered frames on the CPU side. See
+//! [`crate::renderer::Renderer::capture_frame`] and [`FrameSequenceDumper`] docs for more info.
+
+use crate::{
+    core::{math::Rect, parking_lot::Mutex},
+    renderer::{
+        framework::{
+            error::FrameworkError, framebuffer::FrameBuffer, pixel_buffer::AsyncPixelReadback,
+            state::PipelineState,
+        },
+        Renderer,
+    },
+};
+use image::{ImageResult, RgbaImage};
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Maximum amount of frames to wait for a delayed capture. If the GPU is still busy after this
+/// amount of frames, the renderer will wait for it.
+const MAX_CAPTURE_DELAY: u32 = 2;
+
+/// Defines how a frame is captured.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub enum FrameCaptureMode {
+    /// The pixels are read right after the frame is rendered. It forces the CPU to wait until the
+    /// GPU finishes rendering the frame, which may cause a noticeable stall. The result is
+    /// available right after the frame is rendered.
+    Immediate,
+    /// The pixels are copied to a GPU-side buffer and fetched 1-2 frames later, when the GPU is
+    /// done with the copy. This mode does not stall the pipeline and suits well for continuous
+    /// capture (for example, video recording).
+    #[default]
+    Delayed,
+}
+
+/// A pending frame capture, created by [`crate::renderer::Renderer::capture_frame`]. The request
+/// could be freely cloned and sent to other threads, all the clones share the same result.
+#[derive(Clone, Default, Debug)]
+pub struct FrameCaptureRequest {
+    result: Arc<Mutex<Option<RgbaImage>>>,
+}
+
+impl FrameCaptureRequest {
+    /// Returns `true` if the frame is captured, `false` - otherwise.
+    pub fn is_ready(&self) -> bool {
+        self.result.lock().is_some()
+    }
+
+    /// Takes the captured frame (if any). The image is stored from top to bottom, its alpha
+    /// channel contains alpha of the back buffer. Subsequent calls will return `None`.
+    pub fn take(&self) -> Option<RgbaImage> {
+        self.result.lock().take()
+    }
+
+    fn fulfill(&self, image: RgbaImage) {
+        *self.result.lock() = Some(image);
+    }
+}
+
+struct InFlightCapture {
+    readback: AsyncPixelReadback,
+    width: u32,
+    height: u32,
+    frames_waited: u32,
+    request: FrameCaptureRequest,
+}
+
+#[derive(Default)]
+pub(crate) struct FrameCapturer {
+    requests: Vec<(FrameCaptureMode, FrameCaptureRequest)>,
+    in_flight: Vec<InFlightCapture>,
+}
+
+// Converts bottom-to-top RGBA8 rows (as they're read from OpenGL) to an image.
+fn image_from_pixels(width: u32, height: u32, pixels: Vec<u8>) -> RgbaImage {
+    let mut flipped = Vec::with_capacity(pixels.len());
+    for row in pixels.chunks_exact(width as usize * 4).rev() {
+        flipped.extend_from_slice(row);
+    }
+    RgbaImage::from_raw(width, height, flipped).unwrap()
+}
+
+impl FrameCapturer {
+    pub(crate) fn request(&mut self, mode: FrameCaptureMode) -> FrameCaptureRequest {
+        let request = FrameCaptureRequest::default();
+        self.requests.push((mode, request.clone()));
+        request
+    }
+
+    /// Must be called after a frame was rendered into the back buffer, but before the buffers are
+    /// swapped.
+    pub(crate) fn process(
+        &mut self,
+        state: &PipelineState,
+        backbuffer: &FrameBuffer,
+        frame_size: (u32, u32),
+    ) -> Result<(), FrameworkError> {
+        let mut i = 0;
+        while i < self.in_flight.len() {
+            let capture = &mut self.in_flight[i];
+            capture.frames_waited += 1;
+            if capture.frames_waited >= MAX_CAPTURE_DELAY || capture.readback.is_ready(state) {
+                let capture = self.in_flight.remove(i);
+                let pixels = capture.readback.fetch(state);
+                capture
+                    .request
+                    .fulfill(image_from_pixels(capture.width, capture.height, pixels));
+            } else {
+                i += 1;
+            }
+        }
+
+        let (width, height) = frame_size;
+        if width == 0 || height == 0 {
+            // Nothing to capture (the window is minimized), keep the requests for the next frames.
+            return Ok(());
+        }
+
+        let region = Rect::new(0, 0, width as i32, height as i32);
+        for (mode, request) in self.requests.drain(..) {
+            match mode {
+                FrameCaptureMode::Immediate => {
+                    let pixels = backbuffer.read_pixels(state, 0, region);
+                    request.fulfill(image_from_pixels(width, height, pixels));
+                }
+                FrameCaptureMode::Delayed => {
+                    self.in_flight.push(InFlightCapture {
+                        readback: AsyncPixelReadback::new(state, backbuffer, 0, region)?,
+                        width,
+                        height,
+                        frames_waited: 0,
+                        request,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Frame sequence dumper saves every rendered frame into a numbered image file (`prefix_00000.png`,
+/// `prefix_00001.png`, etc.), which could be used to produce videos with external tools or compared
+/// with reference images in automated visual regression tests. It uses delayed frame capture (see
+/// [`FrameCaptureMode::Delayed`]), so the recording does not stall the rendering pipeline, however
+/// image encoding is still performed on the calling thread.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use fyrox::{engine::Engine, renderer::capture::FrameSequenceDumper};
+/// fn record_frame(engine: &mut Engine, dumper: &mut FrameSequenceDumper) {
+///     // Call this once per frame, while the recording is active.
+///     let graphics_context = engine.graphics_context.as_initialized_mut();
+///     dumper.update(&mut graphics_context.renderer).unwrap();
+/// }
+/// ```
+pub struct FrameSequenceDumper {
+    directory: PathBuf,
+    prefix: String,
+    next_index: usize,
+    pending: VecDeque<(usize, FrameCaptureRequest)>,
+}
+
+impl FrameSequenceDumper {
+    /// Creates new frame sequence dumper, that will save frames into the given directory. The
+    /// directory will be created if it does not exist.
+    pub fn new<P: AsRef<Path>>(directory: P, prefix: &str) -> std::io::Result<Self> {
+        std::fs::create_dir_all(directory.as_ref())?;
+        Ok(Self {
+            directory: directory.as_ref().to_path_buf(),
+            prefix: prefix.to_string(),
+            next_index: 0,
+            pending: Default::default(),
+        })
+    }
+
+    /// Requests a capture of the next frame and saves every frame that was captured so far. Must
+    /// be called once per frame. Keep in mind, that frames are captured with 1-2 frame delay, so
+    /// last few frames will be saved only on subsequent calls.
+    pub fn update(&mut self, renderer: &mut Renderer) -> ImageResult<()> {
+        self.pending.push_back((
+            self.next_index,
+            renderer.capture_frame(FrameCaptureMode::Delayed),
+        ));
+        self.next_index += 1;
+
+        while let Some(image) = self.pending.front().and_then(|(_, request)| request.take()) {
+            let (index, _) = self.pending.pop_front().unwrap();
+            image.save(self.frame_path(index))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns a path to a file of a frame with the given index.
+    pub fn frame_path(&self, index: usize) -> PathBuf {
+        self.directory
+            .join(format!("{}_{:05}.png", self.prefix, index))
+    }
+
+    /// Returns total amount of requested frames.
+    pub fn frame_count(&self) -> usize {
+        self.next_index
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::capture::image_from_pixels;
+    use image::Rgba;
+
+    #[test]
+    fn test_image_from_pixels() {
+        // Two rows, the bottom one goes first.
+        let pixels = vec![1, 1, 1, 1, 2, 2, 2, 2];
+        let image = image_from_pixels(1, 2, pixels);
+        assert_eq!(image.get_pixel(0, 0), &Rgba([2, 2, 2, 2]));
+        assert_eq!(image.get_pixel(0, 1), &Rgba([1, 1, 1, 1]));
+    }
+}
@@ -10,44 +10,49 @@ use crate::{
     core::{
         algebra::{Vector2, Vector4},
         color::Color,
-        math::{frustum::Frustum, Rect},
+        log::Log,
+        math::Rect,
         scope_profile,
         sstorage::ImmutableString,
     },
     renderer::{
         apply_material,
-        batch::RenderDataBatchStorage,
+        batch::{LightSource, RenderDataBatchStorage},
         cache::{shader::ShaderCache, texture::TextureCache},
         framework::{
-            error::FrameworkError, framebuffer::FrameBuffer, gpu_texture::GpuTexture,
+            error::FrameworkError,
+            framebuffer::{DrawParameters, FrameBuffer},
+            gpu_program::BuiltInUniform,
+            gpu_texture::GpuTexture,
             state::PipelineState,
         },
         storage::MatrixStorageCache,
         GeometryCache, LightData, MaterialContext, QualitySettings, RenderPassStatistics,
+        MAX_2D_SHADOW_OCCLUDER_EDGES,
     },
-    scene::{
-        camera::Camera,
-        graph::Graph,
-        light::{directional::DirectionalLight, point::PointLight, spot::SpotLight},
-        mesh::RenderPath,
-    },
+    scene::{camera::Camera, mesh::RenderPath, sorting::SortingOrder},
 };
 use fyrox_core::math::Matrix4Ext;
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
 pub(crate) struct ForwardRenderer {
     render_pass_name: ImmutableString,
+    // The warning about too many occluder edges is printed only once to not spam the log every frame.
+    occluder_overflow_reported: Cell<bool>,
 }
 
 pub(crate) struct ForwardRenderContext<'a, 'b> {
     pub state: &'a PipelineState,
-    pub graph: &'b Graph,
     pub camera: &'b Camera,
     pub geom_cache: &'a mut GeometryCache,
     pub texture_cache: &'a mut TextureCache,
     pub shader_cache: &'a mut ShaderCache,
     pub batch_storage: &'a RenderDataBatchStorage,
     pub framebuffer: &'a mut FrameBuffer,
+    pub scene_color_framebuffer: &'a FrameBuffer,
     pub viewport: Rect<i32>,
     pub quality_settings: &'a QualitySettings,
     pub white_dummy: Rc<RefCell<GpuTexture>>,
@@ -59,10 +64,62 @@ pub(crate) struct ForwardRenderContext<'a, 'b> {
     pub ambient_light: Color,
 }
 
+fn distance_to_segment(point: Vector2<f32>, begin: Vector2<f32>, end: Vector2<f32>) -> f32 {
+    let edge = end - begin;
+    let t = (point - begin).dot(&edge) / edge.norm_squared().max(f32::EPSILON);
+    (begin + edge.scale(t.clamp(0.0, 1.0))).metric_distance(&point)
+}
+
+/// Fills the light data with the given light sources. Edges of 2D shadow occluders are packed for
+/// each light source separately and only if they could block the light (an edge is within the radius
+/// of the light), so the shader checks only a few edges per light. Returns `false` if some edges did
+/// not fit in the light data.
+fn fill_light_data(
+    light_data: &mut LightData,
+    light_sources: &[LightSource],
+    occluder_edges: &[(Vector2<f32>, Vector2<f32>)],
+) -> bool {
+    let mut all_edges_fit = true;
+    let mut edge_count = 0;
+
+    for light in light_sources.iter().take(light_data.parameters.len()) {
+        let light_num = light_data.count;
+
+        light_data.position[light_num] = light.position;
+        light_data.direction[light_num] = light.direction;
+        light_data.color_radius[light_num] = light.color.push(light.radius);
+        light_data.parameters[light_num] =
+            Vector2::new(light.half_cone_angle_cos, light.half_hotspot_angle_cos);
+
+        let offset = edge_count;
+        if light.cast_shadows {
+            for (begin, end) in occluder_edges.iter().filter(|(begin, end)| {
+                light.radius.is_infinite()
+                    || distance_to_segment(light.position.xy(), *begin, *end) <= light.radius
+            }) {
+                if edge_count == light_data.occluder_edges.len() {
+                    all_edges_fit = false;
+                    break;
+                }
+                light_data.occluder_edges[edge_count] =
+                    Vector4::new(begin.x, begin.y, end.x, end.y);
+                edge_count += 1;
+            }
+        }
+        light_data.shadow_edges_offset[light_num] = offset as i32;
+        light_data.shadow_edges_count[light_num] = (edge_count - offset) as i32;
+
+        light_data.count += 1;
+    }
+
+    all_edges_fit
+}
+
 impl ForwardRenderer {
     pub(crate) fn new() -> Self {
         Self {
             render_pass_name: ImmutableString::new("Forward"),
+            occluder_overflow_reported: Cell::new(false),
         }
     }
 
@@ -76,13 +133,13 @@ impl ForwardRenderer {
 
         let ForwardRenderContext {
             state,
-            graph,
             camera,
             geom_cache,
             texture_cache,
             shader_cache,
             batch_storage,
             framebuffer,
+            scene_color_framebuffer,
             viewport,
             quality_settings,
             white_dummy,
@@ -96,60 +153,30 @@ impl ForwardRenderer {
 
         let initial_view_projection = camera.view_projection_matrix();
 
-        let frustum = Frustum::from_view_projection_matrix(camera.view_projection_matrix())
-            .unwrap_or_default();
-
         let inv_view = camera.inv_view_matrix().unwrap();
 
         let camera_up = inv_view.up();
         let camera_side = inv_view.side();
 
         let mut light_data = LightData::default();
-        for light in graph.linear_iter() {
-            if !light.global_visibility() || light_data.count == light_data.parameters.len() {
-                continue;
-            }
-
-            let (radius, half_cone_angle_cos, half_hotspot_angle_cos, color) =
-                if let Some(point) = light.cast::<PointLight>() {
-                    (
-                        point.radius(),
-                        std::f32::consts::PI.cos(),
-                        std::f32::consts::PI.cos(),
-                        point.base_light_ref().color().as_frgb(),
-                    )
-                } else if let Some(spot) = light.cast::<SpotLight>() {
-                    (
-                        spot.distance(),
-                        (spot.hotspot_cone_angle() * 0.5).cos(),
-                        (spot.full_cone_angle() * 0.5).cos(),
-                        spot.base_light_ref().color().as_frgb(),
-                    )
-                } else if let Some(directional) = light.cast::<DirectionalLight>() {
-                    (
-                        f32::INFINITY,
-                        std::f32::consts::PI.cos(),
-                        std::f32::consts::PI.cos(),
-                        directional.base_light_ref().color().as_frgb(),
-                    )
-                } else {
-                    continue;
-                };
-
-            if frustum.is_intersects_aabb(&light.world_bounding_box()) {
-                let light_num = light_data.count;
-
-                light_data.position[light_num] = light.global_position();
-                light_data.direction[light_num] = light.up_vector();
-                light_data.color_radius[light_num] =
-                    Vector4::new(color.x, color.y, color.z, radius);
-                light_data.parameters[light_num] =
-                    Vector2::new(half_cone_angle_cos, half_hotspot_angle_cos);
-
-                light_data.count += 1;
-            }
+        if !fill_light_data(
+            &mut light_data,
+            &batch_storage.light_sources,
+            &batch_storage.shadow_occluder_edges,
+        ) && !self.occluder_overflow_reported.replace(true)
+        {
+            Log::warn(format!(
+                "There are too many 2D shadow occluder edges near the light sources, only \
+                {MAX_2D_SHADOW_OCCLUDER_EDGES} edges could be used at once. Some shadows will be \
+                missing!"
+            ));
         }
 
+        let scene_color = scene_color_framebuffer.color_attachments()[0]
+            .texture
+            .clone();
+        let mut is_scene_color_copied = false;
+
         for batch in batch_storage
             .batches
             .iter()
@@ -179,6 +206,44 @@ impl ForwardRenderer {
                 continue;
             };
 
+            // The copy is made only once and only if there's at least one material that needs it,
+            // which means that it contains everything drawn before the first such material.
+            if !is_scene_color_copied
+                && render_pass.program.built_in_uniform_locations
+                    [BuiltInUniform::SceneColor as usize]
+                    .is_some()
+            {
+                state.blit_framebuffer(
+                    framebuffer.id(),
+                    scene_color_framebuffer.id(),
+                    viewport.x(),
+                    viewport.y(),
+                    viewport.x() + viewport.w(),
+                    viewport.y() + viewport.h(),
+                    viewport.x(),
+                    viewport.y(),
+                    viewport.x() + viewport.w(),
+                    viewport.y() + viewport.h(),
+                    true,
+                    false,
+                    false,
+                );
+                is_scene_color_copied = true;
+            }
+
+            // Nodes with sorting order are drawn in order, which defines how they overlap each other,
+            // so they must not occlude each other by depth.
+            let sorted_draw_params;
+            let draw_params = if SortingOrder::is_sorted_index(batch.sort_index()) {
+                sorted_draw_params = DrawParameters {
+                    depth_write: false,
+                    ..render_pass.draw_params.clone()
+                };
+                &sorted_draw_params
+            } else {
+                &render_pass.draw_params
+            };
+
             for instance in batch.instances.iter() {
                 let view_projection = if instance.depth_offset != 0.0 {
                     let mut projection = camera.projection_matrix();
@@ -193,7 +258,7 @@ impl ForwardRenderer {
                     state,
                     viewport,
                     &render_pass.program,
-                    &render_pass.draw_params,
+                    draw_params,
                     instance.element_range,
                     |mut program_binding| {
                         apply_material(MaterialContext {
@@ -205,6 +270,10 @@ impl ForwardRenderer {
                             wvp_matrix: &(view_projection * instance.world_transform),
                             bone_matrices: &instance.bone_matrices,
                             use_skeletal_animation: batch.is_skinned,
+                            use_dual_quaternion_skinning: instance.use_dual_quaternion_skinning,
+                            instance_matrices: &[],
+                            instance_bone_count: 0,
+                            property_overrides: instance.property_overrides(),
                             camera_position: &camera.global_position(),
                             camera_up_vector: &camera_up,
                             camera_side_vector: &camera_side,
@@ -221,8 +290,10 @@ impl ForwardRenderer {
                             matrix_storage,
                             persistent_identifier: instance.persistent_identifier,
                             light_data: Some(&light_data),
+                            velocity_data: None,
                             ambient_light,
                             scene_depth: Some(&scene_depth),
+                            scene_color: Some(&scene_color),
                         });
                     },
                 )?;
@@ -232,3 +303,79 @@ impl ForwardRenderer {
         Ok(statistics)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        renderer::{
+            batch::LightSource, forward_renderer::fill_light_data, LightData,
+            MAX_2D_SHADOW_OCCLUDER_EDGES,
+        },
+    };
+
+    fn light(position: Vector3<f32>, radius: f32, cast_shadows: bool) -> LightSource {
+        LightSource {
+            position,
+            direction: Vector3::default(),
+            color: Vector3::repeat(1.0),
+            radius,
+            half_cone_angle_cos: -1.0,
+            half_hotspot_angle_cos: -1.0,
+            cast_shadows,
+        }
+    }
+
+    #[test]
+    fn test_fill_light_data() {
+        let near = (Vector2::new(1.0, -1.0), Vector2::new(1.0, 1.0));
+        let far = (Vector2::new(10.0, -1.0), Vector2::new(10.0, 1.0));
+        let edges = [near, far];
+
+        let mut light_data = LightData::default();
+        assert!(fill_light_data(
+            &mut light_data,
+            &[
+                light(Vector3::default(), 2.0, true),
+                light(Vector3::default(), 2.0, false),
+                light(Vector3::default(), f32::INFINITY, true),
+            ],
+            &edges,
+        ));
+        assert_eq!(light_data.count, 3);
+
+        // Only the edges within the radius of a light are used.
+        assert_eq!(light_data.shadow_edges_offset[0], 0);
+        assert_eq!(light_data.shadow_edges_count[0], 1);
+        assert_eq!(light_data.occluder_edges[0].x, 1.0);
+
+        // No edges for the lights without shadows.
+        assert_eq!(light_data.shadow_edges_count[1], 0);
+
+        // Directional lights are blocked by every edge.
+        assert_eq!(light_data.shadow_edges_offset[2], 1);
+        assert_eq!(light_data.shadow_edges_count[2], 2);
+    }
+
+    #[test]
+    fn test_fill_light_data_overflow() {
+        let edges =
+            vec![(Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)); MAX_2D_SHADOW_OCCLUDER_EDGES];
+
+        let mut light_data = LightData::default();
+        assert!(!fill_light_data(
+            &mut light_data,
+            &[
+                light(Vector3::default(), 2.0, true),
+                light(Vector3::default(), 2.0, true),
+            ],
+            &edges,
+        ));
+        assert_eq!(light_data.count, 2);
+        assert_eq!(
+            light_data.shadow_edges_count[0] as usize,
+            MAX_2D_SHADOW_OCCLUDER_EDGES
+        );
+        assert_eq!(light_data.shadow_edges_count[1], 0);
+    }
+}
@@ -337,6 +337,74 @@ impl FrameBuffer {
         }
     }
 
+    /// Reads pixels of the given color attachment in RGBA8 format. Rows are stored from bottom
+    /// to top.
+    pub fn read_pixels(
+        &self,
+        state: &PipelineState,
+        attachment_index: usize,
+        region: Rect<i32>,
+    ) -> Vec<u8> {
+        let mut pixels = vec![0u8; (region.w().max(0) * region.h().max(0) * 4) as usize];
+        self.read_pixels_into(
+            state,
+            attachment_index,
+            region,
+            glow::UNSIGNED_BYTE,
+            &mut pixels,
+        );
+        pixels
+    }
+
+    /// Reads pixels of the given color attachment in RGBA32F format. Rows are stored from bottom
+    /// to top. Unlike [`Self::read_pixels`], the values are not clamped to `[0; 1]` range, so it
+    /// could be used to read high dynamic range images.
+    pub fn read_pixels_f32(
+        &self,
+        state: &PipelineState,
+        attachment_index: usize,
+        region: Rect<i32>,
+    ) -> Vec<f32> {
+        let component_count = (region.w().max(0) * region.h().max(0) * 4) as usize;
+        let mut bytes = vec![0u8; component_count * std::mem::size_of::<f32>()];
+        self.read_pixels_into(state, attachment_index, region, glow::FLOAT, &mut bytes);
+        bytes
+            .chunks_exact(std::mem::size_of::<f32>())
+            .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect()
+    }
+
+    fn read_pixels_into(
+        &self,
+        state: &PipelineState,
+        attachment_index: usize,
+        region: Rect<i32>,
+        gl_type: u32,
+        bytes: &mut [u8],
+    ) {
+        scope_profile!();
+
+        state.set_framebuffer(self.id());
+
+        unsafe {
+            if self.fbo.is_some() {
+                state
+                    .gl
+                    .read_buffer(glow::COLOR_ATTACHMENT0 + attachment_index as u32);
+            }
+            state.gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+            state.gl.read_pixels(
+                region.x(),
+                region.y(),
+                region.w(),
+                region.h(),
+                glow::RGBA,
+                gl_type,
+                glow::PixelPackData::Slice(bytes),
+            );
+        }
+    }
+
     pub fn draw<F: FnOnce(GpuProgramBinding<'_, '_>)>(
         &mut self,
         geometry: &GeometryBuffer,
@@ -336,6 +336,32 @@ impl GeometryBuffer {
         buffer.size_bytes = size;
     }
 
+    pub fn buffer_size(&self, buffer: usize) -> usize {
+        self.buffers[buffer].size_bytes
+    }
+
+    pub fn set_buffer_sub_data(
+        &mut self,
+        state: &PipelineState,
+        buffer: usize,
+        offset: usize,
+        data: &[u8],
+    ) {
+        scope_profile!();
+
+        let buffer = &mut self.buffers[buffer];
+
+        assert!(offset + data.len() <= buffer.size_bytes);
+
+        state.set_vertex_buffer_object(Some(buffer.id));
+
+        unsafe {
+            state
+                .gl
+                .buffer_sub_data_u8_slice(glow::ARRAY_BUFFER, offset as i32, data);
+        }
+    }
+
     pub fn bind<'a>(&'a self, state: &'a PipelineState) -> GeometryBufferBinding<'a> {
         scope_profile!();
 
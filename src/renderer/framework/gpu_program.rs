@@ -36,6 +36,7 @@ pub enum BuiltInUniform {
     ZNear,
     ZFar,
     SceneDepth,
+    SceneColor,
     UsePOM,
     LightPosition,
     BlendShapesStorage,
@@ -46,7 +47,17 @@ pub enum BuiltInUniform {
     LightsPosition,
     LightsDirection,
     LightsParameters,
+    LightsShadowEdgesOffset,
+    LightsShadowEdgesCount,
+    OccluderEdges,
     AmbientLight,
+    InstanceMatrices,
+    UseInstancing,
+    UseDualQuaternionSkinning,
+    InstanceBoneCount,
+    PreviousWorldViewProjectionMatrix,
+    PreviousViewProjectionMatrix,
+    ProjectionJitter,
     // Must be last.
     Count,
 }
@@ -455,6 +466,8 @@ fn fetch_built_in_uniform_locations(
 
     locations[BuiltInUniform::SceneDepth as usize] =
         fetch_uniform_location(state, program, "fyrox_sceneDepth");
+    locations[BuiltInUniform::SceneColor as usize] =
+        fetch_uniform_location(state, program, "fyrox_sceneColor");
 
     locations[BuiltInUniform::UsePOM as usize] =
         fetch_uniform_location(state, program, "fyrox_usePOM");
@@ -476,11 +489,32 @@ fn fetch_built_in_uniform_locations(
         fetch_uniform_location(state, program, "fyrox_lightsDirection");
     locations[BuiltInUniform::LightsParameters as usize] =
         fetch_uniform_location(state, program, "fyrox_lightsParameters");
+    locations[BuiltInUniform::LightsShadowEdgesOffset as usize] =
+        fetch_uniform_location(state, program, "fyrox_lightsShadowEdgesOffset");
+    locations[BuiltInUniform::LightsShadowEdgesCount as usize] =
+        fetch_uniform_location(state, program, "fyrox_lightsShadowEdgesCount");
+    locations[BuiltInUniform::OccluderEdges as usize] =
+        fetch_uniform_location(state, program, "fyrox_occluderEdges");
     locations[BuiltInUniform::AmbientLight as usize] =
         fetch_uniform_location(state, program, "fyrox_ambientLightColor");
     locations[BuiltInUniform::LightPosition as usize] =
         fetch_uniform_location(state, program, "fyrox_lightPosition");
 
+    locations[BuiltInUniform::InstanceMatrices as usize] =
+        fetch_uniform_location(state, program, "fyrox_instanceMatrices");
+    locations[BuiltInUniform::UseInstancing as usize] =
+        fetch_uniform_location(state, program, "fyrox_useInstancing");
+    locations[BuiltInUniform::UseDualQuaternionSkinning as usize] =
+        fetch_uniform_location(state, program, "fyrox_useDualQuaternionSkinning");
+    locations[BuiltInUniform::InstanceBoneCount as usize] =
+        fetch_uniform_location(state, program, "fyrox_instanceBoneCount");
+    locations[BuiltInUniform::PreviousWorldViewProjectionMatrix as usize] =
+        fetch_uniform_location(state, program, "fyrox_previousWorldViewProjection");
+    locations[BuiltInUniform::PreviousViewProjectionMatrix as usize] =
+        fetch_uniform_location(state, program, "fyrox_previousViewProjectionMatrix");
+    locations[BuiltInUniform::ProjectionJitter as usize] =
+        fetch_uniform_location(state, program, "fyrox_projectionJitter");
+
     locations
 }
 
@@ -100,6 +100,7 @@ pub enum PixelKind {
     RGBA32F,
     RGB16F,
     RGBA16F,
+    RG16F,
     R8RGTC,
     RG8RGTC,
     R11G11B10F,
@@ -158,6 +159,7 @@ impl PixelKind {
             | Self::SRGBA8
             | Self::BGRA8
             | Self::RG16
+            | Self::RG16F
             | Self::LA16
             | Self::D24S8
             | Self::D32F
@@ -201,6 +203,7 @@ impl PixelKind {
             | Self::BGRA8
             | Self::BGR8
             | Self::RG16
+            | Self::RG16F
             | Self::R16
             | Self::D24S8
             | Self::D32F
@@ -229,6 +232,7 @@ impl PixelKind {
             | Self::RGBA32F
             | Self::RGBA16F
             | Self::RGB16F
+            | Self::RG16F
             | Self::D32F
             | Self::R11G11B10F => PixelElementKind::Float,
             Self::D16
@@ -291,6 +295,7 @@ fn image_3d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize, depth
         | PixelKind::SRGBA8
         | PixelKind::BGRA8
         | PixelKind::RG16
+        | PixelKind::RG16F
         | PixelKind::LA16
         | PixelKind::D24S8
         | PixelKind::D32F
@@ -327,6 +332,7 @@ fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -> us
         | PixelKind::SRGBA8
         | PixelKind::BGRA8
         | PixelKind::RG16
+        | PixelKind::RG16F
         | PixelKind::LA16
         | PixelKind::D24S8
         | PixelKind::D32F
@@ -362,6 +368,7 @@ fn image_1d_size_bytes(pixel_kind: PixelKind, length: usize) -> usize {
         | PixelKind::SRGBA8
         | PixelKind::BGRA8
         | PixelKind::RG16
+        | PixelKind::RG16F
         | PixelKind::LA16
         | PixelKind::D24S8
         | PixelKind::D32F
@@ -723,6 +730,7 @@ impl<'a> TextureBinding<'a> {
                 PixelKind::RGBA32F => (glow::FLOAT, glow::RGBA, glow::RGBA32F, None),
                 PixelKind::RGBA16F => (glow::HALF_FLOAT, glow::RGBA, glow::RGBA16F, None),
                 PixelKind::RGB16F => (glow::HALF_FLOAT, glow::RGB, glow::RGB16F, None),
+                PixelKind::RG16F => (glow::HALF_FLOAT, glow::RG, glow::RG16F, None),
                 PixelKind::R11G11B10F => (glow::FLOAT, glow::RGB, glow::R11F_G11F_B10F, None),
                 PixelKind::L8 => (
                     glow::UNSIGNED_BYTE,
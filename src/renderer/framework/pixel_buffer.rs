@@ -0,0 +1,108 @@
+//! Pixel buffers allow to read pixels of a frame buffer asynchronously, without stalling the
+//! pipeline. See [`AsyncPixelReadback`] docs for more info.
+
+use crate::{
+    core::math::Rect,
+    renderer::framework::{error::FrameworkError, framebuffer::FrameBuffer, state::PipelineState},
+};
+use glow::HasContext;
+use std::rc::Weak;
+
+/// Asynchronous pixel readback. Pixels are copied into a GPU-side pixel buffer first and fetched
+/// later, when the GPU finishes the copy. Use [`AsyncPixelReadback::is_ready`] to check if the
+/// pixels could be fetched without stalling.
+pub struct AsyncPixelReadback {
+    state: Weak<PipelineState>,
+    buffer: glow::Buffer,
+    fence: Option<glow::Fence>,
+    size: usize,
+}
+
+impl AsyncPixelReadback {
+    /// Starts reading pixels of the given color attachment of the frame buffer in RGBA8 format.
+    pub fn new(
+        state: &PipelineState,
+        frame_buffer: &FrameBuffer,
+        attachment_index: usize,
+        region: Rect<i32>,
+    ) -> Result<Self, FrameworkError> {
+        let size = (region.w().max(0) * region.h().max(0) * 4) as usize;
+
+        state.set_framebuffer(frame_buffer.id());
+
+        unsafe {
+            let buffer = state.gl.create_buffer().map_err(FrameworkError::Custom)?;
+            state.gl.bind_buffer(glow::PIXEL_PACK_BUFFER, Some(buffer));
+            state
+                .gl
+                .buffer_data_size(glow::PIXEL_PACK_BUFFER, size as i32, glow::STREAM_READ);
+            if frame_buffer.id().is_some() {
+                state
+                    .gl
+                    .read_buffer(glow::COLOR_ATTACHMENT0 + attachment_index as u32);
+            }
+            state.gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+            state.gl.read_pixels(
+                region.x(),
+                region.y(),
+                region.w(),
+                region.h(),
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelPackData::BufferOffset(0),
+            );
+            state.gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+
+            let fence = state
+                .gl
+                .fence_sync(glow::SYNC_GPU_COMMANDS_COMPLETE, 0)
+                .map_err(FrameworkError::Custom)?;
+
+            Ok(Self {
+                state: state.weak(),
+                buffer,
+                fence: Some(fence),
+                size,
+            })
+        }
+    }
+
+    /// Returns `true` if the GPU finished copying the pixels, `false` - otherwise.
+    pub fn is_ready(&self, state: &PipelineState) -> bool {
+        self.fence.map_or(true, |fence| unsafe {
+            state.gl.get_sync_status(fence) == glow::SIGNALED
+        })
+    }
+
+    /// Fetches the pixels in RGBA8 format. Rows are stored from bottom to top. This method stalls
+    /// until the pixels are available, use [`Self::is_ready`] to prevent this.
+    pub fn fetch(mut self, state: &PipelineState) -> Vec<u8> {
+        let mut pixels = vec![0u8; self.size];
+        unsafe {
+            if let Some(fence) = self.fence.take() {
+                state.gl.delete_sync(fence);
+            }
+            state
+                .gl
+                .bind_buffer(glow::PIXEL_PACK_BUFFER, Some(self.buffer));
+            state
+                .gl
+                .get_buffer_sub_data(glow::PIXEL_PACK_BUFFER, 0, &mut pixels);
+            state.gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+        }
+        pixels
+    }
+}
+
+impl Drop for AsyncPixelReadback {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.upgrade() {
+            unsafe {
+                if let Some(fence) = self.fence.take() {
+                    state.gl.delete_sync(fence);
+                }
+                state.gl.delete_buffer(self.buffer);
+            }
+        }
+    }
+}
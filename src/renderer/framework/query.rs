@@ -0,0 +1,81 @@
+//! GPU queries allow to fetch information about rendering commands from the GPU asynchronously,
+//! without stalling the pipeline.
+
+use crate::renderer::framework::{error::FrameworkError, state::PipelineState};
+use glow::HasContext;
+use std::rc::Weak;
+
+/// A kind of information that will be gathered by a query.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[repr(u32)]
+pub enum QueryKind {
+    /// Amount of time (in nanoseconds) that the GPU spent on executing the commands between
+    /// [`Query::begin`] and [`Query::end`].
+    TimeElapsed = glow::TIME_ELAPSED,
+    /// Amount of samples that passed depth test.
+    SamplesPassed = glow::SAMPLES_PASSED,
+}
+
+/// GPU query. Only one query of a kind could be active at a time. The result of a query is available
+/// after a few frames, use [`Query::is_available`] to check if the result could be fetched without
+/// stalling.
+pub struct Query {
+    state: Weak<PipelineState>,
+    id: glow::Query,
+}
+
+impl Query {
+    /// Creates a new query.
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        unsafe {
+            Ok(Self {
+                state: state.weak(),
+                id: state.gl.create_query().map_err(FrameworkError::Custom)?,
+            })
+        }
+    }
+
+    /// Starts gathering information of the given kind.
+    pub fn begin(&self, state: &PipelineState, kind: QueryKind) {
+        unsafe {
+            state.gl.begin_query(kind as u32, self.id);
+        }
+    }
+
+    /// Stops gathering information of the given kind.
+    pub fn end(&self, state: &PipelineState, kind: QueryKind) {
+        unsafe {
+            state.gl.end_query(kind as u32);
+        }
+    }
+
+    /// Returns `true` if the result of the query is available, `false` - otherwise.
+    pub fn is_available(&self, state: &PipelineState) -> bool {
+        unsafe {
+            state
+                .gl
+                .get_query_parameter_u32(self.id, glow::QUERY_RESULT_AVAILABLE)
+                != 0
+        }
+    }
+
+    /// Returns the result of the query. This method stalls until the result is available, use
+    /// [`Self::is_available`] to prevent this.
+    pub fn result(&self, state: &PipelineState) -> u32 {
+        unsafe {
+            state
+                .gl
+                .get_query_parameter_u32(self.id, glow::QUERY_RESULT)
+        }
+    }
+}
+
+impl Drop for Query {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.upgrade() {
+            unsafe {
+                state.gl.delete_query(self.id);
+            }
+        }
+    }
+}
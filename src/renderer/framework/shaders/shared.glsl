@@ -351,6 +351,90 @@ mat4 S_FetchMatrix(in sampler2D storage, int index) {
     return mat4(col1, col2, col3, col4);
 }
 
+vec4 S_QuaternionFromMatrix(mat3 m) {
+    float trace = m[0][0] + m[1][1] + m[2][2];
+    if (trace > 0.0) {
+        float s = 0.5 / sqrt(trace + 1.0);
+        return vec4((m[1][2] - m[2][1]) * s, (m[2][0] - m[0][2]) * s, (m[0][1] - m[1][0]) * s, 0.25 / s);
+    } else if (m[0][0] > m[1][1] && m[0][0] > m[2][2]) {
+        float s = 2.0 * sqrt(1.0 + m[0][0] - m[1][1] - m[2][2]);
+        return vec4(0.25 * s, (m[1][0] + m[0][1]) / s, (m[2][0] + m[0][2]) / s, (m[1][2] - m[2][1]) / s);
+    } else if (m[1][1] > m[2][2]) {
+        float s = 2.0 * sqrt(1.0 + m[1][1] - m[0][0] - m[2][2]);
+        return vec4((m[1][0] + m[0][1]) / s, 0.25 * s, (m[2][1] + m[1][2]) / s, (m[2][0] - m[0][2]) / s);
+    } else {
+        float s = 2.0 * sqrt(1.0 + m[2][2] - m[0][0] - m[1][1]);
+        return vec4((m[2][0] + m[0][2]) / s, (m[2][1] + m[1][2]) / s, 0.25 * s, (m[0][1] - m[1][0]) / s);
+    }
+}
+
+mat3 S_QuaternionToMatrix(vec4 q) {
+    float xx = q.x * q.x;
+    float yy = q.y * q.y;
+    float zz = q.z * q.z;
+    float xy = q.x * q.y;
+    float xz = q.x * q.z;
+    float yz = q.y * q.z;
+    float wx = q.w * q.x;
+    float wy = q.w * q.y;
+    float wz = q.w * q.z;
+    return mat3(
+        1.0 - 2.0 * (yy + zz), 2.0 * (xy + wz), 2.0 * (xz - wy),
+        2.0 * (xy - wz), 1.0 - 2.0 * (xx + zz), 2.0 * (yz + wx),
+        2.0 * (xz + wy), 2.0 * (yz - wx), 1.0 - 2.0 * (xx + yy)
+    );
+}
+
+vec4 S_QuaternionMultiply(vec4 a, vec4 b) {
+    return vec4(a.w * b.xyz + b.w * a.xyz + cross(a.xyz, b.xyz), a.w * b.w - dot(a.xyz, b.xyz));
+}
+
+// Blends four bone matrices as dual quaternions and returns a resulting matrix. Bone matrices could
+// contain only rotation, translation and uniform scale.
+mat4 S_DualQuaternionBlend(mat4 m0, mat4 m1, mat4 m2, mat4 m3, vec4 weights) {
+    mat4 matrices[4] = mat4[4](m0, m1, m2, m3);
+
+    vec4 blendedReal = vec4(0.0);
+    vec4 blendedDual = vec4(0.0);
+    float blendedScale = 0.0;
+    vec4 pivot = vec4(0.0);
+
+    for (int i = 0; i < 4; ++i) {
+        mat4 m = matrices[i];
+        float scale = length(m[0].xyz);
+        mat3 rotation = mat3(m[0].xyz / scale, normalize(m[1].xyz), normalize(m[2].xyz));
+
+        vec4 real = S_QuaternionFromMatrix(rotation);
+        vec4 dual = 0.5 * S_QuaternionMultiply(vec4(m[3].xyz, 0.0), real);
+
+        // Make sure that every quaternion is in the same hemisphere to take the shortest path.
+        if (i == 0) {
+            pivot = real;
+        } else if (dot(pivot, real) < 0.0) {
+            real = -real;
+            dual = -dual;
+        }
+
+        blendedReal += real * weights[i];
+        blendedDual += dual * weights[i];
+        blendedScale += scale * weights[i];
+    }
+
+    float len = length(blendedReal);
+    blendedReal /= len;
+    blendedDual /= len;
+
+    vec3 translation = 2.0 * S_QuaternionMultiply(blendedDual, vec4(-blendedReal.xyz, blendedReal.w)).xyz;
+    mat3 rotation = S_QuaternionToMatrix(blendedReal) * blendedScale;
+
+    return mat4(
+        vec4(rotation[0], 0.0),
+        vec4(rotation[1], 0.0),
+        vec4(rotation[2], 0.0),
+        vec4(translation, 1.0)
+    );
+}
+
 struct TBlendShapeOffsets {
     vec3 position;
     vec3 normal;
@@ -364,4 +448,12 @@ TBlendShapeOffsets S_FetchBlendShapeOffsets(in sampler3D storage, int vertexInde
     vec3 normal = texelFetch(storage, ivec3(pos.x + 1, pos.y, pos.z), 0).xyz;
     vec3 tangent = texelFetch(storage, ivec3(pos.x + 2, pos.y, pos.z), 0).xyz;
     return TBlendShapeOffsets(position, normal, tangent);
-}
\ No newline at end of file
+}
+// Calculates screen-space velocity (in texture coordinates) of a fragment using its clip-space positions
+// in the current and the previous frames. Sub-pixel jitter of the current projection is removed, so static
+// objects have zero velocity.
+vec2 S_Velocity(vec4 clipPosition, vec4 previousClipPosition, vec2 projectionJitter) {
+    vec2 current = clipPosition.xy / clipPosition.w - projectionJitter;
+    vec2 previous = previousClipPosition.xy / previousClipPosition.w;
+    return 0.5 * (current - previous);
+}
@@ -233,6 +233,7 @@ struct InnerState {
     depth_test: bool,
     depth_write: bool,
     depth_func: CompareFunc,
+    depth_clamp: bool,
 
     color_write: ColorMask,
     stencil_test: bool,
@@ -264,6 +265,7 @@ struct InnerState {
 
     frame_statistics: PipelineStatistics,
     gl_kind: GlKind,
+    graphics_reset_status_fn: Option<GraphicsResetStatusFn>,
 }
 
 impl InnerState {
@@ -273,6 +275,7 @@ impl InnerState {
             depth_test: false,
             depth_write: true,
             depth_func: Default::default(),
+            depth_clamp: false,
             color_write: Default::default(),
             stencil_test: false,
             cull_face: CullFace::Back,
@@ -296,12 +299,18 @@ impl InnerState {
             frame_statistics: Default::default(),
             blend_equation: Default::default(),
             gl_kind,
+            graphics_reset_status_fn: None,
         }
     }
 }
 
 pub type SharedPipelineState = Rc<PipelineState>;
 
+/// Pointer to `glGetGraphicsResetStatus` function (or to one of its extension variants). It is
+/// available only for robust contexts, that were created with "lose context on reset" notification
+/// strategy.
+pub type GraphicsResetStatusFn = unsafe extern "system" fn() -> u32;
+
 pub struct PipelineState {
     pub gl: glow::Context,
     state: RefCell<InnerState>,
@@ -600,6 +609,27 @@ impl PipelineState {
         self.state.borrow().gl_kind
     }
 
+    /// Sets a function, that will be used to query reset status of the context. It must be set only
+    /// for robust contexts with "lose context on reset" notification strategy.
+    pub fn set_graphics_reset_status_fn(&self, func: Option<GraphicsResetStatusFn>) {
+        self.state.borrow_mut().graphics_reset_status_fn = func;
+    }
+
+    /// Checks whether the GL context was lost (for example because of a driver reset or because
+    /// the OS has reclaimed GPU resources of the app). Robust contexts are checked using their
+    /// reset status, for other contexts this method consumes the current error flag of the context.
+    pub fn is_context_lost(&self) -> bool {
+        let reset_status = self
+            .state
+            .borrow()
+            .graphics_reset_status_fn
+            .map(|func| unsafe { func() });
+        match reset_status {
+            Some(reset_status) => is_context_lost(Some(reset_status), glow::NO_ERROR),
+            None => is_context_lost(None, unsafe { self.gl.get_error() }),
+        }
+    }
+
     pub fn set_polygon_fill_mode(
         &self,
         polygon_face: PolygonFace,
@@ -690,6 +720,34 @@ impl PipelineState {
         }
     }
 
+    /// Returns `true` if the current context supports depth clamping (see [`Self::set_depth_clamp`]).
+    pub fn is_depth_clamp_supported(&self) -> bool {
+        self.gl_kind() == GlKind::OpenGL
+    }
+
+    /// Enables or disables depth clamping. When enabled, primitives are not clipped by near and far
+    /// planes, instead their depth is clamped to the depth range. It is used to "pancake" shadow
+    /// casters, that are located in front of the near plane of a shadow map. Does nothing if the
+    /// context does not support depth clamping.
+    pub fn set_depth_clamp(&self, depth_clamp: bool) {
+        if !self.is_depth_clamp_supported() {
+            return;
+        }
+
+        let mut state = self.state.borrow_mut();
+        if state.depth_clamp != depth_clamp {
+            state.depth_clamp = depth_clamp;
+
+            unsafe {
+                if state.depth_clamp {
+                    self.gl.enable(glow::DEPTH_CLAMP);
+                } else {
+                    self.gl.disable(glow::DEPTH_CLAMP);
+                }
+            }
+        }
+    }
+
     pub fn set_color_write(&self, color_write: ColorMask) {
         let mut state = self.state.borrow_mut();
         if state.color_write != color_write {
@@ -979,6 +1037,9 @@ impl PipelineState {
                 mask,
                 glow::NEAREST,
             );
+            // Restore the binding, otherwise cached state will be out of sync.
+            self.gl
+                .bind_framebuffer(glow::FRAMEBUFFER, self.state.borrow().framebuffer);
         }
     }
 
@@ -1028,3 +1089,41 @@ impl PipelineState {
         self.state.borrow().frame_statistics
     }
 }
+
+fn is_context_lost(reset_status: Option<u32>, error: u32) -> bool {
+    // WebGL reports context loss using its own error code.
+    const CONTEXT_LOST_WEBGL: u32 = 0x9242;
+
+    match reset_status {
+        Some(reset_status) => reset_status != glow::NO_ERROR,
+        None => error == glow::CONTEXT_LOST || error == CONTEXT_LOST_WEBGL,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::is_context_lost;
+
+    #[test]
+    fn test_is_context_lost() {
+        // Reset status has priority over the error flag.
+        assert!(!is_context_lost(Some(glow::NO_ERROR), glow::CONTEXT_LOST));
+        assert!(is_context_lost(
+            Some(glow::GUILTY_CONTEXT_RESET),
+            glow::NO_ERROR
+        ));
+        assert!(is_context_lost(
+            Some(glow::INNOCENT_CONTEXT_RESET),
+            glow::NO_ERROR
+        ));
+        assert!(is_context_lost(
+            Some(glow::UNKNOWN_CONTEXT_RESET),
+            glow::NO_ERROR
+        ));
+
+        assert!(!is_context_lost(None, glow::NO_ERROR));
+        assert!(!is_context_lost(None, glow::INVALID_OPERATION));
+        assert!(is_context_lost(None, glow::CONTEXT_LOST));
+        assert!(is_context_lost(None, 0x9242));
+    }
+}
@@ -5,6 +5,7 @@
 //! RT2: RGBA16F - Ambient light + emission (both in xyz)
 //! RT3: RGBA8 - Metallic (x) + Roughness (y) + Ambient Occlusion (z)
 //! RT4: R8UI - Decal mask (x)
+//! RT5: RG16F - Screen-space velocity (xy)
 //!
 //! Every alpha channel is used for layer blending for terrains. This is inefficient, but for
 //! now I don't know better solution.
@@ -14,12 +15,13 @@ use crate::{
         algebra::{Matrix4, Vector2},
         color::Color,
         math::Rect,
+        pool::Handle,
         scope_profile,
         sstorage::ImmutableString,
     },
     renderer::{
         apply_material,
-        batch::RenderDataBatchStorage,
+        batch::{RenderDataBatchStorage, SurfaceInstanceData},
         cache::shader::ShaderCache,
         framework::{
             error::FrameworkError,
@@ -27,7 +29,7 @@ use crate::{
                 Attachment, AttachmentKind, BlendParameters, DrawParameters, FrameBuffer,
             },
             geometry_buffer::{ElementRange, GeometryBuffer, GeometryBufferKind},
-            gpu_program::GpuProgramBinding,
+            gpu_program::{BuiltInUniform, GpuProgramBinding},
             gpu_texture::{
                 Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
                 PixelKind, WrapMode,
@@ -36,20 +38,88 @@ use crate::{
         },
         gbuffer::decal::DecalShader,
         storage::MatrixStorageCache,
-        GeometryCache, MaterialContext, RenderPassStatistics, TextureCache,
+        GeometryCache, MaterialContext, RenderPassStatistics, TextureCache, VelocityData,
     },
     scene::{
         camera::Camera,
         decal::Decal,
         graph::Graph,
         mesh::{surface::SurfaceData, RenderPath},
+        node::Node,
     },
 };
+use fxhash::FxHashMap;
 use fyrox_core::math::Matrix4Ext;
 use std::{cell::RefCell, rc::Rc};
 
 mod decal;
 
+/// World matrices of the nodes and view-projection matrices (without jitter) of the cameras, that were
+/// used to render the previous frame. They're used to calculate per-pixel velocity.
+#[derive(Default)]
+pub struct MotionHistory {
+    frame_index: Option<u32>,
+    world_matrices: FxHashMap<Handle<Node>, Matrix4<f32>>,
+    previous_world_matrices: FxHashMap<Handle<Node>, Matrix4<f32>>,
+    view_projections: FxHashMap<Handle<Node>, Matrix4<f32>>,
+}
+
+impl MotionHistory {
+    /// Makes the matrices of the last rendered frame "previous". Does nothing if the frame with the
+    /// given index has already begun, so the scene could be rendered by multiple cameras.
+    pub fn begin_frame(&mut self, frame_index: u32) {
+        if self.frame_index != Some(frame_index) {
+            self.frame_index = Some(frame_index);
+            std::mem::swap(&mut self.world_matrices, &mut self.previous_world_matrices);
+            self.world_matrices.clear();
+        }
+    }
+
+    /// Returns world matrices of the nodes in the previous frame.
+    pub fn previous_world_matrices(&self) -> &FxHashMap<Handle<Node>, Matrix4<f32>> {
+        &self.previous_world_matrices
+    }
+
+    /// Returns view-projection matrix of the given camera in the previous frame. Current matrix is
+    /// returned if the camera wasn't rendered yet.
+    pub fn previous_view_projection(
+        &self,
+        camera: Handle<Node>,
+        current: Matrix4<f32>,
+    ) -> Matrix4<f32> {
+        self.view_projections
+            .get(&camera)
+            .cloned()
+            .unwrap_or(current)
+    }
+
+    /// Remembers the matrices of the current frame of the given camera.
+    pub fn store(
+        &mut self,
+        camera: Handle<Node>,
+        view_projection: Matrix4<f32>,
+        batch_storage: &RenderDataBatchStorage,
+    ) {
+        self.view_projections.insert(camera, view_projection);
+        for instance in batch_storage
+            .batches
+            .iter()
+            .flat_map(|batch| batch.instances.iter())
+        {
+            self.world_matrices
+                .insert(instance.node_handle, instance.world_transform);
+        }
+    }
+
+    /// Removes the matrices of the cameras, that do not satisfy the given predicate.
+    pub fn retain_cameras<F>(&mut self, mut func: F)
+    where
+        F: FnMut(Handle<Node>) -> bool,
+    {
+        self.view_projections.retain(|camera, _| func(*camera));
+    }
+}
+
 pub struct GBuffer {
     framebuffer: FrameBuffer,
     decal_framebuffer: FrameBuffer,
@@ -58,6 +128,8 @@ pub struct GBuffer {
     cube: GeometryBuffer,
     decal_shader: DecalShader,
     render_pass_name: ImmutableString,
+    instance_matrices: Vec<Matrix4<f32>>,
+    instance_bone_matrices: Vec<Matrix4<f32>>,
 }
 
 pub(crate) struct GBufferRenderContext<'a, 'b> {
@@ -76,6 +148,10 @@ pub(crate) struct GBufferRenderContext<'a, 'b> {
     pub use_parallax_mapping: bool,
     pub graph: &'b Graph,
     pub matrix_storage: &'a mut MatrixStorageCache,
+    /// View-projection matrix of the camera in the previous frame (without jitter).
+    pub previous_view_projection: Matrix4<f32>,
+    /// World matrices of the nodes in the previous frame.
+    pub previous_world_matrices: &'b FxHashMap<Handle<Node>, Matrix4<f32>>,
 }
 
 impl GBuffer {
@@ -170,6 +246,22 @@ impl GBuffer {
             .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
             .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
 
+        // Screen-space velocity of each pixel (in texture coordinates), it is used by temporal
+        // anti-aliasing to find a pixel in the previous frame.
+        let mut velocity_texture = GpuTexture::new(
+            state,
+            GpuTextureKind::Rectangle { width, height },
+            PixelKind::RG16F,
+            MinificationFilter::Nearest,
+            MagnificationFilter::Nearest,
+            1,
+            None,
+        )?;
+        velocity_texture
+            .bind_mut(state, 0)
+            .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
+            .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
+
         let framebuffer = FrameBuffer::new(
             state,
             Some(Attachment {
@@ -197,6 +289,10 @@ impl GBuffer {
                     kind: AttachmentKind::Color,
                     texture: Rc::new(RefCell::new(decal_mask_texture)),
                 },
+                Attachment {
+                    kind: AttachmentKind::Color,
+                    texture: Rc::new(RefCell::new(velocity_texture)),
+                },
             ],
         )?;
 
@@ -227,6 +323,8 @@ impl GBuffer {
             )?,
             decal_framebuffer,
             render_pass_name: ImmutableString::new("GBuffer"),
+            instance_matrices: Default::default(),
+            instance_bone_matrices: Default::default(),
         })
     }
 
@@ -258,6 +356,10 @@ impl GBuffer {
         self.framebuffer.color_attachments()[4].texture.clone()
     }
 
+    pub fn velocity_texture(&self) -> Rc<RefCell<GpuTexture>> {
+        self.framebuffer.color_attachments()[5].texture.clone()
+    }
+
     pub(crate) fn fill(
         &mut self,
         args: GBufferRenderContext,
@@ -280,6 +382,8 @@ impl GBuffer {
             volume_dummy,
             graph,
             matrix_storage,
+            previous_view_projection,
+            previous_world_matrices,
             ..
         } = args;
 
@@ -299,6 +403,8 @@ impl GBuffer {
         let camera_up = inv_view.up();
         let camera_side = inv_view.side();
 
+        let projection_jitter = camera.projection_jitter_ndc();
+
         for batch in batch_storage
             .batches
             .iter()
@@ -328,7 +434,98 @@ impl GBuffer {
                 continue;
             };
 
-            for instance in batch.instances.iter() {
+            // Merge instanceable surfaces in a single draw call, if the shader supports it. Skinned
+            // surfaces additionally require the shader to offset bone indices for each instance.
+            let locations = &render_pass.program.built_in_uniform_locations;
+            let first_instance = batch
+                .instances
+                .iter()
+                .find(|instance| instance.is_instanceable());
+            let can_be_merged = |instance: &SurfaceInstanceData| {
+                instance.is_instanceable()
+                    && first_instance.is_some_and(|first| {
+                        first.bone_matrices.len() == instance.bone_matrices.len()
+                            && first.use_dual_quaternion_skinning
+                                == instance.use_dual_quaternion_skinning
+                    })
+            };
+            self.instance_matrices.clear();
+            self.instance_bone_matrices.clear();
+            if locations[BuiltInUniform::UseInstancing as usize].is_some()
+                && (!batch.is_skinned
+                    || locations[BuiltInUniform::InstanceBoneCount as usize].is_some())
+            {
+                for instance in batch.instances.iter().filter(|i| can_be_merged(i)) {
+                    self.instance_matrices.push(instance.world_transform);
+                    self.instance_bone_matrices
+                        .extend_from_slice(&instance.bone_matrices);
+                }
+            }
+            // There's no point to use instancing for a single instance.
+            let use_instancing = self.instance_matrices.len() > 1;
+
+            if use_instancing {
+                let first_instance = first_instance.unwrap();
+
+                let velocity_data = VelocityData {
+                    previous_wvp_matrix: previous_view_projection,
+                    previous_view_projection_matrix: previous_view_projection,
+                    projection_jitter,
+                };
+
+                let apply_uniforms = |mut program_binding: GpuProgramBinding| {
+                    apply_material(MaterialContext {
+                        material,
+                        program_binding: &mut program_binding,
+                        texture_cache,
+                        matrix_storage,
+                        world_matrix: &Matrix4::identity(),
+                        view_projection_matrix: &initial_view_projection,
+                        wvp_matrix: &initial_view_projection,
+                        bone_matrices: &self.instance_bone_matrices,
+                        use_skeletal_animation: batch.is_skinned,
+                        use_dual_quaternion_skinning: first_instance.use_dual_quaternion_skinning,
+                        instance_matrices: &self.instance_matrices,
+                        instance_bone_count: first_instance.bone_matrices.len(),
+                        property_overrides: &[],
+                        camera_position: &camera.global_position(),
+                        camera_up_vector: &camera_up,
+                        camera_side_vector: &camera_side,
+                        z_near: camera.projection().z_near(),
+                        use_pom: use_parallax_mapping,
+                        light_position: &Default::default(),
+                        blend_shapes_storage: None,
+                        blend_shapes_weights: &[],
+                        normal_dummy: &normal_dummy,
+                        white_dummy: &white_dummy,
+                        black_dummy: &black_dummy,
+                        volume_dummy: &volume_dummy,
+                        persistent_identifier: first_instance.persistent_identifier,
+                        light_data: None,
+                        velocity_data: Some(&velocity_data),
+                        ambient_light: Color::WHITE, // TODO
+                        scene_depth: None,           // TODO. Add z-pre-pass.
+                        scene_color: None,
+                        z_far: camera.projection().z_far(),
+                    });
+                };
+
+                statistics += self.framebuffer.draw_instances(
+                    self.instance_matrices.len(),
+                    geometry,
+                    state,
+                    viewport,
+                    &render_pass.program,
+                    &render_pass.draw_params,
+                    apply_uniforms,
+                );
+            }
+
+            for instance in batch
+                .instances
+                .iter()
+                .filter(|instance| !use_instancing || !can_be_merged(instance))
+            {
                 let apply_uniforms = |mut program_binding: GpuProgramBinding| {
                     let view_projection = if instance.depth_offset != 0.0 {
                         let mut projection = camera.projection_matrix();
@@ -338,6 +535,16 @@ impl GBuffer {
                         initial_view_projection
                     };
 
+                    // Nodes, that weren't rendered in the previous frame, are considered static.
+                    let previous_world_matrix = previous_world_matrices
+                        .get(&instance.node_handle)
+                        .unwrap_or(&instance.world_transform);
+                    let velocity_data = VelocityData {
+                        previous_wvp_matrix: previous_view_projection * previous_world_matrix,
+                        previous_view_projection_matrix: previous_view_projection,
+                        projection_jitter,
+                    };
+
                     apply_material(MaterialContext {
                         material,
                         program_binding: &mut program_binding,
@@ -348,6 +555,10 @@ impl GBuffer {
                         wvp_matrix: &(view_projection * instance.world_transform),
                         bone_matrices: &instance.bone_matrices,
                         use_skeletal_animation: batch.is_skinned,
+                        use_dual_quaternion_skinning: instance.use_dual_quaternion_skinning,
+                        instance_matrices: &[],
+                        instance_bone_count: 0,
+                        property_overrides: instance.property_overrides(),
                         camera_position: &camera.global_position(),
                         camera_up_vector: &camera_up,
                         camera_side_vector: &camera_side,
@@ -362,8 +573,10 @@ impl GBuffer {
                         volume_dummy: &volume_dummy,
                         persistent_identifier: instance.persistent_identifier,
                         light_data: None,
+                        velocity_data: Some(&velocity_data),
                         ambient_light: Color::WHITE, // TODO
                         scene_depth: None,           // TODO. Add z-pre-pass.
+                        scene_color: None,
                         z_far: camera.projection().z_far(),
                     });
                 };
@@ -395,6 +608,12 @@ impl GBuffer {
 
             let world_view_proj = initial_view_projection * decal.global_transform();
 
+            let mut color = decal.color();
+            color.a = (color.a as f32 * decal.opacity()) as u8;
+            if color.a == 0 {
+                continue;
+            }
+
             statistics += self.decal_framebuffer.draw(
                 unit_cube,
                 state,
@@ -439,7 +658,7 @@ impl GBuffer {
                         )
                         .set_texture(&shader.decal_mask, &decal_mask)
                         .set_u32(&shader.layer_index, decal.layer() as u32)
-                        .set_linear_color(&shader.color, &decal.color());
+                        .set_linear_color(&shader.color, &color);
                 },
             )?;
         }
@@ -447,3 +666,75 @@ impl GBuffer {
         Ok(statistics)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::{
+            algebra::{Matrix4, Vector3},
+            math::TriangleDefinition,
+            pool::Handle,
+        },
+        material::{Material, MaterialResource},
+        renderer::{batch::RenderDataBatchStorage, gbuffer::MotionHistory},
+        scene::mesh::{vertex::StaticVertex, RenderPath},
+    };
+
+    #[test]
+    fn test_motion_history() {
+        let camera = Handle::new(1, 1);
+        let node = Handle::new(2, 1);
+        let material = MaterialResource::new_ok(ResourceKind::Embedded, Material::standard());
+
+        let mut storage = RenderDataBatchStorage::default();
+        storage.push_triangles(
+            [StaticVertex::default(); 3].into_iter(),
+            [TriangleDefinition([0, 1, 2])].into_iter(),
+            &material,
+            RenderPath::Deferred,
+            0,
+            0,
+            false,
+            node,
+        );
+
+        let first = Matrix4::new_translation(&Vector3::new(1.0, 0.0, 0.0));
+        let second = Matrix4::new_translation(&Vector3::new(2.0, 0.0, 0.0));
+        let view_projection = Matrix4::new_scaling(2.0);
+
+        let mut history = MotionHistory::default();
+
+        history.begin_frame(0);
+        // Nothing was rendered yet, so the current matrix must be used.
+        assert_eq!(
+            history.previous_view_projection(camera, view_projection),
+            view_projection
+        );
+        assert!(history.previous_world_matrices().is_empty());
+        storage.batches[0].instances[0].world_transform = first;
+        history.store(camera, view_projection, &storage);
+
+        // Second camera in the same frame must see the same previous state.
+        history.begin_frame(0);
+        assert!(history.previous_world_matrices().is_empty());
+
+        history.begin_frame(1);
+        assert_eq!(
+            history.previous_view_projection(camera, Matrix4::identity()),
+            view_projection
+        );
+        assert_eq!(history.previous_world_matrices().get(&node), Some(&first));
+        storage.batches[0].instances[0].world_transform = second;
+        history.store(camera, Matrix4::identity(), &storage);
+
+        history.begin_frame(2);
+        assert_eq!(history.previous_world_matrices().get(&node), Some(&second));
+
+        history.retain_cameras(|_| false);
+        assert_eq!(
+            history.previous_view_projection(camera, view_projection),
+            view_projection
+        );
+    }
+}
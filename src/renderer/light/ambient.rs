@@ -12,6 +12,15 @@ pub struct AmbientLightShader {
     pub ambient_color: UniformLocation,
     pub ao_sampler: UniformLocation,
     pub ambient_texture: UniformLocation,
+    pub depth_texture: UniformLocation,
+    pub normal_texture: UniformLocation,
+    pub material_texture: UniformLocation,
+    pub irradiance_map: UniformLocation,
+    pub prefiltered_map: UniformLocation,
+    pub inv_view_proj_matrix: UniformLocation,
+    pub camera_position: UniformLocation,
+    pub use_ibl: UniformLocation,
+    pub max_reflection_lod: UniformLocation,
 }
 
 impl AmbientLightShader {
@@ -30,6 +39,23 @@ impl AmbientLightShader {
             ao_sampler: program.uniform_location(state, &ImmutableString::new("aoSampler"))?,
             ambient_texture: program
                 .uniform_location(state, &ImmutableString::new("ambientTexture"))?,
+            depth_texture: program
+                .uniform_location(state, &ImmutableString::new("depthTexture"))?,
+            normal_texture: program
+                .uniform_location(state, &ImmutableString::new("normalTexture"))?,
+            material_texture: program
+                .uniform_location(state, &ImmutableString::new("materialTexture"))?,
+            irradiance_map: program
+                .uniform_location(state, &ImmutableString::new("irradianceMap"))?,
+            prefiltered_map: program
+                .uniform_location(state, &ImmutableString::new("prefilteredMap"))?,
+            inv_view_proj_matrix: program
+                .uniform_location(state, &ImmutableString::new("invViewProj"))?,
+            camera_position: program
+                .uniform_location(state, &ImmutableString::new("cameraPosition"))?,
+            use_ibl: program.uniform_location(state, &ImmutableString::new("useIbl"))?,
+            max_reflection_lod: program
+                .uniform_location(state, &ImmutableString::new("maxReflectionLod"))?,
             program,
         })
     }
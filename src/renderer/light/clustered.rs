@@ -0,0 +1,421 @@
+//! Clustered light culling. View frustum of a camera is split into a 3D grid of clusters (screen-space
+//! tiles along X and Y, exponential slices along depth) and every light is assigned to the clusters
+//! it intersects. Then all the lights are rendered in a single full screen pass, where each pixel
+//! iterates only over the lights of its cluster. It is much faster than drawing a light volume for
+//! every light, when there are hundreds of small lights on screen.
+//!
+//! Cluster data is stored in textures instead of SSBO, see [`crate::renderer::storage::MatrixStorage`]
+//! for the reasons.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector3, Vector4},
+        sstorage::ImmutableString,
+    },
+    renderer::framework::{
+        error::FrameworkError,
+        gpu_program::{GpuProgram, UniformLocation},
+        gpu_texture::{
+            GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter, PixelKind,
+        },
+        state::PipelineState,
+    },
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// Amount of clusters along X axis of the screen.
+pub const CLUSTERS_X: usize = 16;
+/// Amount of clusters along Y axis of the screen.
+pub const CLUSTERS_Y: usize = 9;
+/// Amount of depth slices.
+pub const CLUSTERS_Z: usize = 24;
+
+// Must be in sync with the shader.
+const TEXTURE_WIDTH: usize = 1024;
+
+/// A light, that could be rendered using clustered lighting.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClusteredLight {
+    /// World-space position of the light.
+    pub position: Vector3<f32>,
+    /// Radius of the light.
+    pub radius: f32,
+    /// Linear color of the light multiplied by its intensity.
+    pub color: Vector3<f32>,
+}
+
+/// CPU-side representation of the light clusters.
+#[derive(Default, Debug)]
+pub struct LightClusters {
+    /// Offset in the index list and amount of lights for each cluster in X -> Y -> Z order. Z and W
+    /// components are unused.
+    pub clusters: Vec<Vector4<f32>>,
+    /// Indices of lights for every cluster. Indices are stored as floats to be able to put them
+    /// in a float texture, they're exact up to 2^24.
+    pub indices: Vec<f32>,
+}
+
+fn depth_slice(depth: f32, z_near: f32, z_far: f32) -> usize {
+    let slice = (depth.max(z_near) / z_near).ln() / (z_far / z_near).ln() * CLUSTERS_Z as f32;
+    (slice.max(0.0) as usize).min(CLUSTERS_Z - 1)
+}
+
+fn screen_tile(ndc: f32, count: usize) -> usize {
+    (((ndc * 0.5 + 0.5) * count as f32).max(0.0) as usize).min(count - 1)
+}
+
+fn cluster_index(x: usize, y: usize, z: usize) -> usize {
+    (z * CLUSTERS_Y + y) * CLUSTERS_X + x
+}
+
+fn for_each_cluster<F: FnMut(usize)>((min, max): &([usize; 3], [usize; 3]), mut func: F) {
+    for z in min[2]..=max[2] {
+        for y in min[1]..=max[1] {
+            for x in min[0]..=max[0] {
+                func(cluster_index(x, y, z))
+            }
+        }
+    }
+}
+
+impl LightClusters {
+    /// Calculates a range of clusters, that is intersected by the given light. Returns `None` if the
+    /// light is outside of the view frustum.
+    fn light_bounds(
+        light: &ClusteredLight,
+        view_matrix: &Matrix4<f32>,
+        projection_matrix: &Matrix4<f32>,
+        z_near: f32,
+        z_far: f32,
+    ) -> Option<([usize; 3], [usize; 3])> {
+        let center = view_matrix.transform_point(&Point3::from(light.position));
+        let r = light.radius;
+
+        // Camera looks along -Z in view space.
+        let min_depth = -center.z - r;
+        let max_depth = -center.z + r;
+        if max_depth < z_near || min_depth > z_far {
+            return None;
+        }
+
+        // Project corners of the bounding box of the light. Corners behind the near plane are moved
+        // to it, which gives conservative bounds for a part of the box in front of the camera.
+        let mut min = Vector3::repeat(f32::MAX);
+        let mut max = Vector3::repeat(-f32::MAX);
+        for i in 0..8 {
+            let corner = Vector3::new(
+                if i & 1 == 0 {
+                    center.x - r
+                } else {
+                    center.x + r
+                },
+                if i & 2 == 0 {
+                    center.y - r
+                } else {
+                    center.y + r
+                },
+                (if i & 4 == 0 {
+                    center.z - r
+                } else {
+                    center.z + r
+                })
+                .min(-z_near),
+            );
+            let clip = projection_matrix * corner.push(1.0);
+            if clip.w <= f32::EPSILON {
+                return None;
+            }
+            let ndc = clip.xyz() / clip.w;
+            min = min.inf(&ndc);
+            max = max.sup(&ndc);
+        }
+        if max.x < -1.0 || min.x > 1.0 || max.y < -1.0 || min.y > 1.0 {
+            return None;
+        }
+
+        Some((
+            [
+                screen_tile(min.x, CLUSTERS_X),
+                screen_tile(min.y, CLUSTERS_Y),
+                depth_slice(min_depth, z_near, z_far),
+            ],
+            [
+                screen_tile(max.x, CLUSTERS_X),
+                screen_tile(max.y, CLUSTERS_Y),
+                depth_slice(max_depth, z_near, z_far),
+            ],
+        ))
+    }
+
+    /// Assigns every light to the clusters it intersects. Previous content is discarded.
+    pub fn build(
+        &mut self,
+        lights: &[ClusteredLight],
+        view_matrix: &Matrix4<f32>,
+        projection_matrix: &Matrix4<f32>,
+        z_near: f32,
+        z_far: f32,
+    ) {
+        let z_near = z_near.max(0.001);
+        let z_far = z_far.max(z_near * 2.0);
+
+        let bounds = lights
+            .iter()
+            .map(|light| Self::light_bounds(light, view_matrix, projection_matrix, z_near, z_far))
+            .collect::<Vec<_>>();
+
+        // Count lights in every cluster first, then calculate offsets and fill the index list.
+        let mut counts = vec![0usize; CLUSTERS_X * CLUSTERS_Y * CLUSTERS_Z];
+        for light_bounds in bounds.iter().flatten() {
+            for_each_cluster(light_bounds, |i| counts[i] += 1);
+        }
+
+        self.clusters.clear();
+        let mut offsets = Vec::with_capacity(counts.len());
+        let mut total = 0;
+        for &count in counts.iter() {
+            self.clusters
+                .push(Vector4::new(total as f32, count as f32, 0.0, 0.0));
+            offsets.push(total);
+            total += count;
+        }
+
+        self.indices.clear();
+        self.indices.resize(total, 0.0);
+        for (light_index, light_bounds) in bounds.iter().enumerate() {
+            if let Some(light_bounds) = light_bounds {
+                for_each_cluster(light_bounds, |i| {
+                    self.indices[offsets[i]] = light_index as f32;
+                    offsets[i] += 1;
+                });
+            }
+        }
+    }
+}
+
+pub struct ClusteredLightShader {
+    pub program: GpuProgram,
+    pub wvp_matrix: UniformLocation,
+    pub depth_sampler: UniformLocation,
+    pub color_sampler: UniformLocation,
+    pub normal_sampler: UniformLocation,
+    pub material_sampler: UniformLocation,
+    pub lights_sampler: UniformLocation,
+    pub clusters_sampler: UniformLocation,
+    pub indices_sampler: UniformLocation,
+    pub inv_view_proj_matrix: UniformLocation,
+    pub view_matrix: UniformLocation,
+    pub camera_position: UniformLocation,
+    pub cluster_count: UniformLocation,
+    pub z_near: UniformLocation,
+    pub z_far: UniformLocation,
+}
+
+impl ClusteredLightShader {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("../shaders/deferred_clustered_light_fs.glsl");
+        let vertex_source = include_str!("../shaders/deferred_light_vs.glsl");
+        let program = GpuProgram::from_source(
+            state,
+            "ClusteredLightShader",
+            vertex_source,
+            fragment_source,
+        )?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            depth_sampler: program
+                .uniform_location(state, &ImmutableString::new("depthTexture"))?,
+            color_sampler: program
+                .uniform_location(state, &ImmutableString::new("colorTexture"))?,
+            normal_sampler: program
+                .uniform_location(state, &ImmutableString::new("normalTexture"))?,
+            material_sampler: program
+                .uniform_location(state, &ImmutableString::new("materialTexture"))?,
+            lights_sampler: program
+                .uniform_location(state, &ImmutableString::new("lightsTexture"))?,
+            clusters_sampler: program
+                .uniform_location(state, &ImmutableString::new("clustersTexture"))?,
+            indices_sampler: program
+                .uniform_location(state, &ImmutableString::new("indicesTexture"))?,
+            inv_view_proj_matrix: program
+                .uniform_location(state, &ImmutableString::new("invViewProj"))?,
+            view_matrix: program.uniform_location(state, &ImmutableString::new("viewMatrix"))?,
+            camera_position: program
+                .uniform_location(state, &ImmutableString::new("cameraPosition"))?,
+            cluster_count: program
+                .uniform_location(state, &ImmutableString::new("clusterCount"))?,
+            z_near: program.uniform_location(state, &ImmutableString::new("zNear"))?,
+            z_far: program.uniform_location(state, &ImmutableString::new("zFar"))?,
+            program,
+        })
+    }
+}
+
+fn make_texture(state: &PipelineState) -> Result<Rc<RefCell<GpuTexture>>, FrameworkError> {
+    Ok(Rc::new(RefCell::new(GpuTexture::new(
+        state,
+        GpuTextureKind::Rectangle {
+            width: 1,
+            height: 1,
+        },
+        PixelKind::RGBA32F,
+        MinificationFilter::Nearest,
+        MagnificationFilter::Nearest,
+        1,
+        Some(crate::core::array_as_u8_slice(&[Vector4::<f32>::zeros()])),
+    )?)))
+}
+
+/// Uploads the data to the texture. Texels are laid out row by row, each row is [`TEXTURE_WIDTH`]
+/// texels wide, the last row is padded with zeros.
+fn upload<T: Copy + Default>(
+    state: &PipelineState,
+    texture: &Rc<RefCell<GpuTexture>>,
+    pixel_kind: PixelKind,
+    data: &mut Vec<T>,
+) -> Result<(), FrameworkError> {
+    if data.is_empty() {
+        data.push(Default::default());
+    }
+    let width = data.len().min(TEXTURE_WIDTH);
+    let height = (data.len() + width - 1) / width;
+    data.resize(width * height, Default::default());
+    texture.borrow_mut().bind_mut(state, 0).set_data(
+        GpuTextureKind::Rectangle { width, height },
+        pixel_kind,
+        1,
+        Some(crate::core::array_as_u8_slice(data)),
+    )?;
+    Ok(())
+}
+
+pub struct ClusteredLightRenderer {
+    pub shader: ClusteredLightShader,
+    clusters: LightClusters,
+    light_data: Vec<Vector4<f32>>,
+    lights_texture: Rc<RefCell<GpuTexture>>,
+    clusters_texture: Rc<RefCell<GpuTexture>>,
+    indices_texture: Rc<RefCell<GpuTexture>>,
+}
+
+impl ClusteredLightRenderer {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader: ClusteredLightShader::new(state)?,
+            clusters: Default::default(),
+            light_data: Default::default(),
+            lights_texture: make_texture(state)?,
+            clusters_texture: make_texture(state)?,
+            indices_texture: make_texture(state)?,
+        })
+    }
+
+    /// Builds clusters for the given lights and uploads them to GPU.
+    pub fn prepare(
+        &mut self,
+        state: &PipelineState,
+        lights: &[ClusteredLight],
+        view_matrix: &Matrix4<f32>,
+        projection_matrix: &Matrix4<f32>,
+        z_near: f32,
+        z_far: f32,
+    ) -> Result<(), FrameworkError> {
+        self.clusters
+            .build(lights, view_matrix, projection_matrix, z_near, z_far);
+
+        // Two texels per light: position + radius and color.
+        self.light_data.clear();
+        for light in lights {
+            self.light_data.push(light.position.push(light.radius));
+            self.light_data.push(light.color.push(0.0));
+        }
+
+        upload(
+            state,
+            &self.lights_texture,
+            PixelKind::RGBA32F,
+            &mut self.light_data,
+        )?;
+        upload(
+            state,
+            &self.clusters_texture,
+            PixelKind::RGBA32F,
+            &mut self.clusters.clusters,
+        )?;
+        upload(
+            state,
+            &self.indices_texture,
+            PixelKind::R32F,
+            &mut self.clusters.indices,
+        )
+    }
+
+    pub fn lights_texture(&self) -> &Rc<RefCell<GpuTexture>> {
+        &self.lights_texture
+    }
+
+    pub fn clusters_texture(&self) -> &Rc<RefCell<GpuTexture>> {
+        &self.clusters_texture
+    }
+
+    pub fn indices_texture(&self) -> &Rc<RefCell<GpuTexture>> {
+        &self.indices_texture
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Matrix4, Vector3},
+        renderer::light::clustered::{
+            cluster_index, ClusteredLight, LightClusters, CLUSTERS_X, CLUSTERS_Y, CLUSTERS_Z,
+        },
+    };
+
+    #[test]
+    fn test_light_clusters() {
+        let view = Matrix4::identity();
+        let projection = Matrix4::new_perspective(16.0 / 9.0, 90.0f32.to_radians(), 0.1, 100.0);
+        let lights = [
+            // Small light in the center of the screen.
+            ClusteredLight {
+                position: Vector3::new(0.0, 0.0, -10.0),
+                radius: 0.1,
+                color: Vector3::repeat(1.0),
+            },
+            // Behind the camera.
+            ClusteredLight {
+                position: Vector3::new(0.0, 0.0, 10.0),
+                radius: 1.0,
+                color: Vector3::repeat(1.0),
+            },
+            // Huge light around the camera.
+            ClusteredLight {
+                position: Vector3::new(0.0, 0.0, 0.0),
+                radius: 1000.0,
+                color: Vector3::repeat(1.0),
+            },
+        ];
+
+        let mut clusters = LightClusters::default();
+        clusters.build(&lights, &view, &projection, 0.1, 100.0);
+
+        assert_eq!(
+            clusters.clusters.len(),
+            CLUSTERS_X * CLUSTERS_Y * CLUSTERS_Z
+        );
+        // The huge light is in every cluster.
+        assert!(clusters.clusters.iter().all(|c| c.y >= 1.0));
+        // The small light affects only a few clusters.
+        let small_count = clusters.indices.iter().filter(|i| **i == 0.0).count();
+        assert!(small_count > 0 && small_count <= 8);
+        // The light behind the camera is culled.
+        assert!(!clusters.indices.contains(&1.0));
+
+        // The first cluster is near the camera and contains only the huge light.
+        let first = clusters.clusters[cluster_index(0, 0, 0)];
+        assert_eq!(first.y, 1.0);
+        assert_eq!(clusters.indices[first.x as usize], 2.0);
+    }
+}
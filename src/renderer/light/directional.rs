@@ -25,7 +25,8 @@ pub struct DirectionalLightShader {
     pub shadow_cascade2: UniformLocation,
     pub light_view_proj_matrices: UniformLocation,
     pub view_matrix: UniformLocation,
-    pub shadow_bias: UniformLocation,
+    pub shadow_biases: UniformLocation,
+    pub shadow_normal_offsets: UniformLocation,
     pub shadows_enabled: UniformLocation,
     pub soft_shadows: UniformLocation,
     pub shadow_map_inv_size: UniformLocation,
@@ -72,7 +73,10 @@ impl DirectionalLightShader {
             light_view_proj_matrices: program
                 .uniform_location(state, &ImmutableString::new("lightViewProjMatrices"))?,
             view_matrix: program.uniform_location(state, &ImmutableString::new("viewMatrix"))?,
-            shadow_bias: program.uniform_location(state, &ImmutableString::new("shadowBias"))?,
+            shadow_biases: program
+                .uniform_location(state, &ImmutableString::new("shadowBiases"))?,
+            shadow_normal_offsets: program
+                .uniform_location(state, &ImmutableString::new("shadowNormalOffsets"))?,
             shadows_enabled: program
                 .uniform_location(state, &ImmutableString::new("shadowsEnabled"))?,
             soft_shadows: program.uniform_location(state, &ImmutableString::new("softShadows"))?,
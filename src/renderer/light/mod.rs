@@ -20,8 +20,13 @@ use crate::{
         },
         gbuffer::GBuffer,
         light::{
-            ambient::AmbientLightShader, directional::DirectionalLightShader,
-            point::PointLightShader, spot::SpotLightShader,
+            ambient::AmbientLightShader,
+            clustered::{
+                ClusteredLight, ClusteredLightRenderer, CLUSTERS_X, CLUSTERS_Y, CLUSTERS_Z,
+            },
+            directional::DirectionalLightShader,
+            point::PointLightShader,
+            spot::SpotLightShader,
         },
         light_volume::LightVolumeRenderer,
         shadow::{
@@ -30,7 +35,11 @@ use crate::{
             spot::SpotShadowMapRenderer,
         },
         skybox_shader::SkyboxShader,
-        ssao::ScreenSpaceAmbientOcclusionRenderer,
+        ssao::{
+            AmbientOcclusionHistory, AmbientOcclusionRenderContext,
+            ScreenSpaceAmbientOcclusionRenderer,
+        },
+        stats::{GpuProfiler, RenderStage},
         storage::MatrixStorageCache,
         GeometryCache, QualitySettings, RenderPassStatistics, TextureCache,
     },
@@ -53,6 +62,7 @@ use std::{
 };
 
 pub mod ambient;
+pub mod clustered;
 pub mod directional;
 pub mod point;
 pub mod spot;
@@ -114,6 +124,8 @@ pub struct DeferredLightRenderer {
     point_shadow_map_renderer: PointShadowMapRenderer,
     csm_renderer: CsmRenderer,
     light_volume: LightVolumeRenderer,
+    clustered_light_renderer: ClusteredLightRenderer,
+    clustered_lights: Vec<ClusteredLight>,
 }
 
 pub(crate) struct DeferredRendererContext<'a> {
@@ -131,7 +143,10 @@ pub(crate) struct DeferredRendererContext<'a> {
     pub white_dummy: Rc<RefCell<GpuTexture>>,
     pub black_dummy: Rc<RefCell<GpuTexture>>,
     pub volume_dummy: Rc<RefCell<GpuTexture>>,
+    pub environment_dummy: Rc<RefCell<GpuTexture>>,
     pub matrix_storage: &'a mut MatrixStorageCache,
+    pub gpu_profiler: &'a mut GpuProfiler,
+    pub ao_history: &'a mut AmbientOcclusionHistory,
 }
 
 impl DeferredLightRenderer {
@@ -230,6 +245,8 @@ impl DeferredLightRenderer {
                 quality_defaults.point_shadow_map_precision,
             )?,
             light_volume: LightVolumeRenderer::new(state)?,
+            clustered_light_renderer: ClusteredLightRenderer::new(state)?,
+            clustered_lights: Default::default(),
             csm_renderer: CsmRenderer::new(
                 state,
                 quality_defaults.csm_settings.size,
@@ -270,7 +287,6 @@ impl DeferredLightRenderer {
                 settings.csm_settings.precision,
             )?;
         }
-        self.ssao_renderer.set_radius(settings.ssao_radius);
         Ok(())
     }
 
@@ -311,7 +327,10 @@ impl DeferredLightRenderer {
             frame_buffer,
             black_dummy,
             volume_dummy,
+            environment_dummy,
             matrix_storage,
+            gpu_profiler,
+            ao_history,
         } = args;
 
         let viewport = Rect::new(0, 0, gbuffer.width, gbuffer.height);
@@ -339,12 +358,24 @@ impl DeferredLightRenderer {
 
         // Fill SSAO map.
         if settings.use_ssao {
-            pass_stats += self.ssao_renderer.render(
+            pass_stats += self.ssao_renderer.render(AmbientOcclusionRenderContext {
                 state,
                 gbuffer,
                 projection_matrix,
-                camera.view_matrix().basis(),
-            )?;
+                view_matrix: camera.view_matrix().basis(),
+                view_projection,
+                method: settings.ambient_occlusion_method,
+                quality: settings.ambient_occlusion_quality,
+                radius: camera
+                    .ambient_occlusion_radius()
+                    .unwrap_or(settings.ssao_radius),
+                intensity: camera.ambient_occlusion_intensity(),
+                history: if settings.ambient_occlusion_temporal_accumulation {
+                    Some(ao_history)
+                } else {
+                    None
+                },
+            })?;
         }
 
         // Render skybox (if any).
@@ -379,6 +410,7 @@ impl DeferredLightRenderer {
                     |mut program_binding| {
                         program_binding
                             .set_texture(&shader.cubemap_texture, gpu_texture)
+                            .set_bool(&shader.is_linear, skybox.is_linear())
                             .set_matrix4(&shader.wvp_matrix, &(view_projection * wvp));
                     },
                 )?;
@@ -393,6 +425,15 @@ impl DeferredLightRenderer {
         let gbuffer_ambient_map = gbuffer.ambient_texture();
         let ao_map = self.ssao_renderer.ao_map();
 
+        // Image-based lighting is available only for skyboxes made of equirectangular panoramas.
+        let ibl_maps = camera.skybox_ref().and_then(|skybox| {
+            let irradiance_map = textures.get(state, skybox.irradiance_map()?)?.clone();
+            let prefiltered_map = skybox.prefiltered_map()?;
+            let prefiltered_mip_count = prefiltered_map.data_ref().mip_count();
+            let prefiltered_map = textures.get(state, prefiltered_map)?.clone();
+            Some((irradiance_map, prefiltered_map, prefiltered_mip_count))
+        });
+
         pass_stats += frame_buffer.draw(
             &self.quad,
             state,
@@ -430,10 +471,50 @@ impl DeferredLightRenderer {
                     .set_texture(
                         &self.ambient_light_shader.ambient_texture,
                         &gbuffer_ambient_map,
-                    );
+                    )
+                    .set_texture(&self.ambient_light_shader.depth_texture, &gbuffer_depth_map)
+                    .set_texture(
+                        &self.ambient_light_shader.normal_texture,
+                        &gbuffer_normal_map,
+                    )
+                    .set_texture(
+                        &self.ambient_light_shader.material_texture,
+                        &gbuffer_material_map,
+                    )
+                    .set_matrix4(
+                        &self.ambient_light_shader.inv_view_proj_matrix,
+                        &inv_view_projection,
+                    )
+                    .set_vector3(
+                        &self.ambient_light_shader.camera_position,
+                        &camera_global_position,
+                    )
+                    .set_bool(&self.ambient_light_shader.use_ibl, ibl_maps.is_some());
+
+                if let Some((irradiance_map, prefiltered_map, prefiltered_mip_count)) = &ibl_maps {
+                    program_binding
+                        .set_texture(&self.ambient_light_shader.irradiance_map, irradiance_map)
+                        .set_texture(&self.ambient_light_shader.prefiltered_map, prefiltered_map)
+                        .set_f32(
+                            &self.ambient_light_shader.max_reflection_lod,
+                            prefiltered_mip_count.saturating_sub(1) as f32,
+                        );
+                } else {
+                    program_binding
+                        .set_texture(
+                            &self.ambient_light_shader.irradiance_map,
+                            &environment_dummy,
+                        )
+                        .set_texture(
+                            &self.ambient_light_shader.prefiltered_map,
+                            &environment_dummy,
+                        );
+                }
             },
         )?;
 
+        self.clustered_lights.clear();
+
         for (light_handle, light) in scene.graph.pair_iter() {
             if !light.global_visibility() || !light.is_globally_enabled() {
                 continue;
@@ -487,6 +568,27 @@ impl DeferredLightRenderer {
                 continue;
             }
 
+            // Small lights without shadows and light scattering are rendered in a single pass using
+            // clustered lighting.
+            if settings.use_clustered_lighting && !shadows_enabled {
+                if let Some(point_light) = light.cast::<PointLight>() {
+                    let base_light = point_light.base_light_ref();
+                    if !(settings.light_scatter_enabled && base_light.is_scatter_enabled()) {
+                        let color = base_light
+                            .color()
+                            .srgb_to_linear_f32()
+                            .xyz()
+                            .scale(base_light.intensity());
+                        self.clustered_lights.push(ClusteredLight {
+                            position: light_position,
+                            radius: light_radius,
+                            color,
+                        });
+                        continue;
+                    }
+                }
+            }
+
             let b1 = shadows_distance * 0.2;
             let b2 = shadows_distance * 0.4;
             let cascade_index =
@@ -508,6 +610,8 @@ impl DeferredLightRenderer {
             let mut light_view_projection = Matrix4::identity();
 
             if shadows_enabled {
+                gpu_profiler.begin(state, RenderStage::Shadows);
+
                 if let Some(spot) = light.cast::<SpotLight>() {
                     let z_near = 0.01;
                     let z_far = light_radius;
@@ -588,6 +692,8 @@ impl DeferredLightRenderer {
 
                     light_stats.csm_rendered += 1;
                 };
+
+                gpu_profiler.end(state);
             }
 
             // Mark lighted areas in stencil buffer to do light calculations only on them.
@@ -824,6 +930,17 @@ impl DeferredLightRenderer {
                             self.csm_renderer.cascades()[2].view_proj_matrix,
                         ];
                         let csm_map_size = self.csm_renderer.size() as f32;
+                        let biases = [
+                            directional.csm_options.total_cascade_bias(0),
+                            directional.csm_options.total_cascade_bias(1),
+                            directional.csm_options.total_cascade_bias(2),
+                        ];
+                        let normal_offset = directional.csm_options.normal_offset();
+                        let normal_offsets = [
+                            normal_offset * self.csm_renderer.cascades()[0].texel_size,
+                            normal_offset * self.csm_renderer.cascades()[1].texel_size,
+                            normal_offset * self.csm_renderer.cascades()[2].texel_size,
+                        ];
 
                         program_binding
                             .set_vector3(&shader.light_direction, &emit_direction)
@@ -857,7 +974,8 @@ impl DeferredLightRenderer {
                             )
                             .set_f32_slice(&shader.cascade_distances, &distances)
                             .set_matrix4(&shader.view_matrix, &camera.view_matrix())
-                            .set_f32(&shader.shadow_bias, directional.csm_options.shadow_bias())
+                            .set_f32_slice(&shader.shadow_biases, &biases)
+                            .set_f32_slice(&shader.shadow_normal_offsets, &normal_offsets)
                             .set_bool(&shader.shadows_enabled, shadows_enabled)
                             .set_bool(&shader.soft_shadows, settings.csm_settings.pcf)
                             .set_f32(&shader.shadow_map_inv_size, 1.0 / csm_map_size);
@@ -884,6 +1002,65 @@ impl DeferredLightRenderer {
             }
         }
 
+        if !self.clustered_lights.is_empty() {
+            let z_near = camera.projection().z_near();
+            let z_far = camera.projection().z_far();
+
+            self.clustered_light_renderer.prepare(
+                state,
+                &self.clustered_lights,
+                &camera.view_matrix(),
+                &projection_matrix,
+                z_near,
+                z_far,
+            )?;
+
+            light_stats.point_lights_rendered += self.clustered_lights.len();
+
+            let renderer = &self.clustered_light_renderer;
+            let shader = &renderer.shader;
+
+            pass_stats += frame_buffer.draw(
+                &self.quad,
+                state,
+                viewport,
+                &shader.program,
+                &DrawParameters {
+                    cull_face: None,
+                    color_write: Default::default(),
+                    depth_write: false,
+                    stencil_test: None,
+                    depth_test: false,
+                    blend: Some(BlendParameters {
+                        func: BlendFunc::new(BlendFactor::One, BlendFactor::One),
+                        ..Default::default()
+                    }),
+                    stencil_op: Default::default(),
+                },
+                ElementRange::Full,
+                |mut program_binding| {
+                    program_binding
+                        .set_matrix4(&shader.wvp_matrix, &frame_matrix)
+                        .set_matrix4(&shader.inv_view_proj_matrix, &inv_view_projection)
+                        .set_matrix4(&shader.view_matrix, &camera.view_matrix())
+                        .set_vector3(&shader.camera_position, &camera_global_position)
+                        .set_vector3(
+                            &shader.cluster_count,
+                            &Vector3::new(CLUSTERS_X as f32, CLUSTERS_Y as f32, CLUSTERS_Z as f32),
+                        )
+                        .set_f32(&shader.z_near, z_near.max(0.001))
+                        .set_f32(&shader.z_far, z_far.max(z_near.max(0.001) * 2.0))
+                        .set_texture(&shader.depth_sampler, &gbuffer_depth_map)
+                        .set_texture(&shader.color_sampler, &gbuffer_diffuse_map)
+                        .set_texture(&shader.normal_sampler, &gbuffer_normal_map)
+                        .set_texture(&shader.material_sampler, &gbuffer_material_map)
+                        .set_texture(&shader.lights_sampler, renderer.lights_texture())
+                        .set_texture(&shader.clusters_sampler, renderer.clusters_texture())
+                        .set_texture(&shader.indices_sampler, renderer.indices_texture());
+                },
+            )?;
+        }
+
         Ok((pass_stats, light_stats))
     }
 }
@@ -15,8 +15,12 @@ pub mod framework;
 
 pub mod batch;
 pub mod cache;
+pub mod capture;
 pub mod debug_renderer;
+pub mod panorama;
+pub mod stats;
 pub mod storage;
+pub mod thumbnail;
 pub mod ui_renderer;
 
 mod bloom;
@@ -30,15 +34,18 @@ mod light_volume;
 mod shadow;
 mod skybox_shader;
 mod ssao;
+mod taa;
+mod upscale;
 
 use crate::renderer::cache::texture::TextureRenderData;
 
 use crate::renderer::cache::TimeToLive;
+use crate::renderer::capture::{FrameCaptureMode, FrameCaptureRequest, FrameCapturer};
 use crate::renderer::framework::state::SharedPipelineState;
 use crate::{
     asset::{event::ResourceEvent, manager::ResourceManager},
     core::{
-        algebra::{Matrix4, Vector2, Vector3},
+        algebra::{Matrix4, UnitQuaternion, Vector2, Vector3},
         color::Color,
         instant,
         log::{Log, MessageKind},
@@ -74,16 +81,26 @@ use crate::{
             state::{GlKind, PipelineState, PipelineStatistics, PolygonFace, PolygonFillMode},
         },
         fxaa::FxaaRenderer,
-        gbuffer::{GBuffer, GBufferRenderContext},
+        gbuffer::{GBuffer, GBufferRenderContext, MotionHistory},
         hdr::HighDynamicRangeRenderer,
         light::{DeferredLightRenderer, DeferredRendererContext, LightingStatistics},
+        panorama::CubeMapCapture,
+        ssao::AmbientOcclusionHistory,
+        stats::{CameraStatistics, GpuProfiler, RenderStage, RenderStatistics},
         storage::MatrixStorageCache,
+        taa::{TaaHistory, TaaRenderContext, TaaRenderer},
         ui_renderer::{UiRenderContext, UiRenderer},
+        upscale::UpscaleRenderer,
+    },
+    resource::texture::{Texture, TextureKind, TextureResource, TextureResourceExtension},
+    scene::{
+        camera::{Camera, CameraBuilder, RenderTargetFormat},
+        mesh::surface::SurfaceData,
+        node::Node,
+        Scene, SceneContainer,
     },
-    resource::texture::{Texture, TextureKind, TextureResource},
-    scene::{camera::Camera, mesh::surface::SurfaceData, Scene, SceneContainer},
 };
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use fyrox_core::algebra::Vector4;
 use fyrox_core::uuid_provider;
 use glow::HasContext;
@@ -93,6 +110,7 @@ use glutin::{
     prelude::GlSurface,
     surface::{Surface, WindowSurface},
 };
+use image::{Rgba, RgbaImage};
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -204,6 +222,68 @@ impl std::ops::AddAssign<RenderPassStatistics> for Statistics {
     }
 }
 
+/// Quality tier of ambient occlusion. Higher tiers take more samples per pixel, which reduces noise and
+/// banding at the cost of performance.
+#[derive(
+    Copy,
+    Clone,
+    Default,
+    Hash,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Debug,
+    Serialize,
+    Deserialize,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum AmbientOcclusionQuality {
+    /// 8 samples for SSAO, 2 slices with 4 steps each for GTAO.
+    Low,
+    /// 16 samples for SSAO, 3 slices with 6 steps each for GTAO.
+    Medium,
+    /// 32 samples for SSAO, 4 slices with 8 steps each for GTAO.
+    #[default]
+    High,
+}
+
+uuid_provider!(AmbientOcclusionQuality = "3c8e5f1a-6d27-4b94-a0e3-9f5b7c2d1e48");
+
+/// A method that is used to calculate ambient occlusion.
+#[derive(
+    Copy,
+    Clone,
+    Default,
+    Hash,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Debug,
+    Serialize,
+    Deserialize,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum AmbientOcclusionMethod {
+    /// Classic hemisphere-sampling screen space ambient occlusion. Cheap, but tends to over-darken
+    /// flat surfaces near edges.
+    #[default]
+    Ssao,
+    /// Ground-truth ambient occlusion. Searches for horizons in a set of screen-space slices and
+    /// integrates visible part of the hemisphere analytically, which gives results close to
+    /// ray-traced occlusion.
+    Gtao,
+}
+
+uuid_provider!(AmbientOcclusionMethod = "a47d2b96-1e5c-4f83-8b0a-6c3e9d7f2154");
+
 /// Shadow map precision allows you to select compromise between quality and performance.
 #[derive(
     Copy,
@@ -260,6 +340,115 @@ impl Default for CsmSettings {
     }
 }
 
+/// Dynamic resolution settings. Dynamic resolution allows the renderer to draw scenes in lower
+/// resolution and then upscale them to the size of the window. It is useful to keep stable frame
+/// rate on low-end hardware. Scenes with render target are always rendered in full resolution.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct DynamicResolutionSettings {
+    /// Whether the renderer should adjust render scale automatically, depending on frame time. GPU
+    /// frame time (see [`stats::GpuTimings`]) is used when timer queries are supported, otherwise CPU frame
+    /// time is used.
+    pub automatic: bool,
+
+    /// Render scale that is used when automatic mode is off. Should be in `[0.25; 1.0]` range.
+    pub render_scale: f32,
+
+    /// Lowest render scale that could be picked in automatic mode.
+    pub min_render_scale: f32,
+
+    /// Highest render scale that could be picked in automatic mode.
+    pub max_render_scale: f32,
+
+    /// Desired frame time (in seconds) that the renderer tries to keep in automatic mode.
+    pub target_frame_time: f32,
+}
+
+impl Default for DynamicResolutionSettings {
+    fn default() -> Self {
+        Self {
+            automatic: false,
+            render_scale: 1.0,
+            min_render_scale: 0.5,
+            max_render_scale: 1.0,
+            target_frame_time: 1.0 / 60.0,
+        }
+    }
+}
+
+// Adjusts render scale in automatic dynamic resolution mode depending on frame time.
+#[derive(Debug)]
+struct RenderScaleController {
+    scale: f32,
+    // Amount of frames that must pass before automatic render scale could be changed again.
+    cooldown: u32,
+}
+
+impl Default for RenderScaleController {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            cooldown: 0,
+        }
+    }
+}
+
+impl RenderScaleController {
+    // Render scale changes re-creates frame buffers of every scene, so it is quantized and
+    // changed with some delay to prevent doing this every frame.
+    const STEP: f32 = 0.05;
+    const COOLDOWN_FRAMES: u32 = 30;
+
+    fn update(&mut self, settings: &DynamicResolutionSettings, frame_time: f32) {
+        if !settings.automatic {
+            self.scale = settings.render_scale.clamp(0.25, 1.0);
+            return;
+        }
+
+        if self.cooldown > 0 {
+            self.cooldown -= 1;
+            return;
+        }
+
+        let mut new_scale = self.scale;
+        if frame_time > settings.target_frame_time * 1.05 {
+            new_scale -= Self::STEP;
+        } else if frame_time < settings.target_frame_time * 0.85 {
+            new_scale += Self::STEP;
+        }
+        new_scale = new_scale.clamp(
+            settings.min_render_scale.clamp(0.25, 1.0),
+            settings.max_render_scale.clamp(0.25, 1.0),
+        );
+        if new_scale != self.scale {
+            self.scale = new_scale;
+            self.cooldown = Self::COOLDOWN_FRAMES;
+        }
+    }
+}
+
+/// Defines how much data could be uploaded to GPU per frame in background, for example textures that were
+/// just loaded by the resource manager. It prevents frame hitches when lots of resources are loaded at once
+/// (for example, when streaming a new area of a level). Keep in mind, that the resources, that are needed
+/// to render the current frame, are uploaded on demand regardless of the budget.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UploadBudget {
+    /// Maximum amount of textures, that could be uploaded per frame. Default is 5.
+    pub max_textures_per_frame: usize,
+    /// Maximum amount of texture data (in bytes), that could be uploaded per frame. The budget is soft - at
+    /// least one texture is uploaded per frame, even if its size exceeds the budget. Default is `None`
+    /// (unlimited).
+    pub max_texture_bytes_per_frame: Option<usize>,
+}
+
+impl Default for UploadBudget {
+    fn default() -> Self {
+        Self {
+            max_textures_per_frame: 5,
+            max_texture_bytes_per_frame: None,
+        }
+    }
+}
+
 /// Quality settings allows you to find optimal balance between performance and
 /// graphics quality.
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect)]
@@ -300,16 +489,45 @@ pub struct QualitySettings {
     /// Whether to use screen space ambient occlusion or not.
     pub use_ssao: bool,
     /// Radius of sampling hemisphere used in SSAO, it defines much ambient
-    /// occlusion will be in your scene.
+    /// occlusion will be in your scene. Could be overridden per camera, see
+    /// [`crate::scene::camera::Camera::set_ambient_occlusion_radius`].
     pub ssao_radius: f32,
+    /// A method that is used to calculate ambient occlusion.
+    #[serde(default)]
+    pub ambient_occlusion_method: AmbientOcclusionMethod,
+    /// Quality tier of ambient occlusion.
+    #[serde(default)]
+    pub ambient_occlusion_quality: AmbientOcclusionQuality,
+    /// Whether to accumulate ambient occlusion over multiple frames or not. Each frame uses
+    /// different noise, and the results are blended with reprojected history of the camera,
+    /// which removes most of the noise at low quality tiers.
+    #[serde(default)]
+    pub ambient_occlusion_temporal_accumulation: bool,
 
     /// Global switch to enable or disable light scattering. Each light can have
     /// its own scatter switch, but this one is able to globally disable scatter.
     pub light_scatter_enabled: bool,
 
+    /// Whether to render point lights without shadows (and without light scattering) in a single
+    /// pass using clustered light culling or not. It is much faster when there are lots of small
+    /// dynamic lights on screen (muzzle flashes, projectiles, etc.), but could be slightly slower
+    /// when there are only a few large lights.
+    #[serde(default)]
+    pub use_clustered_lighting: bool,
+
     /// Whether to use Fast Approximate AntiAliasing or not.
     pub fxaa: bool,
 
+    /// Whether to use Temporal AntiAliasing or not. It produces much better results than FXAA on
+    /// thin geometry and does not flicker on camera movement, but could produce slight blur. FXAA
+    /// is not applied when TAA is enabled.
+    #[serde(default)]
+    pub taa: bool,
+
+    /// Dynamic resolution settings.
+    #[serde(default)]
+    pub dynamic_resolution: DynamicResolutionSettings,
+
     /// Whether to use Parallax Mapping or not.
     pub use_parallax_mapping: bool,
 
@@ -341,14 +559,23 @@ impl QualitySettings {
 
             use_ssao: true,
             ssao_radius: 0.5,
+            ambient_occlusion_method: AmbientOcclusionMethod::Ssao,
+            ambient_occlusion_quality: AmbientOcclusionQuality::High,
+            ambient_occlusion_temporal_accumulation: true,
 
             light_scatter_enabled: true,
 
+            use_clustered_lighting: true,
+
             point_shadow_map_precision: ShadowMapPrecision::Full,
             spot_shadow_map_precision: ShadowMapPrecision::Full,
 
             fxaa: true,
 
+            taa: false,
+
+            dynamic_resolution: Default::default(),
+
             use_bloom: true,
 
             use_parallax_mapping: true,
@@ -374,14 +601,23 @@ impl QualitySettings {
 
             use_ssao: true,
             ssao_radius: 0.5,
+            ambient_occlusion_method: AmbientOcclusionMethod::Ssao,
+            ambient_occlusion_quality: AmbientOcclusionQuality::High,
+            ambient_occlusion_temporal_accumulation: false,
 
             light_scatter_enabled: true,
 
+            use_clustered_lighting: true,
+
             point_shadow_map_precision: ShadowMapPrecision::Full,
             spot_shadow_map_precision: ShadowMapPrecision::Full,
 
             fxaa: true,
 
+            taa: false,
+
+            dynamic_resolution: Default::default(),
+
             use_bloom: true,
 
             use_parallax_mapping: true,
@@ -412,14 +648,23 @@ impl QualitySettings {
 
             use_ssao: true,
             ssao_radius: 0.5,
+            ambient_occlusion_method: AmbientOcclusionMethod::Ssao,
+            ambient_occlusion_quality: AmbientOcclusionQuality::Medium,
+            ambient_occlusion_temporal_accumulation: true,
 
             light_scatter_enabled: false,
 
+            use_clustered_lighting: true,
+
             point_shadow_map_precision: ShadowMapPrecision::Half,
             spot_shadow_map_precision: ShadowMapPrecision::Half,
 
             fxaa: true,
 
+            taa: false,
+
+            dynamic_resolution: Default::default(),
+
             use_bloom: true,
 
             use_parallax_mapping: false,
@@ -450,14 +695,23 @@ impl QualitySettings {
 
             use_ssao: false,
             ssao_radius: 0.5,
+            ambient_occlusion_method: AmbientOcclusionMethod::Ssao,
+            ambient_occlusion_quality: AmbientOcclusionQuality::Low,
+            ambient_occlusion_temporal_accumulation: false,
 
             light_scatter_enabled: false,
 
+            use_clustered_lighting: true,
+
             point_shadow_map_precision: ShadowMapPrecision::Half,
             spot_shadow_map_precision: ShadowMapPrecision::Half,
 
             fxaa: false,
 
+            taa: false,
+
+            dynamic_resolution: Default::default(),
+
             use_bloom: false,
 
             use_parallax_mapping: false,
@@ -470,6 +724,19 @@ impl QualitySettings {
             },
         }
     }
+
+    /// Returns a copy of the settings with every effect that makes sense only for 3D scenes
+    /// (shadows, ambient occlusion, light scattering, parallax mapping) disabled. The renderer
+    /// uses it for scenes in [`crate::scene::SceneMode::Mode2D`].
+    pub fn without_3d_effects(mut self) -> Self {
+        self.point_shadows_enabled = false;
+        self.spot_shadows_enabled = false;
+        self.csm_settings.enabled = false;
+        self.use_ssao = false;
+        self.light_scatter_enabled = false;
+        self.use_parallax_mapping = false;
+        self
+    }
 }
 
 impl Statistics {
@@ -524,6 +791,12 @@ impl Default for Statistics {
     }
 }
 
+/// Scene data of a camera, that renders into its own render target.
+struct CameraRenderTargetData {
+    data: AssociatedSceneData,
+    time_since_update: f32,
+}
+
 /// A set of frame buffers, renderers, that contains scene-specific data.
 pub struct AssociatedSceneData {
     /// G-Buffer of the scene.
@@ -532,6 +805,11 @@ pub struct AssociatedSceneData {
     /// Intermediate high dynamic range frame buffer.
     pub hdr_scene_framebuffer: FrameBuffer,
 
+    /// A copy of the opaque part of the intermediate high dynamic range frame. It is filled right
+    /// before the first draw call of the forward renderer that uses `fyrox_sceneColor` built-in
+    /// uniform, so materials could sample the frame behind them (for refraction, heat haze, etc.).
+    pub hdr_scene_color_framebuffer: FrameBuffer,
+
     /// Final frame of the scene. Tone mapped + gamma corrected.
     pub ldr_scene_framebuffer: FrameBuffer,
 
@@ -545,6 +823,15 @@ pub struct AssociatedSceneData {
     /// Bloom contains only overly bright pixels that creates light
     /// bleeding effect (glow effect).
     pub bloom_renderer: BloomRenderer,
+
+    /// Accumulated frames of each camera of the scene, used by temporal anti-aliasing.
+    pub taa_history: FxHashMap<Handle<Node>, TaaHistory>,
+
+    /// Accumulated ambient occlusion of each camera of the scene.
+    pub ao_history: FxHashMap<Handle<Node>, AmbientOcclusionHistory>,
+
+    /// Transforms of the previous frame, used to calculate per-pixel velocity.
+    pub motion_history: MotionHistory,
 }
 
 impl AssociatedSceneData {
@@ -589,6 +876,26 @@ impl AssociatedSceneData {
             }],
         )?;
 
+        let hdr_scene_color_texture = GpuTexture::new(
+            state,
+            GpuTextureKind::Rectangle { width, height },
+            PixelKind::RGBA16F,
+            // Linear filtering, because the copy is usually sampled with some distortion.
+            MinificationFilter::Linear,
+            MagnificationFilter::Linear,
+            1,
+            None,
+        )?;
+
+        let hdr_scene_color_framebuffer = FrameBuffer::new(
+            state,
+            None,
+            vec![Attachment {
+                kind: AttachmentKind::Color,
+                texture: Rc::new(RefCell::new(hdr_scene_color_texture)),
+            }],
+        )?;
+
         let ldr_frame_texture = GpuTexture::new(
             state,
             GpuTextureKind::Rectangle { width, height },
@@ -640,8 +947,12 @@ impl AssociatedSceneData {
             hdr_renderer: HighDynamicRangeRenderer::new(state)?,
             bloom_renderer: BloomRenderer::new(state, width, height)?,
             hdr_scene_framebuffer,
+            hdr_scene_color_framebuffer,
             ldr_scene_framebuffer,
             ldr_temp_framebuffer,
+            taa_history: Default::default(),
+            ao_history: Default::default(),
+            motion_history: Default::default(),
         })
     }
 
@@ -722,6 +1033,8 @@ pub struct Renderer {
     /// User interface renderer.
     pub ui_renderer: UiRenderer,
     statistics: Statistics,
+    render_statistics: RenderStatistics,
+    gpu_profiler: GpuProfiler,
     quad: GeometryBuffer,
     frame_size: (u32, u32),
     quality_settings: QualitySettings,
@@ -729,6 +1042,8 @@ pub struct Renderer {
     pub debug_renderer: DebugRenderer,
     /// A set of associated data for each scene that was rendered.
     pub scene_data_map: FxHashMap<Handle<Scene>, AssociatedSceneData>,
+    /// A set of associated data for each camera that renders into its own render target.
+    camera_render_targets: FxHashMap<(Handle<Scene>, Handle<Node>), CameraRenderTargetData>,
     backbuffer_clear_color: Color,
     /// Texture cache with GPU textures.
     pub texture_cache: TextureCache,
@@ -736,12 +1051,26 @@ pub struct Renderer {
     geometry_cache: GeometryCache,
     forward_renderer: ForwardRenderer,
     fxaa_renderer: FxaaRenderer,
+    taa_renderer: TaaRenderer,
+    // Index of the current frame in camera jitter sequence of temporal anti-aliasing.
+    taa_frame_index: u32,
+    // Cameras, that were rendered with temporal anti-aliasing in the last frame.
+    taa_cameras: FxHashSet<(Handle<Scene>, Handle<Node>)>,
+    upscale_renderer: UpscaleRenderer,
+    render_scale: RenderScaleController,
     texture_event_receiver: Receiver<ResourceEvent>,
+    // A texture, that was received, but not uploaded because the upload budget of the previous frame
+    // was exceeded.
+    deferred_texture_upload: Option<TextureResource>,
+    upload_budget: UploadBudget,
     shader_event_receiver: Receiver<ResourceEvent>,
     matrix_storage: MatrixStorageCache,
     // TextureId -> FrameBuffer mapping. This mapping is used for temporal frame buffers
     // like ones used to render UI instances.
     ui_frame_buffers: FxHashMap<usize, FrameBuffer>,
+    // A scene and a camera, that are rendered exclusively while tiled screenshot is being captured.
+    exclusive_camera: Option<(Handle<Scene>, Handle<Node>)>,
+    frame_capturer: FrameCapturer,
     /// Pipeline state.
     pub state: SharedPipelineState,
 }
@@ -868,6 +1197,23 @@ pub struct SceneRenderPassContext<'a, 'b> {
     /// have an ability to write to this texture.
     pub normal_texture: Rc<RefCell<GpuTexture>>,
 
+    /// A texture with diffuse colors (albedo) from G-Buffer.
+    ///
+    /// # Important notes
+    ///
+    /// Keep in mind that G-Buffer cannot be modified in custom render passes, so you don't
+    /// have an ability to write to this texture.
+    pub diffuse_texture: Rc<RefCell<GpuTexture>>,
+
+    /// A texture with material properties from G-Buffer (metallic - R, roughness - G, ambient
+    /// occlusion - B).
+    ///
+    /// # Important notes
+    ///
+    /// Keep in mind that G-Buffer cannot be modified in custom render passes, so you don't
+    /// have an ability to write to this texture.
+    pub material_texture: Rc<RefCell<GpuTexture>>,
+
     /// A texture with ambient lighting values from G-Buffer.
     ///
     /// # Important notes
@@ -883,8 +1229,77 @@ pub struct SceneRenderPassContext<'a, 'b> {
     pub matrix_storage: &'a mut MatrixStorageCache,
 }
 
+impl<'a, 'b> SceneRenderPassContext<'a, 'b> {
+    /// Returns inverse view-projection matrix of the camera. It could be used to reconstruct
+    /// world-space positions from the depth texture. View and projection matrices could be
+    /// obtained directly from the camera.
+    pub fn inv_view_projection_matrix(&self) -> Matrix4<f32> {
+        self.camera
+            .view_projection_matrix()
+            .try_inverse()
+            .unwrap_or_default()
+    }
+}
+
 /// A trait for custom scene rendering pass. It could be used to add your own rendering techniques.
+/// Every method is called for **each** camera of **each** scene registered in the engine, but you
+/// are able to filter out scenes by their handles. Methods are called in the following order:
+///
+/// 1) [`Self::on_opaque_render`] - after opaque (deferred) geometry is lit, but before transparent
+///    (forward) geometry is drawn. Suitable for effects that should be occluded by transparent
+///    objects - outlines, fog volumes, etc.
+/// 2) [`Self::on_hdr_render`] - after transparent geometry is drawn, but before post-processing.
+/// 3) [`Self::on_ldr_render`] - after post-processing (tone mapping, anti-aliasing, etc.) and debug
+///    drawing. Suitable for overlays.
+///
+/// Camera matrices are available via [`SceneRenderPassContext::camera`], G-Buffer textures are
+/// available directly in the context.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox::{
+/// #     core::pool::Handle,
+/// #     renderer::{
+/// #         framework::error::FrameworkError, RenderPassStatistics, SceneRenderPass,
+/// #         SceneRenderPassContext,
+/// #     },
+/// #     scene::Scene,
+/// # };
+/// struct FogVolumePass {
+///     scene: Handle<Scene>,
+/// }
+///
+/// impl SceneRenderPass for FogVolumePass {
+///     fn on_opaque_render(
+///         &mut self,
+///         ctx: SceneRenderPassContext,
+///     ) -> Result<RenderPassStatistics, FrameworkError> {
+///         if ctx.scene_handle != self.scene {
+///             return Ok(Default::default());
+///         }
+///
+///         let _view_projection = ctx.camera.view_projection_matrix();
+///         let _inv_view_projection = ctx.inv_view_projection_matrix();
+///         let _depth = ctx.depth_texture.clone();
+///         // Draw fog volumes into `ctx.framebuffer` here.
+///
+///         Ok(Default::default())
+///     }
+/// }
+/// ```
+///
+/// Passes are registered in the renderer with [`Renderer::add_render_pass`].
 pub trait SceneRenderPass {
+    /// Renders scene into high dynamic range target after opaque geometry is lit, but before
+    /// transparent geometry is drawn. The frame buffer contains depth of opaque geometry.
+    fn on_opaque_render(
+        &mut self,
+        _ctx: SceneRenderPassContext,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        Ok(RenderPassStatistics::default())
+    }
+
     /// Renders scene into high dynamic range target. It will be called for **each** scene
     /// registered in the engine, but you are able to filter out scene by its handle.
     fn on_hdr_render(
@@ -948,6 +1363,9 @@ fn blit_pixels(
     )
 }
 
+/// Maximum amount of edges of 2D shadow occluders, that could be passed to a shader.
+pub const MAX_2D_SHADOW_OCCLUDER_EDGES: usize = 64;
+
 #[allow(missing_docs)] // TODO
 pub struct LightData<const N: usize = 16> {
     pub count: usize,
@@ -955,6 +1373,14 @@ pub struct LightData<const N: usize = 16> {
     pub position: [Vector3<f32>; N],
     pub direction: [Vector3<f32>; N],
     pub parameters: [Vector2<f32>; N],
+    /// Index of the first edge in [`Self::occluder_edges`], that could block a light source.
+    pub shadow_edges_offset: [i32; N],
+    /// Amount of edges in [`Self::occluder_edges`], that could block a light source. It is zero for
+    /// light sources without shadows.
+    pub shadow_edges_count: [i32; N],
+    /// Edges of 2D shadow occluders in world coordinates, each edge is packed as `(x1, y1, x2, y2)`.
+    /// Edges of every light source are stored contiguously.
+    pub occluder_edges: [Vector4<f32>; MAX_2D_SHADOW_OCCLUDER_EDGES],
 }
 
 impl<const N: usize> Default for LightData<N> {
@@ -965,10 +1391,24 @@ impl<const N: usize> Default for LightData<N> {
             position: [Default::default(); N],
             direction: [Default::default(); N],
             parameters: [Default::default(); N],
+            shadow_edges_offset: [0; N],
+            shadow_edges_count: [0; N],
+            occluder_edges: [Default::default(); MAX_2D_SHADOW_OCCLUDER_EDGES],
         }
     }
 }
 
+/// A set of matrices of the previous frame, that is used to calculate per-pixel velocity (motion
+/// vectors) in G-Buffer pass.
+pub struct VelocityData {
+    /// Local-to-clip-space transform of the previous frame without camera jitter.
+    pub previous_wvp_matrix: Matrix4<f32>,
+    /// World-to-clip-space transform of the previous frame without camera jitter.
+    pub previous_view_projection_matrix: Matrix4<f32>,
+    /// Sub-pixel offset of the current projection in normalized device coordinates.
+    pub projection_jitter: Vector2<f32>,
+}
+
 #[allow(missing_docs)] // TODO
 pub struct MaterialContext<'a, 'b, 'c> {
     pub material: &'a Material,
@@ -983,15 +1423,30 @@ pub struct MaterialContext<'a, 'b, 'c> {
     pub wvp_matrix: &'a Matrix4<f32>,
     pub bone_matrices: &'a [Matrix4<f32>],
     pub use_skeletal_animation: bool,
+    /// Whether bone matrices should be blended as dual quaternions or not.
+    pub use_dual_quaternion_skinning: bool,
+    /// World matrices of instances for instanced rendering. Empty slice means that instancing is
+    /// not used and `world_matrix` should be used instead.
+    pub instance_matrices: &'a [Matrix4<f32>],
+    /// Amount of bone matrices per instance in `bone_matrices` for instanced rendering of skinned
+    /// surfaces. Bone matrices of all instances are stored sequentially in a single palette.
+    pub instance_bone_count: usize,
+    /// Material property values that override the values of `material` for the current instance.
+    pub property_overrides: &'a [(ImmutableString, PropertyValue)],
     pub use_pom: bool,
     pub light_position: &'a Vector3<f32>,
     pub blend_shapes_storage: Option<&'a TextureResource>,
     pub blend_shapes_weights: &'a [f32],
     pub light_data: Option<&'a LightData>,
+    /// Data for per-pixel velocity calculation, available only in G-Buffer pass.
+    pub velocity_data: Option<&'a VelocityData>,
     pub ambient_light: Color,
     // TODO: Add depth pre-pass to remove Option here. Current architecture allows only forward
     // renderer to have access to depth buffer that is available from G-Buffer.
     pub scene_depth: Option<&'a Rc<RefCell<GpuTexture>>>,
+    /// A copy of the opaque part of the frame. Available only for the forward renderer, see
+    /// [`AssociatedSceneData::hdr_scene_color_framebuffer`].
+    pub scene_color: Option<&'a Rc<RefCell<GpuTexture>>>,
 
     pub camera_position: &'a Vector3<f32>,
     pub camera_up_vector: &'a Vector3<f32>,
@@ -1024,11 +1479,19 @@ pub fn apply_material(ctx: MaterialContext) {
     if let Some(location) = &built_in_uniforms[BuiltInUniform::BoneMatrices as usize] {
         let active_sampler = ctx.program_binding.active_sampler();
 
+        // Bone palette of instanced skinned surfaces must not collide with the bone matrices of the
+        // first instance, which could be drawn separately (in shadow passes, for example).
+        let id = if ctx.instance_bone_count > 0 {
+            PersistentIdentifier(ctx.persistent_identifier.0.rotate_left(32))
+        } else {
+            ctx.persistent_identifier
+        };
+
         let storage = ctx
             .matrix_storage
             .try_bind_and_upload(
                 ctx.program_binding.state,
-                ctx.persistent_identifier,
+                id,
                 ctx.bone_matrices,
                 active_sampler,
             )
@@ -1040,6 +1503,35 @@ pub fn apply_material(ctx: MaterialContext) {
         ctx.program_binding
             .set_bool(location, ctx.use_skeletal_animation);
     }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::UseDualQuaternionSkinning as usize] {
+        ctx.program_binding
+            .set_bool(location, ctx.use_dual_quaternion_skinning);
+    }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::InstanceMatrices as usize] {
+        let active_sampler = ctx.program_binding.active_sampler();
+
+        // Instance matrices are stored separately from bone matrices, instanced surfaces are never
+        // skinned, but the storages must not collide.
+        let storage = ctx
+            .matrix_storage
+            .try_bind_and_upload(
+                ctx.program_binding.state,
+                PersistentIdentifier(!ctx.persistent_identifier.0),
+                ctx.instance_matrices,
+                active_sampler,
+            )
+            .expect("Failed to upload instance matrices!");
+
+        ctx.program_binding.set_texture(location, storage.texture());
+    }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::UseInstancing as usize] {
+        ctx.program_binding
+            .set_bool(location, !ctx.instance_matrices.is_empty());
+    }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::InstanceBoneCount as usize] {
+        ctx.program_binding
+            .set_i32(location, ctx.instance_bone_count as i32);
+    }
     if let Some(location) = &built_in_uniforms[BuiltInUniform::CameraPosition as usize] {
         ctx.program_binding
             .set_vector3(location, ctx.camera_position);
@@ -1065,6 +1557,11 @@ pub fn apply_material(ctx: MaterialContext) {
             ctx.program_binding.set_texture(location, scene_depth);
         }
     }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::SceneColor as usize] {
+        if let Some(scene_color) = ctx.scene_color.as_ref() {
+            ctx.program_binding.set_texture(location, scene_color);
+        }
+    }
 
     if let Some(location) = &built_in_uniforms[BuiltInUniform::UsePOM as usize] {
         ctx.program_binding.set_bool(location, ctx.use_pom);
@@ -1074,6 +1571,24 @@ pub fn apply_material(ctx: MaterialContext) {
             .set_vector3(location, ctx.light_position);
     }
 
+    if let Some(velocity_data) = ctx.velocity_data {
+        if let Some(location) =
+            &built_in_uniforms[BuiltInUniform::PreviousWorldViewProjectionMatrix as usize]
+        {
+            ctx.program_binding
+                .set_matrix4(location, &velocity_data.previous_wvp_matrix);
+        }
+        if let Some(location) =
+            &built_in_uniforms[BuiltInUniform::PreviousViewProjectionMatrix as usize]
+        {
+            ctx.program_binding
+                .set_matrix4(location, &velocity_data.previous_view_projection_matrix);
+        }
+        if let Some(location) = &built_in_uniforms[BuiltInUniform::ProjectionJitter as usize] {
+            ctx.program_binding
+                .set_vector2(location, &velocity_data.projection_jitter);
+        }
+    }
     if let Some(light_data) = ctx.light_data {
         if let Some(location) = &built_in_uniforms[BuiltInUniform::LightCount as usize] {
             ctx.program_binding
@@ -1099,6 +1614,23 @@ pub fn apply_material(ctx: MaterialContext) {
             ctx.program_binding
                 .set_vector2_slice(location, &light_data.parameters);
         }
+
+        if let Some(location) = &built_in_uniforms[BuiltInUniform::LightsShadowEdgesOffset as usize]
+        {
+            ctx.program_binding
+                .set_i32_slice(location, &light_data.shadow_edges_offset);
+        }
+
+        if let Some(location) = &built_in_uniforms[BuiltInUniform::LightsShadowEdgesCount as usize]
+        {
+            ctx.program_binding
+                .set_i32_slice(location, &light_data.shadow_edges_count);
+        }
+
+        if let Some(location) = &built_in_uniforms[BuiltInUniform::OccluderEdges as usize] {
+            ctx.program_binding
+                .set_vector4_slice(location, &light_data.occluder_edges);
+        }
     }
 
     if let Some(location) = &built_in_uniforms[BuiltInUniform::AmbientLight as usize] {
@@ -1129,8 +1661,13 @@ pub fn apply_material(ctx: MaterialContext) {
             .set_i32(location, ctx.blend_shapes_weights.len() as i32);
     }
 
-    // Apply material properties.
-    for (name, value) in ctx.material.properties() {
+    // Apply material properties. Overridden properties are applied last, so they replace the values
+    // of the material.
+    for (name, value) in ctx.material.properties().iter().chain(
+        ctx.property_overrides
+            .iter()
+            .map(|(name, value)| (name, value)),
+    ) {
         if let Some(uniform) = ctx.program_binding.uniform_location(name) {
             match value {
                 PropertyValue::Float(v) => {
@@ -1341,15 +1878,27 @@ impl Renderer {
             quality_settings: settings,
             debug_renderer: DebugRenderer::new(&state)?,
             scene_data_map: Default::default(),
+            camera_render_targets: Default::default(),
             backbuffer_clear_color: Color::BLACK,
             texture_cache: Default::default(),
             geometry_cache: Default::default(),
             forward_renderer: ForwardRenderer::new(),
             ui_frame_buffers: Default::default(),
+            exclusive_camera: None,
+            frame_capturer: Default::default(),
             fxaa_renderer: FxaaRenderer::new(&state)?,
+            taa_renderer: TaaRenderer::new(&state)?,
+            taa_frame_index: 0,
+            taa_cameras: Default::default(),
+            upscale_renderer: UpscaleRenderer::new(&state)?,
+            render_scale: Default::default(),
             statistics: Statistics::default(),
+            render_statistics: Default::default(),
+            gpu_profiler: GpuProfiler::new(&state),
             shader_event_receiver,
             texture_event_receiver,
+            deferred_texture_upload: None,
+            upload_budget: Default::default(),
             shader_cache,
             scene_render_passes: Default::default(),
             matrix_storage: MatrixStorageCache::new(&state)?,
@@ -1388,6 +1937,12 @@ impl Renderer {
         self.statistics
     }
 
+    /// Returns structured statistics for last frame: draw calls and triangles per camera and GPU
+    /// timings of rendering stages. See [`RenderStatistics`] docs for more info.
+    pub fn render_statistics(&self) -> &RenderStatistics {
+        &self.render_statistics
+    }
+
     /// Unloads texture from GPU memory.
     pub fn unload_texture(&mut self, texture: TextureResource) {
         self.texture_cache.unload(texture)
@@ -1403,6 +1958,13 @@ impl Renderer {
         &mut self.state
     }
 
+    /// Checks whether the underlying GL context was lost. All GPU resources of the renderer are
+    /// invalid after context loss and the renderer must be re-created, see
+    /// [`crate::engine::Engine::recover_graphics_context`].
+    pub fn is_context_lost(&self) -> bool {
+        self.state.is_context_lost()
+    }
+
     /// Sets new frame size. You should call the same method on [`crate::engine::Engine`]
     /// instead, which will update the size for the user interface and rendering context
     /// as well as this one.
@@ -1448,6 +2010,53 @@ impl Renderer {
         self.quality_settings
     }
 
+    /// Sets new upload budget, see [`UploadBudget`] docs for more info.
+    pub fn set_upload_budget(&mut self, budget: UploadBudget) {
+        self.upload_budget = budget;
+    }
+
+    /// Returns current upload budget.
+    pub fn upload_budget(&self) -> UploadBudget {
+        self.upload_budget
+    }
+
+    /// Returns sub-pixel offset (in pixels) for camera projection that should be used to render the
+    /// next frame. It is always zero if temporal anti-aliasing is disabled. The engine applies the
+    /// offset to every camera from [`Self::taa_cameras`] automatically.
+    pub fn projection_jitter(&self) -> Vector2<f32> {
+        if self.quality_settings.taa {
+            // Jitter must be in the pixels of scaled frame.
+            taa::projection_jitter(self.taa_frame_index) / self.render_scale.scale
+        } else {
+            Vector2::default()
+        }
+    }
+
+    /// Returns the cameras (and their scenes), that were rendered with temporal anti-aliasing in the
+    /// last frame. Only these cameras need projection jitter.
+    pub fn taa_cameras(&self) -> &FxHashSet<(Handle<Scene>, Handle<Node>)> {
+        &self.taa_cameras
+    }
+
+    /// Returns render scale that is used to render scenes (that does not have a render target), see
+    /// [`DynamicResolutionSettings`] for more info.
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale.scale
+    }
+
+    fn update_render_scale(&mut self) {
+        // GPU timings lag a few frames behind, but it is fine, because render scale is changed with
+        // a delay anyway.
+        let frame_time = self
+            .gpu_profiler
+            .timings()
+            .map_or(self.statistics.pure_frame_time, |timings| {
+                timings.total().as_secs_f32()
+            });
+        self.render_scale
+            .update(&self.quality_settings.dynamic_resolution, frame_time);
+    }
+
     /// Removes all cached GPU data, forces renderer to re-upload data to GPU.
     /// Do not call this method until you absolutely need! It may cause **significant**
     /// performance lag!
@@ -1526,30 +2135,380 @@ impl Renderer {
         Ok(())
     }
 
-    fn update_texture_cache(&mut self, dt: f32) {
-        // Maximum amount of textures uploaded to GPU per frame. This defines throughput **only** for
-        // requests from resource manager. This is needed to prevent huge lag when there are tons of
-        // requests, so this is some kind of work load balancer.
-        const THROUGHPUT: usize = 5;
+    /// Requests a capture of the next rendered frame. The frame is captured as it is shown on the
+    /// screen (including the UI) and could be fetched from the returned request using
+    /// [`FrameCaptureRequest::take`] once it is ready. See [`FrameCaptureMode`] docs for the
+    /// difference between capture modes. Use [`capture::FrameSequenceDumper`] to capture a sequence
+    /// of frames.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use fyrox::{
+    /// #     engine::Engine,
+    /// #     renderer::capture::{FrameCaptureMode, FrameCaptureRequest},
+    /// # };
+    /// fn request_save_game_thumbnail(engine: &mut Engine) -> FrameCaptureRequest {
+    ///     let graphics_context = engine.graphics_context.as_initialized_mut();
+    ///     graphics_context
+    ///         .renderer
+    ///         .capture_frame(FrameCaptureMode::Delayed)
+    /// }
+    ///
+    /// // Call this every frame until it returns `true`.
+    /// fn try_save_thumbnail(request: &FrameCaptureRequest) -> bool {
+    ///     if let Some(image) = request.take() {
+    ///         image.save("save_game.png").unwrap();
+    ///         true
+    ///     } else {
+    ///         false
+    ///     }
+    /// }
+    /// ```
+    pub fn capture_frame(&mut self, mode: FrameCaptureMode) -> FrameCaptureRequest {
+        self.frame_capturer.request(mode)
+    }
+
+    /// Renders a frame of the given scene using the given camera in `scale` times higher resolution
+    /// than `tile_size` (for example, `scale = 4` and `tile_size = 1920x1080` gives 7680x4320 image).
+    /// The view frustum of the camera is split into `scale x scale` sub-frusta, each sub-frustum is
+    /// rendered off-screen into a separate tile and the tiles are then stitched together. This way the
+    /// size of the resulting image is not limited by the size of the window or maximum size of GPU
+    /// textures, which makes it useful for marketing shots and print assets. Only the viewport of the
+    /// camera is captured, the UI is not included.
+    ///
+    /// ## Limitations
+    ///
+    /// Temporal anti-aliasing is disabled during capture, because its history cannot be shared
+    /// between tiles. Screen-space effects (bloom, ambient occlusion, automatic exposure) are computed
+    /// per tile, so they may produce visible seams between tiles. Use manual exposure for the best
+    /// results.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use fyrox::{
+    /// #     core::{algebra::Vector2, pool::Handle},
+    /// #     engine::Engine,
+    /// #     scene::{node::Node, Scene},
+    /// # };
+    /// fn take_screenshot(engine: &mut Engine, scene: Handle<Scene>, camera: Handle<Node>) {
+    ///     let graphics_context = engine.graphics_context.as_initialized_mut();
+    ///     let image = graphics_context
+    ///         .renderer
+    ///         .render_tiled_screenshot(&mut engine.scenes, scene, camera, Vector2::new(1920, 1080), 4)
+    ///         .unwrap();
+    ///     image.save("screenshot.png").unwrap();
+    /// }
+    /// ```
+    pub fn render_tiled_screenshot(
+        &mut self,
+        scenes: &mut SceneContainer,
+        scene_handle: Handle<Scene>,
+        camera_handle: Handle<Node>,
+        tile_size: Vector2<u32>,
+        scale: u32,
+    ) -> Result<RgbaImage, FrameworkError> {
+        let scale = scale.max(1);
+        let tile_size = tile_size.sup(&Vector2::new(1, 1));
+        let frame_size = Vector2::new(tile_size.x as f32, tile_size.y as f32);
+
+        let scene = scenes.try_get_mut(scene_handle).ok_or_else(|| {
+            FrameworkError::Custom(format!("Invalid scene handle {scene_handle}!"))
+        })?;
+        let camera = scene
+            .graph
+            .try_get_mut(camera_handle)
+            .and_then(|node| node.cast_mut::<Camera>())
+            .ok_or_else(|| {
+                FrameworkError::Custom(format!("{camera_handle} is not a valid camera handle!"))
+            })?;
+
+        let region = camera.viewport_pixels(frame_size);
+        let old_jitter = camera.set_projection_jitter(Vector2::default());
+        let old_render_target = scene
+            .rendering_options
+            .render_target
+            .replace(TextureResource::new_render_target(tile_size.x, tile_size.y));
+
+        let old_quality_settings = self.quality_settings;
+        self.quality_settings.taa = false;
+        self.exclusive_camera = Some((scene_handle, camera_handle));
+
+        let mut image = RgbaImage::new(region.w() as u32 * scale, region.h() as u32 * scale);
+        let result = self.render_tiles(
+            scenes,
+            scene_handle,
+            camera_handle,
+            frame_size,
+            region,
+            scale,
+            &mut image,
+        );
 
+        self.exclusive_camera = None;
+        self.quality_settings = old_quality_settings;
+        if let Some(scene) = scenes.try_get_mut(scene_handle) {
+            scene.rendering_options.render_target = old_render_target;
+            if let Some(camera) = scene
+                .graph
+                .try_get_mut(camera_handle)
+                .and_then(|node| node.cast_mut::<Camera>())
+            {
+                camera.set_sub_frustum(None);
+                camera.set_projection_jitter(old_jitter);
+            }
+        }
+
+        result.map(|_| image)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_tiles(
+        &mut self,
+        scenes: &mut SceneContainer,
+        scene_handle: Handle<Scene>,
+        camera_handle: Handle<Node>,
+        frame_size: Vector2<f32>,
+        region: Rect<i32>,
+        scale: u32,
+        image: &mut RgbaImage,
+    ) -> Result<(), FrameworkError> {
+        let tile_width = region.w() as u32;
+        let tile_height = region.h() as u32;
+        let tile_fraction = 1.0 / scale as f32;
+        let drawing_context = DrawingContext::new();
+
+        // Rows are counted from the bottom, as in OpenGL.
+        for row in 0..scale {
+            for column in 0..scale {
+                if let Some(camera) = scenes
+                    .try_get_mut(scene_handle)
+                    .and_then(|scene| scene.graph.try_get_mut(camera_handle))
+                    .and_then(|node| node.cast_mut::<Camera>())
+                {
+                    camera.set_sub_frustum(Some(Rect::new(
+                        column as f32 * tile_fraction,
+                        row as f32 * tile_fraction,
+                        tile_fraction,
+                        tile_fraction,
+                    )));
+                    camera.calculate_matrices(frame_size);
+                }
+
+                self.render_frame(scenes, &drawing_context)?;
+
+                let pixels = self
+                    .scene_data_map
+                    .get(&scene_handle)
+                    .map(|data| {
+                        data.ldr_scene_framebuffer
+                            .read_pixels(&self.state, 0, region)
+                    })
+                    .ok_or_else(|| FrameworkError::Custom("Scene was not rendered!".to_string()))?;
+
+                for (y, pixel_row) in pixels.chunks_exact(tile_width as usize * 4).enumerate() {
+                    // Read rows are stored from bottom to top, but the image is stored from top
+                    // to bottom.
+                    let image_y = (scale - 1 - row) * tile_height + (tile_height - 1 - y as u32);
+                    for (x, pixel) in pixel_row.chunks_exact(4).enumerate() {
+                        image.put_pixel(
+                            column * tile_width + x as u32,
+                            image_y,
+                            Rgba([pixel[0], pixel[1], pixel[2], 255]),
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Captures a cube map from the given point of the scene. The cube map is rendered with a temporary
+    /// camera, that is created using the given camera builder, so it is possible to specify skybox,
+    /// exposure, clipping planes and other camera settings. Field of view, viewport and position of
+    /// the camera are set automatically. If `hdr` is `true`, the cube map is captured before tone
+    /// mapping (in linear color space without clamping), otherwise the final (tone mapped) image is
+    /// captured. The result could be converted to a cube map texture (for skyboxes and reflection
+    /// sources) or to an equirectangular panorama, see [`CubeMapCapture`] docs for more info.
+    ///
+    /// Temporal anti-aliasing is disabled during capture.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use fyrox::{
+    /// #     core::{algebra::Vector3, pool::Handle},
+    /// #     engine::Engine,
+    /// #     scene::{base::BaseBuilder, camera::CameraBuilder, Scene},
+    /// # };
+    /// fn capture_panorama(engine: &mut Engine, scene: Handle<Scene>) {
+    ///     let graphics_context = engine.graphics_context.as_initialized_mut();
+    ///     let cube_map = graphics_context
+    ///         .renderer
+    ///         .capture_cube_map(
+    ///             &mut engine.scenes,
+    ///             scene,
+    ///             CameraBuilder::new(BaseBuilder::new()),
+    ///             Vector3::new(0.0, 2.0, 0.0),
+    ///             1024,
+    ///             false,
+    ///         )
+    ///         .unwrap();
+    ///     let panorama = cube_map.to_equirectangular(4096);
+    ///     image::DynamicImage::ImageRgba32F(panorama)
+    ///         .to_rgba8()
+    ///         .save("panorama.png")
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn capture_cube_map(
+        &mut self,
+        scenes: &mut SceneContainer,
+        scene_handle: Handle<Scene>,
+        camera: CameraBuilder,
+        position: Vector3<f32>,
+        face_size: u32,
+        hdr: bool,
+    ) -> Result<CubeMapCapture, FrameworkError> {
+        let face_size = face_size.max(1);
+
+        let scene = scenes.try_get_mut(scene_handle).ok_or_else(|| {
+            FrameworkError::Custom(format!("Invalid scene handle {scene_handle}!"))
+        })?;
+        let camera_handle = camera
+            .with_fov(std::f32::consts::FRAC_PI_2)
+            .with_viewport(Rect::new(0.0, 0.0, 1.0, 1.0))
+            .build(&mut scene.graph);
+        scene.graph[camera_handle]
+            .local_transform_mut()
+            .set_position(position);
+        let old_render_target = scene
+            .rendering_options
+            .render_target
+            .replace(TextureResource::new_render_target(face_size, face_size));
+
+        let old_quality_settings = self.quality_settings;
+        self.quality_settings.taa = false;
+        self.exclusive_camera = Some((scene_handle, camera_handle));
+
+        let result =
+            self.render_cube_map_faces(scenes, scene_handle, camera_handle, face_size, hdr);
+
+        self.exclusive_camera = None;
+        self.quality_settings = old_quality_settings;
+        if let Some(scene) = scenes.try_get_mut(scene_handle) {
+            scene.rendering_options.render_target = old_render_target;
+            scene.graph.remove_node(camera_handle);
+        }
+
+        result
+    }
+
+    fn render_cube_map_faces(
+        &mut self,
+        scenes: &mut SceneContainer,
+        scene_handle: Handle<Scene>,
+        camera_handle: Handle<Node>,
+        face_size: u32,
+        hdr: bool,
+    ) -> Result<CubeMapCapture, FrameworkError> {
+        let frame_size = Vector2::new(face_size as f32, face_size as f32);
+        let region = Rect::new(0, 0, face_size as i32, face_size as i32);
+        let drawing_context = DrawingContext::new();
+        let mut faces: [Vec<f32>; 6] = Default::default();
+
+        for (face, (look, up)) in faces.iter_mut().zip(panorama::cube_map_faces()) {
+            if let Some(scene) = scenes.try_get_mut(scene_handle) {
+                scene.graph[camera_handle]
+                    .local_transform_mut()
+                    .set_rotation(UnitQuaternion::face_towards(&look, &up));
+                scene.graph.update_hierarchical_data();
+                if let Some(camera) = scene.graph[camera_handle].cast_mut::<Camera>() {
+                    camera.calculate_matrices(frame_size);
+                }
+            }
+
+            self.render_frame(scenes, &drawing_context)?;
+
+            let data = self
+                .scene_data_map
+                .get(&scene_handle)
+                .ok_or_else(|| FrameworkError::Custom("Scene was not rendered!".to_string()))?;
+
+            *face = if hdr {
+                data.hdr_scene_framebuffer
+                    .read_pixels_f32(&self.state, 0, region)
+            } else {
+                data.ldr_scene_framebuffer
+                    .read_pixels(&self.state, 0, region)
+                    .into_iter()
+                    .map(|value| value as f32 / 255.0)
+                    .collect()
+            };
+        }
+
+        Ok(CubeMapCapture {
+            face_size,
+            hdr,
+            faces,
+        })
+    }
+
+    fn update_texture_cache(&mut self, dt: f32) {
+        // The amount of textures uploaded to GPU per frame is limited by the upload budget. It defines
+        // throughput **only** for requests from resource manager. This is needed to prevent huge lag when
+        // there are tons of requests, so this is some kind of work load balancer.
         let mut uploaded = 0;
-        while let Ok(event) = self.texture_event_receiver.try_recv() {
-            if let ResourceEvent::Loaded(resource) | ResourceEvent::Reloaded(resource) = event {
-                if let Some(texture) = resource.try_cast::<Texture>() {
-                    match self.texture_cache.upload(&self.state, &texture) {
-                        Ok(_) => {
-                            uploaded += 1;
-                            if uploaded >= THROUGHPUT {
-                                break;
-                            }
-                        }
-                        Err(e) => {
-                            Log::writeln(
-                                MessageKind::Error,
-                                format!("Failed to upload texture to GPU. Reason: {:?}", e),
-                            );
+        let mut uploaded_bytes = 0;
+        loop {
+            let texture = match self.deferred_texture_upload.take() {
+                Some(texture) => texture,
+                None => match self.texture_event_receiver.try_recv() {
+                    Ok(ResourceEvent::Loaded(resource) | ResourceEvent::Reloaded(resource)) => {
+                        match resource.try_cast::<Texture>() {
+                            Some(texture) => texture,
+                            None => continue,
                         }
                     }
+                    Ok(_) => continue,
+                    Err(_) => break,
+                },
+            };
+
+            let size = texture
+                .state()
+                .data()
+                .map_or(0, |texture| texture.data().len());
+            if uploaded > 0
+                && self
+                    .upload_budget
+                    .max_texture_bytes_per_frame
+                    .is_some_and(|max_bytes| uploaded_bytes + size > max_bytes)
+            {
+                self.deferred_texture_upload = Some(texture);
+                break;
+            }
+
+            match self.texture_cache.upload(&self.state, &texture) {
+                Ok(_) => {
+                    uploaded += 1;
+                    uploaded_bytes += size;
+                    if uploaded >= self.upload_budget.max_textures_per_frame
+                        || self
+                            .upload_budget
+                            .max_texture_bytes_per_frame
+                            .is_some_and(|max_bytes| uploaded_bytes >= max_bytes)
+                    {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    Log::writeln(
+                        MessageKind::Error,
+                        format!("Failed to upload texture to GPU. Reason: {:?}", e),
+                    );
                 }
             }
         }
@@ -1597,6 +2556,15 @@ impl Renderer {
         // Make sure to drop associated data for destroyed scenes.
         self.scene_data_map
             .retain(|h, _| scenes.is_valid_handle(*h));
+        self.camera_render_targets
+            .retain(|(scene_handle, camera_handle), _| {
+                scenes.try_get(*scene_handle).is_some_and(|scene| {
+                    scene
+                        .graph
+                        .try_get_of_type::<Camera>(*camera_handle)
+                        .is_some_and(|camera| camera.render_target().is_some())
+                })
+            });
 
         // We have to invalidate resource bindings cache because some textures or programs,
         // or other GL resources can be destroyed and then on their "names" some new resource
@@ -1604,7 +2572,11 @@ impl Renderer {
         // object have same name.
         self.state.invalidate_resource_bindings_cache();
         let dt = self.statistics.capped_frame_time;
+        self.update_render_scale();
         self.statistics.begin_frame();
+        self.render_statistics.cameras.clear();
+        self.taa_frame_index = self.taa_frame_index.wrapping_add(1);
+        self.taa_cameras.clear();
 
         let window_viewport = Rect::new(0, 0, self.frame_size.0 as i32, self.frame_size.1 as i32);
         self.backbuffer.clear(
@@ -1618,16 +2590,38 @@ impl Renderer {
         let backbuffer_width = self.frame_size.0 as f32;
         let backbuffer_height = self.frame_size.1 as f32;
 
-        for (scene_handle, scene) in scenes.pair_iter().filter(|(_, s)| *s.enabled) {
+        let exclusive_camera = self.exclusive_camera;
+
+        for (scene_handle, scene) in scenes.pair_iter().filter(|(handle, s)| {
+            *s.enabled
+                && exclusive_camera.map_or(true, |(exclusive_scene, _)| exclusive_scene == *handle)
+        }) {
             let graph = &scene.graph;
 
+            let quality_settings = if scene.is_2d() {
+                self.quality_settings.without_3d_effects()
+            } else {
+                self.quality_settings
+            };
+
+            let render_scale = if scene.rendering_options.render_target.is_some() {
+                1.0
+            } else {
+                self.render_scale.scale
+            };
+
             let frame_size = scene
                 .rendering_options
                 .render_target
                 .as_ref()
                 .map_or_else(
-                    // Use either backbuffer size
-                    || Vector2::new(backbuffer_width, backbuffer_height),
+                    // Use either scaled backbuffer size
+                    || {
+                        Vector2::new(
+                            (backbuffer_width * render_scale).round(),
+                            (backbuffer_height * render_scale).round(),
+                        )
+                    },
                     // Or framebuffer size
                     |rt| {
                         if let TextureKind::Rectangle { width, height } = rt.data_ref().kind() {
@@ -1689,11 +2683,110 @@ impl Renderer {
                 );
             }
 
-            for camera in graph
-                .linear_iter()
-                .filter_map(|node| node.cast::<Camera>().filter(|&camera| camera.is_enabled()))
-            {
+            // Drop temporal history of deleted cameras.
+            scene_associated_data
+                .taa_history
+                .retain(|camera, _| graph.is_valid_handle(*camera));
+            scene_associated_data
+                .ao_history
+                .retain(|camera, _| graph.is_valid_handle(*camera));
+            scene_associated_data
+                .motion_history
+                .retain_cameras(|camera| graph.is_valid_handle(camera));
+
+            let mut cameras = graph
+                .pair_iter()
+                .filter_map(|(handle, node)| {
+                    node.cast::<Camera>()
+                        .filter(|&camera| {
+                            camera.is_enabled()
+                                && exclusive_camera.map_or(true, |(_, exclusive_camera)| {
+                                    exclusive_camera == handle
+                                })
+                        })
+                        .map(|camera| (handle, camera))
+                })
+                .collect::<Vec<_>>();
+            // Cameras with render targets must be rendered first, so their frames could be used
+            // by other cameras in the same frame.
+            cameras.sort_by_key(|(_, camera)| camera.render_target().is_none());
+
+            for (camera_handle, camera) in cameras {
+                // Render targets of cameras are ignored when rendering with an exclusive camera
+                // (screenshots, cube map captures, etc.).
+                let camera_render_target = camera
+                    .render_target()
+                    .filter(|_| exclusive_camera.is_none());
+
+                let (scene_associated_data, frame_size, render_scale) =
+                    if let Some(render_target) = camera_render_target {
+                        let rt_size = match render_target.state().data().map(|rt| rt.kind()) {
+                            Some(TextureKind::Rectangle { width, height }) => {
+                                Vector2::new(width.max(1), height.max(1))
+                            }
+                            _ => continue,
+                        };
+
+                        let camera_data = match self
+                            .camera_render_targets
+                            .entry((scene_handle, camera_handle))
+                        {
+                            Entry::Occupied(entry) => entry.into_mut(),
+                            Entry::Vacant(entry) => entry.insert(CameraRenderTargetData {
+                                data: AssociatedSceneData::new(
+                                    state,
+                                    rt_size.x as usize,
+                                    rt_size.y as usize,
+                                )?,
+                                time_since_update: f32::MAX,
+                            }),
+                        };
+                        if camera_data.data.gbuffer.width != rt_size.x as i32
+                            || camera_data.data.gbuffer.height != rt_size.y as i32
+                        {
+                            camera_data.data = AssociatedSceneData::new(
+                                state,
+                                rt_size.x as usize,
+                                rt_size.y as usize,
+                            )?;
+                            camera_data.time_since_update = f32::MAX;
+                        }
+
+                        camera_data.time_since_update += dt;
+                        if camera_data.time_since_update < camera.render_target_update_interval() {
+                            continue;
+                        }
+                        camera_data.time_since_update = 0.0;
+
+                        // Register the frame of the camera in the texture cache, so it could be
+                        // used as a texture by materials and the UI.
+                        self.texture_cache.map.spawn(
+                            TextureRenderData {
+                                gpu_texture: match camera.render_target_format() {
+                                    RenderTargetFormat::Ldr => {
+                                        camera_data.data.ldr_scene_frame_texture()
+                                    }
+                                    RenderTargetFormat::Hdr => {
+                                        camera_data.data.hdr_scene_frame_texture()
+                                    }
+                                },
+                                data_hash: 0,
+                            },
+                            render_target.data_ref().cache_index.clone(),
+                            TimeToLive(f32::INFINITY),
+                        );
+
+                        (
+                            &mut camera_data.data,
+                            Vector2::new(rt_size.x as f32, rt_size.y as f32),
+                            1.0,
+                        )
+                    } else {
+                        (&mut *scene_associated_data, frame_size, render_scale)
+                    };
+
                 let viewport = camera.viewport_pixels(frame_size);
+                let geometry_before = self.statistics.geometry;
 
                 let batch_storage = RenderDataBatchStorage::from_graph(
                     graph,
@@ -1703,6 +2796,8 @@ impl Renderer {
                         z_far: camera.projection().z_far(),
                         view_matrix: camera.view_matrix(),
                         projection_matrix: camera.projection_matrix(),
+                        render_mask: camera.render_mask(),
+                        lod_camera: camera_handle,
                     },
                     GBUFFER_PASS_NAME.clone(),
                 );
@@ -1712,15 +2807,27 @@ impl Renderer {
                     scene.rendering_options.polygon_rasterization_mode,
                 );
 
+                self.gpu_profiler.begin(state, RenderStage::GBuffer);
+                scene_associated_data
+                    .motion_history
+                    .begin_frame(self.taa_frame_index);
+                let unjittered_view_projection = camera.unjittered_view_projection_matrix();
+                let previous_view_projection = scene_associated_data
+                    .motion_history
+                    .previous_view_projection(camera_handle, unjittered_view_projection);
                 self.statistics += scene_associated_data.gbuffer.fill(GBufferRenderContext {
                     state,
                     camera,
+                    previous_view_projection,
+                    previous_world_matrices: scene_associated_data
+                        .motion_history
+                        .previous_world_matrices(),
                     geom_cache: &mut self.geometry_cache,
                     batch_storage: &batch_storage,
                     texture_cache: &mut self.texture_cache,
                     shader_cache: &mut self.shader_cache,
                     environment_dummy: self.environment_dummy.clone(),
-                    use_parallax_mapping: self.quality_settings.use_parallax_mapping,
+                    use_parallax_mapping: quality_settings.use_parallax_mapping,
                     normal_dummy: self.normal_dummy.clone(),
                     white_dummy: self.white_dummy.clone(),
                     black_dummy: self.black_dummy.clone(),
@@ -1728,6 +2835,12 @@ impl Renderer {
                     graph,
                     matrix_storage: &mut self.matrix_storage,
                 })?;
+                scene_associated_data.motion_history.store(
+                    camera_handle,
+                    unjittered_view_projection,
+                    &batch_storage,
+                );
+                self.gpu_profiler.end(state);
 
                 state.set_polygon_fill_mode(PolygonFace::FrontAndBack, PolygonFillMode::Fill);
 
@@ -1746,6 +2859,7 @@ impl Renderer {
                     Some(0),
                 );
 
+                self.gpu_profiler.begin(state, RenderStage::Lighting);
                 let (pass_stats, light_stats) =
                     self.deferred_light_renderer
                         .render(DeferredRendererContext {
@@ -1755,7 +2869,7 @@ impl Renderer {
                             gbuffer: &mut scene_associated_data.gbuffer,
                             white_dummy: self.white_dummy.clone(),
                             ambient_color: scene.rendering_options.ambient_lighting_color,
-                            settings: &self.quality_settings,
+                            settings: &quality_settings,
                             textures: &mut self.texture_cache,
                             geometry_cache: &mut self.geometry_cache,
                             frame_buffer: &mut scene_associated_data.hdr_scene_framebuffer,
@@ -1763,25 +2877,66 @@ impl Renderer {
                             normal_dummy: self.normal_dummy.clone(),
                             black_dummy: self.black_dummy.clone(),
                             volume_dummy: self.volume_dummy.clone(),
+                            environment_dummy: self.environment_dummy.clone(),
                             matrix_storage: &mut self.matrix_storage,
+                            gpu_profiler: &mut self.gpu_profiler,
+                            ao_history: scene_associated_data
+                                .ao_history
+                                .entry(camera_handle)
+                                .or_default(),
                         })?;
 
                 self.statistics.lighting += light_stats;
                 self.statistics.geometry += pass_stats;
 
+                for render_pass in self.scene_render_passes.iter() {
+                    self.statistics +=
+                        render_pass
+                            .borrow_mut()
+                            .on_opaque_render(SceneRenderPassContext {
+                                pipeline_state: state,
+                                texture_cache: &mut self.texture_cache,
+                                geometry_cache: &mut self.geometry_cache,
+                                shader_cache: &mut self.shader_cache,
+                                quality_settings: &quality_settings,
+                                batch_storage: &batch_storage,
+                                viewport,
+                                scene,
+                                camera,
+                                scene_handle,
+                                white_dummy: self.white_dummy.clone(),
+                                normal_dummy: self.normal_dummy.clone(),
+                                metallic_dummy: self.metallic_dummy.clone(),
+                                environment_dummy: self.environment_dummy.clone(),
+                                black_dummy: self.black_dummy.clone(),
+                                volume_dummy: self.volume_dummy.clone(),
+                                depth_texture: scene_associated_data.gbuffer.depth(),
+                                normal_texture: scene_associated_data.gbuffer.normal_texture(),
+                                diffuse_texture: scene_associated_data.gbuffer.diffuse_texture(),
+                                material_texture: scene_associated_data.gbuffer.material_texture(),
+                                ambient_texture: scene_associated_data.gbuffer.ambient_texture(),
+                                framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
+                                ui_renderer: &mut self.ui_renderer,
+                                matrix_storage: &mut self.matrix_storage,
+                            })?;
+                }
+
+                self.gpu_profiler.end(state);
+
                 let depth = scene_associated_data.gbuffer.depth();
 
+                self.gpu_profiler.begin(state, RenderStage::Forward);
                 self.statistics += self.forward_renderer.render(ForwardRenderContext {
                     state,
-                    graph,
                     camera,
                     geom_cache: &mut self.geometry_cache,
                     texture_cache: &mut self.texture_cache,
                     shader_cache: &mut self.shader_cache,
                     batch_storage: &batch_storage,
                     framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
+                    scene_color_framebuffer: &scene_associated_data.hdr_scene_color_framebuffer,
                     viewport,
-                    quality_settings: &self.quality_settings,
+                    quality_settings: &quality_settings,
                     white_dummy: self.white_dummy.clone(),
                     normal_dummy: self.normal_dummy.clone(),
                     black_dummy: self.black_dummy.clone(),
@@ -1800,7 +2955,7 @@ impl Renderer {
                                 texture_cache: &mut self.texture_cache,
                                 geometry_cache: &mut self.geometry_cache,
                                 shader_cache: &mut self.shader_cache,
-                                quality_settings: &self.quality_settings,
+                                quality_settings: &quality_settings,
                                 batch_storage: &batch_storage,
                                 viewport,
                                 scene,
@@ -1814,6 +2969,8 @@ impl Renderer {
                                 volume_dummy: self.volume_dummy.clone(),
                                 depth_texture: scene_associated_data.gbuffer.depth(),
                                 normal_texture: scene_associated_data.gbuffer.normal_texture(),
+                                diffuse_texture: scene_associated_data.gbuffer.diffuse_texture(),
+                                material_texture: scene_associated_data.gbuffer.material_texture(),
                                 ambient_texture: scene_associated_data.gbuffer.ambient_texture(),
                                 framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
                                 ui_renderer: &mut self.ui_renderer,
@@ -1821,9 +2978,12 @@ impl Renderer {
                             })?;
                 }
 
+                self.gpu_profiler.end(state);
+
                 let quad = &self.quad;
 
                 // Prepare glow map.
+                self.gpu_profiler.begin(state, RenderStage::PostProcessing);
                 self.statistics.geometry += scene_associated_data.bloom_renderer.render(
                     state,
                     quad,
@@ -1845,8 +3005,46 @@ impl Renderer {
                     &mut self.texture_cache,
                 )?;
 
-                // Apply FXAA if needed.
-                if self.quality_settings.fxaa {
+                // Apply TAA or FXAA if needed.
+                if quality_settings.taa {
+                    self.taa_cameras.insert((scene_handle, camera_handle));
+
+                    let frame_texture = scene_associated_data.ldr_scene_frame_texture();
+                    let depth_texture = scene_associated_data.gbuffer.depth();
+                    let velocity_texture = scene_associated_data.gbuffer.velocity_texture();
+                    let taa_history = match scene_associated_data.taa_history.entry(camera_handle) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => entry.insert(TaaHistory::new(
+                            state,
+                            frame_size.x as usize,
+                            frame_size.y as usize,
+                        )?),
+                    };
+
+                    self.statistics.geometry += self.taa_renderer.render(TaaRenderContext {
+                        state,
+                        viewport,
+                        frame_texture,
+                        depth_texture,
+                        velocity_texture,
+                        view_projection: camera.view_projection_matrix(),
+                        previous_view_projection,
+                        jitter: camera.projection_jitter() * render_scale,
+                        history: taa_history,
+                        frame_buffer: &mut scene_associated_data.ldr_temp_framebuffer,
+                    })?;
+
+                    let quad = &self.quad;
+                    let temp_frame_texture = scene_associated_data.ldr_temp_frame_texture();
+                    self.statistics.geometry += blit_pixels(
+                        state,
+                        &mut scene_associated_data.ldr_scene_framebuffer,
+                        temp_frame_texture,
+                        &self.flat_shader,
+                        viewport,
+                        quad,
+                    )?;
+                } else if quality_settings.fxaa {
                     self.statistics.geometry += self.fxaa_renderer.render(
                         state,
                         viewport,
@@ -1884,7 +3082,7 @@ impl Renderer {
                                 texture_cache: &mut self.texture_cache,
                                 geometry_cache: &mut self.geometry_cache,
                                 shader_cache: &mut self.shader_cache,
-                                quality_settings: &self.quality_settings,
+                                quality_settings: &quality_settings,
                                 batch_storage: &batch_storage,
                                 viewport,
                                 scene,
@@ -1898,25 +3096,53 @@ impl Renderer {
                                 volume_dummy: self.volume_dummy.clone(),
                                 depth_texture: scene_associated_data.gbuffer.depth(),
                                 normal_texture: scene_associated_data.gbuffer.normal_texture(),
+                                diffuse_texture: scene_associated_data.gbuffer.diffuse_texture(),
+                                material_texture: scene_associated_data.gbuffer.material_texture(),
                                 ambient_texture: scene_associated_data.gbuffer.ambient_texture(),
                                 framebuffer: &mut scene_associated_data.ldr_scene_framebuffer,
                                 ui_renderer: &mut self.ui_renderer,
                                 matrix_storage: &mut self.matrix_storage,
                             })?;
                 }
+
+                self.gpu_profiler.end(state);
+
+                let geometry = self.statistics.geometry;
+                self.render_statistics.cameras.push(CameraStatistics {
+                    scene: scene_handle,
+                    camera: camera_handle,
+                    geometry: RenderPassStatistics {
+                        draw_calls: geometry.draw_calls - geometry_before.draw_calls,
+                        triangles_rendered: geometry.triangles_rendered
+                            - geometry_before.triangles_rendered,
+                    },
+                    lighting: light_stats,
+                });
             }
 
             // Optionally render everything into back buffer.
             if scene.rendering_options.render_target.is_none() {
+                self.gpu_profiler.begin(state, RenderStage::PostProcessing);
                 let quad = &self.quad;
-                self.statistics.geometry += blit_pixels(
-                    state,
-                    &mut self.backbuffer,
-                    scene_associated_data.ldr_scene_frame_texture(),
-                    &self.flat_shader,
-                    window_viewport,
-                    quad,
-                )?;
+                if render_scale < 1.0 {
+                    self.statistics.geometry += self.upscale_renderer.render(
+                        state,
+                        quad,
+                        window_viewport,
+                        scene_associated_data.ldr_scene_frame_texture(),
+                        &mut self.backbuffer,
+                    )?;
+                } else {
+                    self.statistics.geometry += blit_pixels(
+                        state,
+                        &mut self.backbuffer,
+                        scene_associated_data.ldr_scene_frame_texture(),
+                        &self.flat_shader,
+                        window_viewport,
+                        quad,
+                    )?;
+                }
+                self.gpu_profiler.end(state);
             }
         }
 
@@ -1924,6 +3150,7 @@ impl Renderer {
             .set_polygon_fill_mode(PolygonFace::FrontAndBack, PolygonFillMode::Fill);
 
         // Render UI on top of everything without gamma correction.
+        self.gpu_profiler.begin(&self.state, RenderStage::Ui);
         self.statistics += self.ui_renderer.render(UiRenderContext {
             state: &mut self.state,
             viewport: window_viewport,
@@ -1934,6 +3161,10 @@ impl Renderer {
             white_dummy: self.white_dummy.clone(),
             texture_cache: &mut self.texture_cache,
         })?;
+        self.gpu_profiler.end(&self.state);
+
+        self.gpu_profiler.end_frame(&self.state);
+        self.render_statistics.gpu_timings = self.gpu_profiler.timings();
 
         Ok(())
     }
@@ -1948,6 +3179,8 @@ impl Renderer {
         window: &Window,
     ) -> Result<(), FrameworkError> {
         self.render_frame(scenes, drawing_context)?;
+        self.frame_capturer
+            .process(&self.state, &self.backbuffer, self.frame_size)?;
         self.statistics.end_frame();
         window.pre_present_notify();
         surface.swap_buffers(context)?;
@@ -1963,9 +3196,67 @@ impl Renderer {
         drawing_context: &DrawingContext,
     ) -> Result<(), FrameworkError> {
         self.render_frame(scenes, drawing_context)?;
+        self.frame_capturer
+            .process(&self.state, &self.backbuffer, self.frame_size)?;
         self.statistics.end_frame();
         self.statistics.finalize();
         self.statistics.pipeline = self.state.pipeline_statistics();
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::{DynamicResolutionSettings, RenderScaleController};
+
+    #[test]
+    fn test_render_scale_controller() {
+        let settings = DynamicResolutionSettings {
+            automatic: true,
+            min_render_scale: 0.5,
+            max_render_scale: 1.0,
+            target_frame_time: 0.01,
+            ..Default::default()
+        };
+        let mut controller = RenderScaleController::default();
+
+        // Too slow - the scale goes down, then waits for the cooldown.
+        controller.update(&settings, 0.02);
+        assert_eq!(controller.scale, 0.95);
+        for _ in 0..RenderScaleController::COOLDOWN_FRAMES {
+            controller.update(&settings, 0.02);
+            assert_eq!(controller.scale, 0.95);
+        }
+        controller.update(&settings, 0.02);
+        assert_eq!(controller.scale, 0.9);
+
+        // Within the target range - nothing changes.
+        controller.cooldown = 0;
+        controller.update(&settings, 0.0095);
+        assert_eq!(controller.scale, 0.9);
+
+        // Fast enough - the scale goes up, but never above the maximum.
+        for _ in 0..10 {
+            controller.cooldown = 0;
+            controller.update(&settings, 0.001);
+        }
+        assert_eq!(controller.scale, 1.0);
+
+        // Never below the minimum.
+        for _ in 0..20 {
+            controller.cooldown = 0;
+            controller.update(&settings, 1.0);
+        }
+        assert_eq!(controller.scale, 0.5);
+
+        // Manual mode uses the scale from the settings.
+        controller.update(
+            &DynamicResolutionSettings {
+                render_scale: 0.1,
+                ..Default::default()
+            },
+            1.0,
+        );
+        assert_eq!(controller.scale, 0.25);
+    }
+}
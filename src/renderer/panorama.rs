@@ -0,0 +1,198 @@
+//! Cube map and equirectangular panorama capture. See [`crate::renderer::Renderer::capture_cube_map`]
+//! for more info.
+
+use crate::{
+    asset::untyped::ResourceKind,
+    core::algebra::Vector3,
+    resource::texture::{Texture, TextureKind, TexturePixelKind, TextureResource},
+};
+use image::{Rgba, Rgba32FImage};
+use std::f32::consts::PI;
+
+/// Returns look and up vectors for every face of a cube map in the order of OpenGL cube map faces:
+/// `+X, -X, +Y, -Y, +Z, -Z`.
+pub(crate) fn cube_map_faces() -> [(Vector3<f32>, Vector3<f32>); 6] {
+    [
+        (Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+        (Vector3::new(-1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+        (Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0)),
+        (Vector3::new(0.0, -1.0, 0.0), Vector3::new(0.0, 0.0, -1.0)),
+        (Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, -1.0, 0.0)),
+        (Vector3::new(0.0, 0.0, -1.0), Vector3::new(0.0, -1.0, 0.0)),
+    ]
+}
+
+/// Returns index of a face (in `+X, -X, +Y, -Y, +Z, -Z` order) and coordinates of a texel on it
+/// that corresponds to the given direction. Face selection follows OpenGL specification.
+pub(crate) fn cube_map_texel(direction: Vector3<f32>, face_size: u32) -> (usize, usize, usize) {
+    let abs = direction.abs();
+    let (face, sc, tc, major) = if abs.x >= abs.y && abs.x >= abs.z {
+        if direction.x > 0.0 {
+            (0, -direction.z, -direction.y, abs.x)
+        } else {
+            (1, direction.z, -direction.y, abs.x)
+        }
+    } else if abs.y >= abs.z {
+        if direction.y > 0.0 {
+            (2, direction.x, direction.z, abs.y)
+        } else {
+            (3, direction.x, -direction.z, abs.y)
+        }
+    } else if direction.z > 0.0 {
+        (4, direction.x, -direction.y, abs.z)
+    } else {
+        (5, -direction.x, -direction.y, abs.z)
+    };
+
+    let major = major.max(f32::EPSILON);
+    let size = face_size as f32;
+    let max = face_size.saturating_sub(1) as usize;
+    let x = ((((sc / major) + 1.0) * 0.5 * size) as usize).min(max);
+    let y = ((((tc / major) + 1.0) * 0.5 * size) as usize).min(max);
+    (face, x, y)
+}
+
+/// Returns a direction (not normalized) that points to the center of the given texel of a cube map
+/// face. This is the inverse of [`cube_map_texel`].
+pub(crate) fn cube_map_texel_direction(
+    face: usize,
+    x: u32,
+    y: u32,
+    face_size: u32,
+) -> Vector3<f32> {
+    let sc = ((x as f32 + 0.5) / face_size as f32) * 2.0 - 1.0;
+    let tc = ((y as f32 + 0.5) / face_size as f32) * 2.0 - 1.0;
+    match face {
+        0 => Vector3::new(1.0, -tc, -sc),
+        1 => Vector3::new(-1.0, -tc, sc),
+        2 => Vector3::new(sc, 1.0, tc),
+        3 => Vector3::new(sc, -1.0, -tc),
+        4 => Vector3::new(sc, -tc, 1.0),
+        _ => Vector3::new(-sc, -tc, -1.0),
+    }
+}
+
+/// A cube map, that was captured from a point in a scene. It could be converted to a cube map texture
+/// (to be used as a skybox or a reflection source) or to an equirectangular panorama.
+#[derive(Clone, Debug)]
+pub struct CubeMapCapture {
+    pub(crate) face_size: u32,
+    pub(crate) hdr: bool,
+    pub(crate) faces: [Vec<f32>; 6],
+}
+
+impl CubeMapCapture {
+    /// Returns size of a face of the cube map in pixels.
+    pub fn face_size(&self) -> u32 {
+        self.face_size
+    }
+
+    /// Returns `true` if the cube map was captured in high dynamic range (linear color space, values
+    /// are not clamped), `false` - if it was captured after tone mapping (sRGB, values are in `[0; 1]`
+    /// range).
+    pub fn is_hdr(&self) -> bool {
+        self.hdr
+    }
+
+    /// Returns RGBA pixels of a face in `+X, -X, +Y, -Y, +Z, -Z` order. Rows of a face are stored
+    /// in OpenGL order (from bottom to top).
+    pub fn face(&self, index: usize) -> Option<&[f32]> {
+        self.faces.get(index).map(|face| face.as_slice())
+    }
+
+    /// Samples the cube map in the given direction using nearest filtering.
+    pub fn sample(&self, direction: Vector3<f32>) -> [f32; 4] {
+        let (face, x, y) = cube_map_texel(direction, self.face_size);
+        let offset = (y * self.face_size as usize + x) * 4;
+        let pixels = &self.faces[face];
+        [
+            pixels[offset],
+            pixels[offset + 1],
+            pixels[offset + 2],
+            pixels[offset + 3],
+        ]
+    }
+
+    /// Creates a cube map texture from the captured faces. The texture has `RGBA32F` pixel format
+    /// for high dynamic range captures and `RGBA8` otherwise.
+    pub fn to_texture(&self) -> TextureResource {
+        let (pixel_kind, bytes) = if self.hdr {
+            (
+                TexturePixelKind::RGBA32F,
+                self.faces
+                    .iter()
+                    .flat_map(|face| face.iter().flat_map(|value| value.to_ne_bytes()))
+                    .collect::<Vec<_>>(),
+            )
+        } else {
+            (
+                TexturePixelKind::RGBA8,
+                self.faces
+                    .iter()
+                    .flat_map(|face| {
+                        face.iter()
+                            .map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8)
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        let texture = Texture::from_bytes(
+            TextureKind::Cube {
+                width: self.face_size,
+                height: self.face_size,
+            },
+            pixel_kind,
+            bytes,
+        )
+        .expect("Size of the captured data must match the size of the texture!");
+
+        TextureResource::new_ok(ResourceKind::Embedded, texture)
+    }
+
+    /// Converts the cube map to an equirectangular panorama with the given width, the height of the
+    /// panorama is half of its width. The center of the panorama is the +Z direction, the top row is
+    /// the +Y direction. Use [`image::DynamicImage::to_rgba8`] to convert the panorama to 8-bit
+    /// image, for example to save it as PNG.
+    pub fn to_equirectangular(&self, width: u32) -> Rgba32FImage {
+        let width = width.max(2);
+        let height = width / 2;
+        Rgba32FImage::from_fn(width, height, |x, y| {
+            let longitude = ((x as f32 + 0.5) / width as f32) * 2.0 * PI - PI;
+            let latitude = 0.5 * PI - ((y as f32 + 0.5) / height as f32) * PI;
+            let direction = Vector3::new(
+                -latitude.cos() * longitude.sin(),
+                latitude.sin(),
+                latitude.cos() * longitude.cos(),
+            );
+            Rgba(self.sample(direction))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{core::algebra::Vector3, renderer::panorama::CubeMapCapture};
+
+    #[test]
+    fn test_cube_map_sampling() {
+        let faces = std::array::from_fn(|index| vec![index as f32; 4]);
+        let capture = CubeMapCapture {
+            face_size: 1,
+            hdr: true,
+            faces,
+        };
+
+        assert_eq!(capture.sample(Vector3::new(1.0, 0.1, 0.2))[0], 0.0);
+        assert_eq!(capture.sample(Vector3::new(-1.0, 0.1, 0.2))[0], 1.0);
+        assert_eq!(capture.sample(Vector3::new(0.1, 1.0, 0.2))[0], 2.0);
+        assert_eq!(capture.sample(Vector3::new(0.1, -1.0, 0.2))[0], 3.0);
+        assert_eq!(capture.sample(Vector3::new(0.1, 0.2, 1.0))[0], 4.0);
+        assert_eq!(capture.sample(Vector3::new(0.1, 0.2, -1.0))[0], 5.0);
+
+        let panorama = capture.to_equirectangular(8);
+        assert_eq!(panorama.dimensions(), (8, 4));
+        // Center of the panorama looks at +Z.
+        assert_eq!(panorama.get_pixel(4, 2)[0], 4.0);
+    }
+}
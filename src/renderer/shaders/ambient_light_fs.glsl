@@ -1,18 +1,69 @@
 uniform sampler2D diffuseTexture;
 uniform sampler2D aoSampler;
 uniform sampler2D ambientTexture;
+uniform sampler2D depthTexture;
+uniform sampler2D normalTexture;
+uniform sampler2D materialTexture;
+uniform samplerCube irradianceMap;
+uniform samplerCube prefilteredMap;
 uniform vec4 ambientColor;
+uniform mat4 invViewProj;
+uniform vec3 cameraPosition;
+uniform bool useIbl;
+uniform float maxReflectionLod;
 
 out vec4 FragColor;
 in vec2 texCoord;
 
+// Fresnel-Schlick approximation that takes roughness into account, it is used for ambient lighting,
+// because there is no single half-vector.
+vec3 FresnelSchlickRoughness(float cosTheta, vec3 F0, float roughness)
+{
+    return F0 + (max(vec3(1.0 - roughness), F0) - F0) * pow(max(1.0 - cosTheta, 0.0), 5.0);
+}
+
+// Analytical approximation of the split-sum environment BRDF, see "Physically Based Shading on
+// Mobile" by Brian Karis.
+vec2 EnvBRDFApprox(float roughness, float NdotV)
+{
+    const vec4 c0 = vec4(-1.0, -0.0275, -0.572, 0.022);
+    const vec4 c1 = vec4(1.0, 0.0425, 1.04, -0.04);
+    vec4 r = roughness * c0 + c1;
+    float a004 = min(r.x * r.x, exp2(-9.28 * NdotV)) * r.x + r.y;
+    return vec2(-1.04, 1.04) * a004 + r.zw;
+}
+
 void main()
 {
     float ambientOcclusion = texture(aoSampler, texCoord).r;
     vec4 ambientPixel = texture(ambientTexture, texCoord);
-    FragColor = (ambientColor + ambientPixel) * S_SRGBToLinear(texture(diffuseTexture, texCoord));
+    vec4 albedo = S_SRGBToLinear(texture(diffuseTexture, texCoord));
+    FragColor = (ambientColor + ambientPixel) * albedo;
+
+    if (useIbl) {
+        vec3 material = texture(materialTexture, texCoord).rgb;
+        float metallic = material.x;
+        float roughness = material.y;
+
+        vec3 fragmentPosition = S_UnProject(vec3(texCoord, texture(depthTexture, texCoord).r), invViewProj);
+        vec3 N = normalize(texture(normalTexture, texCoord).xyz * 2.0 - 1.0);
+        vec3 V = normalize(cameraPosition - fragmentPosition);
+        vec3 R = reflect(-V, N);
+        float NdotV = max(dot(N, V), 0.0);
+
+        vec3 F0 = mix(vec3(0.04), albedo.rgb, metallic);
+        vec3 F = FresnelSchlickRoughness(NdotV, F0, roughness);
+        vec3 kD = (vec3(1.0) - F) * (1.0 - metallic);
+
+        vec3 diffuse = kD * albedo.rgb * texture(irradianceMap, N).rgb;
+
+        vec2 envBRDF = EnvBRDFApprox(roughness, NdotV);
+        vec3 prefiltered = textureLod(prefilteredMap, R, roughness * maxReflectionLod).rgb;
+        vec3 specular = prefiltered * (F0 * envBRDF.x + envBRDF.y);
+
+        FragColor.rgb += diffuse + specular;
+    }
+
     FragColor.rgb *= ambientOcclusion;
     FragColor.a = ambientPixel.a;
-
-    // TODO: Implement IBL.
-}
\ No newline at end of file
+}
@@ -0,0 +1,74 @@
+// Must be in sync with TEXTURE_WIDTH in clustered.rs
+#define CLUSTER_TEXTURE_WIDTH 1024
+
+uniform sampler2D depthTexture;
+uniform sampler2D colorTexture;
+uniform sampler2D normalTexture;
+uniform sampler2D materialTexture;
+
+// Two texels per light: (position, radius) and (color * intensity, unused).
+uniform sampler2D lightsTexture;
+// (offset in the index list, amount of lights, unused, unused) for each cluster.
+uniform sampler2D clustersTexture;
+// Light indices of every cluster.
+uniform sampler2D indicesTexture;
+
+uniform mat4 invViewProj;
+uniform mat4 viewMatrix;
+uniform vec3 cameraPosition;
+uniform vec3 clusterCount;
+uniform float zNear;
+uniform float zFar;
+
+in vec2 texCoord;
+out vec4 FragColor;
+
+vec4 FetchTexel(sampler2D storage, int index)
+{
+    return texelFetch(storage, ivec2(index % CLUSTER_TEXTURE_WIDTH, index / CLUSTER_TEXTURE_WIDTH), 0);
+}
+
+void main()
+{
+    vec3 material = texture(materialTexture, texCoord).rgb;
+
+    vec3 fragmentPosition = S_UnProject(vec3(texCoord, texture(depthTexture, texCoord).r), invViewProj);
+
+    // Find the cluster of the fragment.
+    ivec3 count = ivec3(clusterCount);
+    float viewDepth = -(viewMatrix * vec4(fragmentPosition, 1.0)).z;
+    int slice = int(floor(log(max(viewDepth, zNear) / zNear) / log(zFar / zNear) * clusterCount.z));
+    ivec3 cluster = clamp(ivec3(ivec2(texCoord * clusterCount.xy), slice), ivec3(0), count - 1);
+    int clusterIndex = (cluster.z * count.y + cluster.y) * count.x + cluster.x;
+    vec2 lightRange = FetchTexel(clustersTexture, clusterIndex).xy;
+    int offset = int(lightRange.x);
+    int lightCount = int(lightRange.y);
+
+    vec4 diffuseColor = texture(colorTexture, texCoord);
+
+    TPBRContext ctx;
+    ctx.albedo = S_SRGBToLinear(diffuseColor).rgb;
+    ctx.fragmentNormal = normalize(texture(normalTexture, texCoord).xyz * 2.0 - 1.0);
+    ctx.metallic = material.x;
+    ctx.roughness = material.y;
+    ctx.viewVector = normalize(cameraPosition - fragmentPosition);
+
+    vec3 lighting = vec3(0.0);
+    for (int i = 0; i < lightCount; ++i) {
+        int lightIndex = int(FetchTexel(indicesTexture, offset + i).r);
+        vec4 lightPositionRadius = FetchTexel(lightsTexture, 2 * lightIndex);
+
+        vec3 fragmentToLight = lightPositionRadius.xyz - fragmentPosition;
+        float distance = length(fragmentToLight);
+        if (distance >= lightPositionRadius.w) {
+            continue;
+        }
+
+        ctx.fragmentToLight = fragmentToLight / distance;
+        ctx.lightColor = FetchTexel(lightsTexture, 2 * lightIndex + 1).rgb;
+
+        lighting += S_LightDistanceAttenuation(distance, lightPositionRadius.w) * S_PBR_CalculateLight(ctx);
+    }
+
+    FragColor = vec4(lighting, diffuseColor.a);
+}
@@ -20,7 +20,8 @@ uniform sampler2D shadowCascade1;
 uniform sampler2D shadowCascade2;
 
 uniform bool shadowsEnabled;
-uniform float shadowBias;
+uniform float shadowBiases[NUM_CASCADES];
+uniform float shadowNormalOffsets[NUM_CASCADES];
 uniform bool softShadows;
 uniform float shadowMapInvSize;
 
@@ -28,9 +29,10 @@ in vec2 texCoord;
 out vec4 FragColor;
 
 // Returns **inverted** shadow factor where 1 - fully bright, 0 - fully in shadow.
-float CsmGetShadow(in sampler2D sampler, in vec3 fragmentPosition, in mat4 lightViewProjMatrix)
+float CsmGetShadow(in sampler2D sampler, in vec3 fragmentPosition, in vec3 fragmentNormal, in mat4 lightViewProjMatrix, in float shadowBias, in float normalOffset)
 {
-    return S_SpotShadowFactor(shadowsEnabled, softShadows, shadowBias, fragmentPosition, lightViewProjMatrix, shadowMapInvSize, sampler);
+    vec3 offsetFragmentPosition = fragmentPosition + fragmentNormal * normalOffset;
+    return S_SpotShadowFactor(shadowsEnabled, softShadows, shadowBias, offsetFragmentPosition, lightViewProjMatrix, shadowMapInvSize, sampler);
 }
 
 void main()
@@ -55,11 +57,11 @@ void main()
 
     float shadow = 1.0;
     if (fragmentZViewSpace <= cascadeDistances[0]) {
-        shadow = CsmGetShadow(shadowCascade0, fragmentPosition, lightViewProjMatrices[0]);
+        shadow = CsmGetShadow(shadowCascade0, fragmentPosition, ctx.fragmentNormal, lightViewProjMatrices[0], shadowBiases[0], shadowNormalOffsets[0]);
     } else if (fragmentZViewSpace <= cascadeDistances[1]) {
-        shadow = CsmGetShadow(shadowCascade1, fragmentPosition, lightViewProjMatrices[1]);
+        shadow = CsmGetShadow(shadowCascade1, fragmentPosition, ctx.fragmentNormal, lightViewProjMatrices[1], shadowBiases[1], shadowNormalOffsets[1]);
     } else if (fragmentZViewSpace <= cascadeDistances[2]) {
-        shadow = CsmGetShadow(shadowCascade2, fragmentPosition, lightViewProjMatrices[2]);
+        shadow = CsmGetShadow(shadowCascade2, fragmentPosition, ctx.fragmentNormal, lightViewProjMatrices[2], shadowBiases[2], shadowNormalOffsets[2]);
     }
 
     FragColor = shadow * vec4(lightIntensity * lighting, diffuseColor.a);
@@ -0,0 +1,100 @@
+// Ground-truth ambient occlusion (GTAO).
+//
+// Based on "Practical Realtime Strategies for Accurate Indirect Occlusion" by Jimenez et al.
+// The hemisphere around each pixel is split into a set of slices, each slice is a plane that
+// contains view vector. Two horizons are searched in each slice by marching the depth buffer in
+// both directions, then the visible arc between the horizons is integrated analytically using
+// cosine-weighted visibility, projected normal of the pixel is taken into account.
+
+uniform sampler2D depthSampler;
+uniform sampler2D normalSampler;
+uniform sampler2D noiseSampler;
+
+uniform float radius;
+uniform float intensity;
+uniform mat4 inverseProjectionMatrix;
+uniform mat4 projectionMatrix;
+uniform vec2 noiseScale;
+uniform vec2 noiseOffset;
+uniform mat3 viewMatrix;
+uniform int sliceCount;
+uniform int stepCount;
+
+out float finalOcclusion;
+
+in vec2 texCoord;
+
+vec3 GetViewSpacePosition(vec2 screenCoord) {
+    return S_UnProject(vec3(screenCoord, texture(depthSampler, screenCoord).r), inverseProjectionMatrix);
+}
+
+void main() {
+    vec3 fragPos = GetViewSpacePosition(texCoord);
+    vec3 viewDir = normalize(-fragPos);
+    vec3 worldSpaceNormal = texture(normalSampler, texCoord).xyz * 2.0 - 1.0;
+    vec3 viewSpaceNormal = normalize(viewMatrix * worldSpaceNormal);
+    vec2 noise = texture(noiseSampler, texCoord * noiseScale + noiseOffset).xy;
+
+    // Project the radius to the screen to find how far the horizon search should go.
+    vec4 projectedRadius = projectionMatrix * vec4(radius, radius, fragPos.z, 1.0);
+    float screenRadius = 0.5 * abs(projectedRadius.x / projectedRadius.w);
+    if (screenRadius < 1.0 / float(textureSize(depthSampler, 0).x)) {
+        finalOcclusion = 1.0;
+        return;
+    }
+
+    float visibility = 0.0;
+    for (int slice = 0; slice < sliceCount; ++slice) {
+        float phi = (float(slice) + noise.x) * PI / float(sliceCount);
+        vec2 direction = vec2(cos(phi), sin(phi));
+
+        // Basis of the slice plane.
+        vec3 directionVec = vec3(direction, 0.0);
+        vec3 orthoDirection = directionVec - dot(directionVec, viewDir) * viewDir;
+        vec3 axis = normalize(cross(directionVec, viewDir));
+        vec3 projectedNormal = viewSpaceNormal - axis * dot(viewSpaceNormal, axis);
+        float projectedNormalLength = length(projectedNormal);
+        if (projectedNormalLength < 0.0001) {
+            continue;
+        }
+
+        float signN = sign(dot(orthoDirection, projectedNormal));
+        float cosN = clamp(dot(projectedNormal, viewDir) / projectedNormalLength, -1.0, 1.0);
+        float n = signN * acos(cosN);
+
+        // Search for horizons in both directions.
+        float horizonCos0 = -1.0;
+        float horizonCos1 = -1.0;
+        for (int i = 0; i < stepCount; ++i) {
+            float s = (float(i) + noise.y) / float(stepCount);
+            vec2 offset = direction * s * screenRadius;
+
+            vec3 delta0 = GetViewSpacePosition(texCoord + offset) - fragPos;
+            vec3 delta1 = GetViewSpacePosition(texCoord - offset) - fragPos;
+
+            float length0 = length(delta0);
+            float length1 = length(delta1);
+
+            // Samples outside of the radius gradually lose their influence.
+            float falloff0 = clamp(1.0 - length0 / radius, 0.0, 1.0);
+            float falloff1 = clamp(1.0 - length1 / radius, 0.0, 1.0);
+
+            horizonCos0 = max(horizonCos0, mix(-1.0, dot(delta0, viewDir) / max(length0, 0.0001), falloff0));
+            horizonCos1 = max(horizonCos1, mix(-1.0, dot(delta1, viewDir) / max(length1, 0.0001), falloff1));
+        }
+
+        // Horizon angles, clamped to the hemisphere around the projected normal.
+        float h0 = n + max(-acos(horizonCos1) - n, -PI * 0.5);
+        float h1 = n + min(acos(horizonCos0) - n, PI * 0.5);
+
+        // Cosine-weighted integral of the visible arc.
+        float sinN = sin(n);
+        float arc0 = -cos(2.0 * h0 - n) + cosN + 2.0 * h0 * sinN;
+        float arc1 = -cos(2.0 * h1 - n) + cosN + 2.0 * h1 * sinN;
+        visibility += projectedNormalLength * 0.25 * (arc0 + arc1);
+    }
+
+    visibility /= float(sliceCount);
+
+    finalOcclusion = clamp(1.0 - intensity * (1.0 - visibility), 0.0, 1.0);
+}
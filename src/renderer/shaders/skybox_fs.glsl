@@ -1,4 +1,5 @@
 uniform samplerCube cubemapTexture;
+uniform bool isLinear;
 
 out vec4 FragColor;
 
@@ -6,5 +7,6 @@ in vec3 texCoord;
 
 void main()
 {
-    FragColor = S_SRGBToLinear(texture(cubemapTexture, texCoord));
+    vec4 color = texture(cubemapTexture, texCoord);
+    FragColor = isLinear ? color : S_SRGBToLinear(color);
 }
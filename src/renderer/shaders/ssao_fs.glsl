@@ -9,7 +9,10 @@ uniform mat4 inverseProjectionMatrix;
 uniform mat4 projectionMatrix;
 uniform vec3 kernel[KERNEL_SIZE];
 uniform vec2 noiseScale;
+uniform vec2 noiseOffset;
 uniform mat3 viewMatrix;
+uniform int sampleCount;
+uniform float intensity;
 
 out float finalOcclusion;
 
@@ -23,15 +26,19 @@ void main() {
     vec3 fragPos = GetViewSpacePosition(texCoord);
     vec3 worldSpaceNormal = texture(normalSampler, texCoord).xyz * 2.0 - 1.0;
     vec3 viewSpaceNormal = normalize(viewMatrix * worldSpaceNormal);
-    vec3 randomVec = normalize(texture(noiseSampler, texCoord * noiseScale).xyz * 2.0 - 1.0);
+    vec3 randomVec = normalize(texture(noiseSampler, texCoord * noiseScale + noiseOffset).xyz * 2.0 - 1.0);
 
     vec3 tangent = normalize(randomVec - viewSpaceNormal * dot(randomVec, viewSpaceNormal));
     vec3 bitangent = normalize(cross(viewSpaceNormal, tangent));
     mat3 TBN = mat3(tangent, bitangent, viewSpaceNormal);
 
+    // Kernel samples are sorted by their distance to the center, so lower sample counts must
+    // skip samples uniformly to cover the whole hemisphere.
+    int stride = KERNEL_SIZE / sampleCount;
+
     float occlusion = 0.0;
-    for (int i = 0; i < KERNEL_SIZE; ++i) {
-        vec3 samplePoint = fragPos.xyz + TBN * kernel[i] * radius;
+    for (int i = 0; i < sampleCount; ++i) {
+        vec3 samplePoint = fragPos.xyz + TBN * kernel[i * stride] * radius;
 
         vec4 offset = projectionMatrix * vec4(samplePoint, 1.0);
         offset.xy /= offset.w;
@@ -43,5 +50,5 @@ void main() {
         occlusion += rangeCheck * ((position.z > samplePoint.z + 0.04) ? 1.0 : 0.0);
     }
 
-    finalOcclusion = 1.0 - occlusion / float(KERNEL_SIZE);
+    finalOcclusion = clamp(1.0 - intensity * occlusion / float(sampleCount), 0.0, 1.0);
 }
\ No newline at end of file
@@ -0,0 +1,51 @@
+// Temporal accumulation of ambient occlusion.
+//
+// Each pixel is reprojected into the previous frame using scene depth and camera matrices, then
+// the history is clamped to the neighbourhood of the current pixel to suppress ghosting and blended
+// with the current occlusion.
+
+uniform sampler2D currentTexture;
+uniform sampler2D historyTexture;
+uniform sampler2D depthTexture;
+uniform mat4 invViewProjection;
+uniform mat4 previousViewProjection;
+uniform vec2 inverseScreenSize;
+uniform float blendFactor;
+uniform bool historyValid;
+
+in vec2 texCoord;
+out float finalOcclusion;
+
+void main()
+{
+    float current = texture(currentTexture, texCoord).r;
+
+    if (!historyValid) {
+        finalOcclusion = current;
+        return;
+    }
+
+    float depth = texture(depthTexture, texCoord).r;
+    vec3 worldPosition = S_UnProject(vec3(texCoord, depth), invViewProjection);
+    vec2 historyCoord = S_Project(worldPosition, previousViewProjection).xy;
+
+    if (historyCoord.x < 0.0 || historyCoord.x > 1.0 || historyCoord.y < 0.0 || historyCoord.y > 1.0) {
+        finalOcclusion = current;
+        return;
+    }
+
+    // Neighbourhood clamping.
+    float minOcclusion = current;
+    float maxOcclusion = current;
+    for (int y = -1; y <= 1; ++y) {
+        for (int x = -1; x <= 1; ++x) {
+            float neighbour = texture(currentTexture, texCoord + vec2(x, y) * inverseScreenSize).r;
+            minOcclusion = min(minOcclusion, neighbour);
+            maxOcclusion = max(maxOcclusion, neighbour);
+        }
+    }
+
+    float history = clamp(texture(historyTexture, historyCoord).r, minOcclusion, maxOcclusion);
+
+    finalOcclusion = mix(history, current, blendFactor);
+}
@@ -0,0 +1,67 @@
+// Temporal anti-aliasing resolve shader.
+//
+// Each pixel is reprojected into the previous frame using per-pixel velocity from the G-Buffer
+// (it includes both camera and object movement). Pixels without geometry (sky, background) are
+// reprojected using camera matrices only. Then the history color is clamped to the neighbourhood
+// of the current pixel to suppress ghosting and blended with the current color.
+
+uniform sampler2D currentTexture;
+uniform sampler2D historyTexture;
+uniform sampler2D depthTexture;
+uniform sampler2D velocityTexture;
+uniform mat4 invViewProjection;
+uniform mat4 previousViewProjection;
+uniform vec2 jitter;
+uniform vec2 inverseScreenSize;
+uniform float blendFactor;
+uniform bool historyValid;
+
+in vec2 texCoord;
+out vec4 fragColor;
+
+void main()
+{
+    // Current frame is rendered with jittered projection, shift texture coordinates to
+    // fetch "unjittered" color.
+    vec2 currentCoord = texCoord + jitter;
+
+    vec3 current = texture(currentTexture, currentCoord).rgb;
+
+    if (!historyValid) {
+        fragColor = vec4(current, 1.0);
+        return;
+    }
+
+    float depth = texture(depthTexture, currentCoord).r;
+    vec2 historyCoord;
+    if (depth < 1.0) {
+        historyCoord = texCoord - texture(velocityTexture, currentCoord).xy;
+    } else {
+        vec3 worldPosition = S_UnProject(vec3(currentCoord, depth), invViewProjection);
+        historyCoord = S_Project(worldPosition, previousViewProjection).xy;
+    }
+
+    if (historyCoord.x < 0.0 || historyCoord.x > 1.0 || historyCoord.y < 0.0 || historyCoord.y > 1.0) {
+        fragColor = vec4(current, 1.0);
+        return;
+    }
+
+    // Neighbourhood clamping.
+    vec3 minColor = current;
+    vec3 maxColor = current;
+    for (int y = -1; y <= 1; ++y) {
+        for (int x = -1; x <= 1; ++x) {
+            vec3 neighbour = texture(currentTexture, currentCoord + vec2(x, y) * inverseScreenSize).rgb;
+            minColor = min(minColor, neighbour);
+            maxColor = max(maxColor, neighbour);
+        }
+    }
+
+    vec3 history = clamp(texture(historyTexture, historyCoord).rgb, minColor, maxColor);
+
+    // Fast moving pixels should rely on current frame more, otherwise they'll leave trails.
+    vec2 velocity = (texCoord - historyCoord) / inverseScreenSize;
+    float weight = mix(blendFactor, 1.0, clamp(length(velocity) / 32.0, 0.0, 1.0));
+
+    fragColor = vec4(mix(history, current, weight), 1.0);
+}
@@ -0,0 +1,45 @@
+// Bicubic (Catmull-Rom) upsampling that uses bilinear filtering to fetch 4x4 texels with
+// just 9 texture reads.
+//
+// Based on https://gist.github.com/TheRealMJP/c83b8c0f46b63f3a88a5986f4fa982b5
+
+uniform sampler2D sourceTexture;
+uniform vec2 sourceSize;
+
+in vec2 texCoord;
+out vec4 fragColor;
+
+void main()
+{
+    vec2 samplePos = texCoord * sourceSize;
+    vec2 texPos1 = floor(samplePos - 0.5) + 0.5;
+
+    vec2 f = samplePos - texPos1;
+
+    vec2 w0 = f * (-0.5 + f * (1.0 - 0.5 * f));
+    vec2 w1 = 1.0 + f * f * (-2.5 + 1.5 * f);
+    vec2 w2 = f * (0.5 + f * (2.0 - 1.5 * f));
+    vec2 w3 = f * f * (-0.5 + 0.5 * f);
+
+    vec2 w12 = w1 + w2;
+    vec2 offset12 = w2 / w12;
+
+    vec2 texPos0 = (texPos1 - 1.0) / sourceSize;
+    vec2 texPos3 = (texPos1 + 2.0) / sourceSize;
+    vec2 texPos12 = (texPos1 + offset12) / sourceSize;
+
+    vec3 result = vec3(0.0);
+    result += texture(sourceTexture, vec2(texPos0.x, texPos0.y)).rgb * w0.x * w0.y;
+    result += texture(sourceTexture, vec2(texPos12.x, texPos0.y)).rgb * w12.x * w0.y;
+    result += texture(sourceTexture, vec2(texPos3.x, texPos0.y)).rgb * w3.x * w0.y;
+
+    result += texture(sourceTexture, vec2(texPos0.x, texPos12.y)).rgb * w0.x * w12.y;
+    result += texture(sourceTexture, vec2(texPos12.x, texPos12.y)).rgb * w12.x * w12.y;
+    result += texture(sourceTexture, vec2(texPos3.x, texPos12.y)).rgb * w3.x * w12.y;
+
+    result += texture(sourceTexture, vec2(texPos0.x, texPos3.y)).rgb * w0.x * w3.y;
+    result += texture(sourceTexture, vec2(texPos12.x, texPos3.y)).rgb * w12.x * w3.y;
+    result += texture(sourceTexture, vec2(texPos3.x, texPos3.y)).rgb * w3.x * w3.y;
+
+    fragColor = vec4(max(result, vec3(0.0)), 1.0);
+}
@@ -2,6 +2,7 @@ use crate::{
     core::{
         algebra::{Matrix4, Point3, Vector2, Vector3},
         math::{aabb::AxisAlignedBoundingBox, frustum::Frustum, Rect},
+        pool::Handle,
     },
     renderer::{
         apply_material,
@@ -9,20 +10,20 @@ use crate::{
         cache::{geometry::GeometryCache, shader::ShaderCache, texture::TextureCache},
         framework::{
             error::FrameworkError,
-            framebuffer::{Attachment, AttachmentKind, CullFace, DrawParameters, FrameBuffer},
+            framebuffer::{Attachment, AttachmentKind, FrameBuffer},
             gpu_texture::{
                 Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
                 PixelKind, WrapMode,
             },
-            state::{ColorMask, PipelineState},
+            state::PipelineState,
         },
         storage::MatrixStorageCache,
         MaterialContext, RenderPassStatistics, ShadowMapPrecision, DIRECTIONAL_SHADOW_PASS_NAME,
     },
     scene::{
-        camera::Camera,
+        camera::{Camera, Projection},
         graph::Graph,
-        light::directional::{DirectionalLight, FrustumSplitOptions, CSM_NUM_CASCADES},
+        light::directional::{DirectionalLight, CSM_NUM_CASCADES},
     },
 };
 use fyrox_core::color::Color;
@@ -33,6 +34,8 @@ pub struct Cascade {
     pub frame_buffer: FrameBuffer,
     pub view_proj_matrix: Matrix4<f32>,
     pub z_far: f32,
+    /// Size of a shadow map texel in world units.
+    pub texel_size: f32,
 }
 
 impl Cascade {
@@ -75,6 +78,7 @@ impl Cascade {
             )?,
             view_proj_matrix: Default::default(),
             z_far: 0.0,
+            texel_size: 0.0,
         })
     }
 
@@ -170,22 +174,39 @@ impl CsmRenderer {
             .try_normalize(f32::EPSILON)
             .unwrap_or_else(Vector3::z);
 
-        let z_values = match light.csm_options.split_options {
-            FrustumSplitOptions::Absolute { far_planes } => [
-                camera.projection().z_near(),
-                far_planes[0],
-                far_planes[1],
-                far_planes[2],
-            ],
-            FrustumSplitOptions::Relative { fractions } => [
-                camera.projection().z_near(),
-                camera.projection().z_far() * fractions[0],
-                camera.projection().z_far() * fractions[1],
-                camera.projection().z_far() * fractions[2],
-            ],
-        };
+        let z_near = camera.projection().z_near();
+        let far_planes = light
+            .csm_options
+            .split_options
+            .far_planes(z_near, camera.projection().z_far());
+        let z_values = [z_near, far_planes[0], far_planes[1], far_planes[2]];
+
+        let cascade_count = light.csm_options.cascade_count();
+
+        // Orthographic cameras usually do not rotate (2D and 2.5D games), so cascades could be fitted
+        // tightly to the sub-frusta which gives much better shadow map resolution.
+        let tight_fit = matches!(camera.projection(), Projection::Orthographic(_));
+
+        let pancaking =
+            light.csm_options.is_pancaking_enabled() && state.is_depth_clamp_supported();
+
+        // Rotation-only view matrix of the light, it is used to snap cascades to shadow map texels.
+        let light_rotation_matrix = Matrix4::look_at_lh(
+            &Point3::from(light_direction),
+            &Point3::origin(),
+            &light_up_vec,
+        );
+        let inv_light_rotation_matrix = light_rotation_matrix
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity);
 
         for i in 0..CSM_NUM_CASCADES {
+            if i >= cascade_count {
+                // Unused cascades must not be sampled at all.
+                self.cascades[i].z_far = 0.0;
+                continue;
+            }
+
             let z_near = z_values[i];
             let mut z_far = z_values[i + 1];
 
@@ -204,10 +225,52 @@ impl CsmRenderer {
                 Frustum::from_view_projection_matrix(projection_matrix * camera.view_matrix())
                     .unwrap_or_default();
 
-            let center = frustum.center();
+            let corners = frustum.corners();
+            let (center, half_extents) = if tight_fit {
+                // Fit the cascade using bounding box of the sub-frustum in light space.
+                let light_space_bounds = AxisAlignedBoundingBox::from_points(&corners.map(|c| {
+                    light_rotation_matrix
+                        .transform_point(&Point3::from(c))
+                        .coords
+                }));
+                let half_extents = light_space_bounds
+                    .half_extents()
+                    .map(|e| (e * 16.0).ceil() / 16.0);
+                (light_space_bounds.center(), half_extents)
+            } else {
+                // Fit the cascade using bounding sphere of the sub-frustum. Its size does not depend on
+                // camera orientation, so shadows won't shimmer when the camera rotates.
+                let center = corners.iter().fold(Vector3::default(), |acc, c| acc + c)
+                    / corners.len() as f32;
+                let radius = corners
+                    .iter()
+                    .map(|c| (c - center).norm())
+                    .fold(0.0f32, f32::max);
+                // Quantize the radius to prevent tiny size changes because of precision issues.
+                let radius = (radius * 16.0).ceil() / 16.0;
+                (
+                    light_rotation_matrix
+                        .transform_point(&Point3::from(center))
+                        .coords,
+                    Vector3::repeat(radius),
+                )
+            };
+
+            // Snap the center of the cascade to the texels of the shadow map, so the shadows won't
+            // shimmer when the camera moves.
+            let texel_size_x = 2.0 * half_extents.x / self.size as f32;
+            let texel_size_y = 2.0 * half_extents.y / self.size as f32;
+            let snapped_center = inv_light_rotation_matrix
+                .transform_point(&Point3::new(
+                    (center.x / texel_size_x).floor() * texel_size_x,
+                    (center.y / texel_size_y).floor() * texel_size_y,
+                    center.z,
+                ))
+                .coords;
+
             let light_view_matrix = Matrix4::look_at_lh(
-                &Point3::from(center + light_direction),
-                &Point3::from(center),
+                &Point3::from(snapped_center + light_direction),
+                &Point3::from(snapped_center),
                 &light_up_vec,
             );
 
@@ -219,29 +282,32 @@ impl CsmRenderer {
                     z_far,
                     view_matrix: light_view_matrix,
                     projection_matrix,
+                    render_mask: camera.render_mask(),
+                    lod_camera: Handle::NONE,
                 },
                 DIRECTIONAL_SHADOW_PASS_NAME.clone(),
             );
 
-            let mut aabb = AxisAlignedBoundingBox::default();
-            for corner in frustum.corners() {
-                let light_space_corner = light_view_matrix
-                    .transform_point(&Point3::from(corner))
-                    .coords;
-                aabb.add_point(light_space_corner);
-            }
+            // The light is placed at a unit distance from the center of the cascade.
+            let mut aabb = AxisAlignedBoundingBox::from_min_max(
+                Vector3::new(-half_extents.x, -half_extents.y, 1.0 - half_extents.z),
+                Vector3::new(half_extents.x, half_extents.y, 1.0 + half_extents.z),
+            );
 
-            // Make sure most of the objects outside of the frustum will cast shadows.
-            let z_mult = 10.0;
-            if aabb.min.z < 0.0 {
-                aabb.min.z *= z_mult;
-            } else {
-                aabb.min.z /= z_mult;
-            }
-            if aabb.max.z < 0.0 {
-                aabb.max.z /= z_mult;
-            } else {
-                aabb.max.z *= z_mult;
+            // Make sure most of the objects outside of the frustum will cast shadows. This is not
+            // needed with pancaking, because casters in front of the near plane will be clamped to it.
+            if !pancaking {
+                let z_mult = 10.0;
+                if aabb.min.z < 0.0 {
+                    aabb.min.z *= z_mult;
+                } else {
+                    aabb.min.z /= z_mult;
+                }
+                if aabb.max.z < 0.0 {
+                    aabb.max.z /= z_mult;
+                } else {
+                    aabb.max.z *= z_mult;
+                }
             }
 
             let cascade_projection_matrix = Matrix4::new_orthographic(
@@ -255,11 +321,14 @@ impl CsmRenderer {
             let light_view_projection = cascade_projection_matrix * light_view_matrix;
             self.cascades[i].view_proj_matrix = light_view_projection;
             self.cascades[i].z_far = z_far;
+            self.cascades[i].texel_size = texel_size_x.max(texel_size_y);
 
             let viewport = Rect::new(0, 0, self.size as i32, self.size as i32);
             let framebuffer = &mut self.cascades[i].frame_buffer;
             framebuffer.clear(state, viewport, None, Some(1.0), None);
 
+            state.set_depth_clamp(pancaking);
+
             for batch in batches.batches.iter() {
                 let mut material_state = batch.material.state();
                 let Some(material) = material_state.data() else {
@@ -293,15 +362,9 @@ impl CsmRenderer {
                         state,
                         viewport,
                         &render_pass.program,
-                        &DrawParameters {
-                            cull_face: Some(CullFace::Back),
-                            color_write: ColorMask::all(false),
-                            depth_write: true,
-                            stencil_test: None,
-                            depth_test: true,
-                            blend: None,
-                            stencil_op: Default::default(),
-                        },
+                        // Respect draw parameters of the pass, so two-sided geometry (such as flat
+                        // sprites) will cast shadows regardless of its orientation.
+                        &render_pass.draw_params,
                         instance.element_range,
                         |mut program_binding| {
                             apply_material(MaterialContext {
@@ -314,6 +377,10 @@ impl CsmRenderer {
                                 wvp_matrix: &(light_view_projection * instance.world_transform),
                                 bone_matrices: &instance.bone_matrices,
                                 use_skeletal_animation: batch.is_skinned,
+                                use_dual_quaternion_skinning: instance.use_dual_quaternion_skinning,
+                                instance_matrices: &[],
+                                instance_bone_count: 0,
+                                property_overrides: instance.property_overrides(),
                                 camera_position: &camera.global_position(),
                                 camera_up_vector: &camera_up,
                                 camera_side_vector: &camera_side,
@@ -327,9 +394,11 @@ impl CsmRenderer {
                                 black_dummy: &black_dummy,
                                 volume_dummy: &volume_dummy,
                                 persistent_identifier: instance.persistent_identifier,
-                                light_data: None,            // TODO
+                                light_data: None, // TODO
+                                velocity_data: None,
                                 ambient_light: Color::WHITE, // TODO
                                 scene_depth: None,
+                                scene_color: None,
                                 z_far,
                             });
                         },
@@ -338,6 +407,8 @@ impl CsmRenderer {
             }
         }
 
+        state.set_depth_clamp(false);
+
         Ok(stats)
     }
 }
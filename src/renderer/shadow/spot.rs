@@ -3,6 +3,7 @@ use crate::{
         algebra::{Matrix4, Vector3},
         color::Color,
         math::Rect,
+        pool::Handle,
         scope_profile,
     },
     renderer::{
@@ -23,7 +24,7 @@ use crate::{
         GeometryCache, MaterialContext, RenderPassStatistics, ShadowMapPrecision,
         SPOT_SHADOW_PASS_NAME,
     },
-    scene::graph::Graph,
+    scene::{collider::BitMask, graph::Graph},
 };
 use fyrox_core::math::Matrix4Ext;
 use std::{cell::RefCell, rc::Rc};
@@ -155,6 +156,8 @@ impl SpotShadowMapRenderer {
                 z_far,
                 view_matrix: light_view_matrix,
                 projection_matrix: light_projection_matrix,
+                render_mask: BitMask(u32::MAX),
+                lod_camera: Handle::NONE,
             },
             SPOT_SHADOW_PASS_NAME.clone(),
         );
@@ -214,6 +217,10 @@ impl SpotShadowMapRenderer {
                             wvp_matrix: &(light_view_projection * instance.world_transform),
                             bone_matrices: &instance.bone_matrices,
                             use_skeletal_animation: batch.is_skinned,
+                            use_dual_quaternion_skinning: instance.use_dual_quaternion_skinning,
+                            instance_matrices: &[],
+                            instance_bone_count: 0,
+                            property_overrides: instance.property_overrides(),
                             camera_position: &Default::default(),
                             camera_up_vector: &camera_up,
                             camera_side_vector: &camera_side,
@@ -227,9 +234,11 @@ impl SpotShadowMapRenderer {
                             black_dummy: &black_dummy,
                             volume_dummy: &volume_dummy,
                             persistent_identifier: instance.persistent_identifier,
-                            light_data: None,            // TODO
+                            light_data: None, // TODO
+                            velocity_data: None,
                             ambient_light: Color::WHITE, // TODO
                             scene_depth: None,
+                            scene_color: None,
                             z_far,
                         });
                     },
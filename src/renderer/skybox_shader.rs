@@ -9,6 +9,7 @@ pub struct SkyboxShader {
     pub program: GpuProgram,
     pub wvp_matrix: UniformLocation,
     pub cubemap_texture: UniformLocation,
+    pub is_linear: UniformLocation,
 }
 
 impl SkyboxShader {
@@ -23,6 +24,7 @@ impl SkyboxShader {
                 .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
             cubemap_texture: program
                 .uniform_location(state, &ImmutableString::new("cubemapTexture"))?,
+            is_linear: program.uniform_location(state, &ImmutableString::new("isLinear"))?,
             program,
         })
     }
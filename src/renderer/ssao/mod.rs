@@ -1,3 +1,6 @@
+//! Screen space ambient occlusion. Supports classic hemisphere-sampling SSAO and ground-truth
+//! ambient occlusion (GTAO), both with optional temporal accumulation.
+
 use crate::renderer::framework::geometry_buffer::ElementRange;
 use crate::{
     core::{
@@ -21,8 +24,10 @@ use crate::{
             state::PipelineState,
         },
         gbuffer::GBuffer,
+        make_viewport_matrix,
         ssao::blur::Blur,
-        RenderPassStatistics,
+        taa::projection_jitter,
+        AmbientOcclusionMethod, AmbientOcclusionQuality, RenderPassStatistics,
     },
     scene::mesh::surface::SurfaceData,
 };
@@ -36,6 +41,26 @@ const KERNEL_SIZE: usize = 32;
 // Size of noise texture.
 const NOISE_SIZE: usize = 4;
 
+/// How much of the current frame is blended in the history each frame.
+const TEMPORAL_BLEND_FACTOR: f32 = 0.1;
+
+fn ssao_sample_count(quality: AmbientOcclusionQuality) -> i32 {
+    match quality {
+        AmbientOcclusionQuality::Low => 8,
+        AmbientOcclusionQuality::Medium => 16,
+        AmbientOcclusionQuality::High => KERNEL_SIZE as i32,
+    }
+}
+
+/// Returns amount of slices and amount of horizon search steps per slice.
+fn gtao_slices_and_steps(quality: AmbientOcclusionQuality) -> (i32, i32) {
+    match quality {
+        AmbientOcclusionQuality::Low => (2, 4),
+        AmbientOcclusionQuality::Medium => (3, 6),
+        AmbientOcclusionQuality::High => (4, 8),
+    }
+}
+
 struct Shader {
     program: GpuProgram,
     depth_sampler: UniformLocation,
@@ -48,6 +73,9 @@ struct Shader {
     inv_proj_matrix: UniformLocation,
     world_view_proj_matrix: UniformLocation,
     view_matrix: UniformLocation,
+    noise_offset: UniformLocation,
+    sample_count: UniformLocation,
+    intensity: UniformLocation,
 }
 
 impl Shader {
@@ -72,21 +100,170 @@ impl Shader {
             world_view_proj_matrix: program
                 .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
             view_matrix: program.uniform_location(state, &ImmutableString::new("viewMatrix"))?,
+            noise_offset: program.uniform_location(state, &ImmutableString::new("noiseOffset"))?,
+            sample_count: program.uniform_location(state, &ImmutableString::new("sampleCount"))?,
+            intensity: program.uniform_location(state, &ImmutableString::new("intensity"))?,
+            program,
+        })
+    }
+}
+
+struct GtaoShader {
+    program: GpuProgram,
+    depth_sampler: UniformLocation,
+    normal_sampler: UniformLocation,
+    noise_sampler: UniformLocation,
+    radius: UniformLocation,
+    intensity: UniformLocation,
+    projection_matrix: UniformLocation,
+    inv_proj_matrix: UniformLocation,
+    noise_scale: UniformLocation,
+    noise_offset: UniformLocation,
+    world_view_proj_matrix: UniformLocation,
+    view_matrix: UniformLocation,
+    slice_count: UniformLocation,
+    step_count: UniformLocation,
+}
+
+impl GtaoShader {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("../shaders/gtao_fs.glsl");
+        let vertex_source = include_str!("../shaders/ssao_vs.glsl");
+        let program = GpuProgram::from_source(state, "GtaoShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            depth_sampler: program
+                .uniform_location(state, &ImmutableString::new("depthSampler"))?,
+            normal_sampler: program
+                .uniform_location(state, &ImmutableString::new("normalSampler"))?,
+            noise_sampler: program
+                .uniform_location(state, &ImmutableString::new("noiseSampler"))?,
+            radius: program.uniform_location(state, &ImmutableString::new("radius"))?,
+            intensity: program.uniform_location(state, &ImmutableString::new("intensity"))?,
+            projection_matrix: program
+                .uniform_location(state, &ImmutableString::new("projectionMatrix"))?,
+            inv_proj_matrix: program
+                .uniform_location(state, &ImmutableString::new("inverseProjectionMatrix"))?,
+            noise_scale: program.uniform_location(state, &ImmutableString::new("noiseScale"))?,
+            noise_offset: program.uniform_location(state, &ImmutableString::new("noiseOffset"))?,
+            world_view_proj_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            view_matrix: program.uniform_location(state, &ImmutableString::new("viewMatrix"))?,
+            slice_count: program.uniform_location(state, &ImmutableString::new("sliceCount"))?,
+            step_count: program.uniform_location(state, &ImmutableString::new("stepCount"))?,
             program,
         })
     }
 }
 
+struct TemporalShader {
+    program: GpuProgram,
+    wvp_matrix: UniformLocation,
+    current_texture: UniformLocation,
+    history_texture: UniformLocation,
+    depth_texture: UniformLocation,
+    inv_view_projection: UniformLocation,
+    previous_view_projection: UniformLocation,
+    inverse_screen_size: UniformLocation,
+    blend_factor: UniformLocation,
+    history_valid: UniformLocation,
+}
+
+impl TemporalShader {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("../shaders/ssao_temporal_fs.glsl");
+        let vertex_source = include_str!("../shaders/flat_vs.glsl");
+        let program =
+            GpuProgram::from_source(state, "SsaoTemporalShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            current_texture: program
+                .uniform_location(state, &ImmutableString::new("currentTexture"))?,
+            history_texture: program
+                .uniform_location(state, &ImmutableString::new("historyTexture"))?,
+            depth_texture: program
+                .uniform_location(state, &ImmutableString::new("depthTexture"))?,
+            inv_view_projection: program
+                .uniform_location(state, &ImmutableString::new("invViewProjection"))?,
+            previous_view_projection: program
+                .uniform_location(state, &ImmutableString::new("previousViewProjection"))?,
+            inverse_screen_size: program
+                .uniform_location(state, &ImmutableString::new("inverseScreenSize"))?,
+            blend_factor: program.uniform_location(state, &ImmutableString::new("blendFactor"))?,
+            history_valid: program
+                .uniform_location(state, &ImmutableString::new("historyValid"))?,
+            program,
+        })
+    }
+}
+
+fn make_occlusion_framebuffer(
+    state: &PipelineState,
+    width: usize,
+    height: usize,
+) -> Result<FrameBuffer, FrameworkError> {
+    let mut texture = GpuTexture::new(
+        state,
+        GpuTextureKind::Rectangle { width, height },
+        PixelKind::R32F,
+        MinificationFilter::Nearest,
+        MagnificationFilter::Nearest,
+        1,
+        None,
+    )?;
+    texture
+        .bind_mut(state, 0)
+        .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
+        .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
+
+    FrameBuffer::new(
+        state,
+        None,
+        vec![Attachment {
+            kind: AttachmentKind::Color,
+            texture: Rc::new(RefCell::new(texture)),
+        }],
+    )
+}
+
+/// Accumulated ambient occlusion of a camera. It is created lazily on first use with temporal
+/// accumulation enabled.
+#[derive(Default)]
+pub struct AmbientOcclusionHistory {
+    framebuffer: Option<FrameBuffer>,
+    size: (i32, i32),
+    previous_view_projection: Matrix4<f32>,
+    valid: bool,
+}
+
+pub(crate) struct AmbientOcclusionRenderContext<'a> {
+    pub state: &'a PipelineState,
+    pub gbuffer: &'a GBuffer,
+    pub projection_matrix: Matrix4<f32>,
+    pub view_matrix: Matrix3<f32>,
+    pub view_projection: Matrix4<f32>,
+    pub method: AmbientOcclusionMethod,
+    pub quality: AmbientOcclusionQuality,
+    pub radius: f32,
+    pub intensity: f32,
+    /// History of the camera, `None` disables temporal accumulation.
+    pub history: Option<&'a mut AmbientOcclusionHistory>,
+}
+
 pub struct ScreenSpaceAmbientOcclusionRenderer {
     blur: Blur,
     shader: Shader,
+    gtao_shader: GtaoShader,
+    temporal_shader: TemporalShader,
     framebuffer: FrameBuffer,
+    temporal_framebuffer: FrameBuffer,
     quad: GeometryBuffer,
     width: i32,
     height: i32,
     noise: Rc<RefCell<GpuTexture>>,
     kernel: [Vector3<f32>; KERNEL_SIZE],
-    radius: f32,
+    frame_index: u32,
+    result: Rc<RefCell<GpuTexture>>,
 }
 
 impl ScreenSpaceAmbientOcclusionRenderer {
@@ -120,9 +297,15 @@ impl ScreenSpaceAmbientOcclusionRenderer {
 
         let mut rng = crate::rand::thread_rng();
 
+        let blur = Blur::new(state, width, height)?;
+
         Ok(Self {
-            blur: Blur::new(state, width, height)?,
+            result: blur.result(),
+            blur,
             shader: Shader::new(state)?,
+            gtao_shader: GtaoShader::new(state)?,
+            temporal_shader: TemporalShader::new(state)?,
+            temporal_framebuffer: make_occlusion_framebuffer(state, width, height)?,
             framebuffer: FrameBuffer::new(
                 state,
                 None,
@@ -183,47 +366,42 @@ impl ScreenSpaceAmbientOcclusionRenderer {
                     .set_wrap(Coordinate::T, WrapMode::Repeat);
                 texture
             })),
-            radius: 0.5,
+            frame_index: 0,
         })
     }
 
-    pub fn set_radius(&mut self, radius: f32) {
-        self.radius = radius.abs();
-    }
-
     fn raw_ao_map(&self) -> Rc<RefCell<GpuTexture>> {
         self.framebuffer.color_attachments()[0].texture.clone()
     }
 
     pub fn ao_map(&self) -> Rc<RefCell<GpuTexture>> {
-        self.blur.result()
+        self.result.clone()
     }
 
     pub(crate) fn render(
         &mut self,
-        state: &PipelineState,
-        gbuffer: &GBuffer,
-        projection_matrix: Matrix4<f32>,
-        view_matrix: Matrix3<f32>,
+        ctx: AmbientOcclusionRenderContext,
     ) -> Result<RenderPassStatistics, FrameworkError> {
         scope_profile!();
 
+        let AmbientOcclusionRenderContext {
+            state,
+            gbuffer,
+            projection_matrix,
+            view_matrix,
+            view_projection,
+            method,
+            quality,
+            radius,
+            intensity,
+            history,
+        } = ctx;
+
         let mut stats = RenderPassStatistics::default();
 
         let viewport = Rect::new(0, 0, self.width, self.height);
 
-        let frame_matrix = Matrix4::new_orthographic(
-            0.0,
-            viewport.w() as f32,
-            viewport.h() as f32,
-            0.0,
-            -1.0,
-            1.0,
-        ) * Matrix4::new_nonuniform_scaling(&Vector3::new(
-            viewport.w() as f32,
-            viewport.h() as f32,
-            0.0,
-        ));
+        let frame_matrix = make_viewport_matrix(viewport);
 
         self.framebuffer.clear(
             state,
@@ -233,49 +411,169 @@ impl ScreenSpaceAmbientOcclusionRenderer {
             None,
         );
 
-        let shader = &self.shader;
         let noise = &self.noise;
         let kernel = &self.kernel;
         let noise_scale = Vector2::new(
             self.width as f32 / NOISE_SIZE as f32,
             self.height as f32 / NOISE_SIZE as f32,
         );
-        let radius = self.radius;
-        stats += self.framebuffer.draw(
-            &self.quad,
-            state,
-            viewport,
-            &shader.program,
-            &DrawParameters {
-                cull_face: None,
-                color_write: Default::default(),
-                depth_write: false,
-                stencil_test: None,
-                depth_test: false,
-                blend: None,
-                stencil_op: Default::default(),
-            },
-            ElementRange::Full,
-            |mut program_binding| {
-                program_binding
-                    .set_texture(&shader.depth_sampler, &gbuffer.depth())
-                    .set_texture(&shader.normal_sampler, &gbuffer.normal_texture())
-                    .set_texture(&shader.noise_sampler, noise)
-                    .set_vector3_slice(&shader.kernel, kernel)
-                    .set_vector2(&shader.noise_scale, &noise_scale)
-                    .set_f32(&shader.radius, radius)
-                    .set_matrix4(&shader.world_view_proj_matrix, &frame_matrix)
-                    .set_matrix4(&shader.projection_matrix, &projection_matrix)
-                    .set_matrix4(
-                        &shader.inv_proj_matrix,
-                        &projection_matrix.try_inverse().unwrap_or_default(),
-                    )
-                    .set_matrix3(&shader.view_matrix, &view_matrix);
-            },
-        )?;
+        // Shift the noise each frame when accumulating, so the history will contain different
+        // sample patterns.
+        let noise_offset = if history.is_some() {
+            self.frame_index = self.frame_index.wrapping_add(1);
+            projection_jitter(self.frame_index)
+        } else {
+            Vector2::default()
+        };
+        let inv_projection = projection_matrix.try_inverse().unwrap_or_default();
+        let radius = radius.abs();
+        let draw_parameters = DrawParameters {
+            cull_face: None,
+            color_write: Default::default(),
+            depth_write: false,
+            stencil_test: None,
+            depth_test: false,
+            blend: None,
+            stencil_op: Default::default(),
+        };
+
+        match method {
+            AmbientOcclusionMethod::Ssao => {
+                let shader = &self.shader;
+                stats += self.framebuffer.draw(
+                    &self.quad,
+                    state,
+                    viewport,
+                    &shader.program,
+                    &draw_parameters,
+                    ElementRange::Full,
+                    |mut program_binding| {
+                        program_binding
+                            .set_texture(&shader.depth_sampler, &gbuffer.depth())
+                            .set_texture(&shader.normal_sampler, &gbuffer.normal_texture())
+                            .set_texture(&shader.noise_sampler, noise)
+                            .set_vector3_slice(&shader.kernel, kernel)
+                            .set_vector2(&shader.noise_scale, &noise_scale)
+                            .set_vector2(&shader.noise_offset, &noise_offset)
+                            .set_f32(&shader.radius, radius)
+                            .set_f32(&shader.intensity, intensity)
+                            .set_i32(&shader.sample_count, ssao_sample_count(quality))
+                            .set_matrix4(&shader.world_view_proj_matrix, &frame_matrix)
+                            .set_matrix4(&shader.projection_matrix, &projection_matrix)
+                            .set_matrix4(&shader.inv_proj_matrix, &inv_projection)
+                            .set_matrix3(&shader.view_matrix, &view_matrix);
+                    },
+                )?;
+            }
+            AmbientOcclusionMethod::Gtao => {
+                let shader = &self.gtao_shader;
+                let (slice_count, step_count) = gtao_slices_and_steps(quality);
+                stats += self.framebuffer.draw(
+                    &self.quad,
+                    state,
+                    viewport,
+                    &shader.program,
+                    &draw_parameters,
+                    ElementRange::Full,
+                    |mut program_binding| {
+                        program_binding
+                            .set_texture(&shader.depth_sampler, &gbuffer.depth())
+                            .set_texture(&shader.normal_sampler, &gbuffer.normal_texture())
+                            .set_texture(&shader.noise_sampler, noise)
+                            .set_vector2(&shader.noise_scale, &noise_scale)
+                            .set_vector2(&shader.noise_offset, &noise_offset)
+                            .set_f32(&shader.radius, radius)
+                            .set_f32(&shader.intensity, intensity)
+                            .set_i32(&shader.slice_count, slice_count)
+                            .set_i32(&shader.step_count, step_count)
+                            .set_matrix4(&shader.world_view_proj_matrix, &frame_matrix)
+                            .set_matrix4(&shader.projection_matrix, &projection_matrix)
+                            .set_matrix4(&shader.inv_proj_matrix, &inv_projection)
+                            .set_matrix3(&shader.view_matrix, &view_matrix);
+                    },
+                )?;
+            }
+        }
 
         self.blur.render(state, self.raw_ao_map())?;
 
+        self.result = self.blur.result();
+
+        if let Some(history) = history {
+            let size = (self.width, self.height);
+            if history.framebuffer.is_none() || history.size != size {
+                history.framebuffer = Some(make_occlusion_framebuffer(
+                    state,
+                    self.width as usize,
+                    self.height as usize,
+                )?);
+                history.size = size;
+                history.valid = false;
+            }
+            let history_framebuffer = history.framebuffer.as_mut().unwrap();
+
+            let shader = &self.temporal_shader;
+            let inverse_screen_size =
+                Vector2::new(1.0 / self.width as f32, 1.0 / self.height as f32);
+            let inv_view_projection = view_projection
+                .try_inverse()
+                .unwrap_or_else(Matrix4::identity);
+            let current_texture = self.blur.result();
+            let history_texture = history_framebuffer.color_attachments()[0].texture.clone();
+            let depth_texture = gbuffer.depth();
+
+            stats += self.temporal_framebuffer.draw(
+                &self.quad,
+                state,
+                viewport,
+                &shader.program,
+                &draw_parameters,
+                ElementRange::Full,
+                |mut program_binding| {
+                    program_binding
+                        .set_matrix4(&shader.wvp_matrix, &frame_matrix)
+                        .set_texture(&shader.current_texture, &current_texture)
+                        .set_texture(&shader.history_texture, &history_texture)
+                        .set_texture(&shader.depth_texture, &depth_texture)
+                        .set_matrix4(&shader.inv_view_projection, &inv_view_projection)
+                        .set_matrix4(
+                            &shader.previous_view_projection,
+                            &history.previous_view_projection,
+                        )
+                        .set_vector2(&shader.inverse_screen_size, &inverse_screen_size)
+                        .set_f32(&shader.blend_factor, TEMPORAL_BLEND_FACTOR)
+                        .set_bool(&shader.history_valid, history.valid);
+                },
+            )?;
+
+            // Store accumulated occlusion as history for the next frame.
+            let accumulated_texture = self.temporal_framebuffer.color_attachments()[0]
+                .texture
+                .clone();
+            stats += history_framebuffer.draw(
+                &self.quad,
+                state,
+                viewport,
+                &shader.program,
+                &draw_parameters,
+                ElementRange::Full,
+                |mut program_binding| {
+                    program_binding
+                        .set_matrix4(&shader.wvp_matrix, &frame_matrix)
+                        .set_texture(&shader.current_texture, &accumulated_texture)
+                        .set_texture(&shader.history_texture, &accumulated_texture)
+                        .set_texture(&shader.depth_texture, &depth_texture)
+                        .set_vector2(&shader.inverse_screen_size, &inverse_screen_size)
+                        .set_bool(&shader.history_valid, false);
+                },
+            )?;
+
+            history.previous_view_projection = view_projection;
+            history.valid = true;
+
+            self.result = accumulated_texture;
+        }
+
         Ok(stats)
     }
 }
@@ -0,0 +1,259 @@
+//! Structured per-frame rendering statistics: draw calls and triangles per camera and GPU timings of
+//! each rendering stage. See [`RenderStatistics`] docs for more info.
+
+use crate::{
+    core::pool::Handle,
+    renderer::{
+        framework::{
+            query::{Query, QueryKind},
+            state::{GlKind, PipelineState},
+        },
+        LightingStatistics, RenderPassStatistics,
+    },
+    scene::{node::Node, Scene},
+};
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter},
+    time::Duration,
+};
+
+/// Maximum amount of frames, that could wait for the results of GPU queries. Older frames are dropped
+/// to prevent unbounded growth of the queue when the GPU is too slow.
+const MAX_PENDING_FRAMES: usize = 8;
+
+/// A stage of the rendering pipeline, whose GPU time is measured separately.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum RenderStage {
+    /// Rendering of shadow maps.
+    Shadows,
+    /// Filling of the G-Buffer with opaque geometry.
+    GBuffer,
+    /// Deferred lighting (including SSAO) and custom opaque render passes.
+    Lighting,
+    /// Forward rendering of transparent geometry and custom HDR render passes.
+    Forward,
+    /// Bloom, tone mapping, anti-aliasing, debug geometry, custom LDR render passes and upscaling.
+    PostProcessing,
+    /// User interface.
+    Ui,
+}
+
+/// GPU time spent on each stage of the rendering pipeline in a frame. Shadows are rendered in the
+/// middle of the lighting stage, but their time is excluded from the lighting time.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct GpuTimings {
+    /// Time spent on rendering of shadow maps.
+    pub shadows: Duration,
+    /// Time spent on filling the G-Buffer.
+    pub gbuffer: Duration,
+    /// Time spent on deferred lighting.
+    pub lighting: Duration,
+    /// Time spent on forward rendering.
+    pub forward: Duration,
+    /// Time spent on post-processing.
+    pub post_processing: Duration,
+    /// Time spent on rendering of the user interface.
+    pub ui: Duration,
+}
+
+impl GpuTimings {
+    /// Returns time spent on the given stage.
+    pub fn get(&self, stage: RenderStage) -> Duration {
+        match stage {
+            RenderStage::Shadows => self.shadows,
+            RenderStage::GBuffer => self.gbuffer,
+            RenderStage::Lighting => self.lighting,
+            RenderStage::Forward => self.forward,
+            RenderStage::PostProcessing => self.post_processing,
+            RenderStage::Ui => self.ui,
+        }
+    }
+
+    fn get_mut(&mut self, stage: RenderStage) -> &mut Duration {
+        match stage {
+            RenderStage::Shadows => &mut self.shadows,
+            RenderStage::GBuffer => &mut self.gbuffer,
+            RenderStage::Lighting => &mut self.lighting,
+            RenderStage::Forward => &mut self.forward,
+            RenderStage::PostProcessing => &mut self.post_processing,
+            RenderStage::Ui => &mut self.ui,
+        }
+    }
+
+    /// Returns total GPU time of the frame.
+    pub fn total(&self) -> Duration {
+        self.shadows + self.gbuffer + self.lighting + self.forward + self.post_processing + self.ui
+    }
+}
+
+impl Display for GpuTimings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let ms = |d: Duration| d.as_secs_f32() * 1000.0;
+        write!(
+            f,
+            "GPU Timings:\n\
+            \tShadows: {:.2} ms\n\
+            \tG-Buffer: {:.2} ms\n\
+            \tLighting: {:.2} ms\n\
+            \tForward: {:.2} ms\n\
+            \tPost-processing: {:.2} ms\n\
+            \tUI: {:.2} ms\n\
+            \tTotal: {:.2} ms",
+            ms(self.shadows),
+            ms(self.gbuffer),
+            ms(self.lighting),
+            ms(self.forward),
+            ms(self.post_processing),
+            ms(self.ui),
+            ms(self.total())
+        )
+    }
+}
+
+/// Rendering statistics of a single camera.
+#[derive(Copy, Clone, Debug)]
+pub struct CameraStatistics {
+    /// A handle of the scene, that contains the camera.
+    pub scene: Handle<Scene>,
+    /// A handle of the camera.
+    pub camera: Handle<Node>,
+    /// Amount of draw calls and triangles rendered for the camera (including shadow maps and
+    /// post-processing).
+    pub geometry: RenderPassStatistics,
+    /// Amount of lights and shadow maps rendered for the camera.
+    pub lighting: LightingStatistics,
+}
+
+/// Structured rendering statistics, that is updated every frame. It could be used to track performance
+/// regressions in-game (for example, to show an overlay) or in benchmarks.
+///
+/// ## GPU Timings
+///
+/// GPU timings are gathered using timer queries, which results become available a few frames later
+/// without stalling the pipeline. This means that [`Self::gpu_timings`] always lags a few frames
+/// behind. Timer queries are not available on OpenGL ES (and WebGL), `gpu_timings` is always `None`
+/// there.
+#[derive(Clone, Debug, Default)]
+pub struct RenderStatistics {
+    /// Statistics for every camera, that was rendered in the last frame.
+    pub cameras: Vec<CameraStatistics>,
+    /// The most recent GPU timings of rendering stages, if supported.
+    pub gpu_timings: Option<GpuTimings>,
+}
+
+impl Display for RenderStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for camera in self.cameras.iter() {
+            writeln!(
+                f,
+                "Camera {} of Scene {}:\n{}\n{}",
+                camera.camera, camera.scene, camera.geometry, camera.lighting
+            )?;
+        }
+        if let Some(gpu_timings) = self.gpu_timings.as_ref() {
+            writeln!(f, "{}", gpu_timings)?;
+        }
+        Ok(())
+    }
+}
+
+/// Measures GPU time of rendering stages using timer queries. Only one timer query could be active at
+/// a time, so nested stages (shadows inside lighting) are measured by splitting the outer stage into
+/// multiple segments.
+pub(crate) struct GpuProfiler {
+    supported: bool,
+    free: Vec<Query>,
+    current: Vec<(RenderStage, Query)>,
+    pending: VecDeque<Vec<(RenderStage, Query)>>,
+    stack: Vec<RenderStage>,
+    timings: Option<GpuTimings>,
+}
+
+impl GpuProfiler {
+    pub fn new(state: &PipelineState) -> Self {
+        Self {
+            supported: state.gl_kind() == GlKind::OpenGL,
+            free: Default::default(),
+            current: Default::default(),
+            pending: Default::default(),
+            stack: Default::default(),
+            timings: None,
+        }
+    }
+
+    /// Starts measuring the given stage, suspending the current one (if any).
+    pub fn begin(&mut self, state: &PipelineState, stage: RenderStage) {
+        if !self.supported {
+            return;
+        }
+        if !self.stack.is_empty() {
+            self.end_segment(state);
+        }
+        self.stack.push(stage);
+        self.begin_segment(state, stage);
+    }
+
+    /// Stops measuring the current stage, resuming the previous one (if any).
+    pub fn end(&mut self, state: &PipelineState) {
+        if !self.supported || self.stack.pop().is_none() {
+            return;
+        }
+        self.end_segment(state);
+        if let Some(&outer) = self.stack.last() {
+            self.begin_segment(state, outer);
+        }
+    }
+
+    fn begin_segment(&mut self, state: &PipelineState, stage: RenderStage) {
+        let query = match self.free.pop().map_or_else(|| Query::new(state), Ok) {
+            Ok(query) => query,
+            Err(_) => {
+                // Timer queries are not supported by the driver, stop trying.
+                self.supported = false;
+                self.stack.clear();
+                return;
+            }
+        };
+        query.begin(state, QueryKind::TimeElapsed);
+        self.current.push((stage, query));
+    }
+
+    fn end_segment(&mut self, state: &PipelineState) {
+        if let Some((_, query)) = self.current.last() {
+            query.end(state, QueryKind::TimeElapsed);
+        }
+    }
+
+    /// Finishes the current frame and collects the results of the previous frames, that are ready.
+    pub fn end_frame(&mut self, state: &PipelineState) {
+        while !self.stack.is_empty() {
+            self.end(state);
+        }
+
+        if !self.current.is_empty() {
+            self.pending.push_back(std::mem::take(&mut self.current));
+        }
+        while self.pending.len() > MAX_PENDING_FRAMES {
+            self.pending.pop_front();
+        }
+
+        while let Some(frame) = self.pending.front() {
+            if !frame.iter().all(|(_, query)| query.is_available(state)) {
+                break;
+            }
+
+            let mut timings = GpuTimings::default();
+            for (stage, query) in self.pending.pop_front().unwrap() {
+                *timings.get_mut(stage) += Duration::from_nanos(query.result(state) as u64);
+                self.free.push(query);
+            }
+            self.timings = Some(timings);
+        }
+    }
+
+    /// Returns the most recent GPU timings.
+    pub fn timings(&self) -> Option<GpuTimings> {
+        self.timings
+    }
+}
@@ -0,0 +1,343 @@
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector2},
+        math::Rect,
+        sstorage::ImmutableString,
+    },
+    renderer::{
+        framework::{
+            error::FrameworkError,
+            framebuffer::{Attachment, AttachmentKind, DrawParameters, FrameBuffer},
+            geometry_buffer::{ElementRange, GeometryBuffer, GeometryBufferKind},
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{
+                Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
+                PixelKind, WrapMode,
+            },
+            state::PipelineState,
+        },
+        make_viewport_matrix, RenderPassStatistics,
+    },
+    scene::mesh::surface::SurfaceData,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// Amount of unique sub-pixel offsets used for camera jitter.
+const JITTER_SEQUENCE_LENGTH: u32 = 8;
+
+/// How much of the current frame is blended in the history each frame.
+const BLEND_FACTOR: f32 = 0.1;
+
+struct TaaShader {
+    pub program: GpuProgram,
+    pub wvp_matrix: UniformLocation,
+    pub current_texture: UniformLocation,
+    pub history_texture: UniformLocation,
+    pub depth_texture: UniformLocation,
+    pub velocity_texture: UniformLocation,
+    pub inv_view_projection: UniformLocation,
+    pub previous_view_projection: UniformLocation,
+    pub jitter: UniformLocation,
+    pub inverse_screen_size: UniformLocation,
+    pub blend_factor: UniformLocation,
+    pub history_valid: UniformLocation,
+}
+
+impl TaaShader {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/taa_fs.glsl");
+        let vertex_source = include_str!("shaders/flat_vs.glsl");
+
+        let program = GpuProgram::from_source(state, "TAAShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            current_texture: program
+                .uniform_location(state, &ImmutableString::new("currentTexture"))?,
+            history_texture: program
+                .uniform_location(state, &ImmutableString::new("historyTexture"))?,
+            depth_texture: program
+                .uniform_location(state, &ImmutableString::new("depthTexture"))?,
+            velocity_texture: program
+                .uniform_location(state, &ImmutableString::new("velocityTexture"))?,
+            inv_view_projection: program
+                .uniform_location(state, &ImmutableString::new("invViewProjection"))?,
+            previous_view_projection: program
+                .uniform_location(state, &ImmutableString::new("previousViewProjection"))?,
+            jitter: program.uniform_location(state, &ImmutableString::new("jitter"))?,
+            inverse_screen_size: program
+                .uniform_location(state, &ImmutableString::new("inverseScreenSize"))?,
+            blend_factor: program.uniform_location(state, &ImmutableString::new("blendFactor"))?,
+            history_valid: program
+                .uniform_location(state, &ImmutableString::new("historyValid"))?,
+            program,
+        })
+    }
+}
+
+fn halton(mut index: u32, base: u32) -> f32 {
+    let mut fraction = 1.0;
+    let mut result = 0.0;
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
+
+/// Returns sub-pixel camera offset (in pixels, in `[-0.5; 0.5]` range) for the given frame. Offsets
+/// are taken from Halton (2, 3) sequence, which gives good coverage of a pixel area.
+pub fn projection_jitter(frame_index: u32) -> Vector2<f32> {
+    let index = frame_index % JITTER_SEQUENCE_LENGTH + 1;
+    Vector2::new(halton(index, 2) - 0.5, halton(index, 3) - 0.5)
+}
+
+/// Accumulated anti-aliased frame of a camera.
+pub struct TaaHistory {
+    framebuffer: FrameBuffer,
+    valid: bool,
+}
+
+impl TaaHistory {
+    pub fn new(state: &PipelineState, width: usize, height: usize) -> Result<Self, FrameworkError> {
+        let mut texture = GpuTexture::new(
+            state,
+            GpuTextureKind::Rectangle { width, height },
+            PixelKind::RGBA8,
+            MinificationFilter::Linear,
+            MagnificationFilter::Linear,
+            1,
+            None,
+        )?;
+        texture
+            .bind_mut(state, 0)
+            .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
+            .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
+
+        Ok(Self {
+            framebuffer: FrameBuffer::new(
+                state,
+                None,
+                vec![Attachment {
+                    kind: AttachmentKind::Color,
+                    texture: Rc::new(RefCell::new(texture)),
+                }],
+            )?,
+            valid: false,
+        })
+    }
+
+    pub fn texture(&self) -> Rc<RefCell<GpuTexture>> {
+        self.framebuffer.color_attachments()[0].texture.clone()
+    }
+}
+
+pub struct TaaRenderer {
+    shader: TaaShader,
+    quad: GeometryBuffer,
+}
+
+pub struct TaaRenderContext<'a> {
+    pub state: &'a PipelineState,
+    pub viewport: Rect<i32>,
+    pub frame_texture: Rc<RefCell<GpuTexture>>,
+    pub depth_texture: Rc<RefCell<GpuTexture>>,
+    /// Per-pixel velocity from the G-Buffer.
+    pub velocity_texture: Rc<RefCell<GpuTexture>>,
+    /// Jittered view-projection matrix of the current frame.
+    pub view_projection: Matrix4<f32>,
+    /// View-projection matrix of the previous frame without jitter. It is used to reproject the
+    /// pixels, that have no geometry (sky, background).
+    pub previous_view_projection: Matrix4<f32>,
+    /// Current jitter in pixels.
+    pub jitter: Vector2<f32>,
+    pub history: &'a mut TaaHistory,
+    pub frame_buffer: &'a mut FrameBuffer,
+}
+
+impl TaaRenderer {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader: TaaShader::new(state)?,
+            quad: GeometryBuffer::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                GeometryBufferKind::StaticDraw,
+                state,
+            )?,
+        })
+    }
+
+    /// Resolves current frame into the given frame buffer and updates the history.
+    pub(crate) fn render(
+        &self,
+        ctx: TaaRenderContext,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let mut statistics = RenderPassStatistics::default();
+
+        let TaaRenderContext {
+            state,
+            viewport,
+            frame_texture,
+            depth_texture,
+            velocity_texture,
+            view_projection,
+            previous_view_projection,
+            jitter,
+            history,
+            frame_buffer,
+        } = ctx;
+
+        let frame_matrix = make_viewport_matrix(viewport);
+        let inverse_screen_size =
+            Vector2::new(1.0 / viewport.w() as f32, 1.0 / viewport.h() as f32);
+        let inv_view_projection = view_projection
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity);
+        let history_texture = history.texture();
+
+        let draw_parameters = DrawParameters {
+            cull_face: None,
+            color_write: Default::default(),
+            depth_write: false,
+            stencil_test: None,
+            depth_test: false,
+            blend: None,
+            stencil_op: Default::default(),
+        };
+
+        statistics += frame_buffer.draw(
+            &self.quad,
+            state,
+            viewport,
+            &self.shader.program,
+            &draw_parameters,
+            ElementRange::Full,
+            |mut program_binding| {
+                program_binding
+                    .set_matrix4(&self.shader.wvp_matrix, &frame_matrix)
+                    .set_texture(&self.shader.current_texture, &frame_texture)
+                    .set_texture(&self.shader.history_texture, &history_texture)
+                    .set_texture(&self.shader.depth_texture, &depth_texture)
+                    .set_texture(&self.shader.velocity_texture, &velocity_texture)
+                    .set_matrix4(&self.shader.inv_view_projection, &inv_view_projection)
+                    .set_matrix4(
+                        &self.shader.previous_view_projection,
+                        &previous_view_projection,
+                    )
+                    .set_vector2(
+                        &self.shader.jitter,
+                        &jitter.component_mul(&inverse_screen_size),
+                    )
+                    .set_vector2(&self.shader.inverse_screen_size, &inverse_screen_size)
+                    .set_f32(&self.shader.blend_factor, BLEND_FACTOR)
+                    .set_bool(&self.shader.history_valid, history.valid);
+            },
+        )?;
+
+        // Store resolved frame as history for the next frame.
+        let resolved_texture = frame_buffer.color_attachments()[0].texture.clone();
+        statistics += history.framebuffer.draw(
+            &self.quad,
+            state,
+            viewport,
+            &self.shader.program,
+            &draw_parameters,
+            ElementRange::Full,
+            |mut program_binding| {
+                program_binding
+                    .set_matrix4(&self.shader.wvp_matrix, &frame_matrix)
+                    .set_texture(&self.shader.current_texture, &resolved_texture)
+                    .set_texture(&self.shader.history_texture, &resolved_texture)
+                    .set_texture(&self.shader.depth_texture, &depth_texture)
+                    .set_texture(&self.shader.velocity_texture, &velocity_texture)
+                    .set_vector2(&self.shader.jitter, &Vector2::zeros())
+                    .set_vector2(&self.shader.inverse_screen_size, &inverse_screen_size)
+                    .set_bool(&self.shader.history_valid, false);
+            },
+        )?;
+
+        history.valid = true;
+
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Matrix4, Vector2, Vector3, Vector4},
+        renderer::taa::{projection_jitter, JITTER_SEQUENCE_LENGTH},
+        scene::{base::BaseBuilder, camera::CameraBuilder, graph::Graph},
+    };
+
+    // Mirrors `S_Velocity` from shared.glsl.
+    fn velocity(
+        clip_position: Vector4<f32>,
+        previous_clip_position: Vector4<f32>,
+        projection_jitter: Vector2<f32>,
+    ) -> Vector2<f32> {
+        let current = clip_position.xy() / clip_position.w - projection_jitter;
+        let previous = previous_clip_position.xy() / previous_clip_position.w;
+        (current - previous) * 0.5
+    }
+
+    #[test]
+    fn test_jitter_sequence() {
+        let sequence = (0..JITTER_SEQUENCE_LENGTH)
+            .map(projection_jitter)
+            .collect::<Vec<_>>();
+
+        assert!((sequence[0] - Vector2::new(0.0, -1.0 / 6.0)).norm() < f32::EPSILON);
+
+        for (i, jitter) in sequence.iter().enumerate() {
+            assert!(jitter.x.abs() <= 0.5 && jitter.y.abs() <= 0.5);
+            // Every offset must be unique.
+            assert!(sequence[(i + 1)..].iter().all(|other| other != jitter));
+            // The sequence must repeat.
+            assert_eq!(
+                projection_jitter(i as u32 + JITTER_SEQUENCE_LENGTH),
+                *jitter
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_velocity() {
+        let frame_size = Vector2::new(800.0, 600.0);
+        let mut graph = Graph::new();
+        let camera = CameraBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let camera = graph[camera].as_camera_mut();
+
+        camera.calculate_matrices(frame_size);
+        let previous_view_projection = camera.unjittered_view_projection_matrix();
+
+        camera.set_projection_jitter(projection_jitter(1));
+        camera.calculate_matrices(frame_size);
+        assert_ne!(camera.projection_jitter_ndc(), Vector2::default());
+
+        let world = Matrix4::new_translation(&Vector3::new(1.0, 2.0, 10.0));
+        let position = Vector4::new(0.5, -0.5, 0.0, 1.0);
+
+        // A static object must have no velocity, even though the camera is jittered.
+        let v = velocity(
+            camera.view_projection_matrix() * world * position,
+            previous_view_projection * world * position,
+            camera.projection_jitter_ndc(),
+        );
+        assert!(v.norm() < 1.0e-5, "{v}");
+
+        // A moving object must have the velocity of its unjittered projection.
+        let previous_world = Matrix4::new_translation(&Vector3::new(0.0, 2.0, 10.0));
+        let v = velocity(
+            camera.view_projection_matrix() * world * position,
+            previous_view_projection * previous_world * position,
+            camera.projection_jitter_ndc(),
+        );
+        let current = camera.unjittered_view_projection_matrix() * world * position;
+        let previous = previous_view_projection * previous_world * position;
+        let expected = (current.xy() / current.w - previous.xy() / previous.w) * 0.5;
+        assert!((v - expected).norm() < 1.0e-5, "{v} {expected}");
+        assert!(v.norm() > 0.0);
+    }
+}
@@ -0,0 +1,366 @@
+//! Thumbnail renderer renders small preview images of models (prefabs) and materials. See
+//! [`ThumbnailRenderer`] docs for more info.
+
+use crate::{
+    asset::untyped::ResourceKind,
+    core::{
+        algebra::{Matrix4, UnitQuaternion, Vector2, Vector3},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, Rect},
+        pool::Handle,
+    },
+    gui::draw::DrawingContext,
+    material::MaterialResource,
+    renderer::{framework::error::FrameworkError, Renderer},
+    resource::{
+        model::{ModelResource, ModelResourceExtension},
+        texture::{
+            Texture, TextureKind, TexturePixelKind, TextureResource, TextureResourceExtension,
+        },
+    },
+    scene::{
+        base::BaseBuilder,
+        camera::{Camera, CameraBuilder, PerspectiveProjection, Projection, SkyBoxKind},
+        light::{directional::DirectionalLightBuilder, BaseLightBuilder},
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+            MeshBuilder,
+        },
+        node::Node,
+        transform::TransformBuilder,
+        Scene, SceneContainer,
+    },
+};
+
+/// A resource, that could be rendered to a thumbnail.
+#[derive(Clone, Debug)]
+pub enum ThumbnailSource {
+    /// A model (prefab). It is instantiated in the thumbnail scene.
+    Model(ModelResource),
+    /// A material. It is rendered on a sphere.
+    Material(MaterialResource),
+}
+
+/// Defines how thumbnails are rendered.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThumbnailSettings {
+    /// Width and height of thumbnails in pixels.
+    pub size: u32,
+    /// Rotation of the camera around vertical axis in radians.
+    pub yaw: f32,
+    /// Rotation of the camera around horizontal axis in radians. Positive values make the camera to
+    /// look down on the subject.
+    pub pitch: f32,
+    /// Vertical field of view of the camera in radians.
+    pub fov: f32,
+    /// A multiplier for the distance from the camera to the subject. Values larger than `1.0` add
+    /// empty space around the subject.
+    pub padding: f32,
+    /// Color of the background.
+    pub background: Color,
+    /// Color of the ambient lighting.
+    pub ambient_lighting_color: Color,
+}
+
+impl Default for ThumbnailSettings {
+    fn default() -> Self {
+        Self {
+            size: 128,
+            yaw: 30.0f32.to_radians(),
+            pitch: 25.0f32.to_radians(),
+            fov: 30.0f32.to_radians(),
+            padding: 1.1,
+            background: Color::opaque(60, 60, 60),
+            ambient_lighting_color: Color::opaque(80, 80, 80),
+        }
+    }
+}
+
+/// Calculates position and rotation of a camera with the given field of view, so a sphere with the
+/// given center and radius fits the view. Returns the position, the rotation and the distance to
+/// the center of the sphere.
+fn frame_sphere(
+    center: Vector3<f32>,
+    radius: f32,
+    settings: &ThumbnailSettings,
+) -> (Vector3<f32>, UnitQuaternion<f32>, f32) {
+    let rotation = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), settings.yaw)
+        * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), settings.pitch);
+    let half_fov = (settings.fov * 0.5).clamp(1.0f32.to_radians(), 89.0f32.to_radians());
+    let distance = radius.max(f32::EPSILON) / half_fov.sin() * settings.padding.max(1.0);
+    let look = rotation.transform_vector(&Vector3::z());
+    (center - look.scale(distance), rotation, distance)
+}
+
+/// Thumbnail renderer renders small preview images of models (prefabs) and materials, that could be
+/// used as inventory icons, content browser items, etc. Every subject is rendered in an isolated
+/// scene with a simple lighting rig, the camera is positioned so the bounding box of the subject
+/// fits the view.
+///
+/// The thumbnail scene is added to the scene container on first use and it is disabled, so it does
+/// not affect the rest of the game. Remove it using [`Self::destroy`] when thumbnails are not needed
+/// anymore.
+///
+/// Resources of the subject must be fully loaded before rendering, resources that are still loading
+/// will be rendered using fallback values (for example, textures will be white).
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use fyrox::{
+/// #     engine::Engine,
+/// #     renderer::thumbnail::{ThumbnailRenderer, ThumbnailSource},
+/// #     resource::{model::Model, texture::TextureResource},
+/// # };
+/// async fn make_icon(engine: &mut Engine, thumbnails: &mut ThumbnailRenderer) -> TextureResource {
+///     let sword = engine
+///         .resource_manager
+///         .request::<Model>("data/sword.fbx")
+///         .await
+///         .unwrap();
+///     let graphics_context = engine.graphics_context.as_initialized_mut();
+///     thumbnails
+///         .render(
+///             &mut graphics_context.renderer,
+///             &mut engine.scenes,
+///             &ThumbnailSource::Model(sword),
+///         )
+///         .unwrap()
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct ThumbnailRenderer {
+    settings: ThumbnailSettings,
+    scene: Handle<Scene>,
+    camera: Handle<Node>,
+}
+
+impl ThumbnailRenderer {
+    /// Creates new thumbnail renderer with the given settings.
+    pub fn new(settings: ThumbnailSettings) -> Self {
+        Self {
+            settings,
+            scene: Handle::NONE,
+            camera: Handle::NONE,
+        }
+    }
+
+    /// Returns current settings of the renderer.
+    pub fn settings(&self) -> &ThumbnailSettings {
+        &self.settings
+    }
+
+    /// Sets new settings of the renderer, they will be used for every next thumbnail.
+    pub fn set_settings(&mut self, settings: ThumbnailSettings) {
+        self.settings = settings;
+    }
+
+    /// Returns a handle of the thumbnail scene. It is [`Handle::NONE`] until the first thumbnail
+    /// is rendered.
+    pub fn scene(&self) -> Handle<Scene> {
+        self.scene
+    }
+
+    /// Removes the thumbnail scene from the given scene container.
+    pub fn destroy(&mut self, scenes: &mut SceneContainer) {
+        if scenes.is_valid_handle(self.scene) {
+            scenes.remove(self.scene);
+        }
+        self.scene = Handle::NONE;
+        self.camera = Handle::NONE;
+    }
+
+    fn create_scene(&mut self, scenes: &mut SceneContainer) {
+        let mut scene = Scene::new();
+        scene.enabled.set_value_and_mark_modified(false);
+
+        // Lighting rig is attached to the camera, so the subject is always lit from the front.
+        let key_light = DirectionalLightBuilder::new(
+            BaseLightBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_rotation(
+                            UnitQuaternion::from_axis_angle(
+                                &Vector3::y_axis(),
+                                -30.0f32.to_radians(),
+                            ) * UnitQuaternion::from_axis_angle(
+                                &Vector3::x_axis(),
+                                -45.0f32.to_radians(),
+                            ),
+                        )
+                        .build(),
+                ),
+            )
+            .with_intensity(1.0),
+        )
+        .build(&mut scene.graph);
+
+        let fill_light = DirectionalLightBuilder::new(
+            BaseLightBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_rotation(
+                            UnitQuaternion::from_axis_angle(
+                                &Vector3::y_axis(),
+                                60.0f32.to_radians(),
+                            ) * UnitQuaternion::from_axis_angle(
+                                &Vector3::x_axis(),
+                                -20.0f32.to_radians(),
+                            ),
+                        )
+                        .build(),
+                ),
+            )
+            .with_intensity(0.4),
+        )
+        .build(&mut scene.graph);
+
+        self.camera =
+            CameraBuilder::new(BaseBuilder::new().with_children(&[key_light, fill_light]))
+                .with_specific_skybox(SkyBoxKind::None)
+                .build(&mut scene.graph);
+
+        self.scene = scenes.add(scene);
+    }
+
+    /// Renders a thumbnail of the given resource and returns it as a new embedded texture. The thumbnail
+    /// is rendered immediately, so this method should not be called too often (for example, render
+    /// thumbnails once and cache them).
+    pub fn render(
+        &mut self,
+        renderer: &mut Renderer,
+        scenes: &mut SceneContainer,
+        source: &ThumbnailSource,
+    ) -> Result<TextureResource, FrameworkError> {
+        if !scenes.is_valid_handle(self.scene) {
+            self.create_scene(scenes);
+        }
+
+        let size = self.settings.size.max(1);
+        let frame_size = Vector2::new(size as f32, size as f32);
+
+        let scene = &mut scenes[self.scene];
+        let subject = match source {
+            ThumbnailSource::Model(model) => {
+                if !model.is_ok() {
+                    return Err(FrameworkError::Custom(
+                        "The model is not loaded!".to_string(),
+                    ));
+                }
+                model.instantiate(scene)
+            }
+            ThumbnailSource::Material(material) => MeshBuilder::new(BaseBuilder::new())
+                .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                    SurfaceData::make_sphere(32, 32, 0.5, &Matrix4::identity()),
+                ))
+                .with_material(material.clone())
+                .build()])
+                .build(&mut scene.graph),
+        };
+
+        scene.graph.update_hierarchical_data();
+
+        let aabb = scene
+            .graph
+            .aabb_of_descendants(subject, |_, _| true)
+            .unwrap_or_else(AxisAlignedBoundingBox::collapsed);
+        let radius = aabb.half_extents().norm().max(0.01);
+        let (position, rotation, distance) = frame_sphere(aabb.center(), radius, &self.settings);
+
+        if let Some(camera) = scene.graph[self.camera].cast_mut::<Camera>() {
+            camera.set_projection(Projection::Perspective(PerspectiveProjection {
+                fov: self.settings.fov,
+                z_near: (distance - radius).max(distance * 0.01),
+                z_far: distance + radius * 2.0,
+            }));
+            camera
+                .local_transform_mut()
+                .set_position(position)
+                .set_rotation(rotation);
+        }
+        scene.graph.update_hierarchical_data();
+        if let Some(camera) = scene.graph[self.camera].cast_mut::<Camera>() {
+            camera.calculate_matrices(frame_size);
+        }
+
+        scene.rendering_options.render_target =
+            Some(TextureResource::new_render_target(size, size));
+        scene.rendering_options.clear_color = Some(self.settings.background);
+        scene.rendering_options.ambient_lighting_color = self.settings.ambient_lighting_color;
+        scene.enabled.set_value_and_mark_modified(true);
+
+        let old_quality_settings = renderer.quality_settings;
+        renderer.quality_settings.taa = false;
+        renderer.exclusive_camera = Some((self.scene, self.camera));
+
+        let result = renderer
+            .render_frame(scenes, &DrawingContext::new())
+            .and_then(|_| {
+                renderer
+                    .scene_data_map
+                    .get(&self.scene)
+                    .map(|data| {
+                        data.ldr_scene_framebuffer.read_pixels(
+                            &renderer.state,
+                            0,
+                            Rect::new(0, 0, size as i32, size as i32),
+                        )
+                    })
+                    .ok_or_else(|| FrameworkError::Custom("Scene was not rendered!".to_string()))
+            });
+
+        renderer.exclusive_camera = None;
+        renderer.quality_settings = old_quality_settings;
+        let scene = &mut scenes[self.scene];
+        scene.enabled.set_value_and_mark_modified(false);
+        scene.rendering_options.render_target = None;
+        scene.graph.remove_node(subject);
+
+        let pixels = result?;
+
+        // Read rows are stored from bottom to top, but textures are stored from top to bottom.
+        let mut bytes = Vec::with_capacity(pixels.len());
+        for row in pixels.chunks_exact(size as usize * 4).rev() {
+            for pixel in row.chunks_exact(4) {
+                bytes.extend_from_slice(&[pixel[0], pixel[1], pixel[2], 255]);
+            }
+        }
+
+        Texture::from_bytes(
+            TextureKind::Rectangle {
+                width: size,
+                height: size,
+            },
+            TexturePixelKind::RGBA8,
+            bytes,
+        )
+        .map(|texture| TextureResource::new_ok(ResourceKind::Embedded, texture))
+        .ok_or_else(|| FrameworkError::Custom("Unable to create thumbnail texture!".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        renderer::thumbnail::{frame_sphere, ThumbnailSettings},
+    };
+
+    #[test]
+    fn test_frame_sphere() {
+        let settings = ThumbnailSettings {
+            fov: 60.0f32.to_radians(),
+            padding: 1.0,
+            ..Default::default()
+        };
+        let center = Vector3::new(1.0, 2.0, 3.0);
+        let (position, rotation, distance) = frame_sphere(center, 1.0, &settings);
+        // sin(30) = 0.5
+        assert!((distance - 2.0).abs() < 1.0e-5);
+        assert!(((position - center).norm() - distance).abs() < 1.0e-5);
+        // The camera looks at the center and down on it.
+        let look = rotation.transform_vector(&Vector3::z());
+        assert!((position + look.scale(distance) - center).norm() < 1.0e-5);
+        assert!(look.y < 0.0);
+    }
+}
@@ -263,23 +263,14 @@ impl UiRenderer {
             }
 
             match &cmd.texture {
-                CommandTexture::Font {
-                    font,
-                    page_index,
-                    height,
-                } => {
+                CommandTexture::Font { font, page_index } => {
                     if let Some(font) = font.state().data() {
-                        let page_size = font.page_size() as u32;
-                        if let Some(page) = font
-                            .atlases
-                            .get_mut(height)
-                            .and_then(|atlas| atlas.pages.get_mut(*page_index))
-                        {
+                        if let Some(page) = font.pages.get_mut(*page_index) {
                             if page.texture.is_none() || page.modified {
                                 if let Some(details) = Texture::from_bytes(
                                     TextureKind::Rectangle {
-                                        width: page_size,
-                                        height: page_size,
+                                        width: page.size as u32,
+                                        height: page.size as u32,
                                     },
                                     TexturePixelKind::R8,
                                     page.pixels.clone(),
@@ -0,0 +1,92 @@
+use crate::{
+    core::{algebra::Vector2, math::Rect, sstorage::ImmutableString},
+    renderer::{
+        framework::{
+            error::FrameworkError,
+            framebuffer::{DrawParameters, FrameBuffer},
+            geometry_buffer::{DrawCallStatistics, ElementRange, GeometryBuffer},
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{GpuTexture, GpuTextureKind},
+            state::PipelineState,
+        },
+        make_viewport_matrix,
+    },
+};
+use std::{cell::RefCell, rc::Rc};
+
+struct UpscaleShader {
+    pub program: GpuProgram,
+    pub wvp_matrix: UniformLocation,
+    pub source_texture: UniformLocation,
+    pub source_size: UniformLocation,
+}
+
+impl UpscaleShader {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/upscale_fs.glsl");
+        let vertex_source = include_str!("shaders/flat_vs.glsl");
+
+        let program =
+            GpuProgram::from_source(state, "UpscaleShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            source_texture: program
+                .uniform_location(state, &ImmutableString::new("sourceTexture"))?,
+            source_size: program.uniform_location(state, &ImmutableString::new("sourceSize"))?,
+            program,
+        })
+    }
+}
+
+/// Stretches low resolution frame to a target viewport using bicubic filtering.
+pub struct UpscaleRenderer {
+    shader: UpscaleShader,
+}
+
+impl UpscaleRenderer {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader: UpscaleShader::new(state)?,
+        })
+    }
+
+    pub(crate) fn render(
+        &self,
+        state: &PipelineState,
+        quad: &GeometryBuffer,
+        viewport: Rect<i32>,
+        source: Rc<RefCell<GpuTexture>>,
+        frame_buffer: &mut FrameBuffer,
+    ) -> Result<DrawCallStatistics, FrameworkError> {
+        let source_size =
+            if let GpuTextureKind::Rectangle { width, height } = source.borrow().kind() {
+                Vector2::new(width as f32, height as f32)
+            } else {
+                Vector2::new(viewport.w() as f32, viewport.h() as f32)
+            };
+
+        frame_buffer.draw(
+            quad,
+            state,
+            viewport,
+            &self.shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: false,
+                blend: None,
+                stencil_op: Default::default(),
+            },
+            ElementRange::Full,
+            |mut program_binding| {
+                program_binding
+                    .set_matrix4(&self.shader.wvp_matrix, &make_viewport_matrix(viewport))
+                    .set_texture(&self.shader.source_texture, &source)
+                    .set_vector2(&self.shader.source_size, &source_size);
+            },
+        )
+    }
+}
@@ -3,8 +3,13 @@
 use crate::{
     asset::{io::ResourceIo, Resource, ResourceData, CURVE_RESOURCE_UUID},
     core::{
-        curve::Curve, io::FileLoadError, reflect::prelude::*, uuid::Uuid, visitor::prelude::*,
-        TypeUuidProvider,
+        curve::Curve, io::FileLoadError, pool::Handle, reflect::prelude::*, uuid::Uuid,
+        visitor::prelude::*, TypeUuidProvider,
+    },
+    gui::{
+        curve::CurveEditorMessage,
+        message::{MessageDirection, UiMessage},
+        UiNode, UserInterface,
     },
 };
 use std::error::Error;
@@ -74,13 +79,15 @@ impl ResourceData for CurveResourceState {
         <Self as TypeUuidProvider>::type_uuid()
     }
 
-    fn save(&mut self, _path: &Path) -> Result<(), Box<dyn Error>> {
-        // TODO: Add saving.
-        Err("Saving is not supported!".to_string().into())
+    fn save(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut visitor = Visitor::new();
+        self.curve.visit("Curve", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
     }
 
     fn can_be_saved(&self) -> bool {
-        false
+        true
     }
 }
 
@@ -103,3 +110,112 @@ impl CurveResourceState {
 
 /// Type alias for curve resources.
 pub type CurveResource = Resource<CurveResourceState>;
+
+/// Binds a [`CurveEditor`](crate::gui::curve::CurveEditor) widget to a curve resource, so the curve
+/// could be edited at runtime (for example in modding tools or in-game editors). Every change made
+/// in the editor is written back to the resource. Use [`ResourceData::save`] on the resource state
+/// to save the changes to a file.
+///
+/// # Examples
+///
+/// ```rust
+/// # use fyrox::{
+/// #     gui::{curve::CurveEditorBuilder, message::UiMessage, widget::WidgetBuilder, UserInterface},
+/// #     resource::curve::{CurveEditorBinding, CurveResource},
+/// # };
+/// fn create_binding(ui: &mut UserInterface, curve: CurveResource) -> CurveEditorBinding {
+///     let editor = CurveEditorBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(200.0))
+///         .build(&mut ui.build_ctx());
+///     let binding = CurveEditorBinding::new(editor, curve);
+///     binding.sync_to_editor(ui);
+///     binding
+/// }
+///
+/// fn on_ui_message(binding: &CurveEditorBinding, message: &UiMessage) {
+///     binding.handle_ui_message(message);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CurveEditorBinding {
+    editor: Handle<UiNode>,
+    resource: CurveResource,
+}
+
+impl CurveEditorBinding {
+    /// Creates new binding between the given curve editor widget and the curve resource.
+    pub fn new(editor: Handle<UiNode>, resource: CurveResource) -> Self {
+        Self { editor, resource }
+    }
+
+    /// Returns a handle of the bound curve editor widget.
+    pub fn editor(&self) -> Handle<UiNode> {
+        self.editor
+    }
+
+    /// Returns the bound curve resource.
+    pub fn resource(&self) -> &CurveResource {
+        &self.resource
+    }
+
+    /// Sends current curve of the resource to the editor. Call this method when the resource was
+    /// loaded or when the curve was modified from code.
+    pub fn sync_to_editor(&self, ui: &UserInterface) {
+        if let Some(state) = self.resource.state().data() {
+            ui.send_message(CurveEditorMessage::sync(
+                self.editor,
+                MessageDirection::ToWidget,
+                state.curve.clone(),
+            ));
+        }
+    }
+
+    /// Writes the changes made in the editor back to the resource. Returns `true` if the message
+    /// was processed and the curve of the resource was changed.
+    pub fn handle_ui_message(&self, message: &UiMessage) -> bool {
+        if let Some(CurveEditorMessage::Sync(curve)) = message.data() {
+            if message.destination() == self.editor
+                && message.direction() == MessageDirection::FromWidget
+            {
+                if let Some(state) = self.resource.state().data() {
+                    state.curve = curve.clone();
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::{io::FsResourceIo, ResourceData},
+        core::{
+            curve::{Curve, CurveKey, CurveKeyKind},
+            futures::executor::block_on,
+            uuid::Uuid,
+        },
+        resource::curve::CurveResourceState,
+    };
+
+    #[test]
+    fn test_curve_resource_save_load() {
+        let mut curve = Curve::default();
+        curve.add_key(CurveKey::new(0.0, 1.0, CurveKeyKind::Linear));
+        curve.add_key(CurveKey::new(1.0, 2.0, CurveKeyKind::new_cubic(0.1, 0.2)));
+        let mut state = CurveResourceState {
+            curve: curve.clone(),
+        };
+
+        let dir = std::env::temp_dir().join(format!("fyrox_curve_resource_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.curve");
+
+        assert!(state.can_be_saved());
+        state.save(&path).unwrap();
+        let loaded = block_on(CurveResourceState::from_file(&path, &FsResourceIo)).unwrap();
+        assert_eq!(loaded.curve, curve);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
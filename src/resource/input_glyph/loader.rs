@@ -0,0 +1,39 @@
+//! Input glyph atlas loader.
+
+use crate::{
+    asset::{
+        io::ResourceIo,
+        loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+        manager::ResourceManager,
+    },
+    core::{uuid::Uuid, TypeUuidProvider},
+    resource::input_glyph::InputGlyphAtlas,
+};
+use fyrox_resource::state::LoadError;
+use std::{path::PathBuf, sync::Arc};
+
+/// Default implementation for input glyph atlas loading.
+pub struct InputGlyphAtlasLoader {
+    /// Resource manager that will be used to load glyph textures.
+    pub resource_manager: ResourceManager,
+}
+
+impl ResourceLoader for InputGlyphAtlasLoader {
+    fn extensions(&self) -> &[&str] {
+        &["glyphs"]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        InputGlyphAtlas::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        let resource_manager = self.resource_manager.clone();
+        Box::pin(async move {
+            let atlas = InputGlyphAtlas::from_file(&path, io.as_ref(), resource_manager)
+                .await
+                .map_err(LoadError::new)?;
+            Ok(LoaderPayload::new(atlas))
+        })
+    }
+}
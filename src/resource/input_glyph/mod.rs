@@ -0,0 +1,355 @@
+//! Input glyph atlas is a resource that maps logical input actions to glyphs (images of keys and buttons)
+//! for each kind of input device. It allows UIs to show correct prompts ("Press Ⓐ" / "Press E")
+//! depending on the device the player is currently using. See [`InputGlyphAtlas`] docs for more info.
+
+use crate::{
+    asset::{io::ResourceIo, manager::ResourceManager, Resource, ResourceData},
+    core::{
+        math::Rect,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    event::{Event, WindowEvent},
+    gui::{image::ImageMessage, message::MessageDirection, UiNode, UserInterface},
+    resource::texture::TextureResource,
+};
+use std::{any::Any, error::Error, path::Path, sync::Arc};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+pub mod loader;
+
+/// A kind of an input device.
+#[derive(
+    Copy,
+    Clone,
+    Default,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum InputDeviceKind {
+    /// Keyboard and mouse.
+    #[default]
+    KeyboardMouse,
+    /// Touch screen.
+    Touch,
+    /// A gamepad with unknown layout. It is also used as a fallback for other gamepads, if there's no
+    /// glyph for a specific one.
+    GenericGamepad,
+    /// Xbox-like gamepad (A, B, X, Y buttons).
+    XboxGamepad,
+    /// PlayStation-like gamepad (cross, circle, square, triangle buttons).
+    PlayStationGamepad,
+    /// Nintendo-like gamepad (B, A, Y, X buttons).
+    NintendoGamepad,
+}
+
+uuid_provider!(InputDeviceKind = "0b7d4e2f-8c61-4a3e-b9f5-2d6a1c8e7f43");
+
+impl InputDeviceKind {
+    /// Returns `true` if the device is a gamepad.
+    pub fn is_gamepad(self) -> bool {
+        matches!(
+            self,
+            Self::GenericGamepad
+                | Self::XboxGamepad
+                | Self::PlayStationGamepad
+                | Self::NintendoGamepad
+        )
+    }
+}
+
+/// An image of a key or a button.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct InputGlyph {
+    /// A texture that contains the glyph. Usually, it is a shared atlas with the glyphs of all buttons
+    /// of a device.
+    pub texture: Option<TextureResource>,
+    /// A region of the texture (in normalized coordinates) that contains the glyph.
+    pub uv_rect: Rect<f32>,
+    /// A textual representation of the glyph (for example, `E` or `LMB`), that could be used when
+    /// there's no texture.
+    pub text: String,
+}
+
+uuid_provider!(InputGlyph = "5e2c9a17-3f8d-4b60-a4e1-7c9b2d5f8a06");
+
+impl Default for InputGlyph {
+    fn default() -> Self {
+        Self {
+            texture: None,
+            uv_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
+            text: Default::default(),
+        }
+    }
+}
+
+impl InputGlyph {
+    /// Sends the glyph to an [`crate::gui::image::Image`] widget.
+    pub fn send_to_image(&self, ui: &UserInterface, image: Handle<UiNode>) {
+        ui.send_message(ImageMessage::texture(
+            image,
+            MessageDirection::ToWidget,
+            self.texture.clone().map(|t| t.into_untyped()),
+        ));
+        ui.send_message(ImageMessage::uv_rect(
+            image,
+            MessageDirection::ToWidget,
+            self.uv_rect,
+        ));
+    }
+}
+
+/// A glyph of an action for a specific device.
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct DeviceGlyph {
+    /// A kind of the device.
+    pub device: InputDeviceKind,
+    /// A glyph of the action for the device.
+    pub glyph: InputGlyph,
+}
+
+uuid_provider!(DeviceGlyph = "c41f8b6e-2a7d-4d93-8e05-b6a3f9c1d274");
+
+/// A set of glyphs of a logical input action (for example, `Jump` or `Interact`).
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct InputActionGlyphs {
+    /// Name of the action.
+    pub action: String,
+    /// Glyphs of the action for each device.
+    pub glyphs: Vec<DeviceGlyph>,
+}
+
+uuid_provider!(InputActionGlyphs = "8f3a6d2c-9b14-4e7f-a5c8-1d0e7b4f6a92");
+
+/// Input glyph atlas maps logical input actions to glyphs for each kind of input device. Use it
+/// together with [`InputDeviceTracker`] to show correct prompts in the UI, when the player switches
+/// between keyboard and gamepad.
+///
+/// If there's no glyph for a specific gamepad, the glyph for [`InputDeviceKind::GenericGamepad`] is
+/// used instead.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     event::Event,
+///     gui::{UiNode, UserInterface},
+///     resource::input_glyph::{InputDeviceTracker, InputGlyphAtlas},
+/// };
+///
+/// struct InteractionPrompt {
+///     tracker: InputDeviceTracker,
+///     image: Handle<UiNode>,
+/// }
+///
+/// impl InteractionPrompt {
+///     fn on_os_event(&mut self, event: &Event<()>, atlas: &InputGlyphAtlas, ui: &UserInterface) {
+///         if let Some(device) = self.tracker.handle_os_event(event) {
+///             if let Some(glyph) = atlas.glyph("Interact", device) {
+///                 glyph.send_to_image(ui, self.image);
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct InputGlyphAtlas {
+    /// Glyphs of actions.
+    pub actions: Vec<InputActionGlyphs>,
+}
+
+impl TypeUuidProvider for InputGlyphAtlas {
+    fn type_uuid() -> Uuid {
+        uuid!("e6a2d9f4-7b3c-4a58-9d1e-3f8c5b2a7e60")
+    }
+}
+
+impl ResourceData for InputGlyphAtlas {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn type_uuid(&self) -> Uuid {
+        <Self as TypeUuidProvider>::type_uuid()
+    }
+
+    fn save(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut visitor = Visitor::new();
+        self.visit("InputGlyphAtlas", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
+    }
+
+    fn can_be_saved(&self) -> bool {
+        true
+    }
+}
+
+impl InputGlyphAtlas {
+    /// Loads an input glyph atlas from the given file.
+    pub async fn from_file(
+        path: &Path,
+        io: &dyn ResourceIo,
+        resource_manager: ResourceManager,
+    ) -> Result<Self, VisitError> {
+        let bytes = io.load_file(path).await?;
+        let mut visitor = Visitor::load_from_memory(&bytes)?;
+        visitor.blackboard.register(Arc::new(resource_manager));
+        let mut atlas = InputGlyphAtlas::default();
+        atlas.visit("InputGlyphAtlas", &mut visitor)?;
+        Ok(atlas)
+    }
+
+    /// Sets a glyph of the given action for the given device, replacing existing one (if any).
+    pub fn set_glyph<S: AsRef<str>>(
+        &mut self,
+        action: S,
+        device: InputDeviceKind,
+        glyph: InputGlyph,
+    ) {
+        let action = action.as_ref();
+        let index = match self.actions.iter().position(|a| a.action == action) {
+            Some(index) => index,
+            None => {
+                self.actions.push(InputActionGlyphs {
+                    action: action.to_owned(),
+                    glyphs: Default::default(),
+                });
+                self.actions.len() - 1
+            }
+        };
+        let glyphs = &mut self.actions[index].glyphs;
+        if let Some(existing) = glyphs.iter_mut().find(|g| g.device == device) {
+            existing.glyph = glyph;
+        } else {
+            glyphs.push(DeviceGlyph { device, glyph });
+        }
+    }
+
+    /// Returns a glyph of the given action for the given device. Falls back to the generic gamepad
+    /// glyph for gamepads, that does not have their own glyphs.
+    pub fn glyph<S: AsRef<str>>(&self, action: S, device: InputDeviceKind) -> Option<&InputGlyph> {
+        let action = self.actions.iter().find(|a| a.action == action.as_ref())?;
+        let find = |device| {
+            action
+                .glyphs
+                .iter()
+                .find(|g| g.device == device)
+                .map(|g| &g.glyph)
+        };
+        find(device).or_else(|| {
+            if device.is_gamepad() {
+                find(InputDeviceKind::GenericGamepad)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Type alias for input glyph atlas resources.
+pub type InputGlyphAtlasResource = Resource<InputGlyphAtlas>;
+
+/// Tracks the input device, that the player used last. Keyboard, mouse and touch input is detected
+/// automatically from OS events; gamepads are not handled by the window, so gamepad input must be
+/// reported via [`Self::set_active_device`] by the code that polls gamepads.
+#[derive(Clone, Debug, Default)]
+pub struct InputDeviceTracker {
+    active_device: InputDeviceKind,
+}
+
+impl InputDeviceTracker {
+    /// Creates a new tracker with the given initially active device.
+    pub fn new(active_device: InputDeviceKind) -> Self {
+        Self { active_device }
+    }
+
+    /// Returns the device, that was used last.
+    pub fn active_device(&self) -> InputDeviceKind {
+        self.active_device
+    }
+
+    /// Sets the active device. Returns `Some(device)` if the active device has changed, `None` -
+    /// otherwise.
+    pub fn set_active_device(&mut self, device: InputDeviceKind) -> Option<InputDeviceKind> {
+        if self.active_device != device {
+            self.active_device = device;
+            Some(device)
+        } else {
+            None
+        }
+    }
+
+    /// Checks the given OS event and switches the active device, if the event came from a different
+    /// device. Returns `Some(device)` if the active device has changed, `None` - otherwise. Mouse
+    /// movement is ignored, because it could be caused accidentally.
+    pub fn handle_os_event(&mut self, event: &Event<()>) -> Option<InputDeviceKind> {
+        if let Event::WindowEvent { event, .. } = event {
+            match event {
+                WindowEvent::KeyboardInput { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. } => {
+                    return self.set_active_device(InputDeviceKind::KeyboardMouse)
+                }
+                WindowEvent::Touch(_) => return self.set_active_device(InputDeviceKind::Touch),
+                _ => (),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::resource::input_glyph::{
+        InputDeviceKind, InputDeviceTracker, InputGlyph, InputGlyphAtlas,
+    };
+
+    #[test]
+    fn test_glyph_fallback() {
+        let mut atlas = InputGlyphAtlas::default();
+        let glyph = |text: &str| InputGlyph {
+            text: text.to_string(),
+            ..Default::default()
+        };
+        atlas.set_glyph("Jump", InputDeviceKind::KeyboardMouse, glyph("Space"));
+        atlas.set_glyph("Jump", InputDeviceKind::GenericGamepad, glyph("South"));
+        atlas.set_glyph("Jump", InputDeviceKind::XboxGamepad, glyph("A"));
+
+        let text = |device| atlas.glyph("Jump", device).map(|g| g.text.as_str());
+        assert_eq!(text(InputDeviceKind::KeyboardMouse), Some("Space"));
+        assert_eq!(text(InputDeviceKind::XboxGamepad), Some("A"));
+        assert_eq!(text(InputDeviceKind::PlayStationGamepad), Some("South"));
+        assert_eq!(text(InputDeviceKind::Touch), None);
+        assert!(atlas
+            .glyph("Crouch", InputDeviceKind::KeyboardMouse)
+            .is_none());
+
+        let mut tracker = InputDeviceTracker::default();
+        assert_eq!(
+            tracker.set_active_device(InputDeviceKind::KeyboardMouse),
+            None
+        );
+        assert_eq!(
+            tracker.set_active_device(InputDeviceKind::XboxGamepad),
+            Some(InputDeviceKind::XboxGamepad)
+        );
+        assert_eq!(tracker.active_device(), InputDeviceKind::XboxGamepad);
+    }
+}
@@ -0,0 +1,34 @@
+//! Light probe data loader.
+
+use crate::{
+    asset::{
+        io::ResourceIo,
+        loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+    },
+    core::{uuid::Uuid, TypeUuidProvider},
+    resource::light_probe::LightProbeData,
+};
+use fyrox_resource::state::LoadError;
+use std::{path::PathBuf, sync::Arc};
+
+/// Default implementation for light probe data loading.
+pub struct LightProbeDataLoader;
+
+impl ResourceLoader for LightProbeDataLoader {
+    fn extensions(&self) -> &[&str] {
+        &["probes"]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        LightProbeData::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        Box::pin(async move {
+            let data = LightProbeData::from_file(&path, io.as_ref())
+                .await
+                .map_err(LoadError::new)?;
+            Ok(LoaderPayload::new(data))
+        })
+    }
+}
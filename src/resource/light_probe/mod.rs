@@ -0,0 +1,238 @@
+//! Light probe data is a resource, that contains baked indirect lighting of a region of a scene. It is
+//! produced by [`crate::utils::lightmap::bake_light_probes`] and used by
+//! [`crate::scene::light_probe::LightProbeVolume`] nodes. See [`LightProbeData`] docs for more info.
+
+use crate::{
+    asset::{io::ResourceIo, Resource, ResourceData},
+    core::{
+        algebra::Vector3,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+};
+use std::{any::Any, error::Error, ops::Add, path::Path};
+
+pub mod loader;
+
+/// Irradiance at a point, stored as the first two bands of spherical harmonics (L1). Coefficients are
+/// pre-convolved with the cosine lobe and divided by PI, so the diffuse radiance of a surface with unit
+/// albedo and normal `n` is `max(c[0] + c[1] * n.x + c[2] * n.y + c[3] * n.z, 0)`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct ShIrradiance {
+    /// Constant, X, Y and Z coefficients for each color channel.
+    pub coefficients: [Vector3<f32>; 4],
+}
+
+impl ShIrradiance {
+    // Constants of SH basis functions.
+    const Y00: f32 = 0.282_095;
+    const Y1: f32 = 0.488_603;
+
+    /// Creates irradiance from a set of radiance samples in uniformly distributed directions over the
+    /// sphere.
+    pub fn from_radiance_samples<I>(samples: I) -> Self
+    where
+        I: IntoIterator<Item = (Vector3<f32>, Vector3<f32>)>,
+    {
+        // Project radiance on the SH basis.
+        let mut projected = [Vector3::default(); 4];
+        let mut count = 0;
+        for (direction, radiance) in samples {
+            projected[0] += radiance.scale(Self::Y00);
+            projected[1] += radiance.scale(Self::Y1 * direction.x);
+            projected[2] += radiance.scale(Self::Y1 * direction.y);
+            projected[3] += radiance.scale(Self::Y1 * direction.z);
+            count += 1;
+        }
+        if count == 0 {
+            return Self::default();
+        }
+        let weight = 4.0 * std::f32::consts::PI / count as f32;
+
+        // Convolve with the cosine lobe (A0 = PI, A1 = 2 * PI / 3), divide by PI and multiply by the
+        // basis constants, so evaluation in shaders is just a dot product.
+        Self {
+            coefficients: [
+                projected[0].scale(weight * Self::Y00),
+                projected[1].scale(weight * Self::Y1 * 2.0 / 3.0),
+                projected[2].scale(weight * Self::Y1 * 2.0 / 3.0),
+                projected[3].scale(weight * Self::Y1 * 2.0 / 3.0),
+            ],
+        }
+    }
+
+    /// Returns diffuse radiance of a surface with unit albedo and the given normal.
+    pub fn evaluate(&self, normal: Vector3<f32>) -> Vector3<f32> {
+        let c = &self.coefficients;
+        (c[0] + c[1].scale(normal.x) + c[2].scale(normal.y) + c[3].scale(normal.z))
+            .map(|v| v.max(0.0))
+    }
+
+    /// Returns a copy of the irradiance, multiplied by the given factor.
+    pub fn scale(&self, factor: f32) -> Self {
+        Self {
+            coefficients: self.coefficients.map(|c| c.scale(factor)),
+        }
+    }
+}
+
+impl Add for ShIrradiance {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut coefficients = self.coefficients;
+        for (a, b) in coefficients.iter_mut().zip(rhs.coefficients) {
+            *a += b;
+        }
+        Self { coefficients }
+    }
+}
+
+/// A regular 3D grid of light probes, that covers unit cube (`[-0.5; 0.5]` on each axis) in local
+/// coordinates of a [`crate::scene::light_probe::LightProbeVolume`]. Probes are placed at the centers
+/// of the cells of the grid.
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct LightProbeData {
+    /// Amount of probes along each axis.
+    pub resolution: Vector3<u32>,
+    /// Probes in X -> Y -> Z order (X changes first).
+    pub probes: Vec<ShIrradiance>,
+}
+
+impl TypeUuidProvider for LightProbeData {
+    fn type_uuid() -> Uuid {
+        uuid!("5d3b8f2e-1a4c-4e97-b6d0-8c2f7a9e3b15")
+    }
+}
+
+impl ResourceData for LightProbeData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn type_uuid(&self) -> Uuid {
+        <Self as TypeUuidProvider>::type_uuid()
+    }
+
+    fn save(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut visitor = Visitor::new();
+        self.visit("LightProbeData", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
+    }
+
+    fn can_be_saved(&self) -> bool {
+        true
+    }
+}
+
+impl LightProbeData {
+    /// Loads light probe data from the given file.
+    pub async fn from_file(path: &Path, io: &dyn ResourceIo) -> Result<Self, VisitError> {
+        let bytes = io.load_file(path).await?;
+        let mut visitor = Visitor::load_from_memory(&bytes)?;
+        let mut data = LightProbeData::default();
+        data.visit("LightProbeData", &mut visitor)?;
+        Ok(data)
+    }
+
+    /// Returns local position (in `[-0.5; 0.5]` range) of a probe with the given grid coordinates.
+    pub fn probe_local_position(resolution: Vector3<u32>, x: u32, y: u32, z: u32) -> Vector3<f32> {
+        Vector3::new(
+            (x as f32 + 0.5) / resolution.x.max(1) as f32 - 0.5,
+            (y as f32 + 0.5) / resolution.y.max(1) as f32 - 0.5,
+            (z as f32 + 0.5) / resolution.z.max(1) as f32 - 0.5,
+        )
+    }
+
+    fn probe(&self, x: u32, y: u32, z: u32) -> ShIrradiance {
+        let index = (z * self.resolution.y + y) * self.resolution.x + x;
+        self.probes.get(index as usize).copied().unwrap_or_default()
+    }
+
+    /// Samples the grid at the given local position using trilinear interpolation. Positions outside
+    /// of the grid are clamped to the closest probes. Returns `None` if the data is empty or
+    /// malformed.
+    pub fn sample(&self, local_position: Vector3<f32>) -> Option<ShIrradiance> {
+        let r = self.resolution;
+        if r.x == 0 || r.y == 0 || r.z == 0 || self.probes.len() != (r.x * r.y * r.z) as usize {
+            return None;
+        }
+
+        let mut base = [0u32; 3];
+        let mut fraction = [0.0f32; 3];
+        for axis in 0..3 {
+            let size = r[axis];
+            let grid =
+                ((local_position[axis] + 0.5) * size as f32 - 0.5).clamp(0.0, (size - 1) as f32);
+            base[axis] = (grid.floor() as u32).min(size - 1);
+            fraction[axis] = grid - base[axis] as f32;
+        }
+
+        let mut result = ShIrradiance::default();
+        for corner in 0..8u32 {
+            let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+            let mut weight = 1.0;
+            let mut coords = [0u32; 3];
+            for axis in 0..3 {
+                coords[axis] = (base[axis] + offset[axis]).min(r[axis] - 1);
+                weight *= if offset[axis] == 1 {
+                    fraction[axis]
+                } else {
+                    1.0 - fraction[axis]
+                };
+            }
+            if weight > 0.0 {
+                result = result + self.probe(coords[0], coords[1], coords[2]).scale(weight);
+            }
+        }
+        Some(result)
+    }
+}
+
+/// Type alias for light probe data resources.
+pub type LightProbeDataResource = Resource<LightProbeData>;
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        resource::light_probe::{LightProbeData, ShIrradiance},
+    };
+
+    #[test]
+    fn test_sh_irradiance_of_uniform_environment() {
+        // Fibonacci sphere.
+        let count = 512;
+        let samples = (0..count).map(|i| {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+            let r = (1.0 - y * y).sqrt();
+            let phi = i as f32 * std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+            (
+                Vector3::new(r * phi.cos(), y, r * phi.sin()),
+                Vector3::new(1.0, 1.0, 1.0),
+            )
+        });
+        let sh = ShIrradiance::from_radiance_samples(samples);
+        // Uniform white environment gives white diffuse radiance in every direction.
+        for normal in [Vector3::x(), Vector3::y(), -Vector3::z()] {
+            let radiance = sh.evaluate(normal);
+            assert!((radiance.x - 1.0).abs() < 0.01, "{radiance:?}");
+        }
+
+        let mut data = LightProbeData {
+            resolution: Vector3::new(2, 1, 1),
+            probes: vec![ShIrradiance::default(), sh],
+        };
+        let middle = data.sample(Vector3::default()).unwrap();
+        assert!((middle.evaluate(Vector3::y()).x - 0.5).abs() < 0.01);
+        data.probes.pop();
+        assert!(data.sample(Vector3::default()).is_none());
+    }
+}
@@ -4,5 +4,11 @@
 
 pub mod curve;
 pub mod fbx;
+pub mod input_glyph;
+pub mod light_probe;
 pub mod model;
+pub mod sound_event;
+pub mod surface;
 pub mod texture;
+pub mod texture_atlas;
+pub mod timeline;
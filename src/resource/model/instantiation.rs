@@ -0,0 +1,338 @@
+//! Asynchronous (budgeted) instantiation of model resources. See [`ModelInstantiation`] docs for
+//! more info.
+
+use crate::{
+    asset::untyped::UntypedResource,
+    core::{instant::Instant, log::Log, pool::Handle},
+    resource::model::ModelResource,
+    scene::{
+        base::BaseBuilder,
+        graph::{clear_links, map::NodeHandleMap},
+        node::Node,
+        pivot::PivotBuilder,
+        Scene,
+    },
+};
+use std::{any::TypeId, collections::VecDeque, time::Duration};
+
+/// Status of an asynchronous instantiation, returned by [`ModelInstantiation::step`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum InstantiationStatus {
+    /// The instantiation is not finished yet, [`ModelInstantiation::step`] must be called again
+    /// (usually on the next frame).
+    InProgress,
+    /// The instantiation is finished, the handle points to the root node of the instance.
+    Finished(Handle<Node>),
+    /// The instantiation has failed, because the model resource has failed to load or the
+    /// instance was removed from the scene while it was being built.
+    Failed,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Stage {
+    WaitingForResource,
+    Copying,
+    Remapping,
+    Done,
+    Failed,
+}
+
+/// Asynchronous instantiation of a model resource, that spreads the copying of nodes over multiple
+/// frames under a per-frame time budget. It is useful for very large prefabs (levels, buildings,
+/// etc.) whose instantiation using [`super::ModelResourceExtension::instantiate`] takes hundreds of
+/// milliseconds and causes noticeable hitches. Use [`super::ModelResourceExtension::instantiate_async`]
+/// to create one.
+///
+/// The model resource does not have to be loaded, the instantiation waits until the resource is
+/// loaded. Nodes are created under a temporary disabled node, so the partially built instance is not
+/// rendered and not simulated. When every node is created, the instance is attached to the root of
+/// the scene and the temporary node is removed.
+///
+/// # Important notes
+///
+/// The instantiation does not hold any borrows of the scene, so it must always be stepped with the
+/// same scene. If the instantiation is no longer needed, call [`ModelInstantiation::cancel`] to
+/// remove the partially built instance, otherwise it will remain in the scene.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     resource::model::{
+///         instantiation::{InstantiationStatus, ModelInstantiation},
+///         ModelResource, ModelResourceExtension,
+///     },
+///     scene::{node::Node, Scene},
+/// };
+/// use std::time::Duration;
+///
+/// struct Level {
+///     instantiation: Option<ModelInstantiation>,
+///     root: Handle<Node>,
+/// }
+///
+/// impl Level {
+///     fn new(prefab: &ModelResource) -> Self {
+///         Self {
+///             instantiation: Some(prefab.instantiate_async()),
+///             root: Handle::NONE,
+///         }
+///     }
+///
+///     // Call this once per frame.
+///     fn update(&mut self, scene: &mut Scene) {
+///         if let Some(instantiation) = self.instantiation.as_mut() {
+///             // Spend at most 4 ms per frame on the instantiation.
+///             match instantiation.step(scene, Duration::from_millis(4)) {
+///                 InstantiationStatus::InProgress => (),
+///                 InstantiationStatus::Finished(root) => {
+///                     self.root = root;
+///                     self.instantiation = None;
+///                 }
+///                 InstantiationStatus::Failed => self.instantiation = None,
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub struct ModelInstantiation {
+    model: ModelResource,
+    stage: Stage,
+    staging_node: Handle<Node>,
+    queue: Vec<Handle<Node>>,
+    copied: usize,
+    remapped: usize,
+    mapping: NodeHandleMap,
+}
+
+impl ModelInstantiation {
+    pub(crate) fn new(model: ModelResource) -> Self {
+        Self {
+            model,
+            stage: Stage::WaitingForResource,
+            staging_node: Handle::NONE,
+            queue: Default::default(),
+            copied: 0,
+            remapped: 0,
+            mapping: Default::default(),
+        }
+    }
+
+    /// Returns a model resource that is being instantiated.
+    pub fn model(&self) -> &ModelResource {
+        &self.model
+    }
+
+    /// Returns a handle of the root node of the instance. The handle is valid as soon as the first
+    /// node is created, so the instance could be positioned before the instantiation is finished.
+    pub fn root(&self) -> Handle<Node> {
+        self.queue
+            .first()
+            .and_then(|root| self.mapping.inner().get(root))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if the instantiation is finished (successfully or not).
+    pub fn is_finished(&self) -> bool {
+        matches!(self.stage, Stage::Done | Stage::Failed)
+    }
+
+    /// Returns the progress of the instantiation in `[0; 1]` range.
+    pub fn progress(&self) -> f32 {
+        match self.stage {
+            Stage::WaitingForResource | Stage::Failed => 0.0,
+            Stage::Done => 1.0,
+            Stage::Copying | Stage::Remapping => {
+                (self.copied + self.remapped) as f32 / (2 * self.queue.len()).max(1) as f32
+            }
+        }
+    }
+
+    fn status(&self) -> InstantiationStatus {
+        match self.stage {
+            Stage::Done => InstantiationStatus::Finished(self.root()),
+            Stage::Failed => InstantiationStatus::Failed,
+            _ => InstantiationStatus::InProgress,
+        }
+    }
+
+    /// Performs the instantiation until it is finished or the given time budget is exceeded. At
+    /// least one node is processed per call, so the instantiation always makes progress even with
+    /// zero budget.
+    pub fn step(&mut self, scene: &mut Scene, budget: Duration) -> InstantiationStatus {
+        let start = Instant::now();
+
+        if self.is_finished() {
+            return self.status();
+        }
+
+        if self.stage == Stage::WaitingForResource && self.model.is_loading() {
+            return InstantiationStatus::InProgress;
+        }
+
+        let mut header = self.model.state();
+        let Some(model) = header.data() else {
+            Log::err(format!(
+                "Unable to instantiate {}, because the resource has failed to load.",
+                header.kind()
+            ));
+            self.stage = Stage::Failed;
+            return InstantiationStatus::Failed;
+        };
+
+        if self.stage == Stage::WaitingForResource {
+            // Breadth-first order guarantees that parents are created before their children and
+            // keeps the order of children.
+            let graph = &model.scene.graph;
+            let mut queue = VecDeque::from([graph.get_root()]);
+            while let Some(handle) = queue.pop_front() {
+                self.queue.push(handle);
+                queue.extend(graph[handle].children().iter().cloned());
+            }
+
+            self.staging_node = PivotBuilder::new(
+                BaseBuilder::new()
+                    .with_name("__ModelInstantiation")
+                    .with_enabled(false),
+            )
+            .build(&mut scene.graph);
+            self.stage = Stage::Copying;
+        }
+
+        if !scene.graph.is_valid_handle(self.staging_node) {
+            Log::err("Unable to instantiate a model, because the instance was removed.");
+            self.stage = Stage::Failed;
+            return InstantiationStatus::Failed;
+        }
+
+        while self.stage == Stage::Copying {
+            let original_handle = self.queue[self.copied];
+            let original = &model.scene.graph[original_handle];
+
+            let mut copy = clear_links(original.clone_box());
+            copy.set_inheritance_data(original_handle, self.model.clone());
+            let parent = self
+                .mapping
+                .inner()
+                .get(&original.parent())
+                .cloned()
+                .unwrap_or(self.staging_node);
+            let copy_handle = scene.graph.add_node(copy);
+            scene.graph.link_nodes(copy_handle, parent);
+            self.mapping.map.insert(original_handle, copy_handle);
+
+            self.copied += 1;
+            if self.copied == self.queue.len() {
+                self.stage = Stage::Remapping;
+            }
+            if start.elapsed() >= budget {
+                return InstantiationStatus::InProgress;
+            }
+        }
+
+        while self.stage == Stage::Remapping {
+            let copy_handle = self.mapping.inner()[&self.queue[self.remapped]];
+            self.mapping.remap_handles(
+                &mut scene.graph[copy_handle],
+                &[TypeId::of::<UntypedResource>()],
+            );
+
+            self.remapped += 1;
+            if self.remapped == self.queue.len() {
+                self.stage = Stage::Done;
+            } else if start.elapsed() >= budget {
+                return InstantiationStatus::InProgress;
+            }
+        }
+
+        let root = self.root();
+        let graph_root = scene.graph.get_root();
+        scene.graph.link_nodes(root, graph_root);
+        scene.graph.remove_node(self.staging_node);
+        self.staging_node = Handle::NONE;
+
+        // Explicitly mark as root node.
+        scene.graph[root].is_resource_instance_root = true;
+        scene.graph.update_hierarchical_data_for_descendants(root);
+
+        InstantiationStatus::Finished(root)
+    }
+
+    /// Cancels the instantiation and removes the partially built instance from the scene. Does
+    /// nothing with the instance if the instantiation is already finished.
+    pub fn cancel(self, scene: &mut Scene) {
+        if scene.graph.is_valid_handle(self.staging_node) {
+            scene.graph.remove_node(self.staging_node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::pool::Handle,
+        resource::model::{
+            instantiation::InstantiationStatus, Model, ModelResource, ModelResourceExtension,
+            NodeMapping,
+        },
+        scene::{base::BaseBuilder, pivot::PivotBuilder, Scene},
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn test_async_instantiation() {
+        let mut prefab_scene = Scene::new();
+        let child =
+            PivotBuilder::new(BaseBuilder::new().with_name("Child")).build(&mut prefab_scene.graph);
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_name("Parent")
+                .with_children(&[child]),
+        )
+        .build(&mut prefab_scene.graph);
+        let prefab = ModelResource::new_ok(
+            ResourceKind::Embedded,
+            Model {
+                mapping: NodeMapping::UseHandles,
+                scene: prefab_scene,
+            },
+        );
+
+        let mut scene = Scene::new();
+        let mut instantiation = prefab.instantiate_async();
+        assert_eq!(instantiation.root(), Handle::NONE);
+
+        // Zero budget processes exactly one node per step.
+        let mut steps = 0;
+        let root = loop {
+            steps += 1;
+            match instantiation.step(&mut scene, Duration::ZERO) {
+                InstantiationStatus::InProgress => {
+                    // Partially built instance must not be active.
+                    scene.graph.update_hierarchical_data();
+                    assert!(!scene.graph[instantiation.root()].is_globally_enabled());
+                }
+                InstantiationStatus::Finished(root) => break root,
+                InstantiationStatus::Failed => unreachable!(),
+            }
+        };
+        // 3 nodes (including the root of the prefab scene) are copied and then remapped.
+        assert_eq!(steps, 6);
+        assert_eq!(instantiation.progress(), 1.0);
+
+        let graph = &scene.graph;
+        assert_eq!(graph[root].parent(), graph.get_root());
+        assert!(graph[root].is_resource_instance_root);
+        assert!(graph[root].is_globally_enabled());
+        assert!(graph.find_by_name(root, "__ModelInstantiation").is_none());
+        let (parent, _) = graph.find_by_name(root, "Parent").unwrap();
+        let (child, _) = graph.find_by_name(root, "Child").unwrap();
+        assert_eq!(graph[child].parent(), parent);
+        assert_eq!(graph[child].resource(), Some(prefab));
+        // Only the root of the graph, the staging node was removed.
+        assert_eq!(graph.node_count(), 4);
+    }
+}
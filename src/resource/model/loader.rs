@@ -2,6 +2,7 @@
 
 use crate::{
     asset::{
+        hook::ImportHookContainer,
         io::ResourceIo,
         loader::{
             BoxedImportOptionsLoaderFuture, BoxedLoaderFuture, LoaderPayload, ResourceLoader,
@@ -25,11 +26,14 @@ pub struct ModelLoader {
     pub serialization_context: Arc<SerializationContext>,
     /// Default import options for model resources.
     pub default_import_options: ModelImportOptions,
+    /// A set of import hooks, that will be applied to the import options of every model before
+    /// loading.
+    pub import_hooks: ImportHookContainer,
 }
 
 impl ResourceLoader for ModelLoader {
     fn extensions(&self) -> &[&str] {
-        &["rgs", "fbx"]
+        &["rgs", "fbx", "prefab"]
     }
 
     fn data_type_uuid(&self) -> Uuid {
@@ -40,14 +44,17 @@ impl ResourceLoader for ModelLoader {
         let resource_manager = self.resource_manager.clone();
         let node_constructors = self.serialization_context.clone();
         let default_import_options = self.default_import_options.clone();
+        let import_hooks = self.import_hooks.clone();
 
         Box::pin(async move {
             let io = io.as_ref();
 
-            let import_options = try_get_import_settings(&path, io)
+            let mut import_options = try_get_import_settings(&path, io)
                 .await
                 .unwrap_or(default_import_options);
 
+            import_hooks.before_import(&path, &mut import_options);
+
             let model = Model::load(
                 path,
                 io,
@@ -15,8 +15,9 @@
 //!
 //! # Supported formats
 //!
-//! Currently only FBX (common format in game industry for storing complex 3d models)
-//! and RGS (native Fyroxed format) formats are supported.
+//! Currently only FBX (common format in game industry for storing complex 3d models),
+//! RGS (native Fyroxed format) and text prefabs (see [`prefab::TextPrefab`]) formats are
+//! supported.
 
 use crate::{
     asset::{
@@ -33,9 +34,18 @@ use crate::{
         TypeUuidProvider,
     },
     engine::SerializationContext,
-    resource::fbx::{self, error::FbxError},
+    resource::{
+        fbx::{self, error::FbxError},
+        model::{
+            instantiation::ModelInstantiation,
+            prefab::{TextPrefab, TextPrefabError},
+        },
+    },
     scene::{
-        animation::{Animation, AnimationPlayer},
+        animation::{
+            retarget::{retarget_animation, RetargetSettings},
+            Animation, AnimationPlayer,
+        },
         graph::{map::NodeHandleMap, Graph},
         node::Node,
         Scene, SceneLoader,
@@ -48,12 +58,15 @@ use std::error::Error;
 use std::{
     any::Any,
     fmt::{Display, Formatter},
+    ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
 };
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
+pub mod instantiation;
 pub mod loader;
+pub mod prefab;
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Reflect)]
 #[repr(u32)]
@@ -93,6 +106,12 @@ pub trait ModelResourceExtension: Sized {
     /// Tries to instantiate model from given resource.
     fn instantiate(&self, dest_scene: &mut Scene) -> Handle<Node>;
 
+    /// Begins asynchronous instantiation of the model, that spreads the creation of nodes over
+    /// multiple frames under a per-frame time budget. It should be used for very large prefabs, whose
+    /// instantiation using [`Self::instantiate`] causes noticeable hitches. See [`ModelInstantiation`]
+    /// docs for more info.
+    fn instantiate_async(&self) -> ModelInstantiation;
+
     /// Instantiates a prefab and places it at specified position and orientation in global coordinates.
     fn instantiate_at(
         &self,
@@ -124,6 +143,18 @@ pub trait ModelResourceExtension: Sized {
     /// this function will return vector with only one animation.
     fn retarget_animations_directly(&self, root: Handle<Node>, graph: &Graph) -> Vec<Animation>;
 
+    /// Tries to retarget animations from given model resource to a node hierarchy starting from `root`
+    /// on a given scene. Unlike [`Self::retarget_animations_directly`], it maps bones using the bone
+    /// map from the given settings and (optionally) compensates the difference between rest poses of
+    /// the skeletons. It allows you to use a single animation pack for characters with different
+    /// skeletons and proportions. See [`retarget_animation`] docs for more info.
+    fn retarget_animations_with_settings(
+        &self,
+        root: Handle<Node>,
+        graph: &Graph,
+        settings: &RetargetSettings,
+    ) -> Vec<Animation>;
+
     /// Tries to retarget animations from given model resource to a node hierarchy starting
     /// from `root` on a given scene. Unlike [`Self::retarget_animations_directly`], it automatically
     /// adds retargetted animations to the specified animation player in the hierarchy of given `root`.
@@ -189,6 +220,10 @@ impl ModelResourceExtension for ModelResource {
         instance_root
     }
 
+    fn instantiate_async(&self) -> ModelInstantiation {
+        ModelInstantiation::new(self.clone())
+    }
+
     fn instantiate_at(
         &self,
         scene: &mut Scene,
@@ -253,6 +288,34 @@ impl ModelResourceExtension for ModelResource {
         retargetted_animations
     }
 
+    fn retarget_animations_with_settings(
+        &self,
+        root: Handle<Node>,
+        graph: &Graph,
+        settings: &RetargetSettings,
+    ) -> Vec<Animation> {
+        let mut retargetted_animations = Vec::new();
+
+        let mut header = self.state();
+        if let Some(model) = header.data() {
+            for src_node_ref in model.scene.graph.linear_iter() {
+                if let Some(src_player) = src_node_ref.query_component_ref::<AnimationPlayer>() {
+                    for src_anim in src_player.animations().iter() {
+                        retargetted_animations.push(retarget_animation(
+                            src_anim,
+                            &model.scene.graph,
+                            root,
+                            graph,
+                            settings,
+                        ));
+                    }
+                }
+            }
+        }
+
+        retargetted_animations
+    }
+
     fn retarget_animations_to_player(
         &self,
         root: Handle<Node>,
@@ -394,6 +457,50 @@ impl MaterialSearchOptions {
     }
 }
 
+/// Defines an up axis of a model in a foreign file format. The engine uses Y axis as up axis, models with
+/// different up axis will be rotated on import.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Visit,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum ModelUpAxis {
+    /// Y axis is up axis, no conversion is needed. This is **default** option.
+    #[default]
+    Y,
+    /// Z axis is up axis (common for models made in Blender or 3ds Max), the model will be rotated
+    /// by -90 degrees around X axis.
+    Z,
+}
+
+uuid_provider!(ModelUpAxis = "3f6a2c1e-94d8-4b7a-a0e5-6d2b8c9f1e47");
+
+impl ModelUpAxis {
+    /// Returns a rotation, that converts the up axis to the engine's up axis (Y).
+    pub fn conversion_rotation(self) -> UnitQuaternion<f32> {
+        match self {
+            ModelUpAxis::Y => UnitQuaternion::identity(),
+            ModelUpAxis::Z => {
+                UnitQuaternion::from_axis_angle(&Vector3::x_axis(), -std::f32::consts::FRAC_PI_2)
+            }
+        }
+    }
+}
+
+fn default_model_scale() -> f32 {
+    1.0
+}
+
 /// A set of options that will be applied to a model resource when loading it from external source.
 ///
 /// # Details
@@ -404,20 +511,80 @@ impl MaterialSearchOptions {
 ///
 /// ```text
 /// (
-///     material_search_options: RecursiveUp
+///     material_search_options: RecursiveUp,
+///     scale: 0.01,
+///     up_axis: Z,
+///     animation_time_slice: Some((start: 0.0, end: 1.5)),
 /// )
 /// ```
 ///
-/// Check documentation of the field of the structure for more info about each parameter.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default, Reflect, Eq)]
+/// Check documentation of the field of the structure for more info about each parameter. Use
+/// [`ResourceManager::reimport`] to change import options of a model at runtime.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Reflect)]
 pub struct ModelImportOptions {
     /// See [`MaterialSearchOptions`] docs for more info.
     #[serde(default)]
     pub material_search_options: MaterialSearchOptions,
+    /// Uniform scale, that will be applied to the model. It is useful for models, that were made in different
+    /// units (for example, centimeters instead of meters). Default is 1.0.
+    #[serde(default = "default_model_scale")]
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub scale: f32,
+    /// Up axis of the model. See [`ModelUpAxis`] docs for more info.
+    #[serde(default)]
+    pub up_axis: ModelUpAxis,
+    /// Optional time slice (in seconds), that will be used to trim every animation of the model. It is
+    /// useful to extract a part of a long animation.
+    #[serde(default)]
+    pub animation_time_slice: Option<Range<f32>>,
+}
+
+impl Default for ModelImportOptions {
+    fn default() -> Self {
+        Self {
+            material_search_options: Default::default(),
+            scale: default_model_scale(),
+            up_axis: Default::default(),
+            animation_time_slice: None,
+        }
+    }
 }
 
 impl ImportOptions for ModelImportOptions {}
 
+impl ModelImportOptions {
+    /// Applies scale, up axis conversion and animation trimming to the given scene. Scale and rotation are
+    /// applied to the root node of the scene, so the local transforms of the nodes (and the animations) remain
+    /// unchanged.
+    pub fn apply(&self, scene: &mut Scene) {
+        let root = scene.graph.get_root();
+        let transform = scene.graph[root].local_transform_mut();
+        let scale = **transform.scale();
+        transform.set_scale(scale * self.scale);
+        let rotation = **transform.rotation();
+        transform.set_rotation(self.up_axis.conversion_rotation() * rotation);
+
+        if let Some(time_slice) = self.animation_time_slice.as_ref() {
+            for node in scene.graph.linear_iter_mut() {
+                if let Some(animation_player) = node.cast_mut::<AnimationPlayer>() {
+                    for animation in animation_player
+                        .animations_mut()
+                        .get_value_mut_silent()
+                        .iter_mut()
+                    {
+                        let current = animation.time_slice();
+                        let start = time_slice.start.clamp(current.start, current.end);
+                        let end = time_slice.end.clamp(start, current.end);
+                        animation.set_time_slice(start..end);
+                    }
+                }
+            }
+        }
+
+        scene.graph.update_hierarchical_data();
+    }
+}
+
 /// All possible errors that may occur while trying to load model from some
 /// data source.
 #[derive(Debug)]
@@ -428,6 +595,8 @@ pub enum ModelLoadError {
     NotSupported(String),
     /// An error occurred while loading FBX file.
     Fbx(FbxError),
+    /// An error occurred while loading text prefab.
+    Prefab(TextPrefabError),
 }
 
 impl Display for ModelLoadError {
@@ -440,6 +609,7 @@ impl Display for ModelLoadError {
                 write!(f, "Model format is not supported: {v}")
             }
             ModelLoadError::Fbx(v) => v.fmt(f),
+            ModelLoadError::Prefab(v) => v.fmt(f),
         }
     }
 }
@@ -450,6 +620,12 @@ impl From<FbxError> for ModelLoadError {
     }
 }
 
+impl From<TextPrefabError> for ModelLoadError {
+    fn from(e: TextPrefabError) -> Self {
+        ModelLoadError::Prefab(e)
+    }
+}
+
 impl From<VisitError> for ModelLoadError {
     fn from(e: VisitError) -> Self {
         ModelLoadError::Visit(e)
@@ -486,6 +662,7 @@ impl Model {
                     &model_import_options,
                 )
                 .await?;
+                model_import_options.apply(&mut scene);
                 // Set NodeMapping::UseNames as mapping here because FBX does not have
                 // any persistent unique ids, and we have to use names.
                 (scene, NodeMapping::UseNames)
@@ -505,6 +682,21 @@ impl Model {
                 .await,
                 NodeMapping::UseHandles,
             ),
+            // Text prefabs are rebuilt from scratch on every load, so handles of their nodes are
+            // not stable and names must be used.
+            "prefab" => {
+                let bytes = io
+                    .load_file(path.as_ref())
+                    .await
+                    .map_err(TextPrefabError::Io)?;
+                let prefab = TextPrefab::from_bytes(&bytes)?;
+                (
+                    prefab
+                        .build_scene(&serialization_context, &resource_manager)
+                        .await,
+                    NodeMapping::UseNames,
+                )
+            }
             // TODO: Add more formats.
             _ => {
                 return Err(ModelLoadError::NotSupported(format!(
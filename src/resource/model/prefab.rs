@@ -0,0 +1,522 @@
+//! Text prefab is a human-readable (RON) description of a node hierarchy, that could be used as a
+//! model resource. See [`TextPrefab`] docs for more info.
+
+use crate::{
+    asset::manager::ResourceManager,
+    core::{
+        algebra::{UnitQuaternion, Vector2, Vector3, Vector4},
+        color::Color,
+        io::FileLoadError,
+        log::Log,
+        pool::Handle,
+        reflect::{prelude::*, SetFieldByPathError},
+        uuid::Uuid,
+        TypeUuidProvider,
+    },
+    engine::SerializationContext,
+    resource::model::{Model, ModelResource, ModelResourceExtension},
+    scene::{
+        self,
+        animation::{absm::AnimationBlendingStateMachine, AnimationPlayer},
+        camera::Camera,
+        csg::{Brush, CsgModel},
+        decal::Decal,
+        dim2::{self, rectangle::Rectangle},
+        folder::Folder,
+        ik::IkConstraint,
+        light::{directional::DirectionalLight, point::PointLight, spot::SpotLight},
+        light_probe::LightProbeVolume,
+        mesh::Mesh,
+        navmesh::NavigationalMesh,
+        node::Node,
+        particle_system::ParticleSystem,
+        pivot::{Pivot, PivotBuilder},
+        ragdoll::Ragdoll,
+        sound::{listener::Listener, reverb_zone::ReverbZone, Sound},
+        sprite::Sprite,
+        streaming::StreamingVolume,
+        terrain::Terrain,
+        timeline::TimelinePlayer,
+        trail::Trail,
+        water::Water,
+        Scene,
+    },
+    script::Script,
+};
+use fxhash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    path::PathBuf,
+    str::FromStr,
+};
+
+/// A value of a property override. The type of the value must exactly match the type of the
+/// property, for example a `f32` property can be set only by [`PrefabValue::F32`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PrefabValue {
+    /// A `bool` value.
+    Bool(bool),
+    /// An `i32` value.
+    I32(i32),
+    /// An `u32` value.
+    U32(u32),
+    /// An `i64` value.
+    I64(i64),
+    /// An `u64` value.
+    U64(u64),
+    /// A `f32` value.
+    F32(f32),
+    /// A `f64` value.
+    F64(f64),
+    /// A `String` value.
+    String(String),
+    /// A `Vector2<f32>` value.
+    Vector2((f32, f32)),
+    /// A `Vector3<f32>` value.
+    Vector3((f32, f32, f32)),
+    /// A `Vector4<f32>` value.
+    Vector4((f32, f32, f32, f32)),
+    /// A [`Color`] value in `(r, g, b, a)` form.
+    Color((u8, u8, u8, u8)),
+}
+
+impl PrefabValue {
+    fn into_reflect(self) -> Box<dyn Reflect> {
+        match self {
+            PrefabValue::Bool(v) => Box::new(v),
+            PrefabValue::I32(v) => Box::new(v),
+            PrefabValue::U32(v) => Box::new(v),
+            PrefabValue::I64(v) => Box::new(v),
+            PrefabValue::U64(v) => Box::new(v),
+            PrefabValue::F32(v) => Box::new(v),
+            PrefabValue::F64(v) => Box::new(v),
+            PrefabValue::String(v) => Box::new(v),
+            PrefabValue::Vector2((x, y)) => Box::new(Vector2::new(x, y)),
+            PrefabValue::Vector3((x, y, z)) => Box::new(Vector3::new(x, y, z)),
+            PrefabValue::Vector4((x, y, z, w)) => Box::new(Vector4::new(x, y, z, w)),
+            PrefabValue::Color((r, g, b, a)) => Box::new(Color::from_rgba(r, g, b, a)),
+        }
+    }
+}
+
+/// A script of a node of a text prefab.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextPrefabScript {
+    /// Name of the script (the one that was used to register the script in
+    /// [`crate::script::constructor::ScriptConstructorContainer`]) or its type UUID.
+    pub name: String,
+    /// A set of `path -> value` pairs, that will be applied to the script instance using
+    /// reflection.
+    pub properties: BTreeMap<String, PrefabValue>,
+}
+
+/// A node of a text prefab.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextPrefabNode {
+    /// Name of the node. Names are used to map the nodes of instances to the nodes of the prefab,
+    /// so they should be unique.
+    pub name: String,
+    /// Type of the node. It could be either a short name of a built-in node (for example `Mesh` or
+    /// `Collider2D`, see [`built_in_node_type_uuid`]) or a type UUID of a node. [`Pivot`] is used if
+    /// not specified.
+    pub kind: Option<String>,
+    /// A path to a model resource, that will be instantiated instead of creating a node of
+    /// [`Self::kind`]. The rest of the fields of this node are applied to the root of the instance.
+    pub model: Option<PathBuf>,
+    /// Local position of the node.
+    pub position: Option<(f32, f32, f32)>,
+    /// Local rotation of the node in Euler angles (in degrees).
+    pub rotation: Option<(f32, f32, f32)>,
+    /// Local scale of the node.
+    pub scale: Option<(f32, f32, f32)>,
+    /// Tag of the node.
+    pub tag: Option<String>,
+    /// A set of `path -> value` pairs, that will be applied to the node using reflection.
+    pub properties: BTreeMap<String, PrefabValue>,
+    /// A script of the node.
+    pub script: Option<TextPrefabScript>,
+    /// Children nodes.
+    pub children: Vec<TextPrefabNode>,
+}
+
+/// Text prefab is a human-readable description of a node hierarchy in [RON](https://github.com/ron-rs/ron)
+/// format. Text prefabs are stored in files with `.prefab` extension and they're loaded as usual
+/// model resources, which means that they could be instantiated the same way as any other model.
+/// Such prefabs could be edited in any text editor without recompiling the game or using the
+/// editor, any changes in a prefab file will be automatically propagated to its instances (if
+/// the resource manager watches the file system).
+///
+/// ## Example
+///
+/// ```ron
+/// #![enable(implicit_some)]
+/// (
+///     root: (
+///         name: "Barrel",
+///         position: (0.0, 1.0, 0.0),
+///         tag: "Destructible",
+///         script: (
+///             name: "Health",
+///             properties: {
+///                 "max_health": F32(150.0),
+///             },
+///         ),
+///         children: [
+///             (
+///                 name: "BarrelModel",
+///                 model: "data/models/barrel.fbx",
+///                 rotation: (0.0, 45.0, 0.0),
+///             ),
+///             (
+///                 name: "Smoke",
+///                 kind: "ParticleSystem",
+///                 properties: {
+///                     "base.visibility": Bool(false),
+///                 },
+///             ),
+///         ],
+///     ),
+/// )
+/// ```
+///
+/// ## Error handling
+///
+/// Only syntax errors make a prefab fail to load. Unknown node types, scripts, invalid property
+/// paths or mismatched value types are reported to the log and skipped, so a single typo does
+/// not break the whole prefab.
+///
+/// Keep in mind, that a prefab must not reference itself (directly or via other prefabs) using
+/// [`TextPrefabNode::model`], otherwise it will never be loaded.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextPrefab {
+    /// Root node of the prefab.
+    pub root: TextPrefabNode,
+}
+
+/// An error that may occur during text prefab loading.
+#[derive(Debug)]
+pub enum TextPrefabError {
+    /// An i/o error.
+    Io(FileLoadError),
+    /// A syntax error.
+    Parse(ron::error::SpannedError),
+}
+
+impl Display for TextPrefabError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextPrefabError::Io(v) => write!(f, "A file load error has occurred {v:?}"),
+            TextPrefabError::Parse(v) => write!(f, "A text prefab parsing error has occurred {v}"),
+        }
+    }
+}
+
+impl From<FileLoadError> for TextPrefabError {
+    fn from(e: FileLoadError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for TextPrefabError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// Returns type UUID of a built-in node by its short name. 2D nodes, that have the same names as
+/// 3D nodes, have `2D` suffix: `Collider2D`, `Joint2D`, `RigidBody2D`.
+pub fn built_in_node_type_uuid(name: &str) -> Option<Uuid> {
+    Some(match name {
+        "Collider2D" => dim2::collider::Collider::type_uuid(),
+        "Joint2D" => dim2::joint::Joint::type_uuid(),
+        "RigidBody2D" => dim2::rigidbody::RigidBody::type_uuid(),
+        "Rectangle" => Rectangle::type_uuid(),
+        "TileMap" => dim2::tilemap::TileMap::type_uuid(),
+        "DirectionalLight" => DirectionalLight::type_uuid(),
+        "PointLight" => PointLight::type_uuid(),
+        "SpotLight" => SpotLight::type_uuid(),
+        "Mesh" => Mesh::type_uuid(),
+        "ParticleSystem" => ParticleSystem::type_uuid(),
+        "Sound" => Sound::type_uuid(),
+        "Listener" => Listener::type_uuid(),
+        "Camera" => Camera::type_uuid(),
+        "Collider" => scene::collider::Collider::type_uuid(),
+        "Decal" => Decal::type_uuid(),
+        "Joint" => scene::joint::Joint::type_uuid(),
+        "Pivot" => Pivot::type_uuid(),
+        "Folder" => Folder::type_uuid(),
+        "RigidBody" => scene::rigidbody::RigidBody::type_uuid(),
+        "Sprite" => Sprite::type_uuid(),
+        "Terrain" => Terrain::type_uuid(),
+        "AnimationPlayer" => AnimationPlayer::type_uuid(),
+        "AnimationBlendingStateMachine" => AnimationBlendingStateMachine::type_uuid(),
+        "NavigationalMesh" => NavigationalMesh::type_uuid(),
+        "Ragdoll" => Ragdoll::type_uuid(),
+        "TimelinePlayer" => TimelinePlayer::type_uuid(),
+        "Trail" => Trail::type_uuid(),
+        "Water" => Water::type_uuid(),
+        "LightProbeVolume" => LightProbeVolume::type_uuid(),
+        "ReverbZone" => ReverbZone::type_uuid(),
+        "StreamingVolume" => StreamingVolume::type_uuid(),
+        "Brush" => Brush::type_uuid(),
+        "CsgModel" => CsgModel::type_uuid(),
+        "IkConstraint" => IkConstraint::type_uuid(),
+        _ => return None,
+    })
+}
+
+fn apply_properties(entity: &mut dyn Reflect, properties: &BTreeMap<String, PrefabValue>) {
+    for (path, value) in properties {
+        entity.set_field_by_path(path, value.clone().into_reflect(), &mut |result| {
+            if let Err(err) = result {
+                match err {
+                    SetFieldByPathError::InvalidPath { reason, .. } => Log::err(format!(
+                        "Failed to set property {}! Invalid path: {}",
+                        path, reason
+                    )),
+                    SetFieldByPathError::InvalidValue(_) => {
+                        Log::err(format!("Failed to set property {}! Types mismatch!", path))
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl FromStr for TextPrefab {
+    type Err = TextPrefabError;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        Ok(ron::de::from_str(str)?)
+    }
+}
+
+impl TextPrefab {
+    /// Tries to parse a text prefab from the given bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TextPrefabError> {
+        Ok(ron::de::from_bytes(bytes)?)
+    }
+
+    /// Creates a new scene from the prefab. All models, that are referenced by the prefab, are
+    /// loaded first.
+    pub(crate) async fn build_scene(
+        &self,
+        serialization_context: &SerializationContext,
+        resource_manager: &ResourceManager,
+    ) -> Scene {
+        let mut paths = Vec::new();
+        collect_model_paths(&self.root, &mut paths);
+
+        let mut models = FxHashMap::default();
+        for path in paths {
+            if models.contains_key(&path) {
+                continue;
+            }
+
+            match resource_manager.request::<Model>(&path).await {
+                Ok(model) => {
+                    models.insert(path, model);
+                }
+                Err(err) => Log::err(format!(
+                    "Unable to load {} model for text prefab. Reason: {:?}",
+                    path.display(),
+                    err
+                )),
+            }
+        }
+
+        let mut scene = Scene::new();
+        let root = scene.graph.get_root();
+        build_node(&self.root, root, &mut scene, serialization_context, &models);
+        scene
+    }
+}
+
+fn collect_model_paths(node: &TextPrefabNode, paths: &mut Vec<PathBuf>) {
+    if let Some(model) = node.model.as_ref() {
+        paths.push(model.clone());
+    }
+    for child in node.children.iter() {
+        collect_model_paths(child, paths);
+    }
+}
+
+fn create_node(
+    desc: &TextPrefabNode,
+    scene: &mut Scene,
+    serialization_context: &SerializationContext,
+    models: &FxHashMap<PathBuf, ModelResource>,
+) -> Handle<Node> {
+    if let Some(model) = desc.model.as_ref().and_then(|path| models.get(path)) {
+        return model.instantiate(scene);
+    }
+
+    let node = desc.kind.as_ref().and_then(|kind| {
+        let type_uuid =
+            built_in_node_type_uuid(kind).or_else(|| Uuid::from_str(kind.as_str()).ok());
+        let node = type_uuid.and_then(|type_uuid| {
+            serialization_context
+                .node_constructors
+                .try_create(&type_uuid)
+        });
+        if node.is_none() {
+            Log::err(format!(
+                "Unknown node type {} of {} node! Pivot will be used instead.",
+                kind, desc.name
+            ));
+        }
+        node
+    });
+
+    match node {
+        Some(node) => scene.graph.add_node(node),
+        None => PivotBuilder::new(Default::default()).build(&mut scene.graph),
+    }
+}
+
+fn create_script(
+    desc: &TextPrefabScript,
+    serialization_context: &SerializationContext,
+) -> Option<Script> {
+    let uuid = Uuid::from_str(&desc.name).ok();
+    let mut constructors = serialization_context.script_constructors.map();
+    let script = constructors
+        .iter_mut()
+        .find(|(type_uuid, constructor)| constructor.name == desc.name || Some(**type_uuid) == uuid)
+        .map(|(_, constructor)| (constructor.constructor)());
+    if script.is_none() {
+        Log::err(format!("Unknown script {}!", desc.name));
+    }
+    script
+}
+
+fn build_node(
+    desc: &TextPrefabNode,
+    parent: Handle<Node>,
+    scene: &mut Scene,
+    serialization_context: &SerializationContext,
+    models: &FxHashMap<PathBuf, ModelResource>,
+) -> Handle<Node> {
+    let handle = create_node(desc, scene, serialization_context, models);
+    scene.graph.link_nodes(handle, parent);
+
+    let node = &mut scene.graph[handle];
+
+    if !desc.name.is_empty() {
+        node.set_name(&desc.name);
+    }
+    if let Some(tag) = desc.tag.as_ref() {
+        node.set_tag(tag.clone());
+    }
+
+    let transform = node.local_transform_mut();
+    if let Some((x, y, z)) = desc.position {
+        transform.set_position(Vector3::new(x, y, z));
+    }
+    if let Some((x, y, z)) = desc.rotation {
+        transform.set_rotation(UnitQuaternion::from_euler_angles(
+            x.to_radians(),
+            y.to_radians(),
+            z.to_radians(),
+        ));
+    }
+    if let Some((x, y, z)) = desc.scale {
+        transform.set_scale(Vector3::new(x, y, z));
+    }
+
+    node.as_reflect_mut(&mut |node| apply_properties(node, &desc.properties));
+
+    if let Some(script_desc) = desc.script.as_ref() {
+        if let Some(mut script) = create_script(script_desc, serialization_context) {
+            script.as_reflect_mut(&mut |script| apply_properties(script, &script_desc.properties));
+            scene.graph[handle].set_script(Some(script));
+        }
+    }
+
+    for child in desc.children.iter() {
+        build_node(child, handle, scene, serialization_context, models);
+    }
+
+    handle
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::manager::ResourceManager,
+        core::{algebra::Vector3, futures::executor::block_on, task::TaskPool, TypeUuidProvider},
+        engine::SerializationContext,
+        resource::model::prefab::{built_in_node_type_uuid, PrefabValue, TextPrefab},
+        scene::{mesh::Mesh, pivot::Pivot},
+    };
+    use std::{str::FromStr, sync::Arc};
+
+    const PREFAB: &str = r#"
+#![enable(implicit_some)]
+(
+    root: (
+        name: "Barrel",
+        position: (0.0, 1.0, 0.0),
+        tag: "Destructible",
+        children: [
+            (
+                name: "Body",
+                kind: "Mesh",
+                scale: (2.0, 2.0, 2.0),
+                properties: {
+                    "base.visibility": Bool(false),
+                },
+            ),
+        ],
+    ),
+)
+"#;
+
+    #[test]
+    fn test_text_prefab_parsing() {
+        let prefab = TextPrefab::from_str(PREFAB).unwrap();
+        assert_eq!(prefab.root.name, "Barrel");
+        assert_eq!(prefab.root.position, Some((0.0, 1.0, 0.0)));
+        assert_eq!(prefab.root.kind, None);
+        assert_eq!(prefab.root.children.len(), 1);
+
+        let body = &prefab.root.children[0];
+        assert_eq!(body.kind.as_deref(), Some("Mesh"));
+        assert_eq!(
+            body.properties.get("base.visibility"),
+            Some(&PrefabValue::Bool(false))
+        );
+
+        assert!(TextPrefab::from_str("(root: (name: 123))").is_err());
+    }
+
+    #[test]
+    fn test_text_prefab_scene() {
+        let prefab = TextPrefab::from_str(PREFAB).unwrap();
+        let resource_manager = ResourceManager::new(Arc::new(TaskPool::new()));
+        let scene = block_on(prefab.build_scene(&SerializationContext::new(), &resource_manager));
+
+        let (_, barrel) = scene.graph.find_by_name_from_root("Barrel").unwrap();
+        assert!(barrel.is_pivot());
+        assert_eq!(barrel.tag(), "Destructible");
+        assert_eq!(
+            **barrel.local_transform().position(),
+            Vector3::new(0.0, 1.0, 0.0)
+        );
+
+        let (_, body) = scene.graph.find_by_name_from_root("Body").unwrap();
+        assert!(body.cast::<Mesh>().is_some());
+        assert!(!body.visibility());
+        assert_eq!(
+            **body.local_transform().scale(),
+            Vector3::new(2.0, 2.0, 2.0)
+        );
+
+        assert_eq!(built_in_node_type_uuid("Pivot"), Some(Pivot::type_uuid()));
+        assert_eq!(built_in_node_type_uuid("Foo"), None);
+    }
+}
@@ -0,0 +1,39 @@
+//! Sound bank loader.
+
+use crate::{
+    asset::{
+        io::ResourceIo,
+        loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+        manager::ResourceManager,
+    },
+    core::{uuid::Uuid, TypeUuidProvider},
+    resource::sound_event::SoundBank,
+};
+use fyrox_resource::state::LoadError;
+use std::{path::PathBuf, sync::Arc};
+
+/// Default implementation for sound bank loading.
+pub struct SoundBankLoader {
+    /// Resource manager that will be used to load sounds.
+    pub resource_manager: ResourceManager,
+}
+
+impl ResourceLoader for SoundBankLoader {
+    fn extensions(&self) -> &[&str] {
+        &["sndbank"]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        SoundBank::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        let resource_manager = self.resource_manager.clone();
+        Box::pin(async move {
+            let sound_bank = SoundBank::from_file(&path, io.as_ref(), resource_manager)
+                .await
+                .map_err(LoadError::new)?;
+            Ok(LoaderPayload::new(sound_bank))
+        })
+    }
+}
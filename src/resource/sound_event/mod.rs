@@ -0,0 +1,351 @@
+//! Sound bank is a resource that contains a set of named sound events. Sound events describe how
+//! a sound should be played (random sample selection, pitch and gain variations, cooldown, etc.),
+//! so the game code only needs to know a name of an event. See [`SoundBank`] and [`SoundEvent`] docs
+//! for more info.
+
+use crate::{
+    asset::{io::ResourceIo, manager::ResourceManager, Resource, ResourceData},
+    core::{
+        algebra::Vector3,
+        log::Log,
+        numeric_range::RangeExt,
+        pool::Handle,
+        rand::{seq::SliceRandom, thread_rng},
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    generic_animation::signal::AnimationEvent,
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        node::Node,
+        sound::{SoundBufferResource, SoundBuilder, Status},
+        transform::TransformBuilder,
+    },
+};
+use fxhash::FxHashMap;
+use std::{any::Any, error::Error, ops::Range, path::Path, sync::Arc};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+pub mod loader;
+
+/// Defines how a sound of an event is positioned.
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Default,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum SoundEventMode {
+    /// The sound is positioned in the world and it is affected by distance attenuation, panning,
+    /// Doppler effect, etc.
+    #[default]
+    Spatial,
+    /// The sound is not positioned in the world, it is heard with the same volume everywhere. It is
+    /// suitable for UI sounds, music stingers and so on.
+    Flat,
+}
+
+uuid_provider!(SoundEventMode = "3b0b9f5e-8f7a-4c4a-a0a4-7c0c2b3f1d6e");
+
+/// Sound event is a named description of a sound. Every time the event is played, a random sound is
+/// selected from the set of sounds and its gain and pitch are randomly selected from the respective
+/// ranges. It makes repetitive sounds (footsteps, impacts, shots) less monotonous.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct SoundEvent {
+    /// Name of the event. It is used to play the event from code or from animation signals with the
+    /// same name.
+    pub name: String,
+
+    /// A set of sound variations, one of them is selected randomly each time the event is played.
+    pub sounds: Vec<SoundBufferResource>,
+
+    /// A range of gain (volume) of the sound.
+    pub gain: Range<f32>,
+
+    /// A range of pitch of the sound.
+    pub pitch: Range<f32>,
+
+    /// Minimal amount of time (in seconds) between two consecutive plays of the event. Attempts to
+    /// play the event during the cooldown are ignored.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub cooldown: f32,
+
+    /// Positioning mode of the sound. See [`SoundEventMode`] docs for more info.
+    pub mode: SoundEventMode,
+
+    /// Radius of the sound. See [`crate::scene::sound::Sound::set_radius`] for more info.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub radius: f32,
+
+    /// Max distance of the sound. See [`crate::scene::sound::Sound::set_max_distance`] for more info.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub max_distance: f32,
+
+    /// Name of an audio bus, that will be used to play the sound.
+    pub audio_bus: String,
+}
+
+impl Default for SoundEvent {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            sounds: Default::default(),
+            gain: 1.0..1.0,
+            pitch: 1.0..1.0,
+            cooldown: 0.0,
+            mode: Default::default(),
+            radius: 1.0,
+            max_distance: f32::MAX,
+            audio_bus: "Master".to_string(),
+        }
+    }
+}
+
+impl SoundEvent {
+    /// Creates a new sound node, that plays a random sound of the event once at the given position
+    /// and then removes itself. Returns [`Handle::NONE`] if the event has no sounds.
+    pub fn instantiate(&self, position: Vector3<f32>, graph: &mut Graph) -> Handle<Node> {
+        let mut rng = thread_rng();
+
+        let Some(buffer) = self.sounds.choose(&mut rng).cloned() else {
+            return Handle::NONE;
+        };
+
+        let spatial_blend = match self.mode {
+            SoundEventMode::Spatial => 1.0,
+            SoundEventMode::Flat => 0.0,
+        };
+
+        SoundBuilder::new(
+            BaseBuilder::new()
+                .with_name(&self.name)
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                ),
+        )
+        .with_buffer(Some(buffer))
+        .with_gain(self.gain.random(&mut rng))
+        .with_pitch(self.pitch.random(&mut rng) as f64)
+        .with_spatial_blend_factor(spatial_blend)
+        .with_radius(self.radius)
+        .with_max_distance(self.max_distance)
+        .with_audio_bus(self.audio_bus.clone())
+        .with_play_once(true)
+        .with_status(Status::Playing)
+        .build(graph)
+    }
+}
+
+/// Sound bank is a resource that contains a set of sound events. It allows designers to tune sounds
+/// of a game without changing its code. Sound events are played by their names using
+/// [`SoundEventPlayer`].
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox::{
+/// #     core::algebra::Vector3,
+/// #     resource::sound_event::SoundEventPlayer,
+/// #     scene::graph::Graph,
+/// # };
+/// fn play_footstep(player: &mut SoundEventPlayer, position: Vector3<f32>, graph: &mut Graph) {
+///     player.play("Footstep", position, graph);
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Visit, Reflect)]
+pub struct SoundBank {
+    /// A set of sound events of the bank.
+    pub events: Vec<SoundEvent>,
+}
+
+impl TypeUuidProvider for SoundBank {
+    fn type_uuid() -> Uuid {
+        uuid!("c4d61e42-7b0f-4d9e-9f43-2e5d8c1a7b90")
+    }
+}
+
+impl ResourceData for SoundBank {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn type_uuid(&self) -> Uuid {
+        <Self as TypeUuidProvider>::type_uuid()
+    }
+
+    fn save(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut visitor = Visitor::new();
+        self.visit("SoundBank", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
+    }
+
+    fn can_be_saved(&self) -> bool {
+        true
+    }
+}
+
+impl SoundBank {
+    /// Loads a sound bank from the given file. Resource manager is used to load sounds.
+    pub async fn from_file(
+        path: &Path,
+        io: &dyn ResourceIo,
+        resource_manager: ResourceManager,
+    ) -> Result<Self, VisitError> {
+        let bytes = io.load_file(path).await?;
+        let mut visitor = Visitor::load_from_memory(&bytes)?;
+        visitor.blackboard.register(Arc::new(resource_manager));
+        let mut sound_bank = SoundBank::default();
+        sound_bank.visit("SoundBank", &mut visitor)?;
+        Ok(sound_bank)
+    }
+
+    /// Searches for an event with the given name.
+    pub fn event(&self, name: &str) -> Option<&SoundEvent> {
+        self.events.iter().find(|event| event.name == name)
+    }
+}
+
+/// Type alias for sound bank resources.
+pub type SoundBankResource = Resource<SoundBank>;
+
+/// Sound event player plays sound events of a sound bank by their names and tracks cooldowns of
+/// the events. It is meant to be stored in a script or a plugin, and [`Self::update`] must be called
+/// every frame.
+#[derive(Debug, Clone, Default, Visit, Reflect)]
+pub struct SoundEventPlayer {
+    /// A sound bank, that will be used to search for sound events.
+    pub sound_bank: Option<SoundBankResource>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    cooldowns: FxHashMap<String, f32>,
+}
+
+impl SoundEventPlayer {
+    /// Creates new sound event player, that uses the given sound bank.
+    pub fn new(sound_bank: SoundBankResource) -> Self {
+        Self {
+            sound_bank: Some(sound_bank),
+            cooldowns: Default::default(),
+        }
+    }
+
+    /// Returns `true` if the event with the given name is in cooldown and cannot be played.
+    pub fn is_in_cooldown(&self, name: &str) -> bool {
+        self.cooldowns.contains_key(name)
+    }
+
+    /// Plays an event with the given name at the given position. Returns a handle of a new sound
+    /// node or [`Handle::NONE`] if the event is in cooldown, does not exist or the sound bank is
+    /// not loaded yet.
+    pub fn play(&mut self, name: &str, position: Vector3<f32>, graph: &mut Graph) -> Handle<Node> {
+        if self.is_in_cooldown(name) {
+            return Handle::NONE;
+        }
+
+        let Some(sound_bank) = self.sound_bank.as_ref() else {
+            return Handle::NONE;
+        };
+
+        let mut state = sound_bank.state();
+        let Some(sound_bank) = state.data() else {
+            return Handle::NONE;
+        };
+
+        let Some(event) = sound_bank.event(name) else {
+            Log::warn(format!("There's no sound event with {name} name!"));
+            return Handle::NONE;
+        };
+
+        if event.cooldown > 0.0 {
+            self.cooldowns.insert(name.to_string(), event.cooldown);
+        }
+
+        event.instantiate(position, graph)
+    }
+
+    /// Plays sound events, that have the same names as the given animation events. Animation events
+    /// that have no respective sound events are ignored. It is useful to play footsteps and other
+    /// sounds, that are synchronized with animations, using animation signals.
+    pub fn play_animation_events<'a>(
+        &mut self,
+        events: impl IntoIterator<Item = &'a AnimationEvent>,
+        position: Vector3<f32>,
+        graph: &mut Graph,
+    ) {
+        let Some(sound_bank) = self.sound_bank.clone() else {
+            return;
+        };
+
+        for event in events {
+            let has_event = sound_bank
+                .state()
+                .data()
+                .is_some_and(|bank| bank.event(&event.name).is_some());
+            if has_event {
+                self.play(&event.name, position, graph);
+            }
+        }
+    }
+
+    /// Advances cooldowns of the events by the given amount of time.
+    pub fn update(&mut self, dt: f32) {
+        self.cooldowns.retain(|_, cooldown| {
+            *cooldown -= dt;
+            *cooldown > 0.0
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::{untyped::ResourceKind, Resource},
+        core::algebra::Vector3,
+        resource::sound_event::{SoundBank, SoundEvent, SoundEventPlayer},
+        scene::graph::Graph,
+    };
+
+    #[test]
+    fn test_sound_event_cooldown() {
+        let bank = SoundBank {
+            events: vec![SoundEvent {
+                name: "Footstep".to_string(),
+                cooldown: 0.5,
+                ..Default::default()
+            }],
+        };
+        let mut player = SoundEventPlayer::new(Resource::new_ok(ResourceKind::Embedded, bank));
+        let mut graph = Graph::new();
+
+        // The event has no sounds, but it still must start its cooldown.
+        player.play("Footstep", Vector3::default(), &mut graph);
+        assert!(player.is_in_cooldown("Footstep"));
+        assert!(!player.is_in_cooldown("Impact"));
+
+        player.update(0.25);
+        assert!(player.is_in_cooldown("Footstep"));
+
+        player.update(0.25);
+        assert!(!player.is_in_cooldown("Footstep"));
+    }
+}
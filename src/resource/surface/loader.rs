@@ -0,0 +1,39 @@
+//! Surface effects loader.
+
+use crate::{
+    asset::{
+        io::ResourceIo,
+        loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+        manager::ResourceManager,
+    },
+    core::{uuid::Uuid, TypeUuidProvider},
+    resource::surface::SurfaceEffects,
+};
+use fyrox_resource::state::LoadError;
+use std::{path::PathBuf, sync::Arc};
+
+/// Default implementation for surface effects loading.
+pub struct SurfaceEffectsLoader {
+    /// Resource manager that will be used to load sounds and effect prefabs.
+    pub resource_manager: ResourceManager,
+}
+
+impl ResourceLoader for SurfaceEffectsLoader {
+    fn extensions(&self) -> &[&str] {
+        &["surfeff"]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        SurfaceEffects::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        let resource_manager = self.resource_manager.clone();
+        Box::pin(async move {
+            let surface_effects = SurfaceEffects::from_file(&path, io.as_ref(), resource_manager)
+                .await
+                .map_err(LoadError::new)?;
+            Ok(LoaderPayload::new(surface_effects))
+        })
+    }
+}
@@ -0,0 +1,132 @@
+//! Surface effects resource maps types of surfaces to sounds and visual effects. See [`SurfaceEffects`]
+//! docs for more info.
+
+use crate::{
+    asset::{io::ResourceIo, manager::ResourceManager, Resource, ResourceData},
+    core::{
+        rand::seq::SliceRandom,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    resource::model::ModelResource,
+    scene::sound::SoundBufferResource,
+};
+use std::{any::Any, error::Error, path::Path, sync::Arc};
+
+pub mod loader;
+
+/// A set of sounds and effects for a particular type of surface.
+#[derive(Debug, Clone, Default, PartialEq, Visit, Reflect)]
+pub struct SurfaceEffect {
+    /// Type of the surface (for example `grass`, `metal`, `wood`, etc.). It must match the surface
+    /// type of colliders (see [`crate::scene::collider::Collider::set_surface_type`]) or terrain
+    /// layers (see [`crate::scene::terrain::Layer::surface_type`]).
+    pub surface_type: String,
+
+    /// A set of sound variations (for example footsteps or impacts), usually one of them is selected
+    /// randomly using [`SurfaceEffects::random_sound`].
+    pub sounds: Vec<SoundBufferResource>,
+
+    /// An optional prefab with visual effect (for example a particle system with dust or sparks),
+    /// that should be instantiated at the point of contact.
+    pub effect: Option<ModelResource>,
+}
+
+/// Surface effects is a resource that maps types of surfaces to sounds and visual effects. It ties
+/// physics, audio and visual effects together: a ray cast returns a collider, its surface type could
+/// be fetched using [`crate::scene::graph::Graph::surface_type`] and then the surface type is used to
+/// find a sound and an effect.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox::{
+/// #     resource::surface::SurfaceEffectsResource,
+/// #     scene::{graph::{physics::Intersection, Graph}, sound::SoundBufferResource},
+/// # };
+/// fn footstep_sound(
+///     graph: &Graph,
+///     intersection: &Intersection,
+///     surface_effects: &SurfaceEffectsResource,
+/// ) -> Option<SoundBufferResource> {
+///     let surface_type = graph.surface_type(intersection)?;
+///     surface_effects.data_ref().random_sound(surface_type).cloned()
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Visit, Reflect)]
+pub struct SurfaceEffects {
+    /// A set of effects for every known surface type.
+    pub effects: Vec<SurfaceEffect>,
+}
+
+impl TypeUuidProvider for SurfaceEffects {
+    fn type_uuid() -> Uuid {
+        uuid!("a3bc8f4c-0d05-4e0c-9a0b-2ef94cf1e0b6")
+    }
+}
+
+impl ResourceData for SurfaceEffects {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn type_uuid(&self) -> Uuid {
+        <Self as TypeUuidProvider>::type_uuid()
+    }
+
+    fn save(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut visitor = Visitor::new();
+        self.visit("SurfaceEffects", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
+    }
+
+    fn can_be_saved(&self) -> bool {
+        true
+    }
+}
+
+impl SurfaceEffects {
+    /// Loads surface effects from the given file. Resource manager is used to load sounds and effect
+    /// prefabs.
+    pub async fn from_file(
+        path: &Path,
+        io: &dyn ResourceIo,
+        resource_manager: ResourceManager,
+    ) -> Result<Self, VisitError> {
+        let bytes = io.load_file(path).await?;
+        let mut visitor = Visitor::load_from_memory(&bytes)?;
+        visitor.blackboard.register(Arc::new(resource_manager));
+        let mut surface_effects = SurfaceEffects::default();
+        surface_effects.visit("SurfaceEffects", &mut visitor)?;
+        Ok(surface_effects)
+    }
+
+    /// Searches for a set of effects of the given surface type.
+    pub fn effect(&self, surface_type: &str) -> Option<&SurfaceEffect> {
+        self.effects
+            .iter()
+            .find(|effect| effect.surface_type == surface_type)
+    }
+
+    /// Selects a random sound of the given surface type.
+    pub fn random_sound(&self, surface_type: &str) -> Option<&SoundBufferResource> {
+        self.effect(surface_type)?
+            .sounds
+            .choose(&mut crate::core::rand::thread_rng())
+    }
+
+    /// Returns a visual effect prefab of the given surface type.
+    pub fn visual_effect(&self, surface_type: &str) -> Option<&ModelResource> {
+        self.effect(surface_type)?.effect.as_ref()
+    }
+}
+
+/// Type alias for surface effects resources.
+pub type SurfaceEffectsResource = Resource<SurfaceEffects>;
@@ -2,6 +2,7 @@
 
 use crate::{
     asset::{
+        hook::ImportHookContainer,
         io::ResourceIo,
         loader::{
             BoxedImportOptionsLoaderFuture, BoxedLoaderFuture, LoaderPayload, ResourceLoader,
@@ -18,12 +19,15 @@ use std::{path::PathBuf, sync::Arc};
 pub struct TextureLoader {
     /// Default import options for textures.
     pub default_import_options: TextureImportOptions,
+    /// A set of import hooks, that will be applied to the import options of every texture before
+    /// loading.
+    pub import_hooks: ImportHookContainer,
 }
 
 impl ResourceLoader for TextureLoader {
     fn extensions(&self) -> &[&str] {
         &[
-            "jpg", "jpeg", "tga", "gif", "bmp", "png", "tiff", "tif", "dds",
+            "jpg", "jpeg", "tga", "gif", "bmp", "png", "tiff", "tif", "dds", "hdr",
         ]
     }
 
@@ -33,13 +37,16 @@ impl ResourceLoader for TextureLoader {
 
     fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
         let default_import_options = self.default_import_options.clone();
+        let import_hooks = self.import_hooks.clone();
         Box::pin(async move {
             let io = io.as_ref();
 
-            let import_options = try_get_import_settings(&path, io)
+            let mut import_options = try_get_import_settings(&path, io)
                 .await
                 .unwrap_or(default_import_options);
 
+            import_hooks.before_import(&path, &mut import_options);
+
             let raw_texture = Texture::load_from_file(&path, io, import_options)
                 .await
                 .map_err(LoadError::new)?;
@@ -1248,17 +1248,34 @@ fn bytes_in_mip_level(kind: TextureKind, pixel_kind: TexturePixelKind, mip: usiz
     }
 }
 
-fn mip_byte_offset(kind: TextureKind, pixel_kind: TexturePixelKind, mut mip: usize) -> usize {
-    // TODO: This could be done without loop.
-    let mut offset = 0;
-    loop {
-        offset += bytes_in_mip_level(kind, pixel_kind, mip) as usize;
-        mip = mip.saturating_sub(1);
-        if mip == 0 {
-            break;
+// Returns offset of the end of the given mip level, i.e. total size of the mip levels up to the given
+// one (inclusive).
+fn mip_byte_offset(kind: TextureKind, pixel_kind: TexturePixelKind, mip: usize) -> usize {
+    (0..=mip)
+        .map(|level| bytes_in_mip_level(kind, pixel_kind, level) as usize)
+        .sum()
+}
+
+// Downscales an image with floating-point pixels two times using 2x2 box filter. Odd rows and
+// columns are dropped.
+fn box_downscale(pixels: &[f32], width: usize, height: usize, channels: usize) -> Vec<f32> {
+    let new_width = width / 2;
+    let new_height = height / 2;
+    let mut result = Vec::with_capacity(new_width * new_height * channels);
+    for y in 0..new_height {
+        for x in 0..new_width {
+            for channel in 0..channels {
+                let texel = |x: usize, y: usize| pixels[(y * width + x) * channels + channel];
+                result.push(
+                    0.25 * (texel(2 * x, 2 * y)
+                        + texel(2 * x + 1, 2 * y)
+                        + texel(2 * x, 2 * y + 1)
+                        + texel(2 * x + 1, 2 * y + 1)),
+                );
+            }
         }
     }
-    offset
+    result
 }
 
 fn convert_pixel_type_enum(pixel_kind: TexturePixelKind) -> fr::PixelType {
@@ -1442,7 +1459,31 @@ impl Texture {
                 width as usize * height as usize * src_pixel_kind.size_in_bytes().unwrap_or(4),
             );
 
-            if import_options.minification_filter.is_using_mip_mapping() {
+            if import_options.minification_filter.is_using_mip_mapping()
+                && matches!(
+                    src_pixel_kind,
+                    TexturePixelKind::RGB32F | TexturePixelKind::RGBA32F
+                )
+            {
+                // Floating-point images (HDR) are not supported by the resizer, so use simple box
+                // filter for them.
+                let (mut level, channels) = match dyn_img {
+                    DynamicImage::ImageRgb32F(ref img) => (img.as_raw().clone(), 3),
+                    _ => (dyn_img.to_rgba32f().into_raw(), 4),
+                };
+                let mut level_width = width as usize;
+                let mut level_height = height as usize;
+                loop {
+                    mip_count += 1;
+                    bytes.extend(level.iter().flat_map(|value| value.to_ne_bytes()));
+                    if level_width < 2 || level_height < 2 {
+                        break;
+                    }
+                    level = box_downscale(&level, level_width, level_height, channels);
+                    level_width /= 2;
+                    level_height /= 2;
+                }
+            } else if import_options.minification_filter.is_using_mip_mapping() {
                 let src_pixel_type = convert_pixel_type_enum(src_pixel_kind);
                 let mut level_width = width;
                 let mut level_height = height;
@@ -1567,6 +1608,34 @@ impl Texture {
         }
     }
 
+    /// Creates new texture instance with the given number of mip levels. `bytes` must contain data
+    /// of every mip level one after another, each next level is two times smaller than the previous
+    /// one. Cube map mip levels contain all six faces. Returns `None` if size of data does not match
+    /// the required size.
+    pub fn from_bytes_with_mips(
+        kind: TextureKind,
+        pixel_kind: TexturePixelKind,
+        bytes: Vec<u8>,
+        mip_count: u32,
+    ) -> Option<Self> {
+        let mip_count = mip_count.max(1);
+        let size = (0..mip_count as usize)
+            .map(|mip| bytes_in_mip_level(kind, pixel_kind, mip) as usize)
+            .sum::<usize>();
+        if size != bytes.len() {
+            None
+        } else {
+            Some(Self {
+                kind,
+                data_hash: data_hash(&bytes),
+                bytes: bytes.into(),
+                pixel_kind,
+                mip_count,
+                ..Default::default()
+            })
+        }
+    }
+
     /// Sets new minification filter. It is used when texture becomes smaller.
     pub fn set_minification_filter(&mut self, filter: TextureMinificationFilter) {
         self.minification_filter = filter;
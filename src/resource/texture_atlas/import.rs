@@ -0,0 +1,406 @@
+//! Importers for texture atlases, that were created by external texture packers.
+//!
+//! Supported formats:
+//!
+//! - JSON (`.json`) files in "JSON (Hash)" and "JSON (Array)" formats of
+//!   [TexturePacker](https://www.codeandweb.com/texturepacker). The same format is used by many other
+//!   tools, such as Aseprite, Free Texture Packer, ShoeBox, etc.
+//! - libGDX (`.atlas`) files, created by libGDX texture packer (both legacy and current versions of
+//!   the format). Only single-page atlases are supported, regions of other pages are ignored.
+
+use crate::{
+    asset::{io::ResourceIo, manager::ResourceManager},
+    core::{algebra::Vector2, log::Log},
+    resource::{
+        texture::Texture,
+        texture_atlas::{AtlasRegion, AtlasRegionRotation, TextureAtlas, TextureAtlasError},
+    },
+};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path};
+
+#[derive(Deserialize)]
+struct JsonRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct JsonSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonFrame {
+    #[serde(default)]
+    filename: String,
+    frame: JsonRect,
+    #[serde(default)]
+    rotated: bool,
+    sprite_source_size: Option<JsonRect>,
+    source_size: Option<JsonSize>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonFrames {
+    Hash(BTreeMap<String, JsonFrame>),
+    Array(Vec<JsonFrame>),
+}
+
+#[derive(Deserialize)]
+struct JsonMeta {
+    image: Option<String>,
+    size: Option<JsonSize>,
+}
+
+#[derive(Deserialize)]
+struct JsonAtlas {
+    frames: JsonFrames,
+    meta: JsonMeta,
+}
+
+fn json_frame_to_region(name: String, frame: JsonFrame) -> AtlasRegion {
+    let size = Vector2::new(frame.frame.w, frame.frame.h);
+    AtlasRegion {
+        name,
+        position: Vector2::new(frame.frame.x, frame.frame.y),
+        size,
+        // TexturePacker rotates images clockwise.
+        rotation: if frame.rotated {
+            AtlasRegionRotation::Clockwise
+        } else {
+            AtlasRegionRotation::None
+        },
+        offset: frame
+            .sprite_source_size
+            .map(|r| Vector2::new(r.x, r.y))
+            .unwrap_or_default(),
+        source_size: frame
+            .source_size
+            .map(|s| Vector2::new(s.w, s.h))
+            .unwrap_or(size),
+    }
+}
+
+/// Parses a texture atlas in TexturePacker's JSON (Hash or Array) format. Returns the atlas without
+/// a texture and the path of the texture (relative to the atlas file), if any.
+pub fn parse_texture_packer_json(
+    text: &str,
+) -> Result<(TextureAtlas, Option<String>), TextureAtlasError> {
+    let json = serde_json::from_str::<JsonAtlas>(text)?;
+
+    let size = json
+        .meta
+        .size
+        .map(|s| Vector2::new(s.w, s.h))
+        .ok_or_else(|| TextureAtlasError::Format("meta.size is missing".to_string()))?;
+
+    let mut atlas = TextureAtlas::new(None, size);
+    match json.frames {
+        JsonFrames::Hash(frames) => {
+            for (name, frame) in frames {
+                atlas.add_region(json_frame_to_region(name, frame));
+            }
+        }
+        JsonFrames::Array(frames) => {
+            for mut frame in frames {
+                let name = std::mem::take(&mut frame.filename);
+                atlas.add_region(json_frame_to_region(name, frame));
+            }
+        }
+    }
+
+    Ok((atlas, json.meta.image))
+}
+
+fn parse_numbers<const N: usize>(key: &str, value: &str) -> Result<[i64; N], TextureAtlasError> {
+    let mut numbers = [0; N];
+    let mut parts = value.split(',').map(|p| p.trim().parse::<i64>());
+    for number in numbers.iter_mut() {
+        *number = parts.next().and_then(|p| p.ok()).ok_or_else(|| {
+            TextureAtlasError::Format(format!("Invalid value {value} of {key} property"))
+        })?;
+    }
+    Ok(numbers)
+}
+
+fn to_u32(value: i64) -> u32 {
+    value.clamp(0, u32::MAX as i64) as u32
+}
+
+#[derive(Default)]
+struct LibGdxRegion {
+    name: String,
+    position: Vector2<u32>,
+    size: Vector2<u32>,
+    rotation: AtlasRegionRotation,
+    // Offset from the bottom-left corner of the source image.
+    offset: Vector2<u32>,
+    source_size: Option<Vector2<u32>>,
+    index: i64,
+}
+
+impl LibGdxRegion {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            index: -1,
+            ..Default::default()
+        }
+    }
+
+    fn set_property(&mut self, key: &str, value: &str) -> Result<(), TextureAtlasError> {
+        match key {
+            "xy" => {
+                let [x, y] = parse_numbers(key, value)?;
+                self.position = Vector2::new(to_u32(x), to_u32(y));
+            }
+            "size" => {
+                let [w, h] = parse_numbers(key, value)?;
+                self.size = Vector2::new(to_u32(w), to_u32(h));
+            }
+            "bounds" => {
+                let [x, y, w, h] = parse_numbers(key, value)?;
+                self.position = Vector2::new(to_u32(x), to_u32(y));
+                self.size = Vector2::new(to_u32(w), to_u32(h));
+            }
+            "orig" => {
+                let [w, h] = parse_numbers(key, value)?;
+                self.source_size = Some(Vector2::new(to_u32(w), to_u32(h)));
+            }
+            "offset" => {
+                let [x, y] = parse_numbers(key, value)?;
+                self.offset = Vector2::new(to_u32(x), to_u32(y));
+            }
+            "offsets" => {
+                let [x, y, w, h] = parse_numbers(key, value)?;
+                self.offset = Vector2::new(to_u32(x), to_u32(y));
+                self.source_size = Some(Vector2::new(to_u32(w), to_u32(h)));
+            }
+            // libGDX texture packer rotates images counterclockwise.
+            "rotate" => {
+                self.rotation = match value {
+                    "true" | "90" => AtlasRegionRotation::CounterClockwise,
+                    "270" => AtlasRegionRotation::Clockwise,
+                    "false" | "0" => AtlasRegionRotation::None,
+                    _ => {
+                        return Err(TextureAtlasError::Format(format!(
+                            "Unsupported rotation {value} of {} region",
+                            self.name
+                        )))
+                    }
+                }
+            }
+            "index" => {
+                let [index] = parse_numbers(key, value)?;
+                self.index = index;
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn into_region(self) -> AtlasRegion {
+        let source_size = self.source_size.unwrap_or(self.size);
+        AtlasRegion {
+            // Indexed regions are usually frames of an animation, that share the same name.
+            name: if self.index >= 0 {
+                format!("{}_{}", self.name, self.index)
+            } else {
+                self.name
+            },
+            position: self.position,
+            size: self.size,
+            rotation: self.rotation,
+            offset: Vector2::new(
+                self.offset.x,
+                source_size
+                    .y
+                    .saturating_sub(self.offset.y)
+                    .saturating_sub(self.size.y),
+            ),
+            source_size,
+        }
+    }
+}
+
+/// Parses a texture atlas in libGDX format. Returns the atlas without a texture and the path of the
+/// texture (relative to the atlas file). Regions of an animation (with `index` property) are named
+/// as `name_index`.
+pub fn parse_libgdx_atlas(text: &str) -> Result<(TextureAtlas, Option<String>), TextureAtlasError> {
+    let mut atlas = TextureAtlas::default();
+    let mut image = None;
+    let mut page_count = 0;
+    let mut in_page = false;
+    let mut region: Option<LibGdxRegion> = None;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            // An empty line separates pages.
+            in_page = false;
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once(':') {
+            let (key, value) = (key.trim(), value.trim());
+            if page_count > 1 {
+                continue;
+            }
+            if let Some(region) = region.as_mut() {
+                region.set_property(key, value)?;
+            } else if key == "size" {
+                let [w, h] = parse_numbers(key, value)?;
+                atlas.size = Vector2::new(to_u32(w), to_u32(h));
+            }
+        } else if !in_page {
+            // A new page.
+            in_page = true;
+            page_count += 1;
+            if let Some(region) = region.take() {
+                atlas.add_region(region.into_region());
+            }
+            if page_count == 1 {
+                image = Some(line.to_owned());
+            } else if page_count == 2 {
+                Log::warn(
+                    "Multi-page libGDX atlases are not supported, only the first page is used!",
+                );
+            }
+        } else if page_count == 1 {
+            if let Some(region) = region.replace(LibGdxRegion::new(line)) {
+                atlas.add_region(region.into_region());
+            }
+        }
+    }
+
+    if let Some(region) = region {
+        if page_count == 1 {
+            atlas.add_region(region.into_region());
+        }
+    }
+
+    if atlas.size.x == 0 || atlas.size.y == 0 {
+        return Err(TextureAtlasError::Format(
+            "Size of the page is missing".to_string(),
+        ));
+    }
+
+    Ok((atlas, image))
+}
+
+/// Imports a texture atlas from the given file of an external texture packer. The format is chosen
+/// by the extension of the file: `.json` - TexturePacker JSON, `.atlas` - libGDX. The texture of the
+/// atlas is requested from the resource manager.
+pub async fn import(
+    path: &Path,
+    io: &dyn ResourceIo,
+    resource_manager: &ResourceManager,
+) -> Result<TextureAtlas, TextureAtlasError> {
+    let bytes = io.load_file(path).await?;
+    let text = String::from_utf8_lossy(&bytes);
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let (mut atlas, image) = match extension.as_str() {
+        "json" => parse_texture_packer_json(&text)?,
+        "atlas" => parse_libgdx_atlas(&text)?,
+        _ => {
+            return Err(TextureAtlasError::Format(format!(
+                "Unsupported texture atlas format {extension}"
+            )))
+        }
+    };
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    atlas.texture = image.map(|image| resource_manager.request::<Texture>(base_dir.join(image)));
+
+    Ok(atlas)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector2,
+        resource::texture_atlas::{
+            import::{parse_libgdx_atlas, parse_texture_packer_json},
+            AtlasRegionRotation,
+        },
+    };
+
+    #[test]
+    fn test_texture_packer_json() {
+        let text = r#"{
+            "frames": {
+                "idle.png": {
+                    "frame": {"x": 2, "y": 4, "w": 20, "h": 30},
+                    "rotated": true,
+                    "trimmed": true,
+                    "spriteSourceSize": {"x": 6, "y": 1, "w": 20, "h": 30},
+                    "sourceSize": {"w": 32, "h": 32}
+                },
+                "run.png": {
+                    "frame": {"x": 40, "y": 4, "w": 32, "h": 32},
+                    "rotated": false,
+                    "trimmed": false
+                }
+            },
+            "meta": {"image": "sheet.png", "size": {"w": 128, "h": 64}}
+        }"#;
+
+        let (atlas, image) = parse_texture_packer_json(text).unwrap();
+        assert_eq!(image.as_deref(), Some("sheet.png"));
+        assert_eq!(atlas.size, Vector2::new(128, 64));
+        assert_eq!(atlas.regions.len(), 2);
+
+        let idle = atlas.region("idle.png").unwrap();
+        assert_eq!(idle.rotation, AtlasRegionRotation::Clockwise);
+        assert_eq!(idle.packed_size(), Vector2::new(30, 20));
+        assert_eq!(idle.offset, Vector2::new(6, 1));
+        assert_eq!(idle.source_size, Vector2::new(32, 32));
+        assert!(!atlas.region("run.png").unwrap().is_trimmed());
+    }
+
+    #[test]
+    fn test_libgdx_atlas() {
+        let text = "
+sheet.png
+size: 64, 64
+format: RGBA8888
+filter: Nearest, Nearest
+repeat: none
+coin
+  rotate: true
+  xy: 2, 2
+  size: 10, 12
+  orig: 16, 16
+  offset: 3, 1
+  index: -1
+walk
+  bounds: 20, 2, 16, 16
+  index: 3
+";
+
+        let (atlas, image) = parse_libgdx_atlas(text).unwrap();
+        assert_eq!(image.as_deref(), Some("sheet.png"));
+        assert_eq!(atlas.size, Vector2::new(64, 64));
+
+        let coin = atlas.region("coin").unwrap();
+        assert_eq!(coin.rotation, AtlasRegionRotation::CounterClockwise);
+        assert_eq!(coin.size, Vector2::new(10, 12));
+        // Offset is converted from bottom-left to top-left origin.
+        assert_eq!(coin.offset, Vector2::new(3, 3));
+        assert_eq!(coin.source_size, Vector2::new(16, 16));
+
+        let walk = atlas.region("walk_3").unwrap();
+        assert_eq!(walk.position, Vector2::new(20, 2));
+        assert!(!walk.is_trimmed());
+    }
+}
@@ -0,0 +1,49 @@
+//! Texture atlas loader.
+
+use crate::{
+    asset::{
+        io::ResourceIo,
+        loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+        manager::ResourceManager,
+    },
+    core::{uuid::Uuid, TypeUuidProvider},
+    resource::texture_atlas::{import, TextureAtlas},
+};
+use fyrox_resource::state::LoadError;
+use std::{path::PathBuf, sync::Arc};
+
+/// Default implementation for texture atlas loading. Supports native `.texatlas` files, TexturePacker
+/// `.json` files and libGDX `.atlas` files.
+pub struct TextureAtlasLoader {
+    /// Resource manager that will be used to load textures of atlases.
+    pub resource_manager: ResourceManager,
+}
+
+impl ResourceLoader for TextureAtlasLoader {
+    fn extensions(&self) -> &[&str] {
+        &["texatlas", "json", "atlas"]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        TextureAtlas::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        let resource_manager = self.resource_manager.clone();
+        Box::pin(async move {
+            let is_native = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("texatlas"));
+            let atlas = if is_native {
+                TextureAtlas::from_file(&path, io.as_ref(), resource_manager)
+                    .await
+                    .map_err(LoadError::new)?
+            } else {
+                import::import(&path, io.as_ref(), &resource_manager)
+                    .await
+                    .map_err(LoadError::new)?
+            };
+            Ok(LoaderPayload::new(atlas))
+        })
+    }
+}
@@ -0,0 +1,444 @@
+//! Texture atlas is a resource, that describes named regions of a single texture, that contains images
+//! of many sprites. See [`TextureAtlas`] docs for more info.
+
+use crate::{
+    asset::{io::ResourceIo, manager::ResourceManager, Resource, ResourceData},
+    core::{
+        algebra::Vector2,
+        io::FileLoadError,
+        math::Rect,
+        reflect::prelude::*,
+        sstorage::ImmutableString,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    material::MaterialResource,
+    resource::texture::TextureResource,
+};
+use std::{
+    any::Any,
+    error::Error,
+    fmt::{Display, Formatter},
+    path::Path,
+    sync::Arc,
+};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+pub mod import;
+pub mod loader;
+
+/// An error that may occur during texture atlas loading or import.
+#[derive(Debug)]
+pub enum TextureAtlasError {
+    /// An i/o error has occurred.
+    Io(FileLoadError),
+    /// An error that may occur due to version incompatibilities.
+    Visit(VisitError),
+    /// The file is not a valid JSON document.
+    Json(serde_json::Error),
+    /// The file has invalid or unsupported content.
+    Format(String),
+}
+
+impl Display for TextureAtlasError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextureAtlasError::Io(v) => {
+                write!(f, "A file load error has occurred {v:?}")
+            }
+            TextureAtlasError::Visit(v) => {
+                write!(
+                    f,
+                    "An error that may occur due to version incompatibilities. {v:?}"
+                )
+            }
+            TextureAtlasError::Json(v) => {
+                write!(f, "Unable to parse JSON. Reason: {v}")
+            }
+            TextureAtlasError::Format(v) => {
+                write!(f, "Invalid or unsupported texture atlas file: {v}")
+            }
+        }
+    }
+}
+
+impl From<FileLoadError> for TextureAtlasError {
+    fn from(e: FileLoadError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<VisitError> for TextureAtlasError {
+    fn from(e: VisitError) -> Self {
+        Self::Visit(e)
+    }
+}
+
+impl From<serde_json::Error> for TextureAtlasError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+/// Defines how an image of a region is rotated in the atlas. Texture packers rotate images by 90
+/// degrees to pack them tighter.
+#[derive(
+    Copy,
+    Clone,
+    Default,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum AtlasRegionRotation {
+    /// The image is not rotated.
+    #[default]
+    None,
+    /// The image is rotated by 90 degrees clockwise.
+    Clockwise,
+    /// The image is rotated by 90 degrees counterclockwise.
+    CounterClockwise,
+}
+
+uuid_provider!(AtlasRegionRotation = "7d3e1a5b-9c24-4f86-b0e7-2a6f8c4d1b93");
+
+/// A named region of a texture atlas, that contains an image of a single sprite.
+///
+/// Texture packers usually trim transparent borders of images to save space. Trimmed regions keep
+/// the size of the source image and the offset of the trimmed image in it, so sprites that use them
+/// are shown at the same place as untrimmed ones.
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct AtlasRegion {
+    /// Name of the region. Usually, it is the file name of the source image.
+    pub name: String,
+    /// Position of the top-left corner of the region in the atlas, in pixels.
+    pub position: Vector2<u32>,
+    /// Size of the (trimmed) image of the region, in pixels. It is the size of the image before the
+    /// rotation, rotated region occupies `size.y x size.x` pixels in the atlas.
+    pub size: Vector2<u32>,
+    /// Rotation of the image in the atlas.
+    pub rotation: AtlasRegionRotation,
+    /// Offset of the trimmed image from the top-left corner of the source image, in pixels.
+    pub offset: Vector2<u32>,
+    /// Size of the source image (before trimming), in pixels.
+    pub source_size: Vector2<u32>,
+}
+
+impl AtlasRegion {
+    /// Creates new untrimmed and unrotated region.
+    pub fn new<S: AsRef<str>>(name: S, position: Vector2<u32>, size: Vector2<u32>) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            position,
+            size,
+            rotation: AtlasRegionRotation::None,
+            offset: Vector2::default(),
+            source_size: size,
+        }
+    }
+
+    /// Returns the size, that the region occupies in the atlas. It takes the rotation into account.
+    pub fn packed_size(&self) -> Vector2<u32> {
+        match self.rotation {
+            AtlasRegionRotation::None => self.size,
+            AtlasRegionRotation::Clockwise | AtlasRegionRotation::CounterClockwise => {
+                Vector2::new(self.size.y, self.size.x)
+            }
+        }
+    }
+
+    /// Returns `true` if the image of the region is smaller than its source image.
+    pub fn is_trimmed(&self) -> bool {
+        self.offset != Vector2::default() || self.size != self.source_size
+    }
+
+    /// Returns normalized rectangle, that the region occupies in an atlas of the given size.
+    pub fn uv_rect(&self, atlas_size: Vector2<u32>) -> Rect<f32> {
+        let atlas_size = atlas_size.map(|c| c.max(1) as f32);
+        let packed_size = self.packed_size();
+        Rect::new(
+            self.position.x as f32 / atlas_size.x,
+            self.position.y as f32 / atlas_size.y,
+            packed_size.x as f32 / atlas_size.x,
+            packed_size.y as f32 / atlas_size.y,
+        )
+    }
+
+    /// Returns a quad, that could be used to render the region from an atlas of the given size.
+    pub fn quad(&self, atlas_size: Vector2<u32>) -> AtlasQuad {
+        let source_size = self.source_size.map(|c| c as f32);
+        let local_rect = if source_size.x > 0.0 && source_size.y > 0.0 {
+            let offset = self.offset.map(|c| c as f32);
+            let size = self.size.map(|c| c as f32);
+            Rect::new(
+                offset.x / source_size.x,
+                offset.y / source_size.y,
+                size.x / source_size.x,
+                size.y / source_size.y,
+            )
+        } else {
+            Rect::new(0.0, 0.0, 1.0, 1.0)
+        };
+
+        AtlasQuad {
+            uv_rect: self.uv_rect(atlas_size),
+            rotation: self.rotation,
+            local_rect,
+        }
+    }
+}
+
+/// Defines how a node should map its quad to a region of an atlas. Default quad maps the whole
+/// texture to the whole node.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AtlasQuad {
+    /// Normalized rectangle, that the region occupies in the atlas.
+    pub uv_rect: Rect<f32>,
+    /// Rotation of the image in the atlas.
+    pub rotation: AtlasRegionRotation,
+    /// A part of the quad (in normalized coordinates), that is covered by the trimmed image.
+    pub local_rect: Rect<f32>,
+}
+
+impl Default for AtlasQuad {
+    fn default() -> Self {
+        Self {
+            uv_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
+            rotation: AtlasRegionRotation::None,
+            local_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl AtlasQuad {
+    /// Maps a point of the image (in normalized coordinates, where `[0; 0]` is the top-left corner of
+    /// the image) to texture coordinates in the atlas.
+    pub fn tex_coord(&self, point: Vector2<f32>) -> Vector2<f32> {
+        let packed = match self.rotation {
+            AtlasRegionRotation::None => point,
+            AtlasRegionRotation::Clockwise => Vector2::new(1.0 - point.y, point.x),
+            AtlasRegionRotation::CounterClockwise => Vector2::new(point.y, 1.0 - point.x),
+        };
+        self.uv_rect.position + self.uv_rect.size.component_mul(&packed)
+    }
+
+    /// Maps a point of the trimmed image (in normalized coordinates) to a point of the quad, that
+    /// is used to show the whole source image.
+    pub fn local_position(&self, point: Vector2<f32>) -> Vector2<f32> {
+        self.local_rect.position + self.local_rect.size.component_mul(&point)
+    }
+}
+
+/// Texture atlas is a set of named regions of a single texture, where each region contains an image
+/// of a sprite. Sprites of a 2D scene are usually packed into a few atlases, so nodes that use them
+/// could be rendered with a few draw calls. [`crate::scene::dim2::rectangle::Rectangle`] and
+/// [`crate::scene::sprite::Sprite`] nodes could refer to regions of an atlas by name using
+/// [`AtlasRegionRef`].
+///
+/// Texture atlases could be created from code, loaded from native `.texatlas` files or imported from
+/// files of common texture packers (see [`import`] module docs for supported formats).
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::algebra::Vector2,
+///     resource::texture_atlas::{AtlasRegion, TextureAtlas},
+/// };
+///
+/// let mut atlas = TextureAtlas::new(None, Vector2::new(256, 256));
+/// atlas.add_region(AtlasRegion::new("coin", Vector2::new(0, 0), Vector2::new(32, 32)));
+/// atlas.add_region(AtlasRegion::new("gem", Vector2::new(32, 0), Vector2::new(32, 32)));
+///
+/// let uv_rect = atlas.region("gem").unwrap().uv_rect(atlas.size);
+/// assert_eq!(uv_rect.position.x, 0.125);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct TextureAtlas {
+    /// A texture, that contains images of every region of the atlas.
+    pub texture: Option<TextureResource>,
+    /// Size of the texture in pixels. It is used to calculate texture coordinates of the regions.
+    pub size: Vector2<u32>,
+    /// Regions of the atlas.
+    pub regions: Vec<AtlasRegion>,
+}
+
+impl TypeUuidProvider for TextureAtlas {
+    fn type_uuid() -> Uuid {
+        uuid!("c8f2a6d4-3b71-4e95-a0d8-5f1e7b9c2a46")
+    }
+}
+
+impl ResourceData for TextureAtlas {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn type_uuid(&self) -> Uuid {
+        <Self as TypeUuidProvider>::type_uuid()
+    }
+
+    fn save(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut visitor = Visitor::new();
+        self.visit("TextureAtlas", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
+    }
+
+    fn can_be_saved(&self) -> bool {
+        true
+    }
+}
+
+impl TextureAtlas {
+    /// Creates new empty texture atlas.
+    pub fn new(texture: Option<TextureResource>, size: Vector2<u32>) -> Self {
+        Self {
+            texture,
+            size,
+            regions: Default::default(),
+        }
+    }
+
+    /// Loads a texture atlas from the given native (`.texatlas`) file.
+    pub async fn from_file(
+        path: &Path,
+        io: &dyn ResourceIo,
+        resource_manager: ResourceManager,
+    ) -> Result<Self, TextureAtlasError> {
+        let bytes = io.load_file(path).await?;
+        let mut visitor = Visitor::load_from_memory(&bytes)?;
+        visitor.blackboard.register(Arc::new(resource_manager));
+        let mut atlas = TextureAtlas::default();
+        atlas.visit("TextureAtlas", &mut visitor)?;
+        Ok(atlas)
+    }
+
+    /// Adds a new region to the atlas, replacing existing region with the same name (if any).
+    pub fn add_region(&mut self, region: AtlasRegion) {
+        if let Some(existing) = self.regions.iter_mut().find(|r| r.name == region.name) {
+            *existing = region;
+        } else {
+            self.regions.push(region);
+        }
+    }
+
+    /// Returns a region with the given name.
+    pub fn region<S: AsRef<str>>(&self, name: S) -> Option<&AtlasRegion> {
+        self.regions.iter().find(|r| r.name == name.as_ref())
+    }
+
+    /// Returns a quad, that could be used to render a region with the given name.
+    pub fn region_quad<S: AsRef<str>>(&self, name: S) -> Option<AtlasQuad> {
+        self.region(name).map(|region| region.quad(self.size))
+    }
+}
+
+/// Type alias for texture atlas resources.
+pub type TextureAtlasResource = Resource<TextureAtlas>;
+
+/// A reference to a named region of a texture atlas. It is used by 2D nodes to show an image from an
+/// atlas. The texture of the atlas is automatically assigned to the `diffuseTexture` property of the
+/// material of a node, so nodes that use the same atlas (and the same material parameters) are
+/// batched together.
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct AtlasRegionRef {
+    /// A texture atlas. When it is not set, a node uses its own uv rectangle.
+    pub atlas: Option<TextureAtlasResource>,
+    /// Name of the region of the atlas.
+    pub region: String,
+}
+
+uuid_provider!(AtlasRegionRef = "4a9c7e2f-b163-4d58-8e0a-d2f5c6b3a187");
+
+impl AtlasRegionRef {
+    /// Creates new reference to a region with the given name of the given atlas.
+    pub fn new<S: AsRef<str>>(atlas: TextureAtlasResource, region: S) -> Self {
+        Self {
+            atlas: Some(atlas),
+            region: region.as_ref().to_owned(),
+        }
+    }
+
+    /// Returns a quad of the region. `None` is returned if there's no atlas, the atlas is not loaded
+    /// yet or it has no region with the name.
+    pub fn quad(&self) -> Option<AtlasQuad> {
+        let atlas = self.atlas.as_ref()?;
+        let mut state = atlas.state();
+        let atlas = state.data()?;
+        atlas.region_quad(&self.region)
+    }
+
+    /// Keeps the `diffuseTexture` property of the given material in sync with the texture of the
+    /// atlas. Does nothing if there's no atlas or it is not loaded yet.
+    pub fn sync_material(&self, material: &MaterialResource) {
+        let Some(atlas) = self.atlas.as_ref() else {
+            return;
+        };
+        let texture = match atlas.state().data() {
+            Some(atlas) => atlas.texture.clone(),
+            None => return,
+        };
+
+        let property_name = ImmutableString::new("diffuseTexture");
+        let mut material = material.data_ref();
+        if material
+            .property_ref(&property_name)
+            .and_then(|property| property.as_sampler())
+            != texture
+        {
+            // This could fail only for custom materials without diffuseTexture property.
+            let _ = material.set_texture(&property_name, texture);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector2,
+        resource::texture_atlas::{AtlasRegion, AtlasRegionRotation},
+    };
+
+    #[test]
+    fn test_region_quad() {
+        let mut region = AtlasRegion::new("a", Vector2::new(64, 32), Vector2::new(32, 16));
+        region.offset = Vector2::new(8, 0);
+        region.source_size = Vector2::new(64, 16);
+        region.rotation = AtlasRegionRotation::Clockwise;
+        assert_eq!(region.packed_size(), Vector2::new(16, 32));
+        assert!(region.is_trimmed());
+
+        let quad = region.quad(Vector2::new(128, 128));
+        assert_eq!(quad.uv_rect.position, Vector2::new(0.5, 0.25));
+        assert_eq!(quad.uv_rect.size, Vector2::new(0.125, 0.25));
+        assert_eq!(quad.local_rect.position, Vector2::new(0.125, 0.0));
+        assert_eq!(quad.local_rect.size, Vector2::new(0.5, 1.0));
+
+        // Top-left corner of the image is at the top-right corner of a clockwise-rotated region.
+        assert_eq!(
+            quad.tex_coord(Vector2::new(0.0, 0.0)),
+            Vector2::new(0.625, 0.25)
+        );
+        assert_eq!(
+            quad.tex_coord(Vector2::new(1.0, 0.0)),
+            Vector2::new(0.625, 0.5)
+        );
+        assert_eq!(
+            quad.local_position(Vector2::new(1.0, 1.0)),
+            Vector2::new(0.625, 1.0)
+        );
+    }
+}
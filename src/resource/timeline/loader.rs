@@ -0,0 +1,34 @@
+//! Timeline loader.
+
+use crate::{
+    asset::{
+        io::ResourceIo,
+        loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+    },
+    core::{uuid::Uuid, TypeUuidProvider},
+    resource::timeline::Timeline,
+};
+use fyrox_resource::state::LoadError;
+use std::{path::PathBuf, sync::Arc};
+
+/// Default implementation for timeline loading.
+pub struct TimelineLoader;
+
+impl ResourceLoader for TimelineLoader {
+    fn extensions(&self) -> &[&str] {
+        &["timeline"]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        Timeline::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        Box::pin(async move {
+            let timeline = Timeline::from_file(&path, io.as_ref())
+                .await
+                .map_err(LoadError::new)?;
+            Ok(LoaderPayload::new(timeline))
+        })
+    }
+}
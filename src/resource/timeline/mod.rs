@@ -0,0 +1,186 @@
+//! Timeline is a resource that sequences animations, camera cuts, property tracks, sound cues and
+//! script events. It is used to create authored cinematics (cutscenes). See [`Timeline`] docs for
+//! more info.
+
+use crate::{
+    asset::{io::ResourceIo, Resource, ResourceData},
+    core::{
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    generic_animation::{container::TrackDataContainer, value::ValueBinding},
+};
+use std::{any::Any, error::Error, path::Path};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+pub mod loader;
+
+/// An action that will be performed when a timeline reaches a time of an event. Every action refers
+/// to scene nodes by their names, because a timeline is a resource and it can be shared across
+/// multiple scenes.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames)]
+pub enum TimelineAction {
+    /// Rewinds and enables an animation with the given name in an animation player with the given
+    /// name.
+    PlayAnimation {
+        /// Name of an animation player node.
+        animation_player: String,
+        /// Name of an animation in the animation player.
+        animation: String,
+    },
+    /// Makes a camera with the given name the only enabled camera in the scene.
+    CameraCut {
+        /// Name of a camera node.
+        camera: String,
+    },
+    /// Starts playing a sound node with the given name.
+    PlaySound {
+        /// Name of a sound node.
+        sound: String,
+    },
+    /// Emits a named event, that could be handled by game code. See
+    /// [`crate::scene::timeline::TimelinePlayer::pop_script_event`] for more info.
+    ScriptEvent {
+        /// Name of the event.
+        name: String,
+    },
+}
+
+impl Default for TimelineAction {
+    fn default() -> Self {
+        Self::ScriptEvent {
+            name: Default::default(),
+        }
+    }
+}
+
+/// An action at a particular time of a timeline.
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct TimelineEvent {
+    /// Time (in seconds) at which the action will be performed.
+    pub time: f32,
+    /// An action to perform.
+    pub action: TimelineAction,
+}
+
+/// A track that animates a property of a scene node with the given name.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct PropertyTrack {
+    /// Name of a scene node, whose property will be animated.
+    pub node: String,
+    /// A property of the scene node that will be animated.
+    pub binding: ValueBinding,
+    /// Actual values of the property.
+    pub frames: TrackDataContainer,
+}
+
+impl Default for PropertyTrack {
+    fn default() -> Self {
+        Self {
+            node: Default::default(),
+            binding: ValueBinding::Position,
+            frames: Default::default(),
+        }
+    }
+}
+
+/// Timeline is a resource that sequences animations, camera cuts, property tracks, sound cues and
+/// script events. Timelines are played by [`crate::scene::timeline::TimelinePlayer`] scene nodes.
+///
+/// Discrete actions (animations, camera cuts, sounds and script events) are stored as a list of
+/// [`TimelineEvent`]s, while continuous changes are stored as a list of [`PropertyTrack`]s.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox::resource::timeline::{Timeline, TimelineAction, TimelineEvent};
+/// fn intro_timeline() -> Timeline {
+///     Timeline {
+///         duration: 5.0,
+///         events: vec![
+///             TimelineEvent {
+///                 time: 0.0,
+///                 action: TimelineAction::CameraCut {
+///                     camera: "IntroCamera".to_string(),
+///                 },
+///             },
+///             TimelineEvent {
+///                 time: 0.5,
+///                 action: TimelineAction::PlaySound {
+///                     sound: "Thunder".to_string(),
+///                 },
+///             },
+///             TimelineEvent {
+///                 time: 5.0,
+///                 action: TimelineAction::ScriptEvent {
+///                     name: "IntroFinished".to_string(),
+///                 },
+///             },
+///         ],
+///         property_tracks: Default::default(),
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct Timeline {
+    /// Total duration of the timeline in seconds.
+    pub duration: f32,
+    /// Discrete actions of the timeline. The events does not need to be sorted.
+    pub events: Vec<TimelineEvent>,
+    /// Property tracks of the timeline.
+    pub property_tracks: Vec<PropertyTrack>,
+}
+
+impl TypeUuidProvider for Timeline {
+    fn type_uuid() -> Uuid {
+        uuid!("5b0c3d7e-4c5a-4f6e-9f2d-8a1e6b7c9d30")
+    }
+}
+
+impl ResourceData for Timeline {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn type_uuid(&self) -> Uuid {
+        <Self as TypeUuidProvider>::type_uuid()
+    }
+
+    fn save(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut visitor = Visitor::new();
+        self.visit("Timeline", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
+    }
+
+    fn can_be_saved(&self) -> bool {
+        true
+    }
+}
+
+impl Timeline {
+    /// Loads a timeline from the given file.
+    pub async fn from_file(path: &Path, io: &dyn ResourceIo) -> Result<Self, VisitError> {
+        let bytes = io.load_file(path).await?;
+        let mut visitor = Visitor::load_from_memory(&bytes)?;
+        let mut timeline = Timeline::default();
+        timeline.visit("Timeline", &mut visitor)?;
+        Ok(timeline)
+    }
+
+    /// Returns an iterator over the events in `(from..=to]` time range.
+    pub fn events_in_range(&self, from: f32, to: f32) -> impl Iterator<Item = &TimelineEvent> {
+        self.events
+            .iter()
+            .filter(move |event| event.time > from && event.time <= to)
+    }
+}
+
+/// Type alias for timeline resources.
+pub type TimelineResource = Resource<Timeline>;
@@ -43,6 +43,12 @@ pub type BlendSpace = crate::generic_animation::machine::node::blendspace::Blend
 /// Scene specific animation blending state machine blend space point.
 pub type BlendSpacePoint =
     crate::generic_animation::machine::node::blendspace::BlendSpacePoint<Handle<Node>>;
+/// Scene specific animation blending state machine BlendSpace1D node.
+pub type BlendSpace1D =
+    crate::generic_animation::machine::node::blendspace1d::BlendSpace1D<Handle<Node>>;
+/// Scene specific animation blending state machine 1D blend space point.
+pub type BlendSpace1DPoint =
+    crate::generic_animation::machine::node::blendspace1d::BlendSpace1DPoint<Handle<Node>>;
 /// Scene specific animation blending state machine layer mask.
 pub type LayerMask = crate::generic_animation::machine::mask::LayerMask<Handle<Node>>;
 /// Scene specific animation blending state machine layer mask.
@@ -77,10 +83,10 @@ pub type NotNode = crate::generic_animation::machine::transition::NotNode<Handle
 pub mod prelude {
     pub use super::{
         AndNode, AnimationBlendingStateMachine, AnimationBlendingStateMachineBuilder, BasePoseNode,
-        BlendAnimations, BlendAnimationsByIndex, BlendPose, BlendSpace, BlendSpacePoint, Event,
-        IndexedBlendInput, LayerMask, LogicNode, Machine, MachineLayer, NotNode, OrNode,
-        PlayAnimation, PoseNode, RootMotionSettings, State, StateAction, StateActionWrapper,
-        Transition, XorNode,
+        BlendAnimations, BlendAnimationsByIndex, BlendPose, BlendSpace, BlendSpace1D,
+        BlendSpace1DPoint, BlendSpacePoint, Event, IndexedBlendInput, LayerMask, LogicNode,
+        Machine, MachineLayer, NotNode, OrNode, PlayAnimation, PoseNode, RootMotionSettings, State,
+        StateAction, StateActionWrapper, Transition, XorNode,
     };
     pub use crate::generic_animation::machine::{
         node::AnimationEventCollectionStrategy,
@@ -3,6 +3,7 @@
 
 use crate::{
     core::{
+        algebra::Vector3,
         log::{Log, MessageKind},
         math::aabb::AxisAlignedBoundingBox,
         pool::Handle,
@@ -12,16 +13,22 @@ use crate::{
         visitor::prelude::*,
         TypeUuidProvider,
     },
-    generic_animation::value::{BoundValueCollection, TrackValue, ValueBinding},
+    generic_animation::{
+        signal::AnimationEvent,
+        value::{BoundValueCollection, TrackValue, ValueBinding},
+        RootMotion,
+    },
     scene::{
         base::{Base, BaseBuilder},
         graph::{Graph, NodePool},
         node::{Node, NodeTrait, UpdateContext},
+        rigidbody::RigidBody,
     },
 };
 use std::ops::{Deref, DerefMut};
 
 pub mod absm;
+pub mod retarget;
 pub mod spritesheet;
 
 /// Scene specific animation.
@@ -34,19 +41,22 @@ pub type AnimationContainer = crate::generic_animation::AnimationContainer<Handl
 pub type AnimationPose = crate::generic_animation::AnimationPose<Handle<Node>>;
 /// Scene specific animation node pose.
 pub type NodePose = crate::generic_animation::NodePose<Handle<Node>>;
+/// Scene specific animation layer.
+pub type AnimationLayer = crate::generic_animation::layer::AnimationLayer<Handle<Node>>;
 
 /// Standard prelude for animations, that contains all most commonly used types and traits.
 pub mod prelude {
     pub use super::{
-        Animation, AnimationContainer, AnimationContainerExt, AnimationPlayer,
+        Animation, AnimationContainer, AnimationContainerExt, AnimationLayer, AnimationPlayer,
         AnimationPlayerBuilder, AnimationPose, AnimationPoseExt, BoundValueCollectionExt, NodePose,
-        Track,
+        RootMotionExt, Track,
     };
     pub use crate::generic_animation::{
         container::{TrackDataContainer, TrackValueKind},
+        layer::LayerBlendMode,
         signal::AnimationSignal,
         value::{BoundValueCollection, TrackValue, ValueBinding, ValueType},
-        AnimationEvent,
+        AnimationEvent, RootMotion,
     };
 }
 
@@ -188,6 +198,58 @@ impl BoundValueCollectionExt for BoundValueCollection {
     }
 }
 
+/// Extension trait for [`RootMotion`].
+pub trait RootMotionExt {
+    /// Drives the given rigid body using the root motion. Linear velocity of the body is calculated
+    /// from the motion transformed by the global transform of the `model` (usually it is the root
+    /// node of an animated character). Vertical velocity of the body is preserved, if the motion
+    /// does not have vertical part, so the gravity will still affect the body. Rotational part of
+    /// the motion is added to the local rotation of the body. `dt` must be the same time step that
+    /// was used to update the animation.
+    fn apply_to_rigid_body(
+        &self,
+        model: Handle<Node>,
+        rigid_body: Handle<Node>,
+        graph: &mut Graph,
+        dt: f32,
+    );
+}
+
+impl RootMotionExt for RootMotion {
+    fn apply_to_rigid_body(
+        &self,
+        model: Handle<Node>,
+        rigid_body: Handle<Node>,
+        graph: &mut Graph,
+        dt: f32,
+    ) {
+        let Some(model_transform) = graph.try_get(model).map(|model| model.global_transform())
+        else {
+            return;
+        };
+
+        let velocity = self.velocity(&model_transform, dt);
+
+        if let Some(rigid_body) = graph.try_get_mut_of_type::<RigidBody>(rigid_body) {
+            let lin_vel = rigid_body.lin_vel();
+            rigid_body.set_lin_vel(Vector3::new(
+                velocity.x,
+                if velocity.y == 0.0 {
+                    lin_vel.y
+                } else {
+                    velocity.y
+                },
+                velocity.z,
+            ));
+
+            let rotation = **rigid_body.local_transform().rotation();
+            rigid_body
+                .local_transform_mut()
+                .set_rotation(rotation * self.delta_rotation);
+        }
+    }
+}
+
 /// Animation player is a node that contains multiple animations. It updates and plays all the animations.
 /// The node could be a source of animations for animation blending state machines. To learn more about
 /// animations, see [`Animation`] docs.
@@ -251,11 +313,52 @@ impl BoundValueCollectionExt for BoundValueCollection {
 /// The example creates a bounce animation first - it is a simple animation that animates position of a given node
 /// (`animated_node`). Only then it creates an animation player node with an animation container with a single animation.
 /// To understand why this is so complicated, see the docs of [`Animation`].
+///
+/// # Layers
+///
+/// By default, every enabled animation of the player is applied to the graph one after another. Animation layers allow
+/// you to play animations on top of each other in a controlled way - for example upper body aiming over lower body
+/// locomotion. Every layer plays a single animation, has a weight, a mask of nodes that must not be animated by the
+/// layer and a blend mode (see [`prelude::LayerBlendMode`]). Animations, that are not used by layers, form a base pose and the
+/// layers are combined on top of it in order.
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     scene::{
+///         animation::{
+///             absm::{LayerMask, LayerMaskExt},
+///             prelude::*,
+///         },
+///         graph::Graph,
+///         node::Node,
+///     },
+/// };
+///
+/// fn add_aiming_layer(
+///     animation_player: Handle<Node>,
+///     aim_animation: Handle<Animation>,
+///     lower_body: Handle<Node>,
+///     graph: &mut Graph,
+/// ) {
+///     let mask = LayerMask::from_hierarchy(graph, lower_body);
+///     graph[animation_player]
+///         .query_component_mut::<AnimationPlayer>()
+///         .unwrap()
+///         .layers_mut()
+///         .push(AnimationLayer::new("Aim", aim_animation).with_mask(mask));
+/// }
+/// ```
 #[derive(Visit, Reflect, Clone, Debug)]
 pub struct AnimationPlayer {
     base: Base,
     animations: InheritableVariable<AnimationContainer>,
     auto_apply: bool,
+    #[visit(optional)]
+    layers: InheritableVariable<Vec<AnimationLayer>>,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    frame_events: Vec<(Handle<Animation>, AnimationEvent)>,
 }
 
 impl Default for AnimationPlayer {
@@ -264,6 +367,8 @@ impl Default for AnimationPlayer {
             base: Default::default(),
             animations: Default::default(),
             auto_apply: true,
+            layers: Default::default(),
+            frame_events: Default::default(),
         }
     }
 }
@@ -298,6 +403,75 @@ impl AnimationPlayer {
     pub fn set_animations(&mut self, animations: AnimationContainer) {
         self.animations.set_value_and_mark_modified(animations);
     }
+
+    /// Returns a reference to the list of animation layers. See "Layers" section of [`AnimationPlayer`] docs for more
+    /// info.
+    pub fn layers(&self) -> &[AnimationLayer] {
+        &self.layers
+    }
+
+    /// Returns a reference to the list of animation layers. See "Layers" section of [`AnimationPlayer`] docs for more
+    /// info.
+    pub fn layers_mut(&mut self) -> &mut Vec<AnimationLayer> {
+        self.layers.get_value_mut_and_mark_modified()
+    }
+
+    /// Sets new list of animation layers. See "Layers" section of [`AnimationPlayer`] docs for more info.
+    pub fn set_layers(&mut self, layers: Vec<AnimationLayer>) -> Vec<AnimationLayer> {
+        self.layers.set_value_and_mark_modified(layers)
+    }
+
+    fn update_layered_animations(&mut self, nodes: &mut NodePool, dt: f32) {
+        let animations = self.animations.get_value_mut_silent();
+        animations.update_animations(nodes, false, dt);
+
+        if !self.auto_apply {
+            return;
+        }
+
+        let is_layered = |handle: Handle<Animation>| {
+            self.layers
+                .iter()
+                .any(|layer| layer.animation == handle || layer.additive_reference == handle)
+        };
+
+        let mut pose = AnimationPose::default();
+        for (handle, animation) in animations.pair_iter() {
+            if animation.is_enabled() && !is_layered(handle) {
+                pose.blend_with(animation.pose(), 1.0);
+            }
+        }
+
+        for layer in self.layers.iter() {
+            layer.blend_into(animations, &mut pose);
+        }
+
+        pose.apply_internal(nodes);
+    }
+
+    /// Returns animation events (see [`crate::generic_animation::signal::AnimationSignal`]), that were emitted by every animation of
+    /// the player during the last update. The events are collected on every frame, there's no need
+    /// to consume them. It is the easiest way to synchronize sounds, effects or physics impulses
+    /// (footsteps, throws, hit frames, etc.) with animations.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use fyrox::scene::{animation::AnimationPlayer, graph::Graph, node::Node};
+    /// # use fyrox::core::pool::Handle;
+    /// fn handle_footsteps(animation_player: Handle<Node>, graph: &Graph) {
+    ///     if let Some(animation_player) = graph.try_get_of_type::<AnimationPlayer>(animation_player) {
+    ///         for (_, event) in animation_player.frame_events() {
+    ///             if event.name == "Footstep" {
+    ///                 // Play a footstep sound here.
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn frame_events(&self) -> &[(Handle<Animation>, AnimationEvent)] {
+        &self.frame_events
+    }
 }
 
 impl TypeUuidProvider for AnimationPlayer {
@@ -336,11 +510,27 @@ impl NodeTrait for AnimationPlayer {
     }
 
     fn update(&mut self, context: &mut UpdateContext) {
-        self.animations.get_value_mut_silent().update_animations(
-            context.nodes,
-            self.auto_apply,
-            context.dt,
-        );
+        if self.layers.is_empty() {
+            self.animations.get_value_mut_silent().update_animations(
+                context.nodes,
+                self.auto_apply,
+                context.dt,
+            );
+        } else {
+            self.update_layered_animations(context.nodes, context.dt);
+        }
+
+        self.frame_events.clear();
+        for (handle, animation) in self.animations.pair_iter() {
+            if animation.is_enabled() {
+                self.frame_events.extend(
+                    animation
+                        .tick_events()
+                        .iter()
+                        .map(|event| (handle, event.clone())),
+                );
+            }
+        }
     }
 }
 
@@ -349,6 +539,7 @@ pub struct AnimationPlayerBuilder {
     base_builder: BaseBuilder,
     animations: AnimationContainer,
     auto_apply: bool,
+    layers: Vec<AnimationLayer>,
 }
 
 impl AnimationPlayerBuilder {
@@ -358,6 +549,7 @@ impl AnimationPlayerBuilder {
             base_builder,
             animations: AnimationContainer::new(),
             auto_apply: true,
+            layers: Default::default(),
         }
     }
 
@@ -373,12 +565,20 @@ impl AnimationPlayerBuilder {
         self
     }
 
+    /// Sets desired animation layers. See "Layers" section of [`AnimationPlayer`] docs for more info.
+    pub fn with_layers(mut self, layers: Vec<AnimationLayer>) -> Self {
+        self.layers = layers;
+        self
+    }
+
     /// Creates an instance of [`AnimationPlayer`] node.
     pub fn build_node(self) -> Node {
         Node::new(AnimationPlayer {
             base: self.base_builder.build_base(),
             animations: self.animations.into(),
             auto_apply: self.auto_apply,
+            layers: self.layers.into(),
+            frame_events: Default::default(),
         })
     }
 
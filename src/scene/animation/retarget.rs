@@ -0,0 +1,417 @@
+//! Animation retargeting allows you to reuse animations, that were made for one skeleton, on other
+//! skeletons with different bone names and proportions. See [`retarget_animation`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        curve::{Curve, CurveKey, CurveKeyKind},
+        log::Log,
+        pool::Handle,
+    },
+    generic_animation::{
+        container::{TrackDataContainer, TrackValueKind},
+        value::{TrackValue, ValueBinding},
+    },
+    scene::{
+        animation::{Animation, Track},
+        graph::Graph,
+        node::Node,
+    },
+};
+use fxhash::FxHashMap;
+use std::f32::consts::{PI, TAU};
+
+/// Maps names of bones of a source skeleton to names of bones of a target skeleton.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoneMap {
+    map: FxHashMap<String, String>,
+}
+
+impl BoneMap {
+    /// Adds a new pair of names to the map and returns the map. See [`Self::add`] for more info.
+    pub fn with_pair<S: Into<String>, D: Into<String>>(mut self, source: S, target: D) -> Self {
+        self.add(source, target);
+        self
+    }
+
+    /// Adds a new pair of names to the map. Tracks of a source bone with the `source` name will be
+    /// retargeted to a target bone with the `target` name.
+    pub fn add<S: Into<String>, D: Into<String>>(&mut self, source: S, target: D) {
+        self.map.insert(source.into(), target.into());
+    }
+
+    /// Removes a pair of names, that starts from the given source name.
+    pub fn remove(&mut self, source: &str) -> Option<String> {
+        self.map.remove(source)
+    }
+
+    /// Returns a name of a target bone, that corresponds to the given source bone name. Bones that
+    /// are not present in the map are mapped by their own names.
+    pub fn target_name<'a>(&'a self, source: &'a str) -> &'a str {
+        self.map.get(source).map(|s| s.as_str()).unwrap_or(source)
+    }
+
+    /// Returns `true` if the map has no pairs.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+/// Defines how animations will be retargeted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetargetSettings {
+    /// Mapping between bone names of the source and the target skeletons.
+    pub bone_map: BoneMap,
+    /// If set, transform tracks are adjusted using the difference between rest poses of source and
+    /// target bones. It should be enabled when the skeletons have different orientation of bones or
+    /// different proportions. If not set, tracks are just assigned to the target bones.
+    pub compensate_rest_pose: bool,
+    /// If set, translational motion of bones is scaled by the ratio between the lengths of the target
+    /// and the source bones. It makes motion (for example, motion of hips) of short characters look
+    /// correct when animations were made for tall ones. Has effect only if
+    /// [`Self::compensate_rest_pose`] is set.
+    pub scale_translations: bool,
+    /// Amount of samples per second, that is used to bake compensated tracks. Keys of source curves are
+    /// always preserved, extra samples are needed to keep the rotations correct between the keys.
+    pub sample_rate: f32,
+}
+
+impl Default for RetargetSettings {
+    fn default() -> Self {
+        Self {
+            bone_map: Default::default(),
+            compensate_rest_pose: true,
+            scale_translations: true,
+            sample_rate: 30.0,
+        }
+    }
+}
+
+struct RestPose {
+    position: Vector3<f32>,
+    rotation: UnitQuaternion<f32>,
+    scale: Vector3<f32>,
+}
+
+impl RestPose {
+    fn from_node(node: &Node) -> Self {
+        let transform = node.local_transform();
+        Self {
+            position: **transform.position(),
+            rotation: **transform.rotation(),
+            scale: **transform.scale(),
+        }
+    }
+}
+
+fn sample_times(container: &TrackDataContainer, sample_rate: f32) -> Vec<f32> {
+    let mut times = container
+        .curves_ref()
+        .iter()
+        .flat_map(|curve| curve.keys().iter().map(|key| key.location()))
+        .collect::<Vec<_>>();
+
+    let (Some(start), Some(end)) = (
+        times.iter().cloned().reduce(f32::min),
+        times.iter().cloned().reduce(f32::max),
+    ) else {
+        return times;
+    };
+
+    if sample_rate > 0.0 {
+        let step = 1.0 / sample_rate;
+        let mut time = start + step;
+        while time < end {
+            times.push(time);
+            time += step;
+        }
+    }
+
+    times.sort_by(|a, b| a.total_cmp(b));
+    times.dedup_by(|a, b| (*a - *b).abs() <= f32::EPSILON);
+    times
+}
+
+fn bake_container(
+    kind: TrackValueKind,
+    times: &[f32],
+    mut sampler: impl FnMut(f32) -> Option<Vec<f32>>,
+) -> TrackDataContainer {
+    let mut curves = vec![Vec::new(); kind.components_count()];
+    for &time in times {
+        if let Some(components) = sampler(time) {
+            for (keys, value) in curves.iter_mut().zip(components) {
+                keys.push(CurveKey::new(time, value, CurveKeyKind::Linear));
+            }
+        }
+    }
+
+    let mut container = TrackDataContainer::new(kind);
+    for (curve, keys) in container.curves_mut().iter_mut().zip(curves) {
+        *curve = Curve::from(keys);
+    }
+    container
+}
+
+/// Makes the angle as close as possible to the previous one by adding full turns, so the linear
+/// interpolation between the angles won't take the longest way.
+fn unwrap_angle(angle: f32, prev: f32) -> f32 {
+    let mut angle = angle;
+    while angle - prev > PI {
+        angle -= TAU;
+    }
+    while angle - prev < -PI {
+        angle += TAU;
+    }
+    angle
+}
+
+fn safe_div(a: f32, b: f32) -> f32 {
+    if b.abs() > f32::EPSILON {
+        a / b
+    } else {
+        a
+    }
+}
+
+fn compensate_track(
+    track: &mut Track,
+    source_rest: &RestPose,
+    target_rest: &RestPose,
+    settings: &RetargetSettings,
+) {
+    let container = track.data_container().clone();
+    let times = sample_times(&container, settings.sample_rate);
+
+    let baked = match track.binding() {
+        ValueBinding::Position => {
+            let ratio = if settings.scale_translations {
+                let source_length = source_rest.position.norm();
+                if source_length > f32::EPSILON {
+                    target_rest.position.norm() / source_length
+                } else {
+                    1.0
+                }
+            } else {
+                1.0
+            };
+
+            bake_container(TrackValueKind::Vector3, &times, |time| {
+                if let Some(TrackValue::Vector3(position)) = container.fetch(time) {
+                    let position =
+                        target_rest.position + (position - source_rest.position).scale(ratio);
+                    Some(vec![position.x, position.y, position.z])
+                } else {
+                    None
+                }
+            })
+        }
+        ValueBinding::Rotation => {
+            let correction = target_rest.rotation * source_rest.rotation.inverse();
+            let mut prev: Option<[f32; 3]> = None;
+            bake_container(TrackValueKind::UnitQuaternion, &times, |time| {
+                if let Some(TrackValue::UnitQuaternion(rotation)) = container.fetch(time) {
+                    // Rotation order of euler angles of rotation tracks is XYZ, which corresponds
+                    // to roll, pitch and yaw.
+                    let (x, y, z) = (correction * rotation).euler_angles();
+                    let angles = match prev {
+                        Some([px, py, pz]) => [
+                            unwrap_angle(x, px),
+                            unwrap_angle(y, py),
+                            unwrap_angle(z, pz),
+                        ],
+                        None => [x, y, z],
+                    };
+                    prev = Some(angles);
+                    Some(angles.to_vec())
+                } else {
+                    None
+                }
+            })
+        }
+        ValueBinding::Scale => bake_container(TrackValueKind::Vector3, &times, |time| {
+            if let Some(TrackValue::Vector3(scale)) = container.fetch(time) {
+                Some(vec![
+                    target_rest.scale.x * safe_div(scale.x, source_rest.scale.x),
+                    target_rest.scale.y * safe_div(scale.y, source_rest.scale.y),
+                    target_rest.scale.z * safe_div(scale.z, source_rest.scale.z),
+                ])
+            } else {
+                None
+            }
+        }),
+        // Arbitrary properties cannot be compensated.
+        ValueBinding::Property { .. } => return,
+    };
+
+    track.set_data_container(baked);
+}
+
+/// Retargets the given animation, that was made for a skeleton in the `source_graph`, to a skeleton
+/// in the hierarchy of `target_root` node in the `target_graph`. Source bones are mapped to target
+/// bones by names using the bone map from the settings.
+///
+/// When rest pose compensation is enabled (see [`RetargetSettings::compensate_rest_pose`]), local
+/// transforms of source and target bones are considered as rest poses. Rotations of the animation
+/// are applied relative to the rest pose of the target bones and translations are scaled by the
+/// proportions of the skeletons, so a single animation pack can be used for characters with
+/// different proportions and bone orientations. Keep in mind, that the bones of the target skeleton
+/// must be in their rest pose at the moment of retargeting (for example, right after instantiation
+/// of a character).
+///
+/// Tracks, that cannot be mapped to a target bone, will have unassigned targets.
+pub fn retarget_animation(
+    animation: &Animation,
+    source_graph: &Graph,
+    target_root: Handle<Node>,
+    target_graph: &Graph,
+    settings: &RetargetSettings,
+) -> Animation {
+    let mut retargeted = animation.clone();
+
+    for track in retargeted.tracks_mut() {
+        let Some(source_node) = source_graph.try_get(track.target()) else {
+            track.set_target(Default::default());
+            continue;
+        };
+
+        let target_name = settings.bone_map.target_name(source_node.name());
+
+        match target_graph.find_by_name(target_root, target_name) {
+            Some((target_handle, target_node)) => {
+                if settings.compensate_rest_pose {
+                    compensate_track(
+                        track,
+                        &RestPose::from_node(source_node),
+                        &RestPose::from_node(target_node),
+                        settings,
+                    );
+                }
+
+                track.set_target(target_handle);
+            }
+            None => {
+                track.set_target(Default::default());
+                Log::err(format!(
+                    "Failed to retarget animation {} for node {} (mapped to {})",
+                    animation.name(),
+                    source_node.name(),
+                    target_name
+                ));
+            }
+        }
+    }
+
+    if let Some(settings_ref) = retargeted.root_motion_settings_mut() {
+        settings_ref.node = source_graph
+            .try_get(settings_ref.node)
+            .and_then(|node| {
+                target_graph.find_by_name(target_root, settings.bone_map.target_name(node.name()))
+            })
+            .map(|(handle, _)| handle)
+            .unwrap_or_default();
+    }
+
+    retargeted
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            curve::{Curve, CurveKey, CurveKeyKind},
+        },
+        generic_animation::value::TrackValue,
+        scene::{
+            animation::{
+                retarget::{retarget_animation, BoneMap, RetargetSettings},
+                Animation, Track,
+            },
+            base::BaseBuilder,
+            graph::Graph,
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_retarget_with_rest_pose_compensation() {
+        let mut source_graph = Graph::new();
+        let source_bone = PivotBuilder::new(
+            BaseBuilder::new().with_name("Hips").with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 2.0, 0.0))
+                    .build(),
+            ),
+        )
+        .build(&mut source_graph);
+
+        let mut target_graph = Graph::new();
+        let target_rotation = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 1.0);
+        let target_bone = PivotBuilder::new(
+            BaseBuilder::new().with_name("pelvis").with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 1.0, 0.0))
+                    .with_local_rotation(target_rotation)
+                    .build(),
+            ),
+        )
+        .build(&mut target_graph);
+
+        let mut position_track = Track::new_position().with_target(source_bone);
+        let container = position_track.data_container_mut();
+        container.curves_mut()[0] = Curve::from(vec![
+            CurveKey::new(0.0, 0.0, CurveKeyKind::Linear),
+            CurveKey::new(1.0, 0.0, CurveKeyKind::Linear),
+        ]);
+        container.curves_mut()[1] = Curve::from(vec![
+            CurveKey::new(0.0, 2.0, CurveKeyKind::Linear),
+            CurveKey::new(1.0, 3.0, CurveKeyKind::Linear),
+        ]);
+        container.curves_mut()[2] = Curve::from(vec![
+            CurveKey::new(0.0, 0.0, CurveKeyKind::Linear),
+            CurveKey::new(1.0, 0.0, CurveKeyKind::Linear),
+        ]);
+
+        let mut rotation_track = Track::new_rotation().with_target(source_bone);
+        for curve in rotation_track.data_container_mut().curves_mut() {
+            *curve = Curve::from(vec![CurveKey::new(0.0, 0.0, CurveKeyKind::Linear)]);
+        }
+
+        let mut animation = Animation::default();
+        animation.add_track(position_track);
+        animation.add_track(rotation_track);
+
+        let retargeted = retarget_animation(
+            &animation,
+            &source_graph,
+            target_graph.get_root(),
+            &target_graph,
+            &RetargetSettings {
+                bone_map: BoneMap::default().with_pair("Hips", "pelvis"),
+                ..Default::default()
+            },
+        );
+
+        let tracks = retargeted.tracks();
+        assert!(tracks.iter().all(|track| track.target() == target_bone));
+
+        // Rest pose maps to rest pose, motion is scaled by the proportions of the skeletons.
+        let Some(TrackValue::Vector3(start)) = tracks[0].data_container().fetch(0.0) else {
+            unreachable!()
+        };
+        let Some(TrackValue::Vector3(end)) = tracks[0].data_container().fetch(1.0) else {
+            unreachable!()
+        };
+        assert!((start - Vector3::new(0.0, 1.0, 0.0)).norm() < 1.0e-5);
+        assert!((end - Vector3::new(0.0, 1.5, 0.0)).norm() < 1.0e-5);
+
+        // Identity rotation of the source rest pose maps to the rotation of the target rest pose.
+        let Some(TrackValue::UnitQuaternion(rotation)) = tracks[1].data_container().fetch(0.0)
+        else {
+            unreachable!()
+        };
+        assert!(rotation.angle_to(&target_rotation) < 1.0e-4);
+    }
+}
@@ -14,30 +14,36 @@ use crate::{
         visitor::{Visit, VisitError, VisitResult, Visitor},
     },
     engine::SerializationContext,
+    renderer::batch::ObserverInfo,
     resource::model::ModelResource,
-    scene::{node::Node, transform::Transform},
+    scene::{collider::BitMask, graph::Graph, node::Node, transform::Transform},
     script::{Script, ScriptTrait},
 };
+use fxhash::FxHashMap;
 use fyrox_core::uuid_provider;
-use std::{any::Any, cell::Cell, sync::mpsc::Sender};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    fmt::{Debug, Formatter},
+    sync::mpsc::Sender,
+};
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
-/// Level of detail is a collection of objects for given normalized distance range.
-/// Objects will be rendered **only** if they're in specified range.
-/// Normalized distance is a distance in (0; 1) range where 0 - closest to camera,
-/// 1 - farthest. Real distance can be obtained by multiplying normalized distance
-/// with z_far of current projection matrix.
+/// Level of detail is a collection of objects for given range of the metric of a [`LodGroup`].
+/// Objects will be rendered **only** if the value of the metric is in specified range. By
+/// default, the metric is a normalized distance, which is a distance in (0; 1) range where 0 -
+/// closest to camera, 1 - farthest. See [`LodMetric`] for other metrics.
 #[derive(Debug, Default, Clone, Visit, Reflect, PartialEq)]
 pub struct LevelOfDetail {
     #[reflect(
         description = "Beginning of the range in which the level will be visible. \
-    It is expressed in normalized coordinates: where 0.0 - closest to camera, 1.0 - \
-    farthest from camera."
+    It is expressed in the units of the metric of the LOD group, for example in normalized \
+    coordinates: where 0.0 - closest to camera, 1.0 - farthest from camera."
     )]
     begin: f32,
     #[reflect(description = "End of the range in which the level will be visible. \
-    It is expressed in normalized coordinates: where 0.0 - closest to camera, 1.0 - \
-    farthest from camera.")]
+    It is expressed in the units of the metric of the LOD group, for example in normalized \
+    coordinates: where 0.0 - closest to camera, 1.0 - farthest from camera.")]
     end: f32,
     /// List of objects, where each object represents level of detail of parent's
     /// LOD group.
@@ -56,16 +62,15 @@ impl LevelOfDetail {
         let begin = begin.min(end);
         let end = end.max(begin);
         Self {
-            begin: begin.clamp(0.0, 1.0),
-            end: end.clamp(0.0, 1.0),
+            begin: begin.max(0.0),
+            end: end.max(0.0),
             objects,
         }
     }
 
-    /// Sets new starting point in distance range. Input value will be clamped in
-    /// (0; 1) range.
-    pub fn set_begin(&mut self, percent: f32) {
-        self.begin = percent.clamp(0.0, 1.0);
+    /// Sets new starting point in the range. Negative values are clamped to zero.
+    pub fn set_begin(&mut self, begin: f32) {
+        self.begin = begin.max(0.0);
         if self.begin > self.end {
             std::mem::swap(&mut self.begin, &mut self.end);
         }
@@ -76,10 +81,9 @@ impl LevelOfDetail {
         self.begin
     }
 
-    /// Sets new end point in distance range. Input value will be clamped in
-    /// (0; 1) range.
-    pub fn set_end(&mut self, percent: f32) {
-        self.end = percent.clamp(0.0, 1.0);
+    /// Sets new end point in the range. Negative values are clamped to zero.
+    pub fn set_end(&mut self, end: f32) {
+        self.end = end.max(0.0);
         if self.end < self.begin {
             std::mem::swap(&mut self.begin, &mut self.end);
         }
@@ -89,26 +93,209 @@ impl LevelOfDetail {
     pub fn end(&self) -> f32 {
         self.end
     }
+
+    fn contains(&self, value: f32, margin: f32) -> bool {
+        value >= self.begin - margin && value <= self.end + margin
+    }
+}
+
+/// A metric, that is used to select a level of detail of a [`LodGroup`]. Ranges of the levels are
+/// expressed in the units of the metric.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames,
+)]
+pub enum LodMetric {
+    /// Distance from the camera to the center of the bounds of the group, normalized using the
+    /// clipping planes of the camera: 0.0 - closest to camera, 1.0 - farthest.
+    #[default]
+    NormalizedDistance,
+    /// Height of the bounding sphere of the group on screen, expressed as a fraction of the
+    /// viewport height: 1.0 - the group occupies the whole viewport (or more). The most detailed
+    /// level should have the range that ends at 1.0.
+    ScreenSize,
+    /// Distance (in world units) from the camera to the center of the bounds of the group.
+    Distance,
 }
 
+uuid_provider!(LodMetric = "1c5c7a4b-7d4e-4a9f-9d39-03b4a3a3c2d1");
+
 /// LOD (Level-Of-Detail) group is a set of cascades (levels), where each cascade takes specific
-/// distance range. Each cascade contains list of objects that should or shouldn't be rendered
-/// if distance satisfy cascade range. LOD may significantly improve performance if your scene
-/// contains lots of high poly objects and objects may be far away from camera. Distant objects
-/// in this case will be rendered with lower details freeing precious GPU resources for other
-/// useful tasks.
+/// range of a metric (normalized distance, distance or screen size, see [`LodMetric`]). Each
+/// cascade contains list of objects that should or shouldn't be rendered if the metric satisfies
+/// cascade range. LOD may significantly improve performance if your scene contains lots of high
+/// poly objects and objects may be far away from camera. Distant objects in this case will be
+/// rendered with lower details freeing precious GPU resources for other useful tasks.
 ///
 /// Lod group must contain non-overlapping cascades, each cascade with its own set of objects
 /// that belongs to level of detail. Engine does not care if you create overlapping cascades,
-/// it is your responsibility to create non-overlapping cascades.
-#[derive(Debug, Default, Clone, Visit, Reflect, PartialEq)]
+/// it is your responsibility to create non-overlapping cascades. Only one cascade is rendered
+/// at a time, if the ranges overlap, the first suitable cascade is used.
+///
+/// ## Hysteresis
+///
+/// When the metric is close to a boundary of a range, small movements of a camera may cause
+/// levels to switch back and forth every frame, which looks like "flickering". To prevent this,
+/// current level is kept until the metric leaves its range extended by a margin, which is defined
+/// by [`Self::hysteresis`]. Every camera has its own current level, so hysteresis works correctly
+/// when the group is visible to multiple cameras at once. Shadow casters use the levels, selected
+/// by the last rendered camera, so shadows always match visible geometry.
+#[derive(Debug, Default, Clone, Visit, Reflect)]
 pub struct LodGroup {
     /// Set of cascades.
     pub levels: Vec<LevelOfDetail>,
+    /// A metric, that is used to select a cascade.
+    #[visit(optional)]
+    pub metric: LodMetric,
+    /// A fraction of the length of the range of current cascade, that the metric must additionally
+    /// cross to switch to another cascade. The value is clamped to `[0; 1]` range, 0.0 disables
+    /// hysteresis.
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01)]
+    pub hysteresis: f32,
+    /// Indices of current cascades for each camera, `None` means that the group is culled.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    camera_levels: RefCell<FxHashMap<Handle<Node>, Option<usize>>>,
+    /// A cascade, that was selected by the last rendered camera.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    last_level: Cell<Option<Option<usize>>>,
 }
 
 uuid_provider!(LodGroup = "8e7b18b1-c1e0-47d7-b952-4394c1d049e5");
 
+impl PartialEq for LodGroup {
+    fn eq(&self, other: &Self) -> bool {
+        self.levels == other.levels
+            && self.metric == other.metric
+            && self.hysteresis == other.hysteresis
+    }
+}
+
+impl LodGroup {
+    /// Creates new LOD group with the given cascades, metric and hysteresis.
+    pub fn new(levels: Vec<LevelOfDetail>, metric: LodMetric, hysteresis: f32) -> Self {
+        Self {
+            levels,
+            metric,
+            hysteresis,
+            ..Default::default()
+        }
+    }
+
+    fn world_bounds(&self, graph: &Graph, position: Vector3<f32>) -> AxisAlignedBoundingBox {
+        let mut bounds = AxisAlignedBoundingBox::default();
+        for object in self.levels.iter().flat_map(|level| level.objects.iter()) {
+            if let Some(object) = graph.try_get(*object) {
+                bounds.add_box(object.world_bounding_box());
+            }
+        }
+        if bounds.is_valid() {
+            bounds
+        } else {
+            AxisAlignedBoundingBox::from_point(position)
+        }
+    }
+
+    /// Calculates the value of the metric of the group for the given observer. `position` is the
+    /// global position of the node, that owns the group, it is used when the group has no objects.
+    pub fn metric_value(
+        &self,
+        graph: &Graph,
+        position: Vector3<f32>,
+        observer_info: &ObserverInfo,
+    ) -> f32 {
+        let bounds = self.world_bounds(graph, position);
+        let distance = bounds
+            .center()
+            .metric_distance(&observer_info.observer_position);
+        match self.metric {
+            LodMetric::NormalizedDistance => {
+                let z_range = observer_info.z_far - observer_info.z_near;
+                (distance - observer_info.z_near) / z_range
+            }
+            LodMetric::ScreenSize => {
+                let radius = bounds.half_extents().norm();
+                let projection_matrix = &observer_info.projection_matrix;
+                // Element [1; 1] is `1 / tan(fov / 2)` for perspective projection and
+                // `1 / vertical_size` for orthographic projection.
+                let scale = projection_matrix[(1, 1)];
+                // Perspective projection has zero at [3; 3].
+                let size = if projection_matrix[(3, 3)] == 0.0 {
+                    radius * scale / distance.max(f32::EPSILON)
+                } else {
+                    radius * scale
+                };
+                size.min(1.0)
+            }
+            LodMetric::Distance => distance,
+        }
+    }
+
+    fn select_level_index(&self, value: f32, current: Option<usize>) -> Option<usize> {
+        if let Some(level) = current.and_then(|current| self.levels.get(current)) {
+            let margin = (level.end - level.begin) * self.hysteresis.clamp(0.0, 1.0);
+            if level.contains(value, margin) {
+                return current;
+            }
+        }
+
+        self.levels
+            .iter()
+            .position(|level| level.contains(value, 0.0))
+    }
+
+    /// Selects a cascade for the given observer, taking hysteresis into account. `None` means that
+    /// none of the cascades must be rendered. `position` is the global position of the node, that
+    /// owns the group. Observers without a camera (see [`ObserverInfo::lod_camera`]) use the cascade,
+    /// that was selected by the last rendered camera.
+    pub fn select_level(
+        &self,
+        graph: &Graph,
+        position: Vector3<f32>,
+        observer_info: &ObserverInfo,
+    ) -> Option<usize> {
+        let camera = observer_info.lod_camera;
+        if camera.is_none() {
+            if let Some(last_level) = self.last_level.get() {
+                return last_level.filter(|index| *index < self.levels.len());
+            }
+        }
+
+        let value = self.metric_value(graph, position, observer_info);
+        let mut camera_levels = self.camera_levels.borrow_mut();
+        // Forget the cascades of removed cameras.
+        camera_levels.retain(|camera, _| graph.is_valid_handle(*camera));
+        let current = camera_levels.get(&camera).cloned().flatten();
+        let index = self.select_level_index(value, current);
+        if camera.is_some() {
+            camera_levels.insert(camera, index);
+            self.last_level.set(Some(index));
+        }
+        index
+    }
+
+    /// Returns index of the cascade, that was selected by the given camera. `None` means that the
+    /// group was culled or that it was not rendered by the camera yet.
+    pub fn camera_level(&self, camera: Handle<Node>) -> Option<usize> {
+        self.camera_levels
+            .borrow()
+            .get(&camera)
+            .cloned()
+            .flatten()
+            .filter(|index| *index < self.levels.len())
+    }
+
+    /// Returns index of the cascade, that was selected by the last rendered camera. `None` means
+    /// that the group was culled or that it was not rendered yet.
+    pub fn current_level(&self) -> Option<usize> {
+        self.last_level
+            .get()
+            .flatten()
+            .filter(|index| *index < self.levels.len())
+    }
+}
+
 /// Mobility defines a group for scene node which has direct impact on performance
 /// and capabilities of nodes.
 #[derive(
@@ -226,6 +413,8 @@ pub enum PropertyValue {
     F32(f32),
     /// A 64-bit floating point value.
     F64(f64),
+    /// A boolean value.
+    Bool(bool),
 }
 
 uuid_provider!(PropertyValue = "cce94b60-a57e-48ba-b6f4-e5e84788f7f8");
@@ -236,6 +425,91 @@ impl Default for PropertyValue {
     }
 }
 
+impl PropertyValue {
+    /// Returns the value as a boolean, if the property is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        if let Self::Bool(value) = self {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value as a string slice, if the property is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        if let Self::String(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value as a node handle, if the property is a node handle.
+    pub fn as_node_handle(&self) -> Option<Handle<Node>> {
+        if let Self::NodeHandle(value) = self {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value as a 64-bit signed integer, if the property is an integer that fits into
+    /// `i64`. Floating point values are not converted.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Self::I64(value) => Some(value),
+            Self::U64(value) => i64::try_from(value).ok(),
+            Self::I32(value) => Some(value as i64),
+            Self::U32(value) => Some(value as i64),
+            Self::I16(value) => Some(value as i64),
+            Self::U16(value) => Some(value as i64),
+            Self::I8(value) => Some(value as i64),
+            Self::U8(value) => Some(value as i64),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a 64-bit floating point number, if the property is a number of any
+    /// kind.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::F32(value) => Some(value as f64),
+            Self::F64(value) => Some(value),
+            Self::U64(value) => Some(value as f64),
+            _ => self.as_i64().map(|value| value as f64),
+        }
+    }
+}
+
+macro_rules! impl_property_value_from {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl From<$ty> for PropertyValue {
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_property_value_from!(
+    Handle<Node> => NodeHandle,
+    ErasedHandle => Handle,
+    String => String,
+    i64 => I64,
+    u64 => U64,
+    i32 => I32,
+    u32 => U32,
+    i16 => I16,
+    u16 => U16,
+    i8 => I8,
+    u8 => U8,
+    f32 => F32,
+    f64 => F64,
+    bool => Bool
+);
+
 /// A custom property.
 #[derive(Debug, Visit, Reflect, Default, Clone, PartialEq)]
 pub struct Property {
@@ -279,6 +553,37 @@ impl Visit for InstanceId {
     }
 }
 
+/// A message that is sent to every subscriber of a node when global transform of the node has changed.
+/// See [`Base::subscribe_transform_changes`] for more info.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransformChanged {
+    /// A handle of the node which global transform has changed.
+    pub node: Handle<Node>,
+    /// New global transform of the node.
+    pub global_transform: Matrix4<f32>,
+}
+
+/// A set of subscribers of transform changes of a node. Subscriptions belong to a particular node
+/// instance, so they're not cloned together with the node.
+#[derive(Default)]
+pub(crate) struct TransformSubscribers(RefCell<Vec<Sender<TransformChanged>>>);
+
+impl Clone for TransformSubscribers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Debug for TransformSubscribers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TransformSubscribers has {} senders.",
+            self.0.borrow().len()
+        )
+    }
+}
+
 /// Base scene graph node is a simplest possible node, it is used to build more complex ones using composition.
 /// It contains all fundamental properties for each scene graph nodes, like local and global transforms, name,
 /// lifetime, etc. Base node is a building block for all complex node hierarchies - it contains list of children
@@ -347,6 +652,20 @@ pub struct Base {
     #[reflect(setter = "set_frustum_culling")]
     frustum_culling: InheritableVariable<bool>,
 
+    #[reflect(
+        description = "A set of render layers the node belongs to. The node is rendered only by \
+    cameras, which render mask has at least one common bit with the render layers of the node."
+    )]
+    #[reflect(setter = "set_render_layers")]
+    render_layers: InheritableVariable<BitMask>,
+
+    #[reflect(
+        description = "Whether the state of the node should be remembered when its scene is unloaded. \
+    Destroyed persistent nodes won't be respawned when the scene is loaded again."
+    )]
+    #[reflect(setter = "set_persistent")]
+    persistent: InheritableVariable<bool>,
+
     #[reflect(hidden)]
     pub(crate) transform_modified: Cell<bool>,
 
@@ -400,6 +719,14 @@ pub struct Base {
 
     #[reflect(hidden)]
     pub(crate) global_enabled: Cell<bool>,
+
+    #[reflect(hidden)]
+    pub(crate) transform_subscribers: TransformSubscribers,
+
+    // `true` if the node is a folder. Folders are checked for every node on every hierarchy update,
+    // so the flag is stored here to avoid type casts. Set by the folder itself, non-serializable.
+    #[reflect(hidden)]
+    pub(crate) is_folder: bool,
 }
 
 impl Drop for Base {
@@ -465,6 +792,55 @@ impl Base {
         self.properties.iter().find(|p| p.name == name)
     }
 
+    /// Returns a value of a first property with the given name. It is the main way of reading
+    /// custom metadata from scripts:
+    ///
+    /// ```rust
+    /// # use fyrox::scene::base::Base;
+    /// fn loot_tier(base: &Base) -> i64 {
+    ///     base.property("LootTier")
+    ///         .and_then(|value| value.as_i64())
+    ///         .unwrap_or(0)
+    /// }
+    /// ```
+    #[inline]
+    pub fn property(&self, name: &str) -> Option<&PropertyValue> {
+        self.find_first_property_ref(name).map(|p| &p.value)
+    }
+
+    /// Sets a value of a first property with the given name, or adds a new property if there's no
+    /// such property. Returns previous value of the property (if any).
+    #[inline]
+    pub fn set_property<V: Into<PropertyValue>>(
+        &mut self,
+        name: &str,
+        value: V,
+    ) -> Option<PropertyValue> {
+        let value = value.into();
+        let properties = self.properties.get_value_mut_and_mark_modified();
+        if let Some(property) = properties.iter_mut().find(|p| p.name == name) {
+            Some(std::mem::replace(&mut property.value, value))
+        } else {
+            properties.push(Property {
+                name: name.to_string(),
+                value,
+            });
+            None
+        }
+    }
+
+    /// Removes a first property with the given name and returns its value (if any).
+    #[inline]
+    pub fn remove_property(&mut self, name: &str) -> Option<PropertyValue> {
+        let position = self.properties.iter().position(|p| p.name == name)?;
+        Some(
+            self.properties
+                .get_value_mut_and_mark_modified()
+                .remove(position)
+                .value,
+        )
+    }
+
     /// Sets a new set of properties of the node.
     #[inline]
     pub fn set_properties(&mut self, properties: Vec<Property>) -> Vec<Property> {
@@ -697,6 +1073,22 @@ impl Base {
             .set_value_and_mark_modified(frustum_culling)
     }
 
+    /// Returns a set of render layers the node belongs to.
+    #[inline]
+    pub fn render_layers(&self) -> BitMask {
+        *self.render_layers
+    }
+
+    /// Sets a set of render layers the node belongs to. The node is rendered only by cameras, which
+    /// render mask has at least one common bit with the render layers of the node (see
+    /// [`crate::scene::camera::Camera::set_render_mask`]). By default, the node belongs to every
+    /// layer.
+    #[inline]
+    pub fn set_render_layers(&mut self, render_layers: BitMask) -> BitMask {
+        self.render_layers
+            .set_value_and_mark_modified(render_layers)
+    }
+
     /// Returns true if the node should cast shadows, false - otherwise.
     #[inline]
     pub fn cast_shadows(&self) -> bool {
@@ -709,6 +1101,20 @@ impl Base {
         self.cast_shadows.set_value_and_mark_modified(cast_shadows)
     }
 
+    /// Sets whether the state of the node should be remembered when its scene is unloaded, so
+    /// destroyed or modified nodes (for example collected pickups) stay the same when the scene is
+    /// loaded again. See [`crate::scene::persistence::PersistentNodeStore`] for more info.
+    #[inline]
+    pub fn set_persistent(&mut self, persistent: bool) -> bool {
+        self.persistent.set_value_and_mark_modified(persistent)
+    }
+
+    /// Returns `true` if the state of the node should be remembered when its scene is unloaded.
+    #[inline]
+    pub fn is_persistent(&self) -> bool {
+        *self.persistent
+    }
+
     /// Sets instance id of the node. See [`InstanceId`] for more info.
     ///
     /// ## Important notes
@@ -725,6 +1131,55 @@ impl Base {
         self.instance_id
     }
 
+    /// Adds new subscriber, that will receive [`TransformChanged`] message every time when global
+    /// transform of the node changes (for example when the node or any of its ancestors is moved, or
+    /// when the node is moved by physics). It allows to react to movement of the node without polling
+    /// its transform every frame. The subscriber is removed automatically when its receiver is dropped.
+    /// Subscriptions are not serialized and not copied together with the node.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::sync::mpsc::channel;
+    /// # use fyrox::core::{algebra::Vector3, math::Matrix4Ext};
+    /// # use fyrox::scene::base::BaseBuilder;
+    /// # use fyrox::scene::graph::Graph;
+    /// # use fyrox::scene::pivot::PivotBuilder;
+    /// let mut graph = Graph::new();
+    /// let handle = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+    ///
+    /// let (tx, rx) = channel();
+    /// graph[handle].subscribe_transform_changes(tx);
+    ///
+    /// graph[handle]
+    ///     .local_transform_mut()
+    ///     .set_position(Vector3::new(1.0, 2.0, 3.0));
+    /// graph.update_hierarchical_data();
+    ///
+    /// let event = rx.try_recv().unwrap();
+    /// assert_eq!(event.node, handle);
+    /// assert_eq!(event.global_transform.position(), Vector3::new(1.0, 2.0, 3.0));
+    /// ```
+    pub fn subscribe_transform_changes(&mut self, sender: Sender<TransformChanged>) {
+        self.transform_subscribers.0.get_mut().push(sender);
+    }
+
+    /// Returns `true` if there's at least one subscriber of transform changes of the node.
+    pub fn has_transform_subscribers(&self) -> bool {
+        !self.transform_subscribers.0.borrow().is_empty()
+    }
+
+    pub(crate) fn notify_transform_changed(&self, global_transform: &Matrix4<f32>) {
+        let event = TransformChanged {
+            node: self.self_handle,
+            global_transform: *global_transform,
+        };
+        self.transform_subscribers
+            .0
+            .borrow_mut()
+            .retain(|sender| sender.send(event.clone()).is_ok());
+    }
+
     fn remove_script(&mut self) {
         // Send script to the graph to destroy script instances correctly.
         if let Some(script) = self.script.take() {
@@ -962,6 +1417,8 @@ impl Visit for Base {
         let _ = self.cast_shadows.visit("CastShadows", &mut region);
         let _ = self.instance_id.visit("InstanceId", &mut region);
         let _ = self.enabled.visit("Enabled", &mut region);
+        let _ = self.render_layers.visit("RenderLayers", &mut region);
+        let _ = self.persistent.visit("Persistent", &mut region);
 
         // Script visiting may fail for various reasons:
         //
@@ -1000,6 +1457,9 @@ pub struct BaseBuilder {
     script: Option<Script>,
     instance_id: InstanceId,
     enabled: bool,
+    render_layers: BitMask,
+    persistent: bool,
+    properties: Vec<Property>,
 }
 
 impl Default for BaseBuilder {
@@ -1028,6 +1488,9 @@ impl BaseBuilder {
             script: None,
             instance_id: InstanceId(Uuid::new_v4()),
             enabled: true,
+            render_layers: BitMask(u32::MAX),
+            persistent: false,
+            properties: Default::default(),
         }
     }
 
@@ -1114,6 +1577,23 @@ impl BaseBuilder {
         self
     }
 
+    /// Sets desired set of custom properties.
+    #[inline]
+    pub fn with_properties(mut self, properties: Vec<Property>) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Adds a custom property with the given name and value.
+    #[inline]
+    pub fn with_property<V: Into<PropertyValue>>(mut self, name: &str, value: V) -> Self {
+        self.properties.push(Property {
+            name: name.to_string(),
+            value: value.into(),
+        });
+        self
+    }
+
     /// Sets desired frustum_culling flag.
     #[inline]
     pub fn with_frustum_culling(mut self, frustum_culling: bool) -> Self {
@@ -1128,6 +1608,21 @@ impl BaseBuilder {
         self
     }
 
+    /// Sets whether the state of the node should be remembered when its scene is unloaded. See
+    /// [`Base::set_persistent`] for more info.
+    #[inline]
+    pub fn with_persistent(mut self, persistent: bool) -> Self {
+        self.persistent = persistent;
+        self
+    }
+
+    /// Sets desired render layers of the node. See [`Base::set_render_layers`] for more info.
+    #[inline]
+    pub fn with_render_layers(mut self, render_layers: BitMask) -> Self {
+        self.render_layers = render_layers;
+        self
+    }
+
     /// Sets desired script of the node.
     #[inline]
     pub fn with_script(mut self, script: Script) -> Self {
@@ -1163,14 +1658,126 @@ impl BaseBuilder {
             lod_group: self.lod_group.into(),
             mobility: self.mobility.into(),
             tag: self.tag.into(),
-            properties: Default::default(),
+            properties: self.properties.into(),
             transform_modified: Cell::new(false),
             frustum_culling: self.frustum_culling.into(),
+            render_layers: self.render_layers.into(),
+            persistent: self.persistent.into(),
             cast_shadows: self.cast_shadows.into(),
             script: self.script,
             instance_id: InstanceId(Uuid::new_v4()),
             enabled: self.enabled.into(),
             global_enabled: Cell::new(true),
+            transform_subscribers: Default::default(),
+            is_folder: false,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Vector3},
+            pool::Handle,
+        },
+        renderer::batch::ObserverInfo,
+        scene::{
+            base::{BaseBuilder, LevelOfDetail, LodGroup, LodMetric},
+            collider::BitMask,
+            graph::Graph,
+            node::Node,
+            pivot::PivotBuilder,
+        },
+    };
+
+    fn make_group(metric: LodMetric, ranges: &[(f32, f32)]) -> LodGroup {
+        LodGroup::new(
+            ranges
+                .iter()
+                .map(|(begin, end)| LevelOfDetail::new(*begin, *end, vec![]))
+                .collect(),
+            metric,
+            0.1,
+        )
+    }
+
+    fn observer(distance: f32, camera: Handle<Node>) -> ObserverInfo {
+        ObserverInfo {
+            observer_position: Vector3::new(0.0, 0.0, distance),
+            z_near: 0.0,
+            z_far: 100.0,
+            view_matrix: Matrix4::identity(),
+            projection_matrix: Matrix4::identity(),
+            render_mask: BitMask(u32::MAX),
+            lod_camera: camera,
+        }
+    }
+
+    #[test]
+    fn test_level_selection() {
+        let group = make_group(LodMetric::Distance, &[(0.0, 10.0), (10.0, 30.0)]);
+        assert_eq!(group.select_level_index(5.0, None), Some(0));
+        assert_eq!(group.select_level_index(20.0, None), Some(1));
+        assert_eq!(group.select_level_index(40.0, None), None);
+        // Hysteresis keeps current level near the boundaries of its range.
+        assert_eq!(group.select_level_index(10.5, Some(0)), Some(0));
+        assert_eq!(group.select_level_index(11.5, Some(0)), Some(1));
+        assert_eq!(group.select_level_index(9.0, Some(1)), Some(1));
+        assert_eq!(group.select_level_index(7.0, Some(1)), Some(0));
+        assert_eq!(group.select_level_index(31.0, Some(1)), Some(1));
+
+        let group = make_group(LodMetric::ScreenSize, &[(0.5, 1.0), (0.2, 0.5)]);
+        assert_eq!(group.select_level_index(1.0, None), Some(0));
+        assert_eq!(group.select_level_index(0.3, None), Some(1));
+        assert_eq!(group.select_level_index(0.1, None), None);
+        assert_eq!(group.select_level_index(0.48, Some(0)), Some(0));
+    }
+
+    #[test]
+    fn test_metric_value() {
+        let graph = Graph::new();
+        let observer = observer(50.0, Handle::NONE);
+        let mut group = make_group(LodMetric::NormalizedDistance, &[]);
+        assert_eq!(
+            group.metric_value(&graph, Vector3::default(), &observer),
+            0.5
+        );
+        group.metric = LodMetric::Distance;
+        assert_eq!(
+            group.metric_value(&graph, Vector3::default(), &observer),
+            50.0
+        );
+    }
+
+    #[test]
+    fn test_levels_are_tracked_per_camera() {
+        let mut graph = Graph::new();
+        let near_camera = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let far_camera = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let group = make_group(LodMetric::Distance, &[(0.0, 10.0), (10.0, 30.0)]);
+
+        let select = |graph: &Graph, distance: f32, camera| {
+            group.select_level(graph, Vector3::default(), &observer(distance, camera))
+        };
+
+        assert_eq!(select(&graph, 9.0, near_camera), Some(0));
+        assert_eq!(select(&graph, 20.0, far_camera), Some(1));
+        // Hysteresis of each camera must not be affected by the other camera.
+        assert_eq!(select(&graph, 10.5, near_camera), Some(0));
+        assert_eq!(select(&graph, 9.5, far_camera), Some(1));
+        assert_eq!(group.camera_level(near_camera), Some(0));
+        assert_eq!(group.camera_level(far_camera), Some(1));
+        assert_eq!(group.current_level(), Some(1));
+
+        // Observers without a camera use the level of the last rendered camera.
+        assert_eq!(select(&graph, 50.0, Handle::NONE), Some(1));
+        assert_eq!(group.camera_level(near_camera), Some(0));
+
+        // Levels of removed cameras are forgotten.
+        graph.remove_node(near_camera);
+        assert_eq!(select(&graph, 20.0, far_camera), Some(1));
+        assert_eq!(group.camera_levels.borrow().len(), 1);
+        assert_eq!(group.camera_level(near_camera), None);
+    }
+}
@@ -21,10 +21,12 @@ use crate::{
     },
     scene::{
         base::{Base, BaseBuilder},
+        collider::BitMask,
         debug::SceneDrawingContext,
         graph::Graph,
         node::{Node, NodeTrait, UpdateContext},
     },
+    utils::ibl,
 };
 use fyrox_core::uuid_provider;
 use fyrox_resource::state::LoadError;
@@ -106,6 +108,13 @@ pub struct OrthographicProjection {
     /// some minimal value to prevent singularities from occuring.
     #[reflect(step = 0.1)]
     pub vertical_size: f32,
+    /// Optional amount of screen pixels per one world unit. If set, [`Self::vertical_size`] is ignored
+    /// and the size of the "view box" is calculated from the size of the viewport, so one world unit
+    /// always occupies exactly the given amount of pixels (pixel-perfect rendering). Camera position is
+    /// also snapped to the pixel grid to prevent "shimmering" of pixel art when the camera moves.
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 1.0)]
+    pub pixels_per_unit: Option<f32>,
 }
 
 impl Default for OrthographicProjection {
@@ -114,11 +123,34 @@ impl Default for OrthographicProjection {
             z_near: 0.0,
             z_far: 2048.0,
             vertical_size: 5.0,
+            pixels_per_unit: None,
         }
     }
 }
 
 impl OrthographicProjection {
+    /// Creates new pixel-perfect orthographic projection, where one world unit occupies exactly
+    /// `pixels_per_unit` pixels on screen. See [`Self::pixels_per_unit`] for more info.
+    #[inline]
+    pub fn pixel_perfect(pixels_per_unit: f32) -> Self {
+        Self {
+            pixels_per_unit: Some(pixels_per_unit),
+            ..Default::default()
+        }
+    }
+
+    /// Returns actual vertical size of the "view box" for the given frame (viewport) size in pixels.
+    /// It is either [`Self::vertical_size`] or a size calculated from [`Self::pixels_per_unit`].
+    #[inline]
+    pub fn effective_vertical_size(&self, frame_size: Vector2<f32>) -> f32 {
+        match self.pixels_per_unit {
+            Some(pixels_per_unit) if pixels_per_unit > 0.0 => {
+                frame_size.y / (2.0 * pixels_per_unit)
+            }
+            _ => self.vertical_size,
+        }
+    }
+
     /// Returns orthographic projection matrix.
     #[inline]
     pub fn matrix(&self, frame_size: Vector2<f32>) -> Matrix4<f32> {
@@ -137,7 +169,7 @@ impl OrthographicProjection {
         let aspect = (frame_size.x / frame_size.y).max(limit);
 
         // Prevent collapsing projection "box" into a point, which could cause panic.
-        let vertical_size = clamp_to_limit_signed(self.vertical_size, limit);
+        let vertical_size = clamp_to_limit_signed(self.effective_vertical_size(frame_size), limit);
         let horizontal_size = clamp_to_limit_signed(aspect * vertical_size, limit);
 
         let z_near = self.z_far.min(self.z_near);
@@ -285,6 +317,32 @@ impl Default for Exposure {
     }
 }
 
+/// Defines which frame of a camera will be written to its render target.
+#[derive(
+    Visit,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Debug,
+    Default,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum RenderTargetFormat {
+    /// Low dynamic range frame (`RGBA8`) after tone mapping and post effects. It is suitable for
+    /// mirrors, monitors, minimaps, etc.
+    #[default]
+    Ldr,
+    /// High dynamic range frame (`RGBA16F`) before tone mapping. It could be used if the frame
+    /// needs to be processed further, for example by a custom shader.
+    Hdr,
+}
+
+uuid_provider!(RenderTargetFormat = "c8ef2a43-76b0-4f2c-a1d6-3e5b9b7f1a08");
+
 /// Camera allows you to see world from specific point in world. You must have at least one camera in
 /// your scene to see anything.
 ///
@@ -302,7 +360,50 @@ impl Default for Exposure {
 /// ## Multiple cameras
 ///
 /// Fyrox supports multiple cameras per scene, it means that you can create split screen games, make
-/// picture-in-picture insertions in your main camera view and any other combinations you need.
+/// picture-in-picture insertions in your main camera view and any other combinations you need. Each
+/// camera renders to its own viewport (see [`Camera::set_viewport`] and [`Camera::split_screen_viewport`]).
+///
+/// ## Render mask
+///
+/// Every camera has a render mask, that defines which nodes will be rendered by the camera. A node is
+/// rendered only if its render layers (see [`Base::set_render_layers`]) have at least one common bit with
+/// the render mask of the camera. It could be used, for example, to show a first-person weapon only for
+/// a specific camera:
+///
+/// ```rust
+/// # use fyrox::scene::{camera::Camera, collider::BitMask, node::Node};
+/// const WORLD_LAYER: u32 = 1 << 0;
+/// const WEAPON_LAYER: u32 = 1 << 1;
+///
+/// fn setup_layers(main_camera: &mut Camera, spectator_camera: &mut Camera, weapon: &mut Node) {
+///     weapon.set_render_layers(BitMask(WEAPON_LAYER));
+///     main_camera.set_render_mask(BitMask(WORLD_LAYER | WEAPON_LAYER));
+///     spectator_camera.set_render_mask(BitMask(WORLD_LAYER));
+/// }
+/// ```
+///
+/// ## Render to texture
+///
+/// A camera could render into a texture instead of the screen (see [`Camera::set_render_target`]).
+/// The texture could then be sampled by any material or shown in the UI, which allows you to
+/// create mirrors, security monitors, portals, minimaps, etc. Resolution of the frame is defined by
+/// the size of the texture, format of the frame is defined by [`RenderTargetFormat`]. Such cameras
+/// could be updated less frequently than every frame to save some performance (see
+/// [`Camera::set_render_target_update_interval`]).
+///
+/// ```rust
+/// # use fyrox::{
+/// #     resource::texture::{TextureResource, TextureResourceExtension},
+/// #     scene::camera::Camera,
+/// # };
+/// fn make_security_camera(camera: &mut Camera) -> TextureResource {
+///     let monitor_texture = TextureResource::new_render_target(256, 256);
+///     camera.set_render_target(Some(monitor_texture.clone()));
+///     // Update the monitor ten times per second.
+///     camera.set_render_target_update_interval(0.1);
+///     monitor_texture
+/// }
+/// ```
 ///
 /// ## Performance
 ///
@@ -336,6 +437,38 @@ pub struct Camera {
     #[reflect(setter = "set_color_grading_enabled")]
     color_grading_enabled: InheritableVariable<bool>,
 
+    #[visit(optional)]
+    #[reflect(setter = "set_render_mask")]
+    render_mask: InheritableVariable<BitMask>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_render_target")]
+    render_target: InheritableVariable<Option<TextureResource>>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_render_target_format")]
+    render_target_format: InheritableVariable<RenderTargetFormat>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_render_target_update_interval",
+        min_value = 0.0,
+        step = 0.01
+    )]
+    render_target_update_interval: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_ambient_occlusion_radius", min_value = 0.0, step = 0.05)]
+    ambient_occlusion_radius: InheritableVariable<Option<f32>>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_ambient_occlusion_intensity",
+        min_value = 0.0,
+        step = 0.05
+    )]
+    ambient_occlusion_intensity: InheritableVariable<f32>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     view_matrix: Matrix4<f32>,
@@ -343,6 +476,22 @@ pub struct Camera {
     #[visit(skip)]
     #[reflect(hidden)]
     projection_matrix: Matrix4<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    projection_jitter: Vector2<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    projection_jitter_ndc: Vector2<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    unjittered_projection_matrix: Matrix4<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    sub_frustum: Option<Rect<f32>>,
 }
 
 impl Deref for Camera {
@@ -398,12 +547,106 @@ impl Camera {
     /// this method, it will be called automatically when new frame starts.
     #[inline]
     pub fn calculate_matrices(&mut self, frame_size: Vector2<f32>) {
-        let pos = self.base.global_position();
+        let mut pos = self.base.global_position();
         let look = self.base.look_vector();
         let up = self.base.up_vector();
 
+        let viewport = self.viewport_pixels(frame_size);
+        let viewport_size = Vector2::new(viewport.w() as f32, viewport.h() as f32);
+
+        if let Projection::Orthographic(OrthographicProjection {
+            pixels_per_unit: Some(pixels_per_unit),
+            ..
+        }) = *self.projection
+        {
+            if pixels_per_unit > 0.0 {
+                // Snap the camera to the pixel grid on its view plane.
+                for axis in [self.base.side_vector(), up] {
+                    if let Some(axis) = axis.try_normalize(f32::EPSILON) {
+                        let projection = pos.dot(&axis);
+                        let snapped = (projection * pixels_per_unit).round() / pixels_per_unit;
+                        pos += axis.scale(snapped - projection);
+                    }
+                }
+            }
+        }
+
         self.view_matrix = Matrix4::look_at_rh(&Point3::from(pos), &Point3::from(pos + look), &up);
-        self.projection_matrix = self.projection.matrix(frame_size);
+        self.projection_matrix = self.projection.matrix(viewport_size);
+        self.unjittered_projection_matrix = self.projection_matrix;
+        self.projection_jitter_ndc = Vector2::new(
+            2.0 * self.projection_jitter.x / viewport.w() as f32,
+            2.0 * self.projection_jitter.y / viewport.h() as f32,
+        );
+
+        if self.projection_jitter != Vector2::default() {
+            let offset = Vector3::new(
+                self.projection_jitter_ndc.x,
+                self.projection_jitter_ndc.y,
+                0.0,
+            );
+            self.projection_matrix = Matrix4::new_translation(&offset) * self.projection_matrix;
+        }
+
+        if let Some(sub_frustum) = self.sub_frustum {
+            // Stretch the region of normalized device coordinates to the whole [-1; 1] range.
+            let center = Vector3::new(
+                2.0 * sub_frustum.x() - 1.0 + sub_frustum.w(),
+                2.0 * sub_frustum.y() - 1.0 + sub_frustum.h(),
+                0.0,
+            );
+            let scale = Vector3::new(
+                1.0 / sub_frustum.w().max(f32::EPSILON),
+                1.0 / sub_frustum.h().max(f32::EPSILON),
+                1.0,
+            );
+            let sub_frustum_matrix =
+                Matrix4::new_nonuniform_scaling(&scale) * Matrix4::new_translation(&-center);
+            self.projection_matrix = sub_frustum_matrix * self.projection_matrix;
+            self.unjittered_projection_matrix =
+                sub_frustum_matrix * self.unjittered_projection_matrix;
+            self.projection_jitter_ndc = self.projection_jitter_ndc.component_mul(&scale.xy());
+        }
+    }
+
+    /// Restricts the projection of the camera to a region of its view, defined by a normalized
+    /// rectangle (`[0; 0]` - bottom-left corner of the view, `[1; 1]` - top-right corner). The
+    /// region is stretched to the whole viewport, it is used to render a frame in multiple tiles
+    /// (see [`crate::renderer::Renderer::render_tiled_screenshot`]). `None` disables the restriction.
+    /// The change will take effect on the next [`Self::calculate_matrices`] call.
+    pub fn set_sub_frustum(&mut self, sub_frustum: Option<Rect<f32>>) -> Option<Rect<f32>> {
+        std::mem::replace(&mut self.sub_frustum, sub_frustum)
+    }
+
+    /// Returns current region of the view, that is stretched to the whole viewport. See
+    /// [`Self::set_sub_frustum`] for more info.
+    pub fn sub_frustum(&self) -> Option<Rect<f32>> {
+        self.sub_frustum
+    }
+
+    /// Sets sub-pixel offset (in pixels) of the projection matrix. The offset is used by temporal
+    /// anti-aliasing to get slightly different samples of the scene each frame. The renderer sets
+    /// the offset automatically when temporal anti-aliasing is enabled, so normally you should
+    /// not call this method.
+    pub fn set_projection_jitter(&mut self, jitter: Vector2<f32>) -> Vector2<f32> {
+        std::mem::replace(&mut self.projection_jitter, jitter)
+    }
+
+    /// Returns current sub-pixel offset (in pixels) of the projection matrix.
+    pub fn projection_jitter(&self) -> Vector2<f32> {
+        self.projection_jitter
+    }
+
+    /// Returns current sub-pixel offset of the projection matrix in normalized device coordinates.
+    /// The value is calculated in [`Self::calculate_matrices`].
+    pub fn projection_jitter_ndc(&self) -> Vector2<f32> {
+        self.projection_jitter_ndc
+    }
+
+    /// Returns view-projection matrix without sub-pixel offset (see [`Self::set_projection_jitter`]).
+    /// It is used to calculate motion vectors.
+    pub fn unjittered_view_projection_matrix(&self) -> Matrix4<f32> {
+        self.unjittered_projection_matrix * self.view_matrix
     }
 
     /// Sets new viewport in resolution-independent format. In other words
@@ -426,6 +669,76 @@ impl Camera {
         *self.viewport
     }
 
+    /// Returns a normalized viewport rectangle of a player with the given index for split-screen
+    /// games with `count` players. The screen is divided into a grid of nearly square cells, the
+    /// first player occupies top-left cell, next players fill the grid from left to right and from
+    /// top to bottom. For example, two players get left and right halves of the screen, three or
+    /// four players get a quarter of the screen each.
+    ///
+    /// ```rust
+    /// # use fyrox::{
+    /// #     core::pool::Handle,
+    /// #     scene::{camera::Camera, graph::Graph, node::Node},
+    /// # };
+    /// fn setup_split_screen(cameras: &[Handle<Node>], graph: &mut Graph) {
+    ///     for (index, camera) in cameras.iter().enumerate() {
+    ///         graph[*camera]
+    ///             .as_camera_mut()
+    ///             .set_viewport(Camera::split_screen_viewport(index, cameras.len()));
+    ///     }
+    /// }
+    /// ```
+    pub fn split_screen_viewport(index: usize, count: usize) -> Rect<f32> {
+        let count = count.max(1);
+        let columns = (count as f32).sqrt().ceil() as usize;
+        let rows = (count + columns - 1) / columns;
+        let index = index.min(count - 1);
+        let (column, row) = (index % columns, index / columns);
+        let width = 1.0 / columns as f32;
+        let height = 1.0 / rows as f32;
+        // Viewport origin is at the bottom-left corner of the frame.
+        Rect::new(
+            column as f32 * width,
+            1.0 - (row + 1) as f32 * height,
+            width,
+            height,
+        )
+    }
+
+    /// Sets new render mask of the camera. The camera renders only the nodes, which render layers have
+    /// at least one common bit with the render mask (see [`Base::set_render_layers`]). By default, the
+    /// camera renders every node.
+    pub fn set_render_mask(&mut self, render_mask: BitMask) -> BitMask {
+        self.render_mask.set_value_and_mark_modified(render_mask)
+    }
+
+    /// Returns current render mask of the camera.
+    pub fn render_mask(&self) -> BitMask {
+        *self.render_mask
+    }
+
+    /// Returns `true` if the camera should render the given node, according to its render mask.
+    pub fn is_in_render_mask(&self, node: &Base) -> bool {
+        (*self.render_mask & node.render_layers()).0 != 0
+    }
+
+    /// Calculates vertical size of orthographic projection, that makes one world unit to occupy exactly
+    /// `pixels_per_unit` pixels on screen for the given frame size. Returns `None` if the camera does not
+    /// use orthographic projection. See also [`OrthographicProjection::pixels_per_unit`], which does this
+    /// automatically.
+    pub fn pixel_perfect_vertical_size(
+        &self,
+        frame_size: Vector2<f32>,
+        pixels_per_unit: f32,
+    ) -> Option<f32> {
+        match *self.projection {
+            Projection::Orthographic(_) => {
+                Some(self.viewport_pixels(frame_size).h() as f32 / (2.0 * pixels_per_unit))
+            }
+            Projection::Perspective(_) => None,
+        }
+    }
+
     /// Calculates viewport rectangle in pixels based on internal resolution-independent
     /// viewport. It is useful when you need to get real viewport rectangle in pixels.
     ///
@@ -550,13 +863,16 @@ impl Camera {
         (*self.environment).clone()
     }
 
-    /// Creates picking ray from given screen coordinates.
+    /// Creates picking ray from given screen coordinates. Viewport of the camera is taken into account,
+    /// so the method works correctly for split-screen cameras.
     pub fn make_ray(&self, screen_coord: Vector2<f32>, screen_size: Vector2<f32>) -> Ray {
         let viewport = self.viewport_pixels(screen_size);
-        let nx = screen_coord.x / (viewport.w() as f32) * 2.0 - 1.0;
+        let nx = (screen_coord.x - viewport.x() as f32) / (viewport.w() as f32) * 2.0 - 1.0;
         // Invert y here because OpenGL has origin at left bottom corner,
         // but window coordinates starts from left *upper* corner.
-        let ny = (viewport.h() as f32 - screen_coord.y) / (viewport.h() as f32) * 2.0 - 1.0;
+        let ny = (screen_size.y - screen_coord.y - viewport.y() as f32) / (viewport.h() as f32)
+            * 2.0
+            - 1.0;
         let inv_view_proj = self
             .view_projection_matrix()
             .try_inverse()
@@ -642,8 +958,7 @@ impl Camera {
             let k = (1.0 / proj.w) * 0.5;
             Some(Vector2::new(
                 viewport.x() as f32 + viewport.w() as f32 * (proj.x * k + 0.5),
-                viewport.h() as f32
-                    - (viewport.y() as f32 + viewport.h() as f32 * (proj.y * k + 0.5)),
+                screen_size.y - (viewport.y() as f32 + viewport.h() as f32 * (proj.y * k + 0.5)),
             ))
         } else {
             None
@@ -688,6 +1003,72 @@ impl Camera {
     pub fn exposure(&self) -> Exposure {
         *self.exposure
     }
+
+    /// Sets a radius (in world units) of ambient occlusion for the camera. `None` means that the
+    /// radius from [`crate::renderer::QualitySettings::ssao_radius`] will be used.
+    pub fn set_ambient_occlusion_radius(&mut self, radius: Option<f32>) -> Option<f32> {
+        self.ambient_occlusion_radius
+            .set_value_and_mark_modified(radius.map(|r| r.abs()))
+    }
+
+    /// Returns a radius of ambient occlusion for the camera. See
+    /// [`Self::set_ambient_occlusion_radius`] for more info.
+    pub fn ambient_occlusion_radius(&self) -> Option<f32> {
+        *self.ambient_occlusion_radius
+    }
+
+    /// Sets intensity of ambient occlusion for the camera. `1.0` is physically plausible
+    /// occlusion, values greater than `1.0` make it darker, `0.0` - disables it.
+    pub fn set_ambient_occlusion_intensity(&mut self, intensity: f32) -> f32 {
+        self.ambient_occlusion_intensity
+            .set_value_and_mark_modified(intensity.max(0.0))
+    }
+
+    /// Returns intensity of ambient occlusion for the camera.
+    pub fn ambient_occlusion_intensity(&self) -> f32 {
+        *self.ambient_occlusion_intensity
+    }
+
+    /// Sets a texture into which the camera will render its frame, `None` means that the camera
+    /// renders into the frame of its scene (usually the screen). Only rectangle textures could be
+    /// used as render targets, use [`TextureResourceExtension::new_render_target`] to create one.
+    /// The viewport of the camera is relative to the size of the texture.
+    pub fn set_render_target(
+        &mut self,
+        render_target: Option<TextureResource>,
+    ) -> Option<TextureResource> {
+        self.render_target
+            .set_value_and_mark_modified(render_target)
+    }
+
+    /// Returns current render target of the camera.
+    pub fn render_target(&self) -> Option<&TextureResource> {
+        self.render_target.as_ref()
+    }
+
+    /// Sets a format of the frame that will be written to the render target of the camera. See
+    /// [`RenderTargetFormat`] docs for more info.
+    pub fn set_render_target_format(&mut self, format: RenderTargetFormat) -> RenderTargetFormat {
+        self.render_target_format
+            .set_value_and_mark_modified(format)
+    }
+
+    /// Returns current format of the frame of the render target.
+    pub fn render_target_format(&self) -> RenderTargetFormat {
+        *self.render_target_format
+    }
+
+    /// Sets a time interval (in seconds) between updates of the render target of the camera. Zero
+    /// means that the render target is updated every frame.
+    pub fn set_render_target_update_interval(&mut self, interval: f32) -> f32 {
+        self.render_target_update_interval
+            .set_value_and_mark_modified(interval.max(0.0))
+    }
+
+    /// Returns current time interval between updates of the render target of the camera.
+    pub fn render_target_update_interval(&self) -> f32 {
+        *self.render_target_update_interval
+    }
 }
 
 impl NodeTrait for Camera {
@@ -967,6 +1348,7 @@ impl SkyBoxKind {
             right: Some(right),
             top: Some(top),
             bottom: Some(bottom),
+            equirectangular: None,
         }
         .build()
         .unwrap()
@@ -1006,6 +1388,12 @@ pub struct CameraBuilder {
     color_grading_lut: Option<ColorGradingLut>,
     color_grading_enabled: bool,
     projection: Projection,
+    render_mask: BitMask,
+    render_target: Option<TextureResource>,
+    render_target_format: RenderTargetFormat,
+    render_target_update_interval: f32,
+    ambient_occlusion_radius: Option<f32>,
+    ambient_occlusion_intensity: f32,
 }
 
 impl CameraBuilder {
@@ -1024,6 +1412,12 @@ impl CameraBuilder {
             color_grading_lut: None,
             color_grading_enabled: false,
             projection: Projection::default(),
+            render_mask: BitMask(u32::MAX),
+            render_target: None,
+            render_target_format: Default::default(),
+            render_target_update_interval: 0.0,
+            ambient_occlusion_radius: None,
+            ambient_occlusion_intensity: 1.0,
         }
     }
 
@@ -1099,6 +1493,44 @@ impl CameraBuilder {
         self
     }
 
+    /// Sets desired render mask. See [`Camera::set_render_mask`] for more info.
+    pub fn with_render_mask(mut self, render_mask: BitMask) -> Self {
+        self.render_mask = render_mask;
+        self
+    }
+
+    /// Sets desired render target. See [`Camera::set_render_target`] for more info.
+    pub fn with_render_target(mut self, render_target: TextureResource) -> Self {
+        self.render_target = Some(render_target);
+        self
+    }
+
+    /// Sets desired format of the render target. See [`RenderTargetFormat`] for more info.
+    pub fn with_render_target_format(mut self, format: RenderTargetFormat) -> Self {
+        self.render_target_format = format;
+        self
+    }
+
+    /// Sets desired update interval of the render target. See
+    /// [`Camera::set_render_target_update_interval`] for more info.
+    pub fn with_render_target_update_interval(mut self, interval: f32) -> Self {
+        self.render_target_update_interval = interval;
+        self
+    }
+
+    /// Sets desired radius of ambient occlusion. See [`Camera::set_ambient_occlusion_radius`] for
+    /// more info.
+    pub fn with_ambient_occlusion_radius(mut self, radius: f32) -> Self {
+        self.ambient_occlusion_radius = Some(radius);
+        self
+    }
+
+    /// Sets desired intensity of ambient occlusion.
+    pub fn with_ambient_occlusion_intensity(mut self, intensity: f32) -> Self {
+        self.ambient_occlusion_intensity = intensity;
+        self
+    }
+
     /// Creates new instance of camera.
     pub fn build_camera(self) -> Camera {
         Camera {
@@ -1110,6 +1542,9 @@ impl CameraBuilder {
             // recalculated before rendering.
             view_matrix: Matrix4::identity(),
             projection_matrix: Matrix4::identity(),
+            projection_jitter: Default::default(),
+            projection_jitter_ndc: Default::default(),
+            unjittered_projection_matrix: Matrix4::identity(),
             sky_box: InheritableVariable::new_modified(match self.skybox {
                 SkyBoxKind::Builtin => Some(SkyBoxKind::built_in_skybox().clone()),
                 SkyBoxKind::None => None,
@@ -1119,6 +1554,13 @@ impl CameraBuilder {
             exposure: self.exposure.into(),
             color_grading_lut: self.color_grading_lut.into(),
             color_grading_enabled: self.color_grading_enabled.into(),
+            render_mask: self.render_mask.into(),
+            render_target: self.render_target.into(),
+            render_target_format: self.render_target_format.into(),
+            render_target_update_interval: self.render_target_update_interval.max(0.0).into(),
+            ambient_occlusion_radius: self.ambient_occlusion_radius.map(|r| r.abs()).into(),
+            ambient_occlusion_intensity: self.ambient_occlusion_intensity.max(0.0).into(),
+            sub_frustum: None,
         }
     }
 
@@ -1147,6 +1589,8 @@ pub struct SkyBoxBuilder {
     pub top: Option<TextureResource>,
     /// Texture for bottom face.
     pub bottom: Option<TextureResource>,
+    /// Equirectangular panorama, that is used instead of face textures when set.
+    pub equirectangular: Option<TextureResource>,
 }
 
 impl SkyBoxBuilder {
@@ -1186,6 +1630,14 @@ impl SkyBoxBuilder {
         self
     }
 
+    /// Sets desired equirectangular panorama, face textures are ignored in this case. The cube map,
+    /// irradiance map and prefiltered specular map for image-based lighting will be generated from
+    /// the panorama. See [`SkyBox::set_equirectangular`] for more info.
+    pub fn with_equirectangular(mut self, texture: TextureResource) -> Self {
+        self.equirectangular = Some(texture);
+        self
+    }
+
     /// Creates a new instance of skybox.
     pub fn build(self) -> Result<SkyBox, SkyBoxError> {
         let mut skybox = SkyBox {
@@ -1195,7 +1647,10 @@ impl SkyBoxBuilder {
             bottom: self.bottom,
             front: self.front,
             back: self.back,
+            equirectangular: self.equirectangular,
             cubemap: None,
+            irradiance_map: None,
+            prefiltered_map: None,
         };
 
         skybox.create_cubemap()?;
@@ -1235,10 +1690,25 @@ pub struct SkyBox {
     #[reflect(setter = "set_bottom")]
     pub(crate) bottom: Option<TextureResource>,
 
+    /// Equirectangular panorama, that is used instead of face textures when set.
+    #[reflect(setter = "set_equirectangular")]
+    #[visit(optional)]
+    pub(crate) equirectangular: Option<TextureResource>,
+
     /// Cubemap texture
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) cubemap: Option<TextureResource>,
+
+    /// Diffuse irradiance map, generated from equirectangular panorama.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) irradiance_map: Option<TextureResource>,
+
+    /// Prefiltered specular map, generated from equirectangular panorama.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) prefiltered_map: Option<TextureResource>,
 }
 
 uuid_provider!(SkyBox = "45f359f1-e26f-4ace-81df-097f63474c72");
@@ -1281,6 +1751,10 @@ pub enum SkyBoxError {
         /// Index of the faulty input texture.
         index: usize,
     },
+    /// Pixel kind of the equirectangular texture is not supported.
+    UnsupportedPixelKind(TexturePixelKind),
+    /// Occurs when the equirectangular texture is either still loading or failed to load.
+    EquirectangularTextureIsNotReady,
 }
 
 impl SkyBox {
@@ -1357,6 +1831,13 @@ impl SkyBox {
     ///
     /// It will fail if provided face's kind is not TextureKind::Rectangle.
     pub fn create_cubemap(&mut self) -> Result<(), SkyBoxError> {
+        if let Some(equirectangular) = self.equirectangular.clone() {
+            return self.create_environment_maps(&equirectangular);
+        }
+
+        self.irradiance_map = None;
+        self.prefiltered_map = None;
+
         self.validate()?;
 
         let (kind, pixel_kind, bytes_per_face) =
@@ -1405,6 +1886,66 @@ impl SkyBox {
         Ok(())
     }
 
+    fn create_environment_maps(
+        &mut self,
+        equirectangular: &TextureResource,
+    ) -> Result<(), SkyBoxError> {
+        let mut state = equirectangular.state();
+        let Some(texture) = state.data() else {
+            return Err(SkyBoxError::EquirectangularTextureIsNotReady);
+        };
+
+        let maps = ibl::generate_environment_maps(texture)?;
+        self.cubemap = Some(maps.cube_map);
+        self.irradiance_map = Some(maps.irradiance_map);
+        self.prefiltered_map = Some(maps.prefiltered_map);
+
+        Ok(())
+    }
+
+    /// Returns diffuse irradiance map of the skybox. It is available only for skyboxes made of
+    /// equirectangular panorama (see [`SkyBoxBuilder::with_equirectangular`]) and it is used for
+    /// diffuse image-based lighting.
+    pub fn irradiance_map(&self) -> Option<&TextureResource> {
+        self.irradiance_map.as_ref()
+    }
+
+    /// Returns prefiltered specular map of the skybox. It is available only for skyboxes made of
+    /// equirectangular panorama (see [`SkyBoxBuilder::with_equirectangular`]) and it is used for
+    /// specular image-based lighting (ambient reflections).
+    pub fn prefiltered_map(&self) -> Option<&TextureResource> {
+        self.prefiltered_map.as_ref()
+    }
+
+    /// Returns `true` if the cube map of the skybox stores colors in linear color space. This is
+    /// the case for skyboxes made of equirectangular panorama, face textures are treated as sRGB.
+    pub fn is_linear(&self) -> bool {
+        self.equirectangular.is_some()
+    }
+
+    /// Sets new equirectangular panorama for the skybox. When set, face textures are ignored and the
+    /// cube map, irradiance map and prefiltered specular map are generated from the panorama. The
+    /// panorama could be in high dynamic range (for example, `.hdr` file), it must cover the whole
+    /// sphere and have 2:1 aspect ratio. See [`crate::utils::ibl::generate_environment_maps`] for
+    /// more info.
+    ///
+    /// # Important notes.
+    ///
+    /// Generation is done on CPU and it could take some time for large panoramas.
+    pub fn set_equirectangular(
+        &mut self,
+        texture: Option<TextureResource>,
+    ) -> Option<TextureResource> {
+        let prev = std::mem::replace(&mut self.equirectangular, texture);
+        Log::verify(self.create_cubemap());
+        prev
+    }
+
+    /// Returns equirectangular panorama of the skybox (if any).
+    pub fn equirectangular(&self) -> Option<TextureResource> {
+        self.equirectangular.clone()
+    }
+
     /// Returns slice with all textures, where: 0 - Left, 1 - Right, 2 - Top, 3 - Bottom
     /// 4 - Front, 5 - Back.
     ///
@@ -449,6 +449,16 @@ impl ColliderShape {
     pub fn heightfield(geometry_source: GeometrySource) -> Self {
         Self::Heightfield(HeightfieldShape { geometry_source })
     }
+
+    /// Returns a list of mesh nodes, whose geometry is used to build the shape. Heightfield shapes are
+    /// tracking changes of their terrains automatically, so they're not included.
+    pub fn geometry_sources(&self) -> &[GeometrySource] {
+        match self {
+            Self::Trimesh(trimesh) => &trimesh.sources,
+            Self::Polyhedron(polyhedron) => std::slice::from_ref(&polyhedron.geometry_source),
+            _ => &[],
+        }
+    }
 }
 
 /// Collider is a geometric entity that can be attached to a rigid body to allow participate it
@@ -484,9 +494,22 @@ pub struct Collider {
     #[reflect(setter = "set_restitution_combine_rule")]
     pub(crate) restitution_combine_rule: InheritableVariable<CoefficientCombineRule>,
 
+    #[reflect(
+        setter = "set_surface_type",
+        description = "Type of the surface of the collider (for example: grass, metal, wood). It \
+        could be used to select footstep sounds or impact effects."
+    )]
+    #[visit(optional)]
+    pub(crate) surface_type: InheritableVariable<String>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: Cell<ColliderHandle>,
+
+    // Revision of the height map of a terrain, that was used to build heightfield shape.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) height_map_revision: Cell<u64>,
 }
 
 impl Default for Collider {
@@ -502,7 +525,9 @@ impl Default for Collider {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            surface_type: Default::default(),
             native: Cell::new(ColliderHandle::invalid()),
+            height_map_revision: Cell::new(0),
         }
     }
 }
@@ -534,8 +559,10 @@ impl Clone for Collider {
             solver_groups: self.solver_groups.clone(),
             friction_combine_rule: self.friction_combine_rule.clone(),
             restitution_combine_rule: self.restitution_combine_rule.clone(),
+            surface_type: self.surface_type.clone(),
             // Do not copy. The copy will have its own native representation (for example - Rapier's collider)
             native: Cell::new(ColliderHandle::invalid()),
+            height_map_revision: Cell::new(0),
         }
     }
 }
@@ -563,6 +590,13 @@ impl Collider {
         &self.shape
     }
 
+    /// Forces the physics engine to rebuild the native shape of the collider without marking the
+    /// shape as modified. It is useful for shapes, that use geometry of mesh nodes (see
+    /// [`ColliderShape::geometry_sources`]), because changes in the geometry are not tracked.
+    pub fn invalidate_shape(&self) {
+        self.shape.mark_need_sync();
+    }
+
     /// Returns a copy of the collider shape.
     pub fn shape_value(&self) -> ColliderShape {
         (*self.shape).clone()
@@ -726,6 +760,19 @@ impl Collider {
         *self.restitution_combine_rule
     }
 
+    /// Sets the new type of the surface of the collider (for example `grass`, `metal`, `wood`, etc.).
+    /// Surface type does not affect physics simulation, it is used to select footstep sounds, impact
+    /// effects and so on. See [`Graph::surface_type`] and [`crate::resource::surface::SurfaceEffects`]
+    /// for more info.
+    pub fn set_surface_type(&mut self, surface_type: String) -> String {
+        self.surface_type.set_value_and_mark_modified(surface_type)
+    }
+
+    /// Returns current type of the surface of the collider.
+    pub fn surface_type(&self) -> &str {
+        &self.surface_type
+    }
+
     /// Returns an iterator that yields contact information for the collider.
     /// Contacts checks between two regular colliders
     pub fn contacts<'a>(
@@ -818,6 +865,7 @@ pub struct ColliderBuilder {
     solver_groups: InteractionGroups,
     friction_combine_rule: CoefficientCombineRule,
     restitution_combine_rule: CoefficientCombineRule,
+    surface_type: String,
 }
 
 impl ColliderBuilder {
@@ -834,6 +882,7 @@ impl ColliderBuilder {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            surface_type: Default::default(),
         }
     }
 
@@ -891,6 +940,12 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets desired type of the surface.
+    pub fn with_surface_type(mut self, surface_type: String) -> Self {
+        self.surface_type = surface_type;
+        self
+    }
+
     /// Creates collider node, but does not add it to a graph.
     pub fn build_collider(self) -> Collider {
         Collider {
@@ -904,7 +959,9 @@ impl ColliderBuilder {
             solver_groups: self.solver_groups.into(),
             friction_combine_rule: self.friction_combine_rule.into(),
             restitution_combine_rule: self.restitution_combine_rule.into(),
+            surface_type: self.surface_type.into(),
             native: Cell::new(ColliderHandle::invalid()),
+            height_map_revision: Cell::new(0),
         }
     }
 
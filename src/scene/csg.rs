@@ -0,0 +1,598 @@
+//! Brushes and CSG models are level blocking tools, that allow you to build level geometry directly
+//! in a scene by combining simple shapes.
+//!
+//! For more info see [`CsgModel`] and [`Brush`].
+
+use crate::{
+    asset::untyped::ResourceKind,
+    core::{
+        algebra::Matrix4,
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    material::{Material, MaterialResource},
+    scene::{
+        base::{Base, BaseBuilder},
+        collider::{ColliderBuilder, ColliderShape, GeometrySource},
+        debug::SceneDrawingContext,
+        graph::Graph,
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceSharedData},
+            MeshBuilder,
+        },
+        node::{Node, NodeTrait},
+        rigidbody::{RigidBodyBuilder, RigidBodyType},
+    },
+    utils::csg::Csg,
+};
+use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Shape of a [`Brush`].
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Reflect,
+    Visit,
+    PartialEq,
+    Eq,
+    Hash,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum BrushShape {
+    /// Unit cube.
+    #[default]
+    Box,
+    /// Cylinder, aligned with Y axis, with radius 0.5 and height 1.0.
+    Cylinder,
+    /// Unit cube cut in half by a diagonal plane. The slope faces +Y and +Z.
+    Wedge,
+}
+
+uuid_provider!(BrushShape = "0f5c2d8a-4b61-4e3f-9a27-d1c8e6b3f402");
+
+/// Defines how a [`Brush`] is combined with the result of the previous brushes of a [`CsgModel`].
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Reflect,
+    Visit,
+    PartialEq,
+    Eq,
+    Hash,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum CsgOperation {
+    /// Adds the brush to the geometry.
+    #[default]
+    Union,
+    /// Carves the brush out of the geometry.
+    Subtract,
+    /// Leaves only the part of the geometry, that is inside the brush.
+    Intersect,
+}
+
+uuid_provider!(CsgOperation = "7e1a9b35-c2d4-4f68-8b0e-3a5f6d9c1e27");
+
+/// Brush is a simple convex shape (box, cylinder or wedge), that is combined with other brushes of
+/// its parent [`CsgModel`] into level geometry. Size and placement of the shape are defined by the
+/// transform of the brush node, the shape itself always fits into a unit cube in local coordinates.
+///
+/// Brushes are not rendered, they're displayed only as wireframes in the editor.
+#[derive(Debug, Visit, Clone, Reflect)]
+pub struct Brush {
+    base: Base,
+
+    #[reflect(setter = "set_shape")]
+    shape: InheritableVariable<BrushShape>,
+
+    #[reflect(setter = "set_operation")]
+    operation: InheritableVariable<CsgOperation>,
+
+    #[reflect(
+        min_value = 3.0,
+        max_value = 128.0,
+        setter = "set_cylinder_sides",
+        description = "Amount of sides of cylinder brushes."
+    )]
+    cylinder_sides: InheritableVariable<u32>,
+
+    #[reflect(setter = "set_material")]
+    material: InheritableVariable<MaterialResource>,
+}
+
+fn default_material() -> MaterialResource {
+    MaterialResource::new_ok(ResourceKind::Embedded, Material::standard())
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            shape: Default::default(),
+            operation: Default::default(),
+            cylinder_sides: 16.into(),
+            material: default_material().into(),
+        }
+    }
+}
+
+impl Deref for Brush {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Brush {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Brush {
+    fn type_uuid() -> Uuid {
+        uuid!("b4d27e91-6c3a-4f05-8e1d-95a0c7f2b6e8")
+    }
+}
+
+impl Brush {
+    /// Sets new shape of the brush.
+    pub fn set_shape(&mut self, shape: BrushShape) -> BrushShape {
+        self.shape.set_value_and_mark_modified(shape)
+    }
+
+    /// Returns current shape of the brush.
+    pub fn shape(&self) -> BrushShape {
+        *self.shape
+    }
+
+    /// Sets new operation of the brush.
+    pub fn set_operation(&mut self, operation: CsgOperation) -> CsgOperation {
+        self.operation.set_value_and_mark_modified(operation)
+    }
+
+    /// Returns current operation of the brush.
+    pub fn operation(&self) -> CsgOperation {
+        *self.operation
+    }
+
+    /// Sets new amount of sides of cylinder brushes. The value is clamped to `[3; 128]` range.
+    pub fn set_cylinder_sides(&mut self, sides: u32) -> u32 {
+        self.cylinder_sides
+            .set_value_and_mark_modified(sides.clamp(3, 128))
+    }
+
+    /// Returns current amount of sides of cylinder brushes.
+    pub fn cylinder_sides(&self) -> u32 {
+        *self.cylinder_sides
+    }
+
+    /// Sets new material of the faces, that are produced by the brush. Brushes that share the same
+    /// material resource are merged into a single surface.
+    pub fn set_material(&mut self, material: MaterialResource) -> MaterialResource {
+        self.material.set_value_and_mark_modified(material)
+    }
+
+    /// Returns current material of the brush.
+    pub fn material(&self) -> &MaterialResource {
+        &self.material
+    }
+
+    /// Creates a solid of the brush, transformed by the given matrix.
+    pub fn make_solid(&self, transform: &Matrix4<f32>, material: usize) -> Csg {
+        match *self.shape {
+            BrushShape::Box => Csg::cube(transform, material),
+            BrushShape::Cylinder => {
+                Csg::cylinder(*self.cylinder_sides as usize, transform, material)
+            }
+            BrushShape::Wedge => Csg::wedge(transform, material),
+        }
+    }
+}
+
+impl NodeTrait for Brush {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::unit()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        let color = match *self.operation {
+            CsgOperation::Union => Color::opaque(0, 200, 255),
+            CsgOperation::Subtract => Color::opaque(255, 80, 40),
+            CsgOperation::Intersect => Color::opaque(200, 0, 255),
+        };
+        ctx.draw_oob(
+            &AxisAlignedBoundingBox::unit(),
+            self.global_transform(),
+            color,
+        );
+    }
+}
+
+/// Allows you to create a brush in a declarative manner.
+pub struct BrushBuilder {
+    base_builder: BaseBuilder,
+    shape: BrushShape,
+    operation: CsgOperation,
+    cylinder_sides: u32,
+    material: Option<MaterialResource>,
+}
+
+impl BrushBuilder {
+    /// Creates a new instance of the builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            shape: Default::default(),
+            operation: Default::default(),
+            cylinder_sides: 16,
+            material: None,
+        }
+    }
+
+    /// Sets desired shape of the brush.
+    pub fn with_shape(mut self, shape: BrushShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets desired operation of the brush.
+    pub fn with_operation(mut self, operation: CsgOperation) -> Self {
+        self.operation = operation;
+        self
+    }
+
+    /// Sets desired amount of sides of cylinder brushes.
+    pub fn with_cylinder_sides(mut self, sides: u32) -> Self {
+        self.cylinder_sides = sides;
+        self
+    }
+
+    /// Sets desired material of the brush.
+    pub fn with_material(mut self, material: MaterialResource) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    /// Creates new brush.
+    pub fn build_brush(self) -> Brush {
+        Brush {
+            base: self.base_builder.build_base(),
+            shape: self.shape.into(),
+            operation: self.operation.into(),
+            cylinder_sides: self.cylinder_sides.clamp(3, 128).into(),
+            material: self.material.unwrap_or_else(default_material).into(),
+        }
+    }
+
+    /// Creates new brush node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_brush())
+    }
+
+    /// Creates new instance of brush node and puts it in the given graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+/// CSG model combines its child [`Brush`]es into renderable and collidable geometry. Brushes are
+/// combined in the order of children: every brush is added to (or subtracted from, or intersected
+/// with) the result of all the previous brushes, operation of the first brush is ignored.
+///
+/// Geometry is generated when a scene is resolved (right after loading) or when
+/// [`CsgModel::rebuild`] is called. Generated geometry is a static rigid body with a mesh and a
+/// triangle mesh collider, that is attached to the model. It is regenerated from scratch every
+/// time, so it must not be edited manually.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{
+///         base::BaseBuilder,
+///         csg::{BrushBuilder, BrushShape, CsgModel, CsgModelBuilder, CsgOperation},
+///         node::Node,
+///         transform::TransformBuilder,
+///         Scene,
+///     },
+/// };
+///
+/// fn create_room(scene: &mut Scene) -> Handle<Node> {
+///     let walls = BrushBuilder::new(BaseBuilder::new().with_local_transform(
+///         TransformBuilder::new()
+///             .with_local_scale(Vector3::new(10.0, 4.0, 10.0))
+///             .build(),
+///     ))
+///     .build(&mut scene.graph);
+///     let interior = BrushBuilder::new(BaseBuilder::new().with_local_transform(
+///         TransformBuilder::new()
+///             .with_local_scale(Vector3::new(9.5, 3.5, 9.5))
+///             .build(),
+///     ))
+///     .with_operation(CsgOperation::Subtract)
+///     .build(&mut scene.graph);
+///     let pillar = BrushBuilder::new(BaseBuilder::new())
+///         .with_shape(BrushShape::Cylinder)
+///         .build(&mut scene.graph);
+///
+///     let model = CsgModelBuilder::new(
+///         BaseBuilder::new().with_children(&[walls, interior, pillar]),
+///     )
+///     .build(&mut scene.graph);
+///     CsgModel::rebuild(&mut scene.graph, model);
+///     model
+/// }
+/// ```
+#[derive(Debug, Visit, Clone, Reflect)]
+pub struct CsgModel {
+    base: Base,
+
+    #[reflect(
+        setter = "set_generate_collider",
+        description = "Whether to generate a collider for the geometry or not."
+    )]
+    generate_collider: InheritableVariable<bool>,
+
+    #[reflect(
+        min_value = 0.0,
+        setter = "set_texture_scale",
+        description = "Amount of texture repeats per unit of length."
+    )]
+    texture_scale: InheritableVariable<f32>,
+
+    #[reflect(hidden)]
+    geometry: Handle<Node>,
+}
+
+impl Default for CsgModel {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            generate_collider: true.into(),
+            texture_scale: 1.0.into(),
+            geometry: Default::default(),
+        }
+    }
+}
+
+impl Deref for CsgModel {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for CsgModel {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for CsgModel {
+    fn type_uuid() -> Uuid {
+        uuid!("2c8f4e61-a7b3-4d92-b5e0-6f1d3c9a8e74")
+    }
+}
+
+impl CsgModel {
+    /// Sets whether to generate a collider for the geometry or not. Takes effect on the next rebuild.
+    pub fn set_generate_collider(&mut self, generate: bool) -> bool {
+        self.generate_collider.set_value_and_mark_modified(generate)
+    }
+
+    /// Returns `true` if the model generates a collider for its geometry.
+    pub fn is_generate_collider(&self) -> bool {
+        *self.generate_collider
+    }
+
+    /// Sets new amount of texture repeats per unit of length. Takes effect on the next rebuild.
+    pub fn set_texture_scale(&mut self, scale: f32) -> f32 {
+        self.texture_scale
+            .set_value_and_mark_modified(scale.max(0.0))
+    }
+
+    /// Returns current amount of texture repeats per unit of length.
+    pub fn texture_scale(&self) -> f32 {
+        *self.texture_scale
+    }
+
+    /// Returns a handle of the root of generated geometry (could be [`Handle::NONE`] if the model
+    /// was not built yet or has no brushes).
+    pub fn geometry(&self) -> Handle<Node> {
+        self.geometry
+    }
+
+    /// Combines brushes of the model and replaces previously generated geometry with the new one.
+    /// Global transforms of the brushes must be up-to-date. Returns `false` if there's no CSG model
+    /// with the given handle.
+    pub fn rebuild(graph: &mut Graph, model: Handle<Node>) -> bool {
+        let Some(csg_model) = graph.try_get_of_type::<CsgModel>(model) else {
+            return false;
+        };
+        let old_geometry = csg_model.geometry;
+        let generate_collider = *csg_model.generate_collider;
+        let texture_scale = *csg_model.texture_scale;
+        let inv_model_transform = csg_model
+            .global_transform()
+            .try_inverse()
+            .unwrap_or_default();
+
+        // Make sure that the handle points to the node, that was generated by this model.
+        if graph.is_valid_handle(old_geometry) && graph[model].children().contains(&old_geometry) {
+            graph.remove_node(old_geometry);
+        }
+
+        // Combine the brushes in model space.
+        let mut materials = Vec::<MaterialResource>::new();
+        let mut result: Option<Csg> = None;
+        for &child in graph[model].children() {
+            let Some(brush) = graph.try_get_of_type::<Brush>(child) else {
+                continue;
+            };
+            let material = brush.material().clone();
+            let material_index = match materials.iter().position(|m| *m == material) {
+                Some(index) => index,
+                None => {
+                    materials.push(material);
+                    materials.len() - 1
+                }
+            };
+            let solid = brush.make_solid(
+                &(inv_model_transform * brush.global_transform()),
+                material_index,
+            );
+            result = Some(match result {
+                None => solid,
+                Some(result) => match brush.operation() {
+                    CsgOperation::Union => result.union(&solid),
+                    CsgOperation::Subtract => result.subtract(&solid),
+                    CsgOperation::Intersect => result.intersect(&solid),
+                },
+            });
+        }
+
+        let geometry = match result {
+            Some(result) if !result.is_empty() => {
+                let result = result.with_box_mapping(texture_scale);
+                let surfaces = materials
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(index, _)| result.polygons().iter().any(|p| p.material == *index))
+                    .map(|(index, material)| {
+                        SurfaceBuilder::new(SurfaceSharedData::new(result.to_surface_data(index)))
+                            .with_material(material)
+                            .build()
+                    })
+                    .collect::<Vec<_>>();
+
+                let mesh = MeshBuilder::new(BaseBuilder::new().with_name("CsgMesh"))
+                    .with_surfaces(surfaces)
+                    .build(graph);
+
+                let mut children = vec![mesh];
+                if generate_collider {
+                    children.push(
+                        ColliderBuilder::new(BaseBuilder::new().with_name("CsgCollider"))
+                            .with_shape(ColliderShape::trimesh(vec![GeometrySource(mesh)]))
+                            .build(graph),
+                    );
+                }
+
+                let body = RigidBodyBuilder::new(
+                    BaseBuilder::new()
+                        .with_name("CsgGeometry")
+                        .with_children(&children),
+                )
+                .with_body_type(RigidBodyType::Static)
+                .build(graph);
+                graph.link_nodes(body, model);
+                body
+            }
+            _ => Handle::NONE,
+        };
+
+        if let Some(csg_model) = graph.try_get_mut_of_type::<CsgModel>(model) {
+            csg_model.geometry = geometry;
+        }
+
+        true
+    }
+}
+
+impl NodeTrait for CsgModel {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
+
+/// Allows you to create a CSG model in a declarative manner.
+pub struct CsgModelBuilder {
+    base_builder: BaseBuilder,
+    generate_collider: bool,
+    texture_scale: f32,
+}
+
+impl CsgModelBuilder {
+    /// Creates a new instance of the builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            generate_collider: true,
+            texture_scale: 1.0,
+        }
+    }
+
+    /// Sets whether to generate a collider for the geometry or not.
+    pub fn with_generate_collider(mut self, generate: bool) -> Self {
+        self.generate_collider = generate;
+        self
+    }
+
+    /// Sets desired amount of texture repeats per unit of length.
+    pub fn with_texture_scale(mut self, scale: f32) -> Self {
+        self.texture_scale = scale;
+        self
+    }
+
+    /// Creates new CSG model.
+    pub fn build_csg_model(self) -> CsgModel {
+        CsgModel {
+            base: self.base_builder.build_base(),
+            generate_collider: self.generate_collider.into(),
+            texture_scale: self.texture_scale.max(0.0).into(),
+            geometry: Default::default(),
+        }
+    }
+
+    /// Creates new CSG model node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_csg_model())
+    }
+
+    /// Creates new instance of CSG model node and puts it in the given graph. Geometry is not
+    /// generated, use [`CsgModel::rebuild`] for that.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
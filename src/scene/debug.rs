@@ -494,6 +494,24 @@ impl SceneDrawingContext {
         }
     }
 
+    /// Draws a projection of object-oriented bounding box on XY plane with given color. It is a
+    /// flat version of [`Self::draw_oob`], that is suitable for 2D scenes.
+    pub fn draw_oob_2d(
+        &mut self,
+        aabb: &AxisAlignedBoundingBox,
+        transform: Matrix4<f32>,
+        color: Color,
+    ) {
+        let center = aabb.center();
+        let half_extents = aabb.half_extents();
+        self.draw_rectangle(
+            half_extents.x,
+            half_extents.y,
+            transform * Matrix4::new_translation(&Vector3::new(center.x, center.y, 0.0)),
+            color,
+        );
+    }
+
     /// Draws a rectangle with given width and height.
     pub fn draw_rectangle(
         &mut self,
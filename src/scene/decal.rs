@@ -99,6 +99,15 @@ pub struct Decal {
     #[reflect(min_value = 0.0)]
     #[reflect(setter = "set_layer")]
     layer: InheritableVariable<u8>,
+
+    #[reflect(
+        min_value = 0.0,
+        setter = "set_fade_out_time",
+        description = "Time (in seconds) before the end of the lifetime of the decal, during which \
+        the decal will fade out. Has no effect if the decal has no lifetime."
+    )]
+    #[visit(optional)]
+    fade_out_time: InheritableVariable<f32>,
 }
 
 impl Deref for Decal {
@@ -187,6 +196,31 @@ impl Decal {
     pub fn layer(&self) -> u8 {
         *self.layer
     }
+
+    /// Sets the time (in seconds) before the end of the lifetime of the decal (see
+    /// [`Base::set_lifetime`]), during which the decal will smoothly fade out. It is useful for
+    /// temporary decals like bullet holes or blood splats, that should disappear after some time.
+    /// Has no effect if the decal has no lifetime.
+    pub fn set_fade_out_time(&mut self, time: f32) -> f32 {
+        self.fade_out_time
+            .set_value_and_mark_modified(time.max(0.0))
+    }
+
+    /// Returns the fade out time of the decal. See [`Self::set_fade_out_time`] for more info.
+    pub fn fade_out_time(&self) -> f32 {
+        *self.fade_out_time
+    }
+
+    /// Returns current opacity of the decal in `[0; 1]` range, which depends on the remaining lifetime
+    /// of the decal and its fade out time.
+    pub fn opacity(&self) -> f32 {
+        match self.lifetime() {
+            Some(lifetime) if *self.fade_out_time > 0.0 => {
+                (lifetime / *self.fade_out_time).clamp(0.0, 1.0)
+            }
+            _ => 1.0,
+        }
+    }
 }
 
 impl NodeTrait for Decal {
@@ -216,6 +250,7 @@ pub struct DecalBuilder {
     normal_texture: Option<TextureResource>,
     color: Color,
     layer: u8,
+    fade_out_time: f32,
 }
 
 impl DecalBuilder {
@@ -227,6 +262,7 @@ impl DecalBuilder {
             normal_texture: None,
             color: Color::opaque(255, 255, 255),
             layer: 0,
+            fade_out_time: 0.0,
         }
     }
 
@@ -254,6 +290,12 @@ impl DecalBuilder {
         self
     }
 
+    /// Sets desired fade out time. See [`Decal::set_fade_out_time`] for more info.
+    pub fn with_fade_out_time(mut self, time: f32) -> Self {
+        self.fade_out_time = time;
+        self
+    }
+
     /// Creates new Decal node.
     pub fn build_decal(self) -> Decal {
         Decal {
@@ -262,6 +304,7 @@ impl DecalBuilder {
             normal_texture: self.normal_texture.into(),
             color: self.color.into(),
             layer: self.layer.into(),
+            fade_out_time: self.fade_out_time.max(0.0).into(),
         }
     }
 
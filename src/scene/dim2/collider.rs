@@ -3,7 +3,7 @@
 
 use crate::{
     core::{
-        algebra::Vector2,
+        algebra::{Point3, Vector2},
         log::Log,
         math::aabb::AxisAlignedBoundingBox,
         pool::Handle,
@@ -13,6 +13,7 @@ use crate::{
         visitor::prelude::*,
         TypeUuidProvider,
     },
+    renderer::{self, batch::RenderContext},
     scene::{
         base::{Base, BaseBuilder},
         collider::InteractionGroups,
@@ -262,6 +263,14 @@ pub struct Collider {
     #[reflect(setter = "set_restitution_combine_rule")]
     pub(crate) restitution_combine_rule: InheritableVariable<CoefficientCombineRule>,
 
+    #[reflect(
+        setter = "set_cast_light_shadows",
+        description = "Defines whether the collider will block light from light sources in 2D \
+        rendering mode, casting shadows."
+    )]
+    #[visit(optional)]
+    pub(crate) cast_light_shadows: InheritableVariable<bool>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: Cell<ColliderHandle>,
@@ -280,6 +289,7 @@ impl Default for Collider {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            cast_light_shadows: Default::default(),
             native: Cell::new(ColliderHandle::invalid()),
         }
     }
@@ -312,6 +322,7 @@ impl Clone for Collider {
             solver_groups: self.solver_groups.clone(),
             friction_combine_rule: self.friction_combine_rule.clone(),
             restitution_combine_rule: self.restitution_combine_rule.clone(),
+            cast_light_shadows: self.cast_light_shadows.clone(),
             // Do not copy. The copy will have its own native representation.
             native: Cell::new(ColliderHandle::invalid()),
         }
@@ -504,6 +515,82 @@ impl Collider {
         *self.restitution_combine_rule
     }
 
+    /// Defines whether the collider will block light from light sources in 2D rendering mode, casting
+    /// shadows. Only light sources with enabled shadows (see
+    /// [`crate::scene::dim2::light::PointLight::set_cast_shadows`]) are affected. Height field and
+    /// triangle mesh shapes do not cast shadows.
+    pub fn set_cast_light_shadows(&mut self, cast_light_shadows: bool) -> bool {
+        self.cast_light_shadows
+            .set_value_and_mark_modified(cast_light_shadows)
+    }
+
+    /// Returns `true` if the collider blocks light from light sources in 2D rendering mode.
+    pub fn cast_light_shadows(&self) -> bool {
+        *self.cast_light_shadows
+    }
+
+    /// Collects edges of the shape of the collider in world coordinates, the edges are used to cast
+    /// shadows from light sources in 2D rendering mode. Round shapes are approximated with polygons.
+    pub fn shadow_occluder_edges(&self, edges: &mut Vec<(Vector2<f32>, Vector2<f32>)>) {
+        fn add_polygon(edges: &mut Vec<(Vector2<f32>, Vector2<f32>)>, points: &[Vector2<f32>]) {
+            for (i, begin) in points.iter().enumerate() {
+                edges.push((*begin, points[(i + 1) % points.len()]));
+            }
+        }
+
+        fn circle(center: Vector2<f32>, radius: f32) -> [Vector2<f32>; 8] {
+            std::array::from_fn(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / 8.0;
+                center + Vector2::new(angle.cos(), angle.sin()) * radius
+            })
+        }
+
+        let transform = self.global_transform();
+        let to_world = |p: Vector2<f32>| {
+            transform
+                .transform_point(&Point3::new(p.x, p.y, 0.0))
+                .xy()
+                .coords
+        };
+
+        let local_points = match &*self.shape {
+            ColliderShape::Ball(ball) => circle(Vector2::default(), ball.radius).to_vec(),
+            ColliderShape::Cuboid(cuboid) => {
+                let e = cuboid.half_extents;
+                vec![
+                    Vector2::new(-e.x, -e.y),
+                    Vector2::new(e.x, -e.y),
+                    Vector2::new(e.x, e.y),
+                    Vector2::new(-e.x, e.y),
+                ]
+            }
+            ColliderShape::Capsule(capsule) => {
+                // Approximate capsule with a hexagon around its axis.
+                let axis = (capsule.end - capsule.begin)
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_else(Vector2::x);
+                let side = Vector2::new(-axis.y, axis.x) * capsule.radius;
+                vec![
+                    capsule.begin - side,
+                    capsule.end - side,
+                    capsule.end + axis * capsule.radius,
+                    capsule.end + side,
+                    capsule.begin + side,
+                    capsule.begin - axis * capsule.radius,
+                ]
+            }
+            ColliderShape::Segment(segment) => {
+                edges.push((to_world(segment.begin), to_world(segment.end)));
+                return;
+            }
+            ColliderShape::Triangle(triangle) => vec![triangle.a, triangle.b, triangle.c],
+            ColliderShape::Trimesh(_) | ColliderShape::Heightfield(_) => return,
+        };
+
+        let world_points = local_points.into_iter().map(to_world).collect::<Vec<_>>();
+        add_polygon(edges, &world_points);
+    }
+
     /// Returns an iterator that yields contact information for the collider.
     /// Contacts checks between two regular colliders
     pub fn contacts<'a>(
@@ -550,6 +637,18 @@ impl NodeTrait for Collider {
         Self::type_uuid()
     }
 
+    fn collect_render_data(&self, ctx: &mut RenderContext) {
+        // Occluders are not culled, because an occluder outside of the frustum could still cast shadows
+        // on visible objects.
+        if self.cast_light_shadows()
+            && self.global_visibility()
+            && self.is_globally_enabled()
+            && !renderer::is_shadow_pass(ctx.render_pass_name)
+        {
+            self.shadow_occluder_edges(&mut ctx.storage.shadow_occluder_edges);
+        }
+    }
+
     fn on_removed_from_graph(&mut self, graph: &mut Graph) {
         graph.physics2d.remove_collider(self.native.get());
         self.native.set(ColliderHandle::invalid());
@@ -596,6 +695,7 @@ pub struct ColliderBuilder {
     solver_groups: InteractionGroups,
     friction_combine_rule: CoefficientCombineRule,
     restitution_combine_rule: CoefficientCombineRule,
+    cast_light_shadows: bool,
 }
 
 impl ColliderBuilder {
@@ -612,6 +712,7 @@ impl ColliderBuilder {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            cast_light_shadows: false,
         }
     }
 
@@ -669,6 +770,12 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets whether the collider will block light from light sources in 2D rendering mode.
+    pub fn with_cast_light_shadows(mut self, cast_light_shadows: bool) -> Self {
+        self.cast_light_shadows = cast_light_shadows;
+        self
+    }
+
     /// Creates collider node, but does not add it to a graph.
     pub fn build_collider(self) -> Collider {
         Collider {
@@ -682,6 +789,7 @@ impl ColliderBuilder {
             solver_groups: self.solver_groups.into(),
             friction_combine_rule: self.friction_combine_rule.into(),
             restitution_combine_rule: self.restitution_combine_rule.into(),
+            cast_light_shadows: self.cast_light_shadows.into(),
             native: Cell::new(ColliderHandle::invalid()),
         }
     }
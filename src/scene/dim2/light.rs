@@ -0,0 +1,444 @@
+//! 2D light sources. They light objects that use the standard 2D material (rectangles, tile maps) and
+//! could be blocked by 2D colliders (see [`crate::scene::dim2::collider::Collider::set_cast_light_shadows`]).
+//!
+//! See [`PointLight`] and [`DirectionalLight`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector3},
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    renderer::{
+        self,
+        batch::{LightSource, RenderContext},
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::{Line, SceneDrawingContext},
+        graph::Graph,
+        node::{Node, NodeTrait},
+    },
+};
+use std::ops::{Deref, DerefMut};
+
+/// Point light is a round light source (a torch, a lamp, etc.), that emits light in all directions
+/// in the XY plane. Intensity of the light fades out to zero at the radius of the light.
+///
+/// ## Shadows
+///
+/// If shadows are enabled (see [`Self::set_cast_shadows`]), the light is blocked by 2D colliders that
+/// have [`crate::scene::dim2::collider::Collider::set_cast_light_shadows`] enabled.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox::{
+/// #     core::{color::Color, pool::Handle},
+/// #     scene::{base::BaseBuilder, dim2::light::PointLightBuilder, graph::Graph, node::Node},
+/// # };
+/// fn create_torch(graph: &mut Graph) -> Handle<Node> {
+///     PointLightBuilder::new(BaseBuilder::new().with_name("Torch"))
+///         .with_color(Color::opaque(255, 180, 80))
+///         .with_radius(4.0)
+///         .with_cast_shadows(true)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Debug, Reflect, Clone, Visit)]
+pub struct PointLight {
+    base: Base,
+
+    #[reflect(setter = "set_color")]
+    color: InheritableVariable<Color>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_intensity")]
+    intensity: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_radius")]
+    radius: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_cast_shadows")]
+    cast_shadows: InheritableVariable<bool>,
+}
+
+impl Deref for PointLight {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for PointLight {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for PointLight {
+    fn type_uuid() -> Uuid {
+        uuid!("744d1dbe-e071-4caa-9fe5-5ea1d2eed514")
+    }
+}
+
+impl Default for PointLight {
+    fn default() -> Self {
+        PointLightBuilder::new(BaseBuilder::new()).build_point_light()
+    }
+}
+
+impl PointLight {
+    /// Sets color of the light, alpha component of the color is ignored.
+    pub fn set_color(&mut self, color: Color) -> Color {
+        self.color.set_value_and_mark_modified(color)
+    }
+
+    /// Returns current color of the light.
+    pub fn color(&self) -> Color {
+        *self.color
+    }
+
+    /// Sets intensity of the light, the color of the light is multiplied by this value.
+    pub fn set_intensity(&mut self, intensity: f32) -> f32 {
+        self.intensity
+            .set_value_and_mark_modified(intensity.max(0.0))
+    }
+
+    /// Returns current intensity of the light.
+    pub fn intensity(&self) -> f32 {
+        *self.intensity
+    }
+
+    /// Sets radius of the light, at this distance intensity of the light will be zero.
+    pub fn set_radius(&mut self, radius: f32) -> f32 {
+        self.radius.set_value_and_mark_modified(radius.abs())
+    }
+
+    /// Returns radius of the light.
+    pub fn radius(&self) -> f32 {
+        *self.radius
+    }
+
+    /// Defines whether the light will be blocked by 2D colliders, that cast shadows, or not.
+    pub fn set_cast_shadows(&mut self, cast_shadows: bool) -> bool {
+        self.cast_shadows.set_value_and_mark_modified(cast_shadows)
+    }
+
+    /// Returns `true` if the light is blocked by 2D colliders, that cast shadows.
+    pub fn cast_shadows(&self) -> bool {
+        *self.cast_shadows
+    }
+}
+
+impl NodeTrait for PointLight {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::from_radius(self.radius())
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let position = self.global_position();
+        let radius = Vector3::repeat(self.radius());
+        AxisAlignedBoundingBox::from_min_max(position - radius, position + radius)
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn collect_render_data(&self, ctx: &mut RenderContext) {
+        if !self.global_visibility()
+            || !self.is_globally_enabled()
+            || renderer::is_shadow_pass(ctx.render_pass_name)
+            || !ctx.frustum.is_intersects_aabb(&self.world_bounding_box())
+        {
+            return;
+        }
+
+        ctx.storage.light_sources.push(LightSource {
+            position: self.global_position(),
+            direction: Vector3::default(),
+            color: self.color().as_frgb().scale(self.intensity()),
+            radius: self.radius(),
+            half_cone_angle_cos: std::f32::consts::PI.cos(),
+            half_hotspot_angle_cos: std::f32::consts::PI.cos(),
+            cast_shadows: self.cast_shadows(),
+        });
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        ctx.draw_circle(
+            self.global_position(),
+            self.radius(),
+            32,
+            Matrix4::identity(),
+            Color::GREEN,
+        );
+    }
+}
+
+/// Allows you to create 2D point light in declarative manner.
+pub struct PointLightBuilder {
+    base_builder: BaseBuilder,
+    color: Color,
+    intensity: f32,
+    radius: f32,
+    cast_shadows: bool,
+}
+
+impl PointLightBuilder {
+    /// Creates new builder instance.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            color: Color::WHITE,
+            intensity: 1.0,
+            radius: 5.0,
+            cast_shadows: false,
+        }
+    }
+
+    /// Sets desired color of the light.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets desired intensity of the light.
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Sets desired radius of the light.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets whether the light will be blocked by 2D colliders, that cast shadows, or not.
+    pub fn with_cast_shadows(mut self, cast_shadows: bool) -> Self {
+        self.cast_shadows = cast_shadows;
+        self
+    }
+
+    /// Creates new 2D point light.
+    pub fn build_point_light(self) -> PointLight {
+        PointLight {
+            base: self.base_builder.build_base(),
+            color: self.color.into(),
+            intensity: self.intensity.into(),
+            radius: self.radius.into(),
+            cast_shadows: self.cast_shadows.into(),
+        }
+    }
+
+    /// Creates new 2D point light node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_point_light())
+    }
+
+    /// Creates new 2D point light and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+/// Directional light is an infinitely distant light source (the sun, the moon, etc.), that lights
+/// everything in the scene. The light shines along the negative Y axis of the node, which means that
+/// by default it shines downwards. Rotate the node around Z axis to change the direction.
+///
+/// If shadows are enabled (see [`Self::set_cast_shadows`]), the light is blocked by 2D colliders that
+/// have [`crate::scene::dim2::collider::Collider::set_cast_light_shadows`] enabled.
+#[derive(Debug, Reflect, Clone, Visit)]
+pub struct DirectionalLight {
+    base: Base,
+
+    #[reflect(setter = "set_color")]
+    color: InheritableVariable<Color>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_intensity")]
+    intensity: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_cast_shadows")]
+    cast_shadows: InheritableVariable<bool>,
+}
+
+impl Deref for DirectionalLight {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for DirectionalLight {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for DirectionalLight {
+    fn type_uuid() -> Uuid {
+        uuid!("1f23e3cf-2d41-466f-ae2d-3b662a308baf")
+    }
+}
+
+impl Default for DirectionalLight {
+    fn default() -> Self {
+        DirectionalLightBuilder::new(BaseBuilder::new()).build_directional_light()
+    }
+}
+
+impl DirectionalLight {
+    /// Sets color of the light, alpha component of the color is ignored.
+    pub fn set_color(&mut self, color: Color) -> Color {
+        self.color.set_value_and_mark_modified(color)
+    }
+
+    /// Returns current color of the light.
+    pub fn color(&self) -> Color {
+        *self.color
+    }
+
+    /// Sets intensity of the light, the color of the light is multiplied by this value.
+    pub fn set_intensity(&mut self, intensity: f32) -> f32 {
+        self.intensity
+            .set_value_and_mark_modified(intensity.max(0.0))
+    }
+
+    /// Returns current intensity of the light.
+    pub fn intensity(&self) -> f32 {
+        *self.intensity
+    }
+
+    /// Defines whether the light will be blocked by 2D colliders, that cast shadows, or not.
+    pub fn set_cast_shadows(&mut self, cast_shadows: bool) -> bool {
+        self.cast_shadows.set_value_and_mark_modified(cast_shadows)
+    }
+
+    /// Returns `true` if the light is blocked by 2D colliders, that cast shadows.
+    pub fn cast_shadows(&self) -> bool {
+        *self.cast_shadows
+    }
+
+    /// Returns world-space direction in which the light shines.
+    pub fn direction(&self) -> Vector3<f32> {
+        -self.up_vector()
+    }
+}
+
+impl NodeTrait for DirectionalLight {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::unit()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn collect_render_data(&self, ctx: &mut RenderContext) {
+        // Directional light affects everything, so it is never culled.
+        if !self.global_visibility()
+            || !self.is_globally_enabled()
+            || renderer::is_shadow_pass(ctx.render_pass_name)
+        {
+            return;
+        }
+
+        ctx.storage.light_sources.push(LightSource {
+            position: self.global_position(),
+            direction: self.direction(),
+            color: self.color().as_frgb().scale(self.intensity()),
+            radius: f32::INFINITY,
+            half_cone_angle_cos: std::f32::consts::PI.cos(),
+            half_hotspot_angle_cos: std::f32::consts::PI.cos(),
+            cast_shadows: self.cast_shadows(),
+        });
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        let position = self.global_position();
+        ctx.add_line(Line {
+            begin: position,
+            end: position + self.direction().normalize(),
+            color: Color::GREEN,
+        });
+    }
+}
+
+/// Allows you to create 2D directional light in declarative manner.
+pub struct DirectionalLightBuilder {
+    base_builder: BaseBuilder,
+    color: Color,
+    intensity: f32,
+    cast_shadows: bool,
+}
+
+impl DirectionalLightBuilder {
+    /// Creates new builder instance.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            color: Color::WHITE,
+            intensity: 1.0,
+            cast_shadows: false,
+        }
+    }
+
+    /// Sets desired color of the light.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets desired intensity of the light.
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Sets whether the light will be blocked by 2D colliders, that cast shadows, or not.
+    pub fn with_cast_shadows(mut self, cast_shadows: bool) -> Self {
+        self.cast_shadows = cast_shadows;
+        self
+    }
+
+    /// Creates new 2D directional light.
+    pub fn build_directional_light(self) -> DirectionalLight {
+        DirectionalLight {
+            base: self.base_builder.build_base(),
+            color: self.color.into(),
+            intensity: self.intensity.into(),
+            cast_shadows: self.cast_shadows.into(),
+        }
+    }
+
+    /// Creates new 2D directional light node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_directional_light())
+    }
+
+    /// Creates new 2D directional light and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
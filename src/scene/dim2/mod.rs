@@ -3,6 +3,8 @@
 
 pub mod collider;
 pub mod joint;
+pub mod light;
 pub mod physics;
 pub mod rectangle;
 pub mod rigidbody;
+pub mod tilemap;
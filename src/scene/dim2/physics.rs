@@ -459,12 +459,18 @@ impl PhysicsWorld {
         }
     }
 
-    pub(crate) fn update(&mut self, dt: f32) {
+    pub(crate) fn update(&mut self, dt: f32, time_scale: f32) {
         let time = instant::Instant::now();
 
-        if *self.enabled {
+        // Fixed time step must be scaled too, otherwise time dilation will have no effect.
+        let dt = self
+            .integration_parameters
+            .dt
+            .map_or(dt, |fixed_dt| fixed_dt * time_scale);
+
+        if *self.enabled && dt > 0.0 {
             let integration_parameters = rapier2d::dynamics::IntegrationParameters {
-                dt: self.integration_parameters.dt.unwrap_or(dt),
+                dt,
                 min_ccd_dt: self.integration_parameters.min_ccd_dt,
                 erp: self.integration_parameters.erp,
                 damping_ratio: self.integration_parameters.damping_ratio,
@@ -7,7 +7,7 @@ use crate::{
     core::{
         algebra::{Point3, Vector2, Vector3},
         color::Color,
-        math::{aabb::AxisAlignedBoundingBox, Rect, TriangleDefinition},
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext, Rect, TriangleDefinition},
         pool::Handle,
         reflect::prelude::*,
         uuid::{uuid, Uuid},
@@ -17,6 +17,7 @@ use crate::{
     },
     material::{self, Material, MaterialResource},
     renderer::{self, batch::RenderContext},
+    resource::texture_atlas::{AtlasQuad, AtlasRegionRef},
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
@@ -24,7 +25,9 @@ use crate::{
             VertexAttributeDataType, VertexAttributeDescriptor, VertexAttributeUsage, VertexTrait,
         },
         mesh::RenderPath,
-        node::{Node, NodeTrait},
+        node::{Node, NodeTrait, UpdateContext},
+        sorting::SortingOrder,
+        sprite::{Flipbook, NineSlice},
     },
 };
 use std::{
@@ -139,7 +142,10 @@ impl Hash for RectangleVertex {
 ///
 /// ## Performance
 ///
-/// Rectangles use batching to let you draw tons of rectangles with high performance.
+/// Rectangles use batching to let you draw tons of rectangles with high performance. Rectangles are
+/// batched by the content of their materials, which means that rectangles that use the same texture
+/// atlas (with different uv rects) and the same material parameters will be rendered in a single
+/// draw call, even if each rectangle has its own material instance.
 ///
 /// ## Specifying region for rendering
 ///
@@ -148,6 +154,26 @@ impl Hash for RectangleVertex {
 /// image, but just changing portion for rendering. Keep in mind that the coordinates are normalized
 /// which means `[0; 0]` corresponds to top-left corner of the texture and `[1; 1]` corresponds to
 /// right-bottom corner.
+///
+/// ## Texture atlases
+///
+/// Rectangles could show a named region of a [`crate::resource::texture_atlas::TextureAtlas`] using
+/// [`Self::set_atlas_region`]. In this case the uv rectangle is ignored, the texture of the atlas is
+/// automatically assigned to the material and the rotation and trimming of the region are taken into
+/// account - trimmed regions occupy only a part of the rectangle, so animation frames of different sizes
+/// stay aligned.
+///
+/// ## Flipbook animation
+///
+/// Simple frame animations could be done using [`Self::set_flipbook`] - frames are arranged in a regular
+/// grid within the uv rectangle (or the atlas region) and played with the given frame rate. See [`Flipbook`]
+/// docs for more info.
+///
+/// ## 9-slice scaling
+///
+/// Rectangles that are used as panels, frames, bars, etc. could use [`Self::set_nine_slice`] to keep the
+/// corners of the image unstretched, no matter how the rectangle is scaled. See [`NineSlice`] docs for
+/// more info.
 #[derive(Reflect, Debug, Clone)]
 pub struct Rectangle {
     base: Base,
@@ -159,6 +185,18 @@ pub struct Rectangle {
     uv_rect: InheritableVariable<Rect<f32>>,
 
     material: InheritableVariable<MaterialResource>,
+
+    #[reflect(setter = "set_atlas_region")]
+    atlas_region: InheritableVariable<AtlasRegionRef>,
+
+    #[reflect(setter = "set_flipbook")]
+    flipbook: InheritableVariable<Flipbook>,
+
+    #[reflect(setter = "set_nine_slice")]
+    nine_slice: InheritableVariable<NineSlice>,
+
+    #[reflect(setter = "set_sorting_order")]
+    sorting_order: InheritableVariable<SortingOrder>,
 }
 
 impl Visit for Rectangle {
@@ -180,6 +218,10 @@ impl Visit for Rectangle {
         self.base.visit("Base", &mut region)?;
         self.color.visit("Color", &mut region)?;
         let _ = self.uv_rect.visit("UvRect", &mut region);
+        let _ = self.atlas_region.visit("AtlasRegion", &mut region);
+        let _ = self.flipbook.visit("Flipbook", &mut region);
+        let _ = self.nine_slice.visit("NineSlice", &mut region);
+        let _ = self.sorting_order.visit("SortingOrder", &mut region);
 
         Ok(())
     }
@@ -195,6 +237,10 @@ impl Default for Rectangle {
                 Default::default(),
                 Material::standard_2d(),
             )),
+            atlas_region: Default::default(),
+            flipbook: Default::default(),
+            nine_slice: Default::default(),
+            sorting_order: Default::default(),
         }
     }
 }
@@ -258,6 +304,84 @@ impl Rectangle {
     pub fn set_uv_rect(&mut self, uv_rect: Rect<f32>) -> Rect<f32> {
         self.uv_rect.set_value_and_mark_modified(uv_rect)
     }
+
+    /// Sets a region of a texture atlas, that will be shown by the rectangle instead of the uv rectangle.
+    /// The texture of the atlas is assigned to the `diffuseTexture` property of the material on the
+    /// next update. See [`AtlasRegionRef`] docs for more info.
+    pub fn set_atlas_region(&mut self, atlas_region: AtlasRegionRef) -> AtlasRegionRef {
+        self.atlas_region.set_value_and_mark_modified(atlas_region)
+    }
+
+    /// Returns a reference to the current atlas region of the rectangle.
+    pub fn atlas_region(&self) -> &AtlasRegionRef {
+        &self.atlas_region
+    }
+
+    /// Sets new flipbook animation of the rectangle. Frames of the animation are taken from the uv
+    /// rectangle (or the atlas region). See [`Flipbook`] docs for more info.
+    pub fn set_flipbook(&mut self, flipbook: Flipbook) -> Flipbook {
+        self.flipbook.set_value_and_mark_modified(flipbook)
+    }
+
+    /// Returns a reference to the flipbook animation of the rectangle.
+    pub fn flipbook(&self) -> &Flipbook {
+        &self.flipbook
+    }
+
+    /// Returns a reference to the flipbook animation of the rectangle, that could be used to control
+    /// the playback.
+    pub fn flipbook_mut(&mut self) -> &mut Flipbook {
+        self.flipbook.get_value_mut_and_mark_modified()
+    }
+
+    /// Sets new 9-slice scaling parameters of the rectangle. Margins are defined in world units, the size
+    /// of the rectangle is defined by the scale of the node. See [`NineSlice`] docs for more info.
+    pub fn set_nine_slice(&mut self, nine_slice: NineSlice) -> NineSlice {
+        self.nine_slice.set_value_and_mark_modified(nine_slice)
+    }
+
+    /// Returns current 9-slice scaling parameters of the rectangle.
+    pub fn nine_slice(&self) -> &NineSlice {
+        &self.nine_slice
+    }
+
+    /// Sets new sorting order of the rectangle, that defines whether the rectangle is drawn on top of
+    /// other 2D nodes or below them. See [`SortingOrder`] docs for more info.
+    pub fn set_sorting_order(&mut self, sorting_order: SortingOrder) -> SortingOrder {
+        self.sorting_order
+            .set_value_and_mark_modified(sorting_order)
+    }
+
+    /// Returns current sorting order of the rectangle.
+    pub fn sorting_order(&self) -> SortingOrder {
+        *self.sorting_order
+    }
+
+    /// Returns the region of the texture that is currently shown, it takes current frame of the
+    /// flipbook animation into account.
+    pub fn current_uv_rect(&self) -> Rect<f32> {
+        if self.flipbook.enabled {
+            self.flipbook.frame_uv_rect(*self.uv_rect)
+        } else {
+            *self.uv_rect
+        }
+    }
+
+    // Returns the shown part of the image and a quad, that maps the image to the texture.
+    fn frame_and_quad(&self) -> (Rect<f32>, AtlasQuad) {
+        match self.atlas_region.quad() {
+            Some(quad) => {
+                let whole = Rect::new(0.0, 0.0, 1.0, 1.0);
+                let frame = if self.flipbook.enabled {
+                    self.flipbook.frame_uv_rect(whole)
+                } else {
+                    whole
+                };
+                (frame, quad)
+            }
+            None => (self.current_uv_rect(), AtlasQuad::default()),
+        }
+    }
 }
 
 impl NodeTrait for Rectangle {
@@ -276,6 +400,15 @@ impl NodeTrait for Rectangle {
         Self::type_uuid()
     }
 
+    fn update(&mut self, context: &mut UpdateContext) {
+        if self.flipbook.enabled && self.flipbook.playing {
+            // Playback state is not a property change.
+            self.flipbook.get_value_mut_silent().update(context.dt);
+        }
+
+        self.atlas_region.sync_material(&self.material);
+    }
+
     fn collect_render_data(&self, ctx: &mut RenderContext) {
         if !self.global_visibility()
             || !self.is_globally_enabled()
@@ -289,36 +422,42 @@ impl NodeTrait for Rectangle {
         }
 
         let global_transform = self.global_transform();
+        let (frame, quad) = self.frame_and_quad();
+        let make_vertex = |position: Vector2<f32>, tex_coord: Vector2<f32>| RectangleVertex {
+            position: global_transform
+                .transform_point(&Point3::new(0.5 - position.x, 0.5 - position.y, 0.0))
+                .coords,
+            tex_coord: quad.tex_coord(frame.position + frame.size.component_mul(&tex_coord)),
+            color: *self.color,
+        };
+
+        if self.nine_slice.enabled {
+            let size = Vector2::new(global_transform.side().norm(), global_transform.up().norm());
+            let (vertices, triangles) = self.nine_slice.build(size, make_vertex);
+
+            ctx.storage.push_triangles(
+                vertices.into_iter(),
+                triangles.into_iter(),
+                &self.material,
+                RenderPath::Forward,
+                0,
+                self.sorting_order.sort_index(),
+                false,
+                self.self_handle,
+            );
 
+            return;
+        }
+
+        // Trimmed atlas regions cover only a part of the rectangle.
+        let corner = |x: f32, y: f32| {
+            make_vertex(quad.local_position(Vector2::new(x, y)), Vector2::new(x, y))
+        };
         let vertices = [
-            RectangleVertex {
-                position: global_transform
-                    .transform_point(&Point3::new(-0.5, 0.5, 0.0))
-                    .coords,
-                tex_coord: self.uv_rect.right_top_corner(),
-                color: *self.color,
-            },
-            RectangleVertex {
-                position: global_transform
-                    .transform_point(&Point3::new(0.5, 0.5, 0.0))
-                    .coords,
-                tex_coord: self.uv_rect.left_top_corner(),
-                color: *self.color,
-            },
-            RectangleVertex {
-                position: global_transform
-                    .transform_point(&Point3::new(0.5, -0.5, 0.0))
-                    .coords,
-                tex_coord: self.uv_rect.left_bottom_corner(),
-                color: *self.color,
-            },
-            RectangleVertex {
-                position: global_transform
-                    .transform_point(&Point3::new(-0.5, -0.5, 0.0))
-                    .coords,
-                tex_coord: self.uv_rect.right_bottom_corner(),
-                color: *self.color,
-            },
+            corner(1.0, 0.0),
+            corner(0.0, 0.0),
+            corner(0.0, 1.0),
+            corner(1.0, 1.0),
         ];
 
         let triangles = [TriangleDefinition([0, 1, 2]), TriangleDefinition([2, 3, 0])];
@@ -329,7 +468,7 @@ impl NodeTrait for Rectangle {
             &self.material,
             RenderPath::Forward,
             0,
-            0,
+            self.sorting_order.sort_index(),
             false,
             self.self_handle,
         )
@@ -342,6 +481,10 @@ pub struct RectangleBuilder {
     color: Color,
     uv_rect: Rect<f32>,
     material: MaterialResource,
+    atlas_region: AtlasRegionRef,
+    flipbook: Flipbook,
+    nine_slice: NineSlice,
+    sorting_order: SortingOrder,
 }
 
 impl RectangleBuilder {
@@ -352,6 +495,10 @@ impl RectangleBuilder {
             color: Color::WHITE,
             uv_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
             material: MaterialResource::new_ok(Default::default(), Material::standard_2d()),
+            atlas_region: Default::default(),
+            flipbook: Default::default(),
+            nine_slice: Default::default(),
+            sorting_order: Default::default(),
         }
     }
 
@@ -374,6 +521,30 @@ impl RectangleBuilder {
         self
     }
 
+    /// Sets desired region of a texture atlas. See [`Rectangle::set_atlas_region`] for more info.
+    pub fn with_atlas_region(mut self, atlas_region: AtlasRegionRef) -> Self {
+        self.atlas_region = atlas_region;
+        self
+    }
+
+    /// Sets desired flipbook animation. See [`Rectangle::set_flipbook`] for more info.
+    pub fn with_flipbook(mut self, flipbook: Flipbook) -> Self {
+        self.flipbook = flipbook;
+        self
+    }
+
+    /// Sets desired 9-slice scaling parameters. See [`Rectangle::set_nine_slice`] for more info.
+    pub fn with_nine_slice(mut self, nine_slice: NineSlice) -> Self {
+        self.nine_slice = nine_slice;
+        self
+    }
+
+    /// Sets desired sorting order. See [`Rectangle::set_sorting_order`] for more info.
+    pub fn with_sorting_order(mut self, sorting_order: SortingOrder) -> Self {
+        self.sorting_order = sorting_order;
+        self
+    }
+
     /// Creates new [`Rectangle`] instance.
     pub fn build_rectangle(self) -> Rectangle {
         Rectangle {
@@ -381,6 +552,10 @@ impl RectangleBuilder {
             color: self.color.into(),
             uv_rect: self.uv_rect.into(),
             material: self.material.into(),
+            atlas_region: self.atlas_region.into(),
+            flipbook: self.flipbook.into(),
+            nine_slice: self.nine_slice.into(),
+            sorting_order: self.sorting_order.into(),
         }
     }
 
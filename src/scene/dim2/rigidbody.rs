@@ -384,6 +384,16 @@ impl RigidBody {
         self.actions.get_mut().push_back(ApplyAction::WakeUp)
     }
 
+    /// Resets dynamic state of the rigid body - its velocities, pending forces and impulses and
+    /// sleeping flag. It is useful when a rigid body is reused (for example, when it is taken from
+    /// [`crate::scene::pool::NodePool`]).
+    pub fn reset_dynamics(&mut self) {
+        self.set_lin_vel(Vector2::default());
+        self.set_ang_vel(0.0);
+        self.actions.get_mut().clear();
+        self.sleeping = false;
+    }
+
     pub(crate) fn need_sync_model(&self) -> bool {
         self.lin_vel.need_sync()
             || self.ang_vel.need_sync()
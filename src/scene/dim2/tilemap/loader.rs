@@ -0,0 +1,75 @@
+//! Tile set and tile map data loaders.
+
+use crate::{
+    asset::{
+        io::ResourceIo,
+        loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+        manager::ResourceManager,
+    },
+    core::{uuid::Uuid, TypeUuidProvider},
+    scene::dim2::tilemap::{tiled, tileset::TileSet, TileMapData},
+};
+use fyrox_resource::state::LoadError;
+use std::{path::PathBuf, sync::Arc};
+
+/// Default implementation for tile set loading. Supports native `.tileset` files and Tiled `.tsx`
+/// files.
+pub struct TileSetLoader {
+    /// Resource manager that will be used to load textures of tile sets.
+    pub resource_manager: ResourceManager,
+}
+
+impl ResourceLoader for TileSetLoader {
+    fn extensions(&self) -> &[&str] {
+        &["tileset", "tsx"]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        TileSet::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        let resource_manager = self.resource_manager.clone();
+        Box::pin(async move {
+            let is_tsx = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("tsx"));
+            let tile_set = if is_tsx {
+                tiled::load_tsx(&path, io.as_ref(), &resource_manager)
+                    .await
+                    .map_err(LoadError::new)?
+            } else {
+                TileSet::from_file(&path, io.as_ref())
+                    .await
+                    .map_err(LoadError::new)?
+            };
+            Ok(LoaderPayload::new(tile_set))
+        })
+    }
+}
+
+/// Default implementation for tile map data loading. Supports Tiled `.tmx` files.
+pub struct TileMapDataLoader {
+    /// Resource manager that will be used to load tile sets of tile maps.
+    pub resource_manager: ResourceManager,
+}
+
+impl ResourceLoader for TileMapDataLoader {
+    fn extensions(&self) -> &[&str] {
+        &["tmx"]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        TileMapData::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        let resource_manager = self.resource_manager.clone();
+        Box::pin(async move {
+            let data = tiled::load_tmx(&path, io.as_ref(), &resource_manager)
+                .await
+                .map_err(LoadError::new)?;
+            Ok(LoaderPayload::new(data))
+        })
+    }
+}
@@ -0,0 +1,1272 @@
+//! Tile map is a 2D node, that draws a grid of tiles from a tile set. See [`TileMap`] docs for more
+//! info and usage examples.
+
+use crate::{
+    asset::{Resource, ResourceData},
+    core::{
+        algebra::{Matrix4, Point3, Vector2, Vector3},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, Rect, TriangleDefinition},
+        pool::Handle,
+        reflect::prelude::*,
+        sstorage::ImmutableString,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::{prelude::*, PodVecView},
+        TypeUuidProvider,
+    },
+    material::{Material, MaterialResource},
+    renderer::{
+        self,
+        batch::{PersistentIdentifier, RenderContext, SurfaceInstanceData},
+        framework::geometry_buffer::ElementRange,
+    },
+    resource::texture::TextureResource,
+    scene::{
+        base::{Base, BaseBuilder},
+        dim2::{
+            collider::{ColliderBuilder, ColliderShape},
+            rectangle::RectangleVertex,
+            rigidbody::RigidBodyBuilder,
+            tilemap::tileset::{TileSet, TileSetResource},
+        },
+        graph::Graph,
+        mesh::{
+            buffer::{TriangleBuffer, VertexBuffer},
+            surface::{SurfaceData, SurfaceSharedData},
+            RenderPath,
+        },
+        node::{Node, NodeTrait, UpdateContext},
+        rigidbody::RigidBodyType,
+        sorting::SortingOrder,
+        transform::TransformBuilder,
+    },
+};
+use bitflags::bitflags;
+use fxhash::{FxHashMap, FxHasher};
+use std::{
+    any::Any,
+    cell::RefCell,
+    error::Error,
+    hash::Hasher,
+    ops::{Deref, DerefMut},
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+pub mod loader;
+pub mod tiled;
+pub mod tileset;
+
+/// Distance between adjacent layers of a tile map along Z axis. It is used to draw layers in the
+/// correct order.
+const LAYER_DEPTH_STEP: f32 = 0.001;
+
+/// Packed tiles store index of a tile (plus one, zero means "no tile") in lower bits and flags in
+/// upper bits.
+const TILE_INDEX_MASK: u32 = 0x0FFF_FFFF;
+const TILE_FLAGS_SHIFT: u32 = 28;
+
+/// Every change of the tiles of any layer gets a unique revision number, so the cached data of a tile
+/// map could be checked for validity without comparing the tiles.
+static NEXT_LAYER_REVISION: AtomicU64 = AtomicU64::new(0);
+
+fn next_layer_revision() -> u64 {
+    NEXT_LAYER_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A set of flags, that defines how a tile is drawn and whether it is solid or not.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct TileFlags(u8);
+
+bitflags! {
+    impl TileFlags: u8 {
+        /// The tile image is mirrored horizontally.
+        const FLIP_HORIZONTAL = 0b0000_0001;
+        /// The tile image is mirrored vertically.
+        const FLIP_VERTICAL = 0b0000_0010;
+        /// The tile image is mirrored along its main diagonal (top-left to bottom-right). Combined
+        /// with other flips it allows to rotate tiles by 90 degrees.
+        const FLIP_DIAGONAL = 0b0000_0100;
+        /// The tile is solid, even if its definition in the tile set is not.
+        const SOLID = 0b0000_1000;
+    }
+}
+
+/// A tile of a tile map layer.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Tile {
+    /// Index of the tile definition in the tile set.
+    pub index: u32,
+    /// Additional flags of the tile.
+    pub flags: TileFlags,
+}
+
+impl Tile {
+    /// Maximum index of a tile definition, that could be used by a tile.
+    pub const MAX_INDEX: u32 = TILE_INDEX_MASK - 1;
+
+    /// Creates a new tile, that uses a tile definition with the given index.
+    pub fn new(index: u32) -> Self {
+        Self {
+            index,
+            flags: TileFlags::empty(),
+        }
+    }
+
+    /// Sets the desired flags of the tile.
+    pub fn with_flags(mut self, flags: TileFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    fn pack(tile: Option<Tile>) -> u32 {
+        match tile {
+            Some(tile) => {
+                (tile.index.min(Self::MAX_INDEX) + 1)
+                    | ((tile.flags.bits() as u32) << TILE_FLAGS_SHIFT)
+            }
+            None => 0,
+        }
+    }
+
+    fn unpack(packed: u32) -> Option<Tile> {
+        let index = packed & TILE_INDEX_MASK;
+        if index == 0 {
+            None
+        } else {
+            Some(Tile {
+                index: index - 1,
+                flags: TileFlags::from_bits_truncate((packed >> TILE_FLAGS_SHIFT) as u8),
+            })
+        }
+    }
+
+    /// Calculates texture coordinates for the given corner of the tile (`[0; 0]` - top-left corner,
+    /// `[1; 1]` - bottom-right corner), taking flip flags into account.
+    fn tex_coord(&self, uv_rect: &Rect<f32>, corner: Vector2<f32>) -> Vector2<f32> {
+        let mut uv = corner;
+        if self.flags.contains(TileFlags::FLIP_HORIZONTAL) {
+            uv.x = 1.0 - uv.x;
+        }
+        if self.flags.contains(TileFlags::FLIP_VERTICAL) {
+            uv.y = 1.0 - uv.y;
+        }
+        if self.flags.contains(TileFlags::FLIP_DIAGONAL) {
+            uv = Vector2::new(uv.y, uv.x);
+        }
+        uv_rect.position + uv_rect.size.component_mul(&uv)
+    }
+}
+
+/// Layer of a tile map is a rectangular grid of tiles. Layers are drawn in order, which means that
+/// tiles of the last layer will be drawn on top of tiles of every other layer.
+#[derive(Clone, Debug, Reflect)]
+pub struct TileMapLayer {
+    /// Name of the layer.
+    pub name: String,
+    /// Defines whether the layer should be drawn or not. Invisible layers are still used to generate
+    /// colliders.
+    pub visible: bool,
+    /// Color of the layer, it is multiplied with the color of tiles. Could be used to make the layer
+    /// semi-transparent.
+    pub color: Color,
+    #[reflect(hidden)]
+    size: Vector2<u32>,
+    #[reflect(hidden)]
+    tiles: Vec<u32>,
+    #[reflect(hidden)]
+    revision: u64,
+}
+
+impl PartialEq for TileMapLayer {
+    fn eq(&self, other: &Self) -> bool {
+        // Revision is intentionally ignored, it does not define the content of the layer.
+        self.name == other.name
+            && self.visible == other.visible
+            && self.color == other.color
+            && self.size == other.size
+            && self.tiles == other.tiles
+    }
+}
+
+impl TypeUuidProvider for TileMapLayer {
+    fn type_uuid() -> Uuid {
+        uuid!("5bdf6ab6-7262-4e4d-93a8-ca31e0ee5fc1")
+    }
+}
+
+impl Default for TileMapLayer {
+    fn default() -> Self {
+        Self {
+            name: "Layer".to_string(),
+            visible: true,
+            color: Color::WHITE,
+            size: Default::default(),
+            tiles: Default::default(),
+            revision: next_layer_revision(),
+        }
+    }
+}
+
+impl Visit for TileMapLayer {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        self.name.visit("Name", &mut region)?;
+        self.visible.visit("Visible", &mut region)?;
+        self.color.visit("Color", &mut region)?;
+        self.size.visit("Size", &mut region)?;
+        PodVecView::from_pod_vec(&mut self.tiles).visit("Tiles", &mut region)?;
+
+        if region.is_reading() {
+            // Protect from malformed data.
+            self.tiles
+                .resize((self.size.x * self.size.y) as usize, Default::default());
+            self.revision = next_layer_revision();
+        }
+
+        Ok(())
+    }
+}
+
+impl TileMapLayer {
+    /// Creates a new empty layer of the given size (in tiles).
+    pub fn new(name: &str, size: Vector2<u32>) -> Self {
+        Self {
+            name: name.to_owned(),
+            size,
+            tiles: vec![0; (size.x * size.y) as usize],
+            ..Default::default()
+        }
+    }
+
+    /// Returns size of the layer in tiles.
+    pub fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    /// Changes size of the layer. Tiles, that are inside the new bounds, are preserved.
+    pub fn resize(&mut self, new_size: Vector2<u32>) {
+        let mut tiles = vec![0; (new_size.x * new_size.y) as usize];
+        for y in 0..self.size.y.min(new_size.y) {
+            for x in 0..self.size.x.min(new_size.x) {
+                tiles[(y * new_size.x + x) as usize] = self.tiles[(y * self.size.x + x) as usize];
+            }
+        }
+        self.tiles = tiles;
+        self.size = new_size;
+        self.revision = next_layer_revision();
+    }
+
+    /// Returns a number, that changes every time the tiles of the layer are changed. Could be used to
+    /// check whether the data generated from the layer is still valid.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn tile_index(&self, position: Vector2<u32>) -> Option<usize> {
+        if position.x < self.size.x && position.y < self.size.y {
+            Some((position.y * self.size.x + position.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a tile at the given position. Position `[0; 0]` corresponds to the top-left corner of
+    /// the layer, X axis goes to the right, Y axis goes down.
+    pub fn tile(&self, position: Vector2<u32>) -> Option<Tile> {
+        self.tile_index(position)
+            .and_then(|i| Tile::unpack(self.tiles[i]))
+    }
+
+    /// Puts the tile at the given position (or removes a tile, if [`None`] is specified) and returns
+    /// the previous tile at the position. Does nothing if the position is out of bounds of the layer.
+    pub fn set_tile(&mut self, position: Vector2<u32>, tile: Option<Tile>) -> Option<Tile> {
+        let index = self.tile_index(position)?;
+        let packed = Tile::pack(tile);
+        let old = std::mem::replace(&mut self.tiles[index], packed);
+        if old != packed {
+            self.revision = next_layer_revision();
+        }
+        Tile::unpack(old)
+    }
+
+    /// Fills the entire layer with the given tile (or removes every tile, if [`None`] is specified).
+    pub fn fill(&mut self, tile: Option<Tile>) {
+        let packed = Tile::pack(tile);
+        for t in self.tiles.iter_mut() {
+            *t = packed;
+        }
+        self.revision = next_layer_revision();
+    }
+
+    fn build_geometry(
+        &self,
+        tile_set: &TileSet,
+        tile_size: Vector2<f32>,
+        depth: f32,
+    ) -> Option<SurfaceSharedData> {
+        let mut vertices = Vec::new();
+        let mut triangles = Vec::new();
+        for (position, tile) in self.iter() {
+            let Some(definition) = tile_set.tile(tile.index) else {
+                continue;
+            };
+
+            let first = vertices.len() as u32;
+            for corner in [
+                Vector2::new(1.0, 0.0),
+                Vector2::new(0.0, 0.0),
+                Vector2::new(0.0, 1.0),
+                Vector2::new(1.0, 1.0),
+            ] {
+                vertices.push(RectangleVertex {
+                    position: Vector3::new(
+                        -(position.x as f32 + corner.x) * tile_size.x,
+                        -(position.y as f32 + corner.y) * tile_size.y,
+                        depth,
+                    ),
+                    tex_coord: tile.tex_coord(&definition.uv_rect, corner),
+                    color: self.color,
+                });
+            }
+
+            triangles.push(TriangleDefinition([first, first + 1, first + 2]));
+            triangles.push(TriangleDefinition([first + 2, first + 3, first]));
+        }
+
+        if triangles.is_empty() {
+            return None;
+        }
+
+        let vertex_buffer = VertexBuffer::new(vertices.len(), vertices).ok()?;
+        Some(SurfaceSharedData::new(SurfaceData::new(
+            vertex_buffer,
+            TriangleBuffer::new(triangles),
+            true,
+        )))
+    }
+
+    /// Returns an iterator over every non-empty tile of the layer with its position.
+    pub fn iter(&self) -> impl Iterator<Item = (Vector2<u32>, Tile)> + '_ {
+        let width = self.size.x.max(1);
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, packed)| {
+                Tile::unpack(*packed)
+                    .map(|tile| (Vector2::new(i as u32 % width, i as u32 / width), tile))
+            })
+    }
+}
+
+/// Tile map data is a resource, that contains tile map layers along with a tile set. It is produced by
+/// importers of third-party tile map formats (such as Tiled `.tmx` files), use [`TileMapBuilder::with_data`]
+/// to create a tile map node from it.
+#[derive(Debug, Default, Clone, Visit, Reflect)]
+pub struct TileMapData {
+    /// A tile set, that is used by the tiles of the layers.
+    pub tile_set: Option<TileSetResource>,
+    /// Size of a tile in pixels.
+    pub tile_size: Vector2<u32>,
+    /// Layers of the tile map.
+    pub layers: Vec<TileMapLayer>,
+}
+
+impl ResourceData for TileMapData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn type_uuid(&self) -> Uuid {
+        <Self as TypeUuidProvider>::type_uuid()
+    }
+
+    fn save(&mut self, _path: &Path) -> Result<(), Box<dyn Error>> {
+        Err("Saving is not supported!".to_string().into())
+    }
+
+    fn can_be_saved(&self) -> bool {
+        false
+    }
+}
+
+impl TypeUuidProvider for TileMapData {
+    fn type_uuid() -> Uuid {
+        uuid!("8a3d1f5c-6b2e-4c7a-9e41-0d5f2b8c7a36")
+    }
+}
+
+/// Type alias for tile map data resources.
+pub type TileMapDataResource = Resource<TileMapData>;
+
+/// A set of parameters, that was used to generate the geometry of a layer.
+#[derive(Clone, Debug, PartialEq)]
+struct LayerGeometryKey {
+    revision: u64,
+    color: Color,
+    tile_size: Vector2<f32>,
+    tile_set: usize,
+}
+
+/// Cached geometry of a layer in local coordinates of a tile map. It is rebuilt only when the layer or
+/// the tile map is changed.
+#[derive(Clone, Debug, Default)]
+struct LayerGeometry {
+    key: Option<LayerGeometryKey>,
+    data: Option<SurfaceSharedData>,
+}
+
+/// A set of parameters, that defines the content of the colliders of a tile map.
+#[derive(Clone, Debug, PartialEq)]
+struct ColliderSourceKey {
+    layers: Vec<u64>,
+    tile_size: Vector2<f32>,
+    tile_set: Option<usize>,
+    generate_colliders: bool,
+}
+
+/// Tile map is a 2D node, that draws a set of layers of tiles. Each tile refers to a tile definition in
+/// a tile set, which defines a region of the tile set texture (atlas) and whether the tile is solid or
+/// not. Tile maps are the most efficient way of making levels for 2D games.
+///
+/// ## Coordinate system
+///
+/// Tile position `[0; 0]` corresponds to the top-left tile, X axis of the grid goes to the right, Y axis
+/// goes down (as in most of tile map editors). The top-left corner of the top-left tile is placed at the
+/// origin of the node. Keep in mind, that X axis of the engine's 2D coordinate system goes to the left,
+/// and Y axis goes up, so the tile at `[x; y]` occupies the region from `[-x * w; -y * h]` to
+/// `[-(x + 1) * w; -(y + 1) * h]` in local coordinates of the node, where `w` and `h` is the size of
+/// a tile (see [`Self::set_tile_size`]).
+///
+/// ## Rendering
+///
+/// Every visible layer is drawn using a single batch, that uses the material of the tile map. The texture
+/// of the tile set is applied to the `diffuseTexture` property of the material automatically, so in most
+/// cases the default material is enough.
+///
+/// ## Physics
+///
+/// Tile map could generate 2D colliders for solid tiles (see [`TileSet`] and [`TileFlags::SOLID`]). When
+/// [`Self::set_generate_colliders`] is enabled, [`TileMap::rebuild_colliders`] creates a static rigid body
+/// with a set of box colliders as a child node of the tile map. Adjacent solid tiles are merged into as
+/// few boxes as possible. Colliders are rebuilt automatically on the next update of the graph, every time
+/// the solid regions of the tile map are changed. Keep in mind, that colliders are not affected by the
+/// scale of the node, use [`Self::set_tile_size`] to change the size of the tiles instead.
+///
+/// ## Examples
+///
+/// The following example creates a tile map with a single layer with a floor made of solid tiles:
+///
+/// ```rust
+/// # use fyrox::{
+/// #     asset::untyped::ResourceKind,
+/// #     core::{algebra::Vector2, pool::Handle},
+/// #     resource::texture::TextureResource,
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         dim2::tilemap::{
+/// #             tileset::{TileSet, TileSetResource},
+/// #             Tile, TileFlags, TileMapBuilder, TileMapLayer,
+/// #         },
+/// #         graph::Graph,
+/// #         node::Node,
+/// #     },
+/// # };
+/// fn create_tile_map(atlas: TextureResource, graph: &mut Graph) -> Handle<Node> {
+///     // 256x256 atlas with 16x16 tiles.
+///     let tile_set = TileSetResource::new_ok(
+///         ResourceKind::Embedded,
+///         TileSet::from_atlas(
+///             Some(atlas),
+///             Vector2::new(256, 256),
+///             Vector2::new(16, 16),
+///             0,
+///             0,
+///         ),
+///     );
+///
+///     let mut layer = TileMapLayer::new("Ground", Vector2::new(32, 16));
+///     for x in 0..32 {
+///         layer.set_tile(
+///             Vector2::new(x, 15),
+///             Some(Tile::new(1).with_flags(TileFlags::SOLID)),
+///         );
+///     }
+///
+///     TileMapBuilder::new(BaseBuilder::new())
+///         .with_tile_set(tile_set)
+///         .with_layers(vec![layer])
+///         .with_generate_colliders(true)
+///         .build(graph)
+/// }
+/// ```
+///
+/// Tile maps made in [Tiled](https://www.mapeditor.org/) could be loaded using the resource manager,
+/// see [`TileMapData`] docs.
+#[derive(Debug, Visit, Clone, Reflect)]
+pub struct TileMap {
+    base: Base,
+
+    #[reflect(setter = "set_tile_set")]
+    tile_set: InheritableVariable<Option<TileSetResource>>,
+
+    #[reflect(setter = "set_tile_size")]
+    tile_size: InheritableVariable<Vector2<f32>>,
+
+    layers: InheritableVariable<Vec<TileMapLayer>>,
+
+    material: InheritableVariable<MaterialResource>,
+
+    #[reflect(setter = "set_generate_colliders")]
+    generate_colliders: InheritableVariable<bool>,
+
+    #[reflect(read_only)]
+    collider_body: InheritableVariable<Handle<Node>>,
+
+    #[reflect(setter = "set_sorting_order")]
+    sorting_order: InheritableVariable<SortingOrder>,
+
+    /// Hash of the solid regions, that were used to generate current colliders.
+    #[visit(optional)]
+    #[reflect(hidden)]
+    colliders_hash: u64,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    collider_source: Option<ColliderSourceKey>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    needs_collider_rebuild: bool,
+
+    /// Material and texture, that were synchronized last time.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    synced_texture: Option<(usize, Option<TextureResource>)>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    geometry_cache: RefCell<Vec<LayerGeometry>>,
+}
+
+impl Default for TileMap {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            tile_set: Default::default(),
+            tile_size: InheritableVariable::new_modified(Vector2::new(1.0, 1.0)),
+            layers: Default::default(),
+            material: InheritableVariable::new_modified(MaterialResource::new_ok(
+                Default::default(),
+                Material::standard_2d(),
+            )),
+            generate_colliders: Default::default(),
+            collider_body: Default::default(),
+            sorting_order: Default::default(),
+            colliders_hash: Default::default(),
+            collider_source: Default::default(),
+            needs_collider_rebuild: Default::default(),
+            synced_texture: Default::default(),
+            geometry_cache: Default::default(),
+        }
+    }
+}
+
+impl Deref for TileMap {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for TileMap {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for TileMap {
+    fn type_uuid() -> Uuid {
+        uuid!("e5c4a8f1-3b7d-4d92-8f0e-6a1c9b2d5e73")
+    }
+}
+
+impl TileMap {
+    /// Sets new tile set of the tile map.
+    pub fn set_tile_set(&mut self, tile_set: Option<TileSetResource>) -> Option<TileSetResource> {
+        self.tile_set.set_value_and_mark_modified(tile_set)
+    }
+
+    /// Returns current tile set of the tile map.
+    pub fn tile_set(&self) -> Option<&TileSetResource> {
+        self.tile_set.as_ref()
+    }
+
+    /// Sets new size of a tile in local coordinates of the node.
+    pub fn set_tile_size(&mut self, tile_size: Vector2<f32>) -> Vector2<f32> {
+        self.tile_size.set_value_and_mark_modified(tile_size)
+    }
+
+    /// Returns current size of a tile in local coordinates of the node.
+    pub fn tile_size(&self) -> Vector2<f32> {
+        *self.tile_size
+    }
+
+    /// Returns a reference to the layers of the tile map.
+    pub fn layers(&self) -> &[TileMapLayer] {
+        &self.layers
+    }
+
+    /// Returns a reference to the layers of the tile map, that could be used to modify the tiles.
+    pub fn layers_mut(&mut self) -> &mut Vec<TileMapLayer> {
+        self.layers.get_value_mut_and_mark_modified()
+    }
+
+    /// Returns a reference to the current material used by the tile map.
+    pub fn material(&self) -> &InheritableVariable<MaterialResource> {
+        &self.material
+    }
+
+    /// Returns a reference to the current material used by the tile map.
+    pub fn material_mut(&mut self) -> &mut InheritableVariable<MaterialResource> {
+        &mut self.material
+    }
+
+    /// Defines whether the tile map should have colliders for solid tiles or not. Colliders are rebuilt
+    /// on the next update of the graph.
+    pub fn set_generate_colliders(&mut self, generate: bool) -> bool {
+        self.generate_colliders
+            .set_value_and_mark_modified(generate)
+    }
+
+    /// Returns `true` if the tile map should have colliders for solid tiles, `false` - otherwise.
+    pub fn generate_colliders(&self) -> bool {
+        *self.generate_colliders
+    }
+
+    /// Returns a handle of the rigid body, that holds generated colliders of the tile map.
+    pub fn collider_body(&self) -> Handle<Node> {
+        *self.collider_body
+    }
+
+    /// Sets new sorting order of the tile map, that defines whether the tile map is drawn on top of other
+    /// 2D nodes or below them. Layers of the tile map are always drawn in their order. See [`SortingOrder`]
+    /// docs for more info.
+    pub fn set_sorting_order(&mut self, sorting_order: SortingOrder) -> SortingOrder {
+        self.sorting_order
+            .set_value_and_mark_modified(sorting_order)
+    }
+
+    /// Returns current sorting order of the tile map.
+    pub fn sorting_order(&self) -> SortingOrder {
+        *self.sorting_order
+    }
+
+    /// Returns size of the tile map in tiles, it is the size of the largest layer.
+    pub fn size(&self) -> Vector2<u32> {
+        self.layers.iter().fold(Vector2::default(), |size, layer| {
+            Vector2::new(size.x.max(layer.size.x), size.y.max(layer.size.y))
+        })
+    }
+
+    /// Converts a point in world coordinates to a position of a tile. The position could be out of bounds
+    /// of the tile map.
+    pub fn world_to_grid(&self, point: Vector3<f32>) -> Vector2<i32> {
+        let local = self
+            .global_transform()
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity)
+            .transform_point(&Point3::from(point));
+        Vector2::new(
+            (-local.x / self.tile_size.x).floor() as i32,
+            (-local.y / self.tile_size.y).floor() as i32,
+        )
+    }
+
+    fn is_solid(&self, tile_set: Option<&TileSet>, position: Vector2<u32>) -> bool {
+        self.layers.iter().any(|layer| {
+            layer.tile(position).is_some_and(|tile| {
+                tile.flags.contains(TileFlags::SOLID)
+                    || tile_set
+                        .and_then(|tile_set| tile_set.tile(tile.index))
+                        .is_some_and(|definition| definition.solid)
+            })
+        })
+    }
+
+    /// Returns a set of rectangular regions (in tiles), that are fully covered by solid tiles of any
+    /// layer. Adjacent solid tiles are merged in horizontal runs first and then runs of the same width
+    /// are merged vertically.
+    pub fn solid_regions(&self) -> Vec<Rect<u32>> {
+        let mut tile_set_state = self.tile_set.as_ref().map(|tile_set| tile_set.state());
+        let tile_set = tile_set_state
+            .as_mut()
+            .and_then(|state| state.data())
+            .map(|tile_set| &*tile_set);
+
+        let size = self.size();
+        let mut regions = Vec::<Rect<u32>>::new();
+        // Maps (x, width) of a run to a region that ends at the previous row.
+        let mut open_regions = FxHashMap::<(u32, u32), usize>::default();
+
+        for y in 0..size.y {
+            let mut new_open_regions = FxHashMap::default();
+            let mut x = 0;
+            while x < size.x {
+                if !self.is_solid(tile_set, Vector2::new(x, y)) {
+                    x += 1;
+                    continue;
+                }
+
+                let start = x;
+                while x < size.x && self.is_solid(tile_set, Vector2::new(x, y)) {
+                    x += 1;
+                }
+                let key = (start, x - start);
+
+                if let Some(&index) = open_regions.get(&key) {
+                    regions[index].size.y += 1;
+                    new_open_regions.insert(key, index);
+                } else {
+                    new_open_regions.insert(key, regions.len());
+                    regions.push(Rect::new(start, y, x - start, 1));
+                }
+            }
+            open_regions = new_open_regions;
+        }
+
+        regions
+    }
+
+    fn colliders_hash(&self, regions: &[Rect<u32>]) -> u64 {
+        let mut hasher = FxHasher::default();
+        hasher.write_u8(*self.generate_colliders as u8);
+        hasher.write_u32(self.tile_size.x.to_bits());
+        hasher.write_u32(self.tile_size.y.to_bits());
+        for region in regions {
+            hasher.write_u32(region.x());
+            hasher.write_u32(region.y());
+            hasher.write_u32(region.w());
+            hasher.write_u32(region.h());
+        }
+        hasher.finish()
+    }
+
+    fn collider_regions(&self) -> Vec<Rect<u32>> {
+        if *self.generate_colliders {
+            self.solid_regions()
+        } else {
+            Default::default()
+        }
+    }
+
+    /// Checks whether the content of the tile map was changed in a way, that affects its colliders.
+    /// Solid regions are recalculated only when the layers, the tile size or the tile set are changed.
+    fn update_collider_state(&mut self) {
+        let key = ColliderSourceKey {
+            layers: self.layers.iter().map(|layer| layer.revision).collect(),
+            tile_size: *self.tile_size,
+            tile_set: self.tile_set.as_ref().map(|tile_set| tile_set.key()),
+            generate_colliders: *self.generate_colliders,
+        };
+        if self.collider_source.as_ref() == Some(&key) {
+            return;
+        }
+
+        // Solidity of tiles is defined by the tile set, so wait until it is loaded.
+        if self
+            .tile_set
+            .as_ref()
+            .is_some_and(|tile_set| tile_set.is_loading())
+        {
+            return;
+        }
+
+        let hash = self.colliders_hash(&self.collider_regions());
+        self.needs_collider_rebuild = hash != self.colliders_hash;
+        self.collider_source = Some(key);
+    }
+
+    /// Returns `true` if the colliders of the tile map are out of sync with its solid tiles and will be
+    /// rebuilt on the next update of the graph.
+    pub fn needs_collider_rebuild(&self) -> bool {
+        self.needs_collider_rebuild
+    }
+
+    /// Removes previously generated colliders of the tile map with the given handle and generates new
+    /// ones, if [`Self::generate_colliders`] is enabled. The tile map requests a rebuild automatically on
+    /// its update when solid tiles are changed, see [`TileMap`] docs for more info.
+    pub fn rebuild_colliders(handle: Handle<Node>, graph: &mut Graph) {
+        let Some(tile_map) = graph.try_get_of_type::<TileMap>(handle) else {
+            return;
+        };
+
+        let old_body = *tile_map.collider_body;
+        let tile_size = *tile_map.tile_size;
+        let regions = tile_map.collider_regions();
+        let hash = tile_map.colliders_hash(&regions);
+
+        if graph.is_valid_handle(old_body) {
+            graph.remove_node(old_body);
+        }
+
+        let mut body = Handle::NONE;
+        if !regions.is_empty() {
+            let colliders = regions
+                .iter()
+                .map(|region| {
+                    let half_extents = Vector2::new(
+                        region.w() as f32 * tile_size.x * 0.5,
+                        region.h() as f32 * tile_size.y * 0.5,
+                    );
+                    let position = Vector3::new(
+                        -(region.x() as f32 * tile_size.x + half_extents.x),
+                        -(region.y() as f32 * tile_size.y + half_extents.y),
+                        0.0,
+                    );
+                    ColliderBuilder::new(
+                        BaseBuilder::new()
+                            .with_name("TileMapCollider")
+                            .with_local_transform(
+                                TransformBuilder::new()
+                                    .with_local_position(position)
+                                    .build(),
+                            ),
+                    )
+                    .with_shape(ColliderShape::cuboid(half_extents.x, half_extents.y))
+                    .build(graph)
+                })
+                .collect::<Vec<_>>();
+
+            body = RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_name("TileMapBody")
+                    .with_children(&colliders),
+            )
+            .with_body_type(RigidBodyType::Static)
+            .build(graph);
+
+            graph.link_nodes(body, handle);
+        }
+
+        if let Some(tile_map) = graph.try_get_mut_of_type::<TileMap>(handle) {
+            tile_map.collider_body.set_value_and_mark_modified(body);
+            tile_map.colliders_hash = hash;
+            tile_map.needs_collider_rebuild = false;
+        }
+    }
+}
+
+impl NodeTrait for TileMap {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let size = self.size();
+        AxisAlignedBoundingBox::from_points(&[
+            Vector3::default(),
+            Vector3::new(
+                -(size.x as f32) * self.tile_size.x,
+                -(size.y as f32) * self.tile_size.y,
+                -(self.layers.len() as f32) * LAYER_DEPTH_STEP,
+            ),
+        ])
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        self.update_collider_state();
+        if self.needs_collider_rebuild {
+            let handle = self.self_handle;
+            context.commands.push(Box::new(move |graph| {
+                TileMap::rebuild_colliders(handle, graph)
+            }));
+        }
+
+        // Keep the texture of the material in sync with the tile set. The material is locked only when
+        // the texture or the material is changed.
+        let Some(tile_set) = self.tile_set.as_ref() else {
+            return;
+        };
+        let texture = match tile_set.state().data() {
+            Some(tile_set) => tile_set.texture.clone(),
+            None => return,
+        };
+
+        let synced_texture = (self.material.key(), texture);
+        if self.synced_texture.as_ref() == Some(&synced_texture) {
+            return;
+        }
+
+        // This could fail only for custom materials without diffuseTexture property.
+        let _ = self.material.data_ref().set_texture(
+            &ImmutableString::new("diffuseTexture"),
+            synced_texture.1.clone(),
+        );
+        self.synced_texture = Some(synced_texture);
+    }
+
+    fn collect_render_data(&self, ctx: &mut RenderContext) {
+        if !self.global_visibility()
+            || !self.is_globally_enabled()
+            || !ctx.frustum.is_intersects_aabb(&self.world_bounding_box())
+        {
+            return;
+        }
+
+        if renderer::is_shadow_pass(ctx.render_pass_name) {
+            return;
+        }
+
+        let Some(tile_set) = self.tile_set.as_ref() else {
+            return;
+        };
+        let tile_set_key = tile_set.key();
+        // The tile set is locked only when some geometry needs to be rebuilt.
+        let mut tile_set_state = None;
+
+        let global_transform = self.global_transform();
+        let tile_size = *self.tile_size;
+
+        let mut geometry_cache = self.geometry_cache.borrow_mut();
+        geometry_cache.resize_with(self.layers.len(), Default::default);
+
+        for (layer_index, (layer, geometry)) in self
+            .layers
+            .iter()
+            .zip(geometry_cache.iter_mut())
+            .enumerate()
+        {
+            if !layer.visible {
+                continue;
+            }
+
+            let key = LayerGeometryKey {
+                revision: layer.revision,
+                color: layer.color,
+                tile_size,
+                tile_set: tile_set_key,
+            };
+            if geometry.key.as_ref() != Some(&key) {
+                let Some(tile_set) = tile_set_state
+                    .get_or_insert_with(|| tile_set.state())
+                    .data()
+                else {
+                    return;
+                };
+
+                // Next layers are closer to the camera.
+                let depth = -(layer_index as f32) * LAYER_DEPTH_STEP;
+                geometry.data = layer.build_geometry(tile_set, tile_size, depth);
+                geometry.key = Some(key);
+            }
+
+            if let Some(data) = geometry.data.as_ref() {
+                ctx.storage.push(
+                    data,
+                    &self.material,
+                    RenderPath::Forward,
+                    0,
+                    self.sorting_order.sort_index(),
+                    SurfaceInstanceData {
+                        world_transform: global_transform,
+                        bone_matrices: Default::default(),
+                        depth_offset: 0.0,
+                        blend_shapes_weights: Default::default(),
+                        element_range: ElementRange::Full,
+                        persistent_identifier: PersistentIdentifier::new_combined(
+                            data,
+                            self.self_handle,
+                            layer_index,
+                        ),
+                        node_handle: self.self_handle,
+                        allow_instancing: false,
+                        use_dual_quaternion_skinning: false,
+                        property_overrides: Default::default(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Allows you to create tile maps in declarative manner.
+pub struct TileMapBuilder {
+    base_builder: BaseBuilder,
+    tile_set: Option<TileSetResource>,
+    tile_size: Vector2<f32>,
+    layers: Vec<TileMapLayer>,
+    material: MaterialResource,
+    generate_colliders: bool,
+    sorting_order: SortingOrder,
+}
+
+impl TileMapBuilder {
+    /// Creates new tile map builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            tile_set: None,
+            tile_size: Vector2::new(1.0, 1.0),
+            layers: Default::default(),
+            material: MaterialResource::new_ok(Default::default(), Material::standard_2d()),
+            generate_colliders: false,
+            sorting_order: Default::default(),
+        }
+    }
+
+    /// Sets the desired tile set.
+    pub fn with_tile_set(mut self, tile_set: TileSetResource) -> Self {
+        self.tile_set = Some(tile_set);
+        self
+    }
+
+    /// Sets the desired size of a tile in local coordinates of the node.
+    pub fn with_tile_size(mut self, tile_size: Vector2<f32>) -> Self {
+        self.tile_size = tile_size;
+        self
+    }
+
+    /// Sets the desired layers.
+    pub fn with_layers(mut self, layers: Vec<TileMapLayer>) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    /// Sets the tile set and the layers from the given tile map data (for example, imported from a Tiled
+    /// map).
+    pub fn with_data(mut self, data: &TileMapData) -> Self {
+        self.tile_set = data.tile_set.clone();
+        self.layers = data.layers.clone();
+        self
+    }
+
+    /// Sets the desired material of the tile map.
+    pub fn with_material(mut self, material: MaterialResource) -> Self {
+        self.material = material;
+        self
+    }
+
+    /// Defines whether the tile map should have colliders for solid tiles or not.
+    pub fn with_generate_colliders(mut self, generate: bool) -> Self {
+        self.generate_colliders = generate;
+        self
+    }
+
+    /// Sets the desired sorting order. See [`TileMap::set_sorting_order`] for more info.
+    pub fn with_sorting_order(mut self, sorting_order: SortingOrder) -> Self {
+        self.sorting_order = sorting_order;
+        self
+    }
+
+    /// Creates new [`TileMap`] instance.
+    pub fn build_tile_map(self) -> TileMap {
+        TileMap {
+            base: self.base_builder.build_base(),
+            tile_set: self.tile_set.into(),
+            tile_size: self.tile_size.into(),
+            layers: self.layers.into(),
+            material: self.material.into(),
+            generate_colliders: self.generate_colliders.into(),
+            collider_body: Default::default(),
+            sorting_order: self.sorting_order.into(),
+            colliders_hash: Default::default(),
+            collider_source: Default::default(),
+            needs_collider_rebuild: Default::default(),
+            synced_texture: Default::default(),
+            geometry_cache: Default::default(),
+        }
+    }
+
+    /// Creates new [`TileMap`] instance.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_tile_map())
+    }
+
+    /// Creates new [`TileMap`] instance and adds it to the graph. Generates colliders for solid tiles, if
+    /// needed.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        let generate_colliders = self.generate_colliders;
+        let handle = graph.add_node(self.build_node());
+        if generate_colliders {
+            TileMap::rebuild_colliders(handle, graph);
+        }
+        handle
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::{
+            algebra::{Matrix4, Vector2},
+            math::Rect,
+            sstorage::ImmutableString,
+        },
+        renderer::batch::{ObserverInfo, RenderDataBatchStorage},
+        scene::{
+            base::BaseBuilder,
+            collider::BitMask,
+            dim2::{
+                collider::Collider,
+                tilemap::{
+                    tileset::{TileSet, TileSetResource},
+                    Tile, TileFlags, TileMap, TileMapBuilder, TileMapLayer,
+                },
+            },
+            graph::Graph,
+        },
+    };
+
+    fn collect_render_data(graph: &Graph) -> RenderDataBatchStorage {
+        RenderDataBatchStorage::from_graph(
+            graph,
+            ObserverInfo {
+                observer_position: Default::default(),
+                z_near: -100.0,
+                z_far: 100.0,
+                view_matrix: Matrix4::identity(),
+                projection_matrix: Matrix4::new_orthographic(
+                    -100.0, 100.0, -100.0, 100.0, -100.0, 100.0,
+                ),
+                render_mask: BitMask(u32::MAX),
+                lod_camera: Default::default(),
+            },
+            ImmutableString::new("Forward"),
+        )
+    }
+
+    #[test]
+    fn test_geometry_cache() {
+        let tile_set = TileSetResource::new_ok(
+            ResourceKind::Embedded,
+            TileSet::from_atlas(None, Vector2::new(4, 4), Vector2::new(1, 1), 0, 0),
+        );
+        let mut layer = TileMapLayer::new("Test", Vector2::new(4, 4));
+        layer.set_tile(Vector2::new(1, 1), Some(Tile::new(0)));
+
+        let mut graph = Graph::new();
+        let tile_map = TileMapBuilder::new(BaseBuilder::new())
+            .with_tile_set(tile_set)
+            .with_layers(vec![layer])
+            .build(&mut graph);
+
+        let first = collect_render_data(&graph);
+        let second = collect_render_data(&graph);
+        assert_eq!(first.batches.len(), 1);
+        assert_eq!(second.batches.len(), 1);
+        // The geometry must be reused, if nothing has changed.
+        assert_eq!(first.batches[0].data.key(), second.batches[0].data.key());
+
+        graph[tile_map].cast_mut::<TileMap>().unwrap().layers_mut()[0]
+            .set_tile(Vector2::new(2, 2), Some(Tile::new(1)));
+        let third = collect_render_data(&graph);
+        assert_eq!(third.batches.len(), 1);
+        assert_ne!(first.batches[0].data.key(), third.batches[0].data.key());
+        assert_eq!(third.batches[0].data.lock().vertex_buffer.vertex_count(), 8);
+    }
+
+    #[test]
+    fn test_layer_tiles() {
+        let mut layer = TileMapLayer::new("Test", Vector2::new(3, 2));
+        let tile = Tile::new(5).with_flags(TileFlags::FLIP_HORIZONTAL | TileFlags::SOLID);
+
+        assert_eq!(layer.set_tile(Vector2::new(2, 1), Some(tile)), None);
+        assert_eq!(layer.tile(Vector2::new(2, 1)), Some(tile));
+        assert_eq!(layer.set_tile(Vector2::new(3, 1), Some(tile)), None);
+        assert_eq!(layer.tile(Vector2::new(3, 1)), None);
+        assert_eq!(
+            layer.iter().collect::<Vec<_>>(),
+            vec![(Vector2::new(2, 1), tile)]
+        );
+
+        layer.resize(Vector2::new(2, 2));
+        assert_eq!(layer.iter().count(), 0);
+
+        layer.fill(Some(Tile::new(0)));
+        assert_eq!(layer.iter().count(), 4);
+        assert_eq!(layer.set_tile(Vector2::new(0, 0), None), Some(Tile::new(0)));
+    }
+
+    #[test]
+    fn test_collider_generation() {
+        let mut layer = TileMapLayer::new("Test", Vector2::new(4, 3));
+        let solid = Some(Tile::new(0).with_flags(TileFlags::SOLID));
+        // Two rows of solid tiles of the same width and a separate tile.
+        for x in 0..3 {
+            layer.set_tile(Vector2::new(x, 0), solid);
+            layer.set_tile(Vector2::new(x, 1), solid);
+        }
+        layer.set_tile(Vector2::new(3, 2), solid);
+        // Non-solid tile.
+        layer.set_tile(Vector2::new(0, 2), Some(Tile::new(0)));
+
+        let mut graph = Graph::new();
+        let tile_map = TileMapBuilder::new(BaseBuilder::new())
+            .with_layers(vec![layer])
+            .with_generate_colliders(true)
+            .build(&mut graph);
+
+        let tile_map_ref = graph[tile_map].query_component_ref::<TileMap>().unwrap();
+        assert_eq!(
+            tile_map_ref.solid_regions(),
+            vec![Rect::new(0, 0, 3, 2), Rect::new(3, 2, 1, 1)]
+        );
+
+        let body = tile_map_ref.collider_body();
+        assert!(graph.is_valid_handle(body));
+        assert_eq!(graph[body].parent(), tile_map);
+        assert_eq!(
+            graph[body]
+                .children()
+                .iter()
+                .filter(|c| graph[**c].query_component_ref::<Collider>().is_some())
+                .count(),
+            2
+        );
+
+        // Rebuilding must replace the old colliders.
+        TileMap::rebuild_colliders(tile_map, &mut graph);
+        assert!(!graph.is_valid_handle(body));
+
+        // Unchanged tile map must keep its colliders.
+        let body = graph[tile_map].cast::<TileMap>().unwrap().collider_body();
+        graph.update(Vector2::new(1.0, 1.0), 1.0 / 60.0, Default::default());
+        assert_eq!(
+            graph[tile_map].cast::<TileMap>().unwrap().collider_body(),
+            body
+        );
+
+        // Editing the tiles must rebuild the colliders on the next update.
+        graph[tile_map].cast_mut::<TileMap>().unwrap().layers_mut()[0]
+            .set_tile(Vector2::new(0, 2), solid);
+        graph.update(Vector2::new(1.0, 1.0), 1.0 / 60.0, Default::default());
+        let tile_map_ref = graph[tile_map].cast::<TileMap>().unwrap();
+        assert!(!tile_map_ref.needs_collider_rebuild());
+        assert!(!graph.is_valid_handle(body));
+        assert_eq!(
+            graph[tile_map_ref.collider_body()]
+                .children()
+                .iter()
+                .filter(|c| graph[**c].query_component_ref::<Collider>().is_some())
+                .count(),
+            3
+        );
+    }
+}
@@ -0,0 +1,415 @@
+//! Importers for [Tiled](https://www.mapeditor.org/) tile maps (`.tmx`) and tile sets (`.tsx`).
+//!
+//! Only orthogonal, finite maps are supported. Tile layers could use XML, CSV or Base64 (uncompressed
+//! or zlib-compressed) encoding. Tile sets must be based on a single image (atlas). A tile is considered
+//! solid, if it has a boolean `solid` (or `collision`) custom property set to `true` or if it has at
+//! least one collision shape defined in Tiled's collision editor.
+
+use crate::{
+    asset::{io::ResourceIo, manager::ResourceManager, untyped::ResourceKind},
+    core::{algebra::Vector2, color::Color, io::FileLoadError, log::Log},
+    resource::texture::Texture,
+    scene::dim2::tilemap::{
+        tileset::{TileSet, TileSetResource},
+        Tile, TileFlags, TileMapData, TileMapLayer,
+    },
+};
+use base64::Engine;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+    str::FromStr,
+};
+
+const FLIPPED_HORIZONTALLY_FLAG: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY_FLAG: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY_FLAG: u32 = 0x2000_0000;
+const GID_MASK: u32 = 0x0FFF_FFFF;
+
+/// An error that may occur during Tiled files import.
+#[derive(Debug)]
+pub enum TiledError {
+    /// An i/o error has occurred.
+    Io(FileLoadError),
+    /// The file is not a valid XML document.
+    Xml(roxmltree::Error),
+    /// The file has invalid or unsupported content.
+    Format(String),
+}
+
+impl Display for TiledError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TiledError::Io(v) => {
+                write!(f, "A file load error has occurred {v:?}")
+            }
+            TiledError::Xml(v) => {
+                write!(f, "Unable to parse XML. Reason: {v}")
+            }
+            TiledError::Format(v) => {
+                write!(f, "Invalid or unsupported Tiled file: {v}")
+            }
+        }
+    }
+}
+
+impl From<FileLoadError> for TiledError {
+    fn from(e: FileLoadError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<roxmltree::Error> for TiledError {
+    fn from(e: roxmltree::Error) -> Self {
+        Self::Xml(e)
+    }
+}
+
+fn attribute<T: FromStr>(node: roxmltree::Node, name: &str) -> Result<Option<T>, TiledError> {
+    match node.attribute(name) {
+        Some(value) => value.trim().parse::<T>().map(Some).map_err(|_| {
+            TiledError::Format(format!(
+                "Invalid value {value} of {name} attribute of <{}>",
+                node.tag_name().name()
+            ))
+        }),
+        None => Ok(None),
+    }
+}
+
+fn required_attribute<T: FromStr>(node: roxmltree::Node, name: &str) -> Result<T, TiledError> {
+    attribute(node, name)?.ok_or_else(|| {
+        TiledError::Format(format!(
+            "Missing {name} attribute of <{}>",
+            node.tag_name().name()
+        ))
+    })
+}
+
+fn is_solid_tile(tile: roxmltree::Node) -> bool {
+    let has_solid_property = tile
+        .children()
+        .filter(|n| n.has_tag_name("properties"))
+        .flat_map(|n| n.children())
+        .filter(|n| n.has_tag_name("property"))
+        .any(|property| {
+            matches!(property.attribute("name"), Some("solid" | "collision"))
+                && property.attribute("value") == Some("true")
+        });
+
+    let has_collision_shapes = tile
+        .children()
+        .filter(|n| n.has_tag_name("objectgroup"))
+        .any(|group| group.children().any(|n| n.has_tag_name("object")));
+
+    has_solid_property || has_collision_shapes
+}
+
+fn parse_tile_set(
+    node: roxmltree::Node,
+    base_dir: &Path,
+    resource_manager: &ResourceManager,
+) -> Result<TileSet, TiledError> {
+    let tile_size = Vector2::new(
+        required_attribute::<u32>(node, "tilewidth")?,
+        required_attribute::<u32>(node, "tileheight")?,
+    );
+    let margin = attribute::<u32>(node, "margin")?.unwrap_or_default();
+    let spacing = attribute::<u32>(node, "spacing")?.unwrap_or_default();
+
+    let image = node
+        .children()
+        .find(|n| n.has_tag_name("image"))
+        .ok_or_else(|| {
+            TiledError::Format(
+                "Tile sets based on a collection of images are not supported!".to_string(),
+            )
+        })?;
+    let image_size = Vector2::new(
+        required_attribute::<u32>(image, "width")?,
+        required_attribute::<u32>(image, "height")?,
+    );
+    let texture = resource_manager
+        .request::<Texture>(base_dir.join(required_attribute::<String>(image, "source")?));
+
+    let mut tile_set = TileSet::from_atlas(Some(texture), image_size, tile_size, margin, spacing);
+
+    if let Some(tile_count) = attribute::<usize>(node, "tilecount")? {
+        tile_set.tiles.truncate(tile_count);
+    }
+
+    for tile in node.children().filter(|n| n.has_tag_name("tile")) {
+        let id = required_attribute::<u32>(tile, "id")?;
+        if let Some(definition) = tile_set.tiles.get_mut(id as usize) {
+            definition.solid = is_solid_tile(tile);
+        }
+    }
+
+    Ok(tile_set)
+}
+
+/// Returns the size of a layer and the total number of tiles in it. Fails if the number of tiles does not
+/// fit in `u32`.
+fn parse_layer_size(layer: roxmltree::Node) -> Result<(Vector2<u32>, usize), TiledError> {
+    let size = Vector2::new(
+        required_attribute::<u32>(layer, "width")?,
+        required_attribute::<u32>(layer, "height")?,
+    );
+    let count = size.x.checked_mul(size.y).ok_or_else(|| {
+        TiledError::Format(format!("Layer size {}x{} is too large", size.x, size.y))
+    })?;
+    Ok((size, count as usize))
+}
+
+fn parse_layer_data(data: roxmltree::Node, size: usize) -> Result<Vec<u32>, TiledError> {
+    let gids = match data.attribute("encoding") {
+        None => data
+            .children()
+            .filter(|n| n.has_tag_name("tile"))
+            .map(|tile| attribute::<u32>(tile, "gid").map(|gid| gid.unwrap_or_default()))
+            .collect::<Result<Vec<_>, _>>()?,
+        Some("csv") => data
+            .text()
+            .unwrap_or_default()
+            .split(',')
+            .map(|gid| {
+                gid.trim().parse::<u32>().map_err(|_| {
+                    TiledError::Format(format!("Invalid tile {} in CSV layer data", gid.trim()))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        Some("base64") => {
+            let text = data
+                .text()
+                .unwrap_or_default()
+                .split_whitespace()
+                .collect::<String>();
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(text)
+                .map_err(|e| TiledError::Format(format!("Invalid Base64 layer data: {e}")))?;
+            let bytes = match data.attribute("compression") {
+                None => bytes,
+                Some("zlib") => inflate::inflate_bytes_zlib(&bytes).map_err(TiledError::Format)?,
+                Some(compression) => {
+                    return Err(TiledError::Format(format!(
+                        "Unsupported layer data compression {compression}"
+                    )))
+                }
+            };
+            bytes
+                .chunks_exact(4)
+                .map(|gid| u32::from_le_bytes([gid[0], gid[1], gid[2], gid[3]]))
+                .collect()
+        }
+        Some(encoding) => {
+            return Err(TiledError::Format(format!(
+                "Unsupported layer data encoding {encoding}"
+            )))
+        }
+    };
+
+    if gids.len() != size {
+        return Err(TiledError::Format(format!(
+            "Layer data has {} tiles, but {size} is expected",
+            gids.len()
+        )));
+    }
+
+    Ok(gids)
+}
+
+fn gid_to_tile(gid: u32, first_gid: u32, tile_count: u32) -> Option<Tile> {
+    let id = gid & GID_MASK;
+    if id < first_gid || id - first_gid >= tile_count {
+        return None;
+    }
+
+    let mut flags = TileFlags::empty();
+    flags.set(
+        TileFlags::FLIP_HORIZONTAL,
+        gid & FLIPPED_HORIZONTALLY_FLAG != 0,
+    );
+    flags.set(TileFlags::FLIP_VERTICAL, gid & FLIPPED_VERTICALLY_FLAG != 0);
+    flags.set(TileFlags::FLIP_DIAGONAL, gid & FLIPPED_DIAGONALLY_FLAG != 0);
+
+    Some(Tile::new(id - first_gid).with_flags(flags))
+}
+
+/// Imports a tile set from the given Tiled tile set (`.tsx`) file. The texture of the tile set is
+/// requested from the resource manager.
+pub async fn load_tsx(
+    path: &Path,
+    io: &dyn ResourceIo,
+    resource_manager: &ResourceManager,
+) -> Result<TileSet, TiledError> {
+    let bytes = io.load_file(path).await?;
+    let text = String::from_utf8_lossy(&bytes);
+    let document = roxmltree::Document::parse(&text)?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    parse_tile_set(document.root_element(), base_dir, resource_manager)
+}
+
+/// Imports a tile map from the given Tiled map (`.tmx`) file. External tile sets are requested from
+/// the resource manager, embedded tile sets are converted to embedded resources.
+///
+/// Tile map node supports only one tile set, so only the first tile set of the map is used. Tiles that
+/// use other tile sets are ignored (with a warning).
+pub async fn load_tmx(
+    path: &Path,
+    io: &dyn ResourceIo,
+    resource_manager: &ResourceManager,
+) -> Result<TileMapData, TiledError> {
+    let bytes = io.load_file(path).await?;
+    let text = String::from_utf8_lossy(&bytes);
+    let document = roxmltree::Document::parse(&text)?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let map = document.root_element();
+    if !map.has_tag_name("map") {
+        return Err(TiledError::Format("<map> element is expected".to_string()));
+    }
+    if let Some(orientation) = map.attribute("orientation") {
+        if orientation != "orthogonal" {
+            return Err(TiledError::Format(format!(
+                "Unsupported map orientation {orientation}"
+            )));
+        }
+    }
+    if map.attribute("infinite") == Some("1") {
+        return Err(TiledError::Format(
+            "Infinite maps are not supported!".to_string(),
+        ));
+    }
+
+    let tile_size = Vector2::new(
+        required_attribute::<u32>(map, "tilewidth")?,
+        required_attribute::<u32>(map, "tileheight")?,
+    );
+
+    let mut tile_sets = map
+        .children()
+        .filter(|n| n.has_tag_name("tileset"))
+        .map(|n| Ok((required_attribute::<u32>(n, "firstgid")?, n)))
+        .collect::<Result<Vec<_>, TiledError>>()?;
+    tile_sets.sort_by_key(|(first_gid, _)| *first_gid);
+
+    let (tile_set, first_gid, tile_count) = match tile_sets.first() {
+        Some(&(first_gid, node)) => {
+            // Tiles of the first tile set end where the next tile set begins.
+            let tile_count = tile_sets
+                .get(1)
+                .map(|(next_first_gid, _)| next_first_gid - first_gid)
+                .unwrap_or(GID_MASK);
+            let tile_set = match node.attribute("source") {
+                Some(source) => resource_manager.request::<TileSet>(base_dir.join(source)),
+                None => TileSetResource::new_ok(
+                    ResourceKind::Embedded,
+                    parse_tile_set(node, base_dir, resource_manager)?,
+                ),
+            };
+            (Some(tile_set), first_gid, tile_count)
+        }
+        None => (None, 1, 0),
+    };
+
+    let mut layers = Vec::new();
+    let mut ignored_tiles = 0;
+    for layer_node in map.descendants().filter(|n| n.has_tag_name("layer")) {
+        let (size, tile_count_in_layer) = parse_layer_size(layer_node)?;
+        let mut layer = TileMapLayer::new(layer_node.attribute("name").unwrap_or("Layer"), size);
+        layer.visible = attribute::<u8>(layer_node, "visible")?.unwrap_or(1) != 0;
+        let opacity = attribute::<f32>(layer_node, "opacity")?.unwrap_or(1.0);
+        layer.color = Color::WHITE.with_new_alpha((opacity.clamp(0.0, 1.0) * 255.0) as u8);
+
+        if let Some(data) = layer_node.children().find(|n| n.has_tag_name("data")) {
+            let gids = parse_layer_data(data, tile_count_in_layer)?;
+            for (i, gid) in gids.into_iter().enumerate() {
+                if gid & GID_MASK == 0 {
+                    continue;
+                }
+                let position = Vector2::new(i as u32 % size.x, i as u32 / size.x);
+                match gid_to_tile(gid, first_gid, tile_count) {
+                    Some(tile) => {
+                        layer.set_tile(position, Some(tile));
+                    }
+                    None => ignored_tiles += 1,
+                }
+            }
+        }
+
+        layers.push(layer);
+    }
+
+    if ignored_tiles > 0 {
+        Log::warn(format!(
+            "{} tiles of {} map use tile sets other than the first one and were ignored.",
+            ignored_tiles,
+            path.display()
+        ));
+    }
+
+    Ok(TileMapData {
+        tile_set,
+        tile_size,
+        layers,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scene::dim2::tilemap::{
+        tiled::{gid_to_tile, parse_layer_data, parse_layer_size},
+        Tile, TileFlags,
+    };
+
+    #[test]
+    fn test_gid_conversion() {
+        assert_eq!(gid_to_tile(1, 1, 10), Some(Tile::new(0)));
+        assert_eq!(gid_to_tile(11, 1, 10), None);
+        assert_eq!(
+            gid_to_tile(0x8000_0000 | 0x2000_0000 | 3, 1, 10),
+            Some(Tile::new(2).with_flags(TileFlags::FLIP_HORIZONTAL | TileFlags::FLIP_DIAGONAL))
+        );
+    }
+
+    #[test]
+    fn test_layer_data_decoding() {
+        let csv = r#"<data encoding="csv">1,0,
+2,3</data>"#;
+        let document = roxmltree::Document::parse(csv).unwrap();
+        assert_eq!(
+            parse_layer_data(document.root_element(), 4).unwrap(),
+            vec![1, 0, 2, 3]
+        );
+
+        // The same data in Base64 (little-endian u32 values).
+        let base64 = r#"<data encoding="base64">
+   AQAAAAAAAAACAAAAAwAAAA==
+</data>"#;
+        let document = roxmltree::Document::parse(base64).unwrap();
+        assert_eq!(
+            parse_layer_data(document.root_element(), 4).unwrap(),
+            vec![1, 0, 2, 3]
+        );
+
+        let xml = r#"<data><tile gid="1"/><tile/><tile gid="2"/><tile gid="3"/></data>"#;
+        let document = roxmltree::Document::parse(xml).unwrap();
+        assert_eq!(
+            parse_layer_data(document.root_element(), 4).unwrap(),
+            vec![1, 0, 2, 3]
+        );
+
+        assert!(parse_layer_data(document.root_element(), 5).is_err());
+    }
+
+    #[test]
+    fn test_layer_size() {
+        let document = roxmltree::Document::parse(r#"<layer width="4" height="3"/>"#).unwrap();
+        let (size, count) = parse_layer_size(document.root_element()).unwrap();
+        assert_eq!((size.x, size.y, count), (4, 3, 12));
+
+        let document =
+            roxmltree::Document::parse(r#"<layer width="4294967295" height="2"/>"#).unwrap();
+        assert!(parse_layer_size(document.root_element()).is_err());
+    }
+}
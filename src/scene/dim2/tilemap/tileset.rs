@@ -0,0 +1,183 @@
+//! Tile set is a resource, that contains definitions of tiles that could be used in a tile map. See
+//! [`TileSet`] docs for more info.
+
+use crate::{
+    asset::{io::ResourceIo, Resource, ResourceData},
+    core::{
+        algebra::Vector2,
+        io::FileLoadError,
+        math::Rect,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    resource::texture::TextureResource,
+};
+use std::{
+    any::Any,
+    error::Error,
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// An error that may occur during tile set resource loading.
+#[derive(Debug)]
+pub enum TileSetError {
+    /// An i/o error has occurred.
+    Io(FileLoadError),
+
+    /// An error that may occur due to version incompatibilities.
+    Visit(VisitError),
+}
+
+impl Display for TileSetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TileSetError::Io(v) => {
+                write!(f, "A file load error has occurred {v:?}")
+            }
+            TileSetError::Visit(v) => {
+                write!(
+                    f,
+                    "An error that may occur due to version incompatibilities. {v:?}"
+                )
+            }
+        }
+    }
+}
+
+impl From<FileLoadError> for TileSetError {
+    fn from(e: FileLoadError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<VisitError> for TileSetError {
+    fn from(e: VisitError) -> Self {
+        Self::Visit(e)
+    }
+}
+
+/// Definition of a single tile of a tile set.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct TileDefinition {
+    /// A region of the tile set texture, that is used by the tile. The coordinates are normalized
+    /// which means `[0; 0]` corresponds to top-left corner of the texture and `[1; 1]` corresponds
+    /// to right-bottom corner.
+    pub uv_rect: Rect<f32>,
+    /// Defines whether the tile is solid or not. Solid tiles are used to generate colliders of tile
+    /// maps.
+    pub solid: bool,
+}
+
+impl Default for TileDefinition {
+    fn default() -> Self {
+        Self {
+            uv_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
+            solid: false,
+        }
+    }
+}
+
+/// Tile set is a set of tile definitions, that share the same texture (atlas). Tile maps refer to
+/// the tiles of a tile set by their indices.
+///
+/// Tile sets could be created from code (see [`TileSet::from_atlas`]), loaded from native `.tileset`
+/// files or imported from Tiled `.tsx` files.
+#[derive(Debug, Default, Clone, Visit, Reflect)]
+pub struct TileSet {
+    /// A texture (atlas), that contains images of every tile of the tile set.
+    pub texture: Option<TextureResource>,
+    /// Definitions of the tiles.
+    pub tiles: Vec<TileDefinition>,
+}
+
+impl ResourceData for TileSet {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn type_uuid(&self) -> Uuid {
+        <Self as TypeUuidProvider>::type_uuid()
+    }
+
+    fn save(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut visitor = Visitor::new();
+        self.visit("TileSet", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
+    }
+
+    fn can_be_saved(&self) -> bool {
+        true
+    }
+}
+
+impl TypeUuidProvider for TileSet {
+    fn type_uuid() -> Uuid {
+        uuid!("2c2e7b3e-5a52-4e8f-9d3a-41c6b6e3f0a7")
+    }
+}
+
+impl TileSet {
+    /// Creates a new tile set from a texture atlas, where tiles are arranged in a regular grid. All
+    /// sizes are defined in pixels. `margin` is an offset of the first tile from the borders of
+    /// the atlas, `spacing` is a gap between adjacent tiles. Tiles are numbered from left to right
+    /// and from top to bottom.
+    pub fn from_atlas(
+        texture: Option<TextureResource>,
+        atlas_size: Vector2<u32>,
+        tile_size: Vector2<u32>,
+        margin: u32,
+        spacing: u32,
+    ) -> Self {
+        let mut tiles = Vec::new();
+
+        if atlas_size.x > 0 && atlas_size.y > 0 && tile_size.x > 0 && tile_size.y > 0 {
+            let columns =
+                (atlas_size.x.saturating_sub(2 * margin) + spacing) / (tile_size.x + spacing);
+            let rows =
+                (atlas_size.y.saturating_sub(2 * margin) + spacing) / (tile_size.y + spacing);
+
+            for row in 0..rows {
+                for column in 0..columns {
+                    let x = margin + column * (tile_size.x + spacing);
+                    let y = margin + row * (tile_size.y + spacing);
+                    tiles.push(TileDefinition {
+                        uv_rect: Rect::new(
+                            x as f32 / atlas_size.x as f32,
+                            y as f32 / atlas_size.y as f32,
+                            tile_size.x as f32 / atlas_size.x as f32,
+                            tile_size.y as f32 / atlas_size.y as f32,
+                        ),
+                        solid: false,
+                    });
+                }
+            }
+        }
+
+        Self { texture, tiles }
+    }
+
+    /// Returns a definition of the tile with the given index.
+    pub fn tile(&self, index: u32) -> Option<&TileDefinition> {
+        self.tiles.get(index as usize)
+    }
+
+    /// Loads a tile set from a native `.tileset` file.
+    pub async fn from_file(path: &Path, io: &dyn ResourceIo) -> Result<Self, TileSetError> {
+        let bytes = io.load_file(path).await?;
+        let mut visitor = Visitor::load_from_memory(&bytes)?;
+        let mut tile_set = TileSet::default();
+        tile_set.visit("TileSet", &mut visitor)?;
+        Ok(tile_set)
+    }
+}
+
+/// Type alias for tile set resources.
+pub type TileSetResource = Resource<TileSet>;
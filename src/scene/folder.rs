@@ -0,0 +1,300 @@
+//! Folder is an organizational node, that is used to structure scene hierarchy. See [`Folder`] docs
+//! for more info.
+
+use crate::{
+    core::{
+        algebra::Vector3,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        node::{Node, NodeTrait},
+    },
+};
+use std::ops::{Deref, DerefMut};
+
+/// Folder is an organizational node, that is used to group other nodes (for example, all the props
+/// of a room, or all the lights of a level). Unlike [`crate::scene::pivot::Pivot`], folder does not
+/// participate in transform calculations - its local transform is ignored, so its children are
+/// positioned relative to the parent of the folder, and moving a node into a folder (or out of it)
+/// does not change its world position. Transform changes of folders are not synchronized with
+/// physics, sound, etc.
+///
+/// Visibility and enabled state of folders are inherited by their descendants as usual, so the whole
+/// group could be hidden or disabled using [`Base::set_visibility`] and [`Base::set_enabled`]. Whole
+/// groups could be moved using [`Folder::translate_contents`].
+///
+/// Folders could be locked, locked folders ignore [`Folder::translate_contents`] requests and their
+/// contents could not be picked in the editor. Use [`Folder::is_node_locked`] to check whether a node
+/// is in a locked folder.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{
+///         base::BaseBuilder,
+///         folder::{Folder, FolderBuilder},
+///         graph::Graph,
+///         node::Node,
+///     },
+/// };
+///
+/// fn make_room(graph: &mut Graph, props: &[Handle<Node>]) -> Handle<Node> {
+///     let room = FolderBuilder::new(BaseBuilder::new().with_name("Room").with_children(props))
+///         .build(graph);
+///
+///     // Move every prop of the room one meter up.
+///     Folder::translate_contents(graph, room, Vector3::new(0.0, 1.0, 0.0));
+///
+///     // Prevent accidental changes.
+///     graph[room].cast_mut::<Folder>().unwrap().set_locked(true);
+///
+///     room
+/// }
+/// ```
+#[derive(Clone, Reflect, Visit, Debug)]
+pub struct Folder {
+    base: Base,
+
+    #[reflect(setter = "set_locked")]
+    locked: InheritableVariable<bool>,
+}
+
+impl Default for Folder {
+    fn default() -> Self {
+        let mut base = Base::default();
+        base.is_folder = true;
+        Self {
+            base,
+            locked: Default::default(),
+        }
+    }
+}
+
+impl Deref for Folder {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Folder {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Folder {
+    fn type_uuid() -> Uuid {
+        uuid!("5f0e8c2a-7d41-4b96-a3e5-c81d2b7f6a09")
+    }
+}
+
+impl Folder {
+    /// Locks or unlocks the folder. Returns previous value.
+    pub fn set_locked(&mut self, locked: bool) -> bool {
+        self.locked.set_value_and_mark_modified(locked)
+    }
+
+    /// Returns `true` if the folder is locked, `false` - otherwise.
+    pub fn is_locked(&self) -> bool {
+        *self.locked
+    }
+
+    /// Returns `true` if the given node is a locked folder or it is a descendant of a locked folder.
+    pub fn is_node_locked(graph: &Graph, node: Handle<Node>) -> bool {
+        let mut handle = node;
+        while let Some(node) = graph.try_get(handle) {
+            if node
+                .cast::<Folder>()
+                .is_some_and(|folder| folder.is_locked())
+            {
+                return true;
+            }
+            handle = node.parent();
+        }
+        false
+    }
+
+    /// Returns contents of the given folder - its children, children of nested folders are returned
+    /// instead of the nested folders.
+    pub fn contents(graph: &Graph, folder: Handle<Node>) -> Vec<Handle<Node>> {
+        let mut contents = Vec::new();
+        let mut stack = vec![folder];
+        while let Some(handle) = stack.pop() {
+            let Some(node) = graph.try_get(handle) else {
+                continue;
+            };
+            for &child in node.children() {
+                if graph[child].cast::<Folder>().is_some() {
+                    stack.push(child);
+                } else {
+                    contents.push(child);
+                }
+            }
+        }
+        contents
+    }
+
+    /// Moves contents of the given folder by the given offset (defined in the space of the parent of
+    /// the folder). Returns `false` if the handle does not point to a folder or the folder is locked.
+    pub fn translate_contents(
+        graph: &mut Graph,
+        folder: Handle<Node>,
+        offset: Vector3<f32>,
+    ) -> bool {
+        if graph.try_get_of_type::<Folder>(folder).is_none() || Self::is_node_locked(graph, folder)
+        {
+            return false;
+        }
+
+        for node in Self::contents(graph, folder) {
+            let transform = graph[node].local_transform_mut();
+            let position = **transform.position();
+            transform.set_position(position + offset);
+        }
+
+        true
+    }
+}
+
+impl NodeTrait for Folder {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
+
+/// Allows you to create folder node in declarative manner.
+pub struct FolderBuilder {
+    base_builder: BaseBuilder,
+    locked: bool,
+}
+
+impl FolderBuilder {
+    /// Creates new folder builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            locked: false,
+        }
+    }
+
+    /// Sets whether the folder is locked or not.
+    pub fn with_locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Creates new folder node.
+    pub fn build_node(self) -> Node {
+        let mut base = self.base_builder.build_base();
+        base.is_folder = true;
+        Node::new(Folder {
+            base,
+            locked: self.locked.into(),
+        })
+    }
+
+    /// Creates new folder node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        scene::{
+            base::BaseBuilder,
+            folder::{Folder, FolderBuilder},
+            graph::Graph,
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_folder_transform() {
+        let mut graph = Graph::new();
+        let parent = PivotBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(1.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .build(&mut graph);
+        let child = PivotBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 2.0, 0.0))
+                    .build(),
+            ),
+        )
+        .build(&mut graph);
+        // Transform of the folder must be ignored.
+        let folder = FolderBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[child])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(10.0, 10.0, 10.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+        graph.link_nodes(folder, parent);
+
+        graph.update_hierarchical_data();
+        assert_eq!(graph[child].global_position(), Vector3::new(1.0, 2.0, 0.0));
+
+        assert!(Folder::translate_contents(
+            &mut graph,
+            folder,
+            Vector3::new(0.0, 0.0, 3.0)
+        ));
+        graph.update_hierarchical_data();
+        assert_eq!(graph[child].global_position(), Vector3::new(1.0, 2.0, 3.0));
+
+        graph[folder].cast_mut::<Folder>().unwrap().set_locked(true);
+        assert!(Folder::is_node_locked(&graph, child));
+        assert!(!Folder::translate_contents(
+            &mut graph,
+            folder,
+            Vector3::new(0.0, 0.0, 3.0)
+        ));
+    }
+
+    #[test]
+    fn test_folder_flag() {
+        // Deserialized folders are created using `Default`.
+        assert!(Folder::default().is_folder);
+        assert!(
+            FolderBuilder::new(BaseBuilder::new())
+                .build_node()
+                .is_folder
+        );
+        assert!(!PivotBuilder::new(BaseBuilder::new()).build_node().is_folder);
+    }
+}
@@ -0,0 +1,523 @@
+//! Graph diff and patch. See [`GraphPatch`] docs for more info.
+
+use crate::{
+    asset::untyped::UntypedResource,
+    core::{
+        pool::Handle,
+        reflect::{is_path_to_array_element, prelude::*},
+    },
+    scene::{
+        base::NodeScriptMessage,
+        graph::{clear_links, event::GraphEvent, Graph},
+        node::Node,
+    },
+};
+use fxhash::FxHashSet;
+use std::{
+    any::TypeId,
+    fmt::{Display, Formatter},
+};
+
+/// A new value of a single property of a node.
+#[derive(Debug)]
+pub struct PropertyChange {
+    /// Path to the property (see [`ResolvePath`] for the format). The path always points to an
+    /// [`InheritableVariable`](crate::core::variable::InheritableVariable).
+    pub path: String,
+    /// New value of the property.
+    pub value: Box<dyn Reflect>,
+}
+
+/// A node that was added to a graph.
+#[derive(Debug)]
+pub struct AddedNode {
+    /// Handle of the node. The node will be put at the exact same handle when the patch is applied,
+    /// so other nodes (and the patch itself) could reference it.
+    pub handle: Handle<Node>,
+    /// Handle of the parent node.
+    pub parent: Handle<Node>,
+    /// A copy of the node without links to parent and children.
+    pub node: Node,
+}
+
+/// A set of changes of an existing node.
+#[derive(Debug)]
+pub struct ModifiedNode {
+    /// Handle of the node.
+    pub handle: Handle<Node>,
+    /// New parent of the node, `None` if the node was not moved in the hierarchy.
+    pub new_parent: Option<Handle<Node>>,
+    /// New name of the node, `None` if the name was not changed. Names are not inheritable, so
+    /// they're stored separately from other properties.
+    pub new_name: Option<String>,
+    /// Changed properties of the node.
+    pub properties: Vec<PropertyChange>,
+}
+
+/// A structured set of changes between two graphs, produced by [`Graph::diff`] and applied using
+/// [`Graph::apply_patch`]. Nodes of the graphs are matched by their handles and instance ids (see
+/// [`crate::scene::base::Base::instance_id`]), which means that the diff is meaningful only for
+/// graphs with the same "origin" - for example, two instances of the same scene (loaded from the
+/// same file) on different machines, or a graph and its saved copy made before editing.
+///
+/// Node properties are compared using reflection, only
+/// [`InheritableVariable`](crate::core::variable::InheritableVariable)s are compared (which is the
+/// case for the vast majority of node properties) as well as node names. Plain fields of nodes, that
+/// are not wrapped in an inheritable variable, are not diffed and changes of such fields are not
+/// included in a patch. Contents of resources are not compared, only references to them. A patch
+/// contains only changed data, so it could be used for replays of editing sessions, collaborative
+/// editing, and incremental world updates.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::scene::graph::Graph;
+///
+/// fn sync(source: &Graph, replica: &mut Graph) {
+///     let patch = replica.diff(source);
+///     if !patch.is_empty() {
+///         replica.apply_patch(patch).unwrap();
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct GraphPatch {
+    /// Nodes that were removed. Descendants of removed nodes are listed as well.
+    pub removed: Vec<Handle<Node>>,
+    /// Nodes that were added, parents are listed before their children.
+    pub added: Vec<AddedNode>,
+    /// Nodes that exist in both graphs, but have changes.
+    pub modified: Vec<ModifiedNode>,
+}
+
+impl GraphPatch {
+    /// Returns `true` if the patch contains no changes.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// An error that may occur during patch applying.
+#[derive(Debug)]
+pub enum GraphPatchError {
+    /// A handle of an added node is already occupied by some other node.
+    HandleIsOccupied(Handle<Node>),
+    /// A patch references a node that does not exist.
+    InvalidHandle(Handle<Node>),
+    /// A property at the given path does not exist or it has a different type.
+    InvalidProperty {
+        /// Handle of the node.
+        node: Handle<Node>,
+        /// Path to the property.
+        path: String,
+    },
+}
+
+impl Display for GraphPatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphPatchError::HandleIsOccupied(handle) => {
+                write!(f, "Handle {handle} is already occupied by some other node.")
+            }
+            GraphPatchError::InvalidHandle(handle) => {
+                write!(f, "Handle {handle} does not point to a valid node.")
+            }
+            GraphPatchError::InvalidProperty { node, path } => {
+                write!(
+                    f,
+                    "Property {path} of node {node} does not exist or it has a different type."
+                )
+            }
+        }
+    }
+}
+
+fn is_sub_path(path: &str, parent: &str) -> bool {
+    path.len() > parent.len()
+        && path.starts_with(parent)
+        && matches!(path.as_bytes()[parent.len()], b'.' | b'[')
+}
+
+fn diff_properties(old: &Node, new: &Node) -> Vec<PropertyChange> {
+    let mut changes = Vec::<PropertyChange>::new();
+
+    (new as &dyn Reflect).enumerate_fields_recursively(
+        &mut |path, _, new_value| {
+            // Nested variables of a changed variable will be changed together with it.
+            if changes
+                .last()
+                .is_some_and(|change| is_sub_path(path, &change.path))
+            {
+                return;
+            }
+
+            new_value.as_inheritable_variable(&mut |new_variable| {
+                let Some(new_variable) = new_variable else {
+                    return;
+                };
+
+                let mut differs = true;
+                (old as &dyn Reflect).resolve_path(path, &mut |result| {
+                    if let Ok(old_value) = result {
+                        old_value.as_inheritable_variable(&mut |old_variable| {
+                            if let Some(old_variable) = old_variable {
+                                differs = !new_variable.value_equals(old_variable);
+                            }
+                        })
+                    }
+                });
+
+                if differs {
+                    changes.push(PropertyChange {
+                        path: path.to_string(),
+                        value: new_variable.clone_value_box(),
+                    });
+                }
+            })
+        },
+        &[TypeId::of::<UntypedResource>()],
+    );
+
+    changes
+}
+
+impl Graph {
+    /// Compares the graph with the other graph and returns a set of changes, that transforms this
+    /// graph into the other one. See [`GraphPatch`] docs for more info.
+    pub fn diff(&self, other: &Graph) -> GraphPatch {
+        let mut patch = GraphPatch::default();
+
+        let is_same_node = |handle: Handle<Node>, node: &Node, graph: &Graph| {
+            graph
+                .try_get(handle)
+                .is_some_and(|other_node| other_node.instance_id() == node.instance_id())
+        };
+
+        for (handle, node) in self.pair_iter() {
+            if !is_same_node(handle, node, other) {
+                patch.removed.push(handle);
+            }
+        }
+
+        // Use hierarchical order to ensure that parents are added before their children.
+        for handle in other.traverse_handle_iter(other.get_root()) {
+            let node = &other.pool[handle];
+            if is_same_node(handle, node, self) {
+                let old_node = &self.pool[handle];
+                let properties = diff_properties(old_node, node);
+                let new_parent = (old_node.parent() != node.parent()).then_some(node.parent());
+                let new_name = (old_node.name() != node.name()).then(|| node.name_owned());
+                if !properties.is_empty() || new_parent.is_some() || new_name.is_some() {
+                    patch.modified.push(ModifiedNode {
+                        handle,
+                        new_parent,
+                        new_name,
+                        properties,
+                    });
+                }
+            } else {
+                patch.added.push(AddedNode {
+                    handle,
+                    parent: node.parent(),
+                    node: clear_links(node.clone_box()),
+                });
+            }
+        }
+
+        patch
+    }
+
+    /// Applies the given patch to the graph. See [`GraphPatch`] docs for more info.
+    ///
+    /// # Important notes
+    ///
+    /// The patch is validated before applying, if it is invalid, an error is returned and the graph
+    /// is left untouched. Properties are set using reflection (just like it is done when a property
+    /// is changed in the editor), so changed inheritable variables will be marked as modified.
+    pub fn apply_patch(&mut self, patch: GraphPatch) -> Result<(), GraphPatchError> {
+        let removed = patch.removed.iter().cloned().collect::<FxHashSet<_>>();
+
+        self.validate_patch(&patch, &removed)?;
+
+        for &handle in patch.removed.iter() {
+            if !self.is_valid_handle(handle) {
+                // Removed together with its parent.
+                continue;
+            }
+            // Move children, that will survive, out of the node, they will be relinked later.
+            for child in self.pool[handle].children().to_vec() {
+                if !removed.contains(&child) {
+                    self.link_nodes(child, self.root);
+                }
+            }
+            self.remove_node(handle);
+        }
+
+        let mut links = Vec::new();
+        for added in patch.added {
+            self.add_node_at_handle(added.handle, added.node)?;
+            links.push((added.handle, added.parent));
+        }
+
+        for modified in patch.modified {
+            let Some(node) = self.try_get_mut(modified.handle) else {
+                return Err(GraphPatchError::InvalidHandle(modified.handle));
+            };
+
+            if let Some(new_name) = modified.new_name {
+                node.set_name(new_name);
+            }
+
+            for PropertyChange { path, value } in modified.properties {
+                let mut success = false;
+                if is_path_to_array_element(&path) {
+                    let mut value = Some(value);
+                    (node as &mut dyn Reflect).resolve_path_mut(&path, &mut |result| {
+                        if let Ok(property) = result {
+                            success = property.set(value.take().unwrap()).is_ok();
+                        }
+                    });
+                } else {
+                    // Use fields setters (if any), so the node could update its internal state.
+                    (node as &mut dyn Reflect).set_field_by_path(&path, value, &mut |result| {
+                        success = result.is_ok();
+                    });
+                }
+                if !success {
+                    return Err(GraphPatchError::InvalidProperty {
+                        node: modified.handle,
+                        path,
+                    });
+                }
+            }
+
+            if let Some(new_parent) = modified.new_parent {
+                links.push((modified.handle, new_parent));
+            }
+        }
+
+        for (child, parent) in links {
+            if parent.is_some() {
+                if !self.is_valid_handle(parent) {
+                    return Err(GraphPatchError::InvalidHandle(parent));
+                }
+                self.link_nodes(child, parent);
+            } else {
+                // Root node must not have a parent.
+                self.unlink_internal(child);
+                self.root = child;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_patch(
+        &self,
+        patch: &GraphPatch,
+        removed: &FxHashSet<Handle<Node>>,
+    ) -> Result<(), GraphPatchError> {
+        let mut added = FxHashSet::default();
+        for node in patch.added.iter() {
+            let index = node.handle.index();
+            // The slot is either free or it will be freed by the patch.
+            let is_occupied = self.pool.at(index).is_some()
+                && !removed.contains(&self.pool.handle_from_index(index));
+            if node.handle.is_none() || is_occupied || !added.insert(node.handle) {
+                return Err(GraphPatchError::HandleIsOccupied(node.handle));
+            }
+        }
+
+        let find_node = |handle: Handle<Node>| {
+            if removed.contains(&handle) {
+                None
+            } else {
+                self.try_get(handle).or_else(|| {
+                    patch
+                        .added
+                        .iter()
+                        .find(|node| node.handle == handle)
+                        .map(|node| &node.node)
+                })
+            }
+        };
+
+        let check_parent = |parent: Handle<Node>| {
+            if parent.is_some() && find_node(parent).is_none() {
+                Err(GraphPatchError::InvalidHandle(parent))
+            } else {
+                Ok(())
+            }
+        };
+
+        for node in patch.added.iter() {
+            check_parent(node.parent)?;
+        }
+
+        for modified in patch.modified.iter() {
+            let Some(node) = find_node(modified.handle) else {
+                return Err(GraphPatchError::InvalidHandle(modified.handle));
+            };
+
+            for PropertyChange { path, value } in modified.properties.iter() {
+                let mut value_type = None;
+                value.as_any(&mut |value| value_type = Some(value.type_id()));
+
+                let mut property_type = None;
+                (node as &dyn Reflect).resolve_path(path, &mut |result| {
+                    if let Ok(property) = result {
+                        property.as_inheritable_variable(&mut |variable| match variable {
+                            Some(variable) => variable
+                                .inner_value_ref()
+                                .as_any(&mut |value| property_type = Some(value.type_id())),
+                            None => {
+                                property.as_any(&mut |value| property_type = Some(value.type_id()))
+                            }
+                        });
+                    }
+                });
+
+                if property_type.is_none() || property_type != value_type {
+                    return Err(GraphPatchError::InvalidProperty {
+                        node: modified.handle,
+                        path: path.clone(),
+                    });
+                }
+            }
+
+            if let Some(new_parent) = modified.new_parent {
+                check_parent(new_parent)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_node_at_handle(
+        &mut self,
+        handle: Handle<Node>,
+        node: Node,
+    ) -> Result<(), GraphPatchError> {
+        let has_script = node.script.is_some();
+        self.pool
+            .spawn_at_handle(handle, node)
+            .map_err(|_| GraphPatchError::HandleIsOccupied(handle))?;
+
+        if self.root.is_none() {
+            self.root = handle;
+        } else {
+            self.link_nodes(handle, self.root);
+        }
+
+        self.event_broadcaster.broadcast(GraphEvent::Added(handle));
+        if has_script {
+            self.script_message_sender
+                .send(NodeScriptMessage::InitializeScript { handle })
+                .unwrap();
+        }
+
+        let sender = self.script_message_sender.clone();
+        let node = &mut self.pool[handle];
+        node.self_handle = handle;
+        node.script_message_sender = Some(sender);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, reflect::Reflect, uuid::Uuid},
+        scene::{
+            base::{BaseBuilder, InstanceId},
+            graph::{
+                diff::{GraphPatchError, ModifiedNode, PropertyChange},
+                Graph,
+            },
+            pivot::PivotBuilder,
+        },
+    };
+
+    #[test]
+    fn test_graph_diff_and_patch() {
+        let mut graph = Graph::new();
+        let a = PivotBuilder::new(BaseBuilder::new().with_name("A")).build(&mut graph);
+        let b = PivotBuilder::new(BaseBuilder::new().with_name("B")).build(&mut graph);
+        let (mut edited, _) = graph.clone(graph.get_root(), &mut |_, _| true, &mut |_, _, _| {});
+        assert!(graph.diff(&edited).is_empty());
+
+        // Modify, remove, add and relink some nodes.
+        edited[a].set_name("A2");
+        edited[a]
+            .local_transform_mut()
+            .set_position(Vector3::new(1.0, 2.0, 3.0));
+        edited.remove_node(b);
+        let c = PivotBuilder::new(BaseBuilder::new().with_name("C")).build(&mut edited);
+        edited.link_nodes(c, a);
+
+        let patch = graph.diff(&edited);
+        assert_eq!(patch.removed, vec![b]);
+        assert_eq!(patch.added.len(), 1);
+        assert_eq!(patch.modified.len(), 1);
+
+        graph.apply_patch(patch).unwrap();
+        assert!(graph.diff(&edited).is_empty());
+        assert_eq!(graph[a].name(), "A2");
+        assert_eq!(
+            **graph[a].local_transform().position(),
+            Vector3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(graph[c].name(), "C");
+        assert_eq!(graph[c].parent(), a);
+        assert!(!graph.is_valid_handle(b));
+    }
+
+    #[test]
+    fn test_graph_diff_matches_nodes_by_instance_id() {
+        let mut graph = Graph::new();
+        let a = PivotBuilder::new(BaseBuilder::new().with_name("A")).build(&mut graph);
+        let (mut edited, _) = graph.clone(graph.get_root(), &mut |_, _| true, &mut |_, _, _| {});
+
+        // A different node at the same handle.
+        edited[a].set_instance_id(InstanceId(Uuid::new_v4()));
+
+        let patch = graph.diff(&edited);
+        assert_eq!(patch.removed, vec![a]);
+        assert_eq!(patch.added.len(), 1);
+        assert_eq!(patch.added[0].handle, a);
+        assert!(patch.modified.is_empty());
+
+        graph.apply_patch(patch).unwrap();
+        assert_eq!(graph[a].instance_id(), edited[a].instance_id());
+        assert!(graph.diff(&edited).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_patch_leaves_graph_untouched() {
+        let mut graph = Graph::new();
+        let a = PivotBuilder::new(BaseBuilder::new().with_name("A")).build(&mut graph);
+        let b = PivotBuilder::new(BaseBuilder::new().with_name("B")).build(&mut graph);
+        let (mut edited, _) = graph.clone(graph.get_root(), &mut |_, _| true, &mut |_, _, _| {});
+        edited.remove_node(b);
+        edited[a].set_name("A2");
+
+        let mut patch = graph.diff(&edited);
+        patch.modified.push(ModifiedNode {
+            handle: a,
+            new_parent: None,
+            new_name: None,
+            properties: vec![PropertyChange {
+                path: "base.visibility".to_string(),
+                value: Box::new(123u32) as Box<dyn Reflect>,
+            }],
+        });
+
+        assert!(matches!(
+            graph.apply_patch(patch),
+            Err(GraphPatchError::InvalidProperty { .. })
+        ));
+        assert!(graph.is_valid_handle(b));
+        assert_eq!(graph[a].name(), "A");
+    }
+}
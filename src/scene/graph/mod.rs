@@ -32,6 +32,7 @@ use crate::{
         math::Matrix4Ext,
         pool::{Handle, MultiBorrowContext, Pool, Ticket},
         reflect::prelude::*,
+        scope_profile,
         sstorage::ImmutableString,
         variable::try_inherit_properties,
         visitor::{Visit, VisitResult, Visitor},
@@ -43,24 +44,31 @@ use crate::{
     },
     scene::{
         self,
-        base::NodeScriptMessage,
+        animation::{absm::AnimationBlendingStateMachine, AnimationPlayer},
+        base::{Mobility, NodeScriptMessage},
         camera::Camera,
-        dim2::{self},
+        collider::{Collider, ColliderShape},
+        csg::CsgModel,
+        dim2,
         graph::{
             event::{GraphEvent, GraphEventBroadcaster},
             map::NodeHandleMap,
-            physics::{PhysicsPerformanceStatistics, PhysicsWorld},
+            physics::{Intersection, PhysicsPerformanceStatistics, PhysicsWorld},
+            weak::WeakHandle,
         },
+        ik::IkConstraint,
+        light_probe::LightProbeVolume,
         mesh::Mesh,
         node::{container::NodeContainer, Node, NodeTrait, SyncContext, UpdateContext},
         pivot::Pivot,
         sound::context::SoundContext,
+        terrain::Terrain,
         transform::TransformBuilder,
     },
     script::ScriptTrait,
-    utils::lightmap::Lightmap,
+    utils::{batching::StaticBatchStorage, lightmap::Lightmap, state_hash::StateHasher},
 };
-use fxhash::{FxHashMap, FxHashSet};
+use fxhash::{FxHashMap, FxHashSet, FxHasher};
 use fyrox_core::variable;
 use fyrox_resource::untyped::UntypedResource;
 use rapier3d::geometry::ColliderHandle;
@@ -68,14 +76,17 @@ use std::any::TypeId;
 use std::{
     any::Any,
     fmt::Debug,
+    hash::{Hash, Hasher},
     ops::{Index, IndexMut},
     sync::mpsc::{channel, Receiver, Sender},
     time::Duration,
 };
 
+pub mod diff;
 pub mod event;
 pub mod map;
 pub mod physics;
+pub mod weak;
 
 /// Graph performance statistics. Allows you to find out "hot" parts of the scene graph, which
 /// parts takes the most time to update.
@@ -97,6 +108,14 @@ pub struct GraphPerformanceStatistics {
 
     /// A time which was required to render sounds.
     pub sound_update_time: Duration,
+
+    /// Amount of time that was needed to update every node of the graph (animations, particle
+    /// systems, bone attachments, etc.).
+    pub node_update_time: Duration,
+
+    /// Amount of time that was needed to sample and blend animations of animation players and
+    /// animation blending state machines. It is a part of [`Self::node_update_time`].
+    pub animation_time: Duration,
 }
 
 impl GraphPerformanceStatistics {
@@ -107,6 +126,26 @@ impl GraphPerformanceStatistics {
             + self.physics.total()
             + self.physics2d.total()
             + self.sound_update_time
+            + self.node_update_time
+    }
+}
+
+/// Describes how a node is glued to a bone of a skinned mesh. See [`Graph::attach_to_bone`] for more info.
+#[derive(Clone, Debug, Visit, PartialEq)]
+pub struct BoneAttachment {
+    /// A handle of the bone to which the node is attached.
+    pub bone: Handle<Node>,
+    /// An additional transform relative to the bone, it could be used to adjust position and rotation
+    /// of an attached item (for example, to put a weapon grip exactly in the palm).
+    pub offset: Matrix4<f32>,
+}
+
+impl Default for BoneAttachment {
+    fn default() -> Self {
+        Self {
+            bone: Default::default(),
+            offset: Matrix4::identity(),
+        }
     }
 }
 
@@ -146,6 +185,32 @@ pub struct Graph {
     //lightmap: InheritableVariable<Option<Lightmap>>,
     lightmap: Option<Lightmap>,
 
+    #[reflect(hidden)]
+    bone_attachments: FxHashMap<Handle<Node>, BoneAttachment>,
+
+    #[reflect(hidden)]
+    static_batches: StaticBatchStorage,
+
+    #[reflect(hidden)]
+    script_index: FxHashMap<TypeId, Vec<Handle<Node>>>,
+
+    // Whether the script index must be rebuilt on the next update.
+    #[reflect(hidden)]
+    script_index_dirty: bool,
+
+    // A hash of the light probe volumes, that were used to sample lighting for meshes on the last
+    // update. `None` if there were no volumes.
+    #[reflect(hidden)]
+    light_probe_volumes_state: Option<u64>,
+
+    // Effective speed of time of the graph, see `time_control` module docs.
+    #[reflect(hidden)]
+    pub(crate) time_scale: f32,
+
+    // Additional per-node speed of time multipliers, that are produced by time dilations.
+    #[reflect(hidden)]
+    pub(crate) node_time_scales: FxHashMap<Handle<Node>, f32>,
+
     #[reflect(hidden)]
     pub(crate) script_message_sender: Sender<NodeScriptMessage>,
     #[reflect(hidden)]
@@ -168,6 +233,13 @@ impl Default for Graph {
             script_message_receiver: rx,
             script_message_sender: tx,
             lightmap: None,
+            bone_attachments: Default::default(),
+            static_batches: Default::default(),
+            script_index: Default::default(),
+            script_index_dirty: true,
+            light_probe_volumes_state: None,
+            time_scale: 1.0,
+            node_time_scales: Default::default(),
         }
     }
 }
@@ -191,6 +263,19 @@ pub struct SubGraph {
     pub parent: Handle<Node>,
 }
 
+/// A piece of graph that was taken out of a graph using [`Graph::take_subgraph`]. Unlike [`SubGraph`], it
+/// does not reserve handles in the source graph, so it could be put in any other graph (for example, to move
+/// a character between scenes when streaming levels) using [`Graph::put_subgraph`].
+#[derive(Debug)]
+pub struct DetachedSubGraph {
+    /// A handle of the root node of the sub-graph in the graph from which it was taken.
+    pub root: Handle<Node>,
+
+    /// Nodes of the sub-graph with their handles in the graph from which they were taken. Parent-child
+    /// relations as well as every other handle in the nodes are still in terms of the source graph.
+    pub nodes: Vec<(Handle<Node>, Node)>,
+}
+
 fn remap_handles(old_new_mapping: &NodeHandleMap, dest_graph: &mut Graph) {
     // Iterate over instantiated nodes and remap handles.
     for (_, &new_node_handle) in old_new_mapping.inner().iter() {
@@ -224,7 +309,7 @@ fn isometric_global_transform(nodes: &NodePool, node: Handle<Node>) -> Matrix4<f
 // Clears all information about parent-child relations of a given node. This is needed in some
 // cases (mostly when copying a node), because `Graph::add_node` uses children list to attach
 // children to the given node, and when copying a node it is important that this step is skipped.
-fn clear_links(mut node: Node) -> Node {
+pub(crate) fn clear_links(mut node: Node) -> Node {
     node.children.clear();
     node.parent = Handle::NONE;
     node
@@ -287,6 +372,13 @@ impl Graph {
             script_message_receiver: rx,
             script_message_sender: tx,
             lightmap: None,
+            bone_attachments: Default::default(),
+            static_batches: Default::default(),
+            script_index: Default::default(),
+            script_index_dirty: true,
+            light_probe_volumes_state: None,
+            time_scale: 1.0,
+            node_time_scales: Default::default(),
         }
     }
 
@@ -347,6 +439,7 @@ impl Graph {
 
         self.event_broadcaster.broadcast(GraphEvent::Added(handle));
         if has_script {
+            self.script_index_dirty = true;
             self.script_message_sender
                 .send(NodeScriptMessage::InitializeScript { handle })
                 .unwrap();
@@ -495,6 +588,7 @@ impl Graph {
     #[inline]
     pub fn remove_node(&mut self, node_handle: Handle<Node>) {
         self.unlink_internal(node_handle);
+        self.script_index_dirty = true;
 
         self.stack.clear();
         self.stack.push(node_handle);
@@ -504,6 +598,7 @@ impl Graph {
             }
 
             // Remove associated entities.
+            self.bone_attachments.remove(&handle);
             let mut node = self.pool.free(handle);
             node.on_removed_from_graph(self);
 
@@ -570,6 +665,169 @@ impl Graph {
         self.link_nodes(child, parent);
     }
 
+    /// Glues the node to a bone of the skinned mesh, so the node will follow the animated bone (for
+    /// example, a weapon in a hand or a hat on a head). The bone is searched by its name among the
+    /// bones of the surfaces of the mesh. Returns a handle of the bone, or [`None`] if there is no
+    /// such bone. See [`Self::attach_to_bone_with_offset`] for more info.
+    #[inline]
+    pub fn attach_to_bone(
+        &mut self,
+        node: Handle<Node>,
+        skinned_mesh: Handle<Node>,
+        bone_name: &str,
+    ) -> Option<Handle<Node>> {
+        self.attach_to_bone_with_offset(node, skinned_mesh, bone_name, Matrix4::identity())
+    }
+
+    /// Glues the node to a bone of the skinned mesh with an additional offset relative to the bone.
+    /// Returns a handle of the bone, or [`None`] if there is no such bone.
+    ///
+    /// The node does not change its parent, instead its local position and rotation are recalculated
+    /// at the end of every [`Self::update`] call, after animations were applied to the bones. Local
+    /// scale of the node is preserved. Physics will see the new position in the next frame, so use
+    /// kinematic rigid bodies for attached items, otherwise simulation will fight with the attachment.
+    #[inline]
+    pub fn attach_to_bone_with_offset(
+        &mut self,
+        node: Handle<Node>,
+        skinned_mesh: Handle<Node>,
+        bone_name: &str,
+        offset: Matrix4<f32>,
+    ) -> Option<Handle<Node>> {
+        if !self.pool.is_valid_handle(node) {
+            return None;
+        }
+
+        let mesh = self.try_get_of_type::<Mesh>(skinned_mesh)?;
+        let bone = mesh
+            .surfaces()
+            .iter()
+            .flat_map(|surface| surface.bones())
+            .find(|bone| {
+                self.pool
+                    .try_borrow(**bone)
+                    .is_some_and(|bone| bone.name() == bone_name)
+            })
+            .cloned()?;
+
+        self.bone_attachments
+            .insert(node, BoneAttachment { bone, offset });
+
+        Some(bone)
+    }
+
+    /// Detaches the node from a bone it was attached to. Returns the attachment, if any.
+    #[inline]
+    pub fn detach_from_bone(&mut self, node: Handle<Node>) -> Option<BoneAttachment> {
+        self.bone_attachments.remove(&node)
+    }
+
+    /// Returns a bone attachment of the node, if any.
+    #[inline]
+    pub fn bone_attachment(&self, node: Handle<Node>) -> Option<&BoneAttachment> {
+        self.bone_attachments.get(&node)
+    }
+
+    /// Calculates global transform of a node using local transforms of the node and its ancestors.
+    /// Unlike [`crate::scene::base::Base::global_transform`], the result does not depend on the last
+    /// hierarchy update.
+    pub(crate) fn calculate_global_transform(&self, node: Handle<Node>) -> Matrix4<f32> {
+        let mut transform = Matrix4::identity();
+        let mut handle = node;
+        while let Some(node) = self.pool.try_borrow(handle) {
+            // Folders does not have transform.
+            if !node.is_folder {
+                transform = node.local_transform().matrix() * transform;
+            }
+            handle = node.parent();
+        }
+        transform
+    }
+
+    fn update_ik_constraints(&mut self) {
+        let constraints = self
+            .pool
+            .pair_iter()
+            .filter(|(_, node)| node.is_globally_enabled() && node.cast::<IkConstraint>().is_some())
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+
+        for constraint in constraints {
+            IkConstraint::solve(self, constraint);
+        }
+    }
+
+    fn update_bone_attachments(&mut self) {
+        let mut attachments = std::mem::take(&mut self.bone_attachments);
+
+        attachments.retain(|&node, attachment| {
+            if !self.pool.is_valid_handle(node) || !self.pool.is_valid_handle(attachment.bone) {
+                return false;
+            }
+
+            let parent_transform_inv = self
+                .calculate_global_transform(self.pool[node].parent())
+                .try_inverse()
+                .unwrap_or_default();
+            let bone_transform = self.calculate_global_transform(attachment.bone);
+            let relative_transform = parent_transform_inv * bone_transform * attachment.offset;
+            let local_position = relative_transform.position();
+            let local_rotation = UnitQuaternion::from_matrix(&relative_transform.basis());
+            self.pool[node]
+                .local_transform_mut()
+                .set_position(local_position)
+                .set_rotation(local_rotation);
+
+            true
+        });
+
+        self.bone_attachments = attachments;
+    }
+
+    /// Samples baked lighting of light probe volumes for every non-static mesh. Meshes are resampled
+    /// only when they're moved or when the volumes are changed.
+    fn update_light_probes(&mut self) {
+        let mut hasher = FxHasher::default();
+        let mut volumes = Vec::new();
+        for (handle, node) in self.pool.pair_iter() {
+            let Some(volume) = node.cast::<LightProbeVolume>() else {
+                continue;
+            };
+            let Some(data) = volume.data().filter(|_| volume.is_globally_enabled()) else {
+                continue;
+            };
+            handle.hash(&mut hasher);
+            data.key().hash(&mut hasher);
+            data.is_ok().hash(&mut hasher);
+            volume.intensity().to_bits().hash(&mut hasher);
+            for element in volume.global_transform().iter() {
+                element.to_bits().hash(&mut hasher);
+            }
+            volumes.push(volume);
+        }
+
+        let volumes_state = (!volumes.is_empty()).then(|| hasher.finish());
+        if volumes_state.is_none() && self.light_probe_volumes_state.is_none() {
+            // There were no volumes on the previous frame either, so there's nothing to reset.
+            return;
+        }
+        let volumes_changed = volumes_state != self.light_probe_volumes_state;
+        self.light_probe_volumes_state = volumes_state;
+
+        for mesh in self.pool.iter().filter_map(|node| node.cast::<Mesh>()) {
+            if volumes.is_empty() || mesh.mobility() == Mobility::Static {
+                mesh.set_light_probe_irradiance(None, None);
+                continue;
+            }
+
+            let position = mesh.world_bounding_box().center();
+            if volumes_changed || mesh.light_probe_position() != Some(position) {
+                let irradiance = volumes.iter().find_map(|volume| volume.sample(position));
+                mesh.set_light_probe_irradiance(Some(position), irradiance);
+            }
+        }
+    }
+
     /// Unlinks specified node from its parent and attaches it to root graph node.
     #[inline]
     pub fn unlink_node(&mut self, node_handle: Handle<Node>) {
@@ -1197,6 +1455,7 @@ impl Graph {
         self.update_hierarchical_data();
         let instances = self.restore_integrity();
         self.remap_handles(&instances);
+        self.rebuild_csg_models();
 
         // Update cube maps for sky boxes.
         for node in self.linear_iter_mut() {
@@ -1276,6 +1535,93 @@ impl Graph {
         self.lightmap.as_ref()
     }
 
+    /// Merges surfaces of static meshes, that share the same material, into static batches to
+    /// reduce the amount of draw calls. Previous batches are discarded. See [`StaticBatchStorage`]
+    /// docs for more info.
+    pub fn build_static_batches(&mut self) {
+        self.update_hierarchical_data();
+        self.static_batches = StaticBatchStorage::from_graph(self);
+    }
+
+    /// Calculates a platform-independent hash of the state of the graph. It includes global transforms
+    /// of the nodes, velocities of rigid bodies and time positions of animations. It could be used to
+    /// check whether a simulation is deterministic - two runs with the same input must produce the
+    /// same hashes.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        self.write_state_hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Writes the state of the graph to the given hasher. See [`Self::state_hash`] for more info.
+    pub fn write_state_hash(&self, hasher: &mut StateHasher) {
+        for (handle, node) in self.pool.pair_iter() {
+            hasher.write_u32(handle.index());
+            hasher.write_u32(handle.generation());
+            hasher.write_bool(node.is_globally_enabled());
+            hasher.write_matrix4(&node.global_transform());
+
+            if let Some(rigid_body) = node.cast::<scene::rigidbody::RigidBody>() {
+                hasher.write_vector3(&rigid_body.lin_vel());
+                hasher.write_vector3(&rigid_body.ang_vel());
+            } else if let Some(rigid_body) = node.cast::<dim2::rigidbody::RigidBody>() {
+                hasher.write_vector2(&rigid_body.lin_vel());
+                hasher.write_f32(rigid_body.ang_vel());
+            } else if let Some(animation_player) = node.cast::<AnimationPlayer>() {
+                for animation in animation_player.animations().iter() {
+                    hasher.write_bool(animation.is_enabled());
+                    hasher.write_f32(animation.time_position());
+                }
+            }
+        }
+    }
+
+    /// Removes every static batch, the original meshes will be rendered as usual.
+    pub fn clear_static_batches(&mut self) {
+        self.static_batches.clear();
+    }
+
+    /// Returns a reference to the static batches of the graph.
+    pub fn static_batches(&self) -> &StaticBatchStorage {
+        &self.static_batches
+    }
+
+    /// Returns type of the surface at the given intersection point, the result of a ray cast for
+    /// example. Surface type of the collider (see [`Collider::surface_type`]) has priority, if it is
+    /// not set and the collider uses height field shape, then the surface type of the terrain layer
+    /// (see [`Terrain::surface_type_at`]) at the intersection point is used. Use it together with
+    /// [`crate::resource::surface::SurfaceEffects`] to select footstep sounds, impact effects, etc.
+    pub fn surface_type(&self, intersection: &Intersection) -> Option<&str> {
+        let collider = self.try_get_of_type::<Collider>(intersection.collider)?;
+
+        if !collider.surface_type().is_empty() {
+            return Some(collider.surface_type());
+        }
+
+        if let ColliderShape::Heightfield(heightfield) = collider.shape() {
+            return self
+                .try_get_of_type::<Terrain>(heightfield.geometry_source.0)?
+                .surface_type_at(intersection.position.coords);
+        }
+
+        None
+    }
+
+    /// Regenerates geometry of every CSG model of the graph.
+    fn rebuild_csg_models(&mut self) {
+        let models = self
+            .pair_iter()
+            .filter_map(|(handle, node)| node.cast::<CsgModel>().map(|_| handle))
+            .collect::<Vec<_>>();
+
+        if !models.is_empty() {
+            for model in models {
+                CsgModel::rebuild(self, model);
+            }
+            self.update_hierarchical_data();
+        }
+    }
+
     fn apply_lightmap(&mut self) {
         // Re-apply lightmap if any. This has to be done after resolve because we must patch surface
         // data at this stage, but if we'd do this before we wouldn't be able to do this because
@@ -1392,19 +1738,35 @@ impl Graph {
                 (Matrix4::identity(), true, true)
             };
 
-        let new_global_transform = parent_global_transform * node.local_transform().matrix();
+        // Folders does not participate in transform calculations, their children are positioned
+        // relative to the parent of a folder.
+        let is_folder = node.is_folder;
 
-        // TODO: Detect changes from user code here.
-        node.sync_transform(
-            &new_global_transform,
-            &mut SyncContext {
-                nodes,
-                physics,
-                physics2d,
-                sound_context,
-                switches: None,
-            },
-        );
+        let new_global_transform = if is_folder {
+            parent_global_transform
+        } else {
+            parent_global_transform * node.local_transform().matrix()
+        };
+
+        if !is_folder {
+            // TODO: Detect changes from user code here.
+            node.sync_transform(
+                &new_global_transform,
+                &mut SyncContext {
+                    nodes,
+                    physics,
+                    physics2d,
+                    sound_context,
+                    switches: None,
+                },
+            );
+
+            if node.has_transform_subscribers()
+                && node.global_transform.get() != new_global_transform
+            {
+                node.notify_transform_changed(&new_global_transform);
+            }
+        }
 
         node.global_transform.set(new_global_transform);
         node.global_visibility
@@ -1479,6 +1841,19 @@ impl Graph {
         );
     }
 
+    /// Forces every collider, whose shape is built from the geometry of mesh nodes, to rebuild its
+    /// shape. The engine calls this method automatically when a model resource is hot-reloaded, so
+    /// the physical shapes will match the new geometry.
+    pub fn invalidate_geometry_derived_shapes(&self) {
+        for node in self.pool.iter() {
+            if let Some(collider) = node.cast::<Collider>() {
+                if !collider.shape().geometry_sources().is_empty() {
+                    collider.invalidate_shape();
+                }
+            }
+        }
+    }
+
     /// Calculates local and global transform, global visibility for each node in graph.
     /// Normally you not need to call this method directly, it will be called automatically
     /// on each frame. However there is one use case - when you setup complex hierarchy and
@@ -1486,6 +1861,8 @@ impl Graph {
     /// this method.
     #[inline]
     pub fn update_hierarchical_data(&mut self) {
+        scope_profile!();
+
         Self::update_hierarchical_data_recursively(
             &self.pool,
             &mut self.sound_context,
@@ -1501,7 +1878,26 @@ impl Graph {
         self.pool.is_valid_handle(node_handle)
     }
 
+    /// Checks whether given node handle is valid and the node is alive - it is not going to be deleted
+    /// because its lifetime has run out (or because of its internal logic). Use it instead of
+    /// [`Self::is_valid_handle`] when you need to know whether it makes sense to interact with the node.
+    /// See also [`WeakHandle`] for references that are safe to pool slot reuse.
+    #[inline]
+    pub fn is_alive(&self, node_handle: Handle<Node>) -> bool {
+        self.pool.try_borrow(node_handle).is_some_and(|node| {
+            node.is_alive() && node.lifetime().map_or(true, |lifetime| lifetime > 0.0)
+        })
+    }
+
+    /// Creates a weak handle for the given node, see [`WeakHandle`] docs for more info.
+    #[inline]
+    pub fn weak_handle(&self, node_handle: Handle<Node>) -> WeakHandle<Node> {
+        WeakHandle::new(node_handle, self)
+    }
+
     fn sync_native(&mut self, switches: &GraphUpdateSwitches) {
+        scope_profile!();
+
         let mut sync_context = SyncContext {
             nodes: &self.pool,
             physics: &mut self.physics,
@@ -1522,12 +1918,19 @@ impl Graph {
         dt: f32,
         delete_dead_nodes: bool,
     ) {
+        let dt = dt * self.node_time_scales.get(&handle).cloned().unwrap_or(1.0);
+
         if let Some((ticket, mut node)) = self.pool.try_take_reserve(handle) {
             node.transform_modified.set(false);
 
             let mut is_alive = node.is_alive();
+            let mut commands = Vec::new();
 
             if node.is_globally_enabled() {
+                let is_animation = node.cast::<AnimationPlayer>().is_some()
+                    || node.cast::<AnimationBlendingStateMachine>().is_some();
+                let last_time = is_animation.then(instant::Instant::now);
+
                 node.update(&mut UpdateContext {
                     frame_size,
                     dt,
@@ -1535,8 +1938,14 @@ impl Graph {
                     physics: &mut self.physics,
                     physics2d: &mut self.physics2d,
                     sound_context: &mut self.sound_context,
+                    commands: &mut commands,
                 });
 
+                if let Some(last_time) = last_time {
+                    self.performance_statistics.animation_time +=
+                        instant::Instant::now() - last_time;
+                }
+
                 if delete_dead_nodes {
                     if let Some(lifetime) = node.lifetime.get_value_mut_silent().as_mut() {
                         *lifetime -= dt;
@@ -1549,49 +1958,89 @@ impl Graph {
 
             self.pool.put_back(ticket, node);
 
+            for command in commands {
+                command(self);
+            }
+
             if !is_alive && delete_dead_nodes {
                 self.remove_node(handle);
             }
         }
     }
 
-    /// Updates nodes in the graph using given delta time.
+    /// Returns effective speed of time of the graph. It is defined by the time scale of the scene
+    /// and active global time dilations. See [`crate::scene::time_control`] docs for more info.
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Returns effective speed of time of the given node. It takes time dilations, that affect
+    /// the node's hierarchy, into account.
+    pub fn node_time_scale(&self, handle: Handle<Node>) -> f32 {
+        self.time_scale * self.node_time_scales.get(&handle).cloned().unwrap_or(1.0)
+    }
+
+    /// Updates nodes in the graph using given delta time. The delta time is scaled by the
+    /// [`Self::time_scale`] before it is passed to the nodes, physics and sound.
     ///
     /// # Update Switches
     ///
     /// Update switches allows you to disable update for parts of the update pipeline, it could be useful for editors
     /// where you need to have preview mode to update only specific set of nodes, etc.
     pub fn update(&mut self, frame_size: Vector2<f32>, dt: f32, switches: GraphUpdateSwitches) {
+        scope_profile!();
+
         self.sound_context.state().pause(switches.paused);
 
         if switches.paused {
             return;
         }
 
+        if self.script_index_dirty {
+            self.rebuild_script_index();
+        }
+
         let last_time = instant::Instant::now();
         self.update_hierarchical_data();
         self.performance_statistics.hierarchical_properties_time =
             instant::Instant::now() - last_time;
 
+        self.sound_context.update_listener_switch(dt);
+
+        let time_scale = self.time_scale;
+        let dt = dt * time_scale;
+
         let last_time = instant::Instant::now();
         self.sync_native(&switches);
         self.performance_statistics.sync_time = instant::Instant::now() - last_time;
 
         if switches.physics {
             self.physics.performance_statistics.reset();
-            self.physics.update(dt);
+            self.physics.update(dt, time_scale);
             self.performance_statistics.physics = self.physics.performance_statistics.clone();
         }
 
         if switches.physics2d {
             self.physics2d.performance_statistics.reset();
-            self.physics2d.update(dt);
+            self.physics2d.update(dt, time_scale);
             self.performance_statistics.physics2d = self.physics2d.performance_statistics.clone();
         }
 
+        self.sound_context
+            .update_reverb_estimator(&self.physics, dt);
+        self.sound_context.update_reverb_zones(&self.pool);
+        self.sound_context.update_velocities(&self.pool, dt);
+        self.sound_context
+            .update_occlusion(&self.pool, &self.physics, &self.physics2d);
+        self.sound_context
+            .update_time_scales(&self.pool, time_scale, &self.node_time_scales);
+
         self.performance_statistics.sound_update_time =
             self.sound_context.state().full_render_duration();
 
+        let last_time = instant::Instant::now();
+        self.performance_statistics.animation_time = Default::default();
+
         if let Some(overrides) = switches.node_overrides.as_ref() {
             for handle in overrides {
                 self.update_node(*handle, frame_size, dt, switches.delete_dead_nodes);
@@ -1606,6 +2055,16 @@ impl Graph {
                 );
             }
         }
+
+        // Bones are animated in the loop above, so IK constraints are solved on top of the animated
+        // pose and attachments must be updated afterwards.
+        self.update_ik_constraints();
+
+        self.update_bone_attachments();
+
+        self.update_light_probes();
+
+        self.performance_statistics.node_update_time = instant::Instant::now() - last_time;
     }
 
     /// Returns capacity of internal pool. Can be used to iterate over all **potentially**
@@ -1765,6 +2224,115 @@ impl Graph {
         self.pool.forget_ticket(ticket);
     }
 
+    /// Takes the node with all its descendants out of the graph. Handles of the taken nodes become invalid,
+    /// native physics entities (rigid bodies, colliders, joints) of the nodes are removed from the physics
+    /// world, but all their properties (including velocities) are kept in the nodes. Use [`Graph::put_subgraph`]
+    /// to put the sub-graph in this or any other graph. Bone attachments of the taken nodes are removed. Returns
+    /// `None` if the handle is invalid.
+    pub fn take_subgraph(&mut self, root: Handle<Node>) -> Option<DetachedSubGraph> {
+        if !self.pool.is_valid_handle(root) {
+            return None;
+        }
+
+        self.unlink_internal(root);
+        self.script_index_dirty = true;
+
+        let mut nodes = Vec::new();
+        let mut stack = vec![root];
+        while let Some(handle) = stack.pop() {
+            // Reversed to keep the order of children.
+            stack.extend(self.pool[handle].children().iter().rev());
+
+            self.bone_attachments.remove(&handle);
+            let mut node = self.pool.free(handle);
+            node.on_removed_from_graph(self);
+
+            self.event_broadcaster
+                .broadcast(GraphEvent::Removed(handle));
+
+            nodes.push((handle, node));
+        }
+
+        Some(DetachedSubGraph { root, nodes })
+    }
+
+    /// Puts the sub-graph, that was previously taken out of this or some other graph by [`Graph::take_subgraph`],
+    /// in the graph and attaches its root to the given parent (or to the root of the graph if the parent is
+    /// [`Handle::NONE`]). Every handle in the nodes is remapped, so joints, skin bindings, etc. will keep
+    /// pointing to the respective nodes of the sub-graph. Handles to the nodes outside of the sub-graph are
+    /// left untouched. Native physics entities will be re-created on the next update of the graph. Scripts
+    /// that were already initialized keep their state and won't be initialized again, uninitialized scripts
+    /// will be initialized on the next update tick.
+    ///
+    /// Returns a handle to the root of the sub-graph and old-to-new handle mapping.
+    pub fn put_subgraph(
+        &mut self,
+        sub_graph: DetachedSubGraph,
+        parent: Handle<Node>,
+    ) -> (Handle<Node>, NodeHandleMap) {
+        let mut old_new_mapping = NodeHandleMap::default();
+        let mut hierarchy = Vec::with_capacity(sub_graph.nodes.len());
+
+        // Add nodes without any links first, links must be restored only after remapping, otherwise new
+        // handles could be mistakenly mapped as old ones.
+        for (old_handle, mut node) in sub_graph.nodes {
+            let old_parent = std::mem::take(&mut node.parent);
+            node.children.clear();
+            let new_handle = self.pool.spawn(node);
+            old_new_mapping.map.insert(old_handle, new_handle);
+            hierarchy.push((new_handle, old_parent));
+        }
+
+        remap_handles(&old_new_mapping, self);
+        self.script_index_dirty = true;
+
+        let root = old_new_mapping
+            .inner()
+            .get(&sub_graph.root)
+            .cloned()
+            .unwrap_or_default();
+
+        for &(new_handle, old_parent) in hierarchy.iter() {
+            let new_parent = if new_handle == root {
+                if parent.is_some() {
+                    parent
+                } else {
+                    self.root
+                }
+            } else {
+                old_new_mapping
+                    .inner()
+                    .get(&old_parent)
+                    .cloned()
+                    .unwrap_or(self.root)
+            };
+            self.link_nodes(new_handle, new_parent);
+
+            self.event_broadcaster
+                .broadcast(GraphEvent::Added(new_handle));
+
+            let sender = self.script_message_sender.clone();
+            let node = &mut self.pool[new_handle];
+            node.self_handle = new_handle;
+            node.script_message_sender = Some(sender);
+            if node
+                .script
+                .as_ref()
+                .is_some_and(|script| !script.initialized)
+            {
+                self.script_message_sender
+                    .send(NodeScriptMessage::InitializeScript { handle: new_handle })
+                    .unwrap();
+            }
+        }
+
+        if root.is_some() {
+            self.update_hierarchical_data_for_descendants(root);
+        }
+
+        (root, old_new_mapping)
+    }
+
     /// Returns the number of nodes in the graph.
     #[inline]
     pub fn node_count(&self) -> u32 {
@@ -1838,6 +2406,20 @@ impl Graph {
         }
         copy.lightmap = lightmap;
 
+        for (node, attachment) in self.bone_attachments.iter() {
+            let mut node = *node;
+            let mut bone = attachment.bone;
+            if old_new_map.try_map(&mut node) && old_new_map.try_map(&mut bone) {
+                copy.bone_attachments.insert(
+                    node,
+                    BoneAttachment {
+                        bone,
+                        offset: attachment.offset,
+                    },
+                );
+            }
+        }
+
         (copy, old_new_map)
     }
 
@@ -1966,6 +2548,65 @@ impl Graph {
             .and_then(|node| node.try_get_script_component())
     }
 
+    /// Marks the index of scripts as outdated, so it will be rebuilt on the next [`Self::update`]
+    /// call. The index is invalidated automatically when nodes are added or removed and when the
+    /// engine initializes or destroys scripts. Use this method if you replace scripts bypassing
+    /// [`crate::scene::base::Base::set_script`].
+    pub fn invalidate_script_index(&mut self) {
+        self.script_index_dirty = true;
+    }
+
+    /// Rebuilds the index of scripts, that is used by [`Self::scripts_of_type`] and
+    /// [`Self::nodes_with_script`]. The index is rebuilt automatically by [`Self::update`] when
+    /// it is outdated (see [`Self::invalidate_script_index`]), use this method if you need to find
+    /// scripts that were assigned in the current frame.
+    pub fn rebuild_script_index(&mut self) {
+        self.script_index_dirty = false;
+
+        for handles in self.script_index.values_mut() {
+            handles.clear();
+        }
+
+        for (handle, node) in self.pool.pair_iter() {
+            if let Some(script) = node.script() {
+                self.script_index
+                    .entry(script.as_any_ref().type_id())
+                    .or_default()
+                    .push(handle);
+            }
+        }
+
+        self.script_index.retain(|_, handles| !handles.is_empty());
+    }
+
+    /// Returns handles of every node, that had a script of the given type on the last rebuild of the
+    /// script index (see [`Self::rebuild_script_index`]). Some of the handles could be invalid, if
+    /// the nodes were deleted (or their scripts were replaced) after the rebuild.
+    #[inline]
+    pub fn nodes_with_script<T>(&self) -> &[Handle<Node>]
+    where
+        T: ScriptTrait,
+    {
+        self.script_index
+            .get(&TypeId::of::<T>())
+            .map(|handles| handles.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns an iterator over every node with a script of the given type and the script itself.
+    /// The search is performed using the script index, that is rebuilt at most once per frame, which is much
+    /// faster than iterating over every node of the graph. Scripts assigned in the current frame will
+    /// be visible only after the next rebuild of the index (see [`Self::rebuild_script_index`]).
+    #[inline]
+    pub fn scripts_of_type<T>(&self) -> impl Iterator<Item = (Handle<Node>, &T)> + '_
+    where
+        T: ScriptTrait,
+    {
+        self.nodes_with_script::<T>()
+            .iter()
+            .filter_map(|&handle| self.try_get_script_of::<T>(handle).map(|s| (handle, s)))
+    }
+
     /// Tries to borrow a node using the given handle and fetch a reference to a component of the given type
     /// from the script of the node.
     #[inline]
@@ -2100,6 +2741,11 @@ impl Visit for Graph {
         self.physics.visit("PhysicsWorld", &mut region)?;
         self.physics2d.visit("PhysicsWorld2D", &mut region)?;
         let _ = self.lightmap.visit("Lightmap", &mut region);
+        let _ = self.bone_attachments.visit("BoneAttachments", &mut region);
+
+        if region.is_reading() {
+            self.script_index_dirty = true;
+        }
 
         Ok(())
     }
@@ -2108,29 +2754,45 @@ impl Visit for Graph {
 #[cfg(test)]
 mod test {
     use crate::{
+        asset::untyped::ResourceKind,
         asset::{io::FsResourceIo, manager::ResourceManager},
         core::{
-            algebra::{Matrix4, Vector3},
+            algebra::{Matrix4, Vector2, Vector3},
             futures::executor::block_on,
+            impl_component_provider,
             pool::Handle,
-            visitor::Visitor,
+            reflect::prelude::*,
+            uuid_provider,
+            visitor::{prelude::*, Visitor},
         },
         engine::{self, SerializationContext},
-        resource::model::{Model, ModelResourceExtension},
+        resource::{
+            light_probe::{LightProbeData, LightProbeDataResource, ShIrradiance},
+            model::{Model, ModelResourceExtension},
+        },
         scene::{
             base::BaseBuilder,
-            graph::Graph,
+            collider::{Collider, ColliderBuilder, ColliderShape, GeometrySource},
+            graph::{BoneAttachment, Graph, GraphUpdateSwitches, NodeScriptMessage},
+            joint::{Joint, JointBuilder},
+            light_probe::LightProbeVolumeBuilder,
             mesh::{
                 surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
-                MeshBuilder,
+                Mesh, MeshBuilder,
             },
             node::Node,
             pivot::{Pivot, PivotBuilder},
+            rigidbody::RigidBodyBuilder,
             transform::TransformBuilder,
             Scene, SceneLoader,
         },
+        script::{Script, ScriptTrait},
+    };
+    use std::{
+        fs,
+        path::Path,
+        sync::{mpsc::channel, Arc},
     };
-    use std::{fs, path::Path, sync::Arc};
 
     #[test]
     fn graph_init_test() {
@@ -2393,4 +3055,446 @@ mod test {
                 .unwrap();
         }
     }
+
+    #[test]
+    fn test_take_and_put_subgraph() {
+        let mut source = Graph::new();
+
+        // Root_
+        //      |_Character_
+        //                  |_Body1
+        //                  |_Body2
+        //                  |_Joint
+        let body1;
+        let body2;
+        let character = PivotBuilder::new(
+            BaseBuilder::new().with_name("Character").with_children(&[
+                {
+                    body1 = RigidBodyBuilder::new(BaseBuilder::new().with_name("Body1"))
+                        .build(&mut source);
+                    body1
+                },
+                {
+                    body2 = RigidBodyBuilder::new(BaseBuilder::new().with_name("Body2"))
+                        .build(&mut source);
+                    body2
+                },
+                JointBuilder::new(BaseBuilder::new().with_name("Joint"))
+                    .with_body1(body1)
+                    .with_body2(body2)
+                    .build(&mut source),
+            ]),
+        )
+        .build(&mut source);
+
+        let sub_graph = source.take_subgraph(character).unwrap();
+        assert_eq!(sub_graph.nodes.len(), 4);
+        assert!(!source.is_valid_handle(character));
+        assert!(!source.is_valid_handle(body1));
+        assert_eq!(source.node_count(), 1);
+
+        let mut dest = Graph::new();
+        // Occupy some handles to make sure that the handles will be different.
+        PivotBuilder::new(BaseBuilder::new()).build(&mut dest);
+        PivotBuilder::new(BaseBuilder::new()).build(&mut dest);
+
+        let (new_character, mapping) = dest.put_subgraph(sub_graph, Handle::NONE);
+        assert_eq!(dest[new_character].name(), "Character");
+        assert_eq!(dest[new_character].parent(), dest.root);
+
+        let names = dest[new_character]
+            .children()
+            .iter()
+            .map(|c| dest[*c].name_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Body1", "Body2", "Joint"]);
+
+        let new_body1 = mapping.inner()[&body1];
+        let new_body2 = mapping.inner()[&body2];
+        let (_, joint) = dest.find_by_name(new_character, "Joint").unwrap();
+        let joint = joint.cast::<Joint>().unwrap();
+        assert_eq!(joint.body1(), new_body1);
+        assert_eq!(joint.body2(), new_body2);
+        assert_eq!(dest[new_body1].self_handle, new_body1);
+    }
+
+    #[test]
+    fn test_transform_change_notifications() {
+        let mut graph = Graph::new();
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let parent =
+            PivotBuilder::new(BaseBuilder::new().with_children(&[child])).build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let (tx, rx) = channel();
+        graph[child].subscribe_transform_changes(tx);
+
+        // Nothing has changed.
+        graph.update_hierarchical_data();
+        assert!(rx.try_recv().is_err());
+
+        // Moving an ancestor must notify the child.
+        graph[parent]
+            .local_transform_mut()
+            .set_position(Vector3::new(1.0, 0.0, 0.0));
+        graph.update_hierarchical_data();
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.node, child);
+        assert_eq!(
+            event.global_transform,
+            Matrix4::new_translation(&Vector3::new(1.0, 0.0, 0.0))
+        );
+        assert!(rx.try_recv().is_err());
+
+        // Subscriptions are not copied.
+        let copy = graph.copy_single_node(child);
+        assert!(!copy.has_transform_subscribers());
+
+        // Dead subscribers are removed.
+        drop(rx);
+        graph[parent]
+            .local_transform_mut()
+            .set_position(Vector3::new(2.0, 0.0, 0.0));
+        graph.update_hierarchical_data();
+        assert!(!graph[child].has_transform_subscribers());
+    }
+
+    #[test]
+    fn test_weak_handle() {
+        let mut graph = Graph::new();
+        let body = RigidBodyBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let weak = graph.weak_handle(body);
+        assert_eq!(weak.upgrade(&graph), Some(body));
+        assert!(graph.is_alive(body));
+        assert!(weak.is_alive(&graph));
+
+        // Put a different node at the exact same handle.
+        graph.remove_node(body);
+        assert!(!graph.is_alive(body));
+        assert!(weak.upgrade(&graph).is_none());
+        assert!(graph
+            .pool
+            .spawn_at_handle(body, Node::new(Pivot::default()))
+            .is_ok());
+        assert!(graph.is_valid_handle(body));
+        assert!(weak.upgrade(&graph).is_none());
+        assert!(!weak.is_alive(&graph));
+
+        assert!(graph.weak_handle(Handle::NONE).is_none());
+    }
+
+    #[test]
+    fn test_bone_attachment() {
+        let mut graph = Graph::new();
+        let bone = PivotBuilder::new(
+            BaseBuilder::new().with_name("Hand").with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 2.0, 0.0))
+                    .build(),
+            ),
+        )
+        .build(&mut graph);
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[bone])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(1.0, 0.0, 0.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+        let mesh = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .with_bones(vec![bone])
+            .build()])
+            .build(&mut graph);
+        let weapon = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[weapon])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 0.0, 5.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+
+        assert_eq!(graph.attach_to_bone(weapon, mesh, "Foot"), None);
+        assert_eq!(graph.attach_to_bone(weapon, mesh, "Hand"), Some(bone));
+
+        graph.update_bone_attachments();
+        graph.update_hierarchical_data();
+        assert_eq!(
+            **graph[weapon].local_transform().position(),
+            Vector3::new(1.0, 2.0, -5.0)
+        );
+        assert_eq!(graph[weapon].global_position(), Vector3::new(1.0, 2.0, 0.0));
+
+        // Attachments of removed bones are dropped.
+        graph.remove_node(bone);
+        graph.update_bone_attachments();
+        assert!(graph.bone_attachment(weapon).is_none());
+    }
+
+    #[test]
+    fn test_invalidate_geometry_derived_shapes() {
+        let mut graph = Graph::new();
+        let mesh = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .build()])
+            .build(&mut graph);
+        let trimesh = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::trimesh(vec![GeometrySource(mesh)]))
+            .build(&mut graph);
+        let cuboid = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(1.0, 1.0, 1.0))
+            .build(&mut graph);
+        for collider in [trimesh, cuboid] {
+            graph[collider]
+                .cast::<Collider>()
+                .unwrap()
+                .shape
+                .try_sync_model(|_| ());
+        }
+
+        graph.invalidate_geometry_derived_shapes();
+
+        let trimesh = graph[trimesh].cast::<Collider>().unwrap();
+        assert!(trimesh.shape.need_sync());
+        assert!(!graph[cuboid].cast::<Collider>().unwrap().shape.need_sync());
+    }
+
+    #[derive(Reflect, Visit, Debug, Clone, Default)]
+    struct Health(f32);
+
+    impl_component_provider!(Health);
+    uuid_provider!(Health = "1a4f7c4d-a1c6-4bb3-9d0e-6d2fd3b8b1a2");
+
+    impl ScriptTrait for Health {}
+
+    #[test]
+    fn test_scripts_of_type() {
+        let mut graph = Graph::new();
+        let a = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(Health(1.0))))
+            .build(&mut graph);
+        PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let c = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(Health(2.0))))
+            .build(&mut graph);
+
+        // The index is not built yet.
+        assert_eq!(graph.scripts_of_type::<Health>().count(), 0);
+
+        graph.rebuild_script_index();
+        assert_eq!(graph.nodes_with_script::<Health>(), &[a, c]);
+        assert_eq!(
+            graph
+                .scripts_of_type::<Health>()
+                .map(|(handle, health)| (handle, health.0))
+                .collect::<Vec<_>>(),
+            vec![(a, 1.0), (c, 2.0)]
+        );
+
+        // Removed nodes are skipped until the next rebuild.
+        graph.remove_node(a);
+        assert_eq!(graph.scripts_of_type::<Health>().count(), 1);
+        graph.rebuild_script_index();
+        assert_eq!(graph.nodes_with_script::<Health>(), &[c]);
+    }
+
+    #[test]
+    fn test_script_index_is_rebuilt_on_changes() {
+        let mut graph = Graph::new();
+        let update = |graph: &mut Graph, paused: bool| {
+            graph.update(
+                Vector2::new(1.0, 1.0),
+                1.0 / 60.0,
+                GraphUpdateSwitches {
+                    paused,
+                    ..Default::default()
+                },
+            )
+        };
+
+        update(&mut graph, false);
+        assert!(!graph.script_index_dirty);
+
+        let a = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(Health(1.0))))
+            .build(&mut graph);
+        assert!(graph.script_index_dirty);
+
+        // Paused graph must not do any work.
+        update(&mut graph, true);
+        assert_eq!(graph.nodes_with_script::<Health>(), &[]);
+
+        update(&mut graph, false);
+        assert!(!graph.script_index_dirty);
+        assert_eq!(graph.nodes_with_script::<Health>(), &[a]);
+
+        graph.remove_node(a);
+        update(&mut graph, false);
+        assert_eq!(graph.nodes_with_script::<Health>(), &[]);
+    }
+
+    #[test]
+    fn test_take_subgraph_invalid_handle() {
+        let mut graph = Graph::new();
+        let node = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        graph.remove_node(node);
+
+        assert!(graph.take_subgraph(node).is_none());
+        assert!(graph.take_subgraph(Handle::NONE).is_none());
+        assert_eq!(graph.node_count(), 1);
+    }
+
+    #[test]
+    fn test_take_subgraph_removes_bone_attachments() {
+        let mut graph = Graph::new();
+        let bone = PivotBuilder::new(BaseBuilder::new().with_name("Bone")).build(&mut graph);
+        let item = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let character =
+            PivotBuilder::new(BaseBuilder::new().with_children(&[item])).build(&mut graph);
+        graph.bone_attachments.insert(
+            item,
+            BoneAttachment {
+                bone,
+                offset: Matrix4::identity(),
+            },
+        );
+
+        graph.take_subgraph(character).unwrap();
+        assert!(graph.bone_attachment(item).is_none());
+    }
+
+    #[test]
+    fn test_put_subgraph_does_not_reinitialize_scripts() {
+        let mut source = Graph::new();
+        let initialized =
+            PivotBuilder::new(BaseBuilder::new().with_script(Script::new(Health(1.0))))
+                .build(&mut source);
+        let uninitialized =
+            PivotBuilder::new(BaseBuilder::new().with_script(Script::new(Health(2.0))))
+                .build(&mut source);
+        let character =
+            PivotBuilder::new(BaseBuilder::new().with_children(&[initialized, uninitialized]))
+                .build(&mut source);
+        source[initialized].script.as_mut().unwrap().initialized = true;
+
+        let mut dest = Graph::new();
+        let sub_graph = source.take_subgraph(character).unwrap();
+        let (_, mapping) = dest.put_subgraph(sub_graph, Handle::NONE);
+
+        let initialize_requests = dest
+            .script_message_receiver
+            .try_iter()
+            .filter_map(|message| match message {
+                NodeScriptMessage::InitializeScript { handle } => Some(handle),
+                NodeScriptMessage::DestroyScript { .. } => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(initialize_requests, [mapping.inner()[&uninitialized]]);
+        let new_initialized = mapping.inner()[&initialized];
+        assert!(dest[new_initialized].script.as_ref().unwrap().initialized);
+    }
+
+    fn make_falling_box_graph() -> Graph {
+        let mut graph = Graph::new();
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[collider])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 10.0, 0.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+        graph
+    }
+
+    #[test]
+    fn test_state_hash_determinism() {
+        let mut a = make_falling_box_graph();
+        let mut b = make_falling_box_graph();
+        let initial_hash = a.state_hash();
+        assert_eq!(initial_hash, b.state_hash());
+
+        for _ in 0..30 {
+            a.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+            b.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+            assert_eq!(a.state_hash(), b.state_hash());
+        }
+
+        assert_ne!(a.state_hash(), initial_hash);
+    }
+
+    #[test]
+    fn test_light_probes_are_resampled_only_on_changes() {
+        let mut graph = Graph::new();
+        let update = |graph: &mut Graph| {
+            graph.update(Vector2::new(1.0, 1.0), 1.0 / 60.0, Default::default())
+        };
+        let irradiance = |graph: &Graph, mesh: Handle<Node>| {
+            graph[mesh].cast::<Mesh>().unwrap().light_probe_irradiance()
+        };
+
+        let mesh = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .build()])
+            .build(&mut graph);
+        update(&mut graph);
+        assert_eq!(irradiance(&graph, mesh), None);
+        assert_eq!(graph.light_probe_volumes_state, None);
+
+        let probe = ShIrradiance {
+            coefficients: [Vector3::new(1.0, 2.0, 3.0); 4],
+        };
+        let volume = LightProbeVolumeBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_scale(Vector3::repeat(10.0))
+                    .build(),
+            ),
+        )
+        .with_data(LightProbeDataResource::new_ok(
+            ResourceKind::Embedded,
+            LightProbeData {
+                resolution: Vector3::repeat(1),
+                probes: vec![probe],
+            },
+        ))
+        .build(&mut graph);
+        update(&mut graph);
+        assert_eq!(irradiance(&graph, mesh), Some(probe));
+        assert!(graph.light_probe_volumes_state.is_some());
+
+        // Moving the mesh out of the volume resamples it.
+        graph[mesh]
+            .local_transform_mut()
+            .set_position(Vector3::new(100.0, 0.0, 0.0));
+        update(&mut graph);
+        assert_eq!(irradiance(&graph, mesh), None);
+
+        graph[mesh]
+            .local_transform_mut()
+            .set_position(Vector3::default());
+        update(&mut graph);
+        assert_eq!(irradiance(&graph, mesh), Some(probe));
+
+        // Removal of the last volume resets the lighting of meshes.
+        graph.remove_node(volume);
+        update(&mut graph);
+        assert_eq!(irradiance(&graph, mesh), None);
+        assert_eq!(graph.light_probe_volumes_state, None);
+    }
 }
@@ -29,7 +29,7 @@ use crate::{
         },
         node::{Node, NodeTrait},
         rigidbody::ApplyAction,
-        terrain::Terrain,
+        terrain::{HeightMapRegion, Terrain},
     },
     utils::raw_mesh::{RawMeshBuilder, RawVertex},
 };
@@ -43,7 +43,7 @@ use rapier3d::{
     },
     geometry::{
         BroadPhase, Collider, ColliderBuilder, ColliderHandle, ColliderSet, Cuboid,
-        InteractionGroups, NarrowPhase, Ray, SharedShape,
+        InteractionGroups, NarrowPhase, Ray, Shape, SharedShape,
     },
     pipeline::{DebugRenderPipeline, EventHandler, PhysicsPipeline, QueryFilter, QueryPipeline},
     prelude::JointAxis,
@@ -623,6 +623,17 @@ fn make_polyhedron_shape(owner_inv_transform: Matrix4<f32>, mesh: &Mesh) -> Shar
     SharedShape::convex_decomposition(&vertices, &indices)
 }
 
+fn heightfield_scale(terrain: &Terrain) -> Vector3<f32> {
+    // HACK: Temporary solution for https://github.com/FyroxEngine/Fyrox/issues/365
+    let scale = terrain.local_transform().scale();
+
+    Vector3::new(
+        terrain.chunk_size().x * scale.x * terrain.width_chunks().len() as f32,
+        1.0,
+        terrain.chunk_size().y * scale.z * terrain.length_chunks().len() as f32,
+    )
+}
+
 /// Creates height field shape from given terrain.
 fn make_heightfield(terrain: &Terrain) -> SharedShape {
     assert!(!terrain.chunks_ref().is_empty());
@@ -664,14 +675,70 @@ fn make_heightfield(terrain: &Terrain) -> SharedShape {
             Dyn(ncols as usize),
             data,
         )),
-        Vector3::new(
-            terrain.chunk_size().x * scale.x * terrain.width_chunks().len() as f32,
-            1.0,
-            terrain.chunk_size().y * scale.z * terrain.length_chunks().len() as f32,
-        ),
+        heightfield_scale(terrain),
     )
 }
 
+/// Creates a copy of the given height field shape with the given region updated from the height map
+/// of the terrain. Returns `None` if the shape does not match the terrain (for example, when the terrain
+/// was resized), in this case the shape must be fully rebuilt.
+fn patch_heightfield(
+    shape: &dyn Shape,
+    terrain: &Terrain,
+    region: HeightMapRegion,
+) -> Option<SharedShape> {
+    let heightfield = shape.as_heightfield()?;
+
+    let scale = heightfield_scale(terrain);
+    let height_scale = terrain.local_transform().scale().y;
+    let height_map_size = terrain.height_map_size();
+    let width_chunks = terrain.width_chunks().len() as u32;
+    let length_chunks = terrain.length_chunks().len() as u32;
+
+    let mut heights = heightfield.heights().clone();
+    if heights.nrows() != (height_map_size.y * length_chunks) as usize
+        || heights.ncols() != (height_map_size.x * width_chunks) as usize
+        || *heightfield.scale() != scale
+    {
+        return None;
+    }
+
+    // Copy only the pixels of the modified region, chunk by chunk.
+    for cz in region.min.y / height_map_size.y..=region.max.y / height_map_size.y {
+        for cx in region.min.x / height_map_size.x..=region.max.x / height_map_size.x {
+            let chunk = terrain
+                .chunks_ref()
+                .get((cz * width_chunks + cx) as usize)?;
+            let texture = chunk.heightmap().data_ref();
+            let height_map = texture.data_of_type::<f32>()?;
+
+            let ox = cx * height_map_size.x;
+            let oz = cz * height_map_size.y;
+            for z in region.min.y.max(oz)..=region.max.y.min(oz + height_map_size.y - 1) {
+                for x in region.min.x.max(ox)..=region.max.x.min(ox + height_map_size.x - 1) {
+                    let index = ((z - oz) * height_map_size.x + x - ox) as usize;
+                    heights[(z as usize, x as usize)] = height_map[index] * height_scale;
+                }
+            }
+        }
+    }
+
+    Some(SharedShape::heightfield(heights, scale))
+}
+
+fn heightfield_terrain<'a>(
+    nodes: &'a NodePool,
+    collider: &scene::collider::Collider,
+) -> Option<&'a Terrain> {
+    if let ColliderShape::Heightfield(heightfield) = collider.shape() {
+        nodes
+            .try_borrow(heightfield.geometry_source.0)
+            .and_then(|n| n.cast::<Terrain>())
+    } else {
+        None
+    }
+}
+
 // Converts descriptor in a shared shape.
 fn collider_shape_into_native_shape(
     shape: &ColliderShape,
@@ -1011,12 +1078,18 @@ impl PhysicsWorld {
         }
     }
 
-    pub(super) fn update(&mut self, dt: f32) {
+    pub(super) fn update(&mut self, dt: f32, time_scale: f32) {
         let time = instant::Instant::now();
 
-        if *self.enabled {
+        // Fixed time step must be scaled too, otherwise time dilation will have no effect.
+        let dt = self
+            .integration_parameters
+            .dt
+            .map_or(dt, |fixed_dt| fixed_dt * time_scale);
+
+        if *self.enabled && dt > 0.0 {
             let integration_parameters = rapier3d::dynamics::IntegrationParameters {
-                dt: self.integration_parameters.dt.unwrap_or(dt),
+                dt,
                 min_ccd_dt: self.integration_parameters.min_ccd_dt,
                 erp: self.integration_parameters.erp,
                 damping_ratio: self.integration_parameters.damping_ratio,
@@ -1188,6 +1261,57 @@ impl PhysicsWorld {
         );
     }
 
+    /// Casts a batch of rays and writes the closest intersection of every ray (if any) to the given
+    /// buffer, the order of the results matches the order of the rays. Unlike [`Self::cast_ray`], the
+    /// acceleration structure is updated only once for the whole batch, which makes this method
+    /// suitable for a large amount of short rays (for example, for particle collisions).
+    pub fn cast_rays_closest(
+        &self,
+        rays: &[RayCastOptions],
+        results: &mut Vec<Option<Intersection>>,
+    ) {
+        let time = instant::Instant::now();
+
+        let mut query = self.query.borrow_mut();
+        query.update(&self.bodies, &self.colliders);
+
+        results.clear();
+        results.extend(rays.iter().map(|opts| {
+            let ray = Ray::new(
+                opts.ray_origin,
+                opts.ray_direction
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default(),
+            );
+            query
+                .cast_ray_and_get_normal(
+                    &self.bodies,
+                    &self.colliders,
+                    &ray,
+                    opts.max_len,
+                    true,
+                    QueryFilter::new().groups(InteractionGroups::new(
+                        u32_to_group(opts.groups.memberships.0),
+                        u32_to_group(opts.groups.filter.0),
+                    )),
+                )
+                .map(|(handle, intersection)| Intersection {
+                    collider: Handle::decode_from_u128(
+                        self.colliders.get(handle).unwrap().user_data,
+                    ),
+                    normal: intersection.normal,
+                    position: ray.point_at(intersection.toi),
+                    feature: intersection.feature.into(),
+                    toi: intersection.toi,
+                })
+        }));
+
+        self.performance_statistics.total_ray_cast_time.set(
+            self.performance_statistics.total_ray_cast_time.get()
+                + (instant::Instant::now() - time),
+        );
+    }
+
     pub(crate) fn set_rigid_body_position(
         &mut self,
         rigid_body: &scene::rigidbody::RigidBody,
@@ -1416,8 +1540,14 @@ impl PhysicsWorld {
             return;
         }
 
-        let anything_changed =
-            collider_node.transform_modified.get() || collider_node.needs_sync_model();
+        let heightfield_terrain = heightfield_terrain(nodes, collider_node);
+        let height_map_changed = heightfield_terrain.is_some_and(|terrain| {
+            terrain.height_map_revision() != collider_node.height_map_revision.get()
+        });
+
+        let anything_changed = collider_node.transform_modified.get()
+            || collider_node.needs_sync_model()
+            || height_map_changed;
 
         // Important notes!
         // 1) The collider node may lack backing native physics collider in case if it
@@ -1436,6 +1566,7 @@ impl PhysicsWorld {
                         });
                     }
 
+                    let shape_changed = collider_node.shape.need_sync();
                     collider_node.shape.try_sync_model(|v| {
                         let inv_global_transform = isometric_global_transform(nodes, handle)
                             .try_inverse()
@@ -1449,6 +1580,21 @@ impl PhysicsWorld {
                             native.set_shape(shape);
                         }
                     });
+                    if let Some(terrain) = heightfield_terrain {
+                        // Update only modified part of the height field, if possible.
+                        if height_map_changed && !shape_changed {
+                            let shape = terrain
+                                .height_map_modified_region(collider_node.height_map_revision.get())
+                                .and_then(|region| {
+                                    patch_heightfield(native.shape(), terrain, region)
+                                })
+                                .unwrap_or_else(|| make_heightfield(terrain));
+                            native.set_shape(shape);
+                        }
+                        collider_node
+                            .height_map_revision
+                            .set(terrain.height_map_revision());
+                    }
                     collider_node
                         .restitution
                         .try_sync_model(|v| native.set_restitution(v));
@@ -1523,6 +1669,12 @@ impl PhysicsWorld {
 
                     collider_node.native.set(native_handle);
 
+                    if let Some(terrain) = heightfield_terrain {
+                        collider_node
+                            .height_map_revision
+                            .set(terrain.height_map_revision());
+                    }
+
                     Log::writeln(
                         MessageKind::Information,
                         format!(
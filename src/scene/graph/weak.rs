@@ -0,0 +1,129 @@
+//! Weak handles allow you to safely keep references to scene nodes that could be destroyed at any time.
+//!
+//! See [`WeakHandle`] docs for more info.
+
+use crate::{
+    core::{pool::Handle, uuid::Uuid},
+    scene::{base::InstanceId, graph::Graph, node::Node},
+};
+use std::{
+    fmt::{Debug, Formatter},
+    hash::{Hash, Hasher},
+};
+
+/// Weak handle is a handle to an object, that remembers unique instance id of the object it was created
+/// for. It allows you to check whether the object is still alive even if its pool slot was reused by some
+/// other object. Pool handles are already protected by generations, but there are cases when a slot could
+/// be occupied again with the same handle - for example when a node is put back at its exact handle (undo
+/// in editors) or when a scene is reloaded. Weak handle detects such cases, because the new object will
+/// have a different instance id.
+///
+/// # Examples
+///
+/// ```rust
+/// # use fyrox::scene::{graph::{weak::WeakHandle, Graph}, node::Node};
+/// struct Target {
+///     body: WeakHandle<Node>,
+/// }
+///
+/// fn update(target: &Target, graph: &mut Graph) {
+///     // The body could be destroyed by some other game logic, weak handle won't let us to touch
+///     // some other node that occupies the same pool slot.
+///     if let Some(body) = target.body.upgrade(graph) {
+///         graph[body].set_visibility(true);
+///     }
+/// }
+/// ```
+pub struct WeakHandle<T> {
+    handle: Handle<T>,
+    instance_id: InstanceId,
+}
+
+impl<T> WeakHandle<T> {
+    /// Weak handle that does not point to any object.
+    pub const NONE: WeakHandle<T> = WeakHandle {
+        handle: Handle::NONE,
+        instance_id: InstanceId(Uuid::nil()),
+    };
+
+    /// Returns the handle, that was used to create the weak handle. Keep in mind, that the handle
+    /// could be invalid or could point to some other object, use [`WeakHandle::upgrade`] to get
+    /// a handle that is guaranteed to point to the original object.
+    pub fn handle(&self) -> Handle<T> {
+        self.handle
+    }
+
+    /// Returns instance id of the object, that was used to create the weak handle.
+    pub fn instance_id(&self) -> InstanceId {
+        self.instance_id
+    }
+
+    /// Returns `true` if the weak handle does not point to any object.
+    pub fn is_none(&self) -> bool {
+        self.handle.is_none()
+    }
+}
+
+impl WeakHandle<Node> {
+    /// Creates new weak handle for the node with the given handle. Returns [`WeakHandle::NONE`] if the
+    /// handle is invalid.
+    pub fn new(handle: Handle<Node>, graph: &Graph) -> Self {
+        graph
+            .try_get(handle)
+            .map(|node| Self {
+                handle,
+                instance_id: node.instance_id(),
+            })
+            .unwrap_or(Self::NONE)
+    }
+
+    /// Returns a handle of the node if the node is still in the graph and it is the same node that was
+    /// used to create the weak handle, [`None`] - otherwise.
+    pub fn upgrade(&self, graph: &Graph) -> Option<Handle<Node>> {
+        graph
+            .try_get(self.handle)
+            .filter(|node| node.instance_id() == self.instance_id)
+            .map(|_| self.handle)
+    }
+
+    /// Returns `true` if the node is still alive, see [`Graph::is_alive`] for more info.
+    pub fn is_alive(&self, graph: &Graph) -> bool {
+        self.upgrade(graph)
+            .is_some_and(|handle| graph.is_alive(handle))
+    }
+}
+
+impl<T> Default for WeakHandle<T> {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+impl<T> Clone for WeakHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for WeakHandle<T> {}
+
+impl<T> PartialEq for WeakHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle && self.instance_id == other.instance_id
+    }
+}
+
+impl<T> Eq for WeakHandle<T> {}
+
+impl<T> Hash for WeakHandle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.handle.hash(state);
+        self.instance_id.hash(state);
+    }
+}
+
+impl<T> Debug for WeakHandle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{:?}:{:?}]", self.handle, self.instance_id.0)
+    }
+}
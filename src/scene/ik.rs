@@ -0,0 +1,752 @@
+//! Inverse kinematics (IK) constraints. See [`IkConstraint`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix3, Matrix4, UnitQuaternion, Vector3},
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        node::{Node, NodeTrait},
+    },
+};
+use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// A solver, that is used by an [`IkConstraint`].
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum IkSolver {
+    /// Analytic solver for chains of exactly three bones (for example - upper arm, forearm, hand
+    /// or thigh, shin, foot). The first two bones are rotated so the third one reaches the target,
+    /// the bend direction is defined by the pole (if any).
+    #[default]
+    TwoBone,
+    /// Iterative "Forward And Backward Reaching Inverse Kinematics" solver for chains of arbitrary
+    /// length (for example - tails, tentacles, spines). If the pole is set, every joint of the chain
+    /// is bent towards it.
+    Fabrik,
+    /// Rotates bones of the chain, so the look axis of the last bone of the chain points at the
+    /// target. The rotation is distributed evenly across all bones of the chain (for example - neck
+    /// and head).
+    LookAt,
+}
+
+uuid_provider!(IkSolver = "3a8f2c71-5e04-4d9b-b6a2-9c1e7f0d4b58");
+
+/// Inverse kinematics constraint modifies rotations of a chain of bones, so the end of the chain
+/// reaches (or looks at) a target node. Constraints are solved every frame after animations (and
+/// every other node) are updated and before the bone attachments are updated and the scene is
+/// rendered, so the animated pose is used as a starting point. This allows you to use the
+/// constraints for foot placement on uneven terrain, hands on weapons or doors, looking at points of
+/// interest, etc.
+///
+/// The chain of bones is defined by a list of bones, where every bone must be a descendant of the
+/// previous one (usually - its child). The first bone is the root of the chain, its position never
+/// changes. The influence of the constraint could be controlled by its weight, which is useful to
+/// smoothly enable or disable the constraint. Disabled constraints are not solved.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     scene::{
+///         base::BaseBuilder,
+///         graph::Graph,
+///         ik::{IkConstraintBuilder, IkSolver},
+///         node::Node,
+///     },
+/// };
+///
+/// fn add_leg_ik(
+///     graph: &mut Graph,
+///     thigh: Handle<Node>,
+///     shin: Handle<Node>,
+///     foot: Handle<Node>,
+///     foot_target: Handle<Node>,
+///     knee_pole: Handle<Node>,
+/// ) -> Handle<Node> {
+///     IkConstraintBuilder::new(BaseBuilder::new().with_name("LeftLegIK"))
+///         .with_solver(IkSolver::TwoBone)
+///         .with_bones(vec![thigh, shin, foot])
+///         .with_target(foot_target)
+///         .with_pole(knee_pole)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Clone, Reflect, Visit, Debug)]
+pub struct IkConstraint {
+    base: Base,
+
+    #[reflect(setter = "set_solver")]
+    solver: InheritableVariable<IkSolver>,
+
+    #[reflect(setter = "set_bones")]
+    bones: InheritableVariable<Vec<Handle<Node>>>,
+
+    #[reflect(setter = "set_target")]
+    target: InheritableVariable<Handle<Node>>,
+
+    #[reflect(setter = "set_pole")]
+    pole: InheritableVariable<Handle<Node>>,
+
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05, setter = "set_weight")]
+    weight: InheritableVariable<f32>,
+
+    #[reflect(min_value = 1.0, setter = "set_iterations")]
+    iterations: InheritableVariable<u32>,
+
+    #[reflect(min_value = 0.0, step = 0.001, setter = "set_tolerance")]
+    tolerance: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_look_axis")]
+    look_axis: InheritableVariable<Vector3<f32>>,
+}
+
+impl Default for IkConstraint {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            solver: Default::default(),
+            bones: Default::default(),
+            target: Default::default(),
+            pole: Default::default(),
+            weight: 1.0.into(),
+            iterations: 10.into(),
+            tolerance: 0.001.into(),
+            look_axis: Vector3::z().into(),
+        }
+    }
+}
+
+impl Deref for IkConstraint {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for IkConstraint {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for IkConstraint {
+    fn type_uuid() -> Uuid {
+        uuid!("b2f6e1d4-8c3a-4f57-9e20-6d1a5c8b7f43")
+    }
+}
+
+impl IkConstraint {
+    /// Sets new solver of the constraint. Returns previous solver.
+    pub fn set_solver(&mut self, solver: IkSolver) -> IkSolver {
+        self.solver.set_value_and_mark_modified(solver)
+    }
+
+    /// Returns current solver of the constraint.
+    pub fn solver(&self) -> IkSolver {
+        *self.solver
+    }
+
+    /// Sets new chain of bones, every bone must be a descendant of the previous one. Two-bone solver
+    /// requires exactly three bones. Returns previous chain.
+    pub fn set_bones(&mut self, bones: Vec<Handle<Node>>) -> Vec<Handle<Node>> {
+        self.bones.set_value_and_mark_modified(bones)
+    }
+
+    /// Returns current chain of bones.
+    pub fn bones(&self) -> &[Handle<Node>] {
+        &self.bones
+    }
+
+    /// Sets new target node, the end of the chain will reach (or look at) the position of the node.
+    /// Returns previous target.
+    pub fn set_target(&mut self, target: Handle<Node>) -> Handle<Node> {
+        self.target.set_value_and_mark_modified(target)
+    }
+
+    /// Returns current target node.
+    pub fn target(&self) -> Handle<Node> {
+        *self.target
+    }
+
+    /// Sets new pole node, joints of the chain will be bent towards the position of the node (for
+    /// example, a pole in front of a character makes knees bend forward). Pole is optional, if it is
+    /// not set, the bend direction of the current pose is preserved. Returns previous pole.
+    pub fn set_pole(&mut self, pole: Handle<Node>) -> Handle<Node> {
+        self.pole.set_value_and_mark_modified(pole)
+    }
+
+    /// Returns current pole node.
+    pub fn pole(&self) -> Handle<Node> {
+        *self.pole
+    }
+
+    /// Sets new weight of the constraint in `[0; 1]` range. Zero weight means that the constraint
+    /// has no effect, one - the constraint fully overrides the animated pose. Returns previous
+    /// weight.
+    pub fn set_weight(&mut self, weight: f32) -> f32 {
+        self.weight
+            .set_value_and_mark_modified(weight.clamp(0.0, 1.0))
+    }
+
+    /// Returns current weight of the constraint.
+    pub fn weight(&self) -> f32 {
+        *self.weight
+    }
+
+    /// Sets maximum amount of iterations of FABRIK solver. Returns previous value.
+    pub fn set_iterations(&mut self, iterations: u32) -> u32 {
+        self.iterations
+            .set_value_and_mark_modified(iterations.max(1))
+    }
+
+    /// Returns maximum amount of iterations of FABRIK solver.
+    pub fn iterations(&self) -> u32 {
+        *self.iterations
+    }
+
+    /// Sets distance tolerance of FABRIK solver, the solver stops when the end of the chain is
+    /// closer than the tolerance to the target. Returns previous value.
+    pub fn set_tolerance(&mut self, tolerance: f32) -> f32 {
+        self.tolerance
+            .set_value_and_mark_modified(tolerance.max(0.0))
+    }
+
+    /// Returns distance tolerance of FABRIK solver.
+    pub fn tolerance(&self) -> f32 {
+        *self.tolerance
+    }
+
+    /// Sets an axis (in local space of the last bone of the chain), that will be pointed at the
+    /// target by look-at solver. Default is Z axis. Returns previous value.
+    pub fn set_look_axis(&mut self, axis: Vector3<f32>) -> Vector3<f32> {
+        self.look_axis.set_value_and_mark_modified(axis)
+    }
+
+    /// Returns current look axis of look-at solver.
+    pub fn look_axis(&self) -> Vector3<f32> {
+        *self.look_axis
+    }
+
+    /// Solves the constraint with the given handle. The engine calls this method automatically for
+    /// every enabled constraint, use it only if you need to solve a constraint manually (for example
+    /// after changing the pose in a script).
+    pub fn solve(graph: &mut Graph, constraint: Handle<Node>) {
+        let Some(constraint) = graph.try_get_of_type::<IkConstraint>(constraint) else {
+            return;
+        };
+
+        let solver = constraint.solver();
+        let bones = constraint.bones().to_vec();
+        let target = constraint.target();
+        let pole = constraint.pole();
+        let weight = constraint.weight();
+        let iterations = constraint.iterations();
+        let tolerance = constraint.tolerance();
+        let look_axis = constraint.look_axis();
+
+        if weight <= 0.0
+            || bones.is_empty()
+            || !graph.is_valid_handle(target)
+            || bones.iter().any(|bone| !graph.is_valid_handle(*bone))
+        {
+            return;
+        }
+
+        // Animations modify local transforms only, so global transforms of the chain must be
+        // refreshed before solving.
+        refresh_chain(graph, bones[0]);
+
+        let target = graph.calculate_global_transform(target).position();
+        let pole = graph
+            .is_valid_handle(pole)
+            .then(|| graph.calculate_global_transform(pole).position());
+
+        match solver {
+            IkSolver::TwoBone => {
+                if let [a, b, c] = bones.as_slice() {
+                    solve_two_bone(graph, [*a, *b, *c], target, pole, weight);
+                }
+            }
+            IkSolver::Fabrik => {
+                solve_fabrik(graph, &bones, target, pole, iterations, tolerance, weight)
+            }
+            IkSolver::LookAt => solve_look_at(graph, &bones, target, look_axis, weight),
+        }
+    }
+}
+
+impl NodeTrait for IkConstraint {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
+
+/// Allows you to create IK constraints in declarative manner.
+pub struct IkConstraintBuilder {
+    base_builder: BaseBuilder,
+    solver: IkSolver,
+    bones: Vec<Handle<Node>>,
+    target: Handle<Node>,
+    pole: Handle<Node>,
+    weight: f32,
+    iterations: u32,
+    tolerance: f32,
+    look_axis: Vector3<f32>,
+}
+
+impl IkConstraintBuilder {
+    /// Creates new builder instance.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            solver: Default::default(),
+            bones: Default::default(),
+            target: Default::default(),
+            pole: Default::default(),
+            weight: 1.0,
+            iterations: 10,
+            tolerance: 0.001,
+            look_axis: Vector3::z(),
+        }
+    }
+
+    /// Sets desired solver.
+    pub fn with_solver(mut self, solver: IkSolver) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    /// Sets desired chain of bones.
+    pub fn with_bones(mut self, bones: Vec<Handle<Node>>) -> Self {
+        self.bones = bones;
+        self
+    }
+
+    /// Sets desired target node.
+    pub fn with_target(mut self, target: Handle<Node>) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Sets desired pole node.
+    pub fn with_pole(mut self, pole: Handle<Node>) -> Self {
+        self.pole = pole;
+        self
+    }
+
+    /// Sets desired weight.
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets desired maximum amount of iterations of FABRIK solver.
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    /// Sets desired distance tolerance of FABRIK solver.
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance.max(0.0);
+        self
+    }
+
+    /// Sets desired look axis of look-at solver.
+    pub fn with_look_axis(mut self, axis: Vector3<f32>) -> Self {
+        self.look_axis = axis;
+        self
+    }
+
+    /// Creates new IK constraint node, but does not add it to a graph.
+    pub fn build_node(self) -> Node {
+        Node::new(IkConstraint {
+            base: self.base_builder.build_base(),
+            solver: self.solver.into(),
+            bones: self.bones.into(),
+            target: self.target.into(),
+            pole: self.pole.into(),
+            weight: self.weight.into(),
+            iterations: self.iterations.into(),
+            tolerance: self.tolerance.into(),
+            look_axis: self.look_axis.into(),
+        })
+    }
+
+    /// Creates new IK constraint node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+/// Rotates the first two bones of a three-bone chain, so the end of the chain reaches the target.
+/// The chain bends towards the pole (if any). Weight defines how much the result affects the current
+/// pose. Global transforms of the chain must be up-to-date.
+pub fn solve_two_bone(
+    graph: &mut Graph,
+    bones: [Handle<Node>; 3],
+    target: Vector3<f32>,
+    pole: Option<Vector3<f32>>,
+    weight: f32,
+) {
+    let [a, b, c] = bones.map(|bone| graph[bone].global_position());
+
+    let upper_length = (b - a).norm();
+    let lower_length = (c - b).norm();
+    if upper_length <= f32::EPSILON || lower_length <= f32::EPSILON {
+        return;
+    }
+
+    let to_target = target - a;
+    let Some(direction) = to_target.try_normalize(f32::EPSILON) else {
+        return;
+    };
+
+    // Keep the chain slightly bent to prevent jittering at full extension.
+    let max_length = (upper_length + lower_length) * 0.9999;
+    let min_length = (upper_length - lower_length).abs() * 1.0001;
+    let distance = to_target.norm().clamp(min_length, max_length);
+
+    let hint = pole.map_or(b - a, |pole| pole - a);
+    let bend = (hint - direction.scale(hint.dot(&direction)))
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(|| any_orthogonal(&direction));
+
+    // Law of cosines gives the angle between the upper bone and the direction to the target.
+    let cos_angle = ((upper_length * upper_length + distance * distance
+        - lower_length * lower_length)
+        / (2.0 * upper_length * distance))
+        .clamp(-1.0, 1.0);
+    let sin_angle = (1.0 - cos_angle * cos_angle).sqrt();
+
+    let middle =
+        a + direction.scale(upper_length * cos_angle) + bend.scale(upper_length * sin_angle);
+    let end = a + direction.scale(distance);
+
+    apply_positions(graph, &bones, &[a, middle, end], weight);
+}
+
+/// Solves a chain of arbitrary length using FABRIK algorithm, so the end of the chain reaches the
+/// target. If the pole is set, every joint of the chain is bent towards it. Weight defines how much
+/// the result affects the current pose. Global transforms of the chain must be up-to-date.
+pub fn solve_fabrik(
+    graph: &mut Graph,
+    bones: &[Handle<Node>],
+    target: Vector3<f32>,
+    pole: Option<Vector3<f32>>,
+    iterations: u32,
+    tolerance: f32,
+    weight: f32,
+) {
+    if bones.len() < 2 {
+        return;
+    }
+
+    let mut positions = bones
+        .iter()
+        .map(|bone| graph[*bone].global_position())
+        .collect::<Vec<_>>();
+    let lengths = positions
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).norm())
+        .collect::<Vec<_>>();
+    let root = positions[0];
+    let last = positions.len() - 1;
+
+    if (target - root).norm() >= lengths.iter().sum::<f32>() {
+        // Target is unreachable - stretch the chain towards it.
+        let direction = (target - root)
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_default();
+        for i in 0..last {
+            positions[i + 1] = positions[i] + direction.scale(lengths[i]);
+        }
+    } else {
+        for _ in 0..iterations.max(1) {
+            if (positions[last] - target).norm() <= tolerance {
+                break;
+            }
+
+            // Backward pass - from the end to the root.
+            positions[last] = target;
+            for i in (0..last).rev() {
+                let direction = (positions[i] - positions[i + 1])
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default();
+                positions[i] = positions[i + 1] + direction.scale(lengths[i]);
+            }
+
+            // Forward pass - from the root to the end.
+            positions[0] = root;
+            for i in 0..last {
+                let direction = (positions[i + 1] - positions[i])
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default();
+                positions[i + 1] = positions[i] + direction.scale(lengths[i]);
+            }
+        }
+    }
+
+    if let Some(pole) = pole {
+        // Rotate every joint around the line between its neighbours, so it is as close as possible
+        // to the pole.
+        for i in 1..last {
+            let (prev, next) = (positions[i - 1], positions[i + 1]);
+            let Some(axis) = (next - prev).try_normalize(f32::EPSILON) else {
+                continue;
+            };
+            let project = |p: Vector3<f32>| {
+                let v = p - prev;
+                v - axis.scale(v.dot(&axis))
+            };
+            let joint = project(positions[i]);
+            let pole = project(pole);
+            if joint.norm() <= f32::EPSILON || pole.norm() <= f32::EPSILON {
+                continue;
+            }
+            let angle = joint.cross(&pole).dot(&axis).atan2(joint.dot(&pole));
+            let rotation = UnitQuaternion::from_scaled_axis(axis.scale(angle));
+            positions[i] = prev + rotation * (positions[i] - prev);
+        }
+    }
+
+    apply_positions(graph, bones, &positions, weight);
+}
+
+/// Rotates bones of the chain, so the given axis (in local space of the last bone) points at the
+/// target. The rotation is distributed evenly across the bones. Weight defines how much the result
+/// affects the current pose. Global transforms of the chain must be up-to-date.
+pub fn solve_look_at(
+    graph: &mut Graph,
+    bones: &[Handle<Node>],
+    target: Vector3<f32>,
+    look_axis: Vector3<f32>,
+    weight: f32,
+) {
+    let Some(&last) = bones.last() else {
+        return;
+    };
+
+    for (i, &bone) in bones.iter().enumerate() {
+        let last_transform = graph[last].global_transform();
+        let axis = global_rotation(&last_transform) * look_axis;
+        let to_target = target - last_transform.position();
+        let Some(rotation) = UnitQuaternion::rotation_between(&axis, &to_target) else {
+            continue;
+        };
+        let share = weight / (bones.len() - i) as f32;
+        rotate_bone(
+            graph,
+            bone,
+            UnitQuaternion::identity().nlerp(&rotation, share),
+        );
+    }
+}
+
+// Refreshes global transforms of the hierarchy starting from the given node, taking the current
+// local transforms of its ancestors into account.
+fn refresh_chain(graph: &mut Graph, root: Handle<Node>) {
+    let parent = graph[root].parent();
+    if graph.is_valid_handle(parent) {
+        let parent_transform = graph.calculate_global_transform(parent);
+        graph[parent].global_transform.set(parent_transform);
+    }
+    graph.update_hierarchical_data_for_descendants(root);
+}
+
+// Rotates bones of the chain one by one, so every bone points to the next desired position.
+fn apply_positions(
+    graph: &mut Graph,
+    bones: &[Handle<Node>],
+    positions: &[Vector3<f32>],
+    weight: f32,
+) {
+    for i in 0..bones.len().saturating_sub(1) {
+        let bone_position = graph[bones[i]].global_position();
+        let current = graph[bones[i + 1]].global_position() - bone_position;
+        let desired = positions[i + 1] - bone_position;
+        if let Some(rotation) = UnitQuaternion::rotation_between(&current, &desired) {
+            rotate_bone(
+                graph,
+                bones[i],
+                UnitQuaternion::identity().nlerp(&rotation, weight),
+            );
+        }
+    }
+}
+
+// Applies the given world-space rotation to the bone and updates global transforms of its
+// descendants.
+fn rotate_bone(graph: &mut Graph, bone: Handle<Node>, rotation: UnitQuaternion<f32>) {
+    let parent = graph[bone].parent();
+    let parent_rotation = graph
+        .try_get(parent)
+        .map(|parent| global_rotation(&parent.global_transform()))
+        .unwrap_or_default();
+
+    // Convert the rotation to the space of the parent.
+    let local_delta = parent_rotation.inverse() * rotation * parent_rotation;
+
+    let transform = graph[bone].local_transform_mut();
+    let pre_rotation = **transform.pre_rotation();
+    let new_rotation = pre_rotation.inverse() * local_delta * pre_rotation * **transform.rotation();
+    transform.set_rotation(new_rotation);
+
+    graph.update_hierarchical_data_for_descendants(bone);
+}
+
+fn global_rotation(transform: &Matrix4<f32>) -> UnitQuaternion<f32> {
+    let basis = transform.basis();
+    let normalized = Matrix3::from_columns(&[
+        basis.column(0).normalize(),
+        basis.column(1).normalize(),
+        basis.column(2).normalize(),
+    ]);
+    UnitQuaternion::from_matrix_eps(&normalized, f32::EPSILON, 16, Default::default())
+}
+
+fn any_orthogonal(v: &Vector3<f32>) -> Vector3<f32> {
+    let helper = if v.x.abs() > 0.9 {
+        Vector3::y()
+    } else {
+        Vector3::x()
+    };
+    v.cross(&helper).normalize()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, pool::Handle},
+        scene::{
+            base::BaseBuilder,
+            graph::Graph,
+            ik::{IkConstraint, IkConstraintBuilder, IkSolver},
+            node::Node,
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    fn pivot(graph: &mut Graph, position: Vector3<f32>, children: &[Handle<Node>]) -> Handle<Node> {
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_children(children)
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                ),
+        )
+        .build(graph)
+    }
+
+    fn chain(graph: &mut Graph, count: usize) -> Vec<Handle<Node>> {
+        let mut bones = vec![pivot(graph, Vector3::default(), &[])];
+        for _ in 1..count {
+            let bone = pivot(graph, Vector3::new(0.0, 1.0, 0.0), &[]);
+            graph.link_nodes(bone, *bones.last().unwrap());
+            bones.push(bone);
+        }
+        bones
+    }
+
+    #[test]
+    fn test_two_bone_ik() {
+        let mut graph = Graph::new();
+        let bones = chain(&mut graph, 3);
+        let target = pivot(&mut graph, Vector3::new(1.0, 1.0, 0.0), &[]);
+        let pole = pivot(&mut graph, Vector3::new(0.0, 1.0, -1.0), &[]);
+        let constraint = IkConstraintBuilder::new(BaseBuilder::new())
+            .with_solver(IkSolver::TwoBone)
+            .with_bones(bones.clone())
+            .with_target(target)
+            .with_pole(pole)
+            .build(&mut graph);
+
+        graph.update_hierarchical_data();
+        IkConstraint::solve(&mut graph, constraint);
+
+        let end = graph[bones[2]].global_position();
+        assert!((end - Vector3::new(1.0, 1.0, 0.0)).norm() < 0.01, "{end:?}");
+        // Middle joint must be bent towards the pole.
+        assert!(graph[bones[1]].global_position().z < 0.0);
+    }
+
+    #[test]
+    fn test_fabrik_ik() {
+        let mut graph = Graph::new();
+        let bones = chain(&mut graph, 5);
+        let target = pivot(&mut graph, Vector3::new(2.0, 2.0, 0.0), &[]);
+        let constraint = IkConstraintBuilder::new(BaseBuilder::new())
+            .with_solver(IkSolver::Fabrik)
+            .with_bones(bones.clone())
+            .with_target(target)
+            .with_iterations(32)
+            .build(&mut graph);
+
+        graph.update_hierarchical_data();
+        IkConstraint::solve(&mut graph, constraint);
+
+        let end = graph[*bones.last().unwrap()].global_position();
+        assert!((end - Vector3::new(2.0, 2.0, 0.0)).norm() < 0.01, "{end:?}");
+        // Bone lengths must be preserved.
+        assert!((graph[bones[1]].global_position().norm() - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_look_at_ik() {
+        let mut graph = Graph::new();
+        let bones = chain(&mut graph, 2);
+        let target = pivot(&mut graph, Vector3::new(5.0, 1.0, 0.0), &[]);
+        let constraint = IkConstraintBuilder::new(BaseBuilder::new())
+            .with_solver(IkSolver::LookAt)
+            .with_bones(bones.clone())
+            .with_target(target)
+            .with_look_axis(Vector3::z())
+            .build(&mut graph);
+
+        graph.update_hierarchical_data();
+        IkConstraint::solve(&mut graph, constraint);
+
+        let head = graph[bones[1]].global_transform();
+        let look = head.transform_vector(&Vector3::z()).normalize();
+        let to_target =
+            (Vector3::new(5.0, 1.0, 0.0) - graph[bones[1]].global_position()).normalize();
+        assert!(look.dot(&to_target) > 0.999, "{look:?}");
+    }
+}
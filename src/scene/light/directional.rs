@@ -18,6 +18,7 @@ use crate::{
         visitor::{Visit, VisitResult, Visitor},
         TypeUuidProvider,
     },
+    renderer::batch::RenderContext,
     scene::{
         base::Base,
         debug::SceneDrawingContext,
@@ -58,6 +59,20 @@ pub enum FrustumSplitOptions {
         /// sub-frustum will be relative to camera's frustum.
         fractions: [f32; CSM_NUM_CASCADES],
     },
+    /// Camera frustum will be split using "practical split scheme", which blends logarithmic and uniform
+    /// splits. Logarithmic splits give the best distribution of shadow map resolution, but make the
+    /// first cascade very small, uniform splits are the opposite.
+    ///
+    /// This option works best for large outdoor scenes, because it does not require any tweaking when
+    /// the shadow distance changes.
+    Practical {
+        /// A fraction in `[0; 1]` range which defines the blend between uniform (0.0) and
+        /// logarithmic (1.0) splits. Typical values are in `[0.5; 0.9]` range.
+        split_lambda: f32,
+        /// Maximum distance from the camera at which the shadows will be rendered. It will be clamped
+        /// to the far plane of the camera.
+        max_distance: f32,
+    },
 }
 
 uuid_provider!(FrustumSplitOptions = "b2ed128a-b7da-4d34-b027-a0af19c2f563");
@@ -70,6 +85,37 @@ impl Default for FrustumSplitOptions {
     }
 }
 
+impl FrustumSplitOptions {
+    /// Calculates locations of far planes of each cascade using the given near and far planes of a
+    /// camera.
+    pub fn far_planes(&self, z_near: f32, z_far: f32) -> [f32; CSM_NUM_CASCADES] {
+        match self {
+            Self::Absolute { far_planes } => *far_planes,
+            Self::Relative { fractions } => fractions.map(|fraction| z_far * fraction),
+            Self::Practical {
+                split_lambda,
+                max_distance,
+            } => {
+                let lambda = split_lambda.clamp(0.0, 1.0);
+                let z_near = z_near.max(f32::EPSILON);
+                let z_far = if *max_distance > z_near {
+                    max_distance.min(z_far)
+                } else {
+                    z_far
+                };
+                let mut far_planes = [0.0; CSM_NUM_CASCADES];
+                for (i, far_plane) in far_planes.iter_mut().enumerate() {
+                    let fraction = (i + 1) as f32 / CSM_NUM_CASCADES as f32;
+                    let logarithmic = z_near * (z_far / z_near).powf(fraction);
+                    let uniform = z_near + (z_far - z_near) * fraction;
+                    *far_plane = lambda * logarithmic + (1.0 - lambda) * uniform;
+                }
+                far_planes
+            }
+        }
+    }
+}
+
 /// Cascade Shadow Mapping (CSM) options.
 #[derive(Reflect, Clone, Visit, PartialEq, Debug)]
 pub struct CsmOptions {
@@ -78,6 +124,39 @@ pub struct CsmOptions {
 
     #[reflect(min_value = 0.0, step = 0.000025)]
     shadow_bias: f32,
+
+    #[reflect(
+        min_value = 1.0,
+        max_value = 3.0,
+        description = "Amount of cascades that will be used to render shadows."
+    )]
+    #[visit(optional)]
+    cascade_count: usize,
+
+    #[reflect(
+        description = "Additional shadow bias for each cascade. Farther cascades cover larger areas and \
+        usually need larger bias to prevent shadow acne."
+    )]
+    #[visit(optional)]
+    cascade_biases: [f32; CSM_NUM_CASCADES],
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Shifts shadow lookups along the surface normal by the given amount of shadow \
+        map texels. It removes shadow acne on flat surfaces (sprites, 2.5D geometry) without large \
+        depth bias, that makes shadows look detached."
+    )]
+    #[visit(optional)]
+    normal_offset: f32,
+
+    #[reflect(
+        description = "Clamps depth of shadow casters, that are located in front of the near plane \
+        of a cascade, instead of clipping them. It allows to use tight depth ranges for cascades \
+        which gives better depth precision. Has no effect on OpenGL ES."
+    )]
+    #[visit(optional)]
+    pancaking: bool,
 }
 
 impl Default for CsmOptions {
@@ -85,6 +164,10 @@ impl Default for CsmOptions {
         Self {
             split_options: Default::default(),
             shadow_bias: 0.00025,
+            cascade_count: CSM_NUM_CASCADES,
+            cascade_biases: [0.0; CSM_NUM_CASCADES],
+            normal_offset: 0.0,
+            pancaking: false,
         }
     }
 }
@@ -101,6 +184,65 @@ impl CsmOptions {
     pub fn shadow_bias(&self) -> f32 {
         self.shadow_bias
     }
+
+    /// Sets new amount of cascades. The value will be clamped to `[1; CSM_NUM_CASCADES]` range. Lesser
+    /// amount of cascades improves performance, but reduces the quality of distant shadows.
+    pub fn set_cascade_count(&mut self, count: usize) {
+        self.cascade_count = count.clamp(1, CSM_NUM_CASCADES);
+    }
+
+    /// Returns current amount of cascades.
+    pub fn cascade_count(&self) -> usize {
+        self.cascade_count.clamp(1, CSM_NUM_CASCADES)
+    }
+
+    /// Sets additional shadow bias for the cascade with the given index. The final bias of the cascade
+    /// is a sum of [`Self::shadow_bias`] and this value. Does nothing if the index is out of bounds.
+    pub fn set_cascade_bias(&mut self, cascade: usize, bias: f32) {
+        if let Some(cascade_bias) = self.cascade_biases.get_mut(cascade) {
+            *cascade_bias = bias.max(0.0);
+        }
+    }
+
+    /// Returns additional shadow bias of the cascade with the given index.
+    pub fn cascade_bias(&self, cascade: usize) -> f32 {
+        self.cascade_biases
+            .get(cascade)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the final shadow bias of the cascade with the given index.
+    pub fn total_cascade_bias(&self, cascade: usize) -> f32 {
+        self.shadow_bias + self.cascade_bias(cascade)
+    }
+
+    /// Sets new normal offset (in shadow map texels). Normal offset shifts the position of a fragment
+    /// along its normal before fetching the shadow map. Unlike depth bias, it does not make shadows
+    /// "detached" from casters, so it is the preferred way of fighting shadow acne on flat geometry,
+    /// such as sprites in 2.5D games. Values in `[0.5; 2.0]` range usually work well.
+    pub fn set_normal_offset(&mut self, normal_offset: f32) {
+        self.normal_offset = normal_offset.max(0.0);
+    }
+
+    /// Returns current normal offset (in shadow map texels).
+    pub fn normal_offset(&self) -> f32 {
+        self.normal_offset
+    }
+
+    /// Enables or disables shadow "pancaking". When enabled, shadow casters in front of the near
+    /// plane of a cascade are flattened onto it instead of being clipped, which allows the renderer
+    /// to use tight depth range for each cascade. Tight depth range gives much better depth precision
+    /// and thus requires smaller shadow bias. Pancaking is not supported on OpenGL ES, the option is
+    /// ignored there.
+    pub fn set_pancaking(&mut self, pancaking: bool) {
+        self.pancaking = pancaking;
+    }
+
+    /// Returns `true` if shadow pancaking is enabled, `false` - otherwise.
+    pub fn is_pancaking_enabled(&self) -> bool {
+        self.pancaking
+    }
 }
 
 /// See module docs.
@@ -168,6 +310,16 @@ impl NodeTrait for DirectionalLight {
         Self::type_uuid()
     }
 
+    fn collect_render_data(&self, ctx: &mut RenderContext) {
+        self.base_light.collect_light_source(
+            ctx,
+            &self.world_bounding_box(),
+            f32::INFINITY,
+            std::f32::consts::PI.cos(),
+            std::f32::consts::PI.cos(),
+        );
+    }
+
     fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
         ctx.draw_arrow(
             16,
@@ -20,10 +20,15 @@ use crate::{
     core::{
         algebra::Vector3,
         color::Color,
+        math::aabb::AxisAlignedBoundingBox,
         reflect::prelude::*,
         variable::InheritableVariable,
         visitor::{Visit, VisitResult, Visitor},
     },
+    renderer::{
+        self,
+        batch::{LightSource, RenderContext},
+    },
     scene::base::{Base, BaseBuilder},
 };
 use std::ops::{Deref, DerefMut};
@@ -175,6 +180,34 @@ impl BaseLight {
     pub fn is_scatter_enabled(&self) -> bool {
         *self.scatter_enabled
     }
+
+    // Writes the light source to the render data storage, so it could be used by the forward renderer.
+    pub(crate) fn collect_light_source(
+        &self,
+        ctx: &mut RenderContext,
+        world_bounding_box: &AxisAlignedBoundingBox,
+        radius: f32,
+        half_cone_angle_cos: f32,
+        half_hotspot_angle_cos: f32,
+    ) {
+        if !self.global_visibility()
+            || !self.is_globally_enabled()
+            || renderer::is_shadow_pass(ctx.render_pass_name)
+            || !ctx.frustum.is_intersects_aabb(world_bounding_box)
+        {
+            return;
+        }
+
+        ctx.storage.light_sources.push(LightSource {
+            position: self.global_position(),
+            direction: self.up_vector(),
+            color: self.color().as_frgb(),
+            radius,
+            half_cone_angle_cos,
+            half_hotspot_angle_cos,
+            cast_shadows: self.is_cast_shadows(),
+        });
+    }
 }
 
 /// Light scene node builder. Provides easy declarative way of creating light scene
@@ -28,6 +28,7 @@ use crate::{
         visitor::{Visit, VisitResult, Visitor},
         TypeUuidProvider,
     },
+    renderer::batch::RenderContext,
     scene::{
         base::Base,
         debug::SceneDrawingContext,
@@ -124,6 +125,16 @@ impl NodeTrait for PointLight {
         Self::type_uuid()
     }
 
+    fn collect_render_data(&self, ctx: &mut RenderContext) {
+        self.base_light.collect_light_source(
+            ctx,
+            &self.world_bounding_box(),
+            self.radius(),
+            std::f32::consts::PI.cos(),
+            std::f32::consts::PI.cos(),
+        );
+    }
+
     fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
         ctx.draw_wire_sphere(self.global_position(), self.radius(), 30, Color::GREEN);
     }
@@ -34,6 +34,7 @@ use crate::{
         visitor::{Visit, VisitResult, Visitor},
         TypeUuidProvider,
     },
+    renderer::batch::RenderContext,
     resource::texture::TextureResource,
     scene::{
         base::Base,
@@ -210,6 +211,16 @@ impl NodeTrait for SpotLight {
         Self::type_uuid()
     }
 
+    fn collect_render_data(&self, ctx: &mut RenderContext) {
+        self.base_light.collect_light_source(
+            ctx,
+            &self.world_bounding_box(),
+            self.distance(),
+            (self.hotspot_cone_angle() * 0.5).cos(),
+            (self.full_cone_angle() * 0.5).cos(),
+        );
+    }
+
     fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
         ctx.draw_cone(
             16,
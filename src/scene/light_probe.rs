@@ -0,0 +1,264 @@
+//! Light probe volume is a box-shaped region of a scene with baked indirect lighting.
+//!
+//! For more info see [`LightProbeVolume`]
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector3},
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    resource::light_probe::{LightProbeData, LightProbeDataResource, ShIrradiance},
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::SceneDrawingContext,
+        graph::Graph,
+        node::{Node, NodeTrait},
+    },
+};
+use std::ops::{Deref, DerefMut};
+
+/// Light probe volume is a box-shaped region of a scene filled with a regular grid of light probes. Each
+/// probe stores indirect (bounced) lighting at its position, which is then used to light dynamic meshes
+/// (characters, rigid body props, etc.) that are inside the volume. Without it, such objects will be lit
+/// only by direct lights and ambient lighting, which makes interiors look flat and dark away from the
+/// lights.
+///
+/// # Size and transformations
+///
+/// The volume is a unit cube in local coordinates, its exact size is defined by volume's `local scale`
+/// (the same way as for [`crate::scene::decal::Decal`]). Probes are placed evenly inside the volume,
+/// their amount along each axis is defined by [`Self::resolution`].
+///
+/// # Baking
+///
+/// Probes must be baked before use, this is done by [`crate::utils::lightmap::bake_light_probes`] (or
+/// by `Bake Light Probes` button in the editor's lighting panel). Baked data is a resource (see
+/// [`LightProbeData`]), that can be saved on disk and assigned to the volume using
+/// [`Self::set_data`].
+///
+/// # Limitations
+///
+/// Every non-static mesh samples the probes once per frame at the center of its bounding box, so large
+/// meshes get the same lighting across their surface. Static meshes are not affected by light probes,
+/// they should use lightmaps instead. Meshes that are lit by light probes cannot be rendered using
+/// instancing. Only materials with the standard shaders support light probes.
+#[derive(Debug, Visit, Clone, Reflect)]
+pub struct LightProbeVolume {
+    base: Base,
+
+    #[reflect(
+        setter = "set_resolution",
+        description = "Amount of light probes along each axis of the volume. \
+        Light probes must be re-baked after changing this value."
+    )]
+    resolution: InheritableVariable<Vector3<u32>>,
+
+    #[reflect(setter = "set_data")]
+    data: InheritableVariable<Option<LightProbeDataResource>>,
+
+    #[reflect(min_value = 0.0, setter = "set_intensity")]
+    intensity: InheritableVariable<f32>,
+}
+
+impl Default for LightProbeVolume {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            resolution: Vector3::new(4, 4, 4).into(),
+            data: Default::default(),
+            intensity: 1.0.into(),
+        }
+    }
+}
+
+impl Deref for LightProbeVolume {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for LightProbeVolume {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for LightProbeVolume {
+    fn type_uuid() -> Uuid {
+        uuid!("a83e5c1d-7f26-4b09-9d4e-2c61b8f0a7d3")
+    }
+}
+
+impl LightProbeVolume {
+    /// Sets new amount of probes along each axis. Every component is clamped to at least one probe.
+    pub fn set_resolution(&mut self, resolution: Vector3<u32>) -> Vector3<u32> {
+        self.resolution
+            .set_value_and_mark_modified(resolution.map(|v| v.max(1)))
+    }
+
+    /// Returns current amount of probes along each axis.
+    pub fn resolution(&self) -> Vector3<u32> {
+        *self.resolution
+    }
+
+    /// Sets new baked data of the volume.
+    pub fn set_data(
+        &mut self,
+        data: Option<LightProbeDataResource>,
+    ) -> Option<LightProbeDataResource> {
+        self.data.set_value_and_mark_modified(data)
+    }
+
+    /// Returns current baked data of the volume.
+    pub fn data(&self) -> Option<&LightProbeDataResource> {
+        self.data.as_ref()
+    }
+
+    /// Sets new intensity multiplier of the baked lighting.
+    pub fn set_intensity(&mut self, intensity: f32) -> f32 {
+        self.intensity
+            .set_value_and_mark_modified(intensity.max(0.0))
+    }
+
+    /// Returns current intensity multiplier of the baked lighting.
+    pub fn intensity(&self) -> f32 {
+        *self.intensity
+    }
+
+    /// Returns world-space positions of every probe of the volume in X -> Y -> Z order, the same order
+    /// as in [`LightProbeData::probes`].
+    pub fn probe_positions(&self) -> Vec<Vector3<f32>> {
+        let resolution = self.resolution();
+        let transform = self.global_transform();
+        let mut positions =
+            Vec::with_capacity((resolution.x * resolution.y * resolution.z) as usize);
+        for z in 0..resolution.z {
+            for y in 0..resolution.y {
+                for x in 0..resolution.x {
+                    let local = LightProbeData::probe_local_position(resolution, x, y, z);
+                    positions.push(transform.transform_point(&Point3::from(local)).coords);
+                }
+            }
+        }
+        positions
+    }
+
+    /// Returns `true` if the given world-space point is inside the volume.
+    pub fn contains(&self, world_position: Vector3<f32>) -> bool {
+        self.local_position(world_position)
+            .is_some_and(|local| AxisAlignedBoundingBox::unit().is_contains_point(local))
+    }
+
+    fn local_position(&self, world_position: Vector3<f32>) -> Option<Vector3<f32>> {
+        self.global_transform()
+            .try_inverse()
+            .map(|inv| inv.transform_point(&Point3::from(world_position)).coords)
+    }
+
+    /// Samples baked lighting at the given world-space position. Returns `None` if the volume has no
+    /// baked data (or it is not loaded yet), or if the position is outside of the volume. Returned
+    /// irradiance is already multiplied by the intensity of the volume.
+    pub fn sample(&self, world_position: Vector3<f32>) -> Option<ShIrradiance> {
+        let local = self.local_position(world_position)?;
+        if !AxisAlignedBoundingBox::unit().is_contains_point(local) {
+            return None;
+        }
+        let data = self.data.as_ref()?;
+        let mut state = data.state();
+        let data = state.data()?;
+        data.sample(local)
+            .map(|irradiance| irradiance.scale(*self.intensity))
+    }
+}
+
+impl NodeTrait for LightProbeVolume {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::unit()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        ctx.draw_oob(
+            &AxisAlignedBoundingBox::unit(),
+            self.global_transform(),
+            Color::opaque(255, 200, 0),
+        );
+    }
+}
+
+/// Allows you to create a light probe volume in a declarative manner.
+pub struct LightProbeVolumeBuilder {
+    base_builder: BaseBuilder,
+    resolution: Vector3<u32>,
+    data: Option<LightProbeDataResource>,
+    intensity: f32,
+}
+
+impl LightProbeVolumeBuilder {
+    /// Creates a new instance of the builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            resolution: Vector3::new(4, 4, 4),
+            data: None,
+            intensity: 1.0,
+        }
+    }
+
+    /// Sets desired amount of probes along each axis.
+    pub fn with_resolution(mut self, resolution: Vector3<u32>) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Sets desired baked data.
+    pub fn with_data(mut self, data: LightProbeDataResource) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Sets desired intensity of the baked lighting.
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Creates new light probe volume.
+    pub fn build_light_probe_volume(self) -> LightProbeVolume {
+        LightProbeVolume {
+            base: self.base_builder.build_base(),
+            resolution: self.resolution.map(|v| v.max(1)).into(),
+            data: self.data.into(),
+            intensity: self.intensity.max(0.0).into(),
+        }
+    }
+
+    /// Creates new light probe volume node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_light_probe_volume())
+    }
+
+    /// Creates new instance of light probe volume node and puts it in the given graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
@@ -14,11 +14,12 @@ use crate::{
 use fxhash::FxHasher;
 use std::{
     alloc::Layout,
+    collections::VecDeque,
     fmt::{Display, Formatter},
     hash::{Hash, Hasher},
     marker::PhantomData,
     mem::MaybeUninit,
-    ops::{Deref, DerefMut, Index, IndexMut, RangeBounds},
+    ops::{Deref, DerefMut, Index, IndexMut, Range, RangeBounds},
     vec::Drain,
 };
 
@@ -361,8 +362,15 @@ pub struct VertexBuffer {
     layout_hash: u64,
     #[visit(optional)]
     modifications_counter: u64,
+    /// Regions (in bytes) of the most recent modifications along with the value of the modifications
+    /// counter right after each modification. Oldest first.
+    #[visit(skip)]
+    dirty_ranges: VecDeque<(u64, Range<usize>)>,
 }
 
+/// Maximum amount of recent modifications, whose regions are remembered by a vertex buffer.
+const MAX_DIRTY_RANGES: usize = 32;
+
 fn calculate_layout_hash(layout: &[VertexAttribute]) -> u64 {
     let mut hasher = FxHasher::default();
     layout.hash(&mut hasher);
@@ -382,7 +390,8 @@ pub struct VertexBufferRefMut<'a> {
 
 impl<'a> Drop for VertexBufferRefMut<'a> {
     fn drop(&mut self) {
-        self.vertex_buffer.modifications_counter += 1;
+        let len = self.vertex_buffer.data.bytes.len();
+        self.vertex_buffer.mark_modified(0..len);
     }
 }
 
@@ -451,6 +460,18 @@ impl<'a> VertexBufferRefMut<'a> {
         }
     }
 
+    /// Tries to append every vertex of the other vertex buffer. The layouts of the buffers must
+    /// match.
+    pub fn push_vertex_buffer(&mut self, other: &VertexBuffer) -> Result<(), ValidationError> {
+        if self.vertex_buffer.layout_hash != other.layout_hash {
+            Err(ValidationError::IncompatibleLayout)
+        } else {
+            self.vertex_buffer.data.extend_from_slice(&other.data.bytes);
+            self.vertex_buffer.vertex_count += other.vertex_count;
+            Ok(())
+        }
+    }
+
     /// Tries to append the vertices that the given iterator produces.
     ///
     /// # Safety and validation
@@ -713,6 +734,9 @@ pub enum ValidationError {
 
     /// Duplicate shader locations were found.
     ConflictingShaderLocations(usize),
+
+    /// Layouts of vertex buffers do not match.
+    IncompatibleLayout,
 }
 
 impl Display for ValidationError {
@@ -733,6 +757,9 @@ impl Display for ValidationError {
             ValidationError::ConflictingShaderLocations(v) => {
                 write!(f, "Duplicate shader locations were found {v}.")
             }
+            ValidationError::IncompatibleLayout => {
+                write!(f, "Layouts of vertex buffers do not match.")
+            }
         }
     }
 }
@@ -804,6 +831,7 @@ impl VertexBuffer {
             vertex_size: vertex_size_bytes,
             vertex_count: vertex_count as u32,
             modifications_counter: 0,
+            dirty_ranges: Default::default(),
             data: bytes,
             layout_hash: calculate_layout_hash(&dense_layout),
             sparse_layout,
@@ -826,6 +854,80 @@ impl VertexBuffer {
         self.modifications_counter
     }
 
+    /// Overwrites the vertices starting from the given vertex index. Unlike [`Self::modify`], this
+    /// method marks only the written region as modified, so the renderer uploads only this region to
+    /// GPU. It is useful for large buffers that are partially updated every frame (for example, a ring
+    /// buffer of particles).
+    ///
+    /// # Safety and validation
+    ///
+    /// This method accepts any type that has appropriate size, the size must be equal with the size
+    /// defined by layout. The written region must be within the buffer.
+    pub fn write_vertices_at<T>(
+        &mut self,
+        first: usize,
+        vertices: &[T],
+    ) -> Result<(), ValidationError>
+    where
+        T: VertexTrait,
+    {
+        if std::mem::size_of::<T>() != self.vertex_size as usize {
+            return Err(ValidationError::InvalidVertexSize {
+                expected: self.vertex_size,
+                actual: std::mem::size_of::<T>() as u8,
+            });
+        }
+
+        let bytes = array_as_u8_slice(vertices);
+        let start = first * self.vertex_size as usize;
+        let end = start + bytes.len();
+        if end > self.data.bytes.len() {
+            return Err(ValidationError::InvalidDataSize {
+                expected: self.data.bytes.len(),
+                actual: end,
+            });
+        }
+
+        self.data.bytes[start..end].copy_from_slice(bytes);
+        self.mark_modified(start..end);
+
+        Ok(())
+    }
+
+    fn mark_modified(&mut self, range: Range<usize>) {
+        self.modifications_counter += 1;
+        if self.dirty_ranges.len() == MAX_DIRTY_RANGES {
+            self.dirty_ranges.pop_front();
+        }
+        self.dirty_ranges
+            .push_back((self.modifications_counter, range));
+    }
+
+    /// Returns a region of the buffer (in bytes), that was modified since the moment when the buffer had
+    /// the given modifications count (see [`Self::modifications_count`]). Every consumer of the buffer
+    /// should remember the count it has seen last. [`None`] means that the buffer wasn't modified. The
+    /// entire buffer is returned if the modifications are too old to be tracked.
+    pub(crate) fn dirty_range_since(&self, modifications_count: u64) -> Option<Range<usize>> {
+        if modifications_count == self.modifications_counter {
+            return None;
+        }
+
+        let whole = 0..self.data.bytes.len();
+        match self.dirty_ranges.front() {
+            Some((first, _))
+                if *first <= modifications_count + 1
+                    && modifications_count < self.modifications_counter => {}
+            _ => return Some(whole),
+        }
+
+        self.dirty_ranges
+            .iter()
+            .filter(|(count, _)| *count > modifications_count)
+            .map(|(_, range)| range.clone())
+            .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+            .or(Some(whole))
+    }
+
     /// Calculates inner data hash.
     pub fn content_hash(&self) -> u64 {
         calculate_data_hash(&self.data.bytes)
@@ -1420,7 +1522,7 @@ mod test {
         core::algebra::{Vector2, Vector3, Vector4},
         scene::mesh::buffer::{
             VertexAttributeDataType, VertexAttributeDescriptor, VertexAttributeUsage, VertexBuffer,
-            VertexReadTrait,
+            VertexReadTrait, MAX_DIRTY_RANGES,
         },
     };
 
@@ -1609,6 +1711,53 @@ mod test {
         assert_eq!(vertex, VERTICES[2]);
     }
 
+    #[test]
+    fn test_write_vertices_at() {
+        let mut buffer = create_test_buffer();
+        let size = buffer.vertex_size() as usize;
+        let count = buffer.modifications_count();
+
+        buffer.write_vertices_at(1, &[VERTICES[0]]).unwrap();
+
+        assert_eq!(buffer.modifications_count(), count + 1);
+        assert_eq!(buffer.get(1).unwrap(), buffer.get(0).unwrap());
+        assert_eq!(buffer.dirty_range_since(count), Some(size..2 * size));
+        assert_eq!(buffer.dirty_range_since(count + 1), None);
+        assert!(buffer.write_vertices_at(3, &[VERTICES[0]]).is_err());
+    }
+
+    #[test]
+    fn test_dirty_range_per_consumer() {
+        let mut buffer = create_test_buffer();
+        let size = buffer.vertex_size() as usize;
+        let whole = 0..3 * size;
+
+        // The first consumer is up to date, the second one has seen nothing yet.
+        let first = buffer.modifications_count();
+        let second = 0;
+        buffer.write_vertices_at(0, &[VERTICES[1]]).unwrap();
+        assert_eq!(buffer.dirty_range_since(first), Some(0..size));
+
+        // The first consumer uploads the region, but it must stay dirty for the second one.
+        let first = buffer.modifications_count();
+        buffer.write_vertices_at(2, &[VERTICES[1]]).unwrap();
+        assert_eq!(buffer.dirty_range_since(first), Some(2 * size..3 * size));
+        assert_eq!(buffer.dirty_range_since(second), Some(whole.clone()));
+
+        let first = buffer.modifications_count();
+        buffer.modify();
+        assert_eq!(buffer.dirty_range_since(first), Some(whole.clone()));
+
+        // Too old modifications are forgotten, so the entire buffer is dirty.
+        let first = buffer.modifications_count();
+        for _ in 0..=MAX_DIRTY_RANGES {
+            buffer.write_vertices_at(1, &[VERTICES[1]]).unwrap();
+        }
+        assert_eq!(buffer.dirty_range_since(first), Some(whole));
+        let last = buffer.modifications_count() - 1;
+        assert_eq!(buffer.dirty_range_since(last), Some(size..2 * size));
+    }
+
     #[test]
     fn test_remove_last_vertex() {
         let mut buffer = create_test_buffer();
@@ -5,33 +5,36 @@ use crate::{
     core::{
         algebra::{Matrix4, Point3, Vector3},
         color::Color,
-        math::aabb::AxisAlignedBoundingBox,
+        math::{aabb::AxisAlignedBoundingBox, ray::Ray},
         pool::Handle,
         reflect::prelude::*,
+        sstorage::ImmutableString,
         uuid::{uuid, Uuid},
         variable::InheritableVariable,
         visitor::{Visit, VisitResult, Visitor},
         TypeUuidProvider,
     },
+    material::PropertyValue,
     renderer::{
         self,
-        batch::{PersistentIdentifier, RenderContext, SurfaceInstanceData},
+        batch::{PersistentIdentifier, PropertyOverrides, RenderContext, SurfaceInstanceData},
         framework::geometry_buffer::ElementRange,
     },
+    resource::light_probe::ShIrradiance,
     scene::{
         base::{Base, BaseBuilder},
         debug::{Line, SceneDrawingContext},
-        graph::Graph,
+        graph::{Graph, NodePool},
         mesh::{
             buffer::{VertexAttributeUsage, VertexReadTrait},
-            surface::{BlendShape, Surface},
+            surface::{BlendShape, Surface, SurfaceData},
         },
         node::{Node, NodeTrait, UpdateContext},
     },
 };
 use fyrox_core::uuid_provider;
 use std::{
-    cell::Cell,
+    cell::{Cell, Ref, RefCell},
     ops::{Deref, DerefMut},
 };
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
@@ -86,6 +89,51 @@ impl RenderPath {
     }
 }
 
+/// Defines a method that should be used to deform skinned surfaces of a mesh. Skinning is performed on
+/// GPU in both cases.
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Default,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum SkinningMode {
+    /// Linear blend skinning - blends bone matrices linearly. It is the fastest method, but it may
+    /// produce "candy wrapper" artifacts on heavily twisted joints (for example wrists).
+    #[default]
+    Linear,
+
+    /// Dual quaternion skinning - blends rigid bone transformations as dual quaternions, which
+    /// preserves the volume of the mesh around twisted joints. It is a bit slower than linear blend
+    /// skinning and supports only uniform scaling of bones.
+    DualQuaternion,
+}
+
+uuid_provider!(SkinningMode = "5e3c8a1f-9d2b-4a7e-b6f0-1c4d7e9a2b38");
+
+/// A result of ray casting against triangles of a mesh. See [`Mesh::ray_cast`] docs for more info.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshRayCastResult {
+    /// Index of a surface of the mesh, that was hit by the ray.
+    pub surface_index: usize,
+    /// Index of a triangle of the surface, that was hit by the ray.
+    pub triangle_index: usize,
+    /// World-space position of the intersection point.
+    pub position: Vector3<f32>,
+    /// World-space normal of the triangle, that was hit by the ray.
+    pub normal: Vector3<f32>,
+    /// Time of impact in `[0; 1]` range, where `0` is the origin of the ray and `1` is the end of the ray.
+    pub toi: f32,
+}
+
 /// Mesh is a 3D model, each mesh split into multiple surfaces, each surface represents a patch of the mesh with a single material
 /// assigned to each face. See [`Surface`] docs for more info.
 ///
@@ -140,6 +188,22 @@ pub struct Mesh {
     #[visit(optional)]
     blend_shapes: InheritableVariable<Vec<BlendShape>>,
 
+    #[visit(optional)]
+    #[reflect(setter = "set_allow_instancing")]
+    allow_instancing: InheritableVariable<bool>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_skinning_mode")]
+    skinning_mode: InheritableVariable<SkinningMode>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_cpu_skinning")]
+    cpu_skinning: InheritableVariable<bool>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    skinned_vertices: RefCell<Vec<Vec<Vector3<f32>>>>,
+
     #[reflect(hidden)]
     #[visit(skip)]
     local_bounding_box: Cell<AxisAlignedBoundingBox>,
@@ -151,6 +215,21 @@ pub struct Mesh {
     #[reflect(hidden)]
     #[visit(skip)]
     world_bounding_box: Cell<AxisAlignedBoundingBox>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    light_probe_irradiance: Cell<Option<ShIrradiance>>,
+
+    // A position at which the light probe irradiance was sampled.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    light_probe_position: Cell<Option<Vector3<f32>>>,
+
+    // Material property overrides of each surface, shared between frames and rebuilt only when the
+    // overrides are changed.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    property_overrides: RefCell<Vec<Option<PropertyOverrides>>>,
 }
 
 impl Default for Mesh {
@@ -164,6 +243,13 @@ impl Default for Mesh {
             render_path: InheritableVariable::new_modified(RenderPath::Deferred),
             decal_layer_index: InheritableVariable::new_modified(0),
             blend_shapes: Default::default(),
+            allow_instancing: InheritableVariable::new_modified(true),
+            skinning_mode: Default::default(),
+            cpu_skinning: Default::default(),
+            skinned_vertices: Default::default(),
+            light_probe_irradiance: Default::default(),
+            light_probe_position: Default::default(),
+            property_overrides: Default::default(),
         }
     }
 }
@@ -233,6 +319,47 @@ impl Mesh {
         self.blend_shapes.get_value_mut_and_mark_modified()
     }
 
+    /// Returns an index of a blend shape with the given name (if any).
+    pub fn blend_shape_index(&self, name: &str) -> Option<usize> {
+        self.blend_shapes.iter().position(|bs| bs.name == name)
+    }
+
+    /// Returns a weight of a blend shape with the given name (if any). Weights are defined in `[0; 100]` range.
+    pub fn blend_shape_weight(&self, name: &str) -> Option<f32> {
+        self.blend_shapes
+            .iter()
+            .find(|bs| bs.name == name)
+            .map(|bs| bs.weight)
+    }
+
+    /// Sets a weight of a blend shape with the given name. Weights are defined in `[0; 100]` range, where 0 means
+    /// that the blend shape has no effect and 100 means that the blend shape is fully applied. Returns previous
+    /// weight of the blend shape or `None` if there's no blend shape with the given name.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use fyrox::scene::mesh::Mesh;
+    /// fn smile(mesh: &mut Mesh) {
+    ///     mesh.set_blend_shape_weight("Smile", 70.0);
+    /// }
+    /// ```
+    ///
+    /// Blend shape weights could also be animated using property tracks with `blend_shapes[N].weight` path,
+    /// where `N` is an index of a blend shape (see [`Self::blend_shape_index`]).
+    pub fn set_blend_shape_weight(&mut self, name: &str, weight: f32) -> Option<f32> {
+        let index = self.blend_shape_index(name)?;
+        let blend_shape = &mut self.blend_shapes.get_value_mut_and_mark_modified()[index];
+        Some(std::mem::replace(&mut blend_shape.weight, weight))
+    }
+
+    /// Sets weights of all blend shapes to zero, effectively restoring the base shape of the mesh.
+    pub fn reset_blend_shapes(&mut self) {
+        for blend_shape in self.blend_shapes.get_value_mut_and_mark_modified() {
+            blend_shape.weight = 0.0;
+        }
+    }
+
     /// Sets new render path for the mesh.
     pub fn set_render_path(&mut self, render_path: RenderPath) -> RenderPath {
         self.render_path.set_value_and_mark_modified(render_path)
@@ -247,58 +374,135 @@ impl Mesh {
     /// This method is very heavy and not intended to use every frame!
     pub fn accurate_world_bounding_box(&self, graph: &Graph) -> AxisAlignedBoundingBox {
         let mut bounding_box = AxisAlignedBoundingBox::default();
-        for surface in self.surfaces.iter() {
+        for vertices in self.compute_skinned_vertices(graph) {
+            for vertex in vertices {
+                bounding_box.add_point(vertex);
+            }
+        }
+        bounding_box
+    }
+
+    /// Calculates world-space positions of vertices of every surface of the mesh, including influence
+    /// of bones (skinning is performed on CPU). Every element of the returned array contains vertices
+    /// of a respective surface. This method is heavy and not intended to be used every frame, use
+    /// [`Self::set_cpu_skinning`] if you need skinned vertices on every frame.
+    pub fn compute_skinned_vertices(&self, graph: &Graph) -> Vec<Vec<Vector3<f32>>> {
+        let mut vertices = Vec::new();
+        self.skin_vertices(&mut vertices, |bone| {
+            graph
+                .try_get(bone)
+                .map(|bone| bone.global_transform() * bone.inv_bind_pose_transform())
+        });
+        vertices
+    }
+
+    fn skin_vertices<F>(&self, vertices: &mut Vec<Vec<Vector3<f32>>>, mut bone_matrix: F)
+    where
+        F: FnMut(Handle<Node>) -> Option<Matrix4<f32>>,
+    {
+        vertices.resize_with(self.surfaces.len(), Default::default);
+
+        let global_transform = self.global_transform();
+
+        for (surface, surface_vertices) in self.surfaces.iter().zip(vertices.iter_mut()) {
+            // Precalculate bone matrices first to speed up calculations.
+            let bone_matrices = surface
+                .bones()
+                .iter()
+                .map(|&bone| bone_matrix(bone).unwrap_or_else(Matrix4::identity))
+                .collect::<Vec<Matrix4<f32>>>();
+
             let data = surface.data();
             let data = data.lock();
-            if surface.bones().is_empty() {
-                for view in data.vertex_buffer.iter() {
-                    bounding_box.add_point(
-                        self.global_transform()
-                            .transform_point(&Point3::from(
-                                view.read_3_f32(VertexAttributeUsage::Position).unwrap(),
-                            ))
-                            .coords,
-                    );
+            skin_surface_vertices(&data, &global_transform, &bone_matrices, surface_vertices);
+        }
+    }
+
+    /// Enables or disables skinning on CPU. When enabled, world-space positions of vertices of every
+    /// surface (including influence of bones) are calculated on every update of the mesh and could
+    /// be fetched using [`Self::skinned_vertices`]. It allows you to perform precise per-triangle
+    /// queries (ray casting, hit detection, etc.) on animated characters. CPU skinning is quite heavy,
+    /// so it should be enabled only for meshes that really need it. Keep in mind, that the vertices
+    /// are calculated using the transforms of bones at the moment of the update of the mesh, thus
+    /// they could be one frame behind, if the bones are animated after the mesh was updated.
+    /// Rendering is not affected by this option, skinning is still performed on GPU.
+    pub fn set_cpu_skinning(&mut self, enabled: bool) -> bool {
+        if !enabled {
+            self.skinned_vertices.borrow_mut().clear();
+        }
+        self.cpu_skinning.set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns `true` if CPU skinning is enabled, `false` - otherwise.
+    pub fn is_cpu_skinning_enabled(&self) -> bool {
+        *self.cpu_skinning
+    }
+
+    /// Returns world-space positions of vertices of every surface, that were calculated during the last
+    /// update of the mesh. The array will be empty, if CPU skinning is disabled. See
+    /// [`Self::set_cpu_skinning`] docs for more info.
+    pub fn skinned_vertices(&self) -> Ref<'_, Vec<Vec<Vector3<f32>>>> {
+        self.skinned_vertices.borrow()
+    }
+
+    /// Casts a ray against every triangle of the mesh and returns the closest intersection (if any).
+    /// The ray must be in world space. If CPU skinning is enabled, the vertices calculated during the
+    /// last update will be used (see [`Self::set_cpu_skinning`]), so the test takes the actual pose of
+    /// animated characters into account. Otherwise, the vertices are transformed using the global
+    /// transform of the mesh, ignoring influence of bones.
+    pub fn ray_cast(&self, ray: &Ray) -> Option<MeshRayCastResult> {
+        let skinned_vertices = self.skinned_vertices.borrow();
+        let global_transform = self.global_transform();
+
+        let mut closest: Option<MeshRayCastResult> = None;
+        for (surface_index, surface) in self.surfaces.iter().enumerate() {
+            let data = surface.data();
+            let data = data.lock();
+
+            let vertex_position = |index: u32| -> Option<Vector3<f32>> {
+                if *self.cpu_skinning {
+                    skinned_vertices
+                        .get(surface_index)
+                        .and_then(|vertices| vertices.get(index as usize))
+                        .cloned()
+                } else {
+                    data.vertex_buffer
+                        .get(index as usize)
+                        .and_then(|view| view.read_3_f32(VertexAttributeUsage::Position).ok())
+                        .map(|position| {
+                            global_transform
+                                .transform_point(&Point3::from(position))
+                                .coords
+                        })
                 }
-            } else {
-                // Special case for skinned surface. Its actual bounds defined only by bones
-                // influence.
-
-                // Precalculate bone matrices first to speed up calculations.
-                let bone_matrices = surface
-                    .bones()
-                    .iter()
-                    .map(|&b| {
-                        let bone_node = &graph[b];
-                        bone_node.global_transform() * bone_node.inv_bind_pose_transform()
-                    })
-                    .collect::<Vec<Matrix4<f32>>>();
+            };
 
-                for view in data.vertex_buffer.iter() {
-                    let mut position = Vector3::default();
-                    for (&bone_index, &weight) in view
-                        .read_4_u8(VertexAttributeUsage::BoneIndices)
-                        .unwrap()
-                        .iter()
-                        .zip(
-                            view.read_4_f32(VertexAttributeUsage::BoneWeight)
-                                .unwrap()
-                                .iter(),
-                        )
-                    {
-                        position += bone_matrices[bone_index as usize]
-                            .transform_point(&Point3::from(
-                                view.read_3_f32(VertexAttributeUsage::Position).unwrap(),
-                            ))
-                            .coords
-                            .scale(weight);
+            for (triangle_index, triangle) in data.geometry_buffer.iter().enumerate() {
+                let (Some(a), Some(b), Some(c)) = (
+                    vertex_position(triangle[0]),
+                    vertex_position(triangle[1]),
+                    vertex_position(triangle[2]),
+                ) else {
+                    continue;
+                };
+
+                if let Some((toi, position)) = ray.triangle_intersection(&[a, b, c]) {
+                    if !closest.as_ref().is_some_and(|closest| toi >= closest.toi) {
+                        closest = Some(MeshRayCastResult {
+                            surface_index,
+                            triangle_index,
+                            position,
+                            normal: (b - a)
+                                .cross(&(c - a))
+                                .try_normalize(f32::EPSILON)
+                                .unwrap_or_default(),
+                            toi,
+                        });
                     }
-
-                    bounding_box.add_point(position);
                 }
             }
         }
-        bounding_box
+        closest
     }
 
     /// Sets new decal layer index. It defines which decals will be applies to the mesh,
@@ -312,6 +516,159 @@ impl Mesh {
     pub fn decal_layer_index(&self) -> u8 {
         *self.decal_layer_index
     }
+
+    /// Defines whether the surfaces of the mesh could be rendered using GPU instancing. The renderer
+    /// automatically merges surfaces with the same data and material into a single draw call, bone
+    /// matrices of skinned surfaces are packed into a single palette texture shared across instances
+    /// (which is useful for crowds of characters). Disable it if the mesh uses a custom shader, that
+    /// relies on per-object uniforms (for example, `fyrox_worldMatrix`) and does not support instancing.
+    /// Default is `true`.
+    pub fn set_allow_instancing(&mut self, allow: bool) -> bool {
+        self.allow_instancing.set_value_and_mark_modified(allow)
+    }
+
+    /// Returns `true` if the surfaces of the mesh could be rendered using GPU instancing.
+    pub fn allow_instancing(&self) -> bool {
+        *self.allow_instancing
+    }
+
+    /// Sets a method that should be used to deform skinned surfaces of the mesh. See [`SkinningMode`]
+    /// docs for more info.
+    pub fn set_skinning_mode(&mut self, mode: SkinningMode) -> SkinningMode {
+        self.skinning_mode.set_value_and_mark_modified(mode)
+    }
+
+    /// Returns current skinning mode of the mesh.
+    pub fn skinning_mode(&self) -> SkinningMode {
+        *self.skinning_mode
+    }
+
+    /// Returns baked indirect lighting at the position of the mesh, sampled from a light probe volume
+    /// the mesh is in. It is updated by the graph for non-static meshes when they're moved or when
+    /// the volumes are changed, see [`crate::scene::light_probe::LightProbeVolume`] docs for more info.
+    pub fn light_probe_irradiance(&self) -> Option<ShIrradiance> {
+        self.light_probe_irradiance.get()
+    }
+
+    pub(crate) fn light_probe_position(&self) -> Option<Vector3<f32>> {
+        self.light_probe_position.get()
+    }
+
+    pub(crate) fn set_light_probe_irradiance(
+        &self,
+        position: Option<Vector3<f32>>,
+        irradiance: Option<ShIrradiance>,
+    ) {
+        self.light_probe_position.set(position);
+        self.light_probe_irradiance.set(irradiance);
+    }
+
+    fn update_skinned_vertices(&self, nodes: &NodePool) {
+        let mut skinned_vertices = self.skinned_vertices.borrow_mut();
+        self.skin_vertices(&mut skinned_vertices, |bone| {
+            nodes
+                .try_borrow(bone)
+                .map(|bone| bone.global_transform() * bone.inv_bind_pose_transform())
+        });
+    }
+}
+
+fn skin_surface_vertices(
+    data: &SurfaceData,
+    global_transform: &Matrix4<f32>,
+    bone_matrices: &[Matrix4<f32>],
+    vertices: &mut Vec<Vector3<f32>>,
+) {
+    vertices.clear();
+
+    for view in data.vertex_buffer.iter() {
+        let Ok(position) = view.read_3_f32(VertexAttributeUsage::Position) else {
+            continue;
+        };
+
+        if bone_matrices.is_empty() {
+            vertices.push(
+                global_transform
+                    .transform_point(&Point3::from(position))
+                    .coords,
+            );
+        } else {
+            // Special case for skinned surface. Bone matrices are already in world space, so the
+            // global transform of the mesh is not used.
+            let mut skinned_position = Vector3::default();
+            if let (Ok(bone_indices), Ok(bone_weights)) = (
+                view.read_4_u8(VertexAttributeUsage::BoneIndices),
+                view.read_4_f32(VertexAttributeUsage::BoneWeight),
+            ) {
+                for (&bone_index, &weight) in bone_indices.iter().zip(bone_weights.iter()) {
+                    if let Some(bone_matrix) = bone_matrices.get(bone_index as usize) {
+                        skinned_position += bone_matrix
+                            .transform_point(&Point3::from(position))
+                            .coords
+                            .scale(weight);
+                    }
+                }
+            }
+            vertices.push(skinned_position);
+        }
+    }
+}
+
+const LIGHT_PROBE_SH_PROPERTY_NAME: &str = "lightProbeSH";
+
+fn is_same_property_overrides(
+    cached: Option<&[(ImmutableString, PropertyValue)]>,
+    surface: &Surface,
+    light_probe_irradiance: Option<&ShIrradiance>,
+) -> bool {
+    let overrides = surface.property_overrides();
+    let cached = cached.unwrap_or_default();
+    if cached.len() != overrides.len() + light_probe_irradiance.is_some() as usize {
+        return false;
+    }
+
+    let (cached_overrides, cached_irradiance) = cached.split_at(overrides.len());
+    cached_overrides
+        .iter()
+        .zip(overrides)
+        .all(|((name, value), property_override)| {
+            **name == property_override.name && *value == property_override.value
+        })
+        && match (cached_irradiance.first(), light_probe_irradiance) {
+            (Some((_, PropertyValue::Vector3Array(coefficients))), Some(irradiance)) => {
+                coefficients.as_slice() == irradiance.coefficients.as_slice()
+            }
+            (None, None) => true,
+            _ => false,
+        }
+}
+
+fn make_property_overrides(
+    surface: &Surface,
+    light_probe_irradiance: Option<&ShIrradiance>,
+) -> Option<PropertyOverrides> {
+    if surface.property_overrides().is_empty() && light_probe_irradiance.is_none() {
+        return None;
+    }
+
+    Some(
+        surface
+            .property_overrides()
+            .iter()
+            .map(|property_override| {
+                (
+                    ImmutableString::new(&property_override.name),
+                    property_override.value.clone(),
+                )
+            })
+            .chain(light_probe_irradiance.map(|irradiance| {
+                (
+                    ImmutableString::new(LIGHT_PROBE_SH_PROPERTY_NAME),
+                    PropertyValue::Vector3Array(irradiance.coefficients.to_vec()),
+                )
+            }))
+            .collect(),
+    )
 }
 
 impl NodeTrait for Mesh {
@@ -347,6 +704,10 @@ impl NodeTrait for Mesh {
     }
 
     fn update(&mut self, context: &mut UpdateContext) {
+        if *self.cpu_skinning {
+            self.update_skinned_vertices(context.nodes);
+        }
+
         if self.surfaces.iter().any(|s| !s.bones.is_empty()) {
             let mut world_aabb = self
                 .local_bounding_box()
@@ -382,9 +743,23 @@ impl NodeTrait for Mesh {
             return;
         }
 
+        let light_probe_irradiance = self.light_probe_irradiance.get();
+        let mut property_overrides = self.property_overrides.borrow_mut();
+        property_overrides.resize(self.surfaces.len(), None);
+
         for (index, surface) in self.surfaces().iter().enumerate() {
             let is_skinned = !surface.bones.is_empty();
 
+            let property_overrides = &mut property_overrides[index];
+            if !is_same_property_overrides(
+                property_overrides.as_deref(),
+                surface,
+                light_probe_irradiance.as_ref(),
+            ) {
+                *property_overrides =
+                    make_property_overrides(surface, light_probe_irradiance.as_ref());
+            }
+
             let world = if is_skinned {
                 Matrix4::identity()
             } else {
@@ -423,6 +798,10 @@ impl NodeTrait for Mesh {
                         index,
                     ),
                     node_handle: self.self_handle,
+                    allow_instancing: *self.allow_instancing,
+                    use_dual_quaternion_skinning: *self.skinning_mode
+                        == SkinningMode::DualQuaternion,
+                    property_overrides: property_overrides.clone(),
                 },
             );
         }
@@ -489,6 +868,9 @@ pub struct MeshBuilder {
     render_path: RenderPath,
     decal_layer_index: u8,
     blend_shapes: Vec<BlendShape>,
+    allow_instancing: bool,
+    skinning_mode: SkinningMode,
+    cpu_skinning: bool,
 }
 
 impl MeshBuilder {
@@ -500,6 +882,9 @@ impl MeshBuilder {
             render_path: RenderPath::Deferred,
             decal_layer_index: 0,
             blend_shapes: Default::default(),
+            allow_instancing: true,
+            skinning_mode: Default::default(),
+            cpu_skinning: false,
         }
     }
 
@@ -529,6 +914,24 @@ impl MeshBuilder {
         self
     }
 
+    /// Defines whether the surfaces of the mesh could be rendered using GPU instancing.
+    pub fn with_allow_instancing(mut self, allow: bool) -> Self {
+        self.allow_instancing = allow;
+        self
+    }
+
+    /// Sets desired skinning mode. See [`SkinningMode`] docs for more info.
+    pub fn with_skinning_mode(mut self, mode: SkinningMode) -> Self {
+        self.skinning_mode = mode;
+        self
+    }
+
+    /// Enables or disables CPU skinning. See [`Mesh::set_cpu_skinning`] docs for more info.
+    pub fn with_cpu_skinning(mut self, enabled: bool) -> Self {
+        self.cpu_skinning = enabled;
+        self
+    }
+
     /// Creates new mesh.
     pub fn build_node(self) -> Node {
         Node::new(Mesh {
@@ -540,6 +943,13 @@ impl MeshBuilder {
             render_path: self.render_path.into(),
             decal_layer_index: self.decal_layer_index.into(),
             world_bounding_box: Default::default(),
+            allow_instancing: self.allow_instancing.into(),
+            skinning_mode: self.skinning_mode.into(),
+            cpu_skinning: self.cpu_skinning.into(),
+            skinned_vertices: Default::default(),
+            light_probe_irradiance: Default::default(),
+            light_probe_position: Default::default(),
+            property_overrides: Default::default(),
         })
     }
 
@@ -548,3 +958,57 @@ impl MeshBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Matrix4, color::Color},
+        material::PropertyValue,
+        resource::light_probe::ShIrradiance,
+        scene::mesh::{
+            is_same_property_overrides, make_property_overrides,
+            surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+        },
+    };
+
+    #[test]
+    fn test_property_overrides_cache() {
+        let mut surface = SurfaceBuilder::new(SurfaceSharedData::new(SurfaceData::make_cube(
+            Matrix4::identity(),
+        )))
+        .build();
+        assert!(make_property_overrides(&surface, None).is_none());
+        assert!(is_same_property_overrides(None, &surface, None));
+
+        surface.set_property_override("diffuseColor", PropertyValue::Color(Color::RED));
+        assert!(!is_same_property_overrides(None, &surface, None));
+        let overrides = make_property_overrides(&surface, None);
+        assert!(is_same_property_overrides(
+            overrides.as_deref(),
+            &surface,
+            None
+        ));
+
+        let irradiance = ShIrradiance::default();
+        assert!(!is_same_property_overrides(
+            overrides.as_deref(),
+            &surface,
+            Some(&irradiance)
+        ));
+        let overrides = make_property_overrides(&surface, Some(&irradiance));
+        assert_eq!(overrides.as_deref().map(|o| o.len()), Some(2));
+        assert!(is_same_property_overrides(
+            overrides.as_deref(),
+            &surface,
+            Some(&irradiance)
+        ));
+
+        surface.set_property_override("diffuseColor", PropertyValue::Color(Color::GREEN));
+        assert_eq!(surface.property_overrides().len(), 1);
+        assert!(!is_same_property_overrides(
+            overrides.as_deref(),
+            &surface,
+            Some(&irradiance)
+        ));
+    }
+}
@@ -15,7 +15,7 @@ use crate::{
         visitor::{Visit, VisitResult, Visitor},
     },
     material,
-    material::{Material, MaterialResource},
+    material::{Material, MaterialResource, PropertyValue},
     resource::texture::{TextureKind, TexturePixelKind, TextureResource, TextureResourceExtension},
     scene::{
         mesh::{
@@ -57,6 +57,16 @@ impl Default for BlendShape {
     }
 }
 
+impl BlendShape {
+    /// Creates new blend shape with the given name and weight. Weight is defined in `[0; 100]` range.
+    pub fn new<S: AsRef<str>>(name: S, weight: f32) -> Self {
+        Self {
+            weight,
+            name: name.as_ref().to_owned(),
+        }
+    }
+}
+
 /// A container for multiple blend shapes/
 #[derive(Debug, Clone, Default)]
 pub struct BlendShapesContainer {
@@ -1247,6 +1257,12 @@ pub struct Surface {
     )]
     unique_material: InheritableVariable<bool>,
 
+    #[reflect(
+        description = "A set of material property values that override the values of the material only \
+        for this surface."
+    )]
+    property_overrides: InheritableVariable<Vec<PropertyOverride>>,
+
     // Temporal array for FBX conversion needs, it holds skinning data (weight + bone handle)
     // and will be used to fill actual bone indices and weight in vertices that will be
     // sent to GPU. The idea is very simple: GPU needs to know only indices of matrices of
@@ -1259,6 +1275,18 @@ pub struct Surface {
 
 uuid_provider!(Surface = "485caf12-4e7d-4b1a-b6bd-0681fd92f789");
 
+/// A value of a material property, that overrides the value of the property of the material of a
+/// surface. See [`Surface::set_property_override`] for more info.
+#[derive(Debug, Visit, Reflect, Default, Clone, PartialEq)]
+pub struct PropertyOverride {
+    /// Name of the property in the shader of the material.
+    pub name: String,
+    /// New value of the property, it must have the same type as the property in the shader.
+    pub value: PropertyValue,
+}
+
+uuid_provider!(PropertyOverride = "8e83834b-31f9-4859-ba7b-8a6012113e82");
+
 impl Clone for Surface {
     fn clone(&self) -> Self {
         Self {
@@ -1272,6 +1300,7 @@ impl Clone for Surface {
             },
             bones: self.bones.clone(),
             unique_material: self.unique_material.clone(),
+            property_overrides: self.property_overrides.clone(),
             vertex_weights: self.vertex_weights.clone(),
         }
     }
@@ -1295,6 +1324,9 @@ impl Visit for Surface {
         self.data.visit("Data", &mut region)?;
         self.bones.visit("Bones", &mut region)?;
         let _ = self.unique_material.visit("UniqueMaterial", &mut region); // Backward compatibility.
+        let _ = self
+            .property_overrides
+            .visit("PropertyOverrides", &mut region); // Backward compatibility.
 
         Ok(())
     }
@@ -1308,6 +1340,7 @@ impl Default for Surface {
             vertex_weights: Default::default(),
             bones: Default::default(),
             unique_material: Default::default(),
+            property_overrides: Default::default(),
         }
     }
 }
@@ -1347,6 +1380,12 @@ impl Surface {
         &self.data
     }
 
+    /// Sets new data for the surface.
+    #[inline]
+    pub fn set_data(&mut self, data: SurfaceSharedData) {
+        self.data.set_value_and_mark_modified(data);
+    }
+
     /// Returns current material of the surface.
     pub fn material(&self) -> &MaterialResource {
         &self.material
@@ -1372,6 +1411,50 @@ impl Surface {
     pub fn set_unique_material(&mut self, unique: bool) {
         self.unique_material.set_value_and_mark_modified(unique);
     }
+
+    /// Overrides a value of the material property with the given name only for this surface. It is
+    /// a cheap alternative to unique materials (see [`Self::set_unique_material`]), when you need to
+    /// change a few properties (for example - a color) on some instances, while sharing the material
+    /// between all of them. The value must have the same type as the property in the shader. Keep
+    /// in mind, that surfaces with overridden properties cannot be rendered using instancing.
+    pub fn set_property_override(&mut self, name: &str, value: PropertyValue) {
+        let property_overrides = self.property_overrides.get_value_mut_and_mark_modified();
+        if let Some(property_override) = property_overrides.iter_mut().find(|p| p.name == name) {
+            property_override.value = value;
+        } else {
+            property_overrides.push(PropertyOverride {
+                name: name.to_owned(),
+                value,
+            });
+        }
+    }
+
+    /// Removes the override of the material property with the given name and returns its value
+    /// (if any).
+    pub fn remove_property_override(&mut self, name: &str) -> Option<PropertyValue> {
+        let index = self
+            .property_overrides
+            .iter()
+            .position(|p| p.name == name)?;
+        Some(
+            self.property_overrides
+                .get_value_mut_and_mark_modified()
+                .remove(index)
+                .value,
+        )
+    }
+
+    /// Removes all material property overrides of the surface.
+    pub fn clear_property_overrides(&mut self) {
+        self.property_overrides
+            .get_value_mut_and_mark_modified()
+            .clear();
+    }
+
+    /// Returns a set of overridden material properties of the surface.
+    pub fn property_overrides(&self) -> &[PropertyOverride] {
+        &self.property_overrides
+    }
 }
 
 /// Surface builder allows you to create surfaces in declarative manner.
@@ -1380,6 +1463,7 @@ pub struct SurfaceBuilder {
     material: Option<MaterialResource>,
     bones: Vec<Handle<Node>>,
     unique_material: bool,
+    property_overrides: Vec<PropertyOverride>,
 }
 
 impl SurfaceBuilder {
@@ -1390,6 +1474,7 @@ impl SurfaceBuilder {
             material: None,
             bones: Default::default(),
             unique_material: false,
+            property_overrides: Default::default(),
         }
     }
 
@@ -1411,6 +1496,17 @@ impl SurfaceBuilder {
         self
     }
 
+    /// Overrides a value of the material property with the given name only for the new surface. See
+    /// [`Surface::set_property_override`] for more info.
+    pub fn with_property_override(mut self, name: &str, value: PropertyValue) -> Self {
+        self.property_overrides.retain(|p| p.name != name);
+        self.property_overrides.push(PropertyOverride {
+            name: name.to_owned(),
+            value,
+        });
+        self
+    }
+
     /// Creates new instance of surface.
     pub fn build(self) -> Surface {
         Surface {
@@ -1424,6 +1520,7 @@ impl SurfaceBuilder {
             vertex_weights: Default::default(),
             bones: self.bones.into(),
             unique_material: self.unique_material.into(),
+            property_overrides: self.property_overrides.into(),
         }
     }
 }
@@ -9,30 +9,46 @@ pub mod animation;
 pub mod base;
 pub mod camera;
 pub mod collider;
+pub mod csg;
 pub mod debug;
 pub mod decal;
 pub mod dim2;
+pub mod folder;
 pub mod graph;
+pub mod ik;
 pub mod joint;
 pub mod light;
+pub mod light_probe;
 pub mod mesh;
 pub mod navmesh;
 pub mod node;
 pub mod particle_system;
+pub mod persistence;
 pub mod pivot;
+pub mod pool;
 pub mod ragdoll;
+pub mod report;
 pub mod rigidbody;
+pub mod sorting;
 pub mod sound;
 pub mod sprite;
+pub mod streaming;
 pub mod terrain;
+pub mod time_control;
+pub mod timeline;
+pub mod trail;
 pub mod transform;
+pub mod water;
 
 use crate::{
     asset::{self, manager::ResourceManager, untyped::UntypedResource},
     core::{
         algebra::Vector2,
         color::Color,
-        futures::future::join_all,
+        futures::{
+            future::join_all,
+            stream::{FuturesUnordered, StreamExt},
+        },
         log::{Log, MessageKind},
         pool::{Handle, Pool, Ticket},
         reflect::prelude::*,
@@ -43,25 +59,32 @@ use crate::{
     resource::texture::TextureResource,
     scene::{
         base::BaseBuilder,
-        camera::Camera,
+        camera::{Camera, OrthographicProjection, Projection},
         debug::SceneDrawingContext,
         graph::{map::NodeHandleMap, Graph, GraphPerformanceStatistics, GraphUpdateSwitches},
         navmesh::NavigationalMeshBuilder,
         node::Node,
+        report::SceneReport,
         sound::SoundEngine,
+        time_control::TimeControl,
     },
-    utils::navmesh::Navmesh,
+    utils::{navmesh::Navmesh, tween::TweenManager},
 };
 use asset::io::ResourceIo;
 use fxhash::FxHashSet;
-use fyrox_core::variable::InheritableVariable;
+use fyrox_core::{uuid_provider, variable::InheritableVariable};
 use std::{
     fmt::{Display, Formatter},
+    future::Future,
     ops::{Index, IndexMut},
     path::Path,
     path::PathBuf,
-    sync::Arc,
+    sync::{
+        atomic::{self, AtomicU32, AtomicUsize},
+        Arc,
+    },
 };
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
 /// A container for navigational meshes.
 #[derive(Default, Clone, Debug, Visit)]
@@ -160,6 +183,18 @@ pub struct SceneRenderingOptions {
 
     /// Color of the ambient lighting.
     pub ambient_lighting_color: Color,
+
+    /// If `true`, then surfaces of static meshes will be merged into static batches when the scene
+    /// is loaded. See [`crate::utils::batching::StaticBatchStorage`] docs for more info.
+    #[visit(optional)]
+    pub static_batching: bool,
+
+    /// Amount of pixels per one world unit, that is used for cameras of 2D scenes (see [`SceneMode::Mode2D`]).
+    /// Every camera created for a 2D scene by the editor uses pixel-perfect orthographic projection with this
+    /// value, see [`OrthographicProjection::pixel_perfect`] for more info. Default is 100.
+    #[visit(optional)]
+    #[reflect(min_value = 1.0, step = 1.0)]
+    pub pixels_per_unit: f32,
 }
 
 impl Default for SceneRenderingOptions {
@@ -169,6 +204,8 @@ impl Default for SceneRenderingOptions {
             clear_color: None,
             polygon_rasterization_mode: Default::default(),
             ambient_lighting_color: Color::opaque(100, 100, 100),
+            static_batching: false,
+            pixels_per_unit: 100.0,
         }
     }
 }
@@ -180,10 +217,45 @@ impl Clone for SceneRenderingOptions {
             clear_color: self.clear_color,
             polygon_rasterization_mode: self.polygon_rasterization_mode,
             ambient_lighting_color: self.ambient_lighting_color,
+            static_batching: self.static_batching,
+            pixels_per_unit: self.pixels_per_unit,
         }
     }
 }
 
+/// Defines whether a scene is a 3D or a 2D scene. The mode does not restrict the set of nodes that
+/// could be used in the scene, instead it tweaks the engine to do less work for 2D games:
+///
+/// - 3D physics is not simulated, the 2D physics world is the primary one.
+/// - 3D-only rendering effects (shadows, ambient occlusion, light scattering, parallax mapping) are
+///   disabled, see [`crate::renderer::QualitySettings::without_3d_effects`].
+/// - Cameras created for the scene use pixel-perfect orthographic projection, see
+///   [`Scene::default_camera_projection`].
+/// - The editor uses orthographic projection for its camera and draws only 2D debug geometry.
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Default,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum SceneMode {
+    /// Generic 3D scene. It is the default mode.
+    #[default]
+    Mode3D,
+    /// 2D scene. See [`SceneMode`] docs for more info.
+    Mode2D,
+}
+
+uuid_provider!(SceneMode = "e2f782f5-2a4c-480d-bc91-d0e66106ce26");
+
 /// See module docs.
 #[derive(Debug, Reflect)]
 pub struct Scene {
@@ -211,6 +283,24 @@ pub struct Scene {
     /// to false for menu's scene and when you need to open a menu - set it to true and
     /// set `enabled` flag to false for level's scene.
     pub enabled: InheritableVariable<bool>,
+
+    /// Mode of the scene. See [`SceneMode`] docs for more info.
+    pub mode: InheritableVariable<SceneMode>,
+
+    /// Speed of time of the scene. `1.0` - normal speed, `0.5` - two times slower, etc. It affects
+    /// every node, physics and sounds of the scene. Default is `1.0`.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub time_scale: InheritableVariable<f32>,
+
+    /// Temporary time dilations (hit-stop, bullet-time, etc.), that are layered on top of the
+    /// [`Self::time_scale`]. See [`TimeControl`] docs for more info.
+    #[reflect(hidden)]
+    pub time_control: TimeControl,
+
+    /// Tweens of the scene, that allows you to animate simple values (position, scale, color, etc.) of scene nodes
+    /// without creating animations. See [`TweenManager`] docs for more info.
+    #[reflect(hidden)]
+    pub tweens: TweenManager,
 }
 
 impl Default for Scene {
@@ -221,6 +311,10 @@ impl Default for Scene {
             drawing_context: Default::default(),
             performance_statistics: Default::default(),
             enabled: true.into(),
+            mode: Default::default(),
+            time_scale: 1.0.into(),
+            time_control: Default::default(),
+            tweens: Default::default(),
         }
     }
 }
@@ -245,7 +339,9 @@ impl Display for PerformanceStatistics {
             \tPhysics 2D: {:?}\n\
             \t\tSimulation: {:?}\n\
             \t\tRay cast: {:?}\n\
-            \tHierarchy: {:?}",
+            \tHierarchy: {:?}\n\
+            \tNodes: {:?}\n\
+            \t\tAnimation: {:?}",
             self.graph.total(),
             self.graph.sync_time,
             self.graph.sound_update_time,
@@ -256,10 +352,98 @@ impl Display for PerformanceStatistics {
             self.graph.physics2d.step_time,
             self.graph.physics2d.total_ray_cast_time.get(),
             self.graph.hierarchical_properties_time,
+            self.graph.node_update_time,
+            self.graph.animation_time,
         )
     }
 }
 
+/// A stage of scene loading. See [`SceneLoadProgress`] docs for more info.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum SceneLoadStage {
+    /// Scene file is being read.
+    ReadingFile = 0,
+    /// Scene content is being deserialized.
+    Deserializing = 1,
+    /// Resources used by the scene are being loaded.
+    LoadingResources = 2,
+    /// Scene is being resolved (see [`Scene::resolve`]).
+    Resolving = 3,
+    /// Scene is fully loaded (or failed to load).
+    Done = 4,
+}
+
+impl SceneLoadStage {
+    fn from_u32(value: u32) -> Self {
+        match value {
+            0 => Self::ReadingFile,
+            1 => Self::Deserializing,
+            2 => Self::LoadingResources,
+            3 => Self::Resolving,
+            _ => Self::Done,
+        }
+    }
+}
+
+#[derive(Default)]
+struct SceneLoadProgressState {
+    stage: AtomicU32,
+    loaded_resources: AtomicUsize,
+    total_resources: AtomicUsize,
+}
+
+/// Shared progress of scene loading. It can be cloned and sent to other threads, every clone refers
+/// to the same progress. It is updated by the loading task and could be used to render loading screens.
+#[derive(Clone, Default)]
+pub struct SceneLoadProgress(Arc<SceneLoadProgressState>);
+
+impl SceneLoadProgress {
+    fn set_stage(&self, stage: SceneLoadStage) {
+        self.0.stage.store(stage as u32, atomic::Ordering::Release);
+    }
+
+    /// Returns current loading stage.
+    pub fn stage(&self) -> SceneLoadStage {
+        SceneLoadStage::from_u32(self.0.stage.load(atomic::Ordering::Acquire))
+    }
+
+    /// Returns amount of resources, used by the scene, that have finished loading.
+    pub fn loaded_resources(&self) -> usize {
+        self.0.loaded_resources.load(atomic::Ordering::Acquire)
+    }
+
+    /// Returns total amount of resources used by the scene. It is zero until the scene is deserialized.
+    pub fn total_resources(&self) -> usize {
+        self.0.total_resources.load(atomic::Ordering::Acquire)
+    }
+
+    /// Returns `true` if the loading has finished (successfully or not).
+    pub fn is_done(&self) -> bool {
+        self.stage() == SceneLoadStage::Done
+    }
+
+    /// Returns approximate loading progress in `[0; 100]` range. Most of the range is occupied by
+    /// resource loading, since it is the most time-consuming part.
+    pub fn percentage(&self) -> f32 {
+        match self.stage() {
+            SceneLoadStage::ReadingFile => 0.0,
+            SceneLoadStage::Deserializing => 5.0,
+            SceneLoadStage::LoadingResources => {
+                let total = self.total_resources();
+                let fraction = if total == 0 {
+                    1.0
+                } else {
+                    self.loaded_resources() as f32 / total as f32
+                };
+                10.0 + 85.0 * fraction
+            }
+            SceneLoadStage::Resolving => 95.0,
+            SceneLoadStage::Done => 100.0,
+        }
+    }
+}
+
 /// Scene loader.
 pub struct SceneLoader {
     scene: Scene,
@@ -287,6 +471,94 @@ impl SceneLoader {
         Ok((loader, data))
     }
 
+    /// Creates a future that reads the scene from the given file, loads all resources used by the scene
+    /// and resolves it. The progress of the loading is reported using the returned [`SceneLoadProgress`].
+    /// The future does not block and could be executed on a background thread (this is what
+    /// [`crate::engine::AsyncSceneLoader`] does), while the main thread renders a loading screen
+    /// using the progress.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use fyrox::{
+    /// #     asset::{io::FsResourceIo, manager::ResourceManager},
+    /// #     core::futures::executor::block_on,
+    /// #     engine::SerializationContext,
+    /// #     scene::SceneLoader,
+    /// # };
+    /// # use std::sync::Arc;
+    /// fn load(resource_manager: ResourceManager) {
+    ///     let (progress, future) = SceneLoader::load_async(
+    ///         "data/scene.rgs",
+    ///         Arc::new(FsResourceIo),
+    ///         Arc::new(SerializationContext::new()),
+    ///         resource_manager,
+    ///     );
+    ///
+    ///     std::thread::spawn(move || block_on(future));
+    ///
+    ///     while !progress.is_done() {
+    ///         println!("Loading: {}%", progress.percentage());
+    ///     }
+    /// }
+    /// ```
+    pub fn load_async<P: AsRef<Path>>(
+        path: P,
+        io: Arc<dyn ResourceIo>,
+        serialization_context: Arc<SerializationContext>,
+        resource_manager: ResourceManager,
+    ) -> (
+        SceneLoadProgress,
+        impl Future<Output = Result<(Scene, Vec<u8>), VisitError>>,
+    ) {
+        let progress = SceneLoadProgress::default();
+        let path = path.as_ref().to_path_buf();
+        let task_progress = progress.clone();
+        let future = async move {
+            let result = match Self::from_file_with_progress(
+                path,
+                io.as_ref(),
+                serialization_context,
+                resource_manager.clone(),
+                &task_progress,
+            )
+            .await
+            {
+                Ok((loader, data)) => Ok((
+                    loader
+                        .finish_with_progress(&resource_manager, &task_progress)
+                        .await,
+                    data,
+                )),
+                Err(err) => Err(err),
+            };
+            task_progress.set_stage(SceneLoadStage::Done);
+            result
+        };
+        (progress, future)
+    }
+
+    async fn from_file_with_progress(
+        path: PathBuf,
+        io: &dyn ResourceIo,
+        serialization_context: Arc<SerializationContext>,
+        resource_manager: ResourceManager,
+        progress: &SceneLoadProgress,
+    ) -> Result<(Self, Vec<u8>), VisitError> {
+        progress.set_stage(SceneLoadStage::ReadingFile);
+        let data = io.load_file(&path).await?;
+        progress.set_stage(SceneLoadStage::Deserializing);
+        let mut visitor = Visitor::load_from_memory(&data)?;
+        let loader = Self::load(
+            "Scene",
+            serialization_context,
+            resource_manager,
+            &mut visitor,
+            Some(path),
+        )?;
+        Ok((loader, data))
+    }
+
     /// Tries to load a scene using specified visitor and region name.
     pub fn load(
         region_name: &str,
@@ -312,6 +584,16 @@ impl SceneLoader {
 
     /// Finishes scene loading.
     pub async fn finish(self, resource_manager: &ResourceManager) -> Scene {
+        self.finish_with_progress(resource_manager, &SceneLoadProgress::default())
+            .await
+    }
+
+    /// Finishes scene loading and reports the progress using the given progress tracker.
+    pub async fn finish_with_progress(
+        self,
+        resource_manager: &ResourceManager,
+        progress: &SceneLoadProgress,
+    ) -> Scene {
         let mut scene = self.scene;
 
         Log::info("SceneLoader::finish() - Collecting resources used by the scene...");
@@ -338,8 +620,20 @@ impl SceneLoader {
             used_resources_count
         ));
 
-        // Wait everything.
-        join_all(used_resources.into_iter()).await;
+        progress
+            .0
+            .total_resources
+            .store(used_resources_count, atomic::Ordering::Release);
+        progress.set_stage(SceneLoadStage::LoadingResources);
+
+        // Wait everything, counting every loaded resource.
+        let mut loading = used_resources.into_iter().collect::<FuturesUnordered<_>>();
+        while loading.next().await.is_some() {
+            progress
+                .0
+                .loaded_resources
+                .fetch_add(1, atomic::Ordering::AcqRel);
+        }
 
         Log::info(format!(
             "SceneLoader::finish() - All {} resources have finished loading.",
@@ -354,11 +648,14 @@ impl SceneLoader {
             if let Some(camera) = node.cast::<Camera>() {
                 if let Some(skybox) = camera.skybox_ref() {
                     skybox_textures.extend(skybox.textures().iter().filter_map(|t| t.clone()));
+                    skybox_textures.extend(skybox.equirectangular());
                 }
             }
         }
         join_all(skybox_textures).await;
 
+        progress.set_stage(SceneLoadStage::Resolving);
+
         // And do resolve to extract correct graphical data and so on.
         scene.resolve(resource_manager);
 
@@ -382,6 +679,37 @@ impl Scene {
             drawing_context: Default::default(),
             performance_statistics: Default::default(),
             enabled: true.into(),
+            mode: Default::default(),
+            time_scale: 1.0.into(),
+            time_control: Default::default(),
+            tweens: Default::default(),
+        }
+    }
+
+    /// Creates new 2D scene with single root node. See [`SceneMode`] docs for more info.
+    #[inline]
+    pub fn new_2d() -> Self {
+        Self {
+            mode: SceneMode::Mode2D.into(),
+            ..Self::new()
+        }
+    }
+
+    /// Returns `true` if the scene is in [`SceneMode::Mode2D`], `false` - otherwise.
+    #[inline]
+    pub fn is_2d(&self) -> bool {
+        *self.mode == SceneMode::Mode2D
+    }
+
+    /// Returns a projection that should be used by new cameras of the scene. It is a pixel-perfect
+    /// orthographic projection (see [`SceneRenderingOptions::pixels_per_unit`]) for 2D scenes and
+    /// a default perspective projection for 3D scenes.
+    pub fn default_camera_projection(&self) -> Projection {
+        match *self.mode {
+            SceneMode::Mode3D => Projection::default(),
+            SceneMode::Mode2D => Projection::Orthographic(OrthographicProjection::pixel_perfect(
+                self.rendering_options.pixels_per_unit,
+            )),
         }
     }
 
@@ -402,10 +730,28 @@ impl Scene {
         collection
     }
 
+    /// Collects counts and memory estimates of the contents of the scene. See [`SceneReport`] docs
+    /// for more info.
+    pub fn report(&self) -> SceneReport {
+        SceneReport::new(self)
+    }
+
     /// Performs single update tick with given delta time from last frame. Internally
     /// it updates physics, animations, and each graph node. In most cases there is
     /// no need to call it directly, engine automatically updates all available scenes.
-    pub fn update(&mut self, frame_size: Vector2<f32>, dt: f32, switches: GraphUpdateSwitches) {
+    pub fn update(&mut self, frame_size: Vector2<f32>, dt: f32, mut switches: GraphUpdateSwitches) {
+        if self.is_2d() {
+            switches.physics = false;
+        }
+        if !switches.paused {
+            self.time_control.update(dt);
+        }
+        self.time_control.apply(*self.time_scale, &mut self.graph);
+        if !switches.paused {
+            // Tweens must be updated before the graph, so the changes will be reflected in global transforms.
+            let tween_dt = dt * self.graph.time_scale();
+            self.tweens.update(&mut self.graph, tween_dt);
+        }
         self.graph.update(frame_size, dt, switches);
         self.performance_statistics.graph = self.graph.performance_statistics.clone();
     }
@@ -431,6 +777,10 @@ impl Scene {
                 drawing_context: self.drawing_context.clone(),
                 performance_statistics: Default::default(),
                 enabled: self.enabled.clone(),
+                mode: self.mode.clone(),
+                time_scale: self.time_scale.clone(),
+                time_control: Default::default(),
+                tweens: Default::default(),
             },
             old_new_map,
         )
@@ -445,6 +795,8 @@ impl Scene {
         let _ = self
             .rendering_options
             .visit("RenderingOptions", &mut region);
+        let _ = self.mode.visit("Mode", &mut region);
+        let _ = self.time_scale.visit("TimeScale", &mut region);
 
         // Backward compatibility.
         let mut navmeshes = NavMeshContainer::default();
@@ -584,6 +936,80 @@ impl SceneContainer {
         self.destruction_list.push((handle, self.pool.free(handle)));
     }
 
+    /// Merges the source scene into the target scene and removes the source scene from the container.
+    /// The root node of the source scene becomes a child of the root node of the target scene, its
+    /// handle is returned and can be used to unload the merged content later using [`Self::unload_additive`].
+    /// Returns `None` if the handles are invalid or if they point to the same scene.
+    ///
+    /// It could be used for open-world streaming: load chunks of a world as separate scenes (for example,
+    /// using [`crate::engine::AsyncSceneLoader`]), merge them in the main scene when they're loaded and
+    /// unload distant chunks. Everything that is not a part of a chunk (the player, for example) persists.
+    ///
+    /// Merged nodes (including navigational meshes, which are scene nodes) are moved to the graph of the
+    /// target scene, so they share its physics worlds, sound context, etc. Rigid bodies, colliders and
+    /// joints are re-created in the physics worlds of the target scene, physics settings (gravity, etc.)
+    /// of the source scene are discarded. The rest of the source scene (rendering options, etc.) is
+    /// discarded too.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use fyrox::{
+    /// #     core::pool::Handle,
+    /// #     scene::{node::Node, Scene, SceneContainer},
+    /// # };
+    /// fn on_chunk_loaded(
+    ///     scenes: &mut SceneContainer,
+    ///     world: Handle<Scene>,
+    ///     chunk: Handle<Scene>,
+    /// ) -> Option<Handle<Node>> {
+    ///     scenes.load_additive(world, chunk)
+    /// }
+    ///
+    /// fn on_chunk_far_away(scenes: &mut SceneContainer, world: Handle<Scene>, chunk: Handle<Node>) {
+    ///     scenes.unload_additive(world, chunk)
+    /// }
+    /// ```
+    pub fn load_additive(
+        &mut self,
+        target: Handle<Scene>,
+        source: Handle<Scene>,
+    ) -> Option<Handle<Node>> {
+        if target == source || !self.is_valid_handle(target) || !self.is_valid_handle(source) {
+            return None;
+        }
+
+        // The whole graph is moved, so the source scene is destroyed right away instead of being put
+        // in the destruction list - there's nothing left to destroy in it.
+        let mut source_scene = self.pool.free(source);
+        self.sound_engine
+            .state()
+            .remove_context(source_scene.graph.sound_context.native.clone());
+
+        let target_graph = &mut self.pool[target].graph;
+        if *source_scene.graph.physics.gravity != *target_graph.physics.gravity
+            || *source_scene.graph.physics2d.gravity != *target_graph.physics2d.gravity
+        {
+            Log::warn(
+                "Physics settings of an additively loaded scene differ from the settings of the \
+                target scene, the settings of the target scene will be used.",
+            );
+        }
+
+        let source_root = source_scene.graph.get_root();
+        let sub_graph = source_scene.graph.take_subgraph(source_root)?;
+        Some(target_graph.put_subgraph(sub_graph, Handle::NONE).0)
+    }
+
+    /// Removes a content of a scene, that was previously merged in the target scene using
+    /// [`Self::load_additive`].
+    pub fn unload_additive(&mut self, target: Handle<Scene>, chunk: Handle<Node>) {
+        let graph = &mut self.pool[target].graph;
+        if graph.is_valid_handle(chunk) {
+            graph.remove_node(chunk);
+        }
+    }
+
     /// Takes scene from the container and transfers ownership to caller. You must either
     /// put scene back using ticket or call `forget_ticket` to make memory used by scene
     /// vacant again.
@@ -617,3 +1043,39 @@ impl IndexMut<Handle<Scene>> for SceneContainer {
         &mut self.pool[index]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::scene::{
+        base::BaseBuilder, navmesh::NavigationalMeshBuilder, pivot::PivotBuilder,
+        sound::SoundEngine, Scene, SceneContainer,
+    };
+
+    #[test]
+    fn test_load_additive() {
+        let mut scenes = SceneContainer::new(SoundEngine::without_device());
+        let target = scenes.add(Scene::new());
+        let mut source_scene = Scene::new();
+        PivotBuilder::new(BaseBuilder::new().with_name("Prop")).build(&mut source_scene.graph);
+        NavigationalMeshBuilder::new(BaseBuilder::new().with_name("Navmesh"))
+            .build(&mut source_scene.graph);
+        let source = scenes.add(source_scene);
+
+        // Self-merge must be rejected.
+        assert!(scenes.load_additive(target, target).is_none());
+        assert!(scenes.is_valid_handle(target));
+
+        let chunk = scenes.load_additive(target, source).unwrap();
+        assert!(!scenes.is_valid_handle(source));
+
+        let graph = &scenes[target].graph;
+        assert_eq!(graph[chunk].parent(), graph.get_root());
+        assert!(graph.find_by_name(chunk, "Prop").is_some());
+        assert!(graph.find_by_name(chunk, "Navmesh").is_some());
+
+        scenes.unload_additive(target, chunk);
+        let graph = &scenes[target].graph;
+        assert!(!graph.is_valid_handle(chunk));
+        assert_eq!(graph.node_count(), 1);
+    }
+}
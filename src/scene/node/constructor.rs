@@ -6,18 +6,26 @@ use crate::{
         self,
         animation::{absm::AnimationBlendingStateMachine, AnimationPlayer},
         camera::Camera,
+        csg::{Brush, CsgModel},
         decal::Decal,
         dim2::{self, rectangle::Rectangle},
+        folder::Folder,
+        ik::IkConstraint,
         light::{directional::DirectionalLight, point::PointLight, spot::SpotLight},
+        light_probe::LightProbeVolume,
         mesh::Mesh,
         navmesh::NavigationalMesh,
         node::{Node, NodeTrait},
         particle_system::ParticleSystem,
         pivot::Pivot,
         ragdoll::Ragdoll,
-        sound::{listener::Listener, Sound},
+        sound::{listener::Listener, reverb_zone::ReverbZone, Sound},
         sprite::Sprite,
+        streaming::StreamingVolume,
         terrain::Terrain,
+        timeline::TimelinePlayer,
+        trail::Trail,
+        water::Water,
     },
 };
 use fxhash::FxHashMap;
@@ -38,7 +46,10 @@ impl NodeConstructorContainer {
 
         container.add::<dim2::collider::Collider>();
         container.add::<dim2::joint::Joint>();
+        container.add::<dim2::light::PointLight>();
+        container.add::<dim2::light::DirectionalLight>();
         container.add::<Rectangle>();
+        container.add::<dim2::tilemap::TileMap>();
         container.add::<dim2::rigidbody::RigidBody>();
         container.add::<DirectionalLight>();
         container.add::<PointLight>();
@@ -52,6 +63,7 @@ impl NodeConstructorContainer {
         container.add::<Decal>();
         container.add::<scene::joint::Joint>();
         container.add::<Pivot>();
+        container.add::<Folder>();
         container.add::<scene::rigidbody::RigidBody>();
         container.add::<Sprite>();
         container.add::<Terrain>();
@@ -59,6 +71,15 @@ impl NodeConstructorContainer {
         container.add::<AnimationBlendingStateMachine>();
         container.add::<NavigationalMesh>();
         container.add::<Ragdoll>();
+        container.add::<TimelinePlayer>();
+        container.add::<Trail>();
+        container.add::<Water>();
+        container.add::<LightProbeVolume>();
+        container.add::<ReverbZone>();
+        container.add::<StreamingVolume>();
+        container.add::<Brush>();
+        container.add::<CsgModel>();
+        container.add::<IkConstraint>();
 
         container
     }
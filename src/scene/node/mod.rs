@@ -38,7 +38,7 @@ use crate::{
     },
 };
 use fyrox_core::uuid_provider;
-use fyrox_core::variable::mark_inheritable_properties_non_modified;
+use fyrox_core::variable::{mark_inheritable_properties_non_modified, InheritError};
 use fyrox_resource::untyped::UntypedResource;
 use std::{
     any::{Any, TypeId},
@@ -94,6 +94,10 @@ pub struct SyncContext<'a, 'b> {
     pub switches: Option<&'b GraphUpdateSwitches>,
 }
 
+/// A command, that is executed on the graph right after the update of the node, that issued it. See
+/// [`UpdateContext::commands`] for more info.
+pub type GraphCommand = Box<dyn FnOnce(&mut Graph) + Send>;
+
 /// A data for update tick. See [`NodeTrait::update`] for more info.
 pub struct UpdateContext<'a> {
     /// Size of client area of the window.
@@ -108,6 +112,9 @@ pub struct UpdateContext<'a> {
     pub physics2d: &'a mut dim2::physics::PhysicsWorld,
     /// A mutable reference to sound context.
     pub sound_context: &'a mut SoundContext,
+    /// A queue of deferred graph commands. A node can't add or remove other nodes while it is being
+    /// updated, instead it can put a command here and it will be executed right after the update.
+    pub commands: &'a mut Vec<GraphCommand>,
 }
 
 /// Implements [`NodeTrait::query_component_ref`] and [`NodeTrait::query_component_mut`] in a much
@@ -470,6 +477,93 @@ impl Node {
         self.original_handle_in_resource = original_handle;
     }
 
+    /// Compares the node with its original in the prefab it was instantiated from and returns paths of all
+    /// inheritable properties that have different values. Paths are in the same format as used by
+    /// [`ResolvePath`], so they could be used to fetch actual values of the properties. Returns empty
+    /// vector if the node is not a prefab instance or the prefab is not loaded.
+    ///
+    /// This method could be used to save only changed properties of an instance or to show the changes
+    /// to a user.
+    pub fn collect_overrides(&self) -> Vec<String> {
+        let mut overrides = Vec::new();
+
+        if let Some(model) = self.resource.as_ref() {
+            let mut header = model.state();
+            if let Some(data) = header.data() {
+                if let Some(original) = data
+                    .get_scene()
+                    .graph
+                    .try_get(self.original_handle_in_resource)
+                {
+                    self.as_reflect(&mut |reflect| {
+                        original.as_reflect(&mut |original_reflect| {
+                            overrides = variable::collect_overridden_properties(
+                                reflect,
+                                original_reflect,
+                                &[TypeId::of::<UntypedResource>()],
+                            );
+                        })
+                    })
+                }
+            }
+        }
+
+        overrides
+    }
+
+    /// Discards changes of an inheritable property at the given path by taking its value from the
+    /// original in the prefab the node was instantiated from. The property will be marked as non-modified,
+    /// so it will continue to receive changes from the prefab. See [`Self::collect_overrides`] for
+    /// the list of properties that could be reverted.
+    ///
+    /// Prefabs could contain instances of other prefabs, if the property is not modified in the prefab
+    /// the node was instantiated from, then the value is taken from the nested prefab, that actually
+    /// defines it. Returns [`InheritError::NoParent`] if the node is not a prefab instance or the
+    /// prefab is not loaded.
+    pub fn revert_override(&mut self, path: &str) -> Result<(), InheritError> {
+        let Some(model) = self.resource.clone() else {
+            return Err(InheritError::NoParent);
+        };
+
+        let mut result = Err(InheritError::NoParent);
+        visit_prefab_original(
+            &model,
+            self.original_handle_in_resource,
+            path,
+            &mut |original| {
+                self.as_reflect_mut(&mut |reflect| {
+                    original.as_reflect(&mut |original_reflect| {
+                        result = variable::revert_inheritable_property(
+                            reflect,
+                            original_reflect,
+                            path,
+                            &[TypeId::of::<UntypedResource>()],
+                        );
+                    })
+                });
+            },
+        );
+        result
+    }
+
+    // Returns `true` if a property at the given path is not an inheritable variable, or it is a
+    // modified inheritable variable.
+    fn is_property_defined(&self, path: &str) -> bool {
+        let mut defined = true;
+        self.as_reflect(&mut |reflect| {
+            reflect.resolve_path(path, &mut |result| {
+                if let Ok(value) = result {
+                    value.as_inheritable_variable(&mut |variable| {
+                        if let Some(variable) = variable {
+                            defined = variable.is_modified();
+                        }
+                    })
+                }
+            })
+        });
+        defined
+    }
+
     define_is_as!(Mesh => fn is_mesh, fn as_mesh, fn as_mesh_mut);
     define_is_as!(Pivot => fn is_pivot, fn as_pivot, fn as_pivot_mut);
     define_is_as!(Camera  => fn is_camera, fn as_camera, fn as_camera_mut);
@@ -495,6 +589,35 @@ impl Node {
     define_is_as!(Ragdoll => fn is_ragdoll, fn as_ragdoll, fn as_ragdoll_mut);
 }
 
+// Calls the given function with the original of a node in the prefab chain, that defines a value of
+// the property at the given path. Returns `false` if there is no such original.
+fn visit_prefab_original(
+    model: &ModelResource,
+    original_handle: Handle<Node>,
+    path: &str,
+    func: &mut dyn FnMut(&Node),
+) -> bool {
+    let mut header = model.state();
+    let Some(original) = header
+        .data()
+        .and_then(|data| data.get_scene().graph.try_get(original_handle))
+    else {
+        return false;
+    };
+
+    if let Some(nested) = original.resource.as_ref() {
+        if nested.key() != model.key()
+            && !original.is_property_defined(path)
+            && visit_prefab_original(nested, original.original_handle_in_resource, path, func)
+        {
+            return true;
+        }
+    }
+
+    func(original);
+    true
+}
+
 impl Visit for Node {
     fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
         self.0.visit(name, visitor)
@@ -574,7 +697,7 @@ mod test {
             impl_component_provider,
             reflect::prelude::*,
             uuid::{uuid, Uuid},
-            variable::InheritableVariable,
+            variable::{InheritError, InheritableVariable},
             visitor::{prelude::*, Visitor},
             TypeUuidProvider,
         },
@@ -746,4 +869,65 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_revert_nested_prefab_overrides() {
+        if !Path::new("test_output").exists() {
+            fs::create_dir_all("test_output").unwrap();
+        }
+
+        let root_asset_path = Path::new("test_output/nested_root.rgs");
+        let derived_asset_path = Path::new("test_output/nested_derived.rgs");
+
+        let resource_manager = ResourceManager::new(Arc::new(Default::default()));
+        let serialization_context = SerializationContext::new();
+        serialization_context
+            .script_constructors
+            .add::<MyScript>("MyScript");
+        engine::initialize_resource_manager_loaders(
+            &resource_manager,
+            Arc::new(serialization_context),
+        );
+
+        save_scene(&mut create_scene(), root_asset_path);
+        let root_asset = block_on(resource_manager.request::<Model>(root_asset_path)).unwrap();
+
+        // The derived prefab overrides only the shadows of the mesh.
+        {
+            let mut derived = Scene::new();
+            root_asset.instantiate(&mut derived);
+            let mesh = derived.graph.find_by_name_from_root("Mesh").unwrap().0;
+            derived.graph[mesh].set_cast_shadows(false);
+            save_scene(&mut derived, derived_asset_path);
+        }
+        let derived_asset =
+            block_on(resource_manager.request::<Model>(derived_asset_path)).unwrap();
+
+        let mut scene = Scene::new();
+        derived_asset.instantiate(&mut scene);
+        let mesh = scene.graph.find_by_name_from_root("Mesh").unwrap().0;
+        let mesh = &mut scene.graph[mesh];
+        mesh.set_cast_shadows(true);
+        mesh.local_transform_mut()
+            .set_position(Vector3::new(5.0, 5.0, 5.0));
+
+        let overrides = mesh.collect_overrides();
+        assert_eq!(overrides.len(), 2);
+        for path in overrides {
+            mesh.revert_override(&path).unwrap();
+        }
+        assert!(mesh.collect_overrides().is_empty());
+        // Shadows are taken from the derived prefab, position - from the root one.
+        assert!(!mesh.cast_shadows());
+        assert_eq!(
+            **mesh.local_transform().position(),
+            Vector3::new(3.0, 2.0, 1.0)
+        );
+
+        let mut pivot = PivotBuilder::new(BaseBuilder::new()).build_node();
+        assert!(matches!(
+            pivot.revert_override("name"),
+            Err(InheritError::NoParent)
+        ));
+    }
 }
@@ -0,0 +1,90 @@
+//! Particle collision allows particles to collide with scene colliders. See [`ParticleCollision`]
+//! docs for more info.
+
+use crate::{
+    core::{reflect::prelude::*, uuid_provider, visitor::prelude::*},
+    scene::collider::InteractionGroups,
+};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Defines what happens with a particle when it collides with a collider.
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Default,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum ParticleCollisionResponse {
+    /// A particle bounces off the collider. See [`ParticleCollision::restitution`].
+    #[default]
+    Bounce,
+
+    /// A particle dies on contact.
+    Die,
+}
+
+uuid_provider!(ParticleCollisionResponse = "3b7f2c1e-8a4d-4f6b-9e2a-5c1d7b8f4e20");
+
+/// Particle collision allows particles of a particle system to collide with scene colliders
+/// (including terrain heightfields). On contact a particle either bounces off or dies, see
+/// [`ParticleCollisionResponse`].
+///
+/// ## Simulation modes
+///
+/// When particles are simulated on CPU, the movement of every particle during a frame is checked
+/// against the physics world using ray casts. Keep in mind, that the particles are treated as
+/// points, their size is ignored.
+///
+/// When particles are simulated on GPU (see [`super::ParticleSimulationMode::Gpu`]), the collision
+/// is approximated using the depth buffer of the scene: a particle, that went behind visible geometry
+/// by more than [`ParticleCollision::depth_thickness`], is considered dead. Bouncing is not supported
+/// in this mode, the particles always die on contact.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct ParticleCollision {
+    /// Whether the collision is enabled or not.
+    pub enabled: bool,
+
+    /// Defines what happens with a particle when it collides with a collider.
+    pub response: ParticleCollisionResponse,
+
+    /// A fraction of the normal velocity, that is kept after bouncing. `0.0` - a particle slides
+    /// along the surface, `1.0` - a particle bounces off without losing speed.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub restitution: f32,
+
+    /// A fraction of the initial lifetime of a particle, that is lost on every bounce. For example,
+    /// `0.5` means that a particle dies on the second bounce.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub lifetime_loss: f32,
+
+    /// Collision groups, that are used to filter colliders.
+    pub collision_groups: InteractionGroups,
+
+    /// Thickness of geometry (in meters) in the depth buffer, that is used for approximate collision
+    /// detection of particles simulated on GPU.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub depth_thickness: f32,
+}
+
+uuid_provider!(ParticleCollision = "9d2e6a4f-1c8b-4e3d-a7f5-0b6c2e9d8a13");
+
+impl Default for ParticleCollision {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            response: Default::default(),
+            restitution: 0.5,
+            lifetime_loss: 0.0,
+            collision_groups: Default::default(),
+            depth_thickness: 0.25,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     core::{
-        algebra::{Vector2, Vector3},
+        algebra::{Vector2, Vector3, Vector4},
         color::Color,
     },
     scene::mesh::buffer::{
@@ -65,3 +65,81 @@ impl VertexTrait for Vertex {
         ]
     }
 }
+
+/// A vertex of a particle, that is simulated on GPU. It contains the initial state of the particle,
+/// the actual state is calculated in the vertex shader. OpenGL expects this structure packed as in C.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct GpuVertex {
+    pub position: Vector3<f32>,
+    pub tex_coord: Vector2<f32>,
+    pub size: f32,
+    pub rotation: f32,
+    pub color: Color,
+    pub velocity: Vector3<f32>,
+    /// x - size modifier, y - rotation speed, z - lifetime, w - spawn time.
+    pub parameters: Vector4<f32>,
+}
+
+impl VertexTrait for GpuVertex {
+    fn layout() -> &'static [VertexAttributeDescriptor] {
+        &[
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Position,
+                data_type: VertexAttributeDataType::F32,
+                size: 3,
+                divisor: 0,
+                shader_location: 0,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::TexCoord0,
+                data_type: VertexAttributeDataType::F32,
+                size: 2,
+                divisor: 0,
+                shader_location: 1,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom0,
+                data_type: VertexAttributeDataType::F32,
+                size: 1,
+                divisor: 0,
+                shader_location: 2,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom1,
+                data_type: VertexAttributeDataType::F32,
+                size: 1,
+                divisor: 0,
+                shader_location: 3,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Color,
+                data_type: VertexAttributeDataType::U8,
+                size: 4,
+                divisor: 0,
+                shader_location: 4,
+                normalized: true,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom2,
+                data_type: VertexAttributeDataType::F32,
+                size: 3,
+                divisor: 0,
+                shader_location: 5,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom3,
+                data_type: VertexAttributeDataType::F32,
+                size: 4,
+                divisor: 0,
+                shader_location: 6,
+                normalized: false,
+            },
+        ]
+    }
+}
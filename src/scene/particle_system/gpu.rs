@@ -0,0 +1,262 @@
+//! State of a particle system, that is simulated on GPU. See [`super::ParticleSimulationMode`] docs
+//! for more info.
+
+use crate::{
+    core::{
+        algebra::{Vector2, Vector4},
+        log::Log,
+        math::TriangleDefinition,
+    },
+    scene::{
+        mesh::{
+            buffer::{TriangleBuffer, VertexBuffer},
+            surface::{SurfaceData, SurfaceSharedData},
+        },
+        particle_system::{draw::GpuVertex, emitter::Emitter, particle::Particle},
+    },
+};
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// Amount of samples of the color-over-lifetime gradient, that are passed to the shader.
+pub(crate) const COLOR_OVER_LIFETIME_SAMPLES: usize = 16;
+
+#[derive(Copy, Clone, Debug)]
+struct Death {
+    time: f32,
+    emitter_index: u32,
+    slot: u32,
+    spawn_index: u64,
+}
+
+impl PartialEq for Death {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Death {}
+
+impl PartialOrd for Death {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Death {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, so the binary heap pops the earliest death first.
+        other.time.total_cmp(&self.time)
+    }
+}
+
+/// GPU simulation keeps only the initial state of every particle in a ring buffer, the actual state
+/// is calculated in the vertex shader using the time passed since the particle was spawned. This
+/// way only newly spawned particles are uploaded to GPU each frame. CPU side keeps track of death
+/// times of the particles to maintain the amount of alive particles of every emitter.
+#[derive(Default, Debug)]
+pub(crate) struct GpuSimulationState {
+    pub surface: Option<SurfaceSharedData>,
+    pub time: f32,
+    pub time_step: f32,
+    spawned: u64,
+    deaths: BinaryHeap<Death>,
+    // Alive particle of every slot of the ring buffer. Deaths of the particles, that were replaced by
+    // newer ones, are stale and ignored.
+    slots: Vec<Option<Death>>,
+    // Emitters of the alive particles, that were replaced by newer ones (or lost when the ring buffer
+    // was re-created) since the last update.
+    evicted: Vec<u32>,
+}
+
+impl Clone for GpuSimulationState {
+    fn clone(&self) -> Self {
+        // The surface must not be shared between copies, a copy starts from scratch.
+        Self::default()
+    }
+}
+
+impl GpuSimulationState {
+    pub fn reset(&mut self) {
+        *self = Default::default();
+    }
+
+    fn capacity(&self) -> usize {
+        self.surface.as_ref().map_or(0, |surface| {
+            surface.lock().vertex_buffer.vertex_count() as usize / 4
+        })
+    }
+
+    /// Creates the ring buffer if there's none or if its capacity does not match the given one.
+    pub fn ensure_capacity(&mut self, capacity: usize) {
+        if self.surface.is_some() && self.capacity() == capacity {
+            return;
+        }
+
+        let vertices = vec![GpuVertex::default(); capacity * 4];
+        let triangles = (0..capacity)
+            .flat_map(|i| {
+                let base_index = (i * 4) as u32;
+                [
+                    TriangleDefinition([base_index, base_index + 1, base_index + 2]),
+                    TriangleDefinition([base_index, base_index + 2, base_index + 3]),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        self.surface = Some(SurfaceSharedData::new(SurfaceData::new(
+            VertexBuffer::new(vertices.len(), vertices).unwrap(),
+            TriangleBuffer::new(triangles),
+            false,
+        )));
+        self.spawned = 0;
+        self.deaths.clear();
+        self.evicted.extend(
+            self.slots
+                .drain(..)
+                .flatten()
+                .map(|death| death.emitter_index),
+        );
+        self.slots = vec![None; capacity];
+    }
+
+    /// Writes the initial state of the particle into the ring buffer. If the buffer is full, the
+    /// oldest particle is replaced and it is counted as dead on the next [`Self::advance`] call.
+    pub fn spawn(&mut self, particle: &Particle) {
+        let Some(surface) = self.surface.as_ref() else {
+            return;
+        };
+
+        let color = particle.color.srgb_to_linear();
+        let parameters = Vector4::new(
+            particle.size_modifier,
+            particle.rotation_speed,
+            particle.initial_lifetime,
+            self.time,
+        );
+        let vertices = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(0.0, 1.0),
+        ]
+        .map(|tex_coord| GpuVertex {
+            position: particle.position,
+            tex_coord,
+            size: particle.size,
+            rotation: particle.rotation,
+            color,
+            velocity: particle.velocity,
+            parameters,
+        });
+
+        let mut data = surface.lock();
+        let capacity = data.vertex_buffer.vertex_count() as u64 / 4;
+        if capacity == 0 {
+            return;
+        }
+        let slot = (self.spawned % capacity) as usize;
+        Log::verify(data.vertex_buffer.write_vertices_at(slot * 4, &vertices));
+        drop(data);
+
+        let death = Death {
+            time: self.time + particle.initial_lifetime,
+            emitter_index: particle.emitter_index,
+            slot: slot as u32,
+            spawn_index: self.spawned,
+        };
+        if let Some(replaced) = self.slots[slot].replace(death) {
+            self.evicted.push(replaced.emitter_index);
+        }
+        self.deaths.push(death);
+        self.spawned += 1;
+    }
+
+    /// Advances the simulation time and updates the amount of alive particles of the emitters.
+    pub fn advance(&mut self, dt: f32, emitters: &mut [Emitter]) {
+        self.time += dt;
+        self.time_step = dt;
+
+        let mut kill = |emitter_index: u32| {
+            if let Some(emitter) = emitters.get_mut(emitter_index as usize) {
+                emitter.alive_particles = emitter.alive_particles.saturating_sub(1);
+            }
+        };
+
+        for emitter_index in self.evicted.drain(..) {
+            kill(emitter_index);
+        }
+
+        while let Some(death) = self.deaths.peek().cloned() {
+            if death.time > self.time {
+                break;
+            }
+
+            self.deaths.pop();
+
+            let slot = &mut self.slots[death.slot as usize];
+            if slot.is_some_and(|alive| alive.spawn_index == death.spawn_index) {
+                *slot = None;
+                kill(death.emitter_index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scene::particle_system::{
+        emitter::{base::BaseEmitterBuilder, sphere::SphereEmitterBuilder},
+        gpu::GpuSimulationState,
+        particle::Particle,
+    };
+
+    fn spawn(state: &mut GpuSimulationState, emitter_index: u32, lifetime: f32) {
+        state.spawn(&Particle {
+            emitter_index,
+            initial_lifetime: lifetime,
+            ..Particle::default()
+        });
+    }
+
+    #[test]
+    fn test_replaced_particles_are_dead() {
+        let mut emitters = vec![
+            SphereEmitterBuilder::new(BaseEmitterBuilder::new()).build(),
+            SphereEmitterBuilder::new(BaseEmitterBuilder::new()).build(),
+        ];
+        let mut state = GpuSimulationState::default();
+        state.ensure_capacity(2);
+
+        spawn(&mut state, 0, 10.0);
+        spawn(&mut state, 0, 10.0);
+        emitters[0].alive_particles = 2;
+
+        // The ring buffer is full, so the first particle of the first emitter is replaced.
+        spawn(&mut state, 1, 1.0);
+        emitters[1].alive_particles = 1;
+        state.advance(0.1, &mut emitters);
+        assert_eq!(emitters[0].alive_particles, 1);
+        assert_eq!(emitters[1].alive_particles, 1);
+
+        // The replaced particle must not be counted again when its lifetime is over.
+        state.advance(2.0, &mut emitters);
+        assert_eq!(emitters[0].alive_particles, 1);
+        assert_eq!(emitters[1].alive_particles, 0);
+
+        state.advance(10.0, &mut emitters);
+        assert_eq!(emitters[0].alive_particles, 0);
+    }
+
+    #[test]
+    fn test_particles_are_lost_on_capacity_change() {
+        let mut emitters = vec![SphereEmitterBuilder::new(BaseEmitterBuilder::new()).build()];
+        let mut state = GpuSimulationState::default();
+        state.ensure_capacity(2);
+        spawn(&mut state, 0, 10.0);
+        emitters[0].alive_particles = 1;
+
+        state.ensure_capacity(4);
+        state.advance(0.1, &mut emitters);
+        assert_eq!(emitters[0].alive_particles, 0);
+    }
+}
@@ -3,7 +3,7 @@
 
 use crate::{
     core::{
-        algebra::{Point3, Vector2, Vector3},
+        algebra::{Matrix4, Point3, Vector2, Vector3, Vector4},
         color_gradient::ColorGradient,
         log::Log,
         math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
@@ -17,29 +17,88 @@ use crate::{
     },
     material::{self, Material, MaterialResource, PropertyValue},
     rand::{prelude::StdRng, Error, RngCore, SeedableRng},
-    renderer::{self, batch::RenderContext},
+    renderer::{
+        self,
+        batch::{PersistentIdentifier, RenderContext, SurfaceInstanceData},
+        framework::geometry_buffer::ElementRange,
+    },
     scene::{
         base::{Base, BaseBuilder},
-        graph::Graph,
+        graph::{
+            physics::{Intersection, PhysicsWorld, RayCastOptions},
+            Graph,
+        },
         mesh::RenderPath,
         node::{Node, NodeTrait, UpdateContext},
         particle_system::{
+            collision::{ParticleCollision, ParticleCollisionResponse},
             draw::Vertex,
             emitter::{Emit, Emitter},
+            gpu::{GpuSimulationState, COLOR_OVER_LIFETIME_SAMPLES},
             particle::Particle,
         },
+        sorting::SortingOrder,
     },
 };
+use fyrox_core::uuid_provider;
 use std::{
     cmp::Ordering,
     fmt::Debug,
     ops::{Deref, DerefMut},
 };
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
+pub mod collision;
 pub(crate) mod draw;
 pub mod emitter;
+mod gpu;
 pub mod particle;
 
+/// Name of the material property, that tells the shader that the particles are simulated on GPU.
+/// A material must have this property to be used with [`ParticleSimulationMode::Gpu`].
+pub const GPU_SIMULATION_PROPERTY_NAME: &str = "gpuSimulation";
+
+/// Defines where the particles of a particle system are simulated.
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Default,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum ParticleSimulationMode {
+    /// Particles are simulated on CPU. This mode gives full access to the particles (see
+    /// [`ParticleSystem::particles`]) and sorts them back-to-front, but it is suitable only for a few
+    /// thousands of particles.
+    #[default]
+    Cpu,
+
+    /// Particles are simulated on GPU. CPU side only spawns new particles and uploads their initial
+    /// state to a ring buffer on GPU, the actual state of every particle is calculated in the vertex
+    /// shader. This mode is suitable for hundreds of thousands of particles, but it has a few
+    /// limitations:
+    ///
+    /// - The particles are not accessible from CPU, [`ParticleSystem::particles`] returns an empty
+    ///   slice.
+    /// - The particles are not sorted, so the material should use order-independent blending (for
+    ///   example additive).
+    /// - The maximum amount of alive particles is limited by [`ParticleSystem::gpu_particle_capacity`],
+    ///   if it is exceeded, the oldest particles are replaced.
+    /// - The material of the particle system must support GPU simulation (the standard particle system
+    ///   material does), otherwise the particle system falls back to CPU simulation. See
+    ///   [`GPU_SIMULATION_PROPERTY_NAME`].
+    Gpu,
+}
+
+uuid_provider!(ParticleSimulationMode = "c8e5f1a2-4b3d-4e6f-9a7c-2d1b8e5f3a90");
+
 /// Pseudo-random numbers generator for particle systems.
 #[derive(Debug, Clone, Reflect)]
 pub struct ParticleSystemRng {
@@ -231,6 +290,24 @@ pub struct ParticleSystem {
     free_particles: Vec<u32>,
 
     rng: ParticleSystemRng,
+
+    #[reflect(setter = "set_sorting_order")]
+    sorting_order: InheritableVariable<SortingOrder>,
+
+    #[reflect(setter = "set_simulation_mode")]
+    simulation_mode: InheritableVariable<ParticleSimulationMode>,
+
+    #[reflect(setter = "set_gpu_particle_capacity", min_value = 1.0)]
+    gpu_particle_capacity: InheritableVariable<u32>,
+
+    #[reflect(setter = "set_collision")]
+    collision: InheritableVariable<ParticleCollision>,
+
+    #[reflect(hidden)]
+    gpu: GpuSimulationState,
+
+    #[reflect(hidden)]
+    gpu_active: bool,
 }
 
 impl Visit for ParticleSystem {
@@ -246,6 +323,12 @@ impl Visit for ParticleSystem {
         self.particles.visit("Particles", &mut region)?;
         self.free_particles.visit("FreeParticles", &mut region)?;
         let _ = self.rng.visit("Rng", &mut region);
+        let _ = self.sorting_order.visit("SortingOrder", &mut region);
+        let _ = self.simulation_mode.visit("SimulationMode", &mut region);
+        let _ = self
+            .gpu_particle_capacity
+            .visit("GpuParticleCapacity", &mut region);
+        let _ = self.collision.visit("Collision", &mut region);
 
         // Backward compatibility.
         if region.is_reading() {
@@ -326,13 +409,15 @@ impl ParticleSystem {
     }
 
     /// Replaces the particles in the particle system with pre-generated set. It could be useful
-    /// to create procedural particle effects; when particles cannot be pre-made.
+    /// to create procedural particle effects; when particles cannot be pre-made. Has no effect if
+    /// the particle system is simulated on GPU.
     pub fn set_particles(&mut self, particles: Vec<Particle>) {
         self.free_particles.clear();
         self.particles = particles;
     }
 
     /// Returns a reference to a slice to the current set of particles, generated by the particle system.
+    /// The slice is empty if the particle system is simulated on GPU.
     pub fn particles(&self) -> &[Particle] {
         &self.particles
     }
@@ -341,6 +426,7 @@ impl ParticleSystem {
     pub fn clear_particles(&mut self) {
         self.particles.clear();
         self.free_particles.clear();
+        self.gpu.reset();
         for emitter in self.emitters.get_value_mut_silent().iter_mut() {
             emitter.alive_particles = 0;
             emitter.spawned_particles = 0;
@@ -362,7 +448,116 @@ impl ParticleSystem {
         &self.material
     }
 
-    fn tick(&mut self, dt: f32) {
+    /// Sets new sorting order of the particle system, that defines whether the particle system is drawn
+    /// on top of other 2D nodes or below them. See [`SortingOrder`] docs for more info.
+    pub fn set_sorting_order(&mut self, sorting_order: SortingOrder) -> SortingOrder {
+        self.sorting_order
+            .set_value_and_mark_modified(sorting_order)
+    }
+
+    /// Returns current sorting order of the particle system.
+    pub fn sorting_order(&self) -> SortingOrder {
+        *self.sorting_order
+    }
+
+    /// Sets new simulation mode of the particle system. All the particles are removed. See
+    /// [`ParticleSimulationMode`] docs for more info.
+    pub fn set_simulation_mode(
+        &mut self,
+        simulation_mode: ParticleSimulationMode,
+    ) -> ParticleSimulationMode {
+        self.clear_particles();
+        self.simulation_mode
+            .set_value_and_mark_modified(simulation_mode)
+    }
+
+    /// Returns current simulation mode of the particle system.
+    pub fn simulation_mode(&self) -> ParticleSimulationMode {
+        *self.simulation_mode
+    }
+
+    /// Sets the maximum amount of alive particles, when the particle system is simulated on GPU.
+    /// The amount defines the size of the buffer on GPU (about 240 bytes per particle). All the
+    /// particles are removed.
+    pub fn set_gpu_particle_capacity(&mut self, capacity: u32) -> u32 {
+        self.clear_particles();
+        self.gpu_particle_capacity
+            .set_value_and_mark_modified(capacity.max(1))
+    }
+
+    /// Returns the maximum amount of alive particles, when the particle system is simulated on GPU.
+    pub fn gpu_particle_capacity(&self) -> u32 {
+        *self.gpu_particle_capacity
+    }
+
+    /// Returns `true` if the particles are actually simulated on GPU. It could be `false` even if
+    /// the simulation mode is [`ParticleSimulationMode::Gpu`], if the material does not support it.
+    pub fn is_simulated_on_gpu(&self) -> bool {
+        *self.simulation_mode == ParticleSimulationMode::Gpu
+            && self.material.state().data().is_some_and(|material| {
+                material
+                    .properties()
+                    .contains_key(&ImmutableString::new(GPU_SIMULATION_PROPERTY_NAME))
+            })
+    }
+
+    /// Sets new collision parameters of the particle system. See [`ParticleCollision`] docs for
+    /// more info.
+    pub fn set_collision(&mut self, collision: ParticleCollision) -> ParticleCollision {
+        self.collision.set_value_and_mark_modified(collision)
+    }
+
+    /// Returns current collision parameters of the particle system.
+    pub fn collision(&self) -> &ParticleCollision {
+        &self.collision
+    }
+
+    fn tick(&mut self, dt: f32, physics: Option<&PhysicsWorld>) {
+        let gpu_active = self.is_simulated_on_gpu();
+        if gpu_active != self.gpu_active {
+            self.clear_particles();
+            self.gpu_active = gpu_active;
+        }
+
+        if gpu_active {
+            self.tick_gpu(dt);
+        } else {
+            self.tick_cpu(dt, physics);
+        }
+    }
+
+    fn tick_gpu(&mut self, dt: f32) {
+        if self.gpu.surface.is_none() {
+            // Nothing was simulated yet (or the state was reset by cloning or loading), so there's
+            // no alive particles.
+            for emitter in self.emitters.get_value_mut_silent().iter_mut() {
+                emitter.alive_particles = 0;
+            }
+        }
+        self.gpu
+            .ensure_capacity((*self.gpu_particle_capacity).max(1) as usize);
+
+        for emitter in self.emitters.get_value_mut_silent().iter_mut() {
+            emitter.tick(dt);
+        }
+
+        for (i, emitter) in self.emitters.get_value_mut_silent().iter_mut().enumerate() {
+            for _ in 0..emitter.particles_to_spawn {
+                let mut particle = Particle {
+                    emitter_index: i as u32,
+                    ..Particle::default()
+                };
+                emitter.alive_particles += 1;
+                emitter.emit(&mut particle, &mut self.rng);
+                self.gpu.spawn(&particle);
+            }
+        }
+
+        self.gpu
+            .advance(dt, self.emitters.get_value_mut_silent().as_mut_slice());
+    }
+
+    fn tick_cpu(&mut self, dt: f32, physics: Option<&PhysicsWorld>) {
         for emitter in self.emitters.get_value_mut_silent().iter_mut() {
             emitter.tick(dt);
         }
@@ -385,6 +580,9 @@ impl ParticleSystem {
 
         let acceleration_offset = self.acceleration.scale(dt * dt);
 
+        let collide = self.collision.enabled && physics.is_some();
+        let mut moved_particles = Vec::new();
+
         for (i, particle) in self.particles.iter_mut().enumerate() {
             if particle.alive {
                 particle.lifetime += dt;
@@ -400,6 +598,9 @@ impl ParticleSystem {
                     particle.alive = false;
                     particle.lifetime = particle.initial_lifetime;
                 } else {
+                    if collide {
+                        moved_particles.push((i, particle.position));
+                    }
                     particle.velocity += acceleration_offset;
                     particle.position += particle.velocity;
                     particle.size += particle.size_modifier * dt;
@@ -413,9 +614,103 @@ impl ParticleSystem {
                 }
             }
         }
+
+        if let Some(physics) = physics {
+            if !moved_particles.is_empty() {
+                self.collide_particles(physics, &moved_particles);
+            }
+        }
+    }
+
+    fn kill_particle(&mut self, index: usize) {
+        let particle = &mut self.particles[index];
+        if !particle.alive {
+            return;
+        }
+        particle.alive = false;
+        particle.lifetime = particle.initial_lifetime;
+        self.free_particles.push(index as u32);
+        if let Some(emitter) = self
+            .emitters
+            .get_value_mut_silent()
+            .get_mut(particle.emitter_index as usize)
+        {
+            emitter.alive_particles = emitter.alive_particles.saturating_sub(1);
+        }
+    }
+
+    /// Checks the movement of the given particles (index and previous local position) against the
+    /// physics world and applies collision response.
+    fn collide_particles(
+        &mut self,
+        physics: &PhysicsWorld,
+        moved_particles: &[(usize, Vector3<f32>)],
+    ) {
+        let transform = self.global_transform();
+        let inv_transform = transform.try_inverse().unwrap_or_else(Matrix4::identity);
+        let groups = self.collision.collision_groups;
+
+        let rays = moved_particles
+            .iter()
+            .map(|(index, old_position)| {
+                let begin = transform.transform_point(&Point3::from(*old_position));
+                let end = transform.transform_point(&Point3::from(self.particles[*index].position));
+                let direction = end - begin;
+                RayCastOptions {
+                    ray_origin: begin,
+                    ray_direction: direction,
+                    max_len: direction.norm(),
+                    groups,
+                    sort_results: false,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut results: Vec<Option<Intersection>> = Vec::with_capacity(rays.len());
+        physics.cast_rays_closest(&rays, &mut results);
+
+        let collision = (*self.collision).clone();
+        for ((index, _), intersection) in moved_particles.iter().zip(results) {
+            let Some(intersection) = intersection else {
+                continue;
+            };
+
+            match collision.response {
+                ParticleCollisionResponse::Die => self.kill_particle(*index),
+                ParticleCollisionResponse::Bounce => {
+                    let Some(normal) = inv_transform
+                        .transform_vector(&intersection.normal)
+                        .try_normalize(f32::EPSILON)
+                    else {
+                        continue;
+                    };
+
+                    let particle = &mut self.particles[*index];
+
+                    // Put the particle slightly above the surface to prevent tunneling on the next
+                    // frame.
+                    particle.position = inv_transform
+                        .transform_point(
+                            &(intersection.position + intersection.normal.scale(0.001)),
+                        )
+                        .coords;
+
+                    let normal_velocity = normal.scale(particle.velocity.dot(&normal));
+                    let tangent_velocity = particle.velocity - normal_velocity;
+                    particle.velocity =
+                        tangent_velocity - normal_velocity.scale(collision.restitution);
+
+                    particle.lifetime += collision.lifetime_loss * particle.initial_lifetime;
+                    if particle.lifetime >= particle.initial_lifetime {
+                        self.kill_particle(*index);
+                    }
+                }
+            }
+        }
     }
 
     /// Simulates particle system for the given `time` with given time step (`dt`). `dt` is usually `1.0 / 60.0`.
+    /// Collisions are not simulated during rewinding.
     pub fn rewind(&mut self, dt: f32, time: f32) {
         assert!(dt > 0.0);
 
@@ -424,12 +719,86 @@ impl ParticleSystem {
 
         let mut t = 0.0;
         while t < time {
-            self.tick(dt);
+            self.tick(dt, None);
             t += dt;
         }
     }
 }
 
+impl ParticleSystem {
+    fn collect_gpu_render_data(&self, ctx: &mut RenderContext) {
+        let Some(surface) = self.gpu.surface.as_ref() else {
+            return;
+        };
+
+        let color_over_lifetime = (0..COLOR_OVER_LIFETIME_SAMPLES)
+            .map(|i| {
+                let k = i as f32 / (COLOR_OVER_LIFETIME_SAMPLES - 1) as f32;
+                self.color_over_lifetime
+                    .get_color(k)
+                    .srgb_to_linear()
+                    .as_frgba()
+            })
+            .collect::<Vec<Vector4<f32>>>();
+
+        ctx.storage.push(
+            surface,
+            &self.material,
+            RenderPath::Forward,
+            0,
+            self.sorting_order.sort_index(),
+            SurfaceInstanceData {
+                world_transform: self.global_transform(),
+                bone_matrices: Default::default(),
+                depth_offset: 0.0,
+                blend_shapes_weights: Default::default(),
+                element_range: ElementRange::Full,
+                persistent_identifier: PersistentIdentifier::new_combined(
+                    surface,
+                    self.self_handle,
+                    0,
+                ),
+                node_handle: self.self_handle,
+                allow_instancing: false,
+                use_dual_quaternion_skinning: false,
+                property_overrides: Some(
+                    vec![
+                        (
+                            ImmutableString::new(GPU_SIMULATION_PROPERTY_NAME),
+                            PropertyValue::Bool(true),
+                        ),
+                        (
+                            ImmutableString::new("gpuTime"),
+                            PropertyValue::Float(self.gpu.time),
+                        ),
+                        (
+                            ImmutableString::new("gpuTimeStep"),
+                            PropertyValue::Float(self.gpu.time_step),
+                        ),
+                        (
+                            ImmutableString::new("gpuAcceleration"),
+                            PropertyValue::Vector3(*self.acceleration),
+                        ),
+                        (
+                            ImmutableString::new("gpuColorOverLifetime"),
+                            PropertyValue::Vector4Array(color_over_lifetime),
+                        ),
+                        (
+                            ImmutableString::new("gpuCollision"),
+                            PropertyValue::Bool(self.collision.enabled),
+                        ),
+                        (
+                            ImmutableString::new("gpuCollisionThickness"),
+                            PropertyValue::Float(self.collision.depth_thickness),
+                        ),
+                    ]
+                    .into(),
+                ),
+            },
+        );
+    }
+}
+
 impl Default for ParticleSystem {
     fn default() -> Self {
         ParticleSystemBuilder::new(BaseBuilder::new()).build_particle_system()
@@ -456,7 +825,7 @@ impl NodeTrait for ParticleSystem {
         let dt = context.dt;
 
         if *self.is_playing {
-            self.tick(dt);
+            self.tick(dt, Some(&*context.physics));
         }
     }
 
@@ -472,6 +841,11 @@ impl NodeTrait for ParticleSystem {
             return;
         }
 
+        if self.gpu_active {
+            self.collect_gpu_render_data(ctx);
+            return;
+        }
+
         let mut sorted_particles = Vec::new();
         for (i, particle) in self.particles.iter().enumerate() {
             if particle.alive {
@@ -557,7 +931,7 @@ impl NodeTrait for ParticleSystem {
             &self.material,
             RenderPath::Forward,
             0,
-            0,
+            self.sorting_order.sort_index(),
             false,
             self.self_handle,
         )
@@ -575,6 +949,10 @@ pub struct ParticleSystemBuilder {
     color_over_lifetime: ColorGradient,
     is_playing: bool,
     rng: ParticleSystemRng,
+    sorting_order: SortingOrder,
+    simulation_mode: ParticleSimulationMode,
+    gpu_particle_capacity: u32,
+    collision: ParticleCollision,
 }
 
 impl ParticleSystemBuilder {
@@ -592,6 +970,10 @@ impl ParticleSystemBuilder {
             color_over_lifetime: Default::default(),
             is_playing: true,
             rng: ParticleSystemRng::default(),
+            sorting_order: Default::default(),
+            simulation_mode: Default::default(),
+            gpu_particle_capacity: 10_000,
+            collision: Default::default(),
         }
     }
 
@@ -638,6 +1020,30 @@ impl ParticleSystemBuilder {
         self
     }
 
+    /// Sets desired sorting order. See [`ParticleSystem::set_sorting_order`] for more info.
+    pub fn with_sorting_order(mut self, sorting_order: SortingOrder) -> Self {
+        self.sorting_order = sorting_order;
+        self
+    }
+
+    /// Sets desired simulation mode. See [`ParticleSimulationMode`] docs for more info.
+    pub fn with_simulation_mode(mut self, simulation_mode: ParticleSimulationMode) -> Self {
+        self.simulation_mode = simulation_mode;
+        self
+    }
+
+    /// Sets the maximum amount of alive particles, when the particle system is simulated on GPU.
+    pub fn with_gpu_particle_capacity(mut self, capacity: u32) -> Self {
+        self.gpu_particle_capacity = capacity;
+        self
+    }
+
+    /// Sets desired collision parameters. See [`ParticleCollision`] docs for more info.
+    pub fn with_collision(mut self, collision: ParticleCollision) -> Self {
+        self.collision = collision;
+        self
+    }
+
     fn build_particle_system(self) -> ParticleSystem {
         ParticleSystem {
             base: self.base_builder.build_base(),
@@ -649,6 +1055,12 @@ impl ParticleSystemBuilder {
             color_over_lifetime: self.color_over_lifetime.into(),
             is_playing: self.is_playing.into(),
             rng: self.rng,
+            sorting_order: self.sorting_order.into(),
+            simulation_mode: self.simulation_mode.into(),
+            gpu_particle_capacity: self.gpu_particle_capacity.max(1).into(),
+            collision: self.collision.into(),
+            gpu: Default::default(),
+            gpu_active: false,
         }
     }
 
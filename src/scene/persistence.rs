@@ -0,0 +1,279 @@
+//! Persistent node store remembers the state of persistent scene nodes, so it could be restored when
+//! a scene is loaded again. See [`PersistentNodeStore`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        pool::Handle,
+        visitor::prelude::*,
+    },
+    scene::{base::InstanceId, node::Node, Scene},
+};
+use fxhash::{FxHashMap, FxHashSet};
+
+/// Identifier of a persistent node in a scene. Instance id alone is not enough to identify a node,
+/// because it is shared by every instance of the same prefab node. Nodes of a scene loaded from a
+/// file keep their handles, so the handle makes the id unique, while the instance id protects from
+/// matching a wrong node, if the scene file was changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Visit)]
+pub struct PersistentNodeId {
+    /// Handle of the node in the scene.
+    pub handle: Handle<Node>,
+    /// Instance id of the node.
+    pub instance_id: InstanceId,
+}
+
+impl PersistentNodeId {
+    /// Creates an id of the given node with the given handle.
+    pub fn new(handle: Handle<Node>, node: &Node) -> Self {
+        Self {
+            handle,
+            instance_id: node.instance_id(),
+        }
+    }
+}
+
+/// Remembered state of a persistent scene node.
+#[derive(Debug, Clone, Default, PartialEq, Visit)]
+pub struct PersistentNodeState {
+    /// Local position of the node.
+    pub position: Vector3<f32>,
+    /// Local rotation of the node.
+    pub rotation: UnitQuaternion<f32>,
+    /// Local scale of the node.
+    pub scale: Vector3<f32>,
+    /// Whether the node was enabled or not.
+    pub enabled: bool,
+    /// Whether the node was visible or not.
+    pub visible: bool,
+}
+
+impl PersistentNodeState {
+    fn capture(node: &Node) -> Self {
+        let transform = node.local_transform();
+        Self {
+            position: **transform.position(),
+            rotation: **transform.rotation(),
+            scale: **transform.scale(),
+            enabled: node.is_enabled(),
+            visible: node.visibility(),
+        }
+    }
+
+    fn apply(&self, node: &mut Node) {
+        node.local_transform_mut()
+            .set_position(self.position)
+            .set_rotation(self.rotation)
+            .set_scale(self.scale);
+        node.set_enabled(self.enabled);
+        node.set_visibility(self.visible);
+    }
+}
+
+/// Remembered state of persistent nodes of a single scene.
+#[derive(Debug, Clone, Default, Visit)]
+pub struct ScenePersistentState {
+    /// Ids of every persistent node, that was present in the scene when it was loaded for the
+    /// first time.
+    pub known: FxHashSet<PersistentNodeId>,
+    /// Ids of persistent nodes, that were destroyed.
+    pub destroyed: FxHashSet<PersistentNodeId>,
+    /// Last known state of the persistent nodes, that are still alive.
+    pub states: FxHashMap<PersistentNodeId, PersistentNodeState>,
+}
+
+/// Persistent node store remembers the state of scene nodes marked as persistent (see
+/// [`crate::scene::base::Base::set_persistent`]) per scene. It is used to keep the changes made
+/// to a scene, when the scene is unloaded and then loaded again - for example, when a streamed
+/// level chunk is revisited, collected pickups must not be respawned.
+///
+/// Nodes are identified by their handles and instance ids (see [`PersistentNodeId`]), that are
+/// stable across loads of the same scene file, so every instance of the same prefab is tracked
+/// separately. Scenes are identified by an arbitrary key, usually it is a path to a scene file.
+///
+/// The store implements [`Visit`] trait, so it could be a part of a saved game.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox::scene::{persistence::PersistentNodeStore, Scene};
+/// fn on_scene_unloading(store: &mut PersistentNodeStore, path: &str, scene: &Scene) {
+///     store.remember(path, scene);
+/// }
+///
+/// fn on_scene_loaded(store: &mut PersistentNodeStore, path: &str, scene: &mut Scene) {
+///     store.restore(path, scene);
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Visit)]
+pub struct PersistentNodeStore {
+    /// Remembered state of every scene.
+    pub scenes: FxHashMap<String, ScenePersistentState>,
+}
+
+impl PersistentNodeStore {
+    /// Remembers the state of persistent nodes of the given scene. Persistent nodes, that were
+    /// present in the scene when it was restored, but are missing now, are considered destroyed.
+    pub fn remember(&mut self, key: &str, scene: &Scene) {
+        let scene_state = self.scenes.entry(key.to_string()).or_default();
+
+        let mut alive = FxHashSet::default();
+        scene_state.states.clear();
+        for (handle, node) in scene
+            .graph
+            .pair_iter()
+            .filter(|(_, node)| node.is_persistent())
+        {
+            let id = PersistentNodeId::new(handle, node);
+            alive.insert(id);
+            scene_state.known.insert(id);
+            scene_state
+                .states
+                .insert(id, PersistentNodeState::capture(node));
+        }
+
+        for id in scene_state.known.iter() {
+            if !alive.contains(id) {
+                scene_state.destroyed.insert(*id);
+            }
+        }
+    }
+
+    /// Restores the state of persistent nodes of the given scene: destroyed nodes are removed from
+    /// the scene and the remembered state is applied to the rest of persistent nodes. This method
+    /// should be called right after the scene was loaded.
+    pub fn restore(&mut self, key: &str, scene: &mut Scene) {
+        let scene_state = self.scenes.entry(key.to_string()).or_default();
+
+        let mut destroyed = Vec::new();
+        for (handle, node) in scene.graph.pair_iter_mut() {
+            if !node.is_persistent() {
+                continue;
+            }
+
+            let id = PersistentNodeId::new(handle, node);
+            scene_state.known.insert(id);
+            if scene_state.destroyed.contains(&id) {
+                destroyed.push(handle);
+            } else if let Some(state) = scene_state.states.get(&id) {
+                state.apply(node);
+            }
+        }
+
+        for handle in destroyed {
+            // The node could be already removed together with its destroyed ancestor.
+            if scene.graph.is_valid_handle(handle) {
+                scene.graph.remove_node(handle);
+            }
+        }
+    }
+
+    /// Marks a persistent node with the given id as destroyed. Usually there is no need
+    /// to call this method, because destroyed nodes are detected by [`Self::remember`], but it
+    /// could be useful if the store is saved while the scene is still loaded.
+    pub fn mark_destroyed(&mut self, key: &str, id: PersistentNodeId) {
+        let scene_state = self.scenes.entry(key.to_string()).or_default();
+        scene_state.known.insert(id);
+        scene_state.states.remove(&id);
+        scene_state.destroyed.insert(id);
+    }
+
+    /// Returns `true` if a persistent node with the given id was destroyed in a scene with the
+    /// given key.
+    pub fn is_destroyed(&self, key: &str, id: PersistentNodeId) -> bool {
+        self.scenes
+            .get(key)
+            .is_some_and(|scene_state| scene_state.destroyed.contains(&id))
+    }
+
+    /// Forgets everything about a scene with the given key.
+    pub fn forget(&mut self, key: &str) {
+        self.scenes.remove(key);
+    }
+
+    /// Returns a handle of a persistent node with the given id in the given scene.
+    pub fn find_node(scene: &Scene, id: PersistentNodeId) -> Handle<Node> {
+        match scene.graph.try_get(id.handle) {
+            Some(node) if node.is_persistent() && node.instance_id() == id.instance_id => id.handle,
+            _ => Handle::NONE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        resource::model::{Model, ModelResource, ModelResourceExtension, NodeMapping},
+        scene::{
+            base::BaseBuilder,
+            persistence::{PersistentNodeId, PersistentNodeStore},
+            pivot::PivotBuilder,
+            Scene,
+        },
+    };
+
+    #[test]
+    fn test_destroyed_nodes_are_not_respawned() {
+        let make_scene = || {
+            let mut scene = Scene::new();
+            let pickup =
+                PivotBuilder::new(BaseBuilder::new().with_persistent(true)).build(&mut scene.graph);
+            (scene, pickup)
+        };
+
+        let (mut scene, pickup) = make_scene();
+        let id = PersistentNodeId::new(pickup, &scene.graph[pickup]);
+
+        let mut store = PersistentNodeStore::default();
+        store.restore("level", &mut scene);
+        scene.graph.remove_node(pickup);
+        store.remember("level", &scene);
+        assert!(store.is_destroyed("level", id));
+
+        // Load the "same" scene again.
+        let (mut scene, pickup) = make_scene();
+        scene.graph[pickup].set_instance_id(id.instance_id);
+        store.restore("level", &mut scene);
+        assert!(!scene.graph.is_valid_handle(pickup));
+    }
+
+    #[test]
+    fn test_prefab_instances_are_tracked_separately() {
+        let mut prefab_scene = Scene::new();
+        let prefab_root = prefab_scene.graph.get_root();
+        prefab_scene.graph[prefab_root].set_persistent(true);
+        let prefab = ModelResource::new_ok(
+            ResourceKind::Embedded,
+            Model {
+                mapping: NodeMapping::UseHandles,
+                scene: prefab_scene,
+            },
+        );
+
+        let make_scene = || {
+            let mut scene = Scene::new();
+            let a = prefab.instantiate(&mut scene);
+            let b = prefab.instantiate(&mut scene);
+            (scene, a, b)
+        };
+
+        let (mut scene, a, b) = make_scene();
+        // Both instances share the same instance id.
+        assert_eq!(scene.graph[a].instance_id(), scene.graph[b].instance_id());
+
+        let mut store = PersistentNodeStore::default();
+        store.restore("level", &mut scene);
+        let a_id = PersistentNodeId::new(a, &scene.graph[a]);
+        let b_id = PersistentNodeId::new(b, &scene.graph[b]);
+        scene.graph.remove_node(a);
+        store.remember("level", &scene);
+        assert!(store.is_destroyed("level", a_id));
+        assert!(!store.is_destroyed("level", b_id));
+
+        let (mut scene, a, b) = make_scene();
+        store.restore("level", &mut scene);
+        assert!(!scene.graph.is_valid_handle(a));
+        assert!(scene.graph.is_valid_handle(b));
+    }
+}
@@ -0,0 +1,261 @@
+//! Node pool is a simple way to reuse prefab instances, see [`NodePool`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        pool::Handle,
+    },
+    resource::model::{ModelResource, ModelResourceExtension},
+    scene::{dim2, node::Node, rigidbody::RigidBody, Scene},
+};
+use fxhash::FxHashMap;
+
+struct PooledInstance {
+    // Rigid bodies (both 2D and 3D) of the instance, cached to not traverse the hierarchy on each spawn.
+    rigid_bodies: Vec<Handle<Node>>,
+    active: bool,
+}
+
+/// Node pool pre-instantiates a number of prefab instances, disables them and hands them out on spawn
+/// requests. Despawned instances are disabled and returned back to the pool instead of being destroyed.
+/// It is useful when there's a lot of short-living objects (projectiles, particles, debris, etc.) and
+/// the cost of instantiation (and destruction) is too high.
+///
+/// Rigid bodies of spawned instances are reset - their velocities, pending forces and impulses are
+/// cleared. Disabled rigid bodies and colliders are excluded from physics simulation, so the instances
+/// in the pool does not consume physics resources.
+///
+/// # Example
+///
+/// ```rust
+/// # use fyrox::{
+/// #     core::{algebra::{UnitQuaternion, Vector3}, pool::Handle},
+/// #     scene::{node::Node, pool::NodePool, Scene},
+/// # };
+/// fn fire(pool: &mut NodePool, scene: &mut Scene, position: Vector3<f32>) -> Handle<Node> {
+///     pool.spawn(scene, position, UnitQuaternion::default())
+/// }
+/// ```
+pub struct NodePool {
+    prefab: ModelResource,
+    instances: FxHashMap<Handle<Node>, PooledInstance>,
+    free: Vec<Handle<Node>>,
+}
+
+impl NodePool {
+    /// Creates new pool for the given prefab and instantiates `count` disabled instances of the prefab
+    /// in the scene. The prefab must be fully loaded.
+    pub fn new(prefab: ModelResource, scene: &mut Scene, count: usize) -> Self {
+        let mut pool = Self {
+            prefab,
+            instances: Default::default(),
+            free: Default::default(),
+        };
+        pool.reserve(scene, count);
+        pool
+    }
+
+    /// Returns the prefab from which the instances are created.
+    pub fn prefab(&self) -> &ModelResource {
+        &self.prefab
+    }
+
+    /// Instantiates `count` more disabled instances of the prefab.
+    pub fn reserve(&mut self, scene: &mut Scene, count: usize) {
+        for _ in 0..count {
+            let instance = self.instantiate(scene);
+            scene.graph[instance].set_enabled(false);
+            self.free.push(instance);
+        }
+    }
+
+    fn instantiate(&mut self, scene: &mut Scene) -> Handle<Node> {
+        let root = self.prefab.instantiate(scene);
+
+        let rigid_bodies = scene
+            .graph
+            .traverse_handle_iter(root)
+            .filter(|handle| {
+                let node = &scene.graph[*handle];
+                node.is_rigid_body() || node.is_rigid_body2d()
+            })
+            .collect::<Vec<_>>();
+
+        self.instances.insert(
+            root,
+            PooledInstance {
+                rigid_bodies,
+                active: false,
+            },
+        );
+
+        root
+    }
+
+    /// Takes a free instance from the pool (or creates a new one if there's no free instances), moves it
+    /// to the given position, enables it and resets physical state of its rigid bodies. Returns a handle of
+    /// the root node of the instance.
+    pub fn spawn(
+        &mut self,
+        scene: &mut Scene,
+        position: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+    ) -> Handle<Node> {
+        let root = match self.free.pop() {
+            Some(root) if scene.graph.is_valid_handle(root) => root,
+            _ => {
+                // Either the pool is empty or the instance was deleted by someone else.
+                self.instances
+                    .retain(|h, _| scene.graph.is_valid_handle(*h));
+                self.free.retain(|h| scene.graph.is_valid_handle(*h));
+                self.instantiate(scene)
+            }
+        };
+
+        let instance = self
+            .instances
+            .get_mut(&root)
+            .expect("Pool must contain the instance!");
+        instance.active = true;
+
+        for &body in instance.rigid_bodies.iter() {
+            if let Some(node) = scene.graph.try_get_mut(body) {
+                if let Some(rigid_body) = node.cast_mut::<RigidBody>() {
+                    rigid_body.reset_dynamics();
+                } else if let Some(rigid_body) = node.cast_mut::<dim2::rigidbody::RigidBody>() {
+                    rigid_body.reset_dynamics();
+                }
+            }
+        }
+
+        let root_node = &mut scene.graph[root];
+        root_node
+            .local_transform_mut()
+            .set_position(position)
+            .set_rotation(rotation);
+        root_node.set_enabled(true);
+
+        scene.graph.update_hierarchical_data_for_descendants(root);
+
+        root
+    }
+
+    /// Disables the instance and returns it back to the pool. Returns `false` if the instance does not
+    /// belong to the pool or it is already despawned.
+    pub fn despawn(&mut self, scene: &mut Scene, instance: Handle<Node>) -> bool {
+        match self.instances.get_mut(&instance) {
+            Some(pooled) if pooled.active => {
+                pooled.active = false;
+                if let Some(node) = scene.graph.try_get_mut(instance) {
+                    node.set_enabled(false);
+                    self.free.push(instance);
+                } else {
+                    self.instances.remove(&instance);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the instance was spawned from the pool and not yet despawned.
+    pub fn is_active(&self, instance: Handle<Node>) -> bool {
+        self.instances.get(&instance).is_some_and(|i| i.active)
+    }
+
+    /// Returns amount of instances that are ready to be spawned.
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Returns amount of spawned instances.
+    pub fn active_count(&self) -> usize {
+        self.instances.values().filter(|i| i.active).count()
+    }
+
+    /// Destroys every instance (both free and active) of the pool.
+    pub fn clear(&mut self, scene: &mut Scene) {
+        for (instance, _) in self.instances.drain() {
+            if scene.graph.is_valid_handle(instance) {
+                scene.graph.remove_node(instance);
+            }
+        }
+        self.free.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::algebra::{UnitQuaternion, Vector3},
+        resource::model::{Model, ModelResource, NodeMapping},
+        scene::{
+            base::BaseBuilder,
+            pool::NodePool,
+            rigidbody::{RigidBody, RigidBodyBuilder},
+            Scene,
+        },
+    };
+
+    fn make_prefab() -> ModelResource {
+        let mut scene = Scene::new();
+        RigidBodyBuilder::new(BaseBuilder::new().with_name("Projectile")).build(&mut scene.graph);
+        ModelResource::new_ok(
+            ResourceKind::Embedded,
+            Model {
+                mapping: NodeMapping::UseHandles,
+                scene,
+            },
+        )
+    }
+
+    #[test]
+    fn test_node_pool() {
+        let mut scene = Scene::new();
+        let mut pool = NodePool::new(make_prefab(), &mut scene, 2);
+        assert_eq!(pool.free_count(), 2);
+        assert_eq!(pool.active_count(), 0);
+
+        let a = pool.spawn(
+            &mut scene,
+            Vector3::new(1.0, 2.0, 3.0),
+            UnitQuaternion::default(),
+        );
+        assert!(scene.graph[a].is_enabled());
+        assert!(pool.is_active(a));
+        assert_eq!(
+            **scene.graph[a].local_transform().position(),
+            Vector3::new(1.0, 2.0, 3.0)
+        );
+
+        let body = scene
+            .graph
+            .find(a, &mut |n| n.is_rigid_body())
+            .map(|(h, _)| h)
+            .unwrap();
+        scene.graph[body]
+            .as_rigid_body_mut()
+            .set_lin_vel(Vector3::new(1.0, 0.0, 0.0));
+
+        assert!(pool.despawn(&mut scene, a));
+        assert!(!pool.despawn(&mut scene, a));
+        assert!(!scene.graph[a].is_enabled());
+
+        // Reused instance must have its state reset.
+        let b = pool.spawn(&mut scene, Vector3::default(), UnitQuaternion::default());
+        assert_eq!(a, b);
+        assert_eq!(
+            scene.graph[body].cast::<RigidBody>().unwrap().lin_vel(),
+            Vector3::default()
+        );
+
+        pool.spawn(&mut scene, Vector3::default(), UnitQuaternion::default());
+        pool.spawn(&mut scene, Vector3::default(), UnitQuaternion::default());
+        assert_eq!(pool.free_count(), 0);
+        assert_eq!(pool.active_count(), 3);
+
+        pool.clear(&mut scene);
+        assert!(!scene.graph.is_valid_handle(a));
+    }
+}
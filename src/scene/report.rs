@@ -0,0 +1,374 @@
+//! Scene report contains counts and memory estimates of the contents of a scene. It could be used
+//! to enforce content budgets in automated checks. See [`SceneReport`] docs for more info.
+
+use crate::{
+    core::{math::TriangleDefinition, reflect::Reflect},
+    material::{Material, PropertyValue},
+    resource::texture::{Texture, TextureResource},
+    scene::{
+        collider::Collider, dim2, joint::Joint, mesh::Mesh, rigidbody::RigidBody, sound::Sound,
+        Scene,
+    },
+};
+use fxhash::{FxHashMap, FxHashSet};
+use std::fmt::{Display, Formatter};
+
+/// Count and memory estimate of the nodes of a particular type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeTypeReport {
+    /// Short name of the node type (for example `Mesh`).
+    pub type_name: &'static str,
+    /// Amount of nodes of the type.
+    pub count: usize,
+    /// Memory (in bytes), that is occupied by the nodes of the type. It does not include memory of
+    /// shared data, such as surface buffers, textures, etc.
+    pub memory: usize,
+}
+
+/// Limits of a scene content. Every `None` limit is ignored. See [`SceneReport::check_budget`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SceneBudget {
+    /// Max amount of nodes.
+    pub max_nodes: Option<usize>,
+    /// Max total amount of triangles of unique surfaces.
+    pub max_triangles: Option<usize>,
+    /// Max amount of memory (in bytes) of surface buffers.
+    pub max_surface_memory: Option<usize>,
+    /// Max amount of memory (in bytes) of textures.
+    pub max_texture_memory: Option<usize>,
+    /// Max amount of rigid bodies, colliders and joints.
+    pub max_physics_objects: Option<usize>,
+    /// Max amount of sounds.
+    pub max_sounds: Option<usize>,
+    /// Max total amount of memory (in bytes).
+    pub max_total_memory: Option<usize>,
+}
+
+/// A limit of [`SceneBudget`], that was exceeded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BudgetViolation {
+    /// Name of the limit.
+    pub name: &'static str,
+    /// Actual value.
+    pub value: usize,
+    /// Max allowed value.
+    pub limit: usize,
+}
+
+impl Display for BudgetViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} budget exceeded: {} of {}",
+            self.name, self.value, self.limit
+        )
+    }
+}
+
+/// Scene report contains counts and memory estimates of nodes, surface buffers, textures in use,
+/// physics objects and sounds of a scene. Use [`Scene::report`] to create one.
+///
+/// Memory numbers are estimates of CPU-side memory only, the same data could be duplicated in GPU
+/// memory. Shared data (surface buffers, textures, sound buffers) is counted once, no matter how
+/// many times it is used. Textures, that are not loaded yet, are counted, but their memory is not.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox::scene::{report::SceneBudget, Scene};
+/// fn check_level(scene: &Scene) {
+///     let budget = SceneBudget {
+///         max_nodes: Some(10_000),
+///         max_texture_memory: Some(512 * 1024 * 1024),
+///         ..Default::default()
+///     };
+///
+///     for violation in scene.report().check_budget(&budget) {
+///         println!("{violation}");
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SceneReport {
+    /// Total amount of nodes.
+    pub node_count: usize,
+    /// Counts and memory estimates per node type, sorted by memory in descending order.
+    pub node_types: Vec<NodeTypeReport>,
+    /// Amount of unique surface buffers.
+    pub surface_count: usize,
+    /// Total amount of vertices of unique surface buffers.
+    pub vertex_count: usize,
+    /// Total amount of triangles of unique surface buffers.
+    pub triangle_count: usize,
+    /// Memory (in bytes) of unique surface buffers.
+    pub surface_memory: usize,
+    /// Amount of unique textures, that are used by the scene and its materials.
+    pub texture_count: usize,
+    /// Memory (in bytes) of unique textures, including mip levels.
+    pub texture_memory: usize,
+    /// Amount of 3D and 2D rigid bodies.
+    pub rigid_body_count: usize,
+    /// Amount of 3D and 2D colliders.
+    pub collider_count: usize,
+    /// Amount of 3D and 2D joints.
+    pub joint_count: usize,
+    /// Amount of sounds.
+    pub sound_count: usize,
+    /// Amount of unique sound buffers.
+    pub sound_buffer_count: usize,
+    /// Memory (in bytes) of unique sound buffers. Streaming buffers are counted by the size of
+    /// their current block.
+    pub sound_buffer_memory: usize,
+}
+
+fn short_type_name(type_name: &'static str) -> &'static str {
+    // Do not split generic arguments, only the path of the type itself.
+    let end = type_name.find('<').unwrap_or(type_name.len());
+    match type_name[..end].rfind("::") {
+        Some(position) => &type_name[position + 2..],
+        None => type_name,
+    }
+}
+
+impl SceneReport {
+    /// Collects the report of the given scene.
+    pub fn new(scene: &Scene) -> Self {
+        let mut report = Self::default();
+        let mut node_types = FxHashMap::<&'static str, NodeTypeReport>::default();
+        let mut surfaces = FxHashSet::default();
+        let mut sound_buffers = FxHashSet::default();
+        let mut textures = FxHashMap::default();
+
+        for node in scene.graph.linear_iter() {
+            report.node_count += 1;
+
+            let type_name = short_type_name(Reflect::type_name(node));
+            let entry = node_types.entry(type_name).or_insert(NodeTypeReport {
+                type_name,
+                count: 0,
+                memory: 0,
+            });
+            entry.count += 1;
+            entry.memory += std::mem::size_of_val(&**node);
+
+            if let Some(mesh) = node.cast::<Mesh>() {
+                for surface in mesh.surfaces() {
+                    if !surfaces.insert(surface.data_ref().key()) {
+                        continue;
+                    }
+
+                    let data = surface.data_ref().lock();
+                    report.vertex_count += data.vertex_buffer.vertex_count() as usize;
+                    report.triangle_count += data.geometry_buffer.len();
+                    report.surface_memory += data.vertex_buffer.raw_data().len()
+                        + data.geometry_buffer.len() * std::mem::size_of::<TriangleDefinition>();
+                }
+            } else if node.cast::<RigidBody>().is_some()
+                || node.cast::<dim2::rigidbody::RigidBody>().is_some()
+            {
+                report.rigid_body_count += 1;
+            } else if node.cast::<Collider>().is_some()
+                || node.cast::<dim2::collider::Collider>().is_some()
+            {
+                report.collider_count += 1;
+            } else if node.cast::<Joint>().is_some() || node.cast::<dim2::joint::Joint>().is_some()
+            {
+                report.joint_count += 1;
+            } else if let Some(sound) = node.cast::<Sound>() {
+                report.sound_count += 1;
+
+                if let Some(buffer) = sound.buffer() {
+                    if sound_buffers.insert(buffer.key()) {
+                        if let Some(buffer) = buffer.state().data() {
+                            report.sound_buffer_memory += std::mem::size_of_val(buffer.samples());
+                        }
+                    }
+                }
+            }
+        }
+
+        report.surface_count = surfaces.len();
+        report.sound_buffer_count = sound_buffers.len();
+
+        for resource in scene.collect_used_resources() {
+            if let Some(texture) = resource.try_cast::<Texture>() {
+                textures.insert(texture.key(), texture);
+            } else if let Some(material) = resource.try_cast::<Material>() {
+                if let Some(material) = material.state().data() {
+                    for property in material.properties().values() {
+                        if let PropertyValue::Sampler {
+                            value: Some(texture),
+                            ..
+                        } = property
+                        {
+                            textures.insert(texture.key(), texture.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        report.texture_count = textures.len();
+        report.texture_memory = textures.values().map(texture_memory).sum();
+
+        report.node_types = node_types.into_values().collect();
+        report.node_types.sort_by(|a, b| {
+            b.memory
+                .cmp(&a.memory)
+                .then_with(|| a.type_name.cmp(b.type_name))
+        });
+
+        report
+    }
+
+    /// Returns total memory (in bytes) of nodes.
+    pub fn node_memory(&self) -> usize {
+        self.node_types
+            .iter()
+            .map(|node_type| node_type.memory)
+            .sum()
+    }
+
+    /// Returns total amount of rigid bodies, colliders and joints.
+    pub fn physics_object_count(&self) -> usize {
+        self.rigid_body_count + self.collider_count + self.joint_count
+    }
+
+    /// Returns total estimated memory (in bytes) of the scene.
+    pub fn total_memory(&self) -> usize {
+        self.node_memory() + self.surface_memory + self.texture_memory + self.sound_buffer_memory
+    }
+
+    /// Checks the report against the given budget and returns a list of exceeded limits. Empty list
+    /// means that the scene fits the budget.
+    pub fn check_budget(&self, budget: &SceneBudget) -> Vec<BudgetViolation> {
+        [
+            ("Nodes", self.node_count, budget.max_nodes),
+            ("Triangles", self.triangle_count, budget.max_triangles),
+            (
+                "Surface memory",
+                self.surface_memory,
+                budget.max_surface_memory,
+            ),
+            (
+                "Texture memory",
+                self.texture_memory,
+                budget.max_texture_memory,
+            ),
+            (
+                "Physics objects",
+                self.physics_object_count(),
+                budget.max_physics_objects,
+            ),
+            ("Sounds", self.sound_count, budget.max_sounds),
+            ("Total memory", self.total_memory(), budget.max_total_memory),
+        ]
+        .into_iter()
+        .filter_map(|(name, value, limit)| {
+            limit
+                .filter(|limit| value > *limit)
+                .map(|limit| BudgetViolation { name, value, limit })
+        })
+        .collect()
+    }
+}
+
+fn texture_memory(texture: &TextureResource) -> usize {
+    texture
+        .state()
+        .data()
+        .map_or(0, |texture| texture.data().len())
+}
+
+impl Display for SceneReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Nodes: {} ({} bytes)",
+            self.node_count,
+            self.node_memory()
+        )?;
+        for node_type in self.node_types.iter() {
+            writeln!(
+                f,
+                "\t{}: {} ({} bytes)",
+                node_type.type_name, node_type.count, node_type.memory
+            )?;
+        }
+        writeln!(
+            f,
+            "Surfaces: {} ({} vertices, {} triangles, {} bytes)",
+            self.surface_count, self.vertex_count, self.triangle_count, self.surface_memory
+        )?;
+        writeln!(
+            f,
+            "Textures: {} ({} bytes)",
+            self.texture_count, self.texture_memory
+        )?;
+        writeln!(
+            f,
+            "Physics: {} rigid bodies, {} colliders, {} joints",
+            self.rigid_body_count, self.collider_count, self.joint_count
+        )?;
+        writeln!(
+            f,
+            "Sounds: {} ({} buffers, {} bytes)",
+            self.sound_count, self.sound_buffer_count, self.sound_buffer_memory
+        )?;
+        write!(f, "Total memory: {} bytes", self.total_memory())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Matrix4,
+        scene::{
+            base::BaseBuilder,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                MeshBuilder,
+            },
+            pivot::PivotBuilder,
+            report::{SceneBudget, SceneReport},
+            Scene,
+        },
+    };
+
+    #[test]
+    fn test_scene_report() {
+        let mut scene = Scene::new();
+
+        let data = SurfaceSharedData::new(SurfaceData::make_cube(Matrix4::identity()));
+        for _ in 0..2 {
+            MeshBuilder::new(BaseBuilder::new())
+                .with_surfaces(vec![SurfaceBuilder::new(data.clone()).build()])
+                .build(&mut scene.graph);
+        }
+        PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+
+        let report = SceneReport::new(&scene);
+
+        // Root, two meshes and a pivot.
+        assert_eq!(report.node_count, 4);
+        assert_eq!(
+            report
+                .node_types
+                .iter()
+                .find(|node_type| node_type.type_name == "Mesh")
+                .map(|node_type| node_type.count),
+            Some(2)
+        );
+        // The surface is shared and must be counted once.
+        assert_eq!(report.surface_count, 1);
+        assert_eq!(report.triangle_count, 12);
+
+        let violations = report.check_budget(&SceneBudget {
+            max_nodes: Some(3),
+            max_triangles: Some(12),
+            ..Default::default()
+        });
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].name, "Nodes");
+    }
+}
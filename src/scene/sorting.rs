@@ -0,0 +1,145 @@
+//! Sorting order defines the order of rendering of 2D nodes (rectangles, sprites, particle systems,
+//! tile maps). See [`SortingOrder`] docs for more info.
+
+use crate::core::{reflect::prelude::*, uuid_provider, visitor::prelude::*};
+
+/// A bit, that marks sort indices of render batches produced from nodes with sorting order. The renderer
+/// uses it to disable depth writes for such batches, so the order of rendering defines which node is
+/// drawn on top of other.
+const SORTED_FLAG: u64 = 1 << 32;
+
+/// Sorting order defines the order of rendering of 2D nodes. Nodes are sorted by their sorting layer
+/// first, then by their order in the layer. Nodes with higher values are drawn on top of nodes with
+/// lower values, regardless of their Z position. Nodes with the same sorting layer and order are drawn
+/// in the order of their appearance in the scene graph.
+///
+/// Sorting is opt-in: default sorting order is `(0, 0)`, nodes with such order are rendered as usual
+/// (with depth writes and in the order of their appearance in the scene graph). Nodes with any other
+/// sorting order do not write to the depth buffer and they are rendered after every other node in the
+/// forward pass, but they still could be occluded by 3D objects.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use fyrox::scene::{dim2::rectangle::Rectangle, sorting::SortingOrder};
+/// // Background layer is drawn below everything else.
+/// const BACKGROUND: i16 = -1;
+///
+/// fn put_to_background(rect: &mut Rectangle, order: i16) {
+///     rect.set_sorting_order(SortingOrder::new(BACKGROUND, order));
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Visit, Reflect)]
+pub struct SortingOrder {
+    /// Sorting layer of the node. Layers with higher values are drawn on top of layers with lower values.
+    pub layer: i16,
+    /// Order of the node in its sorting layer. Nodes with higher values are drawn on top of nodes with
+    /// lower values.
+    pub order: i16,
+}
+
+uuid_provider!(SortingOrder = "7d4c2e91-3a6f-4b58-8e0d-1f9b5c7a2d63");
+
+impl SortingOrder {
+    /// Creates new sorting order.
+    pub fn new(layer: i16, order: i16) -> Self {
+        Self { layer, order }
+    }
+
+    /// Returns sort index of render batches, that corresponds to the sorting order. Default sorting
+    /// order has zero sort index, just like any other node. Sort index of nodes with non-default sorting
+    /// order is always greater than zero, so such nodes are rendered after other nodes.
+    pub fn sort_index(&self) -> u64 {
+        if *self == Self::default() {
+            return 0;
+        }
+
+        // Flipping the sign bit maps signed values to unsigned ones with the same order.
+        let layer = (self.layer as u16 ^ 0x8000) as u64;
+        let order = (self.order as u16 ^ 0x8000) as u64;
+        SORTED_FLAG | (layer << 16) | order
+    }
+
+    /// Returns `true` if the given sort index of a render batch was produced by [`Self::sort_index`].
+    pub fn is_sorted_index(sort_index: u64) -> bool {
+        sort_index & SORTED_FLAG != 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Point3, Vector3},
+            pool::Handle,
+            sstorage::ImmutableString,
+        },
+        renderer::batch::{ObserverInfo, RenderDataBatchStorage},
+        scene::{
+            base::BaseBuilder, collider::BitMask, dim2::rectangle::RectangleBuilder, graph::Graph,
+            sorting::SortingOrder,
+        },
+    };
+
+    #[test]
+    fn test_sort_index_order() {
+        let orders = [
+            SortingOrder::new(i16::MIN, i16::MIN),
+            SortingOrder::new(-1, 5),
+            SortingOrder::new(0, -1),
+            SortingOrder::new(0, 1),
+            SortingOrder::new(1, i16::MIN),
+            SortingOrder::new(i16::MAX, i16::MAX),
+        ];
+
+        for pair in orders.windows(2) {
+            assert!(pair[0].sort_index() < pair[1].sort_index());
+        }
+
+        for order in orders {
+            assert!(SortingOrder::is_sorted_index(order.sort_index()));
+        }
+        assert_eq!(SortingOrder::default().sort_index(), 0);
+        assert!(!SortingOrder::is_sorted_index(0));
+    }
+
+    #[test]
+    fn test_default_sorting_order_is_not_sorted() {
+        let mut graph = Graph::new();
+        let rect = RectangleBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let sorted_rect = RectangleBuilder::new(BaseBuilder::new())
+            .with_sorting_order(SortingOrder::new(0, 1))
+            .build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let storage = RenderDataBatchStorage::from_graph(
+            &graph,
+            ObserverInfo {
+                observer_position: Vector3::new(0.0, 0.0, -5.0),
+                z_near: 0.025,
+                z_far: 100.0,
+                view_matrix: Matrix4::look_at_rh(
+                    &Point3::new(0.0, 0.0, -5.0),
+                    &Point3::origin(),
+                    &Vector3::y(),
+                ),
+                projection_matrix: Matrix4::new_perspective(1.0, 1.5, 0.025, 100.0),
+                render_mask: BitMask(u32::MAX),
+                lod_camera: Handle::NONE,
+            },
+            ImmutableString::new("Forward"),
+        );
+
+        let batch_of = |node| {
+            storage
+                .batches
+                .iter()
+                .find(|batch| batch.instances.iter().any(|i| i.node_handle == node))
+                .unwrap()
+        };
+        assert_eq!(batch_of(rect).sort_index(), 0);
+        assert!(SortingOrder::is_sorted_index(
+            batch_of(sorted_rect).sort_index()
+        ));
+    }
+}
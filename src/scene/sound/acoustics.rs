@@ -0,0 +1,344 @@
+//! Ray-traced estimation of acoustic properties of the environment around the listener. See
+//! [`ReverbEstimator`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector3},
+        visitor::prelude::*,
+    },
+    scene::{
+        collider::InteractionGroups,
+        graph::physics::{Intersection, PhysicsWorld, RayCastOptions},
+    },
+};
+use fyrox_sound::{
+    bus::AudioBusGraph,
+    context::{State, SAMPLE_RATE},
+    effects::Effect,
+};
+
+/// Sabine's constant (in seconds per meter) for the reverberation time formula.
+const SABINE_CONSTANT: f32 = 0.161;
+
+/// Cutoff frequency (in hertz) of the reverb for fully reflective surfaces.
+const MAX_CUTOFF_FREQUENCY: f32 = 11296.0;
+
+/// Cutoff frequency (in hertz) of the reverb for fully absorptive surfaces.
+const MIN_CUTOFF_FREQUENCY: f32 = 2000.0;
+
+/// Acoustic properties of the environment, estimated by a set of rays. See [`ReverbEstimator`] docs for more info.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AcousticEstimate {
+    /// Average distance (in meters) that a sound wave travels between reflections.
+    pub mean_free_path: f32,
+    /// Fraction of rays that did not hit anything, `0.0` - closed room, `1.0` - open space.
+    pub openness: f32,
+    /// Estimated time (in seconds) for which the reverberation decays by 60 dB.
+    pub decay_time: f32,
+    /// Estimated cutoff frequency (in hertz) of the reflections.
+    pub cutoff_frequency: f32,
+}
+
+impl AcousticEstimate {
+    /// Estimates acoustic properties using a set of ray distances. `None` means that a ray did not hit
+    /// anything within `max_distance`. `absorption` is the average absorption coefficient of the surfaces
+    /// (`0.0` - perfect reflection, `1.0` - full absorption).
+    ///
+    /// The room is approximated by its mean free path (which is `4V/S` for a room with volume `V` and
+    /// surface area `S`), the rays that escaped the room act as fully absorptive "surfaces". Decay time
+    /// is then calculated using Sabine's formula.
+    pub fn from_distances<I>(distances: I, max_distance: f32, absorption: f32) -> Self
+    where
+        I: IntoIterator<Item = Option<f32>>,
+    {
+        let mut total = 0usize;
+        let mut hits = 0usize;
+        let mut distance_sum = 0.0;
+        for distance in distances {
+            total += 1;
+            if let Some(distance) = distance {
+                hits += 1;
+                distance_sum += distance.min(max_distance);
+            }
+        }
+
+        let openness = if total > 0 {
+            (total - hits) as f32 / total as f32
+        } else {
+            1.0
+        };
+
+        let mean_free_path = if hits > 0 {
+            distance_sum / hits as f32
+        } else {
+            max_distance
+        };
+
+        let absorption = absorption.clamp(0.0, 1.0);
+        let effective_absorption = (absorption * (1.0 - openness) + openness).max(0.01);
+
+        Self {
+            mean_free_path,
+            openness,
+            decay_time: SABINE_CONSTANT * mean_free_path / (4.0 * effective_absorption),
+            cutoff_frequency: MAX_CUTOFF_FREQUENCY
+                + (MIN_CUTOFF_FREQUENCY - MAX_CUTOFF_FREQUENCY) * effective_absorption,
+        }
+    }
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        Self {
+            mean_free_path: lerp(self.mean_free_path, other.mean_free_path),
+            openness: lerp(self.openness, other.openness),
+            decay_time: lerp(self.decay_time, other.decay_time),
+            cutoff_frequency: lerp(self.cutoff_frequency, other.cutoff_frequency),
+        }
+    }
+}
+
+/// Reverb estimator shoots a set of rays from the listener, estimates size and absorption of the
+/// environment around it and drives parameters of the [`Effect::Reverb`] effect of an audio bus
+/// automatically. This allows to have believable reverberation in interiors without hand-placed
+/// reverb zones.
+///
+/// The estimator is disabled by default. Rays are cast against 3D physics, so the environment must
+/// have colliders. Make sure that the colliders of the listener itself (a character capsule, for
+/// example) are excluded using [`Self::set_groups`], otherwise the rays will hit them first.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::scene::{sound::AudioBusGraph, Scene};
+///
+/// fn enable_reverb_estimation(scene: &mut Scene) {
+///     let estimator = scene.graph.sound_context.reverb_estimator_mut();
+///     estimator.set_enabled(true);
+///     estimator.set_bus_name(AudioBusGraph::PRIMARY_BUS);
+///     estimator.set_absorption(0.2);
+/// }
+/// ```
+#[derive(Debug, Clone, Visit)]
+pub struct ReverbEstimator {
+    enabled: bool,
+    bus_name: String,
+    ray_count: usize,
+    max_distance: f32,
+    absorption: f32,
+    update_interval: f32,
+    smoothing: f32,
+    groups: InteractionGroups,
+    #[visit(skip)]
+    timer: f32,
+    #[visit(skip)]
+    target: Option<AcousticEstimate>,
+    #[visit(skip)]
+    current: Option<AcousticEstimate>,
+    #[visit(skip)]
+    results: Vec<Option<Intersection>>,
+}
+
+impl Default for ReverbEstimator {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bus_name: AudioBusGraph::PRIMARY_BUS.to_string(),
+            ray_count: 32,
+            max_distance: 50.0,
+            absorption: 0.3,
+            update_interval: 0.25,
+            smoothing: 0.5,
+            groups: Default::default(),
+            timer: 0.0,
+            target: None,
+            current: None,
+            results: Default::default(),
+        }
+    }
+}
+
+impl ReverbEstimator {
+    /// Enables or disables the estimator. Disabled estimator does not touch reverb parameters.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.target = None;
+            self.current = None;
+        }
+    }
+
+    /// Returns `true` if the estimator is enabled, `false` - otherwise.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets the name of an audio bus, whose first reverb effect will be driven by the estimator.
+    pub fn set_bus_name<S: AsRef<str>>(&mut self, name: S) {
+        self.bus_name = name.as_ref().to_owned();
+    }
+
+    /// Returns the name of the driven audio bus.
+    pub fn bus_name(&self) -> &str {
+        &self.bus_name
+    }
+
+    /// Sets the amount of rays that will be cast from the listener. More rays gives more stable results,
+    /// but costs more. Default is 32.
+    pub fn set_ray_count(&mut self, count: usize) {
+        self.ray_count = count.max(1);
+    }
+
+    /// Returns the amount of rays cast from the listener.
+    pub fn ray_count(&self) -> usize {
+        self.ray_count
+    }
+
+    /// Sets the maximum length of the rays (in meters). Rays that did not hit anything within this
+    /// distance are considered "escaped" and make the environment more open.
+    pub fn set_max_distance(&mut self, distance: f32) {
+        self.max_distance = distance.max(f32::EPSILON);
+    }
+
+    /// Returns the maximum length of the rays.
+    pub fn max_distance(&self) -> f32 {
+        self.max_distance
+    }
+
+    /// Sets the average absorption coefficient of the surfaces in `[0; 1]` range. `0.0` - perfectly
+    /// reflective surfaces (concrete, tiles), `1.0` - fully absorptive surfaces (thick carpet, curtains).
+    pub fn set_absorption(&mut self, absorption: f32) {
+        self.absorption = absorption.clamp(0.0, 1.0);
+    }
+
+    /// Returns the average absorption coefficient of the surfaces.
+    pub fn absorption(&self) -> f32 {
+        self.absorption
+    }
+
+    /// Sets the interval (in seconds) between consecutive ray casts.
+    pub fn set_update_interval(&mut self, interval: f32) {
+        self.update_interval = interval.max(0.0);
+    }
+
+    /// Returns the interval between consecutive ray casts.
+    pub fn update_interval(&self) -> f32 {
+        self.update_interval
+    }
+
+    /// Sets the time (in seconds) in which reverb parameters will (mostly) reach the new estimated
+    /// values. It prevents abrupt changes of reverberation when the listener moves between rooms.
+    pub fn set_smoothing(&mut self, smoothing: f32) {
+        self.smoothing = smoothing.max(0.0);
+    }
+
+    /// Returns the smoothing time.
+    pub fn smoothing(&self) -> f32 {
+        self.smoothing
+    }
+
+    /// Sets the collision groups the rays will interact with.
+    pub fn set_groups(&mut self, groups: InteractionGroups) {
+        self.groups = groups;
+    }
+
+    /// Returns the collision groups the rays will interact with.
+    pub fn groups(&self) -> InteractionGroups {
+        self.groups
+    }
+
+    /// Returns the current (smoothed) estimate of the environment, if any.
+    pub fn estimate(&self) -> Option<AcousticEstimate> {
+        self.current
+    }
+
+    pub(crate) fn update(&mut self, state: &mut State, physics: &PhysicsWorld, dt: f32) {
+        if !self.enabled {
+            return;
+        }
+
+        self.timer -= dt;
+        if self.timer <= 0.0 || self.target.is_none() {
+            self.timer = self.update_interval;
+
+            let origin = Point3::from(state.listener().position());
+            let rays = fibonacci_sphere(self.ray_count)
+                .map(|direction| RayCastOptions {
+                    ray_origin: origin,
+                    ray_direction: direction,
+                    max_len: self.max_distance,
+                    groups: self.groups,
+                    sort_results: false,
+                })
+                .collect::<Vec<_>>();
+            physics.cast_rays_closest(&rays, &mut self.results);
+
+            self.target = Some(AcousticEstimate::from_distances(
+                self.results.iter().map(|r| r.as_ref().map(|r| r.toi)),
+                self.max_distance,
+                self.absorption,
+            ));
+        }
+
+        let Some(target) = self.target else {
+            return;
+        };
+
+        let current = match self.current {
+            Some(current) if self.smoothing > 0.0 => {
+                current.lerp(&target, 1.0 - (-dt / self.smoothing).exp())
+            }
+            _ => target,
+        };
+        self.current = Some(current);
+
+        if let Some(bus) = state
+            .bus_graph_mut()
+            .buses_iter_mut()
+            .find(|bus| bus.name() == self.bus_name)
+        {
+            if let Some(Effect::Reverb(reverb)) = bus
+                .effects_mut()
+                .find(|effect| matches!(effect, Effect::Reverb(_)))
+            {
+                reverb.set_decay_time(current.decay_time);
+                reverb.set_fc(current.cutoff_frequency / SAMPLE_RATE as f32);
+            }
+        }
+    }
+}
+
+// Evenly distributed directions on a unit sphere.
+fn fibonacci_sphere(count: usize) -> impl Iterator<Item = Vector3<f32>> {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+    (0..count).map(move |i| {
+        let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+        let radius = (1.0 - y * y).max(0.0).sqrt();
+        let theta = golden_angle * i as f32;
+        Vector3::new(radius * theta.cos(), y, radius * theta.sin())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fibonacci_sphere, AcousticEstimate};
+
+    #[test]
+    fn test_acoustic_estimate() {
+        let small = AcousticEstimate::from_distances([Some(2.0); 16], 50.0, 0.3);
+        let large = AcousticEstimate::from_distances([Some(20.0); 16], 50.0, 0.3);
+        assert_eq!(small.openness, 0.0);
+        assert!(large.decay_time > small.decay_time);
+
+        let absorptive = AcousticEstimate::from_distances([Some(20.0); 16], 50.0, 0.9);
+        assert!(absorptive.decay_time < large.decay_time);
+        assert!(absorptive.cutoff_frequency < large.cutoff_frequency);
+
+        let open = AcousticEstimate::from_distances([None; 16], 50.0, 0.3);
+        assert_eq!(open.openness, 1.0);
+        assert!(open.decay_time < large.decay_time);
+
+        for direction in fibonacci_sphere(32) {
+            assert!((direction.norm() - 1.0).abs() < 1.0e-5);
+        }
+    }
+}
@@ -2,13 +2,24 @@
 
 use crate::{
     core::{
+        algebra::{Point2, Point3, Vector2, Vector3},
         log::{Log, MessageKind},
         pool::Handle,
         visitor::prelude::*,
     },
-    scene::{node::Node, sound::Sound},
+    scene::{
+        collider::{Collider, InteractionGroups},
+        dim2,
+        graph::{
+            physics::{Intersection, PhysicsWorld, RayCastOptions},
+            NodePool,
+        },
+        node::Node,
+        rigidbody::RigidBody,
+        sound::{acoustics::ReverbEstimator, reverb_zone, Sound},
+    },
 };
-use fxhash::FxHashSet;
+use fxhash::{FxHashMap, FxHashSet};
 use fyrox_sound::{
     bus::AudioBusGraph,
     context::DistanceModel,
@@ -22,6 +33,35 @@ use std::{sync::MutexGuard, time::Duration};
 pub struct SoundContext {
     #[visit(optional)]
     pub(crate) native: fyrox_sound::context::SoundContext,
+    #[visit(optional)]
+    active_listener: Handle<Node>,
+    #[visit(optional)]
+    listener_switch_duration: f32,
+    #[visit(skip)]
+    listener_switch: Option<ListenerSwitch>,
+    #[visit(optional)]
+    reverb_estimator: ReverbEstimator,
+    #[visit(skip)]
+    prev_listener_position: Option<Vector3<f32>>,
+    #[visit(optional)]
+    occlusion_groups: InteractionGroups,
+    // A listener node, that was used to set the transform of the native listener.
+    #[visit(skip)]
+    listener_node: Handle<Node>,
+    #[visit(skip)]
+    occlusion_query_buffer: Vec<Intersection>,
+    #[visit(skip)]
+    occlusion_query_buffer_2d: Vec<dim2::physics::Intersection>,
+}
+
+// Position and orientation of the native listener at the moment of active listener switch. It is
+// used to smoothly move the native listener to the new active listener to prevent audio popping.
+#[derive(Debug, Clone)]
+struct ListenerSwitch {
+    position: Vector3<f32>,
+    look: Vector3<f32>,
+    up: Vector3<f32>,
+    elapsed: f32,
 }
 
 /// Proxy for guarded access to the sound context.
@@ -104,7 +144,18 @@ impl Default for SoundContext {
         // There's no need to serialize native sources, because they'll be re-created automatically.
         state.serialization_options.skip_sources = true;
         drop(state);
-        Self { native }
+        Self {
+            native,
+            active_listener: Default::default(),
+            listener_switch_duration: 0.1,
+            listener_switch: None,
+            prev_listener_position: None,
+            reverb_estimator: Default::default(),
+            occlusion_groups: Default::default(),
+            listener_node: Default::default(),
+            occlusion_query_buffer: Default::default(),
+            occlusion_query_buffer_2d: Default::default(),
+        }
     }
 }
 
@@ -117,9 +168,249 @@ impl SoundContext {
     pub fn deep_clone(&self) -> Self {
         Self {
             native: self.native.deep_clone(),
+            active_listener: self.active_listener,
+            listener_switch_duration: self.listener_switch_duration,
+            listener_switch: self.listener_switch.clone(),
+            prev_listener_position: self.prev_listener_position,
+            reverb_estimator: self.reverb_estimator.clone(),
+            occlusion_groups: self.occlusion_groups,
+            listener_node: self.listener_node,
+            occlusion_query_buffer: Default::default(),
+            occlusion_query_buffer_2d: Default::default(),
+        }
+    }
+
+    /// Sets new active listener. When there are multiple listeners in a scene, only the active one
+    /// will be used to render sounds. Use [`Handle::NONE`] to use any enabled listener (this is the
+    /// default behaviour). The native listener will be moved smoothly from the previous listener
+    /// to the new one (see [`Self::set_listener_switch_duration`]), this prevents audio popping when
+    /// switching between cameras in cutscenes or split-screen views.
+    pub fn set_active_listener(&mut self, listener: Handle<Node>) {
+        if self.active_listener != listener {
+            self.active_listener = listener;
+
+            let state = self.native.state();
+            let native = state.listener();
+            self.listener_switch = Some(ListenerSwitch {
+                position: native.position(),
+                look: native.look_axis(),
+                up: native.up_axis(),
+                elapsed: 0.0,
+            });
+        }
+    }
+
+    /// Returns a handle of current active listener. See [`Self::set_active_listener`] for more info.
+    pub fn active_listener(&self) -> Handle<Node> {
+        self.active_listener
+    }
+
+    /// Sets the duration (in seconds) of the smooth transition between listeners, when active listener
+    /// changes. Zero duration means instant switch.
+    pub fn set_listener_switch_duration(&mut self, duration: f32) {
+        self.listener_switch_duration = duration.max(0.0);
+    }
+
+    /// Returns the duration (in seconds) of the smooth transition between listeners.
+    pub fn listener_switch_duration(&self) -> f32 {
+        self.listener_switch_duration
+    }
+
+    pub(crate) fn update_listener_switch(&mut self, dt: f32) {
+        if let Some(switch) = self.listener_switch.as_mut() {
+            switch.elapsed += dt;
+            if switch.elapsed >= self.listener_switch_duration {
+                self.listener_switch = None;
+            }
         }
     }
 
+    /// Returns a reference to the reverb estimator. See [`ReverbEstimator`] docs for more info.
+    pub fn reverb_estimator(&self) -> &ReverbEstimator {
+        &self.reverb_estimator
+    }
+
+    /// Returns a reference to the reverb estimator. See [`ReverbEstimator`] docs for more info.
+    pub fn reverb_estimator_mut(&mut self) -> &mut ReverbEstimator {
+        &mut self.reverb_estimator
+    }
+
+    pub(crate) fn update_reverb_estimator(&mut self, physics: &PhysicsWorld, dt: f32) {
+        let mut state = self.native.state();
+        self.reverb_estimator.update(&mut state, physics, dt);
+    }
+
+    pub(crate) fn update_reverb_zones(&mut self, nodes: &NodePool) {
+        let mut state = self.native.state();
+        reverb_zone::apply_reverb_zones(nodes, &mut state);
+    }
+
+    /// Sets the collision groups of the colliders, that could block (occlude) sounds. See
+    /// [`Sound::set_occlusion`] for more info.
+    pub fn set_occlusion_groups(&mut self, groups: InteractionGroups) {
+        self.occlusion_groups = groups;
+    }
+
+    /// Returns the collision groups of the colliders, that could block (occlude) sounds.
+    pub fn occlusion_groups(&self) -> InteractionGroups {
+        self.occlusion_groups
+    }
+
+    /// Casts rays from every playing sound with occlusion enabled to the listener and sets the amount
+    /// of occlusion of its native source.
+    pub(crate) fn update_occlusion(
+        &mut self,
+        nodes: &NodePool,
+        physics: &PhysicsWorld,
+        physics2d: &dim2::physics::PhysicsWorld,
+    ) {
+        let mut state = self.native.state();
+        let listener_position = state.listener().position();
+
+        for sound in nodes.iter().filter_map(|node| node.cast::<Sound>()) {
+            if sound.occlusion().is_none() || sound.status() != Status::Playing {
+                continue;
+            }
+
+            let Some(source) = state.try_get_source_mut(sound.native.get()) else {
+                continue;
+            };
+
+            let position = sound.global_position();
+            let delta = listener_position - position;
+            let distance = delta.norm();
+
+            let mut occluded = false;
+            if distance > f32::EPSILON {
+                physics.cast_ray(
+                    RayCastOptions {
+                        ray_origin: Point3::from(position),
+                        ray_direction: delta,
+                        max_len: distance,
+                        groups: self.occlusion_groups,
+                        sort_results: false,
+                    },
+                    &mut self.occlusion_query_buffer,
+                );
+                occluded |= self.occlusion_query_buffer.iter().any(|intersection| {
+                    !is_attached_collider(nodes, sound.parent(), intersection.collider)
+                        && !is_attached_collider(nodes, self.listener_node, intersection.collider)
+                });
+
+                let delta_2d = Vector2::new(delta.x, delta.y);
+                let distance_2d = delta_2d.norm();
+                if !occluded && distance_2d > f32::EPSILON {
+                    physics2d.cast_ray(
+                        dim2::physics::RayCastOptions {
+                            ray_origin: Point2::new(position.x, position.y),
+                            ray_direction: delta_2d,
+                            max_len: distance_2d,
+                            groups: self.occlusion_groups,
+                            sort_results: false,
+                        },
+                        &mut self.occlusion_query_buffer_2d,
+                    );
+                    occluded |= self.occlusion_query_buffer_2d.iter().any(|intersection| {
+                        !is_attached_collider(nodes, sound.parent(), intersection.collider)
+                            && !is_attached_collider(
+                                nodes,
+                                self.listener_node,
+                                intersection.collider,
+                            )
+                    });
+                }
+            }
+
+            source.set_occlusion_factor(if occluded { 1.0 } else { 0.0 });
+        }
+    }
+
+    /// Calculates velocities of the sounds and the listener, that are used for the Doppler effect.
+    pub(crate) fn update_velocities(&mut self, nodes: &NodePool, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+
+        let mut state = self.native.state();
+
+        for sound in nodes.iter().filter_map(|node| node.cast::<Sound>()) {
+            let position = sound.global_position();
+            let prev_position = sound.prev_position.replace(Some(position));
+            if let Some(source) = state.try_get_source_mut(sound.native.get()) {
+                let velocity = rigid_body_velocity(nodes, sound.parent()).unwrap_or_else(|| {
+                    prev_position.map_or_else(Vector3::default, |prev| (position - prev) / dt)
+                });
+                source.set_velocity(velocity);
+            }
+        }
+
+        let listener = state.listener_mut();
+        let position = listener.position();
+        let velocity = match self.prev_listener_position.replace(position) {
+            // Smooth transition between listeners must not be heard as a movement.
+            Some(prev) if self.listener_switch.is_none() => (position - prev) / dt,
+            _ => Vector3::default(),
+        };
+        listener.set_velocity(velocity);
+    }
+
+    /// Passes effective speed of time to the native sources, so the sounds will be slowed down or
+    /// paused together with the rest of the scene.
+    pub(crate) fn update_time_scales(
+        &mut self,
+        nodes: &NodePool,
+        time_scale: f32,
+        node_time_scales: &FxHashMap<Handle<Node>, f32>,
+    ) {
+        let mut state = self.native.state();
+
+        for (handle, node) in nodes.pair_iter() {
+            if let Some(sound) = node.cast::<Sound>() {
+                if let Some(source) = state.try_get_source_mut(sound.native.get()) {
+                    let node_time_scale = node_time_scales.get(&handle).cloned().unwrap_or(1.0);
+                    source.set_time_scale((time_scale * node_time_scale) as f64);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn set_listener_transform(
+        &mut self,
+        listener: Handle<Node>,
+        position: Vector3<f32>,
+        look: Vector3<f32>,
+        up: Vector3<f32>,
+    ) {
+        let (position, look, up) = match self.listener_switch.as_ref() {
+            Some(switch) if self.listener_switch_duration > 0.0 => {
+                let t = (switch.elapsed / self.listener_switch_duration).clamp(0.0, 1.0);
+                // Smooth step.
+                let t = t * t * (3.0 - 2.0 * t);
+                (
+                    switch.position.lerp(&position, t),
+                    switch
+                        .look
+                        .lerp(&look, t)
+                        .try_normalize(f32::EPSILON)
+                        .unwrap_or(look),
+                    switch
+                        .up
+                        .lerp(&up, t)
+                        .try_normalize(f32::EPSILON)
+                        .unwrap_or(up),
+                )
+            }
+            _ => (position, look, up),
+        };
+
+        self.listener_node = listener;
+
+        let mut state = self.native.state();
+        let native = state.listener_mut();
+        native.set_position(position);
+        native.set_orientation_lh(look, up);
+    }
+
     /// Returns locked inner state of the sound context.
     pub fn state(&self) -> SoundContextGuard {
         SoundContextGuard {
@@ -216,6 +507,12 @@ impl SoundContext {
             sound.audio_bus.try_sync_model(|audio_bus| {
                 source.set_bus(audio_bus);
             });
+            sound.doppler_factor.try_sync_model(|v| {
+                source.set_doppler_factor(v);
+            });
+            sound.occlusion.try_sync_model(|v| {
+                source.set_occlusion(v);
+            });
         } else {
             match SoundSourceBuilder::new()
                 .with_gain(sound.gain())
@@ -230,6 +527,8 @@ impl SoundContext {
                 .with_max_distance(sound.max_distance())
                 .with_bus(sound.audio_bus())
                 .with_rolloff_factor(sound.rolloff_factor())
+                .with_doppler_factor(sound.doppler_factor())
+                .with_occlusion(sound.occlusion().cloned())
                 .build()
             {
                 Ok(source) => {
@@ -254,3 +553,40 @@ impl SoundContext {
         }
     }
 }
+
+// Returns linear velocity of the closest rigid body up in the hierarchy starting from the given node.
+fn rigid_body_velocity(nodes: &NodePool, mut handle: Handle<Node>) -> Option<Vector3<f32>> {
+    while let Some(node) = nodes.try_borrow(handle) {
+        if let Some(body) = node.cast::<RigidBody>() {
+            return Some(body.lin_vel());
+        } else if let Some(body) = node.cast::<dim2::rigidbody::RigidBody>() {
+            let velocity = body.lin_vel();
+            return Some(Vector3::new(velocity.x, velocity.y, 0.0));
+        }
+        handle = node.parent();
+    }
+    None
+}
+
+// Returns `true` if the given collider belongs to the given node or to one of its ancestors, or if
+// it is attached to the same rigid body as the node. Such colliders must not occlude sounds, because
+// they are part of the sound emitter (or the listener) itself.
+fn is_attached_collider(
+    nodes: &NodePool,
+    mut handle: Handle<Node>,
+    collider: Handle<Node>,
+) -> bool {
+    let body = nodes
+        .try_borrow(collider)
+        .filter(|node| {
+            node.cast::<Collider>().is_some() || node.cast::<dim2::collider::Collider>().is_some()
+        })
+        .map_or(Handle::NONE, |node| node.parent());
+    while let Some(node) = nodes.try_borrow(handle) {
+        if handle == collider || handle == body {
+            return true;
+        }
+        handle = node.parent();
+    }
+    false
+}
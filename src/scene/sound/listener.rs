@@ -26,8 +26,10 @@ use std::ops::{Deref, DerefMut};
 /// basis's side-vector defines ear axis where -X is for left ear and +X for right. Look vector (Z+)
 /// defines "face" of the listener.
 ///
-/// There can be only one listener at a time, if you create multiple listeners, the last one will
-/// have priority.
+/// There can be multiple listeners in a scene, but only one of them is used at a time. The active
+/// listener could be selected using [`crate::scene::sound::context::SoundContext::set_active_listener`], for
+/// example when switching between cameras in a cutscene. If there's no active listener selected and
+/// there are multiple listeners, the last one will have priority.
 ///
 /// Usually listener is attached to the main camera, however there might be some other rare cases
 /// and you can attach listener to any node you like.
@@ -79,15 +81,29 @@ impl NodeTrait for Listener {
         Self::type_uuid()
     }
 
-    fn sync_native(&self, _self_handle: Handle<Node>, context: &mut SyncContext) {
+    fn sync_native(&self, self_handle: Handle<Node>, context: &mut SyncContext) {
         if !self.is_globally_enabled() {
             return;
         }
 
-        let mut state = context.sound_context.native.state();
-        let native = state.listener_mut();
-        native.set_position(self.global_position());
-        native.set_orientation_lh(self.look_vector(), self.up_vector());
+        // Other listeners are ignored only if the active one exists and it is enabled.
+        let active_listener = context.sound_context.active_listener();
+        if active_listener.is_some()
+            && active_listener != self_handle
+            && context
+                .nodes
+                .try_borrow(active_listener)
+                .is_some_and(|listener| listener.is_globally_enabled())
+        {
+            return;
+        }
+
+        context.sound_context.set_listener_transform(
+            self_handle,
+            self.global_position(),
+            self.look_vector(),
+            self.up_vector(),
+        );
     }
 }
 
@@ -2,7 +2,7 @@
 
 use crate::{
     core::{
-        algebra::Matrix4,
+        algebra::{Matrix4, Vector3},
         math::{aabb::AxisAlignedBoundingBox, m4x4_approx_eq},
         pool::Handle,
         reflect::prelude::*,
@@ -33,7 +33,7 @@ pub use fyrox_sound::{
     error::SoundError,
     hrtf::HrirSphere,
     renderer::{hrtf::*, Renderer},
-    source::Status,
+    source::{Occlusion, Status},
 };
 
 use crate::scene::Scene;
@@ -45,8 +45,10 @@ use std::{
     time::Duration,
 };
 
+pub mod acoustics;
 pub mod context;
 pub mod listener;
+pub mod reverb_zone;
 
 /// Sound source.
 #[derive(Visit, Reflect, Debug)]
@@ -102,9 +104,31 @@ pub struct Sound {
     )]
     audio_bus: InheritableVariable<String>,
 
+    #[visit(optional)]
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        setter = "set_doppler_factor",
+        description = "Strength of the Doppler effect. 0.0 disables the effect, 1.0 gives physically correct pitch shift."
+    )]
+    doppler_factor: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_occlusion",
+        description = "Occlusion settings. If set, the sound becomes quieter and muffled when there's an obstacle between the sound and the listener."
+    )]
+    occlusion: InheritableVariable<Option<Occlusion>>,
+
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) native: Cell<Handle<SoundSource>>,
+
+    // Global position of the sound on the previous frame, it is used to calculate the velocity
+    // of the sound.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) prev_position: Cell<Option<Vector3<f32>>>,
 }
 
 impl Deref for Sound {
@@ -138,7 +162,10 @@ impl Default for Sound {
             playback_time: Default::default(),
             spatial_blend: InheritableVariable::new_modified(1.0),
             audio_bus: InheritableVariable::new_modified(AudioBusGraph::PRIMARY_BUS.to_string()),
+            doppler_factor: InheritableVariable::new_modified(1.0),
+            occlusion: InheritableVariable::new_modified(None),
             native: Default::default(),
+            prev_position: Default::default(),
         }
     }
 }
@@ -160,8 +187,11 @@ impl Clone for Sound {
             playback_time: self.playback_time.clone(),
             spatial_blend: self.spatial_blend.clone(),
             audio_bus: self.audio_bus.clone(),
+            doppler_factor: self.doppler_factor.clone(),
+            occlusion: self.occlusion.clone(),
             // Do not copy. The copy will have its own native representation.
             native: Default::default(),
+            prev_position: Default::default(),
         }
     }
 }
@@ -362,6 +392,37 @@ impl Sound {
     pub fn audio_bus(&self) -> &str {
         &self.audio_bus
     }
+
+    /// Sets the strength of the Doppler effect. `0.0` disables the effect, `1.0` gives physically
+    /// correct pitch shift, larger values exaggerate it. Default is `1.0`.
+    ///
+    /// Velocity of the sound is taken from the closest rigid body up in the hierarchy (including the
+    /// sound itself), or calculated from the change of the sound's position if there's no such body.
+    /// The same applies to the active listener.
+    pub fn set_doppler_factor(&mut self, doppler_factor: f32) -> f32 {
+        self.doppler_factor
+            .set_value_and_mark_modified(doppler_factor.max(0.0))
+    }
+
+    /// Returns the strength of the Doppler effect.
+    pub fn doppler_factor(&self) -> f32 {
+        *self.doppler_factor
+    }
+
+    /// Sets new occlusion settings of the sound. `None` disables occlusion (default). When enabled,
+    /// a ray is cast from the sound to the active listener through the physics worlds (both 3D and
+    /// 2D) on every frame, and the sound is attenuated and filtered (see [`Occlusion`] docs) if the
+    /// ray hits a collider. Colliders of the rigid bodies, that the sound or the listener is attached
+    /// to, are ignored. Use [`context::SoundContext::set_occlusion_groups`] to define which colliders
+    /// could block sounds.
+    pub fn set_occlusion(&mut self, occlusion: Option<Occlusion>) -> Option<Occlusion> {
+        self.occlusion.set_value_and_mark_modified(occlusion)
+    }
+
+    /// Returns current occlusion settings of the sound.
+    pub fn occlusion(&self) -> Option<&Occlusion> {
+        self.occlusion.as_ref()
+    }
 }
 
 impl NodeTrait for Sound {
@@ -451,6 +512,8 @@ pub struct SoundBuilder {
     playback_time: Duration,
     spatial_blend: f32,
     audio_bus: String,
+    doppler_factor: f32,
+    occlusion: Option<Occlusion>,
 }
 
 impl SoundBuilder {
@@ -471,6 +534,8 @@ impl SoundBuilder {
             spatial_blend: 1.0,
             playback_time: Default::default(),
             audio_bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+            doppler_factor: 1.0,
+            occlusion: None,
         }
     }
 
@@ -539,6 +604,16 @@ impl SoundBuilder {
         fn with_audio_bus(audio_bus: String)
     );
 
+    define_with!(
+        /// Sets desired Doppler factor. See [`Sound::set_doppler_factor`] for more info.
+        fn with_doppler_factor(doppler_factor: f32)
+    );
+
+    define_with!(
+        /// Sets desired occlusion settings. See [`Sound::set_occlusion`] for more info.
+        fn with_occlusion(occlusion: Option<Occlusion>)
+    );
+
     /// Creates a new [`Sound`] node.
     #[must_use]
     pub fn build_sound(self) -> Sound {
@@ -557,7 +632,10 @@ impl SoundBuilder {
             playback_time: self.playback_time.as_secs_f32().into(),
             spatial_blend: self.spatial_blend.into(),
             audio_bus: self.audio_bus.into(),
+            doppler_factor: self.doppler_factor.max(0.0).into(),
+            occlusion: self.occlusion.into(),
             native: Default::default(),
+            prev_position: Default::default(),
         }
     }
 
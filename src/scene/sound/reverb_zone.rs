@@ -0,0 +1,421 @@
+//! Reverb zone is a box-shaped region of a scene with its own environmental reverberation. See
+//! [`ReverbZone`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector3},
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::SceneDrawingContext,
+        graph::{Graph, NodePool},
+        node::{Node, NodeTrait},
+    },
+};
+use fyrox_sound::{
+    bus::AudioBusGraph,
+    context::{State, SAMPLE_RATE},
+    effects::Effect,
+};
+use std::ops::{Deref, DerefMut};
+
+/// Reverb zone is a box-shaped region of a scene, that defines environmental reverberation (a cave,
+/// a hall, a small room, etc.) for the listener that is inside it. Reverb zones drive parameters of
+/// the first [`Effect::Reverb`] effect of an audio bus with the name [`Self::bus_name`], so the bus
+/// must have such effect.
+///
+/// # Size and blending
+///
+/// The zone is a unit cube in local coordinates, its exact size is defined by zone's `local scale`
+/// (the same way as for [`crate::scene::decal::Decal`]). The influence of the zone is `1.0` inside
+/// the zone and it linearly fades to `0.0` over [`Self::fade_distance`] outside the zone. When the
+/// listener is affected by multiple zones, their parameters are blended by their influence. This way
+/// reverberation changes smoothly when the listener moves from one room to another.
+///
+/// # Important notes
+///
+/// An audio bus, that is driven by reverb zones, has no reverberation outside of the zones (the
+/// reverb level is set to zero). Do not use the same audio bus for reverb zones and for
+/// [`super::acoustics::ReverbEstimator`], they will override each other.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{
+///         base::BaseBuilder, graph::Graph, node::Node, sound::reverb_zone::ReverbZoneBuilder,
+///         transform::TransformBuilder,
+///     },
+/// };
+///
+/// fn create_cave_zone(graph: &mut Graph) -> Handle<Node> {
+///     ReverbZoneBuilder::new(
+///         BaseBuilder::new().with_local_transform(
+///             TransformBuilder::new()
+///                 .with_local_scale(Vector3::new(20.0, 8.0, 30.0))
+///                 .build(),
+///         ),
+///     )
+///     .with_decay_time(4.0)
+///     .with_cutoff_frequency(4000.0)
+///     .with_fade_distance(3.0)
+///     .build(graph)
+/// }
+/// ```
+#[derive(Debug, Visit, Clone, Reflect)]
+pub struct ReverbZone {
+    base: Base,
+
+    #[reflect(setter = "set_bus_name")]
+    bus_name: InheritableVariable<String>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.1,
+        setter = "set_decay_time",
+        description = "Time (in seconds) in which the reverberation decays by 60 dB."
+    )]
+    decay_time: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 100.0,
+        setter = "set_cutoff_frequency",
+        description = "Cutoff frequency (in hertz) of the reflections."
+    )]
+    cutoff_frequency: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05, setter = "set_level")]
+    level: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1, setter = "set_fade_distance")]
+    fade_distance: InheritableVariable<f32>,
+}
+
+impl Default for ReverbZone {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            bus_name: AudioBusGraph::PRIMARY_BUS.to_string().into(),
+            decay_time: 2.0.into(),
+            cutoff_frequency: 11296.0.into(),
+            level: 1.0.into(),
+            fade_distance: 2.0.into(),
+        }
+    }
+}
+
+impl Deref for ReverbZone {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for ReverbZone {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for ReverbZone {
+    fn type_uuid() -> Uuid {
+        uuid!("3c9f27d4-86b1-4e5a-b0d8-5a7e14c2f963")
+    }
+}
+
+impl ReverbZone {
+    /// Sets the name of an audio bus, whose first reverb effect will be driven by the zone.
+    pub fn set_bus_name(&mut self, name: String) -> String {
+        self.bus_name.set_value_and_mark_modified(name)
+    }
+
+    /// Returns the name of the driven audio bus.
+    pub fn bus_name(&self) -> &str {
+        &self.bus_name
+    }
+
+    /// Sets the time (in seconds) in which the reverberation decays by 60 dB.
+    pub fn set_decay_time(&mut self, decay_time: f32) -> f32 {
+        self.decay_time
+            .set_value_and_mark_modified(decay_time.max(0.0))
+    }
+
+    /// Returns the decay time of the reverberation.
+    pub fn decay_time(&self) -> f32 {
+        *self.decay_time
+    }
+
+    /// Sets the cutoff frequency (in hertz) of the reflections. Lower values makes the reverberation
+    /// more muffled.
+    pub fn set_cutoff_frequency(&mut self, frequency: f32) -> f32 {
+        self.cutoff_frequency
+            .set_value_and_mark_modified(frequency.max(0.0))
+    }
+
+    /// Returns the cutoff frequency of the reflections.
+    pub fn cutoff_frequency(&self) -> f32 {
+        *self.cutoff_frequency
+    }
+
+    /// Sets the level of the reverberated signal in `[0; 1]` range.
+    pub fn set_level(&mut self, level: f32) -> f32 {
+        self.level
+            .set_value_and_mark_modified(level.clamp(0.0, 1.0))
+    }
+
+    /// Returns the level of the reverberated signal.
+    pub fn level(&self) -> f32 {
+        *self.level
+    }
+
+    /// Sets the distance (in meters) outside the zone, over which the influence of the zone fades
+    /// out.
+    pub fn set_fade_distance(&mut self, distance: f32) -> f32 {
+        self.fade_distance
+            .set_value_and_mark_modified(distance.max(0.0))
+    }
+
+    /// Returns the fade distance of the zone.
+    pub fn fade_distance(&self) -> f32 {
+        *self.fade_distance
+    }
+
+    /// Returns the influence of the zone at the given world-space position in `[0; 1]` range. See
+    /// [`ReverbZone`] docs for more info.
+    pub fn influence(&self, world_position: Vector3<f32>) -> f32 {
+        let transform = self.global_transform();
+        let Some(inv) = transform.try_inverse() else {
+            return 0.0;
+        };
+        let local = inv.transform_point(&Point3::from(world_position)).coords;
+        let scale = Vector3::new(
+            transform.column(0).xyz().norm(),
+            transform.column(1).xyz().norm(),
+            transform.column(2).xyz().norm(),
+        );
+        let distance = local
+            .map(|c| (c.abs() - 0.5).max(0.0))
+            .component_mul(&scale)
+            .norm();
+        if distance <= 0.0 {
+            1.0
+        } else if *self.fade_distance > 0.0 {
+            (1.0 - distance / *self.fade_distance).max(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl NodeTrait for ReverbZone {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::unit()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        ctx.draw_oob(
+            &AxisAlignedBoundingBox::unit(),
+            self.global_transform(),
+            Color::opaque(0, 200, 255),
+        );
+    }
+}
+
+#[derive(Default)]
+struct ReverbBlend {
+    weight: f32,
+    max_influence: f32,
+    decay_time: f32,
+    cutoff_frequency: f32,
+    level: f32,
+}
+
+/// Blends parameters of every enabled reverb zone by their influence at the listener position and
+/// applies them to respective audio buses.
+pub(crate) fn apply_reverb_zones(nodes: &NodePool, state: &mut State) {
+    let listener_position = state.listener().position();
+
+    let mut blends = Vec::<(&str, ReverbBlend)>::new();
+    for zone in nodes
+        .iter()
+        .filter_map(|node| node.cast::<ReverbZone>())
+        .filter(|zone| zone.is_globally_enabled())
+    {
+        let index = match blends.iter().position(|(name, _)| *name == zone.bus_name()) {
+            Some(index) => index,
+            None => {
+                blends.push((zone.bus_name(), Default::default()));
+                blends.len() - 1
+            }
+        };
+        let blend = &mut blends[index].1;
+
+        let influence = zone.influence(listener_position);
+        if influence > 0.0 {
+            blend.weight += influence;
+            blend.max_influence = blend.max_influence.max(influence);
+            blend.decay_time += zone.decay_time() * influence;
+            blend.cutoff_frequency += zone.cutoff_frequency() * influence;
+            blend.level += zone.level() * influence;
+        }
+    }
+
+    for (bus_name, blend) in blends {
+        let Some(Effect::Reverb(reverb)) = state
+            .bus_graph_mut()
+            .buses_iter_mut()
+            .find(|bus| bus.name() == bus_name)
+            .and_then(|bus| {
+                bus.effects_mut()
+                    .find(|effect| matches!(effect, Effect::Reverb(_)))
+            })
+        else {
+            continue;
+        };
+
+        if blend.weight > 0.0 {
+            let decay_time = blend.decay_time / blend.weight;
+            // Changing decay time recalculates the filters, so do it only when needed.
+            if reverb.decay_time() != decay_time {
+                reverb.set_decay_time(decay_time);
+            }
+            let fc = blend.cutoff_frequency / blend.weight / SAMPLE_RATE as f32;
+            if reverb.fc() != fc {
+                reverb.set_fc(fc);
+            }
+            // Fade the reverberation out when the listener is leaving the zones.
+            reverb.set_level(blend.level / blend.weight * blend.max_influence);
+        } else {
+            reverb.set_level(0.0);
+        }
+    }
+}
+
+/// Allows you to create a reverb zone in a declarative manner.
+pub struct ReverbZoneBuilder {
+    base_builder: BaseBuilder,
+    bus_name: String,
+    decay_time: f32,
+    cutoff_frequency: f32,
+    level: f32,
+    fade_distance: f32,
+}
+
+impl ReverbZoneBuilder {
+    /// Creates a new instance of the builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            bus_name: AudioBusGraph::PRIMARY_BUS.to_string(),
+            decay_time: 2.0,
+            cutoff_frequency: 11296.0,
+            level: 1.0,
+            fade_distance: 2.0,
+        }
+    }
+
+    /// Sets desired name of the driven audio bus.
+    pub fn with_bus_name<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.bus_name = name.as_ref().to_owned();
+        self
+    }
+
+    /// Sets desired decay time of the reverberation.
+    pub fn with_decay_time(mut self, decay_time: f32) -> Self {
+        self.decay_time = decay_time;
+        self
+    }
+
+    /// Sets desired cutoff frequency (in hertz) of the reflections.
+    pub fn with_cutoff_frequency(mut self, frequency: f32) -> Self {
+        self.cutoff_frequency = frequency;
+        self
+    }
+
+    /// Sets desired level of the reverberated signal.
+    pub fn with_level(mut self, level: f32) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets desired fade distance of the zone.
+    pub fn with_fade_distance(mut self, distance: f32) -> Self {
+        self.fade_distance = distance;
+        self
+    }
+
+    /// Creates new reverb zone.
+    pub fn build_reverb_zone(self) -> ReverbZone {
+        ReverbZone {
+            base: self.base_builder.build_base(),
+            bus_name: self.bus_name.into(),
+            decay_time: self.decay_time.max(0.0).into(),
+            cutoff_frequency: self.cutoff_frequency.max(0.0).into(),
+            level: self.level.clamp(0.0, 1.0).into(),
+            fade_distance: self.fade_distance.max(0.0).into(),
+        }
+    }
+
+    /// Creates new reverb zone node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_reverb_zone())
+    }
+
+    /// Creates new instance of reverb zone node and puts it in the given graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        scene::{
+            base::BaseBuilder, graph::Graph, sound::reverb_zone::ReverbZoneBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_reverb_zone_influence() {
+        let mut graph = Graph::new();
+        let zone = ReverbZoneBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_scale(Vector3::new(10.0, 10.0, 10.0))
+                    .build(),
+            ),
+        )
+        .with_fade_distance(2.0)
+        .build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let zone = graph[zone].cast::<super::ReverbZone>().unwrap();
+        assert_eq!(zone.influence(Vector3::new(4.0, 0.0, 0.0)), 1.0);
+        assert!((zone.influence(Vector3::new(6.0, 0.0, 0.0)) - 0.5).abs() < 1.0e-5);
+        assert_eq!(zone.influence(Vector3::new(8.0, 0.0, 0.0)), 0.0);
+    }
+}
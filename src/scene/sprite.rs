@@ -17,6 +17,7 @@ use crate::{
     material,
     material::{Material, MaterialResource},
     renderer::{self, batch::RenderContext},
+    resource::texture_atlas::{AtlasQuad, AtlasRegionRef},
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
@@ -27,10 +28,13 @@ use crate::{
             },
             RenderPath,
         },
-        node::{Node, NodeTrait},
+        node::{Node, NodeTrait, UpdateContext},
+        sorting::SortingOrder,
     },
 };
+use fyrox_core::uuid_provider;
 use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
 /// A vertex for sprites.
 #[derive(Copy, Clone, Debug, Default)]
@@ -44,6 +48,8 @@ pub struct SpriteVertex {
     pub params: Vector2<f32>,
     /// Diffuse color.
     pub color: Color,
+    /// Offset of the vertex from the center of the sprite, in `[-1; 1]` range.
+    pub corner: Vector2<f32>,
 }
 
 impl VertexTrait for SpriteVertex {
@@ -81,10 +87,314 @@ impl VertexTrait for SpriteVertex {
                 shader_location: 3,
                 normalized: true,
             },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom1,
+                data_type: VertexAttributeDataType::F32,
+                size: 2,
+                divisor: 0,
+                shader_location: 4,
+                normalized: false,
+            },
         ]
     }
 }
 
+/// Defines how a [`Flipbook`] animation is played.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum FlipbookMode {
+    /// The animation is played once and stops at the last frame.
+    Once,
+    /// The animation starts from the first frame when it reaches the last frame.
+    #[default]
+    Loop,
+    /// The animation is played forward and then backward.
+    PingPong,
+}
+
+uuid_provider!(FlipbookMode = "5e8a7c31-1f5b-4d8e-9a76-2c3b4f0d6e19");
+
+/// Flipbook is a simple frame animation, where frames are arranged in a regular grid within the uv
+/// rectangle of a node. Frames are counted from the top-left corner row by row. It is used by
+/// [`Sprite`] and [`Rectangle`](super::dim2::rectangle::Rectangle) nodes to show simple animations
+/// without any custom code. Use [`SpriteSheetAnimation`](super::animation::spritesheet::SpriteSheetAnimation)
+/// if you need frames of different sizes or signals.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct Flipbook {
+    /// Whether the flipbook is used or not. When disabled, the whole uv rectangle is shown.
+    pub enabled: bool,
+    /// Amount of columns in the frame grid.
+    #[reflect(min_value = 1.0)]
+    pub columns: u32,
+    /// Amount of rows in the frame grid.
+    #[reflect(min_value = 1.0)]
+    pub rows: u32,
+    /// Index of the first frame of the animation.
+    pub first_frame: u32,
+    /// Amount of frames in the animation.
+    #[reflect(min_value = 1.0)]
+    pub frame_count: u32,
+    /// Playback speed in frames per second.
+    #[reflect(min_value = 0.0)]
+    pub fps: f32,
+    /// Playback mode of the animation.
+    pub mode: FlipbookMode,
+    /// Whether the animation is playing or not.
+    pub playing: bool,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    time: f32,
+}
+
+uuid_provider!(Flipbook = "0f1b8a4d-5c3e-4a27-b6d9-8e7f2a1c9b54");
+
+impl Default for Flipbook {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            columns: 1,
+            rows: 1,
+            first_frame: 0,
+            frame_count: 1,
+            fps: 10.0,
+            mode: Default::default(),
+            playing: true,
+            time: 0.0,
+        }
+    }
+}
+
+impl Flipbook {
+    /// Creates new enabled flipbook, that plays every frame of the given grid in a loop.
+    pub fn new(columns: u32, rows: u32, fps: f32) -> Self {
+        Self {
+            enabled: true,
+            columns,
+            rows,
+            frame_count: columns * rows,
+            fps,
+            ..Default::default()
+        }
+    }
+
+    /// Advances the animation by the given time step.
+    pub fn update(&mut self, dt: f32) {
+        if !self.enabled || !self.playing || self.fps <= 0.0 {
+            return;
+        }
+
+        self.time += dt;
+
+        match self.mode {
+            FlipbookMode::Once => {
+                if self.frame_index() + 1 >= self.frame_count.max(1) {
+                    self.playing = false;
+                }
+            }
+            FlipbookMode::Loop => {
+                // Keep the time bounded to prevent precision loss.
+                self.time %= self.frame_count.max(1) as f32 / self.fps;
+            }
+            FlipbookMode::PingPong => {
+                self.time %= 2.0 * self.frame_count.saturating_sub(1).max(1) as f32 / self.fps;
+            }
+        }
+    }
+
+    fn frame_index(&self) -> u32 {
+        let count = self.frame_count.max(1);
+        let frame = (self.time * self.fps).max(0.0) as u32;
+        match self.mode {
+            FlipbookMode::Once => frame.min(count - 1),
+            FlipbookMode::Loop => frame % count,
+            FlipbookMode::PingPong => {
+                if count == 1 {
+                    0
+                } else {
+                    let period = 2 * (count - 1);
+                    let k = frame % period;
+                    if k < count {
+                        k
+                    } else {
+                        period - k
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns index of the current frame in the grid.
+    pub fn current_frame(&self) -> u32 {
+        self.first_frame + self.frame_index()
+    }
+
+    /// Starts the playback.
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    /// Stops the playback and rewinds the animation to the first frame.
+    pub fn stop(&mut self) {
+        self.playing = false;
+        self.time = 0.0;
+    }
+
+    /// Rewinds the animation to the first frame.
+    pub fn rewind(&mut self) {
+        self.time = 0.0;
+    }
+
+    /// Returns a region of the given uv rectangle, that corresponds to the current frame.
+    pub fn frame_uv_rect(&self, uv_rect: Rect<f32>) -> Rect<f32> {
+        let columns = self.columns.max(1);
+        let rows = self.rows.max(1);
+        let frame = self.current_frame();
+        let w = uv_rect.w() / columns as f32;
+        let h = uv_rect.h() / rows as f32;
+        Rect::new(
+            uv_rect.x() + (frame % columns) as f32 * w,
+            uv_rect.y() + ((frame / columns) % rows) as f32 * h,
+            w,
+            h,
+        )
+    }
+}
+
+/// Sizes of the sides of a border.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct SliceMargins {
+    /// Size of the left side.
+    #[reflect(min_value = 0.0)]
+    pub left: f32,
+    /// Size of the top side.
+    #[reflect(min_value = 0.0)]
+    pub top: f32,
+    /// Size of the right side.
+    #[reflect(min_value = 0.0)]
+    pub right: f32,
+    /// Size of the bottom side.
+    #[reflect(min_value = 0.0)]
+    pub bottom: f32,
+}
+
+uuid_provider!(SliceMargins = "7a2c9e5b-3d41-4f86-a0b7-6c5d8e9f1a23");
+
+/// Grid lines of 9 slices along an axis, every line is a pair of position in the quad and position
+/// in the texture region.
+pub type SliceGridLines = [(f32, f32); 4];
+
+impl SliceMargins {
+    /// Creates new margins with the same size of every side.
+    pub fn uniform(size: f32) -> Self {
+        Self {
+            left: size,
+            top: size,
+            right: size,
+            bottom: size,
+        }
+    }
+}
+
+/// 9-slice (also known as 9-patch) scaling splits an image into 9 parts using a border: corners are
+/// never stretched, sides are stretched along one axis and the center is stretched along both axes.
+/// It allows to make frames, panels, health bars, etc. of arbitrary size from a single small image.
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct NineSlice {
+    /// Whether 9-slice scaling is used or not.
+    pub enabled: bool,
+    /// Border of the image, normalized to the size of the shown region of the texture - `0.25`
+    /// means a quarter of the region.
+    pub texture_margins: SliceMargins,
+    /// Size of the border in world units.
+    pub margins: SliceMargins,
+}
+
+uuid_provider!(NineSlice = "c4e6a1f8-2b97-4d53-8e0a-9f7b3c6d5e12");
+
+impl NineSlice {
+    /// Creates new enabled 9-slice scaling with the given texture and world margins.
+    pub fn new(texture_margins: SliceMargins, margins: SliceMargins) -> Self {
+        Self {
+            enabled: true,
+            texture_margins,
+            margins,
+        }
+    }
+
+    /// Calculates grid lines of 9 slices for a quad of the given size (in world units). Returns
+    /// normalized (in `[0; 1]` range) positions of grid lines along the horizontal and vertical
+    /// axes, every line is a pair of position in the quad and position in the texture region.
+    pub fn grid(&self, size: Vector2<f32>) -> (SliceGridLines, SliceGridLines) {
+        fn axis(size: f32, a: f32, b: f32, tex_a: f32, tex_b: f32) -> SliceGridLines {
+            let size = size.abs().max(f32::EPSILON);
+            // Shrink the border if it does not fit.
+            let k = ((a + b) / size).max(1.0);
+            [
+                (0.0, 0.0),
+                (a / (size * k), tex_a),
+                (1.0 - b / (size * k), 1.0 - tex_b),
+                (1.0, 1.0),
+            ]
+        }
+
+        (
+            axis(
+                size.x,
+                self.margins.left,
+                self.margins.right,
+                self.texture_margins.left,
+                self.texture_margins.right,
+            ),
+            axis(
+                size.y,
+                self.margins.top,
+                self.margins.bottom,
+                self.texture_margins.top,
+                self.texture_margins.bottom,
+            ),
+        )
+    }
+
+    /// Generates vertices and triangles of the 9 slices. `make_vertex` receives normalized position
+    /// in the quad and normalized position in the texture region.
+    pub(crate) fn build<V>(
+        &self,
+        size: Vector2<f32>,
+        mut make_vertex: impl FnMut(Vector2<f32>, Vector2<f32>) -> V,
+    ) -> ([V; 16], [TriangleDefinition; 18]) {
+        let (columns, rows) = self.grid(size);
+
+        let vertices = std::array::from_fn(|i| {
+            let (x, u) = columns[i % 4];
+            let (y, v) = rows[i / 4];
+            make_vertex(Vector2::new(x, y), Vector2::new(u, v))
+        });
+
+        let triangles = std::array::from_fn(|i| {
+            let cell = i / 2;
+            let a = (cell / 3 * 4 + cell % 3) as u32;
+            if i % 2 == 0 {
+                TriangleDefinition([a, a + 1, a + 5])
+            } else {
+                TriangleDefinition([a + 5, a + 4, a])
+            }
+        });
+
+        (vertices, triangles)
+    }
+}
+
 /// Sprite is a billboard which always faces towards camera. It can be used as a "model" for bullets,
 /// and so on.
 ///
@@ -131,9 +441,13 @@ impl VertexTrait for SpriteVertex {
 /// ```
 ///
 /// Keep in mind, that this example creates new material instance each call of the method and
-/// **does not** reuse it. Ideally, you should reuse the shared material across multiple instances
-/// to get best possible performance. Otherwise, each your sprite will be put in a separate batch
-/// which will force your GPU to render a single sprite in dedicated draw call which is quite slow.
+/// **does not** reuse it. Materials are compared by their content when batching, so sprites with
+/// separate materials that use the same shader, texture and parameters will still be rendered in a
+/// single draw call. However, it is still better to reuse the shared material across multiple
+/// instances - it saves memory and time needed to compare materials. Pack your sprites in a texture
+/// atlas and use [`Sprite::set_uv_rect`] to select a region of it, this way sprites with different
+/// images could be rendered in a single draw call as well. Named regions of a
+/// [`crate::resource::texture_atlas::TextureAtlas`] could be selected using [`Sprite::set_atlas_region`].
 #[derive(Debug, Reflect, Clone)]
 pub struct Sprite {
     base: Base,
@@ -143,6 +457,9 @@ pub struct Sprite {
 
     material: InheritableVariable<MaterialResource>,
 
+    #[reflect(setter = "set_atlas_region")]
+    atlas_region: InheritableVariable<AtlasRegionRef>,
+
     #[reflect(setter = "set_color")]
     color: InheritableVariable<Color>,
 
@@ -152,6 +469,15 @@ pub struct Sprite {
 
     #[reflect(setter = "set_rotation")]
     rotation: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_flipbook")]
+    flipbook: InheritableVariable<Flipbook>,
+
+    #[reflect(setter = "set_nine_slice")]
+    nine_slice: InheritableVariable<NineSlice>,
+
+    #[reflect(setter = "set_sorting_order")]
+    sorting_order: InheritableVariable<SortingOrder>,
 }
 
 impl Visit for Sprite {
@@ -177,6 +503,10 @@ impl Visit for Sprite {
 
         // Backward compatibility.
         let _ = self.uv_rect.visit("UvRect", &mut region);
+        let _ = self.atlas_region.visit("AtlasRegion", &mut region);
+        let _ = self.flipbook.visit("Flipbook", &mut region);
+        let _ = self.nine_slice.visit("NineSlice", &mut region);
+        let _ = self.sorting_order.visit("SortingOrder", &mut region);
 
         Ok(())
     }
@@ -270,6 +600,85 @@ impl Sprite {
     pub fn set_uv_rect(&mut self, uv_rect: Rect<f32>) -> Rect<f32> {
         self.uv_rect.set_value_and_mark_modified(uv_rect)
     }
+
+    /// Sets a region of a texture atlas, that will be shown by the sprite instead of the uv rectangle.
+    /// The texture of the atlas is assigned to the `diffuseTexture` property of the material on the
+    /// next update. Trimmed regions occupy only a part of the sprite. See [`AtlasRegionRef`] docs for
+    /// more info.
+    pub fn set_atlas_region(&mut self, atlas_region: AtlasRegionRef) -> AtlasRegionRef {
+        self.atlas_region.set_value_and_mark_modified(atlas_region)
+    }
+
+    /// Returns a reference to the current atlas region of the sprite.
+    pub fn atlas_region(&self) -> &AtlasRegionRef {
+        &self.atlas_region
+    }
+
+    /// Sets new flipbook animation of the sprite. Frames of the animation are taken from the uv
+    /// rectangle (or the atlas region) of the sprite. See [`Flipbook`] docs for more info.
+    pub fn set_flipbook(&mut self, flipbook: Flipbook) -> Flipbook {
+        self.flipbook.set_value_and_mark_modified(flipbook)
+    }
+
+    /// Returns a reference to the flipbook animation of the sprite.
+    pub fn flipbook(&self) -> &Flipbook {
+        &self.flipbook
+    }
+
+    /// Returns a reference to the flipbook animation of the sprite, that could be used to control
+    /// the playback.
+    pub fn flipbook_mut(&mut self) -> &mut Flipbook {
+        self.flipbook.get_value_mut_and_mark_modified()
+    }
+
+    /// Sets new 9-slice scaling parameters of the sprite. Margins are defined in world units, the
+    /// whole sprite is `2 * size` units wide. See [`NineSlice`] docs for more info.
+    pub fn set_nine_slice(&mut self, nine_slice: NineSlice) -> NineSlice {
+        self.nine_slice.set_value_and_mark_modified(nine_slice)
+    }
+
+    /// Returns current 9-slice scaling parameters of the sprite.
+    pub fn nine_slice(&self) -> &NineSlice {
+        &self.nine_slice
+    }
+
+    /// Sets new sorting order of the sprite, that defines whether the sprite is drawn on top of other
+    /// sprites or below them. See [`SortingOrder`] docs for more info.
+    pub fn set_sorting_order(&mut self, sorting_order: SortingOrder) -> SortingOrder {
+        self.sorting_order
+            .set_value_and_mark_modified(sorting_order)
+    }
+
+    /// Returns current sorting order of the sprite.
+    pub fn sorting_order(&self) -> SortingOrder {
+        *self.sorting_order
+    }
+
+    /// Returns the region of the texture that is currently shown, it takes current frame of the
+    /// flipbook animation into account.
+    pub fn current_uv_rect(&self) -> Rect<f32> {
+        if self.flipbook.enabled {
+            self.flipbook.frame_uv_rect(*self.uv_rect)
+        } else {
+            *self.uv_rect
+        }
+    }
+
+    // Returns the shown part of the image and a quad, that maps the image to the texture.
+    fn frame_and_quad(&self) -> (Rect<f32>, AtlasQuad) {
+        match self.atlas_region.quad() {
+            Some(quad) => {
+                let whole = Rect::new(0.0, 0.0, 1.0, 1.0);
+                let frame = if self.flipbook.enabled {
+                    self.flipbook.frame_uv_rect(whole)
+                } else {
+                    whole
+                };
+                (frame, quad)
+            }
+            None => (self.current_uv_rect(), AtlasQuad::default()),
+        }
+    }
 }
 
 impl NodeTrait for Sprite {
@@ -287,6 +696,15 @@ impl NodeTrait for Sprite {
         Self::type_uuid()
     }
 
+    fn update(&mut self, context: &mut UpdateContext) {
+        if self.flipbook.enabled && self.flipbook.playing {
+            // Playback state is not a property change.
+            self.flipbook.get_value_mut_silent().update(context.dt);
+        }
+
+        self.atlas_region.sync_material(&self.material);
+    }
+
     fn collect_render_data(&self, ctx: &mut RenderContext) {
         if !self.global_visibility()
             || !self.is_globally_enabled()
@@ -301,32 +719,42 @@ impl NodeTrait for Sprite {
 
         let position = self.global_position();
         let params = Vector2::new(*self.size, *self.rotation);
+        let (frame, quad) = self.frame_and_quad();
+        let make_vertex = |corner: Vector2<f32>, tex_coord: Vector2<f32>| SpriteVertex {
+            position,
+            tex_coord: quad.tex_coord(frame.position + frame.size.component_mul(&tex_coord)),
+            params,
+            color: *self.color,
+            corner: corner.scale(2.0).add_scalar(-1.0),
+        };
+
+        if self.nine_slice.enabled {
+            let size = Vector2::repeat(2.0 * *self.size);
+            let (vertices, triangles) = self.nine_slice.build(size, make_vertex);
+
+            ctx.storage.push_triangles(
+                vertices.into_iter(),
+                triangles.into_iter(),
+                &self.material,
+                RenderPath::Forward,
+                0,
+                self.sorting_order.sort_index(),
+                false,
+                self.self_handle,
+            );
 
+            return;
+        }
+
+        // Trimmed atlas regions cover only a part of the sprite.
+        let corner = |x: f32, y: f32| {
+            make_vertex(quad.local_position(Vector2::new(x, y)), Vector2::new(x, y))
+        };
         let vertices = [
-            SpriteVertex {
-                position,
-                tex_coord: self.uv_rect.right_top_corner(),
-                params,
-                color: *self.color,
-            },
-            SpriteVertex {
-                position,
-                tex_coord: self.uv_rect.left_top_corner(),
-                params,
-                color: *self.color,
-            },
-            SpriteVertex {
-                position,
-                tex_coord: self.uv_rect.left_bottom_corner(),
-                params,
-                color: *self.color,
-            },
-            SpriteVertex {
-                position,
-                tex_coord: self.uv_rect.right_bottom_corner(),
-                params,
-                color: *self.color,
-            },
+            corner(1.0, 0.0),
+            corner(0.0, 0.0),
+            corner(0.0, 1.0),
+            corner(1.0, 1.0),
         ];
 
         let triangles = [TriangleDefinition([0, 1, 2]), TriangleDefinition([2, 3, 0])];
@@ -337,7 +765,7 @@ impl NodeTrait for Sprite {
             &self.material,
             RenderPath::Forward,
             0,
-            0,
+            self.sorting_order.sort_index(),
             false,
             self.self_handle,
         )
@@ -350,9 +778,13 @@ pub struct SpriteBuilder {
     base_builder: BaseBuilder,
     uv_rect: Rect<f32>,
     material: MaterialResource,
+    atlas_region: AtlasRegionRef,
     color: Color,
     size: f32,
     rotation: f32,
+    flipbook: Flipbook,
+    nine_slice: NineSlice,
+    sorting_order: SortingOrder,
 }
 
 impl SpriteBuilder {
@@ -362,9 +794,13 @@ impl SpriteBuilder {
             base_builder,
             material: MaterialResource::new_ok(Default::default(), Material::standard_sprite()),
             uv_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
+            atlas_region: Default::default(),
             color: Color::WHITE,
             size: 0.2,
             rotation: 0.0,
+            flipbook: Default::default(),
+            nine_slice: Default::default(),
+            sorting_order: Default::default(),
         }
     }
 
@@ -399,14 +835,42 @@ impl SpriteBuilder {
         self
     }
 
+    /// Sets desired region of a texture atlas. See [`Sprite::set_atlas_region`] for more info.
+    pub fn with_atlas_region(mut self, atlas_region: AtlasRegionRef) -> Self {
+        self.atlas_region = atlas_region;
+        self
+    }
+
+    /// Sets desired flipbook animation. See [`Sprite::set_flipbook`] for more info.
+    pub fn with_flipbook(mut self, flipbook: Flipbook) -> Self {
+        self.flipbook = flipbook;
+        self
+    }
+
+    /// Sets desired 9-slice scaling parameters. See [`Sprite::set_nine_slice`] for more info.
+    pub fn with_nine_slice(mut self, nine_slice: NineSlice) -> Self {
+        self.nine_slice = nine_slice;
+        self
+    }
+
+    /// Sets desired sorting order. See [`Sprite::set_sorting_order`] for more info.
+    pub fn with_sorting_order(mut self, sorting_order: SortingOrder) -> Self {
+        self.sorting_order = sorting_order;
+        self
+    }
+
     fn build_sprite(self) -> Sprite {
         Sprite {
             base: self.base_builder.build_base(),
             material: self.material.into(),
             uv_rect: self.uv_rect.into(),
+            atlas_region: self.atlas_region.into(),
             color: self.color.into(),
             size: self.size.into(),
             rotation: self.rotation.into(),
+            flipbook: self.flipbook.into(),
+            nine_slice: self.nine_slice.into(),
+            sorting_order: self.sorting_order.into(),
         }
     }
 
@@ -420,3 +884,57 @@ impl SpriteBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector2, math::Rect},
+        scene::sprite::{Flipbook, FlipbookMode, NineSlice, SliceMargins},
+    };
+
+    #[test]
+    fn test_flipbook() {
+        let mut flipbook = Flipbook::new(2, 2, 1.0);
+        assert_eq!(flipbook.current_frame(), 0);
+
+        flipbook.update(1.5);
+        assert_eq!(flipbook.current_frame(), 1);
+        assert_eq!(
+            flipbook.frame_uv_rect(Rect::new(0.0, 0.0, 1.0, 1.0)),
+            Rect::new(0.5, 0.0, 0.5, 0.5)
+        );
+
+        flipbook.update(3.0);
+        assert_eq!(flipbook.current_frame(), 0);
+
+        flipbook.mode = FlipbookMode::PingPong;
+        flipbook.rewind();
+        let frames = (0..7)
+            .map(|_| {
+                let frame = flipbook.current_frame();
+                flipbook.update(1.0);
+                frame
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(frames, [0, 1, 2, 3, 2, 1, 0]);
+
+        flipbook.mode = FlipbookMode::Once;
+        flipbook.rewind();
+        flipbook.update(10.0);
+        assert_eq!(flipbook.current_frame(), 3);
+        assert!(!flipbook.playing);
+    }
+
+    #[test]
+    fn test_nine_slice_grid() {
+        let nine_slice = NineSlice::new(SliceMargins::uniform(0.25), SliceMargins::uniform(1.0));
+
+        let (columns, rows) = nine_slice.grid(Vector2::new(4.0, 1.0));
+        assert_eq!(
+            columns,
+            [(0.0, 0.0), (0.25, 0.25), (0.75, 0.75), (1.0, 1.0)]
+        );
+        // The border does not fit and must be shrunk.
+        assert_eq!(rows, [(0.0, 0.0), (0.5, 0.25), (0.5, 0.75), (1.0, 1.0)]);
+    }
+}
@@ -0,0 +1,288 @@
+//! Streaming volume is a box-shaped region of a scene, that loads a chunk of a level when a tracked
+//! node enters it. See [`StreamingVolume`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector3},
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::SceneDrawingContext,
+        graph::Graph,
+        node::{Node, NodeTrait},
+    },
+};
+use std::{
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+};
+
+/// Streaming volume is a box-shaped region of a scene, that references a chunk of a level (a separate
+/// scene file). The chunk is loaded asynchronously and merged into the scene (see
+/// [`crate::scene::SceneContainer::load_additive`]) when a tracked node enters the volume, and it is
+/// unloaded when the tracked node leaves the volume. Streaming is performed by
+/// [`crate::engine::streaming::LevelStreamer`], which is a part of the engine.
+///
+/// # Size and hysteresis
+///
+/// The volume is a unit cube in local coordinates, its exact size is defined by volume's `local scale`
+/// (the same way as for [`crate::scene::decal::Decal`]). A chunk is unloaded only when the tracked
+/// node is farther than [`Self::hysteresis`] meters from the volume, this prevents repeated loading
+/// and unloading when the tracked node moves back and forth along the border of the volume.
+///
+/// # Tracked node
+///
+/// Every volume tracks a node set by [`Self::set_tracked_node`] (usually the player or its camera).
+/// If the tracked node is not set, the first enabled camera of the scene is tracked.
+///
+/// # Priority
+///
+/// When multiple chunks must be loaded at once, chunks with higher [`Self::priority`] are requested
+/// first. The amount of chunks that are loaded simultaneously is limited by
+/// [`crate::engine::streaming::LevelStreamer::set_max_concurrent_loads`].
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{
+///         base::BaseBuilder, graph::Graph, node::Node, streaming::StreamingVolumeBuilder,
+///         transform::TransformBuilder,
+///     },
+/// };
+///
+/// fn add_village(graph: &mut Graph, player: Handle<Node>) -> Handle<Node> {
+///     StreamingVolumeBuilder::new(
+///         BaseBuilder::new().with_local_transform(
+///             TransformBuilder::new()
+///                 .with_local_position(Vector3::new(200.0, 0.0, 50.0))
+///                 .with_local_scale(Vector3::new(100.0, 50.0, 100.0))
+///                 .build(),
+///         ),
+///     )
+///     .with_chunk("data/chunks/village.rgs")
+///     .with_tracked_node(player)
+///     .with_hysteresis(20.0)
+///     .build(graph)
+/// }
+/// ```
+#[derive(Debug, Visit, Clone, Reflect)]
+pub struct StreamingVolume {
+    base: Base,
+
+    #[reflect(
+        setter = "set_chunk",
+        description = "Path to a scene, that will be loaded when the tracked node enters the volume."
+    )]
+    chunk: InheritableVariable<PathBuf>,
+
+    #[reflect(setter = "set_priority")]
+    priority: InheritableVariable<i32>,
+
+    #[reflect(
+        min_value = 0.0,
+        step = 0.5,
+        setter = "set_hysteresis",
+        description = "Distance (in meters) outside the volume, at which the chunk will be unloaded."
+    )]
+    hysteresis: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_tracked_node")]
+    tracked_node: InheritableVariable<Handle<Node>>,
+}
+
+impl Default for StreamingVolume {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            chunk: Default::default(),
+            priority: 0.into(),
+            hysteresis: 5.0.into(),
+            tracked_node: Default::default(),
+        }
+    }
+}
+
+impl Deref for StreamingVolume {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for StreamingVolume {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for StreamingVolume {
+    fn type_uuid() -> Uuid {
+        uuid!("b7e2d91a-5c38-4f06-8a1e-93d64f2c07b5")
+    }
+}
+
+impl StreamingVolume {
+    /// Sets a path to a scene, that will be loaded when the tracked node enters the volume.
+    pub fn set_chunk(&mut self, path: PathBuf) -> PathBuf {
+        self.chunk.set_value_and_mark_modified(path)
+    }
+
+    /// Returns a path to the chunk of the volume.
+    pub fn chunk(&self) -> &Path {
+        &self.chunk
+    }
+
+    /// Sets loading priority of the chunk. Chunks with higher priority are loaded first.
+    pub fn set_priority(&mut self, priority: i32) -> i32 {
+        self.priority.set_value_and_mark_modified(priority)
+    }
+
+    /// Returns loading priority of the chunk.
+    pub fn priority(&self) -> i32 {
+        *self.priority
+    }
+
+    /// Sets the distance (in meters) outside the volume, at which the chunk will be unloaded.
+    pub fn set_hysteresis(&mut self, hysteresis: f32) -> f32 {
+        self.hysteresis
+            .set_value_and_mark_modified(hysteresis.max(0.0))
+    }
+
+    /// Returns the unloading distance of the volume.
+    pub fn hysteresis(&self) -> f32 {
+        *self.hysteresis
+    }
+
+    /// Sets a node, whose position will be used to decide whether the chunk must be loaded or not.
+    /// [`Handle::NONE`] means that the first enabled camera of the scene will be tracked.
+    pub fn set_tracked_node(&mut self, node: Handle<Node>) -> Handle<Node> {
+        self.tracked_node.set_value_and_mark_modified(node)
+    }
+
+    /// Returns tracked node of the volume.
+    pub fn tracked_node(&self) -> Handle<Node> {
+        *self.tracked_node
+    }
+
+    /// Returns the distance (in meters) from the volume to the given world-space position, it is
+    /// zero when the position is inside the volume.
+    pub fn distance(&self, world_position: Vector3<f32>) -> f32 {
+        let transform = self.global_transform();
+        let Some(inv) = transform.try_inverse() else {
+            return f32::INFINITY;
+        };
+        let local = inv.transform_point(&Point3::from(world_position)).coords;
+        let scale = Vector3::new(
+            transform.column(0).xyz().norm(),
+            transform.column(1).xyz().norm(),
+            transform.column(2).xyz().norm(),
+        );
+        local
+            .map(|c| (c.abs() - 0.5).max(0.0))
+            .component_mul(&scale)
+            .norm()
+    }
+}
+
+impl NodeTrait for StreamingVolume {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::unit()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        ctx.draw_oob(
+            &AxisAlignedBoundingBox::unit(),
+            self.global_transform(),
+            Color::opaque(120, 255, 120),
+        );
+    }
+}
+
+/// Allows you to create a streaming volume in a declarative manner.
+pub struct StreamingVolumeBuilder {
+    base_builder: BaseBuilder,
+    chunk: PathBuf,
+    priority: i32,
+    hysteresis: f32,
+    tracked_node: Handle<Node>,
+}
+
+impl StreamingVolumeBuilder {
+    /// Creates a new instance of the builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            chunk: Default::default(),
+            priority: 0,
+            hysteresis: 5.0,
+            tracked_node: Handle::NONE,
+        }
+    }
+
+    /// Sets desired path to the chunk.
+    pub fn with_chunk<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.chunk = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Sets desired loading priority of the chunk.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets desired unloading distance.
+    pub fn with_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Sets desired tracked node.
+    pub fn with_tracked_node(mut self, node: Handle<Node>) -> Self {
+        self.tracked_node = node;
+        self
+    }
+
+    /// Creates new streaming volume.
+    pub fn build_streaming_volume(self) -> StreamingVolume {
+        StreamingVolume {
+            base: self.base_builder.build_base(),
+            chunk: self.chunk.into(),
+            priority: self.priority.into(),
+            hysteresis: self.hysteresis.max(0.0).into(),
+            tracked_node: self.tracked_node.into(),
+        }
+    }
+
+    /// Creates new streaming volume node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_streaming_volume())
+    }
+
+    /// Creates new instance of streaming volume node and puts it in the given graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
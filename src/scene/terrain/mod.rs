@@ -43,8 +43,8 @@ use image::{imageops::FilterType, ImageBuffer, Luma};
 use std::{
     cell::Cell,
     cmp::Ordering,
-    collections::HashMap,
-    ops::{Deref, DerefMut, Range},
+    collections::{HashMap, VecDeque},
+    ops::{Deref, DerefMut, Range, RangeInclusive},
 };
 
 mod geometry;
@@ -71,6 +71,11 @@ pub struct Layer {
     /// Name of the node uv offsets property in the material.
     #[visit(optional)]
     pub node_uv_offsets_property_name: String,
+
+    /// Type of the surface of the layer (for example `grass`, `sand`, `rock`, etc.). It is used to
+    /// select footstep sounds and impact effects, see [`Terrain::surface_type_at`] for more info.
+    #[visit(optional)]
+    pub surface_type: String,
 }
 
 uuid_provider!(Layer = "7439d5fd-43a9-45f0-bd7c-76cf4d2ec22e");
@@ -82,10 +87,42 @@ impl Default for Layer {
             mask_property_name: "maskTexture".to_string(),
             height_map_property_name: "heightMapTexture".to_string(),
             node_uv_offsets_property_name: "nodeUvOffsets".to_string(),
+            surface_type: Default::default(),
         }
     }
 }
 
+impl Layer {
+    fn set_material_property(&self, name: &str, value: PropertyValue) {
+        Log::verify(
+            self.material
+                .data_ref()
+                .set_property(&ImmutableString::new(name), value),
+        );
+    }
+
+    /// Sets how many times the textures of the layer are repeated per chunk (or per meter, if the
+    /// triplanar projection is used). Each layer has its own tiling, so detailed textures (grass, sand)
+    /// could be tiled more often than large-scale ones (rocks, cliffs). Works only with materials
+    /// that use the standard terrain shader.
+    pub fn set_tiling(&self, tiling: Vector2<f32>) {
+        self.set_material_property("texCoordScale", PropertyValue::Vector2(tiling));
+    }
+
+    /// Enables or disables triplanar projection of the textures of the layer. Triplanar projection
+    /// removes texture stretching on steep slopes (cliffs, for example) at the cost of extra texture
+    /// fetches. Works only with materials that use the standard terrain shader.
+    pub fn set_triplanar(&self, triplanar: bool) {
+        self.set_material_property("triplanar", PropertyValue::Bool(triplanar));
+    }
+
+    /// Sets sharpness of the transition between projections of the triplanar mapping. Larger values
+    /// make the transitions sharper. Default value is 4.0.
+    pub fn set_triplanar_sharpness(&self, sharpness: f32) {
+        self.set_material_property("triplanarSharpness", PropertyValue::Float(sharpness));
+    }
+}
+
 fn make_quad_tree(
     texture: &Option<TextureResource>,
     height_map_size: Vector2<u32>,
@@ -631,6 +668,9 @@ pub struct Terrain {
     #[reflect(hidden)]
     geometry: TerrainGeometry,
 
+    #[reflect(hidden)]
+    height_map_modifications: HeightMapModifications,
+
     #[reflect(hidden)]
     version: u8,
 }
@@ -651,6 +691,7 @@ impl Default for Terrain {
             bounding_box_dirty: Cell::new(true),
             bounding_box: Cell::new(Default::default()),
             geometry: Default::default(),
+            height_map_modifications: Default::default(),
             version: VERSION,
         }
     }
@@ -797,6 +838,53 @@ impl DerefMut for Terrain {
     }
 }
 
+/// Maximum amount of height map modifications, that are remembered to update heightfield colliders
+/// incrementally.
+const MAX_HEIGHT_MAP_MODIFICATIONS: usize = 64;
+
+/// A rectangular region of the height map of the whole terrain (all chunks combined), in pixels.
+/// Both bounds are inclusive.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HeightMapRegion {
+    /// Top-left corner of the region.
+    pub min: Vector2<u32>,
+    /// Bottom-right corner of the region.
+    pub max: Vector2<u32>,
+}
+
+impl HeightMapRegion {
+    fn union(self, other: Self) -> Self {
+        Self {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct HeightMapModifications {
+    revision: u64,
+    log: VecDeque<(u64, HeightMapRegion)>,
+}
+
+// Returns a range of pixels along an axis, that lie inside the given range of coordinates.
+fn pixel_range(
+    min: f32,
+    max: f32,
+    origin: f32,
+    extent: f32,
+    resolution: u32,
+) -> Option<RangeInclusive<u32>> {
+    let last = resolution.saturating_sub(1) as f32;
+    let begin = ((min - origin) / extent * last).ceil().max(0.0);
+    let end = ((max - origin) / extent * last).floor().min(last);
+    if begin <= end {
+        Some(begin as u32..=end as u32)
+    } else {
+        None
+    }
+}
+
 fn project(global_transform: Matrix4<f32>, p: Vector3<f32>) -> Option<Vector2<f32>> {
     // Transform point in coordinate system of the terrain.
     if let Some(inv_global_transform) = global_transform.try_inverse() {
@@ -1007,25 +1095,117 @@ impl Terrain {
         project(self.global_transform(), p)
     }
 
+    /// Returns an index of the most visible layer at the given point (in world coordinates). Visibility
+    /// of a layer is defined by its mask, if there are multiple layers with the same visibility, the
+    /// top-most layer will be returned.
+    pub fn dominant_layer_at(&self, world_position: Vector3<f32>) -> Option<usize> {
+        let position = self.project(world_position)?;
+
+        let chunk = self.chunks.iter().find(|chunk| {
+            let min = chunk.local_position();
+            let max = min + chunk.physical_size;
+            position.x >= min.x && position.y >= min.y && position.x <= max.x && position.y <= max.y
+        })?;
+
+        let k = (position - chunk.local_position())
+            .component_div(&chunk.physical_size)
+            .map(|k| k.clamp(0.0, 1.0));
+
+        let mut dominant = None;
+        let mut max_weight = 0;
+        for (layer_index, mask) in chunk.layer_masks.iter().enumerate() {
+            let mut state = mask.state();
+            let Some(texture) = state.data() else {
+                continue;
+            };
+
+            let TextureKind::Rectangle { width, height } = texture.kind() else {
+                continue;
+            };
+
+            let x = (k.x * (width - 1) as f32).round() as usize;
+            let z = (k.y * (height - 1) as f32).round() as usize;
+            if let Some(&weight) = texture.data().get(z * width as usize + x) {
+                if weight > 0 && weight >= max_weight {
+                    max_weight = weight;
+                    dominant = Some(layer_index);
+                }
+            }
+        }
+
+        dominant
+    }
+
+    /// Returns surface type (see [`Layer::surface_type`]) of the most visible layer at the given point
+    /// (in world coordinates). It could be used to select footstep sounds, impact effects, etc. for
+    /// the surface under a character.
+    pub fn surface_type_at(&self, world_position: Vector3<f32>) -> Option<&str> {
+        self.dominant_layer_at(world_position)
+            .and_then(|index| self.layers.get(index))
+            .map(|layer| layer.surface_type.as_str())
+            .filter(|surface_type| !surface_type.is_empty())
+    }
+
     /// Applies the given function to each pixel of the height map.
-    pub fn for_each_height_map_pixel<F>(&mut self, mut func: F)
+    pub fn for_each_height_map_pixel<F>(&mut self, func: F)
     where
         F: FnMut(&mut f32, Vector2<f32>),
     {
+        self.modify_height_map(None, func)
+    }
+
+    // Applies the given function to each pixel of the height map, that lies in the given area (in
+    // local 2D coordinates of the terrain). Modified region is remembered, so heightfield colliders
+    // could be updated incrementally.
+    fn modify_height_map<F>(&mut self, area: Option<Rect<f32>>, mut func: F)
+    where
+        F: FnMut(&mut f32, Vector2<f32>),
+    {
+        let grid_origin = Vector2::new(self.width_chunks.start, self.length_chunks.start);
+        let mut modified_region: Option<HeightMapRegion> = None;
+
         for chunk in self.chunks.iter_mut() {
+            let chunk_position = chunk.local_position();
+            let size = chunk.height_map_size;
+
+            let (x_range, z_range) = match area {
+                Some(area) => {
+                    let Some(x_range) = pixel_range(
+                        area.x(),
+                        area.x() + area.w(),
+                        chunk_position.x,
+                        chunk.physical_size.x,
+                        size.x,
+                    ) else {
+                        continue;
+                    };
+                    let Some(z_range) = pixel_range(
+                        area.y(),
+                        area.y() + area.h(),
+                        chunk_position.y,
+                        chunk.physical_size.y,
+                        size.y,
+                    ) else {
+                        continue;
+                    };
+                    (x_range, z_range)
+                }
+                None => (0..=size.x - 1, 0..=size.y - 1),
+            };
+
             let mut texture_data = chunk.heightmap.as_ref().unwrap().data_ref();
             let mut texture_modifier = texture_data.modify();
             let height_map = texture_modifier.data_mut_of_type::<f32>().unwrap();
 
-            for iy in 0..chunk.height_map_size.y {
-                let kz = iy as f32 / (chunk.height_map_size.y - 1) as f32;
-                for ix in 0..chunk.height_map_size.y {
-                    let kx = ix as f32 / (chunk.height_map_size.x - 1) as f32;
+            for iy in z_range.clone() {
+                let kz = iy as f32 / (size.y - 1) as f32;
+                for ix in x_range.clone() {
+                    let kx = ix as f32 / (size.x - 1) as f32;
 
-                    let pixel_position = chunk.local_position()
+                    let pixel_position = chunk_position
                         + Vector2::new(kx * chunk.physical_size.x, kz * chunk.physical_size.y);
 
-                    let index = (iy * chunk.height_map_size.x + ix) as usize;
+                    let index = (iy * size.x + ix) as usize;
 
                     func(&mut height_map[index], pixel_position)
                 }
@@ -1036,19 +1216,71 @@ impl Terrain {
 
             chunk.quad_tree =
                 make_quad_tree(&chunk.heightmap, chunk.height_map_size, chunk.block_size);
+
+            let offset = (chunk.grid_position - grid_origin)
+                .map(|c| c.max(0) as u32)
+                .component_mul(&size);
+            let chunk_region = HeightMapRegion {
+                min: offset + Vector2::new(*x_range.start(), *z_range.start()),
+                max: offset + Vector2::new(*x_range.end(), *z_range.end()),
+            };
+            modified_region = Some(match modified_region {
+                Some(region) => region.union(chunk_region),
+                None => chunk_region,
+            });
+        }
+
+        if let Some(region) = modified_region {
+            let modifications = &mut self.height_map_modifications;
+            modifications.revision += 1;
+            modifications
+                .log
+                .push_back((modifications.revision, region));
+            if modifications.log.len() > MAX_HEIGHT_MAP_MODIFICATIONS {
+                modifications.log.pop_front();
+            }
         }
 
         self.bounding_box_dirty.set(true);
     }
 
+    /// Returns a number, that is incremented every time when the height map is modified using
+    /// [`Self::draw`] (or any other method that uses it) or [`Self::for_each_height_map_pixel`].
+    /// Heightfield colliders use it to track changes of the height map, modifications done
+    /// directly via chunks are not tracked.
+    pub fn height_map_revision(&self) -> u64 {
+        self.height_map_modifications.revision
+    }
+
+    /// Returns a region of the height map, that was modified after the given revision. Returns
+    /// [`None`] if there were no modifications, or if the revision is too old and the region
+    /// cannot be calculated.
+    pub fn height_map_modified_region(&self, since_revision: u64) -> Option<HeightMapRegion> {
+        let modifications = &self.height_map_modifications;
+        let (first_revision, _) = modifications.log.front()?;
+        if since_revision + 1 < *first_revision {
+            return None;
+        }
+
+        modifications
+            .log
+            .iter()
+            .filter(|(revision, _)| *revision > since_revision)
+            .map(|(_, region)| *region)
+            .reduce(HeightMapRegion::union)
+    }
+
     /// Multi-functional drawing method. It uses given brush to modify terrain, see [`Brush`] docs for
-    /// more info.
+    /// more info. Only the part of the terrain, that is covered by the brush, is modified.
     pub fn draw(&mut self, brush: &Brush) {
-        let center = project(self.global_transform(), brush.center).unwrap();
+        let Some(center) = project(self.global_transform(), brush.center) else {
+            return;
+        };
+        let area = brush.shape.bounds(center);
 
         match brush.mode {
             BrushMode::ModifyHeightMap { amount } => {
-                self.for_each_height_map_pixel(|pixel, pixel_position| {
+                self.modify_height_map(Some(area), |pixel, pixel_position| {
                     let k = match brush.shape {
                         BrushShape::Circle { radius } => {
                             1.0 - ((center - pixel_position).norm() / radius).powf(2.0)
@@ -1075,14 +1307,33 @@ impl Terrain {
 
                     let (texture_width, texture_height) =
                         if let TextureKind::Rectangle { width, height } = texture_data_mut.kind() {
-                            (width as usize, height as usize)
+                            (width, height)
                         } else {
                             unreachable!("Mask must be a 2D greyscale image!")
                         };
 
-                    for z in 0..texture_height {
+                    let Some(x_range) = pixel_range(
+                        area.x(),
+                        area.x() + area.w(),
+                        chunk_position.x,
+                        chunk.physical_size.x,
+                        texture_width,
+                    ) else {
+                        continue;
+                    };
+                    let Some(z_range) = pixel_range(
+                        area.y(),
+                        area.y() + area.h(),
+                        chunk_position.y,
+                        chunk.physical_size.y,
+                        texture_height,
+                    ) else {
+                        continue;
+                    };
+
+                    for z in z_range {
                         let kz = z as f32 / (texture_height - 1) as f32;
-                        for x in 0..texture_width {
+                        for x in x_range.clone() {
                             let kx = x as f32 / (texture_width - 1) as f32;
 
                             let pixel_position = chunk_position
@@ -1101,7 +1352,7 @@ impl Terrain {
                             if brush.shape.contains(center, pixel_position) {
                                 // We can draw on mask directly, without any problems because it has R8 pixel format.
                                 let data = texture_data_mut.data_mut();
-                                let pixel = &mut data[z * texture_width + x];
+                                let pixel = &mut data[(z * texture_width + x) as usize];
                                 *pixel = (*pixel as f32 + k * alpha * 255.0).min(255.0) as u8;
                             }
                         }
@@ -1109,7 +1360,7 @@ impl Terrain {
                 }
             }
             BrushMode::FlattenHeightMap { height } => {
-                self.for_each_height_map_pixel(|pixel, pixel_position| {
+                self.modify_height_map(Some(area), |pixel, pixel_position| {
                     if brush.shape.contains(center, pixel_position) {
                         *pixel = height;
                     }
@@ -1118,6 +1369,42 @@ impl Terrain {
         }
     }
 
+    /// Raises the terrain in the area defined by the given shape centered at the given point (in world
+    /// coordinates). The amount is smoothly decreased towards the edges of circular brushes.
+    pub fn raise(&mut self, center: Vector3<f32>, shape: BrushShape, amount: f32) {
+        self.draw(&Brush {
+            center,
+            shape,
+            mode: BrushMode::ModifyHeightMap { amount },
+        })
+    }
+
+    /// Lowers the terrain in the area defined by the given shape centered at the given point (in world
+    /// coordinates). Could be used to make craters from explosions, digging, etc.
+    pub fn lower(&mut self, center: Vector3<f32>, shape: BrushShape, amount: f32) {
+        self.raise(center, shape, -amount)
+    }
+
+    /// Sets the given height (in local coordinates of the terrain) for every pixel of the height map in
+    /// the area defined by the given shape centered at the given point (in world coordinates).
+    pub fn flatten(&mut self, center: Vector3<f32>, shape: BrushShape, height: f32) {
+        self.draw(&Brush {
+            center,
+            shape,
+            mode: BrushMode::FlattenHeightMap { height },
+        })
+    }
+
+    /// Paints on the mask of the given layer in the area defined by the given shape centered at the
+    /// given point (in world coordinates). Negative alpha erases the layer.
+    pub fn paint(&mut self, center: Vector3<f32>, shape: BrushShape, layer: usize, alpha: f32) {
+        self.draw(&Brush {
+            center,
+            shape,
+            mode: BrushMode::DrawOnMask { layer, alpha },
+        })
+    }
+
     /// Casts a ray and looks for intersections with the terrain. This method collects all results in
     /// given array with optional sorting by the time-of-impact.
     ///
@@ -1556,6 +1843,9 @@ impl NodeTrait for Terrain {
                                     node.persistent_index,
                                 ),
                                 node_handle: self.self_handle,
+                                allow_instancing: false,
+                                use_dual_quaternion_skinning: false,
+                                property_overrides: Default::default(),
                             },
                         );
                     } else {
@@ -1579,6 +1869,9 @@ impl NodeTrait for Terrain {
                                             node.persistent_index,
                                         ),
                                         node_handle: self.self_handle,
+                                        allow_instancing: false,
+                                        use_dual_quaternion_skinning: false,
+                                        property_overrides: Default::default(),
                                     },
                                 );
                             }
@@ -1616,6 +1909,19 @@ pub enum BrushShape {
 uuid_provider!(BrushShape = "a4dbfba0-077c-4658-9972-38384a8432f9");
 
 impl BrushShape {
+    fn bounds(&self, brush_center: Vector2<f32>) -> Rect<f32> {
+        let half_size = match *self {
+            BrushShape::Circle { radius } => Vector2::new(radius, radius),
+            BrushShape::Rectangle { width, length } => Vector2::new(width, length).scale(0.5),
+        };
+        Rect::new(
+            brush_center.x - half_size.x,
+            brush_center.y - half_size.y,
+            half_size.x * 2.0,
+            half_size.y * 2.0,
+        )
+    }
+
     fn contains(&self, brush_center: Vector2<f32>, pixel_position: Vector2<f32>) -> bool {
         match *self {
             BrushShape::Circle { radius } => (brush_center - pixel_position).norm() < radius,
@@ -1815,6 +2121,7 @@ impl TerrainBuilder {
             decal_layer_index: self.decal_layer_index.into(),
             version: VERSION,
             geometry: TerrainGeometry::new(self.block_size),
+            height_map_modifications: Default::default(),
             block_size: self.block_size.into(),
         };
         Node::new(terrain)
@@ -1825,3 +2132,87 @@ impl TerrainBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            sstorage::ImmutableString,
+        },
+        material::PropertyValue,
+        scene::{
+            base::BaseBuilder,
+            terrain::{BrushShape, HeightMapRegion, Layer, Terrain, TerrainBuilder},
+        },
+    };
+
+    #[test]
+    fn test_terrain_height_map_modifications() {
+        let mut node = TerrainBuilder::new(BaseBuilder::new())
+            .with_chunk_size(Vector2::new(4.0, 4.0))
+            .with_width_chunks(0..2)
+            .with_length_chunks(0..1)
+            .with_height_map_size(Vector2::new(5, 5))
+            .build_node();
+        let terrain = node.cast_mut::<Terrain>().unwrap();
+        assert_eq!(terrain.height_map_revision(), 0);
+
+        terrain.lower(
+            Vector3::new(2.0, 0.0, 2.0),
+            BrushShape::Circle { radius: 1.0 },
+            1.0,
+        );
+        assert_eq!(terrain.height_map_revision(), 1);
+        assert_eq!(terrain.chunks_ref()[0].heightmap_owned()[2 * 5 + 2], -1.0);
+        assert_eq!(
+            terrain.height_map_modified_region(0),
+            Some(HeightMapRegion {
+                min: Vector2::new(1, 1),
+                max: Vector2::new(3, 3),
+            })
+        );
+        assert_eq!(terrain.height_map_modified_region(1), None);
+
+        // The brush covers both chunks.
+        terrain.flatten(
+            Vector3::new(4.0, 0.0, 2.0),
+            BrushShape::Rectangle {
+                width: 2.0,
+                length: 2.0,
+            },
+            0.5,
+        );
+        assert_eq!(
+            terrain.height_map_modified_region(1),
+            Some(HeightMapRegion {
+                min: Vector2::new(3, 1),
+                max: Vector2::new(6, 3),
+            })
+        );
+        assert_eq!(
+            terrain.height_map_modified_region(0),
+            Some(HeightMapRegion {
+                min: Vector2::new(1, 1),
+                max: Vector2::new(6, 3),
+            })
+        );
+    }
+
+    #[test]
+    fn test_layer_projection() {
+        let layer = Layer::default();
+        layer.set_triplanar(true);
+        layer.set_tiling(Vector2::new(4.0, 4.0));
+
+        let material = layer.material.data_ref();
+        assert_eq!(
+            material.property_ref(&ImmutableString::new("triplanar")),
+            Some(&PropertyValue::Bool(true))
+        );
+        assert_eq!(
+            material.property_ref(&ImmutableString::new("texCoordScale")),
+            Some(&PropertyValue::Vector2(Vector2::new(4.0, 4.0)))
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Time control allows you to slow down, speed up or freeze the time of a scene or a group of its
+//! nodes for a while. It is used to create effects like hit-stop (very short freeze of the game
+//! world on a powerful hit), bullet-time, etc. See [`TimeControl`] docs for more info.
+
+use crate::{
+    core::pool::{Handle, Pool},
+    scene::{graph::Graph, node::Node},
+};
+
+/// Time dilation is a temporary change of the speed of time. It could be applied either to the
+/// entire scene or to specific node hierarchies only. Its duration and fade times are measured in
+/// real (unscaled) seconds, so dilations do not slow down themselves.
+///
+/// # Examples
+///
+/// ```rust
+/// # use fyrox::scene::time_control::TimeDilation;
+/// // Freeze the scene for 0.1 seconds.
+/// let hit_stop = TimeDilation::hit_stop(0.1);
+///
+/// // Slow down the time 4 times for 5 seconds, smoothly entering and leaving the slow motion.
+/// let bullet_time = TimeDilation::bullet_time(0.25, 5.0).with_fade(0.3, 0.5);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeDilation {
+    /// Multiplier for the speed of time. `0.0` freezes the time, `1.0` - normal speed.
+    pub scale: f32,
+    /// Duration of the dilation in real seconds, including fade times. Could be [`f32::INFINITY`],
+    /// in this case the dilation must be stopped manually using [`TimeControl::release`] or
+    /// [`TimeControl::remove`].
+    pub duration: f32,
+    /// Time (in real seconds) that is needed to smoothly reach the desired scale.
+    pub fade_in: f32,
+    /// Time (in real seconds) that is needed to smoothly return to the normal speed of time.
+    pub fade_out: f32,
+    /// A set of nodes, whose hierarchies will be affected by the dilation. Empty set means that
+    /// the dilation is global and affects the entire scene.
+    pub nodes: Vec<Handle<Node>>,
+    elapsed: f32,
+}
+
+impl TimeDilation {
+    /// Creates new global time dilation with the given scale and duration and no fading.
+    pub fn new(scale: f32, duration: f32) -> Self {
+        Self {
+            scale: scale.max(0.0),
+            duration,
+            fade_in: 0.0,
+            fade_out: 0.0,
+            nodes: Default::default(),
+            elapsed: 0.0,
+        }
+    }
+
+    /// Creates new dilation, that freezes the time completely for the given duration.
+    pub fn hit_stop(duration: f32) -> Self {
+        Self::new(0.0, duration)
+    }
+
+    /// Creates new dilation, that slows down the time using the given scale for the given duration.
+    pub fn bullet_time(scale: f32, duration: f32) -> Self {
+        Self::new(scale, duration)
+    }
+
+    /// Sets fade-in and fade-out times of the dilation.
+    pub fn with_fade(mut self, fade_in: f32, fade_out: f32) -> Self {
+        self.fade_in = fade_in.max(0.0);
+        self.fade_out = fade_out.max(0.0);
+        self
+    }
+
+    /// Restricts the dilation to the hierarchies of the given nodes.
+    pub fn with_nodes(mut self, nodes: Vec<Handle<Node>>) -> Self {
+        self.nodes = nodes;
+        self
+    }
+
+    /// Returns `true` if the dilation affects the entire scene, `false` - otherwise.
+    pub fn is_global(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns amount of real time that has passed since the dilation was started.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Returns `true` if the dilation has ended.
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Returns current strength of the dilation in `[0; 1]` range, which depends on fade times.
+    pub fn weight(&self) -> f32 {
+        let fade_in = if self.fade_in > 0.0 {
+            self.elapsed / self.fade_in
+        } else {
+            1.0
+        };
+        let fade_out = if self.fade_out > 0.0 {
+            (self.duration - self.elapsed) / self.fade_out
+        } else if self.is_finished() {
+            0.0
+        } else {
+            1.0
+        };
+        fade_in.min(fade_out).clamp(0.0, 1.0)
+    }
+
+    /// Returns current multiplier for the speed of time.
+    pub fn factor(&self) -> f32 {
+        1.0 + (self.scale - 1.0) * self.weight()
+    }
+}
+
+/// Time control is a container for active time dilations of a scene. Every scene has its own
+/// instance, see [`crate::scene::Scene::time_control`]. Dilations are layered on top of the
+/// [`crate::scene::Scene::time_scale`] by multiplication, so several dilations could be active at
+/// the same time.
+///
+/// Scaled time affects node updates (animations, particle systems, etc.), lifetime of the nodes,
+/// physics stepping and playback speed (pitch) of the sounds. Physics simulation is global, so it
+/// is affected by global dilations only. Scripts still receive real delta time, use
+/// [`Graph::time_scale`] or [`Graph::node_time_scale`] to scale it where needed.
+///
+/// # Examples
+///
+/// ```rust
+/// # use fyrox::scene::{time_control::TimeDilation, Scene};
+/// fn on_powerful_hit(scene: &mut Scene) {
+///     scene.time_control.add(TimeDilation::hit_stop(0.08));
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct TimeControl {
+    dilations: Pool<TimeDilation>,
+}
+
+impl TimeControl {
+    /// Adds new time dilation and returns its handle. The handle could be used to stop the
+    /// dilation before its end.
+    pub fn add(&mut self, dilation: TimeDilation) -> Handle<TimeDilation> {
+        self.dilations.spawn(dilation)
+    }
+
+    /// Immediately removes the dilation.
+    pub fn remove(&mut self, handle: Handle<TimeDilation>) -> Option<TimeDilation> {
+        self.dilations.try_free(handle)
+    }
+
+    /// Starts fading out the dilation, it will be removed automatically after its fade-out time.
+    pub fn release(&mut self, handle: Handle<TimeDilation>) {
+        if let Some(dilation) = self.dilations.try_borrow_mut(handle) {
+            dilation.duration = dilation.duration.min(dilation.elapsed + dilation.fade_out);
+        }
+    }
+
+    /// Removes every dilation.
+    pub fn clear(&mut self) {
+        self.dilations.clear();
+    }
+
+    /// Returns a reference to the dilation, if it is still active.
+    pub fn try_get(&self, handle: Handle<TimeDilation>) -> Option<&TimeDilation> {
+        self.dilations.try_borrow(handle)
+    }
+
+    /// Returns an iterator over active dilations.
+    pub fn iter(&self) -> impl Iterator<Item = &TimeDilation> {
+        self.dilations.iter()
+    }
+
+    /// Returns multiplier for the speed of time of the entire scene, that is produced by the
+    /// active global dilations.
+    pub fn global_scale(&self) -> f32 {
+        self.dilations
+            .iter()
+            .filter(|dilation| dilation.is_global())
+            .map(|dilation| dilation.factor())
+            .product()
+    }
+
+    /// Advances the dilations by the given amount of real time and removes finished ones.
+    pub fn update(&mut self, dt: f32) {
+        for dilation in self.dilations.iter_mut() {
+            dilation.elapsed += dt;
+        }
+        self.dilations.retain(|dilation| !dilation.is_finished());
+    }
+
+    /// Calculates time scales and passes them to the graph. `time_scale` is the base time scale of
+    /// the scene.
+    pub(crate) fn apply(&self, time_scale: f32, graph: &mut Graph) {
+        let mut node_time_scales = std::mem::take(&mut graph.node_time_scales);
+        node_time_scales.clear();
+
+        for dilation in self.dilations.iter().filter(|d| !d.is_global()) {
+            let factor = dilation.factor();
+            if factor == 1.0 {
+                continue;
+            }
+
+            for &root in dilation.nodes.iter() {
+                if !graph.is_valid_handle(root) {
+                    continue;
+                }
+
+                for handle in graph.traverse_handle_iter(root) {
+                    *node_time_scales.entry(handle).or_insert(1.0) *= factor;
+                }
+            }
+        }
+
+        graph.time_scale = time_scale.max(0.0) * self.global_scale();
+        graph.node_time_scales = node_time_scales;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        pivot::PivotBuilder,
+        time_control::{TimeControl, TimeDilation},
+    };
+
+    #[test]
+    fn test_time_dilation_fading() {
+        let mut dilation = TimeDilation::bullet_time(0.5, 2.0).with_fade(1.0, 1.0);
+        assert_eq!(dilation.factor(), 1.0);
+        dilation.elapsed = 0.5;
+        assert_eq!(dilation.factor(), 0.75);
+        dilation.elapsed = 1.0;
+        assert_eq!(dilation.factor(), 0.5);
+        dilation.elapsed = 1.5;
+        assert_eq!(dilation.factor(), 0.75);
+        dilation.elapsed = 2.0;
+        assert!(dilation.is_finished());
+        assert_eq!(dilation.factor(), 1.0);
+    }
+
+    #[test]
+    fn test_time_control() {
+        let mut graph = Graph::new();
+        let parent = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        graph.link_nodes(child, parent);
+        let other = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let mut time_control = TimeControl::default();
+        let hit_stop = time_control.add(TimeDilation::hit_stop(0.1));
+        time_control.add(TimeDilation::bullet_time(0.5, f32::INFINITY).with_nodes(vec![parent]));
+
+        time_control.update(0.05);
+        time_control.apply(2.0, &mut graph);
+        assert_eq!(graph.time_scale(), 0.0);
+        assert!(time_control.try_get(hit_stop).is_some());
+
+        time_control.update(0.1);
+        time_control.apply(2.0, &mut graph);
+        assert!(time_control.try_get(hit_stop).is_none());
+        assert_eq!(graph.time_scale(), 2.0);
+        assert_eq!(graph.node_time_scale(parent), 1.0);
+        assert_eq!(graph.node_time_scale(child), 1.0);
+        assert_eq!(graph.node_time_scale(other), 2.0);
+    }
+}
@@ -0,0 +1,397 @@
+//! Timeline player is a node that plays a [`crate::resource::timeline::Timeline`] resource. See
+//! [`TimelinePlayer`] docs for more info.
+
+use crate::{
+    core::{
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    generic_animation::value::{BoundValue, BoundValueCollection},
+    resource::timeline::{Timeline, TimelineAction, TimelineResource},
+    scene::{
+        animation::{AnimationPlayer, BoundValueCollectionExt},
+        base::{Base, BaseBuilder},
+        camera::Camera,
+        graph::{Graph, NodePool},
+        node::{Node, NodeTrait, UpdateContext},
+        sound::Sound,
+    },
+};
+use std::{
+    collections::VecDeque,
+    ops::{Deref, DerefMut},
+};
+
+/// Timeline player is a node that plays a [`Timeline`] resource - it performs the actions of the
+/// timeline (plays animations, switches cameras, plays sounds, emits script events) at their times
+/// and animates properties of scene nodes using the property tracks of the timeline. Scene nodes
+/// are referenced by their names.
+///
+/// ## Skipping
+///
+/// A timeline can be skipped using [`TimelinePlayer::skip`]. In this case the player jumps to the
+/// end of the timeline: the property tracks are set to their final values, the last camera cut is
+/// applied and the script events are emitted (so game logic stays consistent), but animations and
+/// sounds are not started.
+///
+/// ## Script events
+///
+/// Script events could be fetched using [`TimelinePlayer::pop_script_event`], usually it is done
+/// in a script of the player or any other script that has access to the player.
+///
+/// ```rust
+/// # use fyrox::scene::{graph::Graph, node::Node, timeline::TimelinePlayer};
+/// # use fyrox::core::pool::Handle;
+/// fn handle_cutscene_events(graph: &mut Graph, player: Handle<Node>) {
+///     if let Some(player) = graph.try_get_mut_of_type::<TimelinePlayer>(player) {
+///         while let Some(event) = player.pop_script_event() {
+///             println!("Cutscene event: {event}");
+///         }
+///     }
+/// }
+/// ```
+#[derive(Visit, Reflect, Clone, Debug)]
+pub struct TimelinePlayer {
+    base: Base,
+
+    #[reflect(setter = "set_timeline")]
+    timeline: InheritableVariable<Option<TimelineResource>>,
+
+    #[reflect(
+        setter = "set_auto_play",
+        description = "Whether the timeline should be played automatically."
+    )]
+    auto_play: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_speed", min_value = 0.0, step = 0.1)]
+    speed: InheritableVariable<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    time: f32,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    last_time: f32,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    playing: bool,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    skip_requested: bool,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    auto_play_started: bool,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    script_events: VecDeque<String>,
+}
+
+impl Default for TimelinePlayer {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            timeline: Default::default(),
+            auto_play: Default::default(),
+            speed: 1.0.into(),
+            time: 0.0,
+            last_time: f32::NEG_INFINITY,
+            playing: false,
+            skip_requested: false,
+            auto_play_started: false,
+            script_events: Default::default(),
+        }
+    }
+}
+
+impl TypeUuidProvider for TimelinePlayer {
+    fn type_uuid() -> Uuid {
+        uuid!("0e5d1a0c-7b2f-4b9e-a6c4-3f8e2d9b1c57")
+    }
+}
+
+impl Deref for TimelinePlayer {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for TimelinePlayer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+fn find_node_mut<'a>(nodes: &'a mut NodePool, name: &str) -> Option<&'a mut Node> {
+    nodes.iter_mut().find(|node| node.name() == name)
+}
+
+impl TimelinePlayer {
+    /// Sets new timeline to play. The playback is stopped.
+    pub fn set_timeline(&mut self, timeline: Option<TimelineResource>) -> Option<TimelineResource> {
+        self.stop();
+        self.timeline.set_value_and_mark_modified(timeline)
+    }
+
+    /// Returns current timeline.
+    pub fn timeline(&self) -> Option<&TimelineResource> {
+        self.timeline.as_ref()
+    }
+
+    /// Defines whether the timeline should be played automatically on the first update of the node.
+    pub fn set_auto_play(&mut self, auto_play: bool) -> bool {
+        self.auto_play.set_value_and_mark_modified(auto_play)
+    }
+
+    /// Returns `true` if the timeline is played automatically.
+    pub fn is_auto_play(&self) -> bool {
+        *self.auto_play
+    }
+
+    /// Sets playback speed multiplier. Default is `1.0`.
+    pub fn set_speed(&mut self, speed: f32) -> f32 {
+        self.speed.set_value_and_mark_modified(speed.max(0.0))
+    }
+
+    /// Returns current playback speed multiplier.
+    pub fn speed(&self) -> f32 {
+        *self.speed
+    }
+
+    /// Starts playing the timeline from the beginning.
+    pub fn play(&mut self) {
+        self.rewind();
+        self.playing = true;
+    }
+
+    /// Pauses the playback, it could be resumed using [`Self::resume`].
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Resumes paused playback.
+    pub fn resume(&mut self) {
+        self.playing = true;
+    }
+
+    /// Stops the playback and rewinds the timeline to the beginning.
+    pub fn stop(&mut self) {
+        self.playing = false;
+        self.rewind();
+    }
+
+    /// Skips the rest of the timeline. See [`TimelinePlayer`] docs for more info about skipping. Does
+    /// nothing if the timeline is not playing.
+    pub fn skip(&mut self) {
+        if self.playing {
+            self.skip_requested = true;
+        }
+    }
+
+    /// Returns `true` if the timeline is playing.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Returns current playback time in seconds.
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Fetches the next script event emitted by the timeline (if any). See
+    /// [`TimelineAction::ScriptEvent`] for more info.
+    pub fn pop_script_event(&mut self) -> Option<String> {
+        self.script_events.pop_front()
+    }
+
+    fn rewind(&mut self) {
+        self.time = 0.0;
+        self.last_time = f32::NEG_INFINITY;
+        self.skip_requested = false;
+    }
+
+    fn perform_actions(&mut self, timeline: &Timeline, nodes: &mut NodePool, skipped: bool) {
+        let mut events = timeline
+            .events_in_range(self.last_time, self.time)
+            .collect::<Vec<_>>();
+        events.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        for event in events {
+            match &event.action {
+                TimelineAction::PlayAnimation {
+                    animation_player,
+                    animation,
+                } => {
+                    if skipped {
+                        continue;
+                    }
+                    if let Some(animation_player) = find_node_mut(nodes, animation_player)
+                        .and_then(|node| node.cast_mut::<AnimationPlayer>())
+                    {
+                        if let Some((_, animation)) = animation_player
+                            .animations_mut()
+                            .get_value_mut_silent()
+                            .find_by_name_mut(animation)
+                        {
+                            animation.rewind();
+                            animation.set_enabled(true);
+                        }
+                    }
+                }
+                TimelineAction::CameraCut { camera } => {
+                    for node in nodes.iter_mut() {
+                        let is_target = node.name() == camera.as_str();
+                        if let Some(node_camera) = node.cast_mut::<Camera>() {
+                            node_camera.set_enabled(is_target);
+                        }
+                    }
+                }
+                TimelineAction::PlaySound { sound } => {
+                    if skipped {
+                        continue;
+                    }
+                    if let Some(sound) =
+                        find_node_mut(nodes, sound).and_then(|node| node.cast_mut::<Sound>())
+                    {
+                        sound.play();
+                    }
+                }
+                TimelineAction::ScriptEvent { name } => {
+                    self.script_events.push_back(name.clone());
+                }
+            }
+        }
+    }
+
+    fn apply_property_tracks(&self, timeline: &Timeline, nodes: &mut NodePool) {
+        for track in timeline.property_tracks.iter() {
+            if let Some(value) = track.frames.fetch(self.time) {
+                if let Some(node) = find_node_mut(nodes, &track.node) {
+                    BoundValueCollection {
+                        values: vec![BoundValue {
+                            binding: track.binding.clone(),
+                            value,
+                        }],
+                    }
+                    .apply(node);
+                }
+            }
+        }
+    }
+}
+
+impl NodeTrait for TimelinePlayer {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        if *self.auto_play && !self.auto_play_started {
+            self.auto_play_started = true;
+            self.play();
+        }
+
+        if !self.playing {
+            return;
+        }
+
+        let Some(resource) = self.timeline.clone_inner() else {
+            return;
+        };
+        let mut state = resource.state();
+        let Some(timeline) = state.data() else {
+            return;
+        };
+
+        let skipped = std::mem::take(&mut self.skip_requested);
+        self.time = if skipped {
+            timeline.duration
+        } else {
+            (self.time + context.dt * *self.speed).min(timeline.duration)
+        };
+
+        self.perform_actions(timeline, context.nodes, skipped);
+        self.apply_property_tracks(timeline, context.nodes);
+
+        self.last_time = self.time;
+        if self.time >= timeline.duration {
+            self.playing = false;
+        }
+    }
+}
+
+/// Allows you to create timeline player nodes in declarative manner.
+pub struct TimelinePlayerBuilder {
+    base_builder: BaseBuilder,
+    timeline: Option<TimelineResource>,
+    auto_play: bool,
+    speed: f32,
+}
+
+impl TimelinePlayerBuilder {
+    /// Creates new builder instance.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            timeline: None,
+            auto_play: false,
+            speed: 1.0,
+        }
+    }
+
+    /// Sets desired timeline.
+    pub fn with_timeline(mut self, timeline: TimelineResource) -> Self {
+        self.timeline = Some(timeline);
+        self
+    }
+
+    /// Sets whether the timeline should be played automatically. See
+    /// [`TimelinePlayer::set_auto_play`] for more info.
+    pub fn with_auto_play(mut self, auto_play: bool) -> Self {
+        self.auto_play = auto_play;
+        self
+    }
+
+    /// Sets desired playback speed multiplier.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Creates an instance of [`TimelinePlayer`] node.
+    pub fn build_node(self) -> Node {
+        Node::new(TimelinePlayer {
+            base: self.base_builder.build_base(),
+            timeline: self.timeline.into(),
+            auto_play: self.auto_play.into(),
+            speed: self.speed.max(0.0).into(),
+            ..Default::default()
+        })
+    }
+
+    /// Creates an instance of [`TimelinePlayer`] node and adds it to the given scene graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
@@ -0,0 +1,724 @@
+//! Trail is a ribbon that follows the path of a node. See [`Trail`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Vector2, Vector3},
+        color::Color,
+        color_gradient::{ColorGradient, GradientPoint},
+        curve::{Curve, CurveKey, CurveKeyKind},
+        math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    material::{Material, MaterialResource},
+    renderer::{self, batch::RenderContext},
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        mesh::RenderPath,
+        node::{Node, NodeTrait, UpdateContext},
+        sprite::SpriteVertex,
+    },
+};
+use std::{
+    collections::VecDeque,
+    ops::{Deref, DerefMut},
+};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Defines how the ribbon of a trail is oriented in space.
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Default,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum TrailAlignment {
+    /// The ribbon always faces the camera. Suitable for projectile trails, magic effects, etc.
+    #[default]
+    View,
+    /// The ribbon is stretched along the side (local X) axis of the node at the moment when a point
+    /// of the trail was emitted. Suitable for sword swipes (the axis should go along the blade) and
+    /// tire marks (the axis should be parallel to the ground).
+    Local,
+}
+
+uuid_provider!(TrailAlignment = "b9d4e2a7-6c1f-4e38-8a5b-2f7c0d3e9a41");
+
+/// Defines how a texture is mapped onto the ribbon of a trail.
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Default,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum TrailTextureMode {
+    /// The texture is stretched along the entire ribbon.
+    #[default]
+    Stretch,
+    /// The texture is repeated every [`Trail::texture_tile_length`] meters. Texture coordinates are
+    /// bound to the traveled distance, so the texture does not slide when the trail moves (which
+    /// is important for tire marks, footprints, etc.). Make sure that the texture uses `Repeat`
+    /// wrap mode.
+    Tile,
+}
+
+uuid_provider!(TrailTextureMode = "4e1a7c93-2d5b-4f0e-b8a6-9c3d5e7f1b28");
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct TrailPoint {
+    position: Vector3<f32>,
+    side: Vector3<f32>,
+    age: f32,
+    distance: f32,
+    // Whether the point is connected with the previous point or not. The trail is interrupted
+    // when emission is stopped and then started again.
+    connected: bool,
+}
+
+/// Trail is a ribbon, that follows the world-space path of its node over time. It is useful for
+/// sword swipes, projectile trails, tire marks of vehicles and so on.
+///
+/// ## How it works
+///
+/// While emitting (see [`Trail::set_emitting`]), the trail adds a new point each time the node
+/// moves more than [`Trail::min_segment_length`] meters away from the last point. Every point
+/// lives for [`Trail::lifetime`] seconds, its width and color depend on its normalized age and
+/// are defined by [`Trail::width_over_lifetime`] and [`Trail::color_over_lifetime`] respectively.
+/// The ribbon is always connected to the current position of the node, so it looks smooth even
+/// with large segment lengths.
+///
+/// The points are stored in world space, which means that the trail stays in place when its node
+/// moves. Call [`Trail::clear`] when the node is teleported, otherwise the trail will connect the
+/// old and the new positions.
+///
+/// ## Rendering
+///
+/// Trails use the same vertex format as sprites and the standard sprite material by default (see
+/// [`Material::standard_sprite`]). Custom materials must use a shader, that is compatible with the
+/// standard sprite shader.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox::{
+/// #     core::pool::Handle,
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         graph::Graph,
+/// #         node::Node,
+/// #         trail::{TrailAlignment, TrailBuilder, TrailTextureMode},
+/// #     },
+/// # };
+/// fn create_tire_marks(graph: &mut Graph) -> Handle<Node> {
+///     TrailBuilder::new(BaseBuilder::new())
+///         .with_lifetime(10.0)
+///         .with_width(0.25)
+///         .with_alignment(TrailAlignment::Local)
+///         .with_texture_mode(TrailTextureMode::Tile)
+///         .with_texture_tile_length(0.5)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Debug, Visit, Reflect, Clone)]
+pub struct Trail {
+    base: Base,
+
+    material: InheritableVariable<MaterialResource>,
+
+    #[reflect(setter = "set_emitting")]
+    emitting: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_lifetime", min_value = 0.0, step = 0.1)]
+    lifetime: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_min_segment_length", min_value = 0.0, step = 0.01)]
+    min_segment_length: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_width", min_value = 0.0, step = 0.05)]
+    width: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_width_over_lifetime")]
+    width_over_lifetime: InheritableVariable<Curve>,
+
+    #[reflect(setter = "set_color_over_lifetime")]
+    color_over_lifetime: InheritableVariable<ColorGradient>,
+
+    #[reflect(setter = "set_alignment")]
+    alignment: InheritableVariable<TrailAlignment>,
+
+    #[reflect(setter = "set_texture_mode")]
+    texture_mode: InheritableVariable<TrailTextureMode>,
+
+    #[reflect(setter = "set_texture_tile_length", min_value = 0.0, step = 0.05)]
+    texture_tile_length: InheritableVariable<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    points: VecDeque<TrailPoint>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    was_emitting: bool,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    bounds: AxisAlignedBoundingBox,
+}
+
+impl Default for Trail {
+    fn default() -> Self {
+        TrailBuilder::new(BaseBuilder::new()).build_trail()
+    }
+}
+
+impl TypeUuidProvider for Trail {
+    fn type_uuid() -> Uuid {
+        uuid!("8f3c2b6e-1d7a-4c59-9e04-5a6b7d8c2f13")
+    }
+}
+
+impl Deref for Trail {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Trail {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl Trail {
+    /// Returns a reference to the current material used by the trail.
+    pub fn material(&self) -> &InheritableVariable<MaterialResource> {
+        &self.material
+    }
+
+    /// Returns a reference to the current material used by the trail.
+    pub fn material_mut(&mut self) -> &mut InheritableVariable<MaterialResource> {
+        &mut self.material
+    }
+
+    /// Enables or disables emission of new points. Existing points continue to fade out when the
+    /// emission is disabled. Enabling the emission again starts a new, disconnected, ribbon.
+    pub fn set_emitting(&mut self, emitting: bool) -> bool {
+        self.emitting.set_value_and_mark_modified(emitting)
+    }
+
+    /// Returns `true` if the trail emits new points, `false` - otherwise.
+    pub fn is_emitting(&self) -> bool {
+        *self.emitting
+    }
+
+    /// Sets the time (in seconds) during which every point of the trail lives.
+    pub fn set_lifetime(&mut self, lifetime: f32) -> f32 {
+        self.lifetime.set_value_and_mark_modified(lifetime.max(0.0))
+    }
+
+    /// Returns the time (in seconds) during which every point of the trail lives.
+    pub fn lifetime(&self) -> f32 {
+        *self.lifetime
+    }
+
+    /// Sets the minimal distance (in meters) between two adjacent points of the trail. Smaller
+    /// values give smoother ribbons at the cost of more vertices.
+    pub fn set_min_segment_length(&mut self, length: f32) -> f32 {
+        self.min_segment_length
+            .set_value_and_mark_modified(length.max(0.0))
+    }
+
+    /// Returns the minimal distance (in meters) between two adjacent points of the trail.
+    pub fn min_segment_length(&self) -> f32 {
+        *self.min_segment_length
+    }
+
+    /// Sets the width of the ribbon (in meters). The actual width of the ribbon at a point is the
+    /// width multiplied by the value of [`Self::width_over_lifetime`] curve.
+    pub fn set_width(&mut self, width: f32) -> f32 {
+        self.width.set_value_and_mark_modified(width)
+    }
+
+    /// Returns the width of the ribbon (in meters).
+    pub fn width(&self) -> f32 {
+        *self.width
+    }
+
+    /// Sets a curve, that defines the width multiplier of the ribbon depending on the normalized
+    /// (in `[0; 1]` range) age of a point. An empty curve means no change of the width.
+    pub fn set_width_over_lifetime(&mut self, curve: Curve) -> Curve {
+        self.width_over_lifetime.set_value_and_mark_modified(curve)
+    }
+
+    /// Returns a reference to the width-over-lifetime curve.
+    pub fn width_over_lifetime(&self) -> &Curve {
+        &self.width_over_lifetime
+    }
+
+    /// Sets a gradient, that defines the color of the ribbon depending on the normalized (in
+    /// `[0; 1]` range) age of a point.
+    pub fn set_color_over_lifetime(&mut self, gradient: ColorGradient) -> ColorGradient {
+        self.color_over_lifetime
+            .set_value_and_mark_modified(gradient)
+    }
+
+    /// Returns a reference to the color-over-lifetime gradient.
+    pub fn color_over_lifetime(&self) -> &ColorGradient {
+        &self.color_over_lifetime
+    }
+
+    /// Sets new alignment of the ribbon. See [`TrailAlignment`] docs for more info.
+    pub fn set_alignment(&mut self, alignment: TrailAlignment) -> TrailAlignment {
+        self.alignment.set_value_and_mark_modified(alignment)
+    }
+
+    /// Returns current alignment of the ribbon.
+    pub fn alignment(&self) -> TrailAlignment {
+        *self.alignment
+    }
+
+    /// Sets new texture mapping mode. See [`TrailTextureMode`] docs for more info.
+    pub fn set_texture_mode(&mut self, mode: TrailTextureMode) -> TrailTextureMode {
+        self.texture_mode.set_value_and_mark_modified(mode)
+    }
+
+    /// Returns current texture mapping mode.
+    pub fn texture_mode(&self) -> TrailTextureMode {
+        *self.texture_mode
+    }
+
+    /// Sets the length (in meters) of the ribbon, that is covered by a single repetition of the
+    /// texture. Used only with [`TrailTextureMode::Tile`].
+    pub fn set_texture_tile_length(&mut self, length: f32) -> f32 {
+        self.texture_tile_length
+            .set_value_and_mark_modified(length.max(0.0))
+    }
+
+    /// Returns the length (in meters) of the ribbon, that is covered by a single repetition of
+    /// the texture.
+    pub fn texture_tile_length(&self) -> f32 {
+        *self.texture_tile_length
+    }
+
+    /// Removes every point of the trail. Use it when the node is teleported.
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.was_emitting = false;
+    }
+
+    /// Returns the amount of points of the trail, excluding the point, that is attached to the
+    /// node.
+    pub fn point_count(&self) -> usize {
+        self.points.len()
+    }
+
+    fn head_point(&self) -> Option<TrailPoint> {
+        if !*self.emitting {
+            return None;
+        }
+
+        let last = self.points.back()?;
+        let position = self.global_position();
+        let distance = (position - last.position).norm();
+        if distance <= f32::EPSILON {
+            return None;
+        }
+
+        Some(TrailPoint {
+            position,
+            side: self.side_axis(),
+            age: 0.0,
+            distance: last.distance + distance,
+            connected: true,
+        })
+    }
+
+    fn side_axis(&self) -> Vector3<f32> {
+        self.side_vector()
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::x)
+    }
+
+    fn half_width_at(&self, t: f32) -> f32 {
+        let k = if self.width_over_lifetime.is_empty() {
+            1.0
+        } else {
+            self.width_over_lifetime.value_at(t)
+        };
+        0.5 * *self.width * k
+    }
+
+    fn normalized_age(&self, point: &TrailPoint) -> f32 {
+        if *self.lifetime > 0.0 {
+            (point.age / *self.lifetime).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    fn tick(&mut self, dt: f32) {
+        for point in self.points.iter_mut() {
+            point.age += dt;
+        }
+
+        let lifetime = *self.lifetime;
+        while self.points.front().is_some_and(|p| p.age >= lifetime) {
+            self.points.pop_front();
+        }
+
+        let emitting = *self.emitting && self.is_globally_enabled();
+        if emitting {
+            let position = self.global_position();
+            let side = self.side_axis();
+            let min_segment_length = *self.min_segment_length;
+            let connected = self.was_emitting && !self.points.is_empty();
+
+            let distance = match self.points.back() {
+                Some(last) if connected => {
+                    let segment = (position - last.position).norm();
+                    if segment < min_segment_length.max(f32::EPSILON) {
+                        None
+                    } else {
+                        Some(last.distance + segment)
+                    }
+                }
+                Some(last) => Some(last.distance),
+                None => Some(0.0),
+            };
+
+            if let Some(distance) = distance {
+                self.points.push_back(TrailPoint {
+                    position,
+                    side,
+                    age: 0.0,
+                    distance,
+                    connected,
+                });
+            }
+        }
+        self.was_emitting = emitting;
+
+        let max_half_width = self
+            .width_over_lifetime
+            .keys()
+            .iter()
+            .map(|k| k.value)
+            .fold(1.0f32, f32::max)
+            * 0.5
+            * self.width.abs();
+        let mut bounds = AxisAlignedBoundingBox::from_point(self.global_position());
+        for point in self.points.iter() {
+            bounds.add_point(point.position);
+        }
+        bounds.inflate(Vector3::repeat(2.0 * max_half_width));
+        self.bounds = bounds;
+    }
+}
+
+impl NodeTrait for Trail {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::from_radius(0.5 * *self.width)
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.bounds
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        self.tick(context.dt);
+    }
+
+    fn collect_render_data(&self, ctx: &mut RenderContext) {
+        if !self.global_visibility()
+            || !self.is_globally_enabled()
+            || !ctx.frustum.is_intersects_aabb(&self.world_bounding_box())
+        {
+            return;
+        }
+
+        if renderer::is_shadow_pass(ctx.render_pass_name) {
+            return;
+        }
+
+        let points = self
+            .points
+            .iter()
+            .cloned()
+            .chain(self.head_point())
+            .collect::<Vec<_>>();
+        if points.len() < 2 {
+            return;
+        }
+
+        let total_distance = points.last().unwrap().distance - points[0].distance;
+        let mut vertices = Vec::with_capacity(points.len() * 2);
+        let mut triangles = Vec::with_capacity(points.len() * 2);
+
+        for (i, point) in points.iter().enumerate() {
+            let prev = if point.connected && i > 0 {
+                &points[i - 1]
+            } else {
+                point
+            };
+            let next = match points.get(i + 1) {
+                Some(next) if next.connected => next,
+                _ => point,
+            };
+
+            let side = match *self.alignment {
+                TrailAlignment::View => {
+                    let tangent = next.position - prev.position;
+                    let to_observer = *ctx.observer_position - point.position;
+                    tangent
+                        .cross(&to_observer)
+                        .try_normalize(f32::EPSILON)
+                        .unwrap_or(point.side)
+                }
+                TrailAlignment::Local => point.side,
+            };
+
+            let t = self.normalized_age(point);
+            let offset = side.scale(self.half_width_at(t));
+            let color = self.color_over_lifetime.get_color(t);
+            let u = match *self.texture_mode {
+                TrailTextureMode::Stretch => {
+                    if total_distance > f32::EPSILON {
+                        (points.last().unwrap().distance - point.distance) / total_distance
+                    } else {
+                        0.0
+                    }
+                }
+                TrailTextureMode::Tile => {
+                    point.distance / self.texture_tile_length.max(f32::EPSILON)
+                }
+            };
+
+            let make_vertex = |position: Vector3<f32>, v: f32| SpriteVertex {
+                position,
+                tex_coord: Vector2::new(u, v),
+                params: Vector2::default(),
+                color,
+                corner: Vector2::default(),
+            };
+            vertices.push(make_vertex(point.position + offset, 0.0));
+            vertices.push(make_vertex(point.position - offset, 1.0));
+
+            if next.connected && i + 1 < points.len() {
+                let a = (i * 2) as u32;
+                triangles.push(TriangleDefinition([a, a + 1, a + 2]));
+                triangles.push(TriangleDefinition([a + 1, a + 3, a + 2]));
+            }
+        }
+
+        ctx.storage.push_triangles(
+            vertices.into_iter(),
+            triangles.into_iter(),
+            &self.material,
+            RenderPath::Forward,
+            0,
+            0,
+            false,
+            self.self_handle,
+        )
+    }
+}
+
+/// Trail builder allows you to construct trails in declarative manner.
+/// This is typical implementation of Builder pattern.
+pub struct TrailBuilder {
+    base_builder: BaseBuilder,
+    material: MaterialResource,
+    emitting: bool,
+    lifetime: f32,
+    min_segment_length: f32,
+    width: f32,
+    width_over_lifetime: Curve,
+    color_over_lifetime: ColorGradient,
+    alignment: TrailAlignment,
+    texture_mode: TrailTextureMode,
+    texture_tile_length: f32,
+}
+
+impl TrailBuilder {
+    /// Creates new builder with default parameters: one second lifetime, 0.2 meters width, that
+    /// fades to zero and white color, that fades to fully transparent.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            material: MaterialResource::new_ok(Default::default(), Material::standard_sprite()),
+            emitting: true,
+            lifetime: 1.0,
+            min_segment_length: 0.1,
+            width: 0.2,
+            width_over_lifetime: Curve::from(vec![
+                CurveKey::new(0.0, 1.0, CurveKeyKind::Linear),
+                CurveKey::new(1.0, 0.0, CurveKeyKind::Linear),
+            ]),
+            color_over_lifetime: {
+                let mut gradient = ColorGradient::new();
+                gradient.add_point(GradientPoint::new(0.0, Color::WHITE));
+                gradient.add_point(GradientPoint::new(1.0, Color::from_rgba(255, 255, 255, 0)));
+                gradient
+            },
+            alignment: Default::default(),
+            texture_mode: Default::default(),
+            texture_tile_length: 1.0,
+        }
+    }
+
+    /// Sets the desired material of the trail.
+    pub fn with_material(mut self, material: MaterialResource) -> Self {
+        self.material = material;
+        self
+    }
+
+    /// Sets whether the trail should emit new points or not.
+    pub fn with_emitting(mut self, emitting: bool) -> Self {
+        self.emitting = emitting;
+        self
+    }
+
+    /// Sets desired lifetime of the points. See [`Trail::set_lifetime`] for more info.
+    pub fn with_lifetime(mut self, lifetime: f32) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// Sets desired minimal segment length. See [`Trail::set_min_segment_length`] for more info.
+    pub fn with_min_segment_length(mut self, length: f32) -> Self {
+        self.min_segment_length = length;
+        self
+    }
+
+    /// Sets desired width. See [`Trail::set_width`] for more info.
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets desired width-over-lifetime curve. See [`Trail::set_width_over_lifetime`] for more info.
+    pub fn with_width_over_lifetime(mut self, curve: Curve) -> Self {
+        self.width_over_lifetime = curve;
+        self
+    }
+
+    /// Sets desired color-over-lifetime gradient. See [`Trail::set_color_over_lifetime`] for more
+    /// info.
+    pub fn with_color_over_lifetime(mut self, gradient: ColorGradient) -> Self {
+        self.color_over_lifetime = gradient;
+        self
+    }
+
+    /// Sets desired alignment. See [`TrailAlignment`] docs for more info.
+    pub fn with_alignment(mut self, alignment: TrailAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Sets desired texture mapping mode. See [`TrailTextureMode`] docs for more info.
+    pub fn with_texture_mode(mut self, mode: TrailTextureMode) -> Self {
+        self.texture_mode = mode;
+        self
+    }
+
+    /// Sets desired texture tile length. See [`Trail::set_texture_tile_length`] for more info.
+    pub fn with_texture_tile_length(mut self, length: f32) -> Self {
+        self.texture_tile_length = length;
+        self
+    }
+
+    fn build_trail(self) -> Trail {
+        Trail {
+            base: self.base_builder.build_base(),
+            material: self.material.into(),
+            emitting: self.emitting.into(),
+            lifetime: self.lifetime.into(),
+            min_segment_length: self.min_segment_length.into(),
+            width: self.width.into(),
+            width_over_lifetime: self.width_over_lifetime.into(),
+            color_over_lifetime: self.color_over_lifetime.into(),
+            alignment: self.alignment.into(),
+            texture_mode: self.texture_mode.into(),
+            texture_tile_length: self.texture_tile_length.into(),
+            points: Default::default(),
+            was_emitting: false,
+            bounds: Default::default(),
+        }
+    }
+
+    /// Creates new trail instance.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_trail())
+    }
+
+    /// Creates new trail instance and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scene::{base::BaseBuilder, trail::TrailBuilder};
+
+    #[test]
+    fn test_trail_points() {
+        let mut trail = TrailBuilder::new(BaseBuilder::new())
+            .with_lifetime(1.0)
+            .with_min_segment_length(0.5)
+            .build_trail();
+
+        trail.tick(0.1);
+        assert_eq!(trail.point_count(), 1);
+
+        // The node does not move, no new points.
+        trail.tick(0.1);
+        assert_eq!(trail.point_count(), 1);
+
+        // Points expire.
+        trail.tick(1.0);
+        assert_eq!(trail.point_count(), 1);
+        assert_eq!(trail.points[0].age, 0.0);
+
+        // Restarted emission must not be connected to the previous points.
+        trail.set_emitting(false);
+        trail.tick(0.1);
+        trail.set_emitting(true);
+        trail.tick(0.1);
+        assert_eq!(trail.point_count(), 2);
+        assert!(!trail.points[1].connected);
+
+        trail.clear();
+        assert_eq!(trail.point_count(), 0);
+    }
+}
@@ -0,0 +1,733 @@
+//! Water is an animated surface of a water body. See [`Water`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, UnitQuaternion, Vector2, Vector3, Vector4},
+        math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
+        pool::Handle,
+        reflect::prelude::*,
+        sstorage::ImmutableString,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    material::{Material, MaterialResource, PropertyValue},
+    renderer::{
+        self,
+        batch::{PersistentIdentifier, RenderContext, SurfaceInstanceData},
+        framework::geometry_buffer::ElementRange,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        camera::Camera,
+        graph::Graph,
+        mesh::{
+            buffer::{TriangleBuffer, VertexBuffer},
+            surface::{SurfaceData, SurfaceSharedData},
+            vertex::StaticVertex,
+            RenderPath,
+        },
+        node::{Node, NodeTrait, UpdateContext},
+    },
+};
+use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Maximum amount of Gerstner waves, that could be used by a water surface at once. Any waves
+/// beyond the limit are ignored.
+pub const MAX_WAVES: usize = 4;
+
+const GRAVITY: f32 = 9.81;
+
+/// Defines how the surface of water is animated.
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Default,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+pub enum WaterWaveMode {
+    /// The surface is flat, waves are imitated only by scrolling normal maps. It is the cheapest
+    /// mode, suitable for lakes, pools, puddles, etc.
+    #[default]
+    NormalScroll,
+    /// The surface is displaced by a sum of Gerstner waves (see [`Water::waves`]) and then detailed
+    /// by scrolling normal maps. Suitable for seas and oceans.
+    Gerstner,
+}
+
+uuid_provider!(WaterWaveMode = "6d1f3b8a-4c2e-4f97-a0d5-8e7b9c2a1f64");
+
+/// A single Gerstner (trochoidal) wave. Horizontal positions of the surface points are moved
+/// towards crests, which gives sharp crests and wide troughs, unlike simple sine waves.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct GerstnerWave {
+    /// Direction of the wave in the local XZ plane of the water node. It does not need to be
+    /// normalized.
+    pub direction: Vector2<f32>,
+    /// Steepness of the wave in `[0; 1]` range. Amplitude of the wave is defined by its steepness
+    /// and wavelength. Sum of steepness values of all waves should not exceed 1, otherwise the
+    /// surface will have loops at crests.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01)]
+    pub steepness: f32,
+    /// Distance (in meters) between two adjacent crests of the wave. Speed of the wave is defined
+    /// by its wavelength (longer waves are faster).
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub wavelength: f32,
+}
+
+uuid_provider!(GerstnerWave = "a3e9c7d1-5b2f-4e86-9c04-7f1d6b8e2a35");
+
+impl Default for GerstnerWave {
+    fn default() -> Self {
+        Self {
+            direction: Vector2::new(1.0, 0.0),
+            steepness: 0.25,
+            wavelength: 10.0,
+        }
+    }
+}
+
+impl GerstnerWave {
+    /// Creates new wave with the given parameters.
+    pub fn new(direction: Vector2<f32>, steepness: f32, wavelength: f32) -> Self {
+        Self {
+            direction,
+            steepness,
+            wavelength,
+        }
+    }
+
+    fn normalized_direction(&self) -> Vector2<f32> {
+        self.direction
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector2::x)
+    }
+
+    fn wave_number(&self) -> f32 {
+        2.0 * std::f32::consts::PI / self.wavelength.max(0.0001)
+    }
+
+    /// Returns amplitude (in meters) of the wave.
+    pub fn amplitude(&self) -> f32 {
+        self.steepness / self.wave_number()
+    }
+
+    // Must match the implementation in the water shader.
+    fn displacement(&self, position: Vector2<f32>, time: f32) -> Vector3<f32> {
+        let d = self.normalized_direction();
+        let k = self.wave_number();
+        let c = (GRAVITY / k).sqrt();
+        let f = k * (d.dot(&position) - c * time);
+        let a = self.steepness / k;
+        Vector3::new(d.x * a * f.cos(), a * f.sin(), d.y * a * f.cos())
+    }
+}
+
+/// Water is an animated surface of a water body (a lake, a river, a sea, etc.). The surface is a
+/// flat grid in the local XZ plane of the node, centered at the node's position.
+///
+/// ## Waves
+///
+/// The surface could be animated in two ways (see [`WaterWaveMode`]): by scrolling normal maps
+/// only, or by displacing the grid with a sum of up to [`MAX_WAVES`] Gerstner waves. Use
+/// [`Water::height_at`] to get the height of the displaced surface at a point - it uses exactly the
+/// same waves as the GPU, so objects placed using it do not float above or sink below the visible
+/// surface. Make sure that [`Water::resolution`] is high enough to represent the shortest wave.
+///
+/// ## Rendering
+///
+/// Water is rendered by the forward renderer using the standard water material by default (see
+/// [`Material::standard_water`]). The material refracts the opaque part of the frame behind the
+/// surface and fades it into `deepColor` depending on the depth of the water.
+///
+/// Planar reflections require a separate camera, which renders the scene into a texture (see
+/// [`Camera::set_render_target`]). Assign it using [`Water::set_reflection_camera`] and the water
+/// will mirror the active camera of the scene relative to the surface plane every frame. The
+/// reflection camera should use the same aspect ratio as the main camera and
+/// [`crate::scene::camera::RenderTargetFormat::Hdr`] format. Objects under the surface are not
+/// clipped from the reflection, keep it in mind when placing the water. Without a reflection
+/// camera the surface reflects `skyColor` of the material.
+///
+/// ## Buoyancy
+///
+/// ```rust
+/// # use fyrox::{
+/// #     core::{algebra::Vector3, pool::Handle},
+/// #     scene::{graph::Graph, node::Node, rigidbody::RigidBody, water::Water},
+/// # };
+/// fn apply_buoyancy(graph: &mut Graph, water: Handle<Node>, body: Handle<Node>) {
+///     let position = graph[body].global_position();
+///     let Some(depth) = graph[water].cast::<Water>().map(|w| w.depth_at(position)) else {
+///         return;
+///     };
+///     if let Some(body) = graph[body].cast_mut::<RigidBody>() {
+///         if depth > 0.0 {
+///             // Push the body up proportionally to its submersion and damp its velocity.
+///             let force = Vector3::new(0.0, 9.81 * body.mass() * (1.0 + depth.min(1.0)), 0.0);
+///             body.apply_force(force - body.lin_vel().scale(body.mass()));
+///             body.wake_up();
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Visit, Reflect, Clone)]
+pub struct Water {
+    base: Base,
+
+    material: InheritableVariable<MaterialResource>,
+
+    #[reflect(setter = "set_size")]
+    size: InheritableVariable<Vector2<f32>>,
+
+    #[reflect(setter = "set_resolution", min_value = 1.0)]
+    resolution: InheritableVariable<u32>,
+
+    #[reflect(setter = "set_wave_mode")]
+    wave_mode: InheritableVariable<WaterWaveMode>,
+
+    #[reflect(setter = "set_waves")]
+    waves: InheritableVariable<Vec<GerstnerWave>>,
+
+    #[reflect(setter = "set_reflection_camera")]
+    reflection_camera: InheritableVariable<Handle<Node>>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    time: f32,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    geometry: Option<SurfaceSharedData>,
+}
+
+impl Default for Water {
+    fn default() -> Self {
+        WaterBuilder::new(BaseBuilder::new()).build_water()
+    }
+}
+
+impl TypeUuidProvider for Water {
+    fn type_uuid() -> Uuid {
+        uuid!("2c7e5a91-8f3d-4b16-b0e4-9d6a3c5f7e28")
+    }
+}
+
+impl Deref for Water {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Water {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl Water {
+    /// Returns a reference to the current material used by the water.
+    pub fn material(&self) -> &InheritableVariable<MaterialResource> {
+        &self.material
+    }
+
+    /// Returns a reference to the current material used by the water.
+    pub fn material_mut(&mut self) -> &mut InheritableVariable<MaterialResource> {
+        &mut self.material
+    }
+
+    /// Sets new size (in meters) of the surface along local X and Z axes.
+    pub fn set_size(&mut self, size: Vector2<f32>) -> Vector2<f32> {
+        self.geometry = None;
+        self.size.set_value_and_mark_modified(size)
+    }
+
+    /// Returns current size of the surface.
+    pub fn size(&self) -> Vector2<f32> {
+        *self.size
+    }
+
+    /// Sets the amount of grid cells along each side of the surface. Higher values give smoother
+    /// waves at the cost of more vertices. It does not matter for [`WaterWaveMode::NormalScroll`].
+    pub fn set_resolution(&mut self, resolution: u32) -> u32 {
+        self.geometry = None;
+        self.resolution
+            .set_value_and_mark_modified(resolution.max(1))
+    }
+
+    /// Returns the amount of grid cells along each side of the surface.
+    pub fn resolution(&self) -> u32 {
+        *self.resolution
+    }
+
+    /// Sets new wave mode. See [`WaterWaveMode`] docs for more info.
+    pub fn set_wave_mode(&mut self, mode: WaterWaveMode) -> WaterWaveMode {
+        self.wave_mode.set_value_and_mark_modified(mode)
+    }
+
+    /// Returns current wave mode.
+    pub fn wave_mode(&self) -> WaterWaveMode {
+        *self.wave_mode
+    }
+
+    /// Sets new set of Gerstner waves. Only first [`MAX_WAVES`] waves are used and only in
+    /// [`WaterWaveMode::Gerstner`] mode.
+    pub fn set_waves(&mut self, waves: Vec<GerstnerWave>) -> Vec<GerstnerWave> {
+        self.waves.set_value_and_mark_modified(waves)
+    }
+
+    /// Returns current set of Gerstner waves.
+    pub fn waves(&self) -> &[GerstnerWave] {
+        &self.waves
+    }
+
+    /// Sets a camera, that will be used to render planar reflections. The camera must have a
+    /// render target. Its position and orientation are overwritten every frame.
+    pub fn set_reflection_camera(&mut self, camera: Handle<Node>) -> Handle<Node> {
+        self.reflection_camera.set_value_and_mark_modified(camera)
+    }
+
+    /// Returns current reflection camera.
+    pub fn reflection_camera(&self) -> Handle<Node> {
+        *self.reflection_camera
+    }
+
+    /// Returns the time (in seconds) of the wave animation.
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Sets the time (in seconds) of the wave animation. Could be used to synchronize the waves
+    /// over the network.
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    fn active_waves(&self) -> &[GerstnerWave] {
+        match *self.wave_mode {
+            WaterWaveMode::NormalScroll => &[],
+            WaterWaveMode::Gerstner => &self.waves[..self.waves.len().min(MAX_WAVES)],
+        }
+    }
+
+    fn local_displacement(&self, position: Vector2<f32>) -> Vector3<f32> {
+        self.active_waves()
+            .iter()
+            .map(|wave| wave.displacement(position, self.time))
+            .sum()
+    }
+
+    /// Returns world-space height of the surface at the given world-space point. The vertical
+    /// coordinate of the point is ignored (only its projection on the surface plane matters).
+    pub fn height_at(&self, point: Vector3<f32>) -> f32 {
+        let global_transform = self.global_transform();
+        let local = global_transform
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity)
+            .transform_point(&Point3::from(point));
+        let target = Vector2::new(local.x, local.z);
+
+        // Gerstner waves move points horizontally, so the point of the undisplaced grid, that
+        // ends up at the target, is found iteratively.
+        let mut position = target;
+        for _ in 0..4 {
+            let displacement = self.local_displacement(position);
+            position = target - Vector2::new(displacement.x, displacement.z);
+        }
+        let height = self.local_displacement(position).y;
+
+        global_transform
+            .transform_point(&Point3::new(target.x, height, target.y))
+            .y
+    }
+
+    /// Returns the depth (in meters) of the given world-space point under the surface. Negative
+    /// values mean that the point is above the surface.
+    pub fn depth_at(&self, point: Vector3<f32>) -> f32 {
+        self.height_at(point) - point.y
+    }
+
+    fn make_geometry(&self) -> SurfaceSharedData {
+        let resolution = *self.resolution;
+        let vertex_count = resolution + 1;
+        let mut vertices = Vec::with_capacity((vertex_count * vertex_count) as usize);
+        for iz in 0..vertex_count {
+            let kz = iz as f32 / resolution as f32;
+            for ix in 0..vertex_count {
+                let kx = ix as f32 / resolution as f32;
+                vertices.push(StaticVertex {
+                    position: Vector3::new((kx - 0.5) * self.size.x, 0.0, (kz - 0.5) * self.size.y),
+                    tex_coord: Vector2::new(kx, kz),
+                    normal: Vector3::y(),
+                    tangent: Vector4::new(1.0, 0.0, 0.0, -1.0),
+                });
+            }
+        }
+
+        let mut triangles = Vec::with_capacity((resolution * resolution * 2) as usize);
+        for iz in 0..resolution {
+            for ix in 0..resolution {
+                let i0 = iz * vertex_count + ix;
+                let i1 = (iz + 1) * vertex_count + ix;
+                let i2 = i1 + 1;
+                let i3 = i0 + 1;
+                triangles.push(TriangleDefinition([i0, i1, i2]));
+                triangles.push(TriangleDefinition([i2, i3, i0]));
+            }
+        }
+
+        SurfaceSharedData::new(SurfaceData::new(
+            VertexBuffer::new(vertices.len(), vertices).unwrap(),
+            TriangleBuffer::new(triangles),
+            false,
+        ))
+    }
+
+    fn update_reflection_camera(&self, context: &mut UpdateContext) {
+        let reflection_camera = *self.reflection_camera;
+
+        let Some(parent_inv_transform) = context
+            .nodes
+            .try_borrow(reflection_camera)
+            .and_then(|camera| camera.cast::<Camera>())
+            .map(|camera| {
+                context
+                    .nodes
+                    .try_borrow(camera.parent())
+                    .and_then(|parent| parent.global_transform().try_inverse())
+                    .unwrap_or_else(Matrix4::identity)
+            })
+        else {
+            return;
+        };
+
+        let Some((position, look, up, projection)) = context
+            .nodes
+            .iter()
+            .filter_map(|node| node.cast::<Camera>())
+            .find(|camera| camera.is_globally_enabled() && camera.render_target().is_none())
+            .map(|camera| {
+                (
+                    camera.global_position(),
+                    camera.look_vector(),
+                    camera.up_vector(),
+                    camera.projection().clone(),
+                )
+            })
+        else {
+            return;
+        };
+
+        let origin = self.global_position();
+        let normal = self
+            .up_vector()
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::y);
+        let reflect = |v: Vector3<f32>| v - normal.scale(2.0 * v.dot(&normal));
+
+        let mirrored_position = origin + reflect(position - origin);
+        let mirrored_rotation = UnitQuaternion::face_towards(&reflect(look), &reflect(up));
+        let global_transform =
+            Matrix4::new_translation(&mirrored_position) * mirrored_rotation.to_homogeneous();
+        let local_transform = parent_inv_transform * global_transform;
+
+        let Some(camera) = context
+            .nodes
+            .try_borrow_mut(reflection_camera)
+            .and_then(|camera| camera.cast_mut::<Camera>())
+        else {
+            return;
+        };
+
+        camera
+            .local_transform_mut()
+            .set_position(Vector3::new(
+                local_transform[12],
+                local_transform[13],
+                local_transform[14],
+            ))
+            .set_rotation(UnitQuaternion::from_matrix(
+                &local_transform.fixed_view::<3, 3>(0, 0).into_owned(),
+            ));
+        // Global transform is updated right away, because it is needed by the renderer in this
+        // frame, but it will be recalculated by the graph only at the beginning of the next frame.
+        camera.global_transform.set(global_transform);
+        if camera.projection() != &projection {
+            camera.set_projection(projection);
+        }
+        camera.calculate_matrices(context.frame_size);
+    }
+
+    fn reflection_texture(&self, ctx: &RenderContext) -> Option<PropertyValue> {
+        let camera = ctx
+            .graph
+            .try_get(*self.reflection_camera)
+            .and_then(|camera| camera.cast::<Camera>())?;
+        Some(PropertyValue::Sampler {
+            value: Some(camera.render_target()?.clone()),
+            fallback: Default::default(),
+        })
+    }
+
+    fn is_rendered_by_reflection_camera(&self, ctx: &RenderContext) -> bool {
+        ctx.graph
+            .try_get(*self.reflection_camera)
+            .is_some_and(|camera| {
+                camera
+                    .global_position()
+                    .metric_distance(ctx.observer_position)
+                    <= f32::EPSILON
+            })
+    }
+}
+
+impl NodeTrait for Water {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let amplitude = self
+            .active_waves()
+            .iter()
+            .map(|wave| wave.amplitude())
+            .sum::<f32>();
+        let half_size = Vector3::new(0.5 * self.size.x, 0.0, 0.5 * self.size.y);
+        let extent = Vector3::repeat(amplitude);
+        AxisAlignedBoundingBox::from_min_max(-half_size - extent, half_size + extent)
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        self.time += context.dt;
+
+        if self.geometry.is_none() {
+            self.geometry = Some(self.make_geometry());
+        }
+
+        self.update_reflection_camera(context);
+    }
+
+    fn collect_render_data(&self, ctx: &mut RenderContext) {
+        if !self.global_visibility()
+            || !self.is_globally_enabled()
+            || !ctx.frustum.is_intersects_aabb(&self.world_bounding_box())
+        {
+            return;
+        }
+
+        // Water does not cast shadows and it must not be rendered into its own reflection.
+        if renderer::is_shadow_pass(ctx.render_pass_name)
+            || self.is_rendered_by_reflection_camera(ctx)
+        {
+            return;
+        }
+
+        let Some(geometry) = self.geometry.as_ref() else {
+            return;
+        };
+
+        let waves = self
+            .active_waves()
+            .iter()
+            .map(|wave| {
+                let direction = wave.normalized_direction();
+                Vector4::new(direction.x, direction.y, wave.steepness, wave.wavelength)
+            })
+            .collect::<Vec<_>>();
+
+        let mut property_overrides = vec![
+            (
+                ImmutableString::new("waterTime"),
+                PropertyValue::Float(self.time),
+            ),
+            (
+                ImmutableString::new("waterWaveCount"),
+                PropertyValue::Int(waves.len() as i32),
+            ),
+            (
+                ImmutableString::new("waterWaves"),
+                PropertyValue::Vector4Array(waves),
+            ),
+        ];
+        let reflection_texture = self.reflection_texture(ctx);
+        property_overrides.push((
+            ImmutableString::new("waterUseReflection"),
+            PropertyValue::Bool(reflection_texture.is_some()),
+        ));
+        if let Some(reflection_texture) = reflection_texture {
+            property_overrides.push((
+                ImmutableString::new("waterReflectionTexture"),
+                reflection_texture,
+            ));
+        }
+
+        ctx.storage.push(
+            geometry,
+            &self.material,
+            RenderPath::Forward,
+            0,
+            0,
+            SurfaceInstanceData {
+                world_transform: self.global_transform(),
+                bone_matrices: Default::default(),
+                depth_offset: 0.0,
+                blend_shapes_weights: Default::default(),
+                element_range: ElementRange::Full,
+                persistent_identifier: PersistentIdentifier::new_combined(
+                    geometry,
+                    self.self_handle,
+                    0,
+                ),
+                node_handle: self.self_handle,
+                allow_instancing: false,
+                use_dual_quaternion_skinning: false,
+                property_overrides: Some(property_overrides.into()),
+            },
+        )
+    }
+}
+
+/// Water builder allows you to construct water surfaces in declarative manner.
+/// This is typical implementation of Builder pattern.
+pub struct WaterBuilder {
+    base_builder: BaseBuilder,
+    material: MaterialResource,
+    size: Vector2<f32>,
+    resolution: u32,
+    wave_mode: WaterWaveMode,
+    waves: Vec<GerstnerWave>,
+    reflection_camera: Handle<Node>,
+}
+
+impl WaterBuilder {
+    /// Creates new builder with default parameters: 100x100 meters surface with 128x128 grid,
+    /// normal map scrolling and a set of three waves for Gerstner mode.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            material: MaterialResource::new_ok(Default::default(), Material::standard_water()),
+            size: Vector2::new(100.0, 100.0),
+            resolution: 128,
+            wave_mode: Default::default(),
+            waves: vec![
+                GerstnerWave::new(Vector2::new(1.0, 0.0), 0.2, 20.0),
+                GerstnerWave::new(Vector2::new(0.7, 0.7), 0.15, 11.0),
+                GerstnerWave::new(Vector2::new(0.2, -1.0), 0.1, 6.0),
+            ],
+            reflection_camera: Default::default(),
+        }
+    }
+
+    /// Sets the desired material of the water.
+    pub fn with_material(mut self, material: MaterialResource) -> Self {
+        self.material = material;
+        self
+    }
+
+    /// Sets desired size of the surface. See [`Water::set_size`] for more info.
+    pub fn with_size(mut self, size: Vector2<f32>) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets desired resolution of the grid. See [`Water::set_resolution`] for more info.
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Sets desired wave mode. See [`WaterWaveMode`] docs for more info.
+    pub fn with_wave_mode(mut self, mode: WaterWaveMode) -> Self {
+        self.wave_mode = mode;
+        self
+    }
+
+    /// Sets desired set of waves. See [`Water::set_waves`] for more info.
+    pub fn with_waves(mut self, waves: Vec<GerstnerWave>) -> Self {
+        self.waves = waves;
+        self
+    }
+
+    /// Sets desired reflection camera. See [`Water::set_reflection_camera`] for more info.
+    pub fn with_reflection_camera(mut self, camera: Handle<Node>) -> Self {
+        self.reflection_camera = camera;
+        self
+    }
+
+    fn build_water(self) -> Water {
+        let mut water = Water {
+            base: self.base_builder.build_base(),
+            material: self.material.into(),
+            size: self.size.into(),
+            resolution: self.resolution.max(1).into(),
+            wave_mode: self.wave_mode.into(),
+            waves: self.waves.into(),
+            reflection_camera: self.reflection_camera.into(),
+            time: 0.0,
+            geometry: None,
+        };
+        water.geometry = Some(water.make_geometry());
+        water
+    }
+
+    /// Creates new water instance.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_water())
+    }
+
+    /// Creates new water instance and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            water::{GerstnerWave, WaterBuilder, WaterWaveMode},
+        },
+    };
+
+    #[test]
+    fn test_water_height() {
+        let mut water = WaterBuilder::new(BaseBuilder::new())
+            .with_waves(vec![GerstnerWave::new(Vector2::new(1.0, 0.0), 0.5, 8.0)])
+            .build_water();
+
+        // Flat surface.
+        assert_eq!(water.height_at(Vector3::new(1.0, 5.0, 2.0)), 0.0);
+        assert_eq!(water.depth_at(Vector3::new(1.0, -2.0, 2.0)), 2.0);
+
+        water.set_wave_mode(WaterWaveMode::Gerstner);
+        let amplitude = water.waves()[0].amplitude();
+        for i in 0..32 {
+            water.set_time(i as f32 * 0.1);
+            let point = Vector3::new(i as f32 * 0.37, 0.0, 0.0);
+            let height = water.height_at(point);
+            assert!(height.abs() <= amplitude + f32::EPSILON);
+            assert_eq!(water.depth_at(point), height);
+        }
+    }
+}
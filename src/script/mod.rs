@@ -13,7 +13,9 @@ use crate::{
         visitor::{Visit, VisitResult, Visitor},
         TypeUuidProvider,
     },
-    engine::{task::TaskPoolHandler, GraphicsContext, ScriptMessageDispatcher},
+    engine::{
+        blackboard::Blackboard, task::TaskPoolHandler, GraphicsContext, ScriptMessageDispatcher,
+    },
     event::Event,
     plugin::Plugin,
     scene::{node::Node, Scene},
@@ -331,6 +333,9 @@ pub struct ScriptContext<'a, 'b, 'c> {
     /// Task pool for asynchronous task management.
     pub task_pool: &'a mut TaskPoolHandler,
 
+    /// Global container for shared game state. See [`Blackboard`] docs for more info.
+    pub blackboard: &'a mut Blackboard,
+
     /// Current graphics context of the engine. See [`GraphicsContext`] docs for more info.
     pub graphics_context: &'a mut GraphicsContext,
 
@@ -380,6 +385,9 @@ pub struct ScriptMessageContext<'a, 'b, 'c> {
     /// Task pool for asynchronous task management.
     pub task_pool: &'a mut TaskPoolHandler,
 
+    /// Global container for shared game state. See [`Blackboard`] docs for more info.
+    pub blackboard: &'a mut Blackboard,
+
     /// Current graphics context of the engine. See [`GraphicsContext`] docs for more info.
     pub graphics_context: &'a mut GraphicsContext,
 
@@ -420,6 +428,9 @@ pub struct ScriptDeinitContext<'a, 'b, 'c> {
     /// Task pool for asynchronous task management.
     pub task_pool: &'a mut TaskPoolHandler,
 
+    /// Global container for shared game state. See [`Blackboard`] docs for more info.
+    pub blackboard: &'a mut Blackboard,
+
     /// Current graphics context of the engine. See [`GraphicsContext`] docs for more info.
     pub graphics_context: &'a mut GraphicsContext,
 
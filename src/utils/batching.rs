@@ -0,0 +1,395 @@
+//! Static batching merges surfaces of static meshes, that share the same material, into larger
+//! blocks to reduce the amount of draw calls. See [`StaticBatchStorage`] docs for more info.
+
+use crate::{
+    core::{
+        log::Log,
+        math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
+        pool::Handle,
+    },
+    material::MaterialResource,
+    scene::{
+        base::Mobility,
+        collider::BitMask,
+        graph::Graph,
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait},
+            surface::{Surface, SurfaceData, SurfaceSharedData},
+            Mesh, RenderPath,
+        },
+        node::Node,
+    },
+};
+use fxhash::{FxHashMap, FxHashSet};
+use std::ops::Range;
+
+/// Maximum amount of vertices in a single static batch. Larger batches are split, so they could
+/// still be culled efficiently.
+pub const MAX_STATIC_BATCH_VERTICES: u32 = 1 << 16;
+
+/// A part of a static batch, that was taken from a surface of a mesh.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StaticBatchSource {
+    /// A handle of the mesh, that owns the surface.
+    pub node: Handle<Node>,
+    /// An index of the surface in the mesh.
+    pub surface_index: usize,
+    /// A range of triangles of the batch, that were taken from the surface.
+    pub triangles: Range<u32>,
+}
+
+/// A set of surfaces, that share the same material, merged into a single surface in world space.
+#[derive(Clone, Debug)]
+pub struct StaticBatch {
+    /// Merged geometry in world space.
+    pub data: SurfaceSharedData,
+    /// Shared material of the merged surfaces.
+    pub material: MaterialResource,
+    /// Render path of the merged meshes.
+    pub render_path: RenderPath,
+    /// Decal layer index of the merged meshes.
+    pub decal_layer_index: u8,
+    /// Whether the merged meshes cast shadows or not.
+    pub cast_shadows: bool,
+    /// Render layers of the merged meshes.
+    pub render_layers: BitMask,
+    /// World-space bounding box of the merged geometry.
+    pub bounds: AxisAlignedBoundingBox,
+    /// A list of the merged surfaces, sorted by their triangle ranges.
+    pub sources: Vec<StaticBatchSource>,
+}
+
+impl StaticBatch {
+    /// Returns a source of the given triangle of the batch. It could be used to map a result of
+    /// a ray cast against the merged geometry back to the original mesh.
+    pub fn source_of_triangle(&self, triangle_index: u32) -> Option<&StaticBatchSource> {
+        let position = self
+            .sources
+            .partition_point(|source| source.triangles.end <= triangle_index);
+        self.sources
+            .get(position)
+            .filter(|source| source.triangles.contains(&triangle_index))
+    }
+}
+
+#[derive(Hash, PartialEq, Eq)]
+struct BatchKey {
+    material: usize,
+    layout: u64,
+    render_path: u32,
+    decal_layer_index: u8,
+    cast_shadows: bool,
+    render_layers: u32,
+}
+
+/// Static batch storage contains merged surfaces of static meshes. Static batching reduces the
+/// amount of draw calls for scenes with lots of small static objects (buildings, props, etc.),
+/// at the cost of extra memory, that is used to store a copy of the geometry in world space.
+///
+/// ## Which meshes are batched
+///
+/// A mesh is batched only if it has [`Mobility::Static`] mobility and it is a part of a static
+/// subtree - every ancestor of the mesh (except the root of the graph) must have either static or
+/// stationary mobility. Also, the mesh must be visible and enabled, it must not be controlled by
+/// a LOD group and its surfaces must not be skinned, have blend shapes or material property
+/// overrides. Vertices of the surfaces must have positions, normals and tangents.
+///
+/// ## Original meshes
+///
+/// Original meshes stay in the graph, they are just excluded from rendering. It means that
+/// picking, ray casting, collider generation and scripts still work with the original nodes.
+/// Use [`StaticBatch::source_of_triangle`] to map merged geometry back to the original mesh.
+///
+/// Keep in mind, that changes of the original meshes (transform, visibility, materials, etc.)
+/// are not reflected in the batches. Static batches must be rebuilt (see
+/// [`Graph::build_static_batches`]) if the original meshes were changed.
+#[derive(Clone, Debug, Default)]
+pub struct StaticBatchStorage {
+    batches: Vec<StaticBatch>,
+    batched_nodes: FxHashSet<Handle<Node>>,
+}
+
+fn is_in_static_subtree(graph: &Graph, handle: Handle<Node>) -> bool {
+    let node = &graph[handle];
+    if node.mobility() != Mobility::Static {
+        return false;
+    }
+
+    let mut parent = node.parent();
+    while parent.is_some() && parent != graph.get_root() {
+        let parent_ref = &graph[parent];
+        if parent_ref.mobility() == Mobility::Dynamic {
+            return false;
+        }
+        parent = parent_ref.parent();
+    }
+
+    true
+}
+
+fn is_surface_batchable(surface: &Surface) -> bool {
+    if !surface.bones().is_empty() || !surface.property_overrides().is_empty() {
+        return false;
+    }
+
+    let data = surface.data_ref().lock();
+    data.blend_shapes_container.is_none()
+        && [
+            VertexAttributeUsage::Position,
+            VertexAttributeUsage::Normal,
+            VertexAttributeUsage::Tangent,
+        ]
+        .into_iter()
+        .all(|usage| data.vertex_buffer.has_attribute(usage))
+}
+
+fn collect_lod_controlled_nodes(graph: &Graph) -> FxHashSet<Handle<Node>> {
+    let mut nodes = FxHashSet::default();
+    for node in graph.linear_iter() {
+        if let Some(lod_group) = node.lod_group() {
+            for level in lod_group.levels.iter() {
+                nodes.extend(level.objects.iter().cloned());
+            }
+        }
+    }
+    nodes
+}
+
+impl StaticBatchStorage {
+    /// Merges every suitable static mesh of the graph. Global transforms of the nodes must be
+    /// up-to-date.
+    pub fn from_graph(graph: &Graph) -> Self {
+        let lod_controlled = collect_lod_controlled_nodes(graph);
+
+        let mut groups = FxHashMap::<BatchKey, Vec<(Handle<Node>, usize)>>::default();
+        let mut batched_nodes = FxHashSet::default();
+        for (handle, node) in graph.pair_iter() {
+            let Some(mesh) = node.cast::<Mesh>() else {
+                continue;
+            };
+
+            if !mesh.global_visibility()
+                || !mesh.is_globally_enabled()
+                || mesh.surfaces().is_empty()
+                || mesh.depth_offset_factor() != 0.0
+                || lod_controlled.contains(&handle)
+                || !is_in_static_subtree(graph, handle)
+                || !mesh.surfaces().iter().all(is_surface_batchable)
+            {
+                continue;
+            }
+
+            for (surface_index, surface) in mesh.surfaces().iter().enumerate() {
+                let key = BatchKey {
+                    material: surface.material().key(),
+                    layout: surface.data_ref().lock().vertex_buffer.layout_hash(),
+                    render_path: mesh.render_path() as u32,
+                    decal_layer_index: mesh.decal_layer_index(),
+                    cast_shadows: mesh.cast_shadows(),
+                    render_layers: mesh.render_layers().0,
+                };
+                groups.entry(key).or_default().push((handle, surface_index));
+            }
+
+            batched_nodes.insert(handle);
+        }
+
+        let mut batches = Vec::new();
+        for surfaces in groups.into_values() {
+            let mut current: Option<StaticBatch> = None;
+
+            for (handle, surface_index) in surfaces {
+                let mesh = graph[handle].cast::<Mesh>().unwrap();
+                let surface = &mesh.surfaces()[surface_index];
+
+                let mut data = {
+                    let source = surface.data_ref().lock();
+                    SurfaceData::new(
+                        source.vertex_buffer.clone(),
+                        source.geometry_buffer.clone(),
+                        true,
+                    )
+                };
+                // Cannot fail, because every attribute was checked before.
+                Log::verify(data.transform_geometry(&mesh.global_transform()));
+
+                if current.as_ref().is_some_and(|batch| {
+                    batch.data.lock().vertex_buffer.vertex_count()
+                        + data.vertex_buffer.vertex_count()
+                        > MAX_STATIC_BATCH_VERTICES
+                }) {
+                    batches.extend(current.take());
+                }
+
+                let batch = current.get_or_insert_with(|| StaticBatch {
+                    data: SurfaceSharedData::new(SurfaceData::new(
+                        Default::default(),
+                        Default::default(),
+                        true,
+                    )),
+                    material: surface.material().clone(),
+                    render_path: mesh.render_path(),
+                    decal_layer_index: mesh.decal_layer_index(),
+                    cast_shadows: mesh.cast_shadows(),
+                    render_layers: mesh.render_layers(),
+                    bounds: AxisAlignedBoundingBox::default(),
+                    sources: Vec::new(),
+                });
+
+                for vertex in data.vertex_buffer.iter() {
+                    if let Ok(position) = vertex.read_3_f32(VertexAttributeUsage::Position) {
+                        batch.bounds.add_point(position);
+                    }
+                }
+
+                let mut batch_data = batch.data.lock();
+                let first_triangle = batch_data.geometry_buffer.len() as u32;
+                if batch_data.vertex_buffer.vertex_count() == 0 {
+                    batch_data.vertex_buffer = data.vertex_buffer;
+                    batch_data.geometry_buffer = data.geometry_buffer;
+                } else {
+                    let base = batch_data.vertex_buffer.vertex_count();
+                    Log::verify(
+                        batch_data
+                            .vertex_buffer
+                            .modify()
+                            .push_vertex_buffer(&data.vertex_buffer),
+                    );
+                    batch_data.geometry_buffer.modify().push_triangles_iter(
+                        data.geometry_buffer
+                            .iter()
+                            .map(|triangle| TriangleDefinition(triangle.0.map(|i| i + base))),
+                    );
+                }
+                let last_triangle = batch_data.geometry_buffer.len() as u32;
+                drop(batch_data);
+
+                batch.sources.push(StaticBatchSource {
+                    node: handle,
+                    surface_index,
+                    triangles: first_triangle..last_triangle,
+                });
+            }
+
+            batches.extend(current);
+        }
+
+        Self {
+            batches,
+            batched_nodes,
+        }
+    }
+
+    /// Returns a list of static batches.
+    pub fn batches(&self) -> &[StaticBatch] {
+        &self.batches
+    }
+
+    /// Returns `true` if the given node was merged into static batches and it is excluded from
+    /// rendering.
+    pub fn is_batched(&self, node: Handle<Node>) -> bool {
+        self.batched_nodes.contains(&node)
+    }
+
+    /// Returns `true` if the storage has no batches.
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    /// Returns an iterator over every part of static batches, that was taken from the given node.
+    /// Every item is a pair of an index of a batch and the part.
+    pub fn sources_of(
+        &self,
+        node: Handle<Node>,
+    ) -> impl Iterator<Item = (usize, &StaticBatchSource)> + '_ {
+        self.batches
+            .iter()
+            .enumerate()
+            .flat_map(|(index, batch)| batch.sources.iter().map(move |source| (index, source)))
+            .filter(move |(_, source)| source.node == node)
+    }
+
+    /// Removes every batch.
+    pub fn clear(&mut self) {
+        self.batches.clear();
+        self.batched_nodes.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Vector3},
+            pool::Handle,
+        },
+        material::{Material, MaterialResource},
+        scene::{
+            base::{BaseBuilder, Mobility},
+            graph::Graph,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                MeshBuilder,
+            },
+            node::Node,
+            transform::TransformBuilder,
+        },
+        utils::batching::StaticBatchStorage,
+    };
+
+    fn make_cube(
+        graph: &mut Graph,
+        material: &MaterialResource,
+        x: f32,
+        mobility: Mobility,
+    ) -> Handle<Node> {
+        MeshBuilder::new(
+            BaseBuilder::new()
+                .with_mobility(mobility)
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(x, 0.0, 0.0))
+                        .build(),
+                ),
+        )
+        .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+            SurfaceData::make_cube(Matrix4::identity()),
+        ))
+        .with_material(material.clone())
+        .build()])
+        .build(graph)
+    }
+
+    #[test]
+    fn test_static_batching() {
+        let mut graph = Graph::new();
+        let material = MaterialResource::new_ok(Default::default(), Material::standard());
+
+        let a = make_cube(&mut graph, &material, 0.0, Mobility::Static);
+        let b = make_cube(&mut graph, &material, 10.0, Mobility::Static);
+        let c = make_cube(&mut graph, &material, 20.0, Mobility::Dynamic);
+
+        graph.update_hierarchical_data();
+
+        let storage = StaticBatchStorage::from_graph(&graph);
+        assert_eq!(storage.batches().len(), 1);
+        assert!(storage.is_batched(a));
+        assert!(storage.is_batched(b));
+        assert!(!storage.is_batched(c));
+
+        let batch = &storage.batches()[0];
+        let cube_triangles = batch.sources[0].triangles.len() as u32;
+        assert_eq!(batch.sources.len(), 2);
+        assert_eq!(
+            batch.data.lock().geometry_buffer.len() as u32,
+            2 * cube_triangles
+        );
+        assert_eq!(
+            batch.source_of_triangle(cube_triangles).map(|s| s.node),
+            Some(b)
+        );
+        assert_eq!(batch.source_of_triangle(2 * cube_triangles), None);
+        assert!(batch.bounds.max.x >= 10.0);
+        assert_eq!(storage.sources_of(a).count(), 1);
+    }
+}
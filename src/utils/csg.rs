@@ -0,0 +1,656 @@
+//! Constructive solid geometry (CSG) - boolean operations (union, subtraction, intersection) on
+//! closed polygonal solids. See [`Csg`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix3, Matrix4, Point3, Vector2, Vector3, Vector4},
+        math::TriangleDefinition,
+    },
+    scene::mesh::{
+        buffer::{TriangleBuffer, VertexBuffer},
+        surface::SurfaceData,
+        vertex::StaticVertex,
+    },
+};
+
+const EPSILON: f32 = 1.0e-5;
+
+/// A vertex of a [`CsgPolygon`].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CsgVertex {
+    /// Position of the vertex.
+    pub position: Vector3<f32>,
+    /// Normal of the vertex.
+    pub normal: Vector3<f32>,
+    /// Texture coordinates of the vertex.
+    pub tex_coord: Vector2<f32>,
+}
+
+impl CsgVertex {
+    fn flip(&mut self) {
+        self.normal = -self.normal;
+    }
+
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            position: self.position.lerp(&other.position, t),
+            normal: self.normal.lerp(&other.normal, t),
+            tex_coord: self.tex_coord.lerp(&other.tex_coord, t),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Plane {
+    normal: Vector3<f32>,
+    w: f32,
+}
+
+impl Plane {
+    fn from_points(a: Vector3<f32>, b: Vector3<f32>, c: Vector3<f32>) -> Option<Self> {
+        let normal = (b - a).cross(&(c - a)).try_normalize(f32::EPSILON)?;
+        Some(Self {
+            normal,
+            w: normal.dot(&a),
+        })
+    }
+
+    fn flip(&mut self) {
+        self.normal = -self.normal;
+        self.w = -self.w;
+    }
+
+    /// Splits the polygon by the plane and puts the pieces into the respective lists. Coplanar
+    /// polygons go into either `coplanar_front` or `coplanar_back` depending on their orientation.
+    fn split_polygon(
+        &self,
+        polygon: CsgPolygon,
+        coplanar_front: &mut Vec<CsgPolygon>,
+        coplanar_back: &mut Vec<CsgPolygon>,
+        front: &mut Vec<CsgPolygon>,
+        back: &mut Vec<CsgPolygon>,
+    ) {
+        const COPLANAR: u8 = 0;
+        const FRONT: u8 = 1;
+        const BACK: u8 = 2;
+        const SPANNING: u8 = 3;
+
+        let mut polygon_type = 0;
+        let types = polygon
+            .vertices
+            .iter()
+            .map(|v| {
+                let t = self.normal.dot(&v.position) - self.w;
+                let vertex_type = if t < -EPSILON {
+                    BACK
+                } else if t > EPSILON {
+                    FRONT
+                } else {
+                    COPLANAR
+                };
+                polygon_type |= vertex_type;
+                vertex_type
+            })
+            .collect::<Vec<_>>();
+
+        match polygon_type {
+            COPLANAR => {
+                if self.normal.dot(&polygon.plane.normal) > 0.0 {
+                    coplanar_front.push(polygon)
+                } else {
+                    coplanar_back.push(polygon)
+                }
+            }
+            FRONT => front.push(polygon),
+            BACK => back.push(polygon),
+            _ => {
+                let mut f = Vec::new();
+                let mut b = Vec::new();
+                let count = polygon.vertices.len();
+                for i in 0..count {
+                    let j = (i + 1) % count;
+                    let (ti, tj) = (types[i], types[j]);
+                    let (vi, vj) = (&polygon.vertices[i], &polygon.vertices[j]);
+                    if ti != BACK {
+                        f.push(*vi);
+                    }
+                    if ti != FRONT {
+                        b.push(*vi);
+                    }
+                    if (ti | tj) == SPANNING {
+                        let t = (self.w - self.normal.dot(&vi.position))
+                            / self.normal.dot(&(vj.position - vi.position));
+                        let v = vi.interpolate(vj, t);
+                        f.push(v);
+                        b.push(v);
+                    }
+                }
+                if f.len() >= 3 {
+                    front.push(CsgPolygon {
+                        vertices: f,
+                        plane: polygon.plane,
+                        material: polygon.material,
+                    });
+                }
+                if b.len() >= 3 {
+                    back.push(CsgPolygon {
+                        vertices: b,
+                        plane: polygon.plane,
+                        material: polygon.material,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// A convex planar polygon.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsgPolygon {
+    vertices: Vec<CsgVertex>,
+    plane: Plane,
+    /// An arbitrary index of a material of the polygon, that is preserved through all operations.
+    pub material: usize,
+}
+
+impl CsgPolygon {
+    /// Creates a new polygon from a set of vertices in counter-clockwise order. Vertices must lie on
+    /// the same plane and form a convex polygon. Returns `None` if the polygon is degenerate.
+    pub fn new(vertices: Vec<CsgVertex>, material: usize) -> Option<Self> {
+        if vertices.len() < 3 {
+            return None;
+        }
+        let plane = Plane::from_points(
+            vertices[0].position,
+            vertices[1].position,
+            vertices[2].position,
+        )?;
+        Some(Self {
+            vertices,
+            plane,
+            material,
+        })
+    }
+
+    /// Returns vertices of the polygon.
+    pub fn vertices(&self) -> &[CsgVertex] {
+        &self.vertices
+    }
+
+    fn flip(&mut self) {
+        self.vertices.reverse();
+        for vertex in self.vertices.iter_mut() {
+            vertex.flip();
+        }
+        self.plane.flip();
+    }
+}
+
+/// A node of a BSP tree, that is used to perform CSG operations.
+#[derive(Default)]
+struct Node {
+    plane: Option<Plane>,
+    front: Option<Box<Node>>,
+    back: Option<Box<Node>>,
+    polygons: Vec<CsgPolygon>,
+}
+
+impl Node {
+    fn new(polygons: Vec<CsgPolygon>) -> Self {
+        let mut node = Self::default();
+        node.build(polygons);
+        node
+    }
+
+    /// Converts solid space to empty space and vice versa.
+    fn invert(&mut self) {
+        for polygon in self.polygons.iter_mut() {
+            polygon.flip();
+        }
+        if let Some(plane) = self.plane.as_mut() {
+            plane.flip();
+        }
+        if let Some(front) = self.front.as_mut() {
+            front.invert();
+        }
+        if let Some(back) = self.back.as_mut() {
+            back.invert();
+        }
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    /// Removes all parts of the polygons, that are inside of this BSP tree.
+    fn clip_polygons(&self, polygons: Vec<CsgPolygon>) -> Vec<CsgPolygon> {
+        let Some(plane) = self.plane else {
+            return polygons;
+        };
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        for polygon in polygons {
+            let mut coplanar_front = Vec::new();
+            let mut coplanar_back = Vec::new();
+            plane.split_polygon(
+                polygon,
+                &mut coplanar_front,
+                &mut coplanar_back,
+                &mut front,
+                &mut back,
+            );
+            front.extend(coplanar_front);
+            back.extend(coplanar_back);
+        }
+        let mut front = match self.front.as_ref() {
+            Some(node) => node.clip_polygons(front),
+            None => front,
+        };
+        let back = match self.back.as_ref() {
+            Some(node) => node.clip_polygons(back),
+            None => Vec::new(),
+        };
+        front.extend(back);
+        front
+    }
+
+    /// Removes all polygons of this tree, that are inside the other tree.
+    fn clip_to(&mut self, other: &Node) {
+        self.polygons = other.clip_polygons(std::mem::take(&mut self.polygons));
+        if let Some(front) = self.front.as_mut() {
+            front.clip_to(other);
+        }
+        if let Some(back) = self.back.as_mut() {
+            back.clip_to(other);
+        }
+    }
+
+    fn all_polygons(&self) -> Vec<CsgPolygon> {
+        let mut polygons = self.polygons.clone();
+        if let Some(front) = self.front.as_ref() {
+            polygons.extend(front.all_polygons());
+        }
+        if let Some(back) = self.back.as_ref() {
+            polygons.extend(back.all_polygons());
+        }
+        polygons
+    }
+
+    fn build(&mut self, polygons: Vec<CsgPolygon>) {
+        if polygons.is_empty() {
+            return;
+        }
+        let plane = *self.plane.get_or_insert(polygons[0].plane);
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        for polygon in polygons {
+            let mut coplanar_front = Vec::new();
+            let mut coplanar_back = Vec::new();
+            plane.split_polygon(
+                polygon,
+                &mut coplanar_front,
+                &mut coplanar_back,
+                &mut front,
+                &mut back,
+            );
+            self.polygons.extend(coplanar_front);
+            self.polygons.extend(coplanar_back);
+        }
+        if !front.is_empty() {
+            self.front.get_or_insert_with(Default::default).build(front);
+        }
+        if !back.is_empty() {
+            self.back.get_or_insert_with(Default::default).build(back);
+        }
+    }
+}
+
+/// A closed solid, represented by a set of convex polygons. Solids could be combined using
+/// [`Csg::union`], [`Csg::subtract`] and [`Csg::intersect`]; the result could be converted to a
+/// surface using [`Csg::to_surface_data`].
+///
+/// Operations are implemented using BSP trees, every operation clips polygons of one solid by the
+/// other one and vice versa. The implementation follows [csg.js](https://github.com/evanw/csg.js).
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::algebra::{Matrix4, Vector3},
+///     utils::csg::Csg,
+/// };
+///
+/// // A cube with a cylindrical hole.
+/// let cube = Csg::cube(&Matrix4::identity(), 0);
+/// let hole = Csg::cylinder(
+///     16,
+///     &Matrix4::new_nonuniform_scaling(&Vector3::new(0.5, 2.0, 0.5)),
+///     0,
+/// );
+/// let data = cube.subtract(&hole).to_surface_data(0);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Csg {
+    polygons: Vec<CsgPolygon>,
+}
+
+impl Csg {
+    /// Creates a solid from a set of polygons. Polygons must form a closed surface.
+    pub fn from_polygons(polygons: Vec<CsgPolygon>) -> Self {
+        Self { polygons }
+    }
+
+    /// Returns polygons of the solid.
+    pub fn polygons(&self) -> &[CsgPolygon] {
+        &self.polygons
+    }
+
+    /// Returns `true` if the solid has no polygons.
+    pub fn is_empty(&self) -> bool {
+        self.polygons.is_empty()
+    }
+
+    fn from_faces(faces: &[Vec<Vector3<f32>>], transform: &Matrix4<f32>, material: usize) -> Self {
+        Self::from_polygons(
+            faces
+                .iter()
+                .filter_map(|face| {
+                    CsgPolygon::new(
+                        face.iter()
+                            .map(|p| CsgVertex {
+                                position: *p,
+                                ..Default::default()
+                            })
+                            .collect(),
+                        material,
+                    )
+                })
+                .collect(),
+        )
+        .with_flat_normals()
+        .transform(transform)
+    }
+
+    fn with_flat_normals(mut self) -> Self {
+        for polygon in self.polygons.iter_mut() {
+            for vertex in polygon.vertices.iter_mut() {
+                vertex.normal = polygon.plane.normal;
+            }
+        }
+        self
+    }
+
+    /// Creates a unit cube (`[-0.5; 0.5]` on every axis), transformed by the given matrix.
+    pub fn cube(transform: &Matrix4<f32>, material: usize) -> Self {
+        let p = |x: f32, y: f32, z: f32| Vector3::new(x, y, z).scale(0.5);
+        let faces = [
+            vec![
+                p(-1., -1., -1.),
+                p(-1., -1., 1.),
+                p(-1., 1., 1.),
+                p(-1., 1., -1.),
+            ],
+            vec![
+                p(1., -1., -1.),
+                p(1., 1., -1.),
+                p(1., 1., 1.),
+                p(1., -1., 1.),
+            ],
+            vec![
+                p(-1., -1., -1.),
+                p(1., -1., -1.),
+                p(1., -1., 1.),
+                p(-1., -1., 1.),
+            ],
+            vec![
+                p(-1., 1., -1.),
+                p(-1., 1., 1.),
+                p(1., 1., 1.),
+                p(1., 1., -1.),
+            ],
+            vec![
+                p(-1., -1., -1.),
+                p(-1., 1., -1.),
+                p(1., 1., -1.),
+                p(1., -1., -1.),
+            ],
+            vec![
+                p(-1., -1., 1.),
+                p(1., -1., 1.),
+                p(1., 1., 1.),
+                p(-1., 1., 1.),
+            ],
+        ];
+        Self::from_faces(&faces, transform, material)
+    }
+
+    /// Creates a unit wedge (a cube cut in half by a diagonal plane), transformed by the given
+    /// matrix. The slope faces +Z and +Y, the vertical face is at -Z.
+    pub fn wedge(transform: &Matrix4<f32>, material: usize) -> Self {
+        let p = |x: f32, y: f32, z: f32| Vector3::new(x, y, z).scale(0.5);
+        let faces = [
+            // Bottom.
+            vec![
+                p(-1., -1., -1.),
+                p(1., -1., -1.),
+                p(1., -1., 1.),
+                p(-1., -1., 1.),
+            ],
+            // Back.
+            vec![
+                p(-1., -1., -1.),
+                p(-1., 1., -1.),
+                p(1., 1., -1.),
+                p(1., -1., -1.),
+            ],
+            // Slope.
+            vec![
+                p(-1., 1., -1.),
+                p(-1., -1., 1.),
+                p(1., -1., 1.),
+                p(1., 1., -1.),
+            ],
+            // Sides.
+            vec![p(-1., -1., -1.), p(-1., -1., 1.), p(-1., 1., -1.)],
+            vec![p(1., -1., -1.), p(1., 1., -1.), p(1., -1., 1.)],
+        ];
+        Self::from_faces(&faces, transform, material)
+    }
+
+    /// Creates a unit cylinder (radius 0.5, height 1.0, centered at origin, aligned with Y axis) with
+    /// the given amount of sides, transformed by the given matrix. Side faces have smooth normals.
+    pub fn cylinder(sides: usize, transform: &Matrix4<f32>, material: usize) -> Self {
+        let sides = sides.max(3);
+        let point = |i: usize| {
+            let angle = i as f32 / sides as f32 * std::f32::consts::TAU;
+            Vector3::new(angle.cos() * 0.5, 0.0, -angle.sin() * 0.5)
+        };
+        let up = Vector3::new(0.0, 0.5, 0.0);
+
+        let mut faces = vec![
+            (0..sides).map(|i| point(i) + up).collect::<Vec<_>>(),
+            (0..sides).rev().map(|i| point(i) - up).collect::<Vec<_>>(),
+        ];
+        for i in 0..sides {
+            let (a, b) = (point(i), point(i + 1));
+            faces.push(vec![a - up, b - up, b + up, a + up]);
+        }
+
+        let mut csg = Self::from_faces(&faces, &Matrix4::identity(), material);
+        // Smooth normals for the sides.
+        for polygon in csg.polygons.iter_mut().skip(2) {
+            for vertex in polygon.vertices.iter_mut() {
+                vertex.normal = Vector3::new(vertex.position.x, 0.0, vertex.position.z)
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or(polygon.plane.normal);
+            }
+        }
+        csg.transform(transform)
+    }
+
+    /// Transforms every polygon of the solid using the given matrix. If the matrix flips orientation
+    /// (has negative determinant), the polygons are flipped to keep the solid closed and outward-facing.
+    pub fn transform(mut self, transform: &Matrix4<f32>) -> Self {
+        let basis = transform.fixed_view::<3, 3>(0, 0).into_owned();
+        let normal_matrix = basis
+            .try_inverse()
+            .map(|m| m.transpose())
+            .unwrap_or_else(Matrix3::identity);
+        let flip = basis.determinant() < 0.0;
+        self.polygons.retain_mut(|polygon| {
+            for vertex in polygon.vertices.iter_mut() {
+                vertex.position = transform
+                    .transform_point(&Point3::from(vertex.position))
+                    .coords;
+                vertex.normal = (normal_matrix * vertex.normal)
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default();
+            }
+            if flip {
+                polygon.vertices.reverse();
+            }
+            match Plane::from_points(
+                polygon.vertices[0].position,
+                polygon.vertices[1].position,
+                polygon.vertices[2].position,
+            ) {
+                Some(plane) => {
+                    polygon.plane = plane;
+                    true
+                }
+                None => false,
+            }
+        });
+        self
+    }
+
+    /// Projects texture coordinates on every polygon using box (triplanar) mapping: coordinates are
+    /// taken from the two axes, that are the most perpendicular to the normal of the polygon. It gives
+    /// uniform texel density, which is useful for prototyping.
+    pub fn with_box_mapping(mut self, scale: f32) -> Self {
+        for polygon in self.polygons.iter_mut() {
+            let n = polygon.plane.normal.abs();
+            for vertex in polygon.vertices.iter_mut() {
+                let p = vertex.position.scale(scale);
+                vertex.tex_coord = if n.x >= n.y && n.x >= n.z {
+                    Vector2::new(p.z, p.y)
+                } else if n.y >= n.z {
+                    Vector2::new(p.x, p.z)
+                } else {
+                    Vector2::new(p.x, p.y)
+                };
+            }
+        }
+        self
+    }
+
+    /// Returns a solid, that occupies the space of both solids.
+    pub fn union(&self, other: &Csg) -> Csg {
+        let mut a = Node::new(self.polygons.clone());
+        let mut b = Node::new(other.polygons.clone());
+        a.clip_to(&b);
+        b.clip_to(&a);
+        b.invert();
+        b.clip_to(&a);
+        b.invert();
+        a.build(b.all_polygons());
+        Csg::from_polygons(a.all_polygons())
+    }
+
+    /// Returns a solid, that occupies the space of this solid, but not the other one.
+    pub fn subtract(&self, other: &Csg) -> Csg {
+        let mut a = Node::new(self.polygons.clone());
+        let mut b = Node::new(other.polygons.clone());
+        a.invert();
+        a.clip_to(&b);
+        b.clip_to(&a);
+        b.invert();
+        b.clip_to(&a);
+        b.invert();
+        a.build(b.all_polygons());
+        a.invert();
+        Csg::from_polygons(a.all_polygons())
+    }
+
+    /// Returns a solid, that occupies the space, that is shared by both solids.
+    pub fn intersect(&self, other: &Csg) -> Csg {
+        let mut a = Node::new(self.polygons.clone());
+        let mut b = Node::new(other.polygons.clone());
+        a.invert();
+        b.clip_to(&a);
+        b.invert();
+        a.clip_to(&b);
+        b.clip_to(&a);
+        a.build(b.all_polygons());
+        a.invert();
+        Csg::from_polygons(a.all_polygons())
+    }
+
+    /// Converts polygons with the given material index into surface data. Polygons are triangulated
+    /// as triangle fans, tangents are calculated as well.
+    pub fn to_surface_data(&self, material: usize) -> SurfaceData {
+        let mut vertices = Vec::new();
+        let mut triangles = Vec::new();
+        for polygon in self.polygons.iter().filter(|p| p.material == material) {
+            let first = vertices.len() as u32;
+            for vertex in polygon.vertices.iter() {
+                vertices.push(StaticVertex {
+                    position: vertex.position,
+                    tex_coord: vertex.tex_coord,
+                    normal: vertex.normal,
+                    tangent: Vector4::default(),
+                });
+            }
+            for i in 1..(polygon.vertices.len() as u32 - 1) {
+                triangles.push(TriangleDefinition([first, first + i, first + i + 1]));
+            }
+        }
+        let mut data = SurfaceData::new(
+            VertexBuffer::new(vertices.len(), vertices).unwrap(),
+            TriangleBuffer::new(triangles),
+            true,
+        );
+        let _ = data.calculate_tangents();
+        data
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Matrix4, Vector3},
+        utils::csg::Csg,
+    };
+
+    fn volume(csg: &Csg) -> f32 {
+        // Sum of signed volumes of tetrahedrons formed by the origin and every triangle.
+        let mut volume = 0.0;
+        for polygon in csg.polygons() {
+            let v = polygon.vertices();
+            for i in 1..v.len() - 1 {
+                volume += v[0].position.dot(&v[i].position.cross(&v[i + 1].position)) / 6.0;
+            }
+        }
+        volume
+    }
+
+    #[test]
+    fn test_csg_operations() {
+        let a = Csg::cube(&Matrix4::identity(), 0);
+        let b = Csg::cube(&Matrix4::new_translation(&Vector3::new(0.5, 0.0, 0.0)), 1);
+
+        assert!((volume(&a) - 1.0).abs() < 1.0e-4);
+        assert!((volume(&a.union(&b)) - 1.5).abs() < 1.0e-4);
+        assert!((volume(&a.subtract(&b)) - 0.5).abs() < 1.0e-4);
+        assert!((volume(&a.intersect(&b)) - 0.5).abs() < 1.0e-4);
+        assert!((volume(&Csg::wedge(&Matrix4::identity(), 0)) - 0.5).abs() < 1.0e-4);
+
+        // Materials are preserved.
+        let union = a.union(&b);
+        assert!(union.polygons().iter().any(|p| p.material == 0));
+        assert!(union.polygons().iter().any(|p| p.material == 1));
+
+        // Mirroring keeps the solid outward-facing.
+        let mirrored = a.transform(&Matrix4::new_nonuniform_scaling(&Vector3::new(
+            -1.0, 1.0, 1.0,
+        )));
+        assert!((volume(&mirrored) - 1.0).abs() < 1.0e-4);
+    }
+}
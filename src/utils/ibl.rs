@@ -0,0 +1,485 @@
+//! Image-based lighting (IBL) utilities. Allows you to generate environment maps (a sky cube map,
+//! diffuse irradiance map and prefiltered specular map) from a single equirectangular panorama.
+//! See [`generate_environment_maps`] docs for more info.
+
+use crate::{
+    asset::untyped::ResourceKind,
+    core::{algebra::Vector3, color::Color},
+    renderer::panorama::{cube_map_texel, cube_map_texel_direction},
+    resource::texture::{
+        Texture, TextureKind, TextureMinificationFilter, TexturePixelKind, TextureResource,
+    },
+    scene::camera::SkyBoxError,
+};
+use half::f16;
+use std::f32::consts::PI;
+
+/// Size of a face of the generated irradiance map. Irradiance is very smooth, so small size is
+/// enough.
+pub const IRRADIANCE_MAP_SIZE: u32 = 32;
+
+/// Size of a face of the first mip level of the generated prefiltered specular map.
+pub const PREFILTERED_MAP_SIZE: u32 = 128;
+
+/// Size of a face of the last mip level of the generated prefiltered specular map.
+pub const PREFILTERED_MAP_MIN_SIZE: u32 = 8;
+
+/// Maximum size of a face of the generated sky cube map.
+pub const MAX_CUBE_MAP_SIZE: u32 = 1024;
+
+const PREFILTER_SAMPLE_COUNT: u32 = 64;
+
+/// A set of environment maps, that is used for image-based lighting. All the maps are cube maps in
+/// linear color space with `RGB16F` pixel format.
+#[derive(Clone, Debug)]
+pub struct EnvironmentMaps {
+    /// Cube map, that was made of the source panorama. It could be used as a sky.
+    pub cube_map: TextureResource,
+    /// Cosine-weighted convolution of the environment (divided by Pi), that is used for diffuse
+    /// ambient lighting. Sample it using surface normal.
+    pub irradiance_map: TextureResource,
+    /// GGX-prefiltered environment, that is used for specular ambient lighting. Each mip level
+    /// corresponds to roughness `mip / (mip_count - 1)`. Sample it using reflection vector.
+    pub prefiltered_map: TextureResource,
+}
+
+struct Panorama {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vector3<f32>>,
+}
+
+impl Panorama {
+    fn from_texture(texture: &Texture) -> Result<Self, SkyBoxError> {
+        let TextureKind::Rectangle { width, height } = texture.kind() else {
+            return Err(SkyBoxError::UnsupportedTextureKind(texture.kind()));
+        };
+
+        let data = texture.mip_level_data(0);
+        let srgb = |r: u8, g: u8, b: u8| Color::from_rgba(r, g, b, 255).srgb_to_linear_f32().xyz();
+        let f32s = |bytes: &[u8]| {
+            bytes
+                .chunks_exact(4)
+                .map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+                .collect::<Vec<_>>()
+        };
+        let pixels = match texture.pixel_kind() {
+            TexturePixelKind::RGB8 => data
+                .chunks_exact(3)
+                .map(|p| srgb(p[0], p[1], p[2]))
+                .collect(),
+            TexturePixelKind::RGBA8 => data
+                .chunks_exact(4)
+                .map(|p| srgb(p[0], p[1], p[2]))
+                .collect(),
+            TexturePixelKind::BGR8 => data
+                .chunks_exact(3)
+                .map(|p| srgb(p[2], p[1], p[0]))
+                .collect(),
+            TexturePixelKind::BGRA8 => data
+                .chunks_exact(4)
+                .map(|p| srgb(p[2], p[1], p[0]))
+                .collect(),
+            TexturePixelKind::RGB32F => f32s(data)
+                .chunks_exact(3)
+                .map(|p| Vector3::new(p[0], p[1], p[2]))
+                .collect(),
+            TexturePixelKind::RGBA32F => f32s(data)
+                .chunks_exact(4)
+                .map(|p| Vector3::new(p[0], p[1], p[2]))
+                .collect(),
+            TexturePixelKind::RGB16F => data
+                .chunks_exact(6)
+                .map(|p| {
+                    let channel = |i: usize| f16::from_ne_bytes([p[i], p[i + 1]]).to_f32();
+                    Vector3::new(channel(0), channel(2), channel(4))
+                })
+                .collect(),
+            pixel_kind => return Err(SkyBoxError::UnsupportedPixelKind(pixel_kind)),
+        };
+
+        Ok(Self {
+            width: width as usize,
+            height: height as usize,
+            pixels,
+        })
+    }
+
+    fn texel(&self, x: usize, y: usize) -> Vector3<f32> {
+        self.pixels[y * self.width + x]
+    }
+
+    // Samples the panorama using bilinear filtering. The center of the panorama is the +Z direction,
+    // the top row is the +Y direction (see `CubeMapCapture::to_equirectangular`).
+    fn sample(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        let direction = direction.normalize();
+        let longitude = (-direction.x).atan2(direction.z);
+        let latitude = direction.y.clamp(-1.0, 1.0).asin();
+        let u = (longitude + PI) / (2.0 * PI) * self.width as f32 - 0.5;
+        let v = ((0.5 * PI - latitude) / PI * self.height as f32 - 0.5)
+            .clamp(0.0, self.height.saturating_sub(1) as f32);
+
+        let x0 = u.floor();
+        let y0 = v.floor();
+        let tx = u - x0;
+        let ty = v - y0;
+        let wrap = |x: f32| (x as i64).rem_euclid(self.width as i64) as usize;
+        let (x0, x1) = (wrap(x0), wrap(x0 + 1.0));
+        let y1 = ((y0 + 1.0) as usize).min(self.height - 1);
+        let y0 = y0 as usize;
+
+        let top = self.texel(x0, y0).lerp(&self.texel(x1, y0), tx);
+        let bottom = self.texel(x0, y1).lerp(&self.texel(x1, y1), tx);
+        top.lerp(&bottom, ty)
+    }
+}
+
+#[derive(Clone)]
+struct CubeFaces {
+    size: u32,
+    faces: [Vec<Vector3<f32>>; 6],
+}
+
+impl CubeFaces {
+    fn from_fn(size: u32, mut func: impl FnMut(Vector3<f32>) -> Vector3<f32>) -> Self {
+        Self {
+            size,
+            faces: std::array::from_fn(|face| {
+                let mut pixels = Vec::with_capacity((size * size) as usize);
+                for y in 0..size {
+                    for x in 0..size {
+                        pixels.push(func(cube_map_texel_direction(face, x, y, size).normalize()));
+                    }
+                }
+                pixels
+            }),
+        }
+    }
+
+    fn sample(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        let (face, x, y) = cube_map_texel(direction, self.size);
+        self.faces[face][y * self.size as usize + x]
+    }
+
+    fn downsample(&self) -> Self {
+        let size = (self.size / 2).max(1);
+        let src_size = self.size as usize;
+        Self {
+            size,
+            faces: std::array::from_fn(|face| {
+                let src = &self.faces[face];
+                let mut pixels = Vec::with_capacity((size * size) as usize);
+                for y in 0..size as usize {
+                    for x in 0..size as usize {
+                        let texel = |x: usize, y: usize| {
+                            src[y.min(src_size - 1) * src_size + x.min(src_size - 1)]
+                        };
+                        pixels.push(
+                            (texel(2 * x, 2 * y)
+                                + texel(2 * x + 1, 2 * y)
+                                + texel(2 * x, 2 * y + 1)
+                                + texel(2 * x + 1, 2 * y + 1))
+                                * 0.25,
+                        );
+                    }
+                }
+                pixels
+            }),
+        }
+    }
+
+    fn write_rgb16f(&self, bytes: &mut Vec<u8>) {
+        for face in self.faces.iter() {
+            for pixel in face {
+                for channel in pixel.iter() {
+                    // Clamp to the maximum finite value of half-precision float to prevent infinities.
+                    let value = channel.min(f16::MAX.to_f32());
+                    bytes.extend_from_slice(&f16::from_f32(value).to_ne_bytes());
+                }
+            }
+        }
+    }
+
+    fn into_texture(
+        mip_levels: &[CubeFaces],
+        filter: TextureMinificationFilter,
+    ) -> TextureResource {
+        let mut bytes = Vec::new();
+        for level in mip_levels {
+            level.write_rgb16f(&mut bytes);
+        }
+        let mut texture = Texture::from_bytes_with_mips(
+            TextureKind::Cube {
+                width: mip_levels[0].size,
+                height: mip_levels[0].size,
+            },
+            TexturePixelKind::RGB16F,
+            bytes,
+            mip_levels.len() as u32,
+        )
+        .expect("Size of the generated data must match the size of the texture!");
+        texture.set_minification_filter(filter);
+        TextureResource::new_ok(ResourceKind::Embedded, texture)
+    }
+}
+
+// Solid angle of a cube map texel, `u` and `v` are in [-1; 1] range.
+fn texel_solid_angle(u: f32, v: f32, size: u32) -> f32 {
+    let texel_area = (2.0 / size as f32).powi(2);
+    texel_area / (1.0 + u * u + v * v).powf(1.5)
+}
+
+fn sh_basis(n: Vector3<f32>) -> [f32; 9] {
+    [
+        0.282095,
+        0.488603 * n.y,
+        0.488603 * n.z,
+        0.488603 * n.x,
+        1.092548 * n.x * n.y,
+        1.092548 * n.y * n.z,
+        0.315392 * (3.0 * n.z * n.z - 1.0),
+        1.092548 * n.x * n.z,
+        0.546274 * (n.x * n.x - n.y * n.y),
+    ]
+}
+
+// Projects the environment onto the first three bands of spherical harmonics and convolves it with
+// the clamped cosine lobe, see "An Efficient Representation for Irradiance Environment Maps" by
+// Ramamoorthi and Hanrahan. The result is irradiance divided by Pi.
+fn irradiance_sh(cube: &CubeFaces) -> [Vector3<f32>; 9] {
+    let mut coefficients = [Vector3::default(); 9];
+    let mut total_weight = 0.0;
+    for (face, pixels) in cube.faces.iter().enumerate() {
+        for y in 0..cube.size {
+            for x in 0..cube.size {
+                let direction = cube_map_texel_direction(face, x, y, cube.size);
+                let u = ((x as f32 + 0.5) / cube.size as f32) * 2.0 - 1.0;
+                let v = ((y as f32 + 0.5) / cube.size as f32) * 2.0 - 1.0;
+                let weight = texel_solid_angle(u, v, cube.size);
+                let radiance = pixels[(y * cube.size + x) as usize];
+                for (coefficient, basis) in
+                    coefficients.iter_mut().zip(sh_basis(direction.normalize()))
+                {
+                    *coefficient += radiance * (basis * weight);
+                }
+                total_weight += weight;
+            }
+        }
+    }
+
+    // Compensate the error of the solid angle approximation, the sum must be 4 * Pi.
+    let normalization = 4.0 * PI / total_weight;
+    const BAND_FACTORS: [f32; 9] = [
+        1.0,
+        2.0 / 3.0,
+        2.0 / 3.0,
+        2.0 / 3.0,
+        0.25,
+        0.25,
+        0.25,
+        0.25,
+        0.25,
+    ];
+    for (coefficient, band_factor) in coefficients.iter_mut().zip(BAND_FACTORS) {
+        *coefficient *= normalization * band_factor;
+    }
+    coefficients
+}
+
+fn hammersley(i: u32, count: u32) -> (f32, f32) {
+    (
+        i as f32 / count as f32,
+        i.reverse_bits() as f32 * 2.3283064e-10,
+    )
+}
+
+// Prefilters the environment with GGX lobe of the given roughness using importance sampling. To
+// reduce noise, every sample is fetched from a mip level of the source, that matches the solid angle
+// of the sample, see "GPU-Based Importance Sampling" by Colbert and Krivanek.
+fn prefilter(source_mips: &[CubeFaces], normal: Vector3<f32>, roughness: f32) -> Vector3<f32> {
+    let alpha = roughness * roughness;
+    let alpha2 = alpha * alpha;
+    let up = if normal.z.abs() < 0.999 {
+        Vector3::z()
+    } else {
+        Vector3::x()
+    };
+    let tangent = up.cross(&normal).normalize();
+    let bitangent = normal.cross(&tangent);
+    let texel_solid_angle = 4.0 * PI / (6.0 * (source_mips[0].size as f32).powi(2));
+
+    let mut color = Vector3::default();
+    let mut total_weight = 0.0;
+    for i in 0..PREFILTER_SAMPLE_COUNT {
+        let (xi_x, xi_y) = hammersley(i, PREFILTER_SAMPLE_COUNT);
+        let phi = 2.0 * PI * xi_x;
+        let cos_theta = ((1.0 - xi_y) / (1.0 + (alpha2 - 1.0) * xi_y)).sqrt();
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let half = (tangent * (phi.cos() * sin_theta)
+            + bitangent * (phi.sin() * sin_theta)
+            + normal * cos_theta)
+            .normalize();
+        // View vector is equal to the normal.
+        let n_dot_h = normal.dot(&half).max(0.0);
+        let light = half * (2.0 * n_dot_h) - normal;
+        let n_dot_l = normal.dot(&light);
+        if n_dot_l > 0.0 {
+            let d = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+            let distribution = alpha2 / (PI * d * d).max(f32::EPSILON);
+            let pdf = distribution * 0.25;
+            let sample_solid_angle = 1.0 / (PREFILTER_SAMPLE_COUNT as f32 * pdf + f32::EPSILON);
+            let mip = (0.5 * (sample_solid_angle / texel_solid_angle).log2() + 1.0)
+                .round()
+                .clamp(0.0, (source_mips.len() - 1) as f32) as usize;
+            color += source_mips[mip].sample(light) * n_dot_l;
+            total_weight += n_dot_l;
+        }
+    }
+
+    if total_weight > 0.0 {
+        color / total_weight
+    } else {
+        source_mips[0].sample(normal)
+    }
+}
+
+/// Returns size of a face of a cube map, that will be generated from a panorama of the given width.
+pub fn cube_map_size_for_panorama(width: u32) -> u32 {
+    (width / 4).next_power_of_two().clamp(16, MAX_CUBE_MAP_SIZE)
+}
+
+/// Generates a set of environment maps from the given equirectangular panorama (a texture with 2:1
+/// aspect ratio, that covers the whole sphere; the center of the panorama is the +Z direction, the
+/// top row is the +Y direction). High dynamic range panoramas (`RGB32F`, `RGBA32F`, `RGB16F`) are
+/// used as is, 8-bit panoramas are treated as sRGB.
+///
+/// The generation is done on CPU and may take a noticeable amount of time for large panoramas, so
+/// it should be done at loading stage.
+pub fn generate_environment_maps(panorama: &Texture) -> Result<EnvironmentMaps, SkyBoxError> {
+    let panorama = Panorama::from_texture(panorama)?;
+    if panorama.pixels.is_empty() {
+        return Err(SkyBoxError::UnableToBuildCubeMap);
+    }
+
+    let cube = CubeFaces::from_fn(
+        cube_map_size_for_panorama(panorama.width as u32),
+        |direction| panorama.sample(direction),
+    );
+
+    // Source mip chain for prefiltering.
+    let mut source_mips = vec![cube.clone()];
+    while source_mips.last().unwrap().size > 1 {
+        let next = source_mips.last().unwrap().downsample();
+        source_mips.push(next);
+    }
+
+    let sh_source = source_mips
+        .iter()
+        .find(|level| level.size <= IRRADIANCE_MAP_SIZE)
+        .unwrap_or(&cube);
+    let sh = irradiance_sh(sh_source);
+    let irradiance = CubeFaces::from_fn(IRRADIANCE_MAP_SIZE, |normal| {
+        let irradiance = sh
+            .iter()
+            .zip(sh_basis(normal))
+            .fold(Vector3::default(), |sum, (coefficient, basis)| {
+                sum + coefficient * basis
+            });
+        irradiance.sup(&Vector3::default())
+    });
+
+    let mut prefiltered_mips = Vec::new();
+    let mut size = PREFILTERED_MAP_SIZE.min(cube.size);
+    while size >= PREFILTERED_MAP_MIN_SIZE.min(cube.size) && size > 0 {
+        prefiltered_mips.push(size);
+        size /= 2;
+    }
+    let mip_count = prefiltered_mips.len();
+    let prefiltered_mips = prefiltered_mips
+        .into_iter()
+        .enumerate()
+        .map(|(mip, size)| {
+            if mip == 0 {
+                // Perfect mirror reflection.
+                source_mips
+                    .iter()
+                    .find(|level| level.size == size)
+                    .cloned()
+                    .unwrap_or_else(|| CubeFaces::from_fn(size, |dir| cube.sample(dir)))
+            } else {
+                let roughness = mip as f32 / (mip_count - 1) as f32;
+                CubeFaces::from_fn(size, |normal| prefilter(&source_mips, normal, roughness))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Ok(EnvironmentMaps {
+        cube_map: CubeFaces::into_texture(&[cube], TextureMinificationFilter::Linear),
+        irradiance_map: CubeFaces::into_texture(&[irradiance], TextureMinificationFilter::Linear),
+        prefiltered_map: CubeFaces::into_texture(
+            &prefiltered_mips,
+            TextureMinificationFilter::LinearMipMapLinear,
+        ),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        resource::texture::{Texture, TextureKind, TexturePixelKind},
+        utils::ibl::generate_environment_maps,
+    };
+    use half::f16;
+
+    #[test]
+    fn test_uniform_environment() {
+        // Uniform environment must produce uniform irradiance and prefiltered maps of the same
+        // brightness.
+        let (width, height) = (64, 32);
+        let bytes = std::iter::repeat(2.0f32)
+            .take(width * height * 3)
+            .flat_map(|v| v.to_ne_bytes())
+            .collect();
+        let panorama = Texture::from_bytes(
+            TextureKind::Rectangle {
+                width: width as u32,
+                height: height as u32,
+            },
+            TexturePixelKind::RGB32F,
+            bytes,
+        )
+        .unwrap();
+
+        let maps = generate_environment_maps(&panorama).unwrap();
+
+        let read = |texture: &Texture, mip: usize| {
+            texture
+                .mip_level_data(mip)
+                .chunks_exact(2)
+                .map(|c| f16::from_ne_bytes([c[0], c[1]]).to_f32())
+                .collect::<Vec<_>>()
+        };
+
+        let irradiance = maps.irradiance_map.data_ref();
+        assert!(read(&irradiance, 0)
+            .iter()
+            .all(|value| (value - 2.0).abs() < 0.05));
+
+        let prefiltered = maps.prefiltered_map.data_ref();
+        assert!(prefiltered.mip_count() > 1);
+        for mip in 0..prefiltered.mip_count() as usize {
+            assert!(read(&prefiltered, mip)
+                .iter()
+                .all(|value| (value - 2.0).abs() < 0.05));
+        }
+
+        let cube_map = maps.cube_map.data_ref();
+        assert!(matches!(
+            cube_map.kind(),
+            TextureKind::Cube {
+                width: 16,
+                height: 16
+            }
+        ));
+    }
+}
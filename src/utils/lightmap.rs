@@ -1,4 +1,6 @@
-//! Module to generate lightmaps for surfaces.
+//! Module to generate lightmaps for surfaces. Lighting could also be baked into vertex colors (see
+//! [`VertexLighting`]) for targets where texture memory is scarce. Indirect lighting for dynamic
+//! objects could be baked into light probes (see [`bake_light_probes`]).
 //!
 //! # Performance
 //!
@@ -14,19 +16,31 @@ use crate::{
     core::{
         algebra::{Matrix3, Matrix4, Point3, Vector2, Vector3, Vector4},
         arrayvec::ArrayVec,
+        color::Color,
+        log::{Log, MessageKind},
         math::{self, ray::Ray, Matrix4Ext, Rect, TriangleDefinition, Vector2Ext},
         octree::{Octree, OctreeNode},
         pool::Handle,
+        rand::{rngs::StdRng, Rng, SeedableRng},
         reflect::prelude::*,
         sstorage::ImmutableString,
         visitor::prelude::*,
     },
     material::PropertyValue,
-    resource::texture::{Texture, TextureKind, TexturePixelKind, TextureResource},
+    resource::{
+        light_probe::{LightProbeData, ShIrradiance},
+        texture::{Texture, TextureKind, TexturePixelKind, TextureResource},
+    },
     scene::{
+        base::Mobility,
+        graph::Graph,
         light::{directional::DirectionalLight, point::PointLight, spot::SpotLight},
+        light_probe::LightProbeVolume,
         mesh::{
-            buffer::{VertexAttributeUsage, VertexFetchError, VertexReadTrait},
+            buffer::{
+                VertexAttributeDataType, VertexAttributeDescriptor, VertexAttributeUsage,
+                VertexFetchError, VertexReadTrait, VertexWriteTrait,
+            },
             surface::SurfaceSharedData,
             Mesh,
         },
@@ -74,6 +88,62 @@ pub struct Lightmap {
     pub patches: FxHashMap<u64, SurfaceDataPatch>,
 }
 
+/// Settings of lightmap generation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightmapSettings {
+    /// Amount of texels per unit of area. The higher the value is, the more detailed lightmap will
+    /// be generated, but also it will be slower to generate.
+    pub texels_per_unit: u32,
+    /// Relative spacing between UV elements generated by the UV mapper.
+    pub uv_spacing: f32,
+    /// Amount of paths traced from every texel to calculate indirect (bounced) lighting. Zero
+    /// disables indirect lighting. Generation time grows linearly with this value.
+    pub indirect_samples: u32,
+    /// Maximum amount of bounces of every path.
+    pub indirect_bounces: u32,
+    /// Fraction of light that is reflected by surfaces of the scene. The lightmapper does not read
+    /// materials, so the same value is used for every surface.
+    pub bounce_albedo: f32,
+    /// Radiance of paths that did not hit anything.
+    pub sky_color: Vector3<f32>,
+    /// Maximum length of a segment of a path.
+    pub max_ray_distance: f32,
+}
+
+impl Default for LightmapSettings {
+    fn default() -> Self {
+        Self {
+            texels_per_unit: 64,
+            uv_spacing: 0.005,
+            indirect_samples: 0,
+            indirect_bounces: 2,
+            bounce_albedo: 0.5,
+            sky_color: Vector3::default(),
+            max_ray_distance: 100.0,
+        }
+    }
+}
+
+/// Lighting of a single surface, baked into vertex colors.
+#[derive(Default, Clone, Debug, Visit, Reflect)]
+pub struct VertexLightingEntry {
+    /// Color of every vertex of the surface in linear color space.
+    pub colors: Vec<Color>,
+    /// List of lights that were used to generate the colors.
+    pub lights: Vec<Handle<Node>>,
+}
+
+/// An alternative to [`Lightmap`], that stores precomputed lighting in vertex colors instead of textures.
+/// It has much lower memory footprint and does not need secondary texture coordinates, which makes it
+/// suitable for low-end targets (mobile, WebAssembly). Quality of the lighting depends on tessellation of
+/// meshes, large triangles will have blurry lighting and shadows. Use [`VertexLighting::apply`] to write
+/// the colors to the meshes of a scene.
+#[derive(Default, Clone, Debug, Visit, Reflect)]
+pub struct VertexLighting {
+    /// Node handle to per-surface vertex colors mapping.
+    pub map: FxHashMap<Handle<Node>, Vec<VertexLightingEntry>>,
+}
+
 struct WorldVertex {
     world_normal: Vector3<f32>,
     world_position: Vector3<f32>,
@@ -154,6 +224,10 @@ impl Display for ProgressStage {
     }
 }
 
+/// A callback, that is called when lightmap generation progress changes. Receives current stage and
+/// progress percentage of the stage in [0; 100] range. It is called from worker threads.
+pub type ProgressCallback = Box<dyn Fn(ProgressStage, u32) + Send + Sync>;
+
 /// Progress internals.
 #[derive(Default)]
 pub struct ProgressData {
@@ -161,6 +235,7 @@ pub struct ProgressData {
     // Range is [0; max_iterations]
     progress: AtomicU32,
     max_iterations: AtomicU32,
+    callback: Option<ProgressCallback>,
 }
 
 impl ProgressData {
@@ -191,11 +266,22 @@ impl ProgressData {
             .store(max_iterations, atomic::Ordering::SeqCst);
         self.progress.store(0, atomic::Ordering::SeqCst);
         self.stage.store(stage as u32, atomic::Ordering::SeqCst);
+        if let Some(callback) = self.callback.as_ref() {
+            callback(stage, 0);
+        }
     }
 
     /// Advances progress.
     fn advance_progress(&self) {
-        self.progress.fetch_add(1, atomic::Ordering::SeqCst);
+        let previous = self.progress.fetch_add(1, atomic::Ordering::SeqCst);
+        if let Some(callback) = self.callback.as_ref() {
+            let iterations = self.max_iterations.load(atomic::Ordering::SeqCst).max(1);
+            let percent = (previous + 1) * 100 / iterations;
+            // Report only whole percents to not flood the callback.
+            if percent != previous * 100 / iterations {
+                callback(self.stage(), percent);
+            }
+        }
     }
 }
 
@@ -208,6 +294,19 @@ impl ProgressIndicator {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Creates new progress indicator, that calls the given callback every time when the progress
+    /// changes. It is useful when there is no update loop to poll the progress from, for example in
+    /// command line tools.
+    pub fn with_callback<F>(callback: F) -> Self
+    where
+        F: Fn(ProgressStage, u32) + Send + Sync + 'static,
+    {
+        Self(Arc::new(ProgressData {
+            callback: Some(Box::new(callback)),
+            ..Default::default()
+        }))
+    }
 }
 
 impl Deref for ProgressIndicator {
@@ -225,6 +324,10 @@ pub enum LightmapGenerationError {
     Cancelled,
     /// Vertex buffer of a mesh lacks required data.
     InvalidData(VertexFetchError),
+    /// Unable to save generated textures.
+    SaveError(ResourceRegistrationError),
+    /// Unable to apply generated lightmap to a scene.
+    ApplyError(&'static str),
 }
 
 impl Display for LightmapGenerationError {
@@ -236,6 +339,12 @@ impl Display for LightmapGenerationError {
             LightmapGenerationError::InvalidData(v) => {
                 write!(f, "Vertex buffer of a mesh lacks required data {v}.")
             }
+            LightmapGenerationError::SaveError(v) => {
+                write!(f, "Unable to save lightmap textures {v:?}.")
+            }
+            LightmapGenerationError::ApplyError(v) => {
+                write!(f, "Unable to apply lightmap {v}.")
+            }
         }
     }
 }
@@ -400,7 +509,11 @@ impl Lightmap {
         path: P,
         resource_manager: ResourceManager,
     ) -> Result<Lightmap, VisitError> {
-        let mut visitor = Visitor::load_binary(path).await?;
+        let data = resource_manager
+            .resource_io()
+            .load_file(path.as_ref())
+            .await?;
+        let mut visitor = Visitor::load_from_memory(&data)?;
         visitor.blackboard.register(Arc::new(resource_manager));
         let mut lightmap = Lightmap::default();
         lightmap.visit("Lightmap", &mut visitor)?;
@@ -424,12 +537,37 @@ impl Lightmap {
     /// lightmap will be generated, but also it will be slow to generate.
     /// `progress_indicator` allows you to get info about current progress.
     /// `cancellation_token` allows you to stop generation in any time.
+    ///
+    /// This method calculates direct lighting only, use [`Self::bake`] to calculate indirect lighting
+    /// as well.
     pub fn new(
         data: LightmapInputData,
         texels_per_unit: u32,
         uv_spacing: f32,
         cancellation_token: CancellationToken,
         progress_indicator: ProgressIndicator,
+    ) -> Result<Self, LightmapGenerationError> {
+        Self::bake(
+            data,
+            &LightmapSettings {
+                texels_per_unit,
+                uv_spacing,
+                ..Default::default()
+            },
+            cancellation_token,
+            progress_indicator,
+        )
+    }
+
+    /// Generates lightmap for given scene using the given settings. Unlike [`Self::new`], this method
+    /// is able to calculate indirect lighting by path tracing (see [`LightmapSettings::indirect_samples`]).
+    /// This method **automatically** generates secondary texture coordinates! This method is blocking,
+    /// however internally it uses massive parallelism to use all available CPU power efficiently.
+    pub fn bake(
+        data: LightmapInputData,
+        settings: &LightmapSettings,
+        cancellation_token: CancellationToken,
+        progress_indicator: ProgressIndicator,
     ) -> Result<Self, LightmapGenerationError> {
         let LightmapInputData {
             data_set,
@@ -446,78 +584,14 @@ impl Lightmap {
                     Err(LightmapGenerationError::Cancelled)
                 } else {
                     let mut data = data.lock();
-                    let patch = uvgen::generate_uvs(&mut data, uv_spacing)?;
+                    let patch = uvgen::generate_uvs(&mut data, settings.uv_spacing)?;
                     progress_indicator.advance_progress();
                     Ok((patch.data_id, patch))
                 }
             })
             .collect::<Result<FxHashMap<_, _>, LightmapGenerationError>>()?;
 
-        progress_indicator.set_stage(ProgressStage::GeometryCaching, instances.len() as u32);
-
-        instances
-            .par_iter_mut()
-            .map(|instance: &mut Instance| {
-                if cancellation_token.is_cancelled() {
-                    Err(LightmapGenerationError::Cancelled)
-                } else {
-                    let data = instance.source_data.lock();
-
-                    let normal_matrix = instance
-                        .transform
-                        .basis()
-                        .try_inverse()
-                        .map(|m| m.transpose())
-                        .unwrap_or_else(Matrix3::identity);
-
-                    let world_vertices = data
-                        .vertex_buffer
-                        .iter()
-                        .map(|view| {
-                            let world_position = instance
-                                .transform
-                                .transform_point(&Point3::from(
-                                    view.read_3_f32(VertexAttributeUsage::Position).unwrap(),
-                                ))
-                                .coords;
-                            let world_normal = (normal_matrix
-                                * view.read_3_f32(VertexAttributeUsage::Normal).unwrap())
-                            .try_normalize(f32::EPSILON)
-                            .unwrap_or_default();
-                            WorldVertex {
-                                world_normal,
-                                world_position,
-                                second_tex_coord: view
-                                    .read_2_f32(VertexAttributeUsage::TexCoord1)
-                                    .unwrap(),
-                            }
-                        })
-                        .collect::<Vec<_>>();
-
-                    let world_triangles = data
-                        .geometry_buffer
-                        .iter()
-                        .map(|tri| {
-                            [
-                                world_vertices[tri[0] as usize].world_position,
-                                world_vertices[tri[1] as usize].world_position,
-                                world_vertices[tri[2] as usize].world_position,
-                            ]
-                        })
-                        .collect::<Vec<_>>();
-
-                    instance.data = Some(InstanceData {
-                        vertices: world_vertices,
-                        triangles: data.geometry_buffer.triangles_ref().to_vec(),
-                        octree: Octree::new(&world_triangles, 64),
-                    });
-
-                    progress_indicator.advance_progress();
-
-                    Ok(())
-                }
-            })
-            .collect::<Result<(), LightmapGenerationError>>()?;
+        cache_geometry(&mut instances, &cancellation_token, &progress_indicator)?;
 
         progress_indicator.set_stage(ProgressStage::CalculatingLight, instances.len() as u32);
 
@@ -527,7 +601,7 @@ impl Lightmap {
                 return Err(LightmapGenerationError::Cancelled);
             }
 
-            let lightmap = generate_lightmap(instance, &instances, &lights, texels_per_unit);
+            let lightmap = generate_lightmap(instance, &instances, &lights, settings);
             map.entry(instance.owner).or_default().push(LightmapEntry {
                 texture: Some(TextureResource::new_ok(Default::default(), lightmap)),
                 lights: lights.iter().map(|light| light.handle()).collect(),
@@ -564,6 +638,453 @@ impl Lightmap {
         }
         Ok(())
     }
+
+    /// Generates lightmap for every static mesh of the given scene, saves its textures into the given
+    /// folder and applies the lightmap to the scene. Only nodes with [`Mobility::Static`] (both meshes
+    /// and lights) are taken into account. It is a shortcut for [`LightmapInputData::from_scene`],
+    /// [`Self::bake`], [`Self::save_textures`] and [`Graph::set_lightmap`], that could be used in
+    /// command line tools. The scene must be saved afterwards to keep the lightmap. Returns previous
+    /// lightmap of the scene (if any).
+    pub fn bake_scene<P: AsRef<Path>>(
+        scene: &mut Scene,
+        settings: &LightmapSettings,
+        textures_path: P,
+        resource_manager: ResourceManager,
+        cancellation_token: CancellationToken,
+        progress_indicator: ProgressIndicator,
+    ) -> Result<Option<Lightmap>, LightmapGenerationError> {
+        let data = LightmapInputData::from_scene(
+            scene,
+            |_, node| node.mobility() == Mobility::Static,
+            cancellation_token.clone(),
+            progress_indicator.clone(),
+        )?;
+        let lightmap = Self::bake(data, settings, cancellation_token, progress_indicator)?;
+        lightmap
+            .save_textures(textures_path, resource_manager)
+            .map_err(LightmapGenerationError::SaveError)?;
+        scene
+            .graph
+            .set_lightmap(lightmap)
+            .map_err(LightmapGenerationError::ApplyError)
+    }
+}
+
+impl VertexLighting {
+    /// Loads vertex lighting from the given path. The file is read using the resource io of the
+    /// given resource manager, so it works on every platform that the resource manager supports.
+    pub async fn load<P: AsRef<Path>>(
+        path: P,
+        resource_manager: &ResourceManager,
+    ) -> Result<VertexLighting, VisitError> {
+        let data = resource_manager
+            .resource_io()
+            .load_file(path.as_ref())
+            .await?;
+        let mut visitor = Visitor::load_from_memory(&data)?;
+        let mut lighting = VertexLighting::default();
+        lighting.visit("VertexLighting", &mut visitor)?;
+        Ok(lighting)
+    }
+
+    /// Saves vertex lighting to the given file.
+    pub fn save<P: AsRef<Path>>(&mut self, path: P) -> VisitResult {
+        let mut visitor = Visitor::new();
+        self.visit("VertexLighting", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
+    }
+
+    /// Bakes lighting into vertex colors. Unlike [`Lightmap::new`], this method does not modify surface
+    /// data and does not generate secondary texture coordinates. This method is blocking, however
+    /// internally it uses massive parallelism to use all available CPU power efficiently.
+    pub fn new(
+        data: LightmapInputData,
+        cancellation_token: CancellationToken,
+        progress_indicator: ProgressIndicator,
+    ) -> Result<Self, LightmapGenerationError> {
+        let LightmapInputData {
+            mut instances,
+            lights,
+            ..
+        } = data;
+
+        cache_geometry(&mut instances, &cancellation_token, &progress_indicator)?;
+
+        progress_indicator.set_stage(ProgressStage::CalculatingLight, instances.len() as u32);
+
+        let mut map: FxHashMap<Handle<Node>, Vec<VertexLightingEntry>> = FxHashMap::default();
+        for instance in instances.iter() {
+            if cancellation_token.is_cancelled() {
+                return Err(LightmapGenerationError::Cancelled);
+            }
+
+            let colors = instance
+                .data()
+                .vertices
+                .par_iter()
+                .map(|vertex| {
+                    let color = calculate_illumination(
+                        vertex.world_position,
+                        vertex.world_normal,
+                        &instances,
+                        &lights,
+                    );
+                    Color::from(Vector4::new(
+                        color.x.clamp(0.0, 1.0),
+                        color.y.clamp(0.0, 1.0),
+                        color.z.clamp(0.0, 1.0),
+                        1.0,
+                    ))
+                })
+                .collect::<Vec<_>>();
+
+            map.entry(instance.owner)
+                .or_default()
+                .push(VertexLightingEntry {
+                    colors,
+                    lights: lights.iter().map(|light| light.handle()).collect(),
+                });
+
+            progress_indicator.advance_progress();
+        }
+
+        Ok(Self { map })
+    }
+
+    /// Writes vertex colors to the surfaces of the meshes of the given graph. Every affected surface
+    /// gets its own copy of surface data, because surface data could be shared across multiple meshes
+    /// with different lighting. Materials of the surfaces must have `vertexLightingStrength` property
+    /// (the standard shader has it), the property will be set to `1.0`.
+    pub fn apply(&self, graph: &mut Graph) -> Result<(), &'static str> {
+        for (handle, entries) in self.map.iter() {
+            let Some(mesh) = graph
+                .try_get_mut(*handle)
+                .and_then(|node| node.cast_mut::<Mesh>())
+            else {
+                continue;
+            };
+
+            if mesh.surfaces().len() != entries.len() {
+                return Err("failed to apply vertex lighting, surface count mismatch");
+            }
+
+            for (surface, entry) in mesh.surfaces_mut().iter_mut().zip(entries) {
+                let mut data = surface.data_ref().lock().clone();
+
+                if data.vertex_buffer.vertex_count() as usize != entry.colors.len() {
+                    return Err("failed to apply vertex lighting, vertex count mismatch");
+                }
+
+                if !data
+                    .vertex_buffer
+                    .has_attribute(VertexAttributeUsage::Color)
+                {
+                    data.vertex_buffer
+                        .modify()
+                        .add_attribute(
+                            VertexAttributeDescriptor {
+                                usage: VertexAttributeUsage::Color,
+                                data_type: VertexAttributeDataType::U8,
+                                size: 4,
+                                divisor: 0,
+                                shader_location: 7, // Standard shader expects it to be at 7
+                                normalized: true,
+                            },
+                            Vector4::<u8>::new(0, 0, 0, 255),
+                        )
+                        .map_err(|_| "failed to add color attribute")?;
+                }
+
+                for (mut view, color) in data
+                    .vertex_buffer
+                    .modify()
+                    .iter_mut()
+                    .zip(entry.colors.iter())
+                {
+                    view.write_4_u8(
+                        VertexAttributeUsage::Color,
+                        Vector4::new(color.r, color.g, color.b, color.a),
+                    )
+                    .map_err(|_| "failed to write vertex color")?;
+                }
+
+                surface.set_data(SurfaceSharedData::new(data));
+
+                let mut material_state = surface.material().state();
+                if let Some(material) = material_state.data() {
+                    if let Err(e) = material.set_property(
+                        &ImmutableString::new("vertexLightingStrength"),
+                        PropertyValue::Float(1.0),
+                    ) {
+                        Log::writeln(
+                            MessageKind::Error,
+                            format!(
+                                "Failed to enable vertex lighting for surface of {handle}. Reason: {e:?}"
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Settings of light probe baking.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightProbeBakeSettings {
+    /// Amount of rays traced from every probe. More rays gives less noisy results, but baking
+    /// time grows linearly.
+    pub samples: u32,
+    /// Fraction of light that is reflected by surfaces of the scene. The lightmapper does not read
+    /// materials, so the same value is used for every surface.
+    pub bounce_albedo: f32,
+    /// Radiance of rays that did not hit anything.
+    pub sky_color: Vector3<f32>,
+    /// Maximum length of the rays traced from the probes.
+    pub max_distance: f32,
+}
+
+impl Default for LightProbeBakeSettings {
+    fn default() -> Self {
+        Self {
+            samples: 128,
+            bounce_albedo: 0.5,
+            sky_color: Vector3::default(),
+            max_distance: 100.0,
+        }
+    }
+}
+
+/// Description of a light probe volume, that is used for baking. It could be produced from a graph
+/// using [`LightProbeVolumeDefinition::from_graph`] method.
+#[derive(Clone, Debug)]
+pub struct LightProbeVolumeDefinition {
+    /// A handle of the volume node.
+    pub handle: Handle<Node>,
+    /// Amount of probes along each axis.
+    pub resolution: Vector3<u32>,
+    /// World-space positions of the probes.
+    pub positions: Vec<Vector3<f32>>,
+}
+
+impl LightProbeVolumeDefinition {
+    /// Collects every light probe volume of the given graph.
+    pub fn from_graph(graph: &Graph) -> Vec<Self> {
+        graph
+            .pair_iter()
+            .filter_map(|(handle, node)| {
+                node.cast::<LightProbeVolume>()
+                    .map(|volume| LightProbeVolumeDefinition {
+                        handle,
+                        resolution: volume.resolution(),
+                        positions: volume.probe_positions(),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Bakes indirect lighting into light probes of the given volumes. Every probe traces a set of rays
+/// in uniformly distributed directions, every ray that hits a surface gathers direct lighting of
+/// the surface at the hit point (with shadows), which is then projected on spherical harmonics.
+/// Direct lighting is not stored in the probes, since dynamic objects are lit by the light sources
+/// directly. This method is blocking, however internally it uses massive parallelism to use all
+/// available CPU power efficiently.
+pub fn bake_light_probes(
+    data: LightmapInputData,
+    volumes: Vec<LightProbeVolumeDefinition>,
+    settings: &LightProbeBakeSettings,
+    cancellation_token: CancellationToken,
+    progress_indicator: ProgressIndicator,
+) -> Result<Vec<(Handle<Node>, LightProbeData)>, LightmapGenerationError> {
+    let LightmapInputData {
+        mut instances,
+        lights,
+        ..
+    } = data;
+
+    cache_geometry(&mut instances, &cancellation_token, &progress_indicator)?;
+
+    let directions = fibonacci_sphere(settings.samples.max(1));
+
+    progress_indicator.set_stage(
+        ProgressStage::CalculatingLight,
+        volumes.iter().map(|v| v.positions.len() as u32).sum(),
+    );
+
+    let mut baked = Vec::with_capacity(volumes.len());
+    for volume in volumes {
+        let probes = volume
+            .positions
+            .par_iter()
+            .map(|position| {
+                if cancellation_token.is_cancelled() {
+                    return Err(LightmapGenerationError::Cancelled);
+                }
+
+                let samples = directions.iter().map(|direction| {
+                    let ray = Ray::new(*position, direction.scale(settings.max_distance));
+                    let radiance = match trace_closest(&ray, &instances) {
+                        Some((point, mut normal)) => {
+                            // Faces could be hit from both sides.
+                            if normal.dot(direction) > 0.0 {
+                                normal = -normal;
+                            }
+                            // Shift the point off the surface a bit to prevent self-shadowing.
+                            calculate_illumination(
+                                point + normal.scale(0.01),
+                                normal,
+                                &instances,
+                                &lights,
+                            )
+                            .scale(settings.bounce_albedo)
+                        }
+                        None => settings.sky_color,
+                    };
+                    (*direction, radiance)
+                });
+
+                let irradiance = ShIrradiance::from_radiance_samples(samples);
+
+                progress_indicator.advance_progress();
+
+                Ok(irradiance)
+            })
+            .collect::<Result<Vec<_>, LightmapGenerationError>>()?;
+
+        baked.push((
+            volume.handle,
+            LightProbeData {
+                resolution: volume.resolution,
+                probes,
+            },
+        ));
+    }
+
+    Ok(baked)
+}
+
+/// Generates a set of uniformly distributed directions on a unit sphere.
+fn fibonacci_sphere(count: u32) -> Vec<Vector3<f32>> {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+    (0..count)
+        .map(|i| {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+            let radius = (1.0 - y * y).max(0.0).sqrt();
+            let phi = i as f32 * golden_angle;
+            Vector3::new(radius * phi.cos(), y, radius * phi.sin())
+        })
+        .collect()
+}
+
+/// Finds the closest intersection of the ray with the given instances. Returns world-space position
+/// and normal of the intersected triangle.
+fn trace_closest(ray: &Ray, instances: &[Instance]) -> Option<(Vector3<f32>, Vector3<f32>)> {
+    let mut query_buffer = ArrayVec::<Handle<OctreeNode>, 1024>::new();
+    let mut closest_t = f32::MAX;
+    let mut result = None;
+    for instance in instances {
+        let data = instance.data();
+        data.octree.ray_query_static(ray, &mut query_buffer);
+        for &node in query_buffer.iter() {
+            if let OctreeNode::Leaf { indices, .. } = data.octree.node(node) {
+                for &triangle_index in indices {
+                    let triangle = &data.triangles[triangle_index as usize];
+                    let va = data.vertices[triangle[0] as usize].world_position;
+                    let vb = data.vertices[triangle[1] as usize].world_position;
+                    let vc = data.vertices[triangle[2] as usize].world_position;
+                    if let Some((t, pt)) = ray.triangle_intersection(&[va, vb, vc]) {
+                        if t < closest_t {
+                            if let Some(normal) =
+                                (vb - va).cross(&(vc - va)).try_normalize(f32::EPSILON)
+                            {
+                                closest_t = t;
+                                result = Some((pt, normal));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Calculates world-space vertices and builds acceleration structures for every instance.
+fn cache_geometry(
+    instances: &mut [Instance],
+    cancellation_token: &CancellationToken,
+    progress_indicator: &ProgressIndicator,
+) -> Result<(), LightmapGenerationError> {
+    progress_indicator.set_stage(ProgressStage::GeometryCaching, instances.len() as u32);
+
+    instances
+        .par_iter_mut()
+        .map(|instance: &mut Instance| {
+            if cancellation_token.is_cancelled() {
+                Err(LightmapGenerationError::Cancelled)
+            } else {
+                let data = instance.source_data.lock();
+
+                let normal_matrix = instance
+                    .transform
+                    .basis()
+                    .try_inverse()
+                    .map(|m| m.transpose())
+                    .unwrap_or_else(Matrix3::identity);
+
+                let world_vertices = data
+                    .vertex_buffer
+                    .iter()
+                    .map(|view| {
+                        let world_position = instance
+                            .transform
+                            .transform_point(&Point3::from(
+                                view.read_3_f32(VertexAttributeUsage::Position).unwrap(),
+                            ))
+                            .coords;
+                        let world_normal = (normal_matrix
+                            * view.read_3_f32(VertexAttributeUsage::Normal).unwrap())
+                        .try_normalize(f32::EPSILON)
+                        .unwrap_or_default();
+                        WorldVertex {
+                            world_normal,
+                            world_position,
+                            // Vertex lighting does not need secondary texture coordinates.
+                            second_tex_coord: view
+                                .read_2_f32(VertexAttributeUsage::TexCoord1)
+                                .unwrap_or_default(),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let world_triangles = data
+                    .geometry_buffer
+                    .iter()
+                    .map(|tri| {
+                        [
+                            world_vertices[tri[0] as usize].world_position,
+                            world_vertices[tri[1] as usize].world_position,
+                            world_vertices[tri[2] as usize].world_position,
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+
+                instance.data = Some(InstanceData {
+                    vertices: world_vertices,
+                    triangles: data.geometry_buffer.triangles_ref().to_vec(),
+                    octree: Octree::new(&world_triangles, 64),
+                });
+
+                progress_indicator.advance_progress();
+
+                Ok(())
+            }
+        })
+        .collect::<Result<(), LightmapGenerationError>>()
 }
 
 /// Directional light is a light source with parallel rays. Example: Sun.
@@ -772,6 +1293,146 @@ fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
     k * k * (3.0 - 2.0 * k)
 }
 
+/// Calculates illumination of a point with the given world-space position and normal, including
+/// shadows cast by the given instances.
+fn calculate_illumination(
+    world_position: Vector3<f32>,
+    world_normal: Vector3<f32>,
+    other_instances: &[Instance],
+    lights: &[LightDefinition],
+) -> Vector3<f32> {
+    let mut pixel_color = Vector3::default();
+    for light in lights {
+        let (light_color, mut attenuation, light_position) = match light {
+            LightDefinition::Directional(directional) => {
+                let attenuation =
+                    directional.intensity * lambertian(directional.direction, world_normal);
+                (directional.color, attenuation, Vector3::default())
+            }
+            LightDefinition::Spot(spot) => {
+                let d = spot.position - world_position;
+                let distance = d.norm();
+                let light_vec = d.scale(1.0 / distance);
+                let spot_angle_cos = light_vec.dot(&spot.direction);
+                let cone_factor = smoothstep(spot.edge0, spot.edge1, spot_angle_cos);
+                let attenuation = cone_factor
+                    * spot.intensity
+                    * lambertian(light_vec, world_normal)
+                    * distance_attenuation(distance, spot.sqr_distance);
+                (spot.color, attenuation, spot.position)
+            }
+            LightDefinition::Point(point) => {
+                let d = point.position - world_position;
+                let distance = d.norm();
+                let light_vec = d.scale(1.0 / distance);
+                let attenuation = point.intensity
+                    * lambertian(light_vec, world_normal)
+                    * distance_attenuation(distance, point.sqr_radius);
+                (point.color, attenuation, point.position)
+            }
+        };
+        // Shadows
+        if attenuation >= 0.01 {
+            let mut query_buffer = ArrayVec::<Handle<OctreeNode>, 64>::new();
+            let shadow_bias = 0.01;
+            let ray = Ray::from_two_points(light_position, world_position);
+            'outer_loop: for other_instance in other_instances {
+                other_instance
+                    .data()
+                    .octree
+                    .ray_query_static(&ray, &mut query_buffer);
+                for &node in query_buffer.iter() {
+                    match other_instance.data().octree.node(node) {
+                        OctreeNode::Leaf { indices, .. } => {
+                            let other_data = other_instance.data();
+                            for &triangle_index in indices {
+                                let triangle = &other_data.triangles[triangle_index as usize];
+                                let va = other_data.vertices[triangle[0] as usize].world_position;
+                                let vb = other_data.vertices[triangle[1] as usize].world_position;
+                                let vc = other_data.vertices[triangle[2] as usize].world_position;
+                                if let Some(pt) = ray.triangle_intersection_point(&[va, vb, vc]) {
+                                    if ray.origin.metric_distance(&pt) + shadow_bias
+                                        < ray.dir.norm()
+                                    {
+                                        attenuation = 0.0;
+                                        break 'outer_loop;
+                                    }
+                                }
+                            }
+                        }
+                        OctreeNode::Branch { .. } => unreachable!(),
+                    }
+                }
+            }
+        }
+        pixel_color += light_color.scale(attenuation);
+    }
+    pixel_color
+}
+
+/// Returns a random direction in the hemisphere around the given normal. Directions are distributed
+/// proportionally to the cosine of the angle with the normal.
+fn cosine_weighted_direction<R: Rng>(normal: Vector3<f32>, rng: &mut R) -> Vector3<f32> {
+    let helper = if normal.x.abs() > 0.9 {
+        Vector3::y()
+    } else {
+        Vector3::x()
+    };
+    let tangent = normal.cross(&helper).normalize();
+    let binormal = normal.cross(&tangent);
+
+    let phi = 2.0 * std::f32::consts::PI * rng.gen::<f32>();
+    let r2 = rng.gen::<f32>();
+    let r = r2.sqrt();
+
+    (tangent.scale(r * phi.cos()) + binormal.scale(r * phi.sin()) + normal.scale((1.0 - r2).sqrt()))
+        .try_normalize(f32::EPSILON)
+        .unwrap_or(normal)
+}
+
+/// Calculates indirect illumination of a point by tracing random paths from it. Every vertex of a path
+/// gathers direct lighting of the surface it hit.
+fn calculate_indirect_illumination<R: Rng>(
+    world_position: Vector3<f32>,
+    world_normal: Vector3<f32>,
+    instances: &[Instance],
+    lights: &[LightDefinition],
+    settings: &LightmapSettings,
+    rng: &mut R,
+) -> Vector3<f32> {
+    // Shift the points off the surface a bit to prevent self-intersections.
+    let bias = 0.01;
+
+    let mut sum = Vector3::default();
+    for _ in 0..settings.indirect_samples {
+        let mut origin = world_position + world_normal.scale(bias);
+        let mut normal = world_normal;
+        let mut throughput = 1.0;
+        for _ in 0..settings.indirect_bounces.max(1) {
+            let direction = cosine_weighted_direction(normal, rng);
+            let ray = Ray::new(origin, direction.scale(settings.max_ray_distance));
+            match trace_closest(&ray, instances) {
+                Some((point, mut hit_normal)) => {
+                    // Faces could be hit from both sides.
+                    if hit_normal.dot(&direction) > 0.0 {
+                        hit_normal = -hit_normal;
+                    }
+                    throughput *= settings.bounce_albedo;
+                    origin = point + hit_normal.scale(bias);
+                    normal = hit_normal;
+                    sum +=
+                        calculate_illumination(origin, normal, instances, lights).scale(throughput);
+                }
+                None => {
+                    sum += settings.sky_color.scale(throughput);
+                    break;
+                }
+            }
+        }
+    }
+    sum.scale(1.0 / settings.indirect_samples.max(1) as f32)
+}
+
 /// Generates lightmap for given surface data with specified transform.
 ///
 /// # Performance
@@ -783,11 +1444,11 @@ fn generate_lightmap(
     instance: &Instance,
     other_instances: &[Instance],
     lights: &[LightDefinition],
-    texels_per_unit: u32,
+    settings: &LightmapSettings,
 ) -> Texture {
     // We have to re-generate new set of world-space vertices because UV generator
     // may add new vertices on seams.
-    let atlas_size = estimate_size(instance.data(), texels_per_unit);
+    let atlas_size = estimate_size(instance.data(), settings.texels_per_unit);
     let scale = 1.0 / atlas_size as f32;
     let grid = Grid::new(instance.data(), (atlas_size / 32).max(4) as usize);
 
@@ -805,77 +1466,20 @@ fn generate_lightmap(
             let uv = Vector2::new(x as f32 * scale + half_pixel, y as f32 * scale + half_pixel);
 
             if let Some((world_position, world_normal)) = pick(uv, &grid, instance.data(), scale) {
-                let mut pixel_color = Vector3::default();
-                for light in lights {
-                    let (light_color, mut attenuation, light_position) = match light {
-                        LightDefinition::Directional(directional) => {
-                            let attenuation = directional.intensity
-                                * lambertian(directional.direction, world_normal);
-                            (directional.color, attenuation, Vector3::default())
-                        }
-                        LightDefinition::Spot(spot) => {
-                            let d = spot.position - world_position;
-                            let distance = d.norm();
-                            let light_vec = d.scale(1.0 / distance);
-                            let spot_angle_cos = light_vec.dot(&spot.direction);
-                            let cone_factor = smoothstep(spot.edge0, spot.edge1, spot_angle_cos);
-                            let attenuation = cone_factor
-                                * spot.intensity
-                                * lambertian(light_vec, world_normal)
-                                * distance_attenuation(distance, spot.sqr_distance);
-                            (spot.color, attenuation, spot.position)
-                        }
-                        LightDefinition::Point(point) => {
-                            let d = point.position - world_position;
-                            let distance = d.norm();
-                            let light_vec = d.scale(1.0 / distance);
-                            let attenuation = point.intensity
-                                * lambertian(light_vec, world_normal)
-                                * distance_attenuation(distance, point.sqr_radius);
-                            (point.color, attenuation, point.position)
-                        }
-                    };
-                    // Shadows
-                    if attenuation >= 0.01 {
-                        let mut query_buffer = ArrayVec::<Handle<OctreeNode>, 64>::new();
-                        let shadow_bias = 0.01;
-                        let ray = Ray::from_two_points(light_position, world_position);
-                        'outer_loop: for other_instance in other_instances {
-                            other_instance
-                                .data()
-                                .octree
-                                .ray_query_static(&ray, &mut query_buffer);
-                            for &node in query_buffer.iter() {
-                                match other_instance.data().octree.node(node) {
-                                    OctreeNode::Leaf { indices, .. } => {
-                                        let other_data = other_instance.data();
-                                        for &triangle_index in indices {
-                                            let triangle =
-                                                &other_data.triangles[triangle_index as usize];
-                                            let va = other_data.vertices[triangle[0] as usize]
-                                                .world_position;
-                                            let vb = other_data.vertices[triangle[1] as usize]
-                                                .world_position;
-                                            let vc = other_data.vertices[triangle[2] as usize]
-                                                .world_position;
-                                            if let Some(pt) =
-                                                ray.triangle_intersection_point(&[va, vb, vc])
-                                            {
-                                                if ray.origin.metric_distance(&pt) + shadow_bias
-                                                    < ray.dir.norm()
-                                                {
-                                                    attenuation = 0.0;
-                                                    break 'outer_loop;
-                                                }
-                                            }
-                                        }
-                                    }
-                                    OctreeNode::Branch { .. } => unreachable!(),
-                                }
-                            }
-                        }
-                    }
-                    pixel_color += light_color.scale(attenuation);
+                let mut pixel_color =
+                    calculate_illumination(world_position, world_normal, other_instances, lights);
+
+                if settings.indirect_samples > 0 {
+                    // Seed by texel index to make results reproducible.
+                    let mut rng = StdRng::seed_from_u64(i as u64);
+                    pixel_color += calculate_indirect_illumination(
+                        world_position,
+                        world_normal,
+                        other_instances,
+                        lights,
+                        settings,
+                        &mut rng,
+                    );
                 }
 
                 *pixel = Vector4::new(
@@ -991,17 +1595,52 @@ mod test {
         scene::{
             base::BaseBuilder,
             light::{point::PointLightBuilder, BaseLightBuilder},
+            light_probe::LightProbeVolumeBuilder,
             mesh::{
+                buffer::{VertexAttributeUsage, VertexReadTrait},
                 surface::SurfaceSharedData,
                 surface::{SurfaceBuilder, SurfaceData},
-                MeshBuilder,
+                Mesh, MeshBuilder,
             },
             transform::TransformBuilder,
             Scene,
         },
-        utils::lightmap::{Lightmap, LightmapInputData},
+        utils::lightmap::{
+            bake_light_probes, LightProbeVolumeDefinition, Lightmap, LightmapInputData,
+            ProgressIndicator, ProgressStage, VertexLighting,
+        },
     };
-    use std::path::Path;
+    use std::{
+        path::Path,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        },
+    };
+
+    #[test]
+    fn test_progress_callback() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let last_percent = Arc::new(AtomicU32::new(0));
+        let progress_indicator = {
+            let calls = calls.clone();
+            let last_percent = last_percent.clone();
+            ProgressIndicator::with_callback(move |stage, percent| {
+                assert!(stage == ProgressStage::CalculatingLight);
+                calls.fetch_add(1, Ordering::SeqCst);
+                last_percent.store(percent, Ordering::SeqCst);
+            })
+        };
+
+        progress_indicator.set_stage(ProgressStage::CalculatingLight, 1000);
+        for _ in 0..1000 {
+            progress_indicator.advance_progress();
+        }
+
+        // Once for the stage and once per whole percent.
+        assert_eq!(calls.load(Ordering::SeqCst), 101);
+        assert_eq!(last_percent.load(Ordering::SeqCst), 100);
+    }
 
     #[test]
     fn test_generate_lightmap() {
@@ -1050,4 +1689,118 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_generate_vertex_lighting() {
+        let mut scene = Scene::new();
+
+        let mesh = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .build()])
+            .build(&mut scene.graph);
+
+        PointLightBuilder::new(BaseLightBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 2.0, 0.0))
+                    .build(),
+            ),
+        ))
+        .with_radius(4.0)
+        .build(&mut scene.graph);
+
+        scene.graph.update_hierarchical_data();
+
+        let data = LightmapInputData::from_scene(
+            &scene,
+            |_, _| true,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+
+        let lighting = VertexLighting::new(data, Default::default(), Default::default()).unwrap();
+        lighting.apply(&mut scene.graph).unwrap();
+
+        let surface = &scene.graph[mesh].cast::<Mesh>().unwrap().surfaces()[0];
+        let data = surface.data_ref().lock();
+        for view in data.vertex_buffer.iter() {
+            let position = view.read_3_f32(VertexAttributeUsage::Position).unwrap();
+            let normal = view.read_3_f32(VertexAttributeUsage::Normal).unwrap();
+            let color = view.read_4_u8(VertexAttributeUsage::Color).unwrap();
+            // Only the top face is lit by the light above the cube.
+            if normal.y > 0.5 {
+                assert!(color.x > 0, "{position:?}");
+            } else if normal.y < -0.5 {
+                assert_eq!(color.x, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bake_light_probes() {
+        let mut scene = Scene::new();
+
+        // Floor lit by a light above it.
+        MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::new_nonuniform_scaling(&Vector3::new(
+                    10.0, 0.2, 10.0,
+                ))),
+            ))
+            .build()])
+            .build(&mut scene.graph);
+
+        PointLightBuilder::new(BaseLightBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 3.0, 0.0))
+                    .build(),
+            ),
+        ))
+        .with_radius(10.0)
+        .build(&mut scene.graph);
+
+        let volume = LightProbeVolumeBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 1.5, 0.0))
+                    .build(),
+            ),
+        )
+        .with_resolution(Vector3::new(1, 1, 1))
+        .build(&mut scene.graph);
+
+        scene.graph.update_hierarchical_data();
+
+        let data = LightmapInputData::from_scene(
+            &scene,
+            |_, _| true,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+
+        let baked = bake_light_probes(
+            data,
+            LightProbeVolumeDefinition::from_graph(&scene.graph),
+            &Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+
+        assert_eq!(baked.len(), 1);
+        let (handle, data) = &baked[0];
+        assert_eq!(*handle, volume);
+        assert_eq!(data.probes.len(), 1);
+        // Light bounced off the floor comes from below, the sky is black.
+        let probe = data.probes[0];
+        let from_below = probe.evaluate(-Vector3::y());
+        let from_above = probe.evaluate(Vector3::y());
+        assert!(from_below.x > 0.0);
+        assert!(from_below.x > from_above.x);
+    }
 }
@@ -3,10 +3,17 @@
 //! Utilities module provides set of commonly used algorithms.
 
 pub mod astar;
+pub mod batching;
 pub mod behavior;
+pub mod csg;
+pub mod ibl;
 pub mod lightmap;
 pub mod navmesh;
+pub mod procgen;
 pub mod raw_mesh;
+pub mod state_hash;
+pub mod steering;
+pub mod tween;
 pub mod uvgen;
 
 use crate::{
@@ -0,0 +1,225 @@
+//! Dungeon generator, that uses binary space partitioning. See [`BspDungeon`] docs for more info.
+
+use crate::{
+    core::{algebra::Vector2, math::Rect, rand::Rng},
+    utils::procgen::{CellKind, Grid},
+};
+
+/// Settings of [`BspDungeon`] generation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BspSettings {
+    /// Minimal size of a partition (in cells) along each axis. Partitions smaller than twice of this
+    /// value are not split further.
+    pub min_partition_size: u32,
+    /// Maximum depth of the partition tree. The dungeon will have at most `2 ^ max_depth` rooms.
+    pub max_depth: u32,
+    /// Minimal size of a room (in cells) along each axis.
+    pub min_room_size: u32,
+    /// Minimal distance (in cells) between a room and the bounds of its partition. It guarantees that
+    /// rooms do not touch each other.
+    pub room_padding: u32,
+}
+
+impl Default for BspSettings {
+    fn default() -> Self {
+        Self {
+            min_partition_size: 8,
+            max_depth: 5,
+            min_room_size: 4,
+            room_padding: 1,
+        }
+    }
+}
+
+/// A dungeon made of rectangular rooms, connected by corridors. Generation recursively splits the
+/// area of the dungeon in two parts (alternating horizontal and vertical cuts), puts a room with
+/// random size and position in every leaf partition and then connects rooms of sibling partitions with
+/// L-shaped corridors, which guarantees that every room is reachable.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector2, rand::thread_rng},
+///     scene::dim2::tilemap::Tile,
+///     utils::procgen::{
+///         bsp::{BspDungeon, BspSettings},
+///         CellKind,
+///     },
+/// };
+///
+/// let dungeon = BspDungeon::generate(
+///     Vector2::new(64, 64),
+///     &BspSettings::default(),
+///     &mut thread_rng(),
+/// );
+/// let layer = dungeon.grid.to_tile_map_layer("Walls", |_, cell| match cell {
+///     CellKind::Wall => Some(Tile::new(0)),
+///     CellKind::Floor => None,
+/// });
+/// ```
+#[derive(Clone, Debug)]
+pub struct BspDungeon {
+    /// Cells of the dungeon.
+    pub grid: Grid<CellKind>,
+    /// Bounds of every room of the dungeon.
+    pub rooms: Vec<Rect<u32>>,
+    /// Corridors of the dungeon, every corridor is a pair of room indices it connects.
+    pub corridors: Vec<(usize, usize)>,
+}
+
+fn room_center(room: &Rect<u32>) -> Vector2<u32> {
+    room.position + room.size / 2
+}
+
+impl BspDungeon {
+    /// Generates a new dungeon of the given size (in cells).
+    pub fn generate<R: Rng>(size: Vector2<u32>, settings: &BspSettings, rng: &mut R) -> Self {
+        let mut dungeon = Self {
+            grid: Grid::new(size, CellKind::Wall),
+            rooms: Default::default(),
+            corridors: Default::default(),
+        };
+        dungeon.split(Rect::new(0, 0, size.x, size.y), 0, settings, rng);
+        dungeon
+    }
+
+    /// Recursively splits the partition and returns range of indices of the rooms, that were created in
+    /// it.
+    fn split<R: Rng>(
+        &mut self,
+        partition: Rect<u32>,
+        depth: u32,
+        settings: &BspSettings,
+        rng: &mut R,
+    ) -> std::ops::Range<usize> {
+        let min = settings.min_partition_size.max(1);
+        let can_split_x = partition.w() >= min * 2;
+        let can_split_y = partition.h() >= min * 2;
+
+        if depth < settings.max_depth && (can_split_x || can_split_y) {
+            // Prefer to cut the longest side to keep partitions close to squares.
+            let vertical_cut = if can_split_x && can_split_y {
+                if partition.w() == partition.h() {
+                    rng.gen_bool(0.5)
+                } else {
+                    partition.w() > partition.h()
+                }
+            } else {
+                can_split_x
+            };
+
+            let (first, second) = if vertical_cut {
+                let cut = rng.gen_range(min..=partition.w() - min);
+                (
+                    Rect::new(partition.x(), partition.y(), cut, partition.h()),
+                    Rect::new(
+                        partition.x() + cut,
+                        partition.y(),
+                        partition.w() - cut,
+                        partition.h(),
+                    ),
+                )
+            } else {
+                let cut = rng.gen_range(min..=partition.h() - min);
+                (
+                    Rect::new(partition.x(), partition.y(), partition.w(), cut),
+                    Rect::new(
+                        partition.x(),
+                        partition.y() + cut,
+                        partition.w(),
+                        partition.h() - cut,
+                    ),
+                )
+            };
+
+            let first_rooms = self.split(first, depth + 1, settings, rng);
+            let second_rooms = self.split(second, depth + 1, settings, rng);
+
+            if !first_rooms.is_empty() && !second_rooms.is_empty() {
+                let a = rng.gen_range(first_rooms.clone());
+                let b = rng.gen_range(second_rooms.clone());
+                self.dig_corridor(a, b, rng);
+            }
+
+            first_rooms.start.min(second_rooms.start)..first_rooms.end.max(second_rooms.end)
+        } else {
+            let start = self.rooms.len();
+            if let Some(room) = Self::make_room(partition, settings, rng) {
+                self.grid
+                    .fill_rect(room.position, room.size, CellKind::Floor);
+                self.rooms.push(room);
+            }
+            start..self.rooms.len()
+        }
+    }
+
+    fn make_room<R: Rng>(
+        partition: Rect<u32>,
+        settings: &BspSettings,
+        rng: &mut R,
+    ) -> Option<Rect<u32>> {
+        let padding = settings.room_padding;
+        let max_w = partition.w().checked_sub(padding * 2)?;
+        let max_h = partition.h().checked_sub(padding * 2)?;
+        let min_size = settings.min_room_size.max(1);
+        if max_w < min_size || max_h < min_size {
+            return None;
+        }
+        let w = rng.gen_range(min_size..=max_w);
+        let h = rng.gen_range(min_size..=max_h);
+        let x = partition.x() + padding + rng.gen_range(0..=max_w - w);
+        let y = partition.y() + padding + rng.gen_range(0..=max_h - h);
+        Some(Rect::new(x, y, w, h))
+    }
+
+    fn dig_corridor<R: Rng>(&mut self, a: usize, b: usize, rng: &mut R) {
+        let from = room_center(&self.rooms[a]);
+        let to = room_center(&self.rooms[b]);
+
+        // L-shaped corridor, random order of the segments makes the layout less regular.
+        let corner = if rng.gen_bool(0.5) {
+            Vector2::new(to.x, from.y)
+        } else {
+            Vector2::new(from.x, to.y)
+        };
+        for (begin, end) in [(from, corner), (corner, to)] {
+            let min = Vector2::new(begin.x.min(end.x), begin.y.min(end.y));
+            let max = Vector2::new(begin.x.max(end.x), begin.y.max(end.y));
+            self.grid
+                .fill_rect(min, max - min + Vector2::new(1, 1), CellKind::Floor);
+        }
+
+        self.corridors.push((a, b));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::Vector2,
+            rand::{rngs::StdRng, SeedableRng},
+        },
+        utils::procgen::{
+            bsp::{BspDungeon, BspSettings},
+            cave::flood_fill,
+            CellKind,
+        },
+    };
+
+    #[test]
+    fn test_bsp_dungeon_is_connected() {
+        let mut rng = StdRng::seed_from_u64(123);
+        let dungeon = BspDungeon::generate(Vector2::new(64, 48), &BspSettings::default(), &mut rng);
+
+        assert!(dungeon.rooms.len() > 1);
+        assert_eq!(dungeon.corridors.len(), dungeon.rooms.len() - 1);
+
+        // Every room must be reachable from the first one.
+        let reachable = flood_fill(&dungeon.grid, dungeon.rooms[0].position, CellKind::Floor);
+        for room in dungeon.rooms.iter() {
+            assert!(reachable.get(room.position).copied().unwrap_or_default());
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Cave generator, that uses cellular automata. See [`Cave`] docs for more info.
+
+use crate::{
+    core::{algebra::Vector2, rand::Rng},
+    utils::procgen::{CellKind, Grid},
+};
+
+/// Settings of [`Cave`] generation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaveSettings {
+    /// Probability of a cell to be a wall in the initial random noise.
+    pub fill_probability: f32,
+    /// Amount of smoothing iterations of the automaton. More iterations produce smoother walls.
+    pub iterations: u32,
+    /// A cell becomes a wall if amount of walls among its eight neighbours is greater or equal to
+    /// this value, otherwise it becomes a floor.
+    pub wall_threshold: u32,
+    /// Whether to fill every floor region except the largest one with walls. It guarantees that the
+    /// whole cave is reachable.
+    pub keep_largest_region: bool,
+}
+
+impl Default for CaveSettings {
+    fn default() -> Self {
+        Self {
+            fill_probability: 0.45,
+            iterations: 5,
+            wall_threshold: 5,
+            keep_largest_region: true,
+        }
+    }
+}
+
+/// A cave with organic-looking walls. Generation starts from random noise and then repeatedly applies
+/// a cellular automaton rule: a cell becomes a wall if most of its neighbours are walls. Border of the
+/// cave is always a wall.
+#[derive(Clone, Debug)]
+pub struct Cave {
+    /// Cells of the cave.
+    pub grid: Grid<CellKind>,
+}
+
+impl Cave {
+    /// Generates a new cave of the given size (in cells).
+    pub fn generate<R: Rng>(size: Vector2<u32>, settings: &CaveSettings, rng: &mut R) -> Self {
+        let is_border =
+            |p: Vector2<u32>| p.x == 0 || p.y == 0 || p.x + 1 >= size.x || p.y + 1 >= size.y;
+
+        let probability = settings.fill_probability.clamp(0.0, 1.0) as f64;
+        let mut grid = Grid::new(size, CellKind::Wall).map(|position, _| {
+            if is_border(position) || rng.gen_bool(probability) {
+                CellKind::Wall
+            } else {
+                CellKind::Floor
+            }
+        });
+
+        for _ in 0..settings.iterations {
+            grid = grid.map(|position, _| {
+                if is_border(position)
+                    || count_wall_neighbours(&grid, position) >= settings.wall_threshold
+                {
+                    CellKind::Wall
+                } else {
+                    CellKind::Floor
+                }
+            });
+        }
+
+        let mut cave = Self { grid };
+        if settings.keep_largest_region {
+            cave.keep_largest_region();
+        }
+        cave
+    }
+
+    /// Fills every floor region except the largest one with walls.
+    pub fn keep_largest_region(&mut self) {
+        let mut visited = Grid::new(self.grid.size(), false);
+        let mut largest: Option<(usize, Grid<bool>)> = None;
+        for (position, cell) in self.grid.iter() {
+            if *cell != CellKind::Floor || visited.get(position).copied().unwrap_or_default() {
+                continue;
+            }
+            let region = flood_fill(&self.grid, position, CellKind::Floor);
+            let mut area = 0;
+            for (position, _) in region.iter().filter(|(_, filled)| **filled) {
+                visited.set(position, true);
+                area += 1;
+            }
+            if area > largest.as_ref().map(|(area, _)| *area).unwrap_or_default() {
+                largest = Some((area, region));
+            }
+        }
+
+        let Some((_, largest)) = largest else {
+            return;
+        };
+        self.grid = self.grid.map(|position, cell| {
+            if largest.get(position).copied().unwrap_or_default() {
+                *cell
+            } else {
+                CellKind::Wall
+            }
+        });
+    }
+}
+
+fn count_wall_neighbours(grid: &Grid<CellKind>, position: Vector2<u32>) -> u32 {
+    let mut count = 0;
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            let neighbour = Vector2::new(position.x as i32 + dx, position.y as i32 + dy);
+            // Everything outside of the grid is considered a wall.
+            if !grid.is_inside(neighbour)
+                || grid.get(neighbour.map(|v| v as u32)) == Some(&CellKind::Wall)
+            {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Finds every cell of the given kind, that is reachable from the given position by moving in four
+/// directions. Returns a grid of the same size, where such cells are marked with `true`. The result is
+/// empty if the start cell is not of the given kind.
+pub fn flood_fill<T: PartialEq>(grid: &Grid<T>, start: Vector2<u32>, kind: T) -> Grid<bool> {
+    let mut result = Grid::new(grid.size(), false);
+    if grid.get(start) != Some(&kind) {
+        return result;
+    }
+    let mut stack = vec![start];
+    result.set(start, true);
+    while let Some(position) = stack.pop() {
+        for offset in [
+            Vector2::new(1, 0),
+            Vector2::new(-1, 0),
+            Vector2::new(0, 1),
+            Vector2::new(0, -1),
+        ] {
+            let neighbour = position.map(|v| v as i32) + offset;
+            if !grid.is_inside(neighbour) {
+                continue;
+            }
+            let neighbour = neighbour.map(|v| v as u32);
+            if grid.get(neighbour) == Some(&kind) && result.get(neighbour) == Some(&false) {
+                result.set(neighbour, true);
+                stack.push(neighbour);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::Vector2,
+            rand::{rngs::StdRng, SeedableRng},
+        },
+        utils::procgen::{
+            cave::{flood_fill, Cave, CaveSettings},
+            CellKind,
+        },
+    };
+
+    #[test]
+    fn test_cave_has_single_region() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let cave = Cave::generate(Vector2::new(48, 32), &CaveSettings::default(), &mut rng);
+
+        // Border is always solid.
+        for (position, cell) in cave.grid.iter() {
+            if position.x == 0 || position.y == 0 || position.x == 47 || position.y == 31 {
+                assert_eq!(*cell, CellKind::Wall);
+            }
+        }
+
+        let floor = cave
+            .grid
+            .iter()
+            .filter(|(_, cell)| **cell == CellKind::Floor)
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+        assert!(!floor.is_empty());
+
+        let reachable = flood_fill(&cave.grid, floor[0], CellKind::Floor);
+        assert_eq!(reachable.iter().filter(|(_, r)| **r).count(), floor.len());
+    }
+}
@@ -0,0 +1,190 @@
+//! Procedural level generation toolkit. It contains a set of generators, that produce 2D grids of
+//! cells:
+//!
+//! - [`bsp::BspDungeon`] - rectangular rooms connected by corridors, made by binary space partitioning.
+//! - [`cave::Cave`] - organic caves, made by cellular automata.
+//! - [`wfc::WfcSolver`] - wave function collapse solver, that fills a grid with tiles satisfying
+//!   adjacency rules, which could be specified manually or learned from a sample.
+//!
+//! Generated grids could be converted to tile map layers (see [`Grid::to_tile_map_layer`]) or to a
+//! list of prefab placements (see [`Grid::prefab_placements`]), that could be instantiated in a 3D scene
+//! using [`instantiate_placements`].
+
+use crate::{
+    core::{
+        algebra::{UnitQuaternion, Vector2, Vector3},
+        pool::Handle,
+    },
+    resource::model::{ModelResource, ModelResourceExtension},
+    scene::{
+        dim2::tilemap::{Tile, TileMapLayer},
+        node::Node,
+        Scene,
+    },
+};
+
+pub mod bsp;
+pub mod cave;
+pub mod wfc;
+
+/// Kind of a cell, produced by the [`bsp`] and [`cave`] generators.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CellKind {
+    /// Solid cell.
+    #[default]
+    Wall,
+    /// Walkable cell.
+    Floor,
+}
+
+/// A rectangular grid of cells. Position `[0; 0]` corresponds to the top-left corner of the grid, X
+/// axis goes to the right, Y axis goes down (the same as in [`TileMapLayer`]).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    size: Vector2<u32>,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a new grid of the given size, filled with the given value.
+    pub fn new(size: Vector2<u32>, value: T) -> Self {
+        Self {
+            size,
+            cells: vec![value; (size.x * size.y) as usize],
+        }
+    }
+
+    /// Fills every cell of the given rectangle with the given value. Cells outside of the grid are
+    /// ignored.
+    pub fn fill_rect(&mut self, position: Vector2<u32>, size: Vector2<u32>, value: T) {
+        for y in position.y..(position.y + size.y).min(self.size.y) {
+            for x in position.x..(position.x + size.x).min(self.size.x) {
+                self.set(Vector2::new(x, y), value.clone());
+            }
+        }
+    }
+
+    /// Creates a new grid of the same size by applying the given function to every cell.
+    pub fn map<U, F>(&self, mut func: F) -> Grid<U>
+    where
+        F: FnMut(Vector2<u32>, &T) -> U,
+    {
+        Grid {
+            size: self.size,
+            cells: self
+                .iter()
+                .map(|(position, cell)| func(position, cell))
+                .collect(),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Returns size of the grid in cells.
+    pub fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    /// Returns `true` if the position is inside the grid.
+    pub fn is_inside(&self, position: Vector2<i32>) -> bool {
+        position.x >= 0
+            && position.y >= 0
+            && (position.x as u32) < self.size.x
+            && (position.y as u32) < self.size.y
+    }
+
+    fn index(&self, position: Vector2<u32>) -> Option<usize> {
+        if position.x < self.size.x && position.y < self.size.y {
+            Some((position.y * self.size.x + position.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to a cell at the given position.
+    pub fn get(&self, position: Vector2<u32>) -> Option<&T> {
+        self.index(position).map(|i| &self.cells[i])
+    }
+
+    /// Returns a reference to a cell at the given position.
+    pub fn get_mut(&mut self, position: Vector2<u32>) -> Option<&mut T> {
+        self.index(position).map(|i| &mut self.cells[i])
+    }
+
+    /// Puts the value at the given position and returns the previous value. Does nothing if the
+    /// position is out of bounds of the grid.
+    pub fn set(&mut self, position: Vector2<u32>, value: T) -> Option<T> {
+        self.get_mut(position)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// Returns an iterator over every cell of the grid with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Vector2<u32>, &T)> + '_ {
+        let width = self.size.x.max(1);
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Vector2::new(i as u32 % width, i as u32 / width), cell))
+    }
+
+    /// Converts the grid into a tile map layer with the given name. The given function maps a cell to a
+    /// tile, [`None`] means that the position will be empty.
+    pub fn to_tile_map_layer<F>(&self, name: &str, mut func: F) -> TileMapLayer
+    where
+        F: FnMut(Vector2<u32>, &T) -> Option<Tile>,
+    {
+        let mut layer = TileMapLayer::new(name, self.size);
+        for (position, cell) in self.iter() {
+            layer.set_tile(position, func(position, cell));
+        }
+        layer
+    }
+
+    /// Converts the grid into a list of prefab placements. The given function maps a cell to an index of
+    /// a prefab, that should be placed at the position of the cell, [`None`] means that the position will
+    /// be empty.
+    pub fn prefab_placements<F>(&self, mut func: F) -> Vec<PrefabPlacement>
+    where
+        F: FnMut(Vector2<u32>, &T) -> Option<usize>,
+    {
+        self.iter()
+            .filter_map(|(position, cell)| {
+                func(position, cell).map(|prefab| PrefabPlacement { position, prefab })
+            })
+            .collect()
+    }
+}
+
+/// Position of a prefab instance on a grid.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PrefabPlacement {
+    /// Position of a cell of the grid.
+    pub position: Vector2<u32>,
+    /// Index of the prefab.
+    pub prefab: usize,
+}
+
+/// Instantiates prefabs at the given placements. Grid is mapped on the XZ plane of the scene: X axis of
+/// the grid is mapped to X axis of the scene and Y axis of the grid is mapped to Z axis of the scene,
+/// `cell_size` defines size of a cell along these axes. Every prefab is placed at the center of its
+/// cell. Placements with invalid prefab indices are ignored. Returns handles of the instances.
+pub fn instantiate_placements(
+    placements: &[PrefabPlacement],
+    prefabs: &[ModelResource],
+    cell_size: Vector2<f32>,
+    scene: &mut Scene,
+) -> Vec<Handle<Node>> {
+    placements
+        .iter()
+        .filter_map(|placement| {
+            prefabs.get(placement.prefab).map(|prefab| {
+                let position = Vector3::new(
+                    (placement.position.x as f32 + 0.5) * cell_size.x,
+                    0.0,
+                    (placement.position.y as f32 + 0.5) * cell_size.y,
+                );
+                prefab.instantiate_at(scene, position, UnitQuaternion::identity())
+            })
+        })
+        .collect()
+}
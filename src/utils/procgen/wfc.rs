@@ -0,0 +1,391 @@
+//! Wave function collapse solver. See [`WfcSolver`] docs for more info.
+
+use crate::{
+    core::{algebra::Vector2, rand::Rng},
+    utils::procgen::Grid,
+};
+use std::fmt::{Display, Formatter};
+
+/// Direction from a cell to its neighbour.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Negative Y.
+    Up,
+    /// Positive X.
+    Right,
+    /// Positive Y.
+    Down,
+    /// Negative X.
+    Left,
+}
+
+impl Direction {
+    /// All directions.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Returns grid offset of the direction.
+    pub fn offset(self) -> Vector2<i32> {
+        match self {
+            Direction::Up => Vector2::new(0, -1),
+            Direction::Right => Vector2::new(1, 0),
+            Direction::Down => Vector2::new(0, 1),
+            Direction::Left => Vector2::new(-1, 0),
+        }
+    }
+
+    /// Returns opposite direction.
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Set of rules for [`WfcSolver`]. Tiles are identified by their indices in `0..tile_count` range,
+/// which could be used as indices of tiles in a tile set or indices of prefabs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WfcRules {
+    weights: Vec<f32>,
+    // For each tile and each direction - a set of tiles, that are allowed to be placed there.
+    adjacency: Vec<[Vec<bool>; 4]>,
+}
+
+impl WfcRules {
+    /// Creates new rules for the given amount of tiles. Every tile has unit weight and no tiles are
+    /// allowed to be adjacent.
+    pub fn new(tile_count: usize) -> Self {
+        Self {
+            weights: vec![1.0; tile_count],
+            adjacency: (0..tile_count)
+                .map(|_| std::array::from_fn(|_| vec![false; tile_count]))
+                .collect(),
+        }
+    }
+
+    /// Learns rules from a sample grid of tile indices: every pair of adjacent tiles of the sample is
+    /// allowed and weights are proportional to tile frequencies. Tile count is defined by the maximum
+    /// tile index of the sample.
+    pub fn from_sample(sample: &Grid<usize>) -> Self {
+        let tile_count = sample.iter().map(|(_, t)| *t + 1).max().unwrap_or_default();
+        let mut rules = Self::new(tile_count);
+        rules.weights.iter_mut().for_each(|w| *w = 0.0);
+        for (position, &tile) in sample.iter() {
+            rules.weights[tile] += 1.0;
+            for direction in Direction::ALL {
+                let neighbour = position.map(|v| v as i32) + direction.offset();
+                if sample.is_inside(neighbour) {
+                    if let Some(&other) = sample.get(neighbour.map(|v| v as u32)) {
+                        rules.allow(tile, direction, other);
+                    }
+                }
+            }
+        }
+        rules
+    }
+
+    /// Returns amount of tiles.
+    pub fn tile_count(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Sets relative frequency of the tile. Tiles with zero weight are never picked by the solver
+    /// on their own, but could still be placed via [`WfcSolver::constrain`].
+    pub fn set_weight(&mut self, tile: usize, weight: f32) {
+        if let Some(w) = self.weights.get_mut(tile) {
+            *w = weight.max(0.0);
+        }
+    }
+
+    /// Allows `neighbour` tile to be placed in the given direction from `tile`. The reverse rule is
+    /// added as well.
+    pub fn allow(&mut self, tile: usize, direction: Direction, neighbour: usize) {
+        let count = self.tile_count();
+        if tile < count && neighbour < count {
+            self.adjacency[tile][direction.index()][neighbour] = true;
+            self.adjacency[neighbour][direction.opposite().index()][tile] = true;
+        }
+    }
+
+    /// Allows the tile to be placed next to itself in every direction.
+    pub fn allow_self(&mut self, tile: usize) {
+        for direction in Direction::ALL {
+            self.allow(tile, direction, tile);
+        }
+    }
+
+    /// Returns `true` if `neighbour` tile is allowed in the given direction from `tile`.
+    pub fn is_allowed(&self, tile: usize, direction: Direction, neighbour: usize) -> bool {
+        self.adjacency
+            .get(tile)
+            .and_then(|a| a[direction.index()].get(neighbour))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// An error, that may occur during [`WfcSolver::solve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WfcError {
+    /// The rules have no tiles.
+    InvalidRules,
+    /// The solver was unable to find a solution in the given amount of attempts.
+    Contradiction,
+}
+
+impl Display for WfcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WfcError::InvalidRules => write!(f, "Rules have no tiles."),
+            WfcError::Contradiction => write!(
+                f,
+                "Unable to find a solution, that satisfies the rules and the constraints."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WfcError {}
+
+/// Wave function collapse solver fills a grid with tiles, so that every pair of adjacent tiles satisfies
+/// adjacency rules (see [`WfcRules`]). On each step, the solver picks a cell with the least amount of
+/// possible tiles, picks one of them randomly (with respect to tile weights) and then removes
+/// impossible tiles from the rest of the grid. If some cell runs out of possible tiles, the attempt
+/// is restarted from scratch.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector2, rand::thread_rng},
+///     utils::procgen::wfc::{Direction, WfcRules, WfcSolver},
+/// };
+///
+/// // 0 - water, 1 - sand, 2 - grass. Water can't be next to grass.
+/// let mut rules = WfcRules::new(3);
+/// for tile in 0..3 {
+///     rules.allow_self(tile);
+/// }
+/// for direction in Direction::ALL {
+///     rules.allow(0, direction, 1);
+///     rules.allow(1, direction, 2);
+/// }
+///
+/// let mut solver = WfcSolver::new(rules, Vector2::new(32, 32));
+/// // Force water in the middle.
+/// solver.constrain(Vector2::new(16, 16), 0);
+/// let tiles = solver.solve(10, &mut thread_rng()).unwrap();
+/// ```
+pub struct WfcSolver {
+    rules: WfcRules,
+    size: Vector2<u32>,
+    constraints: Vec<(Vector2<u32>, usize)>,
+}
+
+impl WfcSolver {
+    /// Creates a new solver for a grid of the given size.
+    pub fn new(rules: WfcRules, size: Vector2<u32>) -> Self {
+        Self {
+            rules,
+            size,
+            constraints: Default::default(),
+        }
+    }
+
+    /// Forces the given tile at the given position. Constraints outside of the grid are ignored.
+    pub fn constrain(&mut self, position: Vector2<u32>, tile: usize) {
+        if position.x < self.size.x && position.y < self.size.y {
+            self.constraints.push((position, tile));
+        }
+    }
+
+    /// Tries to solve the grid at most `max_attempts` times. Returns a grid of tile indices on success.
+    pub fn solve<R: Rng>(&self, max_attempts: usize, rng: &mut R) -> Result<Grid<usize>, WfcError> {
+        if self.rules.tile_count() == 0 {
+            return Err(WfcError::InvalidRules);
+        }
+        for _ in 0..max_attempts.max(1) {
+            if let Some(result) = self.try_solve(rng) {
+                return Ok(result);
+            }
+        }
+        Err(WfcError::Contradiction)
+    }
+
+    fn try_solve<R: Rng>(&self, rng: &mut R) -> Option<Grid<usize>> {
+        let tile_count = self.rules.tile_count();
+        let mut wave = Grid::new(self.size, vec![true; tile_count]);
+
+        for &(position, tile) in self.constraints.iter() {
+            let cell = wave.get_mut(position)?;
+            for (i, possible) in cell.iter_mut().enumerate() {
+                *possible &= i == tile;
+            }
+            self.propagate(&mut wave, position)?;
+        }
+
+        loop {
+            // Find a cell with the least amount of options, ties are broken randomly.
+            let mut best: Option<(Vector2<u32>, usize)> = None;
+            let mut ties = 0;
+            for (position, cell) in wave.iter() {
+                let options = cell.iter().filter(|p| **p).count();
+                match options {
+                    0 => return None,
+                    1 => continue,
+                    _ => (),
+                }
+                match best {
+                    Some((_, best_options)) if options > best_options => (),
+                    Some((_, best_options)) if options == best_options => {
+                        ties += 1;
+                        if rng.gen_range(0..ties + 1) == 0 {
+                            best = Some((position, options));
+                        }
+                    }
+                    _ => {
+                        best = Some((position, options));
+                        ties = 0;
+                    }
+                }
+            }
+
+            let Some((position, _)) = best else {
+                break;
+            };
+
+            let cell = wave.get_mut(position)?;
+            let tile = self.pick_tile(cell, rng);
+            for (i, possible) in cell.iter_mut().enumerate() {
+                *possible = i == tile;
+            }
+            self.propagate(&mut wave, position)?;
+        }
+
+        Some(wave.map(|_, cell| cell.iter().position(|p| *p).unwrap_or_default()))
+    }
+
+    fn pick_tile<R: Rng>(&self, cell: &[bool], rng: &mut R) -> usize {
+        let candidates = cell
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| p.then_some(i))
+            .collect::<Vec<_>>();
+        let total = candidates
+            .iter()
+            .map(|t| self.rules.weights[*t])
+            .sum::<f32>();
+        if total <= 0.0 {
+            return candidates[rng.gen_range(0..candidates.len())];
+        }
+        let mut value = rng.gen_range(0.0..total);
+        for &tile in candidates.iter() {
+            let weight = self.rules.weights[tile];
+            if value < weight {
+                return tile;
+            }
+            value -= weight;
+        }
+        *candidates.last().unwrap()
+    }
+
+    /// Removes tiles, that can't be placed next to the changed cell, and repeats it for every affected
+    /// cell. Returns `None` on contradiction.
+    fn propagate(&self, wave: &mut Grid<Vec<bool>>, start: Vector2<u32>) -> Option<()> {
+        let tile_count = self.rules.tile_count();
+        let mut stack = vec![start];
+        while let Some(position) = stack.pop() {
+            let current = wave.get(position)?.clone();
+            if !current.contains(&true) {
+                return None;
+            }
+            for direction in Direction::ALL {
+                let neighbour = position.map(|v| v as i32) + direction.offset();
+                if !wave.is_inside(neighbour) {
+                    continue;
+                }
+                let neighbour = neighbour.map(|v| v as u32);
+                let neighbour_cell = wave.get_mut(neighbour)?;
+                let mut changed = false;
+                for (other, possible) in neighbour_cell.iter_mut().enumerate() {
+                    if *possible
+                        && !(0..tile_count)
+                            .any(|t| current[t] && self.rules.is_allowed(t, direction, other))
+                    {
+                        *possible = false;
+                        changed = true;
+                    }
+                }
+                if changed {
+                    stack.push(neighbour);
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::Vector2,
+            rand::{rngs::StdRng, SeedableRng},
+        },
+        utils::procgen::{
+            wfc::{Direction, WfcError, WfcRules, WfcSolver},
+            Grid,
+        },
+    };
+
+    #[test]
+    fn test_wfc_satisfies_rules() {
+        // Checkerboard is the only valid solution.
+        let mut rules = WfcRules::new(2);
+        for direction in Direction::ALL {
+            rules.allow(0, direction, 1);
+        }
+        let mut solver = WfcSolver::new(rules, Vector2::new(8, 8));
+        solver.constrain(Vector2::new(0, 0), 1);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let result = solver.solve(1, &mut rng).unwrap();
+        for (position, tile) in result.iter() {
+            assert_eq!(*tile, ((position.x + position.y + 1) % 2) as usize);
+        }
+
+        // Conflicting constraints.
+        solver.constrain(Vector2::new(1, 0), 1);
+        assert_eq!(solver.solve(3, &mut rng), Err(WfcError::Contradiction));
+
+        // Rules learned from a sample reproduce its adjacency.
+        let mut sample = Grid::new(Vector2::new(4, 4), 0);
+        sample.fill_rect(Vector2::new(0, 2), Vector2::new(4, 2), 1);
+        let rules = WfcRules::from_sample(&sample);
+        assert!(rules.is_allowed(0, Direction::Down, 1));
+        assert!(!rules.is_allowed(0, Direction::Up, 1));
+        let result = WfcSolver::new(rules.clone(), Vector2::new(16, 16))
+            .solve(10, &mut rng)
+            .unwrap();
+        for (position, &tile) in result.iter() {
+            for direction in Direction::ALL {
+                let neighbour = position.map(|v| v as i32) + direction.offset();
+                if result.is_inside(neighbour) {
+                    let other = *result.get(neighbour.map(|v| v as u32)).unwrap();
+                    assert!(rules.is_allowed(tile, direction, other));
+                }
+            }
+        }
+    }
+}
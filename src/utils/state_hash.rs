@@ -0,0 +1,112 @@
+//! Platform-independent hashing of a world state. It could be used to check whether a simulation
+//! is deterministic - two runs with the same input must produce the same hashes on every frame.
+//! See [`crate::scene::graph::Graph::state_hash`] and [`crate::engine::Engine::step_headless`].
+
+use crate::core::algebra::{Matrix4, Vector2, Vector3};
+
+/// A hasher, that produces the same values on every platform. Unlike [`std::hash::Hasher`]
+/// implementations, it does not depend on pointer width or endianness of a target platform and
+/// it does not use random seeds. It uses 64-bit FNV-1a algorithm internally.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateHasher {
+    state: u64,
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateHasher {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    /// Creates a new hasher.
+    pub fn new() -> Self {
+        Self {
+            state: Self::OFFSET_BASIS,
+        }
+    }
+
+    /// Writes the given bytes.
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Writes the given boolean value.
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_bytes(&[value as u8]);
+    }
+
+    /// Writes the given 32-bit integer.
+    pub fn write_u32(&mut self, value: u32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    /// Writes the given 64-bit integer.
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    /// Writes the given real number. Numbers are compared bitwise, which means that even the
+    /// smallest difference will produce a different hash.
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_u32(value.to_bits());
+    }
+
+    /// Writes the given slice of real numbers.
+    pub fn write_f32_slice(&mut self, values: &[f32]) {
+        for value in values {
+            self.write_f32(*value);
+        }
+    }
+
+    /// Writes the given two-dimensional vector.
+    pub fn write_vector2(&mut self, value: &Vector2<f32>) {
+        self.write_f32_slice(value.as_slice());
+    }
+
+    /// Writes the given three-dimensional vector.
+    pub fn write_vector3(&mut self, value: &Vector3<f32>) {
+        self.write_f32_slice(value.as_slice());
+    }
+
+    /// Writes the given matrix.
+    pub fn write_matrix4(&mut self, value: &Matrix4<f32>) {
+        self.write_f32_slice(value.as_slice());
+    }
+
+    /// Writes the given string.
+    pub fn write_str(&mut self, value: &str) {
+        self.write_u64(value.len() as u64);
+        self.write_bytes(value.as_bytes());
+    }
+
+    /// Returns the hash of the values that were written so far.
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::state_hash::StateHasher;
+
+    #[test]
+    fn test_state_hasher() {
+        // Reference value of FNV-1a for "a".
+        let mut hasher = StateHasher::new();
+        hasher.write_bytes(b"a");
+        assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+
+        let mut a = StateHasher::new();
+        a.write_f32(0.0);
+        let mut b = StateHasher::new();
+        b.write_f32(-0.0);
+        assert_ne!(a.finish(), b.finish());
+    }
+}
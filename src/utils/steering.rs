@@ -0,0 +1,495 @@
+//! Steering behaviors for autonomous agents, such as birds, fish or swarm enemies. See [`SteeringAgent`]
+//! and [`Flock`] docs for more info.
+//!
+//! All behaviors return a steering force (desired change of velocity), that could be weighted and
+//! summed with other forces, and then applied to an agent using [`SteeringAgent::apply_force`].
+//! Behaviors are based on "Steering Behaviors For Autonomous Characters" by Craig W. Reynolds.
+
+use crate::{
+    core::{algebra::Vector3, reflect::prelude::*, visitor::prelude::*},
+    rand::Rng,
+};
+use fxhash::FxHashMap;
+
+/// A point mass, that moves by steering forces.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct SteeringAgent {
+    /// Current position of the agent.
+    pub position: Vector3<f32>,
+    /// Current velocity of the agent.
+    pub velocity: Vector3<f32>,
+    /// Maximum speed of the agent.
+    #[reflect(min_value = 0.0)]
+    pub max_speed: f32,
+    /// Maximum length of a steering force, that could be applied to the agent. Lower values makes
+    /// the agent turn slower.
+    #[reflect(min_value = 0.0)]
+    pub max_force: f32,
+}
+
+impl Default for SteeringAgent {
+    fn default() -> Self {
+        Self {
+            position: Default::default(),
+            velocity: Default::default(),
+            max_speed: 2.0,
+            max_force: 4.0,
+        }
+    }
+}
+
+fn truncate(v: Vector3<f32>, max_length: f32) -> Vector3<f32> {
+    let length = v.norm();
+    if length > max_length && length > f32::EPSILON {
+        v.scale(max_length / length)
+    } else {
+        v
+    }
+}
+
+impl SteeringAgent {
+    /// Creates new agent at the given position.
+    pub fn new(position: Vector3<f32>, max_speed: f32, max_force: f32) -> Self {
+        Self {
+            position,
+            velocity: Default::default(),
+            max_speed,
+            max_force,
+        }
+    }
+
+    /// Returns normalized direction of the movement of the agent, or `None` if the agent does not move.
+    pub fn heading(&self) -> Option<Vector3<f32>> {
+        self.velocity.try_normalize(f32::EPSILON)
+    }
+
+    /// Applies the given steering force (clamped to [`Self::max_force`]) to the agent and moves it.
+    /// Resulting velocity is clamped to [`Self::max_speed`].
+    pub fn apply_force(&mut self, force: Vector3<f32>, dt: f32) {
+        let force = truncate(force, self.max_force);
+        self.velocity = truncate(self.velocity + force.scale(dt), self.max_speed);
+        self.position += self.velocity.scale(dt);
+    }
+
+    /// Returns a force, that steers the agent towards the target at full speed.
+    pub fn seek(&self, target: Vector3<f32>) -> Vector3<f32> {
+        let desired = (target - self.position)
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_default()
+            .scale(self.max_speed);
+        desired - self.velocity
+    }
+
+    /// Returns a force, that steers the agent away from the threat, if the threat is closer than
+    /// `panic_distance`.
+    pub fn flee(&self, threat: Vector3<f32>, panic_distance: f32) -> Vector3<f32> {
+        let offset = self.position - threat;
+        if offset.norm_squared() > panic_distance * panic_distance {
+            return Vector3::default();
+        }
+        let desired = offset
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_default()
+            .scale(self.max_speed);
+        desired - self.velocity
+    }
+
+    /// Returns a force, that steers the agent towards the target and slows it down smoothly when it
+    /// is closer than `slowing_radius`, so the agent stops at the target.
+    pub fn arrive(&self, target: Vector3<f32>, slowing_radius: f32) -> Vector3<f32> {
+        let offset = target - self.position;
+        let distance = offset.norm();
+        if distance <= f32::EPSILON {
+            return -self.velocity;
+        }
+        let speed = if distance < slowing_radius {
+            self.max_speed * distance / slowing_radius
+        } else {
+            self.max_speed
+        };
+        offset.scale(speed / distance) - self.velocity
+    }
+
+    /// Returns a force, that keeps the agent away from its neighbours, that are closer than `radius`.
+    /// Closer neighbours push the agent stronger.
+    pub fn separation<'a>(
+        &self,
+        neighbours: impl IntoIterator<Item = &'a SteeringAgent>,
+        radius: f32,
+    ) -> Vector3<f32> {
+        let mut force = Vector3::default();
+        for neighbour in neighbours {
+            let offset = self.position - neighbour.position;
+            let distance = offset.norm();
+            if distance > f32::EPSILON && distance < radius {
+                force += offset.scale((radius - distance) / (radius * distance));
+            }
+        }
+        force.scale(self.max_speed)
+    }
+
+    /// Returns a force, that aligns velocity of the agent with average velocity of its neighbours.
+    pub fn alignment<'a>(
+        &self,
+        neighbours: impl IntoIterator<Item = &'a SteeringAgent>,
+    ) -> Vector3<f32> {
+        let mut sum = Vector3::default();
+        let mut count = 0;
+        for neighbour in neighbours {
+            sum += neighbour.velocity;
+            count += 1;
+        }
+        if count == 0 {
+            return Vector3::default();
+        }
+        sum.scale(1.0 / count as f32) - self.velocity
+    }
+
+    /// Returns a force, that steers the agent towards the center of mass of its neighbours.
+    pub fn cohesion<'a>(
+        &self,
+        neighbours: impl IntoIterator<Item = &'a SteeringAgent>,
+    ) -> Vector3<f32> {
+        let mut sum = Vector3::default();
+        let mut count = 0;
+        for neighbour in neighbours {
+            sum += neighbour.position;
+            count += 1;
+        }
+        if count == 0 {
+            return Vector3::default();
+        }
+        self.seek(sum.scale(1.0 / count as f32))
+    }
+}
+
+/// Wander behavior makes an agent move randomly, but smoothly. It projects a circle in front of the
+/// agent and moves a target point on the circle by a small random amount each update.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct Wander {
+    /// Radius of the wander circle. Larger values make turns sharper.
+    #[reflect(min_value = 0.0)]
+    pub radius: f32,
+    /// Distance from the agent to the center of the wander circle.
+    #[reflect(min_value = 0.0)]
+    pub distance: f32,
+    /// Maximum random displacement of the target per second.
+    #[reflect(min_value = 0.0)]
+    pub jitter: f32,
+    /// Whether the agent is allowed to wander vertically or not. Should be `false` for ground
+    /// creatures and fish that keep depth.
+    pub vertical: bool,
+    #[reflect(hidden)]
+    target: Vector3<f32>,
+}
+
+impl Default for Wander {
+    fn default() -> Self {
+        Self {
+            radius: 1.0,
+            distance: 2.0,
+            jitter: 4.0,
+            vertical: false,
+            target: Vector3::new(0.0, 0.0, 1.0),
+        }
+    }
+}
+
+impl Wander {
+    /// Returns a wander force for the given agent.
+    pub fn steer<R: Rng>(&mut self, agent: &SteeringAgent, dt: f32, rng: &mut R) -> Vector3<f32> {
+        let jitter = self.jitter * dt;
+        let mut displacement = Vector3::new(
+            rng.gen_range(-1.0..=1.0),
+            rng.gen_range(-1.0..=1.0),
+            rng.gen_range(-1.0..=1.0),
+        )
+        .scale(jitter);
+        if !self.vertical {
+            displacement.y = 0.0;
+            self.target.y = 0.0;
+        }
+        self.target = (self.target + displacement)
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::z);
+
+        let heading = agent.heading().unwrap_or_else(Vector3::z);
+        let circle_center = agent.position + heading.scale(self.distance);
+        agent.seek(circle_center + self.target.scale(self.radius))
+    }
+}
+
+/// Uniform grid, that allows to find neighbours in a radius without checking every pair of agents.
+/// Cell size should be close to the search radius.
+#[derive(Clone, Debug)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: FxHashMap<Vector3<i32>, Vec<usize>>,
+}
+
+impl SpatialGrid {
+    /// Creates new empty grid with the given cell size.
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            cells: Default::default(),
+        }
+    }
+
+    fn cell(&self, position: Vector3<f32>) -> Vector3<i32> {
+        position.map(|c| (c / self.cell_size).floor() as i32)
+    }
+
+    /// Removes every item from the grid, keeping allocated memory.
+    pub fn clear(&mut self) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+    }
+
+    /// Adds an item with the given index at the given position.
+    pub fn insert(&mut self, index: usize, position: Vector3<f32>) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push(index);
+    }
+
+    /// Fills the buffer with indices of the items, that are within the given radius, using positions
+    /// from the `positions` function. The buffer is cleared first.
+    pub fn query<F>(
+        &self,
+        position: Vector3<f32>,
+        radius: f32,
+        positions: F,
+        buffer: &mut Vec<usize>,
+    ) where
+        F: Fn(usize) -> Vector3<f32>,
+    {
+        buffer.clear();
+        let min = self.cell(position.add_scalar(-radius));
+        let max = self.cell(position.add_scalar(radius));
+        let radius_sqr = radius * radius;
+        for z in min.z..=max.z {
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    if let Some(cell) = self.cells.get(&Vector3::new(x, y, z)) {
+                        buffer.extend(cell.iter().copied().filter(|&index| {
+                            (positions(index) - position).norm_squared() <= radius_sqr
+                        }));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Weights and radii of flocking behaviors.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct FlockingSettings {
+    /// Radius in which other agents are considered as neighbours.
+    #[reflect(min_value = 0.0)]
+    pub neighbour_radius: f32,
+    /// Radius in which other agents push the agent away.
+    #[reflect(min_value = 0.0)]
+    pub separation_radius: f32,
+    /// Weight of the separation force.
+    pub separation: f32,
+    /// Weight of the alignment force.
+    pub alignment: f32,
+    /// Weight of the cohesion force.
+    pub cohesion: f32,
+}
+
+impl Default for FlockingSettings {
+    fn default() -> Self {
+        Self {
+            neighbour_radius: 3.0,
+            separation_radius: 1.0,
+            separation: 1.5,
+            alignment: 1.0,
+            cohesion: 1.0,
+        }
+    }
+}
+
+/// A group of agents, that move together (boids). Every update, each agent is steered by separation,
+/// alignment and cohesion forces from its neighbours, that are found using [`SpatialGrid`], plus an
+/// optional external force (seek, wander, etc.) supplied by the caller.
+///
+/// ## Example
+///
+/// A script, that moves a school of fish nodes towards a point:
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{graph::Graph, node::Node},
+///     utils::steering::{Flock, SteeringAgent},
+/// };
+///
+/// struct School {
+///     flock: Flock,
+///     fish: Vec<Handle<Node>>,
+///     goal: Vector3<f32>,
+/// }
+///
+/// impl School {
+///     fn update(&mut self, graph: &mut Graph, dt: f32) {
+///         let goal = self.goal;
+///         self.flock
+///             .update(dt, |_, agent: &SteeringAgent| agent.arrive(goal, 5.0));
+///
+///         for (agent, fish) in self.flock.agents.iter().zip(self.fish.iter()) {
+///             graph[*fish]
+///                 .local_transform_mut()
+///                 .set_position(agent.position);
+///         }
+///     }
+/// }
+/// ```
+///
+/// The same forces could be used with [`crate::utils::navmesh::NavmeshAgent`] - seek its
+/// [`steering_target`](crate::utils::navmesh::NavmeshAgent::steering_target) instead of the final
+/// target and add separation to prevent agents from bumping into each other.
+#[derive(Clone, Debug, Visit, Reflect)]
+pub struct Flock {
+    /// Agents of the flock.
+    pub agents: Vec<SteeringAgent>,
+    /// Flocking settings.
+    pub settings: FlockingSettings,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    grid: Option<SpatialGrid>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    neighbours: Vec<usize>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    forces: Vec<Vector3<f32>>,
+}
+
+impl Default for Flock {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl Flock {
+    /// Creates new empty flock with the given settings.
+    pub fn new(settings: FlockingSettings) -> Self {
+        Self {
+            agents: Default::default(),
+            settings,
+            grid: None,
+            neighbours: Default::default(),
+            forces: Default::default(),
+        }
+    }
+
+    /// Calculates flocking force for the agent with the given index, using the given list of
+    /// neighbour indices.
+    fn flocking_force(&self, index: usize) -> Vector3<f32> {
+        let agent = &self.agents[index];
+        let neighbours = || {
+            self.neighbours
+                .iter()
+                .filter(move |&&i| i != index)
+                .map(|&i| &self.agents[i])
+        };
+        agent
+            .separation(neighbours(), self.settings.separation_radius)
+            .scale(self.settings.separation)
+            + agent.alignment(neighbours()).scale(self.settings.alignment)
+            + agent.cohesion(neighbours()).scale(self.settings.cohesion)
+    }
+
+    /// Moves every agent of the flock. `external_force` is called for every agent and its result is
+    /// added to the flocking forces; return zero vector if no additional steering is needed.
+    pub fn update<F>(&mut self, dt: f32, mut external_force: F)
+    where
+        F: FnMut(usize, &SteeringAgent) -> Vector3<f32>,
+    {
+        let radius = self.settings.neighbour_radius;
+        let mut grid = match self.grid.take() {
+            Some(grid) if grid.cell_size == radius.max(f32::EPSILON) => grid,
+            _ => SpatialGrid::new(radius),
+        };
+        grid.clear();
+        for (index, agent) in self.agents.iter().enumerate() {
+            grid.insert(index, agent.position);
+        }
+
+        // Calculate all forces first, so every agent will see the same state of the flock.
+        let mut forces = std::mem::take(&mut self.forces);
+        forces.clear();
+        for index in 0..self.agents.len() {
+            let position = self.agents[index].position;
+            let mut neighbours = std::mem::take(&mut self.neighbours);
+            grid.query(
+                position,
+                radius,
+                |i| self.agents[i].position,
+                &mut neighbours,
+            );
+            self.neighbours = neighbours;
+            forces.push(self.flocking_force(index) + external_force(index, &self.agents[index]));
+        }
+
+        for (agent, force) in self.agents.iter_mut().zip(forces.iter()) {
+            agent.apply_force(*force, dt);
+        }
+
+        self.forces = forces;
+        self.grid = Some(grid);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        utils::steering::{Flock, SpatialGrid, SteeringAgent},
+    };
+
+    #[test]
+    fn test_spatial_grid_query() {
+        let positions = [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.5, 0.0, 0.0),
+            Vector3::new(-0.9, 0.2, 0.0),
+            Vector3::new(5.0, 0.0, 0.0),
+        ];
+        let mut grid = SpatialGrid::new(1.0);
+        for (i, p) in positions.iter().enumerate() {
+            grid.insert(i, *p);
+        }
+        let mut buffer = Vec::new();
+        grid.query(Vector3::default(), 1.0, |i| positions[i], &mut buffer);
+        buffer.sort_unstable();
+        assert_eq!(buffer, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_seek_and_separation() {
+        let mut agent = SteeringAgent::new(Vector3::default(), 2.0, 100.0);
+        let target = Vector3::new(10.0, 0.0, 0.0);
+        for _ in 0..10 {
+            agent.apply_force(agent.seek(target), 0.1);
+        }
+        assert!(agent.position.x > 0.0);
+        assert!(agent.velocity.norm() <= 2.0 + f32::EPSILON);
+
+        let mut flock = Flock {
+            agents: vec![
+                SteeringAgent::new(Vector3::new(0.0, 0.0, 0.0), 2.0, 10.0),
+                SteeringAgent::new(Vector3::new(0.1, 0.0, 0.0), 2.0, 10.0),
+            ],
+            ..Default::default()
+        };
+        flock.settings.cohesion = 0.0;
+        for _ in 0..10 {
+            flock.update(0.1, |_, _| Vector3::default());
+        }
+        let distance = (flock.agents[0].position - flock.agents[1].position).norm();
+        assert!(distance > 0.1);
+    }
+}
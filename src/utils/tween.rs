@@ -0,0 +1,705 @@
+//! Tweening is a simple way of animating a single value from one state to another over time, without creating an
+//! animation player and animations. It is useful for simple UI and world animations - opening doors, fading sprites,
+//! bouncing pickups, etc. See [`TweenManager`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{UnitQuaternion, Vector2, Vector3},
+        color::Color,
+        log::Log,
+        pool::{Handle, Pool},
+        reflect::prelude::*,
+    },
+    scene::{graph::Graph, node::Node},
+};
+use std::f32::consts::PI;
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Easing function defines the rate of change of a value over time. See <https://easings.net> for visual examples.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, AsRefStr, EnumString, EnumVariantNames)]
+pub enum Easing {
+    /// Constant speed.
+    #[default]
+    Linear,
+    /// Quadratic acceleration from zero velocity.
+    QuadIn,
+    /// Quadratic deceleration to zero velocity.
+    QuadOut,
+    /// Quadratic acceleration until halfway, then deceleration.
+    QuadInOut,
+    /// Cubic acceleration from zero velocity.
+    CubicIn,
+    /// Cubic deceleration to zero velocity.
+    CubicOut,
+    /// Cubic acceleration until halfway, then deceleration.
+    CubicInOut,
+    /// Sinusoidal acceleration from zero velocity.
+    SineIn,
+    /// Sinusoidal deceleration to zero velocity.
+    SineOut,
+    /// Sinusoidal acceleration until halfway, then deceleration.
+    SineInOut,
+    /// Exponential acceleration from zero velocity.
+    ExpoIn,
+    /// Exponential deceleration to zero velocity.
+    ExpoOut,
+    /// Exponential acceleration until halfway, then deceleration.
+    ExpoInOut,
+    /// Moves slightly backwards before moving towards the target.
+    BackIn,
+    /// Overshoots the target and then returns to it.
+    BackOut,
+    /// Moves slightly backwards, then overshoots the target and returns to it.
+    BackInOut,
+    /// Oscillates around the target with decreasing amplitude.
+    ElasticOut,
+    /// Bounces off the target with decreasing amplitude.
+    BounceOut,
+}
+
+impl Easing {
+    /// Maps linear progress `t` in `[0; 1]` range to eased progress. The result is always `0.0` at `t = 0.0` and `1.0`
+    /// at `t = 1.0`, but some functions (back, elastic) go outside of `[0; 1]` range in between.
+    pub fn ease(self, t: f32) -> f32 {
+        const BACK: f32 = 1.70158;
+
+        let t = t.clamp(0.0, 1.0);
+        if t == 0.0 || t == 1.0 {
+            return t;
+        }
+
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Easing::SineOut => (t * PI / 2.0).sin(),
+            Easing::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+            Easing::ExpoIn => 2.0f32.powf(10.0 * t - 10.0),
+            Easing::ExpoOut => 1.0 - 2.0f32.powf(-10.0 * t),
+            Easing::ExpoInOut => {
+                if t < 0.5 {
+                    2.0f32.powf(20.0 * t - 10.0) / 2.0
+                } else {
+                    (2.0 - 2.0f32.powf(-20.0 * t + 10.0)) / 2.0
+                }
+            }
+            Easing::BackIn => (BACK + 1.0) * t * t * t - BACK * t * t,
+            Easing::BackOut => 1.0 + (BACK + 1.0) * (t - 1.0).powi(3) + BACK * (t - 1.0).powi(2),
+            Easing::BackInOut => {
+                let c = BACK * 1.525;
+                if t < 0.5 {
+                    ((2.0 * t).powi(2) * ((c + 1.0) * 2.0 * t - c)) / 2.0
+                } else {
+                    ((2.0 * t - 2.0).powi(2) * ((c + 1.0) * (t * 2.0 - 2.0) + c) + 2.0) / 2.0
+                }
+            }
+            Easing::ElasticOut => {
+                2.0f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+            }
+            Easing::BounceOut => {
+                const N: f32 = 7.5625;
+                const D: f32 = 2.75;
+                if t < 1.0 / D {
+                    N * t * t
+                } else if t < 2.0 / D {
+                    let t = t - 1.5 / D;
+                    N * t * t + 0.75
+                } else if t < 2.5 / D {
+                    let t = t - 2.25 / D;
+                    N * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D;
+                    N * t * t + 0.984375
+                }
+            }
+        }
+    }
+}
+
+/// A value, that could be animated by a tween.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TweenValue {
+    /// Real number.
+    F32(f32),
+    /// Two-dimensional vector.
+    Vector2(Vector2<f32>),
+    /// Three-dimensional vector.
+    Vector3(Vector3<f32>),
+    /// Color.
+    Color(Color),
+    /// Rotation.
+    Rotation(UnitQuaternion<f32>),
+}
+
+impl TweenValue {
+    /// Interpolates the value with the other value of the same type. Returns [`None`] if the types are different.
+    pub fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        match (self, other) {
+            (Self::F32(a), Self::F32(b)) => Some(Self::F32(a + (b - a) * t)),
+            (Self::Vector2(a), Self::Vector2(b)) => Some(Self::Vector2(a.lerp(b, t))),
+            (Self::Vector3(a), Self::Vector3(b)) => Some(Self::Vector3(a.lerp(b, t))),
+            (Self::Color(a), Self::Color(b)) => Some(Self::Color(Color::from(
+                a.as_frgba().lerp(&b.as_frgba(), t),
+            ))),
+            (Self::Rotation(a), Self::Rotation(b)) => Some(Self::Rotation(a.nlerp(b, t))),
+            _ => None,
+        }
+    }
+
+    fn from_reflect(value: &dyn Reflect) -> Option<Self> {
+        let mut result = None;
+        value.as_any(&mut |any| {
+            result = if let Some(v) = any.downcast_ref::<f32>() {
+                Some(Self::F32(*v))
+            } else if let Some(v) = any.downcast_ref::<Vector2<f32>>() {
+                Some(Self::Vector2(*v))
+            } else if let Some(v) = any.downcast_ref::<Vector3<f32>>() {
+                Some(Self::Vector3(*v))
+            } else if let Some(v) = any.downcast_ref::<Color>() {
+                Some(Self::Color(*v))
+            } else {
+                any.downcast_ref::<UnitQuaternion<f32>>()
+                    .map(|v| Self::Rotation(*v))
+            }
+        });
+        result
+    }
+
+    fn into_reflect(self) -> Box<dyn Reflect> {
+        match self {
+            Self::F32(v) => Box::new(v),
+            Self::Vector2(v) => Box::new(v),
+            Self::Vector3(v) => Box::new(v),
+            Self::Color(v) => Box::new(v),
+            Self::Rotation(v) => Box::new(v),
+        }
+    }
+}
+
+impl From<f32> for TweenValue {
+    fn from(value: f32) -> Self {
+        Self::F32(value)
+    }
+}
+
+impl From<Vector2<f32>> for TweenValue {
+    fn from(value: Vector2<f32>) -> Self {
+        Self::Vector2(value)
+    }
+}
+
+impl From<Vector3<f32>> for TweenValue {
+    fn from(value: Vector3<f32>) -> Self {
+        Self::Vector3(value)
+    }
+}
+
+impl From<Color> for TweenValue {
+    fn from(value: Color) -> Self {
+        Self::Color(value)
+    }
+}
+
+impl From<UnitQuaternion<f32>> for TweenValue {
+    fn from(value: UnitQuaternion<f32>) -> Self {
+        Self::Rotation(value)
+    }
+}
+
+/// A property, that is animated by a tween.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum TweenTarget {
+    /// The tween does not modify anything, its current value could be fetched using [`TweenManager::value`].
+    #[default]
+    None,
+    /// Local position of a node.
+    Position(Handle<Node>),
+    /// Local scale of a node.
+    Scale(Handle<Node>),
+    /// Local rotation of a node.
+    Rotation(Handle<Node>),
+    /// An arbitrary property of a node, that is set using reflection. The path must point to a property of `f32`,
+    /// `Vector2<f32>`, `Vector3<f32>`, `Color` or `UnitQuaternion<f32>` type (for example, `color` of a sprite, or
+    /// `base_light.intensity` of a point light).
+    Property {
+        /// A handle of the node.
+        node: Handle<Node>,
+        /// A path to the property.
+        path: String,
+    },
+}
+
+impl TweenTarget {
+    fn read(&self, graph: &Graph) -> Option<TweenValue> {
+        match self {
+            TweenTarget::None => None,
+            TweenTarget::Position(node) => graph
+                .try_get(*node)
+                .map(|n| TweenValue::Vector3(**n.local_transform().position())),
+            TweenTarget::Scale(node) => graph
+                .try_get(*node)
+                .map(|n| TweenValue::Vector3(**n.local_transform().scale())),
+            TweenTarget::Rotation(node) => graph
+                .try_get(*node)
+                .map(|n| TweenValue::Rotation(**n.local_transform().rotation())),
+            TweenTarget::Property { node, path } => {
+                let mut value = None;
+                if let Some(node) = graph.try_get(*node) {
+                    node.as_reflect(&mut |node| {
+                        node.resolve_path(path, &mut |result| {
+                            value = result.ok().and_then(TweenValue::from_reflect);
+                        })
+                    });
+                }
+                value
+            }
+        }
+    }
+
+    fn write(&self, graph: &mut Graph, value: TweenValue) {
+        match (self, value) {
+            (TweenTarget::Position(node), TweenValue::Vector3(v)) => {
+                if let Some(node) = graph.try_get_mut(*node) {
+                    node.local_transform_mut().set_position(v);
+                }
+            }
+            (TweenTarget::Scale(node), TweenValue::Vector3(v)) => {
+                if let Some(node) = graph.try_get_mut(*node) {
+                    node.local_transform_mut().set_scale(v);
+                }
+            }
+            (TweenTarget::Rotation(node), TweenValue::Rotation(v)) => {
+                if let Some(node) = graph.try_get_mut(*node) {
+                    node.local_transform_mut().set_rotation(v);
+                }
+            }
+            (TweenTarget::Property { node, path }, value) => {
+                if let Some(node) = graph.try_get_mut(*node) {
+                    let mut value = Some(value.into_reflect());
+                    node.as_reflect_mut(&mut |node| {
+                        node.set_field_by_path(path, value.take().unwrap(), &mut |result| {
+                            if result.is_err() {
+                                Log::err(format!("Unable to set {path} property using a tween!"));
+                            }
+                        })
+                    });
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Tween changes a value from one state to another over the given period of time using an easing function.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use fyrox::{
+/// #     core::{algebra::Vector3, color::Color, pool::Handle},
+/// #     scene::node::Node,
+/// #     utils::tween::{Easing, Tween},
+/// # };
+/// # let door = Handle::<Node>::NONE;
+/// # let sprite = Handle::<Node>::NONE;
+/// // Move a door up by 3 meters in 1.5 seconds with a smooth start and stop.
+/// let open_door = Tween::position(door, Vector3::new(0.0, 3.0, 0.0), 1.5).with_easing(Easing::SineInOut);
+///
+/// // Fade out a sprite in 0.5 seconds after 2 seconds delay.
+/// let fade_out = Tween::property(sprite, "color", Color::TRANSPARENT, 0.5).with_delay(2.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tween {
+    /// A property, that is animated by the tween.
+    pub target: TweenTarget,
+    /// Initial value. If it is not set, the current value of the target is used when the tween starts.
+    pub from: Option<TweenValue>,
+    /// Final value.
+    pub to: TweenValue,
+    /// Duration of the tween in seconds (not including the delay).
+    pub duration: f32,
+    /// Time in seconds, that the tween waits before it starts changing the value.
+    pub delay: f32,
+    /// Easing function of the tween.
+    pub easing: Easing,
+    elapsed: f32,
+    start: Option<TweenValue>,
+}
+
+impl Tween {
+    /// Creates a new tween, that changes the given target to the given value over the given duration.
+    pub fn new(target: TweenTarget, to: impl Into<TweenValue>, duration: f32) -> Self {
+        Self {
+            target,
+            from: None,
+            to: to.into(),
+            duration,
+            delay: 0.0,
+            easing: Default::default(),
+            elapsed: 0.0,
+            start: None,
+        }
+    }
+
+    /// Creates a new tween, that does not modify anything and just changes a value between the two given values. The
+    /// value could be fetched using [`TweenManager::value`].
+    pub fn value(from: impl Into<TweenValue>, to: impl Into<TweenValue>, duration: f32) -> Self {
+        Self::new(TweenTarget::None, to, duration).with_from(from)
+    }
+
+    /// Creates a new tween, that moves the given node to the given local position.
+    pub fn position(node: Handle<Node>, to: Vector3<f32>, duration: f32) -> Self {
+        Self::new(TweenTarget::Position(node), to, duration)
+    }
+
+    /// Creates a new tween, that scales the given node to the given local scale.
+    pub fn scale(node: Handle<Node>, to: Vector3<f32>, duration: f32) -> Self {
+        Self::new(TweenTarget::Scale(node), to, duration)
+    }
+
+    /// Creates a new tween, that rotates the given node to the given local rotation.
+    pub fn rotation(node: Handle<Node>, to: UnitQuaternion<f32>, duration: f32) -> Self {
+        Self::new(TweenTarget::Rotation(node), to, duration)
+    }
+
+    /// Creates a new tween, that changes an arbitrary property of the given node. See [`TweenTarget::Property`] docs
+    /// for more info.
+    pub fn property<S: AsRef<str>>(
+        node: Handle<Node>,
+        path: S,
+        to: impl Into<TweenValue>,
+        duration: f32,
+    ) -> Self {
+        Self::new(
+            TweenTarget::Property {
+                node,
+                path: path.as_ref().to_owned(),
+            },
+            to,
+            duration,
+        )
+    }
+
+    /// Sets desired initial value.
+    pub fn with_from(mut self, from: impl Into<TweenValue>) -> Self {
+        self.from = Some(from.into());
+        self
+    }
+
+    /// Sets desired delay.
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets desired easing function.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Returns `true` if the tween has reached its final value, `false` - otherwise.
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.delay + self.duration.max(0.0)
+    }
+
+    fn reset(&mut self) {
+        self.elapsed = 0.0;
+        self.start = None;
+    }
+
+    // Returns a new value (if any) and the time left after the tween is finished (if it is finished).
+    fn update(&mut self, graph: &mut Graph, dt: f32) -> (Option<TweenValue>, Option<f32>) {
+        self.elapsed += dt;
+        if self.elapsed < self.delay {
+            return (None, None);
+        }
+
+        if self.start.is_none() {
+            self.start = Some(
+                self.from
+                    .or_else(|| self.target.read(graph))
+                    .unwrap_or(self.to),
+            );
+        }
+
+        let local_time = self.elapsed - self.delay;
+        let t = if self.duration > 0.0 {
+            (local_time / self.duration).min(1.0)
+        } else {
+            1.0
+        };
+
+        let value = self
+            .start
+            .and_then(|start| start.interpolate(&self.to, self.easing.ease(t)))
+            .unwrap_or(self.to);
+        self.target.write(graph, value);
+
+        let left = (t >= 1.0).then(|| local_time - self.duration.max(0.0));
+        (Some(value), left)
+    }
+}
+
+/// Tween sequence plays a set of tweens one after another, optionally in a loop.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use fyrox::{
+/// #     core::{algebra::Vector3, pool::Handle},
+/// #     scene::node::Node,
+/// #     utils::tween::{Easing, Tween, TweenSequence},
+/// # };
+/// # let pickup = Handle::<Node>::NONE;
+/// // Bounce a pickup up and down forever.
+/// let bounce = TweenSequence::new(vec![
+///     Tween::position(pickup, Vector3::new(0.0, 0.5, 0.0), 0.5).with_easing(Easing::QuadOut),
+///     Tween::position(pickup, Vector3::new(0.0, 0.0, 0.0), 0.5).with_easing(Easing::QuadIn),
+/// ])
+/// .with_looping(true);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TweenSequence {
+    tweens: Vec<Tween>,
+    looping: bool,
+    current: usize,
+    value: Option<TweenValue>,
+}
+
+impl From<Tween> for TweenSequence {
+    fn from(tween: Tween) -> Self {
+        Self::new(vec![tween])
+    }
+}
+
+impl TweenSequence {
+    /// Creates a new sequence from the given set of tweens.
+    pub fn new(tweens: Vec<Tween>) -> Self {
+        Self {
+            tweens,
+            ..Default::default()
+        }
+    }
+
+    /// Adds a tween to the end of the sequence.
+    pub fn then(mut self, tween: Tween) -> Self {
+        self.tweens.push(tween);
+        self
+    }
+
+    /// Defines whether the sequence should start over when it is finished.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Returns a reference to the tweens of the sequence.
+    pub fn tweens(&self) -> &[Tween] {
+        &self.tweens
+    }
+
+    /// Returns `true` if the sequence is finished, `false` - otherwise. Looping sequences never finish.
+    pub fn is_finished(&self) -> bool {
+        self.current >= self.tweens.len()
+    }
+
+    /// Returns the last value of the sequence (if any).
+    pub fn value(&self) -> Option<TweenValue> {
+        self.value
+    }
+
+    fn update(&mut self, graph: &mut Graph, mut dt: f32) {
+        while let Some(tween) = self.tweens.get_mut(self.current) {
+            let (value, left) = tween.update(graph, dt);
+            if value.is_some() {
+                self.value = value;
+            }
+
+            let Some(left) = left else {
+                break;
+            };
+
+            self.current += 1;
+            dt = left;
+
+            if self.is_finished() && self.looping {
+                self.current = 0;
+                for tween in self.tweens.iter_mut() {
+                    tween.reset();
+                }
+                // Prevent infinite looping over zero-length sequences.
+                if dt <= 0.0 {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Tween manager updates a set of tweens (and tween sequences). Every scene has its own tween manager, which is
+/// updated automatically right before the scene graph, using the time scale of the scene. Finished sequences are
+/// removed on the next update, so their final value could be fetched right after they're finished.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use fyrox::{
+/// #     core::{algebra::Vector3, pool::Handle},
+/// #     scene::{node::Node, Scene},
+/// #     utils::tween::{Easing, Tween},
+/// # };
+/// fn open_door(scene: &mut Scene, door: Handle<Node>) {
+///     scene.tweens.add(
+///         Tween::position(door, Vector3::new(0.0, 3.0, 0.0), 1.5).with_easing(Easing::SineInOut),
+///     );
+/// }
+/// ```
+#[derive(Default, Debug)]
+pub struct TweenManager {
+    sequences: Pool<TweenSequence>,
+}
+
+impl TweenManager {
+    /// Adds a new tween (or tween sequence) and returns its handle.
+    pub fn add(&mut self, sequence: impl Into<TweenSequence>) -> Handle<TweenSequence> {
+        self.sequences.spawn(sequence.into())
+    }
+
+    /// Removes the given sequence, the animated property keeps its current value.
+    pub fn remove(&mut self, handle: Handle<TweenSequence>) -> Option<TweenSequence> {
+        self.sequences.try_free(handle)
+    }
+
+    /// Returns a reference to the sequence with the given handle (if any).
+    pub fn try_get(&self, handle: Handle<TweenSequence>) -> Option<&TweenSequence> {
+        self.sequences.try_borrow(handle)
+    }
+
+    /// Returns `true` if the given sequence exists and is not finished yet.
+    pub fn is_playing(&self, handle: Handle<TweenSequence>) -> bool {
+        self.try_get(handle).is_some_and(|s| !s.is_finished())
+    }
+
+    /// Returns the last value of the given sequence (if any).
+    pub fn value(&self, handle: Handle<TweenSequence>) -> Option<TweenValue> {
+        self.try_get(handle).and_then(|s| s.value())
+    }
+
+    /// Returns the total amount of the sequences.
+    pub fn len(&self) -> usize {
+        self.sequences.alive_count() as usize
+    }
+
+    /// Returns `true` if there's no sequences.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every sequence.
+    pub fn clear(&mut self) {
+        self.sequences.clear();
+    }
+
+    /// Advances every sequence by the given amount of time. The engine calls this method automatically for every scene.
+    pub fn update(&mut self, graph: &mut Graph, dt: f32) {
+        self.sequences.retain(|sequence| !sequence.is_finished());
+
+        for sequence in self.sequences.iter_mut() {
+            sequence.update(graph, dt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, color::Color},
+        scene::{base::BaseBuilder, graph::Graph, pivot::PivotBuilder},
+        utils::tween::{Easing, Tween, TweenManager, TweenSequence, TweenValue},
+    };
+    use std::str::FromStr;
+    use strum::VariantNames;
+
+    #[test]
+    fn test_easing_endpoints() {
+        for name in Easing::VARIANTS {
+            let easing = Easing::from_str(name).unwrap();
+            assert_eq!(easing.ease(0.0), 0.0, "{name}");
+            assert_eq!(easing.ease(1.0), 1.0, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_tweens() {
+        let mut graph = Graph::new();
+        let node = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let mut tweens = TweenManager::default();
+        let sequence = tweens.add(
+            TweenSequence::new(vec![
+                Tween::position(node, Vector3::new(2.0, 0.0, 0.0), 1.0).with_delay(0.5),
+                Tween::position(node, Vector3::new(2.0, 4.0, 0.0), 1.0),
+            ])
+            .then(Tween::scale(node, Vector3::repeat(2.0), 0.0)),
+        );
+        let value =
+            tweens.add(Tween::value(Color::BLACK, Color::WHITE, 1.0).with_easing(Easing::QuadIn));
+
+        // Still delayed.
+        tweens.update(&mut graph, 0.5);
+        assert_eq!(
+            **graph[node].local_transform().position(),
+            Vector3::default()
+        );
+
+        tweens.update(&mut graph, 0.5);
+        assert_eq!(
+            **graph[node].local_transform().position(),
+            Vector3::new(1.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            tweens.value(value),
+            Some(TweenValue::Color(Color::opaque(255, 255, 255)))
+        );
+
+        // Leftover time is passed to the next tween.
+        tweens.update(&mut graph, 1.0);
+        assert_eq!(
+            **graph[node].local_transform().position(),
+            Vector3::new(2.0, 2.0, 0.0)
+        );
+
+        tweens.update(&mut graph, 1.0);
+        assert_eq!(
+            **graph[node].local_transform().position(),
+            Vector3::new(2.0, 4.0, 0.0)
+        );
+        assert_eq!(
+            **graph[node].local_transform().scale(),
+            Vector3::repeat(2.0)
+        );
+        assert!(!tweens.is_playing(sequence));
+
+        // Finished sequences are removed on the next update.
+        tweens.update(&mut graph, 0.1);
+        assert!(tweens.is_empty());
+    }
+}